@@ -7,8 +7,9 @@ use crate::{
     metrics,
     response::{
         bcs_api_disabled, block_not_found_by_height, block_not_found_by_version,
-        block_pruned_by_height, json_api_disabled, version_not_found, version_pruned,
-        ForbiddenError, InternalError, NotFoundError, ServiceUnavailableError, StdApiError,
+        block_pruned_by_height, json_api_disabled, state_version_pruned, version_not_found,
+        version_pruned, ForbiddenError, InternalError, NotFoundError, ServiceUnavailableError,
+        StdApiError,
     },
 };
 use anyhow::{anyhow, bail, ensure, format_err, Context as AnyhowContext, Result};
@@ -81,6 +82,7 @@ pub struct Context {
     simulate_txn_stats: Arc<FunctionStats>,
     pub indexer_reader: Option<Arc<dyn IndexerReader>>,
     pub wait_for_hash_active_connections: Arc<AtomicUsize>,
+    pub transaction_stream_active_connections: Arc<AtomicUsize>,
 }
 
 impl std::fmt::Debug for Context {
@@ -133,6 +135,7 @@ impl Context {
             simulate_txn_stats,
             indexer_reader,
             wait_for_hash_active_connections: Arc::new(AtomicUsize::new(0)),
+            transaction_stream_active_connections: Arc::new(AtomicUsize::new(0)),
         }
     }
 
@@ -305,6 +308,23 @@ impl Context {
             ));
         }
 
+        // The state values can be pruned ahead of the ledger
+        let oldest_state_version = self
+            .db
+            .get_first_state_value_version()
+            .context("Failed to retrieve the oldest state version")
+            .map_err(|err| {
+                E::internal_with_code(err, AptosErrorCode::InternalError, &latest_ledger_info)
+            })?
+            .unwrap_or(0);
+        if requested_ledger_version < oldest_state_version {
+            return Err(state_version_pruned(
+                requested_ledger_version,
+                oldest_state_version,
+                &latest_ledger_info,
+            ));
+        }
+
         Ok((latest_ledger_info, requested_ledger_version))
     }
 
@@ -23,8 +23,10 @@ mod runtime;
 mod set_failpoints;
 pub mod spec;
 mod state;
+mod state_override;
 #[cfg(test)]
 pub mod tests;
+mod transaction_stream;
 mod transactions;
 mod view_function;
 
@@ -630,9 +630,30 @@ pub fn transaction_not_found_by_hash<E: NotFoundError>(
 }
 
 pub fn version_pruned<E: GoneError>(ledger_version: u64, ledger_info: &LedgerInfo) -> E {
-    E::gone_with_code(
-        format!("Ledger version({}) has been pruned", ledger_version),
-        AptosErrorCode::VersionPruned,
+    E::gone_from_aptos_error(
+        AptosError::new_with_error_code(
+            format!("Ledger version({}) has been pruned", ledger_version),
+            AptosErrorCode::VersionPruned,
+        )
+        .with_oldest_available_version(ledger_info.oldest_ledger_version.0),
+        ledger_info,
+    )
+}
+
+pub fn state_version_pruned<E: GoneError>(
+    ledger_version: u64,
+    oldest_state_version: u64,
+    ledger_info: &LedgerInfo,
+) -> E {
+    E::gone_from_aptos_error(
+        AptosError::new_with_error_code(
+            format!(
+                "State at ledger version({}) has been pruned, the oldest available version is {}",
+                ledger_version, oldest_state_version
+            ),
+            AptosErrorCode::VersionPruned,
+        )
+        .with_oldest_available_version(oldest_state_version),
         ledger_info,
     )
 }
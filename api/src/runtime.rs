@@ -15,6 +15,7 @@ use crate::{
     set_failpoints,
     spec::{spec_endpoint_json, spec_endpoint_yaml},
     state::StateApi,
+    transaction_stream,
     transactions::TransactionsApi,
     view_function::ViewFunctionApi,
 };
@@ -248,6 +249,12 @@ pub fn attach_poem_to_runtime(
                     .at(
                         "/set_failpoint",
                         poem::get(set_failpoints::set_failpoint_poem).data(context.clone()),
+                    )
+                    // Server-sent events are outside of the OpenAPI spec as well.
+                    .at(
+                        "/transactions/stream",
+                        poem::get(transaction_stream::stream_transactions_poem)
+                            .data(context.clone()),
                     ),
             )
             .with(cors)
@@ -15,10 +15,12 @@ use crate::{
 use anyhow::Context as AnyhowContext;
 use aptos_api_types::{
     verify_module_identifier, Address, AptosErrorCode, AsConverter, IdentifierWrapper,
-    MoveModuleBytecode, MoveResource, MoveStructTag, MoveValue, RawStateValueRequest,
-    RawTableItemRequest, TableItemRequest, VerifyInput, VerifyInputWithRecursion, U64,
+    MoveModuleBytecode, MoveResource, MoveStructTag, MoveValue, PackageUpgradeCheckRequest,
+    PackageUpgradeReport, RawStateValueRequest, RawTableItemRequest, TableItemRequest, VerifyInput,
+    VerifyInputWithRecursion, U64,
 };
 use aptos_types::state_store::{state_key::StateKey, table::TableHandle, TStateView};
+use aptos_vm::verifier::package_upgrade;
 use move_core_types::language_storage::StructTag;
 use poem_openapi::{
     param::{Path, Query},
@@ -123,6 +125,43 @@ impl StateApi {
         .await
     }
 
+    /// Check package upgrade
+    ///
+    /// Checks the modules of a package against their versions published under the account,
+    /// with the same compatibility checks as the ones enforced when publishing the package.
+    /// Instead of a single abort code, every incompatibility found is reported: struct layout
+    /// and ability changes, removed or changed public functions, removed friends, etc.
+    ///
+    /// Only the compatibility of the modules is checked. The package metadata, e.g. the upgrade
+    /// policy, and the bytecode are validated when the package is actually published.
+    #[oai(
+        path = "/accounts/:address/check_package_upgrade",
+        method = "post",
+        operation_id = "check_package_upgrade",
+        tag = "ApiTags::Accounts"
+    )]
+    async fn check_package_upgrade(
+        &self,
+        accept_type: AcceptType,
+        /// Address of account with or without a `0x` prefix
+        address: Path<Address>,
+        /// Bytecode of the modules of the package
+        request: Json<PackageUpgradeCheckRequest>,
+        /// Ledger version of the on-chain modules to check against
+        ///
+        /// If not provided, it will be the latest version
+        ledger_version: Query<Option<U64>>,
+    ) -> BasicResultWith404<PackageUpgradeReport> {
+        fail_point_poem("endpoint_check_package_upgrade")?;
+        self.context
+            .check_api_output_enabled("Check package upgrade", &accept_type)?;
+        let api = self.clone();
+        api_spawn_blocking(move || {
+            api.package_upgrade_report(&accept_type, address.0, request.0, ledger_version.0)
+        })
+        .await
+    }
+
     /// Get table item
     ///
     /// Get a table item at a specific ledger version from the table identified by {table_handle}
@@ -523,6 +562,49 @@ impl StateApi {
     }
 
     /// Retrieve state value for a specific ledger version
+    /// Check the modules of a package against the ones published at the ledger version
+    fn package_upgrade_report(
+        &self,
+        accept_type: &AcceptType,
+        address: Address,
+        request: PackageUpgradeCheckRequest,
+        ledger_version: Option<u64>,
+    ) -> BasicResultWith404<PackageUpgradeReport> {
+        let (ledger_info, _ledger_version, state_view) = self.context.state_view(ledger_version)?;
+
+        let modules: Vec<_> = request.modules.into_iter().map(|bytes| bytes.0).collect();
+        let report = package_upgrade::check_package_upgrade(&state_view, &modules)
+            .context("Failed to check the modules of the package")
+            .map_err(|err| {
+                BasicErrorWith404::bad_request_with_code(
+                    err,
+                    AptosErrorCode::InvalidInput,
+                    &ledger_info,
+                )
+            })?;
+        if let Some(module) = report
+            .modules
+            .iter()
+            .find(|module| module.module_id.address() != address.inner())
+        {
+            return Err(BasicErrorWith404::bad_request_with_code(
+                format!(
+                    "Module {} is not published under the account {}",
+                    module.module_id, address
+                ),
+                AptosErrorCode::InvalidInput,
+                &ledger_info,
+            ));
+        }
+
+        BasicResponse::try_from_rust_value((
+            PackageUpgradeReport::from(report),
+            &ledger_info,
+            BasicResponseStatus::Ok,
+            accept_type,
+        ))
+    }
+
     pub fn raw_value(
         &self,
         accept_type: &AcceptType,
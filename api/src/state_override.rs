@@ -0,0 +1,182 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::context::Context;
+use anyhow::{bail, format_err, Context as AnyhowContext, Result};
+use aptos_api_types::{AsConverter, StateOverrides};
+use aptos_types::{
+    account_config::{
+        primary_apt_store, CoinStoreResource, ConcurrentFungibleBalanceResource,
+        FungibleStoreResource,
+    },
+    on_chain_config::CurrentTimeMicroseconds,
+    state_store::{
+        state_key::StateKey, state_storage_usage::StateStorageUsage, state_value::StateValue,
+        StateView, StateViewId, StateViewResult, TStateView,
+    },
+    utility_coin::AptosCoinType,
+};
+use bytes::Bytes;
+use move_core_types::{
+    account_address::AccountAddress,
+    language_storage::{StructTag, TypeTag},
+    move_resource::MoveStructType,
+};
+use std::collections::{BTreeMap, HashMap};
+
+/// A state view with ephemeral overrides on top of a base state view, for the simulation
+pub struct StateOverrideView<S> {
+    base_view: S,
+    overrides: HashMap<StateKey, StateValue>,
+}
+
+impl<S: StateView> StateOverrideView<S> {
+    pub fn new(base_view: S) -> Self {
+        Self {
+            base_view,
+            overrides: HashMap::new(),
+        }
+    }
+
+    /// Applies the overrides, in order: the resources, the balances and then the timestamp
+    pub fn apply(&mut self, context: &Context, state_overrides: StateOverrides) -> Result<()> {
+        for resource in state_overrides.resources {
+            let address = resource.address.into();
+            let tag = StructTag::try_from(resource.typ)?;
+            let bytes = self
+                .as_converter(context.db.clone(), context.indexer_reader.clone())
+                .try_into_vm_value(&TypeTag::Struct(Box::new(tag.clone())), resource.data)
+                .with_context(|| format!("Invalid override of resource {}", tag))?
+                .simple_serialize()
+                .ok_or_else(|| format_err!("Failed to serialize resource {}", tag))?;
+            self.set_resource(context, address, &tag, bytes)?;
+        }
+        for balance in state_overrides.balances {
+            self.set_apt_balance(context, balance.address.into(), balance.amount.0)?;
+        }
+        if let Some(timestamp_usecs) = state_overrides.timestamp_usecs {
+            let timestamp = CurrentTimeMicroseconds {
+                microseconds: timestamp_usecs.0,
+            };
+            self.set_value(
+                StateKey::on_chain_config::<CurrentTimeMicroseconds>()?,
+                bcs::to_bytes(&timestamp)?,
+            )?;
+        }
+        Ok(())
+    }
+
+    /// Sets the balance in the APT coin store of the account if any, or in its primary APT
+    /// fungible store otherwise.
+    fn set_apt_balance(
+        &mut self,
+        context: &Context,
+        address: AccountAddress,
+        amount: u64,
+    ) -> Result<()> {
+        let coin_store_tag = CoinStoreResource::<AptosCoinType>::struct_tag();
+        if let Some(bytes) = self.get_resource(context, address, &coin_store_tag)? {
+            let mut coin_store: CoinStoreResource<AptosCoinType> = bcs::from_bytes(&bytes)?;
+            coin_store.set_coin(amount);
+            return self.set_resource(
+                context,
+                address,
+                &coin_store_tag,
+                bcs::to_bytes(&coin_store)?,
+            );
+        }
+
+        let store_address = primary_apt_store(address);
+        let store_tag = FungibleStoreResource::struct_tag();
+        let bytes = match self.get_resource(context, store_address, &store_tag)? {
+            Some(bytes) => bytes,
+            None => bail!(
+                "Account {} has no APT store to override the balance of",
+                address.to_hex_literal()
+            ),
+        };
+        // The balance is held by the concurrent balance resource instead, if the store has one
+        let concurrent_balance_tag = ConcurrentFungibleBalanceResource::struct_tag();
+        if self
+            .get_resource(context, store_address, &concurrent_balance_tag)?
+            .is_some()
+        {
+            let concurrent_balance = ConcurrentFungibleBalanceResource::new(amount);
+            return self.set_resource(
+                context,
+                store_address,
+                &concurrent_balance_tag,
+                bcs::to_bytes(&concurrent_balance)?,
+            );
+        }
+        let mut store: FungibleStoreResource = bcs::from_bytes(&bytes)?;
+        store.balance = amount;
+        self.set_resource(context, store_address, &store_tag, bcs::to_bytes(&store)?)
+    }
+
+    fn get_resource(
+        &self,
+        context: &Context,
+        address: AccountAddress,
+        tag: &StructTag,
+    ) -> Result<Option<Bytes>> {
+        self.as_converter(context.db.clone(), context.indexer_reader.clone())
+            .find_resource(self, address.into(), tag)
+    }
+
+    /// Sets the resource, within its resource group if it belongs to one
+    fn set_resource(
+        &mut self,
+        context: &Context,
+        address: AccountAddress,
+        tag: &StructTag,
+        bytes: Vec<u8>,
+    ) -> Result<()> {
+        let group_tag = self
+            .as_converter(context.db.clone(), context.indexer_reader.clone())
+            .find_resource_group(tag);
+        match group_tag {
+            Some(group_tag) => {
+                let key = StateKey::resource_group(&address, &group_tag);
+                let mut group: BTreeMap<StructTag, Bytes> =
+                    match self.get_state_value_bytes(&key)? {
+                        Some(group_bytes) => bcs::from_bytes(&group_bytes)?,
+                        None => BTreeMap::new(),
+                    };
+                group.insert(tag.clone(), bytes.into());
+                self.set_value(key, bcs::to_bytes(&group)?)
+            },
+            None => self.set_value(StateKey::resource(&address, tag)?, bytes),
+        }
+    }
+
+    fn set_value(&mut self, key: StateKey, bytes: Vec<u8>) -> Result<()> {
+        // Keep the metadata of an existing value, so that the storage fees are charged as if the
+        // value was written beforehand
+        let value = match self.get_state_value(&key)? {
+            Some(value) => value.map_bytes(|_| Ok(bytes.into()))?,
+            None => StateValue::new_legacy(bytes.into()),
+        };
+        self.overrides.insert(key, value);
+        Ok(())
+    }
+}
+
+impl<S: StateView> TStateView for StateOverrideView<S> {
+    type Key = StateKey;
+
+    fn id(&self) -> StateViewId {
+        self.base_view.id()
+    }
+
+    fn get_state_value(&self, state_key: &StateKey) -> StateViewResult<Option<StateValue>> {
+        match self.overrides.get(state_key) {
+            Some(value) => Ok(Some(value.clone())),
+            None => self.base_view.get_state_value(state_key),
+        }
+    }
+
+    fn get_usage(&self) -> StateViewResult<StateStorageUsage> {
+        self.base_view.get_usage()
+    }
+}
@@ -166,6 +166,28 @@ async fn test_get_account_resources_by_too_large_ledger_version() {
     context.check_golden_output(resp);
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_get_account_module_by_ledger_version() {
+    let mut context = new_test_context(current_function_name!());
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account).await;
+    context.commit_block(&vec![txn.clone()]).await;
+
+    let module = context
+        .get("/accounts/0x1/module/coin?ledger_version=0")
+        .await;
+    assert_eq!(module["abi"]["name"], "coin");
+
+    // The errors of a pruned version report the oldest available version, and are omitted
+    // otherwise
+    let resp = context
+        .expect_status_code(404)
+        .get("/accounts/0x1/module/coin?ledger_version=1000000000000000000")
+        .await;
+    assert_eq!(resp["error_code"], "version_not_found");
+    assert!(resp.get("oldest_available_version").is_none());
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn test_get_account_resources_by_invalid_ledger_version() {
     let mut context = new_test_context(current_function_name!());
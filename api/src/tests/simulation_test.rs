@@ -1,8 +1,9 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use super::new_test_context;
+use super::{new_test_context, new_test_context_with_config};
 use aptos_api_test_context::{current_function_name, pretty, TestContext};
+use aptos_config::config::NodeConfig;
 use aptos_crypto::ed25519::Ed25519Signature;
 use aptos_types::{
     account_address::AccountAddress,
@@ -104,6 +105,174 @@ async fn test_simulate_transaction_with_insufficient_balance() {
     assert!(!resp[0]["success"].as_bool().is_some_and(|v| v));
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_simulate_transaction_with_balance_override() {
+    let mut context = new_test_context(current_function_name!());
+    let alice = &mut context.gen_account();
+    let bob = &mut context.gen_account();
+    let txn = context.mint_user_account(alice).await;
+    context.commit_block(&vec![txn]).await;
+
+    let txn = context.account_transfer_to(alice, bob.address(), LARGE_TRANSFER_AMOUNT);
+    let public_key = match txn.authenticator_ref() {
+        TransactionAuthenticator::Ed25519 { public_key, .. } => public_key.clone(),
+        _ => unreachable!("Simulation uses Ed25519 authenticator."),
+    };
+    let simulate = |state_overrides: serde_json::Value| {
+        warp::test::request()
+            .method("POST")
+            .path("/v1/transactions/simulate_with_overrides")
+            .json(&json!({
+                "transaction": {
+                    "sender": txn.sender().to_string(),
+                    "sequence_number": txn.sequence_number().to_string(),
+                    "max_gas_amount": txn.max_gas_amount().to_string(),
+                    "gas_unit_price": txn.gas_unit_price().to_string(),
+                    "expiration_timestamp_secs": txn.expiration_timestamp_secs().to_string(),
+                    "payload": {
+                        "type": "entry_function_payload",
+                        "function": "0x1::aptos_account::transfer",
+                        "type_arguments": [],
+                        "arguments": [
+                            bob.address().to_standard_string(),
+                            LARGE_TRANSFER_AMOUNT.to_string(),
+                        ]
+                    },
+                    "signature": {
+                        "type": "ed25519_signature",
+                        "public_key": public_key.to_string(),
+                        "signature": Ed25519Signature::dummy_signature().to_string(),
+                    }
+                },
+                "state_overrides": state_overrides,
+            }))
+    };
+
+    // Without overrides, the balance is insufficient as for the simulate endpoint
+    let resp = context.reply(simulate(json!({}))).await;
+    let resp: serde_json::Value = serde_json::from_slice(resp.body()).unwrap();
+    assert!(!resp[0]["success"].as_bool().is_some_and(|v| v));
+
+    // With the balance of the sender overridden, the transfer succeeds
+    let resp = context
+        .reply(simulate(json!({
+            "balances": [{
+                "address": alice.address().to_hex_literal(),
+                "amount": (2 * LARGE_TRANSFER_AMOUNT).to_string(),
+            }],
+        })))
+        .await;
+    let resp: serde_json::Value = serde_json::from_slice(resp.body()).unwrap();
+    assert!(resp[0]["success"].as_bool().is_some_and(|v| v));
+
+    // The overrides are ephemeral
+    let resp = context.reply(simulate(json!({}))).await;
+    let resp: serde_json::Value = serde_json::from_slice(resp.body()).unwrap();
+    assert!(!resp[0]["success"].as_bool().is_some_and(|v| v));
+
+    // The balance of an account without any store can't be overridden
+    let resp = context
+        .reply(simulate(json!({
+            "balances": [{
+                "address": AccountAddress::random().to_hex_literal(),
+                "amount": "1",
+            }],
+        })))
+        .await;
+    assert_eq!(resp.status(), 400);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_simulate_transaction_with_write_scope() {
+    let mut node_config = NodeConfig::default();
+    node_config.api.simulation_write_scope_enabled = true;
+    let mut context = new_test_context_with_config(current_function_name!(), node_config);
+    let alice = &mut context.gen_account();
+    let bob = &mut context.gen_account();
+    let txn = context.mint_user_account(alice).await;
+    context.commit_block(&vec![txn]).await;
+
+    let txn = context.account_transfer_to(alice, bob.address(), SMALL_TRANSFER_AMOUNT);
+    let public_key = match txn.authenticator_ref() {
+        TransactionAuthenticator::Ed25519 { public_key, .. } => public_key.clone(),
+        _ => unreachable!("Simulation uses Ed25519 authenticator."),
+    };
+    let simulate = |write_scope: &[String]| {
+        let query: Vec<_> = write_scope
+            .iter()
+            .map(|address| format!("write_scope={}", address))
+            .collect();
+        warp::test::request()
+            .method("POST")
+            .path(&format!("/v1/transactions/simulate?{}", query.join("&")))
+            .json(&json!({
+                "sender": txn.sender().to_string(),
+                "sequence_number": txn.sequence_number().to_string(),
+                "max_gas_amount": txn.max_gas_amount().to_string(),
+                "gas_unit_price": txn.gas_unit_price().to_string(),
+                "expiration_timestamp_secs": txn.expiration_timestamp_secs().to_string(),
+                "payload": {
+                    "type": "entry_function_payload",
+                    "function": "0x1::aptos_account::transfer",
+                    "type_arguments": [],
+                    "arguments": [
+                        bob.address().to_standard_string(),
+                        SMALL_TRANSFER_AMOUNT.to_string(),
+                    ]
+                },
+                "signature": {
+                    "type": "ed25519_signature",
+                    "public_key": public_key.to_string(),
+                    "signature": Ed25519Signature::dummy_signature().to_string(),
+                }
+            }))
+    };
+
+    // Without a scope, collect the addresses the transfer writes to
+    let resp = context.reply(simulate(&[])).await;
+    let resp: serde_json::Value = serde_json::from_slice(resp.body()).unwrap();
+    assert!(resp[0]["success"].as_bool().is_some_and(|v| v));
+    let addresses: Vec<_> = resp[0]["changes"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter_map(|change| change["address"].as_str().map(str::to_string))
+        .collect();
+
+    // A scope covering them lets the transfer succeed
+    let resp = context.reply(simulate(&addresses)).await;
+    let resp: serde_json::Value = serde_json::from_slice(resp.body()).unwrap();
+    assert!(resp[0]["success"].as_bool().is_some_and(|v| v));
+
+    // Creating the receiver writes outside of a scope bound to the sender
+    let resp = context
+        .reply(simulate(&[alice.address().to_hex_literal()]))
+        .await;
+    let resp: serde_json::Value = serde_json::from_slice(resp.body()).unwrap();
+    assert!(!resp[0]["success"].as_bool().is_some_and(|v| v));
+    assert_eq!(resp[0]["vm_status"], "ACCESS_DENIED");
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_simulate_transaction_with_write_scope_disabled() {
+    let mut context = new_test_context(current_function_name!());
+    let alice = &mut context.gen_account();
+    let bob = &mut context.gen_account();
+    let txn = context.mint_user_account(alice).await;
+    context.commit_block(&vec![txn]).await;
+
+    let txn = context.account_transfer_to(alice, bob.address(), SMALL_TRANSFER_AMOUNT);
+    let body = bcs::to_bytes(&txn).unwrap();
+    let path = format!(
+        "/transactions/simulate?write_scope={}",
+        alice.address().to_hex_literal()
+    );
+    context
+        .expect_status_code(403)
+        .post_bcs_txn(&path, body)
+        .await;
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn test_simulate_txn_with_aggregator() {
     let mut context = new_test_context(current_function_name!());
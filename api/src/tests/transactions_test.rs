@@ -3,9 +3,12 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use super::new_test_context;
-use crate::tests::{
-    new_test_context_with_config, new_test_context_with_db_sharding_and_internal_indexer,
-    new_test_context_with_sharding_and_delayed_internal_indexer,
+use crate::{
+    tests::{
+        new_test_context_with_config, new_test_context_with_db_sharding_and_internal_indexer,
+        new_test_context_with_sharding_and_delayed_internal_indexer,
+    },
+    transaction_stream::{TransactionFilter, TransactionStreamParams},
 };
 use aptos_api_test_context::{assert_json, current_function_name, pretty, TestContext};
 use aptos_config::config::{GasEstimationStaticOverride, NodeConfig};
@@ -20,7 +23,7 @@ use aptos_types::{
     account_config::aptos_test_root_address,
     transaction::{
         authenticator::{AuthenticationKey, TransactionAuthenticator},
-        EntryFunction, Script, SignedTransaction,
+        EntryFunction, Script, SignedTransaction, TransactionPayload,
     },
     utility_coin::{AptosCoinType, CoinType},
 };
@@ -128,6 +131,83 @@ async fn test_get_transactions_output_user_transaction_with_entry_function_paylo
     context.check_golden_output(txns);
 }
 
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn test_transaction_stream_filter() {
+    let mut context = new_test_context(current_function_name!());
+    let account = context.gen_account();
+    let txn = context.create_user_account(&account).await;
+    context.commit_block(&vec![txn.clone()]).await;
+
+    let ledger_info = context.context.get_latest_ledger_info_wrapped().unwrap();
+    let txns = context
+        .context
+        .get_transactions(0, 100, ledger_info.version())
+        .unwrap();
+    let version = txns
+        .iter()
+        .find(|data| data.transaction.try_as_signed_user_txn() == Some(&txn))
+        .unwrap()
+        .version;
+    let entry_function = match txn.payload() {
+        TransactionPayload::EntryFunction(entry_function) => entry_function.clone(),
+        _ => panic!("Expected an entry function payload"),
+    };
+    let matching_versions = |params: TransactionStreamParams| -> Vec<u64> {
+        let filter = TransactionFilter::try_from(&params).unwrap();
+        txns.iter()
+            .filter(|data| filter.matches(data))
+            .map(|data| data.version)
+            .collect()
+    };
+
+    // The sender and the entry function only match the user transaction
+    assert_eq!(
+        matching_versions(TransactionStreamParams {
+            start_version: None,
+            sender: Some(txn.sender().to_hex_literal()),
+            event_type: None,
+            entry_function: Some(format!(
+                "{}::{}",
+                entry_function.module().short_str_lossless(),
+                entry_function.function()
+            )),
+        }),
+        vec![version]
+    );
+
+    // The fee statement is emitted by the user transactions only
+    assert_eq!(
+        matching_versions(TransactionStreamParams {
+            start_version: None,
+            sender: None,
+            event_type: Some("0x1::transaction_fee::FeeStatement".to_string()),
+            entry_function: None,
+        }),
+        vec![version]
+    );
+
+    // Another entry function of the module doesn't match
+    assert!(matching_versions(TransactionStreamParams {
+        start_version: None,
+        sender: None,
+        event_type: None,
+        entry_function: Some(format!(
+            "{}::transfer",
+            entry_function.module().short_str_lossless()
+        )),
+    })
+    .is_empty());
+
+    // An invalid filter is rejected
+    assert!(TransactionFilter::try_from(&TransactionStreamParams {
+        start_version: None,
+        sender: Some("invalid".to_string()),
+        event_type: None,
+        entry_function: None,
+    })
+    .is_err());
+}
+
 #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
 async fn test_post_bcs_format_transaction() {
     let mut context = new_test_context(current_function_name!());
@@ -0,0 +1,350 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! A stream of the committed transactions matching a filter, pushed to the client as server-sent
+//! events. Each `transaction` event holds a transaction rendered as in `/transactions`, and its
+//! version as the event ID. Once the stream has caught up with the ledger, a `checkpoint` event
+//! with the last scanned version as the event ID is sent, so a client reconnecting with the
+//! `Last-Event-ID` header resumes right after the transactions it has already seen (or skipped).
+//! On a failure, an `error` event is sent and the stream ends.
+
+use crate::{context::Context, metrics::TRANSACTION_STREAM_GAUGE, response::BasicError};
+use anyhow::{ensure, Context as AnyhowContext};
+use aptos_api_types::{Address, EntryFunctionId, MoveType, Transaction, TransactionOnChainData};
+use aptos_types::{account_address::AccountAddress, transaction::TransactionPayload};
+use futures::{channel::mpsc, SinkExt};
+use move_core_types::{
+    identifier::Identifier,
+    language_storage::{ModuleId, TypeTag},
+};
+use poem::{
+    handler,
+    http::{HeaderMap, StatusCode},
+    web::{
+        sse::{Event, SSE},
+        Data, Query,
+    },
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    str::FromStr,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+
+const LAST_EVENT_ID_HEADER: &str = "Last-Event-ID";
+const TRANSACTION_EVENT: &str = "transaction";
+const CHECKPOINT_EVENT: &str = "checkpoint";
+const ERROR_EVENT: &str = "error";
+
+/// The number of events buffered for a slow client, before the stream stops reading the storage
+const STREAM_BUFFER_SIZE: usize = 100;
+const KEEP_ALIVE_INTERVAL: Duration = Duration::from_secs(15);
+
+#[derive(Deserialize, Serialize)]
+pub struct TransactionStreamParams {
+    /// The version to stream from, defaults to the next committed transaction. Ignored if the
+    /// `Last-Event-ID` header is set.
+    pub(crate) start_version: Option<u64>,
+    /// Only streams the user transactions sent by this account
+    pub(crate) sender: Option<String>,
+    /// Only streams the transactions emitting an event of this type
+    pub(crate) event_type: Option<String>,
+    /// Only streams the user transactions calling this entry function
+    pub(crate) entry_function: Option<String>,
+}
+
+#[handler]
+pub async fn stream_transactions_poem(
+    context: Data<&Arc<Context>>,
+    Query(params): Query<TransactionStreamParams>,
+    headers: &HeaderMap,
+) -> poem::Result<SSE> {
+    if !context.node_config.api.transaction_stream_enabled {
+        return Err(poem::Error::from_string(
+            "The transaction stream is disabled",
+            StatusCode::FORBIDDEN,
+        ));
+    }
+    let filter = TransactionFilter::try_from(&params)
+        .map_err(|err| poem::Error::from_string(err.to_string(), StatusCode::BAD_REQUEST))?;
+    let last_event_id = headers
+        .get(LAST_EVENT_ID_HEADER)
+        .map(|value| {
+            value
+                .to_str()
+                .ok()
+                .and_then(|value| value.parse::<u64>().ok())
+                .ok_or_else(|| {
+                    poem::Error::from_string(
+                        format!("Invalid {} header", LAST_EVENT_ID_HEADER),
+                        StatusCode::BAD_REQUEST,
+                    )
+                })
+        })
+        .transpose()?;
+
+    let ledger_info = context.get_latest_ledger_info_wrapped().map_err(|err| {
+        poem::Error::from_string(err.to_string(), StatusCode::SERVICE_UNAVAILABLE)
+    })?;
+    let start_version = match last_event_id {
+        Some(version) => version.saturating_add(1),
+        None => params
+            .start_version
+            .unwrap_or_else(|| ledger_info.version() + 1),
+    };
+    if start_version < ledger_info.oldest_version() {
+        return Err(poem::Error::from_string(
+            format!(
+                "Version {} has been pruned, the oldest available version is {}",
+                start_version,
+                ledger_info.oldest_version()
+            ),
+            StatusCode::GONE,
+        ));
+    }
+
+    let guard = StreamGuard::acquire(&context).ok_or_else(|| {
+        poem::Error::from_string(
+            "Too many transaction streams are open",
+            StatusCode::SERVICE_UNAVAILABLE,
+        )
+    })?;
+    let (sender, receiver) = mpsc::channel(STREAM_BUFFER_SIZE);
+    tokio::spawn(stream_transactions(
+        context.0.clone(),
+        filter,
+        start_version,
+        sender,
+        guard,
+    ));
+    Ok(SSE::new(receiver).keep_alive(KEEP_ALIVE_INTERVAL))
+}
+
+/// Reads the transactions from the storage, and sends the ones matching the filter until the
+/// client disconnects or a failure occurs.
+async fn stream_transactions(
+    context: Arc<Context>,
+    filter: TransactionFilter,
+    mut next_version: u64,
+    mut sender: mpsc::Sender<Event>,
+    _guard: StreamGuard,
+) {
+    let poll_interval =
+        Duration::from_millis(context.node_config.api.transaction_stream_poll_interval_ms);
+    let filter = Arc::new(filter);
+    let mut last_event_version = next_version.checked_sub(1);
+
+    while !sender.is_closed() {
+        let page = {
+            let context = context.clone();
+            let filter = filter.clone();
+            tokio::task::spawn_blocking(move || read_page(&context, &filter, next_version))
+                .await
+                .map_err(anyhow::Error::from)
+                .and_then(|page| page)
+        };
+        let page = match page {
+            Ok(page) => page,
+            Err(err) => {
+                let _ = sender
+                    .send(Event::message(format!("{:#}", err)).event_type(ERROR_EVENT))
+                    .await;
+                return;
+            },
+        };
+
+        for (version, transaction) in page.transactions {
+            let data = match serde_json::to_string(&transaction) {
+                Ok(data) => data,
+                Err(err) => {
+                    let _ = sender
+                        .send(Event::message(err.to_string()).event_type(ERROR_EVENT))
+                        .await;
+                    return;
+                },
+            };
+            let event = Event::message(data)
+                .event_type(TRANSACTION_EVENT)
+                .id(version.to_string());
+            if sender.send(event).await.is_err() {
+                // The client disconnected
+                return;
+            }
+            last_event_version = Some(version);
+        }
+        next_version = page.next_version;
+
+        if page.caught_up {
+            // Let the client resume after the scanned transactions, even if none matched
+            let last_scanned_version = next_version.checked_sub(1);
+            if last_scanned_version != last_event_version {
+                if let Some(version) = last_scanned_version {
+                    let event = Event::message(version.to_string())
+                        .event_type(CHECKPOINT_EVENT)
+                        .id(version.to_string());
+                    if sender.send(event).await.is_err() {
+                        return;
+                    }
+                    last_event_version = last_scanned_version;
+                }
+            }
+            tokio::time::sleep(poll_interval).await;
+        }
+    }
+}
+
+/// A page of the transactions matching the filter, along with their versions
+struct TransactionPage {
+    transactions: Vec<(u64, Transaction)>,
+    next_version: u64,
+    caught_up: bool,
+}
+
+/// Reads a page of transactions from the given version, and renders the ones matching the filter
+fn read_page(
+    context: &Context,
+    filter: &TransactionFilter,
+    start_version: u64,
+) -> anyhow::Result<TransactionPage> {
+    let ledger_info = context.get_latest_ledger_info_wrapped()?;
+    let ledger_version = ledger_info.version();
+    if start_version > ledger_version {
+        return Ok(TransactionPage {
+            transactions: vec![],
+            next_version: start_version,
+            caught_up: true,
+        });
+    }
+    ensure!(
+        start_version >= ledger_info.oldest_version(),
+        "Version {} has been pruned, the oldest available version is {}",
+        start_version,
+        ledger_info.oldest_version()
+    );
+
+    let data = context
+        .get_transactions(
+            start_version,
+            context.max_transactions_page_size(),
+            ledger_version,
+        )
+        .context("Failed to read raw transactions from storage")?;
+    let next_version = start_version + data.len() as u64;
+    let caught_up = data.is_empty() || next_version > ledger_version;
+
+    let (versions, data): (Vec<_>, Vec<_>) = data
+        .into_iter()
+        .filter(|txn| filter.matches(txn))
+        .map(|txn| (txn.version, txn))
+        .unzip();
+    let transactions =
+        context.render_transactions_non_sequential::<BasicError>(&ledger_info, data)?;
+    Ok(TransactionPage {
+        transactions: versions.into_iter().zip(transactions).collect(),
+        next_version,
+        caught_up,
+    })
+}
+
+/// The filter of a transaction stream, a transaction matches if it matches all the set fields
+#[derive(Debug, Default, PartialEq)]
+pub(crate) struct TransactionFilter {
+    sender: Option<AccountAddress>,
+    event_type: Option<TypeTag>,
+    entry_function: Option<(ModuleId, Identifier)>,
+}
+
+impl TransactionFilter {
+    pub(crate) fn matches(&self, txn: &TransactionOnChainData) -> bool {
+        let user_txn = txn.transaction.try_as_signed_user_txn();
+        if let Some(sender) = &self.sender {
+            if user_txn.map(|user_txn| user_txn.sender()) != Some(*sender) {
+                return false;
+            }
+        }
+        if let Some((module, function)) = &self.entry_function {
+            let calls_function = user_txn.map_or(false, |user_txn| match user_txn.payload() {
+                TransactionPayload::EntryFunction(entry_function) => {
+                    entry_function.module() == module
+                        && entry_function.function() == function.as_ident_str()
+                },
+                _ => false,
+            });
+            if !calls_function {
+                return false;
+            }
+        }
+        if let Some(event_type) = &self.event_type {
+            if !txn
+                .events
+                .iter()
+                .any(|event| event.type_tag() == event_type)
+            {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+impl TryFrom<&TransactionStreamParams> for TransactionFilter {
+    type Error = anyhow::Error;
+
+    fn try_from(params: &TransactionStreamParams) -> anyhow::Result<Self> {
+        let sender = params
+            .sender
+            .as_deref()
+            .map(Address::from_str)
+            .transpose()?
+            .map(AccountAddress::from);
+        let event_type = params
+            .event_type
+            .as_deref()
+            .map(|event_type| TypeTag::try_from(MoveType::from_str(event_type)?))
+            .transpose()?;
+        let entry_function = params
+            .entry_function
+            .as_deref()
+            .map(EntryFunctionId::from_str)
+            .transpose()?
+            .map(|id| (ModuleId::from(id.module), Identifier::from(id.name)));
+        Ok(Self {
+            sender,
+            event_type,
+            entry_function,
+        })
+    }
+}
+
+/// Counts an open transaction stream, until dropped
+struct StreamGuard {
+    active_connections: Arc<AtomicUsize>,
+}
+
+impl StreamGuard {
+    /// Returns a guard, or None if too many streams are open
+    fn acquire(context: &Context) -> Option<Self> {
+        let active_connections = context.transaction_stream_active_connections.clone();
+        let max_active_connections = context
+            .node_config
+            .api
+            .transaction_stream_max_active_connections;
+        if active_connections.fetch_add(1, Ordering::Relaxed) >= max_active_connections {
+            active_connections.fetch_sub(1, Ordering::Relaxed);
+            return None;
+        }
+        TRANSACTION_STREAM_GAUGE.inc();
+        Some(Self { active_connections })
+    }
+}
+
+impl Drop for StreamGuard {
+    fn drop(&mut self) {
+        self.active_connections.fetch_sub(1, Ordering::Relaxed);
+        TRANSACTION_STREAM_GAUGE.dec();
+    }
+}
@@ -17,20 +17,23 @@ use crate::{
         BasicErrorWith404, BasicResponse, BasicResponseStatus, BasicResult, BasicResultWith404,
         ForbiddenError, InsufficientStorageError, InternalError,
     },
+    state_override::StateOverrideView,
     ApiTags,
 };
 use anyhow::Context as AnyhowContext;
 use aptos_api_types::{
     verify_function_identifier, verify_module_identifier, Address, AptosError, AptosErrorCode,
     AsConverter, EncodeSubmissionRequest, GasEstimation, GasEstimationBcs, HashValue,
-    HexEncodedBytes, LedgerInfo, MoveType, PendingTransaction, SubmitTransactionRequest,
-    Transaction, TransactionData, TransactionOnChainData, TransactionsBatchSingleSubmissionFailure,
+    HexEncodedBytes, LedgerInfo, MoveType, PendingTransaction,
+    SimulateTransactionWithOverridesRequest, SubmitTransactionRequest, Transaction,
+    TransactionData, TransactionOnChainData, TransactionsBatchSingleSubmissionFailure,
     TransactionsBatchSubmissionResult, UserTransaction, VerifyInput, VerifyInputWithRecursion, U64,
 };
 use aptos_crypto::{hash::CryptoHash, signing_message};
 use aptos_types::{
     account_address::AccountAddress,
     mempool_status::MempoolStatusCode,
+    state_store::StateView,
     transaction::{
         EntryFunction, ExecutionStatus, MultisigTransactionPayload, RawTransaction,
         RawTransactionWithData, SignedTransaction, TransactionPayload,
@@ -38,7 +41,7 @@ use aptos_types::{
     vm_status::StatusCode,
     AptosCoinType, CoinType,
 };
-use aptos_vm::{AptosSimulationVM, AptosVM};
+use aptos_vm::{write_scope::WriteScope, AptosSimulationVM, AptosVM};
 use move_core_types::{ident_str, language_storage::ModuleId, vm_status::VMStatus};
 use poem_openapi::{
     param::{Path, Query},
@@ -488,6 +491,10 @@ impl TransactionsApi {
         /// If set to true, the transaction will use a higher price than the original
         /// estimate.
         estimate_prioritized_gas_unit_price: Query<Option<bool>>,
+        /// If set, the transaction fails with an ACCESS_DENIED status if it writes to any
+        /// other address, not counting the gas payment and the sequence number update. Must
+        /// be enabled in the API config of the node.
+        write_scope: Query<Option<Vec<Address>>>,
         data: SubmitTransactionPost,
     ) -> SimulateTransactionResult<Vec<UserTransaction>> {
         data.verify()
@@ -502,6 +509,9 @@ impl TransactionsApi {
         if !self.context.node_config.api.transaction_simulation_enabled {
             return Err(api_disabled("Simulate transaction"));
         }
+        if write_scope.0.is_some() && !self.context.node_config.api.simulation_write_scope_enabled {
+            return Err(api_disabled("Simulate transaction with a write scope"));
+        }
         self.context
             .check_api_output_enabled("Simulate transaction", &accept_type)?;
 
@@ -616,7 +626,97 @@ impl TransactionsApi {
                 );
             }
 
-            api.simulate(&accept_type, ledger_info, signed_transaction)
+            let write_scope = write_scope
+                .0
+                .map(|addresses| WriteScope::new(addresses.into_iter().map(AccountAddress::from)));
+            api.simulate(&accept_type, ledger_info, signed_transaction, write_scope)
+        })
+        .await
+    }
+
+    /// Simulate transaction with state overrides
+    ///
+    /// Simulates a transaction as the simulate endpoint does, but on top of ephemeral state
+    /// overrides applied to the latest state: resources, APT balances and the on-chain timestamp.
+    /// This can be used to preview the outcome of a transaction depending on setup steps that
+    /// haven't been executed yet. The overrides are discarded after the simulation.
+    ///
+    /// Resources are given in the same format as returned by the resource endpoints. An APT
+    /// balance is set in the coin store of the account if it has one, or in its primary
+    /// fungible store otherwise.
+    #[oai(
+        path = "/transactions/simulate_with_overrides",
+        method = "post",
+        operation_id = "simulate_transaction_with_overrides",
+        tag = "ApiTags::Transactions"
+    )]
+    async fn simulate_transaction_with_overrides(
+        &self,
+        accept_type: AcceptType,
+        data: Json<SimulateTransactionWithOverridesRequest>,
+    ) -> SimulateTransactionResult<Vec<UserTransaction>> {
+        data.0
+            .transaction
+            .verify()
+            .context("Simulated transaction invalid")
+            .map_err(|err| {
+                SubmitTransactionError::bad_request_with_code_no_info(
+                    err,
+                    AptosErrorCode::InvalidInput,
+                )
+            })?;
+        fail_point_poem("endpoint_simulate_transaction_with_overrides")?;
+        if !self.context.node_config.api.transaction_simulation_enabled {
+            return Err(api_disabled("Simulate transaction"));
+        }
+        self.context
+            .check_api_output_enabled("Simulate transaction", &accept_type)?;
+
+        let api = self.clone();
+        let context = self.context.clone();
+        api_spawn_blocking(move || {
+            let SimulateTransactionWithOverridesRequest {
+                transaction,
+                state_overrides,
+            } = data.0;
+            let ledger_info = context.get_latest_ledger_info()?;
+            let signed_transaction = api.get_signed_transaction(
+                &ledger_info,
+                SubmitTransactionPost::Json(Json(transaction)),
+            )?;
+
+            // See simulate_transaction for the simulation filter
+            if !context.node_config.api.simulation_filter.allows(
+                aptos_crypto::HashValue::zero(),
+                ledger_info.timestamp(),
+                &signed_transaction,
+            ) {
+                return Err(SubmitTransactionError::forbidden_with_code(
+                    "Transaction not allowed by simulation filter",
+                    AptosErrorCode::InvalidInput,
+                    &ledger_info,
+                ));
+            }
+
+            let mut state_view =
+                StateOverrideView::new(context.latest_state_view_poem(&ledger_info)?);
+            state_view
+                .apply(&context, state_overrides)
+                .context("Failed to apply the state overrides")
+                .map_err(|err| {
+                    SubmitTransactionError::bad_request_with_code(
+                        err,
+                        AptosErrorCode::InvalidInput,
+                        &ledger_info,
+                    )
+                })?;
+            api.simulate_with_state_view(
+                &accept_type,
+                ledger_info,
+                signed_transaction,
+                &state_view,
+                None,
+            )
         })
         .await
     }
@@ -1377,6 +1477,21 @@ impl TransactionsApi {
         accept_type: &AcceptType,
         ledger_info: LedgerInfo,
         txn: SignedTransaction,
+        write_scope: Option<WriteScope>,
+    ) -> SimulateTransactionResult<Vec<UserTransaction>> {
+        let state_view = self.context.latest_state_view_poem(&ledger_info)?;
+        self.simulate_with_state_view(accept_type, ledger_info, txn, &state_view, write_scope)
+    }
+
+    /// Simulates the transaction against the given state view, e.g. the latest state with
+    /// overrides, and optionally within a write scope
+    pub fn simulate_with_state_view(
+        &self,
+        accept_type: &AcceptType,
+        ledger_info: LedgerInfo,
+        txn: SignedTransaction,
+        state_view: &impl StateView,
+        write_scope: Option<WriteScope>,
     ) -> SimulateTransactionResult<Vec<UserTransaction>> {
         // The caller must ensure that the signature is not valid, as otherwise
         // a malicious actor could execute the transaction without their knowledge
@@ -1389,9 +1504,11 @@ impl TransactionsApi {
         }
 
         // Simulate transaction
-        let state_view = self.context.latest_state_view_poem(&ledger_info)?;
-        let (vm_status, output) =
-            AptosSimulationVM::create_vm_and_simulate_signed_transaction(&txn, &state_view);
+        let (vm_status, output) = AptosSimulationVM::create_vm_and_simulate_signed_transaction(
+            &txn,
+            state_view,
+            write_scope,
+        );
         let version = ledger_info.version();
 
         // Ensure that all known statuses return their values in the output (even if they aren't supposed to)
@@ -96,6 +96,11 @@ impl<'a, S: StateView> MoveConverter<'a, S> {
         self.inner.view_resource(tag, bytes)?.try_into()
     }
 
+    /// Returns the resource group the resource belongs to, if any
+    pub fn find_resource_group(&self, tag: &StructTag) -> Option<StructTag> {
+        self.inner.view_resource_group_member(tag)
+    }
+
     pub fn is_resource_group(&self, tag: &StructTag) -> bool {
         if let Ok(Some(module)) = self.inner.view_module(&tag.module_id()) {
             if let Some(md) = aptos_framework::get_metadata(&module.metadata) {
@@ -2,6 +2,7 @@
 // Parts of the project are originally copyright © Meta Platforms, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::U64;
 use aptos_types::vm_status::StatusCode;
 use poem_openapi::{Enum, Object};
 use serde::{Deserialize, Serialize};
@@ -16,6 +17,10 @@ pub struct AptosError {
     pub error_code: AptosErrorCode,
     /// A code providing VM error details when submitting transactions to the VM
     pub vm_error_code: Option<u64>,
+    /// The oldest version that can be queried, when the requested version has been pruned
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[oai(default, skip_serializing_if = "Option::is_none")]
+    pub oldest_available_version: Option<U64>,
 }
 
 impl std::fmt::Display for AptosError {
@@ -35,6 +40,7 @@ impl AptosError {
             message: format!("{:#}", error),
             error_code,
             vm_error_code: None,
+            oldest_available_version: None,
         }
     }
 
@@ -47,8 +53,14 @@ impl AptosError {
             message: format!("{:#}", error),
             error_code,
             vm_error_code: Some(vm_error_code as u64),
+            oldest_available_version: None,
         }
     }
+
+    pub fn with_oldest_available_version(mut self, version: u64) -> Self {
+        self.oldest_available_version = Some(version.into());
+        self
+    }
 }
 
 /// These codes provide more granular error information beyond just the HTTP
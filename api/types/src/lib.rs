@@ -41,7 +41,11 @@ pub use move_types::{
     ResourceGroup, MAX_RECURSIVE_TYPES_ALLOWED, U128, U256, U64,
 };
 use serde::{Deserialize, Deserializer};
-pub use state::RawStateValueRequest;
+pub use state::{
+    BalanceOverride, ModuleUpgradeIssue, ModuleUpgradeIssueKind, ModuleUpgradeReport,
+    PackageUpgradeCheckRequest, PackageUpgradeReport, RawStateValueRequest, ResourceOverride,
+    SimulateTransactionWithOverridesRequest, StateOverrides,
+};
 use std::str::FromStr;
 pub use table::{RawTableItemRequest, TableItemRequest};
 pub use transaction::{
@@ -1,8 +1,13 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::HexEncodedBytes;
-use poem_openapi::Object;
+use crate::{
+    Address, HexEncodedBytes, IdentifierWrapper, MoveModuleId, MoveStructTag,
+    SubmitTransactionRequest, U64,
+};
+use aptos_vm::verifier::package_upgrade;
+use move_binary_format::compatibility::{CompatibilityIssue, FunctionChange};
+use poem_openapi::{Enum, Object};
 use serde::{Deserialize, Serialize};
 
 /// Table Item request for the GetTableItemRaw API
@@ -10,3 +15,148 @@ use serde::{Deserialize, Serialize};
 pub struct RawStateValueRequest {
     pub key: HexEncodedBytes,
 }
+
+/// A request to simulate a transaction on top of ephemeral state overrides
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Object)]
+pub struct SimulateTransactionWithOverridesRequest {
+    pub transaction: SubmitTransactionRequest,
+    pub state_overrides: StateOverrides,
+}
+
+/// Ephemeral changes applied on top of the latest state, in order: the resources, the balances
+/// and then the timestamp
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize, Object)]
+pub struct StateOverrides {
+    /// Resources to set, replacing the existing ones
+    #[serde(default)]
+    #[oai(default)]
+    pub resources: Vec<ResourceOverride>,
+    /// APT balances to set
+    #[serde(default)]
+    #[oai(default)]
+    pub balances: Vec<BalanceOverride>,
+    /// The on-chain timestamp to set, in microseconds
+    pub timestamp_usecs: Option<U64>,
+}
+
+/// A resource to set under an account
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Object)]
+pub struct ResourceOverride {
+    pub address: Address,
+    #[serde(rename = "type")]
+    #[oai(rename = "type")]
+    pub typ: MoveStructTag,
+    /// The resource, in the same format as returned by the resource endpoints
+    pub data: serde_json::Value,
+}
+
+/// An APT balance to set for an account, either in its coin store or its primary fungible store
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Object)]
+pub struct BalanceOverride {
+    pub address: Address,
+    pub amount: U64,
+}
+
+/// A request to check the modules of a package against their versions on chain
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Object)]
+pub struct PackageUpgradeCheckRequest {
+    /// The bytecode of the modules of the package
+    pub modules: Vec<HexEncodedBytes>,
+}
+
+/// Result of checking the modules of a package against their versions on chain
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Object)]
+pub struct PackageUpgradeReport {
+    /// Whether publishing the modules would pass the compatibility checks
+    pub compatible: bool,
+    pub modules: Vec<ModuleUpgradeReport>,
+}
+
+/// Result of checking a module against its version on chain
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Object)]
+pub struct ModuleUpgradeReport {
+    pub module: MoveModuleId,
+    /// Whether the module is published for the first time
+    pub is_new: bool,
+    pub issues: Vec<ModuleUpgradeIssue>,
+}
+
+/// An incompatibility of a module with its version on chain
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize, Object)]
+pub struct ModuleUpgradeIssue {
+    pub kind: ModuleUpgradeIssueKind,
+    /// Name of the struct or function concerned, if any
+    pub name: Option<IdentifierWrapper>,
+    /// Human readable description of the incompatibility
+    pub message: String,
+}
+
+/// Kind of incompatibility of a module upgrade
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize, Enum)]
+#[serde(rename_all = "snake_case")]
+#[oai(rename_all = "snake_case")]
+pub enum ModuleUpgradeIssueKind {
+    ModuleAddressChanged,
+    ModuleNameChanged,
+    StructRemoved,
+    StructAbilitiesRemoved,
+    StructTypeParametersChanged,
+    StructLayoutChanged,
+    FunctionRemoved,
+    FunctionVisibilityChanged,
+    FunctionEntryRemoved,
+    FunctionParameterTypesChanged,
+    FunctionReturnTypeChanged,
+    FunctionTypeParametersChanged,
+    FriendsRemoved,
+}
+
+impl From<&CompatibilityIssue> for ModuleUpgradeIssue {
+    fn from(issue: &CompatibilityIssue) -> Self {
+        use CompatibilityIssue::*;
+        use ModuleUpgradeIssueKind as Kind;
+
+        let (kind, name) = match issue {
+            ModuleAddressChanged(_) => (Kind::ModuleAddressChanged, None),
+            ModuleNameChanged(name) => (Kind::ModuleNameChanged, Some(name)),
+            StructRemoved(name) => (Kind::StructRemoved, Some(name)),
+            StructAbilitiesRemoved { name, .. } => (Kind::StructAbilitiesRemoved, Some(name)),
+            StructTypeParametersChanged(name) => (Kind::StructTypeParametersChanged, Some(name)),
+            StructLayoutChanged(name) => (Kind::StructLayoutChanged, Some(name)),
+            FunctionRemoved(name) => (Kind::FunctionRemoved, Some(name)),
+            FunctionChanged { name, change } => {
+                let kind = match change {
+                    FunctionChange::Visibility => Kind::FunctionVisibilityChanged,
+                    FunctionChange::EntryRemoved => Kind::FunctionEntryRemoved,
+                    FunctionChange::ParameterTypes => Kind::FunctionParameterTypesChanged,
+                    FunctionChange::ReturnType => Kind::FunctionReturnTypeChanged,
+                    FunctionChange::TypeParameters => Kind::FunctionTypeParametersChanged,
+                };
+                (kind, Some(name))
+            },
+            FriendsRemoved(_) => (Kind::FriendsRemoved, None),
+        };
+        Self {
+            kind,
+            name: name.cloned().map(IdentifierWrapper::from),
+            message: issue.to_string(),
+        }
+    }
+}
+
+impl From<package_upgrade::PackageUpgradeReport> for PackageUpgradeReport {
+    fn from(report: package_upgrade::PackageUpgradeReport) -> Self {
+        Self {
+            compatible: report.is_compatible(),
+            modules: report
+                .modules
+                .into_iter()
+                .map(|module| ModuleUpgradeReport {
+                    module: module.module_id.into(),
+                    is_new: module.is_new,
+                    issues: module.issues.iter().map(ModuleUpgradeIssue::from).collect(),
+                })
+                .collect(),
+        }
+    }
+}
@@ -0,0 +1,187 @@
+/// Calibration Functions of the hash natives, across input sizes. Each native is called 10 times
+/// per iteration, so that its running time dominates the one of the loop.
+module natives::hash {
+    use std::hash;
+    use std::vector;
+    use aptos_std::aptos_hash;
+
+    fun bytes(len: u64): vector<u8> {
+        let v = vector::empty();
+        let i = 0;
+        while (i < len) {
+            vector::push_back(&mut v, ((i % 256) as u8));
+            i = i + 1;
+        };
+        v
+    }
+
+    fun calibrate_sha2_256_impl(len: u64, num_iterations: u64) {
+        let msg = bytes(len);
+        let i = 0;
+        while (i < num_iterations) {
+            hash::sha2_256(msg);
+            hash::sha2_256(msg);
+            hash::sha2_256(msg);
+            hash::sha2_256(msg);
+            hash::sha2_256(msg);
+            hash::sha2_256(msg);
+            hash::sha2_256(msg);
+            hash::sha2_256(msg);
+            hash::sha2_256(msg);
+            hash::sha2_256(msg);
+            i = i + 1;
+        }
+    }
+
+    public entry fun calibrate_sha2_256_0b_x100() {
+        calibrate_sha2_256_impl(0, 10);
+    }
+
+    public entry fun calibrate_sha2_256_256b_x100() {
+        calibrate_sha2_256_impl(256, 10);
+    }
+
+    public entry fun calibrate_sha2_256_4kb_x100() {
+        calibrate_sha2_256_impl(4096, 10);
+    }
+
+    public entry fun calibrate_sha2_256_4kb_x500() {
+        calibrate_sha2_256_impl(4096, 50);
+    }
+
+    fun calibrate_sha3_256_impl(len: u64, num_iterations: u64) {
+        let msg = bytes(len);
+        let i = 0;
+        while (i < num_iterations) {
+            hash::sha3_256(msg);
+            hash::sha3_256(msg);
+            hash::sha3_256(msg);
+            hash::sha3_256(msg);
+            hash::sha3_256(msg);
+            hash::sha3_256(msg);
+            hash::sha3_256(msg);
+            hash::sha3_256(msg);
+            hash::sha3_256(msg);
+            hash::sha3_256(msg);
+            i = i + 1;
+        }
+    }
+
+    public entry fun calibrate_sha3_256_0b_x100() {
+        calibrate_sha3_256_impl(0, 10);
+    }
+
+    public entry fun calibrate_sha3_256_256b_x100() {
+        calibrate_sha3_256_impl(256, 10);
+    }
+
+    public entry fun calibrate_sha3_256_4kb_x100() {
+        calibrate_sha3_256_impl(4096, 10);
+    }
+
+    public entry fun calibrate_sha3_256_4kb_x500() {
+        calibrate_sha3_256_impl(4096, 50);
+    }
+
+    fun calibrate_keccak256_impl(len: u64, num_iterations: u64) {
+        let msg = bytes(len);
+        let i = 0;
+        while (i < num_iterations) {
+            aptos_hash::keccak256(msg);
+            aptos_hash::keccak256(msg);
+            aptos_hash::keccak256(msg);
+            aptos_hash::keccak256(msg);
+            aptos_hash::keccak256(msg);
+            aptos_hash::keccak256(msg);
+            aptos_hash::keccak256(msg);
+            aptos_hash::keccak256(msg);
+            aptos_hash::keccak256(msg);
+            aptos_hash::keccak256(msg);
+            i = i + 1;
+        }
+    }
+
+    public entry fun calibrate_keccak256_0b_x100() {
+        calibrate_keccak256_impl(0, 10);
+    }
+
+    public entry fun calibrate_keccak256_256b_x100() {
+        calibrate_keccak256_impl(256, 10);
+    }
+
+    public entry fun calibrate_keccak256_4kb_x100() {
+        calibrate_keccak256_impl(4096, 10);
+    }
+
+    public entry fun calibrate_keccak256_4kb_x500() {
+        calibrate_keccak256_impl(4096, 50);
+    }
+
+    fun calibrate_blake2b_256_impl(len: u64, num_iterations: u64) {
+        let msg = bytes(len);
+        let i = 0;
+        while (i < num_iterations) {
+            aptos_hash::blake2b_256(msg);
+            aptos_hash::blake2b_256(msg);
+            aptos_hash::blake2b_256(msg);
+            aptos_hash::blake2b_256(msg);
+            aptos_hash::blake2b_256(msg);
+            aptos_hash::blake2b_256(msg);
+            aptos_hash::blake2b_256(msg);
+            aptos_hash::blake2b_256(msg);
+            aptos_hash::blake2b_256(msg);
+            aptos_hash::blake2b_256(msg);
+            i = i + 1;
+        }
+    }
+
+    public entry fun calibrate_blake2b_256_0b_x100() {
+        calibrate_blake2b_256_impl(0, 10);
+    }
+
+    public entry fun calibrate_blake2b_256_256b_x100() {
+        calibrate_blake2b_256_impl(256, 10);
+    }
+
+    public entry fun calibrate_blake2b_256_4kb_x100() {
+        calibrate_blake2b_256_impl(4096, 10);
+    }
+
+    public entry fun calibrate_blake2b_256_4kb_x500() {
+        calibrate_blake2b_256_impl(4096, 50);
+    }
+
+    fun calibrate_sip_hash_impl(len: u64, num_iterations: u64) {
+        let msg = bytes(len);
+        let i = 0;
+        while (i < num_iterations) {
+            aptos_hash::sip_hash(msg);
+            aptos_hash::sip_hash(msg);
+            aptos_hash::sip_hash(msg);
+            aptos_hash::sip_hash(msg);
+            aptos_hash::sip_hash(msg);
+            aptos_hash::sip_hash(msg);
+            aptos_hash::sip_hash(msg);
+            aptos_hash::sip_hash(msg);
+            aptos_hash::sip_hash(msg);
+            aptos_hash::sip_hash(msg);
+            i = i + 1;
+        }
+    }
+
+    public entry fun calibrate_sip_hash_0b_x100() {
+        calibrate_sip_hash_impl(0, 10);
+    }
+
+    public entry fun calibrate_sip_hash_256b_x100() {
+        calibrate_sip_hash_impl(256, 10);
+    }
+
+    public entry fun calibrate_sip_hash_4kb_x100() {
+        calibrate_sip_hash_impl(4096, 10);
+    }
+
+    public entry fun calibrate_sip_hash_4kb_x500() {
+        calibrate_sip_hash_impl(4096, 50);
+    }
+}
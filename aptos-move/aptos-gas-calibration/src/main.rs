@@ -5,14 +5,18 @@ mod math;
 mod math_interface;
 mod measurements;
 mod measurements_helpers;
+mod proposal;
 mod solve;
 use aptos_abstract_gas_usage::{aggregate_terms, expand_terms};
 use aptos_gas_algebra::DynamicExpression;
 use clap::Parser;
 use math_interface::{convert_to_matrix_format, total_num_of_cols, total_num_rows};
 use measurements::compile_and_run;
+use proposal::{
+    propose_gas_parameter_changes, report_gas_parameter_changes, write_gas_parameter_changes,
+};
 use solve::{build_coefficient_matrix, build_constant_matrix, least_squares};
-use std::collections::BTreeMap;
+use std::{collections::BTreeMap, path::PathBuf};
 
 /// Automated Gas Calibration to calibrate Move bytecode and Native Functions
 #[derive(Parser, Debug)]
@@ -28,6 +32,10 @@ struct Args {
     /// Maximum execution time in milliseconds
     #[clap(short, long, default_value_t = 300)]
     max_execution_time: u64,
+
+    /// Path of a JSON file to write the proposed gas parameter changes to
+    #[clap(short, long)]
+    output: Option<PathBuf>,
 }
 
 fn main() {
@@ -76,11 +84,25 @@ fn main() {
     let mut const_matrix = build_constant_matrix(measurements.regular_meter, nrows, vec_col);
 
     // Solve the system of linear equations
-    least_squares(
+    let internal_gas_costs = least_squares(
         mappings,
         &mut coeff_matrix,
         &mut const_matrix,
         measurements.equation_names,
         max_execution_time,
     );
+
+    // Propose the changes against the current gas schedule
+    if let Some(internal_gas_costs) = internal_gas_costs {
+        let changes = propose_gas_parameter_changes(&internal_gas_costs);
+        report_gas_parameter_changes(&changes);
+        if let Some(output) = args.output {
+            write_gas_parameter_changes(&output, &changes)
+                .expect("Failed to write the proposed gas parameter changes");
+            println!(
+                "\nproposed gas parameter changes written to {}",
+                output.display()
+            );
+        }
+    }
 }
@@ -0,0 +1,162 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_gas_schedule::{
+    AptosGasParameters, InitialGasSchedule, ToOnChainGasSchedule, LATEST_GAS_FEATURE_VERSION,
+};
+use serde::Serialize;
+use std::{collections::BTreeMap, fs::File, path::Path};
+
+/// A proposed change of a gas parameter, from its calibrated cost
+#[derive(Debug, Serialize)]
+pub struct GasParameterChange {
+    /// Name of the gas parameter in the abstract gas usage, e.g. `HASH_SHA2_256_BASE`
+    pub name: String,
+    /// Key of the gas parameter in the on-chain gas schedule, if found
+    pub key: Option<String>,
+    /// Value of the gas parameter in the current gas schedule, if found
+    pub current: Option<u64>,
+    /// Calibrated value of the gas parameter
+    pub proposed: u64,
+}
+
+/// Compare the calibrated internal gas costs with the current gas schedule
+///
+/// ### Arguments
+///
+/// * `internal_gas_costs` - Calibrated internal gas cost of every gas parameter
+pub fn propose_gas_parameter_changes(
+    internal_gas_costs: &BTreeMap<String, f64>,
+) -> Vec<GasParameterChange> {
+    let current_schedule = current_gas_schedule_by_name();
+    internal_gas_costs
+        .iter()
+        .map(|(name, cost)| {
+            let current = current_schedule.get(name);
+            GasParameterChange {
+                name: name.clone(),
+                key: current.map(|(key, _)| key.clone()),
+                current: current.map(|(_, value)| *value),
+                // A negative cost means the parameter is negligible compared to the noise
+                proposed: cost.max(0.0).round() as u64,
+            }
+        })
+        .collect()
+}
+
+/// The entries of the latest gas schedule, by the name of their gas parameter in the abstract
+/// gas usage. A name is the upper case of the field of the parameter, which usually matches the
+/// on-chain key without its prefix (e.g. `move_stdlib.hash.sha2_256.base` is named
+/// `HASH_SHA2_256_BASE`). The parameters with a diverging key are reported without a current
+/// value.
+fn current_gas_schedule_by_name() -> BTreeMap<String, (String, u64)> {
+    AptosGasParameters::initial()
+        .to_on_chain_gas_schedule(LATEST_GAS_FEATURE_VERSION)
+        .into_iter()
+        .map(|(key, value)| {
+            let name = key
+                .split_once('.')
+                .map_or(key.as_str(), |(_prefix, name)| name)
+                .replace('.', "_")
+                .to_uppercase();
+            (name, (key, value))
+        })
+        .collect()
+}
+
+/// display the proposed changes of the gas parameters to the user
+///
+/// ### Arguments
+///
+/// * `changes` - Proposed changes of the gas parameters
+pub fn report_gas_parameter_changes(changes: &[GasParameterChange]) {
+    println!("\nproposed gas parameter changes:\n");
+    for change in changes {
+        match (&change.key, change.current) {
+            (Some(key), Some(current)) if current == change.proposed => {
+                println!("  {} = {} (unchanged)", key, current);
+            },
+            (Some(key), Some(current)) => {
+                let diff_pct =
+                    (change.proposed as f64 - current as f64) / current.max(1) as f64 * 100.0;
+                println!(
+                    "- {} = {}\n+ {} = {} ({:+.1}%)",
+                    key, current, key, change.proposed, diff_pct
+                );
+            },
+            _ => println!(
+                "? {} = {} (not found in the current gas schedule)",
+                change.name, change.proposed
+            ),
+        }
+    }
+}
+
+/// write the proposed changes of the gas parameters as JSON
+///
+/// ### Arguments
+///
+/// * `path` - Path of the output file
+/// * `changes` - Proposed changes of the gas parameters
+pub fn write_gas_parameter_changes(
+    path: &Path,
+    changes: &[GasParameterChange],
+) -> anyhow::Result<()> {
+    let file = File::create(path)?;
+    serde_json::to_writer_pretty(file, changes)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn on_chain_value(key: &str) -> Option<u64> {
+        AptosGasParameters::initial()
+            .to_on_chain_gas_schedule(LATEST_GAS_FEATURE_VERSION)
+            .into_iter()
+            .find_map(|(k, value)| (k == key).then_some(value))
+    }
+
+    #[test]
+    fn test_propose_gas_parameter_changes() {
+        let internal_gas_costs = BTreeMap::from([
+            ("HASH_SHA2_256_BASE".to_string(), 12345.6),
+            ("HASH_SHA2_256_PER_BYTE".to_string(), -2.0),
+            ("NOT_A_GAS_PARAMETER".to_string(), 7.4),
+        ]);
+
+        let changes = propose_gas_parameter_changes(&internal_gas_costs);
+        assert_eq!(changes.len(), 3);
+
+        assert_eq!(changes[0].name, "HASH_SHA2_256_BASE");
+        assert_eq!(
+            changes[0].key.as_deref(),
+            Some("move_stdlib.hash.sha2_256.base")
+        );
+        assert_eq!(
+            changes[0].current,
+            on_chain_value("move_stdlib.hash.sha2_256.base")
+        );
+        assert!(changes[0].current.is_some());
+        assert_eq!(changes[0].proposed, 12346);
+
+        // Negative costs are proposed as zero.
+        assert_eq!(
+            changes[1].key.as_deref(),
+            Some("move_stdlib.hash.sha2_256.per_byte")
+        );
+        assert_eq!(changes[1].proposed, 0);
+
+        assert_eq!(changes[2].name, "NOT_A_GAS_PARAMETER");
+        assert_eq!(changes[2].key, None);
+        assert_eq!(changes[2].current, None);
+        assert_eq!(changes[2].proposed, 7);
+
+        // The same calibration always results in the same proposal.
+        assert_eq!(
+            serde_json::to_string(&changes).unwrap(),
+            serde_json::to_string(&propose_gas_parameter_changes(&internal_gas_costs)).unwrap()
+        );
+    }
+}
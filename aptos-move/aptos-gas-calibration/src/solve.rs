@@ -45,7 +45,8 @@ pub fn build_constant_matrix(input: Vec<u128>, nrows: usize, ncols: usize) -> DM
     const_matrix
 }
 
-/// compute the least squares solution
+/// compute the least squares solution, and return the internal gas cost of every gas parameter
+/// if the system could be solved
 ///
 /// ### Arguments
 ///
@@ -59,7 +60,7 @@ pub fn least_squares(
     const_matrix: &mut DMatrix<f64>,
     equation_names: Vec<String>,
     max_execution_time: u64,
-) {
+) -> Option<BTreeMap<String, f64>> {
     let lss = compute_least_square_solutions(coeff_matrix, const_matrix);
     if let Ok(answer) = lss {
         let mut x_hat = answer;
@@ -76,9 +77,14 @@ pub fn least_squares(
 
         report_outliers(&equation_names, &computed_time_and_outliers);
 
-        convert_to_internal_gas_cost(&mut x_hat, max_execution_time, keys);
+        Some(convert_to_internal_gas_cost(
+            &mut x_hat,
+            max_execution_time,
+            keys,
+        ))
     } else {
         report_undetermined_gas_params(input, coeff_matrix, const_matrix);
+        None
     }
 }
 
@@ -178,7 +184,7 @@ fn convert_to_internal_gas_cost(
     x_hat: &mut DMatrix<f64>,
     max_execution_time: u64,
     gas_params: Vec<String>,
-) {
+) -> BTreeMap<String, f64> {
     let max_execution_gas = u64::from(TransactionGasParameters::initial().max_execution_gas);
     let one_microsec_per_internal_gas =
         (max_execution_gas / max_execution_time) / MILLISECONDS_TO_MICROSECONDS;
@@ -188,12 +194,15 @@ fn convert_to_internal_gas_cost(
         one_microsec_per_internal_gas
     );
 
+    let mut internal_gas_costs = BTreeMap::new();
     let nrows = x_hat.nrows();
     let ncols = x_hat.ncols();
     for i in 0..nrows {
         for j in 0..ncols {
             let internal_gas_cost = x_hat[(i, j)] * one_microsec_per_internal_gas as f64;
             println!("{} = {}", gas_params[i], internal_gas_cost);
+            internal_gas_costs.insert(gas_params[i].clone(), internal_gas_cost);
         }
     }
+    internal_gas_costs
 }
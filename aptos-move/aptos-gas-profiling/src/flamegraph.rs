@@ -2,15 +2,22 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    log::{CallFrame, ExecutionAndIOCosts, ExecutionGasEvent, StorageFees},
+    log::{CallFrame, ExecutionAndIOCosts, ExecutionGasEvent, StorageFees, TransactionGasLog},
     render::Render,
 };
 use inferno::flamegraph::TextTruncateDirection;
 use move_core_types::gas_algebra::InternalGas;
 use regex::Captures;
 
+/// Whether a cost is spent on execution or on IO.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CostKind {
+    Execution,
+    IO,
+}
+
 #[derive(Debug)]
-struct LineBuffer(Vec<String>);
+struct LineBuffer(Vec<(CostKind, String)>);
 
 impl LineBuffer {
     fn new() -> Self {
@@ -18,15 +25,54 @@ impl LineBuffer {
     }
 
     fn push(&mut self, item: impl AsRef<str>, count: impl Into<u64>) {
+        self.push_of_kind(CostKind::Execution, item, count)
+    }
+
+    fn push_of_kind(&mut self, kind: CostKind, item: impl AsRef<str>, count: impl Into<u64>) {
         let count: u64 = count.into();
 
         if count > 0 {
-            self.0.push(format!("{} {}", item.as_ref(), count));
+            self.0.push((kind, format!("{} {}", item.as_ref(), count)));
         }
     }
 
     fn into_inner(self) -> Vec<String> {
+        self.0.into_iter().map(|(_, line)| line).collect()
+    }
+
+    fn into_lines_of_kind(self, kind: CostKind) -> Vec<String> {
         self.0
+            .into_iter()
+            .filter(|(line_kind, _)| *line_kind == kind)
+            .map(|(_, line)| line)
+            .collect()
+    }
+}
+
+/// The folded stack lines of a transaction, split by the kind of cost, in internal gas units
+/// for the execution and IO, and in Octa for the storage.
+#[derive(Debug, Clone)]
+pub struct FoldedStacks {
+    pub execution: Vec<String>,
+    pub io: Vec<String>,
+    pub storage: Vec<String>,
+}
+
+impl TransactionGasLog {
+    /// Convert the gas log into folded stack lines, split into execution, IO and storage, with
+    /// the frames being the Move call stack.
+    pub fn to_folded_stacks(&self) -> FoldedStacks {
+        FoldedStacks {
+            execution: self
+                .exec_io
+                .to_folded_stack_line_buffer()
+                .into_lines_of_kind(CostKind::Execution),
+            io: self
+                .exec_io
+                .to_folded_stack_line_buffer()
+                .into_lines_of_kind(CostKind::IO),
+            storage: self.storage.to_folded_stack_lines(),
+        }
     }
 }
 
@@ -97,6 +143,10 @@ impl ExecutionAndIOCosts {
     /// Convert the execution gas log into folded stack lines, which can
     /// then be used to generate a flamegraph.
     fn to_folded_stack_lines(&self) -> Vec<String> {
+        self.to_folded_stack_line_buffer().into_inner()
+    }
+
+    fn to_folded_stack_line_buffer(&self) -> LineBuffer {
         let mut lines = LineBuffer::new();
 
         lines.push("intrinsic", self.intrinsic_cost);
@@ -147,7 +197,8 @@ impl ExecutionAndIOCosts {
                             ),
                             *cost,
                         ),
-                        LoadResource { addr, ty, cost } => self.lines.push(
+                        LoadResource { addr, ty, cost } => self.lines.push_of_kind(
+                            CostKind::IO,
                             format!("{};load<{}::{}>", self.path(), Render(addr), ty),
                             *cost,
                         ),
@@ -170,16 +221,18 @@ impl ExecutionAndIOCosts {
         .visit(&self.call_graph);
 
         if let Some(cost) = &self.transaction_transient {
-            lines.push("ledger_writes;transaction", *cost)
+            lines.push_of_kind(CostKind::IO, "ledger_writes;transaction", *cost)
         }
         for item in &self.events_transient {
-            lines.push(
+            lines.push_of_kind(
+                CostKind::IO,
                 format!("ledger_writes;events;{}", Render(&item.ty)),
                 item.cost,
             )
         }
         for item in &self.write_set_transient {
-            lines.push(
+            lines.push_of_kind(
+                CostKind::IO,
                 format!(
                     "ledger_writes;state_write_ops;{}<{}>",
                     Render(&item.op_type),
@@ -189,7 +242,7 @@ impl ExecutionAndIOCosts {
             )
         }
 
-        lines.into_inner()
+        lines
     }
 
     /// Tries to generate a flamegraph from the execution log.
@@ -9,6 +9,8 @@ mod misc;
 mod profiler;
 mod render;
 mod report;
+mod speedscope;
 
+pub use flamegraph::FoldedStacks;
 pub use log::{FrameName, TransactionGasLog};
 pub use profiler::GasProfiler;
@@ -0,0 +1,137 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{flamegraph::FoldedStacks, log::TransactionGasLog};
+use anyhow::Result;
+use serde_json::{json, Value};
+use std::{collections::BTreeMap, fs, path::Path};
+
+const SPEEDSCOPE_SCHEMA: &str = "https://www.speedscope.app/file-format-schema.json";
+
+/// Frames shared by all profiles of a speedscope file, indexed by name.
+#[derive(Default)]
+struct Frames {
+    names: Vec<String>,
+    indices: BTreeMap<String, usize>,
+}
+
+impl Frames {
+    fn index(&mut self, name: &str) -> usize {
+        if let Some(idx) = self.indices.get(name) {
+            return *idx;
+        }
+        let idx = self.names.len();
+        self.names.push(name.to_string());
+        self.indices.insert(name.to_string(), idx);
+        idx
+    }
+}
+
+/// Convert folded stack lines into a sampled speedscope profile, one sample per line in the
+/// order of execution. None will be returned if there are no lines.
+fn to_sampled_profile(
+    frames: &mut Frames,
+    name: &str,
+    lines: &[String],
+    scale: f64,
+) -> Option<Value> {
+    let mut samples = vec![];
+    let mut weights = vec![];
+    for line in lines {
+        let (stack, count) = line
+            .rsplit_once(' ')
+            .expect("folded stack line should end with a count");
+        let count: u64 = count.parse().expect("should be able to parse count as u64");
+        samples.push(
+            stack
+                .split(';')
+                .map(|frame| frames.index(frame))
+                .collect::<Vec<_>>(),
+        );
+        weights.push(count as f64 / scale);
+    }
+
+    if samples.is_empty() {
+        return None;
+    }
+    let total: f64 = weights.iter().sum();
+    Some(json!({
+        "type": "sampled",
+        "name": name,
+        "unit": "none",
+        "startValue": 0,
+        "endValue": total,
+        "samples": samples,
+        "weights": weights,
+    }))
+}
+
+impl TransactionGasLog {
+    /// Convert the gas log into a speedscope file (https://www.speedscope.app), with one
+    /// profile for each of the execution and IO costs in gas units, and the storage fees in
+    /// Octa.
+    pub fn to_speedscope(&self, name: String) -> Value {
+        let FoldedStacks {
+            execution,
+            io,
+            storage,
+        } = self.to_folded_stacks();
+        let scaling_factor = u64::from(self.exec_io.gas_scaling_factor) as f64;
+
+        let mut frames = Frames::default();
+        let profiles: Vec<_> = [
+            to_sampled_profile(
+                &mut frames,
+                "Execution (gas units)",
+                &execution,
+                scaling_factor,
+            ),
+            to_sampled_profile(&mut frames, "IO (gas units)", &io, scaling_factor),
+            to_sampled_profile(&mut frames, "Storage (Octa)", &storage, 1.0),
+        ]
+        .into_iter()
+        .flatten()
+        .collect();
+
+        json!({
+            "$schema": SPEEDSCOPE_SCHEMA,
+            "shared": {
+                "frames": frames
+                    .names
+                    .into_iter()
+                    .map(|name| json!({ "name": name }))
+                    .collect::<Vec<_>>(),
+            },
+            "profiles": profiles,
+            "name": name,
+            "activeProfileIndex": 0,
+            "exporter": "aptos-gas-profiling",
+        })
+    }
+
+    /// Write the gas profiles of the transaction into the given directory: a speedscope file
+    /// (`speedscope.json`), and a folded stack file for each of the execution, IO and storage
+    /// costs (e.g. `execution.folded`), that flamegraph tools can render.
+    pub fn generate_profiles(&self, path: impl AsRef<Path>, name: String) -> Result<()> {
+        let path = path.as_ref();
+        fs::create_dir_all(path)?;
+
+        let folded_stacks = self.to_folded_stacks();
+        for (file_name, lines) in [
+            ("execution.folded", &folded_stacks.execution),
+            ("io.folded", &folded_stacks.io),
+            ("storage.folded", &folded_stacks.storage),
+        ] {
+            if !lines.is_empty() {
+                fs::write(path.join(file_name), lines.join("\n") + "\n")?;
+            }
+        }
+
+        fs::write(
+            path.join("speedscope.json"),
+            serde_json::to_string(&self.to_speedscope(name))?,
+        )?;
+
+        Ok(())
+    }
+}
@@ -134,6 +134,7 @@ pub enum FeatureFlag {
     NativeMemoryOperations,
     EnableLoaderV2,
     DisallowInitModuleToPublishModules,
+    GovernanceBlockGasLimitOverride,
 }
 
 fn generate_features_blob(writer: &CodeWriter, data: &[u64]) {
@@ -357,6 +358,9 @@ impl From<FeatureFlag> for AptosFeatureFlag {
             FeatureFlag::DisallowInitModuleToPublishModules => {
                 AptosFeatureFlag::DISALLOW_INIT_MODULE_TO_PUBLISH_MODULES
             },
+            FeatureFlag::GovernanceBlockGasLimitOverride => {
+                AptosFeatureFlag::GOVERNANCE_BLOCK_GAS_LIMIT_OVERRIDE
+            },
         }
     }
 }
@@ -507,6 +511,9 @@ impl From<AptosFeatureFlag> for FeatureFlag {
             AptosFeatureFlag::DISALLOW_INIT_MODULE_TO_PUBLISH_MODULES => {
                 FeatureFlag::DisallowInitModuleToPublishModules
             },
+            AptosFeatureFlag::GOVERNANCE_BLOCK_GAS_LIMIT_OVERRIDE => {
+                FeatureFlag::GovernanceBlockGasLimitOverride
+            },
         }
     }
 }
@@ -800,7 +800,7 @@ pub fn get_execution_hash(result: &[(String, String)]) -> Option<HashValue> {
     }
 }
 
-fn append_script_hash(raw_script: String) -> String {
+pub(crate) fn append_script_hash(raw_script: String) -> String {
     let temp_script_path = TempPath::new();
     temp_script_path.create_as_file().unwrap();
 
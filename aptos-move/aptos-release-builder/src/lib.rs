@@ -3,6 +3,7 @@
 
 pub mod components;
 pub mod simulate;
+pub mod upgrade_bundle;
 mod utils;
 pub mod validate;
 
@@ -3,13 +3,15 @@
 
 use anyhow::{bail, Context};
 use aptos_crypto::{ed25519::Ed25519PrivateKey, ValidCryptoMaterialStringExt};
-use aptos_framework::natives::code::PackageRegistry;
+use aptos_framework::{natives::code::PackageRegistry, ReleaseBundle};
 use aptos_gas_schedule::LATEST_GAS_FEATURE_VERSION;
 use aptos_release_builder::{
     components::fetch_config,
     initialize_aptos_core_path,
     simulate::simulate_all_proposals,
+    upgrade_bundle::generate_upgrade_bundle,
     validate::{DEFAULT_RESOLUTION_TIME, FAST_RESOLUTION_TIME},
+    ExecutionMode,
 };
 use aptos_types::{
     account_address::AccountAddress,
@@ -86,6 +88,25 @@ pub enum Commands {
         #[clap(long)]
         profile_gas: Option<bool>,
     },
+    /// Generate the governance proposals to upgrade the framework from one release bundle to
+    /// another, along with a summary of the changes of each module and of their compatibility.
+    GenerateUpgradeBundle {
+        /// Path to the release bundle (`.mrb`) which is currently deployed.
+        #[clap(long)]
+        from: PathBuf,
+
+        /// Path to the release bundle (`.mrb`) to upgrade to.
+        #[clap(long)]
+        to: PathBuf,
+
+        /// Output directory to store the proposals and the summary.
+        #[clap(short, long)]
+        output_dir: PathBuf,
+
+        /// Generate multi-step proposals instead of proposals executed by the root account.
+        #[clap(long)]
+        multi_step: bool,
+    },
     /// Simulate a multi-step proposal on the specified network, using its current states.
     /// The simulation will execute the governance scripts, as if the proposal is already
     /// approved.
@@ -221,6 +242,33 @@ async fn main() -> anyhow::Result<()> {
 
             Ok(())
         },
+        Commands::GenerateUpgradeBundle {
+            from,
+            to,
+            output_dir,
+            multi_step,
+        } => {
+            let execution_mode = if multi_step {
+                ExecutionMode::MultiStep
+            } else {
+                ExecutionMode::RootSigner
+            };
+            let bundle = generate_upgrade_bundle(
+                &ReleaseBundle::read(from)?,
+                &ReleaseBundle::read(to)?,
+                execution_mode,
+                output_dir.as_path(),
+            )
+            .with_context(|| "Failed to generate upgrade bundle".to_string())?;
+            print!("{}", bundle);
+            if !bundle.is_compatible() {
+                bail!(
+                    "The upgrade is not compatible, see {}",
+                    output_dir.display()
+                )
+            }
+            Ok(())
+        },
         Commands::Simulate {
             network,
             path,
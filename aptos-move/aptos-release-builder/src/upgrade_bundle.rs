@@ -0,0 +1,443 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Generates the upgrade of the framework from one release bundle to another in one artifact: the
+//! governance proposal scripts of the changed packages, a summary of the bytecode changes of each
+//! module, and the compatibility issues of the upgrade.
+
+use crate::components::{append_script_hash, get_execution_hash, ExecutionMode};
+use anyhow::{anyhow, Result};
+use aptos_framework::{ReleaseBundle, ReleasePackage};
+use aptos_temppath::TempPath;
+use aptos_types::account_address::AccountAddress;
+use move_binary_format::{
+    access::ModuleAccess,
+    compatibility::Compatibility,
+    file_format::{FunctionDefinition, SignatureToken, StructHandleIndex},
+    CompiledModule,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt,
+    path::Path,
+};
+
+const SUMMARY_FILE: &str = "upgrade_summary.json";
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum ModuleChange {
+    Added,
+    Removed,
+    Changed,
+    Unchanged,
+}
+
+/// The difference between the old and the new version of a module.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ModuleDiff {
+    /// The fully qualified name of the module, e.g. `0x1::coin`
+    pub module: String,
+    pub change: ModuleChange,
+    /// The size of the old bytecode in bytes, zero if the module is added
+    pub old_size: usize,
+    /// The size of the new bytecode in bytes, zero if the module is removed
+    pub new_size: usize,
+    pub functions_added: Vec<String>,
+    pub functions_removed: Vec<String>,
+    /// The functions whose signature or instructions differ. Since the instructions refer to the
+    /// pools of the module, a function may be reported even if only other parts of the module
+    /// changed.
+    pub functions_changed: Vec<String>,
+    pub structs_added: Vec<String>,
+    pub structs_removed: Vec<String>,
+    /// The reasons why the new version cannot replace the old version on chain
+    pub compatibility_issues: Vec<String>,
+}
+
+impl ModuleDiff {
+    fn new(module: String, change: ModuleChange, old_size: usize, new_size: usize) -> Self {
+        Self {
+            module,
+            change,
+            old_size,
+            new_size,
+            functions_added: vec![],
+            functions_removed: vec![],
+            functions_changed: vec![],
+            structs_added: vec![],
+            structs_removed: vec![],
+            compatibility_issues: vec![],
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct PackageUpgrade {
+    pub name: String,
+    pub address: AccountAddress,
+    /// The file name of the proposal script, if the package changed
+    pub proposal_script: Option<String>,
+    pub modules: Vec<ModuleDiff>,
+}
+
+impl PackageUpgrade {
+    pub fn is_changed(&self) -> bool {
+        self.modules
+            .iter()
+            .any(|module| module.change != ModuleChange::Unchanged)
+    }
+}
+
+/// The summary of the upgrade from one release bundle to another.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct UpgradeBundle {
+    /// The packages of the new release bundle, in the order in which they are published
+    pub packages: Vec<PackageUpgrade>,
+}
+
+impl UpgradeBundle {
+    /// Compares the packages of the two release bundles, without generating the proposals.
+    pub fn new(old: &ReleaseBundle, new: &ReleaseBundle) -> Result<Self> {
+        let old_packages: BTreeMap<&str, &ReleasePackage> = old
+            .packages
+            .iter()
+            .map(|package| (package.name(), package))
+            .collect();
+        let packages = new
+            .packages
+            .iter()
+            .map(|package| diff_package(old_packages.get(package.name()).copied(), package))
+            .collect::<Result<_>>()?;
+        Ok(Self { packages })
+    }
+
+    /// Returns true if all modules of the new release bundle can replace the old ones on chain.
+    pub fn is_compatible(&self) -> bool {
+        self.packages
+            .iter()
+            .flat_map(|package| package.modules.iter())
+            .all(|module| module.compatibility_issues.is_empty())
+    }
+}
+
+impl fmt::Display for UpgradeBundle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for package in &self.packages {
+            if !package.is_changed() {
+                writeln!(f, "{}: unchanged", package.name)?;
+                continue;
+            }
+            writeln!(f, "{} ({}):", package.name, package.address)?;
+            for module in &package.modules {
+                if module.change == ModuleChange::Unchanged {
+                    continue;
+                }
+                writeln!(
+                    f,
+                    "  {} {:?}: {} -> {} bytes",
+                    module.module, module.change, module.old_size, module.new_size
+                )?;
+                for (kind, items) in [
+                    ("functions added", &module.functions_added),
+                    ("functions removed", &module.functions_removed),
+                    ("functions changed", &module.functions_changed),
+                    ("structs added", &module.structs_added),
+                    ("structs removed", &module.structs_removed),
+                    ("incompatible", &module.compatibility_issues),
+                ] {
+                    if !items.is_empty() {
+                        writeln!(f, "    {}: {}", kind, items.join(", "))?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Compares the release bundles, and writes the proposal scripts of the changed packages to
+/// `<output_dir>/sources`, and the summary of the upgrade to `<output_dir>/upgrade_summary.json`.
+pub fn generate_upgrade_bundle(
+    old: &ReleaseBundle,
+    new: &ReleaseBundle,
+    execution_mode: ExecutionMode,
+    output_dir: &Path,
+) -> Result<UpgradeBundle> {
+    let mut bundle = UpgradeBundle::new(old, new)?;
+
+    let mut changed_packages = bundle
+        .packages
+        .iter()
+        .zip(&new.packages)
+        .filter(|(upgrade, _)| upgrade.is_changed())
+        .map(|(upgrade, package)| (upgrade.address, package))
+        .collect::<Vec<_>>();
+
+    // Multi-step proposals need the hash of the next script, so they are generated in reverse.
+    let is_multi_step = execution_mode == ExecutionMode::MultiStep;
+    if is_multi_step {
+        changed_packages.reverse();
+    }
+    let mut scripts: Vec<(String, String)> = vec![];
+    for (address, package) in changed_packages {
+        let temp_script_path = TempPath::new();
+        temp_script_path.create_as_file()?;
+        let mut move_script_path = temp_script_path.path().to_path_buf();
+        move_script_path.set_extension("move");
+
+        if is_multi_step {
+            package.generate_script_proposal_multi_step(
+                address,
+                move_script_path.clone(),
+                get_execution_hash(&scripts),
+            )?;
+        } else {
+            package.generate_script_proposal_testnet(address, move_script_path.clone())?;
+        }
+
+        let mut script = format!(
+            "// Builder commit hash: {}\n",
+            aptos_build_info::get_git_hash()
+        );
+        script.push_str(&std::fs::read_to_string(move_script_path.as_path())?);
+        scripts.push((package.name().to_string(), script));
+    }
+    if is_multi_step {
+        scripts.reverse();
+    }
+
+    let source_dir = output_dir.join("sources");
+    std::fs::create_dir_all(&source_dir)
+        .map_err(|err| anyhow!("Fail to create folder for source: {:?}", err))?;
+    for (idx, (package_name, script)) in scripts.into_iter().enumerate() {
+        let script_name = format!("{}-{}.move", idx, package_name);
+        std::fs::write(
+            source_dir.join(&script_name),
+            append_script_hash(script).as_bytes(),
+        )
+        .map_err(|err| anyhow!("Failed to write to file: {:?}", err))?;
+        if let Some(upgrade) = bundle
+            .packages
+            .iter_mut()
+            .find(|upgrade| upgrade.name == package_name)
+        {
+            upgrade.proposal_script = Some(script_name);
+        }
+    }
+
+    std::fs::write(
+        output_dir.join(SUMMARY_FILE),
+        serde_json::to_string_pretty(&bundle)?,
+    )
+    .map_err(|err| anyhow!("Failed to write to file: {:?}", err))?;
+    Ok(bundle)
+}
+
+fn diff_package(old: Option<&ReleasePackage>, new: &ReleasePackage) -> Result<PackageUpgrade> {
+    let new_modules = compiled_modules(new)?;
+    let address = new_modules
+        .first()
+        .map(|(_, module)| *module.self_addr())
+        .ok_or_else(|| anyhow!("package {} has no modules", new.name()))?;
+    let mut old_modules: BTreeMap<String, (usize, CompiledModule)> = match old {
+        Some(old) => compiled_modules(old)?
+            .into_iter()
+            .map(|(size, module)| (module_name(&module), (size, module)))
+            .collect(),
+        None => BTreeMap::new(),
+    };
+
+    let mut modules = vec![];
+    for (new_size, new_module) in &new_modules {
+        let name = module_name(new_module);
+        modules.push(match old_modules.remove(&name) {
+            Some((old_size, old_module)) => {
+                diff_module(name, old_size, &old_module, *new_size, new_module)
+            },
+            None => ModuleDiff {
+                functions_added: function_names(new_module),
+                structs_added: struct_names(new_module),
+                ..ModuleDiff::new(name, ModuleChange::Added, 0, *new_size)
+            },
+        });
+    }
+    // The modules of a package cannot be removed on chain, so this is always incompatible.
+    for (name, (old_size, old_module)) in old_modules {
+        modules.push(ModuleDiff {
+            functions_removed: function_names(&old_module),
+            structs_removed: struct_names(&old_module),
+            compatibility_issues: vec!["module removed".to_string()],
+            ..ModuleDiff::new(name, ModuleChange::Removed, old_size, 0)
+        });
+    }
+
+    Ok(PackageUpgrade {
+        name: new.name().to_string(),
+        address,
+        proposal_script: None,
+        modules,
+    })
+}
+
+fn diff_module(
+    name: String,
+    old_size: usize,
+    old: &CompiledModule,
+    new_size: usize,
+    new: &CompiledModule,
+) -> ModuleDiff {
+    if old == new {
+        return ModuleDiff::new(name, ModuleChange::Unchanged, old_size, new_size);
+    }
+
+    let old_functions = function_fingerprints(old);
+    let new_functions = function_fingerprints(new);
+    let old_structs = struct_names(old).into_iter().collect::<BTreeSet<_>>();
+    let new_structs = struct_names(new).into_iter().collect::<BTreeSet<_>>();
+
+    ModuleDiff {
+        functions_added: new_functions
+            .keys()
+            .filter(|name| !old_functions.contains_key(*name))
+            .cloned()
+            .collect(),
+        functions_removed: old_functions
+            .keys()
+            .filter(|name| !new_functions.contains_key(*name))
+            .cloned()
+            .collect(),
+        functions_changed: new_functions
+            .iter()
+            .filter(|(name, fingerprint)| {
+                old_functions
+                    .get(*name)
+                    .map_or(false, |old_fingerprint| old_fingerprint != *fingerprint)
+            })
+            .map(|(name, _)| name.clone())
+            .collect(),
+        structs_added: new_structs.difference(&old_structs).cloned().collect(),
+        structs_removed: old_structs.difference(&new_structs).cloned().collect(),
+        compatibility_issues: Compatibility::full_check()
+            .check_issues(old, new)
+            .iter()
+            .map(|issue| issue.to_string())
+            .collect(),
+        ..ModuleDiff::new(name, ModuleChange::Changed, old_size, new_size)
+    }
+}
+
+fn compiled_modules(package: &ReleasePackage) -> Result<Vec<(usize, CompiledModule)>> {
+    package
+        .code()
+        .into_iter()
+        .map(|code| Ok((code.len(), CompiledModule::deserialize(code)?)))
+        .collect()
+}
+
+fn module_name(module: &CompiledModule) -> String {
+    module.self_id().short_str_lossless()
+}
+
+fn struct_names(module: &CompiledModule) -> Vec<String> {
+    module
+        .struct_defs()
+        .iter()
+        .map(|def| {
+            let handle = module.struct_handle_at(def.struct_handle);
+            module.identifier_at(handle.name).to_string()
+        })
+        .collect()
+}
+
+fn function_names(module: &CompiledModule) -> Vec<String> {
+    function_fingerprints(module).into_keys().collect()
+}
+
+/// Returns the functions of the module along with a description of their signature and code,
+/// which is independent of the indices of the handles used in the signature.
+fn function_fingerprints(module: &CompiledModule) -> BTreeMap<String, String> {
+    module
+        .function_defs()
+        .iter()
+        .map(|def| {
+            let handle = module.function_handle_at(def.function);
+            let name = module.identifier_at(handle.name).to_string();
+            (name, function_fingerprint(module, def))
+        })
+        .collect()
+}
+
+fn function_fingerprint(module: &CompiledModule, def: &FunctionDefinition) -> String {
+    let handle = module.function_handle_at(def.function);
+    let types = |tokens: &[SignatureToken]| {
+        tokens
+            .iter()
+            .map(|token| type_name(module, token))
+            .collect::<Vec<_>>()
+            .join(", ")
+    };
+    format!(
+        "{:?} entry={} {:?}({}): ({}) {:?}",
+        def.visibility,
+        def.is_entry,
+        handle.type_parameters,
+        types(&module.signature_at(handle.parameters).0),
+        types(&module.signature_at(handle.return_).0),
+        def.code.as_ref().map(|code| &code.code),
+    )
+}
+
+fn type_name(module: &CompiledModule, token: &SignatureToken) -> String {
+    use SignatureToken::*;
+    let struct_name = |idx: StructHandleIndex| {
+        let handle = module.struct_handle_at(idx);
+        let module_handle = module.module_handle_at(handle.module);
+        format!(
+            "{}::{}",
+            module
+                .module_id_for_handle(module_handle)
+                .short_str_lossless(),
+            module.identifier_at(handle.name)
+        )
+    };
+    match token {
+        Bool => "bool".to_string(),
+        U8 => "u8".to_string(),
+        U16 => "u16".to_string(),
+        U32 => "u32".to_string(),
+        U64 => "u64".to_string(),
+        U128 => "u128".to_string(),
+        U256 => "u256".to_string(),
+        Address => "address".to_string(),
+        Signer => "signer".to_string(),
+        Vector(elem) => format!("vector<{}>", type_name(module, elem)),
+        Struct(idx) => struct_name(*idx),
+        StructInstantiation(idx, ty_args) => format!(
+            "{}<{}>",
+            struct_name(*idx),
+            ty_args
+                .iter()
+                .map(|ty| type_name(module, ty))
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        Reference(ty) => format!("&{}", type_name(module, ty)),
+        MutableReference(ty) => format!("&mut {}", type_name(module, ty)),
+        TypeParameter(idx) => format!("T{}", idx),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_upgrade_to_same_release_is_unchanged() {
+        let release = aptos_framework::testnet_release_bundle();
+        let bundle = UpgradeBundle::new(release, release).unwrap();
+        assert_eq!(bundle.packages.len(), release.packages.len());
+        assert!(bundle.is_compatible());
+        assert!(bundle.packages.iter().all(|package| !package.is_changed()));
+    }
+}
@@ -0,0 +1,394 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Generation of typed Rust bindings for the events (structs declared with `#[event]`) of
+//! compiled Move modules, so that integrators can decode the BCS payload of an event, or match
+//! its type, without hand-writing the struct.
+
+use move_binary_format::{
+    access::ModuleAccess,
+    file_format::{SignatureToken, StructDefinition, StructFieldInformation},
+    CompiledModule,
+};
+use move_core_types::{
+    account_address::AccountAddress,
+    identifier::{IdentStr, Identifier},
+    language_storage::ModuleId,
+};
+use serde::Deserialize;
+use serde_generate::indent::{IndentConfig, IndentedWriter};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    io::{Result, Write},
+};
+
+/// The key of the Aptos metadata in the metadata section of a module, which holds the struct
+/// attributes.
+const APTOS_METADATA_KEY_V1: &[u8] = b"aptos::metadata_v1";
+
+/// The kind of the `#[event]` attribute, as defined in the framework module metadata.
+const EVENT_ATTRIBUTE_KIND: u8 = 4;
+
+/// The module metadata, as defined in the framework, only the struct attributes are read here.
+#[derive(Deserialize)]
+struct RuntimeModuleMetadataV1 {
+    _error_map: BTreeMap<u64, ErrorDescription>,
+    struct_attributes: BTreeMap<String, Vec<KnownAttribute>>,
+    _fun_attributes: BTreeMap<String, Vec<KnownAttribute>>,
+}
+
+#[derive(Deserialize)]
+struct ErrorDescription {
+    _code_name: String,
+    _code_description: String,
+}
+
+#[derive(Deserialize)]
+struct KnownAttribute {
+    kind: u8,
+    _args: Vec<String>,
+}
+
+const RUST_KEYWORDS: &[&str] = &[
+    "as", "async", "await", "box", "break", "const", "continue", "crate", "dyn", "else", "enum",
+    "extern", "false", "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move",
+    "mut", "pub", "ref", "return", "static", "struct", "super", "trait", "true", "type", "unsafe",
+    "use", "where", "while", "yield",
+];
+
+/// Output typed Rust structs for the events of the given modules, one Rust module per Move
+/// module. The structs from the given modules that the events refer to are output as well.
+/// Events with type parameters, or referring to a type that cannot be bound (e.g. a struct from
+/// a module which is not given), are skipped with a comment.
+pub fn output(out: &mut dyn Write, modules: &[CompiledModule]) -> Result<()> {
+    let mut emitter = EventEmitter {
+        out: IndentedWriter::new(out, IndentConfig::Space(4)),
+        modules: modules
+            .iter()
+            .map(|module| (module.self_id(), module))
+            .collect(),
+        supported: BTreeMap::new(),
+    };
+
+    // Find the events, and all the structs they refer to
+    let mut structs = BTreeMap::<ModuleId, BTreeSet<Identifier>>::new();
+    let mut skipped = BTreeMap::<ModuleId, Vec<Identifier>>::new();
+    let mut events = BTreeSet::new();
+    for module in modules {
+        for name in event_names(module) {
+            let id = (module.self_id(), name.clone());
+            let mut dependencies = BTreeSet::new();
+            if emitter.is_supported(&id, &mut dependencies) {
+                for (module_id, name) in dependencies {
+                    structs.entry(module_id).or_default().insert(name);
+                }
+                events.insert(id);
+            } else {
+                skipped.entry(module.self_id()).or_default().push(name);
+            }
+        }
+    }
+    if structs.is_empty() && skipped.is_empty() {
+        return Ok(());
+    }
+
+    emitter.output_preamble()?;
+    let module_ids: BTreeSet<_> = structs.keys().chain(skipped.keys()).cloned().collect();
+    for module_id in module_ids {
+        writeln!(
+            emitter.out,
+            "\n/// Bindings for the module `{}`.",
+            module_id.short_str_lossless()
+        )?;
+        writeln!(
+            emitter.out,
+            "pub mod {} {{",
+            quote_identifier(module_id.name())
+        )?;
+        emitter.out.indent();
+        writeln!(emitter.out, "use super::*;")?;
+        for name in skipped.get(&module_id).into_iter().flatten() {
+            writeln!(
+                emitter.out,
+                "\n// Skipped the event `{}`: generic or referring to an unsupported type.",
+                name
+            )?;
+        }
+        for name in structs.get(&module_id).into_iter().flatten() {
+            let id = (module_id.clone(), name.clone());
+            emitter.output_struct(&id, events.contains(&id))?;
+        }
+        emitter.out.unindent();
+        writeln!(emitter.out, "}}")?;
+    }
+    Ok(())
+}
+
+/// Returns the names of the structs declared with `#[event]` in the module.
+fn event_names(module: &CompiledModule) -> Vec<Identifier> {
+    let metadata = module
+        .metadata
+        .iter()
+        .find(|metadata| metadata.key == APTOS_METADATA_KEY_V1)
+        .and_then(|metadata| bcs::from_bytes::<RuntimeModuleMetadataV1>(&metadata.value).ok());
+    let metadata = match metadata {
+        Some(metadata) => metadata,
+        None => return vec![],
+    };
+    metadata
+        .struct_attributes
+        .into_iter()
+        .filter(|(_, attributes)| {
+            attributes
+                .iter()
+                .any(|attribute| attribute.kind == EVENT_ATTRIBUTE_KIND)
+        })
+        .filter_map(|(name, _)| Identifier::new(name).ok())
+        .collect()
+}
+
+/// Escapes the identifier if it is a Rust keyword.
+fn quote_identifier(name: &IdentStr) -> String {
+    if RUST_KEYWORDS.contains(&name.as_str()) {
+        format!("r#{}", name)
+    } else {
+        name.to_string()
+    }
+}
+
+/// Shared state for the event bindings generator.
+struct EventEmitter<'a, T> {
+    /// Writer.
+    out: IndentedWriter<T>,
+    /// The modules to generate the bindings for.
+    modules: BTreeMap<ModuleId, &'a CompiledModule>,
+    /// Whether a struct can be bound, by struct.
+    supported: BTreeMap<(ModuleId, Identifier), bool>,
+}
+
+impl<'a, T> EventEmitter<'a, T>
+where
+    T: Write,
+{
+    fn output_preamble(&mut self) -> Result<()> {
+        writeln!(
+            self.out,
+            r#"// This file was generated. Do not modify!
+//
+// To update this code, run `cargo run -p aptos-sdk-builder -- --module-directories <dir>`.
+
+#![allow(dead_code)]
+#![allow(unused_imports)]
+
+use aptos_types::move_utils::move_event_v2::MoveEventV2Type;
+use move_core_types::{{
+    account_address::AccountAddress, ident_str, identifier::IdentStr,
+    move_resource::MoveStructType, u256::U256,
+}};
+use serde::{{Deserialize, Serialize}};"#
+        )
+    }
+
+    fn output_struct(&mut self, id: &(ModuleId, Identifier), is_event: bool) -> Result<()> {
+        let (module_id, name) = id;
+        let (module, definition) = self.find_struct(module_id, name).expect("struct exists");
+        let fields = match &definition.field_information {
+            StructFieldInformation::Declared(fields) => fields,
+            _ => unreachable!("only declared structs are supported"),
+        };
+
+        writeln!(self.out)?;
+        writeln!(
+            self.out,
+            "/// {} `{}::{}`.",
+            if is_event { "Event" } else { "Struct" },
+            module_id.short_str_lossless(),
+            name
+        )?;
+        writeln!(
+            self.out,
+            "#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]"
+        )?;
+        // The compiler adds a `dummy_field` to the structs without fields, so there is always one
+        writeln!(self.out, "pub struct {} {{", name)?;
+        self.out.indent();
+        for field in fields {
+            let typ = self
+                .quote_type(module, module_id, &field.signature.0, &mut BTreeSet::new())
+                .expect("field type is supported");
+            writeln!(
+                self.out,
+                "pub {}: {},",
+                quote_identifier(module.identifier_at(field.name)),
+                typ
+            )?;
+        }
+        self.out.unindent();
+        writeln!(self.out, "}}")?;
+
+        writeln!(self.out, "\nimpl MoveStructType for {} {{", name)?;
+        self.out.indent();
+        if module_id.address() != &AccountAddress::ONE {
+            writeln!(
+                self.out,
+                "const ADDRESS: AccountAddress = AccountAddress::new({:?});",
+                module_id.address().into_bytes()
+            )?;
+        }
+        writeln!(
+            self.out,
+            "const MODULE_NAME: &'static IdentStr = ident_str!(\"{}\");",
+            module_id.name()
+        )?;
+        writeln!(
+            self.out,
+            "const STRUCT_NAME: &'static IdentStr = ident_str!(\"{}\");",
+            name
+        )?;
+        self.out.unindent();
+        writeln!(self.out, "}}")?;
+        if is_event {
+            writeln!(self.out, "\nimpl MoveEventV2Type for {} {{}}", name)?;
+        }
+        Ok(())
+    }
+
+    /// Returns whether the struct can be bound, and adds it along with the structs it refers to
+    /// into `dependencies` if so.
+    fn is_supported(
+        &mut self,
+        id: &(ModuleId, Identifier),
+        dependencies: &mut BTreeSet<(ModuleId, Identifier)>,
+    ) -> bool {
+        let mut struct_dependencies = BTreeSet::new();
+        let supported = match self.supported.get(id) {
+            Some(false) => return false,
+            Some(true) => {
+                self.collect_dependencies(id, &mut struct_dependencies);
+                true
+            },
+            None => {
+                let supported = self.collect_dependencies(id, &mut struct_dependencies);
+                self.supported.insert(id.clone(), supported);
+                supported
+            },
+        };
+        if supported {
+            dependencies.insert(id.clone());
+            dependencies.extend(struct_dependencies);
+        }
+        supported
+    }
+
+    /// Collects the structs that the fields of the struct refer to, returns false if the struct
+    /// cannot be bound.
+    fn collect_dependencies(
+        &mut self,
+        (module_id, name): &(ModuleId, Identifier),
+        dependencies: &mut BTreeSet<(ModuleId, Identifier)>,
+    ) -> bool {
+        let (module, definition) = match self.find_struct(module_id, name) {
+            Some(found) => found,
+            None => return false,
+        };
+        if !module
+            .struct_handle_at(definition.struct_handle)
+            .type_parameters
+            .is_empty()
+        {
+            return false;
+        }
+        let fields = match &definition.field_information {
+            StructFieldInformation::Declared(fields) => fields,
+            _ => return false,
+        };
+        let mut referred = BTreeSet::new();
+        for field in fields {
+            if self
+                .quote_type(module, module_id, &field.signature.0, &mut referred)
+                .is_none()
+            {
+                return false;
+            }
+        }
+        referred
+            .into_iter()
+            .all(|id| self.is_supported(&id, dependencies))
+    }
+
+    fn find_struct(
+        &self,
+        module_id: &ModuleId,
+        name: &IdentStr,
+    ) -> Option<(&'a CompiledModule, &'a StructDefinition)> {
+        let module = *self.modules.get(module_id)?;
+        let definition = module.struct_defs().iter().find(|definition| {
+            module.identifier_at(module.struct_handle_at(definition.struct_handle).name) == name
+        })?;
+        Some((module, definition))
+    }
+
+    /// Returns the Rust type of a field in the Rust module of `current`, adding the structs it
+    /// refers to into `referred`, or None if the type is not supported.
+    fn quote_type(
+        &self,
+        module: &CompiledModule,
+        current: &ModuleId,
+        token: &SignatureToken,
+        referred: &mut BTreeSet<(ModuleId, Identifier)>,
+    ) -> Option<String> {
+        use SignatureToken::*;
+        Some(match token {
+            Bool => "bool".into(),
+            U8 => "u8".into(),
+            U16 => "u16".into(),
+            U32 => "u32".into(),
+            U64 => "u64".into(),
+            U128 => "u128".into(),
+            U256 => "U256".into(),
+            Address => "AccountAddress".into(),
+            Vector(element) => format!(
+                "Vec<{}>",
+                self.quote_type(module, current, element, referred)?
+            ),
+            Struct(idx) | StructInstantiation(idx, _) => {
+                let handle = module.struct_handle_at(*idx);
+                let module_handle = module.module_handle_at(handle.module);
+                let module_id = ModuleId::new(
+                    *module.address_identifier_at(module_handle.address),
+                    module.identifier_at(module_handle.name).to_owned(),
+                );
+                let name = module.identifier_at(handle.name);
+                let type_args = match token {
+                    StructInstantiation(_, type_args) => type_args.as_slice(),
+                    _ => &[],
+                };
+                match (
+                    module_id.address() == &AccountAddress::ONE,
+                    module_id.name().as_str(),
+                    name.as_str(),
+                ) {
+                    (true, "string", "String") => "String".into(),
+                    (true, "object", "Object") => "AccountAddress".into(),
+                    (true, "option", "Option") => format!(
+                        "Option<{}>",
+                        self.quote_type(module, current, type_args.first()?, referred)?
+                    ),
+                    _ => {
+                        if !type_args.is_empty() {
+                            return None;
+                        }
+                        let rust_name = if &module_id == current {
+                            name.to_string()
+                        } else {
+                            format!("super::{}::{}", quote_identifier(module_id.name()), name)
+                        };
+                        referred.insert((module_id, name.to_owned()));
+                        rust_name
+                    },
+                }
+            },
+            Signer | Reference(_) | MutableReference(_) | TypeParameter(_) => return None,
+        })
+    }
+}
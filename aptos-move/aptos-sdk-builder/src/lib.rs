@@ -3,28 +3,34 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use aptos_types::transaction::EntryABI;
+use move_binary_format::{access::ModuleAccess, CompiledModule};
 use std::{ffi::OsStr, fs, io::Read, path::Path};
 
+pub mod events;
 pub mod golang;
 pub mod rust;
 
 /// Internals shared between languages.
 mod common;
 
-fn get_abi_paths(dir: &Path) -> std::io::Result<Vec<String>> {
-    let mut abi_paths = Vec::new();
+fn get_paths(dir: &Path, extension: &str) -> std::io::Result<Vec<String>> {
+    let mut paths = Vec::new();
     if dir.is_dir() {
         for entry in fs::read_dir(dir)? {
             let entry = entry?;
             let path = entry.path();
             if path.is_dir() {
-                abi_paths.append(&mut get_abi_paths(&path)?);
-            } else if let Some("abi") = path.extension().and_then(OsStr::to_str) {
-                abi_paths.push(path.to_str().unwrap().to_string());
+                paths.append(&mut get_paths(&path, extension)?);
+            } else if path.extension().and_then(OsStr::to_str) == Some(extension) {
+                paths.push(path.to_str().unwrap().to_string());
             }
         }
     }
-    Ok(abi_paths)
+    Ok(paths)
+}
+
+fn get_abi_paths(dir: &Path) -> std::io::Result<Vec<String>> {
+    get_paths(dir, "abi")
 }
 
 /// Read all ABI files the specified directories. This supports both new and old `EntryABI`s.
@@ -56,6 +62,21 @@ pub fn read_abis(dir_paths: &[impl AsRef<Path>]) -> anyhow::Result<Vec<EntryABI>
     Ok(abis)
 }
 
+/// Read all compiled modules (`.mv` files) in the specified directories, sorted by module ID.
+pub fn read_modules(dir_paths: &[impl AsRef<Path>]) -> anyhow::Result<Vec<CompiledModule>> {
+    let mut modules = Vec::new();
+    for dir in dir_paths.iter() {
+        for path in get_paths(dir.as_ref(), "mv")? {
+            let bytes = fs::read(&path)?;
+            let module = CompiledModule::deserialize(&bytes)
+                .map_err(|err| anyhow::format_err!("Failed to deserialize {}: {:?}", path, err))?;
+            modules.push(module);
+        }
+    }
+    modules.sort_by_key(|module| module.self_id());
+    Ok(modules)
+}
+
 /// How to copy ABI-generated source code for a given language.
 pub trait SourceInstaller {
     type Error;
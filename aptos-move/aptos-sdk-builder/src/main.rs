@@ -24,6 +24,11 @@ struct Options {
     /// Path to the directory containing ABI files in BCS encoding.
     abi_directories: Vec<PathBuf>,
 
+    /// Path to the directories containing compiled modules (`.mv` files), to also generate typed
+    /// bindings for their events. Only supported in Rust.
+    #[clap(long)]
+    module_directories: Vec<PathBuf>,
+
     /// Language for code generation.
     #[clap(long, value_enum, ignore_case = true, default_value_t = Language::Rust)]
     language: Language,
@@ -62,6 +67,11 @@ fn main() {
     let options = Options::parse();
     let abis = aptos_sdk_builder::read_abis(&options.abi_directories)
         .expect("Failed to read ABI in directory");
+    let modules = aptos_sdk_builder::read_modules(&options.module_directories)
+        .expect("Failed to read compiled modules in directory");
+    if !modules.is_empty() && !matches!(options.language, Language::Rust) {
+        panic!("Event bindings are only supported in Rust");
+    }
 
     let install_dir = match options.target_source_dir {
         None => {
@@ -71,7 +81,8 @@ fn main() {
             match options.language {
                 Language::Rust => {
                     aptos_sdk_builder::rust::output(&mut out, &abis, /* local types */ true)
-                        .unwrap()
+                        .unwrap();
+                    aptos_sdk_builder::events::output(&mut out, &modules).unwrap();
                 },
                 Language::Go => {
                     aptos_sdk_builder::golang::output(
@@ -89,6 +100,13 @@ fn main() {
         Some(dir) => dir,
     };
 
+    // Event bindings
+    if !modules.is_empty() {
+        let mut file = std::fs::File::create(install_dir.join("events.rs"))
+            .expect("events file must be writable");
+        aptos_sdk_builder::events::output(&mut file, &modules).unwrap();
+    }
+
     // Aptos types
     if let Some(registry_file) = options.with_aptos_types {
         let installer: Box<dyn serdegen::SourceInstaller<Error = Box<dyn std::error::Error>>> =
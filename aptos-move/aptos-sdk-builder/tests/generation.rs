@@ -93,3 +93,23 @@ fn test_that_rust_entry_fun_code_compiles() {
         EXPECTED_SCRIPT_FUN_OUTPUT,
     );
 }
+
+#[test]
+fn test_rust_event_bindings() {
+    let modules = aptos_cached_packages::head_release_bundle().compiled_modules();
+    let mut output = Vec::new();
+    buildgen::events::output(&mut output, &modules).unwrap();
+    let output = String::from_utf8(output).unwrap();
+
+    assert!(output.contains("pub mod coin {"));
+    assert!(output.contains(
+        r#"pub struct CoinDeposit {
+        pub coin_type: String,
+        pub account: AccountAddress,
+        pub amount: u64,
+    }"#
+    ));
+    assert!(output.contains("impl MoveEventV2Type for CoinDeposit {}"));
+    // Generic events cannot be bound
+    assert!(output.contains("// Skipped the event `Deposit`"));
+}
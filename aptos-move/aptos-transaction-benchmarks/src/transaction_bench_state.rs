@@ -244,7 +244,7 @@ where
             .unwrap()
             .execute_block(
                 self.state_view.clone(),
-                transactions,
+                &transactions,
                 concurrency_level_per_shard,
                 BlockExecutorConfigFromOnchain::new_maybe_block_limit(maybe_block_gas_limit),
             )
@@ -15,7 +15,8 @@ use aptos_native_interface::SafeNativeBuilder;
 use aptos_types::{
     chain_id::ChainId,
     on_chain_config::{
-        ConfigurationResource, Features, OnChainConfig, TimedFeatures, TimedFeaturesBuilder,
+        ConfigurationResource, Features, OnChainConfig, OnChainExecutionConfig, TimedFeatures,
+        TimedFeaturesBuilder, TransactionOutputLimits,
     },
     state_store::StateView,
 };
@@ -81,6 +82,12 @@ impl AptosEnvironment {
         &self.0.timed_features
     }
 
+    /// Returns the on-chain limits on the output of a single transaction.
+    #[inline]
+    pub fn transaction_output_limits(&self) -> &TransactionOutputLimits {
+        &self.0.transaction_output_limits
+    }
+
     /// Returns the [VMConfig] used by this environment.
     #[inline]
     pub fn vm_config(&self) -> &VMConfig {
@@ -145,6 +152,8 @@ struct Environment {
     features: Features,
     /// Set of timed features enabled in this environment.
     timed_features: TimedFeatures,
+    /// Limits on the output of a single transaction, from the on-chain execution config.
+    transaction_output_limits: TransactionOutputLimits,
 
     /// Gas feature version used in this environment.
     gas_feature_version: u64,
@@ -197,6 +206,11 @@ impl Environment {
         }
         let timed_features = timed_features_builder.build();
 
+        let transaction_output_limits =
+            fetch_config_and_update_hash::<OnChainExecutionConfig>(&mut sha3_256, state_view)
+                .map(|config| config.transaction_output_limits())
+                .unwrap_or_default();
+
         // TODO(Gas):
         //   Right now, we have to use some dummy values for gas parameters if they are not found
         //   on-chain. This only happens in a edge case that is probably related to write set
@@ -242,6 +256,7 @@ impl Environment {
             chain_id,
             features,
             timed_features,
+            transaction_output_limits,
             gas_feature_version,
             gas_params,
             storage_gas_params,
@@ -0,0 +1,195 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Compares the storage fees of a write set under the current and a proposed gas schedule, so
+//! that changes to the storage pricing can be evaluated against replayed transactions.
+
+use crate::{
+    change_set::WriteOpInfo,
+    storage::space_pricing::{ChargeAndRefund, DiskSpacePricing},
+};
+use aptos_gas_schedule::TransactionGasParameters;
+use aptos_types::{
+    state_store::{state_key::StateKey, StateViewResult, TStateView},
+    write_set::{TransactionWrite, WriteSet},
+};
+
+/// Storage fees of a single write op, under the current and the proposed gas schedule.
+#[derive(Clone, Debug)]
+pub struct WriteOpStorageFee {
+    pub key: StateKey,
+    pub current: ChargeAndRefund,
+    pub proposed: ChargeAndRefund,
+}
+
+impl WriteOpStorageFee {
+    /// Difference of the net charge (charge minus refund) of the write op, from the current to
+    /// the proposed gas schedule. Positive if the write op becomes more expensive.
+    pub fn net_charge_diff(&self) -> i128 {
+        net_charge(&self.proposed) - net_charge(&self.current)
+    }
+}
+
+/// Storage fees of a write set under the current and the proposed gas schedule, with the
+/// breakdown per write op.
+#[derive(Clone, Debug, Default)]
+pub struct StorageFeeComparison {
+    pub write_ops: Vec<WriteOpStorageFee>,
+}
+
+impl StorageFeeComparison {
+    /// Total charge and refund of the write set under the current gas schedule.
+    pub fn total_current(&self) -> ChargeAndRefund {
+        Self::total(self.write_ops.iter().map(|op| &op.current))
+    }
+
+    /// Total charge and refund of the write set under the proposed gas schedule.
+    pub fn total_proposed(&self) -> ChargeAndRefund {
+        Self::total(self.write_ops.iter().map(|op| &op.proposed))
+    }
+
+    /// Difference of the total net charge of the write set, from the current to the proposed
+    /// gas schedule.
+    pub fn net_charge_diff(&self) -> i128 {
+        self.write_ops.iter().map(|op| op.net_charge_diff()).sum()
+    }
+
+    fn total<'a>(fees: impl Iterator<Item = &'a ChargeAndRefund>) -> ChargeAndRefund {
+        fees.fold(ChargeAndRefund::zero(), |acc, fee| ChargeAndRefund {
+            charge: acc.charge + fee.charge,
+            refund: acc.refund + fee.refund,
+        })
+    }
+}
+
+fn net_charge(fee: &ChargeAndRefund) -> i128 {
+    u64::from(fee.charge) as i128 - u64::from(fee.refund) as i128
+}
+
+/// Computes the storage fees of every write op in the write set under both gas schedules.
+///
+/// The state view must reflect the state right before the transaction producing the write set
+/// was executed, as the fees depend on the previous size of the slots and the deposits recorded
+/// in their metadata.
+pub fn compare_storage_fees(
+    pricing: &DiskSpacePricing,
+    current_params: &TransactionGasParameters,
+    proposed_params: &TransactionGasParameters,
+    write_set: &WriteSet,
+    state_view: &impl TStateView<Key = StateKey>,
+) -> StateViewResult<StorageFeeComparison> {
+    let write_ops = write_set
+        .iter()
+        .map(|(key, op)| {
+            let prev_value = state_view.get_state_value(key)?;
+            let prev_size = prev_value.as_ref().map_or(0, |value| value.size() as u64);
+
+            let fee = |params: &TransactionGasParameters| {
+                // Each schedule charges against its own copy of the metadata.
+                let mut metadata = match &prev_value {
+                    Some(value) if !op.is_creation() => value.metadata().clone(),
+                    _ => op.metadata().clone(),
+                };
+                pricing.charge_refund_write_op(params, WriteOpInfo {
+                    key,
+                    op_size: op.write_op_size(),
+                    prev_size,
+                    metadata_mut: &mut metadata,
+                })
+            };
+
+            Ok(WriteOpStorageFee {
+                key: key.clone(),
+                current: fee(current_params),
+                proposed: fee(proposed_params),
+            })
+        })
+        .collect::<StateViewResult<_>>()?;
+
+    Ok(StorageFeeComparison { write_ops })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aptos_types::{
+        on_chain_config::CurrentTimeMicroseconds,
+        state_store::{
+            state_value::{StateValue, StateValueMetadata},
+            MockStateView,
+        },
+        write_set::WriteOp,
+    };
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_compare_storage_fees() {
+        let mut current_params = TransactionGasParameters::random();
+        current_params.storage_fee_per_state_slot = 1000.into();
+        current_params.storage_fee_per_state_byte = 5.into();
+        let mut proposed_params = current_params.clone();
+        proposed_params.storage_fee_per_state_byte = 10.into();
+
+        let ts = CurrentTimeMicroseconds { microseconds: 0 };
+        let created = StateKey::raw(&[1, 2, 3]);
+        let modified = StateKey::raw(&[4, 5, 6]);
+        let deleted = StateKey::raw(&[7, 8, 9]);
+        let state_view = MockStateView::new(HashMap::from([
+            (
+                modified.clone(),
+                StateValue::new_with_metadata(
+                    vec![0; 2].into(),
+                    StateValueMetadata::new(1000, 25, &ts),
+                ),
+            ),
+            (
+                deleted.clone(),
+                StateValue::new_with_metadata(
+                    vec![0; 2].into(),
+                    StateValueMetadata::new(1000, 25, &ts),
+                ),
+            ),
+        ]));
+        let metadata = StateValueMetadata::placeholder(&ts);
+        let write_set = WriteSet::new([
+            (
+                created.clone(),
+                WriteOp::creation(vec![0; 2].into(), metadata.clone()),
+            ),
+            (
+                modified.clone(),
+                WriteOp::modification(vec![0; 4].into(), metadata.clone()),
+            ),
+            (deleted.clone(), WriteOp::deletion(metadata)),
+        ])
+        .unwrap();
+
+        let comparison = compare_storage_fees(
+            &DiskSpacePricing::V2,
+            &current_params,
+            &proposed_params,
+            &write_set,
+            &state_view,
+        )
+        .unwrap();
+
+        let fees: HashMap<_, _> = comparison
+            .write_ops
+            .iter()
+            .map(|op| (op.key.clone(), op))
+            .collect();
+        // (3 + 2) bytes for the new slot
+        assert_eq!(fees[&created].current.charge, 1025.into());
+        assert_eq!(fees[&created].proposed.charge, 1050.into());
+        // 2 bytes more than before, capped by the target deposit of (3 + 4) bytes
+        assert_eq!(fees[&modified].current.charge, 10.into());
+        assert_eq!(fees[&modified].proposed.charge, 20.into());
+        // The recorded deposits get refunded regardless of the schedule
+        assert_eq!(fees[&deleted].current.refund, 1025.into());
+        assert_eq!(fees[&deleted].proposed.refund, 1025.into());
+
+        assert_eq!(comparison.total_current().charge, 1035.into());
+        assert_eq!(comparison.total_proposed().charge, 1070.into());
+        assert_eq!(comparison.net_charge_diff(), 35);
+    }
+}
@@ -13,6 +13,7 @@ use bytes::Bytes;
 use std::fmt::Debug;
 
 pub mod change_set_configs;
+pub mod fee_comparison;
 pub mod io_pricing;
 pub mod space_pricing;
 
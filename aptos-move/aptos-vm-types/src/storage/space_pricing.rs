@@ -11,6 +11,7 @@ use aptos_types::{
 use move_core_types::gas_algebra::NumBytes;
 use std::fmt::Debug;
 
+#[derive(Clone, Debug)]
 pub struct ChargeAndRefund {
     pub charge: Fee,
     pub refund: Fee,
@@ -23,7 +23,10 @@ use crate::{
     system_module_names::*,
     transaction_metadata::TransactionMetadata,
     transaction_validation,
-    verifier::{self, randomness::get_randomness_annotation},
+    verifier::{
+        self, package_upgrade::upgrade_compatibility_checks, randomness::get_randomness_annotation,
+    },
+    write_scope::WriteScope,
     VMBlockExecutor, VMValidator,
 };
 use anyhow::anyhow;
@@ -49,7 +52,9 @@ use aptos_types::{
     block_executor::{
         config::{
             BlockExecutorConfig, BlockExecutorConfigFromOnchain, BlockExecutorLocalConfig,
-            BlockExecutorModuleCacheLocalConfig,
+            BlockExecutorModuleCacheLocalConfig, BlockSTMCommitterBackup,
+            BlockSTMCommitterBackupThresholds, BlockSTMProfilingLocalConfig,
+            BlockSTMScheduleReplayMode,
         },
         partitioner::PartitionedTransactions,
         transaction_slice_metadata::TransactionSliceMetadata,
@@ -62,7 +67,7 @@ use aptos_types::{
     move_utils::as_move_value::AsMoveValue,
     on_chain_config::{
         ApprovedExecutionHashes, ConfigStorage, FeatureFlag, Features, OnChainConfig,
-        TimedFeatureFlag, TimedFeatures,
+        TimedFeatureFlag, TimedFeatures, TransactionOutputLimits,
     },
     randomness::Randomness,
     state_store::{state_key::StateKey, StateView, TStateView},
@@ -95,10 +100,10 @@ use aptos_vm_types::{
 use ark_bn254::Bn254;
 use ark_groth16::PreparedVerifyingKey;
 use claims::assert_err;
+use crossbeam_channel::Sender;
 use fail::fail_point;
 use move_binary_format::{
     access::ModuleAccess,
-    compatibility::Compatibility,
     deserializer::DeserializerConfig,
     errors::{Location, PartialVMError, PartialVMResult, VMError, VMResult},
     file_format::CompiledScript,
@@ -119,6 +124,7 @@ use move_vm_metrics::{Timer, VM_TIMER};
 use move_vm_runtime::{
     logging::expect_no_verification_errors,
     module_traversal::{TraversalContext, TraversalStorage},
+    paranoid_diagnostics::set_paranoid_check_diagnostic_hook,
     RuntimeEnvironment, WithRuntimeEnvironment,
 };
 use move_vm_types::gas::{GasMeter, UnmeteredGasMeter};
@@ -128,7 +134,11 @@ use std::{
     cmp::{max, min},
     collections::{BTreeMap, BTreeSet},
     marker::Sync,
-    sync::Arc,
+    path::PathBuf,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
 };
 
 static EXECUTION_CONCURRENCY_LEVEL: OnceCell<usize> = OnceCell::new();
@@ -136,6 +146,12 @@ static NUM_EXECUTION_SHARD: OnceCell<usize> = OnceCell::new();
 static NUM_PROOF_READING_THREADS: OnceCell<usize> = OnceCell::new();
 static DISCARD_FAILED_BLOCKS: OnceCell<bool> = OnceCell::new();
 static PROCESSED_TRANSACTIONS_DETAILED_COUNTERS: OnceCell<bool> = OnceCell::new();
+static SEQUENTIAL_FALLBACK_DUMP_DIR: OnceCell<PathBuf> = OnceCell::new();
+static COMMITTER_BACKUP: OnceCell<BlockSTMCommitterBackup> = OnceCell::new();
+static PIN_WORKERS_TO_NUMA_NODES: OnceCell<bool> = OnceCell::new();
+// Unlike the settings above, BlockSTM profiling can be toggled at runtime (e.g., through the
+// admin service), and applies from the next executed block.
+static BLOCK_STM_PROFILING: AtomicBool = AtomicBool::new(false);
 
 macro_rules! deprecated_module_bundle {
     () => {
@@ -200,6 +216,53 @@ pub(crate) fn get_or_vm_startup_failure<'a, T>(
     })
 }
 
+/// Checks the writes and events of the user session against the on-chain limits on the output of a
+/// transaction. A transaction exceeding them fails like on any other execution error, i.e., it is
+/// kept and charged for the gas it used.
+fn check_transaction_output_limits(
+    limits: &TransactionOutputLimits,
+    change_set: &impl ChangeSetInterface,
+) -> Result<(), VMStatus> {
+    let output_exceeds_limits = |message: String| {
+        Err(
+            PartialVMError::new(StatusCode::TRANSACTION_OUTPUT_EXCEEDS_LIMITS)
+                .with_message(message)
+                .finish(Location::Undefined)
+                .into_vm_status(),
+        )
+    };
+
+    if let Some(max_write_ops) = limits.max_write_ops {
+        let num_write_ops = change_set.num_write_ops() as u64;
+        if num_write_ops > max_write_ops {
+            return output_exceeds_limits(format!(
+                "Output has {} write ops, exceeding the limit of {}",
+                num_write_ops, max_write_ops
+            ));
+        }
+    }
+
+    if let Some(max_output_bytes) = limits.max_output_bytes {
+        let write_set_bytes: u64 = change_set
+            .write_set_size_iter()
+            .filter_map(|(key, op_size)| op_size.write_len().map(|len| len + key.size() as u64))
+            .sum();
+        let event_bytes: u64 = change_set
+            .events_iter()
+            .map(|event| event.event_data().len() as u64)
+            .sum();
+        let output_bytes = write_set_bytes + event_bytes;
+        if output_bytes > max_output_bytes {
+            return output_exceeds_limits(format!(
+                "Output has {} bytes, exceeding the limit of {}",
+                output_bytes, max_output_bytes
+            ));
+        }
+    }
+
+    Ok(())
+}
+
 /// Checks if a given transaction is a governance proposal by checking if it has one of the
 /// approved execution hashes.
 fn is_approved_gov_script(
@@ -321,6 +384,19 @@ impl AptosVM {
         EXECUTION_CONCURRENCY_LEVEL.set(concurrency_level).ok();
     }
 
+    /// Logs the structured diagnostic of every paranoid mode failure, i.e., the function, the
+    /// instruction, the types on the operand stack and the module hash.
+    pub fn log_paranoid_check_diagnostics() {
+        set_paranoid_check_diagnostic_hook(|diagnostic| {
+            error!(
+                paranoid_check = diagnostic,
+                "[aptos_vm] Paranoid mode check failed in {} at offset {}",
+                diagnostic.function,
+                diagnostic.code_offset
+            );
+        });
+    }
+
     /// Get the concurrency level if already set, otherwise return default 1
     /// (sequential execution).
     ///
@@ -359,6 +435,34 @@ impl AptosVM {
         }
     }
 
+    /// Sets the policy for the BlockSTM committer backup, when invoked the first time.
+    pub fn set_committer_backup(committer_backup: BlockSTMCommitterBackup) {
+        // Only the first call succeeds, due to OnceCell semantics.
+        COMMITTER_BACKUP.set(committer_backup).ok();
+    }
+
+    /// Get the policy for the BlockSTM committer backup if already set, otherwise return default
+    /// (disabled).
+    pub fn get_committer_backup() -> BlockSTMCommitterBackup {
+        COMMITTER_BACKUP.get().copied().unwrap_or_default()
+    }
+
+    /// Sets whether parallel execution workers are pinned to NUMA nodes, when invoked the first
+    /// time.
+    pub fn set_pin_workers_to_numa_nodes(enable: bool) {
+        // Only the first call succeeds, due to OnceCell semantics.
+        PIN_WORKERS_TO_NUMA_NODES.set(enable).ok();
+    }
+
+    /// Get whether parallel execution workers are pinned to NUMA nodes if already set, otherwise
+    /// return default (false).
+    pub fn get_pin_workers_to_numa_nodes() -> bool {
+        match PIN_WORKERS_TO_NUMA_NODES.get() {
+            Some(enable) => *enable,
+            None => false,
+        }
+    }
+
     /// Sets the # of async proof reading threads.
     pub fn set_num_proof_reading_threads_once(mut num_threads: usize) {
         // TODO(grao): Do more analysis to tune this magic number.
@@ -390,6 +494,29 @@ impl AptosVM {
         }
     }
 
+    /// Sets the directory where blocks falling back to sequential execution are dumped, when
+    /// invoked the first time.
+    pub fn set_sequential_fallback_dump_dir(dir: PathBuf) {
+        // Only the first call succeeds, due to OnceCell semantics.
+        SEQUENTIAL_FALLBACK_DUMP_DIR.set(dir).ok();
+    }
+
+    /// Get the directory where blocks falling back to sequential execution are dumped, if set.
+    pub fn get_sequential_fallback_dump_dir() -> Option<PathBuf> {
+        SEQUENTIAL_FALLBACK_DUMP_DIR.get().cloned()
+    }
+
+    /// Enables or disables writing a BlockSTM profiling report for each block executed in
+    /// parallel. Can be called at any time, and applies from the next executed block.
+    pub fn set_block_stm_profiling(enable: bool) {
+        BLOCK_STM_PROFILING.store(enable, Ordering::Relaxed);
+    }
+
+    /// Get whether BlockSTM profiling reports are written, by default false.
+    pub fn get_block_stm_profiling() -> bool {
+        BLOCK_STM_PROFILING.load(Ordering::Relaxed)
+    }
+
     /// Returns the internal gas schedule if it has been loaded, or an error if it hasn't.
     #[cfg(any(test, feature = "testing"))]
     pub fn gas_params_for_test(&self) -> Result<&AptosGasParameters, VMStatus> {
@@ -1027,6 +1154,14 @@ impl AptosVM {
         gas_meter: &mut impl AptosGasMeter,
         txn_data: &'l TransactionMetadata,
     ) -> Result<EpilogueSession<'r, 'l>, VMStatus> {
+        if let Some(write_scope) = &txn_data.write_scope {
+            write_scope.check_change_set(&user_session_change_set)?;
+        }
+        check_transaction_output_limits(
+            self.move_vm.env.transaction_output_limits(),
+            &user_session_change_set,
+        )?;
+
         let storage_refund = self.charge_change_set(
             &mut user_session_change_set,
             gas_meter,
@@ -1644,16 +1779,8 @@ impl AptosVM {
             )
         })?;
 
-        let check_struct_layout = true;
-        let check_friend_linking = !self
-            .features()
-            .is_enabled(FeatureFlag::TREAT_FRIEND_AS_PRIVATE);
-        let compatibility_checks = Compatibility::new(
-            check_struct_layout,
-            check_friend_linking,
-            self.timed_features()
-                .is_enabled(TimedFeatureFlag::EntryCompatibility),
-        );
+        let compatibility_checks =
+            upgrade_compatibility_checks(self.features(), self.timed_features());
 
         if self.features().is_loader_v2_enabled() {
             session.finish_with_module_publishing_and_initialization(
@@ -2050,8 +2177,29 @@ impl AptosVM {
         G: AptosGasMeter,
         F: FnOnce(u64, VMGasParameters, StorageGasParameters, bool, Gas) -> G,
     {
-        let txn_metadata = TransactionMetadata::new(txn);
+        self.execute_user_transaction_with_metadata(
+            resolver,
+            code_storage,
+            txn,
+            TransactionMetadata::new(txn),
+            log_context,
+            make_gas_meter,
+        )
+    }
 
+    fn execute_user_transaction_with_metadata<G, F>(
+        &self,
+        resolver: &impl AptosMoveResolver,
+        code_storage: &impl AptosCodeStorage,
+        txn: &SignedTransaction,
+        txn_metadata: TransactionMetadata,
+        log_context: &AdapterLogSchema,
+        make_gas_meter: F,
+    ) -> Result<(VMStatus, VMOutput, G), VMStatus>
+    where
+        G: AptosGasMeter,
+        F: FnOnce(u64, VMGasParameters, StorageGasParameters, bool, Gas) -> G,
+    {
         let is_approved_gov_script = is_approved_gov_script(resolver, txn, &txn_metadata);
 
         let balance = txn.max_gas_amount().into();
@@ -2136,6 +2284,34 @@ impl AptosVM {
         }
     }
 
+    /// Executes a user transaction using the production gas meter, aborting it if its user
+    /// session writes outside of the declared scope. The scope is not part of the transaction, so
+    /// this is never used by block execution, only when simulating a transaction before
+    /// submitting it, see [`crate::write_scope`].
+    pub fn execute_user_transaction_with_write_scope(
+        &self,
+        resolver: &impl AptosMoveResolver,
+        code_storage: &impl AptosCodeStorage,
+        txn: &SignedTransaction,
+        write_scope: WriteScope,
+        log_context: &AdapterLogSchema,
+    ) -> (VMStatus, VMOutput) {
+        match self.execute_user_transaction_with_metadata(
+            resolver,
+            code_storage,
+            txn,
+            TransactionMetadata::new(txn).with_write_scope(write_scope),
+            log_context,
+            make_prod_gas_meter,
+        ) {
+            Ok((vm_status, vm_output, _gas_meter)) => (vm_status, vm_output),
+            Err(vm_status) => {
+                let vm_output = discarded_output(vm_status.status_code());
+                (vm_status, vm_output)
+            },
+        }
+    }
+
     fn execute_write_set(
         &self,
         resolver: &impl AptosMoveResolver,
@@ -2849,6 +3025,17 @@ impl VMBlockExecutor for AptosVMBlockExecutor {
                 allow_fallback: true,
                 discard_failed_blocks: AptosVM::get_discard_failed_blocks(),
                 module_cache_config: BlockExecutorModuleCacheLocalConfig::default(),
+                committer_backup: AptosVM::get_committer_backup(),
+                committer_backup_thresholds: BlockSTMCommitterBackupThresholds::default(),
+                profiling_config: BlockSTMProfilingLocalConfig {
+                    enable_block_stm_profiling: AptosVM::get_block_stm_profiling(),
+                    ..BlockSTMProfilingLocalConfig::default()
+                },
+                schedule_replay_mode: BlockSTMScheduleReplayMode::Disabled,
+                execution_order_hint: None,
+                pin_workers_to_numa_nodes: AptosVM::get_pin_workers_to_numa_nodes(),
+                block_gas_budget: None,
+                sequential_fallback_dump_dir: AptosVM::get_sequential_fallback_dump_dir(),
             },
             onchain: onchain_config,
         };
@@ -2857,7 +3044,7 @@ impl VMBlockExecutor for AptosVMBlockExecutor {
 
     fn execute_block_sharded<S: StateView + Sync + Send + 'static, C: ExecutorClient<S>>(
         sharded_block_executor: &ShardedBlockExecutor<S, C>,
-        transactions: PartitionedTransactions,
+        transactions: &PartitionedTransactions,
         state_view: Arc<S>,
         onchain_config: BlockExecutorConfigFromOnchain,
     ) -> Result<Vec<TransactionOutput>, VMStatus> {
@@ -2881,6 +3068,38 @@ impl VMBlockExecutor for AptosVMBlockExecutor {
         }
         ret
     }
+
+    fn execute_block_sharded_streaming<
+        S: StateView + Sync + Send + 'static,
+        C: ExecutorClient<S>,
+    >(
+        sharded_block_executor: &ShardedBlockExecutor<S, C>,
+        transactions: &PartitionedTransactions,
+        state_view: Arc<S>,
+        onchain_config: BlockExecutorConfigFromOnchain,
+        output_tx: Sender<Vec<TransactionOutput>>,
+    ) -> Result<(), VMStatus> {
+        let log_context = AdapterLogSchema::new(state_view.id(), 0);
+        info!(
+            log_context,
+            "Executing block with streamed outputs, transaction count: {}",
+            transactions.num_txns()
+        );
+
+        let count = transactions.num_txns();
+        let ret = sharded_block_executor.execute_block_streaming(
+            state_view,
+            transactions,
+            AptosVM::get_concurrency_level(),
+            onchain_config,
+            output_tx,
+        );
+        if ret.is_ok() {
+            // Record the histogram count for transactions per block.
+            BLOCK_TRANSACTION_COUNT.observe(count as f64);
+        }
+        ret
+    }
 }
 
 impl VMValidator for AptosVM {
@@ -3000,11 +3219,13 @@ impl AptosSimulationVM {
     }
 
     /// Simulates a signed transaction (i.e., executes it without performing
-    /// signature verification) on a newly created VM instance.
+    /// signature verification) on a newly created VM instance. If a write scope is
+    /// given, the transaction fails if it writes outside of it.
     /// *Precondition:* the transaction must **not** have a valid signature.
     pub fn create_vm_and_simulate_signed_transaction(
         transaction: &SignedTransaction,
         state_view: &impl StateView,
+        write_scope: Option<WriteScope>,
     ) -> (VMStatus, TransactionOutput) {
         assert_err!(
             transaction.verify_signature(),
@@ -3018,8 +3239,18 @@ impl AptosSimulationVM {
         let resolver = state_view.as_move_resolver();
         let code_storage = state_view.as_aptos_code_storage(env);
 
-        let (vm_status, vm_output) =
-            vm.0.execute_user_transaction(&resolver, &code_storage, transaction, &log_context);
+        let (vm_status, vm_output) = match write_scope {
+            Some(write_scope) => vm.0.execute_user_transaction_with_write_scope(
+                &resolver,
+                &code_storage,
+                transaction,
+                write_scope,
+                &log_context,
+            ),
+            None => {
+                vm.0.execute_user_transaction(&resolver, &code_storage, transaction, &log_context)
+            },
+        };
         let txn_output = vm_output
             .try_materialize_into_transaction_output(&resolver)
             .expect("Materializing aggregator V1 deltas should never fail");
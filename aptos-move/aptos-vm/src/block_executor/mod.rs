@@ -17,7 +17,9 @@ use aptos_block_executor::{
     txn_provider::TxnProvider,
     types::InputOutputKey,
 };
+use aptos_crypto::HashValue;
 use aptos_infallible::Mutex;
+use aptos_mvhashmap::types::TxnIndex;
 use aptos_types::{
     block_executor::{
         config::BlockExecutorConfig, transaction_slice_metadata::TransactionSliceMetadata,
@@ -25,10 +27,11 @@ use aptos_types::{
     contract_event::ContractEvent,
     error::PanicError,
     fee_statement::FeeStatement,
+    on_chain_config::{ApprovedExecutionHashes, BlockGasLimitType, OnChainConfig},
     state_store::{state_key::StateKey, state_value::StateValueMetadata, StateView, StateViewId},
     transaction::{
-        signature_verified_transaction::SignatureVerifiedTransaction, BlockOutput,
-        TransactionOutput, TransactionStatus,
+        signature_verified_transaction::SignatureVerifiedTransaction, BlockOutput, Transaction,
+        TransactionOutput, TransactionPayload, TransactionStatus,
     },
     write_set::WriteOp,
 };
@@ -437,6 +440,25 @@ impl<
             transaction_slice_metadata,
         )?;
 
+        let mut config = config;
+        if matches!(
+            config.onchain.block_gas_limit_type,
+            BlockGasLimitType::WithGovernanceOverride { .. }
+        ) {
+            let governance_override_enabled = module_cache_manager_guard
+                .environment()
+                .features()
+                .is_governance_block_gas_limit_override_enabled();
+            config.onchain.block_gas_limit_type = config
+                .onchain
+                .block_gas_limit_type
+                .resolve_governance_override(
+                    governance_override_enabled
+                        && is_governance_only_block(signature_verified_block, state_view),
+                    governance_override_enabled,
+                );
+        }
+
         let executor = BlockExecutor::<SignatureVerifiedTransaction, E, S, L, TP>::new(
             config,
             executor_thread_pool,
@@ -503,5 +525,43 @@ impl<
     }
 }
 
+/// Returns true if the block contains at least one governance proposal (script with execution
+/// hash approved by governance), and otherwise only governance proposals and system transactions,
+/// e.g., block metadata.
+fn is_governance_only_block(
+    block: &impl TxnProvider<SignatureVerifiedTransaction>,
+    state_view: &impl StateView,
+) -> bool {
+    let approved_execution_hashes = match ApprovedExecutionHashes::fetch_config(state_view) {
+        Some(approved_execution_hashes) => approved_execution_hashes,
+        None => return false,
+    };
+
+    let mut has_governance_txn = false;
+    for idx in 0..block.num_txns() as TxnIndex {
+        match block.get_txn(idx) {
+            SignatureVerifiedTransaction::Valid(Transaction::UserTransaction(txn)) => {
+                let is_approved_script = match txn.payload() {
+                    TransactionPayload::Script(script) => {
+                        let script_hash = HashValue::sha3_256_of(script.code()).to_vec();
+                        approved_execution_hashes
+                            .entries
+                            .iter()
+                            .any(|(_, hash)| hash == &script_hash)
+                    },
+                    _ => false,
+                };
+                if !is_approved_script {
+                    return false;
+                }
+                has_governance_txn = true;
+            },
+            SignatureVerifiedTransaction::Valid(_) => {},
+            SignatureVerifiedTransaction::Invalid(_) => return false,
+        }
+    }
+    has_governance_txn
+}
+
 // Same as AptosBlockExecutorWrapper with AptosExecutorTask
 pub type AptosVMBlockExecutorWrapper = AptosBlockExecutorWrapper<AptosExecutorTask>;
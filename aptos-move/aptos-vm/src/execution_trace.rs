@@ -0,0 +1,375 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Records the execution of a single transaction (the state it reads, the events it emits and
+//! the gas it charges), so that it can be re-executed offline against exactly the same state in
+//! order to reproduce and investigate divergences.
+//!
+//! Tracing is opt-in: a transaction is only traced when executed through
+//! [`execute_and_trace_transaction`], the regular execution paths are not affected.
+
+use crate::AptosVM;
+use anyhow::Context;
+use aptos_crypto::{hash::CryptoHash, HashValue};
+use aptos_infallible::Mutex;
+use aptos_types::{
+    contract_event::ContractEvent,
+    fee_statement::FeeStatement,
+    state_store::{
+        errors::StateViewError, state_key::StateKey, state_storage_usage::StateStorageUsage,
+        state_value::StateValue, StateView, StateViewId, StateViewResult, TStateView,
+    },
+    transaction::{
+        signature_verified_transaction::SignatureVerifiedTransaction, Transaction,
+        TransactionOutput, TransactionStatus,
+    },
+    vm_status::VMStatus,
+};
+use aptos_vm_environment::environment::AptosEnvironment;
+use aptos_vm_logging::log_schema::AdapterLogSchema;
+use aptos_vm_types::module_and_script_storage::AsAptosCodeStorage;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{HashMap, HashSet},
+    fmt, fs,
+    path::Path,
+};
+
+/// A single read of the state, in the order it was performed by the VM.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct StateRead {
+    pub key: StateKey,
+    /// Hash of the value that was read, or None if the slot was empty.
+    pub value_hash: Option<HashValue>,
+}
+
+/// The gas charged for the transaction.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct GasCheckpoint {
+    pub gas_used: u64,
+    pub fee_statement: FeeStatement,
+}
+
+/// Everything observed while executing a transaction that is needed to reproduce its execution.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct ExecutionTrace {
+    pub transaction: Transaction,
+    /// All the reads of the state, including repeated ones, in execution order.
+    pub reads: Vec<StateRead>,
+    /// The values of the distinct keys that were read, in order of their first read.
+    pub values: Vec<(StateKey, Option<StateValue>)>,
+    pub usage: Option<StateStorageUsage>,
+    pub status: TransactionStatus,
+    pub events: Vec<ContractEvent>,
+    pub write_set_hash: HashValue,
+    pub gas: GasCheckpoint,
+}
+
+impl ExecutionTrace {
+    /// Writes the trace to the given file, in BCS.
+    pub fn save(&self, path: impl AsRef<Path>) -> anyhow::Result<()> {
+        let path = path.as_ref();
+        fs::write(path, bcs::to_bytes(self)?)
+            .with_context(|| format!("Failed to write execution trace to {}", path.display()))
+    }
+
+    /// Reads a trace previously written by [`ExecutionTrace::save`].
+    pub fn load(path: impl AsRef<Path>) -> anyhow::Result<Self> {
+        let path = path.as_ref();
+        let bytes = fs::read(path)
+            .with_context(|| format!("Failed to read execution trace from {}", path.display()))?;
+        Ok(bcs::from_bytes(&bytes)?)
+    }
+
+    /// Re-executes the traced transaction against the recorded state, and reports how the new
+    /// execution differs from the traced one. An empty result means the execution reproduced.
+    pub fn replay(&self) -> Result<Vec<TraceDivergence>, VMStatus> {
+        let state_view = RecordedStateView::new(self);
+        let result = execute_transaction(&state_view, self.transaction.clone());
+        let RecordedStateView {
+            recorder, missing, ..
+        } = state_view;
+        let (replayed, missing) = (recorder.into_inner(), missing.into_inner());
+
+        // Reads of unrecorded keys fail, which may abort the replay altogether.
+        let (_vm_status, output, fee_statement) = match result {
+            Ok(result) => result,
+            Err(_) if !missing.is_empty() => {
+                return Ok(vec![TraceDivergence::MissingReads(missing)]);
+            },
+            Err(vm_status) => return Err(vm_status),
+        };
+
+        let mut divergences = vec![];
+        if !missing.is_empty() {
+            divergences.push(TraceDivergence::MissingReads(missing));
+        }
+        if let Some(index) = (0..self.reads.len().max(replayed.reads.len()))
+            .find(|idx| self.reads.get(*idx) != replayed.reads.get(*idx))
+        {
+            divergences.push(TraceDivergence::Read {
+                index,
+                recorded: self.reads.get(index).cloned(),
+                replayed: replayed.reads.get(index).cloned(),
+            });
+        }
+        if &self.status != output.status() {
+            divergences.push(TraceDivergence::Status {
+                recorded: self.status.clone(),
+                replayed: output.status().clone(),
+            });
+        }
+        if self.events.as_slice() != output.events() {
+            divergences.push(TraceDivergence::Events {
+                recorded: self.events.clone(),
+                replayed: output.events().to_vec(),
+            });
+        }
+        let gas = GasCheckpoint {
+            gas_used: output.gas_used(),
+            fee_statement,
+        };
+        if self.gas != gas {
+            divergences.push(TraceDivergence::Gas {
+                recorded: self.gas.clone(),
+                replayed: gas,
+            });
+        }
+        let write_set_hash = CryptoHash::hash(output.write_set());
+        if self.write_set_hash != write_set_hash {
+            divergences.push(TraceDivergence::WriteSet {
+                recorded: self.write_set_hash,
+                replayed: write_set_hash,
+            });
+        }
+        Ok(divergences)
+    }
+}
+
+/// A difference between the traced execution of a transaction and its replay.
+#[derive(Clone, Debug)]
+pub enum TraceDivergence {
+    /// Keys read during the replay that were never read by the traced execution.
+    MissingReads(Vec<StateKey>),
+    /// The first read that differs between the two executions.
+    Read {
+        index: usize,
+        recorded: Option<StateRead>,
+        replayed: Option<StateRead>,
+    },
+    Status {
+        recorded: TransactionStatus,
+        replayed: TransactionStatus,
+    },
+    Events {
+        recorded: Vec<ContractEvent>,
+        replayed: Vec<ContractEvent>,
+    },
+    Gas {
+        recorded: GasCheckpoint,
+        replayed: GasCheckpoint,
+    },
+    WriteSet {
+        recorded: HashValue,
+        replayed: HashValue,
+    },
+}
+
+impl fmt::Display for TraceDivergence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingReads(keys) => {
+                write!(
+                    f,
+                    "{} key(s) read during replay were not recorded: ",
+                    keys.len()
+                )?;
+                for key in keys {
+                    write!(f, "{:?} ", key)?;
+                }
+                Ok(())
+            },
+            Self::Read {
+                index,
+                recorded,
+                replayed,
+            } => write!(
+                f,
+                "read #{} differs: recorded {:?}, replayed {:?}",
+                index, recorded, replayed
+            ),
+            Self::Status { recorded, replayed } => write!(
+                f,
+                "status differs: recorded {:?}, replayed {:?}",
+                recorded, replayed
+            ),
+            Self::Events { recorded, replayed } => write!(
+                f,
+                "events differ: recorded {} event(s), replayed {} event(s)",
+                recorded.len(),
+                replayed.len()
+            ),
+            Self::Gas { recorded, replayed } => write!(
+                f,
+                "gas differs: recorded {:?}, replayed {:?}",
+                recorded, replayed
+            ),
+            Self::WriteSet { recorded, replayed } => write!(
+                f,
+                "write set differs: recorded hash {}, replayed hash {}",
+                recorded, replayed
+            ),
+        }
+    }
+}
+
+/// Executes a transaction on top of the given state, recording an [`ExecutionTrace`] of it.
+pub fn execute_and_trace_transaction(
+    state_view: &impl StateView,
+    transaction: Transaction,
+) -> Result<(VMStatus, TransactionOutput, ExecutionTrace), VMStatus> {
+    let tracing_view = TracingStateView {
+        inner: state_view,
+        recorder: Mutex::new(ReadRecorder::default()),
+    };
+    let (vm_status, output, fee_statement) =
+        execute_transaction(&tracing_view, transaction.clone())?;
+    let ReadRecorder {
+        reads,
+        values,
+        usage,
+        ..
+    } = tracing_view.recorder.into_inner();
+
+    let trace = ExecutionTrace {
+        transaction,
+        reads,
+        values,
+        usage,
+        status: output.status().clone(),
+        events: output.events().to_vec(),
+        write_set_hash: CryptoHash::hash(output.write_set()),
+        gas: GasCheckpoint {
+            gas_used: output.gas_used(),
+            fee_statement,
+        },
+    };
+    Ok((vm_status, output, trace))
+}
+
+fn execute_transaction(
+    state_view: &impl StateView,
+    transaction: Transaction,
+) -> Result<(VMStatus, TransactionOutput, FeeStatement), VMStatus> {
+    let env = AptosEnvironment::new(state_view);
+    let vm = AptosVM::new(env.clone(), state_view);
+    let log_context = AdapterLogSchema::new(state_view.id(), 0);
+    let resolver = vm.as_move_resolver(state_view);
+    let code_storage = state_view.as_aptos_code_storage(env);
+
+    let (vm_status, vm_output) = vm.execute_single_transaction(
+        &SignatureVerifiedTransaction::from(transaction),
+        &resolver,
+        &code_storage,
+        &log_context,
+    )?;
+    let fee_statement = *vm_output.fee_statement();
+    let output = vm_output
+        .try_materialize_into_transaction_output(&resolver)
+        .expect("Materializing aggregator V1 deltas should never fail");
+    Ok((vm_status, output, fee_statement))
+}
+
+#[derive(Default)]
+struct ReadRecorder {
+    reads: Vec<StateRead>,
+    values: Vec<(StateKey, Option<StateValue>)>,
+    usage: Option<StateStorageUsage>,
+    seen: HashSet<StateKey>,
+}
+
+impl ReadRecorder {
+    fn record(&mut self, key: &StateKey, value: &Option<StateValue>) {
+        self.reads.push(StateRead {
+            key: key.clone(),
+            value_hash: value.as_ref().map(CryptoHash::hash),
+        });
+        if self.seen.insert(key.clone()) {
+            self.values.push((key.clone(), value.clone()));
+        }
+    }
+}
+
+/// Forwards reads to the underlying state view, recording them.
+struct TracingStateView<'a, S> {
+    inner: &'a S,
+    recorder: Mutex<ReadRecorder>,
+}
+
+impl<S: StateView> TStateView for TracingStateView<'_, S> {
+    type Key = StateKey;
+
+    fn id(&self) -> StateViewId {
+        self.inner.id()
+    }
+
+    fn get_state_value(&self, state_key: &StateKey) -> StateViewResult<Option<StateValue>> {
+        let value = self.inner.get_state_value(state_key)?;
+        self.recorder.lock().record(state_key, &value);
+        Ok(value)
+    }
+
+    fn get_usage(&self) -> StateViewResult<StateStorageUsage> {
+        let usage = self.inner.get_usage()?;
+        self.recorder.lock().usage = Some(usage);
+        Ok(usage)
+    }
+}
+
+/// Serves reads from the values recorded in a trace, recording the reads of the replay.
+struct RecordedStateView<'a> {
+    values: HashMap<&'a StateKey, &'a Option<StateValue>>,
+    usage: Option<StateStorageUsage>,
+    recorder: Mutex<ReadRecorder>,
+    missing: Mutex<Vec<StateKey>>,
+}
+
+impl<'a> RecordedStateView<'a> {
+    fn new(trace: &'a ExecutionTrace) -> Self {
+        Self {
+            values: trace.values.iter().map(|(k, v)| (k, v)).collect(),
+            usage: trace.usage,
+            recorder: Mutex::new(ReadRecorder::default()),
+            missing: Mutex::new(vec![]),
+        }
+    }
+}
+
+impl TStateView for RecordedStateView<'_> {
+    type Key = StateKey;
+
+    fn id(&self) -> StateViewId {
+        StateViewId::Replay
+    }
+
+    fn get_state_value(&self, state_key: &StateKey) -> StateViewResult<Option<StateValue>> {
+        match self.values.get(state_key) {
+            Some(value) => {
+                self.recorder.lock().record(state_key, value);
+                Ok((*value).clone())
+            },
+            None => {
+                self.missing.lock().push(state_key.clone());
+                Err(StateViewError::NotFound(format!(
+                    "{:?} was not recorded in the trace",
+                    state_key
+                )))
+            },
+        }
+    }
+
+    fn get_usage(&self) -> StateViewResult<StateStorageUsage> {
+        self.usage
+            .ok_or_else(|| StateViewError::Other("Usage was not recorded in the trace".into()))
+    }
+}
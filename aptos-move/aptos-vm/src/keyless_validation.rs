@@ -3,7 +3,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{aptos_vm::fetch_module_metadata_for_struct_tag, move_vm_ext::AptosMoveResolver};
-use aptos_crypto::ed25519::Ed25519PublicKey;
+use aptos_crypto::{ed25519::Ed25519PublicKey, signed_envelope::verify_signed};
 use aptos_types::{
     invalid_signature,
     jwks::{jwk::JWK, AllProvidersJWKs, FederatedJWKs, PatchedJWKs},
@@ -289,17 +289,17 @@ pub(crate) fn validate_authenticators(
                             if training_wheels_pk.is_some() {
                                 match &zksig.training_wheels_signature {
                                     Some(training_wheels_sig) => {
-                                        training_wheels_sig
-                                            .verify(
-                                                &groth16_and_stmt,
-                                                training_wheels_pk.as_ref().unwrap(),
+                                        verify_signed(
+                                            &groth16_and_stmt,
+                                            training_wheels_sig,
+                                            training_wheels_pk.as_ref().unwrap(),
+                                        )
+                                        .map_err(|_| {
+                                            // println!("[aptos-vm][groth16] TW sig verification failed");
+                                            invalid_signature!(
+                                                "Could not verify training wheels signature"
                                             )
-                                            .map_err(|_| {
-                                                // println!("[aptos-vm][groth16] TW sig verification failed");
-                                                invalid_signature!(
-                                                    "Could not verify training wheels signature"
-                                                )
-                                            })?;
+                                        })?;
                                     },
                                     None => {
                                         // println!("[aptos-vm][groth16] Expected TW sig to be set");
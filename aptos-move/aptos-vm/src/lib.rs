@@ -109,6 +109,7 @@ pub mod data_cache;
 pub mod aptos_vm;
 pub mod block_executor;
 mod errors;
+pub mod execution_trace;
 pub mod gas;
 #[cfg(not(feature = "testing"))]
 mod keyless_validation;
@@ -123,6 +124,7 @@ pub mod transaction_metadata;
 mod transaction_validation;
 pub mod validator_txns;
 pub mod verifier;
+pub mod write_scope;
 
 pub use crate::aptos_vm::{AptosSimulationVM, AptosVM};
 use crate::sharded_block_executor::{executor_client::ExecutorClient, ShardedBlockExecutor};
@@ -140,6 +142,7 @@ use aptos_types::{
     vm_status::VMStatus,
 };
 use aptos_vm_types::module_and_script_storage::code_storage::AptosCodeStorage;
+use crossbeam_channel::Sender;
 use std::{marker::Sync, sync::Arc};
 pub use verifier::view_function::determine_is_view;
 
@@ -195,10 +198,33 @@ pub trait VMBlockExecutor: Send + Sync {
     /// Executes a block of transactions using a sharded block executor and returns the results.
     fn execute_block_sharded<S: StateView + Sync + Send + 'static, E: ExecutorClient<S>>(
         _sharded_block_executor: &ShardedBlockExecutor<S, E>,
-        _transactions: PartitionedTransactions,
+        _transactions: &PartitionedTransactions,
         _state_view: Arc<S>,
         _onchain_config: BlockExecutorConfigFromOnchain,
     ) -> Result<Vec<TransactionOutput>, VMStatus> {
         unimplemented!("sharded not supported")
     }
+
+    /// Same as [VMBlockExecutor::execute_block_sharded], but sends the outputs to `output_tx` in
+    /// chunks, in order, so that they can be processed before the whole block is executed. By
+    /// default, all outputs are sent at once.
+    fn execute_block_sharded_streaming<
+        S: StateView + Sync + Send + 'static,
+        E: ExecutorClient<S>,
+    >(
+        sharded_block_executor: &ShardedBlockExecutor<S, E>,
+        transactions: &PartitionedTransactions,
+        state_view: Arc<S>,
+        onchain_config: BlockExecutorConfigFromOnchain,
+        output_tx: Sender<Vec<TransactionOutput>>,
+    ) -> Result<(), VMStatus> {
+        let transaction_outputs = Self::execute_block_sharded(
+            sharded_block_executor,
+            transactions,
+            state_view,
+            onchain_config,
+        )?;
+        output_tx.send(transaction_outputs).ok();
+        Ok(())
+    }
 }
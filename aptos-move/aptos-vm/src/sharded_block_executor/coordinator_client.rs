@@ -2,12 +2,19 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::sharded_block_executor::ExecutorShardCommand;
-use aptos_types::{state_store::StateView, transaction::TransactionOutput};
+use aptos_types::{
+    block_executor::partitioner::RoundId, state_store::StateView, transaction::TransactionOutput,
+};
 use move_core_types::vm_status::VMStatus;
 
 // Interface to communicate from the executor shards to the block executor coordinator.
 pub trait CoordinatorClient<S: StateView + Sync + Send + 'static>: Send + Sync {
     fn receive_execute_command(&self) -> ExecutorShardCommand<S>;
 
-    fn send_execution_result(&self, result: Result<Vec<Vec<TransactionOutput>>, VMStatus>);
+    // Sends the outputs of a round as soon as the shard executed it. Rounds are sent in order.
+    fn send_round_output(&self, round: RoundId, output: Vec<TransactionOutput>);
+
+    // Sends the result of the execution of the sub-blocks, once all the rounds were sent or the
+    // execution of a round failed.
+    fn send_execution_result(&self, result: Result<(), VMStatus>);
 }
@@ -3,8 +3,8 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use aptos_metrics_core::{
-    exponential_buckets, register_histogram, register_histogram_vec, register_int_gauge, Histogram,
-    HistogramVec, IntGauge,
+    exponential_buckets, register_gauge, register_histogram, register_histogram_vec,
+    register_int_counter, register_int_gauge, Gauge, Histogram, HistogramVec, IntCounter, IntGauge,
 };
 use once_cell::sync::Lazy;
 
@@ -72,3 +72,61 @@ pub static SHARDED_EXECUTOR_SERVICE_SECONDS: Lazy<HistogramVec> = Lazy::new(|| {
     )
     .unwrap()
 });
+
+pub static SHARD_EXECUTED_TXNS: Lazy<HistogramVec> = Lazy::new(|| {
+    register_histogram_vec!(
+        "sharded_block_executor_shard_executed_txns",
+        "Number of transactions executed by a shard in a block",
+        &["shard_id"],
+        exponential_buckets(/*start=*/ 1.0, /*factor=*/ 2.0, /*count=*/ 20).unwrap(),
+    )
+    .unwrap()
+});
+
+pub static SHARD_GAS_USED: Lazy<HistogramVec> = Lazy::new(|| {
+    register_histogram_vec!(
+        "sharded_block_executor_shard_gas_used",
+        "Gas used by the transactions executed by a shard in a block",
+        &["shard_id"],
+        exponential_buckets(/*start=*/ 1.0, /*factor=*/ 2.0, /*count=*/ 30).unwrap(),
+    )
+    .unwrap()
+});
+
+pub static SHARD_EXECUTION_SECONDS: Lazy<HistogramVec> = Lazy::new(|| {
+    register_histogram_vec!(
+        "sharded_block_executor_shard_execution_seconds",
+        "Wall-clock time in seconds from sending a block to a shard until receiving its results \
+         on the coordinator",
+        &["shard_id"],
+        exponential_buckets(/*start=*/ 1e-3, /*factor=*/ 2.0, /*count=*/ 20).unwrap(),
+    )
+    .unwrap()
+});
+
+pub static CROSS_SHARD_WAIT_SECONDS: Lazy<HistogramVec> = Lazy::new(|| {
+    register_histogram_vec!(
+        "sharded_block_executor_cross_shard_wait_seconds",
+        "Total time in seconds the workers of a shard were blocked on cross-shard dependencies \
+         while executing a sub block",
+        &["shard_id", "round_id"],
+        exponential_buckets(/*start=*/ 1e-4, /*factor=*/ 2.0, /*count=*/ 20).unwrap(),
+    )
+    .unwrap()
+});
+
+pub static SHARD_EXECUTION_IMBALANCE: Lazy<Gauge> = Lazy::new(|| {
+    register_gauge!(
+        "sharded_block_executor_shard_execution_imbalance",
+        "Ratio between the maximum and the mean execution time of the shards in the last block"
+    )
+    .unwrap()
+});
+
+pub static SHARDED_BLOCK_EXECUTION_FALLBACK_COUNT: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "sharded_block_execution_fallback_count",
+        "Number of blocks executed on the coordinator because sharded execution failed"
+    )
+    .unwrap()
+});
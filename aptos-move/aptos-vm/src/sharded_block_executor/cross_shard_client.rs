@@ -13,13 +13,16 @@ use aptos_logger::trace;
 use aptos_mvhashmap::types::TxnIndex;
 use aptos_types::{
     block_executor::partitioner::{RoundId, ShardId, SubBlock, GLOBAL_ROUND_ID},
-    state_store::{state_key::StateKey, StateView},
-    transaction::analyzed_transaction::AnalyzedTransaction,
-    write_set::TransactionWrite,
+    state_store::{errors::StateViewError, state_key::StateKey, StateView},
+    transaction::{analyzed_transaction::AnalyzedTransaction, TransactionOutput},
+    write_set::{TransactionWrite, WriteOp},
 };
 use std::{
     collections::{HashMap, HashSet},
-    sync::Arc,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
 };
 
 pub struct CrossShardCommitReceiver {}
@@ -42,11 +45,23 @@ impl CrossShardCommitReceiver {
                     trace!("Cross shard commit receiver stopped for round {}", round);
                     break;
                 },
+                CrossShardMsg::AbortMsg => {
+                    trace!("Cross shard values aborted for round {}", round);
+                    cross_shard_state_view.abort_waiting_values();
+                },
             }
         }
     }
 }
 
+struct DependentEdge {
+    // The shard id and round id pairs that depend on the storage location.
+    dependent_shard_ids: HashSet<(ShardId, RoundId)>,
+    // Whether the update has already been sent when the source transaction was committed.
+    sent: AtomicBool,
+}
+
+#[derive(Clone)]
 pub struct CrossShardCommitSender {
     shard_id: ShardId,
     cross_shard_client: Arc<dyn CrossShardClient>,
@@ -54,7 +69,7 @@ pub struct CrossShardCommitSender {
     // list shard id and round id. Please note that the transaction indices stored here is
     // global indices, so we need to convert the local index received from the parallel execution to
     // the global index.
-    dependent_edges: HashMap<TxnIndex, HashMap<StateKey, HashSet<(ShardId, RoundId)>>>,
+    dependent_edges: Arc<HashMap<TxnIndex, HashMap<StateKey, DependentEdge>>>,
     // The offset of the first transaction in the sub-block. This is used to convert the local index
     // in parallel execution to the global index.
     index_offset: TxnIndex,
@@ -78,7 +93,11 @@ impl CrossShardCommitSender {
                 for storage_location in storage_locations {
                     storage_locations_to_target
                         .entry(storage_location.clone().into_state_key())
-                        .or_insert_with(HashSet::new)
+                        .or_insert_with(|| DependentEdge {
+                            dependent_shard_ids: HashSet::new(),
+                            sent: AtomicBool::new(false),
+                        })
+                        .dependent_shard_ids
                         .insert((txn_id_with_shard.shard_id, txn_id_with_shard.round_id));
                     num_dependent_edges += 1;
                 }
@@ -97,11 +116,36 @@ impl CrossShardCommitSender {
         Self {
             shard_id,
             cross_shard_client,
-            dependent_edges,
+            dependent_edges: Arc::new(dependent_edges),
             index_offset: sub_block.start_index as TxnIndex,
         }
     }
 
+    fn send_remote_update(
+        &self,
+        txn_idx: TxnIndex,
+        state_key: &StateKey,
+        write_op: WriteOp,
+        dependent_shard_ids: &HashSet<(ShardId, RoundId)>,
+    ) {
+        for (dependent_shard_id, round_id) in dependent_shard_ids.iter() {
+            trace!("Sending remote update for shard id {:?} and txn_idx: {:?}, state_key: {:?}, dependent shard id: {:?}", self.shard_id, txn_idx, state_key, dependent_shard_id);
+            let message = RemoteTxnWriteMsg(RemoteTxnWrite::new(
+                state_key.clone(),
+                Some(write_op.clone()),
+            ));
+            if *round_id == GLOBAL_ROUND_ID {
+                self.cross_shard_client.send_global_msg(message);
+            } else {
+                self.cross_shard_client.send_cross_shard_msg(
+                    *dependent_shard_id,
+                    *round_id,
+                    message,
+                );
+            }
+        }
+    }
+
     fn send_remote_update_for_success(
         &self,
         txn_idx: TxnIndex,
@@ -112,25 +156,58 @@ impl CrossShardCommitSender {
         let write_set = output.write_set();
 
         for (state_key, write_op) in write_set.iter() {
-            if let Some(dependent_shard_ids) = edges.get(state_key) {
-                for (dependent_shard_id, round_id) in dependent_shard_ids.iter() {
-                    trace!("Sending remote update for success for shard id {:?} and txn_idx: {:?}, state_key: {:?}, dependent shard id: {:?}", self.shard_id, txn_idx, state_key, dependent_shard_id);
-                    let message = RemoteTxnWriteMsg(RemoteTxnWrite::new(
-                        state_key.clone(),
-                        Some(write_op.clone()),
-                    ));
-                    if *round_id == GLOBAL_ROUND_ID {
-                        self.cross_shard_client.send_global_msg(message);
-                    } else {
-                        self.cross_shard_client.send_cross_shard_msg(
-                            *dependent_shard_id,
-                            *round_id,
-                            message,
-                        );
-                    }
+            if let Some(edge) = edges.get(state_key) {
+                self.send_remote_update(
+                    txn_idx,
+                    state_key,
+                    write_op.clone(),
+                    &edge.dependent_shard_ids,
+                );
+                edge.sent.store(true, Ordering::Release);
+            }
+        }
+    }
+
+    /// Sends updates for all storage locations that transactions in later rounds depend on, but
+    /// that were not written by the source transaction (dependencies are computed from the
+    /// estimated write sets, so e.g. a failed transaction may not write them). The value sent is
+    /// the value of the storage location right after the source transaction: the last write of
+    /// a preceding transaction in the sub-block, or the value in the state view of the sub-block.
+    /// Must be called after the sub-block is executed, with its outputs. If there are fewer outputs
+    /// than transactions in the sub-block (e.g. the block was cut), the missing transactions are
+    /// treated as not having written anything.
+    pub fn send_remaining_updates(
+        &self,
+        txn_outputs: &[TransactionOutput],
+        state_view: &impl StateView,
+    ) -> Result<(), StateViewError> {
+        for (txn_idx, edges) in self.dependent_edges.iter() {
+            let num_preceding_outputs =
+                ((txn_idx - self.index_offset) as usize + 1).min(txn_outputs.len());
+            for (state_key, edge) in edges.iter() {
+                if edge.sent.load(Ordering::Acquire) {
+                    continue;
                 }
+
+                let state_value = match txn_outputs[..num_preceding_outputs]
+                    .iter()
+                    .rev()
+                    .find_map(|output| output.write_set().get(state_key))
+                {
+                    Some(write_op) => write_op.as_state_value(),
+                    None => state_view.get_state_value(state_key)?,
+                };
+                let write_op = match state_value {
+                    Some(state_value) => {
+                        let (metadata, bytes) = state_value.unpack();
+                        WriteOp::modification(bytes, metadata)
+                    },
+                    None => WriteOp::legacy_deletion(),
+                };
+                self.send_remote_update(*txn_idx, state_key, write_op, &edge.dependent_shard_ids);
             }
         }
+        Ok(())
     }
 }
 
@@ -158,3 +235,115 @@ pub trait CrossShardClient: Send + Sync {
 
     fn receive_cross_shard_msg(&self, current_round: RoundId) -> CrossShardMsg;
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aptos_crypto::HashValue;
+    use aptos_infallible::Mutex;
+    use aptos_types::{
+        block_executor::partitioner::{
+            CrossShardDependencies, ShardedTxnIndex, TransactionWithDependencies,
+        },
+        state_store::{state_value::StateValue, MockStateView},
+        transaction::{
+            analyzed_transaction::StorageLocation, ExecutionStatus, Transaction,
+            TransactionAuxiliaryData, TransactionStatus,
+        },
+        write_set::WriteSet,
+    };
+
+    #[derive(Default)]
+    struct RecordingCrossShardClient {
+        sent: Mutex<Vec<(ShardId, RoundId, StateKey, Option<StateValue>)>>,
+    }
+
+    impl CrossShardClient for RecordingCrossShardClient {
+        fn send_global_msg(&self, msg: CrossShardMsg) {
+            self.send_cross_shard_msg(0, GLOBAL_ROUND_ID, msg);
+        }
+
+        fn send_cross_shard_msg(&self, shard_id: ShardId, round: RoundId, msg: CrossShardMsg) {
+            if let RemoteTxnWriteMsg(txn_write) = msg {
+                let (state_key, write_op) = txn_write.take();
+                self.sent.lock().push((
+                    shard_id,
+                    round,
+                    state_key,
+                    write_op.and_then(|w| w.as_state_value()),
+                ));
+            }
+        }
+
+        fn receive_cross_shard_msg(&self, _current_round: RoundId) -> CrossShardMsg {
+            unreachable!()
+        }
+    }
+
+    fn txn_with_dependent_edge(
+        state_key: Option<&StateKey>,
+    ) -> TransactionWithDependencies<AnalyzedTransaction> {
+        let mut cross_shard_dependencies = CrossShardDependencies::default();
+        if let Some(state_key) = state_key {
+            cross_shard_dependencies.add_dependent_edge(ShardedTxnIndex::new(20, 1, 1), vec![
+                StorageLocation::Specific(state_key.clone()),
+            ]);
+        }
+        TransactionWithDependencies::new(
+            Transaction::StateCheckpoint(HashValue::zero()).into(),
+            cross_shard_dependencies,
+        )
+    }
+
+    fn output_with_writes(writes: Vec<(StateKey, Option<StateValue>)>) -> TransactionOutput {
+        TransactionOutput::new(
+            WriteSet::new_for_test(writes),
+            vec![],
+            0,
+            TransactionStatus::Keep(ExecutionStatus::Success),
+            TransactionAuxiliaryData::default(),
+        )
+    }
+
+    #[test]
+    fn test_send_remaining_updates() {
+        let key_a = StateKey::raw(b"a");
+        let key_b = StateKey::raw(b"b");
+        let key_c = StateKey::raw(b"c");
+        let value = |v: &str| StateValue::from(v.as_bytes().to_owned());
+
+        let sub_block = SubBlock::new(10, vec![
+            txn_with_dependent_edge(Some(&key_a)),
+            txn_with_dependent_edge(Some(&key_c)),
+            txn_with_dependent_edge(None),
+            txn_with_dependent_edge(Some(&key_b)),
+        ]);
+        let client = Arc::new(RecordingCrossShardClient::default());
+        let sender = CrossShardCommitSender::new(0, client.clone(), &sub_block);
+        // The update for key c was already sent when txn 11 was committed.
+        sender.dependent_edges[&11][&key_c]
+            .sent
+            .store(true, Ordering::Release);
+
+        // Fewer outputs than transactions, the last transaction of the sub-block is missing.
+        let txn_outputs = vec![
+            output_with_writes(vec![]),
+            output_with_writes(vec![(key_b.clone(), Some(value("b1")))]),
+            output_with_writes(vec![(key_b.clone(), Some(value("b2")))]),
+        ];
+        let state_view = MockStateView::new(HashMap::from([
+            (key_a.clone(), value("a0")),
+            (key_b.clone(), value("b0")),
+        ]));
+        sender
+            .send_remaining_updates(&txn_outputs, &state_view)
+            .unwrap();
+
+        let mut sent = client.sent.lock().clone();
+        sent.sort_by(|x, y| x.2.cmp(&y.2));
+        assert_eq!(sent, vec![
+            (1, 1, key_a, Some(value("a0"))),
+            (1, 1, key_b, Some(value("b2"))),
+        ]);
+    }
+}
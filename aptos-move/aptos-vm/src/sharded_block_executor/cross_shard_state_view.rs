@@ -11,15 +11,20 @@ use aptos_types::{
     },
     transaction::analyzed_transaction::AnalyzedTransaction,
 };
-use std::collections::{HashMap, HashSet};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::atomic::{AtomicU64, Ordering},
+    time::{Duration, Instant},
+};
 
 /// A state view for reading cross shard state values. It is backed by a state view
 /// and a hashmap of cross shard state keys. When a cross shard state value is not
 /// available in the hashmap, it will be fetched from the underlying base view.
-#[derive(Clone)]
 pub struct CrossShardStateView<'a, S> {
     cross_shard_data: HashMap<StateKey, RemoteStateValue>,
     base_view: &'a S,
+    // Total time readers were blocked waiting for cross shard state values.
+    wait_time_nanos: AtomicU64,
 }
 
 impl<'a, S: StateView + Sync + Send> CrossShardStateView<'a, S> {
@@ -35,9 +40,15 @@ impl<'a, S: StateView + Sync + Send> CrossShardStateView<'a, S> {
         Self {
             cross_shard_data,
             base_view,
+            wait_time_nanos: AtomicU64::new(0),
         }
     }
 
+    /// Total time readers were blocked waiting for cross shard state values, summed over readers.
+    pub fn wait_time(&self) -> Duration {
+        Duration::from_nanos(self.wait_time_nanos.load(Ordering::Relaxed))
+    }
+
     #[cfg(test)]
     fn waiting_count(&self) -> usize {
         self.cross_shard_data
@@ -55,6 +66,14 @@ impl<'a, S: StateView + Sync + Send> CrossShardStateView<'a, S> {
         // trace!("waiting count for shard id {} is {}", self.shard_id, self.waiting_count());
     }
 
+    /// Aborts all the values that were not received yet, so that reading them fails instead of
+    /// blocking forever.
+    pub fn abort_waiting_values(&self) {
+        for value in self.cross_shard_data.values() {
+            value.abort();
+        }
+    }
+
     pub fn create_cross_shard_state_view(
         base_view: &'a S,
         transactions: &[TransactionWithDependencies<AnalyzedTransaction>],
@@ -76,7 +95,19 @@ impl<'a, S: StateView + Sync + Send> TStateView for CrossShardStateView<'a, S> {
 
     fn get_state_value(&self, state_key: &StateKey) -> Result<Option<StateValue>, StateViewError> {
         if let Some(value) = self.cross_shard_data.get(state_key) {
-            return Ok(value.get_value());
+            if value.is_ready() {
+                return Ok(value.get_value());
+            }
+            let start_time = Instant::now();
+            let state_value = value.wait_for_value();
+            self.wait_time_nanos
+                .fetch_add(start_time.elapsed().as_nanos() as u64, Ordering::Relaxed);
+            return state_value.ok_or_else(|| {
+                StateViewError::Other(format!(
+                    "Cross shard state value for {:?} was aborted",
+                    state_key
+                ))
+            });
         }
         self.base_view.get_state_value(state_key)
     }
@@ -137,5 +168,34 @@ mod tests {
         assert_eq!(cross_shard_state_view.waiting_count(), 0);
 
         wait_thread.join().unwrap();
+        assert!(cross_shard_state_view.wait_time() > Duration::ZERO);
+    }
+
+    #[test]
+    fn test_cross_shard_state_view_abort_waiting_values() {
+        let ready_key = StateKey::raw(b"ready");
+        let waiting_key = StateKey::raw(b"waiting");
+        let state_value = StateValue::from("value".as_bytes().to_owned());
+
+        let state_keys = HashSet::from([ready_key.clone(), waiting_key.clone()]);
+        let cross_shard_state_view = Arc::new(CrossShardStateView::new(state_keys, &EmptyView));
+        cross_shard_state_view.set_value(&ready_key, Some(state_value.clone()));
+
+        let cross_shard_state_view_clone = cross_shard_state_view.clone();
+        let wait_thread = thread::spawn(move || {
+            assert!(cross_shard_state_view_clone
+                .get_state_value(&waiting_key)
+                .is_err());
+        });
+
+        thread::sleep(Duration::from_millis(100));
+        cross_shard_state_view.abort_waiting_values();
+        wait_thread.join().unwrap();
+
+        // Values that were already received are not affected.
+        assert_eq!(
+            cross_shard_state_view.get_state_value(&ready_key).unwrap(),
+            Some(state_value)
+        );
     }
 }
@@ -3,17 +3,25 @@
 
 use aptos_types::{
     block_executor::{
-        config::BlockExecutorConfigFromOnchain, partitioner::PartitionedTransactions,
+        config::BlockExecutorConfigFromOnchain,
+        partitioner::{PartitionedTransactions, ShardId},
     },
     state_store::StateView,
     transaction::TransactionOutput,
 };
+use crossbeam_channel::{Receiver, Select, Sender};
 use move_core_types::vm_status::VMStatus;
-use std::sync::Arc;
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
 pub struct ShardedExecutionOutput {
     pub sharded_output: Vec<Vec<Vec<TransactionOutput>>>,
     pub global_output: Vec<TransactionOutput>,
+    // Time each shard took to execute its sub-blocks, as observed by the coordinator. Empty if
+    // not measured by the executor client.
+    pub shard_execution_times: Vec<Duration>,
 }
 
 impl ShardedExecutionOutput {
@@ -24,12 +32,64 @@ impl ShardedExecutionOutput {
         Self {
             sharded_output,
             global_output,
+            shard_execution_times: vec![],
+        }
+    }
+
+    pub fn with_shard_execution_times(mut self, shard_execution_times: Vec<Duration>) -> Self {
+        self.shard_execution_times = shard_execution_times;
+        self
+    }
+
+    /// Returns the statistics of the shards, as observed by the coordinator.
+    pub fn stats(&self) -> ShardedExecutionStats {
+        ShardedExecutionStats {
+            shard_txns_and_gas: self
+                .sharded_output
+                .iter()
+                .map(|shard_output| {
+                    let txn_outputs = shard_output.iter().flatten();
+                    (
+                        txn_outputs.clone().count(),
+                        txn_outputs.map(TransactionOutput::gas_used).sum(),
+                    )
+                })
+                .collect(),
+            shard_execution_times: self.shard_execution_times.clone(),
         }
     }
 
     pub fn into_inner(self) -> (Vec<Vec<Vec<TransactionOutput>>>, Vec<TransactionOutput>) {
         (self.sharded_output, self.global_output)
     }
+
+    /// Returns the outputs in the global execution order: the outputs of each round, in the order
+    /// of the shard ids, followed by the global output.
+    pub fn into_ordered_outputs(self) -> Vec<TransactionOutput> {
+        let num_shards = self.sharded_output.len();
+        let num_rounds = self.sharded_output.first().map_or(0, Vec::len);
+        let mut ordered_results = vec![vec![]; num_shards * num_rounds];
+        for (shard_id, results_from_shard) in self.sharded_output.into_iter().enumerate() {
+            for (round, result) in results_from_shard.into_iter().enumerate() {
+                ordered_results[round * num_shards + shard_id] = result;
+            }
+        }
+        ordered_results
+            .into_iter()
+            .flatten()
+            .chain(self.global_output)
+            .collect()
+    }
+}
+
+/// Statistics of the execution of a block by the shards, used for metrics.
+#[derive(Clone, Debug, Default)]
+pub struct ShardedExecutionStats {
+    // Number of txns executed and gas used by each shard.
+    pub shard_txns_and_gas: Vec<(usize, u64)>,
+    // Time each shard took to execute its sub-blocks, as observed by the coordinator. Empty if
+    // not measured by the executor client.
+    pub shard_execution_times: Vec<Duration>,
 }
 
 // Interface to communicate from the block executor coordinator to the executor shards.
@@ -37,7 +97,8 @@ pub trait ExecutorClient<S: StateView + Sync + Send + 'static>: Send + Sync {
     fn num_shards(&self) -> usize;
 
     // A blocking call that executes the transactions in the block. It returns the execution results from each shard
-    // and in the round order and also the global output.
+    // and in the round order and also the global output. The block may be partitioned into fewer sub-blocks than the
+    // number of shards, in which case only the first shards are used.
     fn execute_block(
         &self,
         state_view: Arc<S>,
@@ -46,5 +107,190 @@ pub trait ExecutorClient<S: StateView + Sync + Send + 'static>: Send + Sync {
         onchain_config: BlockExecutorConfigFromOnchain,
     ) -> Result<ShardedExecutionOutput, VMStatus>;
 
+    // Same as `execute_block`, but instead of returning the outputs, sends them to `output_tx` in chunks, in the
+    // global execution order, and returns the statistics of the shards. A chunk is sent as soon as the outputs of all
+    // txns before it are available, so the receiver can start processing them while the rest of the block is being
+    // executed. By default, all outputs are sent at once after the whole block is executed. If an error is returned,
+    // some of the outputs may have been sent already.
+    fn execute_block_streaming(
+        &self,
+        state_view: Arc<S>,
+        transactions: PartitionedTransactions,
+        concurrency_level_per_shard: usize,
+        onchain_config: BlockExecutorConfigFromOnchain,
+        output_tx: Sender<Vec<TransactionOutput>>,
+    ) -> Result<ShardedExecutionStats, VMStatus> {
+        let execution_output = self.execute_block(
+            state_view,
+            transactions,
+            concurrency_level_per_shard,
+            onchain_config,
+        )?;
+        let stats = execution_output.stats();
+        // The receiver may have stopped listening, e.g. because it gave up on the block.
+        output_tx.send(execution_output.into_ordered_outputs()).ok();
+        Ok(stats)
+    }
+
     fn shutdown(&mut self);
 }
+
+/// Receives one message from each shard, in the order the shards complete. Returns the messages in
+/// the shard order, together with the time elapsed since `start_time` when each of them was
+/// received. Fails with the id of a shard that disconnected or did not respond before `deadline`.
+pub fn receive_from_shards<T>(
+    receivers: &[Receiver<T>],
+    start_time: Instant,
+    deadline: Option<Instant>,
+) -> Result<Vec<(T, Duration)>, ShardId> {
+    let mut results: Vec<Option<(T, Duration)>> = receivers.iter().map(|_| None).collect();
+    receive_from_shards_with(receivers, deadline, |shard_id, message| {
+        results[shard_id] = Some((message, start_time.elapsed()));
+    })?;
+    Ok(results.into_iter().map(Option::unwrap).collect())
+}
+
+/// Same as [receive_from_shards], but passes each message to `on_receive` together with the shard
+/// id as soon as it is received.
+pub fn receive_from_shards_with<T>(
+    receivers: &[Receiver<T>],
+    deadline: Option<Instant>,
+    on_receive: impl FnMut(ShardId, T),
+) -> Result<(), ShardId> {
+    receive_streams_from_shards_with(receivers, deadline, |_| true, on_receive)
+}
+
+/// Same as [receive_from_shards_with], but each shard may send several messages. Messages are
+/// received from a shard until `is_last` returns true for one of them.
+pub fn receive_streams_from_shards_with<T>(
+    receivers: &[Receiver<T>],
+    deadline: Option<Instant>,
+    is_last: impl Fn(&T) -> bool,
+    mut on_receive: impl FnMut(ShardId, T),
+) -> Result<(), ShardId> {
+    let mut select = Select::new();
+    for receiver in receivers {
+        select.recv(receiver);
+    }
+    let mut received = vec![false; receivers.len()];
+    let mut num_remaining = receivers.len();
+    while num_remaining > 0 {
+        let operation = match deadline {
+            Some(deadline) => select
+                .select_deadline(deadline)
+                .map_err(|_| received.iter().position(|received| !received).unwrap())?,
+            None => select.select(),
+        };
+        let shard_id = operation.index();
+        let message = operation.recv(&receivers[shard_id]).map_err(|_| shard_id)?;
+        if is_last(&message) {
+            select.remove(shard_id);
+            received[shard_id] = true;
+            num_remaining -= 1;
+        }
+        on_receive(shard_id, message);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aptos_types::{
+        transaction::{ExecutionStatus, TransactionAuxiliaryData, TransactionStatus},
+        write_set::WriteSet,
+    };
+    use crossbeam_channel::unbounded;
+    use std::thread;
+
+    #[test]
+    fn test_receive_from_shards() {
+        let (txs, rxs): (Vec<_>, Vec<_>) = (0..3).map(|_| unbounded()).unzip();
+        let start_time = Instant::now();
+        let handle = thread::spawn(move || {
+            for shard_id in [2, 0, 1] {
+                thread::sleep(Duration::from_millis(20));
+                txs[shard_id].send(shard_id).unwrap();
+            }
+        });
+        let results = receive_from_shards(&rxs, start_time, None).unwrap();
+        handle.join().unwrap();
+
+        let messages: Vec<_> = results.iter().map(|(message, _)| *message).collect();
+        assert_eq!(messages, vec![0, 1, 2]);
+        // Shard 2 completed first, and shard 1 last.
+        assert!(results[2].1 < results[0].1);
+        assert!(results[0].1 < results[1].1);
+    }
+
+    #[test]
+    fn test_receive_from_shards_failure() {
+        let (txs, rxs): (Vec<_>, Vec<_>) = (0..2).map(|_| unbounded::<()>()).unzip();
+        txs[0].send(()).unwrap();
+        let deadline = Instant::now() + Duration::from_millis(20);
+        assert_eq!(
+            receive_from_shards(&rxs, Instant::now(), Some(deadline)).unwrap_err(),
+            1
+        );
+
+        drop(txs);
+        assert!(receive_from_shards(&rxs, Instant::now(), None).is_err());
+    }
+
+    #[test]
+    fn test_receive_streams_from_shards() {
+        let (txs, rxs): (Vec<_>, Vec<_>) = (0..2).map(|_| unbounded()).unzip();
+        // Each shard sends its messages, then None once done.
+        for (tx, messages) in txs.iter().zip([vec![1, 2, 3], vec![4]]) {
+            for message in messages {
+                tx.send(Some(message)).unwrap();
+            }
+            tx.send(None).unwrap();
+        }
+        // Not received, since it is sent after the last message of shard 0.
+        txs[0].send(Some(5)).unwrap();
+
+        let mut messages = vec![vec![]; 2];
+        receive_streams_from_shards_with(&rxs, None, Option::is_none, |shard_id, message| {
+            messages[shard_id].extend(message)
+        })
+        .unwrap();
+        assert_eq!(messages, vec![vec![1, 2, 3], vec![4]]);
+    }
+
+    #[test]
+    fn test_into_ordered_outputs() {
+        // Gas used is the expected position in the global execution order.
+        let outputs = |gas_used: &[u64]| -> Vec<TransactionOutput> {
+            gas_used
+                .iter()
+                .map(|gas_used| {
+                    TransactionOutput::new(
+                        WriteSet::default(),
+                        vec![],
+                        *gas_used,
+                        TransactionStatus::Keep(ExecutionStatus::Success),
+                        TransactionAuxiliaryData::default(),
+                    )
+                })
+                .collect()
+        };
+        let execution_output = ShardedExecutionOutput::new(
+            vec![vec![outputs(&[0, 1]), outputs(&[4])], vec![
+                outputs(&[2, 3]),
+                outputs(&[]),
+            ]],
+            outputs(&[5, 6]),
+        );
+        assert_eq!(execution_output.stats().shard_txns_and_gas, vec![
+            (3, 5),
+            (2, 5)
+        ]);
+        let gas_used: Vec<_> = execution_output
+            .into_ordered_outputs()
+            .iter()
+            .map(TransactionOutput::gas_used)
+            .collect();
+        assert_eq!(gas_used, vec![0, 1, 2, 3, 4, 5, 6]);
+    }
+}
@@ -2,11 +2,13 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::sharded_block_executor::{
-    local_executor_shard::GlobalCrossShardClient, sharded_executor_service::ShardedExecutorService,
+    cross_shard_client::CrossShardClient, local_executor_shard::GlobalCrossShardClient,
+    messages::CrossShardMsg, sharded_executor_service::ShardedExecutorService,
 };
 use aptos_logger::trace;
 use aptos_types::{
     block_executor::{
+        block_gas_budget::BlockGasBudget,
         config::{BlockExecutorConfig, BlockExecutorConfigFromOnchain, BlockExecutorLocalConfig},
         partitioner::{TransactionWithDependencies, GLOBAL_ROUND_ID},
     },
@@ -46,6 +48,7 @@ impl<S: StateView + Sync + Send + 'static> GlobalExecutor<S> {
         transactions: Vec<TransactionWithDependencies<AnalyzedTransaction>>,
         state_view: &S,
         onchain_config: BlockExecutorConfigFromOnchain,
+        block_gas_budget: Option<u64>,
     ) -> Result<Vec<TransactionOutput>, VMStatus> {
         trace!("executing the last round in global executor",);
         if transactions.is_empty() {
@@ -60,14 +63,31 @@ impl<S: StateView + Sync + Send + 'static> GlobalExecutor<S> {
             GLOBAL_ROUND_ID,
             state_view,
             BlockExecutorConfig {
-                local: BlockExecutorLocalConfig::default_with_concurrency_level(
-                    self.concurrency_level,
-                ),
+                local: BlockExecutorLocalConfig {
+                    block_gas_budget: block_gas_budget
+                        .map(|budget| Arc::new(BlockGasBudget::new(budget))),
+                    ..BlockExecutorLocalConfig::default_with_concurrency_level(
+                        self.concurrency_level,
+                    )
+                },
                 onchain: onchain_config,
             },
         )
     }
 
+    /// Makes the global txns that are blocked on cross-shard values fail instead of waiting
+    /// forever, once a shard failed and may never send them.
+    pub fn abort_cross_shard_dependencies(&self) {
+        self.global_cross_shard_client
+            .send_global_msg(CrossShardMsg::AbortMsg);
+    }
+
+    /// Drops the cross-shard messages left over from a block that failed, so that they are not
+    /// received while executing the next block.
+    pub fn drain_cross_shard_msgs(&self) {
+        self.global_cross_shard_client.drain();
+    }
+
     pub fn get_executor_thread_pool(&self) -> Arc<rayon::ThreadPool> {
         self.executor_thread_pool.clone()
     }
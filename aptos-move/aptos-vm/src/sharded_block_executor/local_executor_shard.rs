@@ -2,31 +2,51 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::sharded_block_executor::{
+    block_gas_budget_per_executor,
     coordinator_client::CoordinatorClient,
     counters::WAIT_FOR_SHARDED_OUTPUT_SECONDS,
     cross_shard_client::CrossShardClient,
-    executor_client::{ExecutorClient, ShardedExecutionOutput},
+    executor_client::{
+        receive_streams_from_shards_with, ExecutorClient, ShardedExecutionOutput,
+        ShardedExecutionStats,
+    },
     global_executor::GlobalExecutor,
     messages::CrossShardMsg,
     sharded_aggregator_service,
+    sharded_aggregator_service::StreamingOutputAggregator,
     sharded_executor_service::ShardedExecutorService,
     ExecutorShardCommand, ShardedBlockExecutor,
 };
-use aptos_logger::trace;
+use aptos_logger::{error, trace};
 use aptos_types::{
     block_executor::{
         config::BlockExecutorConfigFromOnchain,
         partitioner::{
-            PartitionedTransactions, RoundId, ShardId, GLOBAL_ROUND_ID,
-            MAX_ALLOWED_PARTITIONING_ROUNDS,
+            PartitionedTransactions, RoundId, ShardId, SubBlocksForShard,
+            TransactionWithDependencies, GLOBAL_ROUND_ID, MAX_ALLOWED_PARTITIONING_ROUNDS,
         },
     },
     state_store::StateView,
-    transaction::TransactionOutput,
+    transaction::{analyzed_transaction::AnalyzedTransaction, TransactionOutput},
 };
 use crossbeam_channel::{unbounded, Receiver, Sender};
-use move_core_types::vm_status::VMStatus;
-use std::{sync::Arc, thread};
+use move_core_types::vm_status::{StatusCode, VMStatus};
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread,
+    time::{Duration, Instant},
+};
+
+/// Message sent by a local executor shard to the coordinator.
+pub enum ShardOutputMsg {
+    // Outputs of a round, sent as soon as the round is executed.
+    RoundOutput(RoundId, Vec<TransactionOutput>),
+    // Sent once all the rounds were sent, or the execution of a round failed.
+    Done(Result<(), VMStatus>),
+}
 
 /// Executor service that runs on local machine and waits for commands from the coordinator and executes
 /// them in parallel.
@@ -41,7 +61,7 @@ impl<S: StateView + Sync + Send + 'static> LocalExecutorService<S> {
         num_shards: usize,
         num_threads: usize,
         command_rx: Receiver<ExecutorShardCommand<S>>,
-        result_tx: Sender<Result<Vec<Vec<TransactionOutput>>, VMStatus>>,
+        result_tx: Sender<ShardOutputMsg>,
         cross_shard_client: LocalCrossShardClient,
     ) -> Self {
         let coordinator_client = Arc::new(LocalCoordinatorClient::new(command_rx, result_tx));
@@ -85,10 +105,8 @@ impl<S: StateView + Sync + Send + 'static> LocalExecutorService<S> {
             Vec<Sender<ExecutorShardCommand<S>>>,
             Vec<Receiver<ExecutorShardCommand<S>>>,
         ) = (0..num_shards).map(|_| unbounded()).unzip();
-        let (result_txs, result_rxs): (
-            Vec<Sender<Result<Vec<Vec<TransactionOutput>>, VMStatus>>>,
-            Vec<Receiver<Result<Vec<Vec<TransactionOutput>>, VMStatus>>>,
-        ) = (0..num_shards).map(|_| unbounded()).unzip();
+        let (result_txs, result_rxs): (Vec<Sender<ShardOutputMsg>>, Vec<Receiver<ShardOutputMsg>>) =
+            (0..num_shards).map(|_| unbounded()).unzip();
         // We need to create channels for each shard and each round. This is needed because individual
         // shards might send cross shard messages to other shards that will be consumed in different rounds.
         // Having a single channel per shard will cause a shard to receiver messages that is not intended in the current round.
@@ -131,15 +149,18 @@ pub struct LocalExecutorClient<S: StateView + Sync + Send + 'static> {
     // Channels to send execute block commands to the executor shards.
     command_txs: Vec<Sender<ExecutorShardCommand<S>>>,
     // Channels to receive execution results from the executor shards.
-    result_rxs: Vec<Receiver<Result<Vec<Vec<TransactionOutput>>, VMStatus>>>,
+    result_rxs: Vec<Receiver<ShardOutputMsg>>,
     executor_services: Vec<LocalExecutorService<S>>,
     global_executor: GlobalExecutor<S>,
+    // Set once a shard terminated unexpectedly. The remaining shards may be blocked on cross-shard
+    // dependencies from the failed shard, so no more blocks can be executed.
+    shard_failure_detected: AtomicBool,
 }
 
 impl<S: StateView + Sync + Send + 'static> LocalExecutorClient<S> {
     pub fn new(
         command_tx: Vec<Sender<ExecutorShardCommand<S>>>,
-        result_rx: Vec<Receiver<Result<Vec<Vec<TransactionOutput>>, VMStatus>>>,
+        result_rx: Vec<Receiver<ShardOutputMsg>>,
         executor_shards: Vec<LocalExecutorService<S>>,
         global_executor: GlobalExecutor<S>,
     ) -> Self {
@@ -148,6 +169,7 @@ impl<S: StateView + Sync + Send + 'static> LocalExecutorClient<S> {
             result_rxs: result_rx,
             executor_services: executor_shards,
             global_executor,
+            shard_failure_detected: AtomicBool::new(false),
         }
     }
 
@@ -161,17 +183,123 @@ impl<S: StateView + Sync + Send + 'static> LocalExecutorClient<S> {
         ))
     }
 
-    fn get_output_from_shards(&self) -> Result<Vec<Vec<Vec<TransactionOutput>>>, VMStatus> {
+    /// Passes the outputs of each round to `on_round_output` as soon as a shard sends them, until
+    /// all shards are done. Returns the time each shard took to execute its sub-blocks.
+    fn get_output_from_shards(
+        &self,
+        num_active_shards: usize,
+        start_time: Instant,
+        mut on_round_output: impl FnMut(ShardId, RoundId, Vec<TransactionOutput>),
+    ) -> Result<Vec<Duration>, VMStatus> {
         let _timer = WAIT_FOR_SHARDED_OUTPUT_SECONDS.start_timer();
         trace!("LocalExecutorClient Waiting for results");
-        let mut results = vec![];
-        for (i, rx) in self.result_rxs.iter().enumerate() {
-            results.push(
-                rx.recv()
-                    .unwrap_or_else(|_| panic!("Did not receive output from shard {}", i))?,
-            );
+        let mut shard_execution_times = vec![Duration::ZERO; num_active_shards];
+        let mut shard_error = None;
+        receive_streams_from_shards_with(
+            &self.result_rxs[..num_active_shards],
+            None,
+            |message| matches!(message, ShardOutputMsg::Done(_)),
+            |shard_id, message| match message {
+                ShardOutputMsg::RoundOutput(round, txn_outputs) => {
+                    on_round_output(shard_id, round, txn_outputs)
+                },
+                ShardOutputMsg::Done(result) => {
+                    shard_execution_times[shard_id] = start_time.elapsed();
+                    if let Err(err) = result {
+                        shard_error.get_or_insert(err);
+                    }
+                },
+            },
+        )
+        .map_err(|shard_id| {
+            error!("Did not receive output from shard {}", shard_id);
+            self.shard_failure_error(shard_id)
+        })?;
+        match shard_error {
+            Some(err) => Err(err),
+            None => Ok(shard_execution_times),
+        }
+    }
+
+    fn check_no_previous_shard_failure(&self) -> Result<(), VMStatus> {
+        if self.shard_failure_detected.load(Ordering::Relaxed) {
+            return Err(VMStatus::error(
+                StatusCode::UNKNOWN_INVARIANT_VIOLATION_ERROR,
+                Some("Executor shard failure was detected in a previous block".to_string()),
+            ));
+        }
+        Ok(())
+    }
+
+    fn send_sub_blocks(
+        &self,
+        state_view: &Arc<S>,
+        sub_blocks: Vec<SubBlocksForShard<AnalyzedTransaction>>,
+        concurrency_level_per_shard: usize,
+        onchain_config: &BlockExecutorConfigFromOnchain,
+        block_gas_budget: Option<u64>,
+    ) -> Result<(), VMStatus> {
+        for (i, sub_blocks_for_shard) in sub_blocks.into_iter().enumerate() {
+            self.command_txs[i]
+                .send(ExecutorShardCommand::ExecuteSubBlocks(
+                    state_view.clone(),
+                    sub_blocks_for_shard,
+                    concurrency_level_per_shard,
+                    onchain_config.clone(),
+                    block_gas_budget,
+                ))
+                .map_err(|_| self.shard_failure_error(i))?;
         }
-        Ok(results)
+        Ok(())
+    }
+
+    /// Executes the global txns while `receive_shard_outputs` receives the outputs of the shards.
+    /// The global txns are blocked on the cross-shard values they depend on, so they may finish
+    /// before the shards do. If receiving the outputs of the shards fails, the global txns
+    /// waiting for cross-shard values are aborted, as a failed shard may never send them.
+    fn execute_global_txns_with_shards<T>(
+        &self,
+        global_txns: Vec<TransactionWithDependencies<AnalyzedTransaction>>,
+        state_view: &S,
+        onchain_config: BlockExecutorConfigFromOnchain,
+        block_gas_budget: Option<u64>,
+        receive_shard_outputs: impl FnOnce() -> Result<T, VMStatus>,
+    ) -> Result<(T, Vec<TransactionOutput>), VMStatus> {
+        thread::scope(|scope| {
+            let global_execution = scope.spawn(move || {
+                self.global_executor.execute_global_txns(
+                    global_txns,
+                    state_view,
+                    onchain_config,
+                    block_gas_budget,
+                )
+            });
+            let shard_result = receive_shard_outputs();
+            if shard_result.is_err() {
+                self.global_executor.abort_cross_shard_dependencies();
+            }
+            let global_result = global_execution.join().unwrap_or_else(|_| {
+                Err(VMStatus::error(
+                    StatusCode::UNKNOWN_INVARIANT_VIOLATION_ERROR,
+                    Some("Global executor panicked".to_string()),
+                ))
+            });
+            if shard_result.is_err() {
+                self.global_executor.drain_cross_shard_msgs();
+            }
+            Ok((shard_result?, global_result?))
+        })
+    }
+
+    fn shard_failure_error(&self, shard_id: ShardId) -> VMStatus {
+        self.shard_failure_detected.store(true, Ordering::Relaxed);
+        VMStatus::error(
+            StatusCode::UNKNOWN_INVARIANT_VIOLATION_ERROR,
+            Some(format!(
+                "Executor shard {} terminated unexpectedly",
+                shard_id
+            )),
+        )
     }
 }
 
@@ -187,31 +315,37 @@ impl<S: StateView + Sync + Send + 'static> ExecutorClient<S> for LocalExecutorCl
         concurrency_level_per_shard: usize,
         onchain_config: BlockExecutorConfigFromOnchain,
     ) -> Result<ShardedExecutionOutput, VMStatus> {
-        assert_eq!(transactions.num_shards(), self.num_shards());
+        self.check_no_previous_shard_failure()?;
+        let num_active_shards = transactions.num_shards();
+        assert!(num_active_shards <= self.num_shards());
         let (sub_blocks, global_txns) = transactions.into();
-        for (i, sub_blocks_for_shard) in sub_blocks.into_iter().enumerate() {
-            self.command_txs[i]
-                .send(ExecutorShardCommand::ExecuteSubBlocks(
-                    state_view.clone(),
-                    sub_blocks_for_shard,
-                    concurrency_level_per_shard,
-                    onchain_config.clone(),
-                ))
-                .unwrap();
-        }
+        let start_time = Instant::now();
+        // Each shard stops committing txns once its txns exhaust its share of the block gas limit,
+        // and the outputs are cut at the first skipped txn in the global order.
+        let block_gas_budget = block_gas_budget_per_executor(&onchain_config, num_active_shards);
+        self.send_sub_blocks(
+            &state_view,
+            sub_blocks,
+            concurrency_level_per_shard,
+            &onchain_config,
+            block_gas_budget,
+        )?;
 
-        // This means that we are executing the global transactions concurrently with the individual shards but the
-        // global transactions will be blocked for cross shard transaction results. This hopefully will help with
-        // finishing the global transactions faster but we need to evaluate if this causes thread contention. If it
-        // does, then we can simply move this call to the end of the function.
-        let mut global_output = self.global_executor.execute_global_txns(
+        let mut sharded_output = vec![vec![]; num_active_shards];
+        let (shard_execution_times, mut global_output) = self.execute_global_txns_with_shards(
             global_txns,
             state_view.as_ref(),
             onchain_config,
+            block_gas_budget,
+            || {
+                self.get_output_from_shards(
+                    num_active_shards,
+                    start_time,
+                    |shard_id, _round, txn_outputs| sharded_output[shard_id].push(txn_outputs),
+                )
+            },
         )?;
 
-        let mut sharded_output = self.get_output_from_shards()?;
-
         sharded_aggregator_service::aggregate_and_update_total_supply(
             &mut sharded_output,
             &mut global_output,
@@ -219,7 +353,71 @@ impl<S: StateView + Sync + Send + 'static> ExecutorClient<S> for LocalExecutorCl
             self.global_executor.get_executor_thread_pool(),
         );
 
-        Ok(ShardedExecutionOutput::new(sharded_output, global_output))
+        Ok(ShardedExecutionOutput::new(sharded_output, global_output)
+            .with_shard_execution_times(shard_execution_times))
+    }
+
+    fn execute_block_streaming(
+        &self,
+        state_view: Arc<S>,
+        transactions: PartitionedTransactions,
+        concurrency_level_per_shard: usize,
+        onchain_config: BlockExecutorConfigFromOnchain,
+        output_tx: Sender<Vec<TransactionOutput>>,
+    ) -> Result<ShardedExecutionStats, VMStatus> {
+        self.check_no_previous_shard_failure()?;
+        let num_active_shards = transactions.num_shards();
+        assert!(num_active_shards <= self.num_shards());
+        let num_rounds = transactions.sharded_txns()[0].num_sub_blocks();
+        let (sub_blocks, global_txns) = transactions.into();
+        let start_time = Instant::now();
+        // Each shard stops committing txns once its txns exhaust its share of the block gas limit,
+        // and the outputs are cut at the first skipped txn in the global order.
+        let block_gas_budget = block_gas_budget_per_executor(&onchain_config, num_active_shards);
+        self.send_sub_blocks(
+            &state_view,
+            sub_blocks,
+            concurrency_level_per_shard,
+            &onchain_config,
+            block_gas_budget,
+        )?;
+
+        // The outputs of each round of a shard are forwarded as soon as the outputs before them in
+        // the global order are received, instead of waiting for the shards to execute all rounds.
+        let mut aggregator = StreamingOutputAggregator::new(
+            num_active_shards,
+            num_rounds,
+            state_view.as_ref(),
+            self.global_executor.get_executor_thread_pool(),
+            output_tx,
+        );
+        let mut shard_txns_and_gas = vec![(0, 0); num_active_shards];
+        let (shard_execution_times, global_output) = self.execute_global_txns_with_shards(
+            global_txns,
+            state_view.as_ref(),
+            onchain_config,
+            block_gas_budget,
+            || {
+                self.get_output_from_shards(
+                    num_active_shards,
+                    start_time,
+                    |shard_id, round, txn_outputs| {
+                        let (num_txns, gas_used) = &mut shard_txns_and_gas[shard_id];
+                        *num_txns += txn_outputs.len();
+                        *gas_used += txn_outputs
+                            .iter()
+                            .map(TransactionOutput::gas_used)
+                            .sum::<u64>();
+                        aggregator.add_round_output(shard_id, round, txn_outputs);
+                    },
+                )
+            },
+        )?;
+        aggregator.finish(global_output);
+        Ok(ShardedExecutionStats {
+            shard_txns_and_gas,
+            shard_execution_times,
+        })
     }
 
     fn shutdown(&mut self) {}
@@ -231,6 +429,11 @@ impl<S: StateView + Sync + Send + 'static> Drop for LocalExecutorClient<S> {
             let _ = command_tx.send(ExecutorShardCommand::Stop);
         }
 
+        // Shards that are blocked on a failed shard never finish, so their threads are leaked.
+        if self.shard_failure_detected.load(Ordering::Relaxed) {
+            return;
+        }
+
         // wait for join handles to finish
         for executor_service in self.executor_services.iter_mut() {
             let _ = executor_service.join_handle.take().unwrap().join();
@@ -241,13 +444,13 @@ impl<S: StateView + Sync + Send + 'static> Drop for LocalExecutorClient<S> {
 pub struct LocalCoordinatorClient<S> {
     command_rx: Receiver<ExecutorShardCommand<S>>,
     // Channel to send execution results to the coordinator.
-    result_tx: Sender<Result<Vec<Vec<TransactionOutput>>, VMStatus>>,
+    result_tx: Sender<ShardOutputMsg>,
 }
 
 impl<S> LocalCoordinatorClient<S> {
     pub fn new(
         command_rx: Receiver<ExecutorShardCommand<S>>,
-        result_tx: Sender<Result<Vec<Vec<TransactionOutput>>, VMStatus>>,
+        result_tx: Sender<ShardOutputMsg>,
     ) -> Self {
         Self {
             command_rx,
@@ -261,8 +464,14 @@ impl<S: StateView + Sync + Send + 'static> CoordinatorClient<S> for LocalCoordin
         self.command_rx.recv().unwrap()
     }
 
-    fn send_execution_result(&self, result: Result<Vec<Vec<TransactionOutput>>, VMStatus>) {
-        self.result_tx.send(result).unwrap()
+    fn send_round_output(&self, round: RoundId, output: Vec<TransactionOutput>) {
+        self.result_tx
+            .send(ShardOutputMsg::RoundOutput(round, output))
+            .unwrap()
+    }
+
+    fn send_execution_result(&self, result: Result<(), VMStatus>) {
+        self.result_tx.send(ShardOutputMsg::Done(result)).unwrap()
     }
 }
 
@@ -281,6 +490,10 @@ impl GlobalCrossShardClient {
             global_message_rx: message_rx,
         }
     }
+
+    pub fn drain(&self) {
+        while self.global_message_rx.try_recv().is_ok() {}
+    }
 }
 
 impl CrossShardClient for GlobalCrossShardClient {
@@ -8,6 +8,8 @@ use serde::{Deserialize, Serialize};
 pub enum CrossShardMsg {
     RemoteTxnWriteMsg(RemoteTxnWrite),
     StopMsg,
+    // Sent when a shard failed, so the cross shard values that were not received yet never will be.
+    AbortMsg,
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
@@ -2,24 +2,51 @@
 // Parts of the project are originally copyright © Meta Platforms, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::sharded_block_executor::{
-    counters::{
-        NUM_EXECUTOR_SHARDS, SHARDED_BLOCK_EXECUTION_SECONDS,
-        SHARDED_EXECUTION_RESULT_AGGREGATION_SECONDS,
+use crate::{
+    block_executor::{AptosTransactionOutput, AptosVMBlockExecutorWrapper},
+    sharded_block_executor::{
+        counters::{
+            NUM_EXECUTOR_SHARDS, SHARDED_BLOCK_EXECUTION_FALLBACK_COUNT,
+            SHARDED_BLOCK_EXECUTION_SECONDS, SHARDED_EXECUTION_RESULT_AGGREGATION_SECONDS,
+            SHARD_EXECUTED_TXNS, SHARD_EXECUTION_IMBALANCE, SHARD_EXECUTION_SECONDS,
+            SHARD_GAS_USED,
+        },
+        executor_client::{ExecutorClient, ShardedExecutionStats},
+        sharded_aggregator_service::skip_outputs_after_first_retry,
     },
-    executor_client::ExecutorClient,
 };
-use aptos_logger::info;
+use aptos_block_executor::{
+    code_cache_global_manager::AptosModuleCacheManager, txn_commit_hook::NoOpTransactionCommitHook,
+    txn_provider::default::DefaultTxnProvider,
+};
+use aptos_block_partitioner::BlockPartitioner;
+use aptos_infallible::Mutex;
+use aptos_logger::{info, warn};
 use aptos_types::{
     block_executor::{
-        config::BlockExecutorConfigFromOnchain,
-        partitioner::{PartitionedTransactions, SubBlocksForShard},
+        config::{BlockExecutorConfig, BlockExecutorConfigFromOnchain, BlockExecutorLocalConfig},
+        partitioner::{PartitionedTransactions, ShardId, SubBlocksForShard},
+        transaction_slice_metadata::TransactionSliceMetadata,
     },
     state_store::StateView,
-    transaction::{analyzed_transaction::AnalyzedTransaction, TransactionOutput},
+    transaction::{
+        analyzed_transaction::AnalyzedTransaction,
+        signature_verified_transaction::SignatureVerifiedTransaction, BlockOutput,
+        TransactionOutput,
+    },
 };
+use crossbeam_channel::{unbounded, Sender};
 use move_core_types::vm_status::VMStatus;
-use std::{marker::PhantomData, sync::Arc};
+use serde::{Deserialize, Serialize};
+use std::{
+    marker::PhantomData,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc,
+    },
+    thread,
+    time::Duration,
+};
 
 pub mod aggr_overridden_state_view;
 pub mod coordinator_client;
@@ -37,19 +64,121 @@ pub mod sharded_executor_service;
 /// Coordinator for sharded block executors that manages multiple shards and aggregates the results.
 pub struct ShardedBlockExecutor<S: StateView + Sync + Send + 'static, C: ExecutorClient<S>> {
     executor_client: C,
+    // Number of shards the next block should be partitioned into. Equal to the number of shards
+    // of the executor client, unless dynamic sharding is enabled.
+    num_active_shards: ActiveShards,
+    dynamic_sharding_config: Option<DynamicShardingConfig>,
+    partitioner: Option<Mutex<Box<dyn BlockPartitioner>>>,
+    shard_failure_policy: ShardFailurePolicy,
     phantom: PhantomData<S>,
 }
 
+/// What to do when the executor client fails to execute a block, e.g. because a shard panicked,
+/// disconnected or did not respond in time.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ShardFailurePolicy {
+    /// Return the error to the caller.
+    #[default]
+    Abort,
+    /// Re-execute the whole block on the coordinator, in the same order as the sharded execution
+    /// would have, so the caller gets the same outputs. Remote executor clients fail fast once a
+    /// shard failure was detected, so subsequent blocks are executed on the coordinator as well.
+    ///
+    /// NOTE: sub-blocks of a failed shard cannot be moved to the surviving shards, since their
+    /// txns may be blocked on cross-shard dependencies from the failed shard.
+    SequentialFallback,
+}
+
+/// Number of shards the next block should be partitioned into, which can be shared with the
+/// components partitioning blocks ahead of their execution, e.g. in a separate pipeline stage.
+#[derive(Clone, Debug)]
+pub struct ActiveShards(Arc<AtomicUsize>);
+
+impl ActiveShards {
+    fn new(num_shards: usize) -> Self {
+        Self(Arc::new(AtomicUsize::new(num_shards)))
+    }
+
+    pub fn get(&self) -> usize {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    fn set(&self, num_shards: usize) {
+        self.0.store(num_shards, Ordering::Relaxed)
+    }
+}
+
+/// Policy to change the number of active shards between blocks, based on the load observed in
+/// the previously executed block. Shards of the executor client are always running, but blocks
+/// are only partitioned across the active ones, so the remaining shards stay idle.
+#[derive(Clone, Debug)]
+pub struct DynamicShardingConfig {
+    /// Minimum number of active shards.
+    pub min_num_shards: usize,
+    /// Desired number of transactions executed by a single shard in a block.
+    pub target_txns_per_shard: usize,
+    /// Maximum ratio between the number of transactions executed by the most loaded shard and the
+    /// average number of transactions per shard. If exceeded, partitioning could not spread the
+    /// load across the active shards, and the number of active shards is decreased.
+    pub max_imbalance_factor: f64,
+}
+
+impl DynamicShardingConfig {
+    /// Returns the number of shards the next block should be partitioned into, given the number
+    /// of txns of the previous block and the number of txns each of its shards executed.
+    fn next_num_shards(
+        &self,
+        num_txns: usize,
+        txns_per_shard: &[usize],
+        max_num_shards: usize,
+    ) -> usize {
+        let num_active_shards = txns_per_shard.len();
+        let num_sharded_txns: usize = txns_per_shard.iter().sum();
+
+        let target_txns_per_shard = self.target_txns_per_shard.max(1);
+        let mut num_shards = (num_txns + target_txns_per_shard - 1) / target_txns_per_shard;
+        if num_sharded_txns > 0 {
+            let avg_txns_per_shard = num_sharded_txns as f64 / num_active_shards as f64;
+            let max_txns_per_shard = txns_per_shard.iter().copied().max().unwrap_or(0);
+            if max_txns_per_shard as f64 / avg_txns_per_shard > self.max_imbalance_factor {
+                num_shards = num_shards.min(num_active_shards.saturating_sub(1));
+            }
+        }
+        num_shards
+            .max(self.min_num_shards)
+            .min(max_num_shards)
+            .max(1)
+    }
+}
+
 pub enum ExecutorShardCommand<S> {
     ExecuteSubBlocks(
         Arc<S>,
         SubBlocksForShard<AnalyzedTransaction>,
         usize,
         BlockExecutorConfigFromOnchain,
+        // Share of the block gas limit given to the shard, see `block_gas_budget_per_executor`.
+        Option<u64>,
     ),
     Stop,
 }
 
+/// Returns the share of the block gas limit given to each of the shards and to the global executor
+/// of a block partitioned into `num_shards` shards, so that the limit applies to the whole block
+/// instead of to each shard. Each executor stops committing txns once its own txns (across all
+/// its rounds) exhaust its share, so the committed txns only depend on the block and are the same
+/// on all nodes, regardless of how fast each shard runs.
+pub fn block_gas_budget_per_executor(
+    onchain_config: &BlockExecutorConfigFromOnchain,
+    num_shards: usize,
+) -> Option<u64> {
+    onchain_config
+        .block_gas_limit_type
+        .block_gas_limit()
+        .map(|block_gas_limit| block_gas_limit / (num_shards as u64 + 1))
+}
+
 impl<S: StateView + Sync + Send + 'static, C: ExecutorClient<S>> ShardedBlockExecutor<S, C> {
     pub fn new(executor_client: C) -> Self {
         info!(
@@ -57,66 +186,345 @@ impl<S: StateView + Sync + Send + 'static, C: ExecutorClient<S>> ShardedBlockExe
             executor_client.num_shards()
         );
         Self {
+            num_active_shards: ActiveShards::new(executor_client.num_shards()),
             executor_client,
+            dynamic_sharding_config: None,
+            partitioner: None,
+            shard_failure_policy: ShardFailurePolicy::default(),
             phantom: PhantomData,
         }
     }
 
+    /// Enables changing the number of active shards between blocks according to the config.
+    pub fn with_dynamic_sharding(mut self, dynamic_sharding_config: DynamicShardingConfig) -> Self {
+        self.dynamic_sharding_config = Some(dynamic_sharding_config);
+        self
+    }
+
+    pub fn with_shard_failure_policy(mut self, shard_failure_policy: ShardFailurePolicy) -> Self {
+        self.shard_failure_policy = shard_failure_policy;
+        self
+    }
+
+    /// Sets the partitioner used by [Self::partition]. Any `BlockPartitioner` can be plugged in, e.g.
+    /// `PartitionerV2` or `ConnectedComponentBlockPartitioner`.
+    pub fn with_partitioner(mut self, partitioner: Box<dyn BlockPartitioner>) -> Self {
+        self.partitioner = Some(Mutex::new(partitioner));
+        self
+    }
+
+    /// Partitions a block into the number of active shards, using the partitioner of the executor.
+    pub fn partition(&self, transactions: Vec<AnalyzedTransaction>) -> PartitionedTransactions {
+        self.partitioner
+            .as_ref()
+            .expect("ShardedBlockExecutor has no partitioner")
+            .lock()
+            .partition(transactions, self.num_active_shards())
+    }
+
+    /// Total number of shards of the executor client.
     pub fn num_shards(&self) -> usize {
         self.executor_client.num_shards()
     }
 
+    /// Number of shards the next block should be partitioned into. Blocks may be partitioned into
+    /// fewer shards than the executor client has.
+    pub fn num_active_shards(&self) -> usize {
+        self.num_active_shards.get()
+    }
+
+    /// Returns a handle on the number of active shards, which follows the changes made by dynamic
+    /// sharding after each block.
+    pub fn active_shards(&self) -> ActiveShards {
+        self.num_active_shards.clone()
+    }
+
+    /// Sizes the next block from the load observed while executing the block of `num_txns` txns.
+    fn update_num_active_shards(&self, num_txns: usize, stats: &ShardedExecutionStats) {
+        if let Some(config) = &self.dynamic_sharding_config {
+            let txns_per_shard: Vec<usize> = stats
+                .shard_txns_and_gas
+                .iter()
+                .map(|(num_txns, _)| *num_txns)
+                .collect();
+            let num_active_shards = config.next_num_shards(
+                num_txns,
+                &txns_per_shard,
+                self.executor_client.num_shards(),
+            );
+            if num_active_shards != txns_per_shard.len() {
+                info!(
+                    "Changing the number of active shards from {} to {}",
+                    txns_per_shard.len(),
+                    num_active_shards
+                );
+            }
+            self.num_active_shards.set(num_active_shards);
+        }
+    }
+
+    fn finish_block(&self, num_txns: usize, stats: &ShardedExecutionStats) {
+        Self::observe_shard_metrics(stats);
+        self.update_num_active_shards(num_txns, stats);
+    }
+
+    fn prepare_block(&self, transactions: &PartitionedTransactions) {
+        let num_executor_shards = transactions.num_shards();
+        NUM_EXECUTOR_SHARDS.set(num_executor_shards as i64);
+        assert!(
+            num_executor_shards > 0 && num_executor_shards <= self.executor_client.num_shards(),
+            "Block must be partitioned into at most {} sub-blocks, got {}",
+            self.executor_client.num_shards(),
+            num_executor_shards
+        );
+    }
+
     /// Execute a block of transactions in parallel by splitting the block into num_remote_executors partitions and
-    /// dispatching each partition to a remote executor shard.
+    /// dispatching each partition to a remote executor shard. The block is only copied again if it
+    /// has to be re-executed after a shard failure.
     pub fn execute_block(
         &self,
         state_view: Arc<S>,
-        transactions: PartitionedTransactions,
+        transactions: &PartitionedTransactions,
         concurrency_level_per_shard: usize,
         onchain_config: BlockExecutorConfigFromOnchain,
     ) -> Result<Vec<TransactionOutput>, VMStatus> {
         let _timer = SHARDED_BLOCK_EXECUTION_SECONDS.start_timer();
-        let num_executor_shards = self.executor_client.num_shards();
-        NUM_EXECUTOR_SHARDS.set(num_executor_shards as i64);
-        assert_eq!(
-            num_executor_shards,
-            transactions.num_shards(),
-            "Block must be partitioned into {} sub-blocks",
-            num_executor_shards
-        );
-        let (sharded_output, global_output) = self
-            .executor_client
-            .execute_block(
-                state_view,
-                transactions,
-                concurrency_level_per_shard,
-                onchain_config,
-            )?
-            .into_inner();
+        self.prepare_block(transactions);
+        let execution_output = match self.executor_client.execute_block(
+            state_view.clone(),
+            transactions.clone(),
+            concurrency_level_per_shard,
+            onchain_config.clone(),
+        ) {
+            Ok(execution_output) => execution_output,
+            Err(err) => {
+                return match self.shard_failure_policy {
+                    ShardFailurePolicy::SequentialFallback => {
+                        Self::execute_block_after_shard_failure(
+                            err,
+                            state_view.as_ref(),
+                            transactions,
+                            concurrency_level_per_shard,
+                            onchain_config,
+                        )
+                    },
+                    ShardFailurePolicy::Abort => Err(err),
+                };
+            },
+        };
+        self.finish_block(transactions.num_txns(), &execution_output.stats());
         // wait for all remote executors to send the result back and append them in order by shard id
         info!("ShardedBlockExecutor Received all results");
         let _aggregation_timer = SHARDED_EXECUTION_RESULT_AGGREGATION_SECONDS.start_timer();
-        let num_rounds = sharded_output[0].len();
-        let mut aggregated_results = vec![];
-        let mut ordered_results = vec![vec![]; num_executor_shards * num_rounds];
-        // Append the output from individual shards in the round order
-        for (shard_id, results_from_shard) in sharded_output.into_iter().enumerate() {
-            for (round, result) in results_from_shard.into_iter().enumerate() {
-                ordered_results[round * num_executor_shards + shard_id] = result;
-            }
+        let mut txn_outputs = execution_output.into_ordered_outputs();
+        skip_outputs_after_first_retry(&mut txn_outputs, false);
+        Ok(txn_outputs)
+    }
+
+    /// Same as [Self::execute_block], but sends the outputs to `output_tx` in chunks, in the same
+    /// order as [Self::execute_block] returns them. With the local executor client, the outputs of
+    /// a round are sent as soon as the outputs of all txns before them are available, so that they
+    /// can be processed (e.g. hashed) while the rest of the block is being executed.
+    ///
+    /// If the sharded execution fails and the block is re-executed on the coordinator, only the
+    /// outputs that were not sent yet are sent from the re-execution, which produces the same
+    /// outputs in the same order.
+    pub fn execute_block_streaming(
+        &self,
+        state_view: Arc<S>,
+        transactions: &PartitionedTransactions,
+        concurrency_level_per_shard: usize,
+        onchain_config: BlockExecutorConfigFromOnchain,
+        output_tx: Sender<Vec<TransactionOutput>>,
+    ) -> Result<(), VMStatus> {
+        let _timer = SHARDED_BLOCK_EXECUTION_SECONDS.start_timer();
+        self.prepare_block(transactions);
+        if self.shard_failure_policy == ShardFailurePolicy::Abort {
+            let stats = self.executor_client.execute_block_streaming(
+                state_view,
+                transactions.clone(),
+                concurrency_level_per_shard,
+                onchain_config,
+                output_tx,
+            )?;
+            self.finish_block(transactions.num_txns(), &stats);
+            return Ok(());
         }
 
-        for result in ordered_results.into_iter() {
-            aggregated_results.extend(result);
+        // The outputs are forwarded by a thread counting them, to know which of them still have to
+        // be sent if the block is re-executed.
+        let (sharded_output_tx, sharded_output_rx) = unbounded::<Vec<TransactionOutput>>();
+        let (result, num_sent_txns) = thread::scope(|scope| {
+            let forwarder = scope.spawn(|| {
+                let mut num_sent_txns = 0;
+                for txn_outputs in sharded_output_rx {
+                    num_sent_txns += txn_outputs.len();
+                    // The receiver may have stopped listening, e.g. because it gave up on the block.
+                    output_tx.send(txn_outputs).ok();
+                }
+                num_sent_txns
+            });
+            let result = self.executor_client.execute_block_streaming(
+                state_view.clone(),
+                transactions.clone(),
+                concurrency_level_per_shard,
+                onchain_config.clone(),
+                sharded_output_tx,
+            );
+            (result, forwarder.join().unwrap())
+        });
+        match result {
+            Ok(stats) => {
+                self.finish_block(transactions.num_txns(), &stats);
+                Ok(())
+            },
+            Err(err) => {
+                let mut txn_outputs = Self::execute_block_after_shard_failure(
+                    err,
+                    state_view.as_ref(),
+                    transactions,
+                    concurrency_level_per_shard,
+                    onchain_config,
+                )?;
+                let remaining_outputs = txn_outputs.split_off(num_sent_txns.min(txn_outputs.len()));
+                if !remaining_outputs.is_empty() {
+                    output_tx.send(remaining_outputs).ok();
+                }
+                Ok(())
+            },
         }
+    }
 
-        // Lastly append the global output
-        aggregated_results.extend(global_output);
+    fn execute_block_after_shard_failure(
+        err: VMStatus,
+        state_view: &S,
+        transactions: &PartitionedTransactions,
+        concurrency_level_per_shard: usize,
+        onchain_config: BlockExecutorConfigFromOnchain,
+    ) -> Result<Vec<TransactionOutput>, VMStatus> {
+        warn!(
+            "Sharded execution failed ({:?}), falling back to executing the block on the coordinator",
+            err
+        );
+        SHARDED_BLOCK_EXECUTION_FALLBACK_COUNT.inc();
+        let concurrency_level =
+            (concurrency_level_per_shard * transactions.num_shards()).min(num_cpus::get());
+        Self::execute_block_on_coordinator(
+            state_view,
+            transactions.clone(),
+            concurrency_level,
+            onchain_config,
+        )
+    }
+
+    fn execute_block_on_coordinator(
+        state_view: &S,
+        transactions: PartitionedTransactions,
+        concurrency_level: usize,
+        onchain_config: BlockExecutorConfigFromOnchain,
+    ) -> Result<Vec<TransactionOutput>, VMStatus> {
+        let txns: Vec<SignatureVerifiedTransaction> =
+            PartitionedTransactions::flatten(transactions)
+                .into_iter()
+                .map(|txn| txn.into_txn())
+                .collect();
+        AptosVMBlockExecutorWrapper::execute_block::<
+            _,
+            NoOpTransactionCommitHook<AptosTransactionOutput, VMStatus>,
+            _,
+        >(
+            &DefaultTxnProvider::new(txns),
+            state_view,
+            &AptosModuleCacheManager::new(),
+            BlockExecutorConfig {
+                local: BlockExecutorLocalConfig::default_with_concurrency_level(concurrency_level),
+                onchain: onchain_config,
+            },
+            TransactionSliceMetadata::unknown(),
+            None,
+        )
+        .map(BlockOutput::into_transaction_outputs_forced)
+    }
 
-        Ok(aggregated_results)
+    fn observe_shard_metrics(stats: &ShardedExecutionStats) {
+        for (shard_id, (num_txns, gas_used)) in stats.shard_txns_and_gas.iter().enumerate() {
+            observe_shard_output_metrics(shard_id, *num_txns, *gas_used);
+        }
+        observe_shard_execution_times(&stats.shard_execution_times);
     }
 
     pub fn shutdown(&mut self) {
         self.executor_client.shutdown();
     }
 }
+
+fn observe_shard_output_metrics(shard_id: ShardId, num_txns: usize, gas_used: u64) {
+    let shard_label = shard_id.to_string();
+    SHARD_EXECUTED_TXNS
+        .with_label_values(&[&shard_label])
+        .observe(num_txns as f64);
+    SHARD_GAS_USED
+        .with_label_values(&[&shard_label])
+        .observe(gas_used as f64);
+}
+
+fn observe_shard_execution_times(shard_execution_times: &[Duration]) {
+    for (shard_id, execution_time) in shard_execution_times.iter().enumerate() {
+        SHARD_EXECUTION_SECONDS
+            .with_label_values(&[&shard_id.to_string()])
+            .observe(execution_time.as_secs_f64());
+    }
+    if let Some(imbalance) = execution_imbalance(shard_execution_times) {
+        SHARD_EXECUTION_IMBALANCE.set(imbalance);
+    }
+}
+
+/// Ratio between the maximum and the mean execution time of the shards, i.e. 1.0 if the load is
+/// perfectly balanced.
+fn execution_imbalance(shard_execution_times: &[Duration]) -> Option<f64> {
+    let max_time = shard_execution_times.iter().max()?.as_secs_f64();
+    let mean_time = shard_execution_times
+        .iter()
+        .map(Duration::as_secs_f64)
+        .sum::<f64>()
+        / shard_execution_times.len() as f64;
+    (mean_time > 0.0).then(|| max_time / mean_time)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dynamic_sharding_next_num_shards() {
+        let config = DynamicShardingConfig {
+            min_num_shards: 2,
+            target_txns_per_shard: 100,
+            max_imbalance_factor: 2.0,
+        };
+
+        // Load above the target activates more shards, up to the total number of shards.
+        assert_eq!(config.next_num_shards(400, &[200, 200], 8), 4);
+        assert_eq!(config.next_num_shards(1100, &[500, 500], 8), 8);
+        // Load below the target deactivates shards, down to the minimum.
+        assert_eq!(config.next_num_shards(200, &[50, 50, 50, 50], 8), 2);
+        assert_eq!(config.next_num_shards(40, &[10, 10, 10, 10], 8), 2);
+        assert_eq!(config.next_num_shards(0, &[0, 0, 0], 8), 2);
+        // Txns of the block that were not executed by the shards, e.g. global txns, count as load.
+        assert_eq!(config.next_num_shards(600, &[100, 100], 8), 6);
+        // Imbalanced load deactivates a shard.
+        assert_eq!(config.next_num_shards(520, &[400, 40, 40, 40], 8), 3);
+    }
+
+    #[test]
+    fn test_execution_imbalance() {
+        let times = |secs: &[u64]| -> Vec<Duration> {
+            secs.iter().copied().map(Duration::from_secs).collect()
+        };
+        assert_eq!(execution_imbalance(&times(&[1, 1, 1])), Some(1.0));
+        assert_eq!(execution_imbalance(&times(&[3, 1, 2])), Some(1.5));
+        assert_eq!(execution_imbalance(&times(&[0, 0])), None);
+        assert_eq!(execution_imbalance(&[]), None);
+    }
+}
@@ -26,14 +26,32 @@ impl RemoteStateValue {
         cvar.notify_all();
     }
 
+    /// Marks a value that is still being waited for as never becoming available, e.g. because the
+    /// remote shard that should have sent it failed, and wakes up the blocked readers.
+    pub fn abort(&self) {
+        let (lock, cvar) = &*self.value_condition;
+        let mut status = lock.lock().unwrap();
+        if let RemoteValueStatus::Waiting = *status {
+            *status = RemoteValueStatus::Aborted;
+            cvar.notify_all();
+        }
+    }
+
     pub fn get_value(&self) -> Option<StateValue> {
+        self.wait_for_value()
+            .expect("Remote state value was aborted")
+    }
+
+    /// Blocks until the value is available, returns None if it was aborted instead.
+    pub fn wait_for_value(&self) -> Option<Option<StateValue>> {
         let (lock, cvar) = &*self.value_condition;
         let mut status = lock.lock().unwrap();
         while let RemoteValueStatus::Waiting = *status {
             status = cvar.wait(status).unwrap();
         }
         match &*status {
-            RemoteValueStatus::Ready(value) => value.clone(),
+            RemoteValueStatus::Ready(value) => Some(value.clone()),
+            RemoteValueStatus::Aborted => None,
             RemoteValueStatus::Waiting => unreachable!(),
         }
     }
@@ -51,4 +69,6 @@ pub enum RemoteValueStatus {
     Ready(Option<StateValue>),
     /// We are still waiting for remote shard to push the state value
     Waiting,
+    /// The remote shard will never push the state value
+    Aborted,
 }
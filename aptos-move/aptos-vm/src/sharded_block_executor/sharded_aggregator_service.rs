@@ -4,10 +4,12 @@
 use crate::sharded_block_executor::aggr_overridden_state_view::TOTAL_SUPPLY_AGGR_BASE_VAL;
 use aptos_experimental_runtimes::thread_manager::optimal_min_len;
 use aptos_types::{
+    block_executor::partitioner::{RoundId, ShardId},
     state_store::{state_key::StateKey, StateView},
-    transaction::TransactionOutput,
-    write_set::TOTAL_SUPPLY_STATE_KEY,
+    transaction::{TransactionAuxiliaryData, TransactionOutput, TransactionStatus},
+    write_set::{WriteSet, TOTAL_SUPPLY_STATE_KEY},
 };
+use crossbeam_channel::Sender;
 use rayon::prelude::*;
 use serde::de::DeserializeOwned;
 use std::{ops, sync::Arc};
@@ -255,3 +257,301 @@ pub fn aggregate_and_update_total_supply<S: StateView>(
             });
     });
 }
+
+fn last_total_supply_delta(txn_outputs: &[TransactionOutput]) -> DeltaU128 {
+    // Txns like 'block meta' and 'chkpt info' may not have total supply, see
+    // `aggregate_and_update_total_supply`.
+    txn_outputs
+        .iter()
+        .rev()
+        .find_map(|txn| txn.write_set().get_total_supply())
+        .map_or_else(DeltaU128::default, |last_txn_total_supply| {
+            DeltaU128::get_delta(last_txn_total_supply, TOTAL_SUPPLY_AGGR_BASE_VAL)
+        })
+}
+
+fn update_total_supply(txn_outputs: &mut [TransactionOutput], delta: DeltaU128) {
+    let num_txn_outputs = txn_outputs.len();
+    txn_outputs
+        .par_iter_mut()
+        .with_min_len(optimal_min_len(num_txn_outputs, 32))
+        .for_each(|txn_output| {
+            if let Some(txn_total_supply) = txn_output.write_set().get_total_supply() {
+                txn_output.update_total_supply(delta.add_delta(txn_total_supply));
+            }
+        });
+}
+
+/// Each shard stops committing txns once its share of the block gas limit is exhausted, so skipped
+/// (`Retry`) txns may be followed by committed txns of other shards in the global order. Since only a prefix of
+/// the block can be committed, all txns after the first skipped one are skipped as well (and
+/// retried in a later block). Committed txns never depend on txns after them in the global
+/// order, so the outputs of the prefix are still valid.
+///
+/// If `skipping` is set, all txns are skipped. Returns whether any txn is skipped, i.e. whether
+/// the txns after `txn_outputs` have to be skipped.
+pub fn skip_outputs_after_first_retry(
+    txn_outputs: &mut [TransactionOutput],
+    skipping: bool,
+) -> bool {
+    let first_skipped_idx = if skipping {
+        Some(0)
+    } else {
+        txn_outputs
+            .iter()
+            .position(|txn_output| txn_output.status().is_retry())
+    };
+    match first_skipped_idx {
+        Some(idx) => {
+            for txn_output in txn_outputs[idx..].iter_mut() {
+                if !txn_output.status().is_retry() {
+                    *txn_output = TransactionOutput::new(
+                        WriteSet::default(),
+                        vec![],
+                        0,
+                        TransactionStatus::Retry,
+                        TransactionAuxiliaryData::default(),
+                    );
+                }
+            }
+            true
+        },
+        None => false,
+    }
+}
+
+/// Streaming version of [aggregate_and_update_total_supply]: the outputs of the sub-blocks are
+/// forwarded in the global execution order (by round, and by shard id within a round) as soon as
+/// the outputs of all the sub-blocks before them are available, which is enough to update their
+/// total supply.
+pub struct StreamingOutputAggregator {
+    num_shards: usize,
+    // Outputs that are not forwarded yet, indexed by `round * num_shards + shard_id`.
+    pending_outputs: Vec<Option<Vec<TransactionOutput>>>,
+    // Index of the next sub-block to forward.
+    next_idx: usize,
+    // Delta to apply to the total supply of the next sub-block.
+    total_supply_delta: DeltaU128,
+    // Whether a skipped txn was forwarded, after which all txns are skipped.
+    skipping: bool,
+    executor_thread_pool: Arc<rayon::ThreadPool>,
+    output_tx: Sender<Vec<TransactionOutput>>,
+}
+
+impl StreamingOutputAggregator {
+    pub fn new<S: StateView>(
+        num_shards: usize,
+        num_rounds: usize,
+        state_view: &S,
+        executor_thread_pool: Arc<rayon::ThreadPool>,
+        output_tx: Sender<Vec<TransactionOutput>>,
+    ) -> Self {
+        let total_supply_base_val: u128 =
+            get_state_value(&TOTAL_SUPPLY_STATE_KEY, state_view).unwrap();
+        Self {
+            num_shards,
+            pending_outputs: (0..num_shards * num_rounds).map(|_| None).collect(),
+            next_idx: 0,
+            total_supply_delta: DeltaU128::get_delta(
+                total_supply_base_val,
+                TOTAL_SUPPLY_AGGR_BASE_VAL,
+            ),
+            skipping: false,
+            executor_thread_pool,
+            output_tx,
+        }
+    }
+
+    /// Adds the outputs of a round of a shard, and forwards the outputs whose prefix is now
+    /// complete.
+    pub fn add_round_output(
+        &mut self,
+        shard_id: ShardId,
+        round: RoundId,
+        txn_outputs: Vec<TransactionOutput>,
+    ) {
+        self.pending_outputs[round * self.num_shards + shard_id] = Some(txn_outputs);
+        while let Some(Some(mut txn_outputs)) = self
+            .pending_outputs
+            .get_mut(self.next_idx)
+            .map(Option::take)
+        {
+            self.skipping = skip_outputs_after_first_retry(&mut txn_outputs, self.skipping);
+            let sub_block_delta = last_total_supply_delta(&txn_outputs);
+            let delta = self.total_supply_delta;
+            self.executor_thread_pool
+                .install(|| update_total_supply(&mut txn_outputs, delta));
+            self.total_supply_delta = self.total_supply_delta + sub_block_delta;
+            self.next_idx += 1;
+            self.forward(txn_outputs);
+        }
+    }
+
+    /// Forwards the outputs of the global txns, which are executed after all the shards.
+    pub fn finish(self, mut global_output: Vec<TransactionOutput>) {
+        assert_eq!(
+            self.next_idx,
+            self.pending_outputs.len(),
+            "Outputs of all shards must be added before the global output"
+        );
+        skip_outputs_after_first_retry(&mut global_output, self.skipping);
+        let delta = self.total_supply_delta;
+        self.executor_thread_pool
+            .install(|| update_total_supply(&mut global_output, delta));
+        self.forward(global_output);
+    }
+
+    fn forward(&self, txn_outputs: Vec<TransactionOutput>) {
+        // The receiver may have stopped listening, e.g. because it gave up on the block.
+        if !txn_outputs.is_empty() {
+            self.output_tx.send(txn_outputs).ok();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aptos_types::{
+        state_store::{state_value::StateValue, MockStateView},
+        transaction::ExecutionStatus,
+    };
+    use crossbeam_channel::unbounded;
+    use std::collections::HashMap;
+
+    fn output_with_total_supply(total_supply: Option<u128>) -> TransactionOutput {
+        let write_set = WriteSet::new_for_test(total_supply.map(|total_supply| {
+            (
+                TOTAL_SUPPLY_STATE_KEY.clone(),
+                Some(StateValue::new_legacy(
+                    bcs::to_bytes(&total_supply).unwrap().into(),
+                )),
+            )
+        }));
+        TransactionOutput::new(
+            write_set,
+            vec![],
+            0,
+            TransactionStatus::Keep(ExecutionStatus::Success),
+            TransactionAuxiliaryData::default(),
+        )
+    }
+
+    fn total_supplies(txn_outputs: &[TransactionOutput]) -> Vec<Option<u128>> {
+        txn_outputs
+            .iter()
+            .map(|txn_output| txn_output.write_set().get_total_supply())
+            .collect()
+    }
+
+    #[test]
+    fn test_skip_outputs_after_first_retry() {
+        let retry_output = || {
+            TransactionOutput::new(
+                WriteSet::default(),
+                vec![],
+                0,
+                TransactionStatus::Retry,
+                TransactionAuxiliaryData::default(),
+            )
+        };
+        let statuses = |txn_outputs: &[TransactionOutput]| -> Vec<bool> {
+            txn_outputs
+                .iter()
+                .map(|txn_output| txn_output.status().is_retry())
+                .collect()
+        };
+
+        let mut txn_outputs = vec![
+            output_with_total_supply(Some(1)),
+            output_with_total_supply(None),
+        ];
+        assert!(!skip_outputs_after_first_retry(&mut txn_outputs, false));
+        assert_eq!(statuses(&txn_outputs), vec![false, false]);
+
+        let mut txn_outputs = vec![
+            output_with_total_supply(Some(1)),
+            retry_output(),
+            output_with_total_supply(Some(2)),
+        ];
+        assert!(skip_outputs_after_first_retry(&mut txn_outputs, false));
+        assert_eq!(statuses(&txn_outputs), vec![false, true, true]);
+        assert_eq!(total_supplies(&txn_outputs), vec![Some(1), None, None]);
+
+        let mut txn_outputs = vec![output_with_total_supply(Some(1))];
+        assert!(skip_outputs_after_first_retry(&mut txn_outputs, true));
+        assert_eq!(statuses(&txn_outputs), vec![true]);
+    }
+
+    #[test]
+    fn test_streaming_output_aggregator() {
+        let base = TOTAL_SUPPLY_AGGR_BASE_VAL;
+        // 2 shards, 2 rounds. Each shard starts from the base value in each round.
+        let sharded_output = || {
+            vec![
+                vec![
+                    vec![
+                        output_with_total_supply(Some(base + 10)),
+                        output_with_total_supply(None),
+                    ],
+                    vec![output_with_total_supply(Some(base - 5))],
+                ],
+                vec![
+                    vec![
+                        output_with_total_supply(Some(base + 1)),
+                        output_with_total_supply(Some(base + 3)),
+                    ],
+                    vec![],
+                ],
+            ]
+        };
+        let global_output = || vec![output_with_total_supply(Some(base + 100))];
+        let state_view = MockStateView::new(HashMap::from([(
+            TOTAL_SUPPLY_STATE_KEY.clone(),
+            StateValue::new_legacy(bcs::to_bytes(&1000u128).unwrap().into()),
+        )]));
+        let thread_pool = Arc::new(rayon::ThreadPoolBuilder::new().build().unwrap());
+
+        let mut expected_sharded_output = sharded_output();
+        let mut expected_global_output = global_output();
+        aggregate_and_update_total_supply(
+            &mut expected_sharded_output,
+            &mut expected_global_output,
+            &state_view,
+            thread_pool.clone(),
+        );
+        let mut expected_chunks = vec![];
+        for round in 0..2 {
+            for shard_output in expected_sharded_output.iter() {
+                if !shard_output[round].is_empty() {
+                    expected_chunks.push(total_supplies(&shard_output[round]));
+                }
+            }
+        }
+        expected_chunks.push(total_supplies(&expected_global_output));
+
+        let (output_tx, output_rx) = unbounded();
+        let mut aggregator =
+            StreamingOutputAggregator::new(2, 2, &state_view, thread_pool, output_tx);
+        let mut sharded_output = sharded_output();
+        let mut shard_1_output = sharded_output.pop().unwrap().into_iter();
+        let mut shard_0_output = sharded_output.pop().unwrap().into_iter();
+        // Nothing can be forwarded before the output of shard 0 is available.
+        aggregator.add_round_output(1, 0, shard_1_output.next().unwrap());
+        assert!(output_rx.try_recv().is_err());
+        // The first round is forwarded before the shards execute the second one.
+        aggregator.add_round_output(0, 0, shard_0_output.next().unwrap());
+        assert_eq!(output_rx.len(), 2);
+        aggregator.add_round_output(1, 1, shard_1_output.next().unwrap());
+        aggregator.add_round_output(0, 1, shard_0_output.next().unwrap());
+        aggregator.finish(global_output());
+
+        let chunks: Vec<_> = output_rx
+            .iter()
+            .map(|txn_outputs| total_supplies(&txn_outputs))
+            .collect();
+        assert_eq!(chunks, expected_chunks);
+        assert_eq!(chunks[0], vec![Some(1010), None]);
+        assert_eq!(chunks[3], vec![Some(1108)]);
+    }
+}
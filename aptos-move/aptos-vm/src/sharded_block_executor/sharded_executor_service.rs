@@ -7,8 +7,8 @@ use crate::{
         aggr_overridden_state_view::{AggregatorOverriddenStateView, TOTAL_SUPPLY_AGGR_BASE_VAL},
         coordinator_client::CoordinatorClient,
         counters::{
-            SHARDED_BLOCK_EXECUTION_BY_ROUNDS_SECONDS, SHARDED_BLOCK_EXECUTOR_TXN_COUNT,
-            SHARDED_EXECUTOR_SERVICE_SECONDS,
+            CROSS_SHARD_WAIT_SECONDS, SHARDED_BLOCK_EXECUTION_BY_ROUNDS_SECONDS,
+            SHARDED_BLOCK_EXECUTOR_TXN_COUNT, SHARDED_EXECUTOR_SERVICE_SECONDS,
         },
         cross_shard_client::{CrossShardClient, CrossShardCommitReceiver, CrossShardCommitSender},
         cross_shard_state_view::CrossShardStateView,
@@ -22,6 +22,7 @@ use aptos_block_executor::{
 use aptos_logger::{info, trace};
 use aptos_types::{
     block_executor::{
+        block_gas_budget::BlockGasBudget,
         config::{BlockExecutorConfig, BlockExecutorLocalConfig},
         partitioner::{ShardId, SubBlock, SubBlocksForShard, TransactionWithDependencies},
         transaction_slice_metadata::TransactionSliceMetadata,
@@ -35,7 +36,7 @@ use aptos_types::{
 };
 use aptos_vm_logging::disable_speculative_logging;
 use futures::{channel::oneshot, executor::block_on};
-use move_core_types::vm_status::VMStatus;
+use move_core_types::vm_status::{StatusCode, VMStatus};
 use std::sync::Arc;
 
 pub struct ShardedExecutorService<S: StateView + Sync + Send + 'static> {
@@ -117,7 +118,9 @@ impl<S: StateView + Sync + Send + 'static> ShardedExecutorService<S> {
         ));
 
         let cross_shard_state_view_clone = cross_shard_state_view.clone();
+        let cross_shard_state_view_ref = cross_shard_state_view.as_ref();
         let cross_shard_client_clone = cross_shard_client.clone();
+        let remaining_updates_sender = cross_shard_commit_sender.clone();
 
         let aggr_overridden_state_view = Arc::new(AggregatorOverriddenStateView::new(
             cross_shard_state_view.as_ref(),
@@ -151,7 +154,23 @@ impl<S: StateView + Sync + Send + 'static> ShardedExecutorService<S> {
                     TransactionSliceMetadata::unknown(),
                     cross_shard_commit_sender,
                 )
-                .map(BlockOutput::into_transaction_outputs_forced);
+                .map(BlockOutput::into_transaction_outputs_forced)
+                .and_then(|txn_outputs| {
+                    // Dependent transactions in later rounds wait for all storage locations in
+                    // the dependent edges, including those not written by the source transaction.
+                    if let Some(sender) = remaining_updates_sender {
+                        sender
+                            .send_remaining_updates(&txn_outputs, cross_shard_state_view_ref)
+                            .map_err(|err| {
+                                VMStatus::error(StatusCode::STORAGE_ERROR, Some(err.to_string()))
+                            })?;
+                    }
+                    Ok(txn_outputs)
+                });
+                let shard_label = shard_id.map_or("global".to_string(), |id| id.to_string());
+                CROSS_SHARD_WAIT_SECONDS
+                    .with_label_values(&[&shard_label, &round.to_string()])
+                    .observe(cross_shard_state_view_ref.wait_time().as_secs_f64());
                 if let Some(shard_id) = shard_id {
                     trace!(
                         "executed sub block for shard {} and round {}",
@@ -185,8 +204,7 @@ impl<S: StateView + Sync + Send + 'static> ShardedExecutorService<S> {
         transactions: SubBlocksForShard<AnalyzedTransaction>,
         state_view: &S,
         config: BlockExecutorConfig,
-    ) -> Result<Vec<Vec<TransactionOutput>>, VMStatus> {
-        let mut result = vec![];
+    ) -> Result<(), VMStatus> {
         for (round, sub_block) in transactions.into_sub_blocks().into_iter().enumerate() {
             let _timer = SHARDED_BLOCK_EXECUTION_BY_ROUNDS_SECONDS
                 .with_label_values(&[&self.shard_id.to_string(), &round.to_string()])
@@ -200,14 +218,17 @@ impl<S: StateView + Sync + Send + 'static> ShardedExecutorService<S> {
                 round,
                 sub_block.transactions.len()
             );
-            result.push(self.execute_sub_block(sub_block, round, state_view, config.clone())?);
+            let round_output =
+                self.execute_sub_block(sub_block, round, state_view, config.clone())?;
+            self.coordinator_client
+                .send_round_output(round, round_output);
             trace!(
                 "Finished executing sub block for shard {} and round {}",
                 self.shard_id,
                 round
             );
         }
-        Ok(result)
+        Ok(())
     }
 
     pub fn start(&self) {
@@ -225,6 +246,7 @@ impl<S: StateView + Sync + Send + 'static> ShardedExecutorService<S> {
                     transactions,
                     concurrency_level_per_shard,
                     onchain_config,
+                    block_gas_budget,
                 ) => {
                     num_txns += transactions.num_txns();
                     trace!(
@@ -239,9 +261,14 @@ impl<S: StateView + Sync + Send + 'static> ShardedExecutorService<S> {
                         transactions,
                         state_view.as_ref(),
                         BlockExecutorConfig {
-                            local: BlockExecutorLocalConfig::default_with_concurrency_level(
-                                concurrency_level_per_shard,
-                            ),
+                            local: BlockExecutorLocalConfig {
+                                // Carried across the rounds of the shard.
+                                block_gas_budget: block_gas_budget
+                                    .map(|budget| Arc::new(BlockGasBudget::new(budget))),
+                                ..BlockExecutorLocalConfig::default_with_concurrency_level(
+                                    concurrency_level_per_shard,
+                                )
+                            },
                             onchain: onchain_config,
                         },
                     );
@@ -2,6 +2,7 @@
 // Parts of the project are originally copyright © Meta Platforms, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::write_scope::WriteScope;
 use aptos_crypto::HashValue;
 use aptos_gas_algebra::{FeePerGasUnit, Gas, NumBytes};
 use aptos_types::{
@@ -33,6 +34,8 @@ pub struct TransactionMetadata {
     pub is_keyless: bool,
     pub entry_function_payload: Option<EntryFunction>,
     pub multisig_payload: Option<Multisig>,
+    /// Addresses the user session is allowed to write to, if declared.
+    pub write_scope: Option<WriteScope>,
 }
 
 impl TransactionMetadata {
@@ -91,9 +94,15 @@ impl TransactionMetadata {
                 TransactionPayload::Multisig(m) => Some(m.clone()),
                 _ => None,
             },
+            write_scope: None,
         }
     }
 
+    pub fn with_write_scope(mut self, write_scope: WriteScope) -> Self {
+        self.write_scope = Some(write_scope);
+        self
+    }
+
     pub fn max_gas_amount(&self) -> Gas {
         self.max_gas_amount
     }
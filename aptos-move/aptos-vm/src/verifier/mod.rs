@@ -3,6 +3,7 @@
 pub(crate) mod event_validation;
 pub(crate) mod module_init;
 pub(crate) mod native_validation;
+pub mod package_upgrade;
 pub(crate) mod randomness;
 pub(crate) mod resource_groups;
 pub mod transaction_arg_validation;
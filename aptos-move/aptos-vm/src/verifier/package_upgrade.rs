@@ -0,0 +1,94 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_types::{
+    on_chain_config::{FeatureFlag, Features, TimedFeatureFlag, TimedFeatures},
+    state_store::{state_key::StateKey, StateView},
+};
+use aptos_vm_environment::environment::AptosEnvironment;
+use move_binary_format::{
+    compatibility::{Compatibility, CompatibilityIssue},
+    CompiledModule,
+};
+use move_core_types::language_storage::ModuleId;
+
+/// Returns the compatibility checks performed when upgrading a module, according to the
+/// on-chain configuration.
+pub(crate) fn upgrade_compatibility_checks(
+    features: &Features,
+    timed_features: &TimedFeatures,
+) -> Compatibility {
+    let check_struct_layout = true;
+    let check_friend_linking = !features.is_enabled(FeatureFlag::TREAT_FRIEND_AS_PRIVATE);
+    Compatibility::new(
+        check_struct_layout,
+        check_friend_linking,
+        timed_features.is_enabled(TimedFeatureFlag::EntryCompatibility),
+    )
+}
+
+/// Result of checking a single module of a package against its on-chain version.
+#[derive(Clone, Debug)]
+pub struct ModuleUpgradeReport {
+    pub module_id: ModuleId,
+    /// True if there is no on-chain version of the module, i.e. it is published for the first
+    /// time and does not need to be compatible with anything.
+    pub is_new: bool,
+    /// The incompatibilities with the on-chain version of the module.
+    pub issues: Vec<CompatibilityIssue>,
+}
+
+/// Result of checking the modules of a package against their on-chain versions.
+#[derive(Clone, Debug, Default)]
+pub struct PackageUpgradeReport {
+    pub modules: Vec<ModuleUpgradeReport>,
+}
+
+impl PackageUpgradeReport {
+    /// Returns true if publishing the modules would pass the compatibility checks.
+    pub fn is_compatible(&self) -> bool {
+        self.modules.iter().all(|module| module.issues.is_empty())
+    }
+}
+
+/// Checks the given serialized modules against their versions on chain, with the same
+/// compatibility checks as the ones enforced when publishing, and reports every incompatibility
+/// found instead of failing at the first incompatible module.
+///
+/// Only the compatibility of the modules is checked: the package metadata (e.g. the upgrade
+/// policy) and the bytecode verification are validated when the package is actually published.
+pub fn check_package_upgrade(
+    state_view: &impl StateView,
+    modules: &[Vec<u8>],
+) -> anyhow::Result<PackageUpgradeReport> {
+    let env = AptosEnvironment::new(state_view);
+    let deserializer_config = &env.vm_config().deserializer_config;
+    let compatibility = upgrade_compatibility_checks(env.features(), env.timed_features());
+
+    let modules = modules
+        .iter()
+        .map(|bytes| {
+            let new_module = CompiledModule::deserialize_with_config(bytes, deserializer_config)?;
+            let module_id = new_module.self_id();
+            let report = match state_view.get_state_value_bytes(&StateKey::module_id(&module_id))? {
+                Some(old_bytes) => {
+                    let old_module =
+                        CompiledModule::deserialize_with_config(&old_bytes, deserializer_config)?;
+                    ModuleUpgradeReport {
+                        module_id,
+                        is_new: false,
+                        issues: compatibility.check_issues(&old_module, &new_module),
+                    }
+                },
+                None => ModuleUpgradeReport {
+                    module_id,
+                    is_new: true,
+                    issues: vec![],
+                },
+            };
+            Ok(report)
+        })
+        .collect::<anyhow::Result<_>>()?;
+
+    Ok(PackageUpgradeReport { modules })
+}
@@ -0,0 +1,95 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Bounds the state a user transaction is allowed to write to.
+//!
+//! A transaction can be executed with a declared [`WriteScope`], in which case the writes of its
+//! user session (i.e., excluding the prologue and the epilogue, which charge gas and bump the
+//! sequence number) must only touch the declared addresses. This allows fee payer services to
+//! check what the transactions they sponsor would modify before signing them, by simulating them
+//! with a scope, see [`crate::AptosSimulationVM::create_vm_and_simulate_signed_transaction`].
+//!
+//! The scope is not part of the signed transaction, so it is only enforced when simulating a
+//! transaction with it explicitly, and never during block execution.
+
+use aptos_types::{
+    access_path::AccessPath,
+    account_address::AccountAddress,
+    state_store::state_key::{inner::StateKeyInner, StateKey},
+};
+use aptos_vm_types::change_set::ChangeSetInterface;
+use move_binary_format::errors::{Location, PartialVMError};
+use move_core_types::vm_status::{StatusCode, VMStatus};
+use std::collections::BTreeSet;
+
+/// The set of addresses a transaction declares it may write to.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct WriteScope {
+    addresses: BTreeSet<AccountAddress>,
+}
+
+impl WriteScope {
+    pub fn new(addresses: impl IntoIterator<Item = AccountAddress>) -> Self {
+        Self {
+            addresses: addresses.into_iter().collect(),
+        }
+    }
+
+    pub fn addresses(&self) -> &BTreeSet<AccountAddress> {
+        &self.addresses
+    }
+
+    /// Returns true if the key is stored under one of the declared addresses. Resources,
+    /// resource groups and modules belong to the address they are stored at, and table items
+    /// belong to the address of their table handle.
+    pub fn allows(&self, key: &StateKey) -> bool {
+        match key.inner() {
+            StateKeyInner::AccessPath(AccessPath { address, .. }) => {
+                self.addresses.contains(address)
+            },
+            StateKeyInner::TableItem { handle, .. } => self.addresses.contains(&handle.0),
+            StateKeyInner::Raw(_) => false,
+        }
+    }
+
+    /// Checks that every write in the change set is within the scope, returning an
+    /// [`StatusCode::ACCESS_DENIED`] error for the first one which is not.
+    pub fn check_change_set(&self, change_set: &impl ChangeSetInterface) -> Result<(), VMStatus> {
+        match change_set
+            .write_set_size_iter()
+            .find(|(key, _)| !self.allows(key))
+        {
+            Some((key, _)) => Err(PartialVMError::new(StatusCode::ACCESS_DENIED)
+                .with_message(format!(
+                    "Write to {:?} is outside of the declared write scope",
+                    key
+                ))
+                .finish(Location::Undefined)
+                .into_vm_status()),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aptos_types::state_store::table::TableHandle;
+    use move_core_types::{identifier::Identifier, language_storage::ModuleId};
+
+    #[test]
+    fn test_write_scope_allows() {
+        let inside = AccountAddress::from_hex_literal("0xcafe").unwrap();
+        let outside = AccountAddress::from_hex_literal("0xbeef").unwrap();
+        let scope = WriteScope::new([inside]);
+
+        let module =
+            |address| StateKey::module_id(&ModuleId::new(address, Identifier::new("m").unwrap()));
+        assert!(scope.allows(&module(inside)));
+        assert!(!scope.allows(&module(outside)));
+
+        assert!(scope.allows(&StateKey::table_item(&TableHandle(inside), &[1])));
+        assert!(!scope.allows(&StateKey::table_item(&TableHandle(outside), &[1])));
+        assert!(!scope.allows(&StateKey::raw(&[1])));
+    }
+}
@@ -8,6 +8,7 @@
 /// the same process while testing, resulting in the counters failing to register with "AlreadyReg"
 /// error.
 use aptos_block_partitioner::{
+    connected_component_partitioner::config::ConnectedComponentBlockPartitionerConfig,
     pre_partition::{
         connected_component::config::ConnectedComponentPartitionerConfig,
         uniform_partitioner::config::UniformPartitionerConfig,
@@ -16,9 +17,10 @@ use aptos_block_partitioner::{
     PartitionerConfig,
 };
 use aptos_vm::sharded_block_executor::{
-    local_executor_shard::LocalExecutorService, ShardedBlockExecutor,
+    local_executor_shard::LocalExecutorService, DynamicShardingConfig, ShardFailurePolicy,
+    ShardedBlockExecutor,
 };
-use rand::{rngs::OsRng, Rng};
+use rand::Rng;
 
 #[test]
 fn test_partitioner_v2_uniform_sharded_block_executor_no_conflict() {
@@ -35,8 +37,6 @@ fn test_partitioner_v2_uniform_sharded_block_executor_no_conflict() {
 }
 
 #[test]
-// Sharded execution with cross shard conflict doesn't work for now because we don't have
-// cross round dependency tracking yet.
 fn test_partitioner_v2_uniform_sharded_block_executor_with_conflict_parallel() {
     for merge_discard in [false, true] {
         let num_shards = 7;
@@ -66,6 +66,7 @@ fn test_partitioner_v2_uniform_sharded_block_executor_with_conflict_sequential()
 
 #[test]
 fn test_partitioner_v2_uniform_sharded_block_executor_with_random_transfers_parallel() {
+    let mut rng = test_utils::seeded_rng();
     for merge_discard in [false, true] {
         let num_shards = 3;
         let client = LocalExecutorService::setup_local_executor_shards(num_shards, Some(4));
@@ -78,14 +79,15 @@ fn test_partitioner_v2_uniform_sharded_block_executor_with_random_transfers_para
             partitioner,
             sharded_block_executor,
             4,
+            &mut rng,
         )
     }
 }
 
 #[test]
 fn test_partitioner_v2_uniform_sharded_block_executor_with_random_transfers_sequential() {
+    let mut rng = test_utils::seeded_rng();
     for merge_discard in [false, true] {
-        let mut rng = OsRng;
         let max_num_shards = 32;
         let num_shards = rng.gen_range(1, max_num_shards);
         let client = LocalExecutorService::setup_local_executor_shards(num_shards, Some(1));
@@ -98,6 +100,7 @@ fn test_partitioner_v2_uniform_sharded_block_executor_with_random_transfers_sequ
             partitioner,
             sharded_block_executor,
             1,
+            &mut rng,
         )
     }
 }
@@ -117,8 +120,6 @@ fn test_partitioner_v2_connected_component_sharded_block_executor_no_conflict()
 }
 
 #[test]
-// Sharded execution with cross shard conflict doesn't work for now because we don't have
-// cross round dependency tracking yet.
 fn test_partitioner_v2_connected_component_sharded_block_executor_with_conflict_parallel() {
     for merge_discard in [false, true] {
         let num_shards = 7;
@@ -148,6 +149,7 @@ fn test_partitioner_v2_connected_component_sharded_block_executor_with_conflict_
 
 #[test]
 fn test_partitioner_v2_connected_component_sharded_block_executor_with_random_transfers_parallel() {
+    let mut rng = test_utils::seeded_rng();
     for merge_discard in [false, true] {
         let num_shards = 3;
         let client = LocalExecutorService::setup_local_executor_shards(num_shards, Some(4));
@@ -160,6 +162,7 @@ fn test_partitioner_v2_connected_component_sharded_block_executor_with_random_tr
             partitioner,
             sharded_block_executor,
             4,
+            &mut rng,
         )
     }
 }
@@ -167,8 +170,8 @@ fn test_partitioner_v2_connected_component_sharded_block_executor_with_random_tr
 #[test]
 fn test_partitioner_v2_connected_component_sharded_block_executor_with_random_transfers_sequential()
 {
+    let mut rng = test_utils::seeded_rng();
     for merge_discard in [false, true] {
-        let mut rng = OsRng;
         let max_num_shards = 32;
         let num_shards = rng.gen_range(1, max_num_shards);
         let client = LocalExecutorService::setup_local_executor_shards(num_shards, Some(1));
@@ -181,10 +184,81 @@ fn test_partitioner_v2_connected_component_sharded_block_executor_with_random_tr
             partitioner,
             sharded_block_executor,
             1,
+            &mut rng,
         )
     }
 }
 
+#[test]
+fn test_partitioner_v2_sharded_block_executor_with_dynamic_sharding() {
+    let num_shards = 8;
+    let client = LocalExecutorService::setup_local_executor_shards(num_shards, Some(2));
+    let sharded_block_executor = ShardedBlockExecutor::new(client)
+        .with_partitioner(PartitionerV2Config::default().build())
+        .with_dynamic_sharding(DynamicShardingConfig {
+            min_num_shards: 1,
+            target_txns_per_shard: 20,
+            max_imbalance_factor: 4.0,
+        });
+    test_utils::sharded_block_executor_with_dynamic_sharding(sharded_block_executor);
+}
+
+#[test]
+fn test_connected_component_partitioner_sharded_block_executor_with_random_transfers() {
+    let mut rng = test_utils::seeded_rng();
+    let max_num_shards = 32;
+    let num_shards = rng.gen_range(1, max_num_shards);
+    let client = LocalExecutorService::setup_local_executor_shards(num_shards, Some(2));
+    let sharded_block_executor = ShardedBlockExecutor::new(client);
+    let partitioner = ConnectedComponentBlockPartitionerConfig::default().build();
+    test_utils::sharded_block_executor_with_random_transfers(
+        partitioner,
+        sharded_block_executor,
+        2,
+        &mut rng,
+    )
+}
+
+#[test]
+fn test_partitioner_v2_sharded_block_executor_streaming() {
+    let mut rng = test_utils::seeded_rng();
+    let num_shards = 4;
+    let client = LocalExecutorService::setup_local_executor_shards(num_shards, Some(2));
+    let sharded_block_executor = ShardedBlockExecutor::new(client);
+    let partitioner = PartitionerV2Config::default().build();
+    test_utils::sharded_block_executor_streaming(partitioner, sharded_block_executor, &mut rng);
+}
+
+#[test]
+fn test_partitioner_v2_sharded_block_executor_with_global_block_gas_limit() {
+    let num_shards = 4;
+    let client = LocalExecutorService::setup_local_executor_shards(num_shards, Some(2));
+    let sharded_block_executor = ShardedBlockExecutor::new(client);
+    let partitioner = PartitionerV2Config::default().build();
+    test_utils::sharded_block_executor_with_block_gas_limit(partitioner, sharded_block_executor);
+}
+
+#[test]
+fn test_sharded_block_executor_sequential_fallback_on_shard_failure() {
+    let num_shards = 4;
+    let client = test_utils::FailingExecutorClient::new(num_shards);
+    let sharded_block_executor = ShardedBlockExecutor::new(client)
+        .with_shard_failure_policy(ShardFailurePolicy::SequentialFallback);
+    let partitioner = PartitionerV2Config::default().build();
+    test_utils::sharded_block_executor_with_conflict(partitioner, sharded_block_executor, 2);
+}
+
+#[test]
+fn test_sharded_block_executor_streaming_sequential_fallback_on_shard_failure() {
+    let mut rng = test_utils::seeded_rng();
+    let num_shards = 4;
+    let client = test_utils::FailingExecutorClient::new(num_shards);
+    let sharded_block_executor = ShardedBlockExecutor::new(client)
+        .with_shard_failure_policy(ShardFailurePolicy::SequentialFallback);
+    let partitioner = PartitionerV2Config::default().build();
+    test_utils::sharded_block_executor_streaming(partitioner, sharded_block_executor, &mut rng);
+}
+
 mod test_utils {
     use aptos_block_executor::txn_provider::default::DefaultTxnProvider;
     use aptos_block_partitioner::BlockPartitioner;
@@ -204,16 +278,195 @@ mod test_utils {
     };
     use aptos_vm::{
         aptos_vm::AptosVMBlockExecutor,
-        sharded_block_executor::{executor_client::ExecutorClient, ShardedBlockExecutor},
+        sharded_block_executor::{
+            executor_client::{ExecutorClient, ShardedExecutionOutput, ShardedExecutionStats},
+            ShardedBlockExecutor,
+        },
         VMBlockExecutor,
     };
-    use move_core_types::account_address::AccountAddress;
-    use rand::{rngs::OsRng, Rng};
+    use crossbeam_channel::Sender;
+    use move_core_types::{
+        account_address::AccountAddress,
+        vm_status::{StatusCode, VMStatus},
+    };
+    use rand::{rngs::StdRng, Rng, SeedableRng};
     use std::{
         collections::HashMap,
+        env,
         sync::{Arc, Mutex},
     };
 
+    /// If set, tests using [seeded_rng] use this seed instead of a random one.
+    pub const SEED_ENV_VAR: &str = "SHARDED_BLOCK_EXECUTOR_TEST_SEED";
+
+    /// Returns an RNG seeded from `SHARDED_BLOCK_EXECUTOR_TEST_SEED` if set, or from a random seed
+    /// otherwise. The seed is printed, so that it is shown if the test fails and the same workload
+    /// can be re-generated.
+    pub fn seeded_rng() -> StdRng {
+        let seed = match env::var(SEED_ENV_VAR) {
+            Ok(seed) => seed
+                .parse()
+                .unwrap_or_else(|_| panic!("{} must be a u64, got {:?}", SEED_ENV_VAR, seed)),
+            Err(_) => rand::thread_rng().gen(),
+        };
+        println!(
+            "Using seed {}, set {}={} to reproduce",
+            seed, SEED_ENV_VAR, seed
+        );
+        StdRng::seed_from_u64(seed)
+    }
+
+    /// How senders and receivers of the transfers are picked.
+    #[derive(Clone, Copy, Debug)]
+    pub enum ConflictShape {
+        /// Any two distinct accounts.
+        Uniform,
+        /// With probability `hot_ratio`, the receiver is one of the first `num_hot_accounts`
+        /// accounts, which creates long dependency chains on the hot accounts.
+        Hotspot {
+            num_hot_accounts: usize,
+            hot_ratio: f64,
+        },
+        /// Account `i` sends to account `i + 1`, so consecutive txns conflict pairwise.
+        Chain,
+    }
+
+    /// A reproducible workload of p2p transfers, fully determined by the RNG it is generated from.
+    #[derive(Clone, Debug)]
+    pub struct TransferWorkload {
+        pub num_accounts: usize,
+        pub num_txns: usize,
+        pub conflict_shape: ConflictShape,
+        pub max_transfer_amount: u64,
+    }
+
+    impl TransferWorkload {
+        /// Picks the workload parameters, including the conflict shape, at random.
+        pub fn random(rng: &mut StdRng) -> Self {
+            let num_accounts = rng.gen_range(2, 200);
+            let conflict_shape = match rng.gen_range(0, 3) {
+                0 => ConflictShape::Uniform,
+                1 => ConflictShape::Hotspot {
+                    num_hot_accounts: rng.gen_range(1, num_accounts),
+                    hot_ratio: rng.gen_range(0.1, 0.9),
+                },
+                _ => ConflictShape::Chain,
+            };
+            Self {
+                num_accounts,
+                num_txns: rng.gen_range(1, 1000),
+                conflict_shape,
+                max_transfer_amount: 1000,
+            }
+        }
+
+        fn sender_and_receiver(&self, rng: &mut StdRng, txn_idx: usize) -> (usize, usize) {
+            match self.conflict_shape {
+                ConflictShape::Uniform => {
+                    let indices = rand::seq::index::sample(rng, self.num_accounts, 2);
+                    (indices.index(0), indices.index(1))
+                },
+                ConflictShape::Hotspot {
+                    num_hot_accounts,
+                    hot_ratio,
+                } => {
+                    let sender = rng.gen_range(0, self.num_accounts);
+                    let receiver = if rng.gen_bool(hot_ratio) {
+                        rng.gen_range(0, num_hot_accounts)
+                    } else {
+                        rng.gen_range(0, self.num_accounts)
+                    };
+                    if sender == receiver {
+                        (sender, (receiver + 1) % self.num_accounts)
+                    } else {
+                        (sender, receiver)
+                    }
+                },
+                ConflictShape::Chain => {
+                    let sender = txn_idx % self.num_accounts;
+                    (sender, (sender + 1) % self.num_accounts)
+                },
+            }
+        }
+
+        /// Creates the accounts in the executor's data store and returns the transfers between them.
+        pub fn generate(
+            &self,
+            executor: &mut FakeExecutor,
+            rng: &mut StdRng,
+        ) -> Vec<AnalyzedTransaction> {
+            let mut accounts: Vec<_> = (0..self.num_accounts)
+                .map(|_| generate_account_at(executor, AccountAddress::new(rng.gen())))
+                .collect();
+            (0..self.num_txns)
+                .map(|txn_idx| {
+                    let (sender_idx, receiver_idx) = self.sender_and_receiver(rng, txn_idx);
+                    let receiver = accounts[receiver_idx].clone();
+                    let transfer_amount = rng.gen_range(1, self.max_transfer_amount);
+                    generate_p2p_txn(&mut accounts[sender_idx], &receiver, transfer_amount)
+                })
+                .collect()
+        }
+    }
+
+    /// An executor client whose shards always fail. When streaming, the outputs of the first half
+    /// of the block are sent before failing.
+    pub struct FailingExecutorClient {
+        num_shards: usize,
+    }
+
+    impl FailingExecutorClient {
+        pub fn new(num_shards: usize) -> Self {
+            Self { num_shards }
+        }
+
+        fn shard_failure() -> VMStatus {
+            VMStatus::error(
+                StatusCode::UNKNOWN_INVARIANT_VIOLATION_ERROR,
+                Some("Executor shard 0 terminated unexpectedly".to_string()),
+            )
+        }
+    }
+
+    impl ExecutorClient<FakeDataStore> for FailingExecutorClient {
+        fn num_shards(&self) -> usize {
+            self.num_shards
+        }
+
+        fn execute_block(
+            &self,
+            _state_view: Arc<FakeDataStore>,
+            _transactions: PartitionedTransactions,
+            _concurrency_level_per_shard: usize,
+            _onchain_config: BlockExecutorConfigFromOnchain,
+        ) -> Result<ShardedExecutionOutput, VMStatus> {
+            Err(Self::shard_failure())
+        }
+
+        fn execute_block_streaming(
+            &self,
+            state_view: Arc<FakeDataStore>,
+            transactions: PartitionedTransactions,
+            _concurrency_level_per_shard: usize,
+            _onchain_config: BlockExecutorConfigFromOnchain,
+            output_tx: Sender<Vec<TransactionOutput>>,
+        ) -> Result<ShardedExecutionStats, VMStatus> {
+            let txns: Vec<SignatureVerifiedTransaction> =
+                PartitionedTransactions::flatten(transactions)
+                    .into_iter()
+                    .map(|t| t.into_txn())
+                    .collect();
+            let mut txn_outputs = AptosVMBlockExecutor::new()
+                .execute_block_no_limit(&DefaultTxnProvider::new(txns), state_view.as_ref())
+                .unwrap();
+            txn_outputs.truncate(txn_outputs.len() / 2);
+            output_tx.send(txn_outputs).unwrap();
+            Err(Self::shard_failure())
+        }
+
+        fn shutdown(&mut self) {}
+    }
+
     pub fn generate_account_at(
         executor: &mut FakeExecutor,
         address: AccountAddress,
@@ -298,7 +551,7 @@ mod test_utils {
         let sharded_txn_output = sharded_block_executor
             .execute_block(
                 Arc::new(executor.data_store().clone()),
-                partitioned_txns.clone(),
+                &partitioned_txns,
                 2,
                 BlockExecutorConfigFromOnchain::new_no_block_limit(),
             )
@@ -354,7 +607,7 @@ mod test_utils {
         let sharded_txn_output = sharded_block_executor
             .execute_block(
                 Arc::new(executor.data_store().clone()),
-                partitioned_txns,
+                &partitioned_txns,
                 concurrency,
                 BlockExecutorConfigFromOnchain::new_no_block_limit(),
             )
@@ -371,32 +624,13 @@ mod test_utils {
         partitioner: Box<dyn BlockPartitioner>,
         sharded_block_executor: ShardedBlockExecutor<FakeDataStore, E>,
         concurrency: usize,
+        rng: &mut StdRng,
     ) {
-        let mut rng = OsRng;
-        let max_accounts = 200;
-        let max_txns = 1000;
-        let num_accounts = rng.gen_range(2, max_accounts);
-        let mut accounts = Vec::new();
         let mut executor = FakeExecutor::from_head_genesis();
-
-        for _ in 0..num_accounts {
-            let account = generate_account_at(&mut executor, AccountAddress::random());
-            accounts.push(Mutex::new(account));
-        }
-
-        let num_txns = rng.gen_range(1, max_txns);
-        let num_shards = sharded_block_executor.num_shards();
-
-        let mut transactions = Vec::new();
-
-        for _ in 0..num_txns {
-            let indices = rand::seq::index::sample(&mut rng, num_accounts, 2);
-            let sender = &mut accounts[indices.index(0)].lock().unwrap();
-            let receiver = &accounts[indices.index(1)].lock().unwrap();
-            let transfer_amount = rng.gen_range(1, 1000);
-            let txn = generate_p2p_txn(sender, receiver, transfer_amount);
-            transactions.push(txn)
-        }
+        let workload = TransferWorkload::random(rng);
+        println!("Generating {:?}", workload);
+        let transactions = workload.generate(&mut executor, rng);
+        let num_shards = sharded_block_executor.num_active_shards();
 
         let partitioned_txns = partitioner.partition(transactions.clone(), num_shards);
 
@@ -409,7 +643,7 @@ mod test_utils {
         let sharded_txn_output = sharded_block_executor
             .execute_block(
                 Arc::new(executor.data_store().clone()),
-                partitioned_txns,
+                &partitioned_txns,
                 concurrency,
                 BlockExecutorConfigFromOnchain::new_no_block_limit(),
             )
@@ -421,4 +655,154 @@ mod test_utils {
             .unwrap();
         compare_txn_outputs(unsharded_txn_output, sharded_txn_output);
     }
+
+    pub fn sharded_block_executor_streaming<E: ExecutorClient<FakeDataStore>>(
+        partitioner: Box<dyn BlockPartitioner>,
+        sharded_block_executor: ShardedBlockExecutor<FakeDataStore, E>,
+        rng: &mut StdRng,
+    ) {
+        let mut executor = FakeExecutor::from_head_genesis();
+        let workload = TransferWorkload::random(rng);
+        println!("Generating {:?}", workload);
+        let transactions = workload.generate(&mut executor, rng);
+        let partitioned_txns =
+            partitioner.partition(transactions, sharded_block_executor.num_shards());
+
+        let execution_ordered_txns: Vec<SignatureVerifiedTransaction> =
+            PartitionedTransactions::flatten(partitioned_txns.clone())
+                .into_iter()
+                .map(|t| t.into_txn())
+                .collect();
+        let (output_tx, output_rx) = crossbeam_channel::unbounded();
+        sharded_block_executor
+            .execute_block_streaming(
+                Arc::new(executor.data_store().clone()),
+                &partitioned_txns,
+                2,
+                BlockExecutorConfigFromOnchain::new_no_block_limit(),
+                output_tx,
+            )
+            .unwrap();
+        let sharded_txn_output: Vec<TransactionOutput> = output_rx.iter().flatten().collect();
+
+        let txn_provider = DefaultTxnProvider::new(execution_ordered_txns);
+        let unsharded_txn_output = AptosVMBlockExecutor::new()
+            .execute_block_no_limit(&txn_provider, executor.data_store())
+            .unwrap();
+        compare_txn_outputs(unsharded_txn_output, sharded_txn_output);
+    }
+
+    fn effective_block_gas(txn_output: &TransactionOutput) -> u64 {
+        txn_output
+            .try_extract_fee_statement()
+            .unwrap()
+            .map_or(0, |fee_statement| {
+                fee_statement.execution_gas_used() + fee_statement.io_gas_used()
+            })
+    }
+
+    pub fn sharded_block_executor_with_block_gas_limit<E: ExecutorClient<FakeDataStore>>(
+        partitioner: Box<dyn BlockPartitioner>,
+        sharded_block_executor: ShardedBlockExecutor<FakeDataStore, E>,
+    ) {
+        let num_txns = 400;
+        let num_shards = sharded_block_executor.num_shards();
+        let mut executor = FakeExecutor::from_head_genesis();
+        let transactions: Vec<AnalyzedTransaction> = (0..num_txns)
+            .map(|_| generate_non_conflicting_p2p(&mut executor).0)
+            .collect();
+        let partitioned_txns = partitioner.partition(transactions, num_shards);
+        let execution_ordered_txns: Vec<SignatureVerifiedTransaction> =
+            PartitionedTransactions::flatten(partitioned_txns.clone())
+                .into_iter()
+                .map(|t| t.into_txn())
+                .collect();
+        let txn_provider = DefaultTxnProvider::new(execution_ordered_txns);
+        let unsharded_txn_output = AptosVMBlockExecutor::new()
+            .execute_block_no_limit(&txn_provider, executor.data_store())
+            .unwrap();
+
+        // Each shard alone would stay below the limit for most of its sub-block.
+        let block_gas_limit: u64 = unsharded_txn_output[..num_txns / 8]
+            .iter()
+            .map(effective_block_gas)
+            .sum();
+        let max_txn_gas = unsharded_txn_output
+            .iter()
+            .map(effective_block_gas)
+            .max()
+            .unwrap();
+        let execute = |partitioned_txns: PartitionedTransactions| {
+            sharded_block_executor
+                .execute_block(
+                    Arc::new(executor.data_store().clone()),
+                    &partitioned_txns,
+                    2,
+                    BlockExecutorConfigFromOnchain::new_maybe_block_limit(Some(block_gas_limit)),
+                )
+                .unwrap()
+        };
+        let sharded_txn_output = execute(partitioned_txns.clone());
+        assert_eq!(sharded_txn_output.len(), num_txns);
+        // The committed txns do not depend on the timing of the shards.
+        for _ in 0..3 {
+            assert_eq!(sharded_txn_output, execute(partitioned_txns.clone()));
+        }
+
+        // A prefix of the block is committed, and all txns after it are retried.
+        let num_committed = sharded_txn_output
+            .iter()
+            .position(|txn_output| txn_output.status().is_retry())
+            .expect("Block gas limit must be reached");
+        assert!(num_committed > 0);
+        assert!(sharded_txn_output[num_committed..]
+            .iter()
+            .all(|txn_output| txn_output.status().is_retry()));
+        let committed_gas: u64 = sharded_txn_output[..num_committed]
+            .iter()
+            .map(effective_block_gas)
+            .sum();
+        // Each shard (and the global executor) may commit one txn past its share of the limit.
+        assert!(committed_gas <= block_gas_limit + num_shards as u64 * max_txn_gas);
+        compare_txn_outputs(
+            unsharded_txn_output[..num_committed].to_vec(),
+            sharded_txn_output[..num_committed].to_vec(),
+        );
+    }
+
+    pub fn sharded_block_executor_with_dynamic_sharding<E: ExecutorClient<FakeDataStore>>(
+        sharded_block_executor: ShardedBlockExecutor<FakeDataStore, E>,
+    ) {
+        // The number of active shards follows the block size of the previous block.
+        for (num_txns, expected_num_active_shards) in [(40, 2), (160, 8), (20, 1), (100, 5)] {
+            let mut executor = FakeExecutor::from_head_genesis();
+            let transactions: Vec<AnalyzedTransaction> = (0..num_txns)
+                .map(|_| generate_non_conflicting_p2p(&mut executor).0)
+                .collect();
+            let partitioned_txns = sharded_block_executor.partition(transactions);
+            let execution_ordered_txns: Vec<SignatureVerifiedTransaction> =
+                PartitionedTransactions::flatten(partitioned_txns.clone())
+                    .into_iter()
+                    .map(|t| t.into_txn())
+                    .collect();
+            let sharded_txn_output = sharded_block_executor
+                .execute_block(
+                    Arc::new(executor.data_store().clone()),
+                    &partitioned_txns,
+                    2,
+                    BlockExecutorConfigFromOnchain::new_no_block_limit(),
+                )
+                .unwrap();
+            assert_eq!(
+                sharded_block_executor.num_active_shards(),
+                expected_num_active_shards
+            );
+
+            let txn_provider = DefaultTxnProvider::new(execution_ordered_txns);
+            let unsharded_txn_output = AptosVMBlockExecutor::new()
+                .execute_block_no_limit(&txn_provider, executor.data_store())
+                .unwrap();
+            compare_txn_outputs(unsharded_txn_output, sharded_txn_output);
+        }
+    }
 }
@@ -18,6 +18,23 @@ fn random_benches(c: &mut Criterion) {
     });
 }
 
-criterion_group!(benches, random_benches);
+// Compares parallel execution with and without pinning workers to NUMA nodes. On multi-socket
+// machines, pinning avoids cross-socket cache-line transfers on the multi-version data structure.
+fn numa_pinning_benches(c: &mut Criterion) {
+    let mut group = c.benchmark_group("numa_pinning_benches");
+    for pin_workers_to_numa_nodes in [false, true] {
+        group.bench_function(
+            format!("pin_workers_to_numa_nodes={}", pin_workers_to_numa_nodes),
+            |b| {
+                let bencher = Bencher::<[u8; 32], [u8; 32]>::new(10000, 100)
+                    .with_numa_pinning(pin_workers_to_numa_nodes);
+                bencher.bench(&any::<[u8; 32]>(), b)
+            },
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, random_benches, numa_pinning_benches);
 
 criterion_main!(benches);
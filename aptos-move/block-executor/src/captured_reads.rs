@@ -814,6 +814,47 @@ where
 
         ret
     }
+
+    /// Returns the data and group reads together with the captured information (e.g. the read
+    /// values), so that the reads can be dumped.
+    pub(crate) fn get_data_and_group_reads(
+        &self,
+    ) -> Vec<(InputOutputKey<T::Key, T::Tag>, &DataRead<T::Value>)> {
+        let mut ret: Vec<_> = self
+            .data_reads
+            .iter()
+            .map(|(key, read)| (InputOutputKey::Resource(key.clone()), read))
+            .collect();
+
+        for (key, group_reads) in &self.group_reads {
+            for (tag, read) in &group_reads.inner_reads {
+                ret.push((InputOutputKey::Group(key.clone(), tag.clone()), read));
+            }
+        }
+
+        ret
+    }
+
+    /// Returns the keys of data and group reads that observed a value written by another
+    /// transaction in the block, together with the index of that transaction.
+    pub(crate) fn get_read_dependencies(&self) -> Vec<(TxnIndex, InputOutputKey<T::Key, T::Tag>)> {
+        let mut ret = Vec::new();
+        for (key, read) in &self.data_reads {
+            if let DataRead::Versioned(Ok((txn_idx, _)), _, _) = read {
+                ret.push((*txn_idx, InputOutputKey::Resource(key.clone())));
+            }
+        }
+
+        for (key, group_reads) in &self.group_reads {
+            for (tag, read) in &group_reads.inner_reads {
+                if let DataRead::Versioned(Ok((txn_idx, _)), _, _) = read {
+                    ret.push((*txn_idx, InputOutputKey::Group(key.clone(), tag.clone())));
+                }
+            }
+        }
+
+        ret
+    }
 }
 
 #[derive(Derivative)]
@@ -2,7 +2,8 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use aptos_types::error::PanicError;
-use hashbrown::HashMap;
+use hashbrown::{HashMap, HashSet};
+use move_core_types::{account_address::AccountAddress, language_storage::ModuleId};
 use move_vm_types::code::{ModuleCode, WithSize};
 use std::{
     hash::Hash,
@@ -58,11 +59,25 @@ where
     }
 }
 
+/// Key of a module stored in [GlobalModuleCache], which knows the address the module is published
+/// at. Used to index cached modules by address.
+pub trait ModuleCacheKey: Hash + Eq + Clone {
+    fn module_address(&self) -> &AccountAddress;
+}
+
+impl ModuleCacheKey for ModuleId {
+    fn module_address(&self) -> &AccountAddress {
+        self.address()
+    }
+}
+
 /// A global module cache for verified code that is read-only and concurrently accessed during the
 /// block execution. Modified safely only at block boundaries.
 pub struct GlobalModuleCache<K, D, V, E> {
     /// Module cache containing the verified code.
     module_cache: HashMap<K, Entry<D, V, E>>,
+    /// Keys of cached modules, grouped by the address of the module.
+    keys_by_address: HashMap<AccountAddress, HashSet<K>>,
     /// Sum of serialized sizes (in bytes) of all cached modules.
     size: usize,
 }
@@ -77,6 +92,7 @@ where
     pub fn empty() -> Self {
         Self {
             module_cache: HashMap::new(),
+            keys_by_address: HashMap::new(),
             size: 0,
         }
     }
@@ -120,8 +136,27 @@ where
     /// Flushes the module cache.
     pub fn flush(&mut self) {
         self.module_cache.clear();
+        self.keys_by_address.clear();
         self.size = 0;
     }
+}
+
+impl<K, D, V, E> GlobalModuleCache<K, D, V, E>
+where
+    K: ModuleCacheKey,
+    V: Deref<Target = Arc<D>>,
+    E: WithSize,
+{
+    /// Marks all cached modules at the specified address as overridden. Used when a module is
+    /// published, because the whole package at the address may be upgraded, and so the cached
+    /// modules of the package should not be used by the subsequent transactions.
+    pub fn mark_overridden_at_address(&self, address: &AccountAddress) {
+        if let Some(keys) = self.keys_by_address.get(address) {
+            for key in keys {
+                self.mark_overridden(key);
+            }
+        }
+    }
 
     /// Inserts modules into the cache.
     /// Notes:
@@ -143,6 +178,7 @@ where
                 } else {
                     self.size -= entry.get().module_code().extension().size_in_bytes();
                     entry.remove();
+                    self.remove_from_address_index(&key);
                 }
             }
 
@@ -153,20 +189,42 @@ where
                 let prev = self.module_cache.insert(key.clone(), entry);
 
                 // At this point, we must have removed the entry, or returned a panic error.
-                assert!(prev.is_none())
+                assert!(prev.is_none());
+                self.add_to_address_index(key);
             }
         }
         Ok(())
     }
 
+    /// Removes all overridden modules from the cache. Called at block boundaries, after modules
+    /// verified during the block execution have been inserted, as overridden entries can never
+    /// be used again.
+    pub fn evict_overridden(&mut self) {
+        let mut evicted_size = 0;
+        let mut evicted_keys = vec![];
+        self.module_cache.retain(|key, entry| {
+            let not_overridden = entry.is_not_overridden();
+            if !not_overridden {
+                evicted_size += entry.module_code().extension().size_in_bytes();
+                evicted_keys.push(key.clone());
+            }
+            not_overridden
+        });
+        self.size -= evicted_size;
+        for key in &evicted_keys {
+            self.remove_from_address_index(key);
+        }
+    }
+
     /// Insert the module to cache. Used for tests only.
     #[cfg(any(test, feature = "testing"))]
     pub fn insert(&mut self, key: K, module: Arc<ModuleCode<D, V, E>>) {
         self.size += module.extension().size_in_bytes();
         self.module_cache.insert(
-            key,
+            key.clone(),
             Entry::new(module).expect("Module code should be verified"),
         );
+        self.add_to_address_index(key);
     }
 
     /// Removes the module from cache and returns true. If the module does not exist for the
@@ -175,19 +233,51 @@ where
     pub fn remove(&mut self, key: &K) -> bool {
         if let Some(entry) = self.module_cache.remove(key) {
             self.size -= entry.module_code().extension().size_in_bytes();
+            self.remove_from_address_index(key);
             true
         } else {
             false
         }
     }
+
+    fn add_to_address_index(&mut self, key: K) {
+        self.keys_by_address
+            .entry(*key.module_address())
+            .or_default()
+            .insert(key);
+    }
+
+    fn remove_from_address_index(&mut self, key: &K) {
+        let address = key.module_address();
+        if let Some(keys) = self.keys_by_address.get_mut(address) {
+            keys.remove(key);
+            if keys.is_empty() {
+                self.keys_by_address.remove(address);
+            }
+        }
+    }
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
     use claims::{assert_err, assert_ok};
+    use move_core_types::identifier::Identifier;
     use move_vm_types::code::{mock_deserialized_code, mock_verified_code, MockExtension};
 
+    // Integer keys used in tests are all stored at the same address.
+    impl ModuleCacheKey for i32 {
+        fn module_address(&self) -> &AccountAddress {
+            &AccountAddress::ZERO
+        }
+    }
+
+    impl ModuleCacheKey for u32 {
+        fn module_address(&self) -> &AccountAddress {
+            &AccountAddress::ZERO
+        }
+    }
+
     #[test]
     fn test_entry_new() {
         assert!(Entry::new(mock_deserialized_code(0, MockExtension::new(8))).is_err());
@@ -244,6 +334,51 @@ mod test {
         assert_eq!(cache.size_in_bytes(), 0);
     }
 
+    #[test]
+    fn test_cache_mark_overridden_at_address_and_evict() {
+        let mut cache = GlobalModuleCache::empty();
+
+        let id = |address: AccountAddress, name: &str| {
+            ModuleId::new(address, Identifier::new(name).unwrap())
+        };
+        cache.insert(
+            id(AccountAddress::ONE, "a"),
+            mock_verified_code(0, MockExtension::new(8)),
+        );
+        cache.insert(
+            id(AccountAddress::ONE, "b"),
+            mock_verified_code(1, MockExtension::new(8)),
+        );
+        cache.insert(
+            id(AccountAddress::TWO, "a"),
+            mock_verified_code(2, MockExtension::new(16)),
+        );
+
+        cache.mark_overridden_at_address(&AccountAddress::ONE);
+        assert!(!cache.contains_not_overridden(&id(AccountAddress::ONE, "a")));
+        assert!(!cache.contains_not_overridden(&id(AccountAddress::ONE, "b")));
+        assert!(cache.contains_not_overridden(&id(AccountAddress::TWO, "a")));
+        assert_eq!(cache.num_modules(), 3);
+
+        cache.evict_overridden();
+        assert_eq!(cache.num_modules(), 1);
+        assert_eq!(cache.size_in_bytes(), 16);
+        assert!(cache.get(&id(AccountAddress::TWO, "a")).is_some());
+        assert!(!cache.keys_by_address.contains_key(&AccountAddress::ONE));
+
+        // Newly inserted modules are indexed by their address as well.
+        let new_modules = vec![(
+            id(AccountAddress::ONE, "a"),
+            mock_verified_code(3, MockExtension::new(8)),
+        )];
+        assert_ok!(cache.insert_verified(new_modules.into_iter()));
+        assert!(cache.contains_not_overridden(&id(AccountAddress::ONE, "a")));
+
+        cache.mark_overridden_at_address(&AccountAddress::ONE);
+        assert!(!cache.contains_not_overridden(&id(AccountAddress::ONE, "a")));
+        assert!(cache.contains_not_overridden(&id(AccountAddress::TWO, "a")));
+    }
+
     #[test]
     fn test_cache_insert_verified() {
         let mut cache = GlobalModuleCache::empty();
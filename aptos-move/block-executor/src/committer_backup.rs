@@ -0,0 +1,166 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_types::block_executor::config::{
+    BlockSTMCommitterBackup, BlockSTMCommitterBackupThresholds,
+};
+use std::{
+    sync::atomic::{AtomicU64, Ordering},
+    time::Instant,
+};
+
+/// Decides whether the thread coordinating commits should perform backup work (validation and
+/// possibly re-execution) for the next transaction to commit, when its commit is stalled.
+///
+/// For the adaptive policy, the validation outcomes and the time of the last commit are tracked
+/// for the block being executed, so that the backup work is only performed for blocks with high
+/// contention, where it is likely to pay off.
+pub(crate) struct CommitterBackupPolicy {
+    policy: BlockSTMCommitterBackup,
+    thresholds: BlockSTMCommitterBackupThresholds,
+    start_time: Instant,
+    num_validations: AtomicU64,
+    num_validation_failures: AtomicU64,
+    // Time of the last commit, in microseconds since the start time.
+    last_commit_micros: AtomicU64,
+}
+
+impl CommitterBackupPolicy {
+    pub(crate) fn new(
+        policy: BlockSTMCommitterBackup,
+        thresholds: BlockSTMCommitterBackupThresholds,
+    ) -> Self {
+        Self {
+            policy,
+            thresholds,
+            start_time: Instant::now(),
+            num_validations: AtomicU64::new(0),
+            num_validation_failures: AtomicU64::new(0),
+            last_commit_micros: AtomicU64::new(0),
+        }
+    }
+
+    /// Records the outcome of a validation. Only tracked for the adaptive policy.
+    pub(crate) fn record_validation(&self, valid: bool) {
+        if self.policy != BlockSTMCommitterBackup::Adaptive {
+            return;
+        }
+
+        self.num_validations.fetch_add(1, Ordering::Relaxed);
+        if !valid {
+            self.num_validation_failures.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+
+    /// Records that a transaction has been committed. Only tracked for the adaptive policy.
+    pub(crate) fn record_commit(&self) {
+        if self.policy != BlockSTMCommitterBackup::Adaptive {
+            return;
+        }
+
+        self.last_commit_micros
+            .store(self.elapsed_micros(), Ordering::Relaxed);
+    }
+
+    /// Returns true if the committer should perform backup work for a stalled transaction.
+    pub(crate) fn should_backup(&self) -> bool {
+        match self.policy {
+            BlockSTMCommitterBackup::Disabled => false,
+            BlockSTMCommitterBackup::Always => true,
+            BlockSTMCommitterBackup::Adaptive => {
+                self.validation_failure_rate_exceeded() || self.commit_stall_exceeded()
+            },
+        }
+    }
+
+    fn validation_failure_rate_exceeded(&self) -> bool {
+        let num_validations = self.num_validations.load(Ordering::Relaxed);
+        if num_validations == 0 || num_validations < self.thresholds.min_num_validations {
+            return false;
+        }
+
+        let num_failures = self.num_validation_failures.load(Ordering::Relaxed);
+        num_failures * 100 >= num_validations * self.thresholds.validation_failure_rate_percent
+    }
+
+    fn commit_stall_exceeded(&self) -> bool {
+        let stall_micros = self
+            .elapsed_micros()
+            .saturating_sub(self.last_commit_micros.load(Ordering::Relaxed));
+        stall_micros >= self.thresholds.commit_stall_duration.as_micros() as u64
+    }
+
+    fn elapsed_micros(&self) -> u64 {
+        self.start_time.elapsed().as_micros() as u64
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use std::time::Duration;
+
+    fn thresholds(commit_stall_duration: Duration) -> BlockSTMCommitterBackupThresholds {
+        BlockSTMCommitterBackupThresholds {
+            validation_failure_rate_percent: 50,
+            min_num_validations: 4,
+            commit_stall_duration,
+        }
+    }
+
+    #[test]
+    fn test_disabled_and_always() {
+        let disabled = CommitterBackupPolicy::new(
+            BlockSTMCommitterBackup::Disabled,
+            thresholds(Duration::ZERO),
+        );
+        assert!(!disabled.should_backup());
+
+        let always = CommitterBackupPolicy::new(
+            BlockSTMCommitterBackup::Always,
+            thresholds(Duration::from_secs(3600)),
+        );
+        assert!(always.should_backup());
+    }
+
+    #[test]
+    fn test_adaptive_validation_failure_rate() {
+        let policy = CommitterBackupPolicy::new(
+            BlockSTMCommitterBackup::Adaptive,
+            thresholds(Duration::from_secs(3600)),
+        );
+        assert!(!policy.should_backup());
+
+        // Below the minimum number of validations, failures are not taken into account.
+        for _ in 0..3 {
+            policy.record_validation(false);
+        }
+        assert!(!policy.should_backup());
+
+        // 3 failures out of 7 validations is below 50%.
+        for _ in 0..4 {
+            policy.record_validation(true);
+        }
+        assert!(!policy.should_backup());
+
+        // 4 failures out of 8 validations.
+        policy.record_validation(false);
+        assert!(policy.should_backup());
+    }
+
+    #[test]
+    fn test_adaptive_commit_stall() {
+        let policy = CommitterBackupPolicy::new(
+            BlockSTMCommitterBackup::Adaptive,
+            thresholds(Duration::ZERO),
+        );
+        assert!(policy.should_backup());
+
+        let policy = CommitterBackupPolicy::new(
+            BlockSTMCommitterBackup::Adaptive,
+            thresholds(Duration::from_secs(3600)),
+        );
+        policy.record_commit();
+        assert!(!policy.should_backup());
+    }
+}
@@ -70,6 +70,16 @@ pub static MODULE_PUBLISHING_FALLBACK_COUNT: Lazy<IntCounter> = Lazy::new(|| {
     .unwrap()
 });
 
+/// Count of failed parallel executions, requiring a fallback to sequential execution, by reason.
+pub static PARALLEL_EXECUTION_FALLBACK_COUNT: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_execution_parallel_execution_fallback_count",
+        "Count of parallel executions that failed and required a fallback to sequential execution",
+        &["reason"]
+    )
+    .unwrap()
+});
+
 /// Count of speculative transaction re-executions due to a failed validation.
 pub static SPECULATIVE_ABORT_COUNT: Lazy<IntCounter> = Lazy::new(|| {
     register_int_counter!(
@@ -79,6 +89,16 @@ pub static SPECULATIVE_ABORT_COUNT: Lazy<IntCounter> = Lazy::new(|| {
     .unwrap()
 });
 
+/// Count of backup validations performed by the thread coordinating commits.
+pub static COMMITTER_BACKUP_COUNT: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_execution_committer_backup_count",
+        "Number of backup validations performed by the committer for stalled transactions",
+        &["outcome"]
+    )
+    .unwrap()
+});
+
 /// Count of times the BlockSTM is early halted due to exceeding the per-block gas limit.
 pub static EXCEED_PER_BLOCK_GAS_LIMIT_COUNT: Lazy<IntCounterVec> = Lazy::new(|| {
     register_int_counter_vec!(
@@ -99,6 +119,17 @@ pub static EXCEED_PER_BLOCK_OUTPUT_LIMIT_COUNT: Lazy<IntCounterVec> = Lazy::new(
     .unwrap()
 });
 
+/// Count of times the BlockSTM is early halted due to exhausting the share of the block gas limit
+/// given to the executor (e.g. to a shard).
+pub static EXCEED_BLOCK_GAS_BUDGET_COUNT: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_execution_gas_budget_count",
+        "Count of times the BlockSTM is early halted due to exhausting the share of the block gas limit given to the executor",
+        &["mode"]
+    )
+    .unwrap()
+});
+
 pub static PARALLEL_EXECUTION_SECONDS: Lazy<Histogram> = Lazy::new(|| {
     register_histogram!(
         // metric name
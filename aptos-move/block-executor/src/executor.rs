@@ -5,6 +5,7 @@
 use crate::{
     code_cache_global::GlobalModuleCache,
     code_cache_global_manager::AptosModuleCacheManagerGuard,
+    committer_backup::CommitterBackupPolicy,
     counters::{
         self, BLOCK_EXECUTOR_INNER_EXECUTE_BLOCK, PARALLEL_EXECUTION_SECONDS,
         RAYON_EXECUTION_SECONDS, TASK_EXECUTE_SECONDS, TASK_VALIDATE_SECONDS, VM_INIT_SECONDS,
@@ -13,7 +14,11 @@ use crate::{
     errors::*,
     executor_utilities::*,
     explicit_sync_wrapper::ExplicitSyncWrapper,
+    fallback_dump::{FallbackDump, ParallelExecutionFailure},
     limit_processor::BlockGasLimitProcessor,
+    numa::NumaWorkerPlacement,
+    profiler::BlockProfile,
+    schedule_replay::{BlockSchedule, ScheduleEvent},
     scheduler::{DependencyStatus, ExecutionTaskType, Scheduler, SchedulerTask, Wave},
     task::{ExecutionStatus, ExecutorTask, TransactionOutput},
     txn_commit_hook::TransactionCommitHook,
@@ -26,8 +31,8 @@ use aptos_aggregator::{
     delayed_change::{ApplyBase, DelayedChange},
     delta_change_set::serialize,
 };
-use aptos_drop_helper::DEFAULT_DROPPER;
-use aptos_logger::{debug, error, info};
+use aptos_drop_helper::{DropPriority, DropTag, DEFAULT_DROPPER};
+use aptos_logger::{debug, error, info, warn};
 use aptos_mvhashmap::{
     types::{Incarnation, MVDelayedFieldsError, TxnIndex, ValueWithLayout},
     unsync_map::UnsyncMap,
@@ -35,7 +40,13 @@ use aptos_mvhashmap::{
     MVHashMap,
 };
 use aptos_types::{
-    block_executor::config::BlockExecutorConfig,
+    block_executor::{
+        config::{
+            BlockExecutorConfig, BlockSTMCommitterBackup, BlockSTMCommitterBackupThresholds,
+            BlockSTMScheduleReplayMode,
+        },
+        execution_order_hint::TransactionOrderingInfo,
+    },
     error::{code_invariant_error, expect_ok, PanicError, PanicOr},
     on_chain_config::BlockGasLimitType,
     state_store::{state_value::StateValue, TStateView},
@@ -60,6 +71,7 @@ use move_core_types::{language_storage::ModuleId, value::MoveTypeLayout, vm_stat
 use move_vm_runtime::{Module, RuntimeEnvironment, WithRuntimeEnvironment};
 use move_vm_types::{code::ModuleCache, delayed_values::delayed_field_id::DelayedFieldID};
 use num_cpus;
+use parking_lot::Mutex;
 use rayon::ThreadPool;
 use std::{
     cell::RefCell,
@@ -578,10 +590,12 @@ where
         executor: &E,
         block: &TP,
         num_workers: usize,
+        committer_backup_policy: &CommitterBackupPolicy,
     ) -> Result<(), PanicOr<ParallelBlockExecutionError>> {
         let mut block_limit_processor = shared_commit_state.acquire();
 
         while let Some((txn_idx, incarnation)) = scheduler.try_commit() {
+            committer_backup_policy.record_commit();
             let mut executed_at_commit = false;
             if !Self::validate_and_commit_delayed_fields(
                 txn_idx,
@@ -752,6 +766,46 @@ where
                 return Ok(());
             }
         }
+
+        // Commit is stalled, as the next transaction needs to be [re]validated (and possibly also
+        // re-executed). Depending on the policy, the committer performs the validation itself,
+        // and if it fails, takes the re-execution task. This is only possible if the caller does
+        // not already hold a task, as otherwise the task could be lost.
+        if matches!(scheduler_task, SchedulerTask::Retry) && committer_backup_policy.should_backup()
+        {
+            if let Some((txn_idx, incarnation, wave)) = scheduler.try_backup_validation_task() {
+                let valid = Self::validate(
+                    txn_idx,
+                    last_input_output,
+                    global_module_cache,
+                    versioned_cache,
+                    scheduler,
+                );
+                counters::COMMITTER_BACKUP_COUNT
+                    .with_label_values(&[if valid { "valid" } else { "invalid" }])
+                    .inc();
+                committer_backup_policy.record_validation(valid);
+
+                // Recorded before the outcome is published to the scheduler, so that tasks that
+                // observe it are always recorded after it.
+                scheduler.record_schedule_event(ScheduleEvent::Validation {
+                    txn_idx,
+                    incarnation,
+                    wave,
+                    valid,
+                });
+                *scheduler_task = Self::update_on_validation(
+                    txn_idx,
+                    incarnation,
+                    valid,
+                    wave,
+                    last_input_output,
+                    versioned_cache,
+                    scheduler,
+                    runtime_environment,
+                )?;
+            }
+        }
         Ok(())
     }
 
@@ -966,6 +1020,7 @@ where
         shared_commit_state: &ExplicitSyncWrapper<BlockGasLimitProcessor<T>>,
         final_results: &ExplicitSyncWrapper<Vec<E::Output>>,
         num_workers: usize,
+        committer_backup_policy: &CommitterBackupPolicy,
     ) -> Result<(), PanicOr<ParallelBlockExecutionError>> {
         // Make executor for each task. TODO: fast concurrent executor.
         let num_txns = block.num_txns();
@@ -1024,6 +1079,7 @@ where
                     &executor,
                     block,
                     num_workers,
+                    committer_backup_policy,
                 )?;
                 scheduler.queueing_commits_mark_done();
             }
@@ -1039,6 +1095,15 @@ where
                         versioned_cache,
                         scheduler,
                     );
+                    committer_backup_policy.record_validation(valid);
+                    // Recorded before the outcome is published to the scheduler, so that tasks
+                    // that observe it are always recorded after it.
+                    scheduler.record_schedule_event(ScheduleEvent::Validation {
+                        txn_idx,
+                        incarnation,
+                        wave,
+                        valid,
+                    });
                     Self::update_on_validation(
                         txn_idx,
                         incarnation,
@@ -1072,6 +1137,12 @@ where
                             shared_counter,
                         ),
                     )?;
+                    // Recorded before the execution is published to the scheduler, so that tasks
+                    // that observe it (e.g. validations) are always recorded after it.
+                    scheduler.record_schedule_event(ScheduleEvent::Execution {
+                        txn_idx,
+                        incarnation,
+                    });
                     scheduler.finish_execution(txn_idx, incarnation, needs_suffix_validation)?
                 },
                 SchedulerTask::ExecutionTask(_, _, ExecutionTaskType::Wakeup(condvar)) => {
@@ -1096,6 +1167,166 @@ where
         }
     }
 
+    /// Replays a recorded schedule of a parallel execution on the current thread. Executions
+    /// and validations are performed in the recorded order, and after each of them, the
+    /// transactions that are ready get committed. Returns an error as soon as the replay
+    /// diverges from the recorded schedule, e.g. if the recorded execution is not possible or
+    /// a validation has a different outcome.
+    #[allow(clippy::too_many_arguments)]
+    fn replay_schedule(
+        &self,
+        schedule: BlockSchedule,
+        environment: &AptosEnvironment,
+        block: &TP,
+        last_input_output: &TxnLastInputOutput<T, E::Output, E::Error>,
+        versioned_cache: &MVHashMap<T::Key, T::Tag, T::Value, DelayedFieldID>,
+        scheduler: &Scheduler,
+        base_view: &S,
+        global_module_cache: &GlobalModuleCache<
+            ModuleId,
+            CompiledModule,
+            Module,
+            AptosModuleExtension,
+        >,
+        start_shared_counter: u32,
+        shared_counter: &AtomicU32,
+        shared_commit_state: &ExplicitSyncWrapper<BlockGasLimitProcessor<T>>,
+        final_results: &ExplicitSyncWrapper<Vec<E::Output>>,
+    ) -> Result<(), PanicOr<ParallelBlockExecutionError>> {
+        let executor = E::init(environment.clone(), base_view);
+        let runtime_environment = environment.runtime_environment();
+
+        // Backup validations of the committer are recorded as any other validation.
+        let committer_backup_policy = CommitterBackupPolicy::new(
+            BlockSTMCommitterBackup::Disabled,
+            BlockSTMCommitterBackupThresholds::default(),
+        );
+        // Tasks returned by the scheduler are ignored, as the recorded schedule determines the
+        // next task. Since workers never ask the scheduler for tasks during the replay, aborts do
+        // not return execution tasks, and the task always remains Retry.
+        let mut scheduler_task = SchedulerTask::Retry;
+
+        for event in schedule.events {
+            if scheduler.has_halted() {
+                // Remaining events were speculative tasks finished after the halt.
+                break;
+            }
+
+            match event {
+                ScheduleEvent::Execution {
+                    txn_idx,
+                    incarnation,
+                } => {
+                    let replayed_incarnation = scheduler.try_incarnate_for_replay(txn_idx);
+                    if replayed_incarnation != Some(incarnation) {
+                        return Err(code_invariant_error(format!(
+                            "Replayed schedule diverged: execution of txn {} incarnation {}, \
+                             but incarnation {:?} is ready",
+                            txn_idx, incarnation, replayed_incarnation
+                        ))
+                        .into());
+                    }
+
+                    let needs_suffix_validation = Self::execute(
+                        txn_idx,
+                        incarnation,
+                        block,
+                        last_input_output,
+                        versioned_cache,
+                        &executor,
+                        base_view,
+                        global_module_cache,
+                        runtime_environment,
+                        ParallelState::new(
+                            versioned_cache,
+                            scheduler,
+                            start_shared_counter,
+                            shared_counter,
+                        ),
+                    )?;
+                    scheduler.finish_execution(txn_idx, incarnation, needs_suffix_validation)?;
+                },
+                ScheduleEvent::Validation {
+                    txn_idx,
+                    incarnation,
+                    wave,
+                    valid,
+                } => {
+                    let replayed_valid = Self::validate(
+                        txn_idx,
+                        last_input_output,
+                        global_module_cache,
+                        versioned_cache,
+                        scheduler,
+                    );
+                    if replayed_valid != valid {
+                        return Err(code_invariant_error(format!(
+                            "Replayed schedule diverged: validation of txn {} incarnation {} \
+                             returned {}, recorded {}",
+                            txn_idx, incarnation, replayed_valid, valid
+                        ))
+                        .into());
+                    }
+
+                    Self::update_on_validation(
+                        txn_idx,
+                        incarnation,
+                        valid,
+                        wave,
+                        last_input_output,
+                        versioned_cache,
+                        scheduler,
+                        runtime_environment,
+                    )?;
+                },
+            }
+
+            while scheduler.should_coordinate_commits() {
+                self.prepare_and_queue_commit_ready_txns(
+                    &self.config.onchain.block_gas_limit_type,
+                    scheduler,
+                    versioned_cache,
+                    &mut scheduler_task,
+                    last_input_output,
+                    shared_commit_state,
+                    base_view,
+                    global_module_cache,
+                    runtime_environment,
+                    start_shared_counter,
+                    shared_counter,
+                    &executor,
+                    block,
+                    1,
+                    &committer_backup_policy,
+                )?;
+                scheduler.queueing_commits_mark_done();
+            }
+
+            while let Ok(txn_idx) = scheduler.pop_from_commit_queue() {
+                self.materialize_txn_commit(
+                    txn_idx,
+                    versioned_cache,
+                    scheduler,
+                    start_shared_counter,
+                    shared_counter,
+                    last_input_output,
+                    base_view,
+                    global_module_cache,
+                    runtime_environment,
+                    final_results,
+                )?;
+            }
+        }
+
+        if !scheduler.has_halted() {
+            return Err(code_invariant_error(
+                "Replayed schedule diverged: block not committed after all recorded events",
+            )
+            .into());
+        }
+        Ok(())
+    }
+
     pub(crate) fn execute_transactions_parallel(
         &self,
         signature_verified_block: &TP,
@@ -1123,10 +1354,10 @@ where
 
         let num_workers = self.config.local.concurrency_level.min(num_txns / 2).max(2);
 
-        let shared_commit_state = ExplicitSyncWrapper::new(BlockGasLimitProcessor::new(
-            self.config.onchain.block_gas_limit_type.clone(),
-            num_txns,
-        ));
+        let shared_commit_state = ExplicitSyncWrapper::new(
+            BlockGasLimitProcessor::new(self.config.onchain.block_gas_limit_type.clone(), num_txns)
+                .with_block_gas_budget(self.config.local.block_gas_budget.clone()),
+        );
         let shared_maybe_error = AtomicBool::new(false);
 
         let final_results = ExplicitSyncWrapper::new(Vec::with_capacity(num_txns));
@@ -1139,41 +1370,113 @@ where
 
         let num_txns = num_txns as u32;
 
+        let schedule_to_replay = match &self.config.local.schedule_replay_mode {
+            BlockSTMScheduleReplayMode::Replay(path) => match BlockSchedule::read_from_file(path) {
+                Ok(schedule) if schedule.num_txns == num_txns => Some(schedule),
+                Ok(schedule) => {
+                    alert!(
+                        "[BlockSTM] Schedule to replay has {} txns, block has {} txns",
+                        schedule.num_txns,
+                        num_txns
+                    );
+                    return Err(());
+                },
+                Err(err) => {
+                    alert!("[BlockSTM] Failed to read schedule to replay: {:?}", err);
+                    return Err(());
+                },
+            },
+            BlockSTMScheduleReplayMode::Disabled | BlockSTMScheduleReplayMode::Record(_) => None,
+        };
+
         let last_input_output = TxnLastInputOutput::new(num_txns);
-        let scheduler = Scheduler::new(num_txns);
+        let scheduler = match &self.config.local.schedule_replay_mode {
+            BlockSTMScheduleReplayMode::Disabled => Scheduler::new(num_txns),
+            BlockSTMScheduleReplayMode::Record(_) => {
+                Scheduler::new_with_schedule_recording(num_txns)
+            },
+            BlockSTMScheduleReplayMode::Replay(_) => Scheduler::new_for_schedule_replay(num_txns),
+        };
+        let scheduler = match self.execution_order(signature_verified_block) {
+            Some(execution_order) => scheduler.with_execution_order(execution_order),
+            None => scheduler,
+        };
+        let committer_backup_policy = CommitterBackupPolicy::new(
+            self.config.local.committer_backup,
+            self.config.local.committer_backup_thresholds.clone(),
+        );
 
-        let timer = RAYON_EXECUTION_SECONDS.start_timer();
-        self.executor_thread_pool.scope(|s| {
-            for _ in 0..num_workers {
-                s.spawn(|_| {
-                    if let Err(err) = self.worker_loop(
-                        module_cache_manager_guard.environment(),
-                        signature_verified_block,
-                        &last_input_output,
-                        &versioned_cache,
-                        &scheduler,
-                        base_view,
-                        module_cache_manager_guard.module_cache(),
-                        start_shared_counter,
-                        &shared_counter,
-                        &shared_commit_state,
-                        &final_results,
-                        num_workers,
-                    ) {
-                        // If there are multiple errors, they all get logged:
-                        // ModulePathReadWriteError and FatalVMError variant is logged at construction,
-                        // and below we log CodeInvariantErrors.
-                        if let PanicOr::CodeInvariantError(err_msg) = err {
-                            alert!("[BlockSTM] worker loop: CodeInvariantError({:?})", err_msg);
-                        }
-                        shared_maybe_error.store(true, Ordering::SeqCst);
+        // The first error that made the parallel execution fail, reported after the execution.
+        let parallel_failure = Mutex::new(None);
+
+        let handle_worker_error = |err: PanicOr<ParallelBlockExecutionError>| {
+            parallel_failure
+                .lock()
+                .get_or_insert_with(|| ParallelExecutionFailure::from_worker_error(&err));
+            // If there are multiple errors, they all get logged:
+            // ModulePathReadWriteError and FatalVMError variant is logged at construction,
+            // and below we log CodeInvariantErrors.
+            if let PanicOr::CodeInvariantError(err_msg) = err {
+                alert!("[BlockSTM] worker loop: CodeInvariantError({:?})", err_msg);
+            }
+            shared_maybe_error.store(true, Ordering::SeqCst);
 
-                        // Make sure to halt the scheduler if it hasn't already been halted.
-                        scheduler.halt();
-                    }
-                });
+            // Make sure to halt the scheduler if it hasn't already been halted.
+            scheduler.halt();
+        };
+
+        let timer = RAYON_EXECUTION_SECONDS.start_timer();
+        if let Some(schedule) = schedule_to_replay {
+            if let Err(err) = self.replay_schedule(
+                schedule,
+                module_cache_manager_guard.environment(),
+                signature_verified_block,
+                &last_input_output,
+                &versioned_cache,
+                &scheduler,
+                base_view,
+                module_cache_manager_guard.module_cache(),
+                start_shared_counter,
+                &shared_counter,
+                &shared_commit_state,
+                &final_results,
+            ) {
+                handle_worker_error(err);
             }
-        });
+        } else {
+            let worker_placement = if self.config.local.pin_workers_to_numa_nodes {
+                NumaWorkerPlacement::new(num_workers)
+            } else {
+                None
+            };
+            self.executor_thread_pool.scope(|s| {
+                for _ in 0..num_workers {
+                    s.spawn(|_| {
+                        // Unpins the thread once the worker is done.
+                        let _pinned_worker_guard = worker_placement
+                            .as_ref()
+                            .map(NumaWorkerPlacement::pin_worker);
+                        if let Err(err) = self.worker_loop(
+                            module_cache_manager_guard.environment(),
+                            signature_verified_block,
+                            &last_input_output,
+                            &versioned_cache,
+                            &scheduler,
+                            base_view,
+                            module_cache_manager_guard.module_cache(),
+                            start_shared_counter,
+                            &shared_counter,
+                            &shared_commit_state,
+                            &final_results,
+                            num_workers,
+                            &committer_backup_policy,
+                        ) {
+                            handle_worker_error(err);
+                        }
+                    });
+                }
+            });
+        }
         drop(timer);
 
         if !shared_maybe_error.load(Ordering::SeqCst) && scheduler.pop_from_commit_queue().is_ok() {
@@ -1182,19 +1485,73 @@ where
             // exit, hence we log an error and fallback to sequential execution.
             alert!("[BlockSTM] error: commit tasks not drained after parallel execution");
 
+            parallel_failure.lock().get_or_insert_with(|| {
+                ParallelExecutionFailure::new(
+                    "commit_tasks_not_drained",
+                    "commit tasks not drained after parallel execution",
+                )
+            });
             shared_maybe_error.store(true, Ordering::Relaxed);
         }
 
+        if shared_maybe_error.load(Ordering::SeqCst) {
+            let failure = parallel_failure.into_inner().unwrap_or_else(|| {
+                ParallelExecutionFailure::new("unknown", "no error recorded by the workers")
+            });
+            self.report_parallel_failure(signature_verified_block, &last_input_output, failure);
+        }
+
+        if self
+            .config
+            .local
+            .profiling_config
+            .enable_block_stm_profiling
+            && !shared_maybe_error.load(Ordering::SeqCst)
+        {
+            let profile = BlockProfile::collect(num_workers, &scheduler, &last_input_output);
+            if let Err(err) = profile.write_to_dir(&self.config.local.profiling_config.output_dir) {
+                warn!("[BlockSTM] Failed to write profiling report: {:?}", err);
+            }
+        }
+
+        if let BlockSTMScheduleReplayMode::Record(output_dir) =
+            &self.config.local.schedule_replay_mode
+        {
+            let schedule = BlockSchedule {
+                num_txns,
+                num_workers,
+                events: scheduler
+                    .take_recorded_schedule_events()
+                    .unwrap_or_default(),
+            };
+            if let Err(err) = schedule.write_to_dir(output_dir) {
+                warn!("[BlockSTM] Failed to write recorded schedule: {:?}", err);
+            }
+        }
+
         counters::update_state_counters(versioned_cache.stats(), true);
         module_cache_manager_guard
             .module_cache_mut()
             .insert_verified(versioned_cache.take_modules_iter())
             .map_err(|err| {
                 alert!("[BlockSTM] Encountered panic error: {:?}", err);
+                if !shared_maybe_error.load(Ordering::SeqCst) {
+                    self.report_parallel_failure(
+                        signature_verified_block,
+                        &last_input_output,
+                        ParallelExecutionFailure::new("module_cache_error", format!("{:?}", err)),
+                    );
+                }
             })?;
+        module_cache_manager_guard
+            .module_cache_mut()
+            .evict_overridden();
 
         // Explicit async drops.
-        DEFAULT_DROPPER.schedule_drop((last_input_output, scheduler, versioned_cache));
+        DEFAULT_DROPPER.schedule_tagged_drop(
+            (last_input_output, scheduler, versioned_cache),
+            DropTag::new("block_stm_state").with_priority(DropPriority::High),
+        );
 
         let block_end_info = if self
             .config
@@ -1212,6 +1569,67 @@ where
             .ok_or(())
     }
 
+    /// Counts the failure of the parallel execution of the block and, if configured, dumps the
+    /// block so that the failure (which is masked by the sequential fallback) can be diagnosed.
+    fn report_parallel_failure(
+        &self,
+        block: &TP,
+        last_input_output: &TxnLastInputOutput<T, E::Output, E::Error>,
+        failure: ParallelExecutionFailure,
+    ) {
+        counters::PARALLEL_EXECUTION_FALLBACK_COUNT
+            .with_label_values(&[failure.reason])
+            .inc();
+
+        if let Some(dump_dir) = &self.config.local.sequential_fallback_dump_dir {
+            let dump = FallbackDump::collect(block, last_input_output, failure);
+            match dump.write_to_dir(dump_dir) {
+                Ok(path) => warn!(
+                    "[BlockSTM] Parallel execution failed, block dumped to {}",
+                    path.display()
+                ),
+                Err(err) => warn!(
+                    "[BlockSTM] Failed to write sequential fallback dump: {:?}",
+                    err
+                ),
+            }
+        }
+    }
+
+    /// Returns the order in which transactions should be picked for parallel execution, if the
+    /// execution order hint is configured and returns a valid permutation. Note: the hint needs
+    /// all transactions of the block to be available before the execution starts.
+    fn execution_order(&self, block: &TP) -> Option<Vec<TxnIndex>> {
+        let hint = self.config.local.execution_order_hint.as_ref()?;
+
+        let num_txns = block.num_txns();
+        let txns: Vec<_> = (0..num_txns as TxnIndex)
+            .map(|idx| {
+                let txn = block.get_txn(idx);
+                TransactionOrderingInfo {
+                    sender: txn.sender(),
+                    user_txn_bytes_len: txn.user_txn_bytes_len(),
+                }
+            })
+            .collect();
+        let execution_order = hint.execution_order(&txns);
+
+        let mut seen = vec![false; num_txns];
+        let is_permutation = execution_order.len() == num_txns
+            && execution_order.iter().all(|idx| {
+                (*idx as usize) < num_txns && !std::mem::replace(&mut seen[*idx as usize], true)
+            });
+        if !is_permutation {
+            alert!(
+                "[BlockSTM] Execution order hint {:?} returned an invalid order for {} txns",
+                hint,
+                num_txns
+            );
+            return None;
+        }
+        Some(execution_order)
+    }
+
     /// Converts module write into cached module representation, and adds it to the module cache.
     fn add_module_write_to_module_cache(
         write: ModuleWrite<T::Value>,
@@ -1247,7 +1665,7 @@ where
             })?;
         let extension = Arc::new(AptosModuleExtension::new(state_value));
 
-        global_module_cache.mark_overridden(&id);
+        global_module_cache.mark_overridden_at_address(id.address());
         per_block_module_cache
             .insert_deserialized_module(id.clone(), compiled_module, extension, Some(txn_idx))
             .map_err(|err| {
@@ -1377,7 +1795,8 @@ where
         let mut block_limit_processor = BlockGasLimitProcessor::<T>::new(
             self.config.onchain.block_gas_limit_type.clone(),
             num_txns,
-        );
+        )
+        .with_block_gas_budget(self.config.local.block_gas_budget.clone());
 
         let last_input_output: TxnLastInputOutput<T, E::Output, E::Error> =
             TxnLastInputOutput::new(num_txns as TxnIndex);
@@ -1669,6 +2088,9 @@ where
         module_cache_manager_guard
             .module_cache_mut()
             .insert_verified(unsync_map.into_modules_iter())?;
+        module_cache_manager_guard
+            .module_cache_mut()
+            .evict_overridden();
 
         let block_end_info = if self
             .config
@@ -1684,6 +2106,14 @@ where
         Ok(BlockOutput::new(ret, block_end_info))
     }
 
+    fn reset_block_gas_budget(&self, consumed: Option<u64>) {
+        if let (Some(block_gas_budget), Some(consumed)) =
+            (&self.config.local.block_gas_budget, consumed)
+        {
+            block_gas_budget.reset_consumed(consumed);
+        }
+    }
+
     fn empty_block_end_info(&self) -> Option<BlockEndInfo> {
         if self
             .config
@@ -1710,6 +2140,15 @@ where
     ) -> BlockExecutionResult<BlockOutput<E::Output>, E::Error> {
         let _timer = BLOCK_EXECUTOR_INNER_EXECUTE_BLOCK.start_timer();
 
+        // Only the attempt whose outputs are returned may consume the block gas budget, so that
+        // the block is cut at the same txn whether or not it falls back to sequential execution.
+        let block_gas_budget_consumed = self
+            .config
+            .local
+            .block_gas_budget
+            .as_ref()
+            .map(|block_gas_budget| block_gas_budget.consumed());
+
         if self.config.local.concurrency_level > 1 {
             let parallel_result = self.execute_transactions_parallel(
                 signature_verified_block,
@@ -1736,6 +2175,7 @@ where
                 .runtime_environment()
                 .flush_struct_name_and_info_caches();
             module_cache_manager_guard.module_cache_mut().flush();
+            self.reset_block_gas_budget(block_gas_budget_consumed);
 
             info!("parallel execution requiring fallback");
         }
@@ -1763,6 +2203,7 @@ where
                 // All logs from the first pass of sequential execution should be cleared and not reported.
                 // Clear by re-initializing the speculative logs.
                 init_speculative_logs(signature_verified_block.num_txns());
+                self.reset_block_gas_budget(block_gas_budget_consumed);
 
                 let sequential_result = self.execute_transactions_sequential(
                     signature_verified_block,
@@ -1787,6 +2228,7 @@ where
             Err(SequentialBlockExecutionError::ErrorToReturn(err)) => err,
         };
 
+        self.reset_block_gas_budget(block_gas_budget_consumed);
         if self.config.local.discard_failed_blocks {
             // We cannot execute block, discard everything (including block metadata and validator transactions)
             // (TODO: maybe we should add fallback here to first try BlockMetadataTransaction alone)
@@ -0,0 +1,255 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    captured_reads::DataRead,
+    errors::ParallelBlockExecutionError,
+    profiler::new_report_path,
+    task::{ExecutionStatus, TransactionOutput},
+    txn_last_input_output::TxnLastInputOutput,
+    txn_provider::TxnProvider,
+};
+use aptos_mvhashmap::types::{Incarnation, TxnIndex};
+use aptos_types::{
+    error::PanicOr, transaction::BlockExecutableTransaction as Transaction,
+    write_set::TransactionWrite,
+};
+use serde::Serialize;
+use std::{
+    fmt::Debug,
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// Why the parallel execution of a block failed, requiring a fallback to sequential execution.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct ParallelExecutionFailure {
+    /// Kind of the failure, also used as the label of the fallback counter.
+    pub reason: &'static str,
+    pub error: String,
+}
+
+impl ParallelExecutionFailure {
+    pub(crate) fn new(reason: &'static str, error: impl Into<String>) -> Self {
+        Self {
+            reason,
+            error: error.into(),
+        }
+    }
+
+    pub(crate) fn from_worker_error(err: &PanicOr<ParallelBlockExecutionError>) -> Self {
+        let reason = match err {
+            PanicOr::CodeInvariantError(_) => "code_invariant_error",
+            PanicOr::Or(ParallelBlockExecutionError::ModulePathReadWriteError) => {
+                "module_read_write_conflict"
+            },
+            PanicOr::Or(ParallelBlockExecutionError::FatalVMError) => "fatal_vm_error",
+            PanicOr::Or(ParallelBlockExecutionError::IncarnationTooHigh) => "incarnation_too_high",
+        };
+        Self::new(reason, format!("{:?}", err))
+    }
+}
+
+/// What a transaction read at a key, values are hex encoded.
+#[derive(Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReadValueDump {
+    /// The value (none for a deletion), with the transaction and incarnation that wrote it (none
+    /// if it was read from storage).
+    Value {
+        writer: Option<(TxnIndex, Incarnation)>,
+        value: Option<String>,
+    },
+    Metadata(Option<String>),
+    Exists(bool),
+    /// An aggregator v1 delta resolved to the value.
+    Resolved(u128),
+}
+
+impl ReadValueDump {
+    fn new<V: TransactionWrite>(read: &DataRead<V>) -> Self {
+        match read {
+            DataRead::Versioned(version, value, _) => Self::Value {
+                writer: version.as_ref().ok().copied(),
+                value: value.bytes().map(hex::encode),
+            },
+            DataRead::Metadata(metadata) => {
+                Self::Metadata(metadata.as_ref().map(|metadata| format!("{:?}", metadata)))
+            },
+            DataRead::Exists(exists) => Self::Exists(*exists),
+            DataRead::Resolved(value) => Self::Resolved(*value),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct ReadDump {
+    pub key: String,
+    pub read: ReadValueDump,
+}
+
+#[derive(Debug, Serialize)]
+pub struct WriteDump {
+    pub key: String,
+    /// The hex encoded value written, none for a deletion.
+    pub value: Option<String>,
+}
+
+/// A transaction of the block, with the reads and the writes of its latest incarnation (empty if
+/// it was never executed).
+#[derive(Debug, Serialize)]
+pub struct TxnFallbackDump {
+    pub txn_idx: TxnIndex,
+    pub transaction: String,
+    /// Status of the latest incarnation, with the error if it failed.
+    pub status: Option<String>,
+    pub reads: Vec<ReadDump>,
+    pub writes: Vec<WriteDump>,
+}
+
+/// Dump of a block whose parallel execution failed, written when the sequential fallback dump
+/// directory is configured. Fallbacks keep the node going, but can mask bugs in BlockSTM or in
+/// the VM, so the dump captures what is needed to reproduce the failure: the error (or panic)
+/// that triggered the fallback, and the values read and written by each transaction, so that the
+/// block can be replayed offline.
+#[derive(Debug, Serialize)]
+pub struct FallbackDump {
+    pub num_txns: u32,
+    pub failure: ParallelExecutionFailure,
+    /// The transactions whose latest incarnation failed with a fatal or invariant error, with the
+    /// error (e.g. the VM error behind a `fatal_vm_error` failure).
+    pub failed_txns: Vec<(TxnIndex, String)>,
+    pub txns: Vec<TxnFallbackDump>,
+}
+
+impl FallbackDump {
+    /// Collects the dump after the parallel execution of the block has failed.
+    pub(crate) fn collect<T, O, E>(
+        block: &impl TxnProvider<T>,
+        last_input_output: &TxnLastInputOutput<T, O, E>,
+        failure: ParallelExecutionFailure,
+    ) -> Self
+    where
+        T: Transaction,
+        O: TransactionOutput<Txn = T>,
+        E: Debug + Send + Clone,
+    {
+        let num_txns = block.num_txns() as TxnIndex;
+        let mut failed_txns = vec![];
+        let txns = (0..num_txns)
+            .map(|txn_idx| {
+                let mut reads: Vec<_> = last_input_output
+                    .read_set(txn_idx)
+                    .map(|read_set| {
+                        read_set
+                            .get_data_and_group_reads()
+                            .into_iter()
+                            .map(|(key, read)| ReadDump {
+                                key: format!("{:?}", key),
+                                read: ReadValueDump::new(read),
+                            })
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                reads.sort_by(|a, b| a.key.cmp(&b.key));
+
+                let output = last_input_output.txn_output(txn_idx);
+                let (status, writes) = match output.as_deref() {
+                    None => (None, vec![]),
+                    Some(ExecutionStatus::Success(output)) => {
+                        (Some("success".to_string()), Self::writes(output))
+                    },
+                    Some(ExecutionStatus::SkipRest(output)) => {
+                        (Some("skip_rest".to_string()), Self::writes(output))
+                    },
+                    Some(ExecutionStatus::Abort(err)) => {
+                        let error = format!("{:?}", err);
+                        failed_txns.push((txn_idx, error.clone()));
+                        (Some(format!("abort: {}", error)), vec![])
+                    },
+                    Some(ExecutionStatus::SpeculativeExecutionAbortError(msg)) => {
+                        (Some(format!("speculative_abort: {}", msg)), vec![])
+                    },
+                    Some(ExecutionStatus::DelayedFieldsCodeInvariantError(msg)) => {
+                        failed_txns.push((txn_idx, msg.clone()));
+                        (Some(format!("code_invariant_error: {}", msg)), vec![])
+                    },
+                };
+
+                TxnFallbackDump {
+                    txn_idx,
+                    transaction: format!("{:?}", block.get_txn(txn_idx)),
+                    status,
+                    reads,
+                    writes,
+                }
+            })
+            .collect();
+
+        Self {
+            num_txns,
+            failure,
+            failed_txns,
+            txns,
+        }
+    }
+
+    fn writes<O: TransactionOutput>(output: &O) -> Vec<WriteDump> {
+        let write = |key: &dyn Debug, value: &<O::Txn as Transaction>::Value| WriteDump {
+            key: format!("{:?}", key),
+            value: value.bytes().map(hex::encode),
+        };
+
+        let mut writes: Vec<_> = output
+            .resource_write_set()
+            .iter()
+            .map(|(key, value, _)| write(key, &**value))
+            .chain(
+                output
+                    .aggregator_v1_write_set()
+                    .iter()
+                    .map(|(key, value)| write(key, value)),
+            )
+            .chain(
+                output
+                    .module_write_set()
+                    .iter()
+                    .map(|(key, module_write)| write(key, module_write.write_op())),
+            )
+            .collect();
+        for (group_key, _, _, inner_writes) in output.resource_group_write_set() {
+            for (tag, (value, _)) in &inner_writes {
+                writes.push(write(&(&group_key, tag), value));
+            }
+        }
+        writes.sort_by(|a, b| a.key.cmp(&b.key));
+        writes
+    }
+
+    /// Writes the dump as JSON into a new file in the specified directory, and returns the path
+    /// to the file.
+    pub(crate) fn write_to_dir(&self, dir: &Path) -> anyhow::Result<PathBuf> {
+        let path = new_report_path(dir, "sequential_fallback_dump")?;
+        fs::write(&path, serde_json::to_vec_pretty(self)?)?;
+        Ok(path)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_failure_from_worker_error() {
+        let failure = ParallelExecutionFailure::from_worker_error(&PanicOr::Or(
+            ParallelBlockExecutionError::IncarnationTooHigh,
+        ));
+        assert_eq!(failure.reason, "incarnation_too_high");
+
+        let failure = ParallelExecutionFailure::from_worker_error(&PanicOr::CodeInvariantError(
+            "invariant".to_string(),
+        ));
+        assert_eq!(failure.reason, "code_invariant_error");
+        assert!(failure.error.contains("invariant"));
+    }
+}
@@ -143,14 +143,19 @@ mod captured_reads;
 mod code_cache;
 pub mod code_cache_global;
 pub mod code_cache_global_manager;
+mod committer_backup;
 pub mod counters;
 pub mod errors;
 pub mod executor;
 mod executor_utilities;
 pub mod explicit_sync_wrapper;
+mod fallback_dump;
 mod limit_processor;
+mod numa;
+mod profiler;
 #[cfg(any(test, feature = "fuzzing"))]
 pub mod proptest_types;
+mod schedule_replay;
 mod scheduler;
 pub mod task;
 pub mod txn_commit_hook;
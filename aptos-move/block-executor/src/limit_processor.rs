@@ -4,12 +4,13 @@
 use crate::{counters, types::ReadWriteSummary};
 use aptos_logger::info;
 use aptos_types::{
+    block_executor::block_gas_budget::BlockGasBudget,
     fee_statement::FeeStatement,
     on_chain_config::BlockGasLimitType,
     transaction::{block_epilogue::BlockEndInfo, BlockExecutableTransaction as Transaction},
 };
 use claims::{assert_le, assert_none};
-use std::time::Instant;
+use std::{sync::Arc, time::Instant};
 
 pub struct BlockGasLimitProcessor<T: Transaction> {
     block_gas_limit_type: BlockGasLimitType,
@@ -20,6 +21,7 @@ pub struct BlockGasLimitProcessor<T: Transaction> {
     txn_read_write_summaries: Vec<ReadWriteSummary<T>>,
     module_rw_conflict: bool,
     start_time: Instant,
+    block_gas_budget: Option<Arc<BlockGasBudget>>,
 }
 
 impl<T: Transaction> BlockGasLimitProcessor<T> {
@@ -33,9 +35,21 @@ impl<T: Transaction> BlockGasLimitProcessor<T> {
             txn_read_write_summaries: Vec::with_capacity(init_size),
             module_rw_conflict: false,
             start_time: Instant::now(),
+            block_gas_budget: None,
         }
     }
 
+    /// Sets the share of the block gas limit given to this executor, which the effective gas of
+    /// the committed txns consumes. The block is ended once the budget is exhausted, even if the
+    /// block gas limit of this block is not reached.
+    pub(crate) fn with_block_gas_budget(
+        mut self,
+        block_gas_budget: Option<Arc<BlockGasBudget>>,
+    ) -> Self {
+        self.block_gas_budget = block_gas_budget;
+        self
+    }
+
     pub(crate) fn accumulate_fee_statement(
         &mut self,
         fee_statement: FeeStatement,
@@ -75,6 +89,7 @@ impl<T: Transaction> BlockGasLimitProcessor<T> {
         // When the accumulated execution and io gas of the committed txns exceeds
         // PER_BLOCK_GAS_LIMIT, early halt BlockSTM. Storage fee does not count towards
         // the per block gas limit, as we measure execution related cost here.
+        let prev_accumulated_effective_block_gas = self.accumulated_effective_block_gas;
         self.accumulated_effective_block_gas += conflict_multiplier
             * (fee_statement.execution_gas_used()
                 * self
@@ -82,6 +97,7 @@ impl<T: Transaction> BlockGasLimitProcessor<T> {
                     .execution_gas_effective_multiplier()
                 + fee_statement.io_gas_used()
                     * self.block_gas_limit_type.io_gas_effective_multiplier());
+        self.update_block_gas_budget(prev_accumulated_effective_block_gas);
 
         if self.block_gas_limit_type.block_output_limit().is_some() {
             self.accumulated_approx_output_size += approx_output_size
@@ -108,6 +124,7 @@ impl<T: Transaction> BlockGasLimitProcessor<T> {
             return;
         };
 
+        let prev_accumulated_effective_block_gas = self.accumulated_effective_block_gas;
         self.accumulated_effective_block_gas = conflict_multiplier as u64
             * (self.accumulated_fee_statement.execution_gas_used()
                 * self
@@ -115,9 +132,19 @@ impl<T: Transaction> BlockGasLimitProcessor<T> {
                     .execution_gas_effective_multiplier()
                 + self.accumulated_fee_statement.io_gas_used()
                     * self.block_gas_limit_type.io_gas_effective_multiplier());
+        self.update_block_gas_budget(prev_accumulated_effective_block_gas);
         self.module_rw_conflict = true;
     }
 
+    fn update_block_gas_budget(&self, prev_accumulated_effective_block_gas: u64) {
+        if let Some(block_gas_budget) = &self.block_gas_budget {
+            block_gas_budget.consume(
+                self.accumulated_effective_block_gas
+                    .saturating_sub(prev_accumulated_effective_block_gas),
+            );
+        }
+    }
+
     fn should_end_block(&mut self, mode: &str) -> bool {
         if let Some(per_block_gas_limit) = self.block_gas_limit_type.block_gas_limit() {
             // When the accumulated block gas of the committed txns exceeds
@@ -136,6 +163,23 @@ impl<T: Transaction> BlockGasLimitProcessor<T> {
             }
         }
 
+        if let Some(block_gas_budget) = &self.block_gas_budget {
+            // Previous blocks of this executor may have consumed most of the budget.
+            if block_gas_budget.is_exhausted() {
+                counters::EXCEED_BLOCK_GAS_BUDGET_COUNT
+                    .with_label_values(&[mode])
+                    .inc();
+                info!(
+                    "[BlockSTM]: execution ({}) early halted due to \
+                    consumed block gas budget {} >= BLOCK_GAS_BUDGET {}",
+                    mode,
+                    block_gas_budget.consumed(),
+                    block_gas_budget.budget(),
+                );
+                return true;
+            }
+        }
+
         if let Some(per_block_output_limit) = self.block_gas_limit_type.block_output_limit() {
             let accumulated_output = self.get_accumulated_approx_output_size();
             if accumulated_output >= per_block_output_limit {
@@ -335,6 +379,27 @@ mod test {
         assert!(processor.should_end_block_parallel());
     }
 
+    #[test]
+    fn test_block_gas_budget() {
+        let block_gas_budget = Arc::new(BlockGasBudget::new(100));
+        // The budget is carried from one block (round) of the executor to the next.
+        let mut processor = BlockGasLimitProcessor::<TestTxn>::new(DEFAULT_COMPLEX_LIMIT, 10)
+            .with_block_gas_budget(Some(block_gas_budget.clone()));
+        processor.accumulate_fee_statement(execution_fee(30), None, None);
+        processor.accumulate_fee_statement(execution_fee(40), None, None);
+        assert!(!processor.should_end_block_parallel());
+
+        let mut next_processor = BlockGasLimitProcessor::<TestTxn>::new(DEFAULT_COMPLEX_LIMIT, 10)
+            .with_block_gas_budget(Some(block_gas_budget.clone()));
+        next_processor.accumulate_fee_statement(execution_fee(20), None, None);
+        assert_eq!(block_gas_budget.consumed(), 90);
+        assert!(!next_processor.should_end_block_sequential());
+
+        // The next block does not reach the limit on its own, but exhausts the budget.
+        next_processor.accumulate_fee_statement(execution_fee(10), None, None);
+        assert!(next_processor.should_end_block_sequential());
+    }
+
     #[test]
     fn test_output_limit_used() {
         let block_gas_limit = BlockGasLimitType::ComplexLimitV1 {
@@ -0,0 +1,234 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_logger::{info, warn};
+use core_affinity::CoreId;
+use once_cell::sync::Lazy;
+use std::{
+    fs,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+const SYSFS_NODE_DIR: &str = "/sys/devices/system/node";
+
+/// NUMA topology of the machine, detected once per process.
+static NUMA_TOPOLOGY: Lazy<Option<NumaTopology>> = Lazy::new(|| {
+    let topology = NumaTopology::detect();
+    match &topology {
+        Some(topology) => info!(
+            "[BlockSTM]: detected {} NUMA node(s) for worker placement",
+            topology.nodes.len()
+        ),
+        None => warn!("[BlockSTM]: NUMA topology not available, workers will not be pinned"),
+    }
+    topology
+});
+
+/// CPUs of each NUMA node (socket), ordered by node id.
+#[derive(Debug, Eq, PartialEq)]
+pub(crate) struct NumaTopology {
+    nodes: Vec<Vec<usize>>,
+}
+
+impl NumaTopology {
+    /// Reads the topology from sysfs (only available on Linux).
+    fn detect() -> Option<Self> {
+        let mut nodes: Vec<(usize, Vec<usize>)> = fs::read_dir(SYSFS_NODE_DIR)
+            .ok()?
+            .filter_map(|entry| {
+                let entry = entry.ok()?;
+                let node_id = entry
+                    .file_name()
+                    .to_str()?
+                    .strip_prefix("node")?
+                    .parse()
+                    .ok()?;
+                let cpu_list = fs::read_to_string(entry.path().join("cpulist")).ok()?;
+                Some((node_id, parse_cpu_list(&cpu_list)?))
+            })
+            .filter(|(_, cpus)| !cpus.is_empty())
+            .collect();
+        nodes.sort_by_key(|(node_id, _)| *node_id);
+
+        Self::new(nodes.into_iter().map(|(_, cpus)| cpus).collect())
+    }
+
+    fn new(nodes: Vec<Vec<usize>>) -> Option<Self> {
+        (!nodes.is_empty()).then_some(Self { nodes })
+    }
+
+    /// Assigns cores to the specified number of workers. Workers are split into contiguous
+    /// groups of (almost) equal size, one group per node, so that workers with close ids (which
+    /// tend to work on close transactions) share the socket. Within a node, cores are assigned
+    /// round-robin.
+    fn worker_cores(&self, num_workers: usize) -> Vec<usize> {
+        let num_nodes = self.nodes.len();
+        let mut current_node = None;
+        let mut first_worker_of_node = 0;
+        (0..num_workers)
+            .map(|worker_id| {
+                let node_id = worker_id * num_nodes / num_workers;
+                if current_node != Some(node_id) {
+                    current_node = Some(node_id);
+                    first_worker_of_node = worker_id;
+                }
+                let cpus = &self.nodes[node_id];
+                cpus[(worker_id - first_worker_of_node) % cpus.len()]
+            })
+            .collect()
+    }
+}
+
+/// Parses the kernel's CPU list format, e.g. "0-3,8,10-11".
+fn parse_cpu_list(cpu_list: &str) -> Option<Vec<usize>> {
+    let mut cpus = Vec::new();
+    for range in cpu_list.trim().split(',').filter(|range| !range.is_empty()) {
+        match range.split_once('-') {
+            Some((start, end)) => {
+                let (start, end): (usize, usize) = (start.parse().ok()?, end.parse().ok()?);
+                cpus.extend(start..=end);
+            },
+            None => cpus.push(range.parse().ok()?),
+        }
+    }
+    Some(cpus)
+}
+
+#[cfg(target_os = "linux")]
+type CpuSet = libc::cpu_set_t;
+#[cfg(not(target_os = "linux"))]
+type CpuSet = ();
+
+/// Returns the set of CPUs the current thread may run on.
+#[cfg(target_os = "linux")]
+fn current_affinity() -> Option<CpuSet> {
+    // SAFETY: the CPU set is plain data, and its size is passed along with it.
+    unsafe {
+        let mut cpu_set: CpuSet = std::mem::zeroed();
+        (libc::sched_getaffinity(0, std::mem::size_of::<CpuSet>(), &mut cpu_set) == 0)
+            .then_some(cpu_set)
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn current_affinity() -> Option<CpuSet> {
+    None
+}
+
+/// Sets the set of CPUs the current thread may run on, returns false on failure.
+#[cfg(target_os = "linux")]
+fn set_current_affinity(cpu_set: &CpuSet) -> bool {
+    // SAFETY: the CPU set is plain data, and its size is passed along with it.
+    unsafe { libc::sched_setaffinity(0, std::mem::size_of::<CpuSet>(), cpu_set) == 0 }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn set_current_affinity(_cpu_set: &CpuSet) -> bool {
+    false
+}
+
+/// Pins parallel execution workers to cores of the NUMA nodes while they execute a block.
+/// Workers call [Self::pin_worker] when they start, and are assigned cores in the order they
+/// start. Threads of the executor thread pool are unpinned when the worker is done.
+///
+/// Note: only the placement of workers depends on the NUMA topology. The scheduler hands out
+/// transactions to all workers from shared counters, so there are no per-node queues and workers
+/// do not prefer transactions executed by workers of the same node.
+pub(crate) struct NumaWorkerPlacement {
+    cores: Vec<usize>,
+    next_worker: AtomicUsize,
+}
+
+impl NumaWorkerPlacement {
+    /// Returns None if the NUMA topology of the machine is not available.
+    pub(crate) fn new(num_workers: usize) -> Option<Self> {
+        NUMA_TOPOLOGY.as_ref().map(|topology| Self {
+            cores: topology.worker_cores(num_workers),
+            next_worker: AtomicUsize::new(0),
+        })
+    }
+
+    /// Pins the current thread to the core of the next worker, until the returned guard is
+    /// dropped.
+    pub(crate) fn pin_worker(&self) -> PinnedWorkerGuard {
+        let worker_id = self.next_worker.fetch_add(1, Ordering::Relaxed);
+        let previous_affinity = current_affinity();
+        if let Some(core) = self.cores.get(worker_id) {
+            if !core_affinity::set_for_current(CoreId { id: *core }) {
+                warn!(
+                    "[BlockSTM]: failed to pin worker {} to core {}",
+                    worker_id, core
+                );
+            }
+        }
+        PinnedWorkerGuard { previous_affinity }
+    }
+}
+
+/// Restores the CPUs a thread could run on before it was pinned as a worker, when dropped.
+pub(crate) struct PinnedWorkerGuard {
+    previous_affinity: Option<CpuSet>,
+}
+
+impl Drop for PinnedWorkerGuard {
+    fn drop(&mut self) {
+        if let Some(previous_affinity) = &self.previous_affinity {
+            if !set_current_affinity(previous_affinity) {
+                warn!("[BlockSTM]: failed to unpin worker thread");
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_parse_cpu_list() {
+        assert_eq!(
+            parse_cpu_list("0-3,8,10-11\n"),
+            Some(vec![0, 1, 2, 3, 8, 10, 11])
+        );
+        assert_eq!(parse_cpu_list(""), Some(vec![]));
+        assert_eq!(parse_cpu_list("0-a"), None);
+    }
+
+    #[test]
+    fn test_worker_cores() {
+        let topology = NumaTopology::new(vec![vec![0, 1, 2], vec![3, 4, 5]]).unwrap();
+        assert_eq!(topology.worker_cores(4), vec![0, 1, 3, 4]);
+        assert_eq!(topology.worker_cores(5), vec![0, 1, 2, 3, 4]);
+        assert_eq!(topology.worker_cores(8), vec![0, 1, 2, 0, 3, 4, 5, 3]);
+
+        let topology = NumaTopology::new(vec![vec![0, 1]]).unwrap();
+        assert_eq!(topology.worker_cores(3), vec![0, 1, 0]);
+        assert!(NumaTopology::new(vec![]).is_none());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    fn test_pinned_worker_is_unpinned() {
+        std::thread::spawn(|| {
+            let core_ids = || -> Vec<usize> {
+                core_affinity::get_core_ids()
+                    .unwrap()
+                    .into_iter()
+                    .map(|core_id| core_id.id)
+                    .collect()
+            };
+            let cores = core_ids();
+            let placement = NumaWorkerPlacement {
+                cores: vec![cores[0]],
+                next_worker: AtomicUsize::new(0),
+            };
+            {
+                let _guard = placement.pin_worker();
+                assert_eq!(core_ids(), vec![cores[0]]);
+            }
+            assert_eq!(core_ids(), cores);
+        })
+        .join()
+        .unwrap();
+    }
+}
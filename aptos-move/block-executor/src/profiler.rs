@@ -0,0 +1,204 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    scheduler::Scheduler, task::TransactionOutput, txn_last_input_output::TxnLastInputOutput,
+};
+use aptos_mvhashmap::types::{Incarnation, TxnIndex};
+use aptos_types::transaction::BlockExecutableTransaction as Transaction;
+use serde::Serialize;
+use std::{
+    collections::BTreeSet,
+    fmt::Debug,
+    fs,
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicU64, Ordering},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// Used to make report file names unique when multiple blocks finish within the same
+/// microsecond.
+static REPORT_SEQUENCE_NUMBER: AtomicU64 = AtomicU64::new(0);
+
+/// Re-executions, dependencies and conflicts of a single committed transaction.
+#[derive(Debug, Eq, PartialEq, Serialize)]
+pub struct TxnProfile {
+    pub txn_idx: TxnIndex,
+    /// Number of times the transaction was re-executed before it was committed.
+    pub num_re_executions: Incarnation,
+    /// Indices of transactions in the block whose writes were read by the committed
+    /// incarnation, i.e. transactions that had to be executed before this one.
+    pub blocking_dependencies: Vec<TxnIndex>,
+    /// Length of the longest chain of dependencies that ends with this transaction
+    /// (including the transaction itself).
+    pub critical_path_length: u32,
+    /// Keys read by the committed incarnation that were written by other transactions in the
+    /// block.
+    pub conflict_keys: Vec<String>,
+}
+
+/// Report of the parallel execution of a single block, written when BlockSTM profiling is
+/// enabled. Helps to understand why a workload does not scale: long critical paths or many
+/// re-executions mean that transactions could not be executed in parallel.
+#[derive(Debug, Serialize)]
+pub struct BlockProfile {
+    pub num_txns: u32,
+    pub num_committed_txns: u32,
+    pub num_workers: usize,
+    pub total_re_executions: u64,
+    /// Longest chain of dependencies in the block. Bounds the achievable speed-up: the block
+    /// cannot be executed faster than executing the critical path sequentially.
+    pub critical_path_length: u32,
+    pub txns: Vec<TxnProfile>,
+}
+
+impl BlockProfile {
+    /// Builds the profile from the committed transactions (in order), each provided with the
+    /// committed incarnation and reads that observed writes of other transactions in the block.
+    pub(crate) fn new(
+        num_txns: u32,
+        num_workers: usize,
+        committed_txns: impl IntoIterator<Item = (TxnIndex, Incarnation, Vec<(TxnIndex, String)>)>,
+    ) -> Self {
+        let mut critical_path_lengths = vec![0; num_txns as usize];
+        let mut txns = Vec::new();
+
+        for (txn_idx, incarnation, read_dependencies) in committed_txns {
+            let mut blocking_dependencies = BTreeSet::new();
+            let mut conflict_keys = BTreeSet::new();
+            for (dep_idx, key) in read_dependencies {
+                blocking_dependencies.insert(dep_idx);
+                conflict_keys.insert(key);
+            }
+
+            let critical_path_length = 1 + blocking_dependencies
+                .iter()
+                .map(|dep_idx| critical_path_lengths[*dep_idx as usize])
+                .max()
+                .unwrap_or(0);
+            critical_path_lengths[txn_idx as usize] = critical_path_length;
+
+            txns.push(TxnProfile {
+                txn_idx,
+                num_re_executions: incarnation,
+                blocking_dependencies: blocking_dependencies.into_iter().collect(),
+                critical_path_length,
+                conflict_keys: conflict_keys.into_iter().collect(),
+            });
+        }
+
+        Self {
+            num_txns,
+            num_committed_txns: txns.len() as u32,
+            num_workers,
+            total_re_executions: txns.iter().map(|t| t.num_re_executions as u64).sum(),
+            critical_path_length: critical_path_lengths.into_iter().max().unwrap_or(0),
+            txns,
+        }
+    }
+
+    /// Collects the profile after the parallel execution of the block has finished.
+    pub(crate) fn collect<T, O, E>(
+        num_workers: usize,
+        scheduler: &Scheduler,
+        last_input_output: &TxnLastInputOutput<T, O, E>,
+    ) -> Self
+    where
+        T: Transaction,
+        O: TransactionOutput<Txn = T>,
+        E: Debug + Send + Clone,
+    {
+        // Transactions are committed in order, so committed transactions form a prefix.
+        let committed_txns = (0..scheduler.num_txns()).map_while(|txn_idx| {
+            scheduler.committed_incarnation(txn_idx).map(|incarnation| {
+                let read_dependencies = last_input_output
+                    .read_set(txn_idx)
+                    .map(|read_set| {
+                        read_set
+                            .get_read_dependencies()
+                            .into_iter()
+                            .map(|(dep_idx, key)| (dep_idx, format!("{:?}", key)))
+                            .collect()
+                    })
+                    .unwrap_or_default();
+                (txn_idx, incarnation, read_dependencies)
+            })
+        });
+        Self::new(scheduler.num_txns(), num_workers, committed_txns)
+    }
+
+    /// Writes the profile as JSON into a new file in the specified directory, and returns the
+    /// path to the file.
+    pub(crate) fn write_to_dir(&self, dir: &Path) -> anyhow::Result<PathBuf> {
+        let path = new_report_path(dir, "block_stm_profile")?;
+        fs::write(&path, serde_json::to_vec_pretty(self)?)?;
+        Ok(path)
+    }
+}
+
+/// Creates the directory if needed, and returns a path for a new JSON report in it, with the
+/// file name starting with the specified prefix.
+pub(crate) fn new_report_path(dir: &Path, prefix: &str) -> anyhow::Result<PathBuf> {
+    fs::create_dir_all(dir)?;
+
+    let timestamp_micros = SystemTime::now().duration_since(UNIX_EPOCH)?.as_micros();
+    let sequence_number = REPORT_SEQUENCE_NUMBER.fetch_add(1, Ordering::Relaxed);
+    Ok(dir.join(format!(
+        "{}_{}_{}.json",
+        prefix, timestamp_micros, sequence_number
+    )))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use aptos_temppath::TempPath;
+
+    fn key(name: &str) -> String {
+        name.to_string()
+    }
+
+    #[test]
+    fn test_block_profile_critical_path() {
+        // 0 <- 1 <- 3, 0 <- 2, 4 is independent. Transaction 5 is not committed.
+        let committed_txns = vec![
+            (0, 0, vec![]),
+            (1, 2, vec![(0, key("a")), (0, key("b"))]),
+            (2, 1, vec![(0, key("a"))]),
+            (3, 0, vec![(1, key("c")), (2, key("d")), (1, key("c"))]),
+            (4, 0, vec![]),
+        ];
+        let profile = BlockProfile::new(6, 4, committed_txns);
+
+        assert_eq!(profile.num_txns, 6);
+        assert_eq!(profile.num_committed_txns, 5);
+        assert_eq!(profile.total_re_executions, 3);
+        assert_eq!(profile.critical_path_length, 3);
+
+        let lengths: Vec<_> = profile
+            .txns
+            .iter()
+            .map(|t| t.critical_path_length)
+            .collect();
+        assert_eq!(lengths, vec![1, 2, 2, 3, 1]);
+
+        assert_eq!(profile.txns[3], TxnProfile {
+            txn_idx: 3,
+            num_re_executions: 0,
+            blocking_dependencies: vec![1, 2],
+            critical_path_length: 3,
+            conflict_keys: vec![key("c"), key("d")],
+        });
+    }
+
+    #[test]
+    fn test_block_profile_write_to_dir() {
+        let profile = BlockProfile::new(1, 2, vec![(0, 0, vec![])]);
+        let dir = TempPath::new();
+
+        let path = profile.write_to_dir(dir.path()).unwrap();
+        let json: serde_json::Value = serde_json::from_slice(&fs::read(path).unwrap()).unwrap();
+        assert_eq!(json["num_committed_txns"], 1);
+        assert_eq!(json["txns"][0]["critical_path_length"], 1);
+    }
+}
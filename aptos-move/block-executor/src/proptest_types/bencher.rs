@@ -33,6 +33,7 @@ pub struct Bencher<K, V, E> {
     transaction_size: usize,
     transaction_gen_param: TransactionGenParams,
     universe_size: usize,
+    pin_workers_to_numa_nodes: bool,
     phantom: PhantomData<(K, V, E)>,
 }
 
@@ -56,10 +57,16 @@ where
             transaction_size,
             transaction_gen_param: TransactionGenParams::default(),
             universe_size,
+            pin_workers_to_numa_nodes: false,
             phantom: PhantomData,
         }
     }
 
+    pub fn with_numa_pinning(mut self, pin_workers_to_numa_nodes: bool) -> Self {
+        self.pin_workers_to_numa_nodes = pin_workers_to_numa_nodes;
+        self
+    }
+
     pub fn bench(&self, key_strategy: &impl Strategy<Value = K>, bencher: &mut CBencher) {
         bencher.iter_batched(
             || {
@@ -69,7 +76,7 @@ where
                     self.transaction_gen_param,
                 )
             },
-            |state| state.run(),
+            |state| state.run(self.pin_workers_to_numa_nodes),
             // The input here is the entire list of signed transactions, so it's pretty large.
             BatchSize::LargeInput,
         )
@@ -118,7 +125,7 @@ where
         }
     }
 
-    pub(crate) fn run(self) {
+    pub(crate) fn run(self, pin_workers_to_numa_nodes: bool) {
         let state_view = MockStateView::empty();
 
         let executor_thread_pool = Arc::new(
@@ -128,7 +135,8 @@ where
                 .unwrap(),
         );
 
-        let config = BlockExecutorConfig::new_no_block_limit(num_cpus::get());
+        let mut config = BlockExecutorConfig::new_no_block_limit(num_cpus::get());
+        config.local.pin_workers_to_numa_nodes = pin_workers_to_numa_nodes;
         let mut guard = AptosModuleCacheManagerGuard::none();
 
         let output = BlockExecutor::<
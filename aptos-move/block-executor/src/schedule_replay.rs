@@ -0,0 +1,108 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{profiler::new_report_path, scheduler::Wave};
+use aptos_infallible::Mutex;
+use aptos_mvhashmap::types::{Incarnation, TxnIndex};
+use serde::{Deserialize, Serialize};
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// A task performed by a worker during parallel execution, recorded once it has finished.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum ScheduleEvent {
+    Execution {
+        txn_idx: TxnIndex,
+        incarnation: Incarnation,
+    },
+    Validation {
+        txn_idx: TxnIndex,
+        incarnation: Incarnation,
+        wave: Wave,
+        valid: bool,
+    },
+}
+
+/// Schedule of the parallel execution of a single block: executions and validations of
+/// transactions, in the order in which they finished (across all workers).
+///
+/// Replaying the schedule on a single thread performs the same executions and validations in the
+/// same order, with commits attempted after each of them. Executions that are re-done during the
+/// commit are not recorded, as they are deterministically triggered by the commit itself.
+#[derive(Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct BlockSchedule {
+    pub num_txns: TxnIndex,
+    pub num_workers: usize,
+    pub events: Vec<ScheduleEvent>,
+}
+
+impl BlockSchedule {
+    /// Writes the schedule as JSON into a new file in the specified directory, and returns the
+    /// path to the file.
+    pub(crate) fn write_to_dir(&self, dir: &Path) -> anyhow::Result<PathBuf> {
+        let path = new_report_path(dir, "block_stm_schedule")?;
+        fs::write(&path, serde_json::to_vec(self)?)?;
+        Ok(path)
+    }
+
+    pub(crate) fn read_from_file(path: &Path) -> anyhow::Result<Self> {
+        Ok(serde_json::from_slice(&fs::read(path)?)?)
+    }
+}
+
+/// Collects schedule events from all workers. The order of events is the order in which the
+/// workers record them, which is always consistent with the order of the recorded tasks.
+pub(crate) struct ScheduleRecorder {
+    events: Mutex<Vec<ScheduleEvent>>,
+}
+
+impl ScheduleRecorder {
+    pub(crate) fn new() -> Self {
+        Self {
+            events: Mutex::new(Vec::new()),
+        }
+    }
+
+    pub(crate) fn record(&self, event: ScheduleEvent) {
+        self.events.lock().push(event);
+    }
+
+    pub(crate) fn take_events(&self) -> Vec<ScheduleEvent> {
+        std::mem::take(&mut *self.events.lock())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use aptos_temppath::TempPath;
+
+    #[test]
+    fn test_block_schedule_write_and_read() {
+        let recorder = ScheduleRecorder::new();
+        recorder.record(ScheduleEvent::Execution {
+            txn_idx: 1,
+            incarnation: 0,
+        });
+        recorder.record(ScheduleEvent::Validation {
+            txn_idx: 1,
+            incarnation: 0,
+            wave: 2,
+            valid: false,
+        });
+
+        let schedule = BlockSchedule {
+            num_txns: 3,
+            num_workers: 2,
+            events: recorder.take_events(),
+        };
+        assert_eq!(schedule.events.len(), 2);
+        assert!(recorder.take_events().is_empty());
+
+        let dir = TempPath::new();
+        let path = schedule.write_to_dir(dir.path()).unwrap();
+        assert_eq!(BlockSchedule::read_from_file(&path).unwrap(), schedule);
+    }
+}
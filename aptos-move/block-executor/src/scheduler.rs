@@ -2,7 +2,10 @@
 // Parts of the project are originally copyright © Meta Platforms, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::explicit_sync_wrapper::ExplicitSyncWrapper;
+use crate::{
+    explicit_sync_wrapper::ExplicitSyncWrapper,
+    schedule_replay::{ScheduleEvent, ScheduleRecorder},
+};
 use aptos_infallible::Mutex;
 use aptos_mvhashmap::types::{Incarnation, TxnIndex};
 use aptos_types::error::{code_invariant_error, PanicError};
@@ -10,7 +13,7 @@ use concurrent_queue::{ConcurrentQueue, PopError};
 use crossbeam::utils::CachePadded;
 use parking_lot::{RwLock, RwLockUpgradableReadGuard};
 use std::{
-    cmp::{max, min},
+    cmp::max,
     sync::{
         atomic::{AtomicBool, AtomicU32, AtomicU64, Ordering},
         Arc, Condvar,
@@ -278,6 +281,7 @@ pub struct Scheduler {
     /// transaction, if the status of the txn is 'Ready'. This implements a counting-based
     /// concurrent ordered set. It is reduced as necessary when transactions become ready to be
     /// executed, in particular, when execution finishes and dependencies are resolved.
+    /// If an execution order is set, the index is a position in the execution order.
     execution_idx: AtomicU32,
     /// The first 32 bits identifies a validation wave while the last 32 bits contain an index
     /// that tracks the minimum of all transaction indices that require validation.
@@ -303,6 +307,18 @@ pub struct Scheduler {
     queueing_commits_lock: CachePadded<ArmedLock>,
 
     commit_queue: ConcurrentQueue<u32>,
+
+    /// Optional execution order (a permutation of transaction indices) and the position of each
+    /// transaction in it. Only affects the order in which transactions are picked for execution,
+    /// while validation and commit always follow the order of the block.
+    execution_order: Option<(Vec<TxnIndex>, Vec<TxnIndex>)>,
+
+    /// If set, finished executions and validations are recorded, so that the schedule can be
+    /// replayed later.
+    schedule_recorder: Option<ScheduleRecorder>,
+    /// Set when a recorded schedule is replayed on a single thread. Then, waiting for a
+    /// dependency would never finish, and instead an error is returned.
+    single_threaded_replay: bool,
 }
 
 /// Public Interfaces for the Scheduler
@@ -332,7 +348,70 @@ impl Scheduler {
             skip_module_reads_validation: CachePadded::new(AtomicBool::new(true)),
             queueing_commits_lock: CachePadded::new(ArmedLock::new()),
             commit_queue: ConcurrentQueue::<u32>::bounded(num_txns as usize),
+            execution_order: None,
+            schedule_recorder: None,
+            single_threaded_replay: false,
+        }
+    }
+
+    /// Sets the order in which transactions are picked for execution. The order must be a
+    /// permutation of transaction indices.
+    pub(crate) fn with_execution_order(self, execution_order: Vec<TxnIndex>) -> Self {
+        assert_eq!(execution_order.len(), self.num_txns as usize);
+
+        let mut positions = vec![0; execution_order.len()];
+        for (position, txn_idx) in execution_order.iter().enumerate() {
+            positions[*txn_idx as usize] = position as TxnIndex;
         }
+        Self {
+            execution_order: Some((execution_order, positions)),
+            ..self
+        }
+    }
+
+    /// Creates a scheduler that records the schedule of the parallel execution.
+    pub(crate) fn new_with_schedule_recording(num_txns: TxnIndex) -> Self {
+        Self {
+            schedule_recorder: Some(ScheduleRecorder::new()),
+            ..Self::new(num_txns)
+        }
+    }
+
+    /// Creates a scheduler used to replay a recorded schedule on a single thread.
+    pub(crate) fn new_for_schedule_replay(num_txns: TxnIndex) -> Self {
+        Self {
+            single_threaded_replay: true,
+            ..Self::new(num_txns)
+        }
+    }
+
+    /// Records a finished execution or validation, if the schedule is being recorded. Must be
+    /// called before the outcome of the task is published (e.g. by finish_execution), so that the
+    /// events are recorded in an order consistent with the transitions they depend on.
+    pub(crate) fn record_schedule_event(&self, event: ScheduleEvent) {
+        if let Some(recorder) = &self.schedule_recorder {
+            recorder.record(event);
+        }
+    }
+
+    /// Returns the recorded events (in order), or None if the schedule is not being recorded.
+    pub(crate) fn take_recorded_schedule_events(&self) -> Option<Vec<ScheduleEvent>> {
+        self.schedule_recorder
+            .as_ref()
+            .map(|recorder| recorder.take_events())
+    }
+
+    /// Used when replaying a recorded schedule: if the transaction is ready to be executed,
+    /// returns the incarnation to execute (and updates the status to executing).
+    pub(crate) fn try_incarnate_for_replay(&self, txn_idx: TxnIndex) -> Option<Incarnation> {
+        self.try_incarnate(txn_idx)
+            .map(|(incarnation, _)| incarnation)
+    }
+
+    /// Returns true if the execution has been halted (e.g. all transactions have been
+    /// committed, or the block has been cut short).
+    pub(crate) fn has_halted(&self) -> bool {
+        self.has_halted.load(Ordering::SeqCst)
     }
 
     pub fn num_txns(&self) -> TxnIndex {
@@ -415,6 +494,32 @@ impl Scheduler {
         None
     }
 
+    /// If the next transaction to commit is executed (i.e. its commit is stalled waiting for
+    /// a validation), returns its version along with the current validation wave, so that
+    /// the caller can perform a backup validation of the transaction. Must only be called
+    /// by the thread that coordinates commits.
+    pub fn try_backup_validation_task(&self) -> Option<(TxnIndex, Incarnation, Wave)> {
+        let commit_idx = self.commit_state.dereference().0;
+        if commit_idx == self.num_txns {
+            return None;
+        }
+
+        // The wave must be loaded before the validation is performed (same as when validation
+        // tasks are created in try_validate_next_version).
+        let (_, wave) = Self::unpack_validation_idx(self.validation_idx.load(Ordering::Acquire));
+        self.is_executed(commit_idx, false)
+            .map(|incarnation| (commit_idx, incarnation, wave))
+    }
+
+    /// Returns the incarnation with which the transaction was committed, or None if the
+    /// transaction has not been committed.
+    pub fn committed_incarnation(&self, txn_idx: TxnIndex) -> Option<Incarnation> {
+        match *self.txn_status[txn_idx as usize].0.read() {
+            ExecutionStatus::Committed(incarnation) => Some(incarnation),
+            _ => None,
+        }
+    }
+
     #[cfg(test)]
     /// Return the TxnIndex and Wave of current commit index
     pub fn commit_state(&self) -> (TxnIndex, u32) {
@@ -455,7 +560,10 @@ impl Scheduler {
 
             let idx_to_execute = self.execution_idx.load(Ordering::Acquire);
 
-            let prefer_validate = idx_to_validate < min(idx_to_execute, self.num_txns)
+            // The validation index is a transaction index, while the execution index is a
+            // position in the execution order, so compare the position of the transaction.
+            let prefer_validate = idx_to_validate < self.num_txns
+                && self.execution_position(idx_to_validate) < idx_to_execute
                 && !self.never_executed(idx_to_validate);
 
             if !prefer_validate && idx_to_execute >= self.num_txns {
@@ -501,6 +609,7 @@ impl Scheduler {
         for dep in txn_deps {
             self.resume(dep)?;
 
+            let dep = self.execution_position(dep);
             if min_dep.is_none() || min_dep.is_some_and(|min_dep| min_dep > dep) {
                 min_dep = Some(dep);
             }
@@ -606,7 +715,7 @@ impl Scheduler {
         }
 
         // txn_idx must be re-executed, and if execution_idx is lower, it will be.
-        if self.execution_idx.load(Ordering::Acquire) > txn_idx {
+        if self.execution_idx.load(Ordering::Acquire) > self.execution_position(txn_idx) {
             // Optimization: execution_idx is higher than txn_idx, but decreasing it may
             // lead to wasted work for all indices between txn_idx and execution_idx.
             // Instead, attempt to create a new incarnation and return the corresponding
@@ -694,6 +803,14 @@ impl TWaitForDependency for Scheduler {
             return Ok(DependencyResult::Resolved);
         }
 
+        if self.single_threaded_replay {
+            // No other thread can resolve the dependency.
+            return Err(code_invariant_error(format!(
+                "Replayed schedule diverged: txn {} encountered dependency on txn {}",
+                txn_idx, dep_txn_idx
+            )));
+        }
+
         // If the execution is already halted, suspend will return false.
         // The synchronization is guaranteed by the Mutex around txn_status.
         // If the execution is halted, the first finishing thread will first set the status of each txn
@@ -916,12 +1033,24 @@ impl Scheduler {
             return None;
         }
 
+        let txn_idx = match &self.execution_order {
+            Some((execution_order, _)) => execution_order[idx_to_execute as usize],
+            None => idx_to_execute,
+        };
+
         // If successfully incarnated (changed status from ready to executing),
         // return version for execution task, otherwise None.
-        self.try_incarnate(idx_to_execute)
-            .map(|(incarnation, execution_task_type)| {
-                (idx_to_execute, incarnation, execution_task_type)
-            })
+        self.try_incarnate(txn_idx)
+            .map(|(incarnation, execution_task_type)| (txn_idx, incarnation, execution_task_type))
+    }
+
+    /// Returns the position of the transaction in the execution order, which is the index of the
+    /// transaction unless the execution order is set.
+    fn execution_position(&self, txn_idx: TxnIndex) -> TxnIndex {
+        match &self.execution_order {
+            Some((_, positions)) => positions[txn_idx as usize],
+            None => txn_idx,
+        }
     }
 
     /// Put a transaction in a suspended state, with a condition variable that can be
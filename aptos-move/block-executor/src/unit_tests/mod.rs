@@ -27,11 +27,19 @@ use aptos_aggregator::{
     delta_math::DeltaHistory,
 };
 use aptos_mvhashmap::types::TxnIndex;
+use aptos_temppath::TempPath;
 use aptos_types::{
-    block_executor::config::BlockExecutorConfig, contract_event::TransactionEvent,
-    executable::ModulePath, state_store::state_value::StateValueMetadata, write_set::WriteOpKind,
+    block_executor::{
+        block_gas_budget::BlockGasBudget,
+        config::{BlockExecutorConfig, BlockSTMScheduleReplayMode},
+    },
+    contract_event::TransactionEvent,
+    executable::ModulePath,
+    state_store::state_value::StateValueMetadata,
+    transaction::BlockOutput,
+    write_set::WriteOpKind,
 };
-use claims::{assert_matches, assert_ok};
+use claims::{assert_err, assert_matches, assert_ok};
 use fail::FailScenario;
 use rand::{prelude::*, random};
 use std::{
@@ -227,6 +235,245 @@ fn resource_group_bcs_fallback() {
     scenario.teardown();
 }
 
+#[test]
+fn block_gas_budget_with_fallback() {
+    let writes = |key: u32| {
+        vec![(
+            KeyType::<u32>(key, false),
+            ValueType::from_value(vec![5], true),
+        )]
+    };
+    let mut group_incarnation: MockIncarnation<KeyType<u32>, MockEvent> =
+        MockIncarnation::new(vec![KeyType::<u32>(1, false)], vec![], vec![], vec![], 10);
+    group_incarnation.group_writes.push((
+        KeyType::<u32>(100, false),
+        StateValueMetadata::none(),
+        HashMap::from([(101, ValueType::from_value(vec![5], true))]),
+    ));
+    // Each txn has an effective gas of 10.
+    let transactions = Vec::from([
+        MockTransaction::from_behavior(MockIncarnation::new(
+            vec![KeyType::<u32>(1, false)],
+            writes(2),
+            vec![],
+            vec![],
+            10,
+        )),
+        MockTransaction::from_behavior(group_incarnation),
+        MockTransaction::from_behavior(MockIncarnation::new(
+            vec![KeyType::<u32>(3, false)],
+            writes(4),
+            vec![],
+            vec![],
+            10,
+        )),
+        MockTransaction::from_behavior(MockIncarnation::new(
+            vec![KeyType::<u32>(5, false)],
+            writes(6),
+            vec![],
+            vec![],
+            10,
+        )),
+    ]);
+    let txn_provider = DefaultTxnProvider::new(transactions);
+
+    let data_view = NonEmptyGroupDataView::<KeyType<u32>> {
+        group_keys: HashSet::new(),
+    };
+    let executor_thread_pool = Arc::new(
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(num_cpus::get())
+            .build()
+            .unwrap(),
+    );
+    let block_executor_with_budget = |block_gas_budget| {
+        let mut config = BlockExecutorConfig::new_no_block_limit(num_cpus::get());
+        config.local.block_gas_budget = Some(block_gas_budget);
+        BlockExecutor::<
+            MockTransaction<KeyType<u32>, MockEvent>,
+            MockTask<KeyType<u32>, MockEvent>,
+            NonEmptyGroupDataView<KeyType<u32>>,
+            NoOpTransactionCommitHook<MockOutput<KeyType<u32>, MockEvent>, usize>,
+            DefaultTxnProvider<MockTransaction<KeyType<u32>, MockEvent>>,
+        >::new(config, executor_thread_pool.clone(), None)
+    };
+    let skipped = |output: BlockOutput<MockOutput<KeyType<u32>, MockEvent>>| {
+        output
+            .into_transaction_outputs_forced()
+            .into_iter()
+            .map(|txn_output| txn_output.skipped)
+            .collect::<Vec<_>>()
+    };
+
+    // Fails parallel execution and the first sequential pass, only the sequential pass with the
+    // resource group fallback succeeds.
+    let scenario = FailScenario::setup();
+    fail::cfg("fail-point-resource-group-serialization", "return()").unwrap();
+
+    let sequential_budget = Arc::new(BlockGasBudget::new(25));
+    let mut guard = AptosModuleCacheManagerGuard::none();
+    let sequential_output = block_executor_with_budget(sequential_budget.clone())
+        .execute_transactions_sequential(&txn_provider, &data_view, &mut guard, true)
+        .map_err(|_| ())
+        .unwrap();
+
+    let fallback_budget = Arc::new(BlockGasBudget::new(25));
+    let mut guard = AptosModuleCacheManagerGuard::none();
+    let fallback_output = block_executor_with_budget(fallback_budget.clone())
+        .execute_block(&txn_provider, &data_view, &mut guard)
+        .unwrap();
+
+    // Txn 1 is discarded but its gas counts, and the budget is exhausted after txn 2.
+    let expected_skipped = vec![false, true, false, true];
+    assert_eq!(skipped(sequential_output), expected_skipped);
+    assert_eq!(sequential_budget.consumed(), 30);
+    // The failed attempts do not consume the budget, so the block is cut at the same txn.
+    assert_eq!(skipped(fallback_output), expected_skipped);
+    assert_eq!(fallback_budget.consumed(), 30);
+
+    scenario.teardown();
+}
+
+#[test]
+fn schedule_record_and_replay() {
+    let transactions: Vec<_> = (0..20)
+        .map(|i| {
+            MockTransaction::from_behavior(MockIncarnation::<KeyType<u32>, MockEvent>::new(
+                vec![KeyType::<u32>(1, false)],
+                vec![
+                    (
+                        KeyType::<u32>(1, false),
+                        ValueType::from_value(vec![i as u8], true),
+                    ),
+                    (
+                        KeyType::<u32>(100 + i, false),
+                        ValueType::from_value(vec![5], true),
+                    ),
+                ],
+                vec![],
+                vec![],
+                10,
+            ))
+        })
+        .collect();
+    let txn_provider = DefaultTxnProvider::new(transactions);
+
+    let data_view = DeltaDataView::<KeyType<u32>> {
+        phantom: PhantomData,
+    };
+    let executor_thread_pool = Arc::new(
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(num_cpus::get())
+            .build()
+            .unwrap(),
+    );
+    let execute = |schedule_replay_mode| {
+        let mut config = BlockExecutorConfig::new_no_block_limit(num_cpus::get());
+        config.local.schedule_replay_mode = schedule_replay_mode;
+        let block_executor = BlockExecutor::<
+            MockTransaction<KeyType<u32>, MockEvent>,
+            MockTask<KeyType<u32>, MockEvent>,
+            DeltaDataView<KeyType<u32>>,
+            NoOpTransactionCommitHook<MockOutput<KeyType<u32>, MockEvent>, usize>,
+            DefaultTxnProvider<MockTransaction<KeyType<u32>, MockEvent>>,
+        >::new(config, executor_thread_pool.clone(), None);
+
+        let mut guard = AptosModuleCacheManagerGuard::none();
+        block_executor
+            .execute_transactions_parallel(&txn_provider, &data_view, &mut guard)
+            .map(|output| {
+                output
+                    .into_transaction_outputs_forced()
+                    .into_iter()
+                    .map(|txn_output| txn_output.read_results)
+                    .collect::<Vec<_>>()
+            })
+    };
+
+    let dir = TempPath::new();
+    let recorded_output =
+        execute(BlockSTMScheduleReplayMode::Record(dir.path().to_path_buf())).unwrap();
+    let schedule_paths: Vec<_> = std::fs::read_dir(dir.path())
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .collect();
+    assert_eq!(schedule_paths.len(), 1);
+
+    let replayed_output = execute(BlockSTMScheduleReplayMode::Replay(
+        schedule_paths[0].clone(),
+    ))
+    .unwrap();
+    assert_eq!(replayed_output, recorded_output);
+
+    // Schedule can not be replayed if the file does not exist.
+    assert_err!(execute(BlockSTMScheduleReplayMode::Replay(
+        dir.path().join("missing.json")
+    )));
+}
+
+#[test]
+fn sequential_fallback_dump() {
+    let transactions = Vec::from([
+        MockTransaction::from_behavior(MockIncarnation::<KeyType<u32>, MockEvent>::new(
+            vec![KeyType::<u32>(1, false)],
+            vec![(
+                KeyType::<u32>(2, false),
+                ValueType::from_value(vec![5], true),
+            )],
+            vec![],
+            vec![],
+            10,
+        )),
+        MockTransaction::Abort,
+    ]);
+    let txn_provider = DefaultTxnProvider::new(transactions);
+
+    let data_view = DeltaDataView::<KeyType<u32>> {
+        phantom: PhantomData,
+    };
+    let executor_thread_pool = Arc::new(
+        rayon::ThreadPoolBuilder::new()
+            .num_threads(num_cpus::get())
+            .build()
+            .unwrap(),
+    );
+    let dir = TempPath::new();
+    let mut config = BlockExecutorConfig::new_no_block_limit(num_cpus::get());
+    config.local.sequential_fallback_dump_dir = Some(dir.path().to_path_buf());
+    let block_executor = BlockExecutor::<
+        MockTransaction<KeyType<u32>, MockEvent>,
+        MockTask<KeyType<u32>, MockEvent>,
+        DeltaDataView<KeyType<u32>>,
+        NoOpTransactionCommitHook<MockOutput<KeyType<u32>, MockEvent>, usize>,
+        DefaultTxnProvider<MockTransaction<KeyType<u32>, MockEvent>>,
+    >::new(config, executor_thread_pool, None);
+
+    // The abort fails parallel execution (and then the sequential fallback as well).
+    let mut guard = AptosModuleCacheManagerGuard::none();
+    assert_err!(block_executor.execute_block(&txn_provider, &data_view, &mut guard));
+
+    let dump_paths: Vec<_> = std::fs::read_dir(dir.path())
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .collect();
+    assert_eq!(dump_paths.len(), 1);
+    let dump: serde_json::Value =
+        serde_json::from_slice(&std::fs::read(&dump_paths[0]).unwrap()).unwrap();
+    assert_eq!(dump["num_txns"], 2);
+    assert_eq!(dump["failure"]["reason"], "fatal_vm_error");
+    // The error behind the fatal VM error is captured.
+    assert_eq!(dump["failed_txns"].as_array().unwrap().len(), 1);
+    assert_eq!(dump["failed_txns"][0][0], 1);
+    // The values read and written are captured, so that the block can be replayed.
+    let reads = dump["txns"][0]["reads"].as_array().unwrap();
+    assert_eq!(reads.len(), 1);
+    assert!(reads[0]["read"].get("value").is_some());
+    let writes = dump["txns"][0]["writes"].as_array().unwrap();
+    assert_eq!(writes.len(), 1);
+    assert!(writes[0]["value"].is_string());
+    assert_eq!(dump["txns"][0]["status"], "success");
+}
+
 #[test]
 fn block_output_err_precedence() {
     let incarnation: MockIncarnation<KeyType<u32>, MockEvent> = MockIncarnation::new(
@@ -767,6 +1014,79 @@ fn scheduler_dependency() {
     );
 }
 
+#[test]
+fn scheduler_execution_order() {
+    let s = Scheduler::new(5).with_execution_order(vec![3, 1, 4, 0, 2]);
+
+    for i in [3, 1, 4, 0, 2] {
+        assert_matches!(
+            s.next_task(),
+            SchedulerTask::ExecutionTask(j, 0, ExecutionTaskType::Execution) if j == i
+        );
+    }
+
+    // Dependency added for transaction 4 on transaction 1.
+    assert_matches!(
+        s.wait_for_dependency(4, 1),
+        Ok(DependencyResult::Dependency(_))
+    );
+    assert_matches!(s.finish_execution(1, 0, false), Ok(SchedulerTask::Retry));
+
+    // Execution index is decreased to the position of transaction 4 in the execution order.
+    assert_matches!(
+        s.next_task(),
+        SchedulerTask::ExecutionTask(4, 0, ExecutionTaskType::Wakeup(_))
+    );
+    // Transactions 0 and 2 are still executing, and 0 can not be validated yet.
+    assert_matches!(s.next_task(), SchedulerTask::Retry);
+}
+
+#[test]
+fn scheduler_execution_order_validation() {
+    let s = Scheduler::new(3).with_execution_order(vec![1, 2, 0]);
+
+    assert_matches!(
+        s.next_task(),
+        SchedulerTask::ExecutionTask(1, 0, ExecutionTaskType::Execution)
+    );
+    assert_matches!(
+        s.next_task(),
+        SchedulerTask::ExecutionTask(2, 0, ExecutionTaskType::Execution)
+    );
+    // Dependency added for transaction 2 on transaction 1.
+    assert_matches!(
+        s.wait_for_dependency(2, 1),
+        Ok(DependencyResult::Dependency(_))
+    );
+    assert_matches!(
+        s.next_task(),
+        SchedulerTask::ExecutionTask(0, 0, ExecutionTaskType::Execution)
+    );
+    assert_matches!(s.finish_execution(0, 0, false), Ok(SchedulerTask::Retry));
+    assert_matches!(s.next_task(), SchedulerTask::ValidationTask(0, 0, 0));
+    s.finish_validation(0, 0);
+
+    // Execution index is decreased to the position of transaction 2, i.e. 1.
+    assert_matches!(s.finish_execution(1, 0, false), Ok(SchedulerTask::Retry));
+    // Transaction 0 is at position 2, so it is not re-executed right away.
+    assert!(s.try_abort(0, 0));
+    assert_matches!(s.finish_abort(0, 0), Ok(SchedulerTask::Retry));
+
+    // Transaction 1 is at position 0 which is below the execution index, so it is validated
+    // even though its index is not below the execution index.
+    assert_matches!(s.next_task(), SchedulerTask::ValidationTask(1, 0, _));
+    assert_matches!(
+        s.next_task(),
+        SchedulerTask::ExecutionTask(2, 0, ExecutionTaskType::Wakeup(_))
+    );
+    // Transaction 2 is executing and can not be validated yet, so transaction 0 is re-executed.
+    assert_matches!(
+        s.next_task(),
+        SchedulerTask::ExecutionTask(0, 1, ExecutionTaskType::Execution)
+    );
+    assert_matches!(s.next_task(), SchedulerTask::Retry);
+}
+
 // Will return a scheduler in a state where all transactions are scheduled for
 // for execution, validation index = num_txns, and wave = 0.
 fn incarnation_one_scheduler(num_txns: TxnIndex) -> Scheduler {
@@ -0,0 +1,42 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::MoveHarness;
+use aptos_cached_packages::aptos_stdlib;
+use aptos_types::{account_address::AccountAddress, transaction::Transaction};
+use aptos_vm::execution_trace::{execute_and_trace_transaction, ExecutionTrace, TraceDivergence};
+
+#[test]
+fn test_trace_and_replay_transaction() {
+    let mut harness = MoveHarness::new();
+    let sender = harness.new_account_at(AccountAddress::from_hex_literal("0x121").unwrap());
+    let receiver = harness.new_account_at(AccountAddress::from_hex_literal("0x122").unwrap());
+    let txn = harness.create_transaction_payload(
+        &sender,
+        aptos_stdlib::aptos_account_transfer(*receiver.address(), 1000),
+    );
+
+    let (_vm_status, output, trace) = execute_and_trace_transaction(
+        harness.executor.get_state_view(),
+        Transaction::UserTransaction(txn),
+    )
+    .unwrap();
+    assert!(output.status().status().unwrap().is_success());
+    assert!(!trace.reads.is_empty());
+    assert_eq!(trace.events.as_slice(), output.events());
+    assert_eq!(trace.gas.gas_used, output.gas_used());
+
+    // The trace survives a round trip through its serialized form, and replays identically.
+    let trace: ExecutionTrace = bcs::from_bytes(&bcs::to_bytes(&trace).unwrap()).unwrap();
+    let divergences = trace.replay().unwrap();
+    assert!(divergences.is_empty(), "{:?}", divergences);
+
+    // Replaying against incomplete state reports the reads that were not recorded.
+    let mut incomplete_trace = trace.clone();
+    let (missing_key, _) = incomplete_trace.values.pop().unwrap();
+    let divergences = incomplete_trace.replay().unwrap();
+    assert!(divergences.iter().any(|divergence| matches!(
+        divergence,
+        TraceDivergence::MissingReads(keys) if keys.contains(&missing_key)
+    )));
+}
@@ -73,8 +73,37 @@ fn test_modify_gas_schedule_check_hash() {
 
 fn save_profiling_results(name: &str, log: &TransactionGasLog) {
     let path = Path::new("gas-profiling").join(name);
-    log.generate_html_report(path, format!("Gas Report - {}", name))
+    log.generate_html_report(&path, format!("Gas Report - {}", name))
         .unwrap();
+    log.generate_profiles(path.join("profiles"), name.to_string())
+        .unwrap();
+}
+
+#[test]
+fn test_gas_profiles() {
+    let mut harness = MoveHarness::new();
+    let account_1 = &harness.new_account_at(AccountAddress::from_hex_literal("0x121").unwrap());
+    let account_2 = &harness.new_account_at(AccountAddress::from_hex_literal("0x122").unwrap());
+
+    let (log, _, _) = harness.evaluate_gas_with_profiler(
+        account_1,
+        aptos_stdlib::aptos_account_transfer(*account_2.address(), 1000),
+    );
+
+    let folded_stacks = log.to_folded_stacks();
+    assert!(!folded_stacks.execution.is_empty());
+    assert!(!folded_stacks.io.is_empty());
+
+    // The execution and IO profiles add up to the execution and IO costs of the transaction
+    let speedscope = log.to_speedscope("transfer".to_string());
+    let profiles = speedscope["profiles"].as_array().unwrap();
+    assert_eq!(profiles[0]["name"], "Execution (gas units)");
+    assert_eq!(profiles[1]["name"], "IO (gas units)");
+    let total_gas_units =
+        profiles[0]["endValue"].as_f64().unwrap() + profiles[1]["endValue"].as_f64().unwrap();
+    let expected_gas_units =
+        u64::from(log.exec_io.total) as f64 / u64::from(log.exec_io.gas_scaling_factor) as f64;
+    assert!((total_gas_units - expected_gas_units).abs() < 1e-6);
 }
 
 pub struct SummaryExeAndIO {
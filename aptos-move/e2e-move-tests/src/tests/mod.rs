@@ -16,6 +16,7 @@ mod constructor_args;
 mod dependencies;
 mod enum_upgrade;
 mod error_map;
+mod execution_trace;
 mod fee_payer;
 mod fungible_asset;
 mod gas;
@@ -53,8 +54,10 @@ mod test_self;
 mod token_event_store;
 mod token_objects;
 mod transaction_context;
+mod transaction_output_limits;
 mod type_too_large;
 mod upgrade_compatibility;
 mod vector_numeric_address;
 mod vm;
 mod vote;
+mod write_scope;
@@ -0,0 +1,76 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::MoveHarness;
+use aptos_cached_packages::aptos_stdlib::aptos_account_transfer;
+use aptos_types::{
+    account_address::AccountAddress,
+    on_chain_config::{
+        BlockGasLimitType, ExecutionConfigV5, OnChainConfig, OnChainExecutionConfig,
+        TransactionDeduperType, TransactionOutputLimits, TransactionShufflerType,
+    },
+    transaction::{ExecutionStatus, TransactionStatus},
+};
+use move_core_types::{language_storage::CORE_CODE_ADDRESS, vm_status::StatusCode};
+
+fn set_transaction_output_limits(h: &mut MoveHarness, limits: TransactionOutputLimits) {
+    let config = OnChainExecutionConfig::V5(ExecutionConfigV5 {
+        transaction_shuffler_type: TransactionShufflerType::default_for_genesis(),
+        block_gas_limit_type: BlockGasLimitType::default_for_genesis(),
+        transaction_deduper_type: TransactionDeduperType::TxnHashAndAuthenticatorV1,
+        transaction_output_limits: limits,
+    });
+    // On-chain configs are stored as BCS-serialized bytes.
+    h.set_resource(
+        CORE_CODE_ADDRESS,
+        OnChainExecutionConfig::struct_tag(),
+        &bcs::to_bytes(&config).unwrap(),
+    );
+}
+
+#[test]
+fn test_transaction_output_limits() {
+    let mut h = MoveHarness::new();
+    let sender = h.new_account_at(AccountAddress::from_hex_literal("0x131").unwrap());
+    let receiver = h.new_account_at(AccountAddress::from_hex_literal("0x132").unwrap());
+
+    set_transaction_output_limits(&mut h, TransactionOutputLimits {
+        max_output_bytes: None,
+        max_write_ops: Some(1),
+    });
+
+    // The transfer writes to both accounts, so it exceeds the limit. It is still kept and the
+    // sender pays for the gas used, but none of its other writes are applied.
+    let sender_balance = h.read_aptos_balance(sender.address());
+    let receiver_balance = h.read_aptos_balance(receiver.address());
+    let sequence_number = h.sequence_number(sender.address());
+    let txn =
+        h.create_transaction_payload(&sender, aptos_account_transfer(*receiver.address(), 1000));
+    let output = h.run_raw(txn);
+    assert_eq!(
+        output.status(),
+        &TransactionStatus::Keep(ExecutionStatus::MiscellaneousError(Some(
+            StatusCode::TRANSACTION_OUTPUT_EXCEEDS_LIMITS
+        )))
+    );
+    assert!(output.gas_used() > 0);
+    assert_eq!(
+        h.read_aptos_balance(sender.address()),
+        sender_balance - output.gas_used() * h.default_gas_unit_price
+    );
+    assert_eq!(h.read_aptos_balance(receiver.address()), receiver_balance);
+    assert_eq!(h.sequence_number(sender.address()), sequence_number + 1);
+
+    // Without the limit the same transfer goes through.
+    set_transaction_output_limits(&mut h, TransactionOutputLimits::default());
+    let txn =
+        h.create_transaction_payload(&sender, aptos_account_transfer(*receiver.address(), 1000));
+    assert_eq!(
+        h.run(txn),
+        TransactionStatus::Keep(ExecutionStatus::Success)
+    );
+    assert_eq!(
+        h.read_aptos_balance(receiver.address()),
+        receiver_balance + 1000
+    );
+}
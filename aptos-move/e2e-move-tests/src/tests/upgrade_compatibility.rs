@@ -13,12 +13,14 @@
 // Currently, we are testing both the old and the new compatibility checker
 
 use crate::{assert_success, assert_vm_status, MoveHarness};
-use aptos_framework::BuildOptions;
+use aptos_framework::{BuildOptions, BuiltPackage};
 use aptos_package_builder::PackageBuilder;
 use aptos_types::{
     account_address::AccountAddress, on_chain_config::FeatureFlag, transaction::TransactionStatus,
 };
-use move_core_types::vm_status::StatusCode;
+use aptos_vm::verifier::package_upgrade::check_package_upgrade;
+use move_binary_format::compatibility::{CompatibilityIssue, FunctionChange};
+use move_core_types::{identifier::Identifier, vm_status::StatusCode};
 use rstest::rstest;
 
 #[rstest(use_new_checker, case(false), case(true))]
@@ -162,3 +164,65 @@ fn check_upgrade(old_decls: &str, new_decls: &str, use_new_checker: bool) -> Tra
     let path = builder.write_to_temp().unwrap();
     h.publish_package_with_options(&acc, path.path(), BuildOptions::move_2())
 }
+
+#[test]
+fn report_upgrade_issues() {
+    let mut h = MoveHarness::new();
+    let acc = h.new_account_at(AccountAddress::from_hex_literal("0x815").unwrap());
+
+    let mut builder = PackageBuilder::new("Package");
+    builder.add_source(
+        "m.move",
+        r#"
+        module 0x815::m {
+          struct S has key { x: u64 }
+          public fun f() {}
+          public fun g(_x: u64) {}
+        }
+        "#,
+    );
+    let path = builder.write_to_temp().unwrap();
+    assert_success!(h.publish_package_with_options(&acc, path.path(), BuildOptions::move_2()));
+
+    let mut builder = PackageBuilder::new("Package");
+    builder.add_source(
+        "m.move",
+        r#"
+        module 0x815::m {
+          struct S has key { x: u64, y: u64 }
+          public fun g(_x: u128) {}
+        }
+        "#,
+    );
+    builder.add_source("n.move", "module 0x815::n {}");
+    let path = builder.write_to_temp().unwrap();
+    let package = BuiltPackage::build(path.path().to_path_buf(), BuildOptions::move_2()).unwrap();
+
+    let report = check_package_upgrade(h.executor.get_state_view(), &package.extract_code())
+        .expect("modules should be checked");
+    assert!(!report.is_compatible());
+
+    let m = report
+        .modules
+        .iter()
+        .find(|module| module.module_id.name().as_str() == "m")
+        .unwrap();
+    assert!(!m.is_new);
+    let name = |s: &str| Identifier::new(s).unwrap();
+    assert_eq!(m.issues, vec![
+        CompatibilityIssue::StructLayoutChanged(name("S")),
+        CompatibilityIssue::FunctionRemoved(name("f")),
+        CompatibilityIssue::FunctionChanged {
+            name: name("g"),
+            change: FunctionChange::ParameterTypes,
+        },
+    ]);
+
+    let n = report
+        .modules
+        .iter()
+        .find(|module| module.module_id.name().as_str() == "n")
+        .unwrap();
+    assert!(n.is_new);
+    assert!(n.issues.is_empty());
+}
@@ -0,0 +1,69 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::MoveHarness;
+use aptos_cached_packages::aptos_stdlib::aptos_account_transfer;
+use aptos_types::{
+    access_path::AccessPath,
+    account_address::AccountAddress,
+    state_store::state_key::inner::StateKeyInner,
+    transaction::{ExecutionStatus, SignedTransaction},
+};
+use aptos_vm::{data_cache::AsMoveResolver, write_scope::WriteScope, AptosVM};
+use aptos_vm_environment::environment::AptosEnvironment;
+use aptos_vm_logging::log_schema::AdapterLogSchema;
+use aptos_vm_types::{module_and_script_storage::AsAptosCodeStorage, output::VMOutput};
+use claims::assert_ok_eq;
+use move_core_types::vm_status::{StatusCode, VMStatus};
+
+fn execute(
+    h: &MoveHarness,
+    txn: &SignedTransaction,
+    write_scope: Option<WriteScope>,
+) -> (VMStatus, VMOutput) {
+    let state_view = h.executor.get_state_view();
+    let env = AptosEnvironment::new(state_view);
+    let vm = AptosVM::new(env.clone(), state_view);
+    let log_context = AdapterLogSchema::new(state_view.id(), 0);
+    let resolver = state_view.as_move_resolver();
+    let code_storage = state_view.as_aptos_code_storage(env);
+    match write_scope {
+        Some(write_scope) => vm.execute_user_transaction_with_write_scope(
+            &resolver,
+            &code_storage,
+            txn,
+            write_scope,
+            &log_context,
+        ),
+        None => vm.execute_user_transaction(&resolver, &code_storage, txn, &log_context),
+    }
+}
+
+#[test]
+fn test_write_scope() {
+    let mut h = MoveHarness::new();
+    let sender = h.new_account_at(AccountAddress::from_hex_literal("0x121").unwrap());
+    let receiver = h.new_account_at(AccountAddress::from_hex_literal("0x122").unwrap());
+    let txn =
+        h.create_transaction_payload(&sender, aptos_account_transfer(*receiver.address(), 1000));
+
+    // A scope covering everything the transaction writes to lets it succeed.
+    let (_, output) = execute(&h, &txn, None);
+    let addresses = output
+        .concrete_write_set_iter()
+        .filter_map(|(key, _)| match key.inner() {
+            StateKeyInner::AccessPath(AccessPath { address, .. }) => Some(*address),
+            StateKeyInner::TableItem { handle, .. } => Some(handle.0),
+            StateKeyInner::Raw(_) => None,
+        });
+    let (_, output) = execute(&h, &txn, Some(WriteScope::new(addresses)));
+    assert_ok_eq!(output.status().as_kept_status(), ExecutionStatus::Success);
+
+    // Transferring to the receiver writes outside of a scope bound to the sender.
+    let (vm_status, output) = execute(&h, &txn, Some(WriteScope::new([*sender.address()])));
+    assert_eq!(vm_status.status_code(), StatusCode::ACCESS_DENIED);
+    assert_ok_eq!(
+        output.status().as_kept_status(),
+        ExecutionStatus::MiscellaneousError(Some(StatusCode::ACCESS_DENIED))
+    );
+}
@@ -32,7 +32,9 @@ use aptos_types::{
     block_executor::{
         config::{
             BlockExecutorConfig, BlockExecutorConfigFromOnchain, BlockExecutorLocalConfig,
-            BlockExecutorModuleCacheLocalConfig,
+            BlockExecutorModuleCacheLocalConfig, BlockSTMCommitterBackup,
+            BlockSTMCommitterBackupThresholds, BlockSTMProfilingLocalConfig,
+            BlockSTMScheduleReplayMode,
         },
         transaction_slice_metadata::TransactionSliceMetadata,
     },
@@ -674,6 +676,14 @@ impl FakeExecutor {
                 allow_fallback: self.allow_block_executor_fallback,
                 discard_failed_blocks: false,
                 module_cache_config: BlockExecutorModuleCacheLocalConfig::default(),
+                committer_backup: BlockSTMCommitterBackup::Disabled,
+                committer_backup_thresholds: BlockSTMCommitterBackupThresholds::default(),
+                profiling_config: BlockSTMProfilingLocalConfig::default(),
+                schedule_replay_mode: BlockSTMScheduleReplayMode::Disabled,
+                execution_order_hint: None,
+                pin_workers_to_numa_nodes: false,
+                block_gas_budget: None,
+                sequential_fallback_dump_dir: None,
             },
             onchain: onchain_config,
         };
@@ -858,6 +868,19 @@ impl FakeExecutor {
         ))
     }
 
+    /// Executes the transaction with the gas profiler, and writes its gas profiles (a speedscope
+    /// file and folded stacks of the execution, IO and storage costs) into the given directory.
+    pub fn execute_transaction_with_gas_profiles(
+        &self,
+        txn: SignedTransaction,
+        path: impl AsRef<Path>,
+    ) -> anyhow::Result<TransactionOutput> {
+        let name = format!("txn-{}", txn.committed_hash());
+        let (output, gas_log) = self.execute_transaction_with_gas_profiler(txn)?;
+        gas_log.generate_profiles(path, name)?;
+        Ok(output)
+    }
+
     fn trace<P: AsRef<Path>, T: Serialize>(dir: P, item: &T) -> usize {
         let dir = dir.as_ref();
         let seq = fs::read_dir(dir).expect("Unable to read trace dir").count();
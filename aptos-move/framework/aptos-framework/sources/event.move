@@ -27,6 +27,29 @@ module aptos_framework::event {
         vector::contains(&emitted_events<T>(), msg)
     }
 
+    #[test_only]
+    /// The expected event was not emitted
+    const EEVENT_NOT_EMITTED: u64 = 1;
+
+    #[test_only]
+    /// Abort with `EEVENT_NOT_EMITTED` unless an event equal to `msg` was emitted. On failure, the expected
+    /// event and the events of the same type which were emitted are printed in the test output, to show how
+    /// they differ.
+    public fun assert_event_emitted<T: drop + store>(msg: &T) {
+        use std::string;
+        use std::vector;
+        use aptos_std::debug;
+
+        let events = emitted_events<T>();
+        if (!vector::contains(&events, msg)) {
+            debug::print(&string::utf8(b"expected event:"));
+            debug::print(msg);
+            debug::print(&string::utf8(b"emitted events of the same type:"));
+            vector::for_each_ref(&events, |event| debug::print(event));
+            abort EEVENT_NOT_EMITTED
+        }
+    }
+
     #[deprecated]
     /// A handle for an event such that:
     /// 1. Other modules can emit events to this handle.
@@ -89,4 +112,23 @@ module aptos_framework::event {
         use std::vector;
         vector::contains(&emitted_events_by_handle(handle), msg)
     }
+
+    #[test_only]
+    #[event]
+    struct TestEvent has drop, store {
+        value: u64,
+    }
+
+    #[test]
+    fun test_assert_event_emitted() {
+        emit(TestEvent { value: 1 });
+        assert_event_emitted(&TestEvent { value: 1 });
+    }
+
+    #[test]
+    #[expected_failure(abort_code = EEVENT_NOT_EMITTED, location = Self)]
+    fun test_assert_event_emitted_fails_on_different_payload() {
+        emit(TestEvent { value: 1 });
+        assert_event_emitted(&TestEvent { value: 2 });
+    }
 }
@@ -9,4 +9,17 @@ module std::unit_test {
     /// This will cause a linking failure if an attempt is made to publish a
     /// test module in a VM that isn't in unit test mode.
     native public fun create_signers_for_testing(num_signers: u64): vector<signer>;
+
+    /// The gas used is not within the expected range
+    const EGAS_USED_OUT_OF_RANGE: u64 = 1;
+
+    /// Return the gas remaining for the test, in the units of the gas meter of the test
+    /// runner, as reported in the test statistics. Take it before the code to measure, and
+    /// pass it to `assert_gas_used_within` afterwards.
+    native public fun remaining_gas_for_testing(): u64;
+
+    /// Abort with `EGAS_USED_OUT_OF_RANGE` if the gas used since `remaining_gas_before`, as
+    /// returned by `remaining_gas_for_testing`, is not within `min_gas..=max_gas`. On failure,
+    /// the expected range and the actual gas used are printed in the test output.
+    native public fun assert_gas_used_within(remaining_gas_before: u64, min_gas: u64, max_gas: u64);
 }
@@ -6,7 +6,8 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use aptos_native_interface::{
-    safely_pop_arg, RawSafeNative, SafeNativeBuilder, SafeNativeContext, SafeNativeResult,
+    safely_pop_arg, RawSafeNative, SafeNativeBuilder, SafeNativeContext, SafeNativeError,
+    SafeNativeResult,
 };
 use move_core_types::account_address::AccountAddress;
 use move_vm_runtime::native_functions::NativeFunction;
@@ -44,16 +45,80 @@ fn native_create_signers_for_testing(
     Ok(smallvec![signers])
 }
 
+/***************************************************************************************************
+ * native fun remaining_gas_for_testing
+ *
+ *   gas cost: none
+ *
+ **************************************************************************************************/
+fn native_remaining_gas_for_testing(
+    context: &mut SafeNativeContext,
+    ty_args: Vec<Type>,
+    args: VecDeque<Value>,
+) -> SafeNativeResult<SmallVec<[Value; 1]>> {
+    debug_assert!(ty_args.is_empty());
+    debug_assert!(args.is_empty());
+
+    let remaining_gas: u64 = context.gas_balance().into();
+    Ok(smallvec![Value::u64(remaining_gas)])
+}
+
+/***************************************************************************************************
+ * native fun assert_gas_used_within
+ *
+ *   gas cost: none
+ *
+ **************************************************************************************************/
+/// Abort code of `assert_gas_used_within`, matching `EGAS_USED_OUT_OF_RANGE` in `std::unit_test`.
+const EGAS_USED_OUT_OF_RANGE: u64 = 1;
+
+fn native_assert_gas_used_within(
+    context: &mut SafeNativeContext,
+    ty_args: Vec<Type>,
+    mut args: VecDeque<Value>,
+) -> SafeNativeResult<SmallVec<[Value; 1]>> {
+    debug_assert!(ty_args.is_empty());
+    debug_assert!(args.len() == 3);
+
+    let max_gas = safely_pop_arg!(args, u64);
+    let min_gas = safely_pop_arg!(args, u64);
+    let remaining_gas_before = safely_pop_arg!(args, u64);
+
+    let remaining_gas: u64 = context.gas_balance().into();
+    let gas_used = remaining_gas_before.saturating_sub(remaining_gas);
+    if gas_used < min_gas || gas_used > max_gas {
+        println!(
+            "[gas] expected the gas used to be within {}..={}, but it was {}",
+            min_gas, max_gas, gas_used
+        );
+        return Err(SafeNativeError::Abort {
+            abort_code: EGAS_USED_OUT_OF_RANGE,
+        });
+    }
+
+    Ok(smallvec![])
+}
+
 /***************************************************************************************************
  * module
  **************************************************************************************************/
 pub fn make_all(
     builder: &SafeNativeBuilder,
 ) -> impl Iterator<Item = (String, NativeFunction)> + '_ {
-    let natives = [(
-        "create_signers_for_testing",
-        native_create_signers_for_testing as RawSafeNative,
-    )];
+    let natives = [
+        (
+            "create_signers_for_testing",
+            native_create_signers_for_testing as RawSafeNative,
+        ),
+        (
+            "remaining_gas_for_testing",
+            native_remaining_gas_for_testing as RawSafeNative,
+        ),
+        (
+            "assert_gas_used_within",
+            native_assert_gas_used_within as RawSafeNative,
+        ),
+    ];
 
     builder.make_named_natives(natives)
 }
@@ -0,0 +1,27 @@
+#[test_only]
+module std::unit_test_tests {
+    use std::unit_test;
+    use std::vector;
+
+    #[test]
+    fun test_gas_used_within() {
+        let remaining_gas_before = unit_test::remaining_gas_for_testing();
+        let v = vector[];
+        let i = 0;
+        while (i < 100) {
+            vector::push_back(&mut v, i);
+            i = i + 1;
+        };
+        assert!(unit_test::remaining_gas_for_testing() < remaining_gas_before, 0);
+        unit_test::assert_gas_used_within(remaining_gas_before, 1, 1000000);
+    }
+
+    #[test]
+    #[expected_failure(abort_code = 1, location = std::unit_test)]
+    fun test_gas_used_out_of_range() {
+        let remaining_gas_before = unit_test::remaining_gas_for_testing();
+        let v = vector[];
+        vector::push_back(&mut v, 1);
+        unit_test::assert_gas_used_within(remaining_gas_before, 1000000, 2000000);
+    }
+}
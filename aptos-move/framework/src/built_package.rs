@@ -76,6 +76,9 @@ pub struct BuildOptions {
     pub with_error_map: bool,
     #[clap(long)]
     pub with_docs: bool,
+    /// Also write a JSON report of the size and complexity of each module of the package
+    #[clap(long)]
+    pub with_metrics_report: bool,
     /// Installation directory for compiled artifacts. Defaults to `<package>/build`.
     #[clap(long, value_parser)]
     pub install_dir: Option<PathBuf>,
@@ -115,6 +118,7 @@ impl Default for BuildOptions {
             with_source_maps: false,
             with_error_map: true,
             with_docs: false,
+            with_metrics_report: false,
             install_dir: None,
             named_addresses: Default::default(),
             override_std: None,
@@ -186,6 +190,7 @@ pub fn build_model(
         architecture: None,
         generate_abis: false,
         generate_docs: false,
+        generate_metrics_report: false,
         generate_move_model: false,
         full_model_generation: false,
         install_dir: None,
@@ -242,6 +247,7 @@ impl BuiltPackage {
                 architecture: None,
                 generate_abis: options.with_abis,
                 generate_docs: false,
+                generate_metrics_report: options.with_metrics_report,
                 generate_move_model: true,
                 full_model_generation: options.check_test_code,
                 install_dir: options.install_dir.clone(),
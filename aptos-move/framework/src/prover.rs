@@ -17,7 +17,7 @@ use move_prover::cli::Options;
 use std::{
     collections::{BTreeMap, BTreeSet},
     fs,
-    path::Path,
+    path::{Path, PathBuf},
     time::Instant,
 };
 use tempfile::TempDir;
@@ -114,6 +114,12 @@ pub struct ProverOptions {
     #[clap(long = "skip-instance-check")]
     pub skip_instance_check: bool,
 
+    /// Directory of a cache of the verified functions. Functions which did not change since
+    /// they were verified, including their specifications and the functions they call, are not
+    /// verified again. The directory can be shared between packages.
+    #[clap(long)]
+    pub verification_cache_dir: Option<PathBuf>,
+
     #[clap(skip)]
     pub for_test: bool,
 }
@@ -140,6 +146,7 @@ impl Default for ProverOptions {
             benchmark: false,
             for_test: false,
             skip_instance_check: false,
+            verification_cache_dir: None,
         }
     }
 }
@@ -276,6 +283,9 @@ impl ProverOptions {
                 skip_instance_check: self.skip_instance_check,
                 ..Default::default()
             },
+            verification_cache_dir: self
+                .verification_cache_dir
+                .map(|dir| dir.display().to_string()),
             ..Default::default()
         };
         if self.for_test {
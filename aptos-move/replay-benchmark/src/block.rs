@@ -10,7 +10,9 @@ use aptos_logger::error;
 use aptos_types::{
     block_executor::config::{
         BlockExecutorConfig, BlockExecutorConfigFromOnchain, BlockExecutorLocalConfig,
-        BlockExecutorModuleCacheLocalConfig,
+        BlockExecutorModuleCacheLocalConfig, BlockSTMCommitterBackup,
+        BlockSTMCommitterBackupThresholds, BlockSTMProfilingLocalConfig,
+        BlockSTMScheduleReplayMode,
     },
     state_store::{state_key::StateKey, state_value::StateValue, StateView},
     transaction::{TransactionOutput, Version},
@@ -26,6 +28,14 @@ fn block_execution_config(concurrency_level: usize) -> BlockExecutorConfig {
             allow_fallback: true,
             discard_failed_blocks: false,
             module_cache_config: BlockExecutorModuleCacheLocalConfig::default(),
+            committer_backup: BlockSTMCommitterBackup::Disabled,
+            committer_backup_thresholds: BlockSTMCommitterBackupThresholds::default(),
+            profiling_config: BlockSTMProfilingLocalConfig::default(),
+            schedule_replay_mode: BlockSTMScheduleReplayMode::Disabled,
+            execution_order_hint: None,
+            pin_workers_to_numa_nodes: false,
+            block_gas_budget: None,
+            sequential_fallback_dump_dir: None,
         },
         // For replay, there is no block limit.
         onchain: BlockExecutorConfigFromOnchain::new_no_block_limit(),
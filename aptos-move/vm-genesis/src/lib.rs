@@ -112,6 +112,9 @@ pub struct GenesisConfiguration {
     pub jwk_consensus_config_override: Option<OnChainJWKConsensusConfig>,
     pub initial_jwks: Vec<IssuerJWK>,
     pub keyless_groth16_vk_override: Option<Groth16VerificationKey>,
+    /// Accounts to create and fund at genesis, in addition to the validators. Only used by
+    /// `encode_genesis_change_set`, mainnet genesis takes its accounts separately.
+    pub initial_accounts: Vec<AccountBalance>,
 }
 
 pub static GENESIS_KEYPAIR: Lazy<(Ed25519PrivateKey, Ed25519PublicKey)> = Lazy::new(|| {
@@ -297,6 +300,15 @@ pub fn encode_genesis_change_set(
     initialize_randomness_config(&mut session, &module_storage, randomness_config);
     initialize_randomness_resources(&mut session, &module_storage);
     initialize_on_chain_governance(&mut session, &module_storage, genesis_config);
+    // Genesis only funds the accounts it creates, so an initial account must not also be a
+    // validator account.
+    if !genesis_config.initial_accounts.is_empty() {
+        create_accounts(
+            &mut session,
+            &module_storage,
+            &genesis_config.initial_accounts,
+        );
+    }
     create_and_initialize_validators(&mut session, &module_storage, validators);
     if genesis_config.is_test {
         allow_core_resources_to_set_version(&mut session, &module_storage);
@@ -1256,6 +1268,7 @@ pub fn generate_test_genesis(
             jwk_consensus_config_override: None,
             initial_jwks: vec![],
             keyless_groth16_vk_override: None,
+            initial_accounts: vec![],
         },
         &OnChainConsensusConfig::default_for_genesis(),
         &OnChainExecutionConfig::default_for_genesis(),
@@ -1308,6 +1321,7 @@ fn mainnet_genesis_config() -> GenesisConfiguration {
         jwk_consensus_config_override: None,
         initial_jwks: vec![],
         keyless_groth16_vk_override: None,
+        initial_accounts: vec![],
     }
 }
 
@@ -20,7 +20,9 @@ use anyhow::anyhow;
 use aptos_admin_service::AdminService;
 use aptos_api::bootstrap as bootstrap_api;
 use aptos_build_info::build_information;
-use aptos_config::config::{merge_node_config, NodeConfig, PersistableConfig};
+use aptos_config::config::{
+    effective_config_report, merge_node_config, ConfigOverride, NodeConfig, PersistableConfig,
+};
 use aptos_framework::ReleaseBundle;
 use aptos_logger::{prelude::*, telemetry_log_writer::TelemetryLog, Level, LoggerFilterUpdater};
 use aptos_state_sync_driver::driver_factory::StateSyncRuntimes;
@@ -57,6 +59,15 @@ pub struct AptosNodeArgs {
     #[cfg_attr(target_os = "linux", clap(required_unless_present_any = ["stacktrace"]))]
     config: Option<PathBuf>,
 
+    /// Overrides a field of the node configuration file, e.g.,
+    /// `--config-override execution.concurrency_level=16`. Can be repeated.
+    ///
+    /// Fields can also be overridden with `APTOS_NODE_CONFIG__` environment variables
+    /// (e.g., `APTOS_NODE_CONFIG__EXECUTION__CONCURRENCY_LEVEL=16`), but the command
+    /// line takes precedence over the environment.
+    #[clap(long = "config-override", value_parser = ConfigOverride::parse_cli)]
+    config_overrides: Vec<ConfigOverride>,
+
     /// Directory to run the test mode in.
     ///
     /// Repeated runs will start up from previous state.
@@ -98,6 +109,11 @@ pub struct AptosNodeArgs {
     #[clap(long)]
     info: bool,
 
+    /// Print exactly what this node would send via telemetry, with the telemetry opt-outs
+    /// (e.g., `APTOS_DISABLE_TELEMETRY_HARDWARE`) applied, without starting the node.
+    #[clap(long)]
+    dump_telemetry: bool,
+
     #[cfg(target_os = "linux")]
     /// Start as a child process to collect thread dump.
     /// See rstack-self crate for more details.
@@ -168,8 +184,22 @@ impl AptosNodeArgs {
                 )
             }
 
+            // Gather the config overrides from the environment and the command line
+            let mut config_overrides = ConfigOverride::from_env_vars(std::env::vars())
+                .unwrap_or_else(|error| {
+                    panic!(
+                        "Failed to parse the node config overrides from the environment! Error: {:?}",
+                        error
+                    )
+                });
+            config_overrides.extend(self.config_overrides);
+
             // A config file exists, attempt to parse the config
-            let config = NodeConfig::load_from_path(config_path.clone()).unwrap_or_else(|error| {
+            let config = NodeConfig::load_from_path_with_overrides(
+                config_path.clone(),
+                config_overrides.clone(),
+            )
+            .unwrap_or_else(|error| {
                 panic!(
                     "Failed to load the node config file! Given file path: {:?}. Error: {:?}",
                     config_path.display(),
@@ -177,6 +207,20 @@ impl AptosNodeArgs {
                 )
             });
 
+            if self.dump_telemetry {
+                match aptos_telemetry::service::dump_telemetry(&config, build_information!()) {
+                    Ok(report) => println!("{}", report),
+                    Err(error) => println!("Failed to dump the telemetry: {:?}", error),
+                }
+                return;
+            }
+
+            // Print the effective config, so that misconfigurations are easy to spot
+            match effective_config_report(&config, &config_overrides) {
+                Ok(report) => println!("{}", report),
+                Err(error) => println!("Failed to print the effective node config: {:?}", error),
+            }
+
             // Start the node
             start(config, None, true).expect("Node should start correctly");
         };
@@ -209,6 +253,51 @@ pub struct AptosHandle {
     _indexer_db_runtime: Option<Runtime>,
 }
 
+impl AptosHandle {
+    /// Exports the stats of the runtimes of the node as metrics
+    fn register_runtime_metrics(&self) {
+        let runtimes = [
+            ("api", self._api_runtime.as_ref()),
+            ("backup", self._backup_runtime.as_ref()),
+            (
+                "consensus_observer",
+                self._consensus_observer_runtime.as_ref(),
+            ),
+            (
+                "consensus_publisher",
+                self._consensus_publisher_runtime.as_ref(),
+            ),
+            ("consensus", self._consensus_runtime.as_ref()),
+            ("dkg", self._dkg_runtime.as_ref()),
+            ("indexer_grpc", self._indexer_grpc_runtime.as_ref()),
+            ("indexer", self._indexer_runtime.as_ref()),
+            (
+                "indexer_table_info",
+                self._indexer_table_info_runtime.as_ref(),
+            ),
+            ("jwk_consensus", self._jwk_consensus_runtime.as_ref()),
+            ("mempool", Some(&self._mempool_runtime)),
+            (
+                "peer_monitoring_service",
+                Some(&self._peer_monitoring_service_runtime),
+            ),
+            ("telemetry", self._telemetry_runtime.as_ref()),
+            ("indexer_db", self._indexer_db_runtime.as_ref()),
+        ];
+        for (name, runtime) in runtimes {
+            if let Some(runtime) = runtime {
+                aptos_node_resource_metrics::register_tokio_runtime(name, runtime.handle().clone());
+            }
+        }
+        for (index, runtime) in self._network_runtimes.iter().enumerate() {
+            aptos_node_resource_metrics::register_tokio_runtime(
+                format!("network_{}", index),
+                runtime.handle().clone(),
+            );
+        }
+    }
+}
+
 pub fn start(
     config: NodeConfig,
     log_file: Option<PathBuf>,
@@ -771,7 +860,7 @@ pub fn setup_environment_and_start_node(
         &mut admin_service,
     );
 
-    Ok(AptosHandle {
+    let aptos_handle = AptosHandle {
         _admin_service: admin_service,
         _api_runtime: api_runtime,
         _backup_runtime: backup_service,
@@ -789,7 +878,13 @@ pub fn setup_environment_and_start_node(
         _state_sync_runtimes: state_sync_runtimes,
         _telemetry_runtime: telemetry_runtime,
         _indexer_db_runtime: internal_indexer_db_runtime,
-    })
+    };
+
+    // Export the runtime and allocator stats
+    aptos_node_resource_metrics::register_node_metrics_collector();
+    aptos_handle.register_runtime_metrics();
+
+    Ok(aptos_handle)
 }
 
 #[test]
@@ -37,6 +37,9 @@ pub fn create_logger(
     logger_builder
         .channel_size(node_config.logger.chan_size)
         .is_async(node_config.logger.is_async)
+        .overflow_policy(node_config.logger.overflow_policy)
+        .recent_logs_capacity(node_config.logger.recent_logs_capacity)
+        .recent_logs_level(node_config.logger.recent_logs_level)
         .level(node_config.logger.level)
         .telemetry_level(node_config.logger.telemetry_level)
         .enable_telemetry_flush(node_config.logger.enable_telemetry_flush)
@@ -45,6 +48,7 @@ pub fn create_logger(
         logger_builder.enable_backtrace();
     }
     if let Some(log_file) = log_file {
+        logger_builder.recent_logs_dump_path(log_file.with_extension("recent.log"));
         logger_builder.printer(Box::new(FileWriter::new(log_file)));
     }
     if node_config.logger.enable_telemetry_remote_log {
@@ -9,13 +9,21 @@ use aptos_db_indexer::db_indexer::InternalIndexerDB;
 use aptos_executor::db_bootstrapper::maybe_bootstrap;
 use aptos_indexer_grpc_table_info::internal_indexer_db_service::InternalIndexerDBService;
 use aptos_logger::{debug, info};
-use aptos_storage_interface::{DbReader, DbReaderWriter};
+use aptos_storage_interface::{
+    commit_recorder::COMMIT_RECORDER, state_read_profiler::STATE_READ_PROFILER, DbReader,
+    DbReaderWriter,
+};
 use aptos_types::{
     ledger_info::LedgerInfoWithSignatures, transaction::Version, waypoint::Waypoint,
 };
 use aptos_vm::aptos_vm::AptosVMBlockExecutor;
 use either::Either;
-use std::{fs, path::Path, sync::Arc, time::Instant};
+use std::{
+    fs,
+    path::Path,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 use tokio::{
     runtime::Runtime,
     sync::watch::{channel, Receiver as WatchReceiver},
@@ -185,6 +193,25 @@ pub fn initialize_database_and_checkpoints(
         create_rocksdb_checkpoint_and_change_working_dir(node_config, working_dir);
     }
 
+    // Start profiling the state reads, if enabled
+    let profiler_config = node_config.storage.state_read_profiler;
+    if profiler_config.enable {
+        info!(
+            "Profiling the state reads, keeping the latest {}.",
+            profiler_config.capacity
+        );
+        STATE_READ_PROFILER.enable(profiler_config.capacity);
+    }
+
+    // Start recording the slow commits, if enabled
+    let recorder_config = node_config.storage.slow_commit_recorder;
+    if recorder_config.enable {
+        COMMIT_RECORDER.enable(
+            Duration::from_millis(recorder_config.threshold_ms),
+            recorder_config.capacity,
+        );
+    }
+
     // Open the database
     let instant = Instant::now();
     let (_aptos_db, db_rw, backup_service, indexer_db_opt, update_receiver) =
@@ -49,6 +49,7 @@ pub fn fetch_chain_id(db: &DbReaderWriter) -> anyhow::Result<ChainId> {
 /// Sets the Aptos VM configuration based on the node configurations
 pub fn set_aptos_vm_configurations(node_config: &NodeConfig) {
     set_paranoid_type_checks(node_config.execution.paranoid_type_verification);
+    AptosVM::log_paranoid_check_diagnostics();
     let effective_concurrency_level = if node_config.execution.concurrency_level == 0 {
         min(
             DEFAULT_EXECUTION_CONCURRENCY_LEVEL,
@@ -59,6 +60,11 @@ pub fn set_aptos_vm_configurations(node_config: &NodeConfig) {
     };
     AptosVM::set_concurrency_level_once(effective_concurrency_level as usize);
     AptosVM::set_discard_failed_blocks(node_config.execution.discard_failed_blocks);
+    AptosVM::set_committer_backup(node_config.execution.committer_backup);
+    AptosVM::set_pin_workers_to_numa_nodes(node_config.execution.pin_workers_to_numa_nodes);
+    if let Some(dump_dir) = &node_config.execution.sequential_fallback_dump_dir {
+        AptosVM::set_sequential_fallback_dump_dir(dump_dir.clone());
+    }
     AptosVM::set_num_proof_reading_threads_once(
         node_config.execution.num_proof_reading_threads as usize,
     );
@@ -49,6 +49,10 @@ pub struct ApiConfig {
     /// Enables transaction simulation
     #[serde(default = "default_enabled")]
     pub transaction_simulation_enabled: bool,
+    /// Enables simulating transactions with a write scope, i.e., a set of addresses outside of
+    /// which the transaction must not write
+    #[serde(default = "default_disabled")]
+    pub simulation_write_scope_enabled: bool,
     /// Maximum number of transactions that can be sent with the Batch submit API
     pub max_submit_transaction_batch_size: usize,
     /// Maximum page size for transaction paginated APIs
@@ -89,6 +93,13 @@ pub struct ApiConfig {
     pub wait_by_hash_poll_interval_ms: u64,
     /// The number of active wait_by_hash requests that can be active at any given time.
     pub wait_by_hash_max_active_connections: usize,
+    /// Enables the transaction stream endpoint (server-sent events)
+    #[serde(default = "default_enabled")]
+    pub transaction_stream_enabled: bool,
+    /// The interval at which the transaction streams poll the storage for new transactions.
+    pub transaction_stream_poll_interval_ms: u64,
+    /// The number of transaction streams that can be open at any given time.
+    pub transaction_stream_max_active_connections: usize,
 }
 
 const DEFAULT_ADDRESS: &str = "127.0.0.1";
@@ -124,6 +135,7 @@ impl Default for ApiConfig {
             encode_submission_enabled: default_enabled(),
             transaction_submission_enabled: default_enabled(),
             transaction_simulation_enabled: default_enabled(),
+            simulation_write_scope_enabled: default_disabled(),
             max_submit_transaction_batch_size: DEFAULT_MAX_SUBMIT_TRANSACTION_BATCH_SIZE,
             max_block_transactions_page_size: *MAX_RECEIVING_BLOCK_TXNS as u16,
             max_transactions_page_size: DEFAULT_MAX_PAGE_SIZE,
@@ -141,6 +153,9 @@ impl Default for ApiConfig {
             wait_by_hash_timeout_ms: 1_000,
             wait_by_hash_poll_interval_ms: 20,
             wait_by_hash_max_active_connections: 100,
+            transaction_stream_enabled: default_enabled(),
+            transaction_stream_poll_interval_ms: 200,
+            transaction_stream_max_active_connections: 100,
         }
     }
 }
@@ -190,6 +205,16 @@ impl ConfigSanitizer for ApiConfig {
             ));
         }
 
+        // Verify that the transaction streams don't poll the storage in a busy loop
+        if api_config.transaction_stream_enabled
+            && api_config.transaction_stream_poll_interval_ms == 0
+        {
+            return Err(Error::ConfigSanitizerFailed(
+                sanitizer_name,
+                "transaction_stream_poll_interval_ms must be greater than 0!".into(),
+            ));
+        }
+
         // We don't support Block ID based simulation filters.
         for rule in api_config.simulation_filter.rules() {
             if let Matcher::BlockId(_) = rule.matcher() {
@@ -6,7 +6,7 @@ use crate::config::{
     SecureBackend,
 };
 use aptos_secure_storage::{KVStorage, Storage};
-use aptos_types::{chain_id::ChainId, waypoint::Waypoint};
+use aptos_types::{chain_id::ChainId, waypoint::Waypoint, waypoint_bundle::WaypointBundle};
 use poem_openapi::Enum as PoemEnum;
 use serde::{Deserialize, Serialize};
 use std::{fmt, fs, path::PathBuf, str::FromStr};
@@ -59,6 +59,13 @@ pub enum WaypointConfig {
     FromConfig(Waypoint),
     FromFile(PathBuf),
     FromStorage(SecureBackend),
+    /// A waypoint bundle (see `WaypointBundle`), verified from the trusted root, e.g. the genesis
+    /// waypoint. The node can bootstrap from the bundle without fetching the epoch ending ledger
+    /// infos from the network.
+    FromBundle {
+        path: PathBuf,
+        trusted_root: Waypoint,
+    },
     None,
 }
 
@@ -104,11 +111,45 @@ impl WaypointConfig {
                     .value;
                 Some(waypoint)
             },
+            WaypointConfig::FromBundle { .. } => {
+                self.waypoint_bundle().map(|bundle| bundle.waypoint())
+            },
             WaypointConfig::None => None,
         };
         waypoint.expect("waypoint should be present")
     }
 
+    /// Reads and verifies the waypoint bundle, if the waypoint comes from one.
+    pub fn waypoint_bundle(&self) -> Option<WaypointBundle> {
+        let (bundle_path, trusted_root) = match &self {
+            WaypointConfig::FromBundle { path, trusted_root } => (path, trusted_root),
+            _ => return None,
+        };
+        let content = fs::read(bundle_path).unwrap_or_else(|error| {
+            panic!(
+                "Failed to read waypoint bundle file {:?}. Error: {:?}",
+                bundle_path.display(),
+                error
+            )
+        });
+        let bundle: WaypointBundle = bcs::from_bytes(&content).unwrap_or_else(|error| {
+            panic!(
+                "Failed to parse waypoint bundle file {:?}. Error: {:?}",
+                bundle_path.display(),
+                error
+            )
+        });
+        if let Err(error) = bundle.verify(trusted_root) {
+            panic!(
+                "Failed to verify waypoint bundle {:?} from the trusted root {}. Error: {:?}",
+                bundle_path.display(),
+                trusted_root,
+                error
+            );
+        }
+        Some(bundle)
+    }
+
     pub fn genesis_waypoint(&self) -> Waypoint {
         match &self {
             WaypointConfig::FromStorage(backend) => {
@@ -118,6 +159,7 @@ impl WaypointConfig {
                     .expect("Unable to read waypoint")
                     .value
             },
+            WaypointConfig::FromBundle { trusted_root, .. } => *trusted_root,
             _ => self.waypoint(),
         }
     }
@@ -0,0 +1,260 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::config::{Error, NodeConfig};
+use serde_yaml::{Mapping, Value};
+use std::fmt;
+
+/// The prefix of the environment variables overriding node config fields. Path
+/// segments are separated by double underscores, e.g., the variable
+/// `APTOS_NODE_CONFIG__EXECUTION__CONCURRENCY_LEVEL=16` sets `execution.concurrency_level`.
+pub const CONFIG_OVERRIDE_ENV_PREFIX: &str = "APTOS_NODE_CONFIG__";
+const CONFIG_OVERRIDE_ENV_SEPARATOR: &str = "__";
+
+/// The value displayed in place of secrets in the effective config report
+pub const REDACTED_VALUE: &str = "<redacted>";
+
+// Config fields with these (lowercase) suffixes are redacted from the effective config report
+const REDACTED_FIELD_SUFFIXES: [&str; 4] = ["key", "password", "secret", "token"];
+
+/// The layers the node config is assembled from, in increasing order of precedence
+#[derive(Clone, Copy, Debug, Eq, Ord, PartialEq, PartialOrd)]
+pub enum ConfigLayer {
+    Environment,
+    CommandLine,
+}
+
+impl fmt::Display for ConfigLayer {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ConfigLayer::Environment => write!(f, "environment"),
+            ConfigLayer::CommandLine => write!(f, "command line"),
+        }
+    }
+}
+
+/// A single node config field set on top of the config file
+#[derive(Clone, Debug, PartialEq)]
+pub struct ConfigOverride {
+    pub layer: ConfigLayer,
+    pub path: Vec<String>,
+    pub value: Value,
+}
+
+impl ConfigOverride {
+    /// Parses a command line override of the form `path.to.field=value`. The value
+    /// is parsed as YAML, so numbers, booleans and lists keep their type.
+    pub fn parse_cli(config_override: &str) -> Result<Self, Error> {
+        let (path, value) = config_override.split_once('=').ok_or_else(|| {
+            Error::Unexpected(format!(
+                "Invalid config override {:?}, expected path.to.field=value",
+                config_override
+            ))
+        })?;
+        Self::new(
+            ConfigLayer::CommandLine,
+            path.split('.').map(str::to_string).collect(),
+            value,
+        )
+    }
+
+    /// Extracts the overrides from the given environment variables (e.g., `std::env::vars()`),
+    /// ignoring the variables without the [`CONFIG_OVERRIDE_ENV_PREFIX`].
+    pub fn from_env_vars(
+        env_vars: impl IntoIterator<Item = (String, String)>,
+    ) -> Result<Vec<Self>, Error> {
+        let mut overrides = env_vars
+            .into_iter()
+            .filter_map(|(name, value)| {
+                let path = name.strip_prefix(CONFIG_OVERRIDE_ENV_PREFIX)?;
+                let path = path
+                    .split(CONFIG_OVERRIDE_ENV_SEPARATOR)
+                    .map(str::to_lowercase)
+                    .collect();
+                Some(Self::new(ConfigLayer::Environment, path, &value))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // Sort the overrides so that they apply deterministically
+        overrides.sort_by(|a, b| a.path.cmp(&b.path));
+        Ok(overrides)
+    }
+
+    fn new(layer: ConfigLayer, path: Vec<String>, value: &str) -> Result<Self, Error> {
+        if path.iter().any(|segment| segment.is_empty()) {
+            return Err(Error::Unexpected(format!(
+                "Invalid config override path {:?}, segments cannot be empty",
+                path.join(".")
+            )));
+        }
+        let value = serde_yaml::from_str(value)
+            .map_err(|error| Error::Yaml(format!("config override {}", path.join(".")), error))?;
+        Ok(Self { layer, path, value })
+    }
+
+    /// Sets the overridden field in the given config YAML, creating
+    /// the intermediate mappings if they don't exist.
+    fn apply(&self, config_yaml: &mut Value) -> Result<(), Error> {
+        let mut current = config_yaml;
+        for segment in &self.path {
+            if current.is_null() {
+                *current = Value::Mapping(Mapping::new());
+            }
+            let mapping = current.as_mapping_mut().ok_or_else(|| {
+                Error::Unexpected(format!(
+                    "Failed to apply the config override {}, {:?} is not a mapping",
+                    self, segment
+                ))
+            })?;
+            let segment = Value::String(segment.clone());
+            if !mapping.contains_key(&segment) {
+                mapping.insert(segment.clone(), Value::Null);
+            }
+            current = mapping
+                .get_mut(&segment)
+                .expect("The segment was inserted above");
+        }
+        *current = self.value.clone();
+        Ok(())
+    }
+}
+
+impl fmt::Display for ConfigOverride {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{} (from {})", self.path.join("."), self.layer)
+    }
+}
+
+/// Applies the overrides to the config YAML. Overrides of higher precedence layers
+/// are applied last, so that they take precedence over the lower ones.
+pub fn apply_config_overrides(
+    config_yaml: &mut Value,
+    overrides: &[ConfigOverride],
+) -> Result<(), Error> {
+    let mut overrides: Vec<_> = overrides.iter().collect();
+    overrides.sort_by_key(|config_override| config_override.layer);
+    for config_override in overrides {
+        config_override.apply(config_yaml)?;
+    }
+    Ok(())
+}
+
+/// Returns a human readable report of the effective node config, listing the
+/// applied overrides followed by the full config, with all secrets redacted.
+pub fn effective_config_report(
+    node_config: &NodeConfig,
+    overrides: &[ConfigOverride],
+) -> Result<String, Error> {
+    let mut config_yaml = serde_yaml::to_value(node_config)
+        .map_err(|error| Error::Yaml("effective node config".into(), error))?;
+    redact_secrets(&mut config_yaml);
+    let config_yaml = serde_yaml::to_string(&config_yaml)
+        .map_err(|error| Error::Yaml("effective node config".into(), error))?;
+
+    let mut report = vec!["Effective node config (secrets redacted):".to_string()];
+    if overrides.is_empty() {
+        report.push("\tNo config overrides were applied".into());
+    } else {
+        report.push("\tApplied config overrides:".into());
+        for config_override in overrides {
+            report.push(format!("\t\t- {}", config_override));
+        }
+    }
+    report.push(config_yaml);
+    Ok(report.join("\n"))
+}
+
+/// Replaces the values of all secret fields with [`REDACTED_VALUE`]
+fn redact_secrets(config_yaml: &mut Value) {
+    match config_yaml {
+        Value::Mapping(mapping) => {
+            for (field, value) in mapping.iter_mut() {
+                let is_secret = field.as_str().map_or(false, |field| {
+                    let field = field.to_lowercase();
+                    REDACTED_FIELD_SUFFIXES
+                        .iter()
+                        .any(|suffix| field.ends_with(suffix))
+                });
+                if is_secret && !value.is_null() {
+                    *value = Value::String(REDACTED_VALUE.into());
+                } else {
+                    redact_secrets(value);
+                }
+            }
+        },
+        Value::Sequence(values) => values.iter_mut().for_each(redact_secrets),
+        _ => {},
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{Identity, NetworkConfig};
+    use aptos_crypto::{x25519, Uniform};
+    use aptos_types::account_address::AccountAddress;
+
+    #[test]
+    fn test_override_precedence() {
+        let mut config_yaml: Value = serde_yaml::from_str(
+            r#"
+            execution:
+                concurrency_level: 4
+            "#,
+        )
+        .unwrap();
+
+        // The command line takes precedence over the environment, regardless of the order
+        let mut overrides =
+            vec![ConfigOverride::parse_cli("execution.concurrency_level=16").unwrap()];
+        overrides.extend(
+            ConfigOverride::from_env_vars([
+                (
+                    "APTOS_NODE_CONFIG__EXECUTION__CONCURRENCY_LEVEL".to_string(),
+                    "8".to_string(),
+                ),
+                (
+                    "APTOS_NODE_CONFIG__MEMPOOL__CAPACITY".to_string(),
+                    "1000".to_string(),
+                ),
+                ("UNRELATED_VARIABLE".to_string(), "true".to_string()),
+            ])
+            .unwrap(),
+        );
+        assert_eq!(overrides.len(), 3);
+        apply_config_overrides(&mut config_yaml, &overrides).unwrap();
+
+        let node_config: NodeConfig = serde_yaml::from_value(config_yaml).unwrap();
+        assert_eq!(node_config.execution.concurrency_level, 16);
+        assert_eq!(node_config.mempool.capacity, 1000);
+    }
+
+    #[test]
+    fn test_invalid_overrides() {
+        assert!(ConfigOverride::parse_cli("execution.concurrency_level").is_err());
+        assert!(ConfigOverride::parse_cli("execution..concurrency_level=1").is_err());
+
+        // Overriding a field inside a scalar fails
+        let mut config_yaml: Value = serde_yaml::from_str("execution: 1").unwrap();
+        let config_override = ConfigOverride::parse_cli("execution.concurrency_level=1").unwrap();
+        assert!(apply_config_overrides(&mut config_yaml, &[config_override]).is_err());
+    }
+
+    #[test]
+    fn test_effective_config_report_redacts_secrets() {
+        let key = x25519::PrivateKey::generate_for_testing();
+        let mut network_config = NetworkConfig::default();
+        network_config.identity = Identity::from_config(key.clone(), AccountAddress::random());
+        let node_config = NodeConfig {
+            full_node_networks: vec![network_config],
+            ..Default::default()
+        };
+
+        let overrides = vec![ConfigOverride::parse_cli("mempool.capacity=10").unwrap()];
+        let report = effective_config_report(&node_config, &overrides).unwrap();
+        assert!(report.contains("mempool.capacity (from command line)"));
+        assert!(report.contains(REDACTED_VALUE));
+        let serialized_key = serde_yaml::to_value(&key).unwrap();
+        assert!(!report.contains(serialized_key.as_str().unwrap()));
+    }
+}
@@ -78,6 +78,9 @@ pub struct ConsensusConfig {
     // must match one of the CHAIN_HEALTH_WINDOW_SIZES values.
     pub window_for_chain_health: usize,
     pub chain_health_backoff: Vec<ChainHealthBackoffValues>,
+    // Reduces proposal sizes when the batches created by this node take long to get their
+    // proofs of store, i.e. quorum store batch dissemination lags.
+    pub batch_backpressure: Vec<BatchBackpressureValues>,
     // Deprecated
     pub qc_aggregator_type: QcAggregatorType,
     // Max blocks allowed for block retrieval requests
@@ -94,6 +97,46 @@ pub struct ConsensusConfig {
     pub optimistic_sig_verification: bool,
     pub enable_round_timeout_msg: bool,
     pub enable_pipeline: bool,
+    pub message_capture: MessageCaptureConfig,
+}
+
+/// Captures the consensus messages received by the node to disk, so that the rounds leading
+/// to an incident can later be replayed offline.
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MessageCaptureConfig {
+    pub enabled: bool,
+    /// Directory the messages are written to, relative to the data directory unless absolute
+    pub capture_dir: PathBuf,
+    /// Number of most recent rounds kept on disk, older rounds are deleted
+    pub max_rounds: usize,
+    #[serde(skip)]
+    data_dir: PathBuf,
+}
+
+impl Default for MessageCaptureConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            capture_dir: PathBuf::from("consensus_capture"),
+            max_rounds: 1000,
+            data_dir: PathBuf::from("/opt/aptos/data"),
+        }
+    }
+}
+
+impl MessageCaptureConfig {
+    pub fn capture_dir(&self) -> PathBuf {
+        if self.capture_dir.is_relative() {
+            self.data_dir.join(&self.capture_dir)
+        } else {
+            self.capture_dir.clone()
+        }
+    }
+
+    pub fn set_data_dir(&mut self, data_dir: PathBuf) {
+        self.data_dir = data_dir;
+    }
 }
 
 /// Deprecated
@@ -156,6 +199,16 @@ pub struct ChainHealthBackoffValues {
     pub backoff_proposal_delay_ms: u64,
 }
 
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
+pub struct BatchBackpressureValues {
+    // Activates if the local batches still waiting for a proof of store exceed this many bytes,
+    pub back_pressure_pending_batch_bytes_limit: u64,
+    // or if proofs of store of local batches take longer than this to form.
+    pub back_pressure_proof_latency_limit_ms: u64,
+    pub max_sending_block_txns_after_filtering_override: u64,
+    pub max_sending_block_bytes_override: u64,
+}
+
 impl Default for ConsensusConfig {
     fn default() -> ConsensusConfig {
         ConsensusConfig {
@@ -304,6 +357,8 @@ impl Default for ConsensusConfig {
                     backoff_proposal_delay_ms: 300,
                 },
             ],
+            // Disabled by default, so that proposal sizes don't change unless configured.
+            batch_backpressure: vec![],
             qc_aggregator_type: QcAggregatorType::default(),
             // This needs to fit into the network message size, so with quorum store it can be much bigger
             max_blocks_per_sending_request: 10,
@@ -325,13 +380,15 @@ impl Default for ConsensusConfig {
             optimistic_sig_verification: true,
             enable_round_timeout_msg: true,
             enable_pipeline: false,
+            message_capture: MessageCaptureConfig::default(),
         }
     }
 }
 
 impl ConsensusConfig {
     pub fn set_data_dir(&mut self, data_dir: PathBuf) {
-        self.safety_rules.set_data_dir(data_dir);
+        self.safety_rules.set_data_dir(data_dir.clone());
+        self.message_capture.set_data_dir(data_dir);
     }
 
     pub fn enable_broadcast_vote(&mut self, enable: bool) {
@@ -429,6 +486,17 @@ impl ConsensusConfig {
                 ),
             ));
         }
+        for backpressure_values in &config.batch_backpressure {
+            recv_batch_send_block_pairs.push((
+                config.quorum_store.receiver_max_batch_bytes as u64,
+                backpressure_values.max_sending_block_bytes_override,
+                format!(
+                    "batch backpressure {} bytes / {} ms: QS recv batch bytes < max_sending_block_bytes_override",
+                    backpressure_values.back_pressure_pending_batch_bytes_limit,
+                    backpressure_values.back_pressure_proof_latency_limit_ms,
+                ),
+            ));
+        }
 
         for (batch, block, label) in &recv_batch_send_block_pairs {
             if *batch > *block {
@@ -470,6 +538,16 @@ impl ConfigSanitizer for ConsensusConfig {
         // Quorum store batches must be <= consensus blocks
         Self::sanitize_batch_block_limits(&sanitizer_name, &node_config.consensus)?;
 
+        // Message capture must keep at least one round when enabled
+        let message_capture = &node_config.consensus.message_capture;
+        if message_capture.enabled && message_capture.max_rounds == 0 {
+            return Err(Error::ConfigSanitizerFailed(
+                sanitizer_name,
+                "message_capture.max_rounds must be positive when message capture is enabled!"
+                    .to_string(),
+            ));
+        }
+
         Ok(())
     }
 }
@@ -734,4 +812,51 @@ mod test {
             ConsensusConfig::sanitize(&node_config, NodeType::ValidatorFullnode, None).unwrap_err();
         assert!(matches!(error, Error::ConfigSanitizerFailed(_, _)));
     }
+
+    #[test]
+    fn test_invalid_batch_backpressure_byte_limits() {
+        // Create a node config with invalid batch backpressure byte limits
+        let node_config = NodeConfig {
+            consensus: ConsensusConfig {
+                batch_backpressure: vec![BatchBackpressureValues {
+                    back_pressure_pending_batch_bytes_limit: 0,
+                    back_pressure_proof_latency_limit_ms: 0,
+                    max_sending_block_txns_after_filtering_override: 251,
+                    max_sending_block_bytes_override: 100,
+                }],
+                quorum_store: QuorumStoreConfig {
+                    receiver_max_batch_bytes: 2_000_000,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        // Sanitize the config and verify that it fails
+        let error =
+            ConsensusConfig::sanitize(&node_config, NodeType::ValidatorFullnode, None).unwrap_err();
+        assert!(matches!(error, Error::ConfigSanitizerFailed(_, _)));
+    }
+
+    #[test]
+    fn test_invalid_message_capture_rounds() {
+        // Create a node config that captures messages without keeping any round
+        let node_config = NodeConfig {
+            consensus: ConsensusConfig {
+                message_capture: MessageCaptureConfig {
+                    enabled: true,
+                    max_rounds: 0,
+                    ..Default::default()
+                },
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        // Sanitize the config and verify that it fails
+        let error =
+            ConsensusConfig::sanitize(&node_config, NodeType::ValidatorFullnode, None).unwrap_err();
+        assert!(matches!(error, Error::ConfigSanitizerFailed(_, _)));
+    }
 }
@@ -8,7 +8,10 @@ use crate::config::{
     node_config_loader::NodeType, transaction_filter_type::Filter, utils::RootPath, Error,
     NodeConfig,
 };
-use aptos_types::{chain_id::ChainId, transaction::Transaction, waypoint::Waypoint};
+use aptos_types::{
+    block_executor::config::BlockSTMCommitterBackup, chain_id::ChainId, transaction::Transaction,
+    waypoint::Waypoint,
+};
 use serde::{Deserialize, Serialize};
 use serde_yaml::Value;
 use std::{
@@ -46,6 +49,15 @@ pub struct ExecutionConfig {
     pub paranoid_type_verification: bool,
     /// Enabled discarding blocks that fail execution due to BlockSTM/VM issue.
     pub discard_failed_blocks: bool,
+    /// If set, blocks that fail parallel execution and fall back to sequential execution are
+    /// dumped (transactions, read keys and error) into this directory, to diagnose the failure.
+    pub sequential_fallback_dump_dir: Option<PathBuf>,
+    /// Policy for the BlockSTM committer to validate (and re-execute) the next transaction to
+    /// commit itself when it is stalled, instead of waiting for other workers.
+    pub committer_backup: BlockSTMCommitterBackup,
+    /// Pins parallel execution workers to cores grouped by NUMA node (socket) while they execute
+    /// a block. Ignored if the NUMA topology of the machine cannot be detected.
+    pub pin_workers_to_numa_nodes: bool,
     /// Enables paranoid mode for hot potatoes, which adds extra runtime VM checks
     pub paranoid_hot_potato_verification: bool,
     /// Enables enhanced metrics around processed transactions
@@ -54,6 +66,9 @@ pub struct ExecutionConfig {
     pub transaction_filter: Filter,
     /// Used during DB bootstrapping
     pub genesis_waypoint: Option<WaypointConfig>,
+    /// Max number of ancestors whose state checkpoints can still be calculating when a block is
+    /// executed. If 0, the state checkpoint of a block is calculated right after its execution.
+    pub state_checkpoint_pipeline_depth: usize,
 }
 
 impl std::fmt::Debug for ExecutionConfig {
@@ -83,9 +98,13 @@ impl Default for ExecutionConfig {
             paranoid_type_verification: true,
             paranoid_hot_potato_verification: true,
             discard_failed_blocks: false,
+            sequential_fallback_dump_dir: None,
+            committer_backup: BlockSTMCommitterBackup::Disabled,
+            pin_workers_to_numa_nodes: false,
             processed_transactions_detailed_counters: false,
             transaction_filter: Filter::empty(),
             genesis_waypoint: None,
+            state_checkpoint_pipeline_depth: 0,
         }
     }
 }
@@ -460,6 +479,15 @@ mod test {
         assert_eq!(config.genesis, Some(fake_genesis));
     }
 
+    #[test]
+    fn test_parse_committer_backup() {
+        let config: ExecutionConfig = serde_yaml::from_str("committer_backup: adaptive").unwrap();
+        assert_eq!(config.committer_backup, BlockSTMCommitterBackup::Adaptive);
+
+        let config: ExecutionConfig = serde_yaml::from_str("concurrency_level: 4").unwrap();
+        assert_eq!(config.committer_backup, BlockSTMCommitterBackup::Disabled);
+    }
+
     fn generate_config() -> (ExecutionConfig, TempPath) {
         let temp_dir = TempPath::new();
         temp_dir.create_as_dir().expect("error creating tempdir");
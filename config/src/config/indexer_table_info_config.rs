@@ -7,6 +7,9 @@ use serde::{Deserialize, Serialize};
 pub const DEFAULT_PARSER_TASK_COUNT: u16 = 20;
 pub const DEFAULT_PARSER_BATCH_SIZE: u16 = 1000;
 pub const DEFAULT_TABLE_INFO_BUCKET: &str = "default-table-info";
+pub const DEFAULT_BACKFILL_TASK_COUNT: u16 = 40;
+pub const DEFAULT_BACKFILL_BATCH_SIZE: u16 = 1000;
+pub const DEFAULT_BACKFILL_MAX_TRANSACTIONS_PER_SEC: u64 = 20_000;
 
 #[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
 pub enum TableInfoServiceMode {
@@ -33,6 +36,9 @@ pub struct IndexerTableInfoConfig {
     /// Number of transactions each parser will process
     pub parser_batch_size: u16,
     pub table_info_service_mode: TableInfoServiceMode,
+
+    /// Configuration for catching up on historical versions before following the ledger
+    pub backfill: TableInfoBackfillConfig,
 }
 
 // Reminder, #[serde(default)] on IndexerTableInfoConfig means that the default values for
@@ -44,6 +50,37 @@ impl Default for IndexerTableInfoConfig {
             parser_task_count: DEFAULT_PARSER_TASK_COUNT,
             parser_batch_size: DEFAULT_PARSER_BATCH_SIZE,
             table_info_service_mode: TableInfoServiceMode::Disabled,
+            backfill: TableInfoBackfillConfig::default(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct TableInfoBackfillConfig {
+    /// Whether to backfill the versions up to the ledger version at startup, before
+    /// following the ledger. The progress is checkpointed in the table info db, so an
+    /// interrupted backfill resumes where it stopped.
+    pub enabled: bool,
+
+    /// Number of processor tasks to fan out during the backfill
+    pub task_count: u16,
+
+    /// Number of transactions each backfill task will process
+    pub batch_size: u16,
+
+    /// Maximum number of transactions processed per second, to limit the load on the
+    /// fullnode. Zero means unlimited.
+    pub max_transactions_per_sec: u64,
+}
+
+impl Default for TableInfoBackfillConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            task_count: DEFAULT_BACKFILL_TASK_COUNT,
+            batch_size: DEFAULT_BACKFILL_BATCH_SIZE,
+            max_transactions_per_sec: DEFAULT_BACKFILL_MAX_TRANSACTIONS_PER_SEC,
         }
     }
 }
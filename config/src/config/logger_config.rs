@@ -9,7 +9,7 @@ use crate::{
     },
     utils,
 };
-use aptos_logger::{Level, CHANNEL_SIZE};
+use aptos_logger::{Level, OverflowPolicy, CHANNEL_SIZE};
 use aptos_types::chain_id::ChainId;
 use serde::{Deserialize, Serialize};
 use serde_yaml::Value;
@@ -28,6 +28,12 @@ pub struct LoggerConfig {
     pub is_async: bool,
     /// The default logging level for the logger.
     pub level: Level,
+    /// What to do with new logs when the asynchronous logging channel is full
+    pub overflow_policy: OverflowPolicy,
+    /// Number of recent logs kept in memory and dumped on panic (0 to disable)
+    pub recent_logs_capacity: usize,
+    /// Level of the recent logs kept in memory, regardless of `level`
+    pub recent_logs_level: Level,
     /// Whether to enable remote telemetry logging
     pub enable_telemetry_remote_log: bool,
     /// Whether to enable remote telemetry logging flushing
@@ -45,6 +51,9 @@ impl Default for LoggerConfig {
             enable_backtrace: false,
             is_async: true,
             level: Level::Info,
+            overflow_policy: OverflowPolicy::DropNewest,
+            recent_logs_capacity: 0,
+            recent_logs_level: Level::Debug,
             enable_telemetry_remote_log: true,
             enable_telemetry_flush: true,
             telemetry_level: Level::Error,
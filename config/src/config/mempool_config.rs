@@ -6,10 +6,12 @@ use crate::config::{
     config_optimizer::ConfigOptimizer, config_sanitizer::ConfigSanitizer,
     node_config_loader::NodeType, Error, NodeConfig, MAX_APPLICATION_MESSAGE_SIZE,
 };
+use aptos_crypto::HashValue;
 use aptos_global_constants::DEFAULT_BUCKETS;
-use aptos_types::chain_id::ChainId;
+use aptos_types::{account_address::AccountAddress, chain_id::ChainId};
 use serde::{Deserialize, Serialize};
 use serde_yaml::Value;
+use std::path::{Path, PathBuf};
 
 #[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
 #[serde(default, deny_unknown_fields)]
@@ -37,6 +39,172 @@ impl Default for LoadBalancingThresholdConfig {
     }
 }
 
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BroadcastFairnessConfig {
+    /// Whether to build the broadcast batches with the fairness scheduler. The scheduler takes the
+    /// transactions of the sender buckets in a weighted round-robin, so that a spammy sender (or
+    /// sender bucket) can't fill the batches, and limits the transactions of each account.
+    pub enable: bool,
+    /// The maximum number of transactions of a single account in a broadcast batch.
+    pub max_txns_per_account: usize,
+    /// The number of transactions taken from each gas unit price bucket (see `broadcast_buckets`)
+    /// of a sender bucket in every round-robin round. The buckets without a weight get 1.
+    pub gas_bucket_weights: Vec<usize>,
+}
+
+impl Default for BroadcastFairnessConfig {
+    fn default() -> BroadcastFairnessConfig {
+        BroadcastFairnessConfig {
+            enable: false,
+            max_txns_per_account: 16,
+            gas_bucket_weights: vec![],
+        }
+    }
+}
+
+impl BroadcastFairnessConfig {
+    /// Returns the round-robin weight of the gas unit price bucket at the given index
+    pub fn gas_bucket_weight(&self, bucket_index: usize) -> usize {
+        self.gas_bucket_weights
+            .get(bucket_index)
+            .copied()
+            .unwrap_or(1)
+    }
+}
+
+/// The order in which the parked (i.e., not ready) transactions are evicted when Mempool is full
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq, Serialize)]
+pub enum MempoolEvictionOrder {
+    /// Evict the transactions of random accounts
+    Random,
+    /// Evict the transactions with the lowest gas unit price first
+    LowestGasUnitPrice,
+    /// Evict the oldest transactions first
+    Oldest,
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MempoolEvictionConfig {
+    /// The order in which the parked transactions are evicted when Mempool is full. Only the last
+    /// transaction of an account can be evicted, so that the remaining ones can still become ready.
+    pub order: MempoolEvictionOrder,
+    /// The number of accounts sampled to pick each transaction to evict (for the non-random
+    /// orders), which bounds the cost of an eviction.
+    pub num_sampled_accounts: usize,
+    /// Whether the transactions that would be parked on insertion can evict other transactions
+    /// when Mempool is full. Otherwise, only the transactions ready for broadcast can.
+    pub evict_for_parked_transactions: bool,
+}
+
+impl Default for MempoolEvictionConfig {
+    fn default() -> MempoolEvictionConfig {
+        MempoolEvictionConfig {
+            order: MempoolEvictionOrder::Random,
+            num_sampled_accounts: 16,
+            evict_for_parked_transactions: false,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SignaturePrevalidationConfig {
+    /// Whether to verify the signatures of the client submitted transactions in batches, before
+    /// the VM validation. The transactions with an invalid signature are rejected without reading
+    /// the storage, and the valid ones are validated and inserted together.
+    pub enable: bool,
+    /// The maximum number of batches being verified (and validated) concurrently
+    pub num_workers: usize,
+    /// The maximum number of submitted transactions waiting for verification. Once reached,
+    /// submissions are rejected as if Mempool was full, so that the clients back off.
+    pub max_pending_txns: usize,
+    /// The maximum number of transactions verified in a single batch
+    pub max_batch_size: usize,
+}
+
+impl Default for SignaturePrevalidationConfig {
+    fn default() -> SignaturePrevalidationConfig {
+        SignaturePrevalidationConfig {
+            enable: false,
+            num_workers: 4,
+            max_pending_txns: 4_096,
+            max_batch_size: 64,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PriorityLanesConfig {
+    /// Whether to classify the operational transactions (i.e., of the framework addresses, of the
+    /// priority senders and the allowlisted governance transactions) into the priority lane. The
+    /// priority transactions are pulled into blocks first, and aren't subject to the Mempool and
+    /// per account capacities.
+    pub enable: bool,
+    /// The senders of the priority transactions, besides the framework addresses
+    pub priority_senders: Vec<AccountAddress>,
+    /// The entry functions (formatted as `address::module::function`, e.g.,
+    /// `0x1::aptos_governance::resolve`) whose calls are priority transactions, from any sender
+    pub priority_entry_functions: Vec<String>,
+    /// The SHA3-256 hashes of the scripts (e.g., of the approved governance proposals) that are
+    /// priority transactions, from any sender
+    pub priority_script_hashes: Vec<HashValue>,
+    /// The maximum number of priority transactions in Mempool. Once reached, the transactions
+    /// classified as priority are treated as normal ones.
+    pub max_priority_txns: usize,
+}
+
+impl Default for PriorityLanesConfig {
+    fn default() -> PriorityLanesConfig {
+        PriorityLanesConfig {
+            enable: false,
+            priority_senders: vec![],
+            priority_entry_functions: vec![],
+            priority_script_hashes: vec![],
+            max_priority_txns: 100,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct MempoolPersistenceConfig {
+    /// Whether to persist the client submitted transactions accepted into Mempool, so that they
+    /// are re-validated and re-inserted after a restart instead of being dropped
+    pub enable: bool,
+    /// The path of the transaction log. A relative path is relative to the data directory.
+    pub path: PathBuf,
+    /// The maximum number of transactions in the log. Once reached, the accepted transactions
+    /// aren't persisted until the next compaction.
+    pub max_persisted_txns: usize,
+    /// The interval at which the log is compacted to the transactions still in Mempool
+    pub compaction_interval_ms: u64,
+}
+
+impl Default for MempoolPersistenceConfig {
+    fn default() -> MempoolPersistenceConfig {
+        MempoolPersistenceConfig {
+            enable: false,
+            path: PathBuf::from("mempool/transactions.log"),
+            max_persisted_txns: 100_000,
+            compaction_interval_ms: 60_000,
+        }
+    }
+}
+
+impl MempoolPersistenceConfig {
+    /// Returns the path of the transaction log, given the data directory
+    pub fn full_path(&self, data_dir: &Path) -> PathBuf {
+        if self.path.is_relative() {
+            data_dir.join(&self.path)
+        } else {
+            self.path.clone()
+        }
+    }
+}
+
 #[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
 #[serde(default, deny_unknown_fields)]
 pub struct MempoolConfig {
@@ -100,6 +268,16 @@ pub struct MempoolConfig {
     /// up to 10 minutes (shared_mempool_priority_update_interval_secs) to enable the load balancing. If this flag is enabled,
     /// then the PFNs will always do load balancing irrespective of the load.
     pub enable_max_load_balancing_at_any_load: bool,
+    /// Fairness of the broadcast batches across the senders
+    pub broadcast_fairness: BroadcastFairnessConfig,
+    /// How transactions are evicted when Mempool reaches its capacity
+    pub eviction: MempoolEvictionConfig,
+    /// Batched signature verification of the client submitted transactions
+    pub signature_prevalidation: SignaturePrevalidationConfig,
+    /// The priority lane of the operational transactions
+    pub priority_lanes: PriorityLanesConfig,
+    /// Persistence of the client submitted transactions across restarts
+    pub persistence: MempoolPersistenceConfig,
 }
 
 impl Default for MempoolConfig {
@@ -164,17 +342,67 @@ impl Default for MempoolConfig {
                 },
             ],
             enable_max_load_balancing_at_any_load: false,
+            broadcast_fairness: BroadcastFairnessConfig::default(),
+            eviction: MempoolEvictionConfig::default(),
+            signature_prevalidation: SignaturePrevalidationConfig::default(),
+            priority_lanes: PriorityLanesConfig::default(),
+            persistence: MempoolPersistenceConfig::default(),
         }
     }
 }
 
 impl ConfigSanitizer for MempoolConfig {
     fn sanitize(
-        _node_config: &NodeConfig,
+        node_config: &NodeConfig,
         _node_type: NodeType,
         _chain_id: Option<ChainId>,
     ) -> Result<(), Error> {
-        Ok(()) // TODO: add reasonable verifications
+        let sanitizer_name = Self::get_sanitizer_name();
+        let broadcast_fairness = &node_config.mempool.broadcast_fairness;
+
+        // Verify that the fairness scheduler can make progress
+        if broadcast_fairness.enable {
+            if broadcast_fairness.max_txns_per_account == 0 {
+                return Err(Error::ConfigSanitizerFailed(
+                    sanitizer_name,
+                    "The max transactions per account of the broadcast fairness must be positive!"
+                        .into(),
+                ));
+            }
+            if (0..node_config.mempool.broadcast_buckets.len())
+                .all(|bucket_index| broadcast_fairness.gas_bucket_weight(bucket_index) == 0)
+            {
+                return Err(Error::ConfigSanitizerFailed(
+                    sanitizer_name,
+                    "At least one gas bucket weight of the broadcast fairness must be positive!"
+                        .into(),
+                ));
+            }
+        }
+
+        // Verify that the eviction can pick a transaction
+        if node_config.mempool.eviction.num_sampled_accounts == 0 {
+            return Err(Error::ConfigSanitizerFailed(
+                sanitizer_name,
+                "The number of accounts sampled for eviction must be positive!".into(),
+            ));
+        }
+
+        // Verify that the signature prevalidation can make progress
+        let signature_prevalidation = &node_config.mempool.signature_prevalidation;
+        if signature_prevalidation.enable
+            && (signature_prevalidation.num_workers == 0
+                || signature_prevalidation.max_pending_txns == 0
+                || signature_prevalidation.max_batch_size == 0)
+        {
+            return Err(Error::ConfigSanitizerFailed(
+                sanitizer_name,
+                "The workers, pending transactions and batch size of the signature prevalidation must be positive!"
+                    .into(),
+            ));
+        }
+
+        Ok(())
     }
 }
 
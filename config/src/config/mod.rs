@@ -7,6 +7,7 @@ mod admin_service_config;
 mod api_config;
 mod base_config;
 mod config_optimizer;
+mod config_overrides;
 mod config_sanitizer;
 mod consensus_config;
 mod consensus_observer_config;
@@ -44,6 +45,7 @@ mod utils;
 pub use admin_service_config::*;
 pub use api_config::*;
 pub use base_config::*;
+pub use config_overrides::*;
 pub use consensus_config::*;
 pub use consensus_observer_config::*;
 pub use dag_consensus_config::*;
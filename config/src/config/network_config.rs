@@ -23,7 +23,7 @@ use rand::{
 };
 use serde::{Deserialize, Serialize};
 use std::{
-    collections::{HashMap, HashSet},
+    collections::{BTreeMap, HashMap, HashSet},
     convert::TryFrom,
     fmt,
     path::PathBuf,
@@ -120,6 +120,11 @@ pub struct NetworkConfig {
     pub outbound_rate_limit_config: Option<RateLimitConfig>,
     /// The maximum size of an inbound or outbound message (it may be divided into multiple frame)
     pub max_message_size: usize,
+    /// The maximum message sizes of specific application protocols (keyed by protocol name, e.g.,
+    /// "ConsensusRpcBcs"), overriding `max_message_size`. They are negotiated with the peers that
+    /// support it, i.e., the lowest limit of both ends applies. With other peers, limits above
+    /// `max_message_size` don't apply.
+    pub max_message_size_per_protocol: BTreeMap<String, usize>,
     /// The maximum number of parallel message deserialization tasks that can run (per application)
     pub max_parallel_deserialization_tasks: Option<usize>,
     /// Whether or not to enable latency aware peer dialing
@@ -159,6 +164,7 @@ impl NetworkConfig {
             inbound_rate_limit_config: None,
             outbound_rate_limit_config: None,
             max_message_size: MAX_MESSAGE_SIZE,
+            max_message_size_per_protocol: BTreeMap::new(),
             inbound_rx_buffer_size_bytes: None,
             inbound_tx_buffer_size_bytes: None,
             outbound_rx_buffer_size_bytes: None,
@@ -4,8 +4,8 @@
 use super::{DagConsensusConfig, IndexerTableInfoConfig};
 use crate::{
     config::{
-        consensus_observer_config::ConsensusObserverConfig, dkg_config::DKGConfig,
-        internal_indexer_db_config::InternalIndexerDBConfig,
+        config_overrides::ConfigOverride, consensus_observer_config::ConsensusObserverConfig,
+        dkg_config::DKGConfig, internal_indexer_db_config::InternalIndexerDBConfig,
         jwk_consensus_config::JWKConsensusConfig, netbench_config::NetbenchConfig,
         node_config_loader::NodeConfigLoader, node_startup_config::NodeStartupConfig,
         persistable_config::PersistableConfig, utils::RootPath, AdminServiceConfig, ApiConfig,
@@ -139,6 +139,16 @@ impl NodeConfig {
         node_config_loader.load_and_sanitize_config()
     }
 
+    /// Load the node config from the given path, with the given overrides applied on top
+    /// of it, and sanitize it. Overrides of higher [`crate::config::ConfigLayer`]s win.
+    pub fn load_from_path_with_overrides<P: AsRef<Path>>(
+        input_path: P,
+        overrides: Vec<ConfigOverride>,
+    ) -> Result<Self, Error> {
+        let node_config_loader = NodeConfigLoader::new(input_path).with_overrides(overrides);
+        node_config_loader.load_and_sanitize_config()
+    }
+
     /// Returns the peer ID of the node based on the role
     pub fn get_peer_id(&self) -> Option<PeerId> {
         self.get_primary_network_config()
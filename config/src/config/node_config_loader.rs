@@ -3,7 +3,10 @@
 
 use crate::{
     config::{
-        config_optimizer::ConfigOptimizer, config_sanitizer::ConfigSanitizer, utils::RootPath,
+        config_optimizer::ConfigOptimizer,
+        config_overrides::{apply_config_overrides, ConfigOverride},
+        config_sanitizer::ConfigSanitizer,
+        utils::RootPath,
         Error, NodeConfig, PersistableConfig,
     },
     utils::get_genesis_txn,
@@ -60,18 +63,36 @@ impl NodeType {
 /// sanitization and post-processing.
 pub struct NodeConfigLoader<P> {
     node_config_path: P,
+    overrides: Vec<ConfigOverride>,
 }
 
 impl<P: AsRef<Path>> NodeConfigLoader<P> {
     pub fn new(node_config_path: P) -> Self {
-        Self { node_config_path }
+        Self {
+            node_config_path,
+            overrides: vec![],
+        }
+    }
+
+    /// Sets the overrides to apply on top of the node config file
+    pub fn with_overrides(mut self, overrides: Vec<ConfigOverride>) -> Self {
+        self.overrides = overrides;
+        self
     }
 
     /// Load the node config, validate the configuration options
     /// and process the config for the current environment.
     pub fn load_and_sanitize_config(&self) -> Result<NodeConfig, Error> {
-        // Load the node config from disk
-        let mut node_config = NodeConfig::load_config(&self.node_config_path)?;
+        // Load the node config from disk, and apply the overrides. The overrides
+        // are part of the local config, so the optimizers will not change them.
+        let mut local_config_yaml = get_local_config_yaml(&self.node_config_path)?;
+        apply_config_overrides(&mut local_config_yaml, &self.overrides)?;
+        let mut node_config = if self.overrides.is_empty() {
+            NodeConfig::load_config(&self.node_config_path)?
+        } else {
+            serde_yaml::from_value(local_config_yaml.clone())
+                .map_err(|error| Error::Yaml("node config with overrides".into(), error))?
+        };
 
         // Load the execution config
         let input_dir = RootPath::new(&self.node_config_path);
@@ -83,7 +104,6 @@ impl<P: AsRef<Path>> NodeConfigLoader<P> {
         node_config.set_data_dir(node_config.get_data_dir().to_path_buf());
 
         // Optimize and sanitize the node config
-        let local_config_yaml = get_local_config_yaml(&self.node_config_path)?;
         optimize_and_sanitize_node_config(&mut node_config, local_config_yaml)?;
 
         Ok(node_config)
@@ -2,9 +2,12 @@
 // Parts of the project are originally copyright © Meta Platforms, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::config::{
-    config_optimizer::ConfigOptimizer, config_sanitizer::ConfigSanitizer,
-    node_config_loader::NodeType, Error, NodeConfig,
+use crate::{
+    config::{
+        config_optimizer::ConfigOptimizer, config_sanitizer::ConfigSanitizer,
+        node_config_loader::NodeType, Error, NodeConfig,
+    },
+    network_id::NetworkId,
 };
 use aptos_types::chain_id::ChainId;
 use serde::{Deserialize, Serialize};
@@ -288,6 +291,18 @@ pub struct DynamicPrefetchingConfig {
 
     /// The duration by which to freeze the prefetching value on a timeout
     pub timeout_freeze_duration_secs: u64,
+
+    /// Whether or not to size the prefetching value based on the observed throughput
+    /// (instead of increasing it on every successful response)
+    pub enable_throughput_based_sizing: bool,
+
+    /// The estimated number of requests queued at the peers (i.e., the requests
+    /// that don't add to the throughput) below which to increase the prefetching value
+    pub min_queued_requests: u64,
+
+    /// The estimated number of requests queued at the peers above which to
+    /// decrease the prefetching value
+    pub max_queued_requests: u64,
 }
 
 impl Default for DynamicPrefetchingConfig {
@@ -301,6 +316,9 @@ impl Default for DynamicPrefetchingConfig {
             prefetching_value_increase: 1,
             prefetching_value_decrease: 2,
             timeout_freeze_duration_secs: 30,
+            enable_throughput_based_sizing: false,
+            min_queued_requests: 1,
+            max_queued_requests: 3,
         }
     }
 }
@@ -368,6 +386,67 @@ impl Default for AptosDataMultiFetchConfig {
     }
 }
 
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AptosDataBandwidthBudgetConfig {
+    /// Whether or not to budget the bandwidth used by data requests on each network.
+    /// This avoids starving the other traffic on the network (e.g., consensus
+    /// and mempool) when the node is catching up on constrained links.
+    pub enable_bandwidth_budget: bool,
+    /// The number of seconds of budget that can be used in a burst
+    pub max_burst_secs: u64,
+    /// The maximum number of response bytes per second on the public network
+    pub public_network_max_bytes_per_second: u64,
+    /// The maximum number of response bytes per second on the validator network
+    pub validator_network_max_bytes_per_second: u64,
+    /// The maximum number of response bytes per second on the VFN network
+    pub vfn_network_max_bytes_per_second: u64,
+}
+
+impl AptosDataBandwidthBudgetConfig {
+    /// Returns the maximum number of response bytes per second on the given network
+    pub fn max_bytes_per_second(&self, network_id: NetworkId) -> u64 {
+        match network_id {
+            NetworkId::Validator => self.validator_network_max_bytes_per_second,
+            NetworkId::Vfn => self.vfn_network_max_bytes_per_second,
+            NetworkId::Public => self.public_network_max_bytes_per_second,
+        }
+    }
+}
+
+impl Default for AptosDataBandwidthBudgetConfig {
+    fn default() -> Self {
+        Self {
+            enable_bandwidth_budget: false,
+            max_burst_secs: 2,
+            public_network_max_bytes_per_second: 100 * 1024 * 1024, // 100 MiB
+            validator_network_max_bytes_per_second: 50 * 1024 * 1024, // 50 MiB
+            vfn_network_max_bytes_per_second: 50 * 1024 * 1024,     // 50 MiB
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AptosDataPeerRateLimitConfig {
+    /// Whether or not to rate limit the data requests sent to each peer
+    pub enable_peer_rate_limiting: bool,
+    /// The maximum number of data requests that can be sent to a peer in a burst
+    pub max_burst_requests_per_peer: u64,
+    /// The maximum number of data requests to send to each peer (per second)
+    pub max_requests_per_peer_per_second: u64,
+}
+
+impl Default for AptosDataPeerRateLimitConfig {
+    fn default() -> Self {
+        Self {
+            enable_peer_rate_limiting: false,
+            max_burst_requests_per_peer: 20,
+            max_requests_per_peer_per_second: 10,
+        }
+    }
+}
+
 #[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq, Serialize)]
 #[serde(default, deny_unknown_fields)]
 pub struct AptosLatencyFilteringConfig {
@@ -392,6 +471,8 @@ impl Default for AptosLatencyFilteringConfig {
 #[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq, Serialize)]
 #[serde(default, deny_unknown_fields)]
 pub struct AptosDataClientConfig {
+    /// The bandwidth budget config for the data client
+    pub bandwidth_budget_config: AptosDataBandwidthBudgetConfig,
     /// The aptos data poller config for the data client
     pub data_poller_config: AptosDataPollerConfig,
     /// The aptos data multi-fetch config for the data client
@@ -420,6 +501,8 @@ pub struct AptosDataClientConfig {
     pub max_transaction_output_chunk_size: u64,
     /// Timeout (in ms) when waiting for an optimistic fetch response
     pub optimistic_fetch_timeout_ms: u64,
+    /// The per-peer rate limit config for the data client
+    pub peer_rate_limit_config: AptosDataPeerRateLimitConfig,
     /// First timeout (in ms) when waiting for a response
     pub response_timeout_ms: u64,
     /// Timeout (in ms) when waiting for a subscription response
@@ -431,6 +514,7 @@ pub struct AptosDataClientConfig {
 impl Default for AptosDataClientConfig {
     fn default() -> Self {
         Self {
+            bandwidth_budget_config: AptosDataBandwidthBudgetConfig::default(),
             data_poller_config: AptosDataPollerConfig::default(),
             data_multi_fetch_config: AptosDataMultiFetchConfig::default(),
             ignore_low_score_peers: true,
@@ -444,7 +528,8 @@ impl Default for AptosDataClientConfig {
             max_subscription_lag_secs: 20, // 20 seconds
             max_transaction_chunk_size: MAX_TRANSACTION_CHUNK_SIZE,
             max_transaction_output_chunk_size: MAX_TRANSACTION_OUTPUT_CHUNK_SIZE,
-            optimistic_fetch_timeout_ms: 5000,        // 5 seconds
+            optimistic_fetch_timeout_ms: 5000, // 5 seconds
+            peer_rate_limit_config: AptosDataPeerRateLimitConfig::default(),
             response_timeout_ms: 10_000,              // 10 seconds
             subscription_response_timeout_ms: 15_000, // 15 seconds (longer than a regular timeout because of prefetching)
             use_compression: true,
@@ -3,7 +3,10 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    config::{config_sanitizer::ConfigSanitizer, node_config_loader::NodeType, Error, NodeConfig},
+    config::{
+        config_sanitizer::ConfigSanitizer, node_config_loader::NodeType, BootstrappingMode,
+        ContinuousSyncingMode, Error, NodeConfig,
+    },
     utils,
 };
 use anyhow::{bail, ensure, Result};
@@ -116,6 +119,27 @@ pub struct RocksdbConfig {
     pub block_size: u64,
     /// Whether cache index and filter blocks into block cache.
     pub cache_index_and_filter_blocks: bool,
+    /// Selects the block cache size, compaction style and compression of each column family.
+    pub tuning_profile: RocksdbTuningProfile,
+}
+
+/// Named sets of column family options, for the different workloads of the nodes.
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RocksdbTuningProfile {
+    /// LZ4 compression and level compaction for all the column families, with the configured
+    /// block cache size.
+    #[default]
+    Default,
+    /// For validators: a 4x block cache, and no compression of the state column families, which
+    /// are read on the critical path of execution.
+    Throughput,
+    /// For memory constrained nodes: half of the block cache size, ZSTD compression everywhere,
+    /// and index and filter blocks in the block cache so that they are bounded too.
+    LowMemory,
+    /// For archival nodes: ZSTD compression and universal compaction for the historical ledger
+    /// column families, which are written once and rarely read, to minimize the disk usage.
+    Archive,
 }
 
 impl Default for RocksdbConfig {
@@ -135,6 +159,7 @@ impl Default for RocksdbConfig {
             block_size: 4 * (1u64 << 10),
             // Whether cache index and filter blocks into block cache.
             cache_index_and_filter_blocks: false,
+            tuning_profile: RocksdbTuningProfile::default(),
         }
     }
 }
@@ -197,6 +222,134 @@ pub struct StorageConfig {
     /// If not specificed, will use `dir` as default.
     /// Only allowed when sharding is enabled.
     pub db_path_overrides: Option<DbPathConfig>,
+    /// Profiling of the state reads, for diagnosing hot keys and read amplification.
+    pub state_read_profiler: StateReadProfilerConfig,
+    /// Offloading of the historical ledger data to an object store.
+    pub cold_storage: ColdStorageConfig,
+    /// Recording of the slow commits, for attributing tail latency regressions.
+    pub slow_commit_recorder: SlowCommitRecorderConfig,
+    /// Storing only a subset of the state, for light fullnodes tracking a few accounts.
+    pub partial_state_sync: PartialStateSyncConfig,
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ColdStorageConfig {
+    /// Whether to move the transactions, events and write sets older than `horizon` versions to
+    /// the object store. The indices stay local, and reads of the offloaded data go through the
+    /// object store.
+    pub enable: bool,
+    /// The number of latest versions kept locally.
+    pub horizon: u64,
+    /// The number of versions per object. Cannot be changed once data is offloaded.
+    pub chunk_size: u64,
+    /// The number of objects cached in memory, for reads.
+    pub cache_size: usize,
+    pub backend: ColdStorageBackend,
+}
+
+impl Default for ColdStorageConfig {
+    fn default() -> Self {
+        Self {
+            enable: false,
+            horizon: 150_000_000,
+            chunk_size: 10_000,
+            cache_size: 16,
+            backend: ColdStorageBackend::LocalFs {
+                path: PathBuf::from("/opt/aptos/cold_storage"),
+            },
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case", tag = "type")]
+pub enum ColdStorageBackend {
+    /// Objects are files under `path`, e.g., on a network file system.
+    LocalFs { path: PathBuf },
+    /// Objects are written and read by shell commands, e.g., the S3 or GCS CLIs. The name of the
+    /// object is passed in the `OBJECT_NAME` environment variable. The put command reads the
+    /// object from its stdin, and the get command writes it to its stdout, e.g.,
+    /// `aws s3 cp - "s3://bucket/$OBJECT_NAME"` and `aws s3 cp "s3://bucket/$OBJECT_NAME" -`.
+    Command {
+        put_command: String,
+        get_command: String,
+    },
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct StateReadProfilerConfig {
+    /// Whether to record the state reads served by the DB. The latest reads are exposed by the
+    /// inspection service. This slows down the reads, so it's disabled by default.
+    pub enable: bool,
+    /// The number of latest reads to keep.
+    pub capacity: usize,
+}
+
+impl Default for StateReadProfilerConfig {
+    fn default() -> Self {
+        Self {
+            enable: false,
+            capacity: 100_000,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SlowCommitRecorderConfig {
+    /// Whether to record the commits taking longer than the threshold, along with the breakdowns
+    /// of the batches written. The latest ones are exposed by the inspection service.
+    pub enable: bool,
+    /// The commit latency above which a commit is recorded.
+    pub threshold_ms: u64,
+    /// The number of latest slow commits to keep.
+    pub capacity: usize,
+}
+
+impl Default for SlowCommitRecorderConfig {
+    fn default() -> Self {
+        Self {
+            enable: true,
+            threshold_ms: 1000,
+            capacity: 20,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PartialStateSyncConfig {
+    /// Whether to store only the state values under the account address prefixes below, plus the
+    /// framework state (i.e., the special addresses 0x0 to 0xf). The state tree is still stored in
+    /// full, so every state value synced is verified, and the proofs of the stored values can be
+    /// served. The node must bootstrap by downloading the latest states and apply the transaction
+    /// outputs afterwards, as executing transactions requires the full state.
+    pub enable: bool,
+    /// The hex prefixes of the account addresses (e.g., "0xa550c18") of which the resources, and
+    /// the table items under the table handles matching them, are stored.
+    pub account_address_prefixes: Vec<String>,
+}
+
+impl PartialStateSyncConfig {
+    /// Returns the normalized prefixes, i.e., lower case hex without the `0x`.
+    pub fn normalized_prefixes(&self) -> Result<Vec<String>> {
+        self.account_address_prefixes
+            .iter()
+            .map(|prefix| {
+                let normalized = prefix.trim_start_matches("0x").to_lowercase();
+                ensure!(
+                    !normalized.is_empty()
+                        && normalized.len() <= 64
+                        && normalized.chars().all(|c| c.is_ascii_hexdigit()),
+                    "Invalid account address prefix: {:?}",
+                    prefix
+                );
+                Ok(normalized)
+            })
+            .collect()
+    }
 }
 
 pub const NO_OP_STORAGE_PRUNER_CONFIG: PrunerConfig = PrunerConfig {
@@ -216,6 +369,11 @@ pub const NO_OP_STORAGE_PRUNER_CONFIG: PrunerConfig = PrunerConfig {
         prune_window: 0,
         batch_size: 0,
     },
+    pacing_config: PrunerPacingConfig {
+        enable: false,
+        commit_latency_threshold_ms: 0,
+        max_delay_ms: 0,
+    },
 };
 
 #[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
@@ -278,6 +436,29 @@ pub struct PrunerConfig {
     pub ledger_pruner_config: LedgerPrunerConfig,
     pub state_merkle_pruner_config: StateMerklePrunerConfig,
     pub epoch_snapshot_pruner_config: EpochSnapshotPrunerConfig,
+    pub pacing_config: PrunerPacingConfig,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PrunerPacingConfig {
+    /// Boolean to enable/disable slowing down the pruners when the commits are slow.
+    pub enable: bool,
+    /// The pruners wait after each batch while the (moving average of the) commit latency is
+    /// above this threshold.
+    pub commit_latency_threshold_ms: u64,
+    /// The longest wait after each batch, reached at twice the commit latency threshold.
+    pub max_delay_ms: u64,
+}
+
+impl Default for PrunerPacingConfig {
+    fn default() -> Self {
+        Self {
+            enable: true,
+            commit_latency_threshold_ms: 500,
+            max_delay_ms: 1000,
+        }
+    }
 }
 
 impl Default for LedgerPrunerConfig {
@@ -346,6 +527,10 @@ impl Default for StorageConfig {
             db_path_overrides: None,
             buffered_state_target_items: BUFFERED_STATE_TARGET_ITEMS,
             max_num_nodes_per_lru_cache_shard: DEFAULT_MAX_NUM_NODES_PER_LRU_CACHE_SHARD,
+            state_read_profiler: StateReadProfilerConfig::default(),
+            cold_storage: ColdStorageConfig::default(),
+            slow_commit_recorder: SlowCommitRecorderConfig::default(),
+            partial_state_sync: PartialStateSyncConfig::default(),
         }
     }
 }
@@ -496,7 +681,7 @@ impl ShardedDbPaths {
 impl ConfigSanitizer for StorageConfig {
     fn sanitize(
         node_config: &NodeConfig,
-        _node_type: NodeType,
+        node_type: NodeType,
         _chain_id: Option<ChainId>,
     ) -> Result<(), Error> {
         let sanitizer_name = Self::get_sanitizer_name();
@@ -541,6 +726,44 @@ impl ConfigSanitizer for StorageConfig {
             ));
         }
 
+        for (db_name, rocksdb_config) in [
+            ("ledger_db", &config.rocksdb_configs.ledger_db_config),
+            (
+                "state_merkle_db",
+                &config.rocksdb_configs.state_merkle_db_config,
+            ),
+            ("state_kv_db", &config.rocksdb_configs.state_kv_db_config),
+            ("index_db", &config.rocksdb_configs.index_db_config),
+        ] {
+            match rocksdb_config.tuning_profile {
+                RocksdbTuningProfile::Archive if node_type.is_validator() => {
+                    return Err(Error::ConfigSanitizerFailed(
+                        sanitizer_name,
+                        format!(
+                            "The archive tuning profile of {db_name} trades the read latency for \
+                             disk space, and is not allowed on validators."
+                        ),
+                    ));
+                },
+                RocksdbTuningProfile::LowMemory
+                    if rocksdb_config.block_cache_size
+                        > RocksdbConfig::default().block_cache_size =>
+                {
+                    warn!(
+                        "The low memory tuning profile of {} is used with a block cache larger \
+                         than the default one.",
+                        db_name
+                    );
+                },
+                _ => {},
+            }
+        }
+
+        if config.partial_state_sync.enable {
+            sanitize_partial_state_sync(node_config, node_type)
+                .map_err(|e| Error::ConfigSanitizerFailed(sanitizer_name.clone(), e.to_string()))?;
+        }
+
         if let Some(db_path_overrides) = config.db_path_overrides.as_ref() {
             if !config.rocksdb_configs.enable_storage_sharding {
                 return Err(Error::ConfigSanitizerFailed(
@@ -596,9 +819,40 @@ impl ConfigSanitizer for StorageConfig {
     }
 }
 
+/// Verifies the node can run with a partial state: it must be a fullnode which
+/// downloads the latest states and applies the transaction outputs.
+fn sanitize_partial_state_sync(node_config: &NodeConfig, node_type: NodeType) -> Result<()> {
+    ensure!(
+        !node_type.is_validator(),
+        "Partial state sync is not allowed on validators."
+    );
+    let driver_config = &node_config.state_sync.state_sync_driver;
+    ensure!(
+        driver_config.bootstrapping_mode == BootstrappingMode::DownloadLatestStates,
+        "Partial state sync requires the DownloadLatestStates bootstrapping mode."
+    );
+    ensure!(
+        driver_config.continuous_syncing_mode == ContinuousSyncingMode::ApplyTransactionOutputs,
+        "Partial state sync requires the ApplyTransactionOutputs continuous syncing mode."
+    );
+    let prefixes = node_config
+        .storage
+        .partial_state_sync
+        .normalized_prefixes()?;
+    ensure!(
+        !prefixes.is_empty(),
+        "Partial state sync requires at least one account address prefix."
+    );
+    Ok(())
+}
+
 #[cfg(test)]
 mod test {
-    use crate::config::{PrunerConfig, ShardPathConfig, ShardedDbPathConfig};
+    use crate::config::{
+        config_sanitizer::ConfigSanitizer, node_config_loader::NodeType, BootstrappingMode,
+        ContinuousSyncingMode, Error, NodeConfig, PartialStateSyncConfig, PrunerConfig,
+        RocksdbTuningProfile, ShardPathConfig, ShardedDbPathConfig, StorageConfig,
+    };
 
     #[test]
     pub fn test_default_prune_window() {
@@ -674,4 +928,60 @@ mod test {
 
         assert!(path_overrides.get_shard_paths().is_err());
     }
+
+    #[test]
+    pub fn test_sanitize_tuning_profile() {
+        let mut node_config = NodeConfig::default();
+        node_config
+            .storage
+            .rocksdb_configs
+            .ledger_db_config
+            .tuning_profile = RocksdbTuningProfile::Archive;
+
+        // The archive profile is rejected on validators, but allowed on fullnodes
+        let error = StorageConfig::sanitize(&node_config, NodeType::Validator, None).unwrap_err();
+        assert!(matches!(error, Error::ConfigSanitizerFailed(_, _)));
+        StorageConfig::sanitize(&node_config, NodeType::PublicFullnode, None).unwrap();
+
+        node_config
+            .storage
+            .rocksdb_configs
+            .ledger_db_config
+            .tuning_profile = RocksdbTuningProfile::Throughput;
+        StorageConfig::sanitize(&node_config, NodeType::Validator, None).unwrap();
+    }
+
+    #[test]
+    pub fn test_sanitize_partial_state_sync() {
+        let mut node_config = NodeConfig::default();
+        node_config.storage.partial_state_sync = PartialStateSyncConfig {
+            enable: true,
+            account_address_prefixes: vec!["0xA550C18".to_string()],
+        };
+
+        // Partial state sync requires syncing the latest states and the transaction outputs
+        assert!(StorageConfig::sanitize(&node_config, NodeType::PublicFullnode, None).is_err());
+        let driver_config = &mut node_config.state_sync.state_sync_driver;
+        driver_config.bootstrapping_mode = BootstrappingMode::DownloadLatestStates;
+        driver_config.continuous_syncing_mode = ContinuousSyncingMode::ApplyTransactionOutputs;
+        StorageConfig::sanitize(&node_config, NodeType::PublicFullnode, None).unwrap();
+        assert_eq!(
+            node_config
+                .storage
+                .partial_state_sync
+                .normalized_prefixes()
+                .unwrap(),
+            vec!["a550c18".to_string()]
+        );
+
+        // Partial state sync is rejected on validators
+        assert!(StorageConfig::sanitize(&node_config, NodeType::Validator, None).is_err());
+
+        // Invalid prefixes are rejected
+        node_config
+            .storage
+            .partial_state_sync
+            .account_address_prefixes = vec!["0xzz".to_string()];
+        assert!(StorageConfig::sanitize(&node_config, NodeType::PublicFullnode, None).is_err());
+    }
 }
@@ -0,0 +1,54 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Replays the consensus messages captured by a node through a fresh RoundManager, and reports
+//! the messages that failed to process, e.g.
+//!
+//! ```text
+//! cargo run -p aptos-consensus --features fuzzing --bin consensus-replay -- \
+//!     --capture-dir /tmp/capture
+//! ```
+
+use aptos_consensus::round_manager_replay::replay_capture_dir;
+use clap::Parser;
+use std::path::PathBuf;
+
+#[derive(Debug, Parser)]
+#[clap(
+    name = "consensus-replay",
+    about = "Replays captured consensus messages to reproduce liveness incidents offline"
+)]
+struct Args {
+    /// Directory the messages were captured to (`capture_dir` of the message capture config).
+    #[clap(long)]
+    capture_dir: PathBuf,
+
+    /// Epoch to replay. Defaults to the first captured epoch.
+    #[clap(long)]
+    epoch: Option<u64>,
+
+    /// Exit with an error if any message failed to process.
+    #[clap(long)]
+    fail_on_error: bool,
+}
+
+fn main() -> anyhow::Result<()> {
+    let args = Args::parse();
+
+    let report = replay_capture_dir(&args.capture_dir, args.epoch)?;
+    for (round, error) in &report.errors {
+        println!("round {}: {}", round, error);
+    }
+    println!(
+        "processed {} message(s), skipped {}, {} error(s), final round {}",
+        report.processed,
+        report.skipped,
+        report.errors.len(),
+        report.final_round
+    );
+
+    if args.fail_on_error && !report.errors.is_empty() {
+        anyhow::bail!("{} message(s) failed to process", report.errors.len());
+    }
+    Ok(())
+}
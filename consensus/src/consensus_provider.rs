@@ -65,7 +65,12 @@ pub fn start_consensus(
     ));
 
     let execution_proxy = ExecutionProxy::new(
-        Arc::new(BlockExecutor::<AptosVMBlockExecutor>::new(aptos_db)),
+        Arc::new(
+            BlockExecutor::<AptosVMBlockExecutor>::new_with_state_checkpoint_pipeline_depth(
+                aptos_db,
+                node_config.execution.state_checkpoint_pipeline_depth,
+            ),
+        ),
         txn_notifier,
         state_sync_notifier,
         runtime.handle(),
@@ -158,7 +163,12 @@ pub fn start_consensus_observer(
             node_config.consensus.mempool_executed_txn_timeout_ms,
         ));
         let execution_proxy = ExecutionProxy::new(
-            Arc::new(BlockExecutor::<AptosVMBlockExecutor>::new(aptos_db.clone())),
+            Arc::new(
+                BlockExecutor::<AptosVMBlockExecutor>::new_with_state_checkpoint_pipeline_depth(
+                    aptos_db.clone(),
+                    node_config.execution.state_checkpoint_pipeline_depth,
+                ),
+            ),
             txn_notifier,
             state_sync_notifier,
             consensus_observer_runtime.handle(),
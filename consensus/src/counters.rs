@@ -13,11 +13,13 @@ use aptos_crypto::HashValue;
 use aptos_executor_types::{state_compute_result::StateComputeResult, ExecutorError};
 use aptos_logger::prelude::{error, warn};
 use aptos_metrics_core::{
-    exponential_buckets, op_counters::DurationHistogram, register_avg_counter, register_counter,
-    register_gauge, register_gauge_vec, register_histogram, register_histogram_vec,
-    register_int_counter, register_int_counter_vec, register_int_gauge, register_int_gauge_vec,
-    Counter, Gauge, GaugeVec, Histogram, HistogramVec, IntCounter, IntCounterVec, IntGauge,
-    IntGaugeVec,
+    exemplars::{register_exemplar_histogram_vec, ExemplarHistogramVec},
+    exponential_buckets, histogram_opts,
+    op_counters::DurationHistogram,
+    register_avg_counter, register_counter, register_gauge, register_gauge_vec, register_histogram,
+    register_histogram_vec, register_int_counter, register_int_counter_vec, register_int_gauge,
+    register_int_gauge_vec, Counter, Gauge, GaugeVec, Histogram, HistogramVec, IntCounter,
+    IntCounterVec, IntGauge, IntGaugeVec,
 };
 use aptos_types::transaction::TransactionStatus;
 use move_core_types::vm_status::DiscardedVMStatus;
@@ -309,6 +311,14 @@ pub static PIPELINE_BACKPRESSURE_ON_PROPOSAL_TRIGGERED: Lazy<Histogram> = Lazy::
     )
 });
 
+/// Counts when quorum store batch backpressure is triggered
+pub static BATCH_BACKPRESSURE_ON_PROPOSAL_TRIGGERED: Lazy<Histogram> = Lazy::new(|| {
+    register_avg_counter(
+        "aptos_batch_backpressure_on_proposal_triggered",
+        "Counts when quorum store batch backpressure is triggered",
+    )
+});
+
 /// Counts when execution backpressure is triggered
 pub static EXECUTION_BACKPRESSURE_ON_PROPOSAL_TRIGGERED: Lazy<Histogram> = Lazy::new(|| {
     register_avg_counter(
@@ -502,6 +512,16 @@ pub static CONSENSUS_CURRENT_ROUND_QUORUM_VOTING_POWER: Lazy<Gauge> = Lazy::new(
     .unwrap()
 });
 
+/// This validator's own performance over sliding windows, as exposed by the inspection service
+pub static VALIDATOR_SELF_REPORT: Lazy<GaugeVec> = Lazy::new(|| {
+    register_gauge_vec!(
+        "aptos_consensus_validator_self_report",
+        "Proposal success, vote latency and pipeline latencies of this validator over sliding windows",
+        &["metric", "window"]
+    )
+    .unwrap()
+});
+
 /// For the current ordering round, for each peer, whether they have voted, and for which hash_index
 pub static CONSENSUS_CURRENT_ROUND_VOTED_POWER: Lazy<GaugeVec> = Lazy::new(|| {
     register_gauge_vec!(
@@ -853,13 +873,15 @@ pub static BLOCK_TRACING: Lazy<HistogramVec> = Lazy::new(|| {
     .unwrap()
 });
 
-/// Traces pipeline stages
-pub static PIPELINE_TRACING: Lazy<HistogramVec> = Lazy::new(|| {
-    register_histogram_vec!(
-        "aptos_consensus_pipeline_tracing",
-        "Histogram for different stages of a block's pipeline",
+/// Traces pipeline stages, with the block id as exemplar
+pub static PIPELINE_TRACING: Lazy<ExemplarHistogramVec> = Lazy::new(|| {
+    register_exemplar_histogram_vec(
+        histogram_opts!(
+            "aptos_consensus_pipeline_tracing",
+            "Histogram for different stages of a block's pipeline",
+            TRACING_BUCKETS.to_vec()
+        ),
         &["stage", "type"],
-        TRACING_BUCKETS.to_vec()
     )
     .unwrap()
 });
@@ -1130,33 +1152,31 @@ pub static PIPELINE_DISCARDED_EXECUTOR_ERROR_COUNT: Lazy<IntCounterVec> = Lazy::
     .unwrap()
 });
 
+/// Label of an executor error in the executor error counters. Only the errors expected during
+/// normal operation have their own label, to keep the existing dashboards and alerts working.
+fn executor_error_label(e: &ExecutorError) -> &'static str {
+    match e {
+        ExecutorError::CouldNotGetData | ExecutorError::BlockNotFound(_) => e.error_code(),
+        _ => "UnexpectedError",
+    }
+}
+
 pub fn log_executor_error_occurred(
     e: ExecutorError,
     counter: &Lazy<IntCounterVec>,
     block_id: HashValue,
 ) {
-    match e {
-        ExecutorError::CouldNotGetData => {
-            counter.with_label_values(&["CouldNotGetData"]).inc();
-            warn!(
-                block_id = block_id,
-                "Execution error - CouldNotGetData {}", block_id
-            );
-        },
-        ExecutorError::BlockNotFound(block_id) => {
-            counter.with_label_values(&["BlockNotFound"]).inc();
-            warn!(
-                block_id = block_id,
-                "Execution error BlockNotFound {}", block_id
-            );
-        },
-        e => {
-            counter.with_label_values(&["UnexpectedError"]).inc();
-            error!(
-                block_id = block_id,
-                "Execution error {:?} for {}", e, block_id
-            );
-        },
+    counter.with_label_values(&[executor_error_label(&e)]).inc();
+    if e.is_retryable() {
+        warn!(
+            block_id = block_id,
+            "Execution error {:?} for {}", e, block_id
+        );
+    } else {
+        error!(
+            block_id = block_id,
+            "Execution error {:?} for {}", e, block_id
+        );
     }
 }
 
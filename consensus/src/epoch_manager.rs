@@ -19,7 +19,8 @@ use crate::{
             ProposerAndVoterHeuristic, ReputationHeuristic,
         },
         proposal_generator::{
-            ChainHealthBackoffConfig, PipelineBackpressureConfig, ProposalGenerator,
+            BatchBackpressureConfig, ChainHealthBackoffConfig, PipelineBackpressureConfig,
+            ProposalGenerator,
         },
         proposal_status_tracker::{ExponentialWindowFailureTracker, OptQSPullParamsProvider},
         proposer_election::ProposerElection,
@@ -28,6 +29,7 @@ use crate::{
         round_state::{ExponentialTimeInterval, RoundState},
     },
     logging::{LogEvent, LogSchema},
+    message_capture::MessageCapture,
     metrics_safety_rules::MetricsSafetyRules,
     monitor,
     network::{
@@ -42,6 +44,7 @@ use crate::{
     persistent_liveness_storage::{LedgerRecoveryData, PersistentLivenessStorage, RecoveryData},
     pipeline::execution_client::TExecutionClient,
     quorum_store::{
+        dissemination_status::BatchDisseminationStatus,
         quorum_store_builder::{DirectMempoolInnerBuilder, InnerBuilder, QuorumStoreBuilder},
         quorum_store_coordinator::CoordinatorCommand,
         quorum_store_db::QuorumStoreStorage,
@@ -173,6 +176,8 @@ pub struct EpochManager<P: OnChainConfigProvider> {
     consensus_publisher: Option<Arc<ConsensusPublisher>>,
     pending_blocks: Arc<Mutex<PendingBlocks>>,
     key_storage: PersistentSafetyStorage,
+    batch_dissemination_status: Arc<BatchDisseminationStatus>,
+    message_capture: Option<MessageCapture>,
 }
 
 impl<P: OnChainConfigProvider> EpochManager<P> {
@@ -201,6 +206,16 @@ impl<P: OnChainConfigProvider> EpochManager<P> {
         let sr_config = &node_config.consensus.safety_rules;
         let safety_rules_manager = SafetyRulesManager::new(sr_config);
         let key_storage = safety_rules_manager::storage(sr_config);
+        let message_capture = node_config
+            .consensus
+            .message_capture
+            .enabled
+            .then(|| MessageCapture::new(&node_config.consensus.message_capture))
+            .transpose()
+            .unwrap_or_else(|e| {
+                error!("Failed to enable consensus message capture: {:?}", e);
+                None
+            });
         Self {
             author,
             config,
@@ -244,6 +259,8 @@ impl<P: OnChainConfigProvider> EpochManager<P> {
             consensus_publisher,
             pending_blocks: Arc::new(Mutex::new(PendingBlocks::new())),
             key_storage,
+            batch_dissemination_status: Arc::new(BatchDisseminationStatus::default()),
+            message_capture,
         }
     }
 
@@ -685,6 +702,7 @@ impl<P: OnChainConfigProvider> EpochManager<P> {
             self.config.quorum_store.clone()
         };
 
+        self.batch_dissemination_status = Arc::new(BatchDisseminationStatus::default());
         let mut quorum_store_builder = if self.quorum_store_enabled {
             info!("Building QuorumStore");
             QuorumStoreBuilder::QuorumStore(InnerBuilder::new(
@@ -703,6 +721,7 @@ impl<P: OnChainConfigProvider> EpochManager<P> {
                 self.quorum_store_storage.clone(),
                 !consensus_config.is_dag_enabled(),
                 consensus_key,
+                self.batch_dissemination_status.clone(),
             ))
         } else {
             info!("Building DirectMempool");
@@ -807,6 +826,10 @@ impl<P: OnChainConfigProvider> EpochManager<P> {
             self.config.pipeline_backpressure.clone(),
             self.config.execution_backpressure.clone(),
         );
+        let batch_backpressure_config = BatchBackpressureConfig::new(
+            self.config.batch_backpressure.clone(),
+            self.batch_dissemination_status.clone(),
+        );
 
         let safety_rules_container = Arc::new(Mutex::new(safety_rules));
 
@@ -882,6 +905,7 @@ impl<P: OnChainConfigProvider> EpochManager<P> {
                 .min_max_txns_in_block_after_filtering_from_backpressure,
             pipeline_backpressure_config,
             chain_health_backoff_config,
+            batch_backpressure_config,
             self.quorum_store_enabled,
             onchain_consensus_config.effective_validator_txn_config(),
             self.config
@@ -1105,6 +1129,11 @@ impl<P: OnChainConfigProvider> EpochManager<P> {
         });
 
         self.epoch_state = Some(epoch_state.clone());
+        if let Some(message_capture) = &self.message_capture {
+            if let Err(e) = message_capture.record_epoch_state(&epoch_state) {
+                warn!("Failed to record epoch state for message capture: {:?}", e);
+            }
+        }
 
         let onchain_consensus_config: anyhow::Result<OnChainConsensusConfig> = payload.get();
         let onchain_execution_config: anyhow::Result<OnChainExecutionConfig> = payload.get();
@@ -1452,6 +1481,11 @@ impl<P: OnChainConfigProvider> EpochManager<P> {
                 BlockStage::EPOCH_MANAGER_RECEIVED,
             );
         }
+        if let Some(message_capture) = &mut self.message_capture {
+            if let Err(e) = message_capture.capture(peer_id, &consensus_msg) {
+                warn!("Failed to capture consensus message: {:?}", e);
+            }
+        }
         // we can't verify signatures from a different epoch
         let maybe_unverified_event = self.check_epoch(peer_id, consensus_msg).await?;
 
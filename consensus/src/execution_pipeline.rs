@@ -114,14 +114,12 @@ impl ExecutionPipeline {
             .expect("Failed to send block to execution pipeline.");
 
         Box::pin(async move {
-            result_rx
-                .await
-                .map_err(|err| ExecutorError::InternalError {
-                    error: format!(
-                        "Failed to receive execution result for block {}: {:?}.",
-                        block_id, err
-                    ),
-                })?
+            result_rx.await.map_err(|err| {
+                ExecutorError::reset(format!(
+                    "Failed to receive execution result for block {}: {:?}.",
+                    block_id, err
+                ))
+            })?
         })
     }
 
@@ -288,39 +286,36 @@ impl ExecutionPipeline {
             .await;
             let pipeline_res = res.map(|(output, execution_duration)| {
                 let pre_commit_hook_fut = pre_commit_hook(&output);
-                let pre_commit_fut: BoxFuture<'static, ExecutorResult<()>> =
-                    if output.epoch_state().is_some() || !enable_pre_commit {
-                        // hack: it causes issue if pre-commit is finished at an epoch ending, and
-                        // we switch to state sync, so we do the pre-commit only after we actually
-                        // decide to commit (in the commit phase)
-                        let executor = executor.clone();
-                        Box::pin(async move {
-                            tokio::task::spawn_blocking(move || {
-                                executor.pre_commit_block(block_id)
-                            })
+                let pre_commit_fut: BoxFuture<'static, ExecutorResult<()>> = if output
+                    .epoch_state()
+                    .is_some()
+                    || !enable_pre_commit
+                {
+                    // hack: it causes issue if pre-commit is finished at an epoch ending, and
+                    // we switch to state sync, so we do the pre-commit only after we actually
+                    // decide to commit (in the commit phase)
+                    let executor = executor.clone();
+                    Box::pin(async move {
+                        tokio::task::spawn_blocking(move || executor.pre_commit_block(block_id))
                             .await
                             .expect("failed to spawn_blocking")?;
-                            pre_commit_hook_fut.await;
-                            Ok(())
-                        })
-                    } else {
-                        // kick off pre-commit right away
-                        let (pre_commit_result_tx, pre_commit_result_rx) = oneshot::channel();
-                        // schedule pre-commit
-                        pre_commit_tx
-                            .send(PreCommitCommand {
-                                block_id,
-                                pre_commit_hook_fut,
-                                result_tx: pre_commit_result_tx,
-                                lifetime_guard,
-                            })
-                            .expect("Failed to send block to pre_commit stage.");
-                        Box::pin(async {
-                            pre_commit_result_rx
-                                .await
-                                .map_err(ExecutorError::internal_err)?
+                        pre_commit_hook_fut.await;
+                        Ok(())
+                    })
+                } else {
+                    // kick off pre-commit right away
+                    let (pre_commit_result_tx, pre_commit_result_rx) = oneshot::channel();
+                    // schedule pre-commit
+                    pre_commit_tx
+                        .send(PreCommitCommand {
+                            block_id,
+                            pre_commit_hook_fut,
+                            result_tx: pre_commit_result_tx,
+                            lifetime_guard,
                         })
-                    };
+                        .expect("Failed to send block to pre_commit stage.");
+                    Box::pin(async { pre_commit_result_rx.await.map_err(ExecutorError::reset)? })
+                };
 
                 PipelineExecutionResult::new(input_txns, output, execution_duration, pre_commit_fut)
             });
@@ -24,6 +24,7 @@ mod epoch_manager;
 mod error;
 mod liveness;
 mod logging;
+pub mod message_capture;
 mod metrics_safety_rules;
 mod network;
 #[cfg(test)]
@@ -49,6 +50,7 @@ mod test_utils;
 mod twins;
 mod txn_notifier;
 pub mod util;
+mod validator_performance;
 
 mod block_preparer;
 pub mod consensus_observer;
@@ -74,6 +76,8 @@ pub use consensusdb::CONSENSUS_DB_NAME;
 pub use quorum_store::quorum_store_db::QUORUM_STORE_DB_NAME;
 #[cfg(feature = "fuzzing")]
 pub use round_manager::round_manager_fuzzing;
+#[cfg(feature = "fuzzing")]
+pub use round_manager::round_manager_replay;
 
 pub struct IntGaugeGuard {
     gauge: IntGauge,
@@ -8,18 +8,20 @@ use super::{
 use crate::{
     block_storage::BlockReader,
     counters::{
-        CHAIN_HEALTH_BACKOFF_TRIGGERED, EXECUTION_BACKPRESSURE_ON_PROPOSAL_TRIGGERED,
-        PIPELINE_BACKPRESSURE_ON_PROPOSAL_TRIGGERED, PROPOSER_DELAY_PROPOSAL,
-        PROPOSER_ESTIMATED_CALIBRATED_BLOCK_TXNS, PROPOSER_MAX_BLOCK_TXNS_AFTER_FILTERING,
-        PROPOSER_MAX_BLOCK_TXNS_TO_EXECUTE, PROPOSER_PENDING_BLOCKS_COUNT,
-        PROPOSER_PENDING_BLOCKS_FILL_FRACTION,
+        BATCH_BACKPRESSURE_ON_PROPOSAL_TRIGGERED, CHAIN_HEALTH_BACKOFF_TRIGGERED,
+        EXECUTION_BACKPRESSURE_ON_PROPOSAL_TRIGGERED, PIPELINE_BACKPRESSURE_ON_PROPOSAL_TRIGGERED,
+        PROPOSER_DELAY_PROPOSAL, PROPOSER_ESTIMATED_CALIBRATED_BLOCK_TXNS,
+        PROPOSER_MAX_BLOCK_TXNS_AFTER_FILTERING, PROPOSER_MAX_BLOCK_TXNS_TO_EXECUTE,
+        PROPOSER_PENDING_BLOCKS_COUNT, PROPOSER_PENDING_BLOCKS_FILL_FRACTION,
     },
     payload_client::PayloadClient,
+    quorum_store::dissemination_status::BatchDisseminationStatus,
     util::time_service::TimeService,
 };
 use anyhow::{bail, ensure, format_err, Context};
 use aptos_config::config::{
-    ChainHealthBackoffValues, ExecutionBackpressureConfig, PipelineBackpressureValues,
+    BatchBackpressureValues, ChainHealthBackoffValues, ExecutionBackpressureConfig,
+    PipelineBackpressureValues,
 };
 use aptos_consensus_types::{
     block::Block,
@@ -225,6 +227,64 @@ impl PipelineBackpressureConfig {
     }
 }
 
+/// Backpressure from quorum store batch dissemination: reduces proposal sizes when the batches
+/// created by this node take long to get their proofs of store.
+#[derive(Clone)]
+pub struct BatchBackpressureConfig {
+    backoffs: Vec<BatchBackpressureValues>,
+    dissemination_status: Arc<BatchDisseminationStatus>,
+}
+
+impl BatchBackpressureConfig {
+    pub fn new(
+        backoffs: Vec<BatchBackpressureValues>,
+        dissemination_status: Arc<BatchDisseminationStatus>,
+    ) -> Self {
+        Self {
+            backoffs,
+            dissemination_status,
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn new_no_backoff() -> Self {
+        Self {
+            backoffs: vec![],
+            dissemination_status: Arc::new(BatchDisseminationStatus::default()),
+        }
+    }
+
+    /// Backoffs are in increasing order of severity, the last one activated by the current
+    /// dissemination status is used.
+    pub fn get_backoff(&self) -> Option<&BatchBackpressureValues> {
+        if self.backoffs.is_empty() {
+            return None;
+        }
+
+        let pending_batch_bytes = self.dissemination_status.pending_batch_bytes();
+        let proof_latency = self.dissemination_status.proof_latency();
+        self.backoffs
+            .iter()
+            .filter(|v| {
+                pending_batch_bytes > v.back_pressure_pending_batch_bytes_limit
+                    || proof_latency.as_millis() as u64 > v.back_pressure_proof_latency_limit_ms
+            })
+            .last()
+            .map(|v| {
+                sample!(
+                    SampleRate::Duration(Duration::from_secs(10)),
+                    warn!(
+                        "Using batch backpressure config for {} pending batch bytes and {}ms proof latency: {:?}",
+                        pending_batch_bytes,
+                        proof_latency.as_millis(),
+                        v
+                    )
+                );
+                v
+            })
+    }
+}
+
 /// ProposalGenerator is responsible for generating the proposed block on demand: it's typically
 /// used by a validator that believes it's a valid candidate for serving as a proposer at a given
 /// round.
@@ -263,6 +323,7 @@ pub struct ProposalGenerator {
 
     pipeline_backpressure_config: PipelineBackpressureConfig,
     chain_health_backoff_config: ChainHealthBackoffConfig,
+    batch_backpressure_config: BatchBackpressureConfig,
 
     // Last round that a proposal was generated
     last_round_generated: Mutex<Round>,
@@ -288,6 +349,7 @@ impl ProposalGenerator {
         min_max_txns_in_block_after_filtering_from_backpressure: u64,
         pipeline_backpressure_config: PipelineBackpressureConfig,
         chain_health_backoff_config: ChainHealthBackoffConfig,
+        batch_backpressure_config: BatchBackpressureConfig,
         quorum_store_enabled: bool,
         vtxn_config: ValidatorTxnConfig,
         allow_batches_without_pos_in_proposal: bool,
@@ -306,6 +368,7 @@ impl ProposalGenerator {
             max_failed_authors_to_store,
             pipeline_backpressure_config,
             chain_health_backoff_config,
+            batch_backpressure_config,
             last_round_generated: Mutex::new(0),
             quorum_store_enabled,
             vtxn_config,
@@ -560,6 +623,19 @@ impl ProposalGenerator {
             PIPELINE_BACKPRESSURE_ON_PROPOSAL_TRIGGERED.observe(0.0);
         };
 
+        let batch_backpressure = self.batch_backpressure_config.get_backoff();
+        if let Some(value) = batch_backpressure {
+            values_max_block_txns_after_filtering
+                .push(value.max_sending_block_txns_after_filtering_override);
+            values_max_block.push(
+                self.max_block_txns
+                    .compute_with_bytes(value.max_sending_block_bytes_override),
+            );
+            BATCH_BACKPRESSURE_ON_PROPOSAL_TRIGGERED.observe(1.0);
+        } else {
+            BATCH_BACKPRESSURE_ON_PROPOSAL_TRIGGERED.observe(0.0);
+        }
+
         let mut execution_backpressure_applied = false;
         if let Some(config) = &self.pipeline_backpressure_config.execution {
             let execution_backpressure = self
@@ -617,6 +693,7 @@ impl ProposalGenerator {
                 max_txns_from_block_to_execute.unwrap_or(max_block_txns_after_filtering),
             max_block_size = max_block_size,
             is_pipeline_backpressure = pipeline_backpressure.is_some(),
+            is_batch_backpressure = batch_backpressure.is_some(),
             is_execution_backpressure = execution_backpressure_applied,
             is_chain_health_backoff = chain_health_backoff.is_some(),
             round = round,
@@ -6,15 +6,18 @@ use crate::{
     block_storage::BlockReader,
     liveness::{
         proposal_generator::{
-            ChainHealthBackoffConfig, PipelineBackpressureConfig, ProposalGenerator,
+            BatchBackpressureConfig, ChainHealthBackoffConfig, PipelineBackpressureConfig,
+            ProposalGenerator,
         },
         proposal_status_tracker::TOptQSPullParamsProvider,
         rotating_proposer_election::RotatingProposer,
         unequivocal_proposer_election::UnequivocalProposerElection,
     },
+    quorum_store::dissemination_status::BatchDisseminationStatus,
     test_utils::{build_empty_tree, MockPayloadManager, TreeInserter},
     util::mock_time_service::SimulatedTimeService,
 };
+use aptos_config::config::BatchBackpressureValues;
 use aptos_consensus_types::{
     block::{block_test_utils::certificate_for_genesis, Block},
     common::Author,
@@ -54,6 +57,7 @@ async fn test_proposal_generation_empty_tree() {
         1,
         PipelineBackpressureConfig::new_no_backoff(),
         ChainHealthBackoffConfig::new_no_backoff(),
+        BatchBackpressureConfig::new_no_backoff(),
         false,
         ValidatorTxnConfig::default_disabled(),
         true,
@@ -100,6 +104,7 @@ async fn test_proposal_generation_parent() {
         1,
         PipelineBackpressureConfig::new_no_backoff(),
         ChainHealthBackoffConfig::new_no_backoff(),
+        BatchBackpressureConfig::new_no_backoff(),
         false,
         ValidatorTxnConfig::default_disabled(),
         true,
@@ -176,6 +181,7 @@ async fn test_old_proposal_generation() {
         1,
         PipelineBackpressureConfig::new_no_backoff(),
         ChainHealthBackoffConfig::new_no_backoff(),
+        BatchBackpressureConfig::new_no_backoff(),
         false,
         ValidatorTxnConfig::default_disabled(),
         true,
@@ -217,6 +223,7 @@ async fn test_correct_failed_authors() {
         1,
         PipelineBackpressureConfig::new_no_backoff(),
         ChainHealthBackoffConfig::new_no_backoff(),
+        BatchBackpressureConfig::new_no_backoff(),
         false,
         ValidatorTxnConfig::default_disabled(),
         true,
@@ -242,3 +249,52 @@ async fn test_correct_failed_authors() {
     assert_eq!(result.failed_authors().unwrap()[3], (4, peer1));
     assert_eq!(result.failed_authors().unwrap()[4], (5, peer2));
 }
+
+#[test]
+fn test_batch_backpressure_backoff() {
+    let values = |bytes_limit, latency_limit_ms, block_bytes| BatchBackpressureValues {
+        back_pressure_pending_batch_bytes_limit: bytes_limit,
+        back_pressure_proof_latency_limit_ms: latency_limit_ms,
+        max_sending_block_txns_after_filtering_override: 100,
+        max_sending_block_bytes_override: block_bytes,
+    };
+    let status = Arc::new(BatchDisseminationStatus::default());
+    let config = BatchBackpressureConfig::new(
+        vec![values(1000, 1000, 2000), values(2000, 2000, 1000)],
+        status.clone(),
+    );
+
+    assert!(config.get_backoff().is_none());
+
+    // Either signal activates a backoff
+    status.update(1500, Duration::ZERO);
+    assert_eq!(
+        config
+            .get_backoff()
+            .unwrap()
+            .max_sending_block_bytes_override,
+        2000
+    );
+    status.update(0, Duration::from_millis(1500));
+    assert_eq!(
+        config
+            .get_backoff()
+            .unwrap()
+            .max_sending_block_bytes_override,
+        2000
+    );
+
+    // The most severe activated backoff is used
+    status.update(2500, Duration::from_millis(1500));
+    assert_eq!(
+        config
+            .get_backoff()
+            .unwrap()
+            .max_sending_block_bytes_override,
+        1000
+    );
+
+    // Backoff stops once dissemination catches up
+    status.update(0, Duration::from_millis(100));
+    assert!(config.get_backoff().is_none());
+}
@@ -0,0 +1,290 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Captures the consensus messages received by the node into a bounded on-disk ring, so that
+//! the rounds leading to a liveness incident can be replayed offline (see
+//! `round_manager_replay` and the `consensus-replay` binary).
+//!
+//! Messages are appended to one file per (epoch, round), each record being a little endian u32
+//! length followed by the BCS encoded [`CapturedMessage`]. Only the most recent
+//! `max_rounds` rounds are kept, older files are deleted as new rounds are captured. The
+//! [`EpochState`] of every captured epoch is written alongside, so that the validators can be
+//! recreated for the replay.
+
+use crate::network_interface::{CommitMessage, ConsensusMsg};
+use anyhow::{ensure, Context};
+use aptos_config::config::MessageCaptureConfig;
+use aptos_consensus_types::common::{Author, Round};
+use aptos_infallible::duration_since_epoch;
+use aptos_logger::prelude::*;
+use aptos_types::epoch_state::EpochState;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeSet,
+    fs::{self, OpenOptions},
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+const CAPTURE_FILE_EXTENSION: &str = "msgs";
+const EPOCH_STATE_FILE_EXTENSION: &str = "epoch";
+
+/// A consensus message as received from the network.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct CapturedMessage {
+    pub epoch: u64,
+    pub round: Round,
+    pub author: Author,
+    pub timestamp_usecs: u64,
+    pub message: ConsensusMsg,
+}
+
+/// Returns the epoch and round of the messages driving the ordering of blocks, None for the
+/// other messages (e.g., block retrieval or quorum store messages), which are not captured.
+pub fn message_epoch_and_round(message: &ConsensusMsg) -> Option<(u64, Round)> {
+    match message {
+        ConsensusMsg::ProposalMsg(proposal) => {
+            Some((proposal.epoch(), proposal.proposal().round()))
+        },
+        ConsensusMsg::VoteMsg(vote) => {
+            Some((vote.epoch(), vote.vote().vote_data().proposed().round()))
+        },
+        ConsensusMsg::OrderVoteMsg(order_vote) => Some((
+            order_vote.epoch(),
+            order_vote.order_vote().ledger_info().round(),
+        )),
+        ConsensusMsg::RoundTimeoutMsg(timeout) => Some((timeout.epoch(), timeout.round())),
+        ConsensusMsg::SyncInfo(sync_info) => Some((sync_info.epoch(), sync_info.highest_round())),
+        ConsensusMsg::CommitVoteMsg(vote) => Some((vote.epoch(), vote.round())),
+        ConsensusMsg::CommitDecisionMsg(decision) => Some((decision.epoch(), decision.round())),
+        ConsensusMsg::CommitMessage(message) => match message.as_ref() {
+            CommitMessage::Vote(vote) => Some((vote.epoch(), vote.round())),
+            CommitMessage::Decision(decision) => Some((decision.epoch(), decision.round())),
+            CommitMessage::Ack(_) | CommitMessage::Nack => None,
+        },
+        _ => None,
+    }
+}
+
+/// Writes the captured messages to disk, keeping at most `max_rounds` rounds.
+pub struct MessageCapture {
+    dir: PathBuf,
+    max_rounds: usize,
+    /// The (epoch, round) of the files currently on disk.
+    rounds: BTreeSet<(u64, Round)>,
+}
+
+impl MessageCapture {
+    pub fn new(config: &MessageCaptureConfig) -> anyhow::Result<Self> {
+        let dir = config.capture_dir();
+        fs::create_dir_all(&dir)
+            .with_context(|| format!("Failed to create capture dir {}", dir.display()))?;
+        // Pick up the rounds captured before a restart, so that they count towards the limit.
+        let rounds = capture_files(&dir)?
+            .into_iter()
+            .map(|(epoch_round, _)| epoch_round)
+            .collect();
+        Ok(Self {
+            dir,
+            max_rounds: config.max_rounds,
+            rounds,
+        })
+    }
+
+    /// Appends the message to the file of its round. Messages which do not belong to a round, or
+    /// belong to a round which already fell out of the ring, are ignored.
+    pub fn capture(&mut self, author: Author, message: &ConsensusMsg) -> anyhow::Result<()> {
+        let Some((epoch, round)) = message_epoch_and_round(message) else {
+            return Ok(());
+        };
+        if !self.rounds.contains(&(epoch, round)) {
+            if self.rounds.len() >= self.max_rounds
+                && self
+                    .rounds
+                    .first()
+                    .map_or(true, |oldest| *oldest > (epoch, round))
+            {
+                return Ok(());
+            }
+            self.rounds.insert((epoch, round));
+            self.prune()?;
+        }
+
+        let captured = CapturedMessage {
+            epoch,
+            round,
+            author,
+            timestamp_usecs: duration_since_epoch().as_micros() as u64,
+            message: message.clone(),
+        };
+        let bytes = bcs::to_bytes(&captured)?;
+        let mut record = Vec::with_capacity(4 + bytes.len());
+        record.extend_from_slice(&(bytes.len() as u32).to_le_bytes());
+        record.extend_from_slice(&bytes);
+        OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(self.dir.join(capture_file_name(epoch, round)))?
+            .write_all(&record)?;
+        Ok(())
+    }
+
+    /// Records the validators of a new epoch.
+    pub fn record_epoch_state(&self, epoch_state: &EpochState) -> anyhow::Result<()> {
+        let path = self.dir.join(epoch_state_file_name(epoch_state.epoch));
+        fs::write(&path, bcs::to_bytes(epoch_state)?)
+            .with_context(|| format!("Failed to write epoch state to {}", path.display()))
+    }
+
+    fn prune(&mut self) -> anyhow::Result<()> {
+        while self.rounds.len() > self.max_rounds {
+            if let Some((epoch, round)) = self.rounds.pop_first() {
+                let path = self.dir.join(capture_file_name(epoch, round));
+                if path.exists() {
+                    fs::remove_file(&path).with_context(|| {
+                        format!("Failed to delete capture file {}", path.display())
+                    })?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Reads all the messages captured in the directory, ordered by (epoch, round) and then by
+/// the order they were received in.
+pub fn read_captured_messages(dir: &Path) -> anyhow::Result<Vec<CapturedMessage>> {
+    let mut messages = vec![];
+    for (_, path) in capture_files(dir)? {
+        let bytes = fs::read(&path)
+            .with_context(|| format!("Failed to read capture file {}", path.display()))?;
+        let mut remaining = bytes.as_slice();
+        while !remaining.is_empty() {
+            ensure!(
+                remaining.len() >= 4,
+                "Truncated record length in {}",
+                path.display()
+            );
+            let (len, rest) = remaining.split_at(4);
+            let len = u32::from_le_bytes(len.try_into()?) as usize;
+            if rest.len() < len {
+                // The node may have stopped in the middle of a write.
+                warn!("Truncated record in capture file {}", path.display());
+                break;
+            }
+            let (record, rest) = rest.split_at(len);
+            messages.push(bcs::from_bytes(record)?);
+            remaining = rest;
+        }
+    }
+    Ok(messages)
+}
+
+/// Reads the epoch state recorded for the epoch, if any.
+pub fn read_epoch_state(dir: &Path, epoch: u64) -> anyhow::Result<Option<EpochState>> {
+    let path = dir.join(epoch_state_file_name(epoch));
+    if !path.exists() {
+        return Ok(None);
+    }
+    let bytes = fs::read(&path)
+        .with_context(|| format!("Failed to read epoch state from {}", path.display()))?;
+    Ok(Some(bcs::from_bytes(&bytes)?))
+}
+
+fn epoch_state_file_name(epoch: u64) -> String {
+    format!("{:020}.{}", epoch, EPOCH_STATE_FILE_EXTENSION)
+}
+
+fn capture_file_name(epoch: u64, round: Round) -> String {
+    format!("{:020}_{:020}.{}", epoch, round, CAPTURE_FILE_EXTENSION)
+}
+
+fn parse_capture_file_name(path: &Path) -> Option<(u64, Round)> {
+    if path.extension()? != CAPTURE_FILE_EXTENSION {
+        return None;
+    }
+    let (epoch, round) = path.file_stem()?.to_str()?.split_once('_')?;
+    Some((epoch.parse().ok()?, round.parse().ok()?))
+}
+
+fn capture_files(dir: &Path) -> anyhow::Result<Vec<((u64, Round), PathBuf)>> {
+    let mut files = vec![];
+    for entry in fs::read_dir(dir)
+        .with_context(|| format!("Failed to list capture dir {}", dir.display()))?
+    {
+        let path = entry?.path();
+        if let Some(epoch_round) = parse_capture_file_name(&path) {
+            files.push((epoch_round, path));
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aptos_consensus_types::pipeline::commit_decision::CommitDecision;
+    use aptos_crypto::HashValue;
+    use aptos_temppath::TempPath;
+    use aptos_types::{
+        aggregate_signature::AggregateSignature,
+        block_info::BlockInfo,
+        ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
+    };
+
+    fn commit_decision_msg(round: Round) -> ConsensusMsg {
+        let ledger_info = LedgerInfoWithSignatures::new(
+            LedgerInfo::new(BlockInfo::random_with_epoch(1, round), HashValue::zero()),
+            AggregateSignature::empty(),
+        );
+        ConsensusMsg::CommitDecisionMsg(Box::new(CommitDecision::new(ledger_info)))
+    }
+
+    #[test]
+    fn test_capture_ring() {
+        let dir = TempPath::new();
+        let config = MessageCaptureConfig {
+            enabled: true,
+            capture_dir: dir.path().to_path_buf(),
+            max_rounds: 2,
+            ..Default::default()
+        };
+        let author = Author::random();
+
+        let mut capture = MessageCapture::new(&config).unwrap();
+        for round in [1, 2, 2, 3] {
+            capture
+                .capture(author, &commit_decision_msg(round))
+                .unwrap();
+        }
+        // Round 1 is older than the ring
+        capture.capture(author, &commit_decision_msg(1)).unwrap();
+
+        let rounds: Vec<_> = read_captured_messages(dir.path())
+            .unwrap()
+            .iter()
+            .map(|message| message.round)
+            .collect();
+        assert_eq!(rounds, vec![2, 2, 3]);
+
+        // The ring survives restarts
+        let mut capture = MessageCapture::new(&config).unwrap();
+        capture.capture(author, &commit_decision_msg(4)).unwrap();
+        let rounds: Vec<_> = read_captured_messages(dir.path())
+            .unwrap()
+            .iter()
+            .map(|message| message.round)
+            .collect();
+        assert_eq!(rounds, vec![3, 4]);
+
+        let epoch_state = EpochState::empty();
+        assert!(read_epoch_state(dir.path(), epoch_state.epoch)
+            .unwrap()
+            .is_none());
+        capture.record_epoch_state(&epoch_state).unwrap();
+        assert!(
+            read_epoch_state(dir.path(), epoch_state.epoch).unwrap() == Some(epoch_state.clone())
+        );
+    }
+}
@@ -19,7 +19,7 @@ use aptos_consensus_types::{
 };
 use aptos_crypto::HashValue;
 use aptos_executor_types::{
-    ExecutorError::{DataNotFound, InternalError},
+    ExecutorError::{CouldNotGetData, DataNotFound},
     *,
 };
 use aptos_infallible::Mutex;
@@ -511,23 +511,25 @@ async fn get_transactions_for_observer(
         Entry::Occupied(mut value) => match value.get_mut() {
             BlockPayloadStatus::AvailableAndVerified(block_payload) => block_payload.clone(),
             BlockPayloadStatus::AvailableAndUnverified(_) => {
-                // This shouldn't happen (the payload should already be verified)
-                let error = format!(
+                // This shouldn't happen (the payload should already be verified), but the
+                // payload may still be verified later, so the caller should retry
+                warn!(
                     "Payload data for block epoch {}, round {} is unverified!",
                     block.epoch(),
                     block.round()
                 );
-                return Err(InternalError { error });
+                return Err(CouldNotGetData);
             },
         },
         Entry::Vacant(_) => {
-            // This shouldn't happen (the payload should already be present)
-            let error = format!(
+            // This shouldn't happen (the payload should already be present), but the
+            // payload may still be received later, so the caller should retry
+            warn!(
                 "Missing payload data for block epoch {}, round {}!",
                 block.epoch(),
                 block.round()
             );
-            return Err(InternalError { error });
+            return Err(DataNotFound(block.id()));
         },
     };
 
@@ -758,3 +760,49 @@ impl TPayloadManager for ConsensusObserverPayloadManager {
             .await;
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::consensus_observer::network::observer_message::BlockPayload;
+    use aptos_consensus_types::block::block_test_utils::certificate_for_genesis;
+
+    #[tokio::test]
+    async fn test_observer_payload_not_available_is_retryable() {
+        let block = Block::new_nil(1, certificate_for_genesis(), vec![]);
+        let block_payloads = Arc::new(Mutex::new(BTreeMap::new()));
+
+        // The payload is missing
+        let error = get_transactions_for_observer(&block, &block_payloads, &None)
+            .await
+            .unwrap_err();
+        assert_eq!(error, DataNotFound(block.id()));
+        assert!(error.is_retryable());
+
+        // The payload is not verified yet
+        let block_payload = BlockPayload::new(
+            block.gen_block_info(HashValue::zero(), 0, None),
+            BlockTransactionPayload::new_in_quorum_store(vec![], vec![]),
+        );
+        block_payloads.lock().insert(
+            (block.epoch(), block.round()),
+            BlockPayloadStatus::AvailableAndUnverified(block_payload.clone()),
+        );
+        let error = get_transactions_for_observer(&block, &block_payloads, &None)
+            .await
+            .unwrap_err();
+        assert_eq!(error, CouldNotGetData);
+        assert!(error.is_retryable());
+
+        // The payload is verified
+        block_payloads.lock().insert(
+            (block.epoch(), block.round()),
+            BlockPayloadStatus::AvailableAndVerified(block_payload),
+        );
+        let (transactions, limit) = get_transactions_for_observer(&block, &block_payloads, &None)
+            .await
+            .unwrap();
+        assert!(transactions.is_empty());
+        assert_eq!(limit, None);
+    }
+}
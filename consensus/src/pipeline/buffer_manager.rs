@@ -32,7 +32,7 @@ use aptos_consensus_types::{
     pipelined_block::PipelinedBlock,
 };
 use aptos_crypto::HashValue;
-use aptos_executor_types::ExecutorResult;
+use aptos_executor_types::{ExecutorErrorCategory, ExecutorResult};
 use aptos_logger::prelude::*;
 use aptos_network::protocols::{rpc::error::RpcError, wire::handshake::v1::ProtocolId};
 use aptos_reliable_broadcast::{DropGuard, ReliableBroadcast};
@@ -985,10 +985,15 @@ impl BufferManager {
                 Some(response) = self.execution_wait_phase_rx.next() => {
                     monitor!("buffer_manager_process_execution_wait_response", {
                     let response_block_id = response.block_id;
+                    // the request is dropped if the pipeline was reset, which resets us as well
+                    let is_reset = matches!(
+                        &response.inner,
+                        Err(e) if e.category() == ExecutorErrorCategory::Reset
+                    );
                     self.process_execution_response(response).await;
                     if let Some(block_id) = self.advance_execution_root() {
                         // if the response is for the current execution root, retry the schedule phase
-                        if response_block_id == block_id {
+                        if response_block_id == block_id && !is_reset {
                             let mut tx = self.execution_schedule_retry_tx.clone();
                             tokio::spawn(async move {
                                 tokio::time::sleep(Duration::from_millis(100)).await;
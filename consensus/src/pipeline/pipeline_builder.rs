@@ -144,12 +144,17 @@ impl Tracker {
         };
         let wait_time = started_at.duration_since(self.created_at);
         let work_time = Instant::now().duration_since(started_at);
-        counters::PIPELINE_TRACING
-            .with_label_values(&[self.name, "wait_time"])
-            .observe(wait_time.as_secs_f64());
-        counters::PIPELINE_TRACING
-            .with_label_values(&[self.name, "work_time"])
-            .observe(work_time.as_secs_f64());
+        let exemplar = || vec![("block_id", self.block_id.to_hex())];
+        counters::PIPELINE_TRACING.observe_with_exemplar(
+            &[self.name, "wait_time"],
+            wait_time.as_secs_f64(),
+            exemplar(),
+        );
+        counters::PIPELINE_TRACING.observe_with_exemplar(
+            &[self.name, "work_time"],
+            work_time.as_secs_f64(),
+            exemplar(),
+        );
         info!(
             "[Pipeline] Block {} {} {} finishes {}, waits {}, takes {}",
             self.block_id,
@@ -409,10 +414,18 @@ impl PipelineBuilder {
     async fn prepare(preparer: Arc<BlockPreparer>, block: Arc<Block>) -> TaskResult<PrepareResult> {
         let mut tracker = Tracker::start_waiting("prepare", &block);
         tracker.start_working();
-        // the loop can only be abort by the caller
+        // the loop can only be abort by the caller, or by an error which retrying won't fix
         let input_txns = loop {
             match preparer.prepare_block(&block).await {
                 Ok(input_txns) => break input_txns,
+                Err(e) if !e.is_retryable() => {
+                    error!(
+                        "[BlockPreparer] failed to prepare block {}: {}",
+                        block.id(),
+                        e
+                    );
+                    return Err(TaskError::InternalError(Arc::new(e.into())));
+                },
                 Err(e) => {
                     warn!(
                         "[BlockPreparer] failed to prepare block {}, retrying: {}",
@@ -5,13 +5,17 @@
 use crate::{
     counters::BUFFER_MANAGER_PHASE_PROCESS_SECONDS,
     pipeline::buffer_manager::{Receiver, Sender},
+    validator_performance::VALIDATOR_PERFORMANCE,
 };
 use aptos_logger::debug;
 use async_trait::async_trait;
 use futures::{SinkExt, StreamExt};
-use std::sync::{
-    atomic::{AtomicBool, AtomicU64, Ordering},
-    Arc,
+use std::{
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Arc,
+    },
+    time::Instant,
 };
 
 #[async_trait]
@@ -97,7 +101,10 @@ impl<T: StatelessPipeline> PipelinePhase<T> {
                 let _timer = BUFFER_MANAGER_PHASE_PROCESS_SECONDS
                     .with_label_values(&[T::NAME])
                     .start_timer();
-                self.processor.process(req).await
+                let start = Instant::now();
+                let response = self.processor.process(req).await;
+                VALIDATOR_PERFORMANCE.record_pipeline_latency(T::NAME, start.elapsed());
+                response
             };
             if let Some(tx) = &mut self.maybe_tx {
                 if tx.send(response).await.is_err() {
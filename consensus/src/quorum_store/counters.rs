@@ -5,8 +5,8 @@
 
 use aptos_metrics_core::{
     exponential_buckets, op_counters::DurationHistogram, register_avg_counter, register_histogram,
-    register_histogram_vec, register_int_counter, register_int_counter_vec, Histogram,
-    HistogramVec, IntCounter, IntCounterVec,
+    register_histogram_vec, register_int_counter, register_int_counter_vec, register_int_gauge,
+    Histogram, HistogramVec, IntCounter, IntCounterVec, IntGauge,
 };
 use once_cell::sync::Lazy;
 use std::time::Duration;
@@ -873,6 +873,24 @@ pub static BATCH_CREATION_PERSIST_LATENCY: Lazy<DurationHistogram> = Lazy::new(|
 });
 
 /// Histogram of the time durations from created batch to created PoS.
+/// Bytes of the batches created by this node that are still waiting for a proof of store.
+pub static PENDING_BATCH_BYTES_WITHOUT_POS: Lazy<IntGauge> = Lazy::new(|| {
+    register_int_gauge!(
+        "quorum_store_pending_batch_bytes_without_PoS",
+        "Bytes of the local batches that are still waiting for a proof of store."
+    )
+    .unwrap()
+});
+
+/// Proof of store latency reported to the proposer for batch backpressure.
+pub static PROOF_LATENCY_FOR_BACKPRESSURE_MS: Lazy<IntGauge> = Lazy::new(|| {
+    register_int_gauge!(
+        "quorum_store_proof_latency_for_backpressure_ms",
+        "Proof of store latency of local batches, as reported to the proposer for backpressure."
+    )
+    .unwrap()
+});
+
 pub static BATCH_TO_POS_DURATION: Lazy<DurationHistogram> = Lazy::new(|| {
     DurationHistogram::new(
         register_histogram!(
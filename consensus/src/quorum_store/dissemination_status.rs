@@ -0,0 +1,36 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use std::{
+    sync::atomic::{AtomicU64, Ordering},
+    time::Duration,
+};
+
+/// How far behind the dissemination of the batches created by this node is, as observed by the
+/// proof coordinator. Shared with the proposal generator, which reduces the size of proposals
+/// when batches take long to get their proofs of store.
+#[derive(Debug, Default)]
+pub struct BatchDisseminationStatus {
+    /// Total bytes of the local batches still waiting for a proof of store.
+    pending_batch_bytes: AtomicU64,
+    /// Time it takes for local batches to get a proof of store: the latency of the last proof
+    /// formed, or the age of the oldest batch still waiting for one, if that is larger.
+    proof_latency_ms: AtomicU64,
+}
+
+impl BatchDisseminationStatus {
+    pub fn update(&self, pending_batch_bytes: u64, proof_latency: Duration) {
+        self.pending_batch_bytes
+            .store(pending_batch_bytes, Ordering::Relaxed);
+        self.proof_latency_ms
+            .store(proof_latency.as_millis() as u64, Ordering::Relaxed);
+    }
+
+    pub fn pending_batch_bytes(&self) -> u64 {
+        self.pending_batch_bytes.load(Ordering::Relaxed)
+    }
+
+    pub fn proof_latency(&self) -> Duration {
+        Duration::from_millis(self.proof_latency_ms.load(Ordering::Relaxed))
+    }
+}
@@ -10,6 +10,7 @@ pub(crate) mod batch_generator;
 pub(crate) mod batch_proof_queue;
 pub(crate) mod batch_requester;
 pub(crate) mod batch_store;
+pub(crate) mod dissemination_status;
 pub(crate) mod network_listener;
 pub(crate) mod proof_coordinator;
 pub(crate) mod proof_manager;
@@ -9,6 +9,7 @@ use crate::{
         batch_generator::BatchGeneratorCommand,
         batch_store::BatchReader,
         counters,
+        dissemination_status::BatchDisseminationStatus,
         tracing::{observe_batch, observe_batch_vote_pct, BatchStage},
         utils::Timeouts,
     },
@@ -171,6 +172,9 @@ pub(crate) struct ProofCoordinator {
     proof_cache: ProofCache,
     broadcast_proofs: bool,
     batch_expiry_gap_when_init_usecs: u64,
+    // to signal the proposer when batch dissemination lags
+    dissemination_status: Arc<BatchDisseminationStatus>,
+    last_proof_latency: Duration,
 }
 
 //PoQS builder object - gather signed digest to form PoQS
@@ -183,6 +187,7 @@ impl ProofCoordinator {
         proof_cache: ProofCache,
         broadcast_proofs: bool,
         batch_expiry_gap_when_init_usecs: u64,
+        dissemination_status: Arc<BatchDisseminationStatus>,
     ) -> Self {
         Self {
             peer_id,
@@ -195,6 +200,8 @@ impl ProofCoordinator {
             proof_cache,
             broadcast_proofs,
             batch_expiry_gap_when_init_usecs,
+            dissemination_status,
+            last_proof_latency: Duration::ZERO,
         }
     }
 
@@ -267,6 +274,7 @@ impl ProofCoordinator {
                     )?
                     .elapsed();
                 counters::BATCH_TO_POS_DURATION.observe_duration(duration);
+                self.last_proof_latency = duration;
                 return Ok(Some(proof));
             }
         } else {
@@ -289,6 +297,27 @@ impl ProofCoordinator {
         }
     }
 
+    /// Publishes the bytes of the local batches still waiting for a proof of store, and how long
+    /// proofs currently take to form.
+    fn update_dissemination_status(&self) {
+        let mut pending_batch_bytes = 0;
+        let mut proof_latency = self.last_proof_latency;
+        for (batch_info, state) in &self.batch_info_to_proof {
+            // Proofs re-inited due to very late votes are not waiting for anything.
+            if state.completed || !state.self_voted {
+                continue;
+            }
+            pending_batch_bytes += batch_info.num_bytes();
+            if let Some(created) = self.batch_info_to_time.get(batch_info) {
+                proof_latency = proof_latency.max(created.elapsed());
+            }
+        }
+        counters::PENDING_BATCH_BYTES_WITHOUT_POS.set(pending_batch_bytes as i64);
+        counters::PROOF_LATENCY_FOR_BACKPRESSURE_MS.set(proof_latency.as_millis() as i64);
+        self.dissemination_status
+            .update(pending_batch_bytes, proof_latency);
+    }
+
     async fn expire(&mut self) {
         let mut batch_ids = vec![];
         for signed_batch_info_info in self.timeouts.expire() {
@@ -416,7 +445,10 @@ impl ProofCoordinator {
                     }
                 }),
                 _ = interval.tick() => {
-                    monitor!("proof_coordinator_handle_tick", self.expire().await);
+                    monitor!("proof_coordinator_handle_tick", {
+                        self.expire().await;
+                        self.update_dissemination_status();
+                    });
                 }
             }
         }
@@ -16,6 +16,7 @@ use crate::{
         batch_store::{BatchReader, BatchReaderImpl, BatchStore},
         counters,
         direct_mempool_quorum_store::DirectMempoolQuorumStore,
+        dissemination_status::BatchDisseminationStatus,
         network_listener::NetworkListener,
         proof_coordinator::{ProofCoordinator, ProofCoordinatorCommand},
         proof_manager::{ProofManager, ProofManagerCommand},
@@ -149,6 +150,7 @@ pub struct InnerBuilder {
     batch_reader: Option<Arc<dyn BatchReader>>,
     broadcast_proofs: bool,
     consensus_key: Arc<PrivateKey>,
+    dissemination_status: Arc<BatchDisseminationStatus>,
 }
 
 impl InnerBuilder {
@@ -169,6 +171,7 @@ impl InnerBuilder {
         quorum_store_storage: Arc<dyn QuorumStoreStorage>,
         broadcast_proofs: bool,
         consensus_key: Arc<PrivateKey>,
+        dissemination_status: Arc<BatchDisseminationStatus>,
     ) -> Self {
         let (coordinator_tx, coordinator_rx) = futures_channel::mpsc::channel(config.channel_size);
         let (batch_generator_cmd_tx, batch_generator_cmd_rx) =
@@ -225,6 +228,7 @@ impl InnerBuilder {
             batch_reader: None,
             broadcast_proofs,
             consensus_key,
+            dissemination_status,
         }
     }
 
@@ -345,6 +349,7 @@ impl InnerBuilder {
             self.proof_cache,
             self.broadcast_proofs,
             self.config.batch_expiry_gap_when_init_usecs,
+            self.dissemination_status.clone(),
         );
         spawn_named!(
             "proof_coordinator",
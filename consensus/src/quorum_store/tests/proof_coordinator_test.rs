@@ -5,6 +5,7 @@ use crate::{
     network_interface::ConsensusMsg,
     quorum_store::{
         batch_store::BatchReader,
+        dissemination_status::BatchDisseminationStatus,
         proof_coordinator::{ProofCoordinator, ProofCoordinatorCommand},
         types::Batch,
     },
@@ -59,6 +60,7 @@ async fn test_proof_coordinator_basic() {
         proof_cache.clone(),
         true,
         10,
+        Arc::new(BatchDisseminationStatus::default()),
     );
     let (proof_coordinator_tx, proof_coordinator_rx) = channel(100);
     let (tx, mut rx) = channel(100);
@@ -111,6 +113,7 @@ async fn test_proof_coordinator_with_unverified_signatures() {
         proof_cache.clone(),
         true,
         10,
+        Arc::new(BatchDisseminationStatus::default()),
     );
     let (proof_coordinator_tx, proof_coordinator_rx) = channel(100);
     let (tx, mut rx) = channel(100);
@@ -32,6 +32,7 @@ use crate::{
     quorum_store::types::BatchMsg,
     rand::rand_gen::types::{FastShare, RandConfig, Share, TShare},
     util::is_vtxn_expected,
+    validator_performance::VALIDATOR_PERFORMANCE,
 };
 use anyhow::{bail, ensure, Context};
 use aptos_channels::aptos_channel;
@@ -55,7 +56,7 @@ use aptos_consensus_types::{
     wrapped_ledger_info::WrappedLedgerInfo,
 };
 use aptos_crypto::{hash::CryptoHash, HashValue};
-use aptos_infallible::{checked, Mutex};
+use aptos_infallible::{checked, duration_since_epoch, Mutex};
 use aptos_logger::prelude::*;
 #[cfg(test)]
 use aptos_safety_rules::ConsensusState;
@@ -239,6 +240,10 @@ mod round_manager_test;
 #[path = "round_manager_fuzzing.rs"]
 pub mod round_manager_fuzzing;
 
+#[cfg(feature = "fuzzing")]
+#[path = "round_manager_replay.rs"]
+pub mod round_manager_replay;
+
 /// Consensus SMR is working in an event based fashion: RoundManager is responsible for
 /// processing the individual events (e.g., process_new_round, process_proposal, process_vote,
 /// etc.). It is exposing the async processing functions for each event type.
@@ -365,6 +370,11 @@ impl RoundManager {
 
         counters::CURRENT_ROUND.set(new_round_event.round as i64);
         counters::ROUND_TIMEOUT_MS.set(new_round_event.timeout.as_millis() as i64);
+        if prev_proposer == self.proposal_generator.author() {
+            VALIDATOR_PERFORMANCE
+                .record_proposal(matches!(new_round_event.reason, NewRoundReason::QCReady));
+        }
+        VALIDATOR_PERFORMANCE.update_gauges();
         match new_round_event.reason {
             NewRoundReason::QCReady => {
                 counters::QC_ROUNDS_COUNT.inc();
@@ -1135,6 +1145,7 @@ impl RoundManager {
 
     pub async fn process_verified_proposal(&mut self, proposal: Block) -> anyhow::Result<()> {
         let proposal_round = proposal.round();
+        let proposal_timestamp = Duration::from_micros(proposal.timestamp_usecs());
         let vote = self.create_vote(proposal).await?;
         self.round_state.record_vote(vote.clone());
         let vote_msg = VoteMsg::new(vote.clone(), self.block_store.sync_info());
@@ -1156,6 +1167,8 @@ impl RoundManager {
             );
             self.network.send_vote(vote_msg, vec![recipient]).await;
         }
+        VALIDATOR_PERFORMANCE
+            .record_vote_latency(duration_since_epoch().saturating_sub(proposal_timestamp));
         Ok(())
     }
 
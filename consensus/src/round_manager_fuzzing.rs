@@ -8,8 +8,10 @@ use crate::{
     block_storage::{pending_blocks::PendingBlocks, BlockStore},
     liveness::{
         proposal_generator::{
-            ChainHealthBackoffConfig, PipelineBackpressureConfig, ProposalGenerator,
+            BatchBackpressureConfig, ChainHealthBackoffConfig, PipelineBackpressureConfig,
+            ProposalGenerator,
         },
+        proposer_election::ProposerElection,
         rotating_proposer_election::RotatingProposer,
         round_state::{ExponentialTimeInterval, NewRoundEvent, NewRoundReason, RoundState},
     },
@@ -108,11 +110,10 @@ fn make_initial_epoch_change_proof(signer: &ValidatorSigner) -> EpochChangeProof
 }
 
 // TODO: MockStorage -> EmptyStorage
-fn create_round_state() -> RoundState {
+fn create_round_state(time_service: Arc<SimulatedTimeService>) -> RoundState {
     let base_timeout = std::time::Duration::new(60, 0);
     let time_interval = Box::new(ExponentialTimeInterval::fixed(base_timeout));
     let (round_timeout_sender, _) = aptos_channels::new_test(1_024);
-    let time_service = Arc::new(SimulatedTimeService::new());
 
     RoundState::new(time_interval, time_service, round_timeout_sender)
 }
@@ -121,9 +122,24 @@ fn create_round_state() -> RoundState {
 fn create_node_for_fuzzing() -> RoundManager {
     // signer is re-used accross fuzzing runs
     let signer = FUZZING_SIGNER.clone();
+    let validator = ValidatorVerifier::new_single(signer.author(), signer.public_key());
+    let proposer_election = Arc::new(RotatingProposer::new(vec![signer.author()], 1));
+    create_node(
+        signer,
+        validator,
+        proposer_election,
+        Arc::new(SimulatedTimeService::new()),
+    )
+}
 
+// Creates an RoundManager for the given validators, driven by the given time service
+pub(crate) fn create_node(
+    signer: ValidatorSigner,
+    validator: ValidatorVerifier,
+    proposer_election: Arc<dyn ProposerElection + Send + Sync>,
+    time_service: Arc<SimulatedTimeService>,
+) -> RoundManager {
     // TODO: remove
-    let validator = ValidatorVerifier::new_single(signer.author(), signer.public_key());
     let validator_set = (&validator).into();
 
     // TODO: EmptyStorage
@@ -162,8 +178,6 @@ fn create_node_for_fuzzing() -> RoundManager {
     // TODO: mock
     let block_store = build_empty_store(storage.clone(), initial_data);
 
-    // TODO: remove
-    let time_service = Arc::new(SimulatedTimeService::new());
     block_on(time_service.sleep(Duration::from_millis(1)));
 
     // TODO: remove
@@ -171,7 +185,7 @@ fn create_node_for_fuzzing() -> RoundManager {
         signer.author(),
         block_store.clone(),
         Arc::new(MockPayloadManager::new(None)),
-        time_service,
+        time_service.clone(),
         Duration::ZERO,
         PayloadTxnsSize::new(1, 1024),
         1,
@@ -180,6 +194,7 @@ fn create_node_for_fuzzing() -> RoundManager {
         1,
         PipelineBackpressureConfig::new_no_backoff(),
         ChainHealthBackoffConfig::new_no_backoff(),
+        BatchBackpressureConfig::new_no_backoff(),
         false,
         ValidatorTxnConfig::default_disabled(),
         true,
@@ -187,10 +202,7 @@ fn create_node_for_fuzzing() -> RoundManager {
     );
 
     //
-    let round_state = create_round_state();
-
-    // TODO: have two different nodes, one for proposing, one for accepting a proposal
-    let proposer_election = Arc::new(RotatingProposer::new(vec![signer.author()], 1));
+    let round_state = create_round_state(time_service);
 
     let (round_manager_tx, _) = aptos_channel::new(QueueStyle::LIFO, 1, None);
 
@@ -0,0 +1,161 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+#![allow(clippy::unwrap_used)]
+
+//! Replays consensus messages captured by a node (see `message_capture`) through a fresh
+//! RoundManager, driven by a simulated clock, in order to reproduce liveness incidents offline.
+//!
+//! The validators are recreated from the epoch state recorded with the capture, and the
+//! proposers from the captured proposals. Blocks certified before the capture started cannot be
+//! retrieved, so the replay is most faithful when the capture covers the start of the epoch;
+//! every message that fails to process is reported along with its error.
+
+use crate::{
+    liveness::round_proposer_election::RoundProposer,
+    message_capture::{read_captured_messages, read_epoch_state, CapturedMessage},
+    network_interface::ConsensusMsg,
+    round_manager::{round_manager_fuzzing::create_node, RoundManager},
+    util::{mock_time_service::SimulatedTimeService, time_service::TimeService},
+};
+use aptos_consensus_types::common::Round;
+use aptos_types::{validator_signer::ValidatorSigner, validator_verifier::ValidatorVerifier};
+use futures::executor::block_on;
+use std::{collections::HashMap, path::Path, sync::Arc, time::Duration};
+
+/// Outcome of replaying a set of captured messages.
+#[derive(Debug, Default)]
+pub struct ReplayReport {
+    /// Messages handed to the RoundManager.
+    pub processed: usize,
+    /// Messages which are not handled by the RoundManager (e.g., commit messages).
+    pub skipped: usize,
+    /// The messages the RoundManager failed to process, with the round they belong to.
+    pub errors: Vec<(Round, String)>,
+    /// The round of the RoundManager once all the messages were replayed.
+    pub final_round: Round,
+}
+
+/// Replays the messages of the given epoch captured in the directory, or of the first captured
+/// epoch if none is given. Only a single epoch is replayed, as the RoundManager does not cross
+/// epochs.
+pub fn replay_capture_dir(dir: &Path, epoch: Option<u64>) -> anyhow::Result<ReplayReport> {
+    let messages = read_captured_messages(dir)?;
+    let Some(epoch) = epoch.or_else(|| messages.first().map(|message| message.epoch)) else {
+        return Ok(ReplayReport::default());
+    };
+    let messages: Vec<_> = messages
+        .into_iter()
+        .filter(|message| message.epoch == epoch)
+        .collect();
+    if messages.is_empty() {
+        return Ok(ReplayReport::default());
+    }
+
+    // The replaying node does not sign anything, so its own key does not matter.
+    let signer = ValidatorSigner::from_int(1);
+    let validator = match read_epoch_state(dir, epoch)? {
+        Some(epoch_state) => (*epoch_state.verifier).clone(),
+        None => ValidatorVerifier::new_single(signer.author(), signer.public_key()),
+    };
+    Ok(replay_captured_messages(signer, validator, &messages))
+}
+
+/// Replays the messages through a RoundManager for the given validators, in the order they were
+/// captured, advancing the simulated clock by the time elapsed between consecutive messages.
+pub fn replay_captured_messages(
+    signer: ValidatorSigner,
+    validator: ValidatorVerifier,
+    messages: &[CapturedMessage],
+) -> ReplayReport {
+    let proposers: HashMap<_, _> = messages
+        .iter()
+        .filter_map(|captured| match &captured.message {
+            ConsensusMsg::ProposalMsg(proposal) => proposal
+                .proposal()
+                .author()
+                .map(|proposer| (proposal.proposal().round(), proposer)),
+            _ => None,
+        })
+        .collect();
+    let proposer_election = Arc::new(RoundProposer::new(proposers, signer.author()));
+    let time_service = Arc::new(SimulatedTimeService::new());
+    let mut round_manager = create_node(signer, validator, proposer_election, time_service.clone());
+
+    let mut report = ReplayReport::default();
+    let mut last_timestamp_usecs = messages.first().map_or(0, |m| m.timestamp_usecs);
+    for captured in messages {
+        let elapsed = captured
+            .timestamp_usecs
+            .saturating_sub(last_timestamp_usecs);
+        block_on(time_service.sleep(Duration::from_micros(elapsed)));
+        last_timestamp_usecs = last_timestamp_usecs.max(captured.timestamp_usecs);
+
+        match block_on(process_captured_message(&mut round_manager, captured)) {
+            Some(Ok(())) => report.processed += 1,
+            Some(Err(e)) => {
+                report.processed += 1;
+                report.errors.push((captured.round, format!("{:#}", e)));
+            },
+            None => report.skipped += 1,
+        }
+    }
+    report.final_round = round_manager.round_state().current_round();
+    report
+}
+
+async fn process_captured_message(
+    round_manager: &mut RoundManager,
+    captured: &CapturedMessage,
+) -> Option<anyhow::Result<()>> {
+    let result = match captured.message.clone() {
+        ConsensusMsg::ProposalMsg(proposal) => round_manager.process_proposal_msg(*proposal).await,
+        ConsensusMsg::VoteMsg(vote) => round_manager.process_vote_msg(*vote).await,
+        ConsensusMsg::OrderVoteMsg(order_vote) => {
+            round_manager.process_order_vote_msg(*order_vote).await
+        },
+        ConsensusMsg::RoundTimeoutMsg(timeout) => {
+            round_manager.process_round_timeout_msg(*timeout).await
+        },
+        ConsensusMsg::SyncInfo(sync_info) => {
+            round_manager
+                .process_sync_info_msg(*sync_info, captured.author)
+                .await
+        },
+        // Commit messages are handled by the execution pipeline.
+        _ => return None,
+    };
+    Some(result)
+}
+
+#[test]
+fn test_replay_captured_proposal() {
+    use crate::{
+        message_capture::MessageCapture,
+        round_manager::round_manager_fuzzing::generate_corpus_proposal,
+    };
+    use aptos_config::config::MessageCaptureConfig;
+    use aptos_consensus_types::proposal_msg::ProposalMsg;
+    use aptos_temppath::TempPath;
+
+    let dir = TempPath::new();
+    let mut capture = MessageCapture::new(&MessageCaptureConfig {
+        enabled: true,
+        capture_dir: dir.path().to_path_buf(),
+        ..Default::default()
+    })
+    .unwrap();
+    let proposal: ProposalMsg = serde_json::from_slice(&generate_corpus_proposal()).unwrap();
+    let author = proposal.proposer();
+    capture
+        .capture(author, &ConsensusMsg::ProposalMsg(Box::new(proposal)))
+        .unwrap();
+
+    let report = replay_capture_dir(dir.path(), None).unwrap();
+    assert_eq!(report.processed, 1);
+    assert_eq!(report.skipped, 0);
+
+    // Nothing was captured in other epochs.
+    let report = replay_capture_dir(dir.path(), Some(u64::MAX)).unwrap();
+    assert_eq!(report.processed, 0);
+}
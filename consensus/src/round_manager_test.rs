@@ -7,7 +7,8 @@ use crate::{
     counters,
     liveness::{
         proposal_generator::{
-            ChainHealthBackoffConfig, PipelineBackpressureConfig, ProposalGenerator,
+            BatchBackpressureConfig, ChainHealthBackoffConfig, PipelineBackpressureConfig,
+            ProposalGenerator,
         },
         proposer_election::ProposerElection,
         rotating_proposer_election::RotatingProposer,
@@ -310,6 +311,7 @@ impl NodeSetup {
             1,
             PipelineBackpressureConfig::new_no_backoff(),
             ChainHealthBackoffConfig::new_no_backoff(),
+            BatchBackpressureConfig::new_no_backoff(),
             false,
             onchain_consensus_config.effective_validator_txn_config(),
             true,
@@ -0,0 +1,249 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Tracks the recent performance of this validator (whether its proposals get certified, how
+//! quickly it votes, and how long its blocks spend in each pipeline phase) over sliding windows,
+//! so that operators can self-diagnose before being penalized by leader reputation.
+//!
+//! The summaries are published through the `aptos_consensus_validator_self_report` gauges, which
+//! the inspection service assembles into its `/validator_performance` endpoint.
+
+use crate::counters::VALIDATOR_SELF_REPORT;
+use aptos_infallible::Mutex;
+use once_cell::sync::Lazy;
+use std::{
+    collections::{BTreeMap, VecDeque},
+    time::{Duration, Instant},
+};
+
+/// The windows the performance is summarized over, with their label.
+const WINDOWS: [(&str, Duration); 2] = [
+    ("10m", Duration::from_secs(10 * 60)),
+    ("1h", Duration::from_secs(60 * 60)),
+];
+/// Bounds the memory used by each series, regardless of the round rate.
+const MAX_SAMPLES_PER_SERIES: usize = 20_000;
+
+pub static VALIDATOR_PERFORMANCE: Lazy<ValidatorPerformance> =
+    Lazy::new(ValidatorPerformance::default);
+
+#[derive(Default)]
+pub struct ValidatorPerformance {
+    inner: Mutex<Series>,
+}
+
+#[derive(Default)]
+struct Series {
+    /// 1.0 for the proposals which got certified, 0.0 for the rounds which timed out.
+    proposals: Samples,
+    vote_latency_ms: Samples,
+    pipeline_latency_ms: BTreeMap<&'static str, Samples>,
+}
+
+#[derive(Default)]
+struct Samples {
+    samples: VecDeque<(Instant, f64)>,
+}
+
+impl Samples {
+    fn push(&mut self, now: Instant, value: f64) {
+        if self.samples.len() >= MAX_SAMPLES_PER_SERIES {
+            self.samples.pop_front();
+        }
+        self.samples.push_back((now, value));
+    }
+
+    fn prune(&mut self, now: Instant, max_age: Duration) {
+        while let Some((time, _)) = self.samples.front() {
+            if now.saturating_duration_since(*time) <= max_age {
+                break;
+            }
+            self.samples.pop_front();
+        }
+    }
+
+    fn values_since(&self, now: Instant, window: Duration) -> Vec<f64> {
+        self.samples
+            .iter()
+            .rev()
+            .take_while(|(time, _)| now.saturating_duration_since(*time) <= window)
+            .map(|(_, value)| *value)
+            .collect()
+    }
+}
+
+/// Summary of a series over one window.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct WindowSummary {
+    pub proposals_succeeded: usize,
+    /// Rounds this validator was the leader of which ended in a timeout.
+    pub round_timeouts_caused: usize,
+    pub vote_latency_p50_ms: Option<f64>,
+    pub vote_latency_p99_ms: Option<f64>,
+    /// The p50 and p99 latencies of each pipeline phase.
+    pub pipeline_latency_ms: BTreeMap<&'static str, (f64, f64)>,
+}
+
+impl WindowSummary {
+    pub fn proposal_success_rate(&self) -> Option<f64> {
+        let proposals = self.proposals_succeeded + self.round_timeouts_caused;
+        (proposals > 0).then(|| self.proposals_succeeded as f64 / proposals as f64)
+    }
+}
+
+impl ValidatorPerformance {
+    /// Records the outcome of a round this validator was the leader of: certified, or timed out.
+    pub fn record_proposal(&self, certified: bool) {
+        self.record_proposal_at(Instant::now(), certified)
+    }
+
+    /// Records the time from a block being proposed to this validator voting for it.
+    pub fn record_vote_latency(&self, latency: Duration) {
+        self.record_vote_latency_at(Instant::now(), latency)
+    }
+
+    /// Records the time a block spent in the given pipeline phase.
+    pub fn record_pipeline_latency(&self, phase: &'static str, latency: Duration) {
+        self.inner
+            .lock()
+            .pipeline_latency_ms
+            .entry(phase)
+            .or_default()
+            .push(Instant::now(), as_millis(latency));
+    }
+
+    /// Summarizes the series over each window, and publishes the summaries as gauges.
+    pub fn update_gauges(&self) {
+        for (label, summary) in self.summarize(Instant::now()) {
+            let set = |metric: &str, value: Option<f64>| {
+                // NaN marks the metrics without any sample in the window
+                VALIDATOR_SELF_REPORT
+                    .with_label_values(&[metric, label])
+                    .set(value.unwrap_or(f64::NAN));
+            };
+            set(
+                "proposals_succeeded",
+                Some(summary.proposals_succeeded as f64),
+            );
+            set(
+                "round_timeouts_caused",
+                Some(summary.round_timeouts_caused as f64),
+            );
+            set("proposal_success_rate", summary.proposal_success_rate());
+            set("vote_latency_p50_ms", summary.vote_latency_p50_ms);
+            set("vote_latency_p99_ms", summary.vote_latency_p99_ms);
+            for (phase, (p50, p99)) in summary.pipeline_latency_ms {
+                set(&format!("{}_latency_p50_ms", phase), Some(p50));
+                set(&format!("{}_latency_p99_ms", phase), Some(p99));
+            }
+        }
+    }
+
+    fn record_proposal_at(&self, now: Instant, certified: bool) {
+        let value = if certified { 1.0 } else { 0.0 };
+        self.inner.lock().proposals.push(now, value);
+    }
+
+    fn record_vote_latency_at(&self, now: Instant, latency: Duration) {
+        self.inner
+            .lock()
+            .vote_latency_ms
+            .push(now, as_millis(latency));
+    }
+
+    fn summarize(&self, now: Instant) -> Vec<(&'static str, WindowSummary)> {
+        let mut series = self.inner.lock();
+        let max_window = WINDOWS
+            .iter()
+            .map(|(_, window)| *window)
+            .max()
+            .unwrap_or_default();
+        series.proposals.prune(now, max_window);
+        series.vote_latency_ms.prune(now, max_window);
+        for samples in series.pipeline_latency_ms.values_mut() {
+            samples.prune(now, max_window);
+        }
+
+        WINDOWS
+            .iter()
+            .map(|(label, window)| {
+                let proposals = series.proposals.values_since(now, *window);
+                let proposals_succeeded = proposals.iter().filter(|value| **value > 0.0).count();
+                let mut vote_latency = series.vote_latency_ms.values_since(now, *window);
+                let pipeline_latency_ms = series
+                    .pipeline_latency_ms
+                    .iter()
+                    .filter_map(|(phase, samples)| {
+                        let mut latency = samples.values_since(now, *window);
+                        Some((
+                            *phase,
+                            (
+                                percentile(&mut latency, 50.0)?,
+                                percentile(&mut latency, 99.0)?,
+                            ),
+                        ))
+                    })
+                    .collect();
+                (*label, WindowSummary {
+                    proposals_succeeded,
+                    round_timeouts_caused: proposals.len() - proposals_succeeded,
+                    vote_latency_p50_ms: percentile(&mut vote_latency, 50.0),
+                    vote_latency_p99_ms: percentile(&mut vote_latency, 99.0),
+                    pipeline_latency_ms,
+                })
+            })
+            .collect()
+    }
+}
+
+fn as_millis(duration: Duration) -> f64 {
+    duration.as_secs_f64() * 1000.0
+}
+
+/// Nearest-rank percentile, None if there are no values.
+fn percentile(values: &mut [f64], percentile: f64) -> Option<f64> {
+    if values.is_empty() {
+        return None;
+    }
+    values.sort_by(|a, b| a.total_cmp(b));
+    let rank = ((percentile / 100.0) * values.len() as f64).ceil() as usize;
+    Some(values[rank.clamp(1, values.len()) - 1])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sliding_windows() {
+        let performance = ValidatorPerformance::default();
+        let start = Instant::now();
+        let now = start + Duration::from_secs(30 * 60);
+
+        // Only counted in the 1h window
+        performance.record_proposal_at(start, false);
+        performance.record_vote_latency_at(start, Duration::from_millis(1000));
+        // Counted in both windows
+        for latency_ms in 1..=100 {
+            performance.record_proposal_at(now, latency_ms % 4 != 0);
+            performance.record_vote_latency_at(now, Duration::from_millis(latency_ms));
+        }
+
+        let summaries: BTreeMap<_, _> = performance.summarize(now).into_iter().collect();
+        let recent = &summaries["10m"];
+        assert_eq!(recent.proposals_succeeded, 75);
+        assert_eq!(recent.round_timeouts_caused, 25);
+        assert_eq!(recent.proposal_success_rate(), Some(0.75));
+        assert_eq!(recent.vote_latency_p50_ms, Some(50.0));
+        assert_eq!(recent.vote_latency_p99_ms, Some(99.0));
+
+        let hour = &summaries["1h"];
+        assert_eq!(hour.round_timeouts_caused, 26);
+        assert_eq!(hour.vote_latency_p99_ms, Some(100.0));
+
+        // Samples older than the largest window are dropped
+        let later = start + Duration::from_secs(2 * 60 * 60);
+        let summaries: BTreeMap<_, _> = performance.summarize(later).into_iter().collect();
+        assert_eq!(summaries["1h"], WindowSummary::default());
+    }
+}
@@ -0,0 +1,423 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Toggles the features which only change the local behavior of the node (as opposed to the
+//! on-chain features, which all validators have to agree on) at runtime, without a restart.
+//! Every change is logged and kept in an in-memory audit log.
+
+use aptos_config::config::PrunerPacingConfig;
+use aptos_infallible::{duration_since_epoch, Mutex};
+use aptos_logger::info;
+use aptos_storage_interface::DbReaderWriter;
+use aptos_system_utils::utils::{reply_with, reply_with_status};
+use aptos_vm::AptosVM;
+use http::{Request, Response, StatusCode};
+use hyper::Body;
+use std::{
+    collections::{HashMap, VecDeque},
+    fmt,
+    net::SocketAddr,
+    sync::Arc,
+};
+
+/// Bounds the memory used by the audit log, the oldest changes are dropped first.
+const MAX_AUDIT_LOG_ENTRIES: usize = 1000;
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum LocalFeature {
+    /// Writes a report of the conflicts of each block executed by BlockSTM, `true` or `false`.
+    BlockStmProfiling,
+    /// The log filter directives, e.g. `info,aptos_consensus=debug`.
+    LoggingFilter,
+    /// Slows down the pruners when the commits are slow, `true` or `false`.
+    PrunerPacing,
+}
+
+impl LocalFeature {
+    const ALL: [LocalFeature; 3] = [
+        LocalFeature::BlockStmProfiling,
+        LocalFeature::LoggingFilter,
+        LocalFeature::PrunerPacing,
+    ];
+
+    fn name(&self) -> &'static str {
+        match self {
+            LocalFeature::BlockStmProfiling => "block_stm_profiling",
+            LocalFeature::LoggingFilter => "logging_filter",
+            LocalFeature::PrunerPacing => "pruner_pacing",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|feature| feature.name() == name)
+    }
+}
+
+impl fmt::Display for LocalFeature {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+struct AuditEntry {
+    timestamp_usecs: u128,
+    remote_address: SocketAddr,
+    feature: LocalFeature,
+    previous_value: String,
+    value: String,
+}
+
+impl fmt::Display for AuditEntry {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
+            "{} {} set {} from {:?} to {:?}",
+            self.timestamp_usecs,
+            self.remote_address,
+            self.feature,
+            self.previous_value,
+            self.value
+        )
+    }
+}
+
+/// The current values of the local features, and the log of their changes.
+pub struct LocalFeatures {
+    /// The log filter directives set at runtime, None while the startup filter is in effect.
+    logging_filter: Mutex<Option<String>>,
+    pruner_pacing: Mutex<bool>,
+    audit_log: Mutex<VecDeque<AuditEntry>>,
+}
+
+impl LocalFeatures {
+    pub fn new(pruner_pacing: bool) -> Self {
+        Self {
+            logging_filter: Mutex::new(None),
+            pruner_pacing: Mutex::new(pruner_pacing),
+            audit_log: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    fn value(&self, feature: LocalFeature) -> String {
+        match feature {
+            LocalFeature::BlockStmProfiling => AptosVM::get_block_stm_profiling().to_string(),
+            LocalFeature::LoggingFilter => self
+                .logging_filter
+                .lock()
+                .clone()
+                .unwrap_or_else(|| "<startup config>".to_string()),
+            LocalFeature::PrunerPacing => self.pruner_pacing.lock().to_string(),
+        }
+    }
+
+    /// Sets the feature to the value, and records the change in the audit log.
+    pub fn set(
+        &self,
+        feature: LocalFeature,
+        value: &str,
+        aptos_db: Option<Arc<DbReaderWriter>>,
+        remote_address: SocketAddr,
+    ) -> Result<(), String> {
+        let previous_value = self.value(feature);
+        match feature {
+            LocalFeature::BlockStmProfiling => {
+                AptosVM::set_block_stm_profiling(parse_bool(value)?);
+            },
+            LocalFeature::LoggingFilter => {
+                aptos_logger::set_filter(value)
+                    .map_err(|e| format!("Failed to parse {value:?}: {e}."))?;
+                *self.logging_filter.lock() = Some(value.to_string());
+            },
+            LocalFeature::PrunerPacing => {
+                let enable = parse_bool(value)?;
+                let aptos_db = aptos_db.ok_or_else(|| "AptosDB is not available.".to_string())?;
+                aptos_db
+                    .writer
+                    .set_pruner_pacing_enabled(enable)
+                    .map_err(|e| format!("Failed to set pruner pacing: {e}."))?;
+                *self.pruner_pacing.lock() = enable;
+            },
+        }
+
+        let entry = AuditEntry {
+            timestamp_usecs: duration_since_epoch().as_micros(),
+            remote_address,
+            feature,
+            previous_value,
+            value: value.to_string(),
+        };
+        info!("Local feature updated: {entry}.");
+        let mut audit_log = self.audit_log.lock();
+        if audit_log.len() >= MAX_AUDIT_LOG_ENTRIES {
+            audit_log.pop_front();
+        }
+        audit_log.push_back(entry);
+        Ok(())
+    }
+}
+
+impl Default for LocalFeatures {
+    fn default() -> Self {
+        Self::new(PrunerPacingConfig::default().enable)
+    }
+}
+
+fn parse_bool(value: &str) -> Result<bool, String> {
+    value
+        .parse()
+        .map_err(|_| format!("Invalid value {value:?}, expected true or false."))
+}
+
+/// Lists the local features with their current value, one per line.
+pub async fn handle_list_local_features_request(
+    _req: Request<Body>,
+    local_features: &LocalFeatures,
+) -> hyper::Result<Response<Body>> {
+    let features: String = LocalFeature::ALL
+        .iter()
+        .map(|feature| format!("{}: {}\n", feature, local_features.value(*feature)))
+        .collect();
+    Ok(reply_with(vec![], features))
+}
+
+/// Sets the local feature given in the `feature` query parameter to the `value` query parameter,
+/// e.g. `feature=block_stm_profiling&value=true`.
+pub async fn handle_set_local_feature_request(
+    req: Request<Body>,
+    local_features: &LocalFeatures,
+    aptos_db: Option<Arc<DbReaderWriter>>,
+    remote_address: SocketAddr,
+) -> hyper::Result<Response<Body>> {
+    let query = req.uri().query().unwrap_or("");
+    let query_pairs: HashMap<_, _> = url::form_urlencoded::parse(query.as_bytes()).collect();
+
+    let feature = match query_pairs.get("feature") {
+        Some(name) => match LocalFeature::from_name(name) {
+            Some(feature) => feature,
+            None => {
+                return Ok(reply_with_status(
+                    StatusCode::BAD_REQUEST,
+                    format!("Unknown local feature {name:?}."),
+                ))
+            },
+        },
+        None => {
+            return Ok(reply_with_status(
+                StatusCode::BAD_REQUEST,
+                "Missing feature.",
+            ))
+        },
+    };
+    let value = match query_pairs.get("value") {
+        Some(value) => value,
+        None => return Ok(reply_with_status(StatusCode::BAD_REQUEST, "Missing value.")),
+    };
+
+    match local_features.set(feature, value, aptos_db, remote_address) {
+        Ok(()) => Ok(reply_with_status(
+            StatusCode::OK,
+            format!("{feature} set to {value:?}."),
+        )),
+        Err(e) => Ok(reply_with_status(StatusCode::BAD_REQUEST, e)),
+    }
+}
+
+/// Returns the audit log of the local feature changes, oldest first, one per line.
+pub async fn handle_local_features_audit_log_request(
+    _req: Request<Body>,
+    local_features: &LocalFeatures,
+) -> hyper::Result<Response<Body>> {
+    let audit_log: String = local_features
+        .audit_log
+        .lock()
+        .iter()
+        .map(|entry| format!("{entry}\n"))
+        .collect();
+    Ok(reply_with(vec![], audit_log))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aptos_storage_interface::{DbReader, DbWriter};
+    use std::net::{IpAddr, Ipv4Addr};
+
+    /// A mock database recording whether pruner pacing is enabled.
+    #[derive(Default)]
+    struct MockDatabase {
+        pruner_pacing: Mutex<bool>,
+    }
+
+    impl DbReader for MockDatabase {}
+
+    impl DbWriter for MockDatabase {
+        fn set_pruner_pacing_enabled(&self, enable: bool) -> aptos_storage_interface::Result<bool> {
+            Ok(std::mem::replace(&mut *self.pruner_pacing.lock(), enable))
+        }
+    }
+
+    fn remote_address() -> SocketAddr {
+        SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), 9102)
+    }
+
+    fn audit_log(local_features: &LocalFeatures) -> Vec<(LocalFeature, String, String)> {
+        local_features
+            .audit_log
+            .lock()
+            .iter()
+            .map(|entry| {
+                assert_eq!(entry.remote_address, remote_address());
+                (
+                    entry.feature,
+                    entry.previous_value.clone(),
+                    entry.value.clone(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_toggle_local_features() {
+        let local_features = LocalFeatures::new(false);
+        let db = Arc::new(MockDatabase::default());
+        let aptos_db = Some(Arc::new(DbReaderWriter::from_arc(db.clone())));
+
+        local_features
+            .set(
+                LocalFeature::PrunerPacing,
+                "true",
+                aptos_db.clone(),
+                remote_address(),
+            )
+            .unwrap();
+        assert_eq!(local_features.value(LocalFeature::PrunerPacing), "true");
+        assert!(*db.pruner_pacing.lock());
+
+        local_features
+            .set(
+                LocalFeature::PrunerPacing,
+                "false",
+                aptos_db,
+                remote_address(),
+            )
+            .unwrap();
+        assert_eq!(local_features.value(LocalFeature::PrunerPacing), "false");
+        assert!(!*db.pruner_pacing.lock());
+
+        assert_eq!(
+            local_features.value(LocalFeature::LoggingFilter),
+            "<startup config>"
+        );
+        local_features
+            .set(
+                LocalFeature::LoggingFilter,
+                "info,aptos_consensus=debug",
+                None,
+                remote_address(),
+            )
+            .unwrap();
+        assert_eq!(
+            local_features.value(LocalFeature::LoggingFilter),
+            "info,aptos_consensus=debug"
+        );
+
+        // BlockSTM profiling is process wide, so it is restored once toggled.
+        let block_stm_profiling = AptosVM::get_block_stm_profiling();
+        local_features
+            .set(
+                LocalFeature::BlockStmProfiling,
+                &(!block_stm_profiling).to_string(),
+                None,
+                remote_address(),
+            )
+            .unwrap();
+        assert_eq!(AptosVM::get_block_stm_profiling(), !block_stm_profiling);
+        local_features
+            .set(
+                LocalFeature::BlockStmProfiling,
+                &block_stm_profiling.to_string(),
+                None,
+                remote_address(),
+            )
+            .unwrap();
+        assert_eq!(AptosVM::get_block_stm_profiling(), block_stm_profiling);
+
+        // Every change is audited, oldest first.
+        assert_eq!(audit_log(&local_features), vec![
+            (LocalFeature::PrunerPacing, "false".into(), "true".into()),
+            (LocalFeature::PrunerPacing, "true".into(), "false".into()),
+            (
+                LocalFeature::LoggingFilter,
+                "<startup config>".into(),
+                "info,aptos_consensus=debug".into()
+            ),
+            (
+                LocalFeature::BlockStmProfiling,
+                block_stm_profiling.to_string(),
+                (!block_stm_profiling).to_string()
+            ),
+            (
+                LocalFeature::BlockStmProfiling,
+                (!block_stm_profiling).to_string(),
+                block_stm_profiling.to_string()
+            ),
+        ]);
+    }
+
+    #[test]
+    fn test_failed_toggles_are_not_audited() {
+        let local_features = LocalFeatures::new(false);
+
+        assert!(local_features
+            .set(LocalFeature::PrunerPacing, "yes", None, remote_address())
+            .is_err());
+        assert!(local_features
+            .set(LocalFeature::PrunerPacing, "true", None, remote_address())
+            .is_err());
+        assert!(local_features
+            .set(
+                LocalFeature::LoggingFilter,
+                "aptos_consensus=noLevel",
+                None,
+                remote_address()
+            )
+            .is_err());
+
+        assert_eq!(local_features.value(LocalFeature::PrunerPacing), "false");
+        assert!(audit_log(&local_features).is_empty());
+    }
+
+    #[test]
+    fn test_audit_log_is_bounded() {
+        let local_features = LocalFeatures::new(false);
+        for i in 0..=MAX_AUDIT_LOG_ENTRIES {
+            local_features
+                .set(
+                    LocalFeature::LoggingFilter,
+                    &format!("aptos_consensus{i}=debug"),
+                    None,
+                    remote_address(),
+                )
+                .unwrap();
+        }
+
+        let audit_log = audit_log(&local_features);
+        assert_eq!(audit_log.len(), MAX_AUDIT_LOG_ENTRIES);
+        assert_eq!(
+            audit_log[0],
+            (
+                LocalFeature::LoggingFilter,
+                "aptos_consensus0=debug".into(),
+                "aptos_consensus1=debug".into()
+            )
+        );
+    }
+
+    #[test]
+    fn test_local_feature_names() {
+        for feature in LocalFeature::ALL {
+            assert_eq!(LocalFeature::from_name(feature.name()), Some(feature));
+        }
+        assert_eq!(LocalFeature::from_name("unknown"), None);
+    }
+}
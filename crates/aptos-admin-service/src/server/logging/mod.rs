@@ -0,0 +1,43 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::server::local_features::{LocalFeature, LocalFeatures};
+use aptos_system_utils::utils::reply_with_status;
+use http::{Request, Response, StatusCode};
+use hyper::Body;
+use std::{collections::HashMap, net::SocketAddr};
+
+/// Replaces the log filter of the node with the directives given in the `directives` query
+/// parameter, e.g. `info,aptos_consensus=debug,aptos_network=warn`. The change is recorded in
+/// the audit log of the local features.
+pub async fn handle_set_log_filter_request(
+    req: Request<Body>,
+    local_features: &LocalFeatures,
+    remote_address: SocketAddr,
+) -> hyper::Result<Response<Body>> {
+    let query = req.uri().query().unwrap_or("");
+    let query_pairs: HashMap<_, _> = url::form_urlencoded::parse(query.as_bytes()).collect();
+
+    let directives = match query_pairs.get("directives") {
+        Some(directives) => directives,
+        None => {
+            return Ok(reply_with_status(
+                StatusCode::BAD_REQUEST,
+                "Missing directives.",
+            ))
+        },
+    };
+
+    match local_features.set(
+        LocalFeature::LoggingFilter,
+        directives,
+        None,
+        remote_address,
+    ) {
+        Ok(()) => Ok(reply_with_status(
+            StatusCode::OK,
+            format!("Log filter set to {directives:?}."),
+        )),
+        Err(e) => Ok(reply_with_status(StatusCode::BAD_REQUEST, e)),
+    }
+}
@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use aptos_logger::info;
-use aptos_mempool::{MempoolClientRequest, MempoolClientSender};
+use aptos_mempool::{EvictionPressure, MempoolClientRequest, MempoolClientSender};
 use aptos_system_utils::utils::{reply_with, reply_with_status};
 use aptos_types::account_address::AccountAddress;
 use futures_channel::oneshot::Canceled;
@@ -37,6 +37,42 @@ pub async fn mempool_handle_parking_lot_address_request(
     }
 }
 
+pub async fn mempool_handle_eviction_pressure_request(
+    _req: Request<Body>,
+    mempool_client_sender: MempoolClientSender,
+) -> hyper::Result<Response<Body>> {
+    match get_eviction_pressure(mempool_client_sender).await {
+        Ok(eviction_pressure) => {
+            info!("Finished getting eviction pressure from mempool.");
+            Ok(reply_with(vec![], format!("{eviction_pressure:#?}\n")))
+        },
+        Err(e) => {
+            info!("Failed to get eviction pressure from mempool: {e:?}");
+            Ok(reply_with_status(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                e.to_string(),
+            ))
+        },
+    }
+}
+
+async fn get_eviction_pressure(
+    mempool_client_sender: MempoolClientSender,
+) -> Result<EvictionPressure, Canceled> {
+    let (sender, receiver) = futures_channel::oneshot::channel();
+
+    match mempool_client_sender
+        .clone()
+        .try_send(MempoolClientRequest::GetEvictionPressure(sender))
+    {
+        Ok(_) => receiver.await,
+        Err(e) => {
+            info!("Failed to send request for GetEvictionPressure: {e:?}");
+            Err(Canceled)
+        },
+    }
+}
+
 async fn get_parking_lot_addresses(
     mempool_client_sender: MempoolClientSender,
 ) -> Result<Vec<(AccountAddress, u64)>, Canceled> {
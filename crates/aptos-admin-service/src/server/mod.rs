@@ -15,6 +15,7 @@ use aptos_system_utils::{
     profiling::handle_cpu_profiling_request, thread_dump::handle_thread_dump_request,
 };
 use hyper::{
+    server::conn::AddrStream,
     service::{make_service_fn, service_fn},
     Body, Request, Response, Server, StatusCode,
 };
@@ -27,7 +28,10 @@ use std::{
 use tokio::runtime::Runtime;
 
 mod consensus;
+mod local_features;
+mod logging;
 mod mempool;
+mod storage;
 
 #[derive(Default)]
 pub struct Context {
@@ -37,6 +41,7 @@ pub struct Context {
     consensus_db: RwLock<Option<Arc<StorageWriteProxy>>>,
     quorum_store_db: RwLock<Option<Arc<QuorumStoreDB>>>,
     mempool_client_sender: RwLock<Option<MempoolClientSender>>,
+    local_features: local_features::LocalFeatures,
 }
 
 impl Context {
@@ -90,6 +95,13 @@ impl AdminService {
             runtime,
             context: Arc::new(Context {
                 authentication_configs: node_config.admin_service.authentication_configs.clone(),
+                local_features: local_features::LocalFeatures::new(
+                    node_config
+                        .storage
+                        .storage_pruner_config
+                        .pacing_config
+                        .enable,
+                ),
                 ..Default::default()
             }),
         };
@@ -122,11 +134,12 @@ impl AdminService {
     fn start(&self, address: SocketAddr, enabled: bool) {
         let context = self.context.clone();
         self.runtime.spawn(async move {
-            let make_service = make_service_fn(move |_conn| {
+            let make_service = make_service_fn(move |conn: &AddrStream| {
                 let context = context.clone();
+                let remote_address = conn.remote_addr();
                 async move {
                     Ok::<_, Infallible>(service_fn(move |req| {
-                        Self::serve_requests(context.clone(), req, enabled)
+                        Self::serve_requests(context.clone(), req, remote_address, enabled)
                     }))
                 }
             });
@@ -140,6 +153,7 @@ impl AdminService {
     async fn serve_requests(
         context: Arc<Context>,
         req: Request<Body>,
+        remote_address: SocketAddr,
         enabled: bool,
     ) -> hyper::Result<Response<Body>> {
         if !enabled {
@@ -237,6 +251,54 @@ impl AdminService {
                     ))
                 }
             },
+            (hyper::Method::GET, "/debug/mempool/eviction-pressure") => {
+                let mempool_client_sender = context.mempool_client_sender.read().clone();
+                if let Some(mempool_client_sender) = mempool_client_sender {
+                    mempool::mempool_handle_eviction_pressure_request(req, mempool_client_sender)
+                        .await
+                } else {
+                    Ok(reply_with_status(
+                        StatusCode::NOT_FOUND,
+                        "Mempool eviction pressure is not available.",
+                    ))
+                }
+            },
+            (hyper::Method::POST, "/debug/logging/filter") => {
+                logging::handle_set_log_filter_request(req, &context.local_features, remote_address)
+                    .await
+            },
+            (hyper::Method::GET, "/debug/features") => {
+                local_features::handle_list_local_features_request(req, &context.local_features)
+                    .await
+            },
+            (hyper::Method::POST, "/debug/features") => {
+                let aptos_db = context.aptos_db.read().clone();
+                local_features::handle_set_local_feature_request(
+                    req,
+                    &context.local_features,
+                    aptos_db,
+                    remote_address,
+                )
+                .await
+            },
+            (hyper::Method::GET, "/debug/features/audit") => {
+                local_features::handle_local_features_audit_log_request(
+                    req,
+                    &context.local_features,
+                )
+                .await
+            },
+            (hyper::Method::POST, "/debug/storage/prune") => {
+                let aptos_db = context.aptos_db.read().clone();
+                if let Some(aptos_db) = aptos_db {
+                    storage::handle_prune_request(req, aptos_db).await
+                } else {
+                    Ok(reply_with_status(
+                        StatusCode::NOT_FOUND,
+                        "AptosDB is not available.",
+                    ))
+                }
+            },
             _ => Ok(reply_with_status(StatusCode::NOT_FOUND, "Not found.")),
         }
     }
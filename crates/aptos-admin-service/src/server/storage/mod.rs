@@ -0,0 +1,50 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_logger::info;
+use aptos_storage_interface::DbReaderWriter;
+use aptos_system_utils::utils::reply_with_status;
+use aptos_types::transaction::Version;
+use http::{Request, Response, StatusCode};
+use hyper::Body;
+use std::{collections::HashMap, sync::Arc};
+
+/// Makes the DB pruners prune everything below the version given in the `version` query
+/// parameter, regardless of the prune windows.
+pub async fn handle_prune_request(
+    req: Request<Body>,
+    aptos_db: Arc<DbReaderWriter>,
+) -> hyper::Result<Response<Body>> {
+    let query = req.uri().query().unwrap_or("");
+    let query_pairs: HashMap<_, _> = url::form_urlencoded::parse(query.as_bytes()).collect();
+
+    let version: Version = match query_pairs.get("version").map(|v| v.parse()) {
+        Some(Ok(version)) => version,
+        Some(Err(e)) => {
+            return Ok(reply_with_status(
+                StatusCode::BAD_REQUEST,
+                format!("Failed to parse version: {e}."),
+            ))
+        },
+        None => {
+            return Ok(reply_with_status(
+                StatusCode::BAD_REQUEST,
+                "Missing version.",
+            ))
+        },
+    };
+
+    match aptos_db.writer.prune_to_version(version) {
+        Ok(()) => {
+            info!("Pruning to version {version} requested.");
+            Ok(reply_with_status(
+                StatusCode::OK,
+                format!("Pruning to version {version}."),
+            ))
+        },
+        Err(e) => Ok(reply_with_status(
+            StatusCode::BAD_REQUEST,
+            format!("Failed to prune to version {version}: {e}."),
+        )),
+    }
+}
@@ -459,7 +459,7 @@ pub fn bcs_crypto_hash_dispatch(input: TokenStream) -> TokenStream {
                 use aptos_crypto::hash::CryptoHasher;
 
                 let mut state = Self::Hasher::default();
-                bcs::serialize_into(&mut state, &self).expect(#error_msg);
+                state.update_bcs(self).expect(#error_msg);
                 state.finish()
             }
         }
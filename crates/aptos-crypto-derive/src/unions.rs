@@ -276,23 +276,23 @@ pub fn impl_enum_signature(
             type VerifyingKeyMaterial = #pub_kt;
             type SigningKeyMaterial = #priv_kt;
 
-            fn verify<T: aptos_crypto::hash::CryptoHash + serde::Serialize>(&self, message: &T, public_key: &Self::VerifyingKeyMaterial) -> std::result::Result<(), aptos_crypto::error::Error> {
+            fn verify<T: aptos_crypto::hash::CryptoHash + serde::Serialize>(&self, message: &T, public_key: &Self::VerifyingKeyMaterial) -> std::result::Result<(), aptos_crypto::error::CryptoError> {
                 match (self, public_key) {
                     #match_struct_arms
-                    _ => aptos_crypto::error::bail!(
+                    _ => Err(aptos_crypto::error::CryptoError::InvalidSignature(format!(
                         "provided the wrong alternative in {:?}!",
                         (self, public_key)
-                    ),
+                    ))),
                 }
             }
 
-            fn verify_arbitrary_msg(&self, message: &[u8], public_key: &Self::VerifyingKeyMaterial) -> std::result::Result<(), aptos_crypto::error::Error> {
+            fn verify_arbitrary_msg(&self, message: &[u8], public_key: &Self::VerifyingKeyMaterial) -> std::result::Result<(), aptos_crypto::error::CryptoError> {
                 match (self, public_key) {
                     #match_arms
-                    _ => aptos_crypto::error::bail!(
+                    _ => Err(aptos_crypto::error::CryptoError::InvalidSignature(format!(
                         "provided the wrong alternative in {:?}!",
                         (self, public_key)
-                    ),
+                    ))),
                 }
             }
 
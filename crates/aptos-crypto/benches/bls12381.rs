@@ -64,6 +64,7 @@ fn bench_group(c: &mut Criterion) {
         aggregate_pks(&mut group, size);
 
         verify_multisig(&mut group, size);
+        verify_multisig_cached(&mut group, size);
         verify_aggsig(&mut group, size);
         size *= 2;
     }
@@ -372,6 +373,76 @@ fn verify_multisig<M: Measurement>(g: &mut BenchmarkGroup<M>, size: usize) {
     );
 }
 
+/// Benchmarks the time to verify a multisignature from the perspective of a verifier who has cached
+/// the public keys of `n` signers, and receives the same multisignature from `size` of them several
+/// times (e.g., a quorum certificate sent by several peers). Compare with `verify_multisig`, which
+/// deserializes, subgroup-checks and aggregates the public keys of the signers every time.
+fn verify_multisig_cached<M: Measurement>(g: &mut BenchmarkGroup<M>, size: usize) {
+    let mut rng = thread_rng();
+
+    // pick `n` random keypairs
+    let mut key_pairs = vec![];
+    let n = size * 2;
+
+    for _ in 0..n {
+        key_pairs.push(KeyPair::<bls12381::PrivateKey, bls12381::PublicKey>::generate(&mut rng));
+    }
+
+    // the uncached verifier receives serialized public keys, which it has to deserialize and
+    // subgroup-check before aggregating them
+    let pk_bytes: Vec<_> = key_pairs
+        .iter()
+        .map(|kp| kp.public_key.to_bytes())
+        .collect();
+    let cache = bls12381::AggregatePublicKeyCache::new(
+        0,
+        key_pairs.iter().map(|kp| kp.public_key.clone()).collect(),
+    )
+    .unwrap();
+
+    // pick a random message and a random subset of signers to aggregate a multisignature on
+    let msg = random_message(&mut rng);
+    let subset = random_subset(&mut rng, n, size);
+    let mut bitmap = vec![0u8; (n + 7) / 8];
+    let mut sigshares = vec![];
+    for &i in &subset {
+        bitmap[i / 8] |= 0x80 >> (i % 8);
+        sigshares.push(key_pairs[i].private_key.sign(&msg).unwrap());
+    }
+    let multisig = bls12381::Signature::aggregate(sigshares).unwrap();
+
+    g.throughput(Throughput::Elements(size as u64));
+    g.bench_with_input(
+        BenchmarkId::new("verify_multisig_uncached", size),
+        &size,
+        |b, _| {
+            b.iter(|| {
+                let pks: Vec<_> = subset
+                    .iter()
+                    .map(|&i| {
+                        let pk = bls12381::PublicKey::try_from(&pk_bytes[i][..]).unwrap();
+                        pk.subgroup_check().unwrap();
+                        pk
+                    })
+                    .collect();
+                let aggpk = bls12381::PublicKey::aggregate(pks.iter().collect()).unwrap();
+
+                assert!(multisig.verify(&msg, &aggpk).is_ok());
+            })
+        },
+    );
+
+    g.bench_with_input(
+        BenchmarkId::new("verify_multisig_cached", size),
+        &size,
+        |b, _| {
+            b.iter(|| {
+                assert!(cache.verify_multisig(&msg, &bitmap, &multisig).is_ok());
+            })
+        },
+    );
+}
+
 /// Benchmarks the time to verify an aggregate signature from the perspective of a verifier who
 /// receives an aggregate signature from `n` signers.
 fn verify_aggsig<M: Measurement>(g: &mut BenchmarkGroup<M>, n: usize) {
@@ -0,0 +1,169 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! This module provides a cache of the public keys of a validator set, used to verify the
+//! multisignatures of its validators without deserializing, subgroup-checking and aggregating their
+//! public keys over and over.
+//!
+//! The public keys are subgroup-checked once, when the cache is created for an epoch. The aggregate
+//! public keys of the subsets of signers are then cached by their signer bitmap, so that verifying
+//! several multisignatures from the same signers (e.g., a quorum certificate received from several
+//! peers) only aggregates their public keys once.
+//!
+//! The signer bitmaps use the layout of `aptos_bitvec::BitVec`: the `i`-th validator is the
+//! `i % 8`-th most significant bit of the `i / 8`-th byte.
+
+use crate::{
+    bls12381::{PublicKey, Signature},
+    error::CryptoError,
+    hash::CryptoHash,
+    traits::Signature as _,
+};
+use serde::Serialize;
+use std::{collections::HashMap, sync::Mutex};
+
+/// The public keys of the validators of an epoch, along with the aggregate public keys of the
+/// subsets of them that recently signed.
+#[derive(Debug)]
+pub struct AggregatePublicKeyCache {
+    epoch: u64,
+    pubkeys: Vec<PublicKey>,
+    capacity: usize,
+    aggregates: Mutex<HashMap<Vec<u8>, PublicKey>>,
+}
+
+impl AggregatePublicKeyCache {
+    /// The default number of aggregate public keys kept in the cache.
+    pub const DEFAULT_CAPACITY: usize = 64;
+
+    /// Creates a cache for the public keys of the validators of `epoch`, ordered by their index in
+    /// the signer bitmaps. Fails if any of the public keys is not a prime-order subgroup element.
+    pub fn new(epoch: u64, pubkeys: Vec<PublicKey>) -> Result<Self, CryptoError> {
+        Self::with_capacity(epoch, pubkeys, Self::DEFAULT_CAPACITY)
+    }
+
+    /// Same as `new`, but keeps at most `capacity` aggregate public keys in the cache.
+    pub fn with_capacity(
+        epoch: u64,
+        pubkeys: Vec<PublicKey>,
+        capacity: usize,
+    ) -> Result<Self, CryptoError> {
+        for pubkey in &pubkeys {
+            pubkey.subgroup_check()?;
+        }
+
+        Ok(Self {
+            epoch,
+            pubkeys,
+            capacity,
+            aggregates: Mutex::new(HashMap::new()),
+        })
+    }
+
+    /// Returns the epoch of the validator set.
+    pub fn epoch(&self) -> u64 {
+        self.epoch
+    }
+
+    /// Returns the number of validators.
+    pub fn len(&self) -> usize {
+        self.pubkeys.len()
+    }
+
+    /// Returns true if there are no validators.
+    pub fn is_empty(&self) -> bool {
+        self.pubkeys.is_empty()
+    }
+
+    /// Returns the public keys of the validators.
+    pub fn pubkeys(&self) -> &[PublicKey] {
+        &self.pubkeys
+    }
+
+    /// Returns the aggregate public key of the validators set in `bitmap`, aggregating and caching
+    /// it if it is not cached yet.
+    pub fn aggregate(&self, bitmap: &[u8]) -> Result<PublicKey, CryptoError> {
+        self.check_bitmap(bitmap)?;
+
+        if let Some(aggpk) = self.lock_aggregates().get(bitmap) {
+            return Ok(aggpk.clone());
+        }
+
+        let signers = (0..self.pubkeys.len())
+            .filter(|&i| bitmap[i / 8] & (0x80 >> (i % 8)) != 0)
+            .map(|i| &self.pubkeys[i])
+            .collect();
+        let aggpk = PublicKey::aggregate(signers)?;
+
+        let mut aggregates = self.lock_aggregates();
+        // The signers of the next rounds are unlikely to be the ones of the previous rounds, so we
+        // simply start over once the cache is full.
+        if aggregates.len() >= self.capacity {
+            aggregates.clear();
+        }
+        if self.capacity > 0 {
+            aggregates.insert(bitmap.to_vec(), aggpk.clone());
+        }
+
+        Ok(aggpk)
+    }
+
+    /// Verifies a multisignature on `message` by the validators set in `bitmap`.
+    ///
+    /// WARNING: The multisignature is assumed to have been subgroup-checked, e.g., when deserialized
+    /// via `Validatable::validate`.
+    pub fn verify_multisig<T: CryptoHash + Serialize>(
+        &self,
+        message: &T,
+        bitmap: &[u8],
+        multisig: &Signature,
+    ) -> Result<(), CryptoError> {
+        let aggpk = self.aggregate(bitmap)?;
+        multisig.verify(message, &aggpk)
+    }
+
+    /// Same as `verify_multisig`, but on an arbitrary message.
+    pub fn verify_multisig_arbitrary_msg(
+        &self,
+        message: &[u8],
+        bitmap: &[u8],
+        multisig: &Signature,
+    ) -> Result<(), CryptoError> {
+        let aggpk = self.aggregate(bitmap)?;
+        multisig.verify_arbitrary_msg(message, &aggpk)
+    }
+
+    /// Checks that `bitmap` has one bit per validator and at least one of them set.
+    fn check_bitmap(&self, bitmap: &[u8]) -> Result<(), CryptoError> {
+        let num_bytes = (self.pubkeys.len() + 7) / 8;
+        if bitmap.len() != num_bytes {
+            return Err(CryptoError::InvalidBitmap(format!(
+                "expected {} bytes for {} validators, got {}",
+                num_bytes,
+                self.pubkeys.len(),
+                bitmap.len()
+            )));
+        }
+        // The bits past the last validator must not be set.
+        let num_padding_bits = num_bytes * 8 - self.pubkeys.len();
+        if let Some(last) = bitmap.last() {
+            if (*last as u16) & ((1 << num_padding_bits) - 1) != 0 {
+                return Err(CryptoError::InvalidBitmap(format!(
+                    "signer index out of bounds for {} validators",
+                    self.pubkeys.len()
+                )));
+            }
+        }
+        if bitmap.iter().all(|byte| *byte == 0) {
+            return Err(CryptoError::InvalidBitmap("no signers".to_string()));
+        }
+        Ok(())
+    }
+
+    fn lock_aggregates(&self) -> std::sync::MutexGuard<'_, HashMap<Vec<u8>, PublicKey>> {
+        // The map is left consistent if a thread panics while holding the lock.
+        self.aggregates
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
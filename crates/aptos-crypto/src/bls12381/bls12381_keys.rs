@@ -21,11 +21,14 @@
 //! their PoPs verified.
 
 use crate::{
-    bls12381, bls12381::DST_BLS_SIG_IN_G2_WITH_POP, hash::CryptoHash, signing_message, traits,
-    CryptoMaterialError, Genesis, Length, Uniform, ValidCryptoMaterial,
+    bls12381,
+    bls12381::DST_BLS_SIG_IN_G2_WITH_POP,
+    constant_time::{self, ConstantTimeEq},
+    error::CryptoError,
+    hash::CryptoHash,
+    signing_message, traits, CryptoMaterialError, Genesis, Length, Uniform, ValidCryptoMaterial,
     ValidCryptoMaterialStringExt, VerifyingKey,
 };
-use anyhow::{anyhow, Result};
 use aptos_crypto_derive::{DeserializeKey, SerializeKey, SilentDebug, SilentDisplay};
 use serde::Serialize;
 use std::{convert::TryFrom, fmt};
@@ -64,8 +67,10 @@ impl PublicKey {
     /// WARNING: Subgroup-checking is done implicitly when verifying the proof-of-possession (PoP) for
     /// this public key  in `ProofOfPossession::verify`, so this function should not be called
     /// separately for most use-cases. We leave it here just in case.
-    pub fn subgroup_check(&self) -> Result<()> {
-        self.pubkey.validate().map_err(|e| anyhow!("{:?}", e))
+    pub fn subgroup_check(&self) -> Result<(), CryptoError> {
+        self.pubkey
+            .validate()
+            .map_err(|e| CryptoError::MalformedKey(bls12381::subgroup_check_error(e)))
     }
 
     /// Aggregates the public keys of several signers into an aggregate public key, which can be later
@@ -73,12 +78,12 @@ impl PublicKey {
     ///
     /// WARNING: This function assumes all public keys have had their proofs-of-possession verified
     /// and have thus been group-checked.
-    pub fn aggregate(pubkeys: Vec<&Self>) -> Result<PublicKey> {
+    pub fn aggregate(pubkeys: Vec<&Self>) -> Result<PublicKey, CryptoError> {
         let blst_pubkeys: Vec<_> = pubkeys.iter().map(|pk| &pk.pubkey).collect();
 
         // CRYPTONOTE(Alin): We assume the PKs have had their PoPs verified and thus have also been subgroup-checked
         let aggpk = blst::min_pk::AggregatePublicKey::aggregate(&blst_pubkeys[..], false)
-            .map_err(|e| anyhow!("{:?}", e))?;
+            .map_err(|e| CryptoError::AggregationFailed(format!("{:?}", e)))?;
 
         Ok(PublicKey {
             pubkey: aggpk.to_public_key(),
@@ -101,6 +106,12 @@ impl PrivateKey {
 // PrivateKey Traits //
 ///////////////////////
 
+impl ConstantTimeEq for PrivateKey {
+    fn ct_eq(&self, other: &Self) -> bool {
+        constant_time::ct_eq(&self.to_bytes(), &other.to_bytes())
+    }
+}
+
 impl traits::PrivateKey for PrivateKey {
     type PublicKeyMaterial = PublicKey;
 }
@@ -144,7 +155,7 @@ impl TryFrom<&[u8]> for PrivateKey {
     type Error = CryptoMaterialError;
 
     /// Deserializes a PrivateKey from a sequence of bytes.
-    fn try_from(bytes: &[u8]) -> std::result::Result<Self, CryptoMaterialError> {
+    fn try_from(bytes: &[u8]) -> Result<Self, CryptoMaterialError> {
         Ok(Self {
             privkey: blst::min_pk::SecretKey::from_bytes(bytes)
                 .map_err(|_| CryptoMaterialError::DeserializationError)?,
@@ -235,7 +246,7 @@ impl TryFrom<&[u8]> for PublicKey {
     ///    which is mapped to `blst_p1_deserialize` in <https://github.com/supranational/blst/blob/711e1eec747772e8cae15d4a1885dd30a32048a4/bindings/rust/src/lib.rs#L1652>
     ///  - `blst_p1_deserialize` eventually calls `POINTonE1_Deserialize_BE`, which checks
     ///    the point is on the curve: <https://github.com/supranational/blst/blob/711e1eec747772e8cae15d4a1885dd30a32048a4/src/e1.c#L296>
-    fn try_from(bytes: &[u8]) -> std::result::Result<Self, CryptoMaterialError> {
+    fn try_from(bytes: &[u8]) -> Result<Self, CryptoMaterialError> {
         Ok(Self {
             pubkey: blst::min_pk::PublicKey::from_bytes(bytes)
                 .map_err(|_| CryptoMaterialError::DeserializationError)?,
@@ -11,10 +11,14 @@
 //! [^RY07]: The Power of Proofs-of-Possession: Securing Multiparty Signatures against Rogue-Key Attacks; by Ristenpart, Thomas and Yilek, Scott; in Advances in Cryptology - EUROCRYPT 2007; 2007
 
 use crate::{
-    bls12381::bls12381_keys::{PrivateKey, PublicKey},
+    bls12381::{
+        self,
+        bls12381_keys::{PrivateKey, PublicKey},
+    },
+    constant_time,
+    error::CryptoError,
     CryptoMaterialError, Length, ValidCryptoMaterial, ValidCryptoMaterialStringExt,
 };
-use anyhow::{anyhow, Result};
 use aptos_crypto_derive::{DeserializeKey, SerializeKey};
 use blst::BLST_ERROR;
 use std::{convert::TryFrom, fmt};
@@ -44,14 +48,16 @@ impl ProofOfPossession {
     ///
     /// WARNING: Subgroup-checking is done implicitly in `verify` below, so this function need not be called
     /// separately for most use-cases, as it incurs a performance penalty. We leave it here just in case.
-    pub fn subgroup_check(&self) -> Result<()> {
-        self.pop.validate(true).map_err(|e| anyhow!("{:?}", e))
+    pub fn subgroup_check(&self) -> Result<(), CryptoError> {
+        self.pop
+            .validate(true)
+            .map_err(|e| CryptoError::MalformedSignature(bls12381::subgroup_check_error(e)))
     }
 
     /// Verifies the proof-of-possesion (PoP) of the private key corresponding to the specified
     /// BLS public key. Implicitly, subgroup checks the PoP and the specified public key, so
     /// the caller is not responsible for doing it manually.
-    pub fn verify(&self, pk: &PublicKey) -> Result<()> {
+    pub fn verify(&self, pk: &PublicKey) -> Result<(), CryptoError> {
         // CRYPTONOTE(Alin): We call the signature verification function with pk_validate set to true
         // since we do not necessarily trust the PK we deserialized over the network whose PoP we are
         // verifying here.
@@ -66,10 +72,10 @@ impl ProofOfPossession {
         if result == BLST_ERROR::BLST_SUCCESS {
             Ok(())
         } else {
-            Err(anyhow!(
+            Err(CryptoError::InvalidSignature(format!(
                 "Proof-of-possession (PoP) did NOT verify: {:?}",
                 result
-            ))
+            )))
         }
     }
 
@@ -119,7 +125,7 @@ impl Length for ProofOfPossession {
 
 impl PartialEq for ProofOfPossession {
     fn eq(&self, other: &Self) -> bool {
-        self.pop.to_bytes() == other.to_bytes()
+        constant_time::ct_eq(&self.pop.to_bytes(), &other.to_bytes())
     }
 }
 
@@ -130,7 +136,7 @@ impl TryFrom<&[u8]> for ProofOfPossession {
     ///
     /// WARNING: Does NOT subgroup-check the PoP! This is done implicitly when verifying the PoP in
     /// `ProofOfPossession::verify`
-    fn try_from(bytes: &[u8]) -> std::result::Result<ProofOfPossession, CryptoMaterialError> {
+    fn try_from(bytes: &[u8]) -> Result<ProofOfPossession, CryptoMaterialError> {
         Ok(Self {
             pop: blst::min_pk::Signature::from_bytes(bytes)
                 .map_err(|_| CryptoMaterialError::DeserializationError)?,
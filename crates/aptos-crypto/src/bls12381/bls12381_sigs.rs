@@ -18,14 +18,16 @@
 
 use crate::{
     bls12381::{
+        self,
         bls12381_keys::{PrivateKey, PublicKey},
         DST_BLS_SIG_IN_G2_WITH_POP,
     },
+    constant_time,
+    error::CryptoError,
     hash::CryptoHash,
     signing_message, traits, CryptoMaterialError, Length, ValidCryptoMaterial,
     ValidCryptoMaterialStringExt,
 };
-use anyhow::{anyhow, Result};
 use aptos_crypto_derive::{DeserializeKey, SerializeKey};
 use blst::BLST_ERROR;
 use serde::Serialize;
@@ -57,8 +59,10 @@ impl Signature {
     /// WARNING: Subgroup-checking is done implicitly when verifying signatures via
     /// `Signature::verify_arbitrary_msg`. Therefore, this function should not be called separately
     /// for most use-cases. We leave it here just in case.
-    pub fn subgroup_check(&self) -> Result<()> {
-        self.sig.validate(true).map_err(|e| anyhow!("{:?}", e))
+    pub fn subgroup_check(&self) -> Result<(), CryptoError> {
+        self.sig
+            .validate(true)
+            .map_err(|e| CryptoError::MalformedSignature(bls12381::subgroup_check_error(e)))
     }
 
     /// Optimistically-aggregate signatures shares into either (1) a multisignature or (2) an aggregate
@@ -66,10 +70,10 @@ impl Signature {
     /// reasons, we do not subgroup-check the signature shares here, since the verification of the
     /// returned multi-or-aggregate signature includes such a subgroup check. As a result, adversarial
     /// signature shares cannot lead to forgeries.
-    pub fn aggregate(sigs: Vec<Self>) -> Result<Signature> {
+    pub fn aggregate(sigs: Vec<Self>) -> Result<Signature, CryptoError> {
         let sigs: Vec<_> = sigs.iter().map(|s| &s.sig).collect();
         let agg_sig = blst::min_pk::AggregateSignature::aggregate(&sigs[..], false)
-            .map_err(|e| anyhow!("{:?}", e))?;
+            .map_err(|e| CryptoError::AggregationFailed(format!("{:?}", e)))?;
         Ok(Signature {
             sig: agg_sig.to_signature(),
         })
@@ -82,7 +86,11 @@ impl Signature {
     ///
     /// WARNING: This function assumes that the public keys have been subgroup-checked by the caller
     /// implicitly when verifying their proof-of-possession (PoP) in `ProofOfPossession::verify`.
-    pub fn verify_aggregate_arbitrary_msg(&self, msgs: &[&[u8]], pks: &[&PublicKey]) -> Result<()> {
+    pub fn verify_aggregate_arbitrary_msg(
+        &self,
+        msgs: &[&[u8]],
+        pks: &[&PublicKey],
+    ) -> Result<(), CryptoError> {
         let pks = pks
             .iter()
             .map(|&pk| &pk.pubkey)
@@ -95,7 +103,7 @@ impl Signature {
         if result == BLST_ERROR::BLST_SUCCESS {
             Ok(())
         } else {
-            Err(anyhow!("{:?}", result))
+            Err(CryptoError::InvalidSignature(format!("{:?}", result)))
         }
     }
 
@@ -104,10 +112,13 @@ impl Signature {
         &self,
         msgs: &[&T],
         pks: &[&PublicKey],
-    ) -> Result<()> {
+    ) -> Result<(), CryptoError> {
         let mut messages: Vec<Vec<u8>> = vec![];
         for message in msgs {
-            messages.push(signing_message(*message)?);
+            messages.push(
+                signing_message(*message)
+                    .map_err(|e| CryptoError::MalformedMessage(e.to_string()))?,
+            );
         }
 
         let msgs_refs = messages
@@ -138,8 +149,14 @@ impl traits::Signature for Signature {
     type VerifyingKeyMaterial = PublicKey;
 
     /// Serializes the message of type `T` to bytes and calls `Signature::verify_arbitrary_msg`.
-    fn verify<T: CryptoHash + Serialize>(&self, message: &T, public_key: &PublicKey) -> Result<()> {
-        self.verify_arbitrary_msg(&signing_message(message)?, public_key)
+    fn verify<T: CryptoHash + Serialize>(
+        &self,
+        message: &T,
+        public_key: &PublicKey,
+    ) -> Result<(), CryptoError> {
+        let message =
+            signing_message(message).map_err(|e| CryptoError::MalformedMessage(e.to_string()))?;
+        self.verify_arbitrary_msg(&message, public_key)
     }
 
     /// Verifies a BLS signature share or multisignature. Does not assume the signature to be
@@ -149,7 +166,11 @@ impl traits::Signature for Signature {
     /// WARNING: This function does assume the public key has been subgroup-checked by the caller,
     /// either (1) implicitly when verifying the public key's proof-of-possession (PoP) in
     /// `ProofOfPossession::verify` or (2) via `Validatable::<PublicKey>::validate()`.
-    fn verify_arbitrary_msg(&self, message: &[u8], public_key: &PublicKey) -> Result<()> {
+    fn verify_arbitrary_msg(
+        &self,
+        message: &[u8],
+        public_key: &PublicKey,
+    ) -> Result<(), CryptoError> {
         let result = self.sig.verify(
             true,
             message,
@@ -161,7 +182,7 @@ impl traits::Signature for Signature {
         if result == BLST_ERROR::BLST_SUCCESS {
             Ok(())
         } else {
-            Err(anyhow!("{:?}", result))
+            Err(CryptoError::InvalidSignature(format!("{:?}", result)))
         }
     }
 
@@ -200,7 +221,7 @@ impl TryFrom<&[u8]> for Signature {
     ///
     /// WARNING: Does NOT subgroup-check the signature! Instead, this will be done implicitly when
     /// verifying the signature.
-    fn try_from(bytes: &[u8]) -> std::result::Result<Signature, CryptoMaterialError> {
+    fn try_from(bytes: &[u8]) -> Result<Signature, CryptoMaterialError> {
         Ok(Self {
             sig: blst::min_pk::Signature::from_bytes(bytes)
                 .map_err(|_| CryptoMaterialError::DeserializationError)?,
@@ -218,6 +239,6 @@ impl std::hash::Hash for Signature {
 // PartialEq trait implementation is required by the std::hash::Hash trait implementation above
 impl PartialEq for Signature {
     fn eq(&self, other: &Self) -> bool {
-        self.to_bytes()[..] == other.to_bytes()[..]
+        constant_time::ct_eq(&self.to_bytes(), &other.to_bytes())
     }
 }
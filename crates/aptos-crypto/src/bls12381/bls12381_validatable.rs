@@ -10,8 +10,10 @@
 //! the proof-of-possession (PoP) of a public key, which implicitly guarantees the PK lies in the
 //! prime-order subgroup. (See `bls12381_pop.rs` and `mod.rs` for details.)
 
-use crate::{bls12381::PublicKey, validatable::Validate, CryptoMaterialError, ValidCryptoMaterial};
-use anyhow::{anyhow, Result};
+use crate::{
+    bls12381::PublicKey, error::CryptoError, validatable::Validate, CryptoMaterialError,
+    ValidCryptoMaterial,
+};
 use serde::{Deserialize, Serialize};
 use std::{convert::TryFrom, hash::Hash};
 
@@ -34,7 +36,7 @@ impl TryFrom<&[u8]> for UnvalidatedPublicKey {
     /// WARNING: Does NOT do any checks whatsoever on these bytes beyond checking the length.
     /// The returned `UnvalidatedPublicKey` can only be used to create a `Validatable::<PublicKey>`
     /// via `Validatable::<PublicKey>::from_unvalidated`.
-    fn try_from(bytes: &[u8]) -> std::result::Result<Self, CryptoMaterialError> {
+    fn try_from(bytes: &[u8]) -> Result<Self, CryptoMaterialError> {
         if bytes.len() != PublicKey::LENGTH {
             Err(CryptoMaterialError::DeserializationError)
         } else {
@@ -109,11 +111,13 @@ impl PartialEq for UnvalidatedPublicKey {
 impl Validate for PublicKey {
     type Unvalidated = UnvalidatedPublicKey;
 
-    fn validate(unvalidated: &Self::Unvalidated) -> Result<Self> {
-        let pk = Self::try_from(unvalidated.0.as_ref())?;
+    fn validate(unvalidated: &Self::Unvalidated) -> Result<Self, CryptoError> {
+        let pk = Self::try_from(unvalidated.0.as_ref()).map_err(CryptoError::MalformedKey)?;
 
         if pk.subgroup_check().is_err() {
-            return Err(anyhow!("{:?}", CryptoMaterialError::SmallSubgroupError));
+            return Err(CryptoError::MalformedKey(
+                CryptoMaterialError::SmallSubgroupError,
+            ));
         }
 
         Ok(pk)
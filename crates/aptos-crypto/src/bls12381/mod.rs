@@ -419,12 +419,25 @@
 /// Domain separation tag (DST) for hashing a message before signing it.
 pub const DST_BLS_SIG_IN_G2_WITH_POP: &[u8] = b"BLS_SIG_BLS12381G2_XMD:SHA-256_SSWU_RO_POP_";
 
+pub mod bls12381_aggregate_cache;
 pub mod bls12381_keys;
 pub mod bls12381_pop;
 pub mod bls12381_sigs;
 pub mod bls12381_validatable;
 
+pub use bls12381_aggregate_cache::AggregatePublicKeyCache;
 pub use bls12381_keys::{PrivateKey, PublicKey};
 pub use bls12381_pop::ProofOfPossession;
 pub use bls12381_sigs::Signature;
 pub use bls12381_validatable::UnvalidatedPublicKey;
+
+/// Classifies the error of a failed subgroup check on a group element.
+fn subgroup_check_error(error: blst::BLST_ERROR) -> crate::CryptoMaterialError {
+    match error {
+        blst::BLST_ERROR::BLST_POINT_NOT_IN_GROUP => crate::CryptoMaterialError::SmallSubgroupError,
+        blst::BLST_ERROR::BLST_POINT_NOT_ON_CURVE => {
+            crate::CryptoMaterialError::PointNotOnCurveError
+        },
+        _ => crate::CryptoMaterialError::ValidationError,
+    }
+}
@@ -0,0 +1,42 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Constant-time comparisons of secrets, which do not leak how many leading bytes of the compared
+//! values are equal through their execution time.
+//!
+//! Private keys and signatures implement `PartialEq` through these comparisons. Still, private keys
+//! should be compared explicitly via [`ConstantTimeEq::ct_eq`]: when the `reject-secret-eq` feature
+//! is enabled, comparing them with `==` panics in debug builds, so that accidental comparisons are
+//! caught by tests.
+//!
+//! ```
+//! use aptos_crypto::{constant_time::ConstantTimeEq, ed25519::Ed25519PrivateKey, Uniform};
+//!
+//! let private_key = Ed25519PrivateKey::generate(&mut rand_core::OsRng);
+//! let other_private_key = Ed25519PrivateKey::generate(&mut rand_core::OsRng);
+//! assert!(!private_key.ct_eq(&other_private_key));
+//! ```
+
+/// Compares two byte strings in constant time. Only their lengths, which are assumed to be public,
+/// may leak.
+pub fn ct_eq(lhs: &[u8], rhs: &[u8]) -> bool {
+    subtle::ConstantTimeEq::ct_eq(lhs, rhs).into()
+}
+
+/// A type whose values are compared in constant time.
+pub trait ConstantTimeEq {
+    /// Returns true if `self` and `other` are equal, in constant time.
+    fn ct_eq(&self, other: &Self) -> bool;
+}
+
+/// Implements `PartialEq` for secrets: compares them in constant time, or panics in debug builds
+/// with the `reject-secret-eq` feature.
+pub(crate) fn secret_eq<T: ConstantTimeEq>(lhs: &T, rhs: &T) -> bool {
+    if cfg!(all(debug_assertions, feature = "reject-secret-eq")) {
+        panic!(
+            "Secrets of type {} should not be compared with `==`, use `ConstantTimeEq::ct_eq` instead",
+            std::any::type_name::<T>()
+        );
+    }
+    lhs.ct_eq(rhs)
+}
@@ -6,6 +6,7 @@
 #[cfg(any(test, feature = "fuzzing"))]
 use crate::test_utils::{self, KeyPair};
 use crate::{
+    constant_time::{self, ConstantTimeEq},
     ed25519::{Ed25519Signature, ED25519_PRIVATE_KEY_LENGTH, ED25519_PUBLIC_KEY_LENGTH},
     hash::CryptoHash,
     traits::*,
@@ -191,9 +192,15 @@ impl Uniform for Ed25519PrivateKey {
     }
 }
 
+impl ConstantTimeEq for Ed25519PrivateKey {
+    fn ct_eq(&self, other: &Self) -> bool {
+        constant_time::ct_eq(&self.to_bytes(), &other.to_bytes())
+    }
+}
+
 impl PartialEq<Self> for Ed25519PrivateKey {
     fn eq(&self, other: &Self) -> bool {
-        self.to_bytes() == other.to_bytes()
+        constant_time::secret_eq(self, other)
     }
 }
 
@@ -4,14 +4,24 @@
 //! This file implements traits for Ed25519 signatures.
 
 use crate::{
+    constant_time,
     ed25519::{Ed25519PrivateKey, Ed25519PublicKey, ED25519_SIGNATURE_LENGTH, L},
+    error::CryptoError,
     hash::CryptoHash,
     traits::*,
 };
-use anyhow::{anyhow, Result};
 use aptos_crypto_derive::{DeserializeKey, SerializeKey};
 use core::convert::TryFrom;
+use curve25519_dalek::{
+    constants::ED25519_BASEPOINT_POINT,
+    edwards::{CompressedEdwardsY, EdwardsPoint},
+    scalar::Scalar,
+    traits::{IsIdentity, VartimeMultiscalarMul},
+};
+use digest::Digest;
+use rand::Rng;
 use serde::Serialize;
+use sha2::Sha512;
 use std::{cmp::Ordering, fmt};
 
 /// An Ed25519 signature
@@ -40,13 +50,71 @@ impl Ed25519Signature {
     /// apart from expected signature size.
     pub(crate) fn from_bytes_unchecked(
         bytes: &[u8],
-    ) -> std::result::Result<Ed25519Signature, CryptoMaterialError> {
+    ) -> Result<Ed25519Signature, CryptoMaterialError> {
         match ed25519_dalek::Signature::try_from(bytes) {
             Ok(dalek_signature) => Ok(Ed25519Signature(dalek_signature)),
             Err(_) => Err(CryptoMaterialError::DeserializationError),
         }
     }
 
+    /// Verifies a batch of signatures over distinct messages and keys at once, which is
+    /// significantly cheaper than verifying them one by one. Fails if any of the signatures is
+    /// invalid, without telling which one.
+    ///
+    /// Unlike [Ed25519Signature::verify_arbitrary_msg], this checks the cofactored verification
+    /// equation (as the random linear combination of the equations is only sound for it), so a
+    /// batch that verifies may still contain signatures rejected by the strict verification
+    /// (i.e., signatures with mixed-order components). Small order keys and R components, and
+    /// non-canonical S components, are rejected as they are by the strict verification. Callers
+    /// needing strict verification must use this as a fast filter only.
+    pub fn batch_verify_distinct_messages(
+        batch: &[(&[u8], &Ed25519PublicKey, &Ed25519Signature)],
+    ) -> Result<(), CryptoError> {
+        let mut rng = rand::thread_rng();
+        let mut scalars = Vec::with_capacity(1 + 2 * batch.len());
+        let mut points = Vec::with_capacity(1 + 2 * batch.len());
+        let mut basepoint_scalar = Scalar::zero();
+        for (message, public_key, signature) in batch {
+            let signature_bytes = signature.to_bytes();
+            Ed25519Signature::check_s_malleability(&signature_bytes)
+                .map_err(CryptoError::MalformedSignature)?;
+            let r = decompress_non_small_order(&signature_bytes[..32])
+                .map_err(CryptoError::MalformedSignature)?;
+            let a = decompress_non_small_order(public_key.0.as_bytes())
+                .map_err(CryptoError::MalformedKey)?;
+            let mut s_bytes = [0u8; 32];
+            s_bytes.copy_from_slice(&signature_bytes[32..]);
+            let s = Scalar::from_canonical_bytes(s_bytes).ok_or(
+                CryptoError::MalformedSignature(CryptoMaterialError::CanonicalRepresentationError),
+            )?;
+            let h = Scalar::from_hash(
+                Sha512::new()
+                    .chain(&signature_bytes[..32])
+                    .chain(public_key.0.as_bytes())
+                    .chain(message),
+            );
+
+            // Each equation [s]B = R + [h]A is weighted by a random 128-bit scalar z
+            let z = Scalar::from(rng.gen::<u128>());
+            basepoint_scalar -= z * s;
+            scalars.push(z);
+            points.push(r);
+            scalars.push(z * h);
+            points.push(a);
+        }
+        scalars.push(basepoint_scalar);
+        points.push(ED25519_BASEPOINT_POINT);
+
+        let sum = EdwardsPoint::vartime_multiscalar_mul(scalars, points);
+        if sum.mul_by_cofactor().is_identity() {
+            Ok(())
+        } else {
+            Err(CryptoError::InvalidSignature(
+                "Batch verification failed".to_string(),
+            ))
+        }
+    }
+
     /// return an all-zero signature (for test only)
     #[cfg(any(test, feature = "fuzzing"))]
     pub fn dummy_signature() -> Self {
@@ -70,7 +138,7 @@ impl Ed25519Signature {
     /// Note: It's true that malicious signers can already produce varying signatures by
     /// choosing a different nonce, so this method protects against malleability attacks performed
     /// by a non-signer.
-    pub fn check_s_malleability(bytes: &[u8]) -> std::result::Result<(), CryptoMaterialError> {
+    pub fn check_s_malleability(bytes: &[u8]) -> Result<(), CryptoMaterialError> {
         if bytes.len() != ED25519_SIGNATURE_LENGTH {
             return Err(CryptoMaterialError::WrongLengthError);
         }
@@ -94,6 +162,17 @@ impl Ed25519Signature {
     }
 }
 
+/// Decompresses a point, failing if it's not canonically encoded or of small order.
+fn decompress_non_small_order(bytes: &[u8]) -> Result<EdwardsPoint, CryptoMaterialError> {
+    let point = CompressedEdwardsY::from_slice(bytes)
+        .decompress()
+        .ok_or(CryptoMaterialError::DeserializationError)?;
+    if point.is_small_order() {
+        return Err(CryptoMaterialError::SmallSubgroupError);
+    }
+    Ok(point)
+}
+
 //////////////////////
 // Signature Traits //
 //////////////////////
@@ -114,8 +193,10 @@ impl Signature for Ed25519Signature {
         &self,
         message: &T,
         public_key: &Ed25519PublicKey,
-    ) -> Result<()> {
-        Self::verify_arbitrary_msg(self, &signing_message(message)?, public_key)
+    ) -> Result<(), CryptoError> {
+        let message =
+            signing_message(message).map_err(|e| CryptoError::MalformedMessage(e.to_string()))?;
+        Self::verify_arbitrary_msg(self, &message, public_key)
     }
 
     /// Checks that `self` is valid for an arbitrary &[u8] `message` using `public_key`.
@@ -123,20 +204,25 @@ impl Signature for Ed25519Signature {
     /// verification in Move.
     ///
     /// This function will check both the signature and `public_key` for small subgroup attacks.
-    fn verify_arbitrary_msg(&self, message: &[u8], public_key: &Ed25519PublicKey) -> Result<()> {
+    fn verify_arbitrary_msg(
+        &self,
+        message: &[u8],
+        public_key: &Ed25519PublicKey,
+    ) -> Result<(), CryptoError> {
         // NOTE: ed25519::PublicKey::verify_strict already checks that the s-component of the signature
         // is not mauled, but does so via an optimistic path which fails into a slower path. By doing
         // our own (much faster) checking here, we can ensure dalek's optimistic path always succeeds
         // and the slow path is never triggered.
-        Ed25519Signature::check_s_malleability(&self.to_bytes())?;
+        Ed25519Signature::check_s_malleability(&self.to_bytes())
+            .map_err(CryptoError::MalformedSignature)?;
 
         // NOTE: ed25519::PublicKey::verify_strict checks that the signature's R-component and
         // the public key are *not* in a small subgroup.
         public_key
             .0
             .verify_strict(message, &self.0)
-            .map_err(|e| anyhow!("{}", e))
-            .and(Ok(()))
+            .map_err(|e| CryptoError::InvalidSignature(e.to_string()))?;
+        Ok(())
     }
 
     fn to_bytes(&self) -> Vec<u8> {
@@ -166,7 +252,7 @@ impl std::hash::Hash for Ed25519Signature {
 impl TryFrom<&[u8]> for Ed25519Signature {
     type Error = CryptoMaterialError;
 
-    fn try_from(bytes: &[u8]) -> std::result::Result<Ed25519Signature, CryptoMaterialError> {
+    fn try_from(bytes: &[u8]) -> Result<Ed25519Signature, CryptoMaterialError> {
         // We leave this check here to detect mauled signatures earlier, since it does not hurt
         // performance much. (This check is performed again in Ed25519Signature::verify_arbitrary_msg
         // and in ed25519-dalek's verify_strict API.)
@@ -178,7 +264,7 @@ impl TryFrom<&[u8]> for Ed25519Signature {
 // Those are required by the implementation of hash above
 impl PartialEq for Ed25519Signature {
     fn eq(&self, other: &Ed25519Signature) -> bool {
-        self.to_bytes()[..] == other.to_bytes()[..]
+        constant_time::ct_eq(&self.to_bytes(), &other.to_bytes())
     }
 }
 
@@ -2,6 +2,89 @@
 // Parts of the project are originally copyright © Meta Platforms, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-//! Rexport the error types needed for the various crypto traits
+//! The error types needed for the various crypto traits.
+//!
+//! The crypto traits and the signature schemes of this crate fail with a [`CryptoError`], which
+//! callers can match on to handle the failure programmatically:
+//!
+//! ```
+//! use aptos_crypto::{
+//!     ed25519::{Ed25519PrivateKey, Ed25519Signature},
+//!     error::CryptoErrorCategory,
+//!     PrivateKey, Signature, SigningKey, Uniform,
+//! };
+//!
+//! let private_key = Ed25519PrivateKey::generate(&mut rand_core::OsRng);
+//! let signature: Ed25519Signature = private_key.sign_arbitrary_message(b"message");
+//! let error = signature
+//!     .verify_arbitrary_msg(b"other message", &private_key.public_key())
+//!     .unwrap_err();
+//! assert_eq!(error.category(), CryptoErrorCategory::InvalidSignature);
+//! ```
 
-pub use anyhow::{bail, Error};
+use crate::traits::CryptoMaterialError;
+
+/// An error of the signature schemes of this crate.
+#[derive(Clone, Debug, PartialEq, Eq, thiserror::Error)]
+#[non_exhaustive]
+pub enum CryptoError {
+    /// A key is not valid, e.g., it is not in the prime-order subgroup.
+    #[error("Malformed key: {0}")]
+    MalformedKey(CryptoMaterialError),
+    /// A signature is not valid, e.g., it is not canonical.
+    #[error("Malformed signature: {0}")]
+    MalformedSignature(CryptoMaterialError),
+    /// A well-formed signature does not verify.
+    #[error("Invalid signature: {0}")]
+    InvalidSignature(String),
+    /// A message cannot be signed or verified, e.g., it does not serialize.
+    #[error("Malformed message: {0}")]
+    MalformedMessage(String),
+    /// Keys or signatures cannot be aggregated.
+    #[error("Aggregation failed: {0}")]
+    AggregationFailed(String),
+    /// A multi-signature does not match the signers of a multi-key, e.g., it has too few
+    /// signatures to meet the threshold.
+    #[error("Invalid signer bitmap: {0}")]
+    InvalidBitmap(String),
+    /// Two message types share the same domain separation salt, so that a signature on one of
+    /// them could be replayed as a signature on the other.
+    #[error("Signing domain collision: {0}")]
+    DomainCollision(String),
+}
+
+/// The category of a [`CryptoError`], for callers that only need to know which input is at fault.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum CryptoErrorCategory {
+    /// A key is malformed.
+    MalformedKey,
+    /// A signature is malformed, or does not match the signers.
+    MalformedSignature,
+    /// A well-formed signature does not verify.
+    InvalidSignature,
+    /// A key, signature or message does not have the expected length.
+    WrongLength,
+    /// Any other error, e.g., a message that does not serialize.
+    Other,
+}
+
+impl CryptoError {
+    /// Returns the category of the error.
+    pub fn category(&self) -> CryptoErrorCategory {
+        match self {
+            CryptoError::MalformedKey(CryptoMaterialError::WrongLengthError)
+            | CryptoError::MalformedSignature(CryptoMaterialError::WrongLengthError) => {
+                CryptoErrorCategory::WrongLength
+            },
+            CryptoError::MalformedKey(_) => CryptoErrorCategory::MalformedKey,
+            CryptoError::MalformedSignature(_) | CryptoError::InvalidBitmap(_) => {
+                CryptoErrorCategory::MalformedSignature
+            },
+            CryptoError::InvalidSignature(_) => CryptoErrorCategory::InvalidSignature,
+            CryptoError::MalformedMessage(_)
+            | CryptoError::AggregationFailed(_)
+            | CryptoError::DomainCollision(_) => CryptoErrorCategory::Other,
+        }
+    }
+}
@@ -99,13 +99,14 @@
 //! let hash_value = hasher.finish();
 //! ```
 #![allow(clippy::arithmetic_side_effects)]
+use crate::rng::DefaultRng;
 use bytes::Bytes;
 use hex::FromHex;
 use more_asserts::debug_assert_lt;
 use once_cell::sync::{Lazy, OnceCell};
 #[cfg(any(test, feature = "fuzzing"))]
 use proptest_derive::Arbitrary;
-use rand::{distributions::Standard, prelude::Distribution, rngs::OsRng, Rng};
+use rand::{distributions::Standard, prelude::Distribution, Rng};
 use serde::{de, ser, Deserialize, Serialize};
 use std::{
     self,
@@ -159,7 +160,7 @@ impl HashValue {
 
     /// Create a cryptographically random instance.
     pub fn random() -> Self {
-        Self::random_with_rng(&mut OsRng)
+        Self::random_with_rng(&mut DefaultRng)
     }
 
     /// Creates a random instance with given rng. Useful in unit tests.
@@ -179,6 +180,14 @@ impl HashValue {
         HashValue::from_keccak(sha3)
     }
 
+    /// Same as `sha3_256_of`, but reads the bytes from `reader` until EOF, so that large payloads
+    /// (e.g., backup files) can be hashed without buffering them entirely.
+    pub fn sha3_256_of_reader<R: std::io::Read>(mut reader: R) -> std::io::Result<Self> {
+        let mut hasher = Sha3Hasher::new();
+        std::io::copy(&mut reader, &mut hasher)?;
+        Ok(hasher.finish())
+    }
+
     #[cfg(test)]
     pub fn from_iter_sha3<'a, I>(buffers: I) -> Self
     where
@@ -505,6 +514,52 @@ pub trait CryptoHasher: Default + std::io::Write {
         hasher.update(bytes);
         hasher.finish()
     }
+
+    /// Write the BCS serialization of `value` into the hasher, without buffering it.
+    fn update_bcs<T: Serialize + ?Sized>(&mut self, value: &T) -> bcs::Result<()> {
+        bcs::serialize_into(self, value)
+    }
+
+    /// Convenience method to compute the hash of the bytes read from `reader` until EOF, without
+    /// buffering them.
+    fn hash_reader<R: std::io::Read>(mut reader: R) -> std::io::Result<HashValue> {
+        let mut hasher = Self::default();
+        std::io::copy(&mut reader, &mut hasher)?;
+        Ok(hasher.finish())
+    }
+}
+
+/// An incremental version of [`HashValue::sha3_256_of`]: computes the plain sha3-256 of the bytes
+/// fed into it, without any domain separation.
+#[derive(Clone, Debug, Default)]
+pub struct Sha3Hasher(DefaultHasher);
+
+impl Sha3Hasher {
+    /// Creates a hasher which has not been fed any bytes yet.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Write bytes into the hasher.
+    pub fn update(&mut self, bytes: &[u8]) {
+        self.0.update(bytes);
+    }
+
+    /// Finish constructing the [`HashValue`].
+    pub fn finish(self) -> HashValue {
+        self.0.finish()
+    }
+}
+
+impl std::io::Write for Sha3Hasher {
+    fn write(&mut self, bytes: &[u8]) -> std::io::Result<usize> {
+        self.0.update(bytes);
+        Ok(bytes.len())
+    }
+
+    fn flush(&mut self) -> std::io::Result<()> {
+        Ok(())
+    }
 }
 
 /// The default hasher underlying generated implementations of `CryptoHasher`.
@@ -550,6 +605,12 @@ impl DefaultHasher {
     }
 }
 
+impl Default for DefaultHasher {
+    fn default() -> Self {
+        Self::new(b"")
+    }
+}
+
 impl fmt::Debug for DefaultHasher {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         write!(f, "DefaultHasher: state = Sha3")
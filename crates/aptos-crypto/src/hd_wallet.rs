@@ -0,0 +1,221 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Hierarchical deterministic (HD) derivation of Ed25519 keys from a seed or a BIP-39 mnemonic,
+//! following [SLIP-0010](https://github.com/satoshilabs/slips/blob/master/slip-0010.md) along BIP-44
+//! derivation paths.
+//!
+//! Aptos accounts are derived along the path `m/44'/637'/{account}'/0'/0'`, the same way as the
+//! wallets and the TypeScript and Python SDKs do, so that Rust tools can recover the accounts of a
+//! mnemonic.
+//!
+//! ```
+//! use aptos_crypto::{hd_wallet, PrivateKey};
+//!
+//! let mnemonic =
+//!     "shoot island position soft burden budget tooth cruel issue economy destroy above";
+//! let private_key =
+//!     hd_wallet::derive_from_mnemonic(mnemonic, "", &hd_wallet::aptos_derivation_path(0))
+//!         .unwrap();
+//! let _public_key = private_key.public_key();
+//! ```
+//!
+//! Ed25519 only supports hardened derivation, so every index of a derivation path must be
+//! hardened.
+
+use crate::ed25519::Ed25519PrivateKey;
+use bip39::{Language, Mnemonic, Seed};
+use hmac::{Hmac, Mac};
+use sha2_0_10_6::Sha512;
+use std::{convert::TryFrom, fmt, str::FromStr};
+use thiserror::Error;
+
+/// The BIP-44 coin type of Aptos.
+pub const APTOS_COIN_TYPE: u32 = 637;
+
+/// The HMAC key of the master key derivation of Ed25519 keys, as defined in SLIP-0010.
+const ED25519_SEED_KEY: &[u8] = b"ed25519 seed";
+
+/// The offset of hardened indices.
+const HARDENED_OFFSET: u32 = 1 << 31;
+
+type HmacSha512 = Hmac<Sha512>;
+
+/// An error type for HD key derivation issues.
+#[derive(Clone, Debug, PartialEq, Eq, Error)]
+pub enum HdWalletError {
+    /// The derivation path is not of the form `m/44'/637'/0'/0'/0'`.
+    #[error("Invalid derivation path: {0}")]
+    InvalidDerivationPath(String),
+    /// Ed25519 keys can only be derived along hardened indices.
+    #[error("Expected a hardened index, got {0}")]
+    NonHardenedIndex(u32),
+    /// The mnemonic is not a valid BIP-39 English mnemonic.
+    #[error("Invalid mnemonic: {0}")]
+    InvalidMnemonic(String),
+}
+
+/// A derivation path, e.g., `m/44'/637'/0'/0'/0'`, whose indices are all hardened.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct DerivationPath {
+    /// The indices of the path, without the hardened offset.
+    indices: Vec<u32>,
+}
+
+impl DerivationPath {
+    /// Returns the path of the given hardened indices, without their hardened offset.
+    pub fn new(indices: Vec<u32>) -> Result<Self, HdWalletError> {
+        match indices.iter().find(|index| **index >= HARDENED_OFFSET) {
+            Some(index) => Err(HdWalletError::NonHardenedIndex(*index)),
+            None => Ok(Self { indices }),
+        }
+    }
+
+    /// Returns the indices of the path, without their hardened offset.
+    pub fn indices(&self) -> &[u32] {
+        &self.indices
+    }
+}
+
+impl FromStr for DerivationPath {
+    type Err = HdWalletError;
+
+    fn from_str(path: &str) -> Result<Self, HdWalletError> {
+        let invalid = || HdWalletError::InvalidDerivationPath(path.to_string());
+
+        let mut components = path.split('/');
+        if components.next() != Some("m") {
+            return Err(invalid());
+        }
+        let indices = components
+            .map(|component| {
+                let index = component
+                    .strip_suffix('\'')
+                    .ok_or_else(invalid)?
+                    .parse::<u32>()
+                    .map_err(|_| invalid())?;
+                if index >= HARDENED_OFFSET {
+                    return Err(invalid());
+                }
+                Ok(index)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Self { indices })
+    }
+}
+
+impl fmt::Display for DerivationPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "m")?;
+        for index in &self.indices {
+            write!(f, "/{}'", index)?;
+        }
+        Ok(())
+    }
+}
+
+/// Returns the derivation path of the `account_index`-th Aptos account, i.e.,
+/// `m/44'/637'/{account_index}'/0'/0'`.
+pub fn aptos_derivation_path(account_index: u32) -> DerivationPath {
+    DerivationPath {
+        indices: vec![44, APTOS_COIN_TYPE, account_index, 0, 0],
+    }
+}
+
+/// An Ed25519 private key along with the chain code to derive its children.
+#[derive(Clone)]
+pub struct ExtendedPrivateKey {
+    /// How many derivations this key is from the master key.
+    depth: u8,
+    key: [u8; 32],
+    chain_code: [u8; 32],
+}
+
+impl ExtendedPrivateKey {
+    /// Returns the master key of `seed`.
+    pub fn from_seed(seed: &[u8]) -> Self {
+        let mut mac =
+            HmacSha512::new_from_slice(ED25519_SEED_KEY).expect("HMAC accepts keys of any length");
+        mac.update(seed);
+        Self::from_hmac(0, mac)
+    }
+
+    /// Returns the master key of the seed of a BIP-39 English mnemonic, protected by an optional
+    /// passphrase (use `""` if none).
+    pub fn from_mnemonic(mnemonic: &str, passphrase: &str) -> Result<Self, HdWalletError> {
+        let mnemonic = Mnemonic::from_phrase(mnemonic, Language::English)
+            .map_err(|e| HdWalletError::InvalidMnemonic(e.to_string()))?;
+        Ok(Self::from_seed(Seed::new(&mnemonic, passphrase).as_bytes()))
+    }
+
+    /// Derives the child of this key at the hardened `index` (without its hardened offset).
+    pub fn derive_child(&self, index: u32) -> Result<Self, HdWalletError> {
+        if index >= HARDENED_OFFSET {
+            return Err(HdWalletError::NonHardenedIndex(index));
+        }
+        let mut mac =
+            HmacSha512::new_from_slice(&self.chain_code).expect("HMAC accepts keys of any length");
+        mac.update(&[0u8]);
+        mac.update(&self.key);
+        mac.update(&(index | HARDENED_OFFSET).to_be_bytes());
+        Ok(Self::from_hmac(self.depth.saturating_add(1), mac))
+    }
+
+    /// Derives the descendant of this key along `path`.
+    pub fn derive(&self, path: &DerivationPath) -> Result<Self, HdWalletError> {
+        path.indices
+            .iter()
+            .try_fold(self.clone(), |key, index| key.derive_child(*index))
+    }
+
+    /// Returns how many derivations this key is from the master key.
+    pub fn depth(&self) -> u8 {
+        self.depth
+    }
+
+    /// Returns the chain code of this key.
+    pub fn chain_code(&self) -> &[u8; 32] {
+        &self.chain_code
+    }
+
+    /// Returns the Ed25519 private key.
+    pub fn private_key(&self) -> Ed25519PrivateKey {
+        Ed25519PrivateKey::try_from(&self.key[..]).expect("Any 32 bytes are a valid Ed25519 key")
+    }
+
+    fn from_hmac(depth: u8, mac: HmacSha512) -> Self {
+        let bytes = mac.finalize().into_bytes();
+        let mut key = [0u8; 32];
+        key.copy_from_slice(&bytes[..32]);
+        let mut chain_code = [0u8; 32];
+        chain_code.copy_from_slice(&bytes[32..]);
+        Self {
+            depth,
+            key,
+            chain_code,
+        }
+    }
+}
+
+// Do not print the private key and chain code
+impl fmt::Debug for ExtendedPrivateKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "ExtendedPrivateKey(depth: {}, <elided secret>)",
+            self.depth
+        )
+    }
+}
+
+/// Derives the Ed25519 private key along `path` from a BIP-39 English mnemonic, protected by an
+/// optional passphrase (use `""` if none).
+pub fn derive_from_mnemonic(
+    mnemonic: &str,
+    passphrase: &str,
+    path: &DerivationPath,
+) -> Result<Ed25519PrivateKey, HdWalletError> {
+    Ok(ExtendedPrivateKey::from_mnemonic(mnemonic, passphrase)?
+        .derive(path)?
+        .private_key())
+}
@@ -10,22 +10,26 @@ pub mod asymmetric_encryption;
 pub mod bls12381;
 pub mod bulletproofs;
 pub mod compat;
+pub mod constant_time;
 pub mod ed25519;
 pub mod elgamal;
 pub mod encoding_type;
 pub mod error;
 pub mod hash;
+pub mod hd_wallet;
 pub mod hkdf;
 pub mod multi_ed25519;
 pub mod noise;
 pub mod secp256k1_ecdsa;
 pub mod secp256r1_ecdsa;
+pub mod signed_envelope;
 pub mod test_utils;
 pub mod traits;
 pub mod validatable;
 pub mod x25519;
 
 pub mod poseidon_bn254;
+pub mod rng;
 #[cfg(test)]
 mod unit_tests;
 
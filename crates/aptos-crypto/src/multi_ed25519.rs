@@ -8,14 +8,15 @@
 //! Signature verification also checks and rejects non-canonical signatures.
 
 use crate::{
+    constant_time::{self, ConstantTimeEq},
     ed25519::{
         Ed25519PrivateKey, Ed25519PublicKey, Ed25519Signature, ED25519_PRIVATE_KEY_LENGTH,
         ED25519_PUBLIC_KEY_LENGTH, ED25519_SIGNATURE_LENGTH,
     },
+    error::CryptoError,
     hash::{CryptoHash, CryptoHasher},
     traits::*,
 };
-use anyhow::{anyhow, Result};
 use aptos_crypto_derive::{DeserializeKey, SerializeKey, SilentDebug, SilentDisplay};
 use core::convert::TryFrom;
 use rand::Rng;
@@ -28,7 +29,7 @@ pub const MAX_NUM_OF_KEYS: usize = 32;
 pub const BITMAP_NUM_OF_BYTES: usize = 4;
 
 /// Vector of private keys in the multi-key Ed25519 structure along with the threshold.
-#[derive(DeserializeKey, Eq, PartialEq, SilentDisplay, SilentDebug, SerializeKey)]
+#[derive(DeserializeKey, SilentDisplay, SilentDebug, SerializeKey)]
 pub struct MultiEd25519PrivateKey {
     private_keys: Vec<Ed25519PrivateKey>,
     threshold: u8,
@@ -60,7 +61,7 @@ impl MultiEd25519PrivateKey {
     pub fn new(
         private_keys: Vec<Ed25519PrivateKey>,
         threshold: u8,
-    ) -> std::result::Result<Self, CryptoMaterialError> {
+    ) -> Result<Self, CryptoMaterialError> {
         let num_of_private_keys = private_keys.len();
         if threshold == 0 || num_of_private_keys < threshold as usize {
             Err(CryptoMaterialError::ValidationError)
@@ -89,7 +90,7 @@ impl MultiEd25519PublicKey {
     pub fn new(
         public_keys: Vec<Ed25519PublicKey>,
         threshold: u8,
-    ) -> std::result::Result<Self, CryptoMaterialError> {
+    ) -> Result<Self, CryptoMaterialError> {
         let num_of_public_keys = public_keys.len();
         if threshold == 0 || num_of_public_keys < threshold as usize {
             Err(CryptoMaterialError::ValidationError)
@@ -133,6 +134,20 @@ impl From<&Ed25519PrivateKey> for MultiEd25519PrivateKey {
     }
 }
 
+impl ConstantTimeEq for MultiEd25519PrivateKey {
+    fn ct_eq(&self, other: &Self) -> bool {
+        constant_time::ct_eq(&self.to_bytes(), &other.to_bytes())
+    }
+}
+
+impl PartialEq<Self> for MultiEd25519PrivateKey {
+    fn eq(&self, other: &Self) -> bool {
+        constant_time::secret_eq(self, other)
+    }
+}
+
+impl Eq for MultiEd25519PrivateKey {}
+
 impl PrivateKey for MultiEd25519PrivateKey {
     type PublicKeyMaterial = MultiEd25519PublicKey;
 }
@@ -209,7 +224,7 @@ impl TryFrom<&[u8]> for MultiEd25519PrivateKey {
     type Error = CryptoMaterialError;
 
     /// Deserialize an Ed25519PrivateKey. This method will also check for key and threshold validity.
-    fn try_from(bytes: &[u8]) -> std::result::Result<MultiEd25519PrivateKey, CryptoMaterialError> {
+    fn try_from(bytes: &[u8]) -> Result<MultiEd25519PrivateKey, CryptoMaterialError> {
         if bytes.is_empty() {
             return Err(CryptoMaterialError::WrongLengthError);
         }
@@ -298,7 +313,7 @@ impl TryFrom<&[u8]> for MultiEd25519PublicKey {
     /// Deserialize a MultiEd25519PublicKey. This method will also check for threshold validity.
     /// This method will NOT ensure keys are safe against small subgroup attacks, since our signature
     /// verification API will automatically prevent it.
-    fn try_from(bytes: &[u8]) -> std::result::Result<MultiEd25519PublicKey, CryptoMaterialError> {
+    fn try_from(bytes: &[u8]) -> Result<MultiEd25519PublicKey, CryptoMaterialError> {
         if bytes.is_empty() {
             return Err(CryptoMaterialError::WrongLengthError);
         }
@@ -346,9 +361,7 @@ impl ValidCryptoMaterial for MultiEd25519PublicKey {
 
 impl MultiEd25519Signature {
     /// This method will also sort signatures based on index.
-    pub fn new(
-        signatures: Vec<(Ed25519Signature, u8)>,
-    ) -> std::result::Result<Self, CryptoMaterialError> {
+    pub fn new(signatures: Vec<(Ed25519Signature, u8)>) -> Result<Self, CryptoMaterialError> {
         let num_of_sigs = signatures.len();
         if num_of_sigs == 0 || num_of_sigs > MAX_NUM_OF_KEYS {
             return Err(CryptoMaterialError::ValidationError);
@@ -423,7 +436,7 @@ impl TryFrom<&[u8]> for MultiEd25519Signature {
 
     /// Deserialize a MultiEd25519Signature. This method will also check for malleable signatures
     /// and bitmap validity.
-    fn try_from(bytes: &[u8]) -> std::result::Result<MultiEd25519Signature, CryptoMaterialError> {
+    fn try_from(bytes: &[u8]) -> Result<MultiEd25519Signature, CryptoMaterialError> {
         let length = bytes.len();
         let bitmap_num_of_bytes = length % ED25519_SIGNATURE_LENGTH;
         let num_of_sigs = length / ED25519_SIGNATURE_LENGTH;
@@ -491,12 +504,12 @@ impl Signature for MultiEd25519Signature {
         &self,
         message: &T,
         public_key: &MultiEd25519PublicKey,
-    ) -> Result<()> {
+    ) -> Result<(), CryptoError> {
         // NOTE: Public keys need not be validated because we use ed25519_dalek's verify_strict,
         // which checks for small order public keys.
         let mut bytes = <T as CryptoHash>::Hasher::seed().to_vec();
         bcs::serialize_into(&mut bytes, &message)
-            .map_err(|_| CryptoMaterialError::SerializationError)?;
+            .map_err(|e| CryptoError::MalformedMessage(e.to_string()))?;
         Self::verify_arbitrary_msg(self, &bytes, public_key)
     }
 
@@ -507,24 +520,20 @@ impl Signature for MultiEd25519Signature {
         &self,
         message: &[u8],
         public_key: &MultiEd25519PublicKey,
-    ) -> Result<()> {
+    ) -> Result<(), CryptoError> {
         // NOTE: Public keys need not be validated because we use ed25519_dalek's verify_strict,
         // which checks for small order public keys.
         match bitmap_last_set_bit(self.bitmap) {
             Some(last_bit) if (last_bit as usize) < public_key.public_keys.len() => (),
             _ => {
-                return Err(anyhow!(
-                    "{}",
-                    CryptoMaterialError::BitVecError("Signature index is out of range".to_string())
+                return Err(CryptoError::InvalidBitmap(
+                    "Signature index is out of range".to_string(),
                 ))
             },
         };
         if bitmap_count_ones(self.bitmap) < public_key.threshold as u32 {
-            return Err(anyhow!(
-                "{}",
-                CryptoMaterialError::BitVecError(
-                    "Not enough signatures to meet the threshold".to_string()
-                )
+            return Err(CryptoError::InvalidBitmap(
+                "Not enough signatures to meet the threshold".to_string(),
             ));
         }
         let mut bitmap_index = 0;
@@ -573,7 +582,7 @@ fn to_bytes<T: ValidCryptoMaterial>(keys: &[T], threshold: u8) -> Vec<u8> {
 pub fn check_and_get_threshold(
     bytes: &[u8],
     key_size: usize,
-) -> std::result::Result<(u8, u8), CryptoMaterialError> {
+) -> Result<(u8, u8), CryptoMaterialError> {
     let payload_length = bytes.len();
     if bytes.is_empty() {
         return Err(CryptoMaterialError::WrongLengthError);
@@ -0,0 +1,105 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! The default source of randomness, for the callers which do not bring their own RNG.
+//!
+//! [`DefaultRng`] is the OS RNG. When the non-default `deterministic-rng` feature is enabled, it
+//! can be replaced by a DRBG, so that tests and fuzzers generating keys or running noise handshakes
+//! are reproducible:
+//! - `set_seed` seeds a DRBG for the current thread, which does not interfere with the tests running
+//!   in parallel;
+//! - the `APTOS_CRYPTO_RNG_SEED` environment variable (a hex-encoded 32-byte seed) seeds a DRBG
+//!   shared by all the other threads, e.g., for e2e tests spawning their own threads.
+//!
+//! WARNING: The `deterministic-rng` feature must never be enabled in production builds, as anybody
+//! knowing the seed can recover all the generated keys.
+//!
+//! ```
+//! use aptos_crypto::{ed25519::Ed25519PrivateKey, rng::DefaultRng, Uniform};
+//!
+//! let _private_key = Ed25519PrivateKey::generate(&mut DefaultRng);
+//! ```
+
+use rand::{rngs::OsRng, CryptoRng, RngCore};
+#[cfg(feature = "deterministic-rng")]
+use rand::{rngs::StdRng, SeedableRng};
+#[cfg(feature = "deterministic-rng")]
+use std::{cell::RefCell, sync::Mutex};
+
+/// The environment variable holding the hex-encoded seed of the deterministic RNG.
+#[cfg(feature = "deterministic-rng")]
+pub const SEED_ENV_VAR: &str = "APTOS_CRYPTO_RNG_SEED";
+
+/// The DRBG seeded from the environment, shared by the threads without their own seeded DRBG.
+#[cfg(feature = "deterministic-rng")]
+static ENV_SEEDED_RNG: once_cell::sync::Lazy<Mutex<Option<StdRng>>> =
+    once_cell::sync::Lazy::new(|| Mutex::new(seed_from_env().map(StdRng::from_seed)));
+
+#[cfg(feature = "deterministic-rng")]
+thread_local! {
+    static THREAD_SEEDED_RNG: RefCell<Option<StdRng>> = RefCell::new(None);
+}
+
+#[cfg(feature = "deterministic-rng")]
+fn seed_from_env() -> Option<[u8; 32]> {
+    let seed = std::env::var(SEED_ENV_VAR).ok()?;
+    let seed = hex::decode(seed.trim_start_matches("0x"))
+        .ok()
+        .and_then(|seed| <[u8; 32]>::try_from(seed).ok())
+        .unwrap_or_else(|| panic!("{} must be a hex-encoded 32-byte seed", SEED_ENV_VAR));
+    Some(seed)
+}
+
+/// Replaces the RNG of the current thread with a DRBG seeded with `seed`, or restores the default
+/// one if `seed` is `None`.
+#[cfg(feature = "deterministic-rng")]
+pub fn set_seed(seed: Option<[u8; 32]>) {
+    THREAD_SEEDED_RNG.with(|rng| *rng.borrow_mut() = seed.map(StdRng::from_seed));
+}
+
+/// Draws from the DRBG of the current thread if any, then from the DRBG seeded from the
+/// environment if any, and from the OS RNG otherwise.
+#[cfg(feature = "deterministic-rng")]
+fn with_rng<T>(f: impl FnOnce(&mut dyn RngCore) -> T) -> T {
+    THREAD_SEEDED_RNG.with(|thread_rng| {
+        if let Some(rng) = thread_rng.borrow_mut().as_mut() {
+            return f(rng);
+        }
+        let mut env_rng = ENV_SEEDED_RNG
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        match env_rng.as_mut() {
+            Some(rng) => f(rng),
+            None => f(&mut OsRng),
+        }
+    })
+}
+
+#[cfg(not(feature = "deterministic-rng"))]
+fn with_rng<T>(f: impl FnOnce(&mut dyn RngCore) -> T) -> T {
+    f(&mut OsRng)
+}
+
+/// The OS RNG, unless replaced by a seeded DRBG with the `deterministic-rng` feature.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct DefaultRng;
+
+impl RngCore for DefaultRng {
+    fn next_u32(&mut self) -> u32 {
+        with_rng(|rng| rng.next_u32())
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        with_rng(|rng| rng.next_u64())
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        with_rng(|rng| rng.fill_bytes(dest))
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        with_rng(|rng| rng.try_fill_bytes(dest))
+    }
+}
+
+impl CryptoRng for DefaultRng {}
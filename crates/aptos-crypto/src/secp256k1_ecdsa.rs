@@ -4,11 +4,12 @@
 //! This module provides APIs for private keys and public keys used in Secp256k1 ecdsa.
 
 use crate::{
+    constant_time::{self, ConstantTimeEq},
+    error::CryptoError,
     hash::{CryptoHash, HashValue},
     traits,
     traits::{CryptoMaterialError, ValidCryptoMaterial, ValidCryptoMaterialStringExt},
 };
-use anyhow::{anyhow, Result};
 use aptos_crypto_derive::{key_name, DeserializeKey, SerializeKey, SilentDebug, SilentDisplay};
 use core::convert::TryFrom;
 use serde::Serialize;
@@ -23,7 +24,7 @@ pub const PUBLIC_KEY_LENGTH: usize = 65;
 pub const SIGNATURE_LENGTH: usize = 64;
 
 /// Secp256k1 ecdsa private key
-#[derive(DeserializeKey, Eq, PartialEq, SerializeKey, SilentDebug, SilentDisplay)]
+#[derive(DeserializeKey, SerializeKey, SilentDebug, SilentDisplay)]
 #[key_name("Secp256k1EcdsaPrivateKey")]
 pub struct PrivateKey(pub(crate) libsecp256k1::SecretKey);
 
@@ -60,10 +61,24 @@ impl PrivateKey {
     }
 }
 
+impl ConstantTimeEq for PrivateKey {
+    fn ct_eq(&self, other: &Self) -> bool {
+        constant_time::ct_eq(&self.to_bytes(), &other.to_bytes())
+    }
+}
+
+impl PartialEq<Self> for PrivateKey {
+    fn eq(&self, other: &Self) -> bool {
+        constant_time::secret_eq(self, other)
+    }
+}
+
+impl Eq for PrivateKey {}
+
 impl TryFrom<&[u8]> for PrivateKey {
     type Error = CryptoMaterialError;
 
-    fn try_from(bytes: &[u8]) -> std::result::Result<PrivateKey, CryptoMaterialError> {
+    fn try_from(bytes: &[u8]) -> Result<PrivateKey, CryptoMaterialError> {
         match libsecp256k1::SecretKey::parse_slice(bytes) {
             Ok(private_key) => Ok(PrivateKey(private_key)),
             Err(_) => Err(CryptoMaterialError::DeserializationError),
@@ -156,7 +171,7 @@ impl std::hash::Hash for PublicKey {
 impl TryFrom<&[u8]> for PublicKey {
     type Error = CryptoMaterialError;
 
-    fn try_from(bytes: &[u8]) -> std::result::Result<PublicKey, CryptoMaterialError> {
+    fn try_from(bytes: &[u8]) -> Result<PublicKey, CryptoMaterialError> {
         match libsecp256k1::PublicKey::parse_slice(bytes, None) {
             Ok(public_key) => Ok(PublicKey(public_key)),
             Err(_) => Err(CryptoMaterialError::DeserializationError),
@@ -206,15 +221,19 @@ impl Signature {
         &self,
         message: &libsecp256k1::Message,
         public_key: &libsecp256k1::PublicKey,
-    ) -> Result<()> {
+    ) -> Result<(), CryptoError> {
         // Prevent malleability attacks, low order only. The library only signs in low
         // order, so this was done intentionally.
         if self.0.s.is_high() {
-            Err(anyhow!(CryptoMaterialError::CanonicalRepresentationError))
+            Err(CryptoError::MalformedSignature(
+                CryptoMaterialError::CanonicalRepresentationError,
+            ))
         } else if libsecp256k1::verify(message, &self.0, public_key) {
             Ok(())
         } else {
-            Err(anyhow!("Unable to verify signature."))
+            Err(CryptoError::InvalidSignature(
+                "Unable to verify signature.".to_string(),
+            ))
         }
     }
 }
@@ -223,14 +242,14 @@ impl Eq for Signature {}
 
 impl PartialEq for Signature {
     fn eq(&self, other: &Signature) -> bool {
-        self.to_bytes()[..] == other.to_bytes()[..]
+        constant_time::ct_eq(&self.to_bytes(), &other.to_bytes())
     }
 }
 
 impl TryFrom<&[u8]> for Signature {
     type Error = CryptoMaterialError;
 
-    fn try_from(bytes: &[u8]) -> std::result::Result<Signature, CryptoMaterialError> {
+    fn try_from(bytes: &[u8]) -> Result<Signature, CryptoMaterialError> {
         match libsecp256k1::Signature::parse_standard_slice(bytes) {
             Ok(signature) => Ok(Signature(signature)),
             Err(_) => Err(CryptoMaterialError::DeserializationError),
@@ -260,12 +279,22 @@ impl traits::Signature for Signature {
     type SigningKeyMaterial = PrivateKey;
     type VerifyingKeyMaterial = PublicKey;
 
-    fn verify<T: CryptoHash + Serialize>(&self, message: &T, public_key: &PublicKey) -> Result<()> {
-        let message = bytes_to_message(&traits::signing_message(message)?)?;
+    fn verify<T: CryptoHash + Serialize>(
+        &self,
+        message: &T,
+        public_key: &PublicKey,
+    ) -> Result<(), CryptoError> {
+        let message = traits::signing_message(message)
+            .map_err(|e| CryptoError::MalformedMessage(e.to_string()))?;
+        let message = bytes_to_message(&message)?;
         self.verify(&message, &public_key.0)
     }
 
-    fn verify_arbitrary_msg(&self, message: &[u8], public_key: &PublicKey) -> Result<()> {
+    fn verify_arbitrary_msg(
+        &self,
+        message: &[u8],
+        public_key: &PublicKey,
+    ) -> Result<(), CryptoError> {
         let message = bytes_to_message(message)?;
         self.verify(&message, &public_key.0)
     }
@@ -287,7 +316,8 @@ impl ValidCryptoMaterial for Signature {
     }
 }
 
-fn bytes_to_message(message: &[u8]) -> Result<libsecp256k1::Message> {
+fn bytes_to_message(message: &[u8]) -> Result<libsecp256k1::Message, CryptoError> {
     let message_digest = HashValue::sha3_256_of(message).to_vec();
-    libsecp256k1::Message::parse_slice(&message_digest).map_err(|e| anyhow!("{}", e))
+    libsecp256k1::Message::parse_slice(&message_digest)
+        .map_err(|e| CryptoError::MalformedMessage(e.to_string()))
 }
@@ -6,6 +6,7 @@
 #[cfg(any(test, feature = "fuzzing"))]
 use crate::test_utils::{self, KeyPair};
 use crate::{
+    constant_time::{self, ConstantTimeEq},
     hash::CryptoHash,
     secp256r1_ecdsa::{Signature, ORDER, PRIVATE_KEY_LENGTH, PUBLIC_KEY_LENGTH},
     traits::{PrivateKey as PrivateKeyTrait, PublicKey as PublicKeyTrait, *},
@@ -145,9 +146,15 @@ impl Uniform for PrivateKey {
     }
 }
 
+impl ConstantTimeEq for PrivateKey {
+    fn ct_eq(&self, other: &Self) -> bool {
+        constant_time::ct_eq(&self.to_bytes(), &other.to_bytes())
+    }
+}
+
 impl PartialEq<Self> for PrivateKey {
     fn eq(&self, other: &Self) -> bool {
-        self.to_bytes() == other.to_bytes()
+        constant_time::secret_eq(self, other)
     }
 }
 
@@ -5,11 +5,12 @@
 
 use super::SIGNATURE_LENGTH;
 use crate::{
+    constant_time,
+    error::CryptoError,
     hash::CryptoHash,
     secp256r1_ecdsa::{PrivateKey, PublicKey, ORDER_HALF},
     traits::{Signature as SignatureTrait, *},
 };
-use anyhow::{anyhow, Result};
 use aptos_crypto_derive::{key_name, DeserializeKey, SerializeKey};
 use core::convert::TryFrom;
 use p256::NonZeroScalar;
@@ -36,9 +37,7 @@ impl Signature {
     /// Deserialize an P256Signature, without checking for malleability
     /// Uses the SEC1 serialization format.
     #[cfg(not(feature = "fuzzing"))]
-    pub(crate) fn from_bytes_unchecked(
-        bytes: &[u8],
-    ) -> std::result::Result<Signature, CryptoMaterialError> {
+    pub(crate) fn from_bytes_unchecked(bytes: &[u8]) -> Result<Signature, CryptoMaterialError> {
         match p256::ecdsa::Signature::try_from(bytes) {
             Ok(p256_signature) => Ok(Signature(p256_signature)),
             Err(_) => Err(CryptoMaterialError::DeserializationError),
@@ -48,9 +47,7 @@ impl Signature {
     /// Deserialize an P256Signature, without checking for malleability
     /// Uses the SEC1 serialization format.
     #[cfg(any(test, feature = "fuzzing"))]
-    pub fn from_bytes_unchecked(
-        bytes: &[u8],
-    ) -> std::result::Result<Signature, CryptoMaterialError> {
+    pub fn from_bytes_unchecked(bytes: &[u8]) -> Result<Signature, CryptoMaterialError> {
         match p256::ecdsa::Signature::try_from(bytes) {
             Ok(p256_signature) => Ok(Signature(p256_signature)),
             Err(_) => Err(CryptoMaterialError::DeserializationError),
@@ -74,7 +71,7 @@ impl Signature {
     /// divided by 2. If this is not done, a value S > n/2 can be replaced by S' = n - S to form another distinct valid
     /// signature, where n is the curve order. This check is not performed by the RustCrypto P256 library
     /// we use
-    pub fn check_s_malleability(bytes: &[u8]) -> std::result::Result<(), CryptoMaterialError> {
+    pub fn check_s_malleability(bytes: &[u8]) -> Result<(), CryptoMaterialError> {
         if bytes.len() != SIGNATURE_LENGTH {
             return Err(CryptoMaterialError::WrongLengthError);
         }
@@ -133,8 +130,14 @@ impl SignatureTrait for Signature {
 
     /// Verifies that the provided signature is valid for the provided message, going beyond the
     /// [NIST SP 800-186](https://csrc.nist.gov/publications/detail/sp/800-186/final) specification, to prevent scalar malleability as done in [BIP146](https://github.com/bitcoin/bips/blob/master/bip-0146.mediawiki).
-    fn verify<T: CryptoHash + Serialize>(&self, message: &T, public_key: &PublicKey) -> Result<()> {
-        Self::verify_arbitrary_msg(self, &signing_message(message)?, public_key)
+    fn verify<T: CryptoHash + Serialize>(
+        &self,
+        message: &T,
+        public_key: &PublicKey,
+    ) -> Result<(), CryptoError> {
+        let message =
+            signing_message(message).map_err(|e| CryptoError::MalformedMessage(e.to_string()))?;
+        Self::verify_arbitrary_msg(self, &message, public_key)
     }
 
     /// Checks that `self` is valid for an arbitrary &[u8] `message` using `public_key`.
@@ -143,14 +146,19 @@ impl SignatureTrait for Signature {
     ///
     /// Checks for and rejects non-canonical signatures (r,s) where s > (n/2), where n is the group
     /// order
-    fn verify_arbitrary_msg(&self, message: &[u8], public_key: &PublicKey) -> Result<()> {
-        Signature::check_s_malleability(&self.to_bytes())?;
+    fn verify_arbitrary_msg(
+        &self,
+        message: &[u8],
+        public_key: &PublicKey,
+    ) -> Result<(), CryptoError> {
+        Signature::check_s_malleability(&self.to_bytes())
+            .map_err(CryptoError::MalformedSignature)?;
 
         public_key
             .0
             .verify(message, &self.0)
-            .map_err(|e| anyhow!("{}", e))
-            .and(Ok(()))
+            .map_err(|e| CryptoError::InvalidSignature(e.to_string()))?;
+        Ok(())
     }
 
     fn to_bytes(&self) -> Vec<u8> {
@@ -180,7 +188,7 @@ impl std::hash::Hash for Signature {
 impl TryFrom<&[u8]> for Signature {
     type Error = CryptoMaterialError;
 
-    fn try_from(bytes: &[u8]) -> std::result::Result<Signature, CryptoMaterialError> {
+    fn try_from(bytes: &[u8]) -> Result<Signature, CryptoMaterialError> {
         Signature::check_s_malleability(bytes)?;
         Signature::from_bytes_unchecked(bytes)
     }
@@ -189,7 +197,7 @@ impl TryFrom<&[u8]> for Signature {
 // Those are required by the implementation of hash above
 impl PartialEq for Signature {
     fn eq(&self, other: &Signature) -> bool {
-        self.to_bytes()[..] == other.to_bytes()[..]
+        constant_time::ct_eq(&self.to_bytes(), &other.to_bytes())
     }
 }
 
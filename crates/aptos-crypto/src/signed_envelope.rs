@@ -0,0 +1,174 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! A message along with its signature, signed with domain separation.
+//!
+//! Messages are signed over the salt of their `CryptoHasher` followed by their BCS serialization
+//! (see [`signing_message`]), so that a signature on a message of one type cannot be replayed as a
+//! signature on a message of another type. The salt is derived from the serde name of the type,
+//! which is not necessarily unique: two types named alike in different modules share it.
+//!
+//! [`SignedEnvelope`] closes that gap: only message types implementing [`SigningDomain`] can be
+//! signed or verified through an envelope, and each crate declares its signing domains with
+//! [`signing_domains!`], which generates a test checking that no two of them share the same salt.
+//!
+//! ```
+//! use aptos_crypto::{
+//!     ed25519::{Ed25519PrivateKey, Ed25519Signature},
+//!     signed_envelope::SignedEnvelope,
+//!     test_utils::TestAptosCrypto,
+//!     PrivateKey, Uniform,
+//! };
+//!
+//! let private_key = Ed25519PrivateKey::generate(&mut rand_core::OsRng);
+//! let message = TestAptosCrypto("Hello, World".to_string());
+//! let envelope: SignedEnvelope<_, Ed25519Signature> =
+//!     SignedEnvelope::sign(message, &private_key).unwrap();
+//! envelope.verify(&private_key.public_key()).unwrap();
+//! ```
+
+use crate::{
+    error::CryptoError,
+    hash::{CryptoHash, CryptoHasher},
+    traits::{signing_message, Signature, SigningKey},
+    CryptoMaterialError,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// A message type which can be signed through a [`SignedEnvelope`]. Implement it with
+/// [`signing_domains!`], so that the salt of the type is checked against the other signing domains.
+pub trait SigningDomain: CryptoHash + Serialize {}
+
+/// Returns the name of the signing domain `T` along with the salt its messages are signed with.
+pub fn signing_domain<T: SigningDomain>() -> (&'static str, [u8; 32]) {
+    (
+        std::any::type_name::<T>(),
+        *<T::Hasher as CryptoHasher>::seed(),
+    )
+}
+
+/// Checks that no two of `domains`, as returned by [`signing_domain`], share the same salt.
+pub fn check_signing_domains(domains: &[(&'static str, [u8; 32])]) -> Result<(), CryptoError> {
+    let mut domains_by_salt = HashMap::new();
+    for (type_name, salt) in domains {
+        if let Some(registered) = domains_by_salt.insert(salt, type_name) {
+            return Err(CryptoError::DomainCollision(format!(
+                "{} has the same salt as {}",
+                type_name, registered
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Implements [`SigningDomain`] for the given message types, and generates a test checking that
+/// their salts are all distinct. Each crate should declare all its signing domains in a single
+/// invocation, so that they are checked against each other.
+#[macro_export]
+macro_rules! signing_domains {
+    ($($domain:ty),+ $(,)?) => {
+        $(impl $crate::signed_envelope::SigningDomain for $domain {})+
+
+        #[cfg(test)]
+        #[test]
+        fn signing_domains_are_distinct() {
+            $crate::signed_envelope::check_signing_domains(&[
+                $($crate::signed_envelope::signing_domain::<$domain>()),+
+            ])
+            .unwrap();
+        }
+    };
+}
+
+/// Verifies `signature` on `message` against `public_key`, the same way as
+/// [`SignedEnvelope::verify`], for callers holding the message and the signature separately.
+pub fn verify_signed<T: SigningDomain, S: EnvelopeSignature>(
+    message: &T,
+    signature: &S,
+    public_key: &S::PublicKey,
+) -> Result<(), CryptoError> {
+    signature.verify_message(message, public_key)
+}
+
+/// A signature which can be verified on a message of an envelope.
+///
+/// It is implemented for all the signature schemes of this crate, and can be implemented for
+/// signatures over several schemes, e.g., enums of signatures.
+pub trait EnvelopeSignature {
+    /// The public key which verifies the signature.
+    type PublicKey;
+
+    /// Verifies the signature on `message` against `public_key`.
+    fn verify_message<T: CryptoHash + Serialize>(
+        &self,
+        message: &T,
+        public_key: &Self::PublicKey,
+    ) -> Result<(), CryptoError>;
+}
+
+impl<S: Signature> EnvelopeSignature for S {
+    type PublicKey = S::VerifyingKeyMaterial;
+
+    fn verify_message<T: CryptoHash + Serialize>(
+        &self,
+        message: &T,
+        public_key: &Self::PublicKey,
+    ) -> Result<(), CryptoError> {
+        self.verify(message, public_key)
+    }
+}
+
+/// A message of type `T` along with a signature of type `S` on it.
+///
+/// The envelope serializes as the `(message, signature)` pair. It is not verified when
+/// deserialized, see [`SignedEnvelope::verify`].
+#[derive(Clone, Debug, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct SignedEnvelope<T, S> {
+    message: T,
+    signature: S,
+}
+
+impl<T: SigningDomain, S> SignedEnvelope<T, S> {
+    /// Wraps a message along with its signature, e.g., received separately. The signature is not
+    /// verified.
+    pub fn new(message: T, signature: S) -> Self {
+        Self { message, signature }
+    }
+
+    /// Signs `message` with `signing_key`.
+    pub fn sign<K: SigningKey<SignatureMaterial = S>>(
+        message: T,
+        signing_key: &K,
+    ) -> Result<Self, CryptoMaterialError> {
+        let signature = signing_key.sign(&message)?;
+        Ok(Self { message, signature })
+    }
+
+    /// Returns the bytes the signature is computed on.
+    pub fn signing_message(&self) -> Result<Vec<u8>, CryptoMaterialError> {
+        signing_message(&self.message)
+    }
+
+    /// Returns the message.
+    pub fn message(&self) -> &T {
+        &self.message
+    }
+
+    /// Returns the signature.
+    pub fn signature(&self) -> &S {
+        &self.signature
+    }
+
+    /// Returns the message and the signature.
+    pub fn into_parts(self) -> (T, S) {
+        (self.message, self.signature)
+    }
+}
+
+impl<T: SigningDomain, S: EnvelopeSignature> SignedEnvelope<T, S> {
+    /// Verifies the signature on the message against `public_key`.
+    pub fn verify(&self, public_key: &S::PublicKey) -> Result<(), CryptoError> {
+        verify_signed(&self.message, &self.signature, public_key)
+    }
+}
@@ -227,6 +227,8 @@ where
 #[derive(Debug, Serialize, Deserialize)]
 pub struct TestAptosCrypto(pub String);
 
+crate::signing_domains!(TestAptosCrypto);
+
 // the following block is macro expanded from derive(CryptoHasher, BCSCryptoHash)
 
 /// Cryptographic hasher for an BCS-serializable #item
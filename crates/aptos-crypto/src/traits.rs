@@ -6,8 +6,10 @@
 //!
 //! For examples on how to use these traits, see the implementations of the [`crate::ed25519`]
 
-use crate::hash::{CryptoHash, CryptoHasher};
-use anyhow::Result;
+use crate::{
+    error::CryptoError,
+    hash::{CryptoHash, CryptoHasher},
+};
 use core::convert::{From, TryFrom};
 use rand::{rngs::StdRng, CryptoRng, RngCore, SeedableRng};
 use serde::{de::DeserializeOwned, Serialize};
@@ -73,7 +75,7 @@ pub trait ValidCryptoMaterial:
 pub trait ValidCryptoMaterialStringExt: ValidCryptoMaterial {
     /// When trying to convert from bytes, we simply decode the string into
     /// bytes before checking if we can convert.
-    fn from_encoded_string(encoded_str: &str) -> std::result::Result<Self, CryptoMaterialError> {
+    fn from_encoded_string(encoded_str: &str) -> Result<Self, CryptoMaterialError> {
         // Strip 0x at beginning if there is one
         let encoded_str = encoded_str.strip_prefix("0x").unwrap_or(encoded_str);
 
@@ -86,7 +88,7 @@ pub trait ValidCryptoMaterialStringExt: ValidCryptoMaterial {
     }
 
     /// A function to encode into hex-string after serializing.
-    fn to_encoded_string(&self) -> Result<String> {
+    fn to_encoded_string(&self) -> Result<String, CryptoError> {
         Ok(format!("0x{}", ::hex::encode(self.to_bytes())))
     }
 }
@@ -201,7 +203,7 @@ pub trait VerifyingKey:
         &self,
         message: &T,
         signature: &Self::SignatureMaterial,
-    ) -> Result<()> {
+    ) -> Result<(), CryptoError> {
         signature.verify(message, self)
     }
 
@@ -209,7 +211,7 @@ pub trait VerifyingKey:
     fn batch_verify<T: CryptoHash + Serialize>(
         message: &T,
         keys_and_signatures: Vec<(Self, Self::SignatureMaterial)>,
-    ) -> Result<()> {
+    ) -> Result<(), CryptoError> {
         Self::SignatureMaterial::batch_verify(message, keys_and_signatures)
     }
 }
@@ -249,14 +251,14 @@ pub trait Signature:
         &self,
         message: &T,
         public_key: &Self::VerifyingKeyMaterial,
-    ) -> Result<()>;
+    ) -> Result<(), CryptoError>;
 
     /// Native verification function.
     fn verify_arbitrary_msg(
         &self,
         message: &[u8],
         public_key: &Self::VerifyingKeyMaterial,
-    ) -> Result<()>;
+    ) -> Result<(), CryptoError>;
 
     /// Convert the signature into a byte representation.
     fn to_bytes(&self) -> Vec<u8>;
@@ -267,7 +269,7 @@ pub trait Signature:
     fn batch_verify<T: CryptoHash + Serialize>(
         message: &T,
         keys_and_signatures: Vec<(Self::VerifyingKeyMaterial, Self)>,
-    ) -> Result<()> {
+    ) -> Result<(), CryptoError> {
         for (key, signature) in keys_and_signatures {
             signature.verify(message, &key)?
         }
@@ -0,0 +1,142 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    bls12381::{AggregatePublicKeyCache, PrivateKey, PublicKey, Signature},
+    error::{CryptoError, CryptoErrorCategory},
+    test_utils::{KeyPair, TestAptosCrypto},
+    SigningKey, Uniform,
+};
+use rand_core::OsRng;
+use std::convert::TryFrom;
+
+fn keygen(n: usize) -> Vec<KeyPair<PrivateKey, PublicKey>> {
+    let mut rng = OsRng;
+    (0..n)
+        .map(|_| KeyPair::<PrivateKey, PublicKey>::generate(&mut rng))
+        .collect()
+}
+
+/// Returns the bitmap of the signers, in the layout of `aptos_bitvec::BitVec`
+fn bitmap(n: usize, signers: &[usize]) -> Vec<u8> {
+    let mut bitmap = vec![0u8; (n + 7) / 8];
+    for i in signers {
+        bitmap[i / 8] |= 0x80 >> (i % 8);
+    }
+    bitmap
+}
+
+fn multisig(
+    key_pairs: &[KeyPair<PrivateKey, PublicKey>],
+    signers: &[usize],
+    message: &TestAptosCrypto,
+) -> Signature {
+    let sigshares = signers
+        .iter()
+        .map(|i| key_pairs[*i].private_key.sign(message).unwrap())
+        .collect();
+    Signature::aggregate(sigshares).unwrap()
+}
+
+fn cache(epoch: u64, key_pairs: &[KeyPair<PrivateKey, PublicKey>]) -> AggregatePublicKeyCache {
+    let pubkeys = key_pairs.iter().map(|kp| kp.public_key.clone()).collect();
+    AggregatePublicKeyCache::new(epoch, pubkeys).unwrap()
+}
+
+#[test]
+fn test_cached_multisig_verify() {
+    let n = 10;
+    let key_pairs = keygen(n);
+    let cache = cache(3, &key_pairs);
+    assert_eq!(cache.epoch(), 3);
+    assert_eq!(cache.len(), n);
+
+    let message = TestAptosCrypto("Hello world".to_string());
+    let message_wrong = TestAptosCrypto("Wello Horld".to_string());
+    let signers = [0, 2, 3, 8, 9];
+    let bitmap = bitmap(n, &signers);
+    let multisig = multisig(&key_pairs, &signers, &message);
+
+    // Verifies both when aggregating and when hitting the cache
+    for _ in 0..2 {
+        assert!(cache.verify_multisig(&message, &bitmap, &multisig).is_ok());
+        assert!(cache
+            .verify_multisig(&message_wrong, &bitmap, &multisig)
+            .is_err());
+    }
+
+    // The cached aggregate matches the uncached one
+    let pubkeys = signers.iter().map(|i| &key_pairs[*i].public_key).collect();
+    assert_eq!(
+        cache.aggregate(&bitmap).unwrap(),
+        PublicKey::aggregate(pubkeys).unwrap()
+    );
+
+    // Does not verify for another subset of signers
+    let other_bitmap = self::bitmap(n, &[0, 2, 3, 8]);
+    assert!(cache
+        .verify_multisig(&message, &other_bitmap, &multisig)
+        .is_err());
+}
+
+#[test]
+fn test_cache_eviction() {
+    let n = 4;
+    let key_pairs = keygen(n);
+    let pubkeys: Vec<_> = key_pairs.iter().map(|kp| kp.public_key.clone()).collect();
+    let message = TestAptosCrypto("Hello world".to_string());
+
+    for capacity in [0, 1, 2] {
+        let cache = AggregatePublicKeyCache::with_capacity(0, pubkeys.clone(), capacity).unwrap();
+        for signers in [[0, 1], [1, 2], [2, 3], [0, 1]] {
+            let multisig = multisig(&key_pairs, &signers, &message);
+            assert!(cache
+                .verify_multisig(&message, &bitmap(n, &signers), &multisig)
+                .is_ok());
+        }
+    }
+}
+
+#[test]
+fn test_invalid_bitmap() {
+    let n = 10;
+    let key_pairs = keygen(n);
+    let cache = cache(0, &key_pairs);
+
+    for bitmap in [
+        // Wrong length
+        vec![0xFF],
+        vec![0xFF, 0xC0, 0x00],
+        // Signer index 10 is out of bounds
+        vec![0x00, 0x20],
+        // No signers
+        vec![0x00, 0x00],
+    ] {
+        let error = cache.aggregate(&bitmap).unwrap_err();
+        assert!(matches!(error, CryptoError::InvalidBitmap(_)));
+        assert_eq!(error.category(), CryptoErrorCategory::MalformedSignature);
+    }
+
+    // All signers
+    assert!(cache.aggregate(&[0xFF, 0xC0]).is_ok());
+}
+
+#[test]
+fn test_empty_validator_set() {
+    let cache = AggregatePublicKeyCache::new(0, vec![]).unwrap();
+    assert!(cache.is_empty());
+    assert!(cache.aggregate(&[]).is_err());
+}
+
+#[test]
+fn test_public_keys_are_checked() {
+    let mut rng = OsRng;
+    let pubkey = PublicKey::from(&PrivateKey::generate(&mut rng));
+
+    // A low-order point, see `bls12381_validatable_pk`
+    let low_order_point = hex::decode("ae3cd9403b69c20a0d455fd860e977fe6ee7140a7f091f26c860f2caccd3e0a7a7365798ac10df776675b3a67db8faa0").unwrap();
+    let low_order_pubkey = PublicKey::try_from(low_order_point.as_slice()).unwrap();
+
+    let error = AggregatePublicKeyCache::new(0, vec![pubkey, low_order_pubkey]).unwrap_err();
+    assert_eq!(error.category(), CryptoErrorCategory::MalformedKey);
+}
@@ -0,0 +1,74 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    bls12381,
+    constant_time::{ct_eq, ConstantTimeEq},
+    ed25519::Ed25519PrivateKey,
+    multi_ed25519::MultiEd25519PrivateKey,
+    secp256k1_ecdsa, secp256r1_ecdsa, x25519, SigningKey, Uniform, ValidCryptoMaterial,
+};
+use rand::{rngs::StdRng, SeedableRng};
+use std::convert::TryFrom;
+
+#[test]
+fn test_ct_eq() {
+    assert!(ct_eq(&[], &[]));
+    assert!(ct_eq(&[1, 2, 3], &[1, 2, 3]));
+    assert!(!ct_eq(&[1, 2, 3], &[1, 2, 4]));
+    assert!(!ct_eq(&[1, 2, 3], &[1, 2]));
+}
+
+fn assert_ct_eq<T: ConstantTimeEq + Uniform>(deserialize: impl Fn(&T) -> T) {
+    let mut rng = StdRng::from_seed([0u8; 32]);
+    let key = T::generate(&mut rng);
+    let other_key = T::generate(&mut rng);
+    assert!(key.ct_eq(&deserialize(&key)));
+    assert!(!key.ct_eq(&other_key));
+}
+
+#[test]
+fn test_private_keys_ct_eq() {
+    assert_ct_eq(|key: &Ed25519PrivateKey| {
+        Ed25519PrivateKey::try_from(&key.to_bytes()[..]).unwrap()
+    });
+    assert_ct_eq(|key: &MultiEd25519PrivateKey| {
+        MultiEd25519PrivateKey::try_from(&key.to_bytes()[..]).unwrap()
+    });
+    assert_ct_eq(|key: &bls12381::PrivateKey| {
+        bls12381::PrivateKey::try_from(&key.to_bytes()[..]).unwrap()
+    });
+    assert_ct_eq(|key: &secp256k1_ecdsa::PrivateKey| {
+        secp256k1_ecdsa::PrivateKey::try_from(&key.to_bytes()[..]).unwrap()
+    });
+    assert_ct_eq(|key: &secp256r1_ecdsa::PrivateKey| {
+        secp256r1_ecdsa::PrivateKey::try_from(&key.to_bytes()[..]).unwrap()
+    });
+    assert_ct_eq(|key: &x25519::PrivateKey| {
+        x25519::PrivateKey::try_from(&key.to_bytes()[..]).unwrap()
+    });
+}
+
+#[test]
+fn test_signatures_eq() {
+    let mut rng = StdRng::from_seed([0u8; 32]);
+    let private_key = Ed25519PrivateKey::generate(&mut rng);
+    let signature = private_key.sign_arbitrary_message(b"message");
+    assert_eq!(signature, private_key.sign_arbitrary_message(b"message"));
+    assert_ne!(
+        signature,
+        private_key.sign_arbitrary_message(b"other message")
+    );
+}
+
+#[test]
+#[cfg_attr(
+    all(debug_assertions, feature = "reject-secret-eq"),
+    should_panic(expected = "should not be compared with `==`")
+)]
+fn test_private_keys_eq() {
+    let mut rng = StdRng::from_seed([0u8; 32]);
+    let private_key = Ed25519PrivateKey::generate(&mut rng);
+    let same_private_key = Ed25519PrivateKey::try_from(&private_key.to_bytes()[..]).unwrap();
+    assert!(private_key == same_private_key);
+}
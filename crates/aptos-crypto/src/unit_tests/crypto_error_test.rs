@@ -0,0 +1,97 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    bls12381,
+    ed25519::{Ed25519PrivateKey, Ed25519PublicKey},
+    error::{CryptoError, CryptoErrorCategory},
+    multi_ed25519::MultiEd25519PrivateKey,
+    secp256k1_ecdsa,
+    test_utils::KeyPair,
+    CryptoMaterialError, PrivateKey, Signature, SigningKey, Uniform,
+};
+use rand_core::OsRng;
+
+const MESSAGE: &[u8] = b"Hello world";
+const MESSAGE_WRONG: &[u8] = b"Wello Horld";
+
+#[test]
+fn test_category() {
+    assert_eq!(
+        CryptoError::MalformedKey(CryptoMaterialError::SmallSubgroupError).category(),
+        CryptoErrorCategory::MalformedKey
+    );
+    assert_eq!(
+        CryptoError::MalformedSignature(CryptoMaterialError::WrongLengthError).category(),
+        CryptoErrorCategory::WrongLength
+    );
+    assert_eq!(
+        CryptoError::InvalidBitmap("Not enough signatures".to_string()).category(),
+        CryptoErrorCategory::MalformedSignature
+    );
+}
+
+#[test]
+fn test_anyhow_compatibility() {
+    let error: anyhow::Error = CryptoError::InvalidSignature("BLST_VERIFY_FAIL".to_string()).into();
+    assert_eq!(error.to_string(), "Invalid signature: BLST_VERIFY_FAIL");
+    assert_eq!(
+        error.downcast_ref::<CryptoError>(),
+        Some(&CryptoError::InvalidSignature(
+            "BLST_VERIFY_FAIL".to_string()
+        ))
+    );
+}
+
+/// Tests that a signature on a different message fails verification with an `InvalidSignature`
+/// error for each signature scheme.
+#[test]
+fn test_invalid_signature() {
+    let mut rng = OsRng;
+
+    let key_pair = KeyPair::<Ed25519PrivateKey, Ed25519PublicKey>::generate(&mut rng);
+    let error = key_pair
+        .private_key
+        .sign_arbitrary_message(MESSAGE)
+        .verify_arbitrary_msg(MESSAGE_WRONG, &key_pair.public_key)
+        .unwrap_err();
+    assert_eq!(error.category(), CryptoErrorCategory::InvalidSignature);
+
+    let key_pair = KeyPair::<bls12381::PrivateKey, bls12381::PublicKey>::generate(&mut rng);
+    let error = key_pair
+        .private_key
+        .sign_arbitrary_message(MESSAGE)
+        .verify_arbitrary_msg(MESSAGE_WRONG, &key_pair.public_key)
+        .unwrap_err();
+    assert_eq!(error.category(), CryptoErrorCategory::InvalidSignature);
+
+    let key_pair =
+        KeyPair::<secp256k1_ecdsa::PrivateKey, secp256k1_ecdsa::PublicKey>::generate(&mut rng);
+    let error = key_pair
+        .private_key
+        .sign_arbitrary_message(MESSAGE)
+        .verify_arbitrary_msg(MESSAGE_WRONG, &key_pair.public_key)
+        .unwrap_err();
+    assert_eq!(error.category(), CryptoErrorCategory::InvalidSignature);
+}
+
+/// Tests that a multi-signature with too few signatures fails verification with an
+/// `InvalidBitmap` error.
+#[test]
+fn test_invalid_bitmap() {
+    let mut rng = OsRng;
+    let private_keys: Vec<_> = (0..3)
+        .map(|_| Ed25519PrivateKey::generate(&mut rng))
+        .collect();
+    let multi_private_key_1of3 = MultiEd25519PrivateKey::new(private_keys.clone(), 1).unwrap();
+    let multi_private_key_2of3 = MultiEd25519PrivateKey::new(private_keys, 2).unwrap();
+
+    let error = multi_private_key_1of3
+        .sign_arbitrary_message(MESSAGE)
+        .verify_arbitrary_msg(MESSAGE, &multi_private_key_2of3.public_key())
+        .unwrap_err();
+    assert_eq!(
+        error,
+        CryptoError::InvalidBitmap("Not enough signatures to meet the threshold".to_string())
+    );
+}
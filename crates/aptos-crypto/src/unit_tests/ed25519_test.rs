@@ -325,6 +325,27 @@ proptest! {
         prop_assert!(Ed25519Signature::batch_verify(&message, signatures).is_err());
     }
 
+    #[test]
+    fn test_batch_verify_distinct_messages(
+        messages in vec(any::<Vec<u8>>(), 10),
+        keypairs in proptest::array::uniform10(uniform_keypair_strategy::<Ed25519PrivateKey, Ed25519PublicKey>())
+    ) {
+        let signatures: Vec<Ed25519Signature> = keypairs.iter().zip(messages.iter()).map(|(keypair, message)| {
+            keypair.private_key.sign_arbitrary_message(message)
+        }).collect();
+        let mut batch: Vec<(&[u8], &Ed25519PublicKey, &Ed25519Signature)> = messages
+            .iter()
+            .zip(keypairs.iter())
+            .zip(signatures.iter())
+            .map(|((message, keypair), signature)| (message.as_slice(), &keypair.public_key, signature))
+            .collect();
+        prop_assert!(Ed25519Signature::batch_verify_distinct_messages(&batch).is_ok());
+        // We swap the signatures of the first two elements, resulting in incorrect signatures
+        batch[0].2 = &signatures[1];
+        batch[1].2 = &signatures[0];
+        prop_assert!(Ed25519Signature::batch_verify_distinct_messages(&batch).is_err());
+    }
+
     #[test]
     fn test_keys_custom_serialisation(
         keypair in uniform_keypair_strategy::<Ed25519PrivateKey, Ed25519PublicKey>()
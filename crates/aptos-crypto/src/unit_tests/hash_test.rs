@@ -194,6 +194,38 @@ fn test_common_prefix_bits_len() {
     }
 }
 
+#[test]
+fn test_streaming_hash() {
+    let payload: Vec<u8> = (0..100_000u32).map(|i| i as u8).collect();
+
+    // Hashing a reader is the same as hashing the whole buffer
+    assert_eq!(
+        HashValue::sha3_256_of_reader(payload.as_slice()).unwrap(),
+        HashValue::sha3_256_of(&payload),
+    );
+    assert_eq!(
+        TestOnlyHasher::hash_reader(payload.as_slice()).unwrap(),
+        TestOnlyHasher::hash_all(&payload),
+    );
+
+    // Feeding the hasher chunk by chunk is the same as hashing the whole buffer
+    let mut hasher = Sha3Hasher::new();
+    for chunk in payload.chunks(4096) {
+        hasher.update(chunk);
+    }
+    assert_eq!(hasher.finish(), HashValue::sha3_256_of(&payload));
+    assert_eq!(Sha3Hasher::new().finish(), HashValue::sha3_256_of(&[]));
+
+    // Serializing into the hasher is the same as hashing the serialized bytes
+    let value = (Foo(3), payload);
+    let mut hasher = TestOnlyHasher::default();
+    hasher.update_bcs(&value).unwrap();
+    assert_eq!(
+        hasher.finish(),
+        TestOnlyHasher::hash_all(&bcs::to_bytes(&value).unwrap()),
+    );
+}
+
 proptest! {
     #[test]
     fn test_hashvalue_to_bits_roundtrip(hash in any::<HashValue>()) {
@@ -0,0 +1,147 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    hd_wallet::{
+        aptos_derivation_path, derive_from_mnemonic, DerivationPath, ExtendedPrivateKey,
+        HdWalletError,
+    },
+    HashValue, PrivateKey, ValidCryptoMaterial,
+};
+use std::str::FromStr;
+
+/// Test vector 1 for ed25519 of SLIP-0010, as (path, chain code, private key)
+const SLIP_0010_VECTOR_1: &[(&str, &str, &str)] = &[
+    (
+        "m",
+        "90046a93de5380a72b5e45010748567d5ea02bbf6522f979e05c0d8d8ca9fffb",
+        "2b4be7f19ee27bbf30c667b642d5f4aa69fd169872f8fc3059c08ebae2eb19e7",
+    ),
+    (
+        "m/0'",
+        "8b59aa11380b624e81507a27fedda59fea6d0b779a778918a2fd3590e16e9c69",
+        "68e0fe46dfb67e368c75379acec591dad19df3cde26e63b93a8e704f1dade7a3",
+    ),
+    (
+        "m/0'/1'",
+        "a320425f77d1b5c2505a6b1b27382b37368ee640e3557c315416801243552f14",
+        "b1d0bad404bf35da785a64ca1ac54b2617211d2777696fbffaf208f746ae84f2",
+    ),
+    (
+        "m/0'/1'/2'",
+        "2e69929e00b5ab250f49c3fb1c12f252de4fed2c1db88387094a0f8c4c9ccd6c",
+        "92a5b23c0b8a99e37d07df3fb9966917f5d06e02ddbd909c7e184371463e9fc9",
+    ),
+    (
+        "m/0'/1'/2'/2'",
+        "8f6d87f93d750e0efccda017d662a1b31a266e4a6f5993b15f5c1f07f74dd5cc",
+        "30d1dc7e5fc04c31219ab25a27ae00b50f6fd66622f6e9c913253d6511d1e662",
+    ),
+    (
+        "m/0'/1'/2'/2'/1000000000'",
+        "68789923a0cac2cd5a29172a475fe9e0fb14cd6adb5ad98a3fa70333e7afa230",
+        "8f94d394a8e8fd6b1bc2f3f49f5c47e385281d5c17e65324b0f62483e37e8793",
+    ),
+];
+
+#[test]
+fn test_slip_0010_vector() {
+    let seed = hex::decode("000102030405060708090a0b0c0d0e0f").unwrap();
+    let master = ExtendedPrivateKey::from_seed(&seed);
+
+    for (path, chain_code, private_key) in SLIP_0010_VECTOR_1 {
+        let path = DerivationPath::from_str(path).unwrap();
+        let key = master.derive(&path).unwrap();
+        assert_eq!(key.depth() as usize, path.indices().len());
+        assert_eq!(hex::encode(key.chain_code()), *chain_code);
+        assert_eq!(hex::encode(key.private_key().to_bytes()), *private_key);
+    }
+}
+
+/// Same mnemonic and account as in the tests of the TypeScript and Rust SDKs
+#[test]
+fn test_aptos_account_from_mnemonic() {
+    let mnemonic =
+        "shoot island position soft burden budget tooth cruel issue economy destroy above";
+    let path = aptos_derivation_path(0);
+    assert_eq!(path.to_string(), "m/44'/637'/0'/0'/0'");
+
+    let private_key = derive_from_mnemonic(mnemonic, "", &path).unwrap();
+    assert_eq!(
+        hex::encode(private_key.to_bytes()),
+        "5d996aa76b3212142792d9130796cd2e11e3c445a93118c08414df4f66bc60ec"
+    );
+
+    // The authentication key of a single Ed25519 key is sha3-256(public key | 0x00)
+    let mut bytes = private_key.public_key().to_bytes().to_vec();
+    bytes.push(0);
+    assert_eq!(
+        HashValue::sha3_256_of(&bytes).to_hex(),
+        "07968dab936c1bad187c60ce4082f307d030d780e91e694ae03aef16aba73f30"
+    );
+
+    // A passphrase and another account derive other keys
+    assert_ne!(
+        derive_from_mnemonic(mnemonic, "passphrase", &path)
+            .unwrap()
+            .to_bytes(),
+        private_key.to_bytes()
+    );
+    assert_ne!(
+        derive_from_mnemonic(mnemonic, "", &aptos_derivation_path(1))
+            .unwrap()
+            .to_bytes(),
+        private_key.to_bytes()
+    );
+}
+
+#[test]
+fn test_invalid_mnemonic() {
+    let path = aptos_derivation_path(0);
+    for mnemonic in [
+        "",
+        // Bad checksum
+        "shoot island position soft burden budget tooth cruel issue economy destroy shoot",
+        // Not in the wordlist
+        "shoot island position soft burden budget tooth cruel issue economy destroy aptos",
+    ] {
+        assert!(matches!(
+            derive_from_mnemonic(mnemonic, "", &path),
+            Err(HdWalletError::InvalidMnemonic(_))
+        ));
+    }
+}
+
+#[test]
+fn test_derivation_path() {
+    let path = DerivationPath::from_str("m/44'/637'/3'/0'/0'").unwrap();
+    assert_eq!(path, aptos_derivation_path(3));
+    assert_eq!(path.indices(), &[44, 637, 3, 0, 0]);
+    assert_eq!(DerivationPath::from_str("m").unwrap().indices(), &[]
+        as &[u32]);
+
+    for path in [
+        "",
+        "44'/637'",
+        "m/",
+        "m/44'/637'/0'/0'/0",
+        "m/44'/-1'",
+        "m/2147483648'",
+        "m/44h/637h",
+    ] {
+        assert_eq!(
+            DerivationPath::from_str(path),
+            Err(HdWalletError::InvalidDerivationPath(path.to_string()))
+        );
+    }
+
+    assert_eq!(
+        DerivationPath::new(vec![44, 1 << 31]),
+        Err(HdWalletError::NonHardenedIndex(1 << 31))
+    );
+    let master = ExtendedPrivateKey::from_seed(&[0u8; 32]);
+    assert_eq!(
+        master.derive_child(1 << 31).unwrap_err(),
+        HdWalletError::NonHardenedIndex(1 << 31)
+    );
+}
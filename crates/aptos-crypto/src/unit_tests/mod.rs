@@ -2,15 +2,21 @@
 // SPDX-License-Identifier: Apache-2.0
 
 mod bcs_test;
+mod bls12381_aggregate_cache_test;
 mod bls12381_test;
 mod bulletproofs_test;
 mod compat_test;
+mod constant_time_test;
 mod cross_test;
+mod crypto_error_test;
 mod cryptohasher;
 mod ed25519_test;
 mod hash_test;
+mod hd_wallet_test;
 mod hkdf_test;
 mod multi_ed25519_test;
 mod noise_test;
+mod rng_test;
 mod secp256k1_ecdsa_test;
 mod secp256r1_ecdsa_test;
+mod signed_envelope_test;
@@ -0,0 +1,39 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{ed25519::Ed25519PrivateKey, rng::DefaultRng, Uniform};
+use rand::RngCore;
+
+#[test]
+fn test_default_rng() {
+    let mut bytes = [0u8; 32];
+    DefaultRng.fill_bytes(&mut bytes);
+    assert_ne!(bytes, [0u8; 32]);
+    let _private_key = Ed25519PrivateKey::generate(&mut DefaultRng);
+}
+
+#[cfg(feature = "deterministic-rng")]
+#[test]
+fn test_deterministic_rng() {
+    use crate::{rng::set_seed, HashValue};
+
+    let draw = || {
+        let private_key = Ed25519PrivateKey::generate(&mut DefaultRng);
+        (
+            private_key.to_bytes(),
+            HashValue::random(),
+            DefaultRng.next_u64(),
+        )
+    };
+
+    set_seed(Some([7u8; 32]));
+    let first = draw();
+    set_seed(Some([7u8; 32]));
+    assert_eq!(draw(), first);
+
+    set_seed(Some([8u8; 32]));
+    assert_ne!(draw(), first);
+
+    set_seed(None);
+    assert_ne!(draw(), first);
+}
@@ -0,0 +1,92 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate as aptos_crypto;
+use crate::{
+    ed25519::{Ed25519PrivateKey, Ed25519Signature},
+    error::{CryptoError, CryptoErrorCategory},
+    hash::{CryptoHash, CryptoHasher},
+    signed_envelope::{
+        check_signing_domains, signing_domain, verify_signed, SignedEnvelope, SigningDomain,
+    },
+    test_utils::TestAptosCrypto,
+    traits::signing_message,
+    PrivateKey, SigningKey, Uniform,
+};
+use aptos_crypto_derive::{BCSCryptoHash, CryptoHasher};
+use rand_core::OsRng;
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize, Deserialize, CryptoHasher, BCSCryptoHash)]
+struct EnvelopeMessage(u64);
+
+// Shares the salt of `EnvelopeMessage`, as the salt is derived from the serde name.
+#[derive(Serialize, Deserialize, CryptoHasher, BCSCryptoHash)]
+#[serde(rename = "EnvelopeMessage")]
+struct OtherEnvelopeMessage(u64);
+
+impl SigningDomain for EnvelopeMessage {}
+
+impl SigningDomain for OtherEnvelopeMessage {}
+
+#[test]
+fn test_sign_and_verify() {
+    let private_key = Ed25519PrivateKey::generate(&mut OsRng);
+    let other_private_key = Ed25519PrivateKey::generate(&mut OsRng);
+    let message = TestAptosCrypto("Hello, World".to_string());
+
+    let envelope: SignedEnvelope<_, Ed25519Signature> =
+        SignedEnvelope::sign(message, &private_key).unwrap();
+    assert!(envelope.verify(&private_key.public_key()).is_ok());
+    assert!(envelope.verify(&other_private_key.public_key()).is_err());
+
+    // The signature is the same as when signing the message directly
+    assert_eq!(
+        envelope.signature(),
+        &private_key.sign(envelope.message()).unwrap()
+    );
+    assert_eq!(
+        envelope.signing_message().unwrap(),
+        signing_message(envelope.message()).unwrap()
+    );
+
+    // Same as verifying the message and the signature separately
+    assert!(verify_signed(
+        envelope.message(),
+        envelope.signature(),
+        &private_key.public_key()
+    )
+    .is_ok());
+
+    // Does not verify on another message
+    let (_, signature) = envelope.into_parts();
+    let forged = SignedEnvelope::new(TestAptosCrypto("Hello, Moon".to_string()), signature);
+    assert!(forged.verify(&private_key.public_key()).is_err());
+}
+
+#[test]
+fn test_domain_collision() {
+    assert_eq!(
+        <EnvelopeMessageHasher as CryptoHasher>::seed(),
+        <OtherEnvelopeMessageHasher as CryptoHasher>::seed(),
+    );
+    assert_eq!(
+        OtherEnvelopeMessage(1).hash(),
+        EnvelopeMessage(1).hash(),
+        "The messages are indistinguishable"
+    );
+
+    assert!(check_signing_domains(&[
+        signing_domain::<EnvelopeMessage>(),
+        signing_domain::<TestAptosCrypto>(),
+    ])
+    .is_ok());
+    let error = check_signing_domains(&[
+        signing_domain::<EnvelopeMessage>(),
+        signing_domain::<TestAptosCrypto>(),
+        signing_domain::<OtherEnvelopeMessage>(),
+    ])
+    .unwrap_err();
+    assert!(matches!(error, CryptoError::DomainCollision(_)));
+    assert_eq!(error.category(), CryptoErrorCategory::Other);
+}
@@ -5,8 +5,7 @@
 //! This module provides the `Validate` trait and `Validatable` type in order to aid in deferred
 //! validation.
 
-use crate::ValidCryptoMaterial;
-use anyhow::Result;
+use crate::{error::CryptoError, ValidCryptoMaterial};
 use once_cell::sync::OnceCell;
 use serde::{Deserialize, Serialize};
 use std::hash::Hash;
@@ -28,7 +27,7 @@ pub trait Validate: Sized {
     type Unvalidated: ValidCryptoMaterial;
 
     /// Attempt to validate a `V::Unvalidated` and returning a validated `V` on success
-    fn validate(unvalidated: &Self::Unvalidated) -> Result<Self>;
+    fn validate(unvalidated: &Self::Unvalidated) -> Result<Self, CryptoError>;
 
     /// Return the unvalidated form of type `V`
     fn to_unvalidated(&self) -> Self::Unvalidated;
@@ -77,7 +76,7 @@ impl<V: Validate> Validatable<V> {
 
     // TODO maybe optimize to only try once and keep track when we fail. This would avoid multiple calls to validate() by valid() when validation fails
     /// Attempt to validate `V::Unvalidated` and return a reference to a valid `V`
-    pub fn validate(&self) -> Result<&V> {
+    pub fn validate(&self) -> Result<&V, CryptoError> {
         self.maybe_valid
             .get_or_try_init(|| V::validate(&self.unvalidated))
     }
@@ -32,6 +32,7 @@
 //!
 
 use crate::{
+    constant_time::{self, ConstantTimeEq},
     traits::{self, CryptoMaterialError, ValidCryptoMaterial, ValidCryptoMaterialStringExt},
     x25519,
 };
@@ -190,10 +191,16 @@ impl traits::ValidCryptoMaterial for PrivateKey {
     }
 }
 
+impl ConstantTimeEq for PrivateKey {
+    fn ct_eq(&self, other: &Self) -> bool {
+        constant_time::ct_eq(&self.to_bytes(), &other.to_bytes())
+    }
+}
+
 #[cfg(any(test, feature = "fuzzing"))]
 impl PartialEq for PrivateKey {
     fn eq(&self, other: &Self) -> bool {
-        self.to_bytes() == other.to_bytes()
+        constant_time::secret_eq(self, other)
     }
 }
 
@@ -2,26 +2,44 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    metrics::{GAUGE, TIMER},
-    IN_ANY_DROP_POOL,
+    metrics::{DROP_LATENCY, GAUGE, INLINE_DROPS, PENDING_DROPS, QUEUED_BYTES, TIMER},
+    DropPriority, DropTag, IN_ANY_DROP_POOL,
 };
 use aptos_infallible::Mutex;
-use aptos_metrics_core::{IntGaugeHelper, TimerHelper};
-use std::sync::{
-    mpsc::{channel, Receiver, Sender},
-    Arc, Condvar,
+use aptos_metrics_core::{IntCounterHelper, IntGaugeHelper, TimerHelper};
+use std::{
+    collections::VecDeque,
+    sync::{
+        mpsc::{channel, Receiver, Sender},
+        Arc, Condvar,
+    },
+    time::Instant,
 };
 use threadpool::ThreadPool;
 
+/// What `schedule_drop` does when the backlog is full.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BackpressurePolicy {
+    /// Blocks the caller until the backlog shrinks.
+    Block,
+    /// Drops the value in the caller thread.
+    DropInline,
+}
+
 /// A helper to send things to a thread pool for asynchronous dropping.
 ///
-/// Be aware that there is a bounded number of concurrent drops, as a result:
-///   1. when it's "out of capacity", `schedule_drop` will block until a slot to be available.
+/// Be aware that there is a bounded number of concurrent drops (and optionally a bound on the
+/// estimated bytes pending a drop), as a result:
+///   1. when it's "out of capacity", `schedule_drop` will block until a slot to be available, or
+///      drop the value inline with `BackpressurePolicy::DropInline`.
 ///   2. if the `Drop` implementation tries to lock things, there can be a potential deadlock due
 ///      to another thing being waiting for a slot to be available.
+///
+/// The bound on the number of concurrent drops applies to each `DropPriority` lane separately.
 pub struct AsyncConcurrentDropper {
     name: &'static str,
     num_tasks_tracker: Arc<NumTasksTracker>,
+    lanes: Arc<DropLanes>,
     /// use dedicated thread pool to minimize the possibility of deadlock
     thread_pool: ThreadPool,
 }
@@ -30,18 +48,50 @@ impl AsyncConcurrentDropper {
     pub fn new(name: &'static str, max_tasks: usize, num_threads: usize) -> Self {
         Self {
             name,
-            num_tasks_tracker: Arc::new(NumTasksTracker::new(name, max_tasks)),
+            num_tasks_tracker: Arc::new(NumTasksTracker::new(
+                name,
+                max_tasks,
+                usize::MAX,
+                BackpressurePolicy::Block,
+            )),
+            lanes: Arc::new(DropLanes::new()),
             thread_pool: ThreadPool::with_name(format!("{}_conc_dropper", name), num_threads),
         }
     }
 
+    /// Bounds the estimated bytes pending a drop (see `DropTag::with_size_bytes`) on top of the
+    /// number of pending drops, and sets what to do when either bound is hit.
+    pub fn with_backpressure(self, max_bytes: usize, policy: BackpressurePolicy) -> Self {
+        let max_tasks = self.max_tasks();
+        Self {
+            num_tasks_tracker: Arc::new(NumTasksTracker::new(
+                self.name, max_tasks, max_bytes, policy,
+            )),
+            ..self
+        }
+    }
+
     pub fn schedule_drop<V: Send + 'static>(&self, v: V) {
-        self.schedule_drop_impl(v, None)
+        self.schedule_drop_impl(v, DropTag::UNTAGGED, None)
     }
 
     pub fn schedule_drop_with_waiter<V: Send + 'static>(&self, v: V) -> Receiver<()> {
+        self.schedule_tagged_drop_with_waiter(v, DropTag::UNTAGGED)
+    }
+
+    /// Same as `schedule_drop`, but reports the metrics under the use case of `tag`, and accounts
+    /// for its size against the bytes bound.
+    pub fn schedule_tagged_drop<V: Send + 'static>(&self, v: V, tag: DropTag) {
+        self.schedule_drop_impl(v, tag, None)
+    }
+
+    pub fn schedule_tagged_drop_with_waiter<V: Send + 'static>(
+        &self,
+        v: V,
+        tag: DropTag,
+    ) -> Receiver<()> {
         let (tx, rx) = channel();
-        self.schedule_drop_impl(v, Some(tx));
+        self.schedule_drop_impl(v, tag, Some(tx));
         rx
     }
 
@@ -49,6 +99,10 @@ impl AsyncConcurrentDropper {
         self.num_tasks_tracker.max_tasks
     }
 
+    pub fn max_bytes(&self) -> usize {
+        self.num_tasks_tracker.max_bytes
+    }
+
     pub fn num_threads(&self) -> usize {
         self.thread_pool.max_count()
     }
@@ -58,28 +112,54 @@ impl AsyncConcurrentDropper {
         self.num_tasks_tracker.wait_for_backlog_drop(no_more_than);
     }
 
-    fn schedule_drop_impl<V: Send + 'static>(&self, v: V, notif_sender_opt: Option<Sender<()>>) {
+    fn schedule_drop_impl<V: Send + 'static>(
+        &self,
+        v: V,
+        tag: DropTag,
+        notif_sender_opt: Option<Sender<()>>,
+    ) {
         if IN_ANY_DROP_POOL.get() {
             Self::do_drop(v, notif_sender_opt);
             return;
         }
 
         let _timer = TIMER.timer_with(&[self.name, "enqueue_drop"]);
-        self.num_tasks_tracker.inc();
+        if !self.num_tasks_tracker.inc(tag) {
+            let _timer = TIMER.timer_with(&[self.name, "inline_drop"]);
+            INLINE_DROPS.inc_with(&[self.name, tag.use_case]);
+            Self::do_drop(v, notif_sender_opt);
+            return;
+        }
 
         let name = self.name;
         let num_tasks_tracker = self.num_tasks_tracker.clone();
+        let scheduled_at = Instant::now();
 
-        self.thread_pool.execute(move || {
-            let _timer = TIMER.timer_with(&[name, "real_drop"]);
+        self.lanes.push(
+            tag.priority,
+            Box::new(move || {
+                let _timer = TIMER.timer_with(&[name, "real_drop"]);
+
+                Self::do_drop(v, notif_sender_opt);
 
+                num_tasks_tracker.dec(tag);
+                DROP_LATENCY
+                    .with_label_values(&[name, tag.use_case])
+                    .observe(scheduled_at.elapsed().as_secs_f64());
+            }),
+        );
+
+        // One pool task per drop, each running the highest priority drop pending at the time it
+        // runs.
+        let lanes = self.lanes.clone();
+        self.thread_pool.execute(move || {
             IN_ANY_DROP_POOL.with(|flag| {
                 flag.set(true);
             });
 
-            Self::do_drop(v, notif_sender_opt);
-
-            num_tasks_tracker.dec();
+            if let Some(job) = lanes.pop() {
+                job();
+            }
         })
     }
 
@@ -92,50 +172,152 @@ impl AsyncConcurrentDropper {
     }
 }
 
+type DropJob = Box<dyn FnOnce() + Send>;
+
+struct DropLanes {
+    high: Mutex<VecDeque<DropJob>>,
+    low: Mutex<VecDeque<DropJob>>,
+}
+
+impl DropLanes {
+    fn new() -> Self {
+        Self {
+            high: Mutex::new(VecDeque::new()),
+            low: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    fn push(&self, priority: DropPriority, job: DropJob) {
+        match priority {
+            DropPriority::High => self.high.lock().push_back(job),
+            DropPriority::Low => self.low.lock().push_back(job),
+        }
+    }
+
+    fn pop(&self) -> Option<DropJob> {
+        let job = self.high.lock().pop_front();
+        job.or_else(|| self.low.lock().pop_front())
+    }
+}
+
+#[derive(Default)]
+struct Backlog {
+    num_tasks: usize,
+    num_high_priority_tasks: usize,
+    num_bytes: usize,
+}
+
+impl Backlog {
+    fn num_tasks_in_lane(&self, priority: DropPriority) -> usize {
+        match priority {
+            DropPriority::High => self.num_high_priority_tasks,
+            DropPriority::Low => self.num_tasks - self.num_high_priority_tasks,
+        }
+    }
+}
+
 struct NumTasksTracker {
     name: &'static str,
-    lock: Mutex<usize>,
+    lock: Mutex<Backlog>,
     cvar: Condvar,
     max_tasks: usize,
+    max_bytes: usize,
+    policy: BackpressurePolicy,
 }
 
 impl NumTasksTracker {
-    fn new(name: &'static str, max_tasks: usize) -> Self {
+    fn new(
+        name: &'static str,
+        max_tasks: usize,
+        max_bytes: usize,
+        policy: BackpressurePolicy,
+    ) -> Self {
         Self {
             name,
-            lock: Mutex::new(0),
+            lock: Mutex::new(Backlog::default()),
             cvar: Condvar::new(),
             max_tasks,
+            max_bytes,
+            policy,
         }
     }
 
-    fn inc(&self) {
-        let mut num_tasks = self.lock.lock();
-        while *num_tasks >= self.max_tasks {
-            num_tasks = self.cvar.wait(num_tasks).expect("lock poisoned.");
+    fn is_full(&self, backlog: &Backlog, tag: DropTag) -> bool {
+        // A value larger than the bytes bound is still accepted when nothing else is pending, so
+        // that it doesn't block forever.
+        backlog.num_tasks_in_lane(tag.priority) >= self.max_tasks
+            || (backlog.num_bytes > 0
+                && backlog.num_bytes.saturating_add(tag.size_bytes) > self.max_bytes)
+    }
+
+    /// Accounts for a new pending drop, blocking until there is room for it with
+    /// `BackpressurePolicy::Block`. Returns false if there is no room for it with
+    /// `BackpressurePolicy::DropInline`.
+    fn inc(&self, tag: DropTag) -> bool {
+        let mut backlog = self.lock.lock();
+        while self.is_full(&backlog, tag) {
+            match self.policy {
+                BackpressurePolicy::Block => {
+                    backlog = self.cvar.wait(backlog).expect("lock poisoned.");
+                },
+                BackpressurePolicy::DropInline => return false,
+            }
         }
-        *num_tasks += 1;
-        GAUGE.set_with(&[self.name, "num_tasks"], *num_tasks as i64);
+        backlog.num_tasks += 1;
+        if tag.priority == DropPriority::High {
+            backlog.num_high_priority_tasks += 1;
+        }
+        backlog.num_bytes += tag.size_bytes;
+        self.update_gauges(&backlog);
+        PENDING_DROPS
+            .with_label_values(&[self.name, tag.use_case])
+            .inc();
+        QUEUED_BYTES
+            .with_label_values(&[self.name, tag.use_case])
+            .add(tag.size_bytes as i64);
+        true
     }
 
-    fn dec(&self) {
-        let mut num_tasks = self.lock.lock();
-        *num_tasks -= 1;
-        GAUGE.set_with(&[self.name, "num_tasks"], *num_tasks as i64);
+    fn dec(&self, tag: DropTag) {
+        let mut backlog = self.lock.lock();
+        backlog.num_tasks -= 1;
+        if tag.priority == DropPriority::High {
+            backlog.num_high_priority_tasks -= 1;
+        }
+        backlog.num_bytes -= tag.size_bytes;
+        self.update_gauges(&backlog);
+        PENDING_DROPS
+            .with_label_values(&[self.name, tag.use_case])
+            .dec();
+        QUEUED_BYTES
+            .with_label_values(&[self.name, tag.use_case])
+            .sub(tag.size_bytes as i64);
         self.cvar.notify_all();
     }
 
+    fn update_gauges(&self, backlog: &Backlog) {
+        GAUGE.set_with(&[self.name, "num_tasks"], backlog.num_tasks as i64);
+        GAUGE.set_with(
+            &[self.name, "num_high_priority_tasks"],
+            backlog.num_high_priority_tasks as i64,
+        );
+        GAUGE.set_with(&[self.name, "num_bytes"], backlog.num_bytes as i64);
+    }
+
     fn wait_for_backlog_drop(&self, no_more_than: usize) {
-        let mut num_tasks = self.lock.lock();
-        while *num_tasks > no_more_than {
-            num_tasks = self.cvar.wait(num_tasks).expect("lock poisoned.");
+        let mut backlog = self.lock.lock();
+        while backlog.num_tasks > no_more_than {
+            backlog = self.cvar.wait(backlog).expect("lock poisoned.");
         }
     }
 }
 
 #[cfg(test)]
 mod tests {
-    use crate::{AsyncConcurrentDropper, DropHelper, DEFAULT_DROPPER};
+    use crate::{
+        async_concurrent_dropper::BackpressurePolicy, AsyncConcurrentDropper, DropHelper,
+        DropPriority, DropTag, DEFAULT_DROPPER,
+    };
     use rayon::prelude::*;
     use std::{sync::Arc, thread::sleep, time::Duration};
     use threadpool::ThreadPool;
@@ -185,6 +367,68 @@ mod tests {
         assert!(now.elapsed() < Duration::from_millis(400));
     }
 
+    #[test]
+    fn test_drop_inline_beyond_limit() {
+        let s = AsyncConcurrentDropper::new("test", 2, 2)
+            .with_backpressure(usize::MAX, BackpressurePolicy::DropInline);
+        let now = std::time::Instant::now();
+        s.schedule_drop(SlowDropper);
+        s.schedule_drop(SlowDropper);
+        assert!(now.elapsed() < Duration::from_millis(200));
+        // out of capacity, dropped by the caller
+        s.schedule_drop(SlowDropper);
+        assert!(now.elapsed() > Duration::from_millis(200));
+        assert!(now.elapsed() < Duration::from_millis(400));
+        s.wait_for_backlog_drop(0);
+        // capacity is back
+        s.schedule_drop(SlowDropper);
+        assert!(now.elapsed() < Duration::from_millis(400));
+    }
+
+    #[test]
+    fn test_bytes_limit_hit() {
+        let s = AsyncConcurrentDropper::new("test", 8, 4)
+            .with_backpressure(100, BackpressurePolicy::Block);
+        let tag = DropTag::new("test").with_size_bytes(40);
+        let now = std::time::Instant::now();
+        s.schedule_tagged_drop(SlowDropper, tag);
+        s.schedule_tagged_drop(SlowDropper, tag);
+        assert!(now.elapsed() < Duration::from_millis(200));
+        // 120 bytes would be pending
+        s.schedule_tagged_drop(SlowDropper, tag);
+        assert!(now.elapsed() > Duration::from_millis(200));
+        assert!(now.elapsed() < Duration::from_millis(400));
+        s.wait_for_backlog_drop(0);
+
+        // a value larger than the limit is accepted when nothing else is pending
+        let now = std::time::Instant::now();
+        s.schedule_tagged_drop(SlowDropper, tag.with_size_bytes(1000));
+        assert!(now.elapsed() < Duration::from_millis(200));
+    }
+
+    #[test]
+    fn test_high_priority_lane() {
+        let s = AsyncConcurrentDropper::new("test", 4, 1);
+        let low = DropTag::new("low");
+        let high = DropTag::new("high").with_priority(DropPriority::High);
+        let now = std::time::Instant::now();
+        // the single thread is busy with the first drop, the other ones are queued
+        let rx_low = s.schedule_tagged_drop_with_waiter(SlowDropper, low);
+        for _ in 0..3 {
+            s.schedule_tagged_drop(SlowDropper, low);
+        }
+        // doesn't block on the full low priority lane
+        let rx_high = s.schedule_tagged_drop_with_waiter(SlowDropper, high);
+        assert!(now.elapsed() < Duration::from_millis(200));
+
+        rx_low.recv().unwrap();
+        // jumps ahead of the 3 pending low priority drops
+        rx_high.recv().unwrap();
+        assert!(now.elapsed() < Duration::from_millis(600));
+        s.wait_for_backlog_drop(0);
+        assert!(now.elapsed() > Duration::from_millis(1000));
+    }
+
     fn async_wait(
         thread_pool: &ThreadPool,
         dropper: &Arc<AsyncConcurrentDropper>,
@@ -1,10 +1,16 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::metrics::TIMER;
+use crate::{
+    metrics::{DROP_LATENCY, PENDING_DROPS, TIMER},
+    DropTag,
+};
 use aptos_infallible::Mutex;
 use aptos_metrics_core::TimerHelper;
-use std::sync::mpsc::{channel, Receiver, Sender};
+use std::{
+    sync::mpsc::{channel, Receiver, Sender},
+    time::Instant,
+};
 use threadpool::ThreadPool;
 
 #[derive(Debug)]
@@ -34,17 +40,29 @@ impl AsyncDropQueue {
     }
 
     pub fn enqueue_drop<V: Send + 'static>(&self, v: V) {
+        self.enqueue_tagged_drop(v, DropTag::UNTAGGED)
+    }
+
+    /// Same as `enqueue_drop`, but reports the metrics under the use case of `tag`.
+    pub fn enqueue_tagged_drop<V: Send + 'static>(&self, v: V, tag: DropTag) {
         let _timer = TIMER.timer_with(&[self.name, "enqueue_drop"]);
 
         self.token_rx.lock().recv().unwrap();
 
         let token_tx = self.token_tx.clone();
         let name = self.name;
+        let scheduled_at = Instant::now();
+        let pending_drops = PENDING_DROPS.with_label_values(&[name, tag.use_case]);
+        pending_drops.inc();
         self.thread.execute(move || {
             let _timer = TIMER.timer_with(&[name, "real_drop"]);
 
             drop(v);
 
+            pending_drops.dec();
+            DROP_LATENCY
+                .with_label_values(&[name, tag.use_case])
+                .observe(scheduled_at.elapsed().as_secs_f64());
             token_tx.send(()).ok();
         })
     }
@@ -16,6 +16,45 @@ thread_local! {
     static IN_ANY_DROP_POOL: Cell<bool> = const { Cell::new(false) };
 }
 
+/// The lane of a scheduled drop. The drop threads always pick the pending high priority drops
+/// first, and each lane has its own bound on the number of pending drops, so that a backlog of
+/// large background drops doesn't delay the drops on latency-critical paths (e.g., block commit).
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum DropPriority {
+    High,
+    #[default]
+    Low,
+}
+
+/// Describes a scheduled drop: the use case its metrics are reported under, the estimated size of
+/// the dropped value, accounted for against the bytes bound of the dropper, and its priority.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct DropTag {
+    pub use_case: &'static str,
+    pub size_bytes: usize,
+    pub priority: DropPriority,
+}
+
+impl DropTag {
+    pub const UNTAGGED: Self = Self::new("untagged");
+
+    pub const fn new(use_case: &'static str) -> Self {
+        Self {
+            use_case,
+            size_bytes: 0,
+            priority: DropPriority::Low,
+        }
+    }
+
+    pub const fn with_size_bytes(self, size_bytes: usize) -> Self {
+        Self { size_bytes, ..self }
+    }
+
+    pub const fn with_priority(self, priority: DropPriority) -> Self {
+        Self { priority, ..self }
+    }
+}
+
 pub static DEFAULT_DROPPER: Lazy<AsyncConcurrentDropper> =
     Lazy::new(|| AsyncConcurrentDropper::new("default", 32, 8));
 
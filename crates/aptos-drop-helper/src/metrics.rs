@@ -2,7 +2,8 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use aptos_metrics_core::{
-    exponential_buckets, register_histogram_vec, register_int_gauge_vec, HistogramVec, IntGaugeVec,
+    exponential_buckets, register_histogram_vec, register_int_counter_vec, register_int_gauge_vec,
+    HistogramVec, IntCounterVec, IntGaugeVec,
 };
 use once_cell::sync::Lazy;
 
@@ -24,3 +25,40 @@ pub static GAUGE: Lazy<IntGaugeVec> = Lazy::new(|| {
     )
     .unwrap()
 });
+
+pub static DROP_LATENCY: Lazy<HistogramVec> = Lazy::new(|| {
+    register_histogram_vec!(
+        "aptos_drop_helper_drop_latency_seconds",
+        "Time from scheduling a drop to its completion.",
+        &["helper_name", "use_case"],
+        exponential_buckets(/*start=*/ 1e-6, /*factor=*/ 2.0, /*count=*/ 28).unwrap(),
+    )
+    .unwrap()
+});
+
+pub static PENDING_DROPS: Lazy<IntGaugeVec> = Lazy::new(|| {
+    register_int_gauge_vec!(
+        "aptos_drop_helper_pending_drops",
+        "Number of drops scheduled but not done yet.",
+        &["helper_name", "use_case"],
+    )
+    .unwrap()
+});
+
+pub static QUEUED_BYTES: Lazy<IntGaugeVec> = Lazy::new(|| {
+    register_int_gauge_vec!(
+        "aptos_drop_helper_queued_bytes",
+        "Estimated size of the values scheduled for dropping but not dropped yet.",
+        &["helper_name", "use_case"],
+    )
+    .unwrap()
+});
+
+pub static INLINE_DROPS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_drop_helper_inline_drops",
+        "Number of values dropped by the caller because the backlog was full.",
+        &["helper_name", "use_case"],
+    )
+    .unwrap()
+});
@@ -8,6 +8,7 @@ mod magic_header;
 mod memory_ratelimit;
 mod redis_ratelimit;
 mod referer_blocklist;
+mod sliding_window_ratelimit;
 mod tap_captcha;
 
 pub use self::tap_captcha::CaptchaManager;
@@ -19,6 +20,9 @@ use self::{
     memory_ratelimit::{MemoryRatelimitChecker, MemoryRatelimitCheckerConfig},
     redis_ratelimit::{RedisRatelimitChecker, RedisRatelimitCheckerConfig},
     referer_blocklist::RefererBlocklistChecker,
+    sliding_window_ratelimit::{
+        SlidingWindowRatelimitChecker, SlidingWindowRatelimitCheckerConfig,
+    },
     tap_captcha::{TapCaptchaChecker, TapCaptchaCheckerConfig},
 };
 use crate::{
@@ -102,6 +106,10 @@ pub enum CheckerConfig {
     /// Rejects requests if their Referer is blocklisted.
     RefererBlocklist(ListManagerConfig),
 
+    /// Ratelimiter with per IP and per auth token quotas over sliding windows,
+    /// storing the requests in memory or in Redis.
+    SlidingWindowRatelimit(SlidingWindowRatelimitCheckerConfig),
+
     /// In-house captcha solution.
     TapCaptcha(TapCaptchaCheckerConfig),
 }
@@ -124,6 +132,9 @@ impl CheckerConfig {
             CheckerConfig::RefererBlocklist(config) => {
                 Checker::from(RefererBlocklistChecker::new(config)?)
             },
+            CheckerConfig::SlidingWindowRatelimit(config) => {
+                Checker::from(SlidingWindowRatelimitChecker::new(config).await?)
+            },
             CheckerConfig::TapCaptcha(config) => {
                 Checker::from(TapCaptchaChecker::new(config, captcha_manager)?)
             },
@@ -141,6 +152,7 @@ pub enum Checker {
     MemoryRatelimitChecker,
     RedisRatelimitChecker,
     RefererBlocklistChecker,
+    SlidingWindowRatelimitChecker,
     TapCaptchaChecker,
 }
 
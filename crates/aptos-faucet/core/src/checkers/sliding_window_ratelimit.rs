@@ -0,0 +1,201 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use super::{CheckerData, CheckerTrait, CompleteData};
+use crate::{
+    common::{RatelimitStore, RatelimitStoreConfig},
+    endpoints::{AptosTapError, QuotaExceeded, RejectionReason, RejectionReasonCode},
+};
+use anyhow::{bail, Result};
+use async_trait::async_trait;
+use poem::http::header::AUTHORIZATION;
+use serde::{Deserialize, Serialize};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// What a quota is counted per.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub enum QuotaIdentity {
+    /// The IP the request came from.
+    Ip,
+    /// The token in the Authorization header. Requests without one are not
+    /// counted towards this quota.
+    AuthToken,
+}
+
+impl QuotaIdentity {
+    fn name(&self) -> &'static str {
+        match self {
+            QuotaIdentity::Ip => "ip",
+            QuotaIdentity::AuthToken => "auth_token",
+        }
+    }
+
+    /// Returns the identity of the request, None if the request doesn't have one.
+    fn value(&self, data: &CheckerData) -> Option<String> {
+        match self {
+            QuotaIdentity::Ip => Some(data.source_ip.to_string()),
+            QuotaIdentity::AuthToken => data
+                .headers
+                .get(AUTHORIZATION)
+                .and_then(|v| v.to_str().ok())
+                .and_then(|v| v.split_whitespace().nth(1))
+                .map(|auth_token| auth_token.to_string()),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct QuotaConfig {
+    pub identity: QuotaIdentity,
+
+    /// Max number of requests per identity within the window. 500s are not
+    /// counted, because they are not the user's fault, but everything else is.
+    pub max_requests: u32,
+
+    /// The length of the sliding window.
+    pub window_secs: u64,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct SlidingWindowRatelimitCheckerConfig {
+    /// Where the requests are stored, in memory by default.
+    #[serde(default)]
+    pub store: RatelimitStoreConfig,
+
+    /// A request is rejected if it exceeds any of these quotas.
+    pub quotas: Vec<QuotaConfig>,
+}
+
+/// Ratelimits requests over sliding windows, as opposed to the daily limits of
+/// MemoryRatelimitChecker and RedisRatelimitChecker, which can be drained at
+/// the start of each day. Multiple quotas can be combined, e.g. a tight quota
+/// per IP with a looser one per auth token. A request is only counted towards
+/// the quotas if it is accepted by all of them.
+pub struct SlidingWindowRatelimitChecker {
+    store: Box<dyn RatelimitStore>,
+    quotas: Vec<QuotaConfig>,
+    cost: u8,
+}
+
+impl SlidingWindowRatelimitChecker {
+    pub async fn new(args: SlidingWindowRatelimitCheckerConfig) -> Result<Self> {
+        if args.quotas.is_empty() {
+            bail!("SlidingWindowRatelimitChecker requires at least one quota");
+        }
+        if let Some(quota) = args.quotas.iter().find(|quota| quota.window_secs == 0) {
+            bail!(
+                "The window of the {:?} quota must not be empty",
+                quota.identity
+            );
+        }
+        // Going to Redis costs about as much as the RedisRatelimitChecker.
+        let cost = match args.store {
+            RatelimitStoreConfig::Memory(_) => 20,
+            RatelimitStoreConfig::Redis(_) => 100,
+        };
+        Ok(Self {
+            store: args.store.build().await?,
+            quotas: args.quotas,
+            cost,
+        })
+    }
+
+    /// Returns the quotas applying to the request, along with the key under
+    /// which the requests are counted for each.
+    fn keys<'a>(&'a self, data: &CheckerData) -> Vec<(&'a QuotaConfig, String)> {
+        self.quotas
+            .iter()
+            .filter_map(|quota| {
+                let value = quota.identity.value(data)?;
+                let key = format!(
+                    "sliding:{}:{}:{}",
+                    quota.identity.name(),
+                    quota.window_secs,
+                    value
+                );
+                Some((quota, key))
+            })
+            .collect()
+    }
+
+    async fn check_quotas(
+        &self,
+        keys: &[(&QuotaConfig, String)],
+        now_ms: u64,
+        dry_run: bool,
+    ) -> Result<Vec<RejectionReason>, AptosTapError> {
+        let mut rejection_reasons = vec![];
+        for (quota, key) in keys {
+            if let Some(retry_after_ms) = self
+                .store
+                .record_request(
+                    key,
+                    quota.max_requests,
+                    quota.window_secs * 1000,
+                    now_ms,
+                    dry_run,
+                )
+                .await?
+            {
+                rejection_reasons.push(
+                    RejectionReason::new(
+                        format!(
+                            "You have reached the maximum allowed number of requests per {:?} \
+                             within {} seconds: {}",
+                            quota.identity, quota.window_secs, quota.max_requests
+                        ),
+                        RejectionReasonCode::UsageLimitExhausted,
+                    )
+                    .quota(QuotaExceeded {
+                        identity: format!("{:?}", quota.identity),
+                        max_requests: quota.max_requests,
+                        window_secs: quota.window_secs,
+                        retry_after_secs: retry_after_ms.div_ceil(1000),
+                    }),
+                );
+            }
+        }
+        Ok(rejection_reasons)
+    }
+}
+
+fn get_current_time_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .expect("Time has gone backwards???")
+        .as_millis() as u64
+}
+
+#[async_trait]
+impl CheckerTrait for SlidingWindowRatelimitChecker {
+    async fn check(
+        &self,
+        data: CheckerData,
+        dry_run: bool,
+    ) -> Result<Vec<RejectionReason>, AptosTapError> {
+        let keys = self.keys(&data);
+        let now_ms = get_current_time_ms();
+
+        // Only count the request once we know that no quota rejects it.
+        let rejection_reasons = self.check_quotas(&keys, now_ms, true).await?;
+        if !rejection_reasons.is_empty() || dry_run {
+            return Ok(rejection_reasons);
+        }
+        self.check_quotas(&keys, now_ms, false).await
+    }
+
+    /// Forget the request if it failed due to something wrong on our end.
+    async fn complete(&self, data: CompleteData) -> Result<(), AptosTapError> {
+        if !data.response_is_500 {
+            return Ok(());
+        }
+        for (_, key) in self.keys(&data.checker_data) {
+            self.store.forget_latest_request(&key).await?;
+        }
+        Ok(())
+    }
+
+    fn cost(&self) -> u8 {
+        self.cost
+    }
+}
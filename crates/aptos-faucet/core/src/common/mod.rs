@@ -3,6 +3,11 @@
 
 mod ip_range_manager;
 mod list_manager;
+mod ratelimit_store;
 
 pub use ip_range_manager::{IpRangeManager, IpRangeManagerConfig};
 pub use list_manager::{ListManager, ListManagerConfig};
+pub use ratelimit_store::{
+    MemoryRatelimitStore, MemoryRatelimitStoreConfig, RatelimitStore, RatelimitStoreConfig,
+    RedisConnectionConfig, RedisRatelimitStore,
+};
@@ -0,0 +1,379 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::endpoints::{AptosTapError, AptosTapErrorCode};
+use anyhow::{Context, Result};
+use async_trait::async_trait;
+use deadpool_redis::{
+    redis::{self, AsyncCommands, ConnectionAddr, ConnectionInfo, RedisConnectionInfo},
+    Config, Connection, Pool, Runtime,
+};
+use lru::LruCache;
+use serde::{Deserialize, Serialize};
+use std::{collections::VecDeque, num::NonZeroUsize};
+use tokio::sync::Mutex;
+
+/// The details needed to connect to a Redis database.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct RedisConnectionConfig {
+    /// The database address to connect to, not including port,
+    /// e.g. db.example.com or 234.121.222.42.
+    pub database_address: String,
+
+    /// The port to connect to.
+    #[serde(default = "RedisConnectionConfig::default_database_port")]
+    pub database_port: u16,
+
+    /// The number of the database to use. If it doesn't exist, it will be created (todo verify this)
+    #[serde(default = "RedisConnectionConfig::default_database_number")]
+    pub database_number: i64,
+
+    /// The name of the user to use, if necessary.
+    pub database_user: Option<String>,
+
+    /// The password of the given user, if necessary.
+    pub database_password: Option<String>,
+}
+
+impl RedisConnectionConfig {
+    fn default_database_port() -> u16 {
+        6379
+    }
+
+    fn default_database_number() -> i64 {
+        0
+    }
+
+    fn build_connection_info(&self) -> ConnectionInfo {
+        ConnectionInfo {
+            addr: ConnectionAddr::Tcp(self.database_address.clone(), self.database_port),
+            redis: RedisConnectionInfo {
+                db: self.database_number,
+                username: self.database_user.clone(),
+                password: self.database_password.clone(),
+            },
+        }
+    }
+
+    pub fn build_db_pool(&self) -> Result<Pool> {
+        let connection_info = self.build_connection_info();
+        let cfg = Config {
+            connection: Some(connection_info.into()),
+            ..Default::default()
+        };
+        cfg.create_pool(Some(Runtime::Tokio1))
+            .context("Failed to build redis connection pool")
+    }
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct MemoryRatelimitStoreConfig {
+    /// To avoid OOMing the server, the least recently used keys are dropped
+    /// beyond this many keys.
+    #[serde(default = "MemoryRatelimitStoreConfig::default_max_entries_in_map")]
+    pub max_entries_in_map: NonZeroUsize,
+}
+
+impl MemoryRatelimitStoreConfig {
+    fn default_max_entries_in_map() -> NonZeroUsize {
+        NonZeroUsize::new(1000000).unwrap()
+    }
+}
+
+impl Default for MemoryRatelimitStoreConfig {
+    fn default() -> Self {
+        Self {
+            max_entries_in_map: Self::default_max_entries_in_map(),
+        }
+    }
+}
+
+/// Where the requests counted by a sliding window ratelimiter are stored.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(tag = "type")]
+pub enum RatelimitStoreConfig {
+    /// Requests are stored in the memory of this process, so the limits are not
+    /// shared between replicas and are reset on restart.
+    Memory(MemoryRatelimitStoreConfig),
+
+    /// Requests are stored in Redis, so the limits are shared between replicas.
+    Redis(RedisConnectionConfig),
+}
+
+impl Default for RatelimitStoreConfig {
+    fn default() -> Self {
+        RatelimitStoreConfig::Memory(MemoryRatelimitStoreConfig::default())
+    }
+}
+
+impl RatelimitStoreConfig {
+    pub async fn build(&self) -> Result<Box<dyn RatelimitStore>> {
+        Ok(match self {
+            RatelimitStoreConfig::Memory(config) => Box::new(MemoryRatelimitStore::new(config)),
+            RatelimitStoreConfig::Redis(config) => {
+                Box::new(RedisRatelimitStore::new(config).await?)
+            },
+        })
+    }
+}
+
+/// A store of the times at which requests were made with each key, used to
+/// count the requests within a sliding window.
+#[async_trait]
+pub trait RatelimitStore: Sync + Send + 'static {
+    /// Counts the requests made with the key within the window ending at `now_ms`.
+    /// If there are fewer than `max_requests`, records a request at `now_ms`
+    /// (unless dry_run is set) and returns None. Otherwise returns how many
+    /// milliseconds remain until the oldest request leaves the window.
+    async fn record_request(
+        &self,
+        key: &str,
+        max_requests: u32,
+        window_ms: u64,
+        now_ms: u64,
+        dry_run: bool,
+    ) -> Result<Option<u64>, AptosTapError>;
+
+    /// Forgets the latest request recorded with the key, e.g. because it failed
+    /// due to something wrong on our end.
+    async fn forget_latest_request(&self, key: &str) -> Result<(), AptosTapError>;
+}
+
+/// Returns how long until the oldest request in the window leaves it.
+fn retry_after_ms(oldest_request_ms: Option<u64>, window_ms: u64, now_ms: u64) -> u64 {
+    oldest_request_ms.map_or(window_ms, |oldest_request_ms| {
+        (oldest_request_ms + window_ms).saturating_sub(now_ms)
+    })
+}
+
+pub struct MemoryRatelimitStore {
+    /// Map of key to the times of the requests made with it within the window,
+    /// oldest first.
+    requests: Mutex<LruCache<String, VecDeque<u64>>>,
+}
+
+impl MemoryRatelimitStore {
+    pub fn new(config: &MemoryRatelimitStoreConfig) -> Self {
+        Self {
+            requests: Mutex::new(LruCache::new(config.max_entries_in_map)),
+        }
+    }
+}
+
+#[async_trait]
+impl RatelimitStore for MemoryRatelimitStore {
+    async fn record_request(
+        &self,
+        key: &str,
+        max_requests: u32,
+        window_ms: u64,
+        now_ms: u64,
+        dry_run: bool,
+    ) -> Result<Option<u64>, AptosTapError> {
+        let mut requests = self.requests.lock().await;
+        let request_times = requests.get_or_insert_mut(key.to_string(), VecDeque::new);
+
+        // Drop the requests that have left the window.
+        while let Some(oldest_request_ms) = request_times.front() {
+            if oldest_request_ms + window_ms > now_ms {
+                break;
+            }
+            request_times.pop_front();
+        }
+
+        if request_times.len() >= max_requests as usize {
+            return Ok(Some(retry_after_ms(
+                request_times.front().copied(),
+                window_ms,
+                now_ms,
+            )));
+        }
+        if !dry_run {
+            request_times.push_back(now_ms);
+        }
+        Ok(None)
+    }
+
+    async fn forget_latest_request(&self, key: &str) -> Result<(), AptosTapError> {
+        if let Some(request_times) = self.requests.lock().await.get_mut(key) {
+            request_times.pop_back();
+        }
+        Ok(())
+    }
+}
+
+/// Stores the requests of each key in a Redis sorted set, scored by the time
+/// of the request. Like RedisRatelimitChecker, it checks the limit again after
+/// recording the request, so concurrent requests cannot exceed the limit.
+pub struct RedisRatelimitStore {
+    db_pool: Pool,
+}
+
+impl RedisRatelimitStore {
+    pub async fn new(config: &RedisConnectionConfig) -> Result<Self> {
+        let db_pool = config.build_db_pool()?;
+
+        // Ensure we can connect.
+        db_pool
+            .get()
+            .await
+            .context("Failed to connect to redis on startup")?;
+
+        Ok(Self { db_pool })
+    }
+
+    async fn get_redis_connection(&self) -> Result<Connection, AptosTapError> {
+        self.db_pool.get().await.map_err(|e| {
+            AptosTapError::new_with_error_code(
+                format!("Failed to connect to redis storage: {}", e),
+                AptosTapErrorCode::StorageError,
+            )
+        })
+    }
+}
+
+fn storage_error(message: String) -> AptosTapError {
+    AptosTapError::new_with_error_code(message, AptosTapErrorCode::StorageError)
+}
+
+#[async_trait]
+impl RatelimitStore for RedisRatelimitStore {
+    async fn record_request(
+        &self,
+        key: &str,
+        max_requests: u32,
+        window_ms: u64,
+        now_ms: u64,
+        dry_run: bool,
+    ) -> Result<Option<u64>, AptosTapError> {
+        let mut conn = self.get_redis_connection().await?;
+
+        // Drop the requests that have left the window, then count the rest.
+        let (num_requests, oldest_request): (u64, Vec<(String, f64)>) = redis::pipe()
+            .atomic()
+            .zrembyscore(key, 0, now_ms.saturating_sub(window_ms))
+            .ignore()
+            .zcard(key)
+            .zrange_withscores(key, 0, 0)
+            .query_async(&mut *conn)
+            .await
+            .map_err(|e| storage_error(format!("Failed to read redis key {}: {}", key, e)))?;
+        let oldest_request_ms = oldest_request.first().map(|(_, score)| *score as u64);
+        if num_requests >= max_requests as u64 {
+            return Ok(Some(retry_after_ms(oldest_request_ms, window_ms, now_ms)));
+        }
+        if dry_run {
+            return Ok(None);
+        }
+
+        // Requests made within the same millisecond need distinct members.
+        let member = format!("{}-{}", now_ms, rand::random::<u64>());
+        let (num_requests,): (u64,) = redis::pipe()
+            .atomic()
+            .zadd(key, &member, now_ms)
+            .ignore()
+            .pexpire(key, window_ms as usize)
+            .ignore()
+            .zcard(key)
+            .query_async(&mut *conn)
+            .await
+            .map_err(|e| storage_error(format!("Failed to update redis key {}: {}", key, e)))?;
+
+        // Check the limit again, to ensure there wasn't a race with another request.
+        if num_requests > max_requests as u64 {
+            let _: i64 = conn
+                .zrem(key, &member)
+                .await
+                .map_err(|e| storage_error(format!("Failed to update redis key {}: {}", key, e)))?;
+            return Ok(Some(retry_after_ms(oldest_request_ms, window_ms, now_ms)));
+        }
+        Ok(None)
+    }
+
+    async fn forget_latest_request(&self, key: &str) -> Result<(), AptosTapError> {
+        let mut conn = self.get_redis_connection().await?;
+        let _: Vec<(String, f64)> = conn
+            .zpopmax(key, 1)
+            .await
+            .map_err(|e| storage_error(format!("Failed to update redis key {}: {}", key, e)))?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_memory_ratelimit_store_sliding_window() {
+        let store = MemoryRatelimitStore::new(&MemoryRatelimitStoreConfig::default());
+        let window_ms = 1000;
+
+        // Two requests are allowed within the window, dry runs are not recorded.
+        for now_ms in [0, 100, 200] {
+            assert_eq!(
+                store
+                    .record_request("key", 2, window_ms, now_ms, true)
+                    .await
+                    .unwrap(),
+                None
+            );
+        }
+        assert_eq!(
+            store
+                .record_request("key", 2, window_ms, 0, false)
+                .await
+                .unwrap(),
+            None
+        );
+        assert_eq!(
+            store
+                .record_request("key", 2, window_ms, 400, false)
+                .await
+                .unwrap(),
+            None
+        );
+        assert_eq!(
+            store
+                .record_request("key", 2, window_ms, 500, false)
+                .await
+                .unwrap(),
+            Some(500)
+        );
+
+        // Other keys have their own window.
+        assert_eq!(
+            store
+                .record_request("other", 2, window_ms, 500, false)
+                .await
+                .unwrap(),
+            None
+        );
+
+        // Once the first request leaves the window, a new one is allowed.
+        assert_eq!(
+            store
+                .record_request("key", 2, window_ms, 1000, false)
+                .await
+                .unwrap(),
+            None
+        );
+        assert_eq!(
+            store
+                .record_request("key", 2, window_ms, 1100, false)
+                .await
+                .unwrap(),
+            Some(300)
+        );
+
+        // Forgetting the latest request frees up room in the window.
+        store.forget_latest_request("key").await.unwrap();
+        assert_eq!(
+            store
+                .record_request("key", 2, window_ms, 1100, false)
+                .await
+                .unwrap(),
+            None
+        );
+    }
+}
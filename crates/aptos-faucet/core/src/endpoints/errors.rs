@@ -195,22 +195,42 @@ impl AptosTapErrorCode {
 pub struct RejectionReason {
     reason: String,
     code: RejectionReasonCode,
+    /// If the request was rejected because it exceeded a quota, the details of
+    /// that quota.
+    #[oai(skip_serializing_if_is_none)]
+    quota: Option<QuotaExceeded>,
     #[oai(skip)]
     pub retry_after: Option<u64>,
 }
 
+/// Describes the quota a rejected request exceeded, so that clients can tell
+/// when to try again without parsing the reason.
+#[derive(Debug, Clone, Eq, Object, PartialEq)]
+pub struct QuotaExceeded {
+    /// What the quota is counted per, e.g. Ip or AuthToken.
+    pub identity: String,
+    /// The number of requests allowed within the window.
+    pub max_requests: u32,
+    /// The length of the sliding window, in seconds.
+    pub window_secs: u64,
+    /// The number of seconds until a request will be allowed again.
+    pub retry_after_secs: u64,
+}
+
 impl RejectionReason {
     pub fn new(reason: String, code: RejectionReasonCode) -> Self {
         if *USE_HELPFUL_ERRORS.get().unwrap() {
             Self {
                 reason,
                 code,
+                quota: None,
                 retry_after: None,
             }
         } else {
             Self {
                 reason: "keep dreaming mate".to_string(),
                 code: RejectionReasonCode::Hehe,
+                quota: None,
                 retry_after: None,
             }
         }
@@ -221,6 +241,20 @@ impl RejectionReason {
         self
     }
 
+    /// Attaches the details of the exceeded quota, which also determine the
+    /// Retry-After of the response.
+    pub fn quota(mut self, quota: QuotaExceeded) -> Self {
+        self.retry_after = Some(quota.retry_after_secs);
+        if *USE_HELPFUL_ERRORS.get().unwrap() {
+            self.quota = Some(quota);
+        }
+        self
+    }
+
+    pub fn get_quota(&self) -> Option<&QuotaExceeded> {
+        self.quota.as_ref()
+    }
+
     pub fn get_code(&self) -> RejectionReasonCode {
         self.code
     }
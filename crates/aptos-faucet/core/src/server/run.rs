@@ -690,6 +690,74 @@ mod test {
         Ok(())
     }
 
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn test_sliding_window_ratelimiter() -> Result<()> {
+        init();
+        let config_content = include_str!("../../../configs/testing_sliding_window_ratelimit.yaml");
+        let (port, _handle) = start_server(config_content).await?;
+
+        // The auth token quota allows a single request.
+        unwrap_reqwest_result(
+            reqwest::Client::new()
+                .post(get_fund_endpoint(port))
+                .body(get_fund_request(Some(10)).to_json_string())
+                .header(CONTENT_TYPE, "application/json")
+                .header(AUTHORIZATION, "Bearer test_token")
+                .send()
+                .await,
+        )
+        .await?;
+        let response = reqwest::Client::new()
+            .post(get_fund_endpoint(port))
+            .body(get_fund_request(Some(10)).to_json_string())
+            .header(CONTENT_TYPE, "application/json")
+            .header(AUTHORIZATION, "Bearer test_token")
+            .send()
+            .await?;
+        assert_eq!(response.status(), reqwest::StatusCode::TOO_MANY_REQUESTS);
+
+        // The rejected request didn't count towards the IP quota, so a request
+        // without an auth token still works.
+        unwrap_reqwest_result(
+            reqwest::Client::new()
+                .post(get_fund_endpoint(port))
+                .body(get_fund_request(Some(10)).to_json_string())
+                .header(CONTENT_TYPE, "application/json")
+                .send()
+                .await,
+        )
+        .await?;
+
+        // But now the IP quota is exhausted too, and the response says when to retry.
+        let response = reqwest::Client::new()
+            .post(get_fund_endpoint(port))
+            .body(get_fund_request(Some(10)).to_json_string())
+            .header(CONTENT_TYPE, "application/json")
+            .send()
+            .await?;
+        assert_eq!(response.status(), reqwest::StatusCode::TOO_MANY_REQUESTS);
+        let retry_after: u64 = response
+            .headers()
+            .get("Retry-After")
+            .expect("Retry-After header missing")
+            .to_str()?
+            .parse()?;
+        assert!(retry_after > 0 && retry_after <= 3600);
+        let aptos_error = AptosTapError::parse_from_json_string(&response.text().await?)
+            .expect("Failed to read response as AptosError");
+        let quotas: Vec<_> = aptos_error
+            .rejection_reasons
+            .iter()
+            .filter_map(|r| r.get_quota())
+            .collect();
+        assert_eq!(quotas.len(), 1);
+        assert_eq!(quotas[0].identity, "Ip");
+        assert_eq!(quotas[0].max_requests, 2);
+        assert_eq!(quotas[0].window_secs, 3600);
+
+        Ok(())
+    }
+
     // We skip this for now since we have no current need to use the TransferFunder.
     #[ignore]
     #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
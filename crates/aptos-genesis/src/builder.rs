@@ -36,7 +36,7 @@ use aptos_types::{
     transaction::Transaction,
     waypoint::Waypoint,
 };
-use aptos_vm_genesis::default_gas_schedule;
+use aptos_vm_genesis::{default_gas_schedule, AccountBalance};
 use rand::Rng;
 use serde::{de::DeserializeOwned, Serialize};
 use std::{
@@ -445,6 +445,37 @@ pub struct GenesisConfiguration {
     pub jwk_consensus_config_override: Option<OnChainJWKConsensusConfig>,
     pub initial_jwks: Vec<IssuerJWK>,
     pub keyless_groth16_vk_override: Option<Groth16VerificationKey>,
+    pub initial_accounts: Vec<AccountBalance>,
+}
+
+impl GenesisConfiguration {
+    /// The configuration of test networks, e.g., the local swarms.
+    pub fn default_for_testing() -> Self {
+        Self {
+            allow_new_validators: false,
+            epoch_duration_secs: ONE_DAY,
+            is_test: true,
+            min_stake: 0,
+            min_voting_threshold: 0,
+            max_stake: u64::MAX,
+            recurring_lockup_duration_secs: ONE_DAY,
+            required_proposer_stake: 0,
+            rewards_apy_percentage: 10,
+            voting_duration_secs: ONE_DAY / 24,
+            voting_power_increase_limit: 50,
+            employee_vesting_start: None,
+            employee_vesting_period_duration: None,
+            consensus_config: OnChainConsensusConfig::default_for_genesis(),
+            execution_config: OnChainExecutionConfig::default_for_genesis(),
+            gas_schedule: default_gas_schedule(),
+            initial_features_override: None,
+            randomness_config_override: None,
+            jwk_consensus_config_override: None,
+            initial_jwks: vec![],
+            keyless_groth16_vk_override: None,
+            initial_accounts: vec![],
+        }
+    }
 }
 
 pub type InitConfigFn = Arc<dyn Fn(usize, &mut NodeConfig, &mut NodeConfig) + Send + Sync>;
@@ -646,29 +677,7 @@ impl Builder {
             configs.push(validator.try_into()?);
         }
 
-        let mut genesis_config = GenesisConfiguration {
-            allow_new_validators: false,
-            epoch_duration_secs: ONE_DAY,
-            is_test: true,
-            min_stake: 0,
-            min_voting_threshold: 0,
-            max_stake: u64::MAX,
-            recurring_lockup_duration_secs: ONE_DAY,
-            required_proposer_stake: 0,
-            rewards_apy_percentage: 10,
-            voting_duration_secs: ONE_DAY / 24,
-            voting_power_increase_limit: 50,
-            employee_vesting_start: None,
-            employee_vesting_period_duration: None,
-            consensus_config: OnChainConsensusConfig::default_for_genesis(),
-            execution_config: OnChainExecutionConfig::default_for_genesis(),
-            gas_schedule: default_gas_schedule(),
-            initial_features_override: None,
-            randomness_config_override: None,
-            jwk_consensus_config_override: None,
-            initial_jwks: vec![],
-            keyless_groth16_vk_override: None,
-        };
+        let mut genesis_config = GenesisConfiguration::default_for_testing();
         if let Some(init_genesis_config) = &self.init_genesis_config {
             (init_genesis_config)(&mut genesis_config);
         }
@@ -0,0 +1,286 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Builds the genesis of a custom chain programmatically, for integration tests and private chains
+//! which would otherwise have to template a genesis layout and go through the CLI.
+
+use crate::{builder::GenesisConfiguration, config::ValidatorConfiguration, GenesisInfo};
+use anyhow::{ensure, Context};
+use aptos_crypto::ed25519::Ed25519PublicKey;
+use aptos_framework::ReleaseBundle;
+use aptos_types::{
+    account_address::AccountAddress,
+    chain_id::ChainId,
+    on_chain_config::{
+        Features, GasScheduleV2, OnChainConsensusConfig, OnChainExecutionConfig,
+        OnChainJWKConsensusConfig, OnChainRandomnessConfig,
+    },
+    transaction::Transaction,
+    waypoint::Waypoint,
+};
+use aptos_vm_genesis::AccountBalance;
+use std::{collections::HashSet, fs, path::Path};
+
+const GENESIS_BLOB: &str = "genesis.blob";
+const WAYPOINT_FILE: &str = "waypoint.txt";
+
+/// Builds a genesis transaction and its waypoint from the validators, the framework release, the
+/// initial accounts and the on-chain config overrides. The configuration starts from
+/// [`GenesisConfiguration::default_for_testing`].
+pub struct GenesisBuilder {
+    chain_id: ChainId,
+    root_key: Ed25519PublicKey,
+    framework: ReleaseBundle,
+    validators: Vec<ValidatorConfiguration>,
+    genesis_config: GenesisConfiguration,
+}
+
+impl GenesisBuilder {
+    pub fn new(chain_id: ChainId, root_key: Ed25519PublicKey, framework: ReleaseBundle) -> Self {
+        Self {
+            chain_id,
+            root_key,
+            framework,
+            validators: vec![],
+            genesis_config: GenesisConfiguration::default_for_testing(),
+        }
+    }
+
+    pub fn with_validator(mut self, validator: ValidatorConfiguration) -> Self {
+        self.validators.push(validator);
+        self
+    }
+
+    pub fn with_validators(
+        mut self,
+        validators: impl IntoIterator<Item = ValidatorConfiguration>,
+    ) -> Self {
+        self.validators.extend(validators);
+        self
+    }
+
+    /// Creates the account at genesis, funded with the balance (in octas).
+    pub fn with_account(mut self, account_address: AccountAddress, balance: u64) -> Self {
+        self.genesis_config.initial_accounts.push(AccountBalance {
+            account_address,
+            balance,
+        });
+        self
+    }
+
+    pub fn with_features(mut self, features: Features) -> Self {
+        self.genesis_config.initial_features_override = Some(features);
+        self
+    }
+
+    pub fn with_consensus_config(mut self, consensus_config: OnChainConsensusConfig) -> Self {
+        self.genesis_config.consensus_config = consensus_config;
+        self
+    }
+
+    pub fn with_execution_config(mut self, execution_config: OnChainExecutionConfig) -> Self {
+        self.genesis_config.execution_config = execution_config;
+        self
+    }
+
+    pub fn with_gas_schedule(mut self, gas_schedule: GasScheduleV2) -> Self {
+        self.genesis_config.gas_schedule = gas_schedule;
+        self
+    }
+
+    pub fn with_randomness_config(mut self, randomness_config: OnChainRandomnessConfig) -> Self {
+        self.genesis_config.randomness_config_override = Some(randomness_config);
+        self
+    }
+
+    pub fn with_jwk_consensus_config(
+        mut self,
+        jwk_consensus_config: OnChainJWKConsensusConfig,
+    ) -> Self {
+        self.genesis_config.jwk_consensus_config_override = Some(jwk_consensus_config);
+        self
+    }
+
+    /// Changes any other part of the configuration, e.g., the staking parameters.
+    pub fn with_genesis_config(mut self, update: impl FnOnce(&mut GenesisConfiguration)) -> Self {
+        update(&mut self.genesis_config);
+        self
+    }
+
+    fn validate(&self) -> anyhow::Result<()> {
+        ensure!(
+            !self.validators.is_empty(),
+            "Genesis requires at least one validator"
+        );
+
+        // Genesis doesn't fund the accounts which already exist, so the initial accounts must be
+        // unique and distinct from the validator accounts.
+        let mut addresses = HashSet::new();
+        for validator in &self.validators {
+            addresses.insert(AccountAddress::from(validator.owner_account_address));
+            addresses.insert(AccountAddress::from(validator.operator_account_address));
+            addresses.insert(AccountAddress::from(validator.voter_account_address));
+        }
+        for account in &self.genesis_config.initial_accounts {
+            ensure!(
+                addresses.insert(account.account_address),
+                "Initial account {} is either duplicated or a validator account",
+                account.account_address
+            );
+        }
+        Ok(())
+    }
+
+    /// Generates the genesis transaction and its waypoint.
+    pub fn build(self) -> anyhow::Result<(Transaction, Waypoint)> {
+        self.validate()?;
+        let mut genesis_info = GenesisInfo::new(
+            self.chain_id,
+            self.root_key,
+            self.validators,
+            self.framework,
+            &self.genesis_config,
+        )?;
+        let waypoint = genesis_info.generate_waypoint()?;
+        Ok((genesis_info.get_genesis().clone(), waypoint))
+    }
+
+    /// Generates the genesis, and writes it to `genesis.blob` and its waypoint to `waypoint.txt`
+    /// in the directory, as the CLI does.
+    pub fn write_to_dir(self, dir: &Path) -> anyhow::Result<(Transaction, Waypoint)> {
+        let (genesis, waypoint) = self.build()?;
+        fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create directory {}", dir.display()))?;
+        fs::write(dir.join(GENESIS_BLOB), bcs::to_bytes(&genesis)?)
+            .context("Failed to write the genesis blob")?;
+        fs::write(dir.join(WAYPOINT_FILE), waypoint.to_string())
+            .context("Failed to write the waypoint")?;
+        Ok((genesis, waypoint))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{config::HostAndPort, keys::generate_key_objects};
+    use aptos_crypto::{bls12381, PrivateKey};
+    use aptos_db::AptosDB;
+    use aptos_executor::db_bootstrapper::maybe_bootstrap;
+    use aptos_keygen::KeyGen;
+    use aptos_storage_interface::{
+        state_store::state_view::db_state_view::LatestDbStateCheckpointView, DbReaderWriter,
+    };
+    use aptos_temppath::TempPath;
+    use aptos_types::{
+        account_config::AccountResource,
+        on_chain_config::{OnChainConfig, ValidatorSet},
+        state_store::MoveResourceExt,
+    };
+    use aptos_vm::aptos_vm::AptosVMBlockExecutor;
+
+    fn validator(keygen: &mut KeyGen) -> ValidatorConfiguration {
+        let (_, _, private_identity, _) = generate_key_objects(keygen).unwrap();
+        let account_address = private_identity.account_address;
+        let account_public_key = private_identity.account_private_key.public_key();
+        ValidatorConfiguration {
+            owner_account_address: account_address.into(),
+            owner_account_public_key: account_public_key.clone(),
+            operator_account_address: account_address.into(),
+            operator_account_public_key: account_public_key.clone(),
+            voter_account_address: account_address.into(),
+            voter_account_public_key: account_public_key,
+            consensus_public_key: Some(private_identity.consensus_private_key.public_key()),
+            proof_of_possession: Some(bls12381::ProofOfPossession::create(
+                &private_identity.consensus_private_key,
+            )),
+            validator_network_public_key: Some(
+                private_identity.validator_network_private_key.public_key(),
+            ),
+            validator_host: Some(HostAndPort::local(6180).unwrap()),
+            full_node_network_public_key: Some(
+                private_identity.full_node_network_private_key.public_key(),
+            ),
+            full_node_host: Some(HostAndPort::local(6182).unwrap()),
+            stake_amount: 100_000_000_000_000,
+            commission_percentage: 0,
+            join_during_genesis: true,
+        }
+    }
+
+    fn genesis_builder(keygen: &mut KeyGen) -> GenesisBuilder {
+        GenesisBuilder::new(
+            ChainId::test(),
+            keygen.generate_ed25519_private_key().public_key(),
+            aptos_cached_packages::head_release_bundle().clone(),
+        )
+    }
+
+    #[test]
+    fn test_build_and_bootstrap_custom_genesis() {
+        let mut keygen = KeyGen::from_seed([0; 32]);
+        let validator = validator(&mut keygen);
+        let validator_address = AccountAddress::from(validator.owner_account_address);
+        let account_address = AccountAddress::from_hex_literal("0xcafe").unwrap();
+
+        let dir = TempPath::new();
+        let (genesis, waypoint) = genesis_builder(&mut keygen)
+            .with_validator(validator)
+            .with_account(account_address, 1_000_000)
+            .write_to_dir(dir.path())
+            .unwrap();
+
+        // The written files match the generated genesis.
+        assert_eq!(
+            bcs::from_bytes::<Transaction>(&fs::read(dir.path().join(GENESIS_BLOB)).unwrap())
+                .unwrap(),
+            genesis
+        );
+        assert_eq!(
+            fs::read_to_string(dir.path().join(WAYPOINT_FILE)).unwrap(),
+            waypoint.to_string()
+        );
+
+        // A node can bootstrap its DB from the genesis and the waypoint.
+        let db_dir = TempPath::new();
+        let db_rw = DbReaderWriter::new(AptosDB::new_for_test(&db_dir));
+        assert!(
+            maybe_bootstrap::<AptosVMBlockExecutor>(&db_rw, &genesis, waypoint)
+                .unwrap()
+                .is_some()
+        );
+
+        let state_view = db_rw.reader.latest_state_checkpoint_view().unwrap();
+        let validator_set = ValidatorSet::fetch_config(&state_view).unwrap();
+        assert_eq!(validator_set.active_validators(), vec![validator_address]);
+        assert!(
+            AccountResource::fetch_move_resource(&state_view, &account_address)
+                .unwrap()
+                .is_some()
+        );
+    }
+
+    #[test]
+    fn test_invalid_genesis_configurations() {
+        let mut keygen = KeyGen::from_seed([0; 32]);
+
+        // At least one validator is required.
+        assert!(genesis_builder(&mut keygen).build().is_err());
+
+        // Initial accounts can neither be duplicated nor be validator accounts.
+        let validator = validator(&mut keygen);
+        let validator_address = AccountAddress::from(validator.owner_account_address);
+        assert!(genesis_builder(&mut keygen)
+            .with_validator(validator.clone())
+            .with_account(validator_address, 1)
+            .build()
+            .is_err());
+
+        let account_address = AccountAddress::from_hex_literal("0xcafe").unwrap();
+        assert!(genesis_builder(&mut keygen)
+            .with_validator(validator)
+            .with_account(account_address, 1)
+            .with_account(account_address, 2)
+            .build()
+            .is_err());
+    }
+}
@@ -5,6 +5,7 @@
 
 pub mod builder;
 pub mod config;
+pub mod genesis_builder;
 pub mod keys;
 pub mod mainnet;
 
@@ -33,7 +34,7 @@ use aptos_types::{
     waypoint::Waypoint,
 };
 use aptos_vm::aptos_vm::AptosVMBlockExecutor;
-use aptos_vm_genesis::Validator;
+use aptos_vm_genesis::{AccountBalance, Validator};
 use std::convert::TryInto;
 
 /// Holder object for all pieces needed to generate a genesis transaction
@@ -80,6 +81,8 @@ pub struct GenesisInfo {
     pub jwk_consensus_config_override: Option<OnChainJWKConsensusConfig>,
     pub initial_jwks: Vec<IssuerJWK>,
     pub keyless_groth16_vk_override: Option<Groth16VerificationKey>,
+    /// Accounts created and funded at genesis, in addition to the validators
+    pub initial_accounts: Vec<AccountBalance>,
 }
 
 impl GenesisInfo {
@@ -121,6 +124,7 @@ impl GenesisInfo {
             jwk_consensus_config_override: genesis_config.jwk_consensus_config_override.clone(),
             initial_jwks: genesis_config.initial_jwks.clone(),
             keyless_groth16_vk_override: genesis_config.keyless_groth16_vk_override.clone(),
+            initial_accounts: genesis_config.initial_accounts.clone(),
         })
     }
 
@@ -158,6 +162,7 @@ impl GenesisInfo {
                 jwk_consensus_config_override: self.jwk_consensus_config_override.clone(),
                 initial_jwks: self.initial_jwks.clone(),
                 keyless_groth16_vk_override: self.keyless_groth16_vk_override.clone(),
+                initial_accounts: self.initial_accounts.clone(),
             },
             &self.consensus_config,
             &self.execution_config,
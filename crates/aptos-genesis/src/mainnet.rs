@@ -145,6 +145,7 @@ impl MainnetGenesisInfo {
                 jwk_consensus_config_override: self.jwk_consensus_config_override.clone(),
                 initial_jwks: vec![],
                 keyless_groth16_vk_override: None,
+                initial_accounts: vec![],
             },
         )
     }
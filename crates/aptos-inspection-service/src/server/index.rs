@@ -4,7 +4,8 @@
 use crate::{
     server::utils::CONTENT_TYPE_TEXT, CONFIGURATION_PATH, CONSENSUS_HEALTH_CHECK_PATH,
     FORGE_METRICS_PATH, JSON_METRICS_PATH, METRICS_PATH, PEER_INFORMATION_PATH,
-    SYSTEM_INFORMATION_PATH,
+    PRUNER_PROGRESS_PATH, SLOW_COMMITS_PATH, STATE_READ_PROFILE_PATH, SYSTEM_INFORMATION_PATH,
+    VALIDATOR_PERFORMANCE_PATH,
 };
 use hyper::{Body, StatusCode};
 
@@ -31,7 +32,11 @@ fn get_index_response() -> String {
     index_response.push(format!("\t- {}", JSON_METRICS_PATH));
     index_response.push(format!("\t- {}", METRICS_PATH));
     index_response.push(format!("\t- {}", PEER_INFORMATION_PATH));
+    index_response.push(format!("\t- {}", PRUNER_PROGRESS_PATH));
+    index_response.push(format!("\t- {}", SLOW_COMMITS_PATH));
+    index_response.push(format!("\t- {}", STATE_READ_PROFILE_PATH));
     index_response.push(format!("\t- {}", SYSTEM_INFORMATION_PATH));
+    index_response.push(format!("\t- {}", VALIDATOR_PERFORMANCE_PATH));
 
     index_response.join("\n") // Separate each entry with a newline
 }
@@ -7,6 +7,7 @@ use crate::server::{
     utils::{CONTENT_TYPE_JSON, CONTENT_TYPE_TEXT},
 };
 use aptos_config::config::NodeConfig;
+use aptos_metrics_core::exemplars::{OpenMetricsEncoder, OPENMETRICS_CONTENT_TYPE};
 use hyper::{Body, StatusCode};
 use prometheus::TextEncoder;
 
@@ -74,3 +75,13 @@ pub fn handle_metrics_request() -> (StatusCode, Body, String) {
     let buffer = utils::get_encoded_metrics(TextEncoder::new());
     (StatusCode::OK, Body::from(buffer), CONTENT_TYPE_TEXT.into())
 }
+
+/// Handles a new metrics request (with OpenMetrics encoding, including exemplars)
+pub fn handle_openmetrics_request() -> (StatusCode, Body, String) {
+    let buffer = utils::get_encoded_metrics(OpenMetricsEncoder::new());
+    (
+        StatusCode::OK,
+        Body::from(buffer),
+        OPENMETRICS_CONTENT_TYPE.into(),
+    )
+}
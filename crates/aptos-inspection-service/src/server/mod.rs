@@ -22,8 +22,12 @@ mod index;
 mod json_encoder;
 mod metrics;
 mod peer_information;
+mod pruner_progress;
+mod slow_commits;
+mod state_read_profile;
 mod system_information;
 pub mod utils;
+mod validator_performance;
 
 #[cfg(test)]
 mod tests;
@@ -36,10 +40,15 @@ pub const INDEX_PATH: &str = "/";
 pub const JSON_METRICS_PATH: &str = "/json_metrics";
 pub const METRICS_PATH: &str = "/metrics";
 pub const PEER_INFORMATION_PATH: &str = "/peer_information";
+pub const PRUNER_PROGRESS_PATH: &str = "/pruner_progress";
+pub const SLOW_COMMITS_PATH: &str = "/slow_commits";
+pub const STATE_READ_PROFILE_PATH: &str = "/state_read_profile";
 pub const SYSTEM_INFORMATION_PATH: &str = "/system_information";
+pub const VALIDATOR_PERFORMANCE_PATH: &str = "/validator_performance";
 
 // Useful string constants
 pub const HEADER_CONTENT_TYPE: &str = "Content-Type";
+pub const OPENMETRICS_MEDIA_TYPE: &str = "application/openmetrics-text";
 pub const INVALID_ENDPOINT_MESSAGE: &str = "The requested endpoint is invalid!";
 pub const UNEXPECTED_ERROR_MESSAGE: &str = "An unexpected error was encountered!";
 
@@ -134,8 +143,18 @@ async fn serve_requests(
         },
         METRICS_PATH => {
             // /metrics
-            // Exposes text encoded metrics
-            metrics::handle_metrics_request()
+            // Exposes text encoded metrics (or OpenMetrics encoded, with
+            // exemplars, if the scraper accepts it)
+            let accepts_openmetrics = req
+                .headers()
+                .get(hyper::header::ACCEPT)
+                .and_then(|accept| accept.to_str().ok())
+                .map_or(false, |accept| accept.contains(OPENMETRICS_MEDIA_TYPE));
+            if accepts_openmetrics {
+                metrics::handle_openmetrics_request()
+            } else {
+                metrics::handle_metrics_request()
+            }
         },
         PEER_INFORMATION_PATH => {
             // /peer_information
@@ -146,11 +165,31 @@ async fn serve_requests(
                 peers_and_metadata,
             )
         },
+        PRUNER_PROGRESS_PATH => {
+            // /pruner_progress
+            // Exposes the progress, backlog and ETA of the DB pruners
+            pruner_progress::handle_pruner_progress_request()
+        },
+        SLOW_COMMITS_PATH => {
+            // /slow_commits
+            // Exposes the latest slow DB commits, with the breakdowns of their batches
+            slow_commits::handle_slow_commits_request(&node_config)
+        },
+        STATE_READ_PROFILE_PATH => {
+            // /state_read_profile
+            // Exposes the hot keys and the latest state reads
+            state_read_profile::handle_state_read_profile_request(&node_config)
+        },
         SYSTEM_INFORMATION_PATH => {
             // /system_information
             // Exposes the system and build information
             system_information::handle_system_information_request(node_config)
         },
+        VALIDATOR_PERFORMANCE_PATH => {
+            // /validator_performance
+            // Exposes the recent proposal, voting and pipeline performance of this validator
+            validator_performance::handle_validator_performance_request(&node_config)
+        },
         _ => {
             // Handle the invalid path
             (
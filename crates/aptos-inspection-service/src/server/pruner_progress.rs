@@ -0,0 +1,45 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::server::utils::CONTENT_TYPE_JSON;
+use aptos_storage_interface::pruner_progress::PRUNER_PROGRESS;
+use hyper::{Body, StatusCode};
+use serde_json::json;
+
+/// Handles a new pruner progress request
+pub fn handle_pruner_progress_request() -> (StatusCode, Body, String) {
+    (
+        StatusCode::OK,
+        Body::from(get_pruner_progress_json()),
+        CONTENT_TYPE_JSON.into(),
+    )
+}
+
+/// Returns a JSON formatted string with the progress of each DB pruner
+fn get_pruner_progress_json() -> String {
+    let pruners: serde_json::Map<_, _> = PRUNER_PROGRESS
+        .get_all()
+        .into_iter()
+        .map(|(pruner_name, progress)| {
+            let eta_secs = progress.eta().map(|eta| eta.as_secs());
+            (
+                pruner_name.to_string(),
+                json!({
+                    "progress": progress.progress,
+                    "target": progress.target,
+                    "backlog": progress.backlog(),
+                    "versions_pruned": progress.versions_pruned,
+                    "versions_per_second": progress.versions_per_second,
+                    "eta_secs": eta_secs,
+                    "pacing_delay_ms": progress.pacing_delay.as_millis() as u64,
+                }),
+            )
+        })
+        .collect();
+
+    // Return the progress as a JSON string
+    match serde_json::to_string(&json!({ "pruners": pruners })) {
+        Ok(progress) => progress,
+        Err(error) => format!("Failed to get the pruner progress! Error: {}", error),
+    }
+}
@@ -0,0 +1,82 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::server::utils::{CONTENT_TYPE_JSON, CONTENT_TYPE_TEXT};
+use aptos_config::config::NodeConfig;
+use aptos_storage_interface::commit_recorder::COMMIT_RECORDER;
+use hyper::{Body, StatusCode};
+use serde_json::json;
+use std::time::{Duration, UNIX_EPOCH};
+
+// The message to display when the slow commit recorder is disabled
+pub const SLOW_COMMITS_DISABLED_MESSAGE: &str =
+    "This endpoint is disabled! Enable it in the node config at storage.slow_commit_recorder.enable: true";
+
+/// Handles a new slow commits request
+pub fn handle_slow_commits_request(node_config: &NodeConfig) -> (StatusCode, Body, String) {
+    // Only return the slow commits if the recorder is enabled
+    if node_config.storage.slow_commit_recorder.enable {
+        (
+            StatusCode::OK,
+            Body::from(get_slow_commits_json()),
+            CONTENT_TYPE_JSON.into(),
+        )
+    } else {
+        (
+            StatusCode::FORBIDDEN,
+            Body::from(SLOW_COMMITS_DISABLED_MESSAGE),
+            CONTENT_TYPE_TEXT.into(),
+        )
+    }
+}
+
+fn as_micros(duration: Duration) -> u64 {
+    duration.as_micros() as u64
+}
+
+/// Returns a JSON formatted string with the latest slow commits and their batches
+fn get_slow_commits_json() -> String {
+    let slow_commits: Vec<_> = COMMIT_RECORDER
+        .slow_commits()
+        .into_iter()
+        .rev()
+        .map(|commit| {
+            let batches: Vec<_> = commit
+                .batches
+                .iter()
+                .map(|batch| {
+                    json!({
+                        "db_name": batch.db_name,
+                        "num_rows": batch.num_rows,
+                        "bytes": batch.bytes,
+                        "finished_after_us": as_micros(
+                            batch.finished_at.saturating_duration_since(commit.started_at)
+                        ),
+                        "serialization_us": as_micros(batch.serialization),
+                        "build_us": as_micros(batch.build),
+                        "wal_us": as_micros(batch.wal),
+                        "memtable_us": as_micros(batch.memtable),
+                        "write_delay_us": as_micros(batch.write_delay),
+                        "write_us": as_micros(batch.write),
+                    })
+                })
+                .collect();
+            json!({
+                "operation": commit.operation,
+                "version": commit.version,
+                "timestamp_usecs": commit
+                    .timestamp
+                    .duration_since(UNIX_EPOCH)
+                    .map_or(0, as_micros),
+                "latency_us": as_micros(commit.latency),
+                "batches": batches,
+            })
+        })
+        .collect();
+
+    // Return the slow commits as a JSON string
+    match serde_json::to_string(&json!({ "slow_commits": slow_commits })) {
+        Ok(slow_commits) => slow_commits,
+        Err(error) => format!("Failed to get the slow commits! Error: {}", error),
+    }
+}
@@ -0,0 +1,74 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::server::utils::{CONTENT_TYPE_JSON, CONTENT_TYPE_TEXT};
+use aptos_config::config::NodeConfig;
+use aptos_storage_interface::state_read_profiler::STATE_READ_PROFILER;
+use hyper::{Body, StatusCode};
+use serde_json::json;
+
+// The message to display when the state read profiler is disabled
+pub const STATE_READ_PROFILE_DISABLED_MESSAGE: &str =
+    "This endpoint is disabled! Enable it in the node config at storage.state_read_profiler.enable: true";
+
+// The number of hot keys to return
+const NUM_HOT_KEYS: usize = 100;
+
+/// Handles a new state read profile request
+pub fn handle_state_read_profile_request(node_config: &NodeConfig) -> (StatusCode, Body, String) {
+    // Only return the profile if the profiler is enabled
+    if node_config.storage.state_read_profiler.enable {
+        (
+            StatusCode::OK,
+            Body::from(get_state_read_profile_json()),
+            CONTENT_TYPE_JSON.into(),
+        )
+    } else {
+        (
+            StatusCode::FORBIDDEN,
+            Body::from(STATE_READ_PROFILE_DISABLED_MESSAGE),
+            CONTENT_TYPE_TEXT.into(),
+        )
+    }
+}
+
+/// Returns a JSON formatted string with the hot keys and the latest state reads
+fn get_state_read_profile_json() -> String {
+    let hot_keys: Vec<_> = STATE_READ_PROFILER
+        .hot_keys(NUM_HOT_KEYS)
+        .into_iter()
+        .map(|hot_key| {
+            json!({
+                "state_key": format!("{:?}", hot_key.state_key),
+                "num_reads": hot_key.num_reads,
+                "num_cache_misses": hot_key.num_cache_misses,
+                "block_reads": hot_key.block_reads,
+            })
+        })
+        .collect();
+    let reads: Vec<_> = STATE_READ_PROFILER
+        .records()
+        .into_iter()
+        .rev()
+        .map(|record| {
+            json!({
+                "state_key": format!("{:?}", record.state_key),
+                "version": record.version,
+                "value_version": record.value_version,
+                "cache_hit": record.cache_hit,
+                "block_cache_hits": record.block_cache_hits,
+                "block_reads": record.block_reads,
+                "sorted_runs_probed": record.sorted_runs_probed,
+                "internal_keys_skipped": record.internal_keys_skipped,
+                "latency_us": record.latency.as_micros() as u64,
+            })
+        })
+        .collect();
+
+    // Return the profile as a JSON string
+    let profile = json!({ "hot_keys": hot_keys, "reads": reads });
+    match serde_json::to_string(&profile) {
+        Ok(profile) => profile,
+        Err(error) => format!("Failed to get the state read profile! Error: {}", error),
+    }
+}
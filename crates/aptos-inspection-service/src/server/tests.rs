@@ -5,24 +5,35 @@ use crate::{
     server::{
         configuration::CONFIGURATION_DISABLED_MESSAGE,
         peer_information::PEER_INFO_DISABLED_MESSAGE, serve_requests,
+        slow_commits::SLOW_COMMITS_DISABLED_MESSAGE,
+        state_read_profile::STATE_READ_PROFILE_DISABLED_MESSAGE,
         system_information::SYS_INFO_DISABLED_MESSAGE, utils::get_all_metrics,
+        validator_performance::VALIDATOR_SELF_REPORT_METRIC,
     },
     CONFIGURATION_PATH, FORGE_METRICS_PATH, INDEX_PATH, JSON_METRICS_PATH, METRICS_PATH,
-    PEER_INFORMATION_PATH, SYSTEM_INFORMATION_PATH,
+    PEER_INFORMATION_PATH, PRUNER_PROGRESS_PATH, SLOW_COMMITS_PATH, STATE_READ_PROFILE_PATH,
+    SYSTEM_INFORMATION_PATH, VALIDATOR_PERFORMANCE_PATH,
 };
 use aptos_config::config::{AptosDataClientConfig, BaseConfig, NodeConfig};
 use aptos_data_client::client::AptosDataClient;
 use aptos_network::application::{interface::NetworkClient, storage::PeersAndMetadata};
-use aptos_storage_interface::DbReader;
+use aptos_storage_interface::{
+    commit_recorder::COMMIT_RECORDER,
+    pruner_progress::{PrunerProgress, PRUNER_PROGRESS},
+    DbReader,
+};
 use aptos_storage_service_client::StorageServiceClient;
 use aptos_time_service::TimeService;
 use assert_approx_eq::assert_approx_eq;
 use futures::executor::block_on;
 use hyper::{body, Body, Method, Request, Response, StatusCode};
 use once_cell::sync::Lazy;
-use prometheus::{proto::MetricFamily, register_int_counter, Counter, IntCounter, Opts, Registry};
+use prometheus::{
+    proto::MetricFamily, register_gauge_vec, register_int_counter, Counter, IntCounter, Opts,
+    Registry,
+};
 use rusty_fork::rusty_fork_test;
-use std::{collections::HashMap, io::read_to_string, string::String, sync::Arc};
+use std::{collections::HashMap, io::read_to_string, string::String, sync::Arc, time::Duration};
 
 // This metrics counter only exists in this test context; the rest of the
 // system's metrics counters don't exist, so we need to add this for tests.
@@ -180,6 +191,119 @@ async fn test_inspect_peer_information() {
     assert!(response_body_string.contains("State sync metadata"));
 }
 
+#[tokio::test]
+async fn test_inspect_pruner_progress() {
+    // Report the progress of a pruner
+    PRUNER_PROGRESS.update("test_pruner", PrunerProgress {
+        progress: 100,
+        target: 300,
+        versions_pruned: 100,
+        versions_per_second: 50.0,
+        pacing_delay: Duration::from_millis(10),
+    });
+
+    // Ping the pruner progress endpoint
+    let config = NodeConfig::get_default_validator_config();
+    let mut response = send_get_request_to_path(&config, PRUNER_PROGRESS_PATH).await;
+    let response_body = body::to_bytes(response.body_mut()).await.unwrap();
+    let response_body_string = read_to_string(response_body.as_ref()).unwrap();
+
+    // Verify that the response contains the expected information
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(response_body_string.contains("test_pruner"));
+    assert!(response_body_string.contains("\"backlog\":200"));
+    assert!(response_body_string.contains("\"eta_secs\":4"));
+}
+
+#[tokio::test]
+async fn test_inspect_slow_commits() {
+    // Create a validator node config
+    let mut config = NodeConfig::get_default_validator_config();
+
+    // Disable the slow commit recorder and ping the endpoint
+    config.storage.slow_commit_recorder.enable = false;
+    let mut response = send_get_request_to_path(&config, SLOW_COMMITS_PATH).await;
+    let response_body = body::to_bytes(response.body_mut()).await.unwrap();
+
+    // Verify that the response contains an error
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    assert_eq!(response_body, SLOW_COMMITS_DISABLED_MESSAGE);
+
+    // Record a slow commit, enable the recorder and ping the endpoint
+    COMMIT_RECORDER.enable(Duration::ZERO, 10);
+    COMMIT_RECORDER
+        .start_commit("pre_commit_ledger", 10)
+        .finish();
+    config.storage.slow_commit_recorder.enable = true;
+    let mut response = send_get_request_to_path(&config, SLOW_COMMITS_PATH).await;
+    let response_body = body::to_bytes(response.body_mut()).await.unwrap();
+    let response_body_string = read_to_string(response_body.as_ref()).unwrap();
+
+    // Verify that the response contains the expected information
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(response_body_string.contains("slow_commits"));
+    assert!(response_body_string.contains("pre_commit_ledger"));
+}
+
+#[tokio::test]
+async fn test_inspect_state_read_profile() {
+    // Create a validator node config
+    let mut config = NodeConfig::get_default_validator_config();
+
+    // Disable the state read profiler and ping the endpoint
+    config.storage.state_read_profiler.enable = false;
+    let mut response = send_get_request_to_path(&config, STATE_READ_PROFILE_PATH).await;
+    let response_body = body::to_bytes(response.body_mut()).await.unwrap();
+
+    // Verify that the response contains an error
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    assert_eq!(response_body, STATE_READ_PROFILE_DISABLED_MESSAGE);
+
+    // Enable the state read profiler and ping the endpoint
+    config.storage.state_read_profiler.enable = true;
+    let mut response = send_get_request_to_path(&config, STATE_READ_PROFILE_PATH).await;
+    let response_body = body::to_bytes(response.body_mut()).await.unwrap();
+    let response_body_string = read_to_string(response_body.as_ref()).unwrap();
+
+    // Verify that the response contains the expected information
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(response_body_string.contains("hot_keys"));
+    assert!(response_body_string.contains("reads"));
+}
+
+#[tokio::test]
+async fn test_inspect_validator_performance() {
+    // Ping the endpoint on a VFN and verify that the response contains an error
+    let config = NodeConfig::get_default_vfn_config();
+    let response = send_get_request_to_path(&config, VALIDATOR_PERFORMANCE_PATH).await;
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    // Report the performance of the validator (consensus is not running in this test context)
+    let self_report =
+        register_gauge_vec!(VALIDATOR_SELF_REPORT_METRIC, "Validator self report", &[
+            "metric", "window"
+        ])
+        .unwrap();
+    self_report
+        .with_label_values(&["proposal_success_rate", "10m"])
+        .set(0.5);
+    self_report
+        .with_label_values(&["vote_latency_p99_ms", "10m"])
+        .set(f64::NAN);
+
+    // Ping the endpoint on a validator
+    let config = NodeConfig::get_default_validator_config();
+    let mut response = send_get_request_to_path(&config, VALIDATOR_PERFORMANCE_PATH).await;
+    let response_body = body::to_bytes(response.body_mut()).await.unwrap();
+    let response_body_string = read_to_string(response_body.as_ref()).unwrap();
+
+    // Verify that the response contains the expected information
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(response_body_string.contains("\"10m\""));
+    assert!(response_body_string.contains("\"proposal_success_rate\":0.5"));
+    assert!(response_body_string.contains("\"vote_latency_p99_ms\":null"));
+}
+
 rusty_fork_test! {
 #[test]
 fn test_gather_metrics() {
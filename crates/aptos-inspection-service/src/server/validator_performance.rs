@@ -0,0 +1,73 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::server::{
+    utils,
+    utils::{CONTENT_TYPE_JSON, CONTENT_TYPE_TEXT},
+};
+use aptos_config::config::NodeConfig;
+use hyper::{Body, StatusCode};
+use serde_json::{json, Map, Value};
+
+// The name of the gauges consensus reports this validator's performance with
+pub const VALIDATOR_SELF_REPORT_METRIC: &str = "aptos_consensus_validator_self_report";
+
+/// Handles a new validator performance request
+pub fn handle_validator_performance_request(
+    node_config: &NodeConfig,
+) -> (StatusCode, Body, String) {
+    // Verify the node is a validator. If not, return an error.
+    if !node_config.base.role.is_validator() {
+        return (
+            StatusCode::BAD_REQUEST,
+            Body::from("This node is not a validator!"),
+            CONTENT_TYPE_TEXT.into(),
+        );
+    }
+
+    (
+        StatusCode::OK,
+        Body::from(get_validator_performance_json()),
+        CONTENT_TYPE_JSON.into(),
+    )
+}
+
+/// Returns a JSON formatted string with the performance of this validator,
+/// grouped by sliding window (e.g., {"10m": {"proposal_success_rate": 0.9, ...}}).
+fn get_validator_performance_json() -> String {
+    let mut windows: Map<String, Value> = Map::new();
+    for (name, value) in utils::get_all_metrics() {
+        let Some(labels) = name
+            .strip_prefix(VALIDATOR_SELF_REPORT_METRIC)
+            .and_then(|labels| labels.strip_prefix('{'))
+            .and_then(|labels| labels.strip_suffix('}'))
+        else {
+            continue;
+        };
+        let label = |label_name: &str| {
+            labels.split(',').find_map(|label| {
+                label
+                    .strip_prefix(label_name)
+                    .and_then(|label| label.strip_prefix('='))
+            })
+        };
+        let (Some(metric), Some(window)) = (label("metric"), label("window")) else {
+            continue;
+        };
+
+        // Metrics without any sample in the window are reported as null
+        let value = value.parse::<f64>().ok().filter(|value| value.is_finite());
+        if let Value::Object(metrics) = windows
+            .entry(window.to_string())
+            .or_insert_with(|| json!({}))
+        {
+            metrics.insert(metric.to_string(), json!(value));
+        }
+    }
+
+    // Return the performance as a JSON string
+    match serde_json::to_string(&json!({ "windows": windows })) {
+        Ok(performance) => performance,
+        Err(error) => format!("Failed to get the validator performance! Error: {}", error),
+    }
+}
@@ -4,13 +4,11 @@
 use aptos_crypto::{
     bls12381,
     ed25519::{Ed25519PrivateKey, Ed25519PublicKey},
+    rng::DefaultRng,
     x25519, CryptoMaterialError, PrivateKey, Uniform,
 };
 use aptos_types::{account_address::AccountAddress, transaction::authenticator::AuthenticationKey};
-use rand::{
-    rngs::{OsRng, StdRng},
-    Rng, SeedableRng,
-};
+use rand::{rngs::StdRng, Rng, SeedableRng};
 
 /// Ed25519 key generator.
 #[derive(Debug)]
@@ -23,9 +21,9 @@ impl KeyGen {
     }
 
     /// Constructs a key generator with a random seed.
-    /// The random seed itself is generated using the OS rng.
+    /// The random seed itself is generated using the OS rng (see `aptos_crypto::rng::DefaultRng`).
     pub fn from_os_rng() -> Self {
-        let mut seed_rng = OsRng;
+        let mut seed_rng = DefaultRng;
         let seed: [u8; 32] = seed_rng.gen();
         Self::from_seed(seed)
     }
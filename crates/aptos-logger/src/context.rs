@@ -0,0 +1,149 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Contextual key/values attached to all the logs emitted within a scope, e.g., the block id and
+//! round while executing a block, so that logs can be correlated without repeating the fields in
+//! each of them.
+//!
+//! ```
+//! use aptos_logger::{context::LogContext, info};
+//!
+//! async fn execute_block(block_id: u64, round: u64) {
+//!     LogContext::current()
+//!         .with("block_id", &block_id)
+//!         .with("round", &round)
+//!         .scope(async {
+//!             // Logs `block_id` and `round` along with `num_txns`
+//!             info!(num_txns = 10, "Executing block");
+//!         })
+//!         .await
+//! }
+//! ```
+//!
+//! The context of a task is not inherited by the tasks it spawns through `tokio::spawn`. Use
+//! [`spawn`] and [`spawn_on`] instead to propagate it.
+
+use crate::{Key, Schema, Value, Visitor};
+use serde::Serialize;
+use std::{collections::BTreeMap, future::Future, sync::Arc};
+use tokio::{runtime::Handle, task::JoinHandle};
+
+tokio::task_local! {
+    static CONTEXT: LogContext;
+}
+
+/// A set of key/values that are attached to the logs emitted within its scope. Keys given at the
+/// log site take precedence over the ones of the context.
+#[derive(Clone, Debug, Default)]
+pub struct LogContext {
+    fields: Arc<BTreeMap<Key, serde_json::Value>>,
+}
+
+impl LogContext {
+    /// Returns the context of the current scope, which is empty outside of any scope
+    pub fn current() -> Self {
+        CONTEXT.try_with(Clone::clone).unwrap_or_default()
+    }
+
+    /// Adds (or replaces) a key/value to the context
+    pub fn with<T: Serialize + ?Sized>(mut self, key: &'static str, value: &T) -> Self {
+        match serde_json::to_value(value) {
+            Ok(value) => {
+                Arc::make_mut(&mut self.fields).insert(Key::new(key), value);
+            },
+            Err(e) => {
+                // Log and skip the value that can't be serialized
+                eprintln!("error serializing log context: {} for key {:?}", e, key);
+            },
+        }
+        self
+    }
+
+    /// Runs `future` with this context
+    pub fn scope<F: Future>(self, future: F) -> impl Future<Output = F::Output> {
+        CONTEXT.scope(self, future)
+    }
+
+    /// Runs `f` with this context, e.g., from a thread that is not driven by tokio
+    pub fn sync_scope<F: FnOnce() -> R, R>(self, f: F) -> R {
+        CONTEXT.sync_scope(self, f)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.fields.is_empty()
+    }
+}
+
+impl Schema for LogContext {
+    fn visit(&self, visitor: &mut dyn Visitor) {
+        for (key, value) in self.fields.iter() {
+            visitor.visit_pair(key.clone(), Value::Serde(value));
+        }
+    }
+}
+
+/// Spawns `future` on the current runtime with the log context of the caller
+pub fn spawn<F>(future: F) -> JoinHandle<F::Output>
+where
+    F: Future + Send + 'static,
+    F::Output: Send + 'static,
+{
+    tokio::spawn(LogContext::current().scope(future))
+}
+
+/// Spawns `future` on the runtime of `handle` with the log context of the caller
+pub fn spawn_on<F>(handle: &Handle, future: F) -> JoinHandle<F::Output>
+where
+    F: Future + Send + 'static,
+    F::Output: Send + 'static,
+{
+    handle.spawn(LogContext::current().scope(future))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn current_fields() -> BTreeMap<Key, serde_json::Value> {
+        LogContext::current().fields.as_ref().clone()
+    }
+
+    #[tokio::test]
+    async fn test_context_scope() {
+        assert!(LogContext::current().is_empty());
+
+        LogContext::current()
+            .with("block_id", "abc")
+            .with("round", &1)
+            .scope(async {
+                assert_eq!(current_fields().get(&Key::new("round")), Some(&json!(1)));
+
+                // Nested scopes inherit and override the outer context
+                LogContext::current().with("round", &2).sync_scope(|| {
+                    let fields = current_fields();
+                    assert_eq!(fields.get(&Key::new("block_id")), Some(&json!("abc")));
+                    assert_eq!(fields.get(&Key::new("round")), Some(&json!(2)));
+                });
+
+                assert_eq!(current_fields().get(&Key::new("round")), Some(&json!(1)));
+            })
+            .await;
+
+        assert!(LogContext::current().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_context_propagation() {
+        LogContext::current()
+            .with("request_id", &7)
+            .scope(async {
+                let fields = spawn(async { current_fields() }).await.unwrap();
+                assert_eq!(fields.get(&Key::new("request_id")), Some(&json!(7)));
+
+                let fields = tokio::spawn(async { current_fields() }).await.unwrap();
+                assert!(fields.is_empty());
+            })
+            .await;
+    }
+}
@@ -4,7 +4,10 @@
 
 //! Logging metrics for determining quality of log submission
 use once_cell::sync::Lazy;
-use prometheus::{register_int_counter, IntCounter};
+use prometheus::{
+    register_int_counter, register_int_counter_vec, register_int_gauge, IntCounter, IntCounterVec,
+    IntGauge,
+};
 
 /// Count of the struct logs submitted by macro
 pub static STRUCT_LOG_COUNT: Lazy<IntCounter> = Lazy::new(|| {
@@ -37,6 +40,34 @@ pub static STRUCT_LOG_QUEUE_ERROR_COUNT: Lazy<IntCounter> = Lazy::new(|| {
     .unwrap()
 });
 
+/// Count of the struct logs dropped by the async logger as its channel was full, per level
+pub static DROPPED_STRUCT_LOG_COUNT: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_struct_log_dropped_count",
+        "Count of the struct logs dropped as the log channel was full.",
+        &["level"]
+    )
+    .unwrap()
+});
+
+/// Number of struct logs waiting in the channel of the async logger
+pub static STRUCT_LOG_QUEUE_LENGTH: Lazy<IntGauge> = Lazy::new(|| {
+    register_int_gauge!(
+        "aptos_struct_log_queue_length",
+        "Number of the struct logs waiting in the log channel."
+    )
+    .unwrap()
+});
+
+/// Count of struct logs with structured data truncated due to the field or record length limits
+pub static TRUNCATED_STRUCT_LOG_COUNT: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "aptos_struct_log_truncated_count",
+        "Count of the struct logs with truncated structured data."
+    )
+    .unwrap()
+});
+
 pub static STRUCT_LOG_PARSE_ERROR_COUNT: Lazy<IntCounter> = Lazy::new(|| {
     register_int_counter!(
         "aptos_struct_log_parse_error_count",
@@ -62,3 +93,28 @@ pub static APTOS_LOG_INGEST_WRITER_DISCONNECTED: Lazy<IntCounter> = Lazy::new(||
     )
     .unwrap()
 });
+
+/// Counters of the logs exported by the OTLP writer
+pub static APTOS_LOG_OTLP_EXPORTED_COUNT: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "aptos_log_otlp_exported_count",
+        "Number of logs exported via OTLP"
+    )
+    .unwrap()
+});
+
+pub static APTOS_LOG_OTLP_EXPORT_FAILED_COUNT: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "aptos_log_otlp_export_failed_count",
+        "Number of logs that failed to be exported via OTLP"
+    )
+    .unwrap()
+});
+
+pub static APTOS_LOG_OTLP_DROPPED_COUNT: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "aptos_log_otlp_dropped_count",
+        "Number of logs dropped by the OTLP writer due to its queue being full"
+    )
+    .unwrap()
+});
@@ -5,10 +5,19 @@
 //! Filtering definitions for controlling what modules and levels are logged
 
 use crate::{Level, Metadata};
-use std::{env, str::FromStr};
+use std::{env, fmt, str::FromStr};
 
+#[derive(Debug)]
 pub struct FilterParseError;
 
+impl fmt::Display for FilterParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("invalid filter directive")
+    }
+}
+
+impl std::error::Error for FilterParseError {}
+
 /// A definition of the most verbose `Level` allowed, or completely off.
 #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
 pub enum LevelFilter {
@@ -103,6 +112,19 @@ impl Builder {
         self
     }
 
+    /// Parses a directives string, failing if any of the directives is invalid instead of
+    /// skipping it.
+    pub fn try_parse(&mut self, filters: &str) -> Result<&mut Self, FilterParseError> {
+        let directives = filters
+            .split(',')
+            .map(str::trim)
+            .filter(|directive| !directive.is_empty())
+            .map(Directive::from_str)
+            .collect::<Result<Vec<_>, _>>()?;
+        self.directives.extend(directives);
+        Ok(self)
+    }
+
     pub fn build(&mut self) -> Filter {
         if self.directives.is_empty() {
             // Add the default filter if none exist
@@ -340,6 +362,27 @@ mod tests {
         assert_eq!(dirs[0].level, LevelFilter::max());
     }
 
+    #[test]
+    fn try_parse_valid() {
+        let logger = Builder::new()
+            .try_parse("info, crate1::mod1=debug,,crate2=off")
+            .unwrap()
+            .build();
+        assert!(logger.enabled(&make_metadata(Level::Debug, "crate1::mod1")));
+        assert!(!logger.enabled(&make_metadata(Level::Debug, "crate1::mod2")));
+        assert!(!logger.enabled(&make_metadata(Level::Error, "crate2")));
+    }
+
+    #[test]
+    fn try_parse_invalid() {
+        let mut builder = Builder::new();
+        assert!(builder
+            .try_parse("crate1::mod1=noNumber,crate2=debug")
+            .is_err());
+        assert!(builder.try_parse("crate1::mod1=warn=info").is_err());
+        assert!(builder.directives.is_empty());
+    }
+
     #[test]
     fn parse_global() {
         // test parse with no crate
@@ -145,12 +145,18 @@ pub mod prelude {
 }
 
 pub mod aptos_logger;
+pub mod context;
 mod event;
 mod filter;
 mod kv;
 mod logger;
 mod macros;
 mod metadata;
+#[cfg(feature = "otlp")]
+pub mod otlp_log_writer;
+mod recent_logs;
+pub mod redaction;
+pub mod rolling_file_writer;
 pub mod sample;
 pub mod telemetry_log_writer;
 pub mod tracing_adapter;
@@ -158,13 +164,14 @@ pub mod tracing_adapter;
 mod security;
 
 pub use crate::aptos_logger::{
-    AptosData as Logger, AptosDataBuilder, LoggerFilterUpdater, Writer, CHANNEL_SIZE,
+    AptosData as Logger, AptosDataBuilder, LoggerFilterUpdater, OverflowPolicy, Writer,
+    CHANNEL_SIZE,
 };
 pub use aptos_log_derive::Schema;
 pub use event::Event;
-pub use filter::{Filter, LevelFilter};
+pub use filter::{Filter, FilterParseError, LevelFilter};
 pub use kv::{Key, KeyValue, Schema, Value, Visitor};
-pub use logger::flush;
+pub use logger::{flush, set_filter};
 pub use metadata::{Level, Metadata};
 pub use security::SecurityEvent;
 
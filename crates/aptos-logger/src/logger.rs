@@ -4,7 +4,7 @@
 
 //! Global logger definition and functions
 
-use crate::{counters::STRUCT_LOG_COUNT, error, Event, Metadata};
+use crate::{counters::STRUCT_LOG_COUNT, error, Event, Filter, FilterParseError, Metadata};
 use once_cell::sync::OnceCell;
 use std::sync::Arc;
 use tracing_subscriber::prelude::*;
@@ -22,6 +22,9 @@ pub trait Logger: Sync + Send + 'static {
 
     /// Flush any buffered events
     fn flush(&self);
+
+    /// Replace the filter of locally printed events, e.g. to change log levels at runtime
+    fn override_local_filter(&self, _filter: Filter) {}
 }
 
 /// Record a logging event to the global `Logger`
@@ -73,6 +76,17 @@ pub fn set_global_logger(logger: Arc<dyn Logger>, tokio_console_port: Option<u16
     }
 }
 
+/// Replaces the filter of the events printed locally by the global `Logger` with the given
+/// directives (e.g. `info,aptos_consensus=debug,aptos_network=warn`). The filter is swapped
+/// atomically and takes precedence over `RUST_LOG` until the process is restarted.
+pub fn set_filter(directives: &str) -> Result<(), FilterParseError> {
+    let filter = Filter::builder().try_parse(directives)?.build();
+    if let Some(logger) = LOGGER.get() {
+        logger.override_local_filter(filter);
+    }
+    Ok(())
+}
+
 /// Flush the global `Logger`. Note this is expensive, only use off the critical path.
 pub fn flush() {
     if let Some(logger) = LOGGER.get() {
@@ -0,0 +1,363 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! A `Writer` that exports logs to an OpenTelemetry collector via OTLP/gRPC.
+//!
+//! Logs are expected to be in the JSON format (see `aptos_logger::json_format`), so that the
+//! level, timestamp, message and structured data of each entry can be mapped to the fields of an
+//! OTLP log record. Logs in any other format are exported as-is as the body of the log record.
+
+use crate::{
+    aptos_logger::Writer,
+    counters::{
+        APTOS_LOG_OTLP_DROPPED_COUNT, APTOS_LOG_OTLP_EXPORTED_COUNT,
+        APTOS_LOG_OTLP_EXPORT_FAILED_COUNT,
+    },
+    sample,
+    sample::SampleRate,
+    Level,
+};
+use chrono::DateTime;
+use opentelemetry_proto::tonic::{
+    collector::logs::v1::{logs_service_client::LogsServiceClient, ExportLogsServiceRequest},
+    common::v1::{any_value, AnyValue, ArrayValue, InstrumentationScope, KeyValue, KeyValueList},
+    logs::v1::{LogRecord, ResourceLogs, ScopeLogs, SeverityNumber},
+    resource::v1::Resource,
+};
+use std::{
+    io::{Error, ErrorKind},
+    str::FromStr,
+    sync::mpsc::{self, Receiver, SyncSender},
+    thread,
+    time::{Duration, Instant, SystemTime, UNIX_EPOCH},
+};
+use tonic::transport::Endpoint;
+
+const INSTRUMENTATION_SCOPE_NAME: &str = "aptos-logger";
+
+/// What to do with logs written while the export queue is full
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum BackpressurePolicy {
+    /// Drop the log, so that logging never blocks
+    Drop,
+    /// Block the logging thread until there is room in the queue
+    Block,
+}
+
+/// Configuration of an `OtlpWriter`
+#[derive(Clone, Debug)]
+pub struct OtlpWriterConfig {
+    /// The OTLP/gRPC endpoint of the collector, e.g. `http://localhost:4317`
+    pub endpoint: String,
+    /// Resource attributes identifying the node the logs come from
+    pub chain_id: Option<u8>,
+    pub node_role: Option<String>,
+    pub peer_id: Option<String>,
+    /// Max number of logs queued for export
+    pub channel_size: usize,
+    /// Max number of logs exported in a single request
+    pub max_batch_size: usize,
+    /// Max time a log waits in a batch before the batch is exported
+    pub max_batch_delay: Duration,
+    /// Timeout of a single export request
+    pub export_timeout: Duration,
+    pub backpressure_policy: BackpressurePolicy,
+}
+
+impl Default for OtlpWriterConfig {
+    fn default() -> Self {
+        Self {
+            endpoint: "http://localhost:4317".to_string(),
+            chain_id: None,
+            node_role: None,
+            peer_id: None,
+            channel_size: crate::CHANNEL_SIZE,
+            max_batch_size: 512,
+            max_batch_delay: Duration::from_secs(1),
+            export_timeout: Duration::from_secs(10),
+            backpressure_policy: BackpressurePolicy::Drop,
+        }
+    }
+}
+
+/// A struct for exporting logs to an OpenTelemetry collector. Logs are queued and exported in
+/// batches by a dedicated thread.
+pub struct OtlpWriter {
+    sender: SyncSender<String>,
+    backpressure_policy: BackpressurePolicy,
+}
+
+impl OtlpWriter {
+    pub fn new(config: OtlpWriterConfig) -> std::io::Result<Self> {
+        let endpoint = Endpoint::from_shared(config.endpoint.clone())
+            .map_err(|e| Error::new(ErrorKind::InvalidInput, e))?
+            .timeout(config.export_timeout);
+        let (sender, receiver) = mpsc::sync_channel(config.channel_size);
+        let exporter = OtlpExporter {
+            receiver,
+            endpoint,
+            resource: resource(&config),
+            max_batch_size: config.max_batch_size.max(1),
+            max_batch_delay: config.max_batch_delay,
+        };
+        thread::Builder::new()
+            .name("otlp-log-exporter".to_string())
+            .spawn(move || exporter.run())?;
+
+        Ok(Self {
+            sender,
+            backpressure_policy: config.backpressure_policy,
+        })
+    }
+}
+
+impl Writer for OtlpWriter {
+    /// Queue the log for export
+    fn write(&self, log: String) {
+        let queued = match self.backpressure_policy {
+            BackpressurePolicy::Drop => self.sender.try_send(log).is_ok(),
+            BackpressurePolicy::Block => self.sender.send(log).is_ok(),
+        };
+        if !queued {
+            APTOS_LOG_OTLP_DROPPED_COUNT.inc();
+        }
+    }
+
+    fn write_buferred(&mut self, log: String) {
+        self.write(log);
+    }
+}
+
+/// Batches the queued logs and exports them until the `OtlpWriter` is dropped
+struct OtlpExporter {
+    receiver: Receiver<String>,
+    endpoint: Endpoint,
+    resource: Resource,
+    max_batch_size: usize,
+    max_batch_delay: Duration,
+}
+
+impl OtlpExporter {
+    fn run(self) {
+        let runtime = match tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+        {
+            Ok(runtime) => runtime,
+            Err(err) => {
+                eprintln!("[Logging] Unable to start the OTLP exporter: {}", err);
+                return;
+            },
+        };
+        let mut client =
+            runtime.block_on(async { LogsServiceClient::new(self.endpoint.connect_lazy()) });
+
+        while let Some(log_records) = self.next_batch() {
+            let num_logs = log_records.len() as u64;
+            let request = ExportLogsServiceRequest {
+                resource_logs: vec![ResourceLogs {
+                    resource: Some(self.resource.clone()),
+                    scope_logs: vec![ScopeLogs {
+                        scope: Some(InstrumentationScope {
+                            name: INSTRUMENTATION_SCOPE_NAME.to_string(),
+                            ..Default::default()
+                        }),
+                        log_records,
+                        schema_url: String::new(),
+                    }],
+                    schema_url: String::new(),
+                }],
+            };
+            match runtime.block_on(client.export(request)) {
+                Ok(_) => APTOS_LOG_OTLP_EXPORTED_COUNT.inc_by(num_logs),
+                Err(status) => {
+                    APTOS_LOG_OTLP_EXPORT_FAILED_COUNT.inc_by(num_logs);
+                    sample!(
+                        SampleRate::Duration(Duration::from_secs(60)),
+                        eprintln!("[Logging] Unable to export logs via OTLP: {}", status)
+                    );
+                },
+            }
+        }
+    }
+
+    /// Waits for the next log, then collects more logs until the batch is full or its delay has
+    /// passed. Returns `None` once the writer is dropped and all logs are exported.
+    fn next_batch(&self) -> Option<Vec<LogRecord>> {
+        let mut batch = vec![to_log_record(self.receiver.recv().ok()?)];
+        let deadline = Instant::now() + self.max_batch_delay;
+        while batch.len() < self.max_batch_size {
+            match self
+                .receiver
+                .recv_timeout(deadline.saturating_duration_since(Instant::now()))
+            {
+                Ok(log) => batch.push(to_log_record(log)),
+                Err(_) => break,
+            }
+        }
+        Some(batch)
+    }
+}
+
+fn resource(config: &OtlpWriterConfig) -> Resource {
+    let mut attributes = vec![key_value("service.name", "aptos-node".into())];
+    if let Some(chain_id) = config.chain_id {
+        attributes.push(key_value("aptos.chain_id", chain_id.into()));
+    }
+    if let Some(node_role) = &config.node_role {
+        attributes.push(key_value("aptos.node_role", node_role.as_str().into()));
+    }
+    if let Some(peer_id) = &config.peer_id {
+        attributes.push(key_value("aptos.peer_id", peer_id.as_str().into()));
+    }
+    Resource {
+        attributes,
+        dropped_attributes_count: 0,
+    }
+}
+
+/// Converts a log in the JSON format into a log record, promoting the level, timestamp and
+/// message, and adding the structured data and the remaining fields as attributes.
+fn to_log_record(log: String) -> LogRecord {
+    let mut record = LogRecord {
+        observed_time_unix_nano: SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |duration| duration.as_nanos() as u64),
+        ..Default::default()
+    };
+
+    let entry = match serde_json::from_str::<serde_json::Map<String, serde_json::Value>>(&log) {
+        Ok(entry) => entry,
+        Err(_) => {
+            record.body = Some(any_value(log.into()));
+            return record;
+        },
+    };
+    for (key, value) in entry {
+        match (key.as_str(), value) {
+            ("level", serde_json::Value::String(level)) => {
+                record.severity_number = severity_number(&level) as i32;
+                record.severity_text = level;
+            },
+            ("timestamp", serde_json::Value::String(timestamp)) => {
+                record.time_unix_nano = DateTime::parse_from_rfc3339(&timestamp)
+                    .ok()
+                    .and_then(|timestamp| timestamp.timestamp_nanos_opt())
+                    .map_or(0, |nanos| nanos as u64);
+            },
+            ("message", message) => record.body = Some(any_value(message)),
+            ("data", serde_json::Value::Object(data)) => record
+                .attributes
+                .extend(data.into_iter().map(|(key, value)| key_value(&key, value))),
+            (key, value) => record.attributes.push(key_value(key, value)),
+        }
+    }
+    record
+}
+
+fn severity_number(level: &str) -> SeverityNumber {
+    match Level::from_str(level) {
+        Ok(Level::Error) => SeverityNumber::Error,
+        Ok(Level::Warn) => SeverityNumber::Warn,
+        Ok(Level::Info) => SeverityNumber::Info,
+        Ok(Level::Debug) => SeverityNumber::Debug,
+        Ok(Level::Trace) => SeverityNumber::Trace,
+        Err(_) => SeverityNumber::Unspecified,
+    }
+}
+
+fn key_value(key: &str, value: serde_json::Value) -> KeyValue {
+    KeyValue {
+        key: key.to_string(),
+        value: Some(any_value(value)),
+    }
+}
+
+fn any_value(value: serde_json::Value) -> AnyValue {
+    let value = match value {
+        serde_json::Value::Null => None,
+        serde_json::Value::Bool(value) => Some(any_value::Value::BoolValue(value)),
+        serde_json::Value::Number(value) => value
+            .as_i64()
+            .map(any_value::Value::IntValue)
+            .or_else(|| value.as_f64().map(any_value::Value::DoubleValue)),
+        serde_json::Value::String(value) => Some(any_value::Value::StringValue(value)),
+        serde_json::Value::Array(values) => Some(any_value::Value::ArrayValue(ArrayValue {
+            values: values.into_iter().map(any_value).collect(),
+        })),
+        serde_json::Value::Object(values) => Some(any_value::Value::KvlistValue(KeyValueList {
+            values: values
+                .into_iter()
+                .map(|(key, value)| key_value(&key, value))
+                .collect(),
+        })),
+    };
+    AnyValue { value }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn attribute<'a>(record: &'a LogRecord, key: &str) -> Option<&'a any_value::Value> {
+        record
+            .attributes
+            .iter()
+            .find(|attribute| attribute.key == key)
+            .and_then(|attribute| attribute.value.as_ref())
+            .and_then(|value| value.value.as_ref())
+    }
+
+    #[test]
+    fn test_json_log_to_log_record() {
+        let log = r#"{"level":"WARN","source":{"package":"aptos_logger","file":"src/lib.rs:1"},"timestamp":"2024-01-01T00:00:00.000001Z","message":"hello","data":{"round":5,"epoch":"2"}}"#;
+        let record = to_log_record(log.to_string());
+
+        assert_eq!(record.severity_number, SeverityNumber::Warn as i32);
+        assert_eq!(record.severity_text, "WARN");
+        assert_eq!(record.time_unix_nano, 1_704_067_200_000_001_000);
+        assert_eq!(record.body, Some(any_value("hello".into())));
+        assert_eq!(
+            attribute(&record, "round"),
+            Some(&any_value::Value::IntValue(5))
+        );
+        assert_eq!(
+            attribute(&record, "epoch"),
+            Some(&any_value::Value::StringValue("2".to_string()))
+        );
+        assert!(matches!(
+            attribute(&record, "source"),
+            Some(any_value::Value::KvlistValue(source)) if source.values.len() == 2
+        ));
+    }
+
+    #[test]
+    fn test_text_log_to_log_record() {
+        let log = "2024-01-01T00:00:00.000001Z INFO src/lib.rs:1 hello";
+        let record = to_log_record(log.to_string());
+
+        assert_eq!(record.severity_number, SeverityNumber::Unspecified as i32);
+        assert_eq!(record.body, Some(any_value(log.into())));
+        assert!(record.attributes.is_empty());
+    }
+
+    #[test]
+    fn test_next_batch() {
+        let (sender, receiver) = mpsc::sync_channel(10);
+        let exporter = OtlpExporter {
+            receiver,
+            endpoint: Endpoint::from_static("http://localhost:4317"),
+            resource: resource(&OtlpWriterConfig::default()),
+            max_batch_size: 3,
+            max_batch_delay: Duration::from_millis(10),
+        };
+        for i in 0..5 {
+            sender.send(format!("log {}", i)).unwrap();
+        }
+
+        assert_eq!(exporter.next_batch().unwrap().len(), 3);
+        assert_eq!(exporter.next_batch().unwrap().len(), 2);
+        drop(sender);
+        assert!(exporter.next_batch().is_none());
+    }
+}
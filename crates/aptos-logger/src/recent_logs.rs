@@ -0,0 +1,139 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! A lock-free ring buffer of the most recent logs, which is dumped when the process panics.
+//!
+//! The buffer keeps the logs up to its own level, regardless of the level of the local printer,
+//! so that post-mortem debugging has verbose context of what happened right before a crash.
+
+use crate::{
+    aptos_logger::{json_format, LogEntry},
+    Level, Metadata,
+};
+use chrono::{SecondsFormat, Utc};
+use crossbeam::queue::ArrayQueue;
+use std::{
+    fs::{File, OpenOptions},
+    io::Write,
+    panic,
+    path::PathBuf,
+    sync::Arc,
+};
+
+pub(crate) struct RecentLogs {
+    entries: ArrayQueue<LogEntry>,
+    level: Level,
+    /// The file the logs are dumped to, in addition to stderr
+    dump_path: Option<PathBuf>,
+}
+
+impl RecentLogs {
+    pub(crate) fn new(capacity: usize, level: Level, dump_path: Option<PathBuf>) -> Self {
+        Self {
+            entries: ArrayQueue::new(capacity),
+            level,
+            dump_path,
+        }
+    }
+
+    pub(crate) fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= self.level
+    }
+
+    /// Keeps the log, overwriting the oldest one if the buffer is full
+    pub(crate) fn push(&self, entry: LogEntry) {
+        self.entries.force_push(entry);
+    }
+
+    /// Writes the logs from the oldest to the newest to stderr and the dump file, and clears the
+    /// buffer
+    pub(crate) fn dump(&self) {
+        let mut file = self.dump_path.as_ref().and_then(|path| {
+            match OpenOptions::new().append(true).create(true).open(path) {
+                Ok(file) => Some(file),
+                Err(err) => {
+                    eprintln!("Unable to open recent logs dump file {:?}: {}", path, err);
+                    None
+                },
+            }
+        });
+        let header = format!(
+            "Dumping the {} most recent logs at {}",
+            self.entries.len(),
+            Utc::now().to_rfc3339_opts(SecondsFormat::Micros, true)
+        );
+        write_dump(&mut file, &header);
+
+        while let Some(entry) = self.entries.pop() {
+            match json_format(&entry) {
+                Ok(log) => write_dump(&mut file, &log),
+                Err(err) => eprintln!("Unable to format recent log: {}", err),
+            }
+        }
+        if let Some(file) = &mut file {
+            let _ = file.flush();
+        }
+    }
+
+    /// Dumps the logs when a thread panics, before running the previously set panic hook
+    pub(crate) fn install_panic_hook(self: &Arc<Self>) {
+        let recent_logs = self.clone();
+        let previous_hook = panic::take_hook();
+        panic::set_hook(Box::new(move |panic_info| {
+            recent_logs.dump();
+            previous_hook(panic_info);
+        }));
+    }
+}
+
+fn write_dump(file: &mut Option<File>, log: &str) {
+    eprintln!("{}", log);
+    if let Some(file) = file {
+        let _ = writeln!(file, "{}", log);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Event;
+    use std::fs;
+
+    fn entry(level: Level, message: &str) -> LogEntry {
+        LogEntry::new(
+            &Event::new(
+                &Metadata::new(level, "target", "module_path", "source_path"),
+                Some(format_args!("{}", message)),
+                &[],
+            ),
+            None,
+            false,
+        )
+    }
+
+    #[test]
+    fn test_recent_logs_dump() {
+        let dir = tempfile::tempdir().unwrap();
+        let dump_path = dir.path().join("recent_logs.log");
+        let recent_logs = RecentLogs::new(2, Level::Debug, Some(dump_path.clone()));
+
+        assert!(recent_logs.enabled(&Metadata::new(Level::Debug, "", "", "")));
+        assert!(!recent_logs.enabled(&Metadata::new(Level::Trace, "", "", "")));
+
+        for message in ["log 0", "log 1", "log 2"] {
+            recent_logs.push(entry(Level::Debug, message));
+        }
+        recent_logs.dump();
+
+        let dump = fs::read_to_string(&dump_path).unwrap();
+        let lines: Vec<_> = dump.lines().collect();
+        assert_eq!(lines.len(), 3);
+        assert!(lines[0].starts_with("Dumping the 2 most recent logs"));
+        assert!(lines[1].contains("\"message\":\"log 1\""));
+        assert!(lines[2].contains("\"message\":\"log 2\""));
+
+        // The buffer is cleared by the dump
+        recent_logs.dump();
+        assert_eq!(fs::read_to_string(&dump_path).unwrap().lines().count(), 4);
+    }
+}
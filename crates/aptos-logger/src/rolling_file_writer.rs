@@ -0,0 +1,395 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! A `Writer` that writes logs to a file and rotates it based on its size and age.
+//!
+//! Rotated files are renamed to `<file name>.<rotation time>`, compressed and pruned by a
+//! dedicated thread, so that writing logs is never blocked on compression. Since the writer owns
+//! the rotation, no external `logrotate` setup is needed.
+//!
+//! ```no_run
+//! use aptos_logger::{
+//!     rolling_file_writer::{Compression, RollingFileWriter},
+//!     Logger,
+//! };
+//! use std::time::Duration;
+//!
+//! let writer = RollingFileWriter::builder("/opt/aptos/logs/aptos.log")
+//!     .max_size(100 * 1024 * 1024)
+//!     .max_age(Duration::from_secs(24 * 60 * 60))
+//!     .compression(Compression::Zstd)
+//!     .max_files(10)
+//!     .build()
+//!     .unwrap();
+//! Logger::builder().printer(Box::new(writer)).build();
+//! ```
+
+use crate::aptos_logger::Writer;
+use aptos_infallible::Mutex;
+use chrono::Utc;
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{self, Write},
+    path::{Path, PathBuf},
+    sync::mpsc,
+    thread,
+    time::{Duration, Instant},
+};
+
+/// How rotated log files are compressed
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Compression {
+    None,
+    Gzip,
+    Zstd,
+}
+
+impl Compression {
+    fn extension(&self) -> Option<&'static str> {
+        match self {
+            Compression::None => None,
+            Compression::Gzip => Some("gz"),
+            Compression::Zstd => Some("zst"),
+        }
+    }
+}
+
+/// A builder for a `RollingFileWriter`. By default, the file is never rotated.
+pub struct RollingFileWriterBuilder {
+    path: PathBuf,
+    max_size: Option<u64>,
+    max_age: Option<Duration>,
+    compression: Compression,
+    max_files: Option<usize>,
+}
+
+impl RollingFileWriterBuilder {
+    /// Rotates the file before it grows larger than `max_size` bytes.
+    pub fn max_size(&mut self, max_size: u64) -> &mut Self {
+        self.max_size = Some(max_size);
+        self
+    }
+
+    /// Rotates the file once it has been written to for `max_age`.
+    pub fn max_age(&mut self, max_age: Duration) -> &mut Self {
+        self.max_age = Some(max_age);
+        self
+    }
+
+    pub fn compression(&mut self, compression: Compression) -> &mut Self {
+        self.compression = compression;
+        self
+    }
+
+    /// Keeps at most `max_files` rotated files, deleting the oldest ones.
+    pub fn max_files(&mut self, max_files: usize) -> &mut Self {
+        self.max_files = Some(max_files);
+        self
+    }
+
+    pub fn build(&mut self) -> io::Result<RollingFileWriter> {
+        let file = open(&self.path)?;
+        let size = file.metadata()?.len();
+
+        let (rotated_tx, rotated_rx) = mpsc::channel();
+        let archiver = Archiver {
+            path: self.path.clone(),
+            compression: self.compression,
+            max_files: self.max_files,
+        };
+        let archiver_handle = thread::Builder::new()
+            .name("log-rotation".to_string())
+            .spawn(move || archiver.run(rotated_rx))?;
+
+        Ok(RollingFileWriter {
+            file: Mutex::new(RollingFile {
+                path: self.path.clone(),
+                file,
+                size,
+                opened_at: Instant::now(),
+                max_size: self.max_size,
+                max_age: self.max_age,
+                rotated_tx,
+            }),
+            archiver_handle: Some(archiver_handle),
+        })
+    }
+}
+
+/// A struct for writing logs to a file that is rotated based on its size and age
+pub struct RollingFileWriter {
+    file: Mutex<RollingFile>,
+    archiver_handle: Option<thread::JoinHandle<()>>,
+}
+
+impl RollingFileWriter {
+    pub fn builder<P: Into<PathBuf>>(path: P) -> RollingFileWriterBuilder {
+        RollingFileWriterBuilder {
+            path: path.into(),
+            max_size: None,
+            max_age: None,
+            compression: Compression::None,
+            max_files: None,
+        }
+    }
+}
+
+impl Writer for RollingFileWriter {
+    /// Write to file, rotating it first if needed
+    fn write(&self, log: String) {
+        if let Err(err) = self.file.lock().write(&log) {
+            eprintln!("Unable to write to log file: {}", err);
+        }
+    }
+
+    fn write_buferred(&mut self, log: String) {
+        self.write(log);
+    }
+}
+
+impl Drop for RollingFileWriter {
+    /// Waits for the rotated files to be archived
+    fn drop(&mut self) {
+        // Dropping the sender of rotated files stops the archiver.
+        let (rotated_tx, _) = mpsc::channel();
+        self.file.lock().rotated_tx = rotated_tx;
+        if let Some(handle) = self.archiver_handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+struct RollingFile {
+    path: PathBuf,
+    file: File,
+    size: u64,
+    opened_at: Instant,
+    max_size: Option<u64>,
+    max_age: Option<Duration>,
+    rotated_tx: mpsc::Sender<PathBuf>,
+}
+
+impl RollingFile {
+    fn write(&mut self, log: &str) -> io::Result<()> {
+        let len = log.len() as u64 + 1;
+        if self.should_rotate(len) {
+            self.rotate()?;
+        }
+        writeln!(self.file, "{}", log)?;
+        self.size += len;
+        Ok(())
+    }
+
+    fn should_rotate(&self, len: u64) -> bool {
+        let exceeds_size = self
+            .max_size
+            .is_some_and(|max_size| self.size > 0 && self.size + len > max_size);
+        let exceeds_age = self
+            .max_age
+            .is_some_and(|max_age| self.opened_at.elapsed() >= max_age);
+        exceeds_size || exceeds_age
+    }
+
+    fn rotate(&mut self) -> io::Result<()> {
+        self.file.flush()?;
+        let rotated_path = append_to_file_name(
+            &self.path,
+            &Utc::now().format("%Y%m%dT%H%M%S%.9fZ").to_string(),
+        );
+        fs::rename(&self.path, &rotated_path)?;
+        self.file = open(&self.path)?;
+        self.size = 0;
+        self.opened_at = Instant::now();
+        let _ = self.rotated_tx.send(rotated_path);
+        Ok(())
+    }
+}
+
+/// Compresses the rotated files and deletes the oldest ones
+struct Archiver {
+    path: PathBuf,
+    compression: Compression,
+    max_files: Option<usize>,
+}
+
+impl Archiver {
+    fn run(self, rotated_rx: mpsc::Receiver<PathBuf>) {
+        for rotated_path in rotated_rx {
+            if let Err(err) = self.compress(&rotated_path) {
+                eprintln!(
+                    "Unable to compress rotated log file {:?}: {}",
+                    rotated_path, err
+                );
+            }
+            if let Err(err) = self.prune() {
+                eprintln!("Unable to delete rotated log files: {}", err);
+            }
+        }
+    }
+
+    fn compress(&self, rotated_path: &Path) -> io::Result<()> {
+        let extension = match self.compression.extension() {
+            Some(extension) => extension,
+            None => return Ok(()),
+        };
+        let mut input = File::open(rotated_path)?;
+        let output = File::create(append_to_file_name(rotated_path, extension))?;
+        match self.compression {
+            Compression::None => unreachable!(),
+            Compression::Gzip => {
+                let mut encoder = flate2::write::GzEncoder::new(output, Default::default());
+                io::copy(&mut input, &mut encoder)?;
+                encoder.finish()?;
+            },
+            Compression::Zstd => {
+                let mut encoder = zstd::Encoder::new(output, 0)?;
+                io::copy(&mut input, &mut encoder)?;
+                encoder.finish()?;
+            },
+        }
+        fs::remove_file(rotated_path)
+    }
+
+    fn prune(&self) -> io::Result<()> {
+        let max_files = match self.max_files {
+            Some(max_files) => max_files,
+            None => return Ok(()),
+        };
+        let mut rotated_paths = rotated_files(&self.path)?;
+        if rotated_paths.len() > max_files {
+            // The rotation time in the file names sorts them from the oldest to the newest.
+            rotated_paths.sort();
+            for rotated_path in &rotated_paths[..rotated_paths.len() - max_files] {
+                fs::remove_file(rotated_path)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+fn open(path: &Path) -> io::Result<File> {
+    OpenOptions::new().append(true).create(true).open(path)
+}
+
+fn append_to_file_name(path: &Path, suffix: &str) -> PathBuf {
+    let mut file_name = path.file_name().unwrap_or_default().to_os_string();
+    file_name.push(".");
+    file_name.push(suffix);
+    path.with_file_name(file_name)
+}
+
+/// Returns the rotated files of the log file at `path`
+fn rotated_files(path: &Path) -> io::Result<Vec<PathBuf>> {
+    let prefix = append_to_file_name(path, "");
+    let prefix = prefix.file_name().unwrap_or_default().to_string_lossy();
+    let dir = match path.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    let mut rotated_paths = vec![];
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        if entry.file_name().to_string_lossy().starts_with(&*prefix) {
+            rotated_paths.push(entry.path());
+        }
+    }
+    Ok(rotated_paths)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+
+    fn read_rotated_files(path: &Path, compression: Compression) -> Vec<String> {
+        let mut rotated_paths = rotated_files(path).unwrap();
+        rotated_paths.sort();
+        rotated_paths
+            .iter()
+            .map(|rotated_path| {
+                let file = File::open(rotated_path).unwrap();
+                let mut contents = String::new();
+                match compression {
+                    Compression::None => {
+                        assert!(rotated_path.to_string_lossy().ends_with('Z'));
+                        io::BufReader::new(file).read_to_string(&mut contents)
+                    },
+                    Compression::Gzip => {
+                        assert!(rotated_path.to_string_lossy().ends_with(".gz"));
+                        flate2::read::GzDecoder::new(file).read_to_string(&mut contents)
+                    },
+                    Compression::Zstd => {
+                        assert!(rotated_path.to_string_lossy().ends_with(".zst"));
+                        zstd::Decoder::new(file)
+                            .unwrap()
+                            .read_to_string(&mut contents)
+                    },
+                }
+                .unwrap();
+                contents
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_rotate_by_size() {
+        for compression in [Compression::None, Compression::Gzip, Compression::Zstd] {
+            let dir = tempfile::tempdir().unwrap();
+            let path = dir.path().join("aptos.log");
+            let writer = RollingFileWriter::builder(&path)
+                .max_size(10)
+                .compression(compression)
+                .build()
+                .unwrap();
+            for log in ["log 0", "log 1", "log 2", "log 3", "log 4"] {
+                writer.write(log.to_string());
+            }
+            drop(writer);
+
+            assert_eq!(fs::read_to_string(&path).unwrap(), "log 4\n");
+            assert_eq!(read_rotated_files(&path, compression), vec![
+                "log 0\n", "log 1\n", "log 2\n", "log 3\n"
+            ]);
+        }
+    }
+
+    #[test]
+    fn test_rotate_by_age() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("aptos.log");
+        let writer = RollingFileWriter::builder(&path)
+            .max_age(Duration::from_millis(100))
+            .build()
+            .unwrap();
+        writer.write("log 0".to_string());
+        writer.write("log 1".to_string());
+        thread::sleep(Duration::from_millis(100));
+        writer.write("log 2".to_string());
+        drop(writer);
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "log 2\n");
+        assert_eq!(read_rotated_files(&path, Compression::None), vec![
+            "log 0\nlog 1\n"
+        ]);
+    }
+
+    #[test]
+    fn test_max_files() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("aptos.log");
+        let writer = RollingFileWriter::builder(&path)
+            .max_size(1)
+            .compression(Compression::Gzip)
+            .max_files(2)
+            .build()
+            .unwrap();
+        for i in 0..10 {
+            writer.write(format!("log {}", i));
+        }
+        drop(writer);
+
+        assert_eq!(read_rotated_files(&path, Compression::Gzip), vec![
+            "log 7\n", "log 8\n"
+        ]);
+    }
+}
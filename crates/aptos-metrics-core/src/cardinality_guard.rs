@@ -0,0 +1,215 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! `BoundedLabelVec` caps the number of distinct values of each label of a metric family, to
+//! protect Prometheus from cardinality explosions caused by labels such as peer ids or block ids.
+//!
+//! Once a label has seen `max_values_per_label` distinct values, any new value is collapsed into
+//! `OTHER_LABEL_VALUE`, and the metric family is logged (once) as an offender.
+
+use crate::{IntCounterHelper, IntGaugeHelper, TimerHelper};
+use prometheus::{
+    core::{Collector, MetricVec, MetricVecBuilder},
+    HistogramTimer, HistogramVec, IntCounterVec, IntGaugeVec,
+};
+use std::{
+    collections::HashSet,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        RwLock,
+    },
+};
+
+/// The label value the overflowing label values are collapsed into.
+pub const OTHER_LABEL_VALUE: &str = "other";
+
+pub struct BoundedLabelVec<V> {
+    inner: V,
+    name: String,
+    max_values_per_label: usize,
+    seen_values: RwLock<Vec<HashSet<String>>>,
+    overflowed: AtomicBool,
+}
+
+impl<P: MetricVecBuilder> BoundedLabelVec<MetricVec<P>> {
+    pub fn new(inner: MetricVec<P>, max_values_per_label: usize) -> Self {
+        let name = inner
+            .desc()
+            .first()
+            .map_or_else(String::new, |desc| desc.fq_name.clone());
+        Self {
+            inner,
+            name,
+            max_values_per_label,
+            seen_values: RwLock::new(vec![]),
+            overflowed: AtomicBool::new(false),
+        }
+    }
+
+    /// Same as `MetricVec::with_label_values`, except that the values beyond the cap of each label
+    /// are replaced with `OTHER_LABEL_VALUE`.
+    pub fn with_label_values(&self, vals: &[&str]) -> P::M {
+        self.inner.with_label_values(&self.bound(vals))
+    }
+
+    pub fn inner(&self) -> &MetricVec<P> {
+        &self.inner
+    }
+}
+
+impl<V> BoundedLabelVec<V> {
+    fn bound<'a>(&self, vals: &[&'a str]) -> Vec<&'a str> {
+        if let Some(bounded) = self.bound_without_insert(vals) {
+            return bounded;
+        }
+
+        let mut seen_values = self
+            .seen_values
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        if seen_values.len() < vals.len() {
+            seen_values.resize_with(vals.len(), HashSet::new);
+        }
+        vals.iter()
+            .zip(seen_values.iter_mut())
+            .enumerate()
+            .map(|(idx, (val, seen))| {
+                if seen.contains(*val) {
+                    *val
+                } else if seen.len() < self.max_values_per_label {
+                    seen.insert(val.to_string());
+                    *val
+                } else {
+                    self.log_overflow_once(idx, val);
+                    OTHER_LABEL_VALUE
+                }
+            })
+            .collect()
+    }
+
+    /// Bounds `vals` under the read lock, unless one of them has to be recorded as seen.
+    fn bound_without_insert<'a>(&self, vals: &[&'a str]) -> Option<Vec<&'a str>> {
+        let seen_values = self
+            .seen_values
+            .read()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        if seen_values.len() < vals.len() {
+            return None;
+        }
+        vals.iter()
+            .zip(seen_values.iter())
+            .enumerate()
+            .map(|(idx, (val, seen))| {
+                if seen.contains(*val) {
+                    Some(*val)
+                } else if seen.len() < self.max_values_per_label {
+                    None
+                } else {
+                    self.log_overflow_once(idx, val);
+                    Some(OTHER_LABEL_VALUE)
+                }
+            })
+            .collect()
+    }
+
+    fn log_overflow_once(&self, label_idx: usize, val: &str) {
+        if !self.overflowed.swap(true, Ordering::Relaxed) {
+            tracing::warn!(
+                metric = self.name.as_str(),
+                label_idx = label_idx,
+                label_value = val,
+                max_values_per_label = self.max_values_per_label,
+                "Too many distinct label values, collapsing the new ones into \"{}\".",
+                OTHER_LABEL_VALUE,
+            );
+        }
+    }
+}
+
+impl TimerHelper for BoundedLabelVec<HistogramVec> {
+    fn timer_with(&self, labels: &[&str]) -> HistogramTimer {
+        self.with_label_values(labels).start_timer()
+    }
+
+    fn observe_with(&self, labels: &[&str], val: f64) {
+        self.with_label_values(labels).observe(val)
+    }
+}
+
+impl IntGaugeHelper for BoundedLabelVec<IntGaugeVec> {
+    fn set_with(&self, labels: &[&str], val: i64) {
+        self.with_label_values(labels).set(val)
+    }
+
+    fn concurrency_with(&self, labels: &[&str]) -> crate::ConcurrencyGauge {
+        crate::ConcurrencyGauge::new(self.with_label_values(labels))
+    }
+}
+
+impl IntCounterHelper for BoundedLabelVec<IntCounterVec> {
+    type IntType = u64;
+
+    fn inc_with(&self, labels: &[&str]) {
+        self.with_label_values(labels).inc()
+    }
+
+    fn inc_with_by(&self, labels: &[&str], v: Self::IntType) {
+        self.with_label_values(labels).inc_by(v)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{BoundedLabelVec, OTHER_LABEL_VALUE};
+    use crate::{IntCounterHelper, TimerHelper};
+    use prometheus::{HistogramOpts, HistogramVec, IntCounterVec, Opts};
+
+    #[test]
+    fn test_overflow_collapsed_into_other() {
+        let counter = BoundedLabelVec::new(
+            IntCounterVec::new(Opts::new("test_counter", "test"), &["peer", "op"]).unwrap(),
+            2,
+        );
+        for peer in ["a", "b", "c", "d", "a"] {
+            counter.inc_with(&[peer, "send"]);
+        }
+        counter.inc_with(&["b", "recv"]);
+        counter.inc_with(&["e", "drop"]);
+
+        let inner = counter.inner();
+        assert_eq!(inner.with_label_values(&["a", "send"]).get(), 2);
+        assert_eq!(inner.with_label_values(&["b", "send"]).get(), 1);
+        assert_eq!(
+            inner.with_label_values(&[OTHER_LABEL_VALUE, "send"]).get(),
+            2
+        );
+        assert_eq!(inner.with_label_values(&["b", "recv"]).get(), 1);
+        // Each label is capped separately
+        assert_eq!(
+            inner
+                .with_label_values(&[OTHER_LABEL_VALUE, OTHER_LABEL_VALUE])
+                .get(),
+            1
+        );
+    }
+
+    #[test]
+    fn test_histogram_timer() {
+        let histogram = BoundedLabelVec::new(
+            HistogramVec::new(HistogramOpts::new("test_histogram", "test"), &["block"]).unwrap(),
+            1,
+        );
+        histogram.observe_with(&["1"], 1.0);
+        drop(histogram.timer_with(&["2"]));
+        histogram.observe_with(&["3"], 1.0);
+
+        let inner = histogram.inner();
+        assert_eq!(inner.with_label_values(&["1"]).get_sample_count(), 1);
+        assert_eq!(
+            inner
+                .with_label_values(&[OTHER_LABEL_VALUE])
+                .get_sample_count(),
+            2
+        );
+    }
+}
@@ -0,0 +1,593 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! OpenMetrics exemplars for histograms.
+//!
+//! An exemplar links an observation of a histogram to the trace it comes from, e.g., a block id,
+//! so that a latency spike on a dashboard can be drilled down to the exact block. Each bucket of
+//! an `ExemplarHistogram(Vec)` keeps the latest observation that carried exemplar labels, and the
+//! `OpenMetricsEncoder` exports them along with the bucket counts.
+//!
+//! Exemplar labels are taken either explicitly (`observe_with_exemplar`), or from the labels set
+//! on the current thread with `set_exemplar_labels`. The latter is not suitable for async code, as
+//! tasks move across threads.
+
+use crate::{Histogram, HistogramVec};
+use once_cell::sync::Lazy;
+use prometheus::{
+    core::Collector,
+    proto::{LabelPair, Metric, MetricFamily, MetricType},
+    Encoder, HistogramOpts,
+};
+use std::{
+    cell::RefCell,
+    collections::HashMap,
+    io::Write,
+    marker::PhantomData,
+    sync::{Arc, Mutex, RwLock},
+    time::{Instant, SystemTime, UNIX_EPOCH},
+};
+
+/// The content type of the OpenMetrics text format.
+pub const OPENMETRICS_CONTENT_TYPE: &str =
+    "application/openmetrics-text; version=1.0.0; charset=utf-8";
+
+/// The maximum number of UTF-8 characters of the labels of an exemplar, names and values
+/// included, as per the OpenMetrics specification.
+pub const MAX_EXEMPLAR_LABELS_LENGTH: usize = 128;
+
+/// The labels of an exemplar, e.g., `[("block_id", "...")]`.
+pub type ExemplarLabels = Arc<[(&'static str, String)]>;
+
+thread_local! {
+    static CURRENT_EXEMPLAR_LABELS: RefCell<Option<ExemplarLabels>> = const { RefCell::new(None) };
+}
+
+/// Sets the exemplar labels of the observations made on the current thread, until the returned
+/// guard is dropped.
+pub fn set_exemplar_labels(labels: Vec<(&'static str, String)>) -> ExemplarLabelsGuard {
+    let previous = CURRENT_EXEMPLAR_LABELS.with(|current| current.replace(Some(labels.into())));
+    ExemplarLabelsGuard {
+        previous,
+        _not_send: PhantomData,
+    }
+}
+
+/// Returns the exemplar labels set on the current thread, if any.
+pub fn current_exemplar_labels() -> Option<ExemplarLabels> {
+    CURRENT_EXEMPLAR_LABELS.with(|current| current.borrow().clone())
+}
+
+/// Restores the previous exemplar labels of the thread when dropped.
+pub struct ExemplarLabelsGuard {
+    previous: Option<ExemplarLabels>,
+    // The guard must be dropped on the thread it was created on.
+    _not_send: PhantomData<*const ()>,
+}
+
+impl Drop for ExemplarLabelsGuard {
+    fn drop(&mut self) {
+        let previous = self.previous.take();
+        CURRENT_EXEMPLAR_LABELS.with(|current| *current.borrow_mut() = previous);
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Exemplar {
+    pub labels: ExemplarLabels,
+    pub value: f64,
+    pub timestamp: SystemTime,
+}
+
+/// The latest exemplar of each bucket of each histogram of a vec, by label values.
+struct ExemplarStore {
+    label_names: Vec<String>,
+    upper_bounds: Vec<f64>,
+    exemplars: Mutex<HashMap<Vec<String>, Vec<Option<Exemplar>>>>,
+}
+
+impl ExemplarStore {
+    fn record(&self, label_values: &[&str], value: f64, labels: ExemplarLabels) {
+        let labels_length: usize = labels
+            .iter()
+            .map(|(name, value)| name.chars().count() + value.chars().count())
+            .sum();
+        if labels.is_empty() || labels_length > MAX_EXEMPLAR_LABELS_LENGTH {
+            return;
+        }
+
+        // The last bucket is the implicit +Inf one.
+        let bucket = self
+            .upper_bounds
+            .iter()
+            .position(|upper_bound| value <= *upper_bound)
+            .unwrap_or(self.upper_bounds.len());
+        let exemplar = Exemplar {
+            labels,
+            value,
+            timestamp: SystemTime::now(),
+        };
+
+        let mut exemplars = self
+            .exemplars
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let buckets = exemplars
+            .entry(label_values.iter().map(|value| value.to_string()).collect())
+            .or_insert_with(|| vec![None; self.upper_bounds.len() + 1]);
+        buckets[bucket] = Some(exemplar);
+    }
+
+    /// Returns the exemplars of the histogram of `metric`, the last one being for +Inf.
+    fn get(&self, metric: &Metric) -> Option<Vec<Option<Exemplar>>> {
+        let label_values = self
+            .label_names
+            .iter()
+            .map(|name| {
+                metric
+                    .get_label()
+                    .iter()
+                    .find(|label| label.get_name() == name)
+                    .map(|label| label.get_value().to_string())
+            })
+            .collect::<Option<Vec<_>>>()?;
+        self.exemplars
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .get(&label_values)
+            .cloned()
+    }
+}
+
+/// The exemplar stores of the registered histograms, by metric name.
+static EXEMPLAR_STORES: Lazy<RwLock<HashMap<String, Arc<ExemplarStore>>>> =
+    Lazy::new(|| RwLock::new(HashMap::new()));
+
+fn exemplar_store(name: &str) -> Option<Arc<ExemplarStore>> {
+    EXEMPLAR_STORES
+        .read()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .get(name)
+        .cloned()
+}
+
+/// A `HistogramVec` keeping the latest exemplar of each bucket.
+#[derive(Clone)]
+pub struct ExemplarHistogramVec {
+    inner: HistogramVec,
+    store: Arc<ExemplarStore>,
+}
+
+impl ExemplarHistogramVec {
+    pub fn new(opts: HistogramOpts, label_names: &[&str]) -> prometheus::Result<Self> {
+        let upper_bounds = opts.buckets.clone();
+        let inner = HistogramVec::new(opts, label_names)?;
+        let store = Arc::new(ExemplarStore {
+            label_names: label_names.iter().map(|name| name.to_string()).collect(),
+            upper_bounds,
+            exemplars: Mutex::new(HashMap::new()),
+        });
+        Ok(Self { inner, store })
+    }
+
+    /// Observes `val`, with the exemplar labels of the current thread if any.
+    pub fn observe_with(&self, labels: &[&str], val: f64) {
+        self.inner.with_label_values(labels).observe(val);
+        if let Some(exemplar_labels) = current_exemplar_labels() {
+            self.store.record(labels, val, exemplar_labels);
+        }
+    }
+
+    /// Observes `val`, with the given exemplar labels.
+    pub fn observe_with_exemplar(
+        &self,
+        labels: &[&str],
+        val: f64,
+        exemplar_labels: Vec<(&'static str, String)>,
+    ) {
+        self.inner.with_label_values(labels).observe(val);
+        self.store.record(labels, val, exemplar_labels.into());
+    }
+
+    /// Starts a timer, which observes with the exemplar labels of the current thread when the
+    /// timer was started, if any.
+    pub fn timer_with(&self, labels: &[&str]) -> ExemplarTimer {
+        let exemplar_labels = current_exemplar_labels();
+        let label_values = if exemplar_labels.is_some() {
+            labels.iter().map(|value| value.to_string()).collect()
+        } else {
+            vec![]
+        };
+        ExemplarTimer {
+            histogram: self.inner.with_label_values(labels),
+            store: self.store.clone(),
+            label_values,
+            exemplar_labels,
+            start: Instant::now(),
+        }
+    }
+
+    pub fn inner(&self) -> &HistogramVec {
+        &self.inner
+    }
+}
+
+/// A `Histogram` keeping the latest exemplar of each bucket.
+#[derive(Clone)]
+pub struct ExemplarHistogram {
+    vec: ExemplarHistogramVec,
+    histogram: Histogram,
+}
+
+impl ExemplarHistogram {
+    pub fn new(opts: HistogramOpts) -> prometheus::Result<Self> {
+        Ok(Self::from_vec(ExemplarHistogramVec::new(opts, &[])?))
+    }
+
+    fn from_vec(vec: ExemplarHistogramVec) -> Self {
+        let histogram = vec.inner.with_label_values(&[]);
+        Self { vec, histogram }
+    }
+
+    /// Observes `val`, with the exemplar labels of the current thread if any.
+    pub fn observe(&self, val: f64) {
+        self.vec.observe_with(&[], val)
+    }
+
+    /// Observes `val`, with the given exemplar labels.
+    pub fn observe_with_exemplar(&self, val: f64, exemplar_labels: Vec<(&'static str, String)>) {
+        self.vec.observe_with_exemplar(&[], val, exemplar_labels)
+    }
+
+    /// Starts a timer, see `ExemplarHistogramVec::timer_with`.
+    pub fn start_timer(&self) -> ExemplarTimer {
+        self.vec.timer_with(&[])
+    }
+
+    pub fn get_sample_sum(&self) -> f64 {
+        self.histogram.get_sample_sum()
+    }
+
+    pub fn get_sample_count(&self) -> u64 {
+        self.histogram.get_sample_count()
+    }
+
+    pub fn inner(&self) -> &Histogram {
+        &self.histogram
+    }
+}
+
+/// Observes the time elapsed since it was started when dropped.
+#[must_use = "Timer should be kept in a variable otherwise it cannot observe duration"]
+pub struct ExemplarTimer {
+    histogram: Histogram,
+    store: Arc<ExemplarStore>,
+    label_values: Vec<String>,
+    exemplar_labels: Option<ExemplarLabels>,
+    start: Instant,
+}
+
+impl Drop for ExemplarTimer {
+    fn drop(&mut self) {
+        let elapsed = self.start.elapsed().as_secs_f64();
+        self.histogram.observe(elapsed);
+        if let Some(exemplar_labels) = self.exemplar_labels.take() {
+            let label_values: Vec<&str> = self.label_values.iter().map(String::as_str).collect();
+            self.store.record(&label_values, elapsed, exemplar_labels);
+        }
+    }
+}
+
+/// Creates an `ExemplarHistogramVec` and registers it in the default registry.
+pub fn register_exemplar_histogram_vec(
+    opts: HistogramOpts,
+    label_names: &[&str],
+) -> prometheus::Result<ExemplarHistogramVec> {
+    let vec = ExemplarHistogramVec::new(opts, label_names)?;
+    prometheus::register(Box::new(vec.inner.clone()))?;
+    for desc in vec.inner.desc() {
+        EXEMPLAR_STORES
+            .write()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .insert(desc.fq_name.clone(), vec.store.clone());
+    }
+    Ok(vec)
+}
+
+/// Creates an `ExemplarHistogram` and registers it in the default registry.
+pub fn register_exemplar_histogram(opts: HistogramOpts) -> prometheus::Result<ExemplarHistogram> {
+    Ok(ExemplarHistogram::from_vec(
+        register_exemplar_histogram_vec(opts, &[])?,
+    ))
+}
+
+/// An encoder for the OpenMetrics text format, which exports the exemplars of the registered
+/// exemplar histograms.
+///
+/// Counters not named `*_total` are exported as `unknown`, so that the names of their samples do
+/// not change from the Prometheus text format.
+#[derive(Debug, Default)]
+pub struct OpenMetricsEncoder;
+
+impl OpenMetricsEncoder {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Encoder for OpenMetricsEncoder {
+    fn encode<W: Write>(&self, mfs: &[MetricFamily], writer: &mut W) -> prometheus::Result<()> {
+        for mf in mfs {
+            let name = mf.get_name();
+            let (family_name, metric_type) = match mf.get_field_type() {
+                MetricType::COUNTER => match name.strip_suffix("_total") {
+                    Some(family_name) => (family_name, "counter"),
+                    None => (name, "unknown"),
+                },
+                MetricType::GAUGE => (name, "gauge"),
+                MetricType::SUMMARY => (name, "summary"),
+                MetricType::HISTOGRAM => (name, "histogram"),
+                MetricType::UNTYPED => (name, "unknown"),
+            };
+            writeln!(
+                writer,
+                "# HELP {} {}",
+                family_name,
+                escape_help(mf.get_help())
+            )?;
+            writeln!(writer, "# TYPE {} {}", family_name, metric_type)?;
+
+            let store = exemplar_store(name);
+            for metric in mf.get_metric() {
+                let labels = metric.get_label();
+                match mf.get_field_type() {
+                    MetricType::COUNTER => write_sample(
+                        writer,
+                        name,
+                        labels,
+                        None,
+                        metric.get_counter().get_value(),
+                        None,
+                    )?,
+                    MetricType::GAUGE => write_sample(
+                        writer,
+                        name,
+                        labels,
+                        None,
+                        metric.get_gauge().get_value(),
+                        None,
+                    )?,
+                    MetricType::UNTYPED => write_sample(
+                        writer,
+                        name,
+                        labels,
+                        None,
+                        metric.get_untyped().get_value(),
+                        None,
+                    )?,
+                    MetricType::SUMMARY => {
+                        let summary = metric.get_summary();
+                        for quantile in summary.get_quantile() {
+                            let le = format_float(quantile.get_quantile());
+                            write_sample(
+                                writer,
+                                name,
+                                labels,
+                                Some(("quantile", &le)),
+                                quantile.get_value(),
+                                None,
+                            )?;
+                        }
+                        let sum_name = format!("{}_sum", name);
+                        write_sample(
+                            writer,
+                            &sum_name,
+                            labels,
+                            None,
+                            summary.get_sample_sum(),
+                            None,
+                        )?;
+                        let count_name = format!("{}_count", name);
+                        let count = summary.get_sample_count() as f64;
+                        write_sample(writer, &count_name, labels, None, count, None)?;
+                    },
+                    MetricType::HISTOGRAM => {
+                        let histogram = metric.get_histogram();
+                        let exemplars = store.as_ref().and_then(|store| store.get(metric));
+                        let exemplar = |idx: usize| {
+                            exemplars
+                                .as_ref()
+                                .and_then(|exemplars| exemplars.get(idx).cloned().flatten())
+                        };
+
+                        let bucket_name = format!("{}_bucket", name);
+                        let buckets = histogram.get_bucket();
+                        for (idx, bucket) in buckets.iter().enumerate() {
+                            let le = format_float(bucket.get_upper_bound());
+                            write_sample(
+                                writer,
+                                &bucket_name,
+                                labels,
+                                Some(("le", &le)),
+                                bucket.get_cumulative_count() as f64,
+                                exemplar(idx),
+                            )?;
+                        }
+                        write_sample(
+                            writer,
+                            &bucket_name,
+                            labels,
+                            Some(("le", "+Inf")),
+                            histogram.get_sample_count() as f64,
+                            exemplar(buckets.len()),
+                        )?;
+
+                        let sum_name = format!("{}_sum", name);
+                        write_sample(
+                            writer,
+                            &sum_name,
+                            labels,
+                            None,
+                            histogram.get_sample_sum(),
+                            None,
+                        )?;
+                        let count_name = format!("{}_count", name);
+                        let count = histogram.get_sample_count() as f64;
+                        write_sample(writer, &count_name, labels, None, count, None)?;
+                    },
+                }
+            }
+        }
+        writeln!(writer, "# EOF")?;
+        Ok(())
+    }
+
+    fn format_type(&self) -> &str {
+        OPENMETRICS_CONTENT_TYPE
+    }
+}
+
+fn write_sample<W: Write>(
+    writer: &mut W,
+    name: &str,
+    labels: &[LabelPair],
+    additional_label: Option<(&str, &str)>,
+    value: f64,
+    exemplar: Option<Exemplar>,
+) -> prometheus::Result<()> {
+    write!(writer, "{}", name)?;
+    let labels = labels
+        .iter()
+        .map(|label| (label.get_name(), label.get_value()))
+        .chain(additional_label);
+    write_labels(writer, labels)?;
+    write!(writer, " {}", format_float(value))?;
+    if let Some(exemplar) = exemplar {
+        write_exemplar(writer, exemplar)?;
+    }
+    writeln!(writer)?;
+    Ok(())
+}
+
+fn write_exemplar<W: Write>(writer: &mut W, exemplar: Exemplar) -> prometheus::Result<()> {
+    let timestamp = exemplar
+        .timestamp
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs_f64();
+
+    write!(writer, " # ")?;
+    let labels = exemplar
+        .labels
+        .iter()
+        .map(|(name, value)| (*name, value.as_str()));
+    write_labels(writer, labels)?;
+    write!(writer, " {} {:.3}", format_float(exemplar.value), timestamp)?;
+    Ok(())
+}
+
+fn write_labels<'a, W: Write>(
+    writer: &mut W,
+    labels: impl Iterator<Item = (&'a str, &'a str)>,
+) -> prometheus::Result<()> {
+    let mut separator = "{";
+    for (name, value) in labels {
+        write!(
+            writer,
+            "{}{}=\"{}\"",
+            separator,
+            name,
+            escape_label_value(value)
+        )?;
+        separator = ",";
+    }
+    if separator == "," {
+        write!(writer, "}}")?;
+    }
+    Ok(())
+}
+
+fn format_float(value: f64) -> String {
+    if value.is_nan() {
+        "NaN".to_string()
+    } else if value == f64::INFINITY {
+        "+Inf".to_string()
+    } else if value == f64::NEG_INFINITY {
+        "-Inf".to_string()
+    } else {
+        value.to_string()
+    }
+}
+
+fn escape_help(help: &str) -> String {
+    help.replace('\\', "\\\\").replace('\n', "\\n")
+}
+
+fn escape_label_value(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('\n', "\\n")
+        .replace('"', "\\\"")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        current_exemplar_labels, register_exemplar_histogram_vec, set_exemplar_labels,
+        OpenMetricsEncoder,
+    };
+    use prometheus::{core::Collector, histogram_opts, Encoder, IntCounter};
+
+    #[test]
+    fn test_exemplar_labels_guard() {
+        assert!(current_exemplar_labels().is_none());
+        {
+            let _outer = set_exemplar_labels(vec![("block_id", "1".to_string())]);
+            {
+                let _inner = set_exemplar_labels(vec![("block_id", "2".to_string())]);
+                assert_eq!(current_exemplar_labels().unwrap()[0].1, "2");
+            }
+            assert_eq!(current_exemplar_labels().unwrap()[0].1, "1");
+        }
+        assert!(current_exemplar_labels().is_none());
+    }
+
+    #[test]
+    fn test_encode_exemplars() {
+        let histogram = register_exemplar_histogram_vec(
+            histogram_opts!("test_exemplar_histogram", "test", vec![1.0, 2.0]),
+            &["stage"],
+        )
+        .unwrap();
+        histogram.observe_with_exemplar(&["execute"], 1.5, vec![("block_id", "ab".to_string())]);
+        histogram.observe_with(&["execute"], 0.5);
+        {
+            let _labels = set_exemplar_labels(vec![("block_id", "cd".to_string())]);
+            histogram.observe_with(&["execute"], 3.0);
+        }
+        let counter = IntCounter::new("test_exemplar_counter_total", "test").unwrap();
+        counter.inc();
+
+        let mut mfs = histogram.inner().collect();
+        mfs.extend(counter.collect());
+        let mut buffer = vec![];
+        OpenMetricsEncoder::new().encode(&mfs, &mut buffer).unwrap();
+        let encoded = String::from_utf8(buffer).unwrap();
+        let lines: Vec<_> = encoded.lines().collect();
+
+        assert!(lines.contains(&"# TYPE test_exemplar_histogram histogram"));
+        assert!(lines.contains(&"test_exemplar_histogram_bucket{stage=\"execute\",le=\"1\"} 1"));
+        assert!(lines.iter().any(|line| line.starts_with(
+            "test_exemplar_histogram_bucket{stage=\"execute\",le=\"2\"} 2 # {block_id=\"ab\"} 1.5 "
+        )));
+        assert!(lines
+            .iter()
+            .any(|line| line.starts_with(
+                "test_exemplar_histogram_bucket{stage=\"execute\",le=\"+Inf\"} 3 # {block_id=\"cd\"} 3 "
+            )));
+        assert!(lines.contains(&"test_exemplar_histogram_count{stage=\"execute\"} 3"));
+        assert!(lines.contains(&"# TYPE test_exemplar_counter counter"));
+        assert!(lines.contains(&"test_exemplar_counter_total 1"));
+        assert_eq!(lines.last(), Some(&"# EOF"));
+    }
+}
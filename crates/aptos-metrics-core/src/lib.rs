@@ -13,7 +13,9 @@ pub use prometheus::{
 
 mod avg_counter;
 pub use avg_counter::{register_avg_counter, register_avg_counter_vec};
+pub mod cardinality_guard;
 pub mod const_metric;
+pub mod exemplars;
 pub mod op_counters;
 
 pub trait TimerHelper {
@@ -3,6 +3,7 @@
 
 use crate::{
     auth::with_auth,
+    constants::MAX_CONTENT_LENGTH,
     context::Context,
     debug, error,
     errors::{CustomEventIngestError, ServiceError},
@@ -15,11 +16,13 @@ use crate::{
 };
 use anyhow::anyhow;
 use aptos_types::PeerId;
+use flate2::bufread::GzDecoder;
 use gcp_bigquery_client::model::table_data_insert_all_request::TableDataInsertAllRequest;
+use reqwest::header::CONTENT_ENCODING;
 use serde_json::json;
 use std::{str::FromStr, time::Duration};
 use tokio::time::Instant;
-use warp::{filters::BoxedFilter, hyper::StatusCode, reject, reply, Filter, Rejection, Reply};
+use warp::{filters::BoxedFilter, hyper::StatusCode, reject, reply, Buf, Filter, Rejection, Reply};
 
 pub fn custom_event_ingest(context: Context) -> BoxedFilter<(impl Reply,)> {
     warp::path!("ingest" / "custom-event")
@@ -38,6 +41,27 @@ pub fn custom_event_ingest(context: Context) -> BoxedFilter<(impl Reply,)> {
         .boxed()
 }
 
+/// Ingests a batch of telemetry dumps, e.g. the events a node buffered while the service
+/// was unreachable, possibly in gzip format.
+pub fn custom_event_batch_ingest(context: Context) -> BoxedFilter<(impl Reply,)> {
+    warp::path!("ingest" / "custom-events")
+        .and(warp::post())
+        .and(context.clone().filter())
+        .and(with_auth(context, vec![
+            NodeType::Validator,
+            NodeType::ValidatorFullNode,
+            NodeType::PublicFullNode,
+            NodeType::Unknown,
+            NodeType::UnknownValidator,
+            NodeType::UnknownFullNode,
+        ]))
+        .and(warp::header::optional(CONTENT_ENCODING.as_str()))
+        .and(warp::body::content_length_limit(MAX_CONTENT_LENGTH))
+        .and(warp::body::aggregate())
+        .and_then(handle_custom_event_batch)
+        .boxed()
+}
+
 fn validate_custom_event_body(
     claims: &Claims,
     body: &TelemetryDump,
@@ -70,8 +94,60 @@ pub(crate) async fn handle_custom_event(
     validate_custom_event_body(&claims, &body)?;
 
     let mut insert_request = TableDataInsertAllRequest::new();
+    add_event_row(&mut insert_request, &claims, &body, 0)?;
+    insert_rows(&context, insert_request).await?;
+
+    Ok(reply::with_status(reply::reply(), StatusCode::CREATED))
+}
+
+pub(crate) async fn handle_custom_event_batch(
+    context: Context,
+    claims: Claims,
+    encoding: Option<String>,
+    body: impl Buf,
+) -> anyhow::Result<impl Reply, Rejection> {
+    let batch: Vec<TelemetryDump> = match encoding {
+        Some(encoding) if encoding.eq_ignore_ascii_case("gzip") => {
+            serde_json::from_reader(GzDecoder::new(body.reader()))
+        },
+        Some(_) => {
+            return Err(reject::custom(ServiceError::bad_request(
+                CustomEventIngestError::UnexpectedContentEncoding.into(),
+            )))
+        },
+        None => serde_json::from_reader(body.reader()),
+    }
+    .map_err(|e| {
+        debug!("unable to decode and deserialize body: {}", e);
+        ServiceError::bad_request(CustomEventIngestError::UnexpectedPayloadBody.into())
+    })?;
+
+    if batch.is_empty() {
+        return Err(reject::custom(ServiceError::bad_request(
+            CustomEventIngestError::EmptyPayload.into(),
+        )));
+    }
+
+    let mut insert_request = TableDataInsertAllRequest::new();
+    for dump in &batch {
+        validate_custom_event_body(&claims, dump)?;
+        for index in 0..dump.events.len() {
+            add_event_row(&mut insert_request, &claims, dump, index)?;
+        }
+    }
+    insert_rows(&context, insert_request).await?;
 
-    let telemetry_event = &body.events[0];
+    Ok(reply::with_status(reply::reply(), StatusCode::CREATED))
+}
+
+/// Adds the event at the given index of the dump as a row of the insert request
+fn add_event_row(
+    insert_request: &mut TableDataInsertAllRequest,
+    claims: &Claims,
+    body: &TelemetryDump,
+    index: usize,
+) -> anyhow::Result<(), Rejection> {
+    let telemetry_event = &body.events[index];
     let event_params: Vec<serde_json::Value> = telemetry_event
         .params
         .iter()
@@ -86,7 +162,7 @@ pub(crate) async fn handle_custom_event(
     let duration =
         Duration::from_micros(body.timestamp_micros.as_str().parse::<u64>().map_err(|_| {
             ServiceError::bad_request(
-                CustomEventIngestError::InvalidTimestamp(body.timestamp_micros).into(),
+                CustomEventIngestError::InvalidTimestamp(body.timestamp_micros.clone()).into(),
             )
         })?);
 
@@ -101,7 +177,15 @@ pub(crate) async fn handle_custom_event(
         error!("unable to create row: {}", e);
         ServiceError::internal(CustomEventIngestError::from(e).into())
     })?;
+    debug!("row added: {:?}", &row);
+
+    Ok(())
+}
 
+async fn insert_rows(
+    context: &Context,
+    insert_request: TableDataInsertAllRequest,
+) -> anyhow::Result<(), Rejection> {
     let start_timer = Instant::now();
 
     context
@@ -137,9 +221,9 @@ pub(crate) async fn handle_custom_event(
             }
         })?;
 
-    debug!("row inserted succeefully: {:?}", &row);
+    debug!("rows inserted successfully");
 
-    Ok(reply::with_status(reply::reply(), StatusCode::CREATED))
+    Ok(())
 }
 
 #[cfg(test)]
@@ -61,6 +61,12 @@ pub(crate) enum CustomEventIngestError {
     EmptyPayload,
     #[error("invalid payload timestamp: {0}")]
     InvalidTimestamp(String),
+    #[error(
+        "unexpected payload body. Payload should be an array of telemetry dumps possibly in gzip format"
+    )]
+    UnexpectedPayloadBody,
+    #[error("unexpected content encoding. Supported encodings are: gzip")]
+    UnexpectedContentEncoding,
     #[error("unable to insert row into big query")]
     BigQueryClientError(DebugIgnore<BQError>),
     #[error("invalid payload schema: {0}")]
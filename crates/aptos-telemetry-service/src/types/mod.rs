@@ -27,8 +27,8 @@ pub mod common {
         pub uuid: Uuid,
     }
 
-    impl From<Claims> for EventIdentity {
-        fn from(claims: Claims) -> Self {
+    impl From<&Claims> for EventIdentity {
+        fn from(claims: &Claims) -> Self {
             Self {
                 peer_id: claims.peer_id,
                 chain_id: claims.chain_id,
@@ -0,0 +1,148 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    constants::{
+        ENV_APTOS_DISABLE_TELEMETRY_EXECUTION, ENV_APTOS_DISABLE_TELEMETRY_HARDWARE,
+        ENV_APTOS_DISABLE_TELEMETRY_NETWORK,
+    },
+    core_metrics::APTOS_NODE_CORE_METRICS,
+    network_metrics::APTOS_NODE_NETWORK_METRICS,
+    system_information::APTOS_NODE_SYSTEM_INFORMATION,
+};
+use aptos_telemetry_service::types::telemetry::TelemetryEvent;
+use prometheus::proto::MetricFamily;
+use std::{env, fmt};
+
+/// The prefixes of the prometheus metrics in each category
+const NETWORK_METRIC_PREFIXES: &[&str] = &["aptos_network_", "aptos_connections"];
+const EXECUTION_METRIC_PREFIXES: &[&str] = &[
+    "aptos_executor_",
+    "aptos_execution_",
+    "aptos_processed_",
+    "aptos_vm_",
+];
+const HARDWARE_METRIC_PREFIXES: &[&str] = &["node_"];
+
+/// The categories of node metrics that can be individually opted out of,
+/// by setting their environment variable (e.g., `APTOS_DISABLE_TELEMETRY_HARDWARE`).
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum TelemetryCategory {
+    Network,
+    Execution,
+    Hardware,
+}
+
+impl TelemetryCategory {
+    pub const ALL: [TelemetryCategory; 3] = [
+        TelemetryCategory::Network,
+        TelemetryCategory::Execution,
+        TelemetryCategory::Hardware,
+    ];
+
+    fn env_var(&self) -> &'static str {
+        match self {
+            TelemetryCategory::Network => ENV_APTOS_DISABLE_TELEMETRY_NETWORK,
+            TelemetryCategory::Execution => ENV_APTOS_DISABLE_TELEMETRY_EXECUTION,
+            TelemetryCategory::Hardware => ENV_APTOS_DISABLE_TELEMETRY_HARDWARE,
+        }
+    }
+
+    /// Returns true iff the category has not been opted out of
+    #[inline]
+    pub fn is_enabled(&self) -> bool {
+        env::var(self.env_var()).is_err()
+    }
+
+    /// Returns the category of the given custom event, if any
+    fn of_event(event_name: &str) -> Option<Self> {
+        match event_name {
+            APTOS_NODE_NETWORK_METRICS => Some(TelemetryCategory::Network),
+            APTOS_NODE_CORE_METRICS => Some(TelemetryCategory::Execution),
+            APTOS_NODE_SYSTEM_INFORMATION => Some(TelemetryCategory::Hardware),
+            _ => None,
+        }
+    }
+
+    /// Returns the category of the given prometheus metric, if any
+    fn of_metric(metric_name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|category| {
+            let prefixes = match category {
+                TelemetryCategory::Network => NETWORK_METRIC_PREFIXES,
+                TelemetryCategory::Execution => EXECUTION_METRIC_PREFIXES,
+                TelemetryCategory::Hardware => HARDWARE_METRIC_PREFIXES,
+            };
+            prefixes
+                .iter()
+                .any(|prefix| metric_name.starts_with(prefix))
+        })
+    }
+}
+
+impl fmt::Display for TelemetryCategory {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TelemetryCategory::Network => write!(f, "network"),
+            TelemetryCategory::Execution => write!(f, "execution"),
+            TelemetryCategory::Hardware => write!(f, "hardware"),
+        }
+    }
+}
+
+/// Returns the categories that have been opted out of
+pub fn disabled_categories() -> Vec<TelemetryCategory> {
+    TelemetryCategory::ALL
+        .into_iter()
+        .filter(|category| !category.is_enabled())
+        .collect()
+}
+
+/// Returns true iff the custom event doesn't belong to a category that has been opted out of
+pub(crate) fn is_event_enabled(telemetry_event: &TelemetryEvent) -> bool {
+    TelemetryCategory::of_event(&telemetry_event.name)
+        .map_or(true, |category| category.is_enabled())
+}
+
+/// Drops the metric families belonging to the categories that have been opted out of
+pub(crate) fn filter_metric_families(metric_families: Vec<MetricFamily>) -> Vec<MetricFamily> {
+    let disabled_categories = disabled_categories();
+    if disabled_categories.is_empty() {
+        return metric_families;
+    }
+    metric_families
+        .into_iter()
+        .filter(|metric_family| {
+            TelemetryCategory::of_metric(metric_family.get_name())
+                .map_or(true, |category| !disabled_categories.contains(&category))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_metric_categories() {
+        assert_eq!(
+            TelemetryCategory::of_metric("aptos_network_peers"),
+            Some(TelemetryCategory::Network)
+        );
+        assert_eq!(
+            TelemetryCategory::of_metric("aptos_executor_execute_block_seconds"),
+            Some(TelemetryCategory::Execution)
+        );
+        assert_eq!(
+            TelemetryCategory::of_metric("node_system_cpu_usage"),
+            Some(TelemetryCategory::Hardware)
+        );
+        assert_eq!(
+            TelemetryCategory::of_metric("aptos_consensus_last_committed_round"),
+            None
+        );
+        assert_eq!(
+            TelemetryCategory::of_event(APTOS_NODE_SYSTEM_INFORMATION),
+            Some(TelemetryCategory::Hardware)
+        );
+    }
+}
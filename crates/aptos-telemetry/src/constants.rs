@@ -14,6 +14,9 @@ pub(crate) const ENV_APTOS_DISABLE_TELEMETRY_PUSH_EVENTS: &str =
 pub(crate) const ENV_APTOS_DISABLE_PROMETHEUS_NODE_METRICS: &str =
     "APTOS_DISABLE_PROMETHEUS_NODE_METRICS";
 pub(crate) const ENV_APTOS_DISABLE_LOG_ENV_POLLING: &str = "APTOS_DISABLE_LOG_ENV_POLLING";
+pub(crate) const ENV_APTOS_DISABLE_TELEMETRY_NETWORK: &str = "APTOS_DISABLE_TELEMETRY_NETWORK";
+pub(crate) const ENV_APTOS_DISABLE_TELEMETRY_EXECUTION: &str = "APTOS_DISABLE_TELEMETRY_EXECUTION";
+pub(crate) const ENV_APTOS_DISABLE_TELEMETRY_HARDWARE: &str = "APTOS_DISABLE_TELEMETRY_HARDWARE";
 
 pub(crate) const ENV_GA_MEASUREMENT_ID: &str = "GA_MEASUREMENT_ID";
 pub(crate) const ENV_GA_API_SECRET: &str = "GA_API_SECRET";
@@ -43,3 +46,8 @@ pub(crate) const NODE_CONFIG_FREQ_SECS: u64 = 60 * 60; // 60 minutes
 pub(crate) const PROMETHEUS_PUSH_METRICS_FREQ_SECS: u64 = 15; // 15 seconds
 pub(crate) const CHAIN_ACCESS_CHECK_FREQ_SECS: u64 = 30 * 60; // 30 minutes
 pub(crate) const LOG_ENV_POLL_FREQ_SECS: u64 = 5 * 60; // 5 minutes
+pub(crate) const CUSTOM_EVENT_FLUSH_FREQ_SECS: u64 = 60; // 1 minute
+
+// The offline spool of the custom events that could not be sent
+pub(crate) const TELEMETRY_SPOOL_DIR: &str = "telemetry_spool";
+pub(crate) const MAX_TELEMETRY_SPOOL_BATCHES: usize = 1000;
@@ -9,7 +9,7 @@ use prometheus::core::Collector;
 use std::collections::BTreeMap;
 
 /// Core metrics event name
-const APTOS_NODE_CORE_METRICS: &str = "APTOS_NODE_CORE_METRICS";
+pub(crate) const APTOS_NODE_CORE_METRICS: &str = "APTOS_NODE_CORE_METRICS";
 
 /// Core metric keys
 const CONSENSUS_LAST_COMMITTED_ROUND: &str = "consensus_last_committed_round";
@@ -8,8 +8,10 @@ mod core_metrics;
 mod metrics;
 mod network_metrics;
 mod sender;
+mod spool;
 mod telemetry_log_sender;
 
+pub mod categories;
 pub mod cli_metrics;
 pub mod service;
 pub mod system_information;
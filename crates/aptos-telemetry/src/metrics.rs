@@ -76,6 +76,20 @@ pub(crate) fn increment_telemetry_service_failures(event_name: &str) {
         .inc();
 }
 
+/// Counter for spooled custom event batches successfully replayed to Telemetry Service
+pub(crate) static APTOS_TELEMETRY_SPOOL_REPLAYS: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "aptos_telemetry_spool_replays",
+        "Number of spooled custom event batches successfully sent to telemetry service"
+    )
+    .unwrap()
+});
+
+/// Increments the number of spooled custom event batches replayed to Telemetry service
+pub(crate) fn increment_telemetry_spool_replays() {
+    APTOS_TELEMETRY_SPOOL_REPLAYS.inc();
+}
+
 /// Counter for successful log ingest events sent to Telemetry Service
 pub(crate) static APTOS_LOG_INGEST_SUCCESS: Lazy<IntCounter> = Lazy::new(|| {
     register_int_counter!(
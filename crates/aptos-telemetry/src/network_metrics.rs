@@ -7,7 +7,7 @@ use prometheus::core::Collector;
 use std::collections::BTreeMap;
 
 /// Network metrics event name
-const APTOS_NODE_NETWORK_METRICS: &str = "APTOS_NODE_NETWORK_METRICS";
+pub(crate) const APTOS_NODE_NETWORK_METRICS: &str = "APTOS_NODE_NETWORK_METRICS";
 
 /// Network metric keys
 const NETWORK_INBOUND_CONNECTIONS: &str = "network_inbound_connections";
@@ -1,7 +1,12 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::metrics::{self, increment_log_ingest_failures_by, increment_log_ingest_successes_by};
+use crate::{
+    categories,
+    constants::TELEMETRY_SPOOL_DIR,
+    metrics::{self, increment_log_ingest_failures_by, increment_log_ingest_successes_by},
+    spool::TelemetrySpool,
+};
 use anyhow::{anyhow, Error, Result};
 use aptos_config::config::{NodeConfig, RoleType};
 use aptos_crypto::{
@@ -27,6 +32,7 @@ use uuid::Uuid;
 pub const DEFAULT_VERSION_PATH_BASE: &str = "api/v1/";
 
 pub const PROMETHEUS_PUSH_METRICS_TIMEOUT_SECS: u64 = 8;
+pub const CUSTOM_EVENT_BATCH_TIMEOUT_SECS: u64 = 8;
 pub const TELEMETRY_SERVICE_TOTAL_RETRY_DURATION_SECS: u64 = 10;
 
 struct AuthContext {
@@ -55,6 +61,9 @@ pub(crate) struct TelemetrySender {
     client: ClientWithMiddleware,
     auth_context: Arc<AuthContext>,
     uuid: Uuid,
+    /// The custom events waiting for the next flush
+    pending_custom_events: Arc<Mutex<Vec<TelemetryDump>>>,
+    spool: Arc<TelemetrySpool>,
 }
 
 impl TelemetrySender {
@@ -88,6 +97,10 @@ impl TelemetrySender {
             client,
             auth_context: Arc::new(AuthContext::new(node_config)),
             uuid: uuid::Uuid::new_v4(),
+            pending_custom_events: Arc::new(Mutex::new(vec![])),
+            spool: Arc::new(TelemetrySpool::new(
+                node_config.storage.dir().join(TELEMETRY_SPOOL_DIR),
+            )),
         }
     }
 
@@ -128,12 +141,9 @@ impl TelemetrySender {
     ) -> Result<(), anyhow::Error> {
         debug!("Sending Prometheus Metrics");
 
-        let scraped_metrics =
-            prometheus::TextEncoder::new().encode_to_string(&registry.gather())?;
-
-        let mut gzip_encoder = GzEncoder::new(Vec::new(), Compression::default());
-        gzip_encoder.write_all(scraped_metrics.as_bytes())?;
-        let compressed_bytes = gzip_encoder.finish()?;
+        let metric_families = categories::filter_metric_families(registry.gather());
+        let scraped_metrics = prometheus::TextEncoder::new().encode_to_string(&metric_families)?;
+        let compressed_bytes = gzip(scraped_metrics.as_bytes())?;
 
         let response = self
             .send_authenticated_request(
@@ -195,9 +205,7 @@ impl TelemetrySender {
     async fn post_logs(&self, json: &[u8]) -> Result<Response, anyhow::Error> {
         debug!("Sending logs");
 
-        let mut gzip_encoder = GzEncoder::new(Vec::new(), Compression::default());
-        gzip_encoder.write_all(json)?;
-        let compressed_bytes = gzip_encoder.finish()?;
+        let compressed_bytes = gzip(json)?;
 
         // Send the request and wait for a response
         let response = self
@@ -213,29 +221,97 @@ impl TelemetrySender {
         error_for_status_with_body(response).await
     }
 
-    pub async fn try_send_custom_metrics(&self, event_name: String, telemetry_dump: TelemetryDump) {
-        match self.post_custom_metrics(&telemetry_dump.clone()).await {
-            Ok(_) => {
-                metrics::increment_telemetry_service_successes(&event_name);
-                debug!("Custom metrics with name {} sent successfully.", event_name);
-            },
+    /// Queues the custom metrics, to be sent with the next batch
+    pub fn enqueue_custom_metrics(&self, telemetry_dump: TelemetryDump) {
+        self.pending_custom_events.lock().push(telemetry_dump);
+    }
+
+    /// Sends the queued custom metrics as a single compressed batch. If the batch
+    /// cannot be sent, it is spooled to disk. Otherwise, the spooled batches are
+    /// replayed, oldest first, until one fails.
+    pub(crate) async fn flush_custom_metrics(&self) {
+        let batch = std::mem::take(&mut *self.pending_custom_events.lock());
+        if !batch.is_empty() {
+            let event_names: Vec<String> = batch
+                .iter()
+                .flat_map(|telemetry_dump| telemetry_dump.events.iter())
+                .map(|telemetry_event| telemetry_event.name.clone())
+                .collect();
+            let compressed_batch = match serde_json::to_vec(&batch)
+                .map_err(Error::from)
+                .and_then(|json| gzip(&json))
+            {
+                Ok(compressed_batch) => compressed_batch,
+                Err(e) => {
+                    debug!("Failed to compress the custom metrics batch: {}", e);
+                    return;
+                },
+            };
+
+            let result = self
+                .post_custom_metrics_batch(compressed_batch.clone())
+                .await;
+            for event_name in &event_names {
+                if result.is_ok() {
+                    metrics::increment_telemetry_service_successes(event_name);
+                } else {
+                    metrics::increment_telemetry_service_failures(event_name);
+                }
+            }
+            if let Err(e) = result {
+                debug!("Failed to send custom metrics, spooling them: {}", e);
+                if let Err(e) = self.spool.push(&compressed_batch) {
+                    debug!("Failed to spool custom metrics: {}", e);
+                }
+                return;
+            }
+            debug!("Custom metrics batch of {} sent successfully.", batch.len());
+        }
+
+        self.replay_spooled_custom_metrics().await;
+    }
+
+    async fn replay_spooled_custom_metrics(&self) {
+        let spooled_batches = match self.spool.batches() {
+            Ok(spooled_batches) => spooled_batches,
             Err(e) => {
-                metrics::increment_telemetry_service_failures(&event_name);
-                debug!("Failed to send custom metrics: {}", e);
+                debug!("Failed to list the spooled custom metrics: {}", e);
+                return;
             },
+        };
+        for spooled_batch in spooled_batches {
+            let result = match self.spool.read(&spooled_batch) {
+                Ok(compressed_batch) => self.post_custom_metrics_batch(compressed_batch).await,
+                Err(e) => Err(e),
+            };
+            match result {
+                Ok(_) => {
+                    if let Err(e) = self.spool.remove(&spooled_batch) {
+                        debug!("Failed to remove spooled custom metrics: {}", e);
+                        return;
+                    }
+                    metrics::increment_telemetry_spool_replays();
+                },
+                Err(e) => {
+                    debug!("Failed to replay spooled custom metrics: {}", e);
+                    return;
+                },
+            }
         }
     }
 
-    async fn post_custom_metrics(
+    async fn post_custom_metrics_batch(
         &self,
-        telemetry_dump: &TelemetryDump,
+        compressed_batch: Vec<u8>,
     ) -> Result<Response, anyhow::Error> {
         // Send the request and wait for a response
         let response = self
             .send_authenticated_request(
                 self.client
-                    .post(self.build_path("ingest/custom-event")?)
-                    .json::<TelemetryDump>(telemetry_dump),
+                    .post(self.build_path("ingest/custom-events")?)
+                    .header(CONTENT_ENCODING, "gzip")
+                    .body(compressed_batch)
+                    .timeout(Duration::from_secs(CUSTOM_EVENT_BATCH_TIMEOUT_SECS)),
             )
             .await?;
 
@@ -404,6 +480,12 @@ impl TelemetrySender {
     }
 }
 
+fn gzip(bytes: &[u8]) -> Result<Vec<u8>, anyhow::Error> {
+    let mut gzip_encoder = GzEncoder::new(Vec::new(), Compression::default());
+    gzip_encoder.write_all(bytes)?;
+    Ok(gzip_encoder.finish()?)
+}
+
 async fn error_for_status_with_body(response: Response) -> Result<Response, anyhow::Error> {
     if response.status().is_client_error() || response.status().is_server_error() {
         Err(anyhow!(
@@ -424,6 +506,7 @@ mod tests {
     use crate::metrics::{APTOS_TELEMETRY_SERVICE_FAILURE, APTOS_TELEMETRY_SERVICE_SUCCESS};
     use aptos_crypto::Uniform;
     use aptos_telemetry_service::types::telemetry::TelemetryEvent;
+    use aptos_temppath::TempPath;
     use httpmock::MockServer;
     use prometheus::{register_int_counter_vec_with_registry, Registry};
     use std::{
@@ -471,54 +554,21 @@ mod tests {
         assert_eq!(result3.unwrap(), private_key.public_key());
     }
 
-    #[tokio::test]
-    async fn test_post_custom_metrics() {
-        let mut telemetry_event = TelemetryEvent {
-            name: "sample-event".into(),
-            params: BTreeMap::new(),
-        };
-        telemetry_event
-            .params
-            .insert("key-1".into(), "value-1".into());
-        let telemetry_dump = TelemetryDump {
-            client_id: "client-1".into(),
-            user_id: "user-1".into(),
-            timestamp_micros: SystemTime::now()
-                .duration_since(UNIX_EPOCH)
-                .unwrap()
-                .as_micros()
-                .to_string(),
-            events: vec![],
-        };
-
-        let server = MockServer::start();
-        let mock = server.mock(|when, then| {
-            when.method("POST")
-                .header("Authorization", "Bearer SECRET_JWT_TOKEN")
-                .path("/api/v1/ingest/custom-event")
-                .json_body_obj(&telemetry_dump);
-            then.status(200);
-        });
-
+    fn create_test_client(server: &MockServer, spool_dir: &TempPath) -> TelemetrySender {
         let node_config = NodeConfig::default();
-        let client = TelemetrySender::new(
+        let mut client = TelemetrySender::new(
             Url::parse(&server.base_url()).expect("unable to parse base url"),
             ChainId::default(),
             &node_config,
         );
+        client.spool = Arc::new(TelemetrySpool::new(spool_dir.path().to_path_buf()));
         {
             *client.auth_context.token.write() = Some("SECRET_JWT_TOKEN".into());
         }
-
-        let result = client.post_custom_metrics(&telemetry_dump).await;
-
-        mock.assert();
-        assert!(result.is_ok());
+        client
     }
 
-    #[tokio::test]
-    async fn test_try_send_metrics_retry_unauthorized() {
-        let event_name = "sample-event";
+    fn create_test_dump(event_name: &str) -> TelemetryDump {
         let mut telemetry_event = TelemetryEvent {
             name: event_name.into(),
             params: BTreeMap::new(),
@@ -526,7 +576,7 @@ mod tests {
         telemetry_event
             .params
             .insert("key-1".into(), "value-1".into());
-        let telemetry_dump = TelemetryDump {
+        TelemetryDump {
             client_id: "client-1".into(),
             user_id: "user-1".into(),
             timestamp_micros: SystemTime::now()
@@ -534,30 +584,61 @@ mod tests {
                 .unwrap()
                 .as_micros()
                 .to_string(),
-            events: vec![],
-        };
+            events: vec![telemetry_event],
+        }
+    }
+
+    #[tokio::test]
+    async fn test_flush_custom_metrics() {
+        let batch = vec![
+            create_test_dump("sample-event-1"),
+            create_test_dump("sample-event-2"),
+        ];
+        let expected_compressed_bytes = gzip(&serde_json::to_vec(&batch).unwrap()).unwrap();
 
         let server = MockServer::start();
         let mock = server.mock(|when, then| {
-            when.method("POST").path("/api/v1/ingest/custom-event");
-            then.status(401);
+            when.method("POST")
+                .header("Authorization", "Bearer SECRET_JWT_TOKEN")
+                .header("Content-Encoding", "gzip")
+                .path("/api/v1/ingest/custom-events")
+                .body(String::from_utf8_lossy(&expected_compressed_bytes));
+            then.status(200);
         });
 
-        let node_config = NodeConfig::default();
-        let client = TelemetrySender::new(
-            Url::parse(&server.base_url()).expect("unable to parse base url"),
-            ChainId::default(),
-            &node_config,
-        );
-        {
-            *client.auth_context.token.write() = Some("SECRET_JWT_TOKEN".into());
+        let spool_dir = TempPath::new();
+        let client = create_test_client(&server, &spool_dir);
+        for telemetry_dump in batch {
+            client.enqueue_custom_metrics(telemetry_dump);
         }
+        client.flush_custom_metrics().await;
 
-        client
-            .try_send_custom_metrics(event_name.into(), telemetry_dump)
-            .await;
+        // The events are sent as a single batch, and not spooled
+        mock.assert_hits(1);
+        assert!(client.spool.batches().unwrap().is_empty());
 
+        // Nothing is sent if there are no new events
+        client.flush_custom_metrics().await;
         mock.assert_hits(1);
+    }
+
+    #[tokio::test]
+    async fn test_flush_custom_metrics_spools_failures() {
+        let event_name = "sample-spooled-event";
+
+        let server = MockServer::start();
+        let mut failing_mock = server.mock(|when, then| {
+            when.method("POST").path("/api/v1/ingest/custom-events");
+            then.status(401);
+        });
+
+        let spool_dir = TempPath::new();
+        let client = create_test_client(&server, &spool_dir);
+        client.enqueue_custom_metrics(create_test_dump(event_name));
+        client.flush_custom_metrics().await;
+
+        // The request is not retried after the failed reauthentication, and the batch is spooled
+        failing_mock.assert_hits(1);
         assert_eq!(
             APTOS_TELEMETRY_SERVICE_SUCCESS
                 .with_label_values(&[event_name])
@@ -570,6 +651,25 @@ mod tests {
                 .get(),
             1
         );
+        let spooled_batches = client.spool.batches().unwrap();
+        assert_eq!(spooled_batches.len(), 1);
+        let spooled_batch = client.spool.read(&spooled_batches[0]).unwrap();
+
+        // Once the service is reachable again, the spooled batch is replayed as is
+        failing_mock.delete();
+        let mock = server.mock(|when, then| {
+            when.method("POST")
+                .path("/api/v1/ingest/custom-events")
+                .body(String::from_utf8_lossy(&spooled_batch));
+            then.status(200);
+        });
+        {
+            *client.auth_context.token.write() = Some("SECRET_JWT_TOKEN".into());
+        }
+        client.flush_custom_metrics().await;
+
+        mock.assert_hits(1);
+        assert!(client.spool.batches().unwrap().is_empty());
     }
 
     #[tokio::test]
@@ -4,10 +4,15 @@
 #![forbid(unsafe_code)]
 
 use crate::{
-    constants::*, core_metrics::create_core_metric_telemetry_event, metrics,
-    network_metrics::create_network_metric_telemetry_event, sender::TelemetrySender,
+    categories::{self, TelemetryCategory},
+    constants::*,
+    core_metrics::create_core_metric_telemetry_event,
+    metrics,
+    network_metrics::create_network_metric_telemetry_event,
+    sender::TelemetrySender,
     system_information::create_system_info_telemetry_event,
-    telemetry_log_sender::TelemetryLogSender, utils::create_build_info_telemetry_event,
+    telemetry_log_sender::TelemetryLogSender,
+    utils::create_build_info_telemetry_event,
 };
 use aptos_config::config::NodeConfig;
 use aptos_logger::{
@@ -18,6 +23,7 @@ use aptos_telemetry_service::types::telemetry::{TelemetryDump, TelemetryEvent};
 use aptos_types::chain_id::ChainId;
 use futures::channel::mpsc::{self, Receiver};
 use once_cell::sync::Lazy;
+use prometheus::default_registry;
 use rand::Rng;
 use rand_core::OsRng;
 use reqwest::Url;
@@ -39,6 +45,8 @@ const IP_ADDRESS_KEY: &str = "IP_ADDRESS";
 const TELEMETRY_TOKEN_KEY: &str = "TELEMETRY_TOKEN";
 // The default for unknown metric values
 const UNKNOWN_METRIC_VALUE: &str = "UNKNOWN";
+// The placeholder for the values only known when the telemetry is sent
+const RESOLVED_WHEN_SENT_VALUE: &str = "<resolved when sent>";
 
 const APTOS_NODE_CONFIG_EVENT_NAME: &str = "APTOS_NODE_CONFIG";
 
@@ -228,6 +236,16 @@ fn try_spawn_custom_event_sender(
     build_info: BTreeMap<String, String>,
 ) {
     if enable_push_custom_events() {
+        // Periodically flush the custom events in compressed batches
+        let flushing_sender = telemetry_sender.clone();
+        tokio::spawn(run_function_periodically(
+            CUSTOM_EVENT_FLUSH_FREQ_SECS,
+            move || {
+                let flushing_sender = flushing_sender.clone();
+                async move { flushing_sender.flush_custom_metrics().await }
+            },
+        ));
+
         // Spawn the custom event sender
         let peer_id = fetch_peer_id(&node_config);
         tokio::spawn(custom_event_sender(
@@ -352,13 +370,18 @@ async fn send_build_information(
     send_telemetry_event_with_ip(peer_id, chain_id, telemetry_sender, telemetry_event).await;
 }
 
-/// Collects and sends the core node metrics via telemetry
+/// Collects and sends the node config via telemetry
 async fn send_node_config(
     peer_id: String,
     chain_id: String,
     node_config: &NodeConfig,
     telemetry_sender: Option<TelemetrySender>,
 ) {
+    let telemetry_event = create_node_config_telemetry_event(node_config);
+    send_telemetry_event_with_ip(peer_id, chain_id, telemetry_sender, telemetry_event).await;
+}
+
+fn create_node_config_telemetry_event(node_config: &NodeConfig) -> TelemetryEvent {
     let node_config: BTreeMap<String, String> = serde_json::to_value(node_config)
         .map(|value| {
             value
@@ -372,11 +395,10 @@ async fn send_node_config(
         })
         .unwrap_or_default();
 
-    let telemetry_event = TelemetryEvent {
+    TelemetryEvent {
         name: APTOS_NODE_CONFIG_EVENT_NAME.into(),
         params: node_config,
-    };
-    send_telemetry_event_with_ip(peer_id, chain_id, telemetry_sender, telemetry_event).await;
+    }
 }
 
 /// Collects and sends the core node metrics via telemetry
@@ -386,6 +408,9 @@ async fn send_node_core_metrics(
     node_config: &NodeConfig,
     telemetry_sender: Option<TelemetrySender>,
 ) {
+    if !TelemetryCategory::Execution.is_enabled() {
+        return;
+    }
     let telemetry_event = create_core_metric_telemetry_event(node_config).await;
     send_telemetry_event_with_ip(peer_id, chain_id, telemetry_sender, telemetry_event).await;
 }
@@ -396,6 +421,9 @@ async fn send_node_network_metrics(
     chain_id: String,
     telemetry_sender: Option<TelemetrySender>,
 ) {
+    if !TelemetryCategory::Network.is_enabled() {
+        return;
+    }
     let telemetry_event = create_network_metric_telemetry_event().await;
     send_telemetry_event_with_ip(peer_id, chain_id, telemetry_sender, telemetry_event).await;
 }
@@ -406,6 +434,9 @@ async fn send_system_information(
     chain_id: String,
     telemetry_sender: Option<TelemetrySender>,
 ) {
+    if !TelemetryCategory::Hardware.is_enabled() {
+        return;
+    }
     let telemetry_event = create_system_info_telemetry_event().await;
     send_telemetry_event_with_ip(peer_id, chain_id, telemetry_sender, telemetry_event).await;
 }
@@ -481,17 +512,17 @@ async fn send_telemetry_event(
     }
 }
 
-/// Spawns the telemetry event sender on a new thread to avoid blocking
+/// Queues the telemetry event, to be sent with the next batch of custom events
 fn spawn_event_sender_to_telemetry_service(
     event_name: String,
     telemetry_sender: Option<TelemetrySender>,
     telemetry_dump: TelemetryDump,
 ) -> JoinHandle<()> {
     tokio::spawn(async move {
+        debug!("Queueing telemetry event {}", event_name);
         telemetry_sender
             .unwrap()
-            .try_send_custom_metrics(event_name, telemetry_dump)
-            .await;
+            .enqueue_custom_metrics(telemetry_dump);
     })
 }
 
@@ -552,3 +583,63 @@ fn spawn_event_sender_to_google_analytics(
 struct OriginIP {
     origin: String,
 }
+
+/// Returns a human readable report of exactly what the node would send via
+/// telemetry, without sending anything. The IP address and the chain ID are
+/// only resolved when the telemetry is sent, so they are left as placeholders.
+/// The logs are not included, as they are streamed as they are written.
+pub fn dump_telemetry(
+    node_config: &NodeConfig,
+    build_info: BTreeMap<String, String>,
+) -> anyhow::Result<String> {
+    if telemetry_is_disabled() {
+        return Ok(format!(
+            "Aptos telemetry is disabled ({} is set), nothing is sent!",
+            ENV_APTOS_DISABLE_TELEMETRY
+        ));
+    }
+    if enable_prometheus_node_metrics() {
+        aptos_node_resource_metrics::register_node_metrics_collector();
+    }
+
+    let mut custom_events = vec![];
+    if enable_push_custom_events() {
+        let events = futures::executor::block_on(async {
+            vec![
+                create_build_info_telemetry_event(build_info).await,
+                create_system_info_telemetry_event().await,
+                create_core_metric_telemetry_event(node_config).await,
+                create_network_metric_telemetry_event().await,
+                create_node_config_telemetry_event(node_config),
+            ]
+        });
+        for TelemetryEvent { name, mut params } in events {
+            params.insert(IP_ADDRESS_KEY.to_string(), RESOLVED_WHEN_SENT_VALUE.into());
+            params.insert(TELEMETRY_TOKEN_KEY.to_string(), TELEMETRY_TOKEN.clone());
+            params.insert(CHAIN_ID_KEY.into(), RESOLVED_WHEN_SENT_VALUE.into());
+            let telemetry_event = TelemetryEvent { name, params };
+            if categories::is_event_enabled(&telemetry_event) {
+                custom_events.push(telemetry_event);
+            }
+        }
+    }
+
+    let disabled_categories: Vec<String> = categories::disabled_categories()
+        .iter()
+        .map(ToString::to_string)
+        .collect();
+    let mut report = vec![format!(
+        "Disabled telemetry categories: [{}]",
+        disabled_categories.join(", ")
+    )];
+    report.push("Custom events:".into());
+    report.push(serde_json::to_string_pretty(&custom_events)?);
+    report.push("Prometheus metrics:".into());
+    if enable_prometheus_push_metrics() {
+        let metric_families = categories::filter_metric_families(default_registry().gather());
+        report.push(prometheus::TextEncoder::new().encode_to_string(&metric_families)?);
+    } else {
+        report.push("\tNot sent".into());
+    }
+    Ok(report.join("\n"))
+}
@@ -0,0 +1,110 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::constants::MAX_TELEMETRY_SPOOL_BATCHES;
+use anyhow::Result;
+use aptos_logger::debug;
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+const SPOOL_FILE_EXTENSION: &str = "json.gz";
+
+/// An on-disk spool of the compressed custom event batches that could not be
+/// sent, e.g., because the telemetry service was unreachable. The batches are
+/// stored as they would have been sent, and are replayed oldest first. Beyond
+/// [`MAX_TELEMETRY_SPOOL_BATCHES`], the oldest batches are dropped.
+pub(crate) struct TelemetrySpool {
+    dir: PathBuf,
+    max_batches: usize,
+}
+
+impl TelemetrySpool {
+    pub fn new(dir: PathBuf) -> Self {
+        Self::new_with_max_batches(dir, MAX_TELEMETRY_SPOOL_BATCHES)
+    }
+
+    fn new_with_max_batches(dir: PathBuf, max_batches: usize) -> Self {
+        Self { dir, max_batches }
+    }
+
+    /// Stores the compressed batch, dropping the oldest batches if the spool is full
+    pub fn push(&self, compressed_batch: &[u8]) -> Result<()> {
+        fs::create_dir_all(&self.dir)?;
+
+        let mut batches = self.batches()?;
+        while batches.len() >= self.max_batches {
+            let oldest_batch = batches.remove(0);
+            debug!("Telemetry spool is full, dropping {:?}", oldest_batch);
+            fs::remove_file(oldest_batch)?;
+        }
+
+        // The timestamp prefix keeps the files in chronological order
+        let timestamp_micros = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_micros())
+            .unwrap_or_default();
+        let file_name = format!(
+            "{:020}-{:08x}.{}",
+            timestamp_micros,
+            rand::random::<u32>(),
+            SPOOL_FILE_EXTENSION
+        );
+        fs::write(self.dir.join(file_name), compressed_batch)?;
+        Ok(())
+    }
+
+    /// Returns the paths of the spooled batches, oldest first
+    pub fn batches(&self) -> Result<Vec<PathBuf>> {
+        if !self.dir.exists() {
+            return Ok(vec![]);
+        }
+        let mut batches: Vec<PathBuf> = fs::read_dir(&self.dir)?
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| {
+                path.file_name()
+                    .and_then(|file_name| file_name.to_str())
+                    .map_or(false, |file_name| file_name.ends_with(SPOOL_FILE_EXTENSION))
+            })
+            .collect();
+        batches.sort();
+        Ok(batches)
+    }
+
+    pub fn read(&self, batch: &Path) -> Result<Vec<u8>> {
+        Ok(fs::read(batch)?)
+    }
+
+    pub fn remove(&self, batch: &Path) -> Result<()> {
+        Ok(fs::remove_file(batch)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aptos_temppath::TempPath;
+
+    #[test]
+    fn test_spool_drops_oldest_batches() {
+        let dir = TempPath::new();
+        let spool = TelemetrySpool::new_with_max_batches(dir.path().to_path_buf(), 2);
+        assert!(spool.batches().unwrap().is_empty());
+
+        for batch in [b"batch-1", b"batch-2", b"batch-3"] {
+            spool.push(batch).unwrap();
+            // Ensure the timestamps differ
+            std::thread::sleep(std::time::Duration::from_millis(1));
+        }
+
+        let batches = spool.batches().unwrap();
+        assert_eq!(batches.len(), 2);
+        assert_eq!(spool.read(&batches[0]).unwrap(), b"batch-2");
+        assert_eq!(spool.read(&batches[1]).unwrap(), b"batch-3");
+
+        spool.remove(&batches[0]).unwrap();
+        assert_eq!(spool.batches().unwrap(), vec![batches[1].clone()]);
+    }
+}
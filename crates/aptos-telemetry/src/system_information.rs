@@ -9,7 +9,7 @@ use std::collections::BTreeMap;
 use sysinfo::{CpuExt, DiskExt, System, SystemExt};
 
 /// System information event name
-const APTOS_NODE_SYSTEM_INFORMATION: &str = "APTOS_NODE_SYSTEM_INFORMATION";
+pub(crate) const APTOS_NODE_SYSTEM_INFORMATION: &str = "APTOS_NODE_SYSTEM_INFORMATION";
 
 /// System information keys
 const CPU_BRAND: &str = "cpu_brand";
@@ -141,6 +141,7 @@ pub fn profile_transaction_using_debugger(
     // Generate the report
     let path = Path::new("gas-profiling").join(raw_file_name);
     gas_log.generate_html_report(&path, format!("Gas Report - {}", human_readable_name))?;
+    gas_log.generate_profiles(path.join("profiles"), human_readable_name)?;
 
     println!("Gas report saved to {}.", path.display());
 
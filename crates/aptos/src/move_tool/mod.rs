@@ -64,7 +64,7 @@ use move_compiler_v2::Experiment;
 use move_core_types::{identifier::Identifier, language_storage::ModuleId, u256::U256};
 use move_model::metadata::{CompilerVersion, LanguageVersion};
 use move_package::{source_package::layout::SourcePackageLayout, BuildConfig, CompilerConfig};
-use move_unit_test::UnitTestingConfig;
+use move_unit_test::{TestShard, UnitTestingConfig};
 pub use package_hooks::*;
 use serde::{Deserialize, Serialize};
 use serde_json::json;
@@ -522,6 +522,13 @@ pub struct TestPackage {
     #[clap(long = "coverage")]
     pub compute_coverage: bool,
 
+    /// Only run the tests of the shard, given as `index/count`, e.g. `1/4`
+    ///
+    /// This splits the tests across CI jobs. With `--coverage`, the coverage of all the shards
+    /// which ran in the package is merged.
+    #[clap(long)]
+    pub shard: Option<TestShard>,
+
     /// Dump storage state on failure.
     #[clap(long = "dump")]
     pub dump_state: bool,
@@ -577,6 +584,7 @@ impl CliCommand<&'static str> for TestPackage {
             config.clone(),
             UnitTestingConfig {
                 filter: self.filter.clone(),
+                shard: self.shard,
                 report_stacktrace_on_abort: true,
                 report_storage_on_error: self.dump_state,
                 ignore_compile_warnings: self.ignore_compile_warnings,
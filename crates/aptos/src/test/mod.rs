@@ -874,6 +874,7 @@ impl CliTestFramework {
             filter: filter.map(|str| str.to_string()),
             ignore_compile_warnings: false,
             compute_coverage: false,
+            shard: None,
             dump_state: false,
         }
         .execute()
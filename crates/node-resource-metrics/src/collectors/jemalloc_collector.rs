@@ -0,0 +1,165 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::collectors::common::{MeasureLatency, NAMESPACE};
+use aptos_logger::warn;
+use aptos_metrics_core::const_metric::ConstMetric;
+use prometheus::{
+    core::{Collector, Desc, Describer},
+    proto::MetricFamily,
+    Opts,
+};
+use std::{ffi::c_void, mem::size_of, ptr};
+
+const JEMALLOC_SUBSYSTEM: &str = "jemalloc";
+
+const ALLOCATED: &str = "allocated_bytes";
+const ACTIVE: &str = "active_bytes";
+const RESIDENT: &str = "resident_bytes";
+const MAPPED: &str = "mapped_bytes";
+const RETAINED: &str = "retained_bytes";
+const FRAGMENTATION: &str = "fragmentation_ratio";
+
+/// A Collector for exposing the stats of the jemalloc allocator
+pub(crate) struct JemallocCollector {
+    allocated: Desc,
+    active: Desc,
+    resident: Desc,
+    mapped: Desc,
+    retained: Desc,
+    fragmentation: Desc,
+}
+
+impl JemallocCollector {
+    fn new() -> Self {
+        let describe = |name: &str, help: &str| {
+            Opts::new(name, help)
+                .namespace(NAMESPACE)
+                .subsystem(JEMALLOC_SUBSYSTEM)
+                .describe()
+                .unwrap()
+        };
+
+        Self {
+            allocated: describe(ALLOCATED, "Bytes allocated by the application."),
+            active: describe(ACTIVE, "Bytes in the active pages of the allocator."),
+            resident: describe(
+                RESIDENT,
+                "Bytes in the physically resident pages of the allocator.",
+            ),
+            mapped: describe(MAPPED, "Bytes in the active extents of the allocator."),
+            retained: describe(
+                RETAINED,
+                "Bytes in the virtual memory retained by the allocator for future reuse.",
+            ),
+            fragmentation: describe(
+                FRAGMENTATION,
+                "Ratio of the active bytes over the allocated bytes.",
+            ),
+        }
+    }
+}
+
+impl Default for JemallocCollector {
+    fn default() -> Self {
+        JemallocCollector::new()
+    }
+}
+
+/// Reads the `size_t` statistic `name`, which must be NUL-terminated.
+fn read_stat(name: &[u8]) -> Option<usize> {
+    let mut value: usize = 0;
+    let mut len = size_of::<usize>();
+    let result = unsafe {
+        jemalloc_sys::mallctl(
+            name.as_ptr() as *const _,
+            &mut value as *mut _ as *mut c_void,
+            &mut len,
+            ptr::null_mut(),
+            0,
+        )
+    };
+    (result == 0).then_some(value)
+}
+
+/// Refreshes the statistics, which jemalloc caches until the epoch is advanced.
+fn advance_epoch() -> bool {
+    let mut epoch: u64 = 1;
+    let result = unsafe {
+        jemalloc_sys::mallctl(
+            b"epoch\0".as_ptr() as *const _,
+            ptr::null_mut(),
+            ptr::null_mut(),
+            &mut epoch as *mut _ as *mut c_void,
+            size_of::<u64>(),
+        )
+    };
+    result == 0
+}
+
+impl Collector for JemallocCollector {
+    fn desc(&self) -> Vec<&Desc> {
+        vec![
+            &self.allocated,
+            &self.active,
+            &self.resident,
+            &self.mapped,
+            &self.retained,
+            &self.fragmentation,
+        ]
+    }
+
+    fn collect(&self) -> Vec<MetricFamily> {
+        let _measure = MeasureLatency::new("jemalloc".into());
+
+        if !advance_epoch() {
+            warn!("Failed to refresh the jemalloc stats.");
+            return Vec::new();
+        }
+
+        let mut mfs = Vec::new();
+        let mut gauge = |desc: &Desc, value: f64| {
+            mfs.extend(
+                ConstMetric::new_gauge(desc.clone(), value, None)
+                    .unwrap()
+                    .collect(),
+            );
+        };
+
+        let allocated = read_stat(b"stats.allocated\0");
+        let active = read_stat(b"stats.active\0");
+        for (desc, value) in [
+            (&self.allocated, allocated),
+            (&self.active, active),
+            (&self.resident, read_stat(b"stats.resident\0")),
+            (&self.mapped, read_stat(b"stats.mapped\0")),
+            (&self.retained, read_stat(b"stats.retained\0")),
+        ] {
+            if let Some(value) = value {
+                gauge(desc, value as f64);
+            }
+        }
+        if let (Some(allocated), Some(active)) = (allocated, active) {
+            if allocated > 0 {
+                gauge(&self.fragmentation, active as f64 / allocated as f64);
+            }
+        }
+
+        mfs
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::JemallocCollector;
+    use prometheus::Registry;
+
+    #[test]
+    fn test_jemalloc_collector_register() {
+        let collector = JemallocCollector::default();
+
+        let r = Registry::new();
+        let res = r.register(Box::new(collector));
+        assert!(res.is_ok());
+    }
+}
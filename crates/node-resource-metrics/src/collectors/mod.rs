@@ -9,6 +9,7 @@ mod loadavg_collector;
 mod memory_metrics_collector;
 mod network_metrics_collector;
 mod process_metrics_collector;
+mod tokio_runtime_collector;
 
 pub(crate) use basic_node_info_collector::BasicNodeInfoCollector;
 pub(crate) use common::CollectorLatencyCollector;
@@ -18,6 +19,14 @@ pub(crate) use loadavg_collector::LoadAvgCollector;
 pub(crate) use memory_metrics_collector::MemoryMetricsCollector;
 pub(crate) use network_metrics_collector::NetworkMetricsCollector;
 pub(crate) use process_metrics_collector::ProcessMetricsCollector;
+pub use tokio_runtime_collector::register_tokio_runtime;
+pub(crate) use tokio_runtime_collector::TokioRuntimeCollector;
+
+#[cfg(all(feature = "jemalloc", unix))]
+mod jemalloc_collector;
+
+#[cfg(all(feature = "jemalloc", unix))]
+pub(crate) use jemalloc_collector::JemallocCollector;
 
 #[cfg(target_os = "linux")]
 mod linux_collectors;
@@ -0,0 +1,197 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::collectors::common::{MeasureLatency, NAMESPACE};
+use aptos_infallible::Mutex;
+use aptos_metrics_core::const_metric::ConstMetric;
+use once_cell::sync::Lazy;
+use prometheus::{
+    core::{Collector, Desc, Describer},
+    proto::MetricFamily,
+    Opts,
+};
+use tokio::runtime::Handle;
+
+const TOKIO_SUBSYSTEM: &str = "tokio";
+const RUNTIME_LABEL: &str = "runtime";
+
+const NUM_WORKERS: &str = "num_workers";
+const NUM_ALIVE_TASKS: &str = "num_alive_tasks";
+const GLOBAL_QUEUE_DEPTH: &str = "global_queue_depth";
+const LOCAL_QUEUE_DEPTH: &str = "local_queue_depth";
+const BLOCKING_QUEUE_DEPTH: &str = "blocking_queue_depth";
+const SPAWNED_TASKS: &str = "spawned_tasks_total";
+const WORKER_BUSY_SECONDS: &str = "worker_busy_seconds_total";
+
+/// The runtimes to export the stats of, by name.
+static RUNTIMES: Lazy<Mutex<Vec<(String, Handle)>>> = Lazy::new(|| Mutex::new(vec![]));
+
+/// Exports the stats of the runtime of `handle` under the `runtime` label `name`.
+pub fn register_tokio_runtime(name: impl Into<String>, handle: Handle) {
+    let name = name.into();
+    let mut runtimes = RUNTIMES.lock();
+    runtimes.retain(|(registered_name, _)| *registered_name != name);
+    runtimes.push((name, handle));
+}
+
+/// A Collector for exposing the stats of the registered tokio runtimes
+pub(crate) struct TokioRuntimeCollector {
+    num_workers: Desc,
+    num_alive_tasks: Desc,
+    global_queue_depth: Desc,
+    local_queue_depth: Desc,
+    blocking_queue_depth: Desc,
+    spawned_tasks: Desc,
+    worker_busy_seconds: Desc,
+}
+
+impl TokioRuntimeCollector {
+    fn new() -> Self {
+        let describe = |name: &str, help: &str| {
+            Opts::new(name, help)
+                .namespace(NAMESPACE)
+                .subsystem(TOKIO_SUBSYSTEM)
+                .variable_label(RUNTIME_LABEL)
+                .describe()
+                .unwrap()
+        };
+
+        Self {
+            num_workers: describe(NUM_WORKERS, "Number of worker threads."),
+            num_alive_tasks: describe(NUM_ALIVE_TASKS, "Number of tasks alive."),
+            global_queue_depth: describe(
+                GLOBAL_QUEUE_DEPTH,
+                "Number of tasks in the global (injection) queue.",
+            ),
+            local_queue_depth: describe(
+                LOCAL_QUEUE_DEPTH,
+                "Number of tasks in the local queues of all the workers.",
+            ),
+            blocking_queue_depth: describe(
+                BLOCKING_QUEUE_DEPTH,
+                "Number of tasks waiting for a blocking thread.",
+            ),
+            spawned_tasks: describe(SPAWNED_TASKS, "Number of tasks spawned."),
+            worker_busy_seconds: describe(
+                WORKER_BUSY_SECONDS,
+                "Time spent by all the workers running tasks, in seconds.",
+            ),
+        }
+    }
+}
+
+impl Default for TokioRuntimeCollector {
+    fn default() -> Self {
+        TokioRuntimeCollector::new()
+    }
+}
+
+impl Collector for TokioRuntimeCollector {
+    fn desc(&self) -> Vec<&Desc> {
+        vec![
+            &self.num_workers,
+            &self.num_alive_tasks,
+            &self.global_queue_depth,
+            &self.local_queue_depth,
+            &self.blocking_queue_depth,
+            &self.spawned_tasks,
+            &self.worker_busy_seconds,
+        ]
+    }
+
+    fn collect(&self) -> Vec<MetricFamily> {
+        let _measure = MeasureLatency::new("tokio".into());
+
+        let mut mfs = Vec::new();
+        for (name, handle) in RUNTIMES.lock().iter() {
+            let labels = [name.clone()];
+            let gauge = |desc: &Desc, value: usize| {
+                ConstMetric::new_gauge(desc.clone(), value as f64, Some(&labels))
+                    .unwrap()
+                    .collect()
+            };
+            let metrics = handle.metrics();
+
+            mfs.extend(gauge(&self.num_workers, metrics.num_workers()));
+            mfs.extend(gauge(&self.num_alive_tasks, metrics.num_alive_tasks()));
+            mfs.extend(gauge(
+                &self.global_queue_depth,
+                metrics.global_queue_depth(),
+            ));
+
+            // The other stats are only available with `--cfg tokio_unstable`, see
+            // `.cargo/config.toml`.
+            #[cfg(tokio_unstable)]
+            {
+                let local_queue_depth = (0..metrics.num_workers())
+                    .map(|worker| metrics.worker_local_queue_depth(worker))
+                    .sum();
+                let worker_busy_seconds = (0..metrics.num_workers())
+                    .map(|worker| metrics.worker_total_busy_duration(worker).as_secs_f64())
+                    .sum();
+
+                mfs.extend(gauge(&self.local_queue_depth, local_queue_depth));
+                mfs.extend(gauge(
+                    &self.blocking_queue_depth,
+                    metrics.blocking_queue_depth(),
+                ));
+                mfs.extend(
+                    ConstMetric::new_counter(
+                        self.spawned_tasks.clone(),
+                        metrics.spawned_tasks_count() as f64,
+                        Some(&labels),
+                    )
+                    .unwrap()
+                    .collect(),
+                );
+                mfs.extend(
+                    ConstMetric::new_counter(
+                        self.worker_busy_seconds.clone(),
+                        worker_busy_seconds,
+                        Some(&labels),
+                    )
+                    .unwrap()
+                    .collect(),
+                );
+            }
+        }
+
+        mfs
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{register_tokio_runtime, TokioRuntimeCollector};
+    use prometheus::{core::Collector, Registry};
+
+    #[test]
+    fn test_tokio_runtime_collector_register() {
+        let collector = TokioRuntimeCollector::default();
+
+        let r = Registry::new();
+        let res = r.register(Box::new(collector));
+        assert!(res.is_ok());
+    }
+
+    #[test]
+    fn test_tokio_runtime_collector_collect() {
+        let runtime = tokio::runtime::Builder::new_multi_thread()
+            .worker_threads(2)
+            .build()
+            .unwrap();
+        register_tokio_runtime("test", runtime.handle().clone());
+
+        let mfs = TokioRuntimeCollector::default().collect();
+        let num_workers = mfs
+            .iter()
+            .find(|mf| mf.get_name() == "node_tokio_num_workers")
+            .unwrap();
+        let metric = num_workers
+            .get_metric()
+            .iter()
+            .find(|metric| metric.get_label()[0].get_value() == "test")
+            .unwrap();
+        assert_eq!(metric.get_gauge().get_value(), 2.0);
+    }
+}
@@ -5,9 +5,11 @@ use crate::collectors::BasicNodeInfoCollector;
 use aptos_infallible::Mutex;
 use aptos_logger::warn;
 use cfg_if::cfg_if;
+pub use collectors::register_tokio_runtime;
 use collectors::{
     CollectorLatencyCollector, CpuMetricsCollector, DiskMetricsCollector, LoadAvgCollector,
     MemoryMetricsCollector, NetworkMetricsCollector, ProcessMetricsCollector,
+    TokioRuntimeCollector,
 };
 use once_cell::sync::Lazy;
 use prometheus::core::Collector;
@@ -32,12 +34,18 @@ pub fn register_node_metrics_collector() {
     register_collector(Box::<LoadAvgCollector>::default());
     register_collector(Box::<ProcessMetricsCollector>::default());
     register_collector(Box::<BasicNodeInfoCollector>::default());
+    register_collector(Box::<TokioRuntimeCollector>::default());
     cfg_if! {
         if #[cfg(all(target_os="linux"))] {
             register_collector(Box::<collectors::LinuxCpuMetricsCollector>::default());
             register_collector(Box::<collectors::LinuxDiskMetricsCollector>::default());
         }
     }
+    cfg_if! {
+        if #[cfg(all(feature = "jemalloc", unix))] {
+            register_collector(Box::<collectors::JemallocCollector>::default());
+        }
+    }
     register_collector(Box::<CollectorLatencyCollector>::default());
 }
 
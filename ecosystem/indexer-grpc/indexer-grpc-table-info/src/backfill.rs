@@ -0,0 +1,168 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::table_info_service::TableInfoService;
+use anyhow::ensure;
+use aptos_api::context::Context as ApiContext;
+use aptos_config::config::TableInfoBackfillConfig;
+use aptos_db_indexer::db_v2::IndexerAsyncV2;
+use aptos_indexer_grpc_utils::counters::{log_grpc_step, IndexerGrpcStep};
+use aptos_logger::info;
+use std::{sync::Arc, time::Duration};
+
+const SERVICE_TYPE: &str = "table_info_backfill";
+
+/// TableInfoBackfill catches the table info db up with the ledger before the table info service
+/// starts following it, e.g., when the service is stood up on a node with archival data.
+///
+/// The versions up to the ledger version at startup are processed in rounds of `task_count`
+/// batches of `batch_size` transactions, parsed in parallel. The next version to process is
+/// checkpointed in the db at the end of every round, and the target version is stored until
+/// the backfill is done, so that a backfill interrupted by a crash resumes where it stopped.
+/// Rounds are throttled to `max_transactions_per_sec` to leave room for the fullnode workload.
+pub struct TableInfoBackfill {
+    config: TableInfoBackfillConfig,
+    context: Arc<ApiContext>,
+    indexer_async_v2: Arc<IndexerAsyncV2>,
+    service: TableInfoService,
+}
+
+impl TableInfoBackfill {
+    pub fn new(
+        context: Arc<ApiContext>,
+        config: TableInfoBackfillConfig,
+        indexer_async_v2: Arc<IndexerAsyncV2>,
+    ) -> Self {
+        // Snapshots are not taken during the backfill, so the service has no backup operator.
+        let service = TableInfoService::new(
+            context.clone(),
+            indexer_async_v2.next_version(),
+            config.task_count,
+            config.batch_size,
+            None,
+            indexer_async_v2.clone(),
+        );
+        Self {
+            config,
+            context,
+            indexer_async_v2,
+            service,
+        }
+    }
+
+    /// Processes the versions up to the backfill target version, and returns the next version
+    /// to process.
+    pub async fn run(&mut self) -> anyhow::Result<u64> {
+        let target_version = self.target_version()?;
+        let start_version = self.service.current_version;
+        if start_version > target_version {
+            self.indexer_async_v2.set_backfill_target_version(None)?;
+            return Ok(start_version);
+        }
+        self.indexer_async_v2
+            .set_backfill_target_version(Some(target_version))?;
+        info!(
+            start_version = start_version,
+            target_version = target_version,
+            task_count = self.config.task_count,
+            batch_size = self.config.batch_size,
+            "[Table Info] Starting backfill"
+        );
+
+        while self.service.current_version <= target_version {
+            let start_time = std::time::Instant::now();
+            let round_start_version = self.service.current_version;
+            let batches = self.service.get_batches(target_version).await;
+            let transactions = self.service.fetch_batches(batches, target_version).await?;
+            ensure!(
+                !transactions.is_empty(),
+                "No transactions fetched at version {} during backfill",
+                round_start_version
+            );
+            let num_transactions = transactions.len();
+            let last_version = transactions.last().unwrap().version;
+
+            // Checkpoints the next version once all the transactions of the round are parsed.
+            self.service
+                .process_transactions_in_parallel(self.indexer_async_v2.clone(), transactions)
+                .await;
+            self.service.current_version = last_version + 1;
+
+            log_grpc_step(
+                SERVICE_TYPE,
+                IndexerGrpcStep::TableInfoBackfillProcessed,
+                Some(round_start_version as i64),
+                Some(last_version as i64),
+                None,
+                None,
+                Some(start_time.elapsed().as_secs_f64()),
+                None,
+                Some(num_transactions as i64),
+                None,
+            );
+            info!(
+                last_version = last_version,
+                target_version = target_version,
+                progress_percent = (last_version - start_version + 1) as f64 * 100.0
+                    / (target_version - start_version + 1) as f64,
+                "[Table Info] Backfill round processed"
+            );
+
+            if let Some(min_duration) =
+                round_duration(num_transactions, self.config.max_transactions_per_sec)
+            {
+                if let Some(remaining) = min_duration.checked_sub(start_time.elapsed()) {
+                    tokio::time::sleep(remaining).await;
+                }
+            }
+        }
+
+        self.indexer_async_v2.set_backfill_target_version(None)?;
+        info!(
+            target_version = target_version,
+            "[Table Info] Backfill finished"
+        );
+        Ok(self.service.current_version)
+    }
+
+    /// Returns the target version of the interrupted backfill if it is still ahead, and the
+    /// latest ledger version otherwise.
+    fn target_version(&self) -> anyhow::Result<u64> {
+        if let Some(target_version) = self.indexer_async_v2.backfill_target_version()? {
+            if target_version >= self.service.current_version {
+                info!(
+                    target_version = target_version,
+                    "[Table Info] Resuming interrupted backfill"
+                );
+                return Ok(target_version);
+            }
+        }
+        Ok(self
+            .context
+            .get_latest_ledger_info_wrapped()?
+            .ledger_version
+            .0)
+    }
+}
+
+/// Returns the minimum duration of a round processing `num_transactions`, if throttled.
+fn round_duration(num_transactions: usize, max_transactions_per_sec: u64) -> Option<Duration> {
+    if max_transactions_per_sec == 0 {
+        return None;
+    }
+    Some(Duration::from_secs_f64(
+        num_transactions as f64 / max_transactions_per_sec as f64,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_duration() {
+        assert_eq!(round_duration(1000, 0), None);
+        assert_eq!(round_duration(40_000, 20_000), Some(Duration::from_secs(2)));
+        assert_eq!(round_duration(500, 1000), Some(Duration::from_millis(500)));
+    }
+}
@@ -1,6 +1,7 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
+pub mod backfill;
 pub mod backup_restore;
 pub mod internal_indexer_db_service;
 pub mod runtime;
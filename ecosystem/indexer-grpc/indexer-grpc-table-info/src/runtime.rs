@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    backup_restore::gcs::GcsBackupRestoreOperator,
+    backfill::TableInfoBackfill, backup_restore::gcs::GcsBackupRestoreOperator,
     internal_indexer_db_service::InternalIndexerDBService, table_info_service::TableInfoService,
 };
 use aptos_api::context::Context;
@@ -97,9 +97,22 @@ pub fn bootstrap(
             _ => None,
         };
 
+        // Catch up with the ledger before following it, if enabled
+        let mut start_version = indexer_async_v2_clone.next_version();
+        if node_config.indexer_table_info.backfill.enabled {
+            start_version = TableInfoBackfill::new(
+                context.clone(),
+                node_config.indexer_table_info.backfill,
+                indexer_async_v2_clone.clone(),
+            )
+            .run()
+            .await
+            .expect("[Table Info] Failed to backfill table info");
+        }
+
         let mut parser = TableInfoService::new(
             context,
-            indexer_async_v2_clone.next_version(),
+            start_version,
             node_config.indexer_table_info.parser_task_count,
             node_config.indexer_table_info.parser_batch_size,
             backup_restore_operator,
@@ -168,7 +168,7 @@ impl TableInfoService {
         }
     }
 
-    async fn fetch_batches(
+    pub(crate) async fn fetch_batches(
         &self,
         batches: Vec<TransactionBatchInfo>,
         ledger_version: u64,
@@ -214,7 +214,7 @@ impl TableInfoService {
     /// Processing transactions in 2 stages:
     /// 1. Fetch transactions from ledger db
     /// 2. Get write sets from transactions and parse write sets to get handle -> key,value type mapping, write the mapping to the rocksdb
-    async fn process_transactions_in_parallel(
+    pub(crate) async fn process_transactions_in_parallel(
         &self,
         indexer_async_v2: Arc<IndexerAsyncV2>,
         transactions: Vec<TransactionOnChainData>,
@@ -329,7 +329,7 @@ impl TableInfoService {
     /// Retrieves transaction batches based on the provided ledger version.
     /// The function prepares to fetch transactions by determining the start version,
     /// the number of fetches, and the size of each batch.
-    async fn get_batches(&mut self, ledger_version: u64) -> Vec<TransactionBatchInfo> {
+    pub(crate) async fn get_batches(&mut self, ledger_version: u64) -> Vec<TransactionBatchInfo> {
         info!(
             current_version = self.current_version,
             highest_known_version = ledger_version,
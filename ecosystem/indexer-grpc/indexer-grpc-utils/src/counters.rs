@@ -58,6 +58,8 @@ pub enum IndexerGrpcStep {
     TableInfoProcessedBatch,
     // [Indexer Table Info] Processed transactions from fullnode
     TableInfoProcessed,
+    // [Indexer Table Info] Backfilled historical transactions from fullnode
+    TableInfoBackfillProcessed,
     // [Indexer Indices] Processed transactions from AptosDB
     InternalIndexerDBProcessed,
 }
@@ -93,6 +95,7 @@ impl IndexerGrpcStep {
             // Table info service steps
             IndexerGrpcStep::TableInfoProcessedBatch => "1",
             IndexerGrpcStep::TableInfoProcessed => "2",
+            IndexerGrpcStep::TableInfoBackfillProcessed => "3",
             IndexerGrpcStep::InternalIndexerDBProcessed => "1",
         }
     }
@@ -139,6 +142,9 @@ impl IndexerGrpcStep {
             IndexerGrpcStep::TableInfoProcessed => {
                 "[Indexer Table Info] Processed successfully"
             }
+            IndexerGrpcStep::TableInfoBackfillProcessed => {
+                "[Indexer Table Info] Backfilled successfully"
+            }
             IndexerGrpcStep::InternalIndexerDBProcessed => {
                 "[Indexer DB indices] Processed successfully"
             }
@@ -0,0 +1,96 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Partitions a captured block of transactions with the chosen partitioner, without executing it,
+//! and reports how the block would be spread across the shards of the sharded block executor.
+
+use aptos_block_partitioner::{
+    connected_component_partitioner::config::ConnectedComponentBlockPartitionerConfig,
+    partition_report::PartitionReport, v2::config::PartitionerV2Config, PartitionerConfig,
+};
+use aptos_types::transaction::{analyzed_transaction::AnalyzedTransaction, Transaction};
+use clap::{Parser, ValueEnum};
+use std::{fs, path::PathBuf, time::Instant};
+
+#[cfg(unix)]
+#[global_allocator]
+static ALLOC: jemallocator::Jemalloc = jemallocator::Jemalloc;
+
+#[derive(Clone, Copy, Debug, ValueEnum)]
+enum PartitionerType {
+    V2,
+    ConnectedComponent,
+}
+
+#[derive(Debug, Parser)]
+struct Args {
+    /// BCS-serialized `Vec<Transaction>` of the block.
+    #[clap(long)]
+    pub block_file: PathBuf,
+
+    #[clap(long, value_enum, default_value_t = PartitionerType::V2)]
+    pub partitioner: PartitionerType,
+
+    #[clap(long, default_value_t = 8)]
+    pub num_shards: usize,
+
+    /// V2 only.
+    #[clap(long, default_value_t = 4)]
+    pub max_partitioning_rounds: usize,
+
+    /// V2 only.
+    #[clap(long, default_value_t = 0.9)]
+    pub cross_shard_dep_avoid_threshold: f32,
+
+    /// V2 only.
+    #[clap(long)]
+    pub partition_last_round: bool,
+
+    /// Connected-component partitioner only.
+    #[clap(long, default_value_t = 2.0)]
+    pub load_imbalance_tolerance: f32,
+}
+
+impl Args {
+    fn partitioner_config(&self) -> Box<dyn PartitionerConfig> {
+        match self.partitioner {
+            PartitionerType::V2 => Box::new(
+                PartitionerV2Config::default()
+                    .max_partitioning_rounds(self.max_partitioning_rounds)
+                    .cross_shard_dep_avoid_threshold(self.cross_shard_dep_avoid_threshold)
+                    .partition_last_round(self.partition_last_round),
+            ),
+            PartitionerType::ConnectedComponent => {
+                Box::new(ConnectedComponentBlockPartitionerConfig {
+                    load_imbalance_tolerance: self.load_imbalance_tolerance,
+                })
+            },
+        }
+    }
+}
+
+fn main() {
+    let args = Args::parse();
+    let bytes = fs::read(&args.block_file)
+        .unwrap_or_else(|err| panic!("Failed to read {:?}: {}", args.block_file, err));
+    let transactions: Vec<Transaction> = bcs::from_bytes(&bytes)
+        .unwrap_or_else(|err| panic!("Failed to parse {:?}: {}", args.block_file, err));
+    let transactions: Vec<AnalyzedTransaction> =
+        transactions.into_iter().map(|txn| txn.into()).collect();
+
+    let partitioner_config = args.partitioner_config();
+    println!("Partitioner: {:?}", partitioner_config);
+    let partitioner = partitioner_config.build();
+    let now = Instant::now();
+    let partitioned_txns = partitioner.partition(transactions, args.num_shards);
+    let elapsed = now.elapsed();
+
+    println!("Time taken to partition: {:?}", elapsed);
+    println!("{}", PartitionReport::new(&partitioned_txns));
+}
+
+#[test]
+fn verify_tool() {
+    use clap::CommandFactory;
+    Args::command().debug_assert()
+}
@@ -0,0 +1,32 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    connected_component_partitioner::ConnectedComponentBlockPartitioner, BlockPartitioner,
+    PartitionerConfig,
+};
+
+#[derive(Clone, Debug)]
+pub struct ConnectedComponentBlockPartitionerConfig {
+    /// If the size a connected component is larger than `load_imbalance_tolerance * block_size / num_shards`,
+    /// this component is executed by the global executor.
+    ///
+    /// See the comments of `aptos_block_partitioner::connected_component_partitioner::ConnectedComponentBlockPartitioner` for more details.
+    pub load_imbalance_tolerance: f32,
+}
+
+impl Default for ConnectedComponentBlockPartitionerConfig {
+    fn default() -> Self {
+        ConnectedComponentBlockPartitionerConfig {
+            load_imbalance_tolerance: 2.0,
+        }
+    }
+}
+
+impl PartitionerConfig for ConnectedComponentBlockPartitionerConfig {
+    fn build(&self) -> Box<dyn BlockPartitioner> {
+        Box::new(ConnectedComponentBlockPartitioner {
+            load_imbalance_tolerance: self.load_imbalance_tolerance,
+        })
+    }
+}
@@ -0,0 +1,191 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    v2::{
+        counters::BLOCK_PARTITIONING_SECONDS, load_balance::longest_processing_time_first,
+        union_find::UnionFind,
+    },
+    BlockPartitioner,
+};
+use aptos_types::{
+    block_executor::partitioner::{
+        CrossShardDependencies, PartitionedTransactions, SubBlock, SubBlocksForShard,
+        TransactionWithDependencies,
+    },
+    state_store::state_key::StateKey,
+    transaction::analyzed_transaction::{AnalyzedTransaction, StorageLocation},
+};
+use move_core_types::account_address::AccountAddress;
+use std::collections::{HashMap, HashSet};
+
+pub mod config;
+
+#[cfg(test)]
+mod tests;
+
+/// Union-find element shared by all txns that have to be executed by the global executor.
+const GLOBAL_ELEMENT: usize = 0;
+
+/// A `BlockPartitioner` that groups txns into connected components of their estimated read/write sets,
+/// and assigns whole components to the shards using Longest-processing-time-first (LPT) scheduling.
+///
+/// Two txns are in the same component if they have the same sender, or if one of them writes a storage location
+/// that the other one reads or writes (according to the read/write hints), directly or transitively.
+/// Since txns of different components do not conflict, the shards execute a single round without any cross-shard dependency,
+/// which suits p2p-heavy workloads where most components are small.
+///
+/// The following txns are executed by the global executor after all the shards (and keep their relative order):
+/// - txns whose hints contain wildcards or are empty, since what they access is unknown;
+/// - components larger than `load_imbalance_tolerance * block_size / num_shards`;
+/// - all txns in the same component as any of the above.
+///
+/// NOTE: the remote executor does not support global txns yet.
+pub struct ConnectedComponentBlockPartitioner {
+    pub load_imbalance_tolerance: f32,
+}
+
+impl ConnectedComponentBlockPartitioner {
+    /// Returns the union-find elements of each txn, and the number of elements.
+    fn elements_by_txn(transactions: &[AnalyzedTransaction]) -> (Vec<Vec<usize>>, usize) {
+        let written_keys: HashSet<&StateKey> = transactions
+            .iter()
+            .flat_map(|txn| txn.write_hints())
+            .filter_map(|loc| match loc {
+                StorageLocation::Specific(key) => Some(key),
+                _ => None,
+            })
+            .collect();
+
+        let mut num_elements = GLOBAL_ELEMENT + 1;
+        let mut new_element = || {
+            num_elements += 1;
+            num_elements - 1
+        };
+        let mut sender_elements: HashMap<AccountAddress, usize> = HashMap::new();
+        let mut key_elements: HashMap<&StateKey, usize> = HashMap::new();
+        let elements_by_txn = transactions
+            .iter()
+            .map(|txn| {
+                let mut elements = vec![];
+                if let Some(sender) = txn.sender() {
+                    elements.push(
+                        *sender_elements
+                            .entry(sender)
+                            .or_insert_with(&mut new_element),
+                    );
+                }
+                // Reads of locations that no txn writes do not cause conflicts.
+                let keys = txn
+                    .read_hints()
+                    .iter()
+                    .chain(txn.write_hints().iter())
+                    .filter_map(|loc| match loc {
+                        StorageLocation::Specific(key) => Some(key),
+                        _ => None,
+                    })
+                    .filter(|key| written_keys.contains(key));
+                for key in keys {
+                    elements.push(*key_elements.entry(key).or_insert_with(&mut new_element));
+                }
+                if !txn.predictable_transaction()
+                    || (txn.read_hints().is_empty() && txn.write_hints().is_empty())
+                {
+                    elements.push(GLOBAL_ELEMENT);
+                } else if elements.is_empty() {
+                    elements.push(new_element());
+                }
+                elements
+            })
+            .collect();
+        (elements_by_txn, num_elements)
+    }
+}
+
+impl BlockPartitioner for ConnectedComponentBlockPartitioner {
+    fn partition(
+        &self,
+        transactions: Vec<AnalyzedTransaction>,
+        num_shards: usize,
+    ) -> PartitionedTransactions {
+        let _timer = BLOCK_PARTITIONING_SECONDS.start_timer();
+        assert!(num_shards >= 1);
+        let num_txns = transactions.len();
+
+        // Step 1: union-find over the senders and the conflicting storage locations of the txns.
+        let (elements_by_txn, num_elements) = Self::elements_by_txn(&transactions);
+        let mut uf = UnionFind::new(num_elements);
+        for elements in elements_by_txn.iter() {
+            for &element in elements.iter().skip(1) {
+                uf.union(elements[0], element);
+            }
+        }
+
+        // Step 2: collect the components, in the order of their first txn to make the result deterministic.
+        let global_root = uf.find(GLOBAL_ELEMENT);
+        let mut component_idxs_by_root: HashMap<usize, usize> = HashMap::new();
+        let mut txn_idxs_by_component: Vec<Vec<usize>> = vec![];
+        let mut global_txn_idxs: Vec<usize> = vec![];
+        for (txn_idx, elements) in elements_by_txn.iter().enumerate() {
+            let root = uf.find(elements[0]);
+            if root == global_root {
+                global_txn_idxs.push(txn_idx);
+                continue;
+            }
+            let component_idx = *component_idxs_by_root.entry(root).or_insert_with(|| {
+                txn_idxs_by_component.push(vec![]);
+                txn_idxs_by_component.len() - 1
+            });
+            txn_idxs_by_component[component_idx].push(txn_idx);
+        }
+
+        // Step 3: move the components that are too large to the global executor, and assign the others to the shards.
+        let max_component_size =
+            ((num_txns as f32 * self.load_imbalance_tolerance / num_shards as f32).ceil() as usize)
+                .max(1);
+        let (large_components, components): (Vec<Vec<usize>>, Vec<Vec<usize>>) =
+            txn_idxs_by_component
+                .into_iter()
+                .partition(|txn_idxs| txn_idxs.len() > max_component_size);
+        global_txn_idxs.extend(large_components.into_iter().flatten());
+        global_txn_idxs.sort_unstable();
+
+        let component_sizes: Vec<u64> = components
+            .iter()
+            .map(|txn_idxs| txn_idxs.len() as u64)
+            .collect();
+        let (_, shard_ids_by_component) =
+            longest_processing_time_first(&component_sizes, num_shards);
+        let mut txn_idxs_by_shard: Vec<Vec<usize>> = vec![vec![]; num_shards];
+        for (txn_idxs, shard_id) in components.into_iter().zip(shard_ids_by_component) {
+            txn_idxs_by_shard[shard_id].extend(txn_idxs);
+        }
+
+        // Step 4: build a single round of sub-blocks. Within a shard, txns keep their original order.
+        let mut transactions: Vec<Option<AnalyzedTransaction>> =
+            transactions.into_iter().map(Some).collect();
+        let mut take_txn = |txn_idx: usize| {
+            TransactionWithDependencies::new(
+                transactions[txn_idx].take().unwrap(),
+                CrossShardDependencies::default(),
+            )
+        };
+        let mut start_index = 0;
+        let sharded_txns = txn_idxs_by_shard
+            .into_iter()
+            .enumerate()
+            .map(|(shard_id, mut txn_idxs)| {
+                txn_idxs.sort_unstable();
+                let sub_block = SubBlock::new(
+                    start_index,
+                    txn_idxs.iter().map(|&txn_idx| take_txn(txn_idx)).collect(),
+                );
+                start_index += txn_idxs.len();
+                SubBlocksForShard::new(shard_id, vec![sub_block])
+            })
+            .collect();
+        let global_txns = global_txn_idxs.into_iter().map(take_txn).collect();
+
+        PartitionedTransactions::new(sharded_txns, global_txns)
+    }
+}
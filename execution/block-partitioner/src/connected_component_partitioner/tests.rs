@@ -0,0 +1,118 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    connected_component_partitioner::config::ConnectedComponentBlockPartitionerConfig,
+    test_utils::{
+        assert_deterministic_result, create_non_conflicting_p2p_transaction,
+        create_signed_p2p_transaction, generate_test_account, verify_partitioner_output,
+        P2PBlockGenerator,
+    },
+    PartitionerConfig,
+};
+use aptos_crypto::HashValue;
+use aptos_types::transaction::Transaction;
+use rand::thread_rng;
+use std::sync::Arc;
+
+#[test]
+fn test_non_conflicting_txns() {
+    let num_txns = 8;
+    let num_shards = 4;
+    let transactions: Vec<_> = (0..num_txns)
+        .map(|_| create_non_conflicting_p2p_transaction())
+        .collect();
+    let partitioner = ConnectedComponentBlockPartitionerConfig::default().build();
+    let partitioned_txns = partitioner.partition(transactions.clone(), num_shards);
+    verify_partitioner_output(&transactions, &partitioned_txns);
+    assert_eq!(partitioned_txns.num_shards(), num_shards);
+    assert!(partitioned_txns.global_txns.is_empty());
+    for sub_blocks_for_shard in partitioned_txns.sharded_txns() {
+        assert_eq!(sub_blocks_for_shard.num_sub_blocks(), 1);
+        assert_eq!(sub_blocks_for_shard.num_txns(), num_txns / num_shards);
+    }
+}
+
+#[test]
+fn test_conflicting_txns_in_same_shard() {
+    let mut accounts: Vec<_> = (0..3).map(|_| generate_test_account()).collect();
+    let receivers: Vec<_> = (0..3).map(|_| generate_test_account()).collect();
+    // Components: {0, 1} via the receiver, {2, 3} via the sender, {4} and {5}.
+    let mut transactions = vec![];
+    transactions.extend(create_signed_p2p_transaction(&mut accounts[0], vec![
+        &receivers[0],
+    ]));
+    transactions.extend(create_signed_p2p_transaction(&mut accounts[1], vec![
+        &receivers[0],
+    ]));
+    transactions.extend(create_signed_p2p_transaction(&mut accounts[2], vec![
+        &receivers[1],
+        &receivers[2],
+    ]));
+    transactions.push(create_non_conflicting_p2p_transaction());
+    transactions.push(create_non_conflicting_p2p_transaction());
+
+    let partitioner = ConnectedComponentBlockPartitionerConfig::default().build();
+    let partitioned_txns = partitioner.partition(transactions.clone(), 2);
+    verify_partitioner_output(&transactions, &partitioned_txns);
+    assert!(partitioned_txns.global_txns.is_empty());
+
+    let shard_of = |txn_idx: usize| {
+        partitioned_txns
+            .sharded_txns()
+            .iter()
+            .position(|sub_blocks| {
+                sub_blocks
+                    .iter()
+                    .any(|txn| txn.txn() == &transactions[txn_idx])
+            })
+            .unwrap()
+    };
+    assert_eq!(shard_of(0), shard_of(1));
+    assert_eq!(shard_of(2), shard_of(3));
+    assert_ne!(shard_of(0), shard_of(2));
+}
+
+#[test]
+fn test_large_component_and_unknown_txns_are_global() {
+    let mut sender = generate_test_account();
+    let receivers: Vec<_> = (0..4).map(|_| generate_test_account()).collect();
+    let mut transactions = create_signed_p2p_transaction(&mut sender, receivers.iter().collect());
+    transactions.push(create_non_conflicting_p2p_transaction());
+    transactions.push(create_non_conflicting_p2p_transaction());
+    // A txn without any hints.
+    transactions.push(Transaction::StateCheckpoint(HashValue::zero()).into());
+
+    let partitioner = ConnectedComponentBlockPartitionerConfig {
+        load_imbalance_tolerance: 0.5,
+    }
+    .build();
+    let partitioned_txns = partitioner.partition(transactions.clone(), 2);
+    verify_partitioner_output(&transactions, &partitioned_txns);
+    // The 4 txns of the same sender exceed 7 * 0.5 / 2 txns.
+    assert_eq!(partitioned_txns.num_sharded_txns(), 2);
+    assert_eq!(partitioned_txns.global_txns.len(), 5);
+}
+
+#[test]
+fn test_random_p2p_blocks() {
+    let mut rng = thread_rng();
+    let block_gen = P2PBlockGenerator::new(200);
+    let partitioner = ConnectedComponentBlockPartitionerConfig::default().build();
+    for num_shards in [1, 3, 8] {
+        let transactions = block_gen.rand_block(&mut rng, 100);
+        let partitioned_txns = partitioner.partition(transactions.clone(), num_shards);
+        verify_partitioner_output(&transactions, &partitioned_txns);
+        for sub_blocks_for_shard in partitioned_txns.sharded_txns() {
+            for txn in sub_blocks_for_shard.iter() {
+                assert_eq!(txn.cross_shard_dependencies().num_required_edges(), 0);
+            }
+        }
+    }
+}
+
+#[test]
+fn test_deterministic_result() {
+    let partitioner = ConnectedComponentBlockPartitionerConfig::default().build();
+    assert_deterministic_result(Arc::from(partitioner));
+}
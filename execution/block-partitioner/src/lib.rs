@@ -2,6 +2,8 @@
 // Parts of the project are originally copyright © Meta Platforms, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+pub mod connected_component_partitioner;
+pub mod partition_report;
 pub mod v2;
 
 pub mod test_utils;
@@ -0,0 +1,162 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_types::block_executor::partitioner::{
+    CrossShardDependencies, PartitionedTransactions, RoundId, ShardId, GLOBAL_SHARD_ID,
+};
+use std::fmt;
+
+/// Summary of a partitioned block, used to evaluate partitioners offline.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct PartitionReport {
+    /// Number of txns of each sub-block, indexed by round and shard id.
+    pub txns_per_round_and_shard: Vec<Vec<usize>>,
+    pub num_global_txns: usize,
+    /// Required edges whose source txn is executed by another shard (including edges of the
+    /// global txns).
+    pub num_cross_shard_edges: usize,
+    /// Required edges whose source txn is executed by the same shard in an earlier round.
+    pub num_cross_round_edges: usize,
+}
+
+impl PartitionReport {
+    pub fn new(partitioned_txns: &PartitionedTransactions) -> Self {
+        let num_rounds = partitioned_txns
+            .sharded_txns()
+            .iter()
+            .map(|sub_blocks| sub_blocks.num_sub_blocks())
+            .max()
+            .unwrap_or(0);
+        let mut txns_per_round_and_shard = vec![vec![0; partitioned_txns.num_shards()]; num_rounds];
+        let mut num_cross_shard_edges = 0;
+        let mut num_cross_round_edges = 0;
+        let mut count_edges =
+            |shard_id: ShardId, round_id: RoundId, deps: &CrossShardDependencies| {
+                for (source_idx, _) in deps.required_edges_iter() {
+                    if source_idx.shard_id != shard_id {
+                        num_cross_shard_edges += 1;
+                    } else if source_idx.round_id != round_id {
+                        num_cross_round_edges += 1;
+                    }
+                }
+            };
+
+        for (shard_id, sub_blocks) in partitioned_txns.sharded_txns().iter().enumerate() {
+            for (round_id, sub_block) in sub_blocks.sub_block_iter().enumerate() {
+                txns_per_round_and_shard[round_id][shard_id] = sub_block.num_txns();
+                for txn in sub_block.iter() {
+                    count_edges(shard_id, round_id, txn.cross_shard_dependencies());
+                }
+            }
+        }
+        for txn in partitioned_txns.global_txns.iter() {
+            count_edges(GLOBAL_SHARD_ID, num_rounds, txn.cross_shard_dependencies());
+        }
+
+        Self {
+            txns_per_round_and_shard,
+            num_global_txns: partitioned_txns.global_txns.len(),
+            num_cross_shard_edges,
+            num_cross_round_edges,
+        }
+    }
+
+    pub fn num_txns(&self) -> usize {
+        self.txns_per_shard().iter().sum::<usize>() + self.num_global_txns
+    }
+
+    pub fn txns_per_shard(&self) -> Vec<usize> {
+        let num_shards = self.txns_per_round_and_shard.first().map_or(0, Vec::len);
+        (0..num_shards)
+            .map(|shard_id| {
+                self.txns_per_round_and_shard
+                    .iter()
+                    .map(|txns_per_shard| txns_per_shard[shard_id])
+                    .sum()
+            })
+            .collect()
+    }
+
+    /// Number of txns executed one after another, assuming that each shard executes its
+    /// sub-block of a round only once all the shards finished the previous round, and the global
+    /// txns after all the rounds.
+    pub fn critical_path_len(&self) -> usize {
+        self.txns_per_round_and_shard
+            .iter()
+            .map(|txns_per_shard| txns_per_shard.iter().copied().max().unwrap_or(0))
+            .sum::<usize>()
+            + self.num_global_txns
+    }
+
+    /// Speedup of the sharded execution over executing the block on a single shard, if all txns
+    /// took the same time. Cross-shard messaging and the parallelism within a shard are ignored.
+    pub fn predicted_speedup(&self) -> f64 {
+        let critical_path_len = self.critical_path_len();
+        if critical_path_len == 0 {
+            return 1.0;
+        }
+        self.num_txns() as f64 / critical_path_len as f64
+    }
+}
+
+impl fmt::Display for PartitionReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Number of txns: {}", self.num_txns())?;
+        writeln!(f, "Txns per shard: {:?}", self.txns_per_shard())?;
+        for (round_id, txns_per_shard) in self.txns_per_round_and_shard.iter().enumerate() {
+            writeln!(f, "  Round {}: {:?}", round_id, txns_per_shard)?;
+        }
+        writeln!(f, "Global txns: {}", self.num_global_txns)?;
+        writeln!(f, "Cross-shard edges: {}", self.num_cross_shard_edges)?;
+        writeln!(f, "Cross-round edges: {}", self.num_cross_round_edges)?;
+        write!(
+            f,
+            "Predicted speedup: {:.2} (critical path of {} txns)",
+            self.predicted_speedup(),
+            self.critical_path_len()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        connected_component_partitioner::config::ConnectedComponentBlockPartitionerConfig,
+        test_utils::{create_non_conflicting_p2p_transaction, P2PBlockGenerator},
+        v2::config::PartitionerV2Config,
+        PartitionerConfig,
+    };
+    use rand::thread_rng;
+
+    #[test]
+    fn test_report_of_non_conflicting_block() {
+        let transactions: Vec<_> = (0..12)
+            .map(|_| create_non_conflicting_p2p_transaction())
+            .collect();
+        let partitioner = ConnectedComponentBlockPartitionerConfig::default().build();
+        let report = PartitionReport::new(&partitioner.partition(transactions, 4));
+
+        assert_eq!(report.txns_per_round_and_shard, vec![vec![3, 3, 3, 3]]);
+        assert_eq!(report.num_global_txns, 0);
+        assert_eq!(report.num_cross_shard_edges, 0);
+        assert_eq!(report.num_cross_round_edges, 0);
+        assert_eq!(report.critical_path_len(), 3);
+        assert_eq!(report.predicted_speedup(), 4.0);
+    }
+
+    #[test]
+    fn test_report_of_random_block() {
+        let mut rng = thread_rng();
+        let block_gen = P2PBlockGenerator::new(20);
+        let transactions = block_gen.rand_block(&mut rng, 200);
+        let num_shards = 4;
+        let partitioner = PartitionerV2Config::default().build();
+        let report = PartitionReport::new(&partitioner.partition(transactions, num_shards));
+
+        assert_eq!(report.num_txns(), 200);
+        assert_eq!(report.txns_per_shard().len(), num_shards);
+        let speedup = report.predicted_speedup();
+        assert!((1.0..=num_shards as f64).contains(&speedup));
+    }
+}
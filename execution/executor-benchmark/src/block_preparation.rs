@@ -7,12 +7,14 @@ use crate::{
 };
 use aptos_block_partitioner::{BlockPartitioner, PartitionerConfig};
 use aptos_crypto::HashValue;
+use aptos_executor_service::local_executor_helper;
 use aptos_experimental_runtimes::thread_manager::optimal_min_len;
 use aptos_logger::info;
 use aptos_types::{
     block_executor::partitioner::{ExecutableBlock, ExecutableTransactions},
     transaction::{signature_verified_transaction::SignatureVerifiedTransaction, Transaction},
 };
+use aptos_vm::sharded_block_executor::ActiveShards;
 use rayon::iter::{IndexedParallelIterator, IntoParallelIterator, ParallelIterator};
 use std::time::Instant;
 
@@ -30,6 +32,10 @@ pub(crate) struct BlockPreparationStage {
     sig_verify_pool: rayon::ThreadPool,
     /// When execution sharding is enabled, number of executor shards
     num_executor_shards: usize,
+    /// When dynamic sharding is enabled, number of shards the executor currently partitions blocks
+    /// into, instead of `num_executor_shards`. Blocks are prepared ahead of their execution, so it
+    /// follows the load of the last executed block, which may be a few blocks behind.
+    maybe_active_shards: Option<ActiveShards>,
     /// When execution sharding is enabled, partitioner that splits block into shards
     maybe_partitioner: Option<Box<dyn BlockPartitioner>>,
 }
@@ -46,6 +52,11 @@ impl BlockPreparationStage {
             let partitioner = partitioner_config.build();
             Some(partitioner)
         };
+        let maybe_active_shards = if num_shards == 0 {
+            None
+        } else {
+            local_executor_helper::get_local_active_shards()
+        };
 
         let sig_verify_pool = rayon::ThreadPoolBuilder::new()
             .num_threads(num_sig_verify_threads)
@@ -54,6 +65,7 @@ impl BlockPreparationStage {
             .expect("couldn't create sig_verify thread pool");
         Self {
             num_executor_shards: num_shards,
+            maybe_active_shards,
             num_blocks_processed: 0,
             maybe_partitioner,
             sig_verify_pool,
@@ -91,8 +103,11 @@ impl BlockPreparationStage {
                 let analyzed_transactions =
                     sig_verified_txns.into_iter().map(|t| t.into()).collect();
                 let timer = TIMER.with_label_values(&["partition"]).start_timer();
-                let partitioned_txns =
-                    partitioner.partition(analyzed_transactions, self.num_executor_shards);
+                let num_shards = self
+                    .maybe_active_shards
+                    .as_ref()
+                    .map_or(self.num_executor_shards, ActiveShards::get);
+                let partitioned_txns = partitioner.partition(analyzed_transactions, num_shards);
                 timer.stop_and_record();
                 ExecutableBlock::new(block_id, ExecutableTransactions::Sharded(partitioned_txns))
             },
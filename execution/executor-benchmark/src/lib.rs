@@ -14,10 +14,12 @@ pub mod pipeline;
 pub mod transaction_committer;
 pub mod transaction_executor;
 pub mod transaction_generator;
+pub mod transaction_trace;
 
 use crate::{
     db_access::DbAccessUtil, pipeline::Pipeline, transaction_committer::TransactionCommitter,
     transaction_executor::TransactionExecutor, transaction_generator::TransactionGenerator,
+    transaction_trace::TransactionTrace,
 };
 use aptos_block_executor::counters::{
     self as block_executor_counters, GasType, BLOCK_EXECUTOR_INNER_EXECUTE_BLOCK,
@@ -101,6 +103,8 @@ pub enum BenchmarkWorkload {
         shuffle_connected_txns: bool,
         hotspot_probability: Option<f32>,
     },
+    /// Replays a captured trace of real transactions, block by block
+    Trace(TransactionTrace),
 }
 
 enum InitializedBenchmarkWorkload {
@@ -114,6 +118,7 @@ enum InitializedBenchmarkWorkload {
         shuffle_connected_txns: bool,
         hotspot_probability: Option<f32>,
     },
+    Trace(TransactionTrace),
 }
 
 /// Runs the benchmark with given parameters.
@@ -230,9 +235,26 @@ pub fn run_benchmark<V>(
             shuffle_connected_txns,
             hotspot_probability,
         },
+        BenchmarkWorkload::Trace(trace) => InitializedBenchmarkWorkload::Trace(trace),
     };
 
     let start_version = db.reader.expect_synced_version();
+    if let InitializedBenchmarkWorkload::Trace(TransactionTrace {
+        first_version: Some(first_version),
+        ..
+    }) = &initialized_workload
+    {
+        assert_eq!(
+            *first_version,
+            start_version + 1,
+            "Trace must start right after the latest version of the db"
+        );
+    }
+    // Trace transactions are signed by accounts of the network they were captured from.
+    let num_accounts_to_load = match &initialized_workload {
+        InitializedBenchmarkWorkload::Trace(_) => None,
+        _ => Some(num_accounts_to_load),
+    };
     let executor = BlockExecutor::<V>::new(db.clone());
     let (pipeline, block_sender) =
         Pipeline::new(executor, start_version, &pipeline_config, Some(num_blocks));
@@ -243,7 +265,7 @@ pub fn run_benchmark<V>(
         root_account,
         block_sender,
         source_dir,
-        Some(num_accounts_to_load),
+        num_accounts_to_load,
         pipeline_config.num_generator_workers,
         is_keyless,
     );
@@ -280,6 +302,10 @@ pub fn run_benchmark<V>(
             );
             (num_blocks_created, "raw transfer".to_string())
         },
+        InitializedBenchmarkWorkload::Trace(trace) => {
+            let num_blocks_created = generator.run_trace(trace, num_blocks);
+            (num_blocks_created, "transaction trace".to_string())
+        },
     };
     if pipeline_config.generate_then_execute {
         overall_measuring.start_time = Instant::now();
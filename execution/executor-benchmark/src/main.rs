@@ -3,15 +3,18 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use aptos_block_partitioner::{
+    connected_component_partitioner::config::ConnectedComponentBlockPartitionerConfig,
     pre_partition::{
         connected_component::config::ConnectedComponentPartitionerConfig,
         default_pre_partitioner_config, uniform_partitioner::config::UniformPartitionerConfig,
         PrePartitionerConfig,
     },
     v2::config::PartitionerV2Config,
+    PartitionerConfig,
 };
 use aptos_config::config::{
-    EpochSnapshotPrunerConfig, LedgerPrunerConfig, PrunerConfig, StateMerklePrunerConfig,
+    EpochSnapshotPrunerConfig, LedgerPrunerConfig, PrunerConfig, PrunerPacingConfig,
+    StateMerklePrunerConfig,
 };
 use aptos_executor_benchmark::{
     default_benchmark_features,
@@ -25,9 +28,12 @@ use aptos_executor_benchmark::{
         },
     },
     pipeline::PipelineConfig,
+    transaction_trace::{TraceFormat, TransactionTrace},
     BenchmarkWorkload,
 };
-use aptos_executor_service::remote_executor_client;
+use aptos_executor_service::{
+    local_executor_helper, remote_executor_client, remote_executor_config::RemoteExecutorConfig,
+};
 use aptos_experimental_ptx_executor::PtxBlockExecutor;
 #[cfg(target_os = "linux")]
 use aptos_experimental_runtimes::thread_manager::{ThreadConfigStrategy, ThreadManagerBuilder};
@@ -37,7 +43,10 @@ use aptos_push_metrics::MetricsPusher;
 use aptos_transaction_generator_lib::WorkflowProgress;
 use aptos_transaction_workloads_lib::args::TransactionTypeArg;
 use aptos_types::on_chain_config::{FeatureFlag, Features};
-use aptos_vm::{aptos_vm::AptosVMBlockExecutor, AptosVM, VMBlockExecutor};
+use aptos_vm::{
+    aptos_vm::AptosVMBlockExecutor, sharded_block_executor::DynamicShardingConfig, AptosVM,
+    VMBlockExecutor,
+};
 use aptos_vm_environment::prod_configs::set_paranoid_type_checks;
 use clap::{Parser, Subcommand, ValueEnum};
 use once_cell::sync::Lazy;
@@ -105,6 +114,7 @@ impl PrunerOpt {
                 batch_size: self.ledger_pruning_batch_size,
                 user_pruning_window_offset: 0,
             },
+            pacing_config: PrunerPacingConfig::default(),
         }
     }
 }
@@ -182,6 +192,19 @@ struct ShardingOpt {
     remote_executor_addresses: Option<Vec<SocketAddr>>,
     #[clap(long)]
     coordinator_address: Option<SocketAddr>,
+    /// Path to the YAML remote executor config (coordinator and shard addresses, TLS settings
+    /// and shard failure detection), as an alternative to specifying the addresses.
+    #[clap(long, conflicts_with_all = ["remote_executor_addresses", "coordinator_address"])]
+    remote_executor_config: Option<PathBuf>,
+    /// Enables dynamic sharding with local shards: blocks are partitioned into as many of the
+    /// 'num_executor_shards' shards as needed for each shard to execute about this many
+    /// transactions, based on the previously executed block.
+    #[clap(long, conflicts_with_all = ["remote_executor_addresses", "remote_executor_config"])]
+    dynamic_sharding_target_txns_per_shard: Option<usize>,
+    #[clap(long, default_value = "1")]
+    dynamic_sharding_min_shards: usize,
+    #[clap(long, default_value = "2.0")]
+    dynamic_sharding_max_imbalance_factor: f64,
     #[clap(long, default_value = "4")]
     max_partitioning_rounds: usize,
     #[clap(long, default_value = "0.90")]
@@ -199,6 +222,15 @@ struct ShardingOpt {
 }
 
 impl ShardingOpt {
+    fn dynamic_sharding_config(&self) -> Option<DynamicShardingConfig> {
+        self.dynamic_sharding_target_txns_per_shard
+            .map(|target_txns_per_shard| DynamicShardingConfig {
+                min_num_shards: self.dynamic_sharding_min_shards,
+                target_txns_per_shard,
+                max_imbalance_factor: self.dynamic_sharding_max_imbalance_factor,
+            })
+    }
+
     fn pre_partitioner_config(&self) -> Box<dyn PrePartitionerConfig> {
         match self.pre_partitioner.as_deref() {
             None => default_pre_partitioner_config(),
@@ -210,17 +242,20 @@ impl ShardingOpt {
         }
     }
 
-    fn partitioner_config(&self) -> PartitionerV2Config {
+    fn partitioner_config(&self) -> Box<dyn PartitionerConfig> {
         match self.partitioner_version.as_deref() {
-            Some("v2") => PartitionerV2Config {
+            Some("v2") => Box::new(PartitionerV2Config {
                 num_threads: self.partitioner_v2_num_threads,
                 max_partitioning_rounds: self.max_partitioning_rounds,
                 cross_shard_dep_avoid_threshold: self.partitioner_cross_shard_dep_avoid_threshold,
                 dashmap_num_shards: self.partitioner_v2_dashmap_num_shards,
                 partition_last_round: !self.use_global_executor,
                 pre_partitioner_config: self.pre_partitioner_config(),
-            },
-            None => PartitionerV2Config::default(),
+            }),
+            Some("connected-component") => Box::new(ConnectedComponentBlockPartitionerConfig {
+                load_imbalance_tolerance: self.load_imbalance_tolerance,
+            }),
+            None => Box::<PartitionerV2Config>::default(),
             _ => panic!(
                 "Unknown partitioner version: {:?}",
                 self.partitioner_version
@@ -238,6 +273,24 @@ struct ProfilerOpt {
     memory_profiling: bool,
 }
 
+#[derive(Parser, Debug, ValueEnum, Clone, Copy, Default)]
+enum TraceFormatOpt {
+    /// Transaction chunks of a backup
+    #[default]
+    Backup,
+    /// BCS responses of the /transactions endpoint of the REST API
+    RestApi,
+}
+
+impl From<TraceFormatOpt> for TraceFormat {
+    fn from(format: TraceFormatOpt) -> Self {
+        match format {
+            TraceFormatOpt::Backup => TraceFormat::Backup,
+            TraceFormatOpt::RestApi => TraceFormat::RestApi,
+        }
+    }
+}
+
 #[derive(Parser, Debug, ValueEnum, Clone, Default)]
 enum BlockExecutorTypeOpt {
     /// Transaction execution: AptosVM
@@ -389,6 +442,16 @@ enum Command {
         #[clap(long, num_args = 0..)]
         transaction_weights: Vec<usize>,
 
+        /// Replays the transactions captured in these files, in their original blocks, instead
+        /// of generating them. The db must be at the version preceding the trace, e.g. restored
+        /// from a backup of the same network. Real traffic usually needs --allow-aborts,
+        /// --allow-discards and --allow-retries.
+        #[clap(long, num_args = 1.., conflicts_with = "transaction_type")]
+        trace_files: Vec<PathBuf>,
+
+        #[clap(long, value_enum, ignore_case = true, default_value_t)]
+        trace_format: TraceFormatOpt,
+
         #[clap(long, default_value_t = 1)]
         module_working_set_size: usize,
 
@@ -483,6 +546,8 @@ where
             additional_dst_pool_accounts,
             transaction_type,
             transaction_weights,
+            trace_files,
+            trace_format,
             module_working_set_size,
             use_sender_account_pool,
             data_dir,
@@ -495,7 +560,12 @@ where
             //     disable_feature,
             // );
 
-            let workload = if transaction_type.is_empty() {
+            let workload = if !trace_files.is_empty() {
+                BenchmarkWorkload::Trace(
+                    TransactionTrace::load(&trace_files, trace_format.into())
+                        .expect("Failed to load the transaction trace"),
+                )
+            } else if transaction_type.is_empty() {
                 BenchmarkWorkload::Transfer {
                     connected_tx_grps: opt.connected_tx_grps,
                     shuffle_connected_txns: opt.shuffle_connected_txns,
@@ -592,19 +662,22 @@ fn main() {
         execution_threads_per_shard = execution_threads / execution_shards;
     }
 
-    if opt
-        .pipeline_opt
-        .sharding_opt
-        .remote_executor_addresses
-        .is_some()
+    if let Some(config_path) = &opt.pipeline_opt.sharding_opt.remote_executor_config {
+        remote_executor_client::set_remote_executor_config(
+            RemoteExecutorConfig::load(config_path)
+                .expect("Failed to load the remote executor config"),
+        )
+        .expect("Failed to set the remote executor config");
+    }
+    if let Some(remote_executor_addresses) =
+        &opt.pipeline_opt.sharding_opt.remote_executor_addresses
     {
-        remote_executor_client::set_remote_addresses(
-            opt.pipeline_opt
-                .sharding_opt
-                .remote_executor_addresses
-                .clone()
-                .unwrap(),
+        remote_executor_client::set_remote_addresses(remote_executor_addresses.clone());
+        remote_executor_client::set_coordinator_address(
+            opt.pipeline_opt.sharding_opt.coordinator_address.unwrap(),
         );
+    }
+    if !remote_executor_client::get_remote_addresses().is_empty() {
         assert_eq!(
             execution_shards,
             remote_executor_client::get_remote_addresses().len(),
@@ -612,14 +685,15 @@ fn main() {
             execution_shards,
             remote_executor_client::get_remote_addresses().len()
         );
-        remote_executor_client::set_coordinator_address(
-            opt.pipeline_opt.sharding_opt.coordinator_address.unwrap(),
-        );
         // it does not matter because shards are on remote node, but for sake of correctness lets
         // set it
         execution_threads_per_shard = execution_threads;
     }
 
+    if let Some(config) = opt.pipeline_opt.sharding_opt.dynamic_sharding_config() {
+        local_executor_helper::set_dynamic_sharding_config(config);
+    }
+
     if opt.skip_paranoid_checks {
         set_paranoid_type_checks(false);
     }
@@ -7,7 +7,7 @@ use crate::{
     metrics::NUM_TXNS,
     OverallMeasuring, TransactionCommitter, TransactionExecutor,
 };
-use aptos_block_partitioner::v2::config::PartitionerV2Config;
+use aptos_block_partitioner::{v2::config::PartitionerV2Config, PartitionerConfig};
 use aptos_crypto::HashValue;
 use aptos_executor::block_executor::BlockExecutor;
 use aptos_executor_types::{state_compute_result::StateComputeResult, BlockExecutorTrait};
@@ -41,7 +41,8 @@ pub struct PipelineConfig {
     pub num_executor_shards: usize,
     #[derivative(Default(value = "4"))]
     pub num_generator_workers: usize,
-    pub partitioner_config: PartitionerV2Config,
+    #[derivative(Default(value = "Box::<PartitionerV2Config>::default()"))]
+    pub partitioner_config: Box<dyn PartitionerConfig>,
     #[derivative(Default(value = "8"))]
     pub num_sig_verify_threads: usize,
 }
@@ -116,7 +117,7 @@ where
             std::cmp::min(config.num_sig_verify_threads, num_cpus::get()),
             // Assume the distributed executor and the distributed partitioner share the same worker set.
             config.num_executor_shards,
-            &config.partitioner_config,
+            config.partitioner_config.as_ref(),
         );
 
         let mut exe = TransactionExecutor::new(executor_1, parent_block_id, ledger_update_sender);
@@ -5,6 +5,7 @@
 use crate::{
     account_generator::{AccountCache, AccountGenerator},
     metrics::{NUM_TXNS, TIMER},
+    transaction_trace::TransactionTrace,
 };
 use aptos_crypto::ed25519::Ed25519PrivateKey;
 use aptos_logger::info;
@@ -309,6 +310,20 @@ impl TransactionGenerator {
         num_transfer_blocks
     }
 
+    /// Sends the blocks of the trace, up to `num_blocks`, and returns the number of blocks sent.
+    pub fn run_trace(&mut self, trace: TransactionTrace, num_blocks: usize) -> usize {
+        let sender = self.block_sender.as_ref().unwrap();
+        let mut num_blocks_sent = 0;
+        for block in trace.blocks.into_iter().take(num_blocks) {
+            NUM_TXNS
+                .with_label_values(&["generation_done"])
+                .inc_by(block.len() as u64);
+            sender.send(block).unwrap();
+            num_blocks_sent += 1;
+        }
+        num_blocks_sent
+    }
+
     pub fn run_workload(
         &mut self,
         block_size: usize,
@@ -0,0 +1,171 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::{ensure, Context, Result};
+use aptos_api_types::TransactionOnChainData;
+use aptos_logger::info;
+use aptos_types::{
+    contract_event::ContractEvent,
+    transaction::{Transaction, TransactionInfo, Version},
+    write_set::WriteSet,
+};
+use std::{
+    fs::File,
+    io::{BufReader, ErrorKind, Read},
+    path::{Path, PathBuf},
+};
+
+/// Format of the files of a captured transaction trace.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TraceFormat {
+    /// Transaction chunks of a backup: records of BCS encoded
+    /// `(Transaction, TransactionInfo, Vec<ContractEvent>, WriteSet)`, each prefixed with its
+    /// size as a big endian u32.
+    Backup,
+    /// BCS encoded `Vec<TransactionOnChainData>`, as returned by the `/transactions` endpoint of
+    /// the REST API with `Accept: application/x-bcs`.
+    RestApi,
+}
+
+/// Transactions captured from a network, split into the blocks they were committed in, so that
+/// they can be replayed through block execution with the same block boundaries.
+///
+/// The state checkpoints and block epilogues are dropped, as the executor appends its own at the
+/// end of every block. The trace must be replayed on a db which is at the version preceding the
+/// first transaction of the trace, e.g., restored from a backup of the same network.
+pub struct TransactionTrace {
+    /// Version of the first transaction of the trace, if the format records it
+    pub first_version: Option<Version>,
+    pub blocks: Vec<Vec<Transaction>>,
+}
+
+impl TransactionTrace {
+    /// Loads the trace from the files, which are concatenated in the given order.
+    pub fn load(paths: &[PathBuf], format: TraceFormat) -> Result<Self> {
+        let mut first_version = None;
+        let mut transactions = vec![];
+        for path in paths {
+            match format {
+                TraceFormat::Backup => transactions.extend(read_backup_transactions(path)?),
+                TraceFormat::RestApi => {
+                    for txn in read_rest_api_transactions(path)? {
+                        let expected_version =
+                            first_version.map(|version| version + transactions.len() as Version);
+                        ensure!(
+                            expected_version.map_or(true, |version| version == txn.version),
+                            "Trace is not contiguous: expected version {:?}, found {} in {}",
+                            expected_version,
+                            txn.version,
+                            path.display(),
+                        );
+                        first_version.get_or_insert(txn.version);
+                        transactions.push(txn.transaction);
+                    }
+                },
+            }
+        }
+
+        let trace = Self::from_transactions(first_version, transactions);
+        info!(
+            "Loaded trace of {} transactions in {} blocks, starting at version {:?}",
+            trace.num_transactions(),
+            trace.blocks.len(),
+            trace.first_version,
+        );
+        Ok(trace)
+    }
+
+    /// Splits the transactions into blocks, starting a new block at every block metadata
+    /// transaction. Transactions preceding the first block metadata form a block of their own.
+    pub fn from_transactions(
+        first_version: Option<Version>,
+        transactions: impl IntoIterator<Item = Transaction>,
+    ) -> Self {
+        let mut blocks: Vec<Vec<Transaction>> = vec![];
+        for txn in transactions {
+            if txn.is_non_reconfig_block_ending() {
+                continue;
+            }
+            match blocks.last_mut() {
+                Some(block) if !txn.is_block_start() => block.push(txn),
+                _ => blocks.push(vec![txn]),
+            }
+        }
+        Self {
+            first_version,
+            blocks,
+        }
+    }
+
+    pub fn num_transactions(&self) -> usize {
+        self.blocks.iter().map(Vec::len).sum()
+    }
+}
+
+fn read_backup_transactions(path: &Path) -> Result<Vec<Transaction>> {
+    let mut file = BufReader::new(
+        File::open(path).with_context(|| format!("Failed to open {}", path.display()))?,
+    );
+    let mut transactions = vec![];
+    loop {
+        let mut size_buf = [0u8; 4];
+        match file.read_exact(&mut size_buf) {
+            Ok(()) => {},
+            Err(err) if err.kind() == ErrorKind::UnexpectedEof => break,
+            Err(err) => return Err(err.into()),
+        }
+        let mut record = vec![0u8; u32::from_be_bytes(size_buf) as usize];
+        file.read_exact(&mut record)
+            .with_context(|| format!("Truncated record in {}", path.display()))?;
+        let (txn, _, _, _): (Transaction, TransactionInfo, Vec<ContractEvent>, WriteSet) =
+            bcs::from_bytes(&record)?;
+        transactions.push(txn);
+    }
+    Ok(transactions)
+}
+
+fn read_rest_api_transactions(path: &Path) -> Result<Vec<TransactionOnChainData>> {
+    let bytes =
+        std::fs::read(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    Ok(bcs::from_bytes(&bytes)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aptos_crypto::HashValue;
+    use aptos_types::{account_address::AccountAddress, block_metadata::BlockMetadata};
+
+    fn block_metadata(round: u64) -> Transaction {
+        Transaction::BlockMetadata(BlockMetadata::new(
+            HashValue::zero(),
+            0,
+            round,
+            AccountAddress::ZERO,
+            vec![],
+            vec![],
+            round,
+        ))
+    }
+
+    #[test]
+    fn test_split_into_blocks() {
+        let checkpoint = Transaction::StateCheckpoint(HashValue::zero());
+        let trace = TransactionTrace::from_transactions(Some(10), vec![
+            checkpoint.clone(),
+            block_metadata(1),
+            checkpoint.clone(),
+            block_metadata(2),
+            block_metadata(3),
+            checkpoint,
+        ]);
+
+        assert_eq!(trace.first_version, Some(10));
+        assert_eq!(trace.blocks, vec![
+            vec![block_metadata(1)],
+            vec![block_metadata(2)],
+            vec![block_metadata(3)],
+        ]);
+        assert_eq!(trace.num_transactions(), 3);
+    }
+}
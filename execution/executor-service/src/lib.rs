@@ -18,6 +18,7 @@ pub mod process_executor_service;
 mod remote_cordinator_client;
 mod remote_cross_shard_client;
 pub mod remote_executor_client;
+pub mod remote_executor_config;
 pub mod remote_executor_service;
 mod remote_state_view;
 mod remote_state_view_service;
@@ -30,12 +31,14 @@ mod thread_executor_service;
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct RemoteExecutionResult {
+    /// The id of the block the result is for, see [ExecuteBlockCommand].
+    pub block_id: u64,
     pub inner: Result<Vec<Vec<TransactionOutput>>, VMStatus>,
 }
 
 impl RemoteExecutionResult {
-    pub fn new(inner: Result<Vec<Vec<TransactionOutput>>, VMStatus>) -> Self {
-        Self { inner }
+    pub fn new(block_id: u64, inner: Result<Vec<Vec<TransactionOutput>>, VMStatus>) -> Self {
+        Self { block_id, inner }
     }
 }
 
@@ -46,9 +49,13 @@ pub enum RemoteExecutionRequest {
 
 #[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct ExecuteBlockCommand {
+    /// Identifies the block, so that the coordinator can tell the results of this block from the
+    /// late results of a previous block (e.g. one that timed out).
+    pub(crate) block_id: u64,
     pub(crate) sub_blocks: SubBlocksForShard<AnalyzedTransaction>,
     pub(crate) concurrency_level: usize,
     pub(crate) onchain_config: BlockExecutorConfigFromOnchain,
+    pub(crate) block_gas_budget: Option<u64>,
 }
 
 impl ExecuteBlockCommand {
@@ -58,8 +65,14 @@ impl ExecuteBlockCommand {
         SubBlocksForShard<AnalyzedTransaction>,
         usize,
         BlockExecutorConfigFromOnchain,
+        Option<u64>,
     ) {
-        (self.sub_blocks, self.concurrency_level, self.onchain_config)
+        (
+            self.sub_blocks,
+            self.concurrency_level,
+            self.onchain_config,
+            self.block_gas_budget,
+        )
     }
 }
 
@@ -5,17 +5,39 @@ use aptos_infallible::Mutex;
 use aptos_logger::info;
 use aptos_storage_interface::state_store::state_view::cached_state_view::CachedStateView;
 use aptos_vm::{
-    sharded_block_executor::{local_executor_shard::LocalExecutorClient, ShardedBlockExecutor},
+    sharded_block_executor::{
+        local_executor_shard::LocalExecutorClient, ActiveShards, DynamicShardingConfig,
+        ShardedBlockExecutor,
+    },
     AptosVM,
 };
-use once_cell::sync::Lazy;
+use once_cell::sync::{Lazy, OnceCell};
 use std::sync::Arc;
 
+static DYNAMIC_SHARDING_CONFIG: OnceCell<DynamicShardingConfig> = OnceCell::new();
+
+/// Enables dynamic sharding for the local sharded block executor. Must be called before the
+/// executor is first used to have an effect.
+pub fn set_dynamic_sharding_config(config: DynamicShardingConfig) {
+    DYNAMIC_SHARDING_CONFIG.set(config).ok();
+}
+
+/// Returns the number of shards the next block executed by the local sharded block executor
+/// should be partitioned into, if dynamic sharding is enabled.
+pub fn get_local_active_shards() -> Option<ActiveShards> {
+    DYNAMIC_SHARDING_CONFIG
+        .get()
+        .map(|_| SHARDED_BLOCK_EXECUTOR.lock().active_shards())
+}
+
 pub static SHARDED_BLOCK_EXECUTOR: Lazy<
     Arc<Mutex<ShardedBlockExecutor<CachedStateView, LocalExecutorClient<CachedStateView>>>>,
 > = Lazy::new(|| {
     info!("LOCAL_SHARDED_BLOCK_EXECUTOR created");
-    Arc::new(Mutex::new(
-        LocalExecutorClient::create_local_sharded_block_executor(AptosVM::get_num_shards(), None),
-    ))
+    let mut executor =
+        LocalExecutorClient::create_local_sharded_block_executor(AptosVM::get_num_shards(), None);
+    if let Some(config) = DYNAMIC_SHARDING_CONFIG.get() {
+        executor = executor.with_dynamic_sharding(config.clone());
+    }
+    Arc::new(Mutex::new(executor))
 });
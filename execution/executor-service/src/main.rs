@@ -1,10 +1,12 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use aptos_executor_service::process_executor_service::ProcessExecutorService;
+use aptos_executor_service::{
+    process_executor_service::ProcessExecutorService, remote_executor_config::RemoteExecutorConfig,
+};
 use aptos_logger::info;
 use clap::Parser;
-use std::net::SocketAddr;
+use std::{net::SocketAddr, path::PathBuf};
 
 #[derive(Debug, Parser)]
 struct Args {
@@ -17,11 +19,17 @@ struct Args {
     #[clap(long)]
     pub num_shards: usize,
 
-    #[clap(long, num_args = 1..)]
+    #[clap(long, num_args = 1.., required_unless_present = "config_path")]
     pub remote_executor_addresses: Vec<SocketAddr>,
 
-    #[clap(long)]
-    pub coordinator_address: SocketAddr,
+    #[clap(long, required_unless_present = "config_path")]
+    pub coordinator_address: Option<SocketAddr>,
+
+    /// Path to the YAML remote executor config, with the addresses of the coordinator and all
+    /// shards, and optionally TLS settings. Takes precedence over the addresses specified as
+    /// arguments.
+    #[clap(long, conflicts_with_all = ["remote_executor_addresses", "coordinator_address"])]
+    pub config_path: Option<PathBuf>,
 }
 
 fn main() {
@@ -34,13 +42,32 @@ fn main() {
     })
     .expect("Error setting Ctrl-C handler");
 
+    let config = match &args.config_path {
+        Some(config_path) => RemoteExecutorConfig::load(config_path)
+            .expect("Failed to load the remote executor config"),
+        None => RemoteExecutorConfig::new(
+            args.coordinator_address
+                .expect("Coordinator address must be set without the config"),
+            args.remote_executor_addresses,
+        ),
+    };
+    assert_eq!(
+        args.num_shards,
+        config.num_shards(),
+        "Number of shards ({}) must be equal to the number of shard addresses ({}).",
+        args.num_shards,
+        config.num_shards()
+    );
+
     let _exe_service = ProcessExecutorService::new(
         args.shard_id,
         args.num_shards,
         args.num_executor_threads,
-        args.coordinator_address,
-        args.remote_executor_addresses,
-    );
+        config.coordinator_address,
+        config.shard_addresses,
+        config.tls,
+    )
+    .expect("Failed to start the executor service");
 
     rx.recv()
         .expect("Could not receive Ctrl-C msg from channel.");
@@ -4,23 +4,37 @@ use crate::{
     metrics::REMOTE_EXECUTOR_TIMER, remote_state_view::RemoteStateViewClient, ExecuteBlockCommand,
     RemoteExecutionRequest, RemoteExecutionResult,
 };
+use aptos_infallible::Mutex;
+use aptos_logger::error;
 use aptos_secure_net::network_controller::{Message, NetworkController};
 use aptos_types::{
-    block_executor::partitioner::ShardId, state_store::state_key::StateKey,
-    transaction::TransactionOutput, vm_status::VMStatus,
+    block_executor::partitioner::{RoundId, ShardId},
+    state_store::state_key::StateKey,
+    transaction::TransactionOutput,
+    vm_status::VMStatus,
 };
 use aptos_vm::sharded_block_executor::{
     coordinator_client::CoordinatorClient, ExecutorShardCommand,
 };
 use crossbeam_channel::{Receiver, Sender};
 use rayon::prelude::*;
-use std::{net::SocketAddr, sync::Arc};
+use std::{
+    net::SocketAddr,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+};
 
 pub struct RemoteCoordinatorClient {
     state_view_client: Arc<RemoteStateViewClient>,
     command_rx: Receiver<Message>,
     result_tx: Sender<Message>,
     shard_id: ShardId,
+    // Outputs of the rounds executed so far, sent to the coordinator together with the result.
+    round_outputs: Mutex<Vec<Vec<TransactionOutput>>>,
+    // The id of the block being executed, sent back with the result.
+    block_id: AtomicU64,
 }
 
 impl RemoteCoordinatorClient {
@@ -43,6 +57,8 @@ impl RemoteCoordinatorClient {
             command_rx,
             result_tx,
             shard_id,
+            round_outputs: Mutex::new(vec![]),
+            block_id: AtomicU64::new(0),
         }
     }
 
@@ -78,43 +94,81 @@ impl RemoteCoordinatorClient {
 
 impl CoordinatorClient<RemoteStateViewClient> for RemoteCoordinatorClient {
     fn receive_execute_command(&self) -> ExecutorShardCommand<RemoteStateViewClient> {
-        match self.command_rx.recv() {
-            Ok(message) => {
-                let _rx_timer = REMOTE_EXECUTOR_TIMER
-                    .with_label_values(&[&self.shard_id.to_string(), "cmd_rx"])
-                    .start_timer();
-                let bcs_deser_timer = REMOTE_EXECUTOR_TIMER
-                    .with_label_values(&[&self.shard_id.to_string(), "cmd_rx_bcs_deser"])
-                    .start_timer();
-                let request: RemoteExecutionRequest = bcs::from_bytes(&message.data).unwrap();
-                drop(bcs_deser_timer);
+        loop {
+            let message = match self.command_rx.recv() {
+                Ok(message) => message,
+                Err(_) => return ExecutorShardCommand::Stop,
+            };
+            let _rx_timer = REMOTE_EXECUTOR_TIMER
+                .with_label_values(&[&self.shard_id.to_string(), "cmd_rx"])
+                .start_timer();
+            let bcs_deser_timer = REMOTE_EXECUTOR_TIMER
+                .with_label_values(&[&self.shard_id.to_string(), "cmd_rx_bcs_deser"])
+                .start_timer();
+            let request: RemoteExecutionRequest = match bcs::from_bytes(&message.data) {
+                Ok(request) => request,
+                Err(e) => {
+                    // The coordinator detects the missing result with its timeout.
+                    error!(
+                        "Shard {} failed to deserialize the execute command: {}",
+                        self.shard_id, e
+                    );
+                    continue;
+                },
+            };
+            drop(bcs_deser_timer);
 
-                match request {
-                    RemoteExecutionRequest::ExecuteBlock(command) => {
-                        let init_prefetch_timer = REMOTE_EXECUTOR_TIMER
-                            .with_label_values(&[&self.shard_id.to_string(), "init_prefetch"])
-                            .start_timer();
-                        let state_keys = Self::extract_state_keys(&command);
-                        self.state_view_client.init_for_block(state_keys);
-                        drop(init_prefetch_timer);
+            match request {
+                RemoteExecutionRequest::ExecuteBlock(command) => {
+                    let init_prefetch_timer = REMOTE_EXECUTOR_TIMER
+                        .with_label_values(&[&self.shard_id.to_string(), "init_prefetch"])
+                        .start_timer();
+                    let state_keys = Self::extract_state_keys(&command);
+                    self.state_view_client.init_for_block(state_keys);
+                    drop(init_prefetch_timer);
 
-                        let (sub_blocks, concurrency, onchain_config) = command.into();
-                        ExecutorShardCommand::ExecuteSubBlocks(
-                            self.state_view_client.clone(),
-                            sub_blocks,
-                            concurrency,
-                            onchain_config,
-                        )
-                    },
-                }
-            },
-            Err(_) => ExecutorShardCommand::Stop,
+                    self.block_id.store(command.block_id, Ordering::Relaxed);
+                    let (sub_blocks, concurrency, onchain_config, block_gas_budget) =
+                        command.into();
+                    return ExecutorShardCommand::ExecuteSubBlocks(
+                        self.state_view_client.clone(),
+                        sub_blocks,
+                        concurrency,
+                        onchain_config,
+                        block_gas_budget,
+                    );
+                },
+            }
         }
     }
 
-    fn send_execution_result(&self, result: Result<Vec<Vec<TransactionOutput>>, VMStatus>) {
-        let remote_execution_result = RemoteExecutionResult::new(result);
-        let output_message = bcs::to_bytes(&remote_execution_result).unwrap();
-        self.result_tx.send(Message::new(output_message)).unwrap();
+    fn send_round_output(&self, round: RoundId, output: Vec<TransactionOutput>) {
+        let mut round_outputs = self.round_outputs.lock();
+        assert_eq!(round, round_outputs.len(), "Rounds must be sent in order");
+        round_outputs.push(output);
+    }
+
+    fn send_execution_result(&self, result: Result<(), VMStatus>) {
+        let round_outputs = std::mem::take(&mut *self.round_outputs.lock());
+        let remote_execution_result = RemoteExecutionResult::new(
+            self.block_id.load(Ordering::Relaxed),
+            result.map(|()| round_outputs),
+        );
+        // On failure, the coordinator detects the missing result with its timeout.
+        match bcs::to_bytes(&remote_execution_result) {
+            Ok(output_message) => {
+                if self.result_tx.send(Message::new(output_message)).is_err() {
+                    error!(
+                        "Shard {} failed to send the execution result, the network controller \
+                         was shut down",
+                        self.shard_id
+                    );
+                }
+            },
+            Err(e) => error!(
+                "Shard {} failed to serialize the execution result: {}",
+                self.shard_id, e
+            ),
+        }
     }
 }
@@ -2,36 +2,50 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 use crate::{
+    remote_executor_config::RemoteExecutorConfig,
     remote_state_view_service::RemoteStateViewService, ExecuteBlockCommand, RemoteExecutionRequest,
     RemoteExecutionResult,
 };
-use aptos_logger::{info, trace};
-use aptos_secure_net::network_controller::{Message, NetworkController};
+use aptos_logger::{error, info, trace, warn};
+use aptos_secure_net::{
+    grpc_network_service::tls::GRPCTlsConfig,
+    network_controller::{Message, NetworkController},
+};
 use aptos_storage_interface::state_store::state_view::cached_state_view::CachedStateView;
 use aptos_types::{
     block_executor::{
-        config::BlockExecutorConfigFromOnchain, partitioner::PartitionedTransactions,
+        config::BlockExecutorConfigFromOnchain,
+        partitioner::{PartitionedTransactions, SubBlocksForShard},
     },
     state_store::StateView,
-    transaction::TransactionOutput,
-    vm_status::VMStatus,
+    transaction::analyzed_transaction::AnalyzedTransaction,
+    vm_status::{StatusCode, VMStatus},
 };
 use aptos_vm::sharded_block_executor::{
+    block_gas_budget_per_executor,
     executor_client::{ExecutorClient, ShardedExecutionOutput},
-    ShardedBlockExecutor,
+    ShardFailurePolicy, ShardedBlockExecutor,
 };
-use crossbeam_channel::{Receiver, Sender};
+use crossbeam_channel::{Receiver, Select, Sender};
 use once_cell::sync::{Lazy, OnceCell};
 use std::{
+    io,
     net::{IpAddr, Ipv4Addr, SocketAddr},
-    sync::{Arc, Mutex},
+    sync::{
+        atomic::{AtomicBool, AtomicU64, Ordering},
+        Arc,
+    },
     thread,
+    time::{Duration, Instant},
 };
 
 pub static COORDINATOR_PORT: u16 = 52200;
 
 static REMOTE_ADDRESSES: OnceCell<Vec<SocketAddr>> = OnceCell::new();
 static COORDINATOR_ADDRESS: OnceCell<SocketAddr> = OnceCell::new();
+static TLS_CONFIG: OnceCell<GRPCTlsConfig> = OnceCell::new();
+static SHARD_RESPONSE_TIMEOUT: OnceCell<Duration> = OnceCell::new();
+static SHARD_FAILURE_POLICY: OnceCell<ShardFailurePolicy> = OnceCell::new();
 
 pub fn set_remote_addresses(addresses: Vec<SocketAddr>) {
     REMOTE_ADDRESSES.set(addresses).ok();
@@ -55,21 +69,57 @@ pub fn get_coordinator_address() -> SocketAddr {
     }
 }
 
+pub fn get_tls_config() -> Option<GRPCTlsConfig> {
+    TLS_CONFIG.get().cloned()
+}
+
+pub fn get_shard_response_timeout() -> Option<Duration> {
+    SHARD_RESPONSE_TIMEOUT.get().copied()
+}
+
+pub fn get_shard_failure_policy() -> ShardFailurePolicy {
+    SHARD_FAILURE_POLICY.get().copied().unwrap_or_default()
+}
+
+/// Sets the addresses of the coordinator and the shards, as well as TLS and failure handling
+/// settings, from the config. Fails if the TLS certificates or key can't be loaded.
+pub fn set_remote_executor_config(config: RemoteExecutorConfig) -> io::Result<()> {
+    if let Some(tls_config) = &config.tls {
+        tls_config.load()?;
+    }
+    SHARD_FAILURE_POLICY.set(config.shard_failure_policy).ok();
+    if let Some(timeout) = config.shard_response_timeout() {
+        SHARD_RESPONSE_TIMEOUT.set(timeout).ok();
+    }
+    if let Some(tls_config) = config.tls {
+        TLS_CONFIG.set(tls_config).ok();
+    }
+    set_coordinator_address(config.coordinator_address);
+    set_remote_addresses(config.shard_addresses);
+    Ok(())
+}
+
+/// The remote sharded block executor, or the error if it could not be created (e.g. because the
+/// TLS certificates can't be loaded).
 pub static REMOTE_SHARDED_BLOCK_EXECUTOR: Lazy<
-    Arc<
-        aptos_infallible::Mutex<
-            ShardedBlockExecutor<CachedStateView, RemoteExecutorClient<CachedStateView>>,
+    io::Result<
+        Arc<
+            aptos_infallible::Mutex<
+                ShardedBlockExecutor<CachedStateView, RemoteExecutorClient<CachedStateView>>,
+            >,
         >,
     >,
 > = Lazy::new(|| {
+    let executor = RemoteExecutorClient::create_remote_sharded_block_executor(
+        get_coordinator_address(),
+        get_remote_addresses(),
+        None,
+        get_tls_config(),
+        get_shard_response_timeout(),
+    )?
+    .with_shard_failure_policy(get_shard_failure_policy());
     info!("REMOTE_SHARDED_BLOCK_EXECUTOR created");
-    Arc::new(aptos_infallible::Mutex::new(
-        RemoteExecutorClient::create_remote_sharded_block_executor(
-            get_coordinator_address(),
-            get_remote_addresses(),
-            None,
-        ),
-    ))
+    Ok(Arc::new(aptos_infallible::Mutex::new(executor)))
 });
 
 #[allow(dead_code)]
@@ -80,11 +130,20 @@ pub struct RemoteExecutorClient<S: StateView + Sync + Send + 'static> {
     network_controller: NetworkController,
     state_view_service: Arc<RemoteStateViewService<S>>,
     // Channels to send execute block commands to the executor shards.
-    command_txs: Arc<Vec<Mutex<Sender<Message>>>>,
+    command_txs: Vec<Sender<Message>>,
     // Channels to receive execution results from the executor shards.
     result_rxs: Vec<Receiver<Message>>,
     // Thread pool used to pre-fetch the state values for the block in parallel and create an in-memory state view.
     thread_pool: Arc<rayon::ThreadPool>,
+    // If set, a shard that does not return the execution result within the timeout is
+    // considered failed.
+    shard_response_timeout: Option<Duration>,
+    // Set while the shards are failing, i.e. from a failed block until the next block for which
+    // all the shards return the result.
+    shard_failure_detected: AtomicBool,
+    // The id of the next block, so that the late results of a failed block are told apart from
+    // the results of the later blocks.
+    next_block_id: AtomicU64,
 
     phantom: std::marker::PhantomData<S>,
     _join_handle: Option<thread::JoinHandle<()>>,
@@ -111,9 +170,8 @@ impl<S: StateView + Sync + Send + 'static> RemoteExecutorClient<S> {
             .map(|(shard_id, address)| {
                 let execute_command_type = format!("execute_command_{}", shard_id);
                 let execute_result_type = format!("execute_result_{}", shard_id);
-                let command_tx = Mutex::new(
-                    controller_mut_ref.create_outbound_channel(*address, execute_command_type),
-                );
+                let command_tx =
+                    controller_mut_ref.create_outbound_channel(*address, execute_command_type);
                 let result_rx = controller_mut_ref.create_inbound_channel(execute_result_type);
                 (command_tx, result_rx)
             })
@@ -138,41 +196,156 @@ impl<S: StateView + Sync + Send + 'static> RemoteExecutorClient<S> {
             network_controller: controller,
             state_view_service,
             _join_handle: Some(join_handle),
-            command_txs: Arc::new(command_txs),
+            command_txs,
             result_rxs,
             thread_pool,
+            shard_response_timeout: None,
+            shard_failure_detected: AtomicBool::new(false),
+            next_block_id: AtomicU64::new(0),
             phantom: std::marker::PhantomData,
         }
     }
 
+    pub fn with_shard_response_timeout(mut self, shard_response_timeout: Option<Duration>) -> Self {
+        self.shard_response_timeout = shard_response_timeout;
+        self
+    }
+
     pub fn create_remote_sharded_block_executor(
         coordinator_address: SocketAddr,
         remote_shard_addresses: Vec<SocketAddr>,
         num_threads: Option<usize>,
-    ) -> ShardedBlockExecutor<S, RemoteExecutorClient<S>> {
-        ShardedBlockExecutor::new(RemoteExecutorClient::new(
-            remote_shard_addresses,
-            NetworkController::new(
-                "remote-executor-coordinator".to_string(),
-                coordinator_address,
-                5000,
-            ),
-            num_threads,
+        tls_config: Option<GRPCTlsConfig>,
+        shard_response_timeout: Option<Duration>,
+    ) -> io::Result<ShardedBlockExecutor<S, RemoteExecutorClient<S>>> {
+        Ok(ShardedBlockExecutor::new(
+            RemoteExecutorClient::new(
+                remote_shard_addresses,
+                NetworkController::new_with_tls(
+                    "remote-executor-coordinator".to_string(),
+                    coordinator_address,
+                    5000,
+                    tls_config,
+                )?,
+                num_threads,
+            )
+            .with_shard_response_timeout(shard_response_timeout),
         ))
     }
 
-    fn get_output_from_shards(&self) -> Result<Vec<Vec<Vec<TransactionOutput>>>, VMStatus> {
+    fn shard_failure_error(message: String) -> VMStatus {
+        VMStatus::error(StatusCode::UNKNOWN_INVARIANT_VIOLATION_ERROR, Some(message))
+    }
+
+    fn execute_block_on_shards(
+        &self,
+        block_id: u64,
+        sub_blocks: Vec<SubBlocksForShard<AnalyzedTransaction>>,
+        concurrency_level_per_shard: usize,
+        onchain_config: BlockExecutorConfigFromOnchain,
+    ) -> Result<ShardedExecutionOutput, VMStatus> {
+        trace!("RemoteExecutorClient Sending block to shards");
+        let start_time = Instant::now();
+        let num_active_shards = sub_blocks.len();
+        let block_gas_budget = block_gas_budget_per_executor(&onchain_config, num_active_shards);
+        for (shard_id, sub_blocks) in sub_blocks.into_iter().enumerate() {
+            let execution_request = RemoteExecutionRequest::ExecuteBlock(ExecuteBlockCommand {
+                block_id,
+                sub_blocks,
+                concurrency_level: concurrency_level_per_shard,
+                onchain_config: onchain_config.clone(),
+                block_gas_budget,
+            });
+            let message = bcs::to_bytes(&execution_request).map_err(|e| {
+                Self::shard_failure_error(format!(
+                    "Failed to serialize the execute command for executor shard {}: {}",
+                    shard_id, e
+                ))
+            })?;
+            self.command_txs[shard_id]
+                .send(Message::new(message))
+                .map_err(|_| {
+                    Self::shard_failure_error(format!(
+                        "Failed to send the execute command to executor shard {}",
+                        shard_id
+                    ))
+                })?;
+        }
+
         trace!("RemoteExecutorClient Waiting for results");
+        let deadline = self
+            .shard_response_timeout
+            .map(|timeout| start_time + timeout);
+        let received_results = receive_block_results(
+            &self.result_rxs[..num_active_shards],
+            block_id,
+            start_time,
+            deadline,
+        )
+        .map_err(|message| {
+            error!("{} (timeout: {:?})", message, self.shard_response_timeout);
+            Self::shard_failure_error(message)
+        })?;
         let mut results = vec![];
-        for rx in self.result_rxs.iter() {
-            let received_bytes = rx.recv().unwrap().to_bytes();
-            let result: RemoteExecutionResult = bcs::from_bytes(&received_bytes).unwrap();
+        let mut execution_times = vec![];
+        for (result, execution_time) in received_results {
             results.push(result.inner?);
+            execution_times.push(execution_time);
         }
-        Ok(results)
+        Ok(
+            ShardedExecutionOutput::new(results, vec![])
+                .with_shard_execution_times(execution_times),
+        )
     }
 }
 
+/// Receives the result of the block from each of the shards, skipping the late results of the
+/// previous blocks. Returns the results together with the time it took to receive them, or an
+/// error if a shard doesn't respond before the deadline or sends a malformed result.
+fn receive_block_results(
+    receivers: &[Receiver<Message>],
+    block_id: u64,
+    start_time: Instant,
+    deadline: Option<Instant>,
+) -> Result<Vec<(RemoteExecutionResult, Duration)>, String> {
+    let mut results: Vec<Option<(RemoteExecutionResult, Duration)>> =
+        receivers.iter().map(|_| None).collect();
+    let mut select = Select::new();
+    for receiver in receivers {
+        select.recv(receiver);
+    }
+    let mut num_remaining = receivers.len();
+    while num_remaining > 0 {
+        let operation = match deadline {
+            Some(deadline) => select.select_deadline(deadline).map_err(|_| {
+                let shard_id = results.iter().position(Option::is_none).unwrap_or_default();
+                format!(
+                    "Executor shard {} did not return the execution result",
+                    shard_id
+                )
+            })?,
+            None => select.select(),
+        };
+        let shard_id = operation.index();
+        let message = operation
+            .recv(&receivers[shard_id])
+            .map_err(|_| format!("Channel of executor shard {} is disconnected", shard_id))?;
+        let result: RemoteExecutionResult = bcs::from_bytes(&message.to_bytes())
+            .map_err(|e| format!("Executor shard {} sent a malformed result: {}", shard_id, e))?;
+        if result.block_id != block_id {
+            warn!(
+                "Dropping the late result of block {} from executor shard {} (current block: {})",
+                result.block_id, shard_id, block_id
+            );
+            continue;
+        }
+        select.remove(shard_id);
+        results[shard_id] = Some((result, start_time.elapsed()));
+        num_remaining -= 1;
+    }
+    Ok(results.into_iter().flatten().collect())
+}
+
 impl<S: StateView + Sync + Send + 'static> ExecutorClient<S> for RemoteExecutorClient<S> {
     fn num_shards(&self) -> usize {
         self.command_txs.len()
@@ -185,34 +358,79 @@ impl<S: StateView + Sync + Send + 'static> ExecutorClient<S> for RemoteExecutorC
         concurrency_level_per_shard: usize,
         onchain_config: BlockExecutorConfigFromOnchain,
     ) -> Result<ShardedExecutionOutput, VMStatus> {
-        trace!("RemoteExecutorClient Sending block to shards");
-        self.state_view_service.set_state_view(state_view);
+        let num_active_shards = transactions.num_shards();
+        if num_active_shards > self.command_txs.len() {
+            return Err(Self::shard_failure_error(format!(
+                "Block is partitioned for {} shards, but only {} executor shards are configured",
+                num_active_shards,
+                self.command_txs.len()
+            )));
+        }
         let (sub_blocks, global_txns) = transactions.into();
         if !global_txns.is_empty() {
-            panic!("Global transactions are not supported yet");
-        }
-        for (shard_id, sub_blocks) in sub_blocks.into_iter().enumerate() {
-            let senders = self.command_txs.clone();
-            let execution_request = RemoteExecutionRequest::ExecuteBlock(ExecuteBlockCommand {
-                sub_blocks,
-                concurrency_level: concurrency_level_per_shard,
-                onchain_config: onchain_config.clone(),
-            });
-
-            senders[shard_id]
-                .lock()
-                .unwrap()
-                .send(Message::new(bcs::to_bytes(&execution_request).unwrap()))
-                .unwrap();
+            return Err(Self::shard_failure_error(
+                "Global transactions are not supported by the remote executor shards".to_string(),
+            ));
         }
 
-        let execution_results = self.get_output_from_shards()?;
-
+        let block_id = self.next_block_id.fetch_add(1, Ordering::Relaxed);
+        self.state_view_service.set_state_view(state_view);
+        let result = self.execute_block_on_shards(
+            block_id,
+            sub_blocks,
+            concurrency_level_per_shard,
+            onchain_config,
+        );
         self.state_view_service.drop_state_view();
-        Ok(ShardedExecutionOutput::new(execution_results, vec![]))
+
+        if result.is_err() {
+            self.shard_failure_detected.store(true, Ordering::Relaxed);
+        } else if self.shard_failure_detected.swap(false, Ordering::Relaxed) {
+            info!("Executor shards recovered, block {} was executed", block_id);
+        }
+        result
     }
 
     fn shutdown(&mut self) {
         self.network_controller.shutdown();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crossbeam_channel::unbounded;
+
+    fn result_message(block_id: u64) -> Message {
+        Message::new(bcs::to_bytes(&RemoteExecutionResult::new(block_id, Ok(vec![]))).unwrap())
+    }
+
+    #[test]
+    fn test_receive_block_results_skips_late_results() {
+        let (txs, rxs): (Vec<_>, Vec<_>) = (0..2).map(|_| unbounded()).unzip();
+        // Shard 0 still sends the result of the failed block 3 before the result of block 4.
+        txs[0].send(result_message(3)).unwrap();
+        txs[0].send(result_message(4)).unwrap();
+        txs[1].send(result_message(4)).unwrap();
+
+        let results = receive_block_results(&rxs, 4, Instant::now(), None).unwrap();
+        assert_eq!(results.len(), 2);
+        assert!(results.iter().all(|(result, _)| result.block_id == 4));
+    }
+
+    #[test]
+    fn test_receive_block_results_errors() {
+        let (txs, rxs): (Vec<_>, Vec<_>) = (0..2).map(|_| unbounded()).unzip();
+
+        // Shard 1 does not respond before the deadline.
+        txs[0].send(result_message(0)).unwrap();
+        let deadline = Some(Instant::now() + Duration::from_millis(10));
+        let error = receive_block_results(&rxs, 0, Instant::now(), deadline).unwrap_err();
+        assert!(error.contains("shard 1"), "{}", error);
+
+        // Shard 0 sends a malformed result.
+        txs[0].send(Message::new(vec![1, 2, 3])).unwrap();
+        let error = receive_block_results(&rxs, 1, Instant::now(), None).unwrap_err();
+        assert!(error.contains("malformed"), "{}", error);
+    }
+}
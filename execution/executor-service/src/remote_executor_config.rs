@@ -0,0 +1,119 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::error::Error;
+use aptos_secure_net::grpc_network_service::tls::GRPCTlsConfig;
+use aptos_vm::sharded_block_executor::ShardFailurePolicy;
+use serde::{Deserialize, Serialize};
+use std::{fs, net::SocketAddr, path::Path, time::Duration};
+
+/// Configuration of remote sharded execution, shared by the coordinator and all executor shards
+/// (possibly running on different hosts), so that shards can be discovered from a single file.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct RemoteExecutorConfig {
+    pub coordinator_address: SocketAddr,
+    /// Addresses of the executor shards, indexed by shard id.
+    pub shard_addresses: Vec<SocketAddr>,
+    /// If set, the coordinator and shards communicate using mutual TLS. Each node uses its own
+    /// certificate and key, so the paths may differ between nodes.
+    #[serde(default)]
+    pub tls: Option<GRPCTlsConfig>,
+    /// If set, a shard that does not return the execution result within the timeout is
+    /// considered failed.
+    #[serde(default)]
+    pub shard_response_timeout_ms: Option<u64>,
+    /// What the coordinator does when a shard fails.
+    #[serde(default)]
+    pub shard_failure_policy: ShardFailurePolicy,
+}
+
+impl RemoteExecutorConfig {
+    pub fn new(coordinator_address: SocketAddr, shard_addresses: Vec<SocketAddr>) -> Self {
+        Self {
+            coordinator_address,
+            shard_addresses,
+            tls: None,
+            shard_response_timeout_ms: None,
+            shard_failure_policy: ShardFailurePolicy::default(),
+        }
+    }
+
+    /// Loads the config from a YAML file.
+    pub fn load(path: &Path) -> Result<Self, Error> {
+        let contents = fs::read_to_string(path).map_err(|err| {
+            Error::InternalError(format!(
+                "Failed to read remote executor config {:?}: {}",
+                path, err
+            ))
+        })?;
+        serde_yaml::from_str(&contents).map_err(|err| {
+            Error::SerializationError(format!(
+                "Failed to parse remote executor config {:?}: {}",
+                path, err
+            ))
+        })
+    }
+
+    pub fn num_shards(&self) -> usize {
+        self.shard_addresses.len()
+    }
+
+    pub fn shard_response_timeout(&self) -> Option<Duration> {
+        self.shard_response_timeout_ms.map(Duration::from_millis)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    #[test]
+    fn test_parse_remote_executor_config() {
+        let config: RemoteExecutorConfig = serde_yaml::from_str(
+            r#"
+coordinator_address: "10.0.0.1:52200"
+shard_addresses:
+  - "10.0.0.2:52201"
+  - "10.0.0.3:52202"
+tls:
+  ca_cert_path: "/opt/certs/ca.pem"
+  cert_path: "/opt/certs/node.pem"
+  key_path: "/opt/certs/node.key"
+  domain_name: "executor-service"
+shard_response_timeout_ms: 30000
+shard_failure_policy: sequential_fallback
+"#,
+        )
+        .unwrap();
+
+        assert_eq!(config.num_shards(), 2);
+        assert_eq!(
+            config.shard_response_timeout(),
+            Some(Duration::from_secs(30))
+        );
+        assert_eq!(
+            config.shard_failure_policy,
+            ShardFailurePolicy::SequentialFallback
+        );
+        assert_eq!(
+            config.tls.unwrap().ca_cert_path,
+            PathBuf::from("/opt/certs/ca.pem")
+        );
+
+        let config: RemoteExecutorConfig = serde_yaml::from_str(
+            r#"
+coordinator_address: "127.0.0.1:52200"
+shard_addresses: ["127.0.0.1:52201"]
+"#,
+        )
+        .unwrap();
+        let coordinator_address = "127.0.0.1:52200".parse().unwrap();
+        let shard_address = "127.0.0.1:52201".parse().unwrap();
+        assert_eq!(
+            config,
+            RemoteExecutorConfig::new(coordinator_address, vec![shard_address])
+        );
+    }
+}
@@ -5,10 +5,12 @@ use crate::{
     remote_cordinator_client::RemoteCoordinatorClient,
     remote_cross_shard_client::RemoteCrossShardClient, remote_state_view::RemoteStateViewClient,
 };
-use aptos_secure_net::network_controller::NetworkController;
+use aptos_secure_net::{
+    grpc_network_service::tls::GRPCTlsConfig, network_controller::NetworkController,
+};
 use aptos_types::block_executor::partitioner::ShardId;
 use aptos_vm::sharded_block_executor::sharded_executor_service::ShardedExecutorService;
-use std::{net::SocketAddr, sync::Arc, thread};
+use std::{io, net::SocketAddr, sync::Arc, thread};
 
 /// A service that provides support for remote execution. Essentially, it reads a request from
 /// the remote executor client and executes the block locally and returns the result.
@@ -26,9 +28,11 @@ impl ExecutorService {
         self_address: SocketAddr,
         coordinator_address: SocketAddr,
         remote_shard_addresses: Vec<SocketAddr>,
-    ) -> Self {
+        tls_config: Option<GRPCTlsConfig>,
+    ) -> io::Result<Self> {
         let service_name = format!("executor_service-{}", shard_id);
-        let mut controller = NetworkController::new(service_name, self_address, 5000);
+        let mut controller =
+            NetworkController::new_with_tls(service_name, self_address, 5000, tls_config)?;
         let coordinator_client = Arc::new(RemoteCoordinatorClient::new(
             shard_id,
             &mut controller,
@@ -47,11 +51,11 @@ impl ExecutorService {
             cross_shard_client,
         ));
 
-        Self {
+        Ok(Self {
             shard_id,
             controller,
             executor_service,
-        }
+        })
     }
 
     pub fn start(&mut self) {
@@ -128,7 +128,7 @@ pub fn test_sharded_block_executor_no_conflict<E: ExecutorClient<FakeDataStore>>
     let sharded_txn_output = sharded_block_executor
         .execute_block(
             Arc::new(executor.data_store().clone()),
-            partitioned_txns.clone(),
+            &partitioned_txns,
             2,
             BlockExecutorConfigFromOnchain::new_no_block_limit(),
         )
@@ -173,11 +173,8 @@ pub fn sharded_block_executor_with_conflict<E: ExecutorClient<FakeDataStore>>(
         }
     }
 
-    let partitioner = PartitionerV2Config::default()
-        .max_partitioning_rounds(2)
-        .cross_shard_dep_avoid_threshold(0.9)
-        .partition_last_round(true)
-        .build();
+    // Default partitioning, i.e. multiple rounds with cross-round dependencies between shards.
+    let partitioner = PartitionerV2Config::default().build();
     let partitioned_txns = partitioner.partition(transactions.clone(), num_shards);
 
     let execution_ordered_txns: Vec<SignatureVerifiedTransaction> =
@@ -188,7 +185,7 @@ pub fn sharded_block_executor_with_conflict<E: ExecutorClient<FakeDataStore>>(
     let sharded_txn_output = sharded_block_executor
         .execute_block(
             Arc::new(executor.data_store().clone()),
-            partitioned_txns,
+            &partitioned_txns,
             concurrency,
             BlockExecutorConfigFromOnchain::new_no_block_limit(),
         )
@@ -27,7 +27,9 @@ impl ThreadExecutorService {
             self_address,
             coordinator_address,
             remote_shard_addresses,
-        );
+            None,
+        )
+        .expect("Creating an executor service without TLS must succeed");
         executor_service.start();
         Self {
             _self_address: self_address,
@@ -9,8 +9,26 @@ use serde::{Deserialize, Serialize};
 use std::fmt::Display;
 use thiserror::Error;
 
+/// The broad classes of executor errors, which tell the callers how to react to them.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ExecutorErrorCategory {
+    /// Some input of the block, e.g. its payload or its parent, is not available yet.
+    MissingData,
+    /// Reading or writing the db failed.
+    Storage,
+    /// Speculative execution was invalidated, e.g. by a concurrent change of its base state.
+    SpeculativeAbort,
+    /// The executor or the pipeline was reset while the request was pending.
+    Reset,
+    /// The request itself is invalid.
+    InvalidInput,
+    /// An invariant is violated, which means a bug.
+    InternalBug,
+}
+
 #[derive(Debug, Deserialize, Error, PartialEq, Eq, Serialize, Clone)]
 /// Different reasons for proposal rejection
+// N.B. New variants must be appended, since the error is serialized.
 pub enum ExecutorError {
     #[error("Cannot find speculation result for block id {0}")]
     BlockNotFound(HashValue),
@@ -41,6 +59,15 @@ pub enum ExecutorError {
 
     #[error("request timeout")]
     CouldNotGetData,
+
+    #[error("Storage error: {0}")]
+    StorageError(String),
+
+    #[error("Speculative execution aborted: {0}")]
+    SpeculativeExecutionAborted(String),
+
+    #[error("Executor reset: {0}")]
+    ExecutorReset(String),
 }
 
 impl From<anyhow::Error> for ExecutorError {
@@ -53,17 +80,13 @@ impl From<anyhow::Error> for ExecutorError {
 
 impl From<AptosDbError> for ExecutorError {
     fn from(error: AptosDbError) -> Self {
-        Self::InternalError {
-            error: format!("{}", error),
-        }
+        Self::StorageError(format!("{}", error))
     }
 }
 
 impl From<StateViewError> for ExecutorError {
     fn from(error: StateViewError) -> Self {
-        Self::InternalError {
-            error: format!("{}", error),
-        }
+        Self::StorageError(format!("{}", error))
     }
 }
 
@@ -87,6 +110,110 @@ impl ExecutorError {
             error: format!("{}", e),
         }
     }
+
+    pub fn reset<E: Display>(e: E) -> Self {
+        Self::ExecutorReset(format!("{}", e))
+    }
+
+    pub fn category(&self) -> ExecutorErrorCategory {
+        match self {
+            Self::BlockNotFound(_) | Self::DataNotFound(_) | Self::CouldNotGetData => {
+                ExecutorErrorCategory::MissingData
+            },
+            Self::StorageError(_) => ExecutorErrorCategory::Storage,
+            Self::SpeculativeExecutionAborted(_) => ExecutorErrorCategory::SpeculativeAbort,
+            Self::ExecutorReset(_) => ExecutorErrorCategory::Reset,
+            Self::BadNumTxnsToCommit { .. } | Self::EmptyBlocks => {
+                ExecutorErrorCategory::InvalidInput
+            },
+            Self::InternalError { .. } | Self::SerializationError(_) => {
+                ExecutorErrorCategory::InternalBug
+            },
+        }
+    }
+
+    /// Whether the same request may succeed if it's retried later. After a reset, the request
+    /// should be dropped instead, and the other errors are not expected to go away.
+    pub fn is_retryable(&self) -> bool {
+        matches!(
+            self.category(),
+            ExecutorErrorCategory::MissingData
+                | ExecutorErrorCategory::Storage
+                | ExecutorErrorCategory::SpeculativeAbort
+        )
+    }
+
+    /// A code identifying the kind of the error, which is stable across releases, e.g. to be
+    /// used as a metric label.
+    pub fn error_code(&self) -> &'static str {
+        match self {
+            Self::BlockNotFound(_) => "BlockNotFound",
+            Self::DataNotFound(_) => "DataNotFound",
+            Self::BadNumTxnsToCommit { .. } => "BadNumTxnsToCommit",
+            Self::InternalError { .. } => "InternalError",
+            Self::SerializationError(_) => "SerializationError",
+            Self::EmptyBlocks => "EmptyBlocks",
+            Self::CouldNotGetData => "CouldNotGetData",
+            Self::StorageError(_) => "StorageError",
+            Self::SpeculativeExecutionAborted(_) => "SpeculativeExecutionAborted",
+            Self::ExecutorReset(_) => "ExecutorReset",
+        }
+    }
 }
 
 pub type ExecutorResult<T> = Result<T, ExecutorError>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_classification() {
+        let missing_data = [
+            ExecutorError::BlockNotFound(HashValue::zero()),
+            ExecutorError::DataNotFound(HashValue::zero()),
+            ExecutorError::CouldNotGetData,
+        ];
+        for e in missing_data {
+            assert_eq!(e.category(), ExecutorErrorCategory::MissingData);
+            assert!(e.is_retryable());
+        }
+
+        let storage_error: ExecutorError = AptosDbError::Other("db".to_string()).into();
+        assert_eq!(storage_error.category(), ExecutorErrorCategory::Storage);
+        assert!(storage_error.is_retryable());
+
+        let aborted = ExecutorError::SpeculativeExecutionAborted("aborted".to_string());
+        assert_eq!(aborted.category(), ExecutorErrorCategory::SpeculativeAbort);
+        assert!(aborted.is_retryable());
+
+        let reset = ExecutorError::reset("reset");
+        assert_eq!(reset.category(), ExecutorErrorCategory::Reset);
+        assert!(!reset.is_retryable());
+
+        assert_eq!(
+            ExecutorError::EmptyBlocks.category(),
+            ExecutorErrorCategory::InvalidInput
+        );
+        let internal_error: ExecutorError = anyhow::anyhow!("bug").into();
+        assert_eq!(
+            internal_error.category(),
+            ExecutorErrorCategory::InternalBug
+        );
+        assert!(!internal_error.is_retryable());
+        assert_eq!(internal_error.error_code(), "InternalError");
+    }
+
+    #[test]
+    fn test_serialized_variants_are_stable() {
+        // Variants are serialized by index, so existing variants must keep their index.
+        assert_eq!(
+            bcs::to_bytes(&ExecutorError::CouldNotGetData).unwrap(),
+            vec![6]
+        );
+        assert_eq!(
+            bcs::to_bytes(&ExecutorError::ExecutorReset(String::new())).unwrap(),
+            vec![9, 0]
+        );
+    }
+}
@@ -8,6 +8,7 @@ use crate::{
     planned::Planned,
     transactions_with_output::{TransactionsToKeep, TransactionsWithOutput},
 };
+use aptos_crypto::HashValue;
 use aptos_drop_helper::DropHelper;
 use aptos_storage_interface::state_store::{
     state_delta::StateDelta, state_view::cached_state_view::StateCache,
@@ -40,6 +41,7 @@ impl ExecutionOutput {
         block_end_info: Option<BlockEndInfo>,
         next_epoch_state: Option<EpochState>,
         subscribable_events: Planned<Vec<ContractEvent>>,
+        output_hashes_for_input_txns: Option<OutputHashes>,
     ) -> Self {
         if is_block {
             // If it's a block, ensure it ends with state checkpoint.
@@ -60,6 +62,7 @@ impl ExecutionOutput {
             block_end_info,
             next_epoch_state,
             subscribable_events,
+            output_hashes_for_input_txns,
         })
     }
 
@@ -75,6 +78,7 @@ impl ExecutionOutput {
             block_end_info: None,
             next_epoch_state: None,
             subscribable_events: Planned::ready(vec![]),
+            output_hashes_for_input_txns: None,
         })
     }
 
@@ -92,6 +96,7 @@ impl ExecutionOutput {
             block_end_info: None,
             next_epoch_state: None,
             subscribable_events: Planned::ready(vec![]),
+            output_hashes_for_input_txns: None,
         })
     }
 
@@ -111,6 +116,7 @@ impl ExecutionOutput {
             block_end_info: None,
             next_epoch_state: self.next_epoch_state.clone(),
             subscribable_events: Planned::ready(vec![]),
+            output_hashes_for_input_txns: None,
         })
     }
 
@@ -157,6 +163,31 @@ pub struct Inner {
     /// state cache.
     pub next_epoch_state: Option<EpochState>,
     pub subscribable_events: Planned<Vec<ContractEvent>>,
+    /// Hashes of the outputs of the input transactions, in the same order as the input
+    /// transactions, if they were already calculated during execution.
+    pub output_hashes_for_input_txns: Option<OutputHashes>,
+}
+
+/// Hashes of transaction outputs needed by the ledger update.
+#[derive(Debug, Default)]
+pub struct OutputHashes {
+    pub event_root_hashes: Vec<HashValue>,
+    pub write_set_hashes: Vec<HashValue>,
+}
+
+impl OutputHashes {
+    pub fn len(&self) -> usize {
+        self.event_root_hashes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.event_root_hashes.is_empty()
+    }
+
+    pub fn extend(&mut self, other: OutputHashes) {
+        self.event_root_hashes.extend(other.event_root_hashes);
+        self.write_set_hashes.extend(other.write_set_hashes);
+    }
 }
 
 impl Inner {
@@ -21,7 +21,7 @@ use aptos_types::{
     },
     write_set::WriteSet,
 };
-pub use error::{ExecutorError, ExecutorResult};
+pub use error::{ExecutorError, ExecutorErrorCategory, ExecutorResult};
 pub use ledger_update_output::LedgerUpdateOutput;
 use state_compute_result::StateComputeResult;
 use std::{
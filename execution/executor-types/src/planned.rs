@@ -6,7 +6,10 @@ use aptos_infallible::Mutex;
 use aptos_metrics_core::TimerHelper;
 use once_cell::sync::OnceCell;
 use rayon::ThreadPool;
-use std::{ops::Deref, sync::mpsc::Receiver};
+use std::{
+    ops::Deref,
+    sync::mpsc::{Receiver, RecvError},
+};
 
 #[derive(Debug)]
 pub struct Planned<T> {
@@ -42,18 +45,37 @@ impl<T> Planned<T> {
         }
     }
 
+    /// Returns the value if it's already available, without waiting for the plan to finish.
+    pub fn try_get(&self) -> Option<&T> {
+        if let Some(t) = self.value.get() {
+            return Some(t);
+        }
+        let rx = self.rx.get()?.lock();
+        if self.value.get().is_none() {
+            let t = rx.try_recv().ok()?;
+            self.value.set(t).map_err(|_| "").expect("Already set.");
+        }
+        self.value.get()
+    }
+
     pub fn get(&self, name_for_timer: Option<&str>) -> &T {
+        self.wait(name_for_timer).expect("Plan failed.")
+    }
+
+    /// Waits for the plan to finish, failing if it was dropped without a value, e.g., because the
+    /// job panicked.
+    pub fn wait(&self, name_for_timer: Option<&str>) -> Result<&T, RecvError> {
         if let Some(t) = self.value.get() {
-            t
+            Ok(t)
         } else {
             let _timer = name_for_timer.map(|name| TIMER.timer_with(&[name]));
 
             let rx = self.rx.get().expect("Not planned").lock();
             if self.value.get().is_none() {
-                let t = rx.recv().expect("Plan failed.");
+                let t = rx.recv()?;
                 self.value.set(t).map_err(|_| "").expect("Already set.");
             }
-            self.value.get().expect("Must have been set.")
+            Ok(self.value.get().expect("Must have been set."))
         }
     }
 }
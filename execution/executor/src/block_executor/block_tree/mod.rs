@@ -14,7 +14,7 @@ use crate::{
 use anyhow::{anyhow, ensure, Result};
 use aptos_consensus_types::block::Block as ConsensusBlock;
 use aptos_crypto::HashValue;
-use aptos_drop_helper::DEFAULT_DROPPER;
+use aptos_drop_helper::{DropPriority, DropTag, DEFAULT_DROPPER};
 use aptos_executor_types::ExecutorError;
 use aptos_infallible::Mutex;
 use aptos_logger::{debug, info};
@@ -266,7 +266,10 @@ impl BlockTree {
         let old_root = std::mem::replace(&mut *self.root.lock(), root);
 
         // send old root to async task to drop it
-        Ok(DEFAULT_DROPPER.schedule_drop_with_waiter(old_root))
+        Ok(DEFAULT_DROPPER.schedule_tagged_drop_with_waiter(
+            old_root,
+            DropTag::new("block_tree_root").with_priority(DropPriority::High),
+        ))
     }
 
     pub fn add_block(
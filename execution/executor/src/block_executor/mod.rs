@@ -20,12 +20,13 @@ use crate::{
 use anyhow::Result;
 use aptos_crypto::HashValue;
 use aptos_executor_types::{
-    state_compute_result::StateComputeResult, BlockExecutorTrait, ExecutorError, ExecutorResult,
+    planned::Plan, state_compute_result::StateComputeResult, BlockExecutorTrait, ExecutorError,
+    ExecutorResult,
 };
 use aptos_experimental_runtimes::thread_manager::THREAD_MANAGER;
 use aptos_infallible::RwLock;
 use aptos_logger::prelude::*;
-use aptos_metrics_core::{IntGaugeHelper, TimerHelper};
+use aptos_metrics_core::{exemplars::set_exemplar_labels, IntGaugeHelper, TimerHelper};
 use aptos_storage_interface::{
     state_store::state_view::{
         async_proof_fetcher::AsyncProofFetcher, cached_state_view::CachedStateView,
@@ -50,6 +51,8 @@ pub mod block_tree;
 pub struct BlockExecutor<V> {
     pub db: DbReaderWriter,
     inner: RwLock<Option<BlockExecutorInner<V>>>,
+    state_checkpoint_pipeline_depth: usize,
+    state_checkpoint_pool: Option<Arc<rayon::ThreadPool>>,
 }
 
 impl<V> BlockExecutor<V>
@@ -57,9 +60,34 @@ where
     V: VMBlockExecutor,
 {
     pub fn new(db: DbReaderWriter) -> Self {
+        Self::new_with_state_checkpoint_pipeline_depth(db, 0)
+    }
+
+    /// Creates an executor which executes a block while the state checkpoints of up to
+    /// `state_checkpoint_pipeline_depth` of its ancestors are being calculated. With a depth of
+    /// zero, the state checkpoint of a block is calculated right after it's executed.
+    pub fn new_with_state_checkpoint_pipeline_depth(
+        db: DbReaderWriter,
+        state_checkpoint_pipeline_depth: usize,
+    ) -> Self {
+        // Each job mostly waits for the state checkpoint of the parent block, and does the
+        // calculation on the execution pool. At most `state_checkpoint_pipeline_depth` blocks of a
+        // branch have their checkpoints pending, so the job of the oldest of them is never queued
+        // behind the jobs waiting for it.
+        let state_checkpoint_pool = (state_checkpoint_pipeline_depth > 0).then(|| {
+            Arc::new(
+                rayon::ThreadPoolBuilder::new()
+                    .num_threads(state_checkpoint_pipeline_depth)
+                    .thread_name(|index| format!("state-ckpt-{}", index))
+                    .build()
+                    .expect("Failed to create the state checkpoint pool."),
+            )
+        });
         Self {
             db,
             inner: RwLock::new(None),
+            state_checkpoint_pipeline_depth,
+            state_checkpoint_pool,
         }
     }
 
@@ -89,7 +117,11 @@ where
     fn reset(&self) -> Result<()> {
         let _guard = CONCURRENCY_GAUGE.concurrency_with(&["block", "reset"]);
 
-        *self.inner.write() = Some(BlockExecutorInner::new(self.db.clone())?);
+        *self.inner.write() = Some(BlockExecutorInner::new(
+            self.db.clone(),
+            self.state_checkpoint_pipeline_depth,
+            self.state_checkpoint_pool.clone(),
+        )?);
         Ok(())
     }
 
@@ -155,18 +187,28 @@ struct BlockExecutorInner<V> {
     db: DbReaderWriter,
     block_tree: BlockTree,
     block_executor: V,
+    state_checkpoint_pipeline_depth: usize,
+    /// Pool to calculate the state checkpoints of blocks in the background, if the pipeline depth
+    /// is not zero.
+    state_checkpoint_pool: Option<Arc<rayon::ThreadPool>>,
 }
 
 impl<V> BlockExecutorInner<V>
 where
     V: VMBlockExecutor,
 {
-    pub fn new(db: DbReaderWriter) -> Result<Self> {
+    pub fn new(
+        db: DbReaderWriter,
+        state_checkpoint_pipeline_depth: usize,
+        state_checkpoint_pool: Option<Arc<rayon::ThreadPool>>,
+    ) -> Result<Self> {
         let block_tree = BlockTree::new(&db.reader)?;
         Ok(Self {
             db,
             block_tree,
             block_executor: V::new(),
+            state_checkpoint_pipeline_depth,
+            state_checkpoint_pool,
         })
     }
 }
@@ -185,11 +227,12 @@ where
         parent_block_id: HashValue,
         onchain_config: BlockExecutorConfigFromOnchain,
     ) -> ExecutorResult<()> {
-        let _timer = BLOCK_EXECUTION_WORKFLOW_WHOLE.start_timer();
         let ExecutableBlock {
             block_id,
             transactions,
         } = block;
+        let _exemplar = set_exemplar_labels(vec![("block_id", block_id.to_hex())]);
+        let _timer = BLOCK_EXECUTION_WORKFLOW_WHOLE.start_timer();
         let mut block_vec = self
             .block_tree
             .get_blocks_opt(&[block_id, parent_block_id])?;
@@ -204,65 +247,117 @@ where
             "execute_block"
         );
         let committed_block_id = self.committed_block_id();
-        let (execution_output, state_checkpoint_output) =
-            if parent_block_id != committed_block_id && parent_output.has_reconfiguration() {
-                // ignore reconfiguration suffix, even if the block is non-empty
-                info!(
-                    LogSchema::new(LogEntry::BlockExecutor).block_id(block_id),
-                    "reconfig_descendant_block_received"
-                );
-                (
-                    parent_output.execution_output.reconfig_suffix(),
-                    parent_output
-                        .expect_state_checkpoint_output()
-                        .reconfig_suffix(),
-                )
-            } else {
-                let state_view = {
-                    let _timer = OTHER_TIMERS.timer_with(&["verified_state_view"]);
-
-                    CachedStateView::new(
-                        StateViewId::BlockExecution { block_id },
-                        Arc::clone(&self.db.reader),
-                        parent_output.execution_output.next_version(),
-                        parent_output.expect_result_state().current.clone(),
-                        Arc::new(AsyncProofFetcher::new(self.db.reader.clone())),
-                    )?
-                };
-
-                let execution_output = {
-                    let _timer = GET_BLOCK_EXECUTION_OUTPUT_BY_EXECUTING.start_timer();
-                    fail_point!("executor::block_executor_execute_block", |_| {
-                        Err(ExecutorError::from(anyhow::anyhow!(
-                            "Injected error in block_executor_execute_block"
-                        )))
-                    });
+        let output = if parent_block_id != committed_block_id && parent_output.has_reconfiguration()
+        {
+            // ignore reconfiguration suffix, even if the block is non-empty
+            info!(
+                LogSchema::new(LogEntry::BlockExecutor).block_id(block_id),
+                "reconfig_descendant_block_received"
+            );
+            let output =
+                PartialStateComputeResult::new(parent_output.execution_output.reconfig_suffix());
+            output.set_state_checkpoint_output(
+                parent_output
+                    .wait_for_state_checkpoint_output()?
+                    .reconfig_suffix(),
+            );
+            output
+        } else {
+            let parent_state =
+                parent_output.state_for_children(self.state_checkpoint_pipeline_depth)?;
+            let state_view = {
+                let _timer = OTHER_TIMERS.timer_with(&["verified_state_view"]);
+
+                CachedStateView::new(
+                    StateViewId::BlockExecution { block_id },
+                    Arc::clone(&self.db.reader),
+                    parent_output.execution_output.next_version(),
+                    parent_state.base.clone(),
+                    Arc::new(AsyncProofFetcher::new(self.db.reader.clone())),
+                )?
+                .with_pending_updates(parent_state.pending_updates.clone(), parent_state.usage)
+            };
 
-                    DoGetExecutionOutput::by_transaction_execution(
-                        &self.block_executor,
-                        transactions,
-                        state_view,
-                        onchain_config.clone(),
-                        TransactionSliceMetadata::block(parent_block_id, block_id),
-                    )?
+            let execution_output = {
+                let _timer = GET_BLOCK_EXECUTION_OUTPUT_BY_EXECUTING.start_timer();
+                fail_point!("executor::block_executor_execute_block", |_| {
+                    Err(ExecutorError::from(anyhow::anyhow!(
+                        "Injected error in block_executor_execute_block"
+                    )))
+                });
+
+                DoGetExecutionOutput::by_transaction_execution(
+                    &self.block_executor,
+                    transactions,
+                    state_view,
+                    onchain_config.clone(),
+                    TransactionSliceMetadata::block(parent_block_id, block_id),
+                )?
+            };
+            if parent_state.version.map_or(0, |v| v + 1) != execution_output.first_version {
+                return Err(ExecutorError::SpeculativeExecutionAborted(format!(
+                    "Block {} executed at version {} on the state at version {:?}.",
+                    block_id, execution_output.first_version, parent_state.version,
+                )));
+            }
+
+            if let Some(state_checkpoint_pool) = &self.state_checkpoint_pool {
+                let block_updates = {
+                    let _timer = OTHER_TIMERS.timer_with(&["prepare_state_checkpoint"]);
+                    THREAD_MANAGER.get_exe_cpu_pool().install(|| {
+                        DoStateCheckpoint::prepare_block(&execution_output, parent_state.usage)
+                    })?
                 };
+                let speculative_state = parent_state.child(
+                    block_updates.updates.clone(),
+                    execution_output.expect_last_version(),
+                    block_updates.usage,
+                );
 
+                // The state checkpoint is calculated in the background, on top of the parent's,
+                // while the children are executed on the speculative state.
+                let planned = {
+                    let parent_block = parent_block.clone();
+                    let execution_output = execution_output.clone();
+                    state_checkpoint_pool.plan(move || {
+                        let parent_state = parent_block
+                            .output
+                            .wait_for_state_checkpoint_output()?
+                            .result_state
+                            .clone();
+                        let _timer = OTHER_TIMERS.timer_with(&["state_checkpoint"]);
+                        THREAD_MANAGER.get_exe_cpu_pool().install(|| {
+                            fail_point!("executor::block_state_checkpoint", |_| {
+                                Err(anyhow::anyhow!("Injected error in block state checkpoint."))
+                            });
+                            DoStateCheckpoint::run_for_block(
+                                &execution_output,
+                                &parent_state,
+                                block_updates,
+                            )
+                        })
+                    })
+                };
+                let output = PartialStateComputeResult::new(execution_output);
+                output.plan_state_checkpoint_output(planned, speculative_state);
+                output
+            } else {
                 let _timer = OTHER_TIMERS.timer_with(&["state_checkpoint"]);
 
+                let parent_state = &parent_output
+                    .wait_for_state_checkpoint_output()?
+                    .result_state;
                 let state_checkpoint_output = THREAD_MANAGER.get_exe_cpu_pool().install(|| {
                     fail_point!("executor::block_state_checkpoint", |_| {
                         Err(anyhow::anyhow!("Injected error in block state checkpoint."))
                     });
-                    DoStateCheckpoint::run(
-                        &execution_output,
-                        parent_output.expect_result_state(),
-                        Option::<Vec<_>>::None,
-                    )
+                    DoStateCheckpoint::run(&execution_output, parent_state, Option::<Vec<_>>::None)
                 })?;
-                (execution_output, state_checkpoint_output)
-            };
-        let output = PartialStateComputeResult::new(execution_output);
-        output.set_state_checkpoint_output(state_checkpoint_output);
+                let output = PartialStateComputeResult::new(execution_output);
+                output.set_state_checkpoint_output(state_checkpoint_output);
+                output
+            }
+        };
 
         let _ = self
             .block_tree
@@ -308,10 +403,11 @@ where
                 );
                 parent_output.reconfig_suffix()
             } else {
+                let state_checkpoint_output = output.wait_for_state_checkpoint_output()?;
                 THREAD_MANAGER.get_non_exe_cpu_pool().install(|| {
                     DoLedgerUpdate::run(
                         &output.execution_output,
-                        output.expect_state_checkpoint_output(),
+                        state_checkpoint_output,
                         parent_accumulator.clone(),
                     )
                 })?
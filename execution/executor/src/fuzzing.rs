@@ -69,7 +69,7 @@ impl VMBlockExecutor for FakeVM {
 
     fn execute_block_sharded<S: StateView + Send + Sync, E: ExecutorClient<S>>(
         _sharded_block_executor: &ShardedBlockExecutor<S, E>,
-        _transactions: PartitionedTransactions,
+        _transactions: &PartitionedTransactions,
         _state_view: Arc<S>,
         _onchain_config: BlockExecutorConfigFromOnchain,
     ) -> Result<Vec<TransactionOutput>, VMStatus> {
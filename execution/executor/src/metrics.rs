@@ -4,9 +4,10 @@
 
 use aptos_logger::{prelude::*, sample, warn};
 use aptos_metrics_core::{
-    exponential_buckets, register_histogram, register_histogram_vec, register_int_counter,
-    register_int_counter_vec, register_int_gauge_vec, Histogram, HistogramVec, IntCounter,
-    IntCounterVec, IntGaugeVec,
+    exemplars::{register_exemplar_histogram, ExemplarHistogram},
+    exponential_buckets, histogram_opts, register_histogram, register_histogram_vec,
+    register_int_counter, register_int_counter_vec, register_int_gauge_vec, Histogram,
+    HistogramVec, IntCounter, IntCounterVec, IntGaugeVec,
 };
 use aptos_types::{
     contract_event::ContractEvent,
@@ -53,14 +54,14 @@ pub static COMMIT_CHUNK: Lazy<Histogram> = Lazy::new(|| {
     .unwrap()
 });
 
-pub static GET_BLOCK_EXECUTION_OUTPUT_BY_EXECUTING: Lazy<Histogram> = Lazy::new(|| {
-    register_histogram!(
+pub static GET_BLOCK_EXECUTION_OUTPUT_BY_EXECUTING: Lazy<ExemplarHistogram> = Lazy::new(|| {
+    register_exemplar_histogram(histogram_opts!(
         // metric name
         "aptos_executor_get_block_execution_output_by_executing_seconds",
         // metric description
         "The total time spent in seconds in executing execute_and_state_checkpoint in the BlockExecutorInner.",
-        exponential_buckets(/*start=*/ 1e-3, /*factor=*/ 2.0, /*count=*/ 20).unwrap(),
-    )
+        exponential_buckets(/*start=*/ 1e-3, /*factor=*/ 2.0, /*count=*/ 20).unwrap()
+    ))
     .unwrap()
 });
 
@@ -80,14 +81,14 @@ pub static EXECUTOR_ERRORS: Lazy<IntCounter> = Lazy::new(|| {
     register_int_counter!("aptos_executor_error_total", "Cumulative number of errors").unwrap()
 });
 
-pub static BLOCK_EXECUTION_WORKFLOW_WHOLE: Lazy<Histogram> = Lazy::new(|| {
-    register_histogram!(
+pub static BLOCK_EXECUTION_WORKFLOW_WHOLE: Lazy<ExemplarHistogram> = Lazy::new(|| {
+    register_exemplar_histogram(histogram_opts!(
         // metric name
         "aptos_executor_block_execution_workflow_whole_seconds",
         // metric description
         "The total time spent in seconds in executing execute_and_state_checkpoint in the BlockExecutorInner.",
-        exponential_buckets(/*start=*/ 1e-3, /*factor=*/ 2.0, /*count=*/ 20).unwrap(),
-    )
+        exponential_buckets(/*start=*/ 1e-3, /*factor=*/ 2.0, /*count=*/ 20).unwrap()
+    ))
     .unwrap()
 });
 
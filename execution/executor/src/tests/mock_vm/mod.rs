@@ -199,7 +199,7 @@ impl VMBlockExecutor for MockVM {
 
     fn execute_block_sharded<S: StateView + Sync + Send + 'static, E: ExecutorClient<S>>(
         _sharded_block_executor: &ShardedBlockExecutor<S, E>,
-        _transactions: PartitionedTransactions,
+        _transactions: &PartitionedTransactions,
         _state_view: Arc<S>,
         _onchain_config: BlockExecutorConfigFromOnchain,
     ) -> std::result::Result<Vec<TransactionOutput>, VMStatus> {
@@ -81,13 +81,17 @@ struct TestExecutor {
 
 impl TestExecutor {
     fn new() -> TestExecutor {
+        Self::new_with_state_checkpoint_pipeline_depth(0)
+    }
+
+    fn new_with_state_checkpoint_pipeline_depth(depth: usize) -> TestExecutor {
         let path = aptos_temppath::TempPath::new();
         path.create_as_dir().unwrap();
         let db = DbReaderWriter::new(AptosDB::new_for_test(path.path()));
         let genesis = aptos_vm_genesis::test_genesis_transaction();
         let waypoint = generate_waypoint::<MockVM>(&db, &genesis).unwrap();
         maybe_bootstrap::<MockVM>(&db, &genesis, waypoint).unwrap();
-        let executor = BlockExecutor::new(db.clone());
+        let executor = BlockExecutor::new_with_state_checkpoint_pipeline_depth(db.clone(), depth);
 
         TestExecutor {
             _path: path,
@@ -282,6 +286,53 @@ fn test_executor_two_blocks_with_failed_txns() {
         .unwrap();
 }
 
+#[test]
+fn test_executor_pipelined_state_checkpoints() {
+    let blocks = (0..5u64)
+        .map(|i| {
+            let txns = (0..10)
+                .map(|j| {
+                    if i % 2 == 0 {
+                        encode_mint_transaction(gen_address(j), 100)
+                    } else {
+                        encode_transfer_transaction(gen_address(j), gen_address(j + 1), 50)
+                    }
+                })
+                .collect::<Vec<_>>();
+            (gen_block_id(i + 1), txns)
+        })
+        .collect::<Vec<_>>();
+
+    let root_hashes = [0, 2]
+        .into_iter()
+        .map(|depth| {
+            let executor = TestExecutor::new_with_state_checkpoint_pipeline_depth(depth);
+            let mut parent_block_id = executor.committed_block_id();
+            // Executes all the blocks before any of the state checkpoints is waited for.
+            for (block_id, txns) in &blocks {
+                executor
+                    .execute_and_state_checkpoint(
+                        (*block_id, block(txns.clone())).into(),
+                        parent_block_id,
+                        TEST_BLOCK_EXECUTOR_ONCHAIN_CONFIG,
+                    )
+                    .unwrap();
+                parent_block_id = *block_id;
+            }
+
+            let mut parent_block_id = executor.committed_block_id();
+            let mut root_hashes = vec![];
+            for (block_id, _) in &blocks {
+                let output = executor.ledger_update(*block_id, parent_block_id).unwrap();
+                root_hashes.push(output.root_hash());
+                parent_block_id = *block_id;
+            }
+            root_hashes
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(root_hashes[0], root_hashes[1]);
+}
+
 #[test]
 fn test_executor_commit_twice() {
     let executor = TestExecutor::new();
@@ -33,6 +33,13 @@ use std::{ops::Deref, sync::Arc};
 /// Helper class for calculating state changes after a block of transactions are executed.
 pub struct InMemoryStateCalculatorV2 {}
 
+/// The state updates of a block and the usage after them. They are calculated right after the
+/// block is executed, so that its children can be executed before its state checkpoint is.
+pub struct BlockStateUpdates {
+    pub updates: Arc<ShardedStateUpdates>,
+    pub usage: StateStorageUsage,
+}
+
 impl InMemoryStateCalculatorV2 {
     pub fn calculate_for_transactions(
         execution_output: &ExecutionOutput,
@@ -43,13 +50,91 @@ impl InMemoryStateCalculatorV2 {
             Self::validate_input_for_block(parent_state, &execution_output.to_commit)?;
         }
 
+        let state_cache = &execution_output.state_cache;
+        assert!(state_cache
+            .frozen_base
+            .smt
+            .is_the_same(&parent_state.current));
+        let last_checkpoint_index = execution_output.to_commit.last_checkpoint_index();
+        let state_update_refs = execution_output.to_commit.state_update_refs();
+        let (updates_before_last_checkpoint, updates_after_last_checkpoint) =
+            Self::calculate_updates(state_update_refs, last_checkpoint_index);
+        let usage = Self::calculate_usage_of_updates(
+            parent_state.current.usage(),
+            &state_cache.sharded_state_cache,
+            &updates_before_last_checkpoint,
+            &updates_after_last_checkpoint,
+        );
+
         Self::calculate_impl(
             parent_state,
-            &execution_output.state_cache,
-            execution_output.to_commit.state_update_refs(),
-            execution_output.to_commit.last_checkpoint_index(),
+            state_cache,
+            state_update_refs.num_versions,
+            last_checkpoint_index,
             execution_output.is_block,
             known_state_checkpoints,
+            updates_before_last_checkpoint,
+            updates_after_last_checkpoint,
+            usage,
+        )
+    }
+
+    /// Calculates the state updates of a block and the usage after them, on top of the usage of
+    /// the parent block, which doesn't need its state checkpoint to be calculated.
+    pub fn calculate_block_updates(
+        execution_output: &ExecutionOutput,
+        parent_usage: StateStorageUsage,
+    ) -> Result<BlockStateUpdates> {
+        let num_txns = execution_output.to_commit.len();
+        ensure!(num_txns != 0, "Empty block is not allowed.");
+        let last_checkpoint_index = execution_output.to_commit.last_checkpoint_index();
+        ensure!(
+            last_checkpoint_index == Some(num_txns - 1),
+            "Block must have the checkpoint at the end."
+        );
+
+        let (updates, _) = Self::calculate_updates(
+            execution_output.to_commit.state_update_refs(),
+            last_checkpoint_index,
+        );
+        let usage = Self::calculate_usage(
+            parent_usage,
+            &execution_output.state_cache.sharded_state_cache,
+            &updates,
+        );
+        Ok(BlockStateUpdates {
+            updates: Arc::new(updates),
+            usage,
+        })
+    }
+
+    /// Calculates the state checkpoint of a block from its updates calculated by
+    /// `calculate_block_updates`. The block may have been executed on an older state than its
+    /// parent state, with the updates in between read from memory.
+    pub fn calculate_for_block(
+        execution_output: &ExecutionOutput,
+        parent_state: &Arc<StateDelta>,
+        block_updates: BlockStateUpdates,
+    ) -> Result<StateCheckpointOutput> {
+        Self::validate_input_for_block(parent_state, &execution_output.to_commit)?;
+        let state_cache = &execution_output.state_cache;
+        ensure!(
+            state_cache.frozen_base.smt.is_family(&parent_state.current),
+            "Block was executed on a state unrelated to its parent state."
+        );
+
+        let BlockStateUpdates { updates, usage } = block_updates;
+        let updates = Arc::try_unwrap(updates).unwrap_or_else(|updates| updates.deref().clone());
+        Self::calculate_impl(
+            parent_state,
+            state_cache,
+            execution_output.to_commit.state_update_refs().num_versions,
+            execution_output.to_commit.last_checkpoint_index(),
+            true, /* is_block */
+            Option::<Vec<_>>::None,
+            updates,
+            ShardedStateUpdates::new_empty(),
+            usage,
         )
     }
 
@@ -61,59 +146,53 @@ impl InMemoryStateCalculatorV2 {
     ) -> Result<StateCheckpointOutput> {
         let state_update_refs =
             ShardedStateUpdateRefs::index_write_sets(write_sets, write_sets.len());
+        assert!(state_cache
+            .frozen_base
+            .smt
+            .is_the_same(&parent_state.current));
+        let (updates_before_last_checkpoint, updates_after_last_checkpoint) =
+            Self::calculate_updates(&state_update_refs, last_checkpoint_index);
+        let usage = Self::calculate_usage_of_updates(
+            parent_state.current.usage(),
+            &state_cache.sharded_state_cache,
+            &updates_before_last_checkpoint,
+            &updates_after_last_checkpoint,
+        );
 
         Self::calculate_impl(
             parent_state,
             state_cache,
-            &state_update_refs,
+            state_update_refs.num_versions,
             last_checkpoint_index,
             false,
             Option::<Vec<_>>::None,
+            updates_before_last_checkpoint,
+            updates_after_last_checkpoint,
+            usage,
         )
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn calculate_impl(
         parent_state: &Arc<StateDelta>,
         state_cache: &StateCache,
-        state_update_refs: &ShardedStateUpdateRefs,
+        num_txns: usize,
         last_checkpoint_index: Option<usize>,
         is_block: bool,
         known_state_checkpoints: Option<impl IntoIterator<Item = Option<HashValue>>>,
+        updates_before_last_checkpoint: ShardedStateUpdates,
+        updates_after_last_checkpoint: ShardedStateUpdates,
+        usage: StateStorageUsage,
     ) -> Result<StateCheckpointOutput> {
         let StateCache {
             // This makes sure all in-mem nodes seen while proofs were fetched stays in mem during the
             // calculation
             frozen_base,
-            sharded_state_cache,
+            sharded_state_cache: _,
             proofs,
         } = state_cache;
-        assert!(frozen_base.smt.is_the_same(&parent_state.current));
-
-        // TODO(aldenhu): use maps of refs instead of cloning the state kvs
-        let (updates_before_last_checkpoint, updates_after_last_checkpoint) =
-            Self::calculate_updates(state_update_refs, last_checkpoint_index);
-        // TODO(aldenhu): calculate on the checkpoint as well, and don't need to combine
-        let mut _all_updates_owned: Option<DropHelper<ShardedStateUpdates>> = None;
-        let all_updates = if updates_after_last_checkpoint.all_shards_empty() {
-            &updates_before_last_checkpoint
-        } else if updates_before_last_checkpoint.all_shards_empty() {
-            &updates_after_last_checkpoint
-        } else {
-            let _timer = OTHER_TIMERS.timer_with(&["calculate_all_updates"]);
-            let mut all_updates = updates_before_last_checkpoint.clone();
-            all_updates.clone_merge(&updates_after_last_checkpoint);
-            _all_updates_owned = Some(DropHelper::new(all_updates));
-            _all_updates_owned.as_ref().expect("Just set").deref()
-        };
-
-        let usage = Self::calculate_usage(
-            parent_state.current.usage(),
-            sharded_state_cache,
-            all_updates,
-        );
 
         let first_version = parent_state.current_version.map_or(0, |v| v + 1);
-        let num_txns = state_update_refs.num_versions;
         let proof_reader = ProofReader::new(proofs);
         let latest_checkpoint = if let Some(index) = last_checkpoint_index {
             Self::make_checkpoint(
@@ -210,6 +289,29 @@ impl InMemoryStateCalculatorV2 {
         ))
     }
 
+    fn calculate_usage_of_updates(
+        old_usage: StateStorageUsage,
+        sharded_state_cache: &ShardedStateCache,
+        updates_before_last_checkpoint: &ShardedStateUpdates,
+        updates_after_last_checkpoint: &ShardedStateUpdates,
+    ) -> StateStorageUsage {
+        // TODO(aldenhu): calculate on the checkpoint as well, and don't need to combine
+        let mut _all_updates_owned: Option<DropHelper<ShardedStateUpdates>> = None;
+        let all_updates = if updates_after_last_checkpoint.all_shards_empty() {
+            updates_before_last_checkpoint
+        } else if updates_before_last_checkpoint.all_shards_empty() {
+            updates_after_last_checkpoint
+        } else {
+            let _timer = OTHER_TIMERS.timer_with(&["calculate_all_updates"]);
+            let mut all_updates = updates_before_last_checkpoint.clone();
+            all_updates.clone_merge(updates_after_last_checkpoint);
+            _all_updates_owned = Some(DropHelper::new(all_updates));
+            _all_updates_owned.as_ref().expect("Just set").deref()
+        };
+
+        Self::calculate_usage(old_usage, sharded_state_cache, all_updates)
+    }
+
     fn calculate_updates(
         state_update_refs: &ShardedStateUpdateRefs,
         last_checkpoint_index: Option<usize>,
@@ -3,19 +3,73 @@
 
 #![forbid(unsafe_code)]
 
+use anyhow::{anyhow, ensure, Result};
 use aptos_executor_types::{
-    execution_output::ExecutionOutput, state_checkpoint_output::StateCheckpointOutput,
-    state_compute_result::StateComputeResult, LedgerUpdateOutput,
+    execution_output::ExecutionOutput, planned::Planned,
+    state_checkpoint_output::StateCheckpointOutput, state_compute_result::StateComputeResult,
+    LedgerUpdateOutput,
+};
+use aptos_scratchpad::SparseMerkleTree;
+use aptos_storage_interface::state_store::{
+    sharded_state_updates::ShardedStateUpdates, state_delta::StateDelta,
+};
+use aptos_types::{
+    proof::accumulator::InMemoryTransactionAccumulator,
+    state_store::{state_storage_usage::StateStorageUsage, state_value::StateValue},
+    transaction::Version,
 };
-use aptos_storage_interface::state_store::state_delta::StateDelta;
-use aptos_types::proof::accumulator::InMemoryTransactionAccumulator;
 use once_cell::sync::OnceCell;
 use std::sync::Arc;
 
+/// The state after a block, as needed to execute its children before the state checkpoint of
+/// the block is calculated.
+#[derive(Clone, Debug)]
+pub struct SpeculativeState {
+    /// The latest state tree calculated among the block and its ancestors
+    pub base: SparseMerkleTree<StateValue>,
+    /// The state updates of the blocks after `base`, oldest first
+    pub pending_updates: Vec<Arc<ShardedStateUpdates>>,
+    /// The version of the last transaction of the block
+    pub version: Option<Version>,
+    pub usage: StateStorageUsage,
+}
+
+impl SpeculativeState {
+    fn new(state: &StateDelta) -> Self {
+        Self {
+            base: state.current.clone(),
+            pending_updates: vec![],
+            version: state.current_version,
+            usage: state.current.usage(),
+        }
+    }
+
+    /// Returns the state after a child block with the given updates, ending at `version`.
+    pub fn child(
+        &self,
+        updates: Arc<ShardedStateUpdates>,
+        version: Version,
+        usage: StateStorageUsage,
+    ) -> Self {
+        let mut pending_updates = self.pending_updates.clone();
+        pending_updates.push(updates);
+        Self {
+            base: self.base.clone(),
+            pending_updates,
+            version: Some(version),
+            usage,
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct PartialStateComputeResult {
     pub execution_output: ExecutionOutput,
     pub state_checkpoint_output: OnceCell<StateCheckpointOutput>,
+    /// The state checkpoint being calculated in the background, if it's not set yet.
+    planned_state_checkpoint_output: OnceCell<Arc<Planned<Result<StateCheckpointOutput>>>>,
+    /// The state after the block while its state checkpoint is being calculated.
+    speculative_state: OnceCell<SpeculativeState>,
     pub ledger_update_output: OnceCell<LedgerUpdateOutput>,
 }
 
@@ -24,6 +78,8 @@ impl PartialStateComputeResult {
         Self {
             execution_output,
             state_checkpoint_output: OnceCell::new(),
+            planned_state_checkpoint_output: OnceCell::new(),
+            speculative_state: OnceCell::new(),
             ledger_update_output: OnceCell::new(),
         }
     }
@@ -45,6 +101,8 @@ impl PartialStateComputeResult {
         Self {
             execution_output,
             state_checkpoint_output,
+            planned_state_checkpoint_output: OnceCell::new(),
+            speculative_state: OnceCell::new(),
             ledger_update_output,
         }
     }
@@ -54,13 +112,34 @@ impl PartialStateComputeResult {
     }
 
     pub fn get_state_checkpoint_output(&self) -> Option<&StateCheckpointOutput> {
-        self.state_checkpoint_output.get()
+        if let Some(state_checkpoint_output) = self.state_checkpoint_output.get() {
+            return Some(state_checkpoint_output);
+        }
+        match self.planned_state_checkpoint_output.get()?.try_get()? {
+            Ok(state_checkpoint_output) => Some(state_checkpoint_output),
+            Err(_) => None,
+        }
     }
 
-    pub fn expect_state_checkpoint_output(&self) -> &StateCheckpointOutput {
-        self.state_checkpoint_output
+    /// Returns the state checkpoint output, waiting for it if it's being calculated.
+    pub fn wait_for_state_checkpoint_output(&self) -> Result<&StateCheckpointOutput> {
+        if let Some(state_checkpoint_output) = self.state_checkpoint_output.get() {
+            return Ok(state_checkpoint_output);
+        }
+        let planned = self
+            .planned_state_checkpoint_output
             .get()
-            .expect("StateCheckpointOutput not set")
+            .ok_or_else(|| anyhow!("StateCheckpointOutput not set"))?;
+        planned
+            .wait(Some("wait_for_state_checkpoint"))
+            .map_err(|_| anyhow!("State checkpoint calculation was aborted"))?
+            .as_ref()
+            .map_err(|err| anyhow!("Failed to calculate the state checkpoint: {}", err))
+    }
+
+    pub fn expect_state_checkpoint_output(&self) -> &StateCheckpointOutput {
+        self.wait_for_state_checkpoint_output()
+            .expect("StateCheckpointOutput not available")
     }
 
     pub fn expect_result_state(&self) -> &Arc<StateDelta> {
@@ -73,6 +152,54 @@ impl PartialStateComputeResult {
             .expect("StateCheckpointOutput already set");
     }
 
+    /// Sets the state checkpoint output being calculated in the background, along with the state
+    /// the children of the block can be executed on meanwhile.
+    pub fn plan_state_checkpoint_output(
+        &self,
+        planned: Planned<Result<StateCheckpointOutput>>,
+        speculative_state: SpeculativeState,
+    ) {
+        assert!(
+            self.state_checkpoint_output.get().is_none(),
+            "StateCheckpointOutput already set"
+        );
+        self.planned_state_checkpoint_output
+            .set(Arc::new(planned))
+            .expect("StateCheckpointOutput already planned");
+        self.speculative_state
+            .set(speculative_state)
+            .expect("SpeculativeState already set");
+    }
+
+    /// Returns the state to execute the children of the block on. While the state checkpoint is
+    /// being calculated, it's the latest calculated state of the ancestors with the updates since
+    /// then, unless there are already `max_pending_blocks` of them, in which case this waits for
+    /// the state checkpoint.
+    pub fn state_for_children(&self, max_pending_blocks: usize) -> Result<SpeculativeState> {
+        let speculative_state = match self.speculative_state.get() {
+            Some(speculative_state) if self.get_state_checkpoint_output().is_none() => {
+                speculative_state
+            },
+            _ => {
+                return Ok(SpeculativeState::new(
+                    &self.wait_for_state_checkpoint_output()?.result_state,
+                ))
+            },
+        };
+        if speculative_state.pending_updates.len() < max_pending_blocks {
+            return Ok(speculative_state.clone());
+        }
+
+        let state = SpeculativeState::new(&self.wait_for_state_checkpoint_output()?.result_state);
+        ensure!(
+            state.version == speculative_state.version,
+            "State checkpoint at version {:?} doesn't match the speculative state at version {:?}",
+            state.version,
+            speculative_state.version,
+        );
+        Ok(state)
+    }
+
     pub fn get_ledger_update_output(&self) -> Option<&LedgerUpdateOutput> {
         self.ledger_update_output.get()
     }
@@ -4,6 +4,7 @@
 use crate::{
     metrics,
     metrics::{EXECUTOR_ERRORS, OTHER_TIMERS},
+    workflow::do_ledger_update::DoLedgerUpdate,
 };
 use anyhow::{anyhow, Result};
 use aptos_block_executor::txn_provider::default::DefaultTxnProvider;
@@ -15,7 +16,7 @@ use aptos_executor_service::{
     remote_executor_client::{get_remote_addresses, REMOTE_SHARDED_BLOCK_EXECUTOR},
 };
 use aptos_executor_types::{
-    execution_output::ExecutionOutput,
+    execution_output::{ExecutionOutput, OutputHashes},
     planned::Planned,
     should_forward_to_subscription_service,
     transactions_with_output::{TransactionsToKeep, TransactionsWithOutput},
@@ -48,8 +49,9 @@ use aptos_types::{
     write_set::{TransactionWrite, WriteSet},
 };
 use aptos_vm::VMBlockExecutor;
+use crossbeam_channel::Receiver;
 use itertools::Itertools;
-use std::sync::Arc;
+use std::{sync::Arc, thread};
 
 pub struct DoGetExecutionOutput;
 
@@ -124,6 +126,7 @@ impl DoGetExecutionOutput {
             state_view.into_state_cache(),
             block_end_info,
             append_state_checkpoint_to_block,
+            None, // output hashes
         )
     }
 
@@ -134,8 +137,8 @@ impl DoGetExecutionOutput {
         append_state_checkpoint_to_block: Option<HashValue>,
     ) -> Result<ExecutionOutput> {
         let state_view_arc = Arc::new(state_view);
-        let transaction_outputs = Self::execute_block_sharded::<V>(
-            transactions.clone(),
+        let (transaction_outputs, output_hashes) = Self::execute_block_sharded::<V>(
+            &transactions,
             state_view_arc.clone(),
             onchain_config,
         )?;
@@ -155,6 +158,7 @@ impl DoGetExecutionOutput {
             state_view.into_state_cache(),
             None, // block end info
             append_state_checkpoint_to_block,
+            Some(output_hashes),
         )
     }
 
@@ -179,6 +183,7 @@ impl DoGetExecutionOutput {
             state_view.into_state_cache(),
             None, // block end info
             None, // append state checkpoint to block
+            None, // output hashes
         )?;
 
         let ret = out.clone();
@@ -194,26 +199,67 @@ impl DoGetExecutionOutput {
         Ok(ret)
     }
 
+    /// Executes the block with the sharded block executor. The outputs are streamed by the
+    /// executor, so that the hashes needed by the ledger update are calculated while the rest of
+    /// the block is being executed.
     fn execute_block_sharded<V: VMBlockExecutor>(
-        partitioned_txns: PartitionedTransactions,
+        partitioned_txns: &PartitionedTransactions,
         state_view: Arc<CachedStateView>,
         onchain_config: BlockExecutorConfigFromOnchain,
-    ) -> Result<Vec<TransactionOutput>> {
-        if !get_remote_addresses().is_empty() {
-            Ok(V::execute_block_sharded(
-                &REMOTE_SHARDED_BLOCK_EXECUTOR.lock(),
-                partitioned_txns,
-                state_view,
-                onchain_config,
-            )?)
+    ) -> Result<(Vec<TransactionOutput>, OutputHashes)> {
+        let remote_executor = if !get_remote_addresses().is_empty() {
+            Some(REMOTE_SHARDED_BLOCK_EXECUTOR.as_ref().map_err(|e| {
+                anyhow!("Failed to create the remote sharded block executor: {}", e)
+            })?)
         } else {
-            Ok(V::execute_block_sharded(
-                &SHARDED_BLOCK_EXECUTOR.lock(),
-                partitioned_txns,
-                state_view,
-                onchain_config,
-            )?)
+            None
+        };
+        let (output_tx, output_rx) = crossbeam_channel::unbounded();
+        thread::scope(|s| {
+            let output_collector = s.spawn(move || Self::collect_streamed_outputs(output_rx));
+            // The sender is dropped once the execution finishes, which stops the collector.
+            let execution_result = if let Some(remote_executor) = remote_executor {
+                V::execute_block_sharded_streaming(
+                    &remote_executor.lock(),
+                    partitioned_txns,
+                    state_view,
+                    onchain_config,
+                    output_tx,
+                )
+            } else {
+                V::execute_block_sharded_streaming(
+                    &SHARDED_BLOCK_EXECUTOR.lock(),
+                    partitioned_txns,
+                    state_view,
+                    onchain_config,
+                    output_tx,
+                )
+            };
+            let collected = output_collector
+                .join()
+                .expect("Collecting streamed outputs panicked.");
+            execution_result?;
+            Ok(collected)
+        })
+    }
+
+    fn collect_streamed_outputs(
+        output_rx: Receiver<Vec<TransactionOutput>>,
+    ) -> (Vec<TransactionOutput>, OutputHashes) {
+        let mut transaction_outputs = vec![];
+        let mut output_hashes = OutputHashes::default();
+        for chunk in output_rx {
+            let _timer = OTHER_TIMERS.timer_with(&["hash_streamed_outputs"]);
+            let (event_root_hashes, write_set_hashes) = THREAD_MANAGER
+                .get_non_exe_cpu_pool()
+                .install(|| DoLedgerUpdate::calculate_events_and_writeset_hashes(&chunk));
+            output_hashes.extend(OutputHashes {
+                event_root_hashes,
+                write_set_hashes,
+            });
+            transaction_outputs.extend(chunk);
         }
+        (transaction_outputs, output_hashes)
     }
 
     /// Executes the block of [Transaction]s using the [VMBlockExecutor] and returns
@@ -290,6 +336,7 @@ impl Parser {
         state_cache: StateCache,
         block_end_info: Option<BlockEndInfo>,
         append_state_checkpoint_to_block: Option<HashValue>,
+        output_hashes_for_input_txns: Option<OutputHashes>,
     ) -> Result<ExecutionOutput> {
         let _timer = OTHER_TIMERS.timer_with(&["parse_raw_output"]);
 
@@ -344,6 +391,7 @@ impl Parser {
             block_end_info,
             next_epoch_state,
             Planned::place_holder(),
+            output_hashes_for_input_txns,
         );
         let ret = out.clone();
         ret.subscribable_events
@@ -541,7 +589,7 @@ mod tests {
             ),
         ];
         let execution_output =
-            Parser::parse(0, txns, txn_outs, StateCache::new_dummy(), None, None).unwrap();
+            Parser::parse(0, txns, txn_outs, StateCache::new_dummy(), None, None, None).unwrap();
         assert_eq!(
             vec![event_0, event_2],
             *execution_output.subscribable_events
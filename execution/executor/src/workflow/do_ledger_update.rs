@@ -5,14 +5,16 @@ use crate::metrics::OTHER_TIMERS;
 use anyhow::Result;
 use aptos_crypto::{hash::CryptoHash, HashValue};
 use aptos_executor_types::{
-    execution_output::ExecutionOutput, state_checkpoint_output::StateCheckpointOutput,
-    transactions_with_output::TransactionsWithOutput, LedgerUpdateOutput,
+    execution_output::{ExecutionOutput, OutputHashes},
+    state_checkpoint_output::StateCheckpointOutput,
+    transactions_with_output::TransactionsWithOutput,
+    LedgerUpdateOutput,
 };
 use aptos_experimental_runtimes::thread_manager::optimal_min_len;
 use aptos_metrics_core::TimerHelper;
 use aptos_types::{
     proof::accumulator::{InMemoryEventAccumulator, InMemoryTransactionAccumulator},
-    transaction::{TransactionInfo, TransactionOutput},
+    transaction::{TransactionInfo, TransactionOutput, TransactionStatus},
 };
 use itertools::{izip, Itertools};
 use rayon::prelude::*;
@@ -28,10 +30,17 @@ impl DoLedgerUpdate {
     ) -> Result<LedgerUpdateOutput> {
         let _timer = OTHER_TIMERS.timer_with(&["do_ledger_update"]);
 
-        // Calculate hashes
+        // Calculate hashes, unless already calculated during execution.
         let txn_outs = &execution_output.to_commit.transaction_outputs;
 
-        let (event_hashes, writeset_hashes) = Self::calculate_events_and_writeset_hashes(txn_outs);
+        let (event_hashes, writeset_hashes) = match &execution_output.output_hashes_for_input_txns {
+            Some(output_hashes) => Self::reuse_events_and_writeset_hashes(
+                txn_outs,
+                &execution_output.statuses_for_input_txns,
+                output_hashes,
+            ),
+            None => Self::calculate_events_and_writeset_hashes(txn_outs),
+        };
 
         // Assemble `TransactionInfo`s
         let transaction_infos = Self::assemble_transaction_infos(
@@ -53,7 +62,31 @@ impl DoLedgerUpdate {
         ))
     }
 
-    fn calculate_events_and_writeset_hashes(
+    /// Picks the hashes of the committed txns out of the hashes of all input txns, and calculates
+    /// the hashes of the remaining ones (i.e. the StateCheckpoint/BlockEpilogue appended during
+    /// execution).
+    fn reuse_events_and_writeset_hashes(
+        to_commit: &[TransactionOutput],
+        statuses_for_input_txns: &[TransactionStatus],
+        output_hashes: &OutputHashes,
+    ) -> (Vec<HashValue>, Vec<HashValue>) {
+        assert_eq!(output_hashes.len(), statuses_for_input_txns.len());
+        // Discarded and retried txns are not committed, and the others keep their relative order.
+        let (mut event_hashes, mut writeset_hashes): (Vec<_>, Vec<_>) = statuses_for_input_txns
+            .iter()
+            .zip(output_hashes.event_root_hashes.iter())
+            .zip(output_hashes.write_set_hashes.iter())
+            .filter(|((status, _), _)| matches!(status, TransactionStatus::Keep(_)))
+            .map(|((_, event_hash), writeset_hash)| (*event_hash, *writeset_hash))
+            .unzip();
+        let (remaining_event_hashes, remaining_writeset_hashes) =
+            Self::calculate_events_and_writeset_hashes(&to_commit[event_hashes.len()..]);
+        event_hashes.extend(remaining_event_hashes);
+        writeset_hashes.extend(remaining_writeset_hashes);
+        (event_hashes, writeset_hashes)
+    }
+
+    pub(crate) fn calculate_events_and_writeset_hashes(
         to_commit: &[TransactionOutput],
     ) -> (Vec<HashValue>, Vec<HashValue>) {
         let _timer = OTHER_TIMERS.timer_with(&["calculate_events_and_writeset_hashes"]);
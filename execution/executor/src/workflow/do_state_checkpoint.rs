@@ -1,13 +1,14 @@
 // Copyright (c) Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::types::in_memory_state_calculator_v2::InMemoryStateCalculatorV2;
+use crate::types::in_memory_state_calculator_v2::{BlockStateUpdates, InMemoryStateCalculatorV2};
 use anyhow::Result;
 use aptos_crypto::HashValue;
 use aptos_executor_types::{
     execution_output::ExecutionOutput, state_checkpoint_output::StateCheckpointOutput,
 };
 use aptos_storage_interface::state_store::state_delta::StateDelta;
+use aptos_types::state_store::state_storage_usage::StateStorageUsage;
 use std::sync::Arc;
 
 pub struct DoStateCheckpoint;
@@ -25,4 +26,25 @@ impl DoStateCheckpoint {
             known_state_checkpoints,
         )
     }
+
+    /// Calculates the part of the state checkpoint of a block needed to execute its children.
+    pub fn prepare_block(
+        execution_output: &ExecutionOutput,
+        parent_usage: StateStorageUsage,
+    ) -> Result<BlockStateUpdates> {
+        InMemoryStateCalculatorV2::calculate_block_updates(execution_output, parent_usage)
+    }
+
+    /// Calculates the rest of the state checkpoint of a block, once its parent's is available.
+    pub fn run_for_block(
+        execution_output: &ExecutionOutput,
+        parent_state: &Arc<StateDelta>,
+        block_updates: BlockStateUpdates,
+    ) -> Result<StateCheckpointOutput> {
+        InMemoryStateCalculatorV2::calculate_for_block(
+            execution_output,
+            parent_state,
+            block_updates,
+        )
+    }
 }
@@ -0,0 +1,37 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Typed clients for the keyless pepper and prover services, plus helpers to verify their
+//! responses locally instead of trusting the services blindly.
+
+pub mod pepper;
+pub mod prover;
+pub mod verification;
+
+use anyhow::bail;
+pub use pepper::PepperClient;
+pub use prover::{ProverClient, ProverRequest, ProverResponse};
+use serde::{de::DeserializeOwned, Serialize};
+pub use verification::{verify_groth16_proof, verify_pepper};
+
+/// Sends `body` as JSON to `url` and parses the JSON response, failing on non-2xx statuses.
+async fn post_json<Req: Serialize + ?Sized, Resp: DeserializeOwned>(
+    client: &reqwest::Client,
+    url: &str,
+    body: &Req,
+) -> anyhow::Result<Resp> {
+    let response = client.post(url).json(body).send().await?;
+    parse_response(url, response).await
+}
+
+async fn parse_response<Resp: DeserializeOwned>(
+    url: &str,
+    response: reqwest::Response,
+) -> anyhow::Result<Resp> {
+    let status = response.status();
+    if !status.is_success() {
+        let body = response.text().await.unwrap_or_default();
+        bail!("request to {url} failed with status {status}: {body}");
+    }
+    Ok(response.json::<Resp>().await?)
+}
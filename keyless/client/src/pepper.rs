@@ -0,0 +1,112 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    parse_response, post_json,
+    verification::{derive_address, derive_pepper, verify_pepper},
+};
+use anyhow::{anyhow, bail, ensure};
+use aptos_keyless_pepper_common::{
+    jwt, PepperInput, PepperRequest, PepperResponse, PepperV0VufPubKey, SignatureResponse,
+    VerifyRequest, VerifyResponse,
+};
+use aptos_types::{account_address::AccountAddress, keyless::Pepper};
+use ark_serialize::CanonicalDeserialize;
+
+/// A client of the pepper service (see `keyless/pepper/service`).
+#[derive(Clone, Debug)]
+pub struct PepperClient {
+    base_url: String,
+    client: reqwest::Client,
+}
+
+impl PepperClient {
+    pub fn new(base_url: &str) -> Self {
+        Self::with_client(base_url, reqwest::Client::new())
+    }
+
+    pub fn with_client(base_url: &str, client: reqwest::Client) -> Self {
+        Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            client,
+        }
+    }
+
+    fn url(&self, path: &str) -> String {
+        format!("{}/v0/{}", self.base_url, path)
+    }
+
+    /// Fetches the VUF public key of the service, which pepper signatures are verified against.
+    pub async fn vuf_pub_key(&self) -> anyhow::Result<ark_bls12_381::G2Projective> {
+        let url = self.url("vuf-pub-key");
+        let response = self.client.get(&url).send().await?;
+        let PepperV0VufPubKey { public_key } = parse_response(&url, response).await?;
+        let public_key = ark_bls12_381::G2Affine::deserialize_compressed(public_key.as_slice())
+            .map_err(|e| anyhow!("invalid VUF public key: {e}"))?;
+        Ok(public_key.into())
+    }
+
+    /// Fetches the derived pepper and the address of the keyless account.
+    pub async fn fetch(&self, request: &PepperRequest) -> anyhow::Result<PepperResponse> {
+        post_json(&self.client, &self.url("fetch"), request).await
+    }
+
+    /// Fetches the VUF signature on the pepper input, i.e., the pepper before derivation.
+    pub async fn signature(&self, request: &PepperRequest) -> anyhow::Result<SignatureResponse> {
+        post_json(&self.client, &self.url("signature"), request).await
+    }
+
+    pub async fn verify(&self, request: &VerifyRequest) -> anyhow::Result<VerifyResponse> {
+        post_json(&self.client, &self.url("verify"), request).await
+    }
+
+    /// Fetches the pepper and the address, and checks them against the VUF signature of the
+    /// service on the pepper input taken from the JWT.
+    ///
+    /// NOTE: This does not support account managers that override the `aud` of the pepper input.
+    pub async fn fetch_verified(
+        &self,
+        request: &PepperRequest,
+    ) -> anyhow::Result<(Pepper, AccountAddress)> {
+        let input = pepper_input(&request.jwt, request.uid_key.as_deref())?;
+        let vuf_pk = self.vuf_pub_key().await?;
+        let SignatureResponse { signature } = self.signature(request).await?;
+        verify_pepper(&vuf_pk, &input, &signature)?;
+
+        let pepper = derive_pepper(&signature, request.derivation_path.as_deref())?;
+        let address = derive_address(&input, &pepper)?;
+        let PepperResponse {
+            pepper: fetched_pepper,
+            address: fetched_address,
+        } = self.fetch(request).await?;
+        ensure!(
+            fetched_pepper.as_slice() == pepper.to_bytes(),
+            "fetched pepper does not match the VUF signature"
+        );
+        ensure!(
+            fetched_address == address.to_vec(),
+            "fetched address does not match the VUF signature"
+        );
+        Ok((pepper, address))
+    }
+}
+
+/// Builds the pepper input the service computes from the (unverified) claims of `jwt`. The user ID
+/// is taken from `uid_key`, which defaults to `sub`.
+pub fn pepper_input(jwt: &str, uid_key: Option<&str>) -> anyhow::Result<PepperInput> {
+    let claims = jwt::parse(jwt)?.claims;
+    let uid_key = uid_key.unwrap_or("sub");
+    let uid_val = match uid_key {
+        "sub" => claims.sub,
+        "email" => claims
+            .email
+            .ok_or_else(|| anyhow!("`email` required but not found in jwt"))?,
+        _ => bail!("unsupported uid key: {}", uid_key),
+    };
+    Ok(PepperInput {
+        iss: claims.iss,
+        aud: claims.aud,
+        uid_val,
+        uid_key: uid_key.to_string(),
+    })
+}
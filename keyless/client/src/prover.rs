@@ -0,0 +1,95 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{post_json, verification::verify_groth16_proof};
+use anyhow::bail;
+use aptos_types::{
+    keyless::{Groth16Proof, Groth16ProofAndStatement, Pepper},
+    transaction::authenticator::{EphemeralPublicKey, EphemeralSignature},
+};
+use ark_bn254::Bn254;
+use ark_groth16::PreparedVerifyingKey;
+use serde::{Deserialize, Serialize};
+
+/// The request body of the `/v0/prove` endpoint of the prover service.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct ProverRequest {
+    pub jwt_b64: String,
+    pub epk: EphemeralPublicKey,
+    #[serde(with = "hex")]
+    pub epk_blinder: Vec<u8>,
+    pub exp_date_secs: u64,
+    pub exp_horizon_secs: u64,
+    pub pepper: Pepper,
+    pub uid_key: String,
+    pub extra_field: Option<String>,
+    pub idc_aud: Option<String>,
+}
+
+/// The response of the `/v0/prove` endpoint of the prover service.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub enum ProverResponse {
+    Success {
+        proof: Groth16Proof,
+        #[serde(with = "hex")]
+        public_inputs_hash: [u8; 32],
+        training_wheels_signature: EphemeralSignature,
+    },
+    Error {
+        message: String,
+    },
+}
+
+/// A client of the prover service, which computes the Groth16 proofs of keyless signatures.
+#[derive(Clone, Debug)]
+pub struct ProverClient {
+    base_url: String,
+    client: reqwest::Client,
+}
+
+impl ProverClient {
+    pub fn new(base_url: &str) -> Self {
+        Self::with_client(base_url, reqwest::Client::new())
+    }
+
+    pub fn with_client(base_url: &str, client: reqwest::Client) -> Self {
+        Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            client,
+        }
+    }
+
+    pub async fn prove(&self, request: &ProverRequest) -> anyhow::Result<ProverResponse> {
+        let url = format!("{}/v0/prove", self.base_url);
+        post_json(&self.client, &url, request).await
+    }
+
+    /// Requests a proof and verifies it, together with its training wheels signature, against the
+    /// given verification key before returning it.
+    pub async fn prove_verified(
+        &self,
+        request: &ProverRequest,
+        pvk: &PreparedVerifyingKey<Bn254>,
+        training_wheels_pk: Option<&EphemeralPublicKey>,
+    ) -> anyhow::Result<(Groth16ProofAndStatement, EphemeralSignature)> {
+        match self.prove(request).await? {
+            ProverResponse::Success {
+                proof,
+                public_inputs_hash,
+                training_wheels_signature,
+            } => {
+                let proof_and_statement = Groth16ProofAndStatement {
+                    proof,
+                    public_inputs_hash,
+                };
+                verify_groth16_proof(
+                    &proof_and_statement,
+                    pvk,
+                    training_wheels_pk.map(|pk| (pk, &training_wheels_signature)),
+                )?;
+                Ok((proof_and_statement, training_wheels_signature))
+            },
+            ProverResponse::Error { message } => bail!("prover service error: {message}"),
+        }
+    }
+}
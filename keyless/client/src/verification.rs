@@ -0,0 +1,122 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::anyhow;
+use aptos_crypto::signed_envelope::verify_signed;
+use aptos_keyless_pepper_common::{
+    vuf::{
+        bls12381_g1_bls::{Bls12381G1Bls, PinkasPepper},
+        slip_10::{get_aptos_derivation_path, ExtendedPepper, DEFAULT_DERIVATION_PATH},
+        VUF,
+    },
+    PepperInput,
+};
+use aptos_types::{
+    account_address::AccountAddress,
+    keyless::{Groth16ProofAndStatement, IdCommitment, KeylessPublicKey, Pepper},
+    transaction::authenticator::{
+        AnyPublicKey, AuthenticationKey, EphemeralPublicKey, EphemeralSignature,
+    },
+};
+use ark_bn254::{Bn254, Fr};
+use ark_ff::PrimeField;
+use ark_groth16::PreparedVerifyingKey;
+
+/// Verifies the VUF signature returned by the `/v0/signature` endpoint of the pepper service.
+pub fn verify_pepper(
+    vuf_pk: &ark_bls12_381::G2Projective,
+    input: &PepperInput,
+    signature: &[u8],
+) -> anyhow::Result<()> {
+    let input_bytes = bcs::to_bytes(input)?;
+    Bls12381G1Bls::verify(vuf_pk, &input_bytes, signature, &[])
+}
+
+/// Derives the pepper from a (verified) VUF signature the same way the pepper service does. The
+/// derivation path defaults to `DEFAULT_DERIVATION_PATH`.
+pub fn derive_pepper(signature: &[u8], derivation_path: Option<&str>) -> anyhow::Result<Pepper> {
+    let derivation_path =
+        get_aptos_derivation_path(derivation_path.unwrap_or(DEFAULT_DERIVATION_PATH))?;
+    let master_pepper = PinkasPepper::from_affine_bytes(signature)?.to_master_pepper();
+    Ok(ExtendedPepper::from_seed(master_pepper.to_bytes())?
+        .derive(&derivation_path)?
+        .get_pepper())
+}
+
+/// Computes the address of the keyless account of `input` and `pepper`.
+pub fn derive_address(input: &PepperInput, pepper: &Pepper) -> anyhow::Result<AccountAddress> {
+    let idc = IdCommitment::new_from_preimage(pepper, &input.aud, &input.uid_key, &input.uid_val)?;
+    let public_key = KeylessPublicKey {
+        iss_val: input.iss.clone(),
+        idc,
+    };
+    Ok(AuthenticationKey::any_key(AnyPublicKey::keyless(public_key)).account_address())
+}
+
+/// Verifies a Groth16 proof returned by the prover service against `pvk`, and, if given, its
+/// training wheels signature against the training wheels public key.
+pub fn verify_groth16_proof(
+    proof_and_statement: &Groth16ProofAndStatement,
+    pvk: &PreparedVerifyingKey<Bn254>,
+    training_wheels: Option<(&EphemeralPublicKey, &EphemeralSignature)>,
+) -> anyhow::Result<()> {
+    if let Some((training_wheels_pk, training_wheels_signature)) = training_wheels {
+        verify_signed(
+            proof_and_statement,
+            training_wheels_signature,
+            training_wheels_pk,
+        )
+        .map_err(|e| anyhow!("training wheels signature verification failed: {e}"))?;
+    }
+    let public_inputs_hash = Fr::from_le_bytes_mod_order(&proof_and_statement.public_inputs_hash);
+    proof_and_statement
+        .proof
+        .verify_proof(public_inputs_hash, pvk)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aptos_crypto::ed25519::Ed25519PublicKey;
+    use aptos_types::keyless::{
+        test_utils::{
+            get_sample_groth16_sig_and_pk, get_sample_groth16_zkp_and_statement, get_sample_tw_sk,
+        },
+        EphemeralCertificate, DEVNET_VERIFICATION_KEY,
+    };
+
+    fn sample_training_wheels() -> (EphemeralPublicKey, EphemeralSignature) {
+        let (sig, _) = get_sample_groth16_sig_and_pk();
+        let training_wheels_signature = match sig.cert {
+            EphemeralCertificate::ZeroKnowledgeSig(zks) => zks.training_wheels_signature.unwrap(),
+            EphemeralCertificate::OpenIdSig(_) => unreachable!(),
+        };
+        let training_wheels_pk =
+            EphemeralPublicKey::ed25519(Ed25519PublicKey::from(&get_sample_tw_sk()));
+        (training_wheels_pk, training_wheels_signature)
+    }
+
+    #[test]
+    fn test_verify_groth16_proof() {
+        let proof_and_statement = get_sample_groth16_zkp_and_statement();
+        let (training_wheels_pk, training_wheels_signature) = sample_training_wheels();
+
+        verify_groth16_proof(&proof_and_statement, &DEVNET_VERIFICATION_KEY, None).unwrap();
+        verify_groth16_proof(
+            &proof_and_statement,
+            &DEVNET_VERIFICATION_KEY,
+            Some((&training_wheels_pk, &training_wheels_signature)),
+        )
+        .unwrap();
+
+        let mut tampered = proof_and_statement.clone();
+        tampered.public_inputs_hash[0] ^= 1;
+        assert!(verify_groth16_proof(&tampered, &DEVNET_VERIFICATION_KEY, None).is_err());
+        assert!(verify_groth16_proof(
+            &tampered,
+            &DEVNET_VERIFICATION_KEY,
+            Some((&training_wheels_pk, &training_wheels_signature)),
+        )
+        .is_err());
+    }
+}
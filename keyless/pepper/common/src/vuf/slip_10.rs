@@ -16,6 +16,9 @@ use std::str::FromStr;
 
 const PEPPER_SLIP_10_NAME: &str = "32 bytes";
 
+/// The derivation path used when a pepper request does not specify one.
+pub const DEFAULT_DERIVATION_PATH: &str = "m/44'/637'/0'/0'/0'";
+
 /// Errors thrown while deriving secret keys
 #[derive(Debug)]
 pub enum Error {
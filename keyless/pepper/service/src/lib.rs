@@ -20,7 +20,7 @@ use aptos_keyless_pepper_common::{
     vuf::{
         self,
         bls12381_g1_bls::PinkasPepper,
-        slip_10::{get_aptos_derivation_path, ExtendedPepper},
+        slip_10::{get_aptos_derivation_path, ExtendedPepper, DEFAULT_DERIVATION_PATH},
         VUF,
     },
     PepperInput, PepperRequest, PepperResponse, SignatureResponse, VerifyRequest, VerifyResponse,
@@ -60,8 +60,6 @@ pub enum ProcessingFailure {
     InternalError(String),
 }
 
-pub const DEFAULT_DERIVATION_PATH: &str = "m/44'/637'/0'/0'/0'";
-
 #[async_trait]
 pub trait HandlerTrait<REQ, RES>: Send + Sync {
     async fn handle(&self, request: REQ) -> Result<RES, ProcessingFailure>;
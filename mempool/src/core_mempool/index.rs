@@ -27,7 +27,8 @@ pub type AccountTransactions = BTreeMap<u64, MempoolTransaction>;
 
 /// PriorityIndex represents the main Priority Queue in Mempool.
 /// It's used to form the transaction block for Consensus.
-/// Transactions are ordered by gas price. Second level ordering is done by expiration time.
+/// Transactions in the priority lane come first, then transactions are ordered by gas price.
+/// Second level ordering is done by expiration time.
 ///
 /// We don't store the full content of transactions in the index.
 /// Instead we use `OrderedQueueKey` - logical reference to the transaction in the main store.
@@ -58,6 +59,7 @@ impl PriorityIndex {
 
     fn make_key(&self, txn: &MempoolTransaction) -> OrderedQueueKey {
         OrderedQueueKey {
+            priority_lane: txn.priority_lane,
             gas_ranking_score: txn.ranking_score,
             expiration_time: txn.expiration_time,
             insertion_time: txn.insertion_info.insertion_time,
@@ -78,6 +80,7 @@ impl PriorityIndex {
 
 #[derive(Eq, PartialEq, Clone, Debug, Hash)]
 pub struct OrderedQueueKey {
+    pub priority_lane: bool,
     pub gas_ranking_score: u64,
     pub expiration_time: Duration,
     pub insertion_time: SystemTime,
@@ -94,6 +97,10 @@ impl PartialOrd for OrderedQueueKey {
 
 impl Ord for OrderedQueueKey {
     fn cmp(&self, other: &OrderedQueueKey) -> Ordering {
+        match self.priority_lane.cmp(&other.priority_lane) {
+            Ordering::Equal => {},
+            ordering => return ordering,
+        }
         match self.gas_ranking_score.cmp(&other.gas_ranking_score) {
             Ordering::Equal => {},
             ordering => return ordering,
@@ -346,6 +353,22 @@ impl MultiBucketTimelineIndex {
         returned.iter().rev().cloned().collect()
     }
 
+    /// Read the transactions from the timeline of a single bucket since <timeline_id>.
+    /// At most `count` transactions will be returned.
+    pub(crate) fn read_bucket_timeline(
+        &self,
+        timeline_index_identifier: usize,
+        timeline_id: u64,
+        count: usize,
+        before: Option<Instant>,
+    ) -> Vec<(AccountAddress, u64)> {
+        self.timelines
+            .get(timeline_index_identifier)
+            .map_or_else(Vec::new, |timeline| {
+                timeline.read_timeline(timeline_id, count, before)
+            })
+    }
+
     /// Read transactions from the timeline from `start_id` (exclusive) to `end_id` (inclusive).
     pub(crate) fn timeline_range(
         &self,
@@ -510,10 +533,34 @@ impl ParkingLotIndex {
         })
     }
 
+    /// Returns the "non-ready" transaction (with highest sequence number for its account) with
+    /// the lowest score, among those of `num_samples` random accounts.
+    pub(crate) fn get_poppable_by_sampling(
+        &self,
+        num_samples: usize,
+        score: impl Fn(&TxnPointer) -> u64,
+    ) -> Option<TxnPointer> {
+        let mut rng = rand::thread_rng();
+        self.data
+            .choose_multiple(&mut rng, num_samples)
+            .filter_map(|(sender, txns)| {
+                txns.iter().next_back().map(|(seq_num, hash)| TxnPointer {
+                    sender: *sender,
+                    sequence_number: *seq_num,
+                    hash: *hash,
+                })
+            })
+            .min_by_key(|txn_pointer| score(txn_pointer))
+    }
+
     pub(crate) fn size(&self) -> usize {
         self.size
     }
 
+    pub(crate) fn num_accounts(&self) -> usize {
+        self.data.len()
+    }
+
     pub(crate) fn get_addresses(&self) -> Vec<(AccountAddress, u64)> {
         self.data
             .iter()
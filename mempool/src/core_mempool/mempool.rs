@@ -7,17 +7,18 @@
 use crate::{
     core_mempool::{
         index::TxnPointer,
+        priority_lane::PriorityLaneClassifier,
         transaction::{InsertionInfo, MempoolTransaction, TimelineState},
-        transaction_store::{sender_bucket, TransactionStore},
+        transaction_store::{sender_bucket, FairBroadcastBatch, TransactionStore},
     },
     counters,
     logging::{LogEntry, LogSchema, TxnsLog},
     network::BroadcastPeerPriority,
     shared_mempool::types::{
-        MempoolSenderBucket, MultiBucketTimelineIndexIds, TimelineIndexIdentifier,
+        EvictionPressure, MempoolSenderBucket, MultiBucketTimelineIndexIds, TimelineIndexIdentifier,
     },
 };
-use aptos_config::config::NodeConfig;
+use aptos_config::config::{BroadcastFairnessConfig, NodeConfig};
 use aptos_consensus_types::common::{TransactionInProgress, TransactionSummary};
 use aptos_crypto::HashValue;
 use aptos_logger::prelude::*;
@@ -37,6 +38,9 @@ pub struct Mempool {
     // Stores the metadata of all transactions in mempool (of all states).
     transactions: TransactionStore,
 
+    // Classifies the transactions into the priority lane
+    priority_lane_classifier: PriorityLaneClassifier,
+
     pub system_transaction_timeout: Duration,
 }
 
@@ -44,6 +48,7 @@ impl Mempool {
     pub fn new(config: &NodeConfig) -> Self {
         Mempool {
             transactions: TransactionStore::new(&config.mempool),
+            priority_lane_classifier: PriorityLaneClassifier::new(&config.mempool.priority_lanes),
             system_transaction_timeout: Duration::from_secs(
                 config.mempool.system_transaction_timeout_secs,
             ),
@@ -305,7 +310,7 @@ impl Mempool {
             aptos_infallible::duration_since_epoch_at(&now) + self.system_transaction_timeout;
 
         let sender = txn.sender();
-        let txn_info = MempoolTransaction::new(
+        let mut txn_info = MempoolTransaction::new(
             txn.clone(),
             expiration_time,
             ranking_score,
@@ -315,6 +320,7 @@ impl Mempool {
             client_submitted,
             priority.clone(),
         );
+        txn_info.priority_lane = self.priority_lane_classifier.is_priority(&txn);
 
         let submitted_by_label = txn_info.insertion_info.submitted_by_label();
         let status = self.transactions.insert(txn_info);
@@ -557,6 +563,84 @@ impl Mempool {
         )
     }
 
+    /// Builds a broadcast batch of at most `max_txns` transactions from the timelines of the given
+    /// sender buckets, in a weighted round-robin: every round takes the next transactions of each
+    /// sender bucket in turn, up to the weight of each gas price bucket. The transactions of each
+    /// account are limited, so that a single sender can't fill the batch. Returns the transactions,
+    /// and the old and new timeline ids of each sender bucket.
+    pub(crate) fn read_timelines_fair(
+        &self,
+        sender_buckets: Vec<(
+            MempoolSenderBucket,
+            MultiBucketTimelineIndexIds,
+            Option<Instant>,
+            BroadcastPeerPriority,
+        )>,
+        max_txns: usize,
+        fairness_config: &BroadcastFairnessConfig,
+    ) -> (
+        Vec<(SignedTransaction, u64, BroadcastPeerPriority)>,
+        Vec<(
+            MempoolSenderBucket,
+            (MultiBucketTimelineIndexIds, MultiBucketTimelineIndexIds),
+        )>,
+    ) {
+        let mut batch = FairBroadcastBatch::new(max_txns, fairness_config.max_txns_per_account);
+        let mut timeline_ids: Vec<_> = sender_buckets
+            .iter()
+            .map(|(_, timeline_id, _, _)| timeline_id.clone())
+            .collect();
+
+        // Take the transactions round by round, until the batch is full or there are no more
+        while !batch.is_full() {
+            let num_txns = batch.len();
+            for ((sender_bucket, _, before, priority), timeline_id) in
+                sender_buckets.iter().zip(timeline_ids.iter_mut())
+            {
+                if batch.is_full() {
+                    break;
+                }
+                let quotas: Vec<_> = (0..timeline_id.id_per_bucket.len())
+                    .map(|bucket_index| fairness_config.gas_bucket_weight(bucket_index))
+                    .collect();
+                *timeline_id = self.transactions.read_timeline_fair(
+                    *sender_bucket,
+                    timeline_id,
+                    &quotas,
+                    *before,
+                    priority.clone(),
+                    &mut batch,
+                );
+            }
+            if batch.len() == num_txns {
+                break;
+            }
+        }
+
+        let (txns, txns_per_sender_bucket) = batch.into_parts();
+        for (sender_bucket, _, _, _) in sender_buckets.iter() {
+            let num_txns = txns_per_sender_bucket
+                .get(sender_bucket)
+                .copied()
+                .unwrap_or(0);
+            counters::shared_mempool_broadcast_sender_bucket_share(
+                *sender_bucket,
+                num_txns,
+                txns.len(),
+            );
+        }
+        let timeline_updates = sender_buckets
+            .into_iter()
+            .zip(timeline_ids)
+            .map(
+                |((sender_bucket, old_timeline_id, _, _), new_timeline_id)| {
+                    (sender_bucket, (old_timeline_id, new_timeline_id))
+                },
+            )
+            .collect();
+        (txns, timeline_updates)
+    }
+
     /// Read transactions from timeline from `start_id` (exclusive) to `end_id` (inclusive),
     /// along with their ready times in millis since poch
     pub(crate) fn timeline_range(
@@ -598,7 +682,15 @@ impl Mempool {
         &self.transactions
     }
 
+    pub fn get_eviction_pressure(&self) -> EvictionPressure {
+        self.transactions.get_eviction_pressure()
+    }
+
     pub fn get_parking_lot_addresses(&self) -> Vec<(AccountAddress, u64)> {
         self.transactions.get_parking_lot_addresses()
     }
+
+    pub(crate) fn get_client_submitted_transactions(&self) -> Vec<SignedTransaction> {
+        self.transactions.get_client_submitted_transactions()
+    }
 }
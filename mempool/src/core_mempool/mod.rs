@@ -4,6 +4,7 @@
 
 mod index;
 mod mempool;
+mod priority_lane;
 pub mod transaction;
 mod transaction_store;
 
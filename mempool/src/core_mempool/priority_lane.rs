@@ -0,0 +1,112 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_config::config::PriorityLanesConfig;
+use aptos_crypto::HashValue;
+use aptos_logger::prelude::*;
+use aptos_types::{
+    account_address::AccountAddress,
+    transaction::{SignedTransaction, TransactionPayload},
+};
+use move_core_types::{identifier::Identifier, language_storage::ModuleId};
+use std::{collections::HashSet, str::FromStr};
+
+/// Classifies the operational transactions into the priority lane, see `PriorityLanesConfig`.
+pub(crate) struct PriorityLaneClassifier {
+    enable: bool,
+    priority_senders: HashSet<AccountAddress>,
+    priority_entry_functions: HashSet<(ModuleId, Identifier)>,
+    priority_script_hashes: HashSet<HashValue>,
+}
+
+impl PriorityLaneClassifier {
+    pub(crate) fn new(config: &PriorityLanesConfig) -> Self {
+        let priority_entry_functions = config
+            .priority_entry_functions
+            .iter()
+            .filter_map(|entry_function| {
+                let parsed = parse_entry_function(entry_function);
+                if parsed.is_none() {
+                    warn!(
+                        "Ignoring the invalid priority entry function: {}",
+                        entry_function
+                    );
+                }
+                parsed
+            })
+            .collect();
+        Self {
+            enable: config.enable,
+            priority_senders: config.priority_senders.iter().copied().collect(),
+            priority_entry_functions,
+            priority_script_hashes: config.priority_script_hashes.iter().copied().collect(),
+        }
+    }
+
+    /// Returns true iff the transaction is sent by a framework address or a priority sender, or
+    /// calls an allowlisted entry function or script.
+    pub(crate) fn is_priority(&self, txn: &SignedTransaction) -> bool {
+        if !self.enable {
+            return false;
+        }
+        let sender = txn.sender();
+        if sender.is_special() || self.priority_senders.contains(&sender) {
+            return true;
+        }
+        self.is_allowlisted_payload(txn.payload())
+    }
+
+    fn is_allowlisted_payload(&self, payload: &TransactionPayload) -> bool {
+        match payload {
+            TransactionPayload::EntryFunction(entry_function) => {
+                !self.priority_entry_functions.is_empty()
+                    && self.priority_entry_functions.contains(&(
+                        entry_function.module().clone(),
+                        entry_function.function().to_owned(),
+                    ))
+            },
+            TransactionPayload::Script(script) => {
+                !self.priority_script_hashes.is_empty()
+                    && self
+                        .priority_script_hashes
+                        .contains(&HashValue::sha3_256_of(script.code()))
+            },
+            TransactionPayload::ModuleBundle(_) | TransactionPayload::Multisig(_) => false,
+        }
+    }
+}
+
+/// Parses an entry function formatted as `address::module::function`.
+fn parse_entry_function(entry_function: &str) -> Option<(ModuleId, Identifier)> {
+    let mut parts = entry_function.split("::");
+    let (Some(address), Some(module), Some(function), None) =
+        (parts.next(), parts.next(), parts.next(), parts.next())
+    else {
+        return None;
+    };
+    Some((
+        ModuleId::new(
+            AccountAddress::from_str(address).ok()?,
+            Identifier::new(module).ok()?,
+        ),
+        Identifier::new(function).ok()?,
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_entry_function() {
+        let (module, function) = parse_entry_function("0x1::aptos_governance::resolve").unwrap();
+        assert_eq!(module.address(), &AccountAddress::ONE);
+        assert_eq!(module.name().as_str(), "aptos_governance");
+        assert_eq!(function.as_str(), "resolve");
+
+        assert!(parse_entry_function("0x1::aptos_governance").is_none());
+        assert!(parse_entry_function("0x1::aptos_governance::resolve::extra").is_none());
+        assert!(parse_entry_function("not_an_address::aptos_governance::resolve").is_none());
+        assert!(parse_entry_function("0x1::aptos-governance::resolve").is_none());
+    }
+}
@@ -27,6 +27,8 @@ pub struct MempoolTransaction {
     pub was_parked: bool,
     // The priority of this node for the sender of this transaction.
     pub priority_of_sender: Option<BroadcastPeerPriority>,
+    // Whether the transaction is in the priority lane (see `PriorityLanesConfig`).
+    pub priority_lane: bool,
 }
 
 impl MempoolTransaction {
@@ -52,6 +54,7 @@ impl MempoolTransaction {
             insertion_info: InsertionInfo::new(insertion_time, client_submitted, timeline_state),
             was_parked: false,
             priority_of_sender,
+            priority_lane: false,
         }
     }
 
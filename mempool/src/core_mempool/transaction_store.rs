@@ -6,19 +6,19 @@ use crate::{
     core_mempool::{
         index::{
             AccountTransactions, MultiBucketTimelineIndex, ParkingLotIndex, PriorityIndex,
-            PriorityQueueIter, TTLIndex,
+            PriorityQueueIter, TTLIndex, TxnPointer,
         },
         mempool::Mempool,
-        transaction::{InsertionInfo, MempoolTransaction, TimelineState},
+        transaction::{InsertionInfo, MempoolTransaction, SubmittedBy, TimelineState},
     },
     counters::{self, BROADCAST_BATCHED_LABEL, BROADCAST_READY_LABEL, CONSENSUS_READY_LABEL},
     logging::{LogEntry, LogEvent, LogSchema, TxnsLog},
     network::BroadcastPeerPriority,
     shared_mempool::types::{
-        MempoolSenderBucket, MultiBucketTimelineIndexIds, TimelineIndexIdentifier,
+        EvictionPressure, MempoolSenderBucket, MultiBucketTimelineIndexIds, TimelineIndexIdentifier,
     },
 };
-use aptos_config::config::MempoolConfig;
+use aptos_config::config::{MempoolConfig, MempoolEvictionConfig, MempoolEvictionOrder};
 use aptos_crypto::HashValue;
 use aptos_logger::{prelude::*, Level};
 use aptos_types::{
@@ -47,6 +47,50 @@ pub fn sender_bucket(
     address.as_ref()[address.as_ref().len() - 1] as MempoolSenderBucket % num_sender_buckets
 }
 
+/// A broadcast batch built by the fairness scheduler, see `Mempool::read_timelines_fair`.
+pub(crate) struct FairBroadcastBatch {
+    max_txns: usize,
+    max_txns_per_account: usize,
+    txns: Vec<(SignedTransaction, u64, BroadcastPeerPriority)>,
+    num_bytes: u64,
+    txns_per_account: HashMap<AccountAddress, usize>,
+    txns_per_sender_bucket: HashMap<MempoolSenderBucket, usize>,
+    // Set once a transaction doesn't fit in the max batch bytes
+    is_full: bool,
+}
+
+impl FairBroadcastBatch {
+    pub(crate) fn new(max_txns: usize, max_txns_per_account: usize) -> Self {
+        Self {
+            max_txns,
+            max_txns_per_account,
+            txns: vec![],
+            num_bytes: 0,
+            txns_per_account: HashMap::new(),
+            txns_per_sender_bucket: HashMap::new(),
+            is_full: false,
+        }
+    }
+
+    pub(crate) fn is_full(&self) -> bool {
+        self.is_full || self.txns.len() >= self.max_txns
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.txns.len()
+    }
+
+    /// Returns the transactions, and the number of transactions of each sender bucket
+    pub(crate) fn into_parts(
+        self,
+    ) -> (
+        Vec<(SignedTransaction, u64, BroadcastPeerPriority)>,
+        HashMap<MempoolSenderBucket, usize>,
+    ) {
+        (self.txns, self.txns_per_sender_bucket)
+    }
+}
+
 /// TransactionStore is in-memory storage for all transactions in mempool.
 pub struct TransactionStore {
     // main DS
@@ -85,6 +129,11 @@ pub struct TransactionStore {
     capacity_bytes: usize,
     capacity_per_user: usize,
     max_batch_bytes: u64,
+    eviction_config: MempoolEvictionConfig,
+
+    // priority lane
+    max_priority_txns: usize,
+    num_priority_txns: usize,
 
     // eager expiration
     eager_expire_threshold: Option<Duration>,
@@ -123,6 +172,11 @@ impl TransactionStore {
             capacity_bytes: config.capacity_bytes,
             capacity_per_user: config.capacity_per_user,
             max_batch_bytes: config.shared_mempool_max_batch_bytes,
+            eviction_config: config.eviction.clone(),
+
+            // priority lane
+            max_priority_txns: config.priority_lanes.max_priority_txns,
+            num_priority_txns: 0,
 
             // eager expiration
             eager_expire_threshold: config.eager_expire_threshold_ms.map(Duration::from_millis),
@@ -221,7 +275,7 @@ impl TransactionStore {
     }
 
     /// Insert transaction into TransactionStore. Performs validation checks and updates indexes.
-    pub(crate) fn insert(&mut self, txn: MempoolTransaction) -> MempoolStatus {
+    pub(crate) fn insert(&mut self, mut txn: MempoolTransaction) -> MempoolStatus {
         let address = txn.get_sender();
         let txn_seq_num = txn.sequence_info.transaction_sequence_number;
         let acc_seq_num = txn.sequence_info.account_sequence_number;
@@ -255,6 +309,7 @@ impl TransactionStore {
                         self.index_remove(&txn);
                     };
                     counters::CORE_MEMPOOL_GAS_UPGRADED_TXNS.inc();
+                    counters::core_mempool_evicted_txns(counters::EVICTION_REPLACED_LABEL, 1);
                 } else if current_version.get_gas_price() > txn.get_gas_price() {
                     return MempoolStatus::new(MempoolStatusCode::InvalidUpdate).with_message(
                         "Transaction already in mempool with a higher gas price".to_string(),
@@ -268,7 +323,17 @@ impl TransactionStore {
             }
         }
 
-        if self.check_is_full_after_eviction(&txn, acc_seq_num) {
+        // The priority transactions aren't subject to the capacities, up to the priority quota
+        if txn.priority_lane {
+            if self.num_priority_txns < self.max_priority_txns {
+                counters::core_mempool_priority_lane_txns(counters::PRIORITY_LANE_ACCEPTED_LABEL);
+            } else {
+                txn.priority_lane = false;
+                counters::core_mempool_priority_lane_txns(counters::PRIORITY_LANE_OVER_QUOTA_LABEL);
+            }
+        }
+
+        if !txn.priority_lane && self.check_is_full_after_eviction(&txn, acc_seq_num) {
             return MempoolStatus::new(MempoolStatusCode::MempoolIsFull).with_message(format!(
                 "Mempool is full. Mempool size: {}, Capacity: {}",
                 self.system_ttl_index.size(),
@@ -282,7 +347,7 @@ impl TransactionStore {
 
         if let Some(txns) = self.transactions.get_mut(&address) {
             // capacity check
-            if txns.len() >= self.capacity_per_user {
+            if !txn.priority_lane && txns.len() >= self.capacity_per_user {
                 return MempoolStatus::new(MempoolStatusCode::TooManyTransactions).with_message(
                     format!(
                         "Mempool over capacity for account. Number of transactions from account: {} Capacity per account: {}",
@@ -299,6 +364,9 @@ impl TransactionStore {
                 .insert(txn.get_committed_hash(), (txn.get_sender(), txn_seq_num));
             self.sequence_numbers.insert(txn.get_sender(), acc_seq_num);
             self.size_bytes += txn.get_estimated_bytes();
+            if txn.priority_lane {
+                self.num_priority_txns += 1;
+            }
             txns.insert(txn_seq_num, txn);
             self.track_indices();
         }
@@ -350,18 +418,22 @@ impl TransactionStore {
 
     /// Checks if Mempool is full.
     /// If it's full, tries to free some space by evicting transactions from the ParkingLot.
-    /// We only evict on attempt to insert a transaction that would be ready for broadcast upon insertion.
+    /// Unless configured otherwise, we only evict on attempt to insert a transaction that would be
+    /// ready for broadcast upon insertion.
     fn check_is_full_after_eviction(
         &mut self,
         txn: &MempoolTransaction,
         curr_sequence_number: u64,
     ) -> bool {
-        if self.is_full() && self.check_txn_ready(txn, curr_sequence_number) {
+        if self.is_full()
+            && (self.eviction_config.evict_for_parked_transactions
+                || self.check_txn_ready(txn, curr_sequence_number))
+        {
             let now = Instant::now();
             // try to free some space in Mempool from ParkingLot by evicting non-ready txns
             let mut evicted_txns = 0;
             let mut evicted_bytes = 0;
-            while let Some(txn_pointer) = self.parking_lot_index.get_poppable() {
+            while let Some(txn_pointer) = self.get_txn_to_evict() {
                 if let Some(txn) = self
                     .transactions
                     .get_mut(&txn_pointer.sender)
@@ -385,6 +457,10 @@ impl TransactionStore {
                 }
             }
             if evicted_txns > 0 {
+                counters::core_mempool_evicted_txns(
+                    counters::EVICTION_CAPACITY_LABEL,
+                    evicted_txns,
+                );
                 counters::CORE_MEMPOOL_PARKING_LOT_EVICTED_COUNT.observe(evicted_txns as f64);
                 counters::CORE_MEMPOOL_PARKING_LOT_EVICTED_BYTES.observe(evicted_bytes as f64);
                 counters::CORE_MEMPOOL_PARKING_LOT_EVICTED_LATENCY
@@ -394,10 +470,51 @@ impl TransactionStore {
         self.is_full()
     }
 
+    /// Returns the parked transaction to evict next, in the configured eviction order
+    fn get_txn_to_evict(&self) -> Option<TxnPointer> {
+        let num_samples = self.eviction_config.num_sampled_accounts;
+        match self.eviction_config.order {
+            MempoolEvictionOrder::Random => self.parking_lot_index.get_poppable(),
+            MempoolEvictionOrder::LowestGasUnitPrice => self
+                .parking_lot_index
+                .get_poppable_by_sampling(num_samples, |txn_pointer| {
+                    self.get_mempool_txn(&txn_pointer.sender, txn_pointer.sequence_number)
+                        .map_or(0, |txn| txn.get_gas_price())
+                }),
+            MempoolEvictionOrder::Oldest => {
+                self.parking_lot_index
+                    .get_poppable_by_sampling(num_samples, |txn_pointer| {
+                        self.get_mempool_txn(&txn_pointer.sender, txn_pointer.sequence_number)
+                            .map_or(0, |txn| {
+                                aptos_infallible::duration_since_epoch_at(
+                                    &txn.insertion_info.insertion_time,
+                                )
+                                .as_millis() as u64
+                            })
+                    })
+            },
+        }
+    }
+
     fn is_full(&self) -> bool {
         self.system_ttl_index.size() >= self.capacity || self.size_bytes >= self.capacity_bytes
     }
 
+    /// Returns how close Mempool is to its capacity, and so to evicting transactions
+    pub(crate) fn get_eviction_pressure(&self) -> EvictionPressure {
+        EvictionPressure {
+            num_txns: self.system_ttl_index.size(),
+            capacity: self.capacity,
+            num_bytes: self.size_bytes,
+            capacity_bytes: self.capacity_bytes,
+            num_parked_txns: self.parking_lot_index.size(),
+            num_parked_accounts: self.parking_lot_index.num_accounts(),
+            is_full: self.is_full(),
+            eviction_order: self.eviction_config.order,
+            num_evicted_txns: counters::core_mempool_evicted_txns_by_reason(),
+        }
+    }
+
     /// Check if a transaction would be ready for broadcast in mempool upon insertion (without inserting it).
     /// Two ways this can happen:
     /// 1. txn sequence number == curr_sequence_number
@@ -606,6 +723,7 @@ impl TransactionStore {
                 txns.remove(&sequence_number);
             }
             self.index_remove(&txn_to_remove);
+            counters::core_mempool_evicted_txns(counters::EVICTION_INVALID_LABEL, 1);
 
             if aptos_logger::enabled!(Level::Trace) {
                 let mut txns_log = TxnsLog::new();
@@ -637,6 +755,9 @@ impl TransactionStore {
         self.parking_lot_index.remove(txn);
         self.hash_index.remove(&txn.get_committed_hash());
         self.size_bytes -= txn.get_estimated_bytes();
+        if txn.priority_lane {
+            self.num_priority_txns -= 1;
+        }
 
         // Remove account datastructures if there are no more transactions for the account.
         let address = &txn.get_sender();
@@ -699,19 +820,7 @@ impl TransactionStore {
                         if let TimelineState::Ready(timeline_id) = txn.timeline_state {
                             last_timeline_id[i] = timeline_id;
                         }
-                        let bucket = self.get_bucket(txn.ranking_score, &txn.get_sender());
-                        Mempool::log_txn_latency(
-                            &txn.insertion_info,
-                            bucket.as_str(),
-                            BROADCAST_BATCHED_LABEL,
-                            priority_of_receiver.to_string().as_str(),
-                        );
-                        counters::core_mempool_txn_ranking_score(
-                            BROADCAST_BATCHED_LABEL,
-                            BROADCAST_BATCHED_LABEL,
-                            bucket.as_str(),
-                            txn.ranking_score,
-                        );
+                        self.log_broadcast_batched(txn, &priority_of_receiver);
                     }
                 }
             }
@@ -720,6 +829,91 @@ impl TransactionStore {
         (batch, last_timeline_id.into())
     }
 
+    /// Reads the next transactions of the sender bucket into a fair broadcast batch: at most
+    /// `quotas[i]` transactions from the timeline of the gas price bucket `i` (highest first), and
+    /// at most the batch's limit per account. A timeline is read in order, so the reading stops at
+    /// the first transaction of an account over its limit, which is left for a later batch.
+    pub(crate) fn read_timeline_fair(
+        &self,
+        sender_bucket: MempoolSenderBucket,
+        timeline_id: &MultiBucketTimelineIndexIds,
+        quotas: &[usize],
+        before: Option<Instant>,
+        // The priority of the receipient of the transactions
+        priority_of_receiver: BroadcastPeerPriority,
+        batch: &mut FairBroadcastBatch,
+    ) -> MultiBucketTimelineIndexIds {
+        let timeline_index = self.timeline_index.get(&sender_bucket).unwrap_or_else(|| {
+            panic!(
+                "Unable to get the timeline index for the sender bucket {}",
+                sender_bucket
+            )
+        });
+        let mut last_timeline_id = timeline_id.id_per_bucket.clone();
+
+        for (i, quota) in quotas.iter().enumerate().rev() {
+            if batch.is_full() {
+                break;
+            }
+            let count = std::cmp::min(*quota, batch.max_txns - batch.txns.len());
+            for (address, sequence_number) in
+                timeline_index.read_bucket_timeline(i, last_timeline_id[i], count, before)
+            {
+                if let Some(txn) = self.get_mempool_txn(&address, sequence_number) {
+                    let num_account_txns = batch.txns_per_account.entry(address).or_default();
+                    if *num_account_txns >= batch.max_txns_per_account {
+                        counters::SHARED_MEMPOOL_BROADCAST_ACCOUNT_LIMITED_COUNT.inc();
+                        break; // The account reached its limit
+                    }
+                    let transaction_bytes = txn.txn.raw_txn_bytes_len() as u64;
+                    if batch.num_bytes.saturating_add(transaction_bytes) > self.max_batch_bytes {
+                        batch.is_full = true;
+                        break; // The batch is full
+                    }
+
+                    *num_account_txns += 1;
+                    *batch
+                        .txns_per_sender_bucket
+                        .entry(sender_bucket)
+                        .or_default() += 1;
+                    batch.num_bytes = batch.num_bytes.saturating_add(transaction_bytes);
+                    batch.txns.push((
+                        txn.txn.clone(),
+                        aptos_infallible::duration_since_epoch_at(&txn.insertion_info.ready_time)
+                            .as_millis() as u64,
+                        priority_of_receiver.clone(),
+                    ));
+                    if let TimelineState::Ready(timeline_id) = txn.timeline_state {
+                        last_timeline_id[i] = timeline_id;
+                    }
+                    self.log_broadcast_batched(txn, &priority_of_receiver);
+                }
+            }
+        }
+
+        last_timeline_id.into()
+    }
+
+    fn log_broadcast_batched(
+        &self,
+        txn: &MempoolTransaction,
+        priority_of_receiver: &BroadcastPeerPriority,
+    ) {
+        let bucket = self.get_bucket(txn.ranking_score, &txn.get_sender());
+        Mempool::log_txn_latency(
+            &txn.insertion_info,
+            bucket.as_str(),
+            BROADCAST_BATCHED_LABEL,
+            priority_of_receiver.to_string().as_str(),
+        );
+        counters::core_mempool_txn_ranking_score(
+            BROADCAST_BATCHED_LABEL,
+            BROADCAST_BATCHED_LABEL,
+            bucket.as_str(),
+            txn.ranking_score,
+        );
+    }
+
     pub(crate) fn timeline_range(
         &self,
         sender_bucket: MempoolSenderBucket,
@@ -867,6 +1061,7 @@ impl TransactionStore {
 
                     // remove txn
                     self.index_remove(&txn);
+                    counters::core_mempool_evicted_txns(counters::EVICTION_EXPIRED_LABEL, 1);
                 }
             }
         }
@@ -920,4 +1115,15 @@ impl TransactionStore {
     pub(crate) fn get_parking_lot_addresses(&self) -> Vec<(AccountAddress, u64)> {
         self.parking_lot_index.get_addresses()
     }
+
+    /// Returns the transactions submitted by clients (i.e., not received from peers), in the
+    /// order of their sequence numbers for each account
+    pub(crate) fn get_client_submitted_transactions(&self) -> Vec<SignedTransaction> {
+        self.transactions
+            .values()
+            .flat_map(|txns| txns.values())
+            .filter(|txn| txn.insertion_info.submitted_by == SubmittedBy::Client)
+            .map(|txn| txn.txn.clone())
+            .collect()
+    }
 }
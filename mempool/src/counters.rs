@@ -2,6 +2,7 @@
 // Parts of the project are originally copyright © Meta Platforms, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+use crate::shared_mempool::types::MempoolSenderBucket;
 use aptos_config::network_id::{NetworkId, PeerNetworkId};
 use aptos_metrics_core::{
     exponential_buckets, histogram_opts, op_counters::DurationHistogram, register_histogram,
@@ -11,7 +12,7 @@ use aptos_metrics_core::{
 };
 use aptos_short_hex_str::AsShortHexStr;
 use once_cell::sync::Lazy;
-use std::time::Duration;
+use std::{collections::BTreeMap, time::Duration};
 
 // Core mempool index labels
 pub const PRIORITY_INDEX_LABEL: &str = "priority";
@@ -84,6 +85,18 @@ pub const EXPIRED_BROADCAST_LABEL: &str = "expired";
 pub const RETRY_BROADCAST_LABEL: &str = "retry";
 pub const BACKPRESSURE_BROADCAST_LABEL: &str = "backpressure";
 
+// Eviction reason labels
+pub const EVICTION_EXPIRED_LABEL: &str = "expired";
+pub const EVICTION_REPLACED_LABEL: &str = "replaced";
+pub const EVICTION_CAPACITY_LABEL: &str = "capacity";
+pub const EVICTION_INVALID_LABEL: &str = "invalid";
+const EVICTION_LABELS: [&str; 4] = [
+    EVICTION_EXPIRED_LABEL,
+    EVICTION_REPLACED_LABEL,
+    EVICTION_CAPACITY_LABEL,
+    EVICTION_INVALID_LABEL,
+];
+
 // ACK direction labels
 pub const RECEIVED_LABEL: &str = "received";
 pub const SENT_LABEL: &str = "sent";
@@ -178,6 +191,56 @@ pub static CORE_MEMPOOL_REMOVED_TXNS: Lazy<IntCounter> = Lazy::new(|| {
     .unwrap()
 });
 
+/// Counter tracking number of txns removed from core mempool before being committed, by reason
+static CORE_MEMPOOL_EVICTED_TXNS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_core_mempool_evicted_txns_count",
+        "Number of txns removed from core mempool before being committed, by reason",
+        &["reason"]
+    )
+    .unwrap()
+});
+
+pub fn core_mempool_evicted_txns(reason: &'static str, num_txns: u64) {
+    CORE_MEMPOOL_EVICTED_TXNS
+        .with_label_values(&[reason])
+        .inc_by(num_txns)
+}
+
+/// Returns the number of txns evicted from core mempool so far, by reason
+pub fn core_mempool_evicted_txns_by_reason() -> BTreeMap<String, u64> {
+    EVICTION_LABELS
+        .iter()
+        .map(|reason| {
+            (
+                reason.to_string(),
+                CORE_MEMPOOL_EVICTED_TXNS.with_label_values(&[reason]).get(),
+            )
+        })
+        .collect()
+}
+
+// Priority lane results
+pub const PRIORITY_LANE_ACCEPTED_LABEL: &str = "accepted";
+pub const PRIORITY_LANE_OVER_QUOTA_LABEL: &str = "over_quota";
+
+/// Counter tracking number of priority txns inserted into core mempool, by whether they were
+/// within the priority quota
+static CORE_MEMPOOL_PRIORITY_LANE_TXNS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_core_mempool_priority_lane_txns_count",
+        "Number of priority txns inserted into core mempool, by whether they were within the quota",
+        &["result"]
+    )
+    .unwrap()
+});
+
+pub fn core_mempool_priority_lane_txns(result: &'static str) {
+    CORE_MEMPOOL_PRIORITY_LANE_TXNS
+        .with_label_values(&[result])
+        .inc()
+}
+
 /// Counter tracking number of txns received that are idempotent duplicates
 pub static CORE_MEMPOOL_IDEMPOTENT_TXNS: Lazy<IntCounter> = Lazy::new(|| {
     register_int_counter!(
@@ -556,6 +619,107 @@ pub fn shared_mempool_broadcast_size(network_id: NetworkId, num_txns: usize) {
         .observe(num_txns as f64);
 }
 
+/// Histogram for the share of each sender bucket in the broadcasts built by the fairness scheduler
+static SHARED_MEMPOOL_BROADCAST_SENDER_BUCKET_SHARE: Lazy<HistogramVec> = Lazy::new(|| {
+    register_histogram_vec!(
+        "aptos_shared_mempool_broadcast_sender_bucket_share",
+        "Share of each sender bucket in the broadcasts built by the fairness scheduler",
+        &["sender_bucket"],
+        vec![0.0, 0.1, 0.2, 0.3, 0.4, 0.5, 0.6, 0.7, 0.8, 0.9, 1.0]
+    )
+    .unwrap()
+});
+
+pub fn shared_mempool_broadcast_sender_bucket_share(
+    sender_bucket: MempoolSenderBucket,
+    num_bucket_txns: usize,
+    num_txns: usize,
+) {
+    if num_txns == 0 {
+        return;
+    }
+    SHARED_MEMPOOL_BROADCAST_SENDER_BUCKET_SHARE
+        .with_label_values(&[sender_bucket.to_string().as_str()])
+        .observe(num_bucket_txns as f64 / num_txns as f64);
+}
+
+/// Counter for the times the fairness scheduler stopped reading a timeline at an account over its
+/// limit of transactions per broadcast
+pub static SHARED_MEMPOOL_BROADCAST_ACCOUNT_LIMITED_COUNT: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "aptos_shared_mempool_broadcast_account_limited_count",
+        "Number of times a broadcast timeline read stopped at an account over its limit"
+    )
+    .unwrap()
+});
+
+/// Gauge of the client submitted txns in the Mempool transaction log
+pub static MEMPOOL_PERSISTED_TXNS: Lazy<IntGauge> = Lazy::new(|| {
+    register_int_gauge!(
+        "aptos_mempool_persisted_txns",
+        "Number of client submitted txns in the Mempool transaction log"
+    )
+    .unwrap()
+});
+
+/// Counter of the accepted client submitted txns not persisted, as the log or its queue was full
+pub static MEMPOOL_PERSISTENCE_DROPPED_TXNS: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "aptos_mempool_persistence_dropped_txns_count",
+        "Number of accepted client submitted txns not persisted, as the log or its queue was full"
+    )
+    .unwrap()
+});
+
+/// Counter of the persisted txns re-inserted into Mempool at startup, by status code
+pub static MEMPOOL_PERSISTENCE_RESTORED_TXNS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_mempool_persistence_restored_txns_count",
+        "Number of persisted txns re-submitted to Mempool at startup, by status code",
+        &["status"]
+    )
+    .unwrap()
+});
+
+// Signature prevalidation results
+pub const SIGNATURE_VALID_LABEL: &str = "valid";
+pub const SIGNATURE_INVALID_LABEL: &str = "invalid";
+pub const SIGNATURE_BACKPRESSURE_LABEL: &str = "backpressure";
+
+/// Counter of the client submitted txns going through the signature prevalidation, by result
+static SIGNATURE_PREVALIDATION_TXNS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_shared_mempool_signature_prevalidation_txns_count",
+        "Number of client submitted txns going through the signature prevalidation, by result",
+        &["result"]
+    )
+    .unwrap()
+});
+
+pub fn signature_prevalidation_txns(result: &'static str, num_txns: usize) {
+    SIGNATURE_PREVALIDATION_TXNS
+        .with_label_values(&[result])
+        .inc_by(num_txns as u64)
+}
+
+/// Gauge of the client submitted txns waiting for the signature prevalidation
+pub static SIGNATURE_PREVALIDATION_PENDING_TXNS: Lazy<IntGauge> = Lazy::new(|| {
+    register_int_gauge!(
+        "aptos_shared_mempool_signature_prevalidation_pending_txns",
+        "Number of client submitted txns waiting for the signature prevalidation"
+    )
+    .unwrap()
+});
+
+pub static SIGNATURE_PREVALIDATION_BATCH_SIZE: Lazy<Histogram> = Lazy::new(|| {
+    register_histogram!(
+        "aptos_shared_mempool_signature_prevalidation_batch_size",
+        "Number of txns in the signature prevalidation batches",
+        TXN_COUNT_BUCKETS.clone()
+    )
+    .unwrap()
+});
+
 static SHARED_MEMPOOL_BROADCAST_TYPE_COUNT: Lazy<IntCounterVec> = Lazy::new(|| {
     register_int_counter_vec!(
         "aptos_shared_mempool_rebroadcast_count",
@@ -62,8 +62,8 @@ pub use shared_mempool::{
     bootstrap, network,
     network::MempoolSyncMsg,
     types::{
-        MempoolClientRequest, MempoolClientSender, MempoolEventsReceiver, QuorumStoreRequest,
-        QuorumStoreResponse, SubmissionStatus,
+        EvictionPressure, MempoolClientRequest, MempoolClientSender, MempoolEventsReceiver,
+        QuorumStoreRequest, QuorumStoreResponse, SubmissionStatus,
     },
 };
 #[cfg(any(test, feature = "fuzzing"))]
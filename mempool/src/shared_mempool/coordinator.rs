@@ -10,6 +10,8 @@ use crate::{
     logging::{LogEntry, LogEvent, LogSchema},
     network::{BroadcastPeerPriority, MempoolSyncMsg},
     shared_mempool::{
+        persistence::{PersistenceState, MAX_APPEND_BATCH_SIZE},
+        signature_prevalidation::SignaturePrevalidator,
         tasks::{self, process_committed_transactions},
         types::{
             notify_subscribers, MempoolMessageId, ScheduledBroadcast, SharedMempool,
@@ -64,6 +66,7 @@ pub(crate) async fn coordinator<NetworkClient, TransactionValidator, ConfigProvi
     mut mempool_reconfig_events: ReconfigNotificationListener<ConfigProvider>,
     peer_update_interval_ms: u64,
     peers_and_metadata: Arc<PeersAndMetadata>,
+    persistence: Option<PersistenceState>,
 ) where
     NetworkClient: NetworkClientInterface<MempoolSyncMsg> + 'static,
     TransactionValidator: TransactionValidation + 'static,
@@ -93,6 +96,18 @@ pub(crate) async fn coordinator<NetworkClient, TransactionValidator, ConfigProvi
     let workers_available = smp.config.shared_mempool_max_concurrent_inbound_syncs;
     let bounded_executor = BoundedExecutor::new(workers_available, executor.clone());
 
+    // Spawn the signature prevalidation of the client submitted transactions (if enabled)
+    let signature_prevalidator = if smp.config.signature_prevalidation.enable {
+        let config = smp.config.signature_prevalidation.clone();
+        Some(SignaturePrevalidator::spawn(
+            smp.clone(),
+            &config,
+            &executor,
+        ))
+    } else {
+        None
+    };
+
     let initial_reconfig = mempool_reconfig_events
         .next()
         .await
@@ -104,11 +119,22 @@ pub(crate) async fn coordinator<NetworkClient, TransactionValidator, ConfigProvi
     )
     .await;
 
+    // Spawn the persistence job once the validator is configured, so that the persisted
+    // transactions can be re-validated
+    if let Some(persistence) = persistence {
+        let compaction_interval_ms = smp.config.persistence.compaction_interval_ms;
+        executor.spawn(persistence_job(
+            smp.clone(),
+            persistence,
+            compaction_interval_ms,
+        ));
+    }
+
     loop {
         let _timer = counters::MAIN_LOOP.start_timer();
         ::futures::select! {
             msg = client_events.select_next_some() => {
-                handle_client_request(&mut smp, &bounded_executor, msg).await;
+                handle_client_request(&mut smp, &bounded_executor, signature_prevalidator.as_ref(), msg).await;
             },
             msg = quorum_store_requests.select_next_some() => {
                 tasks::process_quorum_store_request(&smp, msg);
@@ -167,6 +193,7 @@ fn spawn_commit_notification_handler<NetworkClient, TransactionValidator>(
 async fn handle_client_request<NetworkClient, TransactionValidator>(
     smp: &mut SharedMempool<NetworkClient, TransactionValidator>,
     bounded_executor: &BoundedExecutor,
+    signature_prevalidator: Option<&SignaturePrevalidator>,
     request: MempoolClientRequest,
 ) where
     NetworkClient: NetworkClientInterface<MempoolSyncMsg> + 'static,
@@ -187,14 +214,18 @@ async fn handle_client_request<NetworkClient, TransactionValidator>(
             );
             smp.network_interface
                 .num_mempool_txns_received_since_peers_updated += 1;
-            bounded_executor
-                .spawn(tasks::process_client_transaction_submission(
-                    smp.clone(),
-                    txn,
-                    callback,
-                    task_start_timer,
-                ))
-                .await;
+            if let Some(signature_prevalidator) = signature_prevalidator {
+                signature_prevalidator.submit(txn, callback, task_start_timer);
+            } else {
+                bounded_executor
+                    .spawn(tasks::process_client_transaction_submission(
+                        smp.clone(),
+                        txn,
+                        callback,
+                        task_start_timer,
+                    ))
+                    .await;
+            }
         },
         MempoolClientRequest::GetTransactionByHash(hash, callback) => {
             // This timer measures how long it took for the bounded executor to *schedule* the
@@ -222,6 +253,11 @@ async fn handle_client_request<NetworkClient, TransactionValidator>(
                 .spawn(tasks::process_parking_lot_addresses(smp.clone(), callback))
                 .await;
         },
+        MempoolClientRequest::GetEvictionPressure(callback) => {
+            bounded_executor
+                .spawn(tasks::process_eviction_pressure(smp.clone(), callback))
+                .await;
+        },
     }
 }
 
@@ -460,6 +496,79 @@ pub(crate) async fn gc_coordinator(mempool: Arc<Mutex<CoreMempool>>, gc_interval
     ));
 }
 
+/// Re-submits the transactions persisted before the restart, then appends the queued accepted
+/// transactions to the transaction log, and periodically compacts it to the client submitted
+/// transactions still in Mempool. The log is only written on blocking threads.
+pub(crate) async fn persistence_job<NetworkClient, TransactionValidator>(
+    smp: SharedMempool<NetworkClient, TransactionValidator>,
+    persistence: PersistenceState,
+    compaction_interval_ms: u64,
+) where
+    NetworkClient: NetworkClientInterface<MempoolSyncMsg> + 'static,
+    TransactionValidator: TransactionValidation + 'static,
+{
+    let PersistenceState {
+        mut transaction_log,
+        mut queue,
+        persisted_transactions,
+    } = persistence;
+
+    if !persisted_transactions.is_empty() {
+        let resubmit_smp = smp.clone();
+        let result = tokio::task::spawn_blocking(move || {
+            tasks::process_persisted_transactions(&resubmit_smp, persisted_transactions)
+        })
+        .await;
+        if let Err(error) = result {
+            error!("Failed to re-submit the persisted transactions: {}", error);
+        }
+    }
+
+    let mut compaction_interval = interval(Duration::from_millis(compaction_interval_ms));
+    let mut appended_transactions = Vec::with_capacity(MAX_APPEND_BATCH_SIZE);
+    loop {
+        let compacted_transactions = tokio::select! {
+            _ = compaction_interval.tick() => {
+                // The queued transactions were accepted before reading Mempool, so the compaction
+                // covers them (if they're still in Mempool). The transactions queued meanwhile are
+                // appended after the compaction.
+                while queue.try_recv().is_ok() {}
+                Some(smp.mempool.lock().get_client_submitted_transactions())
+            },
+            num_txns = queue.recv_many(&mut appended_transactions, MAX_APPEND_BATCH_SIZE) => {
+                if num_txns == 0 {
+                    return;
+                }
+                None
+            },
+        };
+        let appended = std::mem::take(&mut appended_transactions);
+        let result = tokio::task::spawn_blocking(move || {
+            let result = match &compacted_transactions {
+                Some(transactions) => transaction_log.compact(transactions),
+                None => transaction_log.append_batch(&appended),
+            };
+            (transaction_log, result)
+        })
+        .await;
+        match result {
+            Ok((log, result)) => {
+                transaction_log = log;
+                if let Err(error) = result {
+                    error!("Failed to write the Mempool transaction log: {}", error);
+                }
+            },
+            Err(error) => {
+                error!(
+                    "The Mempool transaction log failed, the transactions won't be persisted: {}",
+                    error
+                );
+                return;
+            },
+        }
+    }
+}
+
 /// Periodically logs a snapshot of transactions in core mempool.
 /// In the future we may want an interactive way to directly query mempool's internal state.
 /// For now, we will rely on this periodic snapshot to observe the internal state.
@@ -3,6 +3,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 pub mod network;
+pub(crate) mod persistence;
 mod priority;
 mod runtime;
 pub(crate) mod types;
@@ -10,5 +11,6 @@ pub use runtime::bootstrap;
 #[cfg(any(test, feature = "fuzzing"))]
 pub(crate) use runtime::start_shared_mempool;
 mod coordinator;
+pub(crate) mod signature_prevalidation;
 pub(crate) mod tasks;
 pub(crate) mod use_case_history;
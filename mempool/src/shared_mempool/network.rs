@@ -509,10 +509,8 @@ impl<NetworkClient: NetworkClientInterface<MempoolSyncMsg>> MempoolNetworkInterf
                     });
 
                     let max_txns = self.mempool_config.shared_mempool_batch_size;
-                    let mut output_txns = vec![];
-                    let mut output_updates = vec![];
-                    for (sender_bucket, peer_priority) in sender_buckets {
-                        let before = match peer_priority {
+                    let before_for_priority =
+                        |peer_priority: &BroadcastPeerPriority| match peer_priority {
                             BroadcastPeerPriority::Primary => None,
                             BroadcastPeerPriority::Failover => Some(
                                 Instant::now()
@@ -521,26 +519,53 @@ impl<NetworkClient: NetworkClientInterface<MempoolSyncMsg>> MempoolNetworkInterf
                                     ),
                             ),
                         };
-                        if max_txns > 0 {
-                            let old_timeline_id = state.timelines.get(&sender_bucket).unwrap();
-                            let (txns, new_timeline_id) = mempool.read_timeline(
-                                sender_bucket,
-                                old_timeline_id,
+                    let (output_txns, output_updates) =
+                        if self.mempool_config.broadcast_fairness.enable {
+                            // Take the transactions of the sender buckets in a weighted round-robin
+                            let sender_buckets = sender_buckets
+                                .into_iter()
+                                .map(|(sender_bucket, peer_priority)| {
+                                    let old_timeline_id =
+                                        state.timelines.get(&sender_bucket).unwrap().clone();
+                                    let before = before_for_priority(&peer_priority);
+                                    (sender_bucket, old_timeline_id, before, peer_priority)
+                                })
+                                .collect();
+                            mempool.read_timelines_fair(
+                                sender_buckets,
                                 max_txns,
-                                before,
-                                peer_priority.clone(),
-                            );
-                            output_txns.extend(
-                                txns.into_iter()
-                                    .map(|(txn, ready_time)| {
-                                        (txn, ready_time, peer_priority.clone())
-                                    })
-                                    .collect::<Vec<_>>(),
-                            );
-                            output_updates
-                                .push((sender_bucket, (old_timeline_id.clone(), new_timeline_id)));
-                        }
-                    }
+                                &self.mempool_config.broadcast_fairness,
+                            )
+                        } else {
+                            let mut output_txns = vec![];
+                            let mut output_updates = vec![];
+                            for (sender_bucket, peer_priority) in sender_buckets {
+                                let before = before_for_priority(&peer_priority);
+                                if max_txns > 0 {
+                                    let old_timeline_id =
+                                        state.timelines.get(&sender_bucket).unwrap();
+                                    let (txns, new_timeline_id) = mempool.read_timeline(
+                                        sender_bucket,
+                                        old_timeline_id,
+                                        max_txns,
+                                        before,
+                                        peer_priority.clone(),
+                                    );
+                                    output_txns.extend(
+                                        txns.into_iter()
+                                            .map(|(txn, ready_time)| {
+                                                (txn, ready_time, peer_priority.clone())
+                                            })
+                                            .collect::<Vec<_>>(),
+                                    );
+                                    output_updates.push((
+                                        sender_bucket,
+                                        (old_timeline_id.clone(), new_timeline_id),
+                                    ));
+                                }
+                            }
+                            (output_txns, output_updates)
+                        };
 
                     (
                         MempoolMessageId::from_timeline_ids(output_updates),
@@ -0,0 +1,165 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! The persistence of the client submitted transactions across restarts. The transactions
+//! accepted into Mempool are queued, and appended in batches to a log (as length prefixed BCS
+//! records) by the persistence job, so that the submissions don't wait on the disk. The log is
+//! periodically compacted to the client submitted transactions still in Mempool. At startup, the
+//! transactions of the log are re-validated and re-inserted.
+//!
+//! The appends are flushed to the OS but not synced, so the log survives a restart of the node,
+//! but not necessarily a crash of the host.
+
+use crate::counters;
+use anyhow::{anyhow, Context, Result};
+use aptos_logger::prelude::*;
+use aptos_types::transaction::SignedTransaction;
+use std::{
+    fs::{self, File, OpenOptions},
+    io::{BufReader, BufWriter, ErrorKind, Read, Write},
+    path::{Path, PathBuf},
+};
+use tokio::sync::mpsc;
+
+/// The maximum size of a record, above which the record is considered corrupted
+const MAX_RECORD_BYTES: usize = 16 * 1024 * 1024;
+
+/// The maximum number of queued transactions appended (and flushed) together
+pub(crate) const MAX_APPEND_BATCH_SIZE: usize = 1_000;
+
+/// The queue of the accepted transactions to append to the transaction log
+pub(crate) type TransactionLogSender = mpsc::Sender<SignedTransaction>;
+pub(crate) type TransactionLogReceiver = mpsc::Receiver<SignedTransaction>;
+
+/// Creates the queue of the transactions to append to the transaction log. Once the queue is
+/// full, the accepted transactions aren't persisted until the persistence job catches up.
+pub(crate) fn transaction_log_queue(
+    capacity: usize,
+) -> (TransactionLogSender, TransactionLogReceiver) {
+    mpsc::channel(capacity.max(1))
+}
+
+/// The transaction log and its queue, along with the transactions persisted before the restart,
+/// which are handed to the persistence job
+pub(crate) struct PersistenceState {
+    pub transaction_log: TransactionLog,
+    pub queue: TransactionLogReceiver,
+    pub persisted_transactions: Vec<SignedTransaction>,
+}
+
+/// An append-only log of the client submitted transactions accepted into Mempool
+pub(crate) struct TransactionLog {
+    path: PathBuf,
+    writer: BufWriter<File>,
+    num_txns: usize,
+    max_txns: usize,
+}
+
+impl TransactionLog {
+    /// Opens the log at the given path (creating it if needed), and returns it along with the
+    /// transactions it holds.
+    pub fn open(path: &Path, max_txns: usize) -> Result<(Self, Vec<SignedTransaction>)> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create the directory {:?}", parent))?;
+        }
+        let txns = read_transactions(path)?;
+
+        // Rewrite the log, which drops a partially written record at its end (if any)
+        let log = Self::create(path, &txns, max_txns)?;
+        Ok((log, txns))
+    }
+
+    /// Writes a new log at the given path with the given transactions, replacing the current one
+    /// atomically.
+    fn create(path: &Path, txns: &[SignedTransaction], max_txns: usize) -> Result<Self> {
+        let txns = &txns[..txns.len().min(max_txns)];
+        let tmp_path = path.with_extension("tmp");
+        {
+            let mut writer = BufWriter::new(File::create(&tmp_path)?);
+            for txn in txns {
+                write_record(&mut writer, txn)?;
+            }
+            writer.into_inner()?.sync_all()?;
+        }
+        fs::rename(&tmp_path, path)?;
+
+        let file = OpenOptions::new().append(true).open(path)?;
+        counters::MEMPOOL_PERSISTED_TXNS.set(txns.len() as i64);
+        Ok(Self {
+            path: path.to_path_buf(),
+            writer: BufWriter::new(file),
+            num_txns: txns.len(),
+            max_txns,
+        })
+    }
+
+    /// Appends the transactions to the log (up to the maximum), and flushes them once
+    pub fn append_batch(&mut self, txns: &[SignedTransaction]) -> Result<()> {
+        let num_appended_txns = txns.len().min(self.max_txns.saturating_sub(self.num_txns));
+        counters::MEMPOOL_PERSISTENCE_DROPPED_TXNS.inc_by((txns.len() - num_appended_txns) as u64);
+        if num_appended_txns == 0 {
+            return Ok(());
+        }
+        for txn in &txns[..num_appended_txns] {
+            write_record(&mut self.writer, txn)?;
+        }
+        self.writer.flush()?;
+        self.num_txns += num_appended_txns;
+        counters::MEMPOOL_PERSISTED_TXNS.set(self.num_txns as i64);
+        Ok(())
+    }
+
+    /// Replaces the content of the log with the given transactions
+    pub fn compact(&mut self, txns: &[SignedTransaction]) -> Result<()> {
+        *self = Self::create(&self.path, txns, self.max_txns)?;
+        Ok(())
+    }
+}
+
+fn write_record(writer: &mut impl Write, txn: &SignedTransaction) -> Result<()> {
+    let bytes = bcs::to_bytes(txn)?;
+    writer.write_all(&(bytes.len() as u32).to_le_bytes())?;
+    writer.write_all(&bytes)?;
+    Ok(())
+}
+
+/// Reads the transactions of the log, stopping at the first incomplete or invalid record
+fn read_transactions(path: &Path) -> Result<Vec<SignedTransaction>> {
+    let file = match File::open(path) {
+        Ok(file) => file,
+        Err(error) if error.kind() == ErrorKind::NotFound => return Ok(vec![]),
+        Err(error) => return Err(error.into()),
+    };
+    let mut reader = BufReader::new(file);
+    let mut txns = vec![];
+    loop {
+        let mut len_bytes = [0u8; 4];
+        match reader.read_exact(&mut len_bytes) {
+            Ok(()) => {},
+            Err(error) if error.kind() == ErrorKind::UnexpectedEof => break,
+            Err(error) => return Err(error.into()),
+        }
+        let len = u32::from_le_bytes(len_bytes) as usize;
+        let txn = if len > MAX_RECORD_BYTES {
+            Err(anyhow!("Record of {} bytes is too large", len))
+        } else {
+            let mut bytes = vec![0u8; len];
+            reader
+                .read_exact(&mut bytes)
+                .map_err(anyhow::Error::from)
+                .and_then(|()| bcs::from_bytes::<SignedTransaction>(&bytes).map_err(Into::into))
+        };
+        match txn {
+            Ok(txn) => txns.push(txn),
+            Err(error) => {
+                warn!(
+                    "Dropping the end of the Mempool transaction log {:?}: {}",
+                    path, error
+                );
+                break;
+            },
+        }
+    }
+    Ok(txns)
+}
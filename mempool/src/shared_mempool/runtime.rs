@@ -7,6 +7,7 @@ use crate::{
     network::MempoolSyncMsg,
     shared_mempool::{
         coordinator::{coordinator, gc_coordinator, snapshot_job},
+        persistence::{transaction_log_queue, PersistenceState, TransactionLog},
         types::{MempoolEventsReceiver, SharedMempool, SharedMempoolNotification},
     },
     QuorumStoreRequest,
@@ -14,7 +15,7 @@ use crate::{
 use aptos_config::config::{NodeConfig, NodeType};
 use aptos_event_notifications::{DbBackedOnChainConfig, ReconfigNotificationListener};
 use aptos_infallible::{Mutex, RwLock};
-use aptos_logger::Level;
+use aptos_logger::{prelude::*, Level};
 use aptos_mempool_notifications::MempoolNotificationListener;
 use aptos_network::application::{
     interface::{NetworkClient, NetworkServiceEvents},
@@ -51,7 +52,7 @@ pub(crate) fn start_shared_mempool<TransactionValidator, ConfigProvider>(
     ConfigProvider: OnChainConfigProvider,
 {
     let node_type = NodeType::extract_from_config(config);
-    let smp: SharedMempool<NetworkClient<MempoolSyncMsg>, TransactionValidator> =
+    let mut smp: SharedMempool<NetworkClient<MempoolSyncMsg>, TransactionValidator> =
         SharedMempool::new(
             mempool.clone(),
             config.mempool.clone(),
@@ -62,6 +63,34 @@ pub(crate) fn start_shared_mempool<TransactionValidator, ConfigProvider>(
             node_type,
         );
 
+    // Open the transaction log, to re-submit the transactions persisted before the restart
+    let mut persistence = None;
+    let persistence_config = &config.mempool.persistence;
+    if persistence_config.enable {
+        let path = persistence_config.full_path(&config.base.data_dir);
+        match TransactionLog::open(&path, persistence_config.max_persisted_txns) {
+            Ok((transaction_log, transactions)) => {
+                info!(
+                    "Opened the Mempool transaction log {:?} with {} transactions",
+                    path,
+                    transactions.len()
+                );
+                let (sender, queue) =
+                    transaction_log_queue(persistence_config.max_persisted_txns);
+                smp.transaction_log_sender = Some(sender);
+                persistence = Some(PersistenceState {
+                    transaction_log,
+                    queue,
+                    persisted_transactions: transactions,
+                });
+            },
+            Err(error) => error!(
+                "Failed to open the Mempool transaction log {:?}, the transactions won't be persisted: {}",
+                path, error
+            ),
+        }
+    }
+
     executor.spawn(coordinator(
         smp,
         executor.clone(),
@@ -72,6 +101,7 @@ pub(crate) fn start_shared_mempool<TransactionValidator, ConfigProvider>(
         mempool_reconfig_events,
         config.mempool.shared_mempool_peer_update_interval_ms,
         peers_and_metadata,
+        persistence,
     ));
 
     executor.spawn(gc_coordinator(
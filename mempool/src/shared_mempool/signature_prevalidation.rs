@@ -0,0 +1,226 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! The signature prevalidation of the client submitted transactions. The submissions are queued
+//! in a bounded channel, from which they're taken in batches. The signatures of each batch are
+//! verified at once (the single Ed25519 signatures with a batch verification), and the
+//! transactions with a valid signature are validated and inserted together, which amortizes the
+//! storage reads and the Mempool lock over the batch.
+//!
+//! The batch verification is a fast filter: the VM validation still verifies each signature
+//! strictly. When the queue is full, the submissions are rejected as if Mempool was full, so that
+//! the clients (e.g., the REST API) back off.
+
+use crate::{
+    counters,
+    network::MempoolSyncMsg,
+    shared_mempool::{tasks, types::SharedMempool},
+    thread_pool::VALIDATION_POOL,
+    SubmissionStatus,
+};
+use anyhow::{format_err, Result};
+use aptos_bounded_executor::BoundedExecutor;
+use aptos_config::config::SignaturePrevalidationConfig;
+use aptos_crypto::{ed25519::Ed25519Signature, signing_message};
+use aptos_logger::prelude::*;
+use aptos_metrics_core::HistogramTimer;
+use aptos_network::application::interface::NetworkClientInterface;
+use aptos_types::{
+    mempool_status::{MempoolStatus, MempoolStatusCode},
+    transaction::{authenticator::TransactionAuthenticator, SignedTransaction},
+    vm_status::DiscardedVMStatus,
+};
+use aptos_vm_validator::vm_validator::TransactionValidation;
+use futures::channel::oneshot;
+use rayon::prelude::*;
+use tokio::{
+    runtime::Handle,
+    sync::mpsc::{self, error::TrySendError},
+};
+
+/// A transaction submitted by a client, waiting for the signature prevalidation
+struct PendingSubmission {
+    transaction: SignedTransaction,
+    callback: oneshot::Sender<Result<SubmissionStatus>>,
+    timer: HistogramTimer,
+}
+
+/// The handle to submit the client transactions to the signature prevalidation
+pub(crate) struct SignaturePrevalidator {
+    sender: mpsc::Sender<PendingSubmission>,
+}
+
+impl SignaturePrevalidator {
+    /// Spawns the task taking the batches of submissions off the queue, and verifying them on at
+    /// most `num_workers` blocking tasks.
+    pub fn spawn<NetworkClient, TransactionValidator>(
+        smp: SharedMempool<NetworkClient, TransactionValidator>,
+        config: &SignaturePrevalidationConfig,
+        executor: &Handle,
+    ) -> Self
+    where
+        NetworkClient: NetworkClientInterface<MempoolSyncMsg> + 'static,
+        TransactionValidator: TransactionValidation + 'static,
+    {
+        let (sender, receiver) = mpsc::channel(config.max_pending_txns);
+        let bounded_executor = BoundedExecutor::new(config.num_workers, executor.clone());
+        executor.spawn(process_batches(
+            smp,
+            receiver,
+            bounded_executor,
+            config.max_batch_size,
+        ));
+        Self { sender }
+    }
+
+    /// Queues the transaction for the signature prevalidation, or rejects it right away if the
+    /// queue is full.
+    pub fn submit(
+        &self,
+        transaction: SignedTransaction,
+        callback: oneshot::Sender<Result<SubmissionStatus>>,
+        timer: HistogramTimer,
+    ) {
+        let submission = PendingSubmission {
+            transaction,
+            callback,
+            timer,
+        };
+        match self.sender.try_send(submission) {
+            Ok(()) => counters::SIGNATURE_PREVALIDATION_PENDING_TXNS.inc(),
+            Err(TrySendError::Full(submission)) => {
+                counters::signature_prevalidation_txns(counters::SIGNATURE_BACKPRESSURE_LABEL, 1);
+                let status = MempoolStatus::new(MempoolStatusCode::MempoolIsFull).with_message(
+                    "Too many transactions are waiting for the signature verification".to_string(),
+                );
+                tasks::send_client_submission_status(submission.callback, Ok((status, None)));
+            },
+            Err(TrySendError::Closed(submission)) => {
+                tasks::send_client_submission_status(
+                    submission.callback,
+                    Err(format_err!("The signature prevalidation stopped")),
+                );
+            },
+        }
+    }
+}
+
+/// Takes the batches of submissions off the queue, and processes each on a blocking task
+async fn process_batches<NetworkClient, TransactionValidator>(
+    smp: SharedMempool<NetworkClient, TransactionValidator>,
+    mut receiver: mpsc::Receiver<PendingSubmission>,
+    bounded_executor: BoundedExecutor,
+    max_batch_size: usize,
+) where
+    NetworkClient: NetworkClientInterface<MempoolSyncMsg> + 'static,
+    TransactionValidator: TransactionValidation + 'static,
+{
+    while let Some(submission) = receiver.recv().await {
+        let mut batch = vec![submission];
+        while batch.len() < max_batch_size {
+            match receiver.try_recv() {
+                Ok(submission) => batch.push(submission),
+                Err(_) => break,
+            }
+        }
+        counters::SIGNATURE_PREVALIDATION_PENDING_TXNS.sub(batch.len() as i64);
+        counters::SIGNATURE_PREVALIDATION_BATCH_SIZE.observe(batch.len() as f64);
+
+        // Waiting for a worker holds the next submissions in the queue, which applies the
+        // backpressure once the queue is full
+        let smp = smp.clone();
+        bounded_executor
+            .spawn_blocking(move || process_batch(&smp, batch))
+            .await;
+    }
+    warn!("The signature prevalidation stopped, as the Mempool client channel closed");
+}
+
+/// Verifies the signatures of the batch, rejects the transactions with an invalid signature, and
+/// validates and inserts the other ones.
+fn process_batch<NetworkClient, TransactionValidator>(
+    smp: &SharedMempool<NetworkClient, TransactionValidator>,
+    batch: Vec<PendingSubmission>,
+) where
+    NetworkClient: NetworkClientInterface<MempoolSyncMsg>,
+    TransactionValidator: TransactionValidation + 'static,
+{
+    let num_txns = batch.len();
+    let transactions: Vec<_> = batch
+        .iter()
+        .map(|submission| &submission.transaction)
+        .collect();
+    let valid_signatures = VALIDATION_POOL.install(|| verify_signatures(&transactions));
+
+    let mut valid_submissions = vec![];
+    for (submission, valid_signature) in batch.into_iter().zip(valid_signatures) {
+        submission.timer.stop_and_record();
+        if valid_signature {
+            valid_submissions.push((submission.transaction, submission.callback));
+        } else {
+            let status = (
+                MempoolStatus::new(MempoolStatusCode::VmError),
+                Some(DiscardedVMStatus::INVALID_SIGNATURE),
+            );
+            tasks::send_client_submission_status(submission.callback, Ok(status));
+        }
+    }
+
+    let num_valid_txns = valid_submissions.len();
+    counters::signature_prevalidation_txns(counters::SIGNATURE_VALID_LABEL, num_valid_txns);
+    counters::signature_prevalidation_txns(
+        counters::SIGNATURE_INVALID_LABEL,
+        num_txns - num_valid_txns,
+    );
+    if !valid_submissions.is_empty() {
+        tasks::process_client_transaction_batch(smp, valid_submissions);
+    }
+}
+
+/// Returns whether the signature of each transaction is valid. The single Ed25519 signatures are
+/// batch verified, and only verified one by one if the batch verification fails. The other
+/// signatures are verified one by one.
+pub(crate) fn verify_signatures(transactions: &[&SignedTransaction]) -> Vec<bool> {
+    let mut valid_signatures = vec![false; transactions.len()];
+
+    // Collect the single Ed25519 signatures for the batch verification
+    let ed25519_signatures: Vec<_> = transactions
+        .iter()
+        .enumerate()
+        .filter_map(
+            |(index, transaction)| match transaction.authenticator_ref() {
+                TransactionAuthenticator::Ed25519 {
+                    public_key,
+                    signature,
+                } => signing_message(transaction.raw_transaction_ref())
+                    .ok()
+                    .map(|message| (index, message, public_key, signature)),
+                _ => None,
+            },
+        )
+        .collect();
+    let ed25519_batch: Vec<_> = ed25519_signatures
+        .iter()
+        .map(|(_, message, public_key, signature)| (message.as_slice(), *public_key, *signature))
+        .collect();
+    if !ed25519_batch.is_empty()
+        && Ed25519Signature::batch_verify_distinct_messages(&ed25519_batch).is_ok()
+    {
+        for (index, ..) in &ed25519_signatures {
+            valid_signatures[*index] = true;
+        }
+    }
+
+    // Verify the remaining signatures one by one
+    let remaining: Vec<usize> = (0..transactions.len())
+        .filter(|index| !valid_signatures[*index])
+        .collect();
+    let remaining_valid: Vec<bool> = remaining
+        .par_iter()
+        .map(|index| transactions[*index].verify_signature().is_ok())
+        .collect();
+    for (index, valid) in remaining.into_iter().zip(remaining_valid) {
+        valid_signatures[index] = valid;
+    }
+    valid_signatures
+}
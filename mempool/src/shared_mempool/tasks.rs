@@ -11,8 +11,8 @@ use crate::{
     network::{BroadcastError, BroadcastPeerPriority, MempoolSyncMsg},
     shared_mempool::{
         types::{
-            notify_subscribers, ScheduledBroadcast, SharedMempool, SharedMempoolNotification,
-            SubmissionStatusBundle,
+            notify_subscribers, EvictionPressure, ScheduledBroadcast, SharedMempool,
+            SharedMempoolNotification, SubmissionStatusBundle,
         },
         use_case_history::UseCaseHistory,
     },
@@ -38,9 +38,11 @@ use aptos_types::{
 };
 use aptos_vm_validator::vm_validator::{get_account_sequence_number, TransactionValidation};
 use futures::{channel::oneshot, stream::FuturesUnordered};
+use itertools::Itertools;
 use rayon::prelude::*;
 use std::{
     cmp,
+    collections::HashMap,
     sync::Arc,
     time::{Duration, Instant},
 };
@@ -124,33 +126,151 @@ pub(crate) async fn process_client_transaction_submission<NetworkClient, Transac
 {
     timer.stop_and_record();
     let _timer = counters::process_txn_submit_latency_timer_client();
-    let ineligible_for_broadcast =
-        smp.network_interface.is_validator() && !smp.broadcast_within_validator_network();
-    let timeline_state = if ineligible_for_broadcast {
-        TimelineState::NonQualified
-    } else {
-        TimelineState::NotReady
-    };
     let statuses: Vec<(SignedTransaction, (MempoolStatus, Option<StatusCode>))> =
         process_incoming_transactions(
             &smp,
             vec![(transaction, None, Some(BroadcastPeerPriority::Primary))],
-            timeline_state,
+            client_timeline_state(&smp),
             true,
         );
     log_txn_process_results(&statuses, None);
+    persist_accepted_transactions(&smp, &statuses);
 
     if let Some(status) = statuses.first() {
-        if callback.send(Ok(status.1.clone())).is_err() {
-            warn!(LogSchema::event_log(
-                LogEntry::JsonRpc,
-                LogEvent::CallbackFail
-            ));
-            counters::CLIENT_CALLBACK_FAIL.inc();
+        send_client_submission_status(callback, Ok(status.1.clone()));
+    }
+}
+
+/// Processes a batch of transactions directly submitted by clients, of which the signatures were
+/// verified. The transactions are validated and inserted together, and each callback gets the
+/// status of its transaction.
+pub(crate) fn process_client_transaction_batch<NetworkClient, TransactionValidator>(
+    smp: &SharedMempool<NetworkClient, TransactionValidator>,
+    submissions: Vec<(SignedTransaction, oneshot::Sender<Result<SubmissionStatus>>)>,
+) where
+    NetworkClient: NetworkClientInterface<MempoolSyncMsg>,
+    TransactionValidator: TransactionValidation + 'static,
+{
+    let _timer = counters::process_txn_submit_latency_timer_client();
+
+    // The statuses aren't in the order of the transactions, so the callbacks are matched by hash
+    // (the same transaction may be submitted more than once)
+    let mut callbacks: HashMap<HashValue, Vec<oneshot::Sender<Result<SubmissionStatus>>>> =
+        HashMap::new();
+    let mut transactions = Vec::with_capacity(submissions.len());
+    for (transaction, callback) in submissions {
+        callbacks
+            .entry(transaction.committed_hash())
+            .or_default()
+            .push(callback);
+        transactions.push((transaction, None, Some(BroadcastPeerPriority::Primary)));
+    }
+
+    let statuses =
+        process_incoming_transactions(smp, transactions, client_timeline_state(smp), true);
+    log_txn_process_results(&statuses, None);
+    persist_accepted_transactions(smp, &statuses);
+
+    for (transaction, status) in statuses {
+        if let Some(callback) = callbacks
+            .get_mut(&transaction.committed_hash())
+            .and_then(|callbacks| callbacks.pop())
+        {
+            send_client_submission_status(callback, Ok(status));
+        }
+    }
+}
+
+/// Re-submits the transactions persisted before a restart, as if they were submitted by clients.
+/// They're already in the transaction log, so they aren't appended again.
+pub(crate) fn process_persisted_transactions<NetworkClient, TransactionValidator>(
+    smp: &SharedMempool<NetworkClient, TransactionValidator>,
+    transactions: Vec<SignedTransaction>,
+) where
+    NetworkClient: NetworkClientInterface<MempoolSyncMsg>,
+    TransactionValidator: TransactionValidation + 'static,
+{
+    let num_txns = transactions.len();
+    let mut num_accepted_txns = 0;
+    for chunk in &transactions
+        .into_iter()
+        .chunks(smp.config.shared_mempool_batch_size.max(1))
+    {
+        let statuses = process_incoming_transactions(
+            smp,
+            chunk
+                .map(|transaction| (transaction, None, Some(BroadcastPeerPriority::Primary)))
+                .collect(),
+            client_timeline_state(smp),
+            true,
+        );
+        for (_, (mempool_status, _)) in &statuses {
+            if mempool_status.code == MempoolStatusCode::Accepted {
+                num_accepted_txns += 1;
+            }
+            counters::MEMPOOL_PERSISTENCE_RESTORED_TXNS
+                .with_label_values(&[&mempool_status.code.to_string()])
+                .inc();
+        }
+    }
+    info!(
+        num_txns = num_txns,
+        num_accepted_txns = num_accepted_txns,
+        "Re-submitted the persisted transactions to Mempool"
+    );
+}
+
+/// Queues the accepted transactions to be appended to the transaction log (if the persistence is
+/// enabled). The submissions never wait on the log: if the queue is full, the transactions aren't
+/// persisted.
+fn persist_accepted_transactions<NetworkClient, TransactionValidator>(
+    smp: &SharedMempool<NetworkClient, TransactionValidator>,
+    statuses: &[SubmissionStatusBundle],
+) {
+    if let Some(transaction_log_sender) = &smp.transaction_log_sender {
+        for (transaction, (mempool_status, _)) in statuses {
+            if mempool_status.code == MempoolStatusCode::Accepted
+                && transaction_log_sender
+                    .try_send(transaction.clone())
+                    .is_err()
+            {
+                counters::MEMPOOL_PERSISTENCE_DROPPED_TXNS.inc();
+            }
         }
     }
 }
 
+/// Returns the timeline state of the transactions submitted by clients
+fn client_timeline_state<NetworkClient, TransactionValidator>(
+    smp: &SharedMempool<NetworkClient, TransactionValidator>,
+) -> TimelineState
+where
+    NetworkClient: NetworkClientInterface<MempoolSyncMsg>,
+    TransactionValidator: TransactionValidation + 'static,
+{
+    let ineligible_for_broadcast =
+        smp.network_interface.is_validator() && !smp.broadcast_within_validator_network();
+    if ineligible_for_broadcast {
+        TimelineState::NonQualified
+    } else {
+        TimelineState::NotReady
+    }
+}
+
+/// Sends the status of a client submitted transaction to the client
+pub(crate) fn send_client_submission_status(
+    callback: oneshot::Sender<Result<SubmissionStatus>>,
+    status: Result<SubmissionStatus>,
+) {
+    if callback.send(status).is_err() {
+        warn!(LogSchema::event_log(
+            LogEntry::JsonRpc,
+            LogEvent::CallbackFail
+        ));
+        counters::CLIENT_CALLBACK_FAIL.inc();
+    }
+}
+
 /// Processes request for all addresses in parking lot
 pub(crate) async fn process_parking_lot_addresses<NetworkClient, TransactionValidator>(
     smp: SharedMempool<NetworkClient, TransactionValidator>,
@@ -170,6 +290,25 @@ pub(crate) async fn process_parking_lot_addresses<NetworkClient, TransactionVali
     }
 }
 
+/// Processes request for the eviction pressure of mempool
+pub(crate) async fn process_eviction_pressure<NetworkClient, TransactionValidator>(
+    smp: SharedMempool<NetworkClient, TransactionValidator>,
+    callback: oneshot::Sender<EvictionPressure>,
+) where
+    NetworkClient: NetworkClientInterface<MempoolSyncMsg>,
+    TransactionValidator: TransactionValidation + 'static,
+{
+    let eviction_pressure = smp.mempool.lock().get_eviction_pressure();
+
+    if callback.send(eviction_pressure).is_err() {
+        warn!(LogSchema::event_log(
+            LogEntry::JsonRpc,
+            LogEvent::CallbackFail
+        ));
+        counters::CLIENT_CALLBACK_FAIL.inc();
+    }
+}
+
 /// Processes get transaction by hash request by client.
 pub(crate) async fn process_client_get_transaction<NetworkClient, TransactionValidator>(
     smp: SharedMempool<NetworkClient, TransactionValidator>,
@@ -6,11 +6,11 @@
 use crate::{
     core_mempool::CoreMempool,
     network::{MempoolNetworkInterface, MempoolSyncMsg},
-    shared_mempool::use_case_history::UseCaseHistory,
+    shared_mempool::{persistence::TransactionLogSender, use_case_history::UseCaseHistory},
 };
 use anyhow::Result;
 use aptos_config::{
-    config::{MempoolConfig, NodeType},
+    config::{MempoolConfig, MempoolEvictionOrder, NodeType},
     network_id::PeerNetworkId,
 };
 use aptos_consensus_types::common::{
@@ -56,6 +56,9 @@ pub(crate) struct SharedMempool<NetworkClient, TransactionValidator> {
     pub subscribers: Vec<UnboundedSender<SharedMempoolNotification>>,
     pub broadcast_within_validator_network: Arc<RwLock<bool>>,
     pub use_case_history: Arc<Mutex<UseCaseHistory>>,
+    // The queue of the accepted client submitted transactions to persist (if the persistence is
+    // enabled)
+    pub transaction_log_sender: Option<TransactionLogSender>,
 }
 
 impl<
@@ -87,6 +90,7 @@ impl<
             subscribers,
             broadcast_within_validator_network: Arc::new(RwLock::new(true)),
             use_case_history: Arc::new(Mutex::new(use_case_history)),
+            transaction_log_sender: None,
         }
     }
 
@@ -243,6 +247,25 @@ pub enum MempoolClientRequest {
     /// Retrieves all addresses with transactions in the mempool's parking lot and
     /// the number of transactions for each address
     GetAddressesFromParkingLot(oneshot::Sender<Vec<(AccountAddress, u64)>>),
+    /// Retrieves how close the mempool is to its capacity, and the number of
+    /// transactions evicted so far
+    GetEvictionPressure(oneshot::Sender<EvictionPressure>),
+}
+
+/// How close the mempool is to its capacity (at which point it starts evicting
+/// the parked transactions), for operators tuning the eviction policy
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct EvictionPressure {
+    pub num_txns: usize,
+    pub capacity: usize,
+    pub num_bytes: usize,
+    pub capacity_bytes: usize,
+    pub num_parked_txns: usize,
+    pub num_parked_accounts: usize,
+    pub is_full: bool,
+    pub eviction_order: MempoolEvictionOrder,
+    /// The number of transactions evicted since the node started, by eviction reason
+    pub num_evicted_txns: BTreeMap<String, u64>,
 }
 
 pub type MempoolClientSender = mpsc::Sender<MempoolClientRequest>;
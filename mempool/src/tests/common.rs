@@ -111,6 +111,21 @@ impl TestTransaction {
         }
     }
 
+    pub(crate) fn new_with_script(
+        address: usize,
+        sequence_number: u64,
+        gas_price: u64,
+        script: Script,
+    ) -> Self {
+        Self {
+            address: TestTransaction::get_address(address),
+            sequence_number,
+            gas_price,
+            account_seqno: 0,
+            script: Some(script),
+        }
+    }
+
     pub(crate) fn new_with_address(
         address: AccountAddress,
         sequence_number: u64,
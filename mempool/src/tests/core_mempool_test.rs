@@ -7,15 +7,20 @@ use crate::{
     network::BroadcastPeerPriority,
     tests::common::{
         add_signed_txn, add_txn, add_txns_to_mempool, setup_mempool,
-        setup_mempool_with_broadcast_buckets, txn_bytes_len, TestTransaction,
+        setup_mempool_with_broadcast_buckets, txn_bytes_len, ConsensusMock, TestTransaction,
     },
 };
-use aptos_config::config::{MempoolConfig, NodeConfig};
+use aptos_config::config::{
+    BroadcastFairnessConfig, MempoolConfig, MempoolEvictionConfig, MempoolEvictionOrder,
+    NodeConfig, PriorityLanesConfig,
+};
 use aptos_consensus_types::common::{TransactionInProgress, TransactionSummary};
 use aptos_crypto::HashValue;
 use aptos_types::{
-    account_address::AccountAddress, mempool_status::MempoolStatusCode,
-    transaction::SignedTransaction, vm_status::DiscardedVMStatus,
+    account_address::AccountAddress,
+    mempool_status::MempoolStatusCode,
+    transaction::{Script, SignedTransaction},
+    vm_status::DiscardedVMStatus,
 };
 use itertools::Itertools;
 use maplit::btreemap;
@@ -725,6 +730,62 @@ fn test_multi_bucket_removal() {
     assert!(view(timeline).is_empty());
 }
 
+#[test]
+fn test_read_timelines_fair() {
+    let mut config = NodeConfig::generate_random_config();
+    config.mempool.broadcast_buckets = vec![0, 101];
+    config.mempool.num_sender_buckets = 1;
+    let mut pool = CoreMempool::new(&config);
+
+    // A spammy account with low gas transactions, and another account with high gas ones
+    let mut test_txns: Vec<_> = (0..10)
+        .map(|sequence_number| TestTransaction::new(1, sequence_number, 1))
+        .collect();
+    test_txns.push(TestTransaction::new(2, 0, 200));
+    test_txns.push(TestTransaction::new(2, 1, 200));
+    add_txns_to_mempool(&mut pool, test_txns);
+
+    // Every round takes 2 high gas transactions and 1 low gas one, and the spammy
+    // account is limited to 3 transactions.
+    let fairness_config = BroadcastFairnessConfig {
+        enable: true,
+        max_txns_per_account: 3,
+        gas_bucket_weights: vec![1, 2],
+    };
+    let (txns, timeline_updates) = pool.read_timelines_fair(
+        vec![(0, vec![0, 0].into(), None, BroadcastPeerPriority::Primary)],
+        100,
+        &fairness_config,
+    );
+    let view_fair = |txns: Vec<(SignedTransaction, u64, BroadcastPeerPriority)>| {
+        txns.into_iter()
+            .map(|(txn, _, _)| (txn.sender(), txn.sequence_number()))
+            .collect::<Vec<_>>()
+    };
+    let spammy_account = TestTransaction::get_address(1);
+    let other_account = TestTransaction::get_address(2);
+    assert_eq!(view_fair(txns), vec![
+        (other_account, 0),
+        (other_account, 1),
+        (spammy_account, 0),
+        (spammy_account, 1),
+        (spammy_account, 2),
+    ]);
+
+    // The next transactions of the spammy account are left for the next batch
+    let (_, (_, timeline_id)) = timeline_updates[0].clone();
+    let (txns, _) = pool.read_timelines_fair(
+        vec![(0, timeline_id, None, BroadcastPeerPriority::Primary)],
+        100,
+        &fairness_config,
+    );
+    assert_eq!(view_fair(txns), vec![
+        (spammy_account, 3),
+        (spammy_account, 4),
+        (spammy_account, 5),
+    ]);
+}
+
 #[test]
 fn test_capacity() {
     let mut config = NodeConfig::generate_random_config();
@@ -853,6 +914,44 @@ fn test_parking_lot_eviction() {
     assert!(add_txn(&mut pool, TestTransaction::new(0, 2, 1)).is_err());
 }
 
+#[test]
+fn test_parking_lot_eviction_by_gas_unit_price() {
+    let mut config = NodeConfig::generate_random_config();
+    config.mempool.capacity = 3;
+    config.mempool.eviction = MempoolEvictionConfig {
+        order: MempoolEvictionOrder::LowestGasUnitPrice,
+        num_sampled_accounts: 16,
+        evict_for_parked_transactions: true,
+    };
+    let mut pool = CoreMempool::new(&config);
+    // Fill Mempool with parked transactions of different gas unit prices.
+    for (address, gas_unit_price) in [(0, 1), (1, 10), (2, 5)] {
+        add_txn(&mut pool, TestTransaction::new(address, 5, gas_unit_price)).unwrap();
+    }
+    let eviction_pressure = pool.get_eviction_pressure();
+    assert!(eviction_pressure.is_full);
+    assert_eq!(eviction_pressure.num_parked_txns, 3);
+
+    // A ready transaction evicts the parked transaction with the lowest gas unit price.
+    add_txn(&mut pool, TestTransaction::new(3, 0, 1)).unwrap();
+    // A parked transaction can evict too, as configured.
+    add_txn(&mut pool, TestTransaction::new(3, 5, 20)).unwrap();
+
+    let mut parked_addresses: Vec<_> = pool
+        .get_parking_lot_addresses()
+        .into_iter()
+        .map(|(address, _)| address)
+        .collect();
+    parked_addresses.sort();
+    let mut expected_addresses = vec![
+        TestTransaction::get_address(1),
+        TestTransaction::get_address(3),
+    ];
+    expected_addresses.sort();
+    assert_eq!(parked_addresses, expected_addresses);
+    assert!(pool.get_eviction_pressure().num_evicted_txns["capacity"] >= 2);
+}
+
 #[test]
 fn test_parking_lot_eviction_bytes() {
     // Get the small transaction size
@@ -1311,3 +1410,76 @@ fn test_include_gas_upgraded() {
     });
     assert_eq!(batch.len(), 0);
 }
+
+#[test]
+fn test_priority_lane() {
+    let mut config = NodeConfig::generate_random_config();
+    config.mempool.capacity_per_user = 1;
+    config.mempool.priority_lanes = PriorityLanesConfig {
+        enable: true,
+        priority_senders: vec![TestTransaction::get_address(1)],
+        max_priority_txns: 2,
+        ..PriorityLanesConfig::default()
+    };
+    let mut pool = CoreMempool::new(&config);
+    let mut consensus = ConsensusMock::new();
+
+    let normal_txn = add_txn(&mut pool, TestTransaction::new(0, 0, 10)).unwrap();
+    let priority_txn_1 = add_txn(&mut pool, TestTransaction::new(1, 0, 1)).unwrap();
+
+    // The priority transactions aren't subject to the per account capacity, up to the quota
+    let priority_txn_2 = add_txn(&mut pool, TestTransaction::new(1, 1, 1)).unwrap();
+    assert!(add_txn(&mut pool, TestTransaction::new(1, 2, 1)).is_err());
+    assert!(add_txn(&mut pool, TestTransaction::new(0, 1, 10)).is_err());
+
+    // The priority transactions come first, despite their lower gas unit price
+    assert_eq!(consensus.get_block(&mut pool, 3, 10240), vec![
+        priority_txn_1,
+        priority_txn_2,
+        normal_txn
+    ]);
+}
+
+#[test]
+fn test_priority_lane_allowlisted_scripts() {
+    let allowlisted_script = Script::new(vec![1, 2, 3], vec![], vec![]);
+    let other_script = Script::new(vec![4, 5, 6], vec![], vec![]);
+
+    let mut config = NodeConfig::generate_random_config();
+    config.mempool.capacity_per_user = 1;
+    config.mempool.priority_lanes = PriorityLanesConfig {
+        enable: true,
+        priority_script_hashes: vec![HashValue::sha3_256_of(allowlisted_script.code())],
+        ..PriorityLanesConfig::default()
+    };
+    let mut pool = CoreMempool::new(&config);
+    let mut consensus = ConsensusMock::new();
+
+    // Only the allowlisted script is a priority transaction, regardless of its sender
+    let normal_txn = add_txn(
+        &mut pool,
+        TestTransaction::new_with_script(0, 0, 10, other_script.clone()),
+    )
+    .unwrap();
+    let priority_txn_1 = add_txn(
+        &mut pool,
+        TestTransaction::new_with_script(2, 0, 1, allowlisted_script.clone()),
+    )
+    .unwrap();
+    let priority_txn_2 = add_txn(
+        &mut pool,
+        TestTransaction::new_with_script(2, 1, 1, allowlisted_script),
+    )
+    .unwrap();
+    assert!(add_txn(
+        &mut pool,
+        TestTransaction::new_with_script(0, 1, 10, other_script)
+    )
+    .is_err());
+
+    assert_eq!(consensus.get_block(&mut pool, 3, 10240), vec![
+        priority_txn_1,
+        priority_txn_2,
+        normal_txn
+    ]);
+}
@@ -6,14 +6,20 @@ use crate::{
     core_mempool::sender_bucket,
     mocks::MockSharedMempool,
     network::BroadcastPeerPriority,
+    shared_mempool::{persistence::TransactionLog, signature_prevalidation::verify_signatures},
     tests::common::{batch_add_signed_txn, TestTransaction},
     QuorumStoreRequest,
 };
 use aptos_config::config::MempoolConfig;
 use aptos_consensus_types::common::RejectedTransactionSummary;
 use aptos_mempool_notifications::MempoolNotificationSender;
-use aptos_types::{transaction::Transaction, vm_status::DiscardedVMStatus};
+use aptos_temppath::TempPath;
+use aptos_types::{
+    transaction::{authenticator::TransactionAuthenticator, SignedTransaction, Transaction},
+    vm_status::DiscardedVMStatus,
+};
 use futures::{channel::oneshot, sink::SinkExt};
+use std::{fs::OpenOptions, io::Write};
 use tokio::time::timeout;
 
 #[tokio::test]
@@ -152,3 +158,57 @@ async fn test_mempool_notify_committed_txns() {
         );
     }
 }
+
+#[test]
+fn test_signature_prevalidation() {
+    let txns: Vec<_> = (0..4)
+        .map(|i| TestTransaction::new(i, 0, 1).make_signed_transaction())
+        .collect();
+
+    // The signatures of the transactions are valid
+    let txn_refs: Vec<_> = txns.iter().collect();
+    assert_eq!(verify_signatures(&txn_refs), vec![true; 4]);
+
+    // A transaction with the signature of another one is invalid, and the other ones are still
+    // valid (once the batch verification fails)
+    let (public_key, signature) = match txns[1].authenticator() {
+        TransactionAuthenticator::Ed25519 {
+            public_key,
+            signature,
+        } => (public_key, signature),
+        _ => unreachable!(),
+    };
+    let invalid_txn =
+        SignedTransaction::new(txns[0].raw_transaction_ref().clone(), public_key, signature);
+    let txn_refs = vec![&txns[0], &invalid_txn, &txns[2], &txns[3]];
+    assert_eq!(verify_signatures(&txn_refs), vec![true, false, true, true]);
+}
+
+#[test]
+fn test_transaction_log() {
+    let dir = TempPath::new();
+    let path = dir.path().join("mempool").join("transactions.log");
+    let txns: Vec<_> = (0..4)
+        .map(|i| TestTransaction::new(i, 0, 1).make_signed_transaction())
+        .collect();
+
+    // The appended transactions are read back, up to the max
+    let (mut log, persisted_txns) = TransactionLog::open(&path, 3).unwrap();
+    assert!(persisted_txns.is_empty());
+    log.append_batch(&txns[..1]).unwrap();
+    log.append_batch(&txns[1..]).unwrap();
+    drop(log);
+    let (mut log, persisted_txns) = TransactionLog::open(&path, 3).unwrap();
+    assert_eq!(persisted_txns, txns[..3].to_vec());
+
+    // The compaction replaces the transactions
+    log.compact(&txns[2..]).unwrap();
+    drop(log);
+
+    // A partially written record at the end is dropped
+    let mut file = OpenOptions::new().append(true).open(&path).unwrap();
+    file.write_all(&[100, 0, 0, 0, 1, 2]).unwrap();
+    drop(file);
+    let (_, persisted_txns) = TransactionLog::open(&path, 3).unwrap();
+    assert_eq!(persisted_txns, txns[2..].to_vec());
+}
@@ -36,6 +36,7 @@ use aptos_network::{
             NetworkApplicationConfig, NetworkClientConfig, NetworkServiceConfig, NewNetworkEvents,
             NewNetworkSender,
         },
+        wire::handshake::v1::{MessageSizeLimits, ProtocolId},
     },
 };
 use aptos_network_discovery::DiscoveryChangeListener;
@@ -196,6 +197,9 @@ impl NetworkBuilder {
                 config.outbound_tx_buffer_size_bytes,
             ),
         );
+        network_builder
+            .peer_manager_builder
+            .set_message_size_limits(message_size_limits(config));
 
         network_builder.add_connection_monitoring(
             config.ping_interval_ms,
@@ -470,6 +474,35 @@ impl NetworkBuilder {
     }
 }
 
+/// Returns the message size limits of the config, panicking on unknown protocols.
+fn message_size_limits(config: &NetworkConfig) -> MessageSizeLimits {
+    // Messages are split into at most u8::MAX frames.
+    let max_limit = config.max_frame_size * u8::MAX as usize;
+    let protocol_limits = config
+        .max_message_size_per_protocol
+        .iter()
+        .map(|(name, limit)| {
+            let protocol = ProtocolId::all()
+                .iter()
+                .find(|protocol| protocol.as_str() == name)
+                .unwrap_or_else(|| {
+                    panic!(
+                        "Unknown protocol in max_message_size_per_protocol: {}",
+                        name
+                    )
+                });
+            assert!(
+                *limit <= max_limit,
+                "Max message size of {} exceeds {} frames of max_frame_size: {}",
+                name,
+                u8::MAX,
+                limit
+            );
+            (*protocol, *limit)
+        });
+    MessageSizeLimits::new(config.max_message_size, protocol_limits)
+}
+
 /// Retrieve and merge seeds so that they have all keys associated
 fn merge_seeds(config: &NetworkConfig) -> PeerSet {
     config.verify_seeds().expect("Seeds must be well formed");
@@ -206,7 +206,7 @@ impl NoiseUpgrader {
         let payload = time_provider();
 
         // craft first handshake message  (-> e, es, s, ss)
-        let mut rng = rand::rngs::OsRng;
+        let mut rng = aptos_crypto::rng::DefaultRng;
         let initiator_state = self
             .noise_config
             .initiate_connection(
@@ -455,7 +455,7 @@ impl NoiseUpgrader {
         }
 
         // construct the response
-        let mut rng = rand::rngs::OsRng;
+        let mut rng = aptos_crypto::rng::DefaultRng;
         let mut server_response = [0u8; Self::SERVER_MESSAGE_SIZE];
         let session = self
             .noise_config
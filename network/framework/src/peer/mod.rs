@@ -18,7 +18,8 @@
 use crate::{
     counters::{
         self, network_application_inbound_traffic, network_application_outbound_traffic,
-        DECLINED_LABEL, FAILED_LABEL, RECEIVED_LABEL, SENT_LABEL, UNKNOWN_LABEL,
+        DECLINED_LABEL, FAILED_LABEL, INBOUND_LABEL, OUTBOUND_LABEL, RECEIVED_LABEL, REQUEST_LABEL,
+        SENT_LABEL, UNKNOWN_LABEL,
     },
     logging::NetworkSchema,
     peer_manager::{PeerManagerError, TransportNotification},
@@ -27,9 +28,12 @@ use crate::{
         network::ReceivedMessage,
         rpc::{error::RpcError, InboundRpcs, OutboundRpcRequest, OutboundRpcs},
         stream::{InboundStreamBuffer, OutboundStream, StreamMessage},
-        wire::messaging::v1::{
-            DirectSendMsg, ErrorCode, MultiplexMessage, MultiplexMessageSink,
-            MultiplexMessageStream, NetworkMessage, Priority, ReadError, WriteError,
+        wire::{
+            handshake::v1::MessageSizeLimits,
+            messaging::v1::{
+                DirectSendMsg, ErrorCode, MultiplexMessage, MultiplexMessageSink,
+                MultiplexMessageStream, NetworkMessage, Priority, ReadError, WriteError,
+            },
         },
     },
     transport::{self, Connection, ConnectionMetadata},
@@ -136,6 +140,8 @@ pub struct Peer<TSocket> {
     max_frame_size: usize,
     /// The maximum size of an inbound or outbound request message
     max_message_size: usize,
+    /// The maximum sizes of the messages of each protocol, as negotiated with the remote peer
+    message_size_limits: MessageSizeLimits,
     /// Inbound stream buffer
     inbound_stream: InboundStreamBuffer,
 }
@@ -164,8 +170,11 @@ where
         let Connection {
             metadata: connection_metadata,
             socket,
+            message_size_limits,
         } = connection;
         let remote_peer_id = connection_metadata.remote_peer_id;
+        // The limits of some protocols may have been negotiated above the network default.
+        let max_message_size = max_message_size.max(message_size_limits.max_limit());
         let max_fragments = max_message_size / max_frame_size;
         Self {
             network_context,
@@ -192,6 +201,7 @@ where
             state: State::Connected,
             max_frame_size,
             max_message_size,
+            message_size_limits,
             inbound_stream: InboundStreamBuffer::new(max_fragments),
         }
     }
@@ -200,6 +210,19 @@ where
         self.connection_metadata.remote_peer_id
     }
 
+    /// Returns an error if the message is larger than the limit of its protocol.
+    fn check_message_size(&self, protocol_id: ProtocolId, size: usize) -> Result<(), RpcError> {
+        let limit = self.message_size_limits.get(protocol_id);
+        if size > limit {
+            return Err(RpcError::MessageTooLarge {
+                protocol_id,
+                size,
+                limit,
+            });
+        }
+        Ok(())
+    }
+
     pub async fn start(mut self) {
         let remote_peer_id = self.remote_peer_id();
         trace!(
@@ -457,6 +480,21 @@ where
                     direct.protocol_id,
                     data_len as u64,
                 );
+                if let Err(err) = self.check_message_size(direct.protocol_id, data_len) {
+                    counters::direct_send_messages(&self.network_context, DECLINED_LABEL).inc();
+                    counters::direct_send_bytes(&self.network_context, DECLINED_LABEL)
+                        .inc_by(data_len as u64);
+                    warn!(
+                        NetworkSchema::new(&self.network_context)
+                            .connection_metadata(&self.connection_metadata),
+                        error = %err,
+                        "{} Dropping direct send message from peer {}: {}",
+                        self.network_context,
+                        self.remote_peer_id().short_str(),
+                        err
+                    );
+                    return Ok(());
+                }
                 match self.upstream_handlers.get(&direct.protocol_id) {
                     None => {
                         counters::direct_send_messages(&self.network_context, UNKNOWN_LABEL).inc();
@@ -504,6 +542,27 @@ where
                 );
             },
             NetworkMessage::RpcRequest(request) => {
+                if let Err(err) =
+                    self.check_message_size(request.protocol_id, request.raw_request.len())
+                {
+                    counters::rpc_messages(
+                        &self.network_context,
+                        REQUEST_LABEL,
+                        INBOUND_LABEL,
+                        DECLINED_LABEL,
+                    )
+                    .inc();
+                    warn!(
+                        NetworkSchema::new(&self.network_context)
+                            .connection_metadata(&self.connection_metadata),
+                        error = %err,
+                        "{} Dropping rpc request from peer {}: {}",
+                        self.network_context,
+                        self.remote_peer_id().short_str(),
+                        err
+                    );
+                    return Ok(());
+                }
                 match self.upstream_handlers.get(&request.protocol_id) {
                     None => {
                         counters::direct_send_messages(&self.network_context, UNKNOWN_LABEL).inc();
@@ -617,6 +676,18 @@ where
                 // Create the direct send message
                 let message_len = message.mdata.len();
                 let protocol_id = message.protocol_id;
+                if let Err(err) = self.check_message_size(protocol_id, message_len) {
+                    counters::direct_send_messages(&self.network_context, FAILED_LABEL).inc();
+                    warn!(
+                        NetworkSchema::new(&self.network_context)
+                            .connection_metadata(&self.connection_metadata),
+                        error = %err,
+                        "Failed to send direct send message to peer: {}. Error: {}",
+                        self.remote_peer_id().short_str(),
+                        err,
+                    );
+                    return;
+                }
                 let message = NetworkMessage::DirectSendMsg(DirectSendMsg {
                     protocol_id,
                     priority: Priority::default(),
@@ -643,6 +714,18 @@ where
             },
             PeerRequest::SendRpc(request) => {
                 let protocol_id = request.protocol_id;
+                if let Err(err) = self.check_message_size(protocol_id, request.data.len()) {
+                    counters::rpc_messages(
+                        &self.network_context,
+                        REQUEST_LABEL,
+                        OUTBOUND_LABEL,
+                        FAILED_LABEL,
+                    )
+                    .inc();
+                    // Notify the application of the oversized request.
+                    let _ = request.res_tx.send(Err(err));
+                    return;
+                }
                 if let Err(e) = self
                     .outbound_rpcs
                     .handle_outbound_request(request, write_reqs_tx)
@@ -14,7 +14,7 @@ use crate::{
         network::ReceivedMessage,
         rpc::{error::RpcError, OutboundRpcRequest},
         wire::{
-            handshake::v1::{MessagingProtocolVersion, ProtocolIdSet},
+            handshake::v1::{MessageSizeLimits, MessagingProtocolVersion, ProtocolIdSet},
             messaging::v1::{
                 DirectSendMsg, MultiplexMessage, MultiplexMessageSink, MultiplexMessageStream,
                 NetworkMessage, RpcRequest, RpcResponse,
@@ -78,6 +78,7 @@ fn build_test_peer(
             PeerRole::Unknown,
         ),
         socket: a,
+        message_size_limits: MessageSizeLimits::uniform(MAX_MESSAGE_SIZE),
     };
 
     let (connection_notifs_tx, connection_notifs_rx) = aptos_channels::new_test(1);
@@ -691,6 +692,47 @@ fn peer_send_rpc() {
     rt.block_on(future::join3(peer.start(), server, client));
 }
 
+#[test]
+fn peer_send_rpc_too_large() {
+    ::aptos_logger::Logger::init_for_testing();
+    let rt = Runtime::new().unwrap();
+    let upstream_handlers = Arc::new(HashMap::new());
+    let (mut peer, mut peer_handle, mut connection, _connection_notifs_rx) = build_test_peer(
+        rt.handle().clone(),
+        TimeService::mock(),
+        ConnectionOrigin::Inbound,
+        upstream_handlers,
+    );
+    peer.message_size_limits = MessageSizeLimits::new(MAX_MESSAGE_SIZE, [(PROTOCOL, 5)]);
+    let (_server_sink, mut server_stream) = build_network_sink_stream(&mut connection);
+    let timeout = Duration::from_millis(10_000);
+
+    let client = async move {
+        // The request exceeds the limit of the protocol, so it is failed without being sent.
+        let result = peer_handle
+            .send_rpc_request(PROTOCOL, Bytes::from(&b"hello world"[..]), timeout)
+            .await;
+        match result {
+            Err(RpcError::MessageTooLarge {
+                protocol_id,
+                size,
+                limit,
+            }) => {
+                assert_eq!(protocol_id, PROTOCOL);
+                assert_eq!(size, 11);
+                assert_eq!(limit, 5);
+            },
+            _ => panic!("Expected MessageTooLarge; unexpected: {:?}", result),
+        }
+        // Client then closes connection.
+    };
+    let server = async move {
+        // Server should not receive any request.
+        assert!(server_stream.next().await.is_none());
+    };
+    rt.block_on(future::join3(peer.start(), server, client));
+}
+
 #[test]
 fn peer_send_rpc_concurrent() {
     ::aptos_logger::Logger::init_for_testing();
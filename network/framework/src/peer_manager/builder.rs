@@ -12,7 +12,7 @@ use crate::{
     },
     protocols::{
         network::{NetworkClientConfig, NetworkServiceConfig, ReceivedMessage},
-        wire::handshake::v1::ProtocolIdSet,
+        wire::handshake::v1::{MessageSizeLimits, ProtocolIdSet},
     },
     transport::{self, AptosNetTransport, Connection, APTOS_TCP_TRANSPORT},
     ProtocolId,
@@ -52,6 +52,7 @@ struct TransportContext {
     authentication_mode: AuthenticationMode,
     peers_and_metadata: Arc<PeersAndMetadata>,
     enable_proxy_protocol: bool,
+    message_size_limits: MessageSizeLimits,
 }
 
 impl TransportContext {
@@ -193,6 +194,7 @@ impl PeerManagerBuilder {
                 authentication_mode,
                 peers_and_metadata: peers_and_metadata.clone(),
                 enable_proxy_protocol,
+                message_size_limits: MessageSizeLimits::uniform(max_message_size),
             }),
             peer_manager_context: Some(PeerManagerContext::new(
                 pm_reqs_tx,
@@ -250,6 +252,7 @@ impl PeerManagerBuilder {
         let protos = transport_context.supported_protocols;
         let chain_id = transport_context.chain_id;
         let enable_proxy_protocol = transport_context.enable_proxy_protocol;
+        let message_size_limits = transport_context.message_size_limits;
 
         let (key, auth_mode) = match transport_context.authentication_mode {
             AuthenticationMode::MaybeMutual(key) => (
@@ -278,6 +281,7 @@ impl PeerManagerBuilder {
                         HANDSHAKE_VERSION,
                         chain_id,
                         protos,
+                        message_size_limits,
                         enable_proxy_protocol,
                     ),
                     executor,
@@ -294,6 +298,7 @@ impl PeerManagerBuilder {
                     HANDSHAKE_VERSION,
                     chain_id,
                     protos,
+                    message_size_limits,
                     enable_proxy_protocol,
                 ),
                 executor,
@@ -380,6 +385,12 @@ impl PeerManagerBuilder {
             .add_connection_event_listener()
     }
 
+    /// Sets the maximum sizes of the messages we accept per protocol, which are negotiated with
+    /// the peers. By default, all protocols are limited to the `max_message_size`.
+    pub fn set_message_size_limits(&mut self, message_size_limits: MessageSizeLimits) {
+        self.transport_context().message_size_limits = message_size_limits;
+    }
+
     pub fn get_tcp_buffers_cfg(&self) -> TCPBufferCfg {
         self.peer_manager_context
             .as_ref()
@@ -11,7 +11,7 @@ use crate::{
         PeerManager, PeerManagerRequest, TransportNotification,
     },
     protocols::wire::{
-        handshake::v1::{MessagingProtocolVersion, ProtocolIdSet},
+        handshake::v1::{MessageSizeLimits, MessagingProtocolVersion, ProtocolIdSet},
         messaging::v1::{
             ErrorCode, MultiplexMessage, MultiplexMessageSink, MultiplexMessageStream,
             NetworkMessage,
@@ -61,6 +61,7 @@ pub fn build_test_transport(
                     ProtocolIdSet::mock(),
                     PeerRole::Unknown,
                 ),
+                message_size_limits: MessageSizeLimits::uniform(constants::MAX_MESSAGE_SIZE),
             })
         })
         .boxed()
@@ -242,6 +243,7 @@ fn create_connection<TSocket: transport::TSocket>(
             ProtocolIdSet::mock(),
             PeerRole::Unknown,
         ),
+        message_size_limits: MessageSizeLimits::uniform(constants::MAX_MESSAGE_SIZE),
     }
 }
 
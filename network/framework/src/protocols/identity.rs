@@ -4,10 +4,11 @@
 
 //! Protocol used to exchange supported protocol information with a remote.
 
-use crate::protocols::wire::handshake::v1::HandshakeMsg;
+use crate::protocols::wire::handshake::v1::{HandshakeMsg, MessageSizeLimits};
 use aptos_netcore::framing::{read_u16frame, write_u16frame};
 use bytes::BytesMut;
 use futures::io::{AsyncRead, AsyncWrite, AsyncWriteExt};
+use serde::{de::DeserializeOwned, Serialize};
 use std::io;
 
 /// The Handshake exchange protocol.
@@ -18,26 +19,44 @@ pub async fn exchange_handshake<T>(
 where
     T: AsyncRead + AsyncWrite + Unpin,
 {
-    // Send serialized handshake message to remote peer.
-    let msg = bcs::to_bytes(own_handshake).map_err(|e| {
+    exchange_msg(own_handshake, socket, "identity").await
+}
+
+/// Exchanges the message size limits with a remote, once both agreed to in the handshake.
+pub async fn exchange_message_size_limits<T>(
+    own_limits: &MessageSizeLimits,
+    socket: &mut T,
+) -> io::Result<MessageSizeLimits>
+where
+    T: AsyncRead + AsyncWrite + Unpin,
+{
+    exchange_msg(own_limits, socket, "message size limits").await
+}
+
+async fn exchange_msg<T, M>(own_msg: &M, socket: &mut T, name: &str) -> io::Result<M>
+where
+    T: AsyncRead + AsyncWrite + Unpin,
+    M: Serialize + DeserializeOwned,
+{
+    // Send serialized message to remote peer.
+    let msg = bcs::to_bytes(own_msg).map_err(|e| {
         io::Error::new(
             io::ErrorKind::InvalidData,
-            format!("Failed to serialize identity msg: {}", e),
+            format!("Failed to serialize {} msg: {}", name, e),
         )
     })?;
     write_u16frame(socket, &msg).await?;
     socket.flush().await?;
 
-    // Read handshake message from the Remote
+    // Read message from the Remote
     let mut response = BytesMut::new();
     read_u16frame(socket, &mut response).await?;
-    let identity = bcs::from_bytes(&response).map_err(|e| {
+    bcs::from_bytes(&response).map_err(|e| {
         io::Error::new(
             io::ErrorKind::InvalidData,
-            format!("Failed to parse identity msg: {}", e),
+            format!("Failed to parse {} msg: {}", name, e),
         )
-    })?;
-    Ok(identity)
+    })
 }
 
 #[cfg(test)]
@@ -4,7 +4,7 @@
 
 //! Rpc protocol errors
 
-use crate::peer_manager::PeerManagerError;
+use crate::{peer_manager::PeerManagerError, protocols::wire::handshake::v1::ProtocolId};
 use anyhow::anyhow;
 use aptos_types::PeerId;
 use futures::channel::{mpsc, oneshot};
@@ -42,6 +42,15 @@ pub enum RpcError {
 
     #[error("Rpc timed out")]
     TimedOut,
+
+    #[error(
+        "Message of {size} bytes exceeds the limit of {limit} bytes for protocol {protocol_id}"
+    )]
+    MessageTooLarge {
+        protocol_id: ProtocolId,
+        size: usize,
+        limit: usize,
+    },
 }
 
 impl From<PeerManagerError> for RpcError {
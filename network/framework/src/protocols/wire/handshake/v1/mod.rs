@@ -11,6 +11,10 @@
 //! supported over that messaging protocol. On receipt, both ends will determine the highest
 //! intersecting messaging protocol version and use that for the remainder of the session.
 //!
+//! If both end-points set the message size limits flag in their protocol sets, they then exchange
+//! their [`MessageSizeLimits`] in a second length-prefixed message, and use the lowest of the two
+//! for each protocol. Older nodes ignore the flag, so the exchange is skipped with them.
+//!
 //! [AptosNet Handshake v1 Specification]: https://github.com/aptos-labs/aptos-core/blob/main/specifications/network/handshake-v1.md
 
 use crate::counters::{start_serialization_timer, DESERIALIZATION_LABEL, SERIALIZATION_LABEL};
@@ -289,6 +293,10 @@ impl fmt::Display for ProtocolId {
 #[cfg_attr(any(test, feature = "fuzzing"), derive(Arbitrary))]
 pub struct ProtocolIdSet(aptos_bitvec::BitVec);
 
+/// A bit of [`ProtocolIdSet`] which is reserved to flag that the node exchanges its
+/// [`MessageSizeLimits`] after the handshake, rather than standing for a [`ProtocolId`].
+const MESSAGE_SIZE_LIMITS_FLAG: u16 = u8::MAX as u16;
+
 impl ProtocolIdSet {
     pub fn empty() -> Self {
         Self::default()
@@ -334,6 +342,26 @@ impl ProtocolIdSet {
     pub fn insert(&mut self, protocol: ProtocolId) {
         self.0.set(protocol as u16)
     }
+
+    /// Flags that the node exchanges its message size limits after the handshake.
+    pub fn set_message_size_limits_flag(&mut self) {
+        self.0.set(MESSAGE_SIZE_LIMITS_FLAG)
+    }
+
+    pub fn has_message_size_limits_flag(&self) -> bool {
+        self.0.is_set(MESSAGE_SIZE_LIMITS_FLAG)
+    }
+
+    /// Returns the set without the flags, i.e., only the protocols.
+    pub fn without_flags(&self) -> ProtocolIdSet {
+        ProtocolIdSet(
+            self.0
+                .iter_ones()
+                .filter(|idx| *idx != MESSAGE_SIZE_LIMITS_FLAG as usize)
+                .map(|idx| idx as u8)
+                .collect(),
+        )
+    }
 }
 
 impl FromIterator<ProtocolId> for ProtocolIdSet {
@@ -455,7 +483,7 @@ impl HandshakeMsg {
             if let Some(their_protocols) = other.supported_protocols.get(our_handshake_version) {
                 let common_protocols = our_protocols.intersect(their_protocols);
 
-                if !common_protocols.is_empty() {
+                if !common_protocols.without_flags().is_empty() {
                     return Ok((*our_handshake_version, common_protocols));
                 }
             }
@@ -481,3 +509,85 @@ impl fmt::Display for HandshakeMsg {
         )
     }
 }
+
+//
+// MessageSizeLimits
+//
+
+/// The maximum sizes of the messages a node accepts, per application protocol. The protocols
+/// without a specific limit are bounded by the default limit, i.e., the `max_message_size` of the
+/// network.
+///
+/// The limits are keyed by the raw protocol id on the wire, so that the limits of protocols
+/// unknown to the receiver are ignored rather than failing the exchange.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct MessageSizeLimits {
+    default_limit: u64,
+    protocol_limits: BTreeMap<u8, u64>,
+}
+
+impl MessageSizeLimits {
+    pub fn new(
+        default_limit: usize,
+        protocol_limits: impl IntoIterator<Item = (ProtocolId, usize)>,
+    ) -> Self {
+        Self {
+            default_limit: default_limit as u64,
+            protocol_limits: protocol_limits
+                .into_iter()
+                .map(|(protocol, limit)| (protocol as u8, limit as u64))
+                .collect(),
+        }
+    }
+
+    /// The same limit for all protocols, as enforced by nodes which don't negotiate limits.
+    pub fn uniform(limit: usize) -> Self {
+        Self::new(limit, [])
+    }
+
+    /// Returns the limit of the messages of the protocol.
+    pub fn get(&self, protocol: ProtocolId) -> usize {
+        self.get_raw(protocol as u8) as usize
+    }
+
+    /// Returns the highest of the limits.
+    pub fn max_limit(&self) -> usize {
+        self.protocol_limits
+            .values()
+            .copied()
+            .chain(std::iter::once(self.default_limit))
+            .max()
+            .unwrap_or_default() as usize
+    }
+
+    /// Returns the limits to use on a connection with a node which advertised the `other` limits,
+    /// i.e., the lowest of the two for every protocol.
+    pub fn negotiate(&self, other: &MessageSizeLimits) -> Self {
+        let protocol_limits = self
+            .protocol_limits
+            .keys()
+            .chain(other.protocol_limits.keys())
+            .map(|protocol| {
+                let limit = self.get_raw(*protocol).min(other.get_raw(*protocol));
+                (*protocol, limit)
+            })
+            .collect();
+        Self {
+            default_limit: self.default_limit.min(other.default_limit),
+            protocol_limits,
+        }
+    }
+
+    /// Returns the limits to use on a connection with a node which doesn't negotiate limits, and
+    /// so enforces the default limit on all protocols.
+    pub fn without_negotiation(&self) -> Self {
+        self.negotiate(&Self::uniform(self.default_limit as usize))
+    }
+
+    fn get_raw(&self, protocol: u8) -> u64 {
+        self.protocol_limits
+            .get(&protocol)
+            .copied()
+            .unwrap_or(self.default_limit)
+    }
+}
@@ -183,3 +183,61 @@ fn ignore_unknown_protocols() {
         ProtocolIdSet::empty(),
     );
 }
+
+#[test]
+fn message_size_limits_flag() {
+    let protocols = ProtocolIdSet::from_iter([ProtocolId::MempoolDirectSend]);
+    let mut flagged_protocols = protocols.clone();
+    flagged_protocols.set_message_size_limits_flag();
+    let flagged_hs = HandshakeMsg::from_supported(flagged_protocols.clone());
+    let legacy_hs = HandshakeMsg::from_supported(protocols.clone());
+
+    // The flag is only kept if both peers set it, and isn't mistaken for a protocol.
+    let (_, common_protos) = flagged_hs.perform_handshake(&flagged_hs).unwrap();
+    assert!(common_protos.has_message_size_limits_flag());
+    assert_eq!(common_protos.without_flags(), protocols);
+    assert_eq!(ProtocolIdSet::from_iter(common_protos.iter()), protocols);
+
+    let (_, common_protos) = flagged_hs.perform_handshake(&legacy_hs).unwrap();
+    assert!(!common_protos.has_message_size_limits_flag());
+
+    // A peer only setting the flag has no protocol in common with us.
+    let mut flag_only = ProtocolIdSet::empty();
+    flag_only.set_message_size_limits_flag();
+    assert_eq!(
+        flagged_hs
+            .perform_handshake(&HandshakeMsg::from_supported(flag_only))
+            .unwrap_err(),
+        HandshakeError::NoCommonProtocols,
+    );
+}
+
+#[test]
+fn negotiate_message_size_limits() {
+    let ours = MessageSizeLimits::new(100, [
+        (ProtocolId::ConsensusRpcBcs, 200),
+        (ProtocolId::MempoolDirectSend, 50),
+    ]);
+    let theirs = MessageSizeLimits::new(150, [(ProtocolId::ConsensusRpcBcs, 300)]);
+
+    let limits = ours.negotiate(&theirs);
+    assert_eq!(limits, theirs.negotiate(&ours));
+    assert_eq!(limits.get(ProtocolId::ConsensusRpcBcs), 200);
+    assert_eq!(limits.get(ProtocolId::MempoolDirectSend), 50);
+    assert_eq!(limits.get(ProtocolId::StorageServiceRpc), 100);
+    assert_eq!(limits.max_limit(), 200);
+
+    // Nodes which don't negotiate enforce the default limit on all protocols.
+    let limits = ours.without_negotiation();
+    assert_eq!(limits.get(ProtocolId::ConsensusRpcBcs), 100);
+    assert_eq!(limits.get(ProtocolId::MempoolDirectSend), 50);
+    assert_eq!(limits.max_limit(), 100);
+
+    // The limits of protocols unknown to the receiver don't fail the exchange.
+    let unknown = MessageSizeLimits {
+        default_limit: 100,
+        protocol_limits: BTreeMap::from([(200, 10)]),
+    };
+    let unknown: MessageSizeLimits = bcs::from_bytes(&bcs::to_bytes(&unknown).unwrap()).unwrap();
+    assert_eq!(unknown.get(ProtocolId::ConsensusRpcBcs), 100);
+}
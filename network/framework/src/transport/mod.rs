@@ -3,11 +3,14 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    constants::MAX_MESSAGE_SIZE,
     logging::NetworkSchema,
     noise::{stream::NoiseStream, AntiReplayTimestamps, HandshakeAuthMode, NoiseUpgrader},
     protocols::{
-        identity::exchange_handshake,
-        wire::handshake::v1::{HandshakeMsg, MessagingProtocolVersion, ProtocolIdSet},
+        identity::{exchange_handshake, exchange_message_size_limits},
+        wire::handshake::v1::{
+            HandshakeMsg, MessageSizeLimits, MessagingProtocolVersion, ProtocolIdSet,
+        },
     },
 };
 use aptos_config::{
@@ -189,6 +192,8 @@ impl fmt::Display for ConnectionMetadata {
 pub struct Connection<TSocket> {
     pub socket: TSocket,
     pub metadata: ConnectionMetadata,
+    /// The message size limits negotiated with the remote peer
+    pub message_size_limits: MessageSizeLimits,
 }
 
 /// Convenience function for adding a timeout to a Future that returns an `io::Result`.
@@ -210,6 +215,7 @@ pub struct UpgradeContext {
     supported_protocols: BTreeMap<MessagingProtocolVersion, ProtocolIdSet>,
     chain_id: ChainId,
     network_id: NetworkId,
+    message_size_limits: MessageSizeLimits,
 }
 
 impl UpgradeContext {
@@ -226,8 +232,38 @@ impl UpgradeContext {
             supported_protocols,
             chain_id,
             network_id,
+            message_size_limits: MessageSizeLimits::uniform(MAX_MESSAGE_SIZE),
         }
     }
+
+    /// Advertises that we negotiate message size limits in the handshake, and sets our limits.
+    pub fn with_message_size_limits(mut self, message_size_limits: MessageSizeLimits) -> Self {
+        for protocols in self.supported_protocols.values_mut() {
+            protocols.set_message_size_limits_flag();
+        }
+        self.message_size_limits = message_size_limits;
+        self
+    }
+}
+
+/// Exchanges the message size limits with the remote peer if both flagged that they negotiate
+/// them in the handshake. Returns the negotiated limits along with the common application
+/// protocols, without the flags.
+async fn negotiate_message_size_limits<T>(
+    ctxt: &UpgradeContext,
+    socket: &mut T,
+    common_protocols: ProtocolIdSet,
+) -> io::Result<(ProtocolIdSet, MessageSizeLimits)>
+where
+    T: AsyncRead + AsyncWrite + Unpin,
+{
+    let message_size_limits = if common_protocols.has_message_size_limits_flag() {
+        let remote_limits = exchange_message_size_limits(&ctxt.message_size_limits, socket).await?;
+        ctxt.message_size_limits.negotiate(&remote_limits)
+    } else {
+        ctxt.message_size_limits.without_negotiation()
+    };
+    Ok((common_protocols.without_flags(), message_size_limits))
 }
 
 /// If we have proxy protocol enabled, then prepend the un-proxied address to the error.
@@ -320,6 +356,10 @@ async fn upgrade_inbound<T: TSocket>(
                 &addr,
             )
         })?;
+    let (application_protocols, message_size_limits) =
+        negotiate_message_size_limits(&ctxt, &mut socket, application_protocols)
+            .await
+            .map_err(|err| add_pp_addr(proxy_protocol_enabled, err, &addr))?;
 
     // return successful connection
     Ok(Connection {
@@ -333,6 +373,7 @@ async fn upgrade_inbound<T: TSocket>(
             application_protocols,
             peer_role,
         ),
+        message_size_limits,
     })
 }
 
@@ -395,6 +436,8 @@ pub async fn upgrade_outbound<T: TSocket>(
             );
             io::Error::new(io::ErrorKind::Other, e)
         })?;
+    let (application_protocols, message_size_limits) =
+        negotiate_message_size_limits(&ctxt, &mut socket, application_protocols).await?;
 
     // return successful connection
     Ok(Connection {
@@ -408,6 +451,7 @@ pub async fn upgrade_outbound<T: TSocket>(
             application_protocols,
             peer_role,
         ),
+        message_size_limits,
     })
 }
 
@@ -448,6 +492,7 @@ where
         handshake_version: u8,
         chain_id: ChainId,
         application_protocols: ProtocolIdSet,
+        message_size_limits: MessageSizeLimits,
         enable_proxy_protocol: bool,
     ) -> Self {
         // build supported protocols
@@ -462,7 +507,8 @@ where
             supported_protocols,
             chain_id,
             network_context.network_id(),
-        );
+        )
+        .with_message_size_limits(message_size_limits);
 
         Self {
             base_transport,
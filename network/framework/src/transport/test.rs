@@ -4,7 +4,9 @@
 
 use crate::{
     application::storage::PeersAndMetadata,
-    protocols::wire::handshake::v1::{MessagingProtocolVersion, ProtocolId, ProtocolIdSet},
+    protocols::wire::handshake::v1::{
+        MessageSizeLimits, MessagingProtocolVersion, ProtocolId, ProtocolIdSet,
+    },
     testutils,
     transport::*,
 };
@@ -169,6 +171,7 @@ where
         HANDSHAKE_VERSION,
         chain_id,
         supported_protocols.clone(),
+        MessageSizeLimits::new(MAX_MESSAGE_SIZE, [(ProtocolId::ConsensusRpcBcs, 1024)]),
         false, /* Disable proxy protocol */
     );
 
@@ -181,6 +184,7 @@ where
         HANDSHAKE_VERSION,
         chain_id,
         supported_protocols.clone(),
+        MessageSizeLimits::uniform(MAX_MESSAGE_SIZE),
         false, /* Disable proxy protocol */
     );
 
@@ -203,6 +207,15 @@ async fn write_read_msg(socket: &mut impl TSocket, msg: &[u8]) -> Bytes {
     buf.freeze()
 }
 
+/// Check that the lowest limits of the listener and the dialer apply to the connection
+fn assert_negotiated_message_size_limits(limits: &MessageSizeLimits) {
+    assert_eq!(limits.get(ProtocolId::ConsensusRpcBcs), 1024);
+    assert_eq!(
+        limits.get(ProtocolId::DiscoveryDirectSend),
+        MAX_MESSAGE_SIZE
+    );
+}
+
 /// Check that the network address matches the format
 /// `"/memory/<port>/noise-ik/<pubkey>/handshake/<version>"`
 fn expect_memory_noise_addr(addr: &NetworkAddress) {
@@ -271,6 +284,7 @@ fn test_transport_success<TTransport>(
             conn.metadata.application_protocols,
             supported_protocols_clone,
         );
+        assert_negotiated_message_size_limits(&conn.message_size_limits);
 
         // test the socket works
         let msg = write_read_msg(&mut conn.socket, b"foobar").await;
@@ -297,6 +311,7 @@ fn test_transport_success<TTransport>(
             MessagingProtocolVersion::V1
         );
         assert_eq!(conn.metadata.application_protocols, supported_protocols);
+        assert_negotiated_message_size_limits(&conn.message_size_limits);
 
         // test the socket works
         let msg = write_read_msg(&mut conn.socket, b"barbaz").await;
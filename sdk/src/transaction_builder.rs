@@ -3,17 +3,25 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    move_types::account_address::AccountAddress,
+    move_types::{account_address::AccountAddress, vm_status::StatusCode},
+    rest_client::{aptos_api_types::AptosErrorCode, error::RestError, Client, PendingTransaction},
     types::{
         chain_id::ChainId,
-        transaction::{authenticator::AuthenticationKey, RawTransaction, TransactionPayload},
+        transaction::{
+            authenticator::{AccountAuthenticator, AuthenticationKey, TransactionAuthenticator},
+            RawTransaction, SignedTransaction, TransactionPayload,
+        },
+        LocalAccount,
     },
 };
+use anyhow::{bail, Context, Result};
 pub use aptos_cached_packages::aptos_stdlib;
 use aptos_crypto::{ed25519::Ed25519PublicKey, HashValue};
 use aptos_global_constants::{GAS_UNIT_PRICE, MAX_GAS_AMOUNT};
 use aptos_types::transaction::{EntryFunction, Script};
+use std::time::Duration;
 
+#[derive(Clone, Debug)]
 pub struct TransactionBuilder {
     sender: Option<AccountAddress>,
     sequence_number: Option<u64>,
@@ -86,12 +94,79 @@ impl TransactionBuilder {
     }
 }
 
+/// How the gas of a transaction is estimated before signing it, see
+/// [`TransactionFactory::with_gas_estimation`].
+#[derive(Clone, Debug)]
+pub struct GasEstimationConfig {
+    /// Sets the gas unit price from the estimation of the node
+    pub estimate_gas_unit_price: bool,
+    /// Uses the prioritized estimation of the gas unit price, for a faster inclusion under load
+    pub prioritized_gas_unit_price: bool,
+    /// Sets the max gas amount from a simulation of the transaction
+    pub estimate_max_gas_amount: bool,
+    /// The margin added to the simulated gas used, in percent
+    pub max_gas_amount_margin_pct: u64,
+}
+
+impl Default for GasEstimationConfig {
+    fn default() -> Self {
+        Self {
+            estimate_gas_unit_price: true,
+            prioritized_gas_unit_price: false,
+            estimate_max_gas_amount: true,
+            max_gas_amount_margin_pct: 50,
+        }
+    }
+}
+
+/// How a submission is retried when the mempool is full, or the sequence number of the sender
+/// is outdated (e.g. another client submitted a transaction for the same account).
+#[derive(Clone, Debug)]
+pub struct RetryPolicy {
+    /// The number of retries after the first attempt
+    pub max_retries: usize,
+    /// The delay before the first retry, doubled on every retry
+    pub initial_backoff: Duration,
+    /// The maximum delay between two retries
+    pub max_backoff: Duration,
+}
+
+impl RetryPolicy {
+    /// A policy submitting only once
+    pub fn no_retry() -> Self {
+        Self {
+            max_retries: 0,
+            initial_backoff: Duration::ZERO,
+            max_backoff: Duration::ZERO,
+        }
+    }
+
+    fn backoff(&self, retry: usize) -> Duration {
+        let factor = 1u32.checked_shl(retry as u32).unwrap_or(u32::MAX);
+        self.initial_backoff
+            .saturating_mul(factor)
+            .min(self.max_backoff)
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 5,
+            initial_backoff: Duration::from_millis(200),
+            max_backoff: Duration::from_secs(5),
+        }
+    }
+}
+
 #[derive(Clone, Debug)]
 pub struct TransactionFactory {
     max_gas_amount: u64,
     gas_unit_price: u64,
     transaction_expiration_time: u64,
     chain_id: ChainId,
+    gas_estimation: Option<GasEstimationConfig>,
+    retry_policy: RetryPolicy,
 }
 
 impl TransactionFactory {
@@ -102,6 +177,8 @@ impl TransactionFactory {
             gas_unit_price: GAS_UNIT_PRICE,
             transaction_expiration_time: 30,
             chain_id,
+            gas_estimation: None,
+            retry_policy: RetryPolicy::no_retry(),
         }
     }
 
@@ -125,6 +202,19 @@ impl TransactionFactory {
         self
     }
 
+    /// Estimates the gas of the transactions from the node before signing them in
+    /// [`Self::estimate_gas`] and [`Self::sign_and_submit`]. The max gas amount of the factory
+    /// stays the upper bound of the estimated max gas amount.
+    pub fn with_gas_estimation(mut self, gas_estimation: GasEstimationConfig) -> Self {
+        self.gas_estimation = Some(gas_estimation);
+        self
+    }
+
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = retry_policy;
+        self
+    }
+
     pub fn get_max_gas_amount(&self) -> u64 {
         self.max_gas_amount
     }
@@ -141,6 +231,122 @@ impl TransactionFactory {
         self.chain_id
     }
 
+    pub fn get_gas_estimation(&self) -> Option<&GasEstimationConfig> {
+        self.gas_estimation.as_ref()
+    }
+
+    pub fn get_retry_policy(&self) -> &RetryPolicy {
+        &self.retry_policy
+    }
+
+    /// Sets the gas unit price and max gas amount of the builder from the node, if gas estimation
+    /// is enabled. The max gas amount is estimated by simulating the transaction as sent by the
+    /// account, at its current sequence number.
+    pub async fn estimate_gas(
+        &self,
+        client: &Client,
+        account: &LocalAccount,
+        mut builder: TransactionBuilder,
+    ) -> Result<TransactionBuilder> {
+        let config = match &self.gas_estimation {
+            Some(config) => config,
+            None => return Ok(builder),
+        };
+
+        if config.estimate_gas_unit_price {
+            let estimation = client
+                .estimate_gas_price()
+                .await
+                .context("Failed to estimate the gas unit price")?
+                .into_inner();
+            let gas_unit_price = if config.prioritized_gas_unit_price {
+                estimation
+                    .prioritized_gas_estimate
+                    .unwrap_or(estimation.gas_estimate)
+            } else {
+                estimation.gas_estimate
+            };
+            builder = builder.gas_unit_price(gas_unit_price);
+        }
+
+        if config.estimate_max_gas_amount {
+            let raw_txn = builder
+                .clone()
+                .sender(account.address())
+                .sequence_number(account.sequence_number())
+                .build();
+            // The simulation rejects transactions with a valid signature
+            let txn = SignedTransaction::new_signed_transaction(
+                raw_txn,
+                TransactionAuthenticator::single_sender(
+                    AccountAuthenticator::NoAccountAuthenticator,
+                ),
+            );
+            let simulated = client
+                .simulate_bcs_with_gas_estimation(&txn, true, false)
+                .await
+                .context("Failed to simulate the transaction")?
+                .into_inner();
+            if !simulated.info.status().is_success() {
+                bail!(
+                    "The simulation of the transaction failed: {:?}",
+                    simulated.info.status()
+                );
+            }
+            let gas_used = simulated.info.gas_used();
+            let max_gas_amount = gas_used
+                .saturating_mul(100 + config.max_gas_amount_margin_pct)
+                .div_ceil(100)
+                .min(self.max_gas_amount);
+            builder = builder.max_gas_amount(max_gas_amount);
+        }
+        Ok(builder)
+    }
+
+    /// Estimates the gas if enabled, then signs the transaction with the account and submits it,
+    /// retrying with backoff according to the retry policy when the mempool is full or the
+    /// sequence number of the account is outdated.
+    pub async fn sign_and_submit(
+        &self,
+        client: &Client,
+        account: &LocalAccount,
+        payload: TransactionPayload,
+    ) -> Result<PendingTransaction> {
+        let mut retry = 0;
+        loop {
+            let builder = self
+                .estimate_gas(client, account, self.payload(payload.clone()))
+                .await?;
+            let sequence_number = account.sequence_number();
+            let txn = account.try_sign_with_transaction_builder(builder)?;
+            let error = match client.submit(&txn).await {
+                Ok(response) => return Ok(response.into_inner()),
+                Err(error) => error,
+            };
+
+            let reason = match SubmissionFailure::from_error(&error) {
+                Some(reason) if retry < self.retry_policy.max_retries => reason,
+                _ => {
+                    account.set_sequence_number(sequence_number);
+                    return Err(error).context("Failed to submit the transaction");
+                },
+            };
+            match reason {
+                SubmissionFailure::MempoolIsFull => account.set_sequence_number(sequence_number),
+                SubmissionFailure::SequenceNumberOutdated => {
+                    let sequence_number = client
+                        .get_account_sequence_number(account.address())
+                        .await
+                        .context("Failed to get the sequence number of the account")?
+                        .into_inner();
+                    account.set_sequence_number(sequence_number);
+                },
+            }
+            tokio::time::sleep(self.retry_policy.backoff(retry)).await;
+            retry += 1;
+        }
+    }
+
     pub fn payload(&self, payload: TransactionPayload) -> TransactionBuilder {
         self.transaction_builder(payload)
     }
@@ -293,3 +499,79 @@ impl TransactionFactory {
             + self.transaction_expiration_time
     }
 }
+
+/// The submission failures worth retrying
+#[derive(Debug, PartialEq, Eq)]
+enum SubmissionFailure {
+    MempoolIsFull,
+    SequenceNumberOutdated,
+}
+
+impl SubmissionFailure {
+    fn from_error(error: &RestError) -> Option<Self> {
+        let error = match error {
+            RestError::Api(error) => &error.error,
+            _ => return None,
+        };
+        match error.error_code {
+            AptosErrorCode::MempoolIsFull => Some(Self::MempoolIsFull),
+            AptosErrorCode::SequenceNumberTooOld => Some(Self::SequenceNumberOutdated),
+            AptosErrorCode::VmError
+                if error.vm_error_code == Some(StatusCode::SEQUENCE_NUMBER_TOO_OLD as u64) =>
+            {
+                Some(Self::SequenceNumberOutdated)
+            },
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rest_client::aptos_api_types::AptosError;
+
+    fn api_error(error: AptosError) -> RestError {
+        RestError::from((error, None, reqwest::StatusCode::BAD_REQUEST))
+    }
+
+    #[test]
+    fn test_submission_failure() {
+        assert_eq!(
+            SubmissionFailure::from_error(&api_error(AptosError::new_with_error_code(
+                "mempool is full",
+                AptosErrorCode::MempoolIsFull
+            ))),
+            Some(SubmissionFailure::MempoolIsFull)
+        );
+        assert_eq!(
+            SubmissionFailure::from_error(&api_error(AptosError::new_with_vm_status(
+                "invalid transaction",
+                AptosErrorCode::VmError,
+                StatusCode::SEQUENCE_NUMBER_TOO_OLD
+            ))),
+            Some(SubmissionFailure::SequenceNumberOutdated)
+        );
+        assert_eq!(
+            SubmissionFailure::from_error(&api_error(AptosError::new_with_vm_status(
+                "invalid transaction",
+                AptosErrorCode::VmError,
+                StatusCode::INSUFFICIENT_BALANCE_FOR_TRANSACTION_FEE
+            ))),
+            None
+        );
+    }
+
+    #[test]
+    fn test_retry_backoff() {
+        let policy = RetryPolicy {
+            max_retries: 10,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(1),
+        };
+        assert_eq!(policy.backoff(0), Duration::from_millis(100));
+        assert_eq!(policy.backoff(2), Duration::from_millis(400));
+        assert_eq!(policy.backoff(4), Duration::from_secs(1));
+        assert_eq!(policy.backoff(64), Duration::from_secs(1));
+    }
+}
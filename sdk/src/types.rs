@@ -39,16 +39,31 @@ use std::{
     time::{Duration, SystemTime, UNIX_EPOCH},
 };
 
+/// A signer of the transactions of an account, whose key is not held by the SDK, e.g. a hardware
+/// wallet or a key management service. It can back a [`LocalAccount`] with
+/// [`LocalAccount::new_with_signer`], to sign through the same `TransactionBuilder` path as the
+/// other accounts.
+pub trait AccountSigner: std::fmt::Debug + Send + Sync {
+    /// The authentication key of the account, matching the authenticator of the signed
+    /// transactions.
+    fn authentication_key(&self) -> AuthenticationKey;
+
+    fn public_key(&self) -> &Ed25519PublicKey;
+
+    fn sign_transaction(&self, txn: RawTransaction) -> Result<SignedTransaction>;
+}
+
 #[derive(Debug)]
 enum LocalAccountAuthenticator {
     PrivateKey(AccountKey),
     Keyless(KeylessAccount),
     FederatedKeyless(FederatedKeylessAccount),
+    Signer(Box<dyn AccountSigner>),
 }
 
 impl LocalAccountAuthenticator {
-    pub fn sign_transaction(&self, txn: RawTransaction) -> SignedTransaction {
-        match self {
+    pub fn sign_transaction(&self, txn: RawTransaction) -> Result<SignedTransaction> {
+        Ok(match self {
             LocalAccountAuthenticator::PrivateKey(key) => txn
                 .sign(key.private_key(), key.public_key().clone())
                 .expect("Signing a txn can't fail")
@@ -65,7 +80,8 @@ impl LocalAccountAuthenticator {
                     sig,
                 )
             },
-        }
+            LocalAccountAuthenticator::Signer(signer) => signer.sign_transaction(txn)?,
+        })
     }
 
     fn build_keyless_signature(
@@ -154,6 +170,21 @@ impl LocalAccount {
         }
     }
 
+    /// Create a local representation of an account whose transactions are signed by the given
+    /// signer, e.g. a hardware wallet. The account has no private key, so signing on behalf of
+    /// other accounts (multi-agent, fee payer) or rotating its key is not supported.
+    pub fn new_with_signer<S: AccountSigner + 'static>(
+        address: AccountAddress,
+        signer: S,
+        sequence_number: u64,
+    ) -> Self {
+        Self {
+            address,
+            auth: LocalAccountAuthenticator::Signer(Box::new(signer)),
+            sequence_number: AtomicU64::new(sequence_number),
+        }
+    }
+
     /// Recover an account from derive path (e.g. m/44'/637'/0'/0'/0') and mnemonic phrase,
     pub fn from_derive_path(
         derive_path: &str,
@@ -287,15 +318,34 @@ impl LocalAccount {
     }
 
     pub fn sign_transaction(&self, txn: RawTransaction) -> SignedTransaction {
-        self.auth.sign_transaction(txn)
+        self.try_sign_transaction(txn)
+            .expect("Signing a txn failed")
     }
 
     pub fn sign_with_transaction_builder(&self, builder: TransactionBuilder) -> SignedTransaction {
+        self.try_sign_with_transaction_builder(builder)
+            .expect("Signing a txn failed")
+    }
+
+    /// Sign the transaction, which only fails for the accounts backed by an [`AccountSigner`].
+    pub fn try_sign_transaction(&self, txn: RawTransaction) -> Result<SignedTransaction> {
+        self.auth.sign_transaction(txn)
+    }
+
+    /// Sign the transaction at the next sequence number of the account, which is only consumed
+    /// if signing succeeds.
+    pub fn try_sign_with_transaction_builder(
+        &self,
+        builder: TransactionBuilder,
+    ) -> Result<SignedTransaction> {
+        let sequence_number = self.increment_sequence_number();
         let raw_txn = builder
             .sender(self.address())
-            .sequence_number(self.increment_sequence_number())
+            .sequence_number(sequence_number)
             .build();
-        self.sign_transaction(raw_txn)
+        self.try_sign_transaction(raw_txn).inspect_err(|_| {
+            self.decrement_sequence_number();
+        })
     }
 
     pub fn sign_multi_agent_with_transaction_builder(
@@ -359,11 +409,24 @@ impl LocalAccount {
         self.address
     }
 
+    /// Panics if the private key is not held by the account, see [`Self::try_private_key`].
     pub fn private_key(&self) -> &Ed25519PrivateKey {
+        self.try_private_key()
+            .expect("The private key of the account is not available")
+    }
+
+    /// The private key of the account, which is not available for the accounts backed by an
+    /// [`AccountSigner`] or by a keyless account.
+    pub fn try_private_key(&self) -> Result<&Ed25519PrivateKey> {
         match &self.auth {
-            LocalAccountAuthenticator::PrivateKey(key) => key.private_key(),
-            LocalAccountAuthenticator::Keyless(_) => todo!(),
-            LocalAccountAuthenticator::FederatedKeyless(_) => todo!(),
+            LocalAccountAuthenticator::PrivateKey(key) => Ok(key.private_key()),
+            LocalAccountAuthenticator::Keyless(_)
+            | LocalAccountAuthenticator::FederatedKeyless(_) => {
+                anyhow::bail!("The private key of a keyless account is not available")
+            },
+            LocalAccountAuthenticator::Signer(_) => {
+                anyhow::bail!("The private key of an account backed by a signer is not available")
+            },
         }
     }
 
@@ -372,6 +435,7 @@ impl LocalAccount {
             LocalAccountAuthenticator::PrivateKey(key) => key.public_key(),
             LocalAccountAuthenticator::Keyless(_) => todo!(),
             LocalAccountAuthenticator::FederatedKeyless(_) => todo!(),
+            LocalAccountAuthenticator::Signer(signer) => signer.public_key(),
         }
     }
 
@@ -384,6 +448,7 @@ impl LocalAccount {
             LocalAccountAuthenticator::FederatedKeyless(federated_keyless_account) => {
                 federated_keyless_account.authentication_key()
             },
+            LocalAccountAuthenticator::Signer(signer) => signer.authentication_key(),
         }
     }
 
@@ -404,11 +469,27 @@ impl LocalAccount {
             .store(sequence_number, Ordering::SeqCst);
     }
 
+    /// Panics if the key of the account cannot be rotated, see [`Self::try_rotate_key`].
     pub fn rotate_key<T: Into<AccountKey>>(&mut self, new_key: T) -> AccountKey {
+        self.try_rotate_key(new_key)
+            .expect("The key of the account cannot be rotated")
+    }
+
+    /// Replaces the key of the account and returns the previous one. The keys of the accounts
+    /// backed by an [`AccountSigner`] or by a keyless account are not held locally, and cannot be
+    /// rotated.
+    pub fn try_rotate_key<T: Into<AccountKey>>(&mut self, new_key: T) -> Result<AccountKey> {
         match &mut self.auth {
-            LocalAccountAuthenticator::PrivateKey(key) => std::mem::replace(key, new_key.into()),
-            LocalAccountAuthenticator::Keyless(_) => todo!(),
-            LocalAccountAuthenticator::FederatedKeyless(_) => todo!(),
+            LocalAccountAuthenticator::PrivateKey(key) => {
+                Ok(std::mem::replace(key, new_key.into()))
+            },
+            LocalAccountAuthenticator::Keyless(_)
+            | LocalAccountAuthenticator::FederatedKeyless(_) => {
+                anyhow::bail!("The key of a keyless account cannot be rotated")
+            },
+            LocalAccountAuthenticator::Signer(_) => {
+                anyhow::bail!("The key of an account backed by a signer cannot be rotated")
+            },
         }
     }
 
@@ -481,6 +562,22 @@ impl TransactionSigner for HardwareWalletAccount {
     }
 }
 
+/// A hardware wallet can back a `LocalAccount`, whose sequence number is then used instead of the
+/// one of the wallet.
+impl AccountSigner for HardwareWalletAccount {
+    fn authentication_key(&self) -> AuthenticationKey {
+        AuthenticationKey::ed25519(&self.public_key)
+    }
+
+    fn public_key(&self) -> &Ed25519PublicKey {
+        &self.public_key
+    }
+
+    fn sign_transaction(&self, txn: RawTransaction) -> Result<SignedTransaction> {
+        TransactionSigner::sign_transaction(self, txn)
+    }
+}
+
 impl HardwareWalletAccount {
     pub fn new(
         address: AccountAddress,
@@ -1080,6 +1177,62 @@ mod tests {
         assert!(LocalAccount::from_private_key("invalid_private_key", 0).is_err());
     }
 
+    /// A signer holding its key outside of the account, as a key management service would
+    #[derive(Debug)]
+    struct TestSigner {
+        key: AccountKey,
+        fail: bool,
+    }
+
+    impl AccountSigner for TestSigner {
+        fn authentication_key(&self) -> AuthenticationKey {
+            self.key.authentication_key()
+        }
+
+        fn public_key(&self) -> &Ed25519PublicKey {
+            self.key.public_key()
+        }
+
+        fn sign_transaction(&self, txn: RawTransaction) -> Result<SignedTransaction> {
+            anyhow::ensure!(!self.fail, "The signer is unavailable");
+            Ok(txn
+                .sign(self.key.private_key(), self.key.public_key().clone())?
+                .into_inner())
+        }
+    }
+
+    #[test]
+    fn test_sign_with_account_signer() {
+        let key = AccountKey::generate(&mut rand::rngs::OsRng);
+        let address = key.authentication_key().account_address();
+        let public_key = key.public_key().clone();
+        let mut account =
+            LocalAccount::new_with_signer(address, TestSigner { key, fail: false }, 7);
+        assert_eq!(account.authentication_key().account_address(), address);
+        assert_eq!(account.public_key(), &public_key);
+        // The key is held by the signer.
+        assert!(account.try_private_key().is_err());
+        assert!(account
+            .try_rotate_key(AccountKey::generate(&mut rand::rngs::OsRng))
+            .is_err());
+
+        let factory =
+            crate::transaction_builder::TransactionFactory::new(chain_id::ChainId::test());
+        let txn = account.sign_with_transaction_builder(factory.transfer(address, 1));
+        assert_eq!(txn.sender(), address);
+        assert_eq!(txn.sequence_number(), 7);
+        txn.verify_signature().unwrap();
+        assert_eq!(account.sequence_number(), 8);
+
+        // The sequence number is not consumed if signing fails
+        let key = AccountKey::generate(&mut rand::rngs::OsRng);
+        let account = LocalAccount::new_with_signer(address, TestSigner { key, fail: true }, 7);
+        assert!(account
+            .try_sign_with_transaction_builder(factory.transfer(address, 1))
+            .is_err());
+        assert_eq!(account.sequence_number(), 7);
+    }
+
     #[ignore]
     #[tokio::test]
     async fn test_derive_keyless_account() {
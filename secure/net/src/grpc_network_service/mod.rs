@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::network_controller::{metrics::NETWORK_HANDLER_TIMER, Message, MessageType};
-use aptos_logger::{error, info};
+use aptos_logger::{error, info, warn};
 use aptos_protos::remote_executor::v1::{
     network_message_service_client::NetworkMessageServiceClient,
     network_message_service_server::{NetworkMessageService, NetworkMessageServiceServer},
@@ -13,18 +13,27 @@ use std::{
     collections::HashMap,
     net::SocketAddr,
     sync::{Arc, Mutex},
+    time::Duration,
 };
 use tokio::{runtime::Runtime, sync::oneshot};
 use tonic::{
-    transport::{Channel, Server},
+    transport::{Channel, ClientTlsConfig, Endpoint, Server, ServerTlsConfig},
     Request, Response, Status,
 };
 
+pub mod tls;
+
 const MAX_MESSAGE_SIZE: usize = 1024 * 1024 * 80;
+// Connecting to the remote server is retried (with exponential backoff), e.g. if the server has not
+// started yet. After the last attempt, the error is returned. Messages themselves are never resent,
+// as they are not idempotent.
+const MAX_CONNECT_ATTEMPTS: u32 = 6;
+const INITIAL_CONNECT_RETRY_BACKOFF: Duration = Duration::from_millis(50);
 
 pub struct GRPCNetworkMessageServiceServerWrapper {
     inbound_handlers: Arc<Mutex<HashMap<MessageType, Sender<Message>>>>,
     self_addr: SocketAddr,
+    tls_config: Option<ServerTlsConfig>,
 }
 
 impl GRPCNetworkMessageServiceServerWrapper {
@@ -35,9 +44,16 @@ impl GRPCNetworkMessageServiceServerWrapper {
         Self {
             inbound_handlers,
             self_addr,
+            tls_config: None,
         }
     }
 
+    /// If set, the server requires mutual TLS.
+    pub fn with_tls_config(mut self, tls_config: Option<ServerTlsConfig>) -> Self {
+        self.tls_config = tls_config;
+        self
+    }
+
     // Note: The object is consumed here. That is once the server is started, we cannot/should not
     //       use the object anymore
     pub fn start(
@@ -65,14 +81,32 @@ impl GRPCNetworkMessageServiceServerWrapper {
             .build_v1()
             .unwrap();
 
-        info!("Starting Server async at {:?}", server_addr);
+        let tls_enabled = self.tls_config.is_some();
+        let mut server_builder = Server::builder();
+        if let Some(tls_config) = self.tls_config.clone() {
+            server_builder = match server_builder.tls_config(tls_config) {
+                Ok(server_builder) => server_builder,
+                Err(e) => {
+                    error!(
+                        "Failed to configure TLS for the server at {:?}, not starting it: {}",
+                        server_addr, e
+                    );
+                    return;
+                },
+            };
+        }
+
+        info!(
+            "Starting Server async at {:?} (TLS: {})",
+            server_addr, tls_enabled
+        );
         // NOTE: (1) serve_with_shutdown() starts the server, if successful the task does not return
         //           till the server is shutdown. Hence this should be called as a separate
         //           non-blocking task. Signal handler 'server_shutdown_rx' is needed to shutdown
         //           the server
         //       (2) There is no easy way to know if/when the server has started successfully. Hence
         //           we may need to implement a healthcheck service to check if the server is up
-        Server::builder()
+        server_builder
             .timeout(std::time::Duration::from_millis(rpc_timeout_ms))
             .add_service(
                 NetworkMessageServiceServer::new(self).max_decoding_message_size(MAX_MESSAGE_SIZE),
@@ -104,7 +138,12 @@ impl NetworkMessageService for GRPCNetworkMessageServiceServerWrapper {
 
         if let Some(handler) = self.inbound_handlers.lock().unwrap().get(&message_type) {
             // Send the message to the registered handler
-            handler.send(msg).unwrap();
+            if handler.send(msg).is_err() {
+                return Err(Status::unavailable(format!(
+                    "Handler for msg type {:?} was dropped",
+                    message_type
+                )));
+            }
         } else {
             error!(
                 "No handler registered for sender: {:?} and msg type {:?}",
@@ -117,56 +156,115 @@ impl NetworkMessageService for GRPCNetworkMessageServiceServerWrapper {
 
 pub struct GRPCNetworkMessageServiceClientWrapper {
     remote_addr: String,
-    remote_channel: NetworkMessageServiceClient<Channel>,
+    endpoint: Endpoint,
+    // Established on the first send, so that the remote server does not need to be up when the
+    // client is created.
+    remote_channel: Option<NetworkMessageServiceClient<Channel>>,
 }
 
 impl GRPCNetworkMessageServiceClientWrapper {
-    pub fn new(rt: &Runtime, remote_addr: SocketAddr) -> Self {
-        Self {
-            remote_addr: remote_addr.to_string(),
-            remote_channel: rt
-                .block_on(async { Self::get_channel(format!("http://{}", remote_addr)).await }),
+    pub fn new(remote_addr: SocketAddr) -> Self {
+        Self::new_with_tls(remote_addr, None).expect("Creating a client without TLS must succeed")
+    }
+
+    /// If the TLS config is set, the client connects to the remote server using mutual TLS.
+    pub fn new_with_tls(
+        remote_addr: SocketAddr,
+        tls_config: Option<ClientTlsConfig>,
+    ) -> Result<Self, tonic::transport::Error> {
+        let scheme = if tls_config.is_some() {
+            "https"
+        } else {
+            "http"
+        };
+        let mut endpoint = Endpoint::new(format!("{}://{}", scheme, remote_addr))?;
+        if let Some(tls_config) = tls_config {
+            endpoint = endpoint.tls_config(tls_config)?;
         }
+        Ok(Self {
+            remote_addr: remote_addr.to_string(),
+            endpoint,
+            remote_channel: None,
+        })
     }
 
-    async fn get_channel(remote_addr: String) -> NetworkMessageServiceClient<Channel> {
-        info!("Trying to connect to remote server at {:?}", remote_addr);
-        let conn = tonic::transport::Endpoint::new(remote_addr)
-            .unwrap()
-            .connect_lazy();
-        NetworkMessageServiceClient::new(conn).max_decoding_message_size(MAX_MESSAGE_SIZE)
+    /// Connects to the remote server, retrying on failures. Nothing has been sent at this point,
+    /// so retrying is always safe.
+    async fn connect(
+        endpoint: &Endpoint,
+        remote_addr: &str,
+    ) -> Result<NetworkMessageServiceClient<Channel>, Status> {
+        let mut backoff = INITIAL_CONNECT_RETRY_BACKOFF;
+        let mut attempt = 1;
+        loop {
+            info!("Trying to connect to remote server at {}", remote_addr);
+            match endpoint.connect().await {
+                Ok(channel) => {
+                    return Ok(NetworkMessageServiceClient::new(channel)
+                        .max_decoding_message_size(MAX_MESSAGE_SIZE))
+                },
+                Err(e) if attempt < MAX_CONNECT_ATTEMPTS => {
+                    warn!(
+                        "Error '{}' on attempt {} to connect to {}, retrying in {:?}",
+                        e, attempt, remote_addr, backoff
+                    );
+                    tokio::time::sleep(backoff).await;
+                    backoff *= 2;
+                    attempt += 1;
+                },
+                Err(e) => {
+                    return Err(Status::unavailable(format!(
+                        "Failed to connect to {} after {} attempts: {}",
+                        remote_addr, attempt, e
+                    )))
+                },
+            }
+        }
     }
 
+    /// Sends the message, connecting to the remote server first if needed. The message is sent at
+    /// most once, and the error is returned if it could not be delivered.
     pub async fn send_message(
         &mut self,
         sender_addr: SocketAddr,
         message: Message,
         mt: &MessageType,
-    ) {
+    ) -> Result<(), Status> {
+        let remote_channel = match &mut self.remote_channel {
+            Some(remote_channel) => remote_channel,
+            slot @ None => {
+                let remote_channel = Self::connect(&self.endpoint, &self.remote_addr)
+                    .await
+                    .inspect_err(|e| {
+                        error!(
+                            "Error '{}' connecting to {} on node {:?}",
+                            e, self.remote_addr, sender_addr
+                        )
+                    })?;
+                slot.insert(remote_channel)
+            },
+        };
         let request = tonic::Request::new(NetworkMessage {
             message: message.data,
             message_type: mt.get_type(),
         });
-        // TODO: Retry with exponential backoff on failures
-        match self.remote_channel.simple_msg_exchange(request).await {
-            Ok(_) => {},
-            Err(e) => {
-                panic!(
+        remote_channel
+            .simple_msg_exchange(request)
+            .await
+            .map(|_| ())
+            .inspect_err(|e| {
+                error!(
                     "Error '{}' sending message to {} on node {:?}",
                     e, self.remote_addr, sender_addr
-                );
-            },
-        }
+                )
+            })
     }
 }
 
 #[test]
 fn basic_test() {
     use aptos_config::utils;
-    use std::{
-        net::{IpAddr, Ipv4Addr},
-        thread,
-    };
+    use std::net::{IpAddr, Ipv4Addr};
 
     let server_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), utils::get_available_port());
     let message_type = "test_type".to_string();
@@ -190,16 +288,11 @@ fn basic_test() {
         server_shutdown_rx,
     );
 
-    let mut grpc_client = GRPCNetworkMessageServiceClientWrapper::new(&rt, server_addr);
+    let mut grpc_client = GRPCNetworkMessageServiceClientWrapper::new(server_addr);
 
     let client_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), utils::get_available_port());
     let test_message_content = "test1".as_bytes().to_vec();
 
-    // wait for the server to be ready before sending messages
-    // TODO: We need to implement retry on send_message failures such that we can pass this test
-    //       without this sleep
-    thread::sleep(std::time::Duration::from_millis(10));
-
     for _ in 0..2 {
         rt.block_on(async {
             grpc_client
@@ -208,7 +301,8 @@ fn basic_test() {
                     Message::new(test_message_content.clone()),
                     &MessageType::new(message_type.clone()),
                 )
-                .await;
+                .await
+                .unwrap();
         });
     }
 
@@ -218,3 +312,26 @@ fn basic_test() {
     }
     server_shutdown_tx.send(()).unwrap();
 }
+
+#[test]
+fn send_to_unreachable_server_returns_error() {
+    use aptos_config::utils;
+    use std::net::{IpAddr, Ipv4Addr};
+
+    // No server is listening at the address, so connecting fails after the retries.
+    let server_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), utils::get_available_port());
+    let client_addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), utils::get_available_port());
+    let rt = Runtime::new().unwrap();
+    let mut grpc_client = GRPCNetworkMessageServiceClientWrapper::new(server_addr);
+
+    let result = rt.block_on(async {
+        grpc_client
+            .send_message(
+                client_addr,
+                Message::new(vec![1]),
+                &MessageType::new("test_type".to_string()),
+            )
+            .await
+    });
+    assert_eq!(result.unwrap_err().code(), tonic::Code::Unavailable);
+}
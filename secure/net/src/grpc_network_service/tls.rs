@@ -0,0 +1,94 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use serde::{Deserialize, Serialize};
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+use tonic::transport::{Certificate, ClientTlsConfig, Identity, ServerTlsConfig};
+
+/// Mutual TLS configuration for the GRPC network service. All nodes use certificates signed by
+/// the same CA: servers only accept clients with such a certificate, and clients only connect to
+/// servers with such a certificate.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct GRPCTlsConfig {
+    /// PEM-encoded certificate of the CA.
+    pub ca_cert_path: PathBuf,
+    /// PEM-encoded certificate of this node, signed by the CA.
+    pub cert_path: PathBuf,
+    /// PEM-encoded private key of this node.
+    pub key_path: PathBuf,
+    /// Domain name the certificates are issued for. Since nodes are addressed by their socket
+    /// addresses, servers are verified against this name instead.
+    pub domain_name: String,
+}
+
+/// The server and client TLS configs, with the certificates and the key loaded.
+#[derive(Clone, Debug)]
+pub struct LoadedGRPCTlsConfig {
+    pub server: ServerTlsConfig,
+    pub client: ClientTlsConfig,
+}
+
+impl GRPCTlsConfig {
+    /// Loads the certificates and the key, failing if any of the files can't be read.
+    pub fn load(&self) -> io::Result<LoadedGRPCTlsConfig> {
+        Ok(LoadedGRPCTlsConfig {
+            server: self.server_tls_config()?,
+            client: self.client_tls_config()?,
+        })
+    }
+
+    fn ca_certificate(&self) -> io::Result<Certificate> {
+        Ok(Certificate::from_pem(read(&self.ca_cert_path)?))
+    }
+
+    fn identity(&self) -> io::Result<Identity> {
+        Ok(Identity::from_pem(
+            read(&self.cert_path)?,
+            read(&self.key_path)?,
+        ))
+    }
+
+    pub fn server_tls_config(&self) -> io::Result<ServerTlsConfig> {
+        Ok(ServerTlsConfig::new()
+            .identity(self.identity()?)
+            .client_ca_root(self.ca_certificate()?))
+    }
+
+    pub fn client_tls_config(&self) -> io::Result<ClientTlsConfig> {
+        Ok(ClientTlsConfig::new()
+            .ca_certificate(self.ca_certificate()?)
+            .identity(self.identity()?)
+            .domain_name(self.domain_name.clone()))
+    }
+}
+
+fn read(path: &Path) -> io::Result<Vec<u8>> {
+    fs::read(path).map_err(|error| {
+        io::Error::new(
+            error.kind(),
+            format!("Failed to read the TLS file {:?}: {}", path, error),
+        )
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_load_missing_files() {
+        let config = GRPCTlsConfig {
+            ca_cert_path: PathBuf::from("/nonexistent/ca.pem"),
+            cert_path: PathBuf::from("/nonexistent/node.pem"),
+            key_path: PathBuf::from("/nonexistent/node.key"),
+            domain_name: "executor".to_string(),
+        };
+        let error = config.load().unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::NotFound);
+        assert!(error.to_string().contains("/nonexistent/"));
+    }
+}
@@ -13,21 +13,29 @@ use std::{
     sync::{Arc, Mutex},
 };
 use tokio::{runtime::Runtime, sync::oneshot};
+use tonic::transport::ServerTlsConfig;
 
 pub struct InboundHandler {
     service: String,
     listen_addr: SocketAddr,
     rpc_timeout_ms: u64,
     inbound_handlers: Arc<Mutex<HashMap<MessageType, Sender<Message>>>>,
+    tls_config: Option<ServerTlsConfig>,
 }
 
 impl InboundHandler {
-    pub fn new(service: String, listen_addr: SocketAddr, rpc_timeout_ms: u64) -> Self {
+    pub fn new(
+        service: String,
+        listen_addr: SocketAddr,
+        rpc_timeout_ms: u64,
+        tls_config: Option<ServerTlsConfig>,
+    ) -> Self {
         Self {
             service: service.clone(),
             listen_addr,
             rpc_timeout_ms,
             inbound_handlers: Arc::new(Mutex::new(HashMap::new())),
+            tls_config,
         }
     }
 
@@ -52,6 +60,7 @@ impl InboundHandler {
             self.inbound_handlers.clone(),
             self.listen_addr,
         )
+        .with_tls_config(self.tls_config.clone())
         .start(
             rt,
             self.service.clone(),
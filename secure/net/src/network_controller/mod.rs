@@ -1,13 +1,15 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::network_controller::{
-    inbound_handler::InboundHandler, outbound_handler::OutboundHandler,
+use crate::{
+    grpc_network_service::tls::GRPCTlsConfig,
+    network_controller::{inbound_handler::InboundHandler, outbound_handler::OutboundHandler},
 };
 use aptos_logger::{info, warn};
 use crossbeam_channel::{unbounded, Receiver, Sender};
 use serde::{Deserialize, Serialize};
 use std::{
+    io,
     net::SocketAddr,
     sync::{Arc, Mutex},
 };
@@ -92,14 +94,39 @@ pub struct NetworkController {
 
 impl NetworkController {
     pub fn new(service: String, listen_addr: SocketAddr, timeout_ms: u64) -> Self {
+        Self::new_with_tls(service, listen_addr, timeout_ms, None)
+            .expect("Creating a network controller without TLS must succeed")
+    }
+
+    /// If the TLS config is set, all communication (inbound and outbound) uses mutual TLS. Fails
+    /// if the certificates or the key of the TLS config can't be loaded.
+    pub fn new_with_tls(
+        service: String,
+        listen_addr: SocketAddr,
+        timeout_ms: u64,
+        tls_config: Option<GRPCTlsConfig>,
+    ) -> io::Result<Self> {
+        let (server_tls_config, client_tls_config) = match tls_config {
+            Some(tls_config) => {
+                let loaded = tls_config.load()?;
+                (Some(loaded.server), Some(loaded.client))
+            },
+            None => (None, None),
+        };
         let inbound_handler = Arc::new(Mutex::new(InboundHandler::new(
             service.clone(),
             listen_addr,
             timeout_ms,
+            server_tls_config,
         )));
-        let outbound_handler = OutboundHandler::new(service, listen_addr, inbound_handler.clone());
+        let outbound_handler = OutboundHandler::new(
+            service,
+            listen_addr,
+            inbound_handler.clone(),
+            client_tls_config,
+        );
         info!("Network controller created for node {}", listen_addr);
-        Self {
+        Ok(Self {
             inbound_handler,
             outbound_handler,
             inbound_rpc_runtime: Runtime::new().unwrap(),
@@ -108,7 +135,7 @@ impl NetworkController {
             inbound_server_shutdown_tx: None,
             outbound_task_shutdown_tx: None,
             listen_addr,
-        }
+        })
     }
 
     pub fn create_outbound_channel(
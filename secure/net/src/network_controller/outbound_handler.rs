@@ -7,7 +7,7 @@ use crate::{
         inbound_handler::InboundHandler, metrics::NETWORK_HANDLER_TIMER, Message, MessageType,
     },
 };
-use aptos_logger::{info, warn};
+use aptos_logger::{error, info, warn};
 use crossbeam_channel::{unbounded, Receiver, Select, Sender};
 use std::{
     collections::{HashMap, HashSet},
@@ -16,6 +16,7 @@ use std::{
     sync::{Arc, Mutex},
 };
 use tokio::runtime::Runtime;
+use tonic::transport::ClientTlsConfig;
 
 pub struct OutboundHandler {
     _service: String,
@@ -24,6 +25,7 @@ pub struct OutboundHandler {
     // Used to route outgoing messages to correct network client with the correct message type
     handlers: Vec<(Receiver<Message>, SocketAddr, MessageType)>,
     inbound_handler: Arc<Mutex<InboundHandler>>,
+    tls_config: Option<ClientTlsConfig>,
 }
 
 impl OutboundHandler {
@@ -31,6 +33,7 @@ impl OutboundHandler {
         service: String,
         listen_addr: SocketAddr,
         inbound_handler: Arc<Mutex<InboundHandler>>,
+        tls_config: Option<ClientTlsConfig>,
     ) -> Self {
         Self {
             _service: service,
@@ -38,6 +41,7 @@ impl OutboundHandler {
             address: listen_addr,
             handlers: Vec::new(),
             inbound_handler,
+            tls_config,
         }
     }
 
@@ -65,14 +69,23 @@ impl OutboundHandler {
             MessageType::new("stop_task".to_string()),
         ));
 
-        // Create a grpc client for each remote address
+        // Create a grpc client for each remote address. The messages to an address without a client
+        // are dropped (and logged).
         let mut grpc_clients: HashMap<SocketAddr, GRPCNetworkMessageServiceClientWrapper> =
             HashMap::new();
         self.remote_addresses.iter().for_each(|remote_addr| {
-            grpc_clients.insert(
+            match GRPCNetworkMessageServiceClientWrapper::new_with_tls(
                 *remote_addr,
-                GRPCNetworkMessageServiceClientWrapper::new(rt, *remote_addr),
-            );
+                self.tls_config.clone(),
+            ) {
+                Ok(grpc_client) => {
+                    grpc_clients.insert(*remote_addr, grpc_client);
+                },
+                Err(e) => error!(
+                    "Failed to create the client for remote address {}: {}",
+                    remote_addr, e
+                ),
+            }
         });
 
         // Prepare for objects to be moved into the async block (&mut self cannot be moved into the
@@ -151,12 +164,17 @@ impl OutboundHandler {
                     .lock()
                     .unwrap()
                     .send_incoming_message_to_handler(message_type, msg);
-            } else {
-                grpc_clients
-                    .get_mut(remote_addr)
-                    .unwrap()
+            } else if let Some(grpc_client) = grpc_clients.get_mut(remote_addr) {
+                // The errors are logged by the client, the receiver is expected to detect the
+                // missing message (e.g. with a timeout).
+                let _ = grpc_client
                     .send_message(*socket_addr, msg, message_type)
                     .await;
+            } else {
+                error!(
+                    "No client for remote address {}, dropping msg type {:?}",
+                    remote_addr, message_type
+                );
             }
         }
     }
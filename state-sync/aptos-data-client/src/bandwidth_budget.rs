@@ -0,0 +1,154 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{error::Error, metrics, peer_states::is_rate_limited_request};
+use aptos_config::{
+    config::AptosDataClientConfig,
+    network_id::{NetworkId, PeerNetworkId},
+};
+use aptos_infallible::Mutex;
+use aptos_storage_service_types::{
+    requests::StorageServiceRequest, responses::StorageServiceResponse,
+};
+use aptos_time_service::{TimeService, TimeServiceTrait};
+use std::{collections::HashMap, sync::Arc, time::Instant};
+
+/// The bandwidth budget of a single network
+#[derive(Debug)]
+struct NetworkBudget {
+    /// The number of response bytes that can currently be received. This
+    /// can go negative, as responses are only accounted for once received.
+    available_bytes: i64,
+
+    /// The time at which the budget was last refilled
+    last_refill_time: Instant,
+}
+
+/// A token-bucket bandwidth budget for each network. Data requests are
+/// only sent on a network if it has budget remaining, and the size of
+/// each response is deducted from the budget of the network it arrived
+/// on. This prevents state sync from starving the other traffic on the
+/// network (e.g., consensus and mempool) when catching up.
+#[derive(Clone, Debug)]
+pub struct BandwidthBudgets {
+    /// The data client configuration
+    data_client_config: Arc<AptosDataClientConfig>,
+
+    /// The budgets of each network
+    network_budgets: Arc<Mutex<HashMap<NetworkId, NetworkBudget>>>,
+
+    /// The time service used to refill the budgets
+    time_service: TimeService,
+}
+
+impl BandwidthBudgets {
+    pub fn new(data_client_config: Arc<AptosDataClientConfig>, time_service: TimeService) -> Self {
+        Self {
+            data_client_config,
+            network_budgets: Arc::new(Mutex::new(HashMap::new())),
+            time_service,
+        }
+    }
+
+    /// Returns true iff the given request can be sent to the peer, i.e., the
+    /// request is not budgeted, or the peer's network has budget remaining.
+    pub fn has_budget(&self, peer: &PeerNetworkId, request: &StorageServiceRequest) -> bool {
+        // Only budget specific data requests (if the config allows it)
+        if !self
+            .data_client_config
+            .bandwidth_budget_config
+            .enable_bandwidth_budget
+            || !is_rate_limited_request(request)
+        {
+            return true;
+        }
+
+        // Check if the network has budget remaining
+        let network_id = peer.network_id();
+        let available_bytes = self.refill_budget(network_id);
+        if available_bytes <= 0 {
+            metrics::increment_request_counter(
+                &metrics::BANDWIDTH_LIMITED_REQUESTS,
+                &request.get_label(),
+                *peer,
+            );
+            return false;
+        }
+
+        true
+    }
+
+    /// Deducts the size of the response from the budget of the peer's network
+    pub fn consume_budget(
+        &self,
+        peer: &PeerNetworkId,
+        response: &StorageServiceResponse,
+    ) -> Result<(), Error> {
+        if !self
+            .data_client_config
+            .bandwidth_budget_config
+            .enable_bandwidth_budget
+        {
+            return Ok(());
+        }
+
+        // Calculate the size of the response
+        let num_bytes = response
+            .get_num_bytes()
+            .map_err(|error| Error::UnexpectedErrorEncountered(error.to_string()))?;
+
+        // Refill the budget and deduct the bytes
+        let network_id = peer.network_id();
+        self.refill_budget(network_id);
+        let mut network_budgets = self.network_budgets.lock();
+        if let Some(network_budget) = network_budgets.get_mut(&network_id) {
+            network_budget.available_bytes = network_budget
+                .available_bytes
+                .saturating_sub(num_bytes as i64);
+            update_budget_metrics(network_id, network_budget.available_bytes);
+        }
+
+        Ok(())
+    }
+
+    /// Refills the network's budget based on the time elapsed since the
+    /// last refill, and returns the number of bytes available.
+    fn refill_budget(&self, network_id: NetworkId) -> i64 {
+        let bandwidth_budget_config = self.data_client_config.bandwidth_budget_config;
+        let max_bytes_per_second = bandwidth_budget_config.max_bytes_per_second(network_id);
+        let max_burst_bytes = max_bytes_per_second
+            .saturating_mul(bandwidth_budget_config.max_burst_secs)
+            .min(i64::MAX as u64) as i64;
+
+        // Get the network budget (the budget of a new network starts full)
+        let now = self.time_service.now();
+        let mut network_budgets = self.network_budgets.lock();
+        let network_budget = network_budgets
+            .entry(network_id)
+            .or_insert_with(|| NetworkBudget {
+                available_bytes: max_burst_bytes,
+                last_refill_time: now,
+            });
+
+        // Refill the budget (up to the burst size)
+        let elapsed_secs = now
+            .duration_since(network_budget.last_refill_time)
+            .as_secs_f64();
+        let refilled_bytes = (elapsed_secs * max_bytes_per_second as f64) as i64;
+        network_budget.available_bytes = network_budget
+            .available_bytes
+            .saturating_add(refilled_bytes)
+            .min(max_burst_bytes);
+        network_budget.last_refill_time = now;
+        update_budget_metrics(network_id, network_budget.available_bytes);
+
+        network_budget.available_bytes
+    }
+}
+
+/// Updates the remaining budget metrics for the given network
+fn update_budget_metrics(network_id: NetworkId, available_bytes: i64) {
+    metrics::BANDWIDTH_BUDGET_BYTES
+        .with_label_values(&[network_id.as_str()])
+        .set(available_bytes);
+}
@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    bandwidth_budget::BandwidthBudgets,
     error::Error,
     global_summary::GlobalDataSummary,
     interface::{
@@ -97,6 +98,8 @@ pub struct AptosDataClient {
     storage_service_client: StorageServiceClient<NetworkClient<StorageServiceMessage>>,
     /// The state of the active subscription stream.
     active_subscription_state: Arc<Mutex<Option<SubscriptionState>>>,
+    /// The bandwidth budgets of each network.
+    bandwidth_budgets: BandwidthBudgets,
     /// All of the data-client specific data we have on each network peer.
     peer_states: Arc<PeerStates>,
     /// A cached, aggregate data summary of all unbanned peers' data summaries.
@@ -126,6 +129,10 @@ impl AptosDataClient {
             data_client_config: data_client_config.clone(),
             storage_service_client: storage_service_client.clone(),
             active_subscription_state: Arc::new(Mutex::new(None)),
+            bandwidth_budgets: BandwidthBudgets::new(
+                data_client_config.clone(),
+                time_service.clone(),
+            ),
             peer_states: Arc::new(PeerStates::new(data_client_config.clone())),
             global_summary_cache: Arc::new(ArcSwap::from(Arc::new(GlobalDataSummary::empty()))),
             response_id_generator: Arc::new(U64IdGenerator::new()),
@@ -541,6 +548,7 @@ impl AptosDataClient {
             .filter(|peer| {
                 self.peer_states
                     .can_service_request(peer, self.time_service.clone(), request)
+                    && self.bandwidth_budgets.has_budget(peer, request)
             })
             .collect()
     }
@@ -794,6 +802,18 @@ impl AptosDataClient {
                 // Update the received response metrics
                 self.update_received_response_metrics(peer, &request);
 
+                // Deduct the response from the network's bandwidth budget
+                if let Err(error) = self.bandwidth_budgets.consume_budget(&peer, &response) {
+                    warn!(
+                        (LogSchema::new(LogEntry::StorageServiceResponse)
+                            .event(LogEvent::ResponseError)
+                            .request_type(&request.get_label())
+                            .request_id(id)
+                            .peer(&peer)
+                            .error(&error))
+                    );
+                }
+
                 // For now, record all responses that at least pass the data
                 // client layer successfully. An alternative might also have the
                 // consumer notify both success and failure via the callback.
@@ -3,6 +3,7 @@
 
 #![forbid(unsafe_code)]
 
+mod bandwidth_budget;
 pub mod client;
 pub mod error;
 pub mod global_summary;
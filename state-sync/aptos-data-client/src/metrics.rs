@@ -49,6 +49,36 @@ pub static ERROR_RESPONSES: Lazy<IntCounterVec> = Lazy::new(|| {
     .unwrap()
 });
 
+/// Counter for tracking the peers skipped for requests because of the per-peer rate limits
+pub static RATE_LIMITED_REQUESTS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_data_client_rate_limited_requests",
+        "Counters related to peers skipped for requests because of rate limits",
+        &["request_types", "network"]
+    )
+    .unwrap()
+});
+
+/// Counter for tracking the requests delayed because of the network bandwidth budgets
+pub static BANDWIDTH_LIMITED_REQUESTS: Lazy<IntCounterVec> = Lazy::new(|| {
+    register_int_counter_vec!(
+        "aptos_data_client_bandwidth_limited_requests",
+        "Counters related to requests delayed because of the network bandwidth budgets",
+        &["request_types", "network"]
+    )
+    .unwrap()
+});
+
+/// Gauge for tracking the remaining bandwidth budget (in bytes) of each network
+pub static BANDWIDTH_BUDGET_BYTES: Lazy<IntGaugeVec> = Lazy::new(|| {
+    register_int_gauge_vec!(
+        "aptos_data_client_bandwidth_budget_bytes",
+        "Gauge related to the remaining bandwidth budget of each network",
+        &["network"]
+    )
+    .unwrap()
+});
+
 // Buckets for tracking the number of multi-fetches sent per request
 const MULTI_FETCH_BUCKETS: &[f64] = &[
     1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0, 9.0, 10.0, 15.0, 20.0, 30.0, 40.0, 50.0, 60.0, 70.0,
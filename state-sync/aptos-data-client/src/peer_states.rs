@@ -15,13 +15,13 @@ use aptos_logger::prelude::*;
 use aptos_storage_service_types::{
     requests::StorageServiceRequest, responses::StorageServerSummary,
 };
-use aptos_time_service::TimeService;
+use aptos_time_service::{TimeService, TimeServiceTrait};
 use dashmap::DashMap;
 use std::{
     cmp::min,
     collections::{BTreeMap, HashSet},
     sync::Arc,
-    time::Duration,
+    time::{Duration, Instant},
 };
 
 // Useful constants
@@ -79,16 +79,28 @@ pub struct PeerState {
 
     /// For now, a simplified port of the original state-sync v1 scoring system.
     score: f64,
+
+    /// The number of data requests that can currently be sent to this peer
+    /// (i.e., the tokens in the peer's rate limiting bucket).
+    request_tokens: f64,
+
+    /// The time at which the request tokens were last refilled (if any)
+    last_token_refill_time: Option<Instant>,
 }
 
 impl PeerState {
     pub fn new(data_client_config: Arc<AptosDataClientConfig>) -> Self {
+        let request_tokens = data_client_config
+            .peer_rate_limit_config
+            .max_burst_requests_per_peer as f64;
         Self {
             data_client_config,
             received_responses_by_type: Arc::new(DashMap::new()),
             sent_requests_by_type: Arc::new(DashMap::new()),
             storage_summary: None,
             score: STARTING_SCORE,
+            request_tokens,
+            last_token_refill_time: None,
         }
     }
 }
@@ -173,6 +185,37 @@ impl PeerState {
         self.score = f64::max(self.score * multiplier, MIN_SCORE);
     }
 
+    /// Returns true iff the peer is currently rate limited (i.e., too
+    /// many data requests have been sent to the peer recently).
+    fn is_rate_limited(&mut self, time_service: &TimeService) -> bool {
+        // Only rate limit peers if the config allows it
+        let peer_rate_limit_config = self.data_client_config.peer_rate_limit_config;
+        if !peer_rate_limit_config.enable_peer_rate_limiting {
+            return false;
+        }
+
+        // Refill the request tokens based on the time elapsed since the last refill
+        let now = time_service.now();
+        if let Some(last_token_refill_time) = self.last_token_refill_time {
+            let elapsed_secs = now.duration_since(last_token_refill_time).as_secs_f64();
+            let refilled_tokens =
+                elapsed_secs * peer_rate_limit_config.max_requests_per_peer_per_second as f64;
+            self.request_tokens = f64::min(
+                self.request_tokens + refilled_tokens,
+                peer_rate_limit_config.max_burst_requests_per_peer as f64,
+            );
+        }
+        self.last_token_refill_time = Some(now);
+
+        // The peer is rate limited if there are no tokens left
+        self.request_tokens < 1.0
+    }
+
+    /// Consumes a request token for a data request sent to the peer
+    fn consume_request_token(&mut self) {
+        self.request_tokens = f64::max(self.request_tokens - 1.0, 0.0);
+    }
+
     /// Updates the storage summary for the peer
     fn update_storage_summary(&mut self, storage_summary: StorageServerSummary) {
         self.storage_summary = Some(storage_summary);
@@ -213,13 +256,31 @@ impl PeerStates {
         }
 
         // Check if the peer can service the request
-        if let Some(peer_state) = self.peer_to_state.get(peer) {
-            return match peer_state.get_storage_summary_if_not_ignored() {
-                Some(storage_summary) => {
-                    storage_summary.can_service(&self.data_client_config, time_service, request)
-                },
+        if let Some(mut peer_state) = self.peer_to_state.get_mut(peer) {
+            let can_service = match peer_state.get_storage_summary_if_not_ignored() {
+                Some(storage_summary) => storage_summary.can_service(
+                    &self.data_client_config,
+                    time_service.clone(),
+                    request,
+                ),
                 None => false, // The peer is temporarily ignored
             };
+
+            // Avoid sending specific data requests to rate limited peers. Optimistic
+            // fetch and subscription requests are long-lived, so they're not limited.
+            if can_service
+                && is_rate_limited_request(request)
+                && peer_state.is_rate_limited(&time_service)
+            {
+                metrics::increment_request_counter(
+                    &metrics::RATE_LIMITED_REQUESTS,
+                    &request.get_label(),
+                    *peer,
+                );
+                return false;
+            }
+
+            return can_service;
         }
 
         // Otherwise, the request cannot be serviced
@@ -250,9 +311,12 @@ impl PeerStates {
         // Get the data request label
         let request_label = request.data_request.get_label().into();
 
-        // Update the peer's counter
+        // Update the peer's counter (and consume a request token, if required)
         if let Some(mut entry) = self.peer_to_state.get_mut(&peer) {
             entry.increment_sent_request_counter(request_label);
+            if is_rate_limited_request(request) {
+                entry.consume_request_token();
+            }
         }
     }
 
@@ -413,6 +477,16 @@ impl PeerStates {
     }
 }
 
+/// Returns true iff the given request counts towards the per-peer rate
+/// limits (and the network bandwidth budgets).
+pub(crate) fn is_rate_limited_request(request: &StorageServiceRequest) -> bool {
+    let data_request = &request.data_request;
+    !(data_request.is_storage_summary_request()
+        || data_request.is_protocol_version_request()
+        || data_request.is_optimistic_fetch()
+        || data_request.is_subscription_request())
+}
+
 /// To calculate the optimal chunk size, we take the median for each
 /// chunk size parameter. This works well when we have an honest
 /// majority that mostly agrees on the same chunk sizes.
@@ -11,12 +11,15 @@ use crate::{
     tests::{mock::MockNetwork, utils},
 };
 use aptos_config::{
-    config::{AptosDataClientConfig, AptosDataMultiFetchConfig},
+    config::{
+        AptosDataBandwidthBudgetConfig, AptosDataClientConfig, AptosDataMultiFetchConfig,
+        AptosDataPeerRateLimitConfig,
+    },
     network_id::{NetworkId, PeerNetworkId},
 };
 use aptos_storage_service_server::network::NetworkRequest;
 use aptos_storage_service_types::{
-    requests::DataRequest,
+    requests::{DataRequest, StorageServiceRequest, TransactionsWithProofRequest},
     responses::{CompleteDataRange, DataResponse, StorageServerSummary, StorageServiceResponse},
     StorageServiceError,
 };
@@ -521,6 +524,130 @@ async fn disconnected_peers_garbage_collection() {
     }
 }
 
+#[tokio::test]
+async fn network_without_bandwidth_budget_is_skipped() {
+    // Ensure the properties hold for all peer priorities
+    for peer_priority in PeerPriority::get_all_ordered_priorities() {
+        // Create a base config for a validator
+        let base_config = utils::create_validator_base_config();
+
+        // Create a data client config with a tiny bandwidth budget (1 byte per second)
+        let data_client_config = AptosDataClientConfig {
+            bandwidth_budget_config: AptosDataBandwidthBudgetConfig {
+                enable_bandwidth_budget: true,
+                max_burst_secs: 1,
+                public_network_max_bytes_per_second: 1,
+                validator_network_max_bytes_per_second: 1,
+                vfn_network_max_bytes_per_second: 1,
+            },
+            ..Default::default()
+        };
+
+        // Create the mock network, mock time and client
+        let (mut mock_network, mock_time, client, _) =
+            MockNetwork::new(Some(base_config), Some(data_client_config), None);
+
+        // Add a connected peer and advertise data for it
+        let (peer, network_id) = utils::add_peer_to_network(peer_priority, &mut mock_network);
+        client.update_peer_storage_summary(peer, utils::create_storage_summary(200));
+
+        // Create a storage request for transactions
+        let storage_request = StorageServiceRequest::new(
+            DataRequest::GetTransactionsWithProof(TransactionsWithProofRequest {
+                proof_version: 100,
+                start_version: 0,
+                end_version: 100,
+                include_events: false,
+            }),
+            true,
+        );
+
+        // Verify the peer is selected (the budget is not yet used)
+        utils::verify_selected_peers_match(&client, hashset![peer], &storage_request);
+
+        // Handle the client's transaction request
+        tokio::spawn(async move {
+            let network_request = utils::get_network_request(&mut mock_network, network_id).await;
+            utils::handle_transactions_request(network_request, true);
+        });
+
+        // Request transactions and verify the request succeeds
+        let request_timeout = data_client_config.response_timeout_ms;
+        client
+            .get_transactions_with_proof(100, 0, 100, false, request_timeout)
+            .await
+            .unwrap();
+
+        // Verify the request is now unserviceable (the response used up the budget)
+        utils::verify_request_is_unserviceable(&client, &storage_request, false);
+
+        // Verify that storage summary requests are not budgeted
+        let summary_request =
+            StorageServiceRequest::new(DataRequest::GetStorageServerSummary, true);
+        utils::verify_selected_peers_match(&client, hashset![peer], &summary_request);
+
+        // Elapse some time and verify the peer is selected again (after the budget is refilled)
+        mock_time.advance_secs(1000);
+        utils::verify_selected_peers_match(&client, hashset![peer], &storage_request);
+    }
+}
+
+#[tokio::test]
+async fn rate_limited_peer_is_skipped() {
+    // Ensure the properties hold for all peer priorities
+    for peer_priority in PeerPriority::get_all_ordered_priorities() {
+        // Create a base config for a validator
+        let base_config = utils::create_validator_base_config();
+
+        // Create a data client config with peer rate limiting enabled
+        let max_burst_requests_per_peer = 5;
+        let data_client_config = AptosDataClientConfig {
+            peer_rate_limit_config: AptosDataPeerRateLimitConfig {
+                enable_peer_rate_limiting: true,
+                max_burst_requests_per_peer,
+                max_requests_per_peer_per_second: 2,
+            },
+            ..Default::default()
+        };
+
+        // Create the mock network, mock time and client
+        let (mut mock_network, mock_time, client, _) =
+            MockNetwork::new(Some(base_config), Some(data_client_config), None);
+
+        // Add a connected peer and advertise data for it
+        let (peer, _) = utils::add_peer_to_network(peer_priority, &mut mock_network);
+        client.update_peer_storage_summary(peer, utils::create_storage_summary(200));
+
+        // Create a storage request for transactions
+        let storage_request = StorageServiceRequest::new(
+            DataRequest::GetTransactionsWithProof(TransactionsWithProofRequest {
+                proof_version: 100,
+                start_version: 0,
+                end_version: 100,
+                include_events: false,
+            }),
+            true,
+        );
+
+        // Send a burst of requests to the peer and verify it's selected until the burst is exhausted
+        let peer_states = client.get_peer_states();
+        for _ in 0..max_burst_requests_per_peer {
+            utils::verify_selected_peers_match(&client, hashset![peer], &storage_request);
+            peer_states.increment_sent_request_counter(peer, &storage_request);
+        }
+        utils::verify_request_is_unserviceable(&client, &storage_request, false);
+
+        // Verify that storage summary requests are not rate limited
+        let summary_request =
+            StorageServiceRequest::new(DataRequest::GetStorageServerSummary, true);
+        utils::verify_selected_peers_match(&client, hashset![peer], &summary_request);
+
+        // Elapse some time and verify the peer is selected again (after the tokens are refilled)
+        mock_time.advance_secs(1);
+        utils::verify_selected_peers_match(&client, hashset![peer], &storage_request);
+    }
+}
+
 #[tokio::test]
 async fn single_good_peer() {
     // Ensure the properties hold for all peer priorities
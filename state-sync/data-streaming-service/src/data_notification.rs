@@ -11,7 +11,7 @@ use aptos_types::{
 };
 use std::{
     fmt::{Debug, Formatter},
-    time::Instant,
+    time::{Duration, Instant},
 };
 
 /// A unique ID used to identify each notification.
@@ -221,6 +221,7 @@ pub struct TransactionsOrOutputsWithProofRequest {
 pub struct PendingClientResponse {
     pub client_request: DataClientRequest,
     pub client_response: Option<Result<Response<ResponsePayload>, aptos_data_client::error::Error>>,
+    pub request_start_time: Instant,
 }
 
 impl PendingClientResponse {
@@ -228,9 +229,19 @@ impl PendingClientResponse {
         Self {
             client_request,
             client_response: None,
+            request_start_time: Instant::now(),
         }
     }
 
+    /// Returns the time it took to receive the given response (i.e.,
+    /// from the time the request was created).
+    pub fn get_response_latency(&self, response: &Response<ResponsePayload>) -> Duration {
+        response
+            .context
+            .creation_time
+            .saturating_duration_since(self.request_start_time)
+    }
+
     #[cfg(test)]
     /// Creates a new pending client response with a response already available
     pub fn new_with_response(
@@ -240,6 +251,7 @@ impl PendingClientResponse {
         Self {
             client_request,
             client_response: Some(client_response),
+            request_start_time: Instant::now(),
         }
     }
 }
@@ -466,6 +466,11 @@ impl<T: AptosDataClientInterface + Send + Clone + 'static> DataStream<T> {
             // Process the client response
             match client_response {
                 Ok(client_response) => {
+                    // Calculate the response latency (used to size the prefetching limit)
+                    let response_latency = pending_response
+                        .lock()
+                        .get_response_latency(&client_response);
+
                     // Sanity check and process the response
                     if sanity_check_client_response_type(client_request, &client_response) {
                         // If the response wasn't enough to satisfy the original request (e.g.,
@@ -503,12 +508,12 @@ impl<T: AptosDataClientInterface + Send + Clone + 'static> DataStream<T> {
                         self.send_data_notification_to_client(client_request, client_response)
                             .await?;
 
-                        // If the request is for specific data, increase the prefetching limit.
-                        // Note: we don't increase the limit for new data requests because
+                        // If the request is for specific data, update the prefetching limit.
+                        // Note: we don't update the limit for new data requests because
                         // those don't invoke the prefetcher (as we're already up-to-date).
                         if !client_request.is_new_data_request() {
                             self.dynamic_prefetching_state
-                                .increase_max_concurrent_requests();
+                                .update_max_concurrent_requests(response_latency);
                         }
 
                         // If we're head of line blocked, we should return early
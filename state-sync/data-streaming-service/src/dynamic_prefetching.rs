@@ -9,6 +9,9 @@ use std::{
     time::{Duration, Instant},
 };
 
+// The weight of the latest response latency in the average response latency
+const RESPONSE_LATENCY_SMOOTHING_FACTOR: f64 = 0.1;
+
 /// A simple container for the dynamic prefetching state
 #[derive(Debug)]
 pub struct DynamicPrefetchingState {
@@ -21,6 +24,13 @@ pub struct DynamicPrefetchingState {
     // The maximum number of concurrent requests that can be executing at any given time
     max_dynamic_concurrent_requests: u64,
 
+    // The minimum response latency observed by the stream (i.e., the latency of
+    // a request that isn't queued at the peers). Used for throughput-based sizing.
+    min_response_latency: Option<Duration>,
+
+    // The moving average of the response latency. Used for throughput-based sizing.
+    average_response_latency: Option<Duration>,
+
     // The time service to track elapsed time (e.g., during stream lag checks)
     time_service: TimeService,
 }
@@ -40,6 +50,8 @@ impl DynamicPrefetchingState {
             streaming_service_config: data_streaming_service_config,
             last_timeout_instant: None,
             max_dynamic_concurrent_requests,
+            min_response_latency: None,
+            average_response_latency: None,
             time_service,
         }
     }
@@ -136,15 +148,76 @@ impl DynamicPrefetchingState {
         // Update the last failure time
         self.last_timeout_instant = Some(self.time_service.now());
 
-        // Otherwise, get and decrease the current max
+        // Otherwise, decrease the current max
+        let amount_to_decrease = self
+            .get_dynamic_prefetching_config()
+            .prefetching_value_decrease;
+        self.reduce_max_concurrent_requests(amount_to_decrease);
+    }
+
+    /// Updates the maximum number of concurrent requests that should be executing,
+    /// after a successful response is received with the given latency.
+    ///
+    /// If throughput-based sizing is enabled, the value is sized to the throughput
+    /// the peers can serve: if all requests were served at the minimum latency, the
+    /// throughput would be `max / min_latency`, but it's only `max / average_latency`.
+    /// The difference (multiplied by the minimum latency) estimates the number of
+    /// requests queued at the peers, which don't add to the throughput. The value is
+    /// increased while few requests are queued, and decreased when too many are.
+    /// Otherwise, the value is increased on every successful response.
+    pub fn update_max_concurrent_requests(&mut self, response_latency: Duration) {
+        // If throughput-based sizing is disabled, simply increase the value
+        if !self.is_dynamic_prefetching_enabled()
+            || !self
+                .get_dynamic_prefetching_config()
+                .enable_throughput_based_sizing
+        {
+            self.increase_max_concurrent_requests();
+            return;
+        }
+
+        // Update the minimum and average response latencies. Note: the minimum
+        // latency is never reset, as streams are short-lived.
+        let min_response_latency = self
+            .min_response_latency
+            .map_or(response_latency, |latency| min(latency, response_latency));
+        let average_response_latency =
+            self.average_response_latency
+                .map_or(response_latency, |average_latency| {
+                    average_latency.mul_f64(1.0 - RESPONSE_LATENCY_SMOOTHING_FACTOR)
+                        + response_latency.mul_f64(RESPONSE_LATENCY_SMOOTHING_FACTOR)
+                });
+        self.min_response_latency = Some(min_response_latency);
+        self.average_response_latency = Some(average_response_latency);
+
+        // Estimate the number of requests queued at the peers
+        let queued_requests = if average_response_latency.is_zero() {
+            0.0
+        } else {
+            let latency_ratio =
+                min_response_latency.as_secs_f64() / average_response_latency.as_secs_f64();
+            self.max_dynamic_concurrent_requests as f64 * (1.0 - latency_ratio)
+        };
+        metrics::set_queued_prefetching_requests(queued_requests.round() as u64);
+
+        // Increase or decrease the value based on the queued requests
         let dynamic_prefetching_config = self.get_dynamic_prefetching_config();
-        let amount_to_decrease = dynamic_prefetching_config.prefetching_value_decrease;
+        if queued_requests < dynamic_prefetching_config.min_queued_requests as f64 {
+            self.increase_max_concurrent_requests();
+        } else if queued_requests > dynamic_prefetching_config.max_queued_requests as f64 {
+            // The peers are saturated, so back off gently (without freezing the value)
+            self.reduce_max_concurrent_requests(1);
+        }
+    }
+
+    /// Reduces the maximum number of concurrent requests by the given amount
+    fn reduce_max_concurrent_requests(&mut self, amount_to_decrease: u64) {
         let max_dynamic_concurrent_requests = self
             .max_dynamic_concurrent_requests
             .saturating_sub(amount_to_decrease);
 
         // Bound the value by the configured minimum
-        let min_prefetching_value = dynamic_prefetching_config.min_prefetching_value;
+        let min_prefetching_value = self.get_dynamic_prefetching_config().min_prefetching_value;
         self.max_dynamic_concurrent_requests =
             max(max_dynamic_concurrent_requests, min_prefetching_value);
     }
@@ -685,6 +758,104 @@ mod test {
         }
     }
 
+    #[test]
+    fn test_throughput_based_sizing() {
+        // Create a data streaming service config with throughput-based sizing enabled
+        let initial_prefetching_value = 5;
+        let dynamic_prefetching_config = DynamicPrefetchingConfig {
+            enable_dynamic_prefetching: true,
+            enable_throughput_based_sizing: true,
+            initial_prefetching_value,
+            min_prefetching_value: 3,
+            max_prefetching_value: 30,
+            prefetching_value_increase: 1,
+            min_queued_requests: 1,
+            max_queued_requests: 3,
+            ..Default::default()
+        };
+        let data_streaming_service_config = DataStreamingServiceConfig {
+            dynamic_prefetching: dynamic_prefetching_config,
+            ..Default::default()
+        };
+
+        // Create a new dynamic prefetching state
+        let mut dynamic_prefetching_state =
+            DynamicPrefetchingState::new(data_streaming_service_config, TimeService::mock());
+
+        // Create a stream engine for transactions or outputs
+        let stream_engine =
+            create_transactions_or_outputs_stream_engine(data_streaming_service_config);
+
+        // Receive several responses with a constant latency and verify the value increases
+        // (no requests are queued at the peers).
+        let mut expected_max_requests = initial_prefetching_value;
+        for _ in 0..10 {
+            dynamic_prefetching_state.update_max_concurrent_requests(Duration::from_millis(100));
+
+            expected_max_requests += 1;
+            verify_max_concurrent_requests(
+                &mut dynamic_prefetching_state,
+                &stream_engine,
+                expected_max_requests,
+            );
+        }
+
+        // Receive many responses with a higher latency (i.e., the requests are queued at
+        // the peers) and verify the value decreases until at most 3 requests are queued.
+        for _ in 0..100 {
+            dynamic_prefetching_state.update_max_concurrent_requests(Duration::from_millis(400));
+        }
+        verify_max_concurrent_requests(&mut dynamic_prefetching_state, &stream_engine, 4);
+
+        // Verify the value isn't frozen (it's not a timeout)
+        assert!(!dynamic_prefetching_state.is_prefetching_value_frozen());
+
+        // Receive many responses with the lower latency again and verify the value increases
+        for _ in 0..100 {
+            dynamic_prefetching_state.update_max_concurrent_requests(Duration::from_millis(100));
+        }
+        assert!(dynamic_prefetching_state.get_max_concurrent_requests(&stream_engine) > 4);
+    }
+
+    #[test]
+    fn test_throughput_based_sizing_disabled() {
+        // Create a data streaming service config with throughput-based sizing disabled
+        let initial_prefetching_value = 5;
+        let dynamic_prefetching_config = DynamicPrefetchingConfig {
+            enable_dynamic_prefetching: true,
+            enable_throughput_based_sizing: false,
+            initial_prefetching_value,
+            prefetching_value_increase: 1,
+            ..Default::default()
+        };
+        let data_streaming_service_config = DataStreamingServiceConfig {
+            dynamic_prefetching: dynamic_prefetching_config,
+            ..Default::default()
+        };
+
+        // Create a new dynamic prefetching state
+        let mut dynamic_prefetching_state =
+            DynamicPrefetchingState::new(data_streaming_service_config, TimeService::mock());
+
+        // Create a stream engine for transactions or outputs
+        let stream_engine =
+            create_transactions_or_outputs_stream_engine(data_streaming_service_config);
+
+        // Verify the value increases on every response, regardless of the latency
+        let mut expected_max_requests = initial_prefetching_value;
+        for latency_ms in [100, 400, 1000, 4000] {
+            dynamic_prefetching_state
+                .update_max_concurrent_requests(Duration::from_millis(latency_ms));
+
+            expected_max_requests += 1;
+            verify_max_concurrent_requests(
+                &mut dynamic_prefetching_state,
+                &stream_engine,
+                expected_max_requests,
+            );
+        }
+    }
+
     /// Creates a stream engine for states
     fn create_state_stream_engine(
         data_streaming_service_config: DataStreamingServiceConfig,
@@ -143,6 +143,15 @@ pub static MAX_CONCURRENT_PREFETCHING_REQUESTS: Lazy<IntGauge> = Lazy::new(|| {
     .unwrap()
 });
 
+/// Gauge for the estimated number of prefetching requests queued at the peers
+pub static QUEUED_PREFETCHING_REQUESTS: Lazy<IntGauge> = Lazy::new(|| {
+    register_int_gauge!(
+        "aptos_data_streaming_service_queued_prefetching_requests",
+        "The estimated number of prefetching requests queued at the peers",
+    )
+    .unwrap()
+});
+
 /// Counter for the number of pending data responses
 pub static PENDING_DATA_RESPONSES: Lazy<IntGauge> = Lazy::new(|| {
     register_int_gauge!(
@@ -266,6 +275,11 @@ pub fn set_max_concurrent_requests(value: u64) {
     MAX_CONCURRENT_PREFETCHING_REQUESTS.set(value as i64);
 }
 
+/// Sets the estimated number of queued prefetching requests
+pub fn set_queued_prefetching_requests(value: u64) {
+    QUEUED_PREFETCHING_REQUESTS.set(value as i64);
+}
+
 /// Sets the number of complete pending data responses
 pub fn set_complete_pending_data_responses(value: u64) {
     COMPLETE_PENDING_DATA_RESPONSES.set(value as i64);
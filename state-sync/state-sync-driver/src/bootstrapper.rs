@@ -29,6 +29,7 @@ use aptos_types::{
     state_store::state_value::StateValueChunkWithProof,
     transaction::{TransactionListWithProof, TransactionOutputListWithProof, Version},
     waypoint::Waypoint,
+    waypoint_bundle::WaypointBundle,
 };
 use futures::channel::oneshot;
 use std::{collections::BTreeMap, sync::Arc, time::Duration};
@@ -129,6 +130,38 @@ impl VerifiedEpochStates {
         self.verify_waypoint(epoch_ending_ledger_info, waypoint)
     }
 
+    /// Verifies the epoch ending ledger infos of the given waypoint bundle
+    /// (from our latest epoch state). If the waypoint is verified, the
+    /// epoch ending ledger infos no longer need to be fetched.
+    pub fn verify_waypoint_bundle(
+        &mut self,
+        waypoint_bundle: &WaypointBundle,
+        waypoint: &Waypoint,
+    ) {
+        let latest_epoch = self.latest_epoch_state.epoch;
+        for epoch_ending_ledger_info in waypoint_bundle.epoch_ending_ledger_infos() {
+            if epoch_ending_ledger_info.ledger_info().epoch() < latest_epoch {
+                continue; // The ledger info is already in storage
+            }
+            if let Err(error) =
+                self.update_verified_epoch_states(epoch_ending_ledger_info, waypoint)
+            {
+                panic!(
+                    "Failed to verify the waypoint bundle! Ledger info: {:?}, error: {:?}",
+                    epoch_ending_ledger_info, error
+                );
+            }
+        }
+
+        if self.verified_waypoint {
+            info!(LogSchema::new(LogEntry::Bootstrapper).message(&format!(
+                "Verified the waypoint bundle! Highest epoch ending version: {:?}.",
+                self.highest_fetched_epoch_ending_version
+            )));
+            self.set_fetched_epoch_ending_ledger_infos();
+        }
+    }
+
     /// Attempts to verify the waypoint using the new epoch ending ledger info
     fn verify_waypoint(
         &mut self,
@@ -342,7 +375,14 @@ impl<
         // Load the latest epoch state from storage
         let latest_epoch_state = utils::fetch_latest_epoch_state(storage.clone())
             .expect("Unable to fetch latest epoch state!");
-        let verified_epoch_states = VerifiedEpochStates::new(latest_epoch_state);
+        let mut verified_epoch_states = VerifiedEpochStates::new(latest_epoch_state);
+
+        // Verify the epoch ending ledger infos of the waypoint bundle (if any),
+        // so that they don't need to be fetched from the network.
+        if let Some(waypoint_bundle) = &driver_configuration.waypoint_bundle {
+            verified_epoch_states
+                .verify_waypoint_bundle(waypoint_bundle, &driver_configuration.waypoint);
+        }
 
         Self {
             state_value_syncer: StateValueSyncer::new(),
@@ -36,7 +36,9 @@ use aptos_mempool_notifications::MempoolNotificationSender;
 use aptos_storage_interface::DbReader;
 use aptos_storage_service_notifications::StorageServiceNotificationSender;
 use aptos_time_service::{TimeService, TimeServiceTrait};
-use aptos_types::{contract_event::ContractEvent, waypoint::Waypoint};
+use aptos_types::{
+    contract_event::ContractEvent, waypoint::Waypoint, waypoint_bundle::WaypointBundle,
+};
 use futures::StreamExt;
 use std::{sync::Arc, time::Instant};
 use tokio::{
@@ -63,6 +65,9 @@ pub struct DriverConfiguration {
 
     // The trusted waypoint for the node
     pub waypoint: Waypoint,
+
+    // The waypoint bundle proving the waypoint (if any)
+    pub waypoint_bundle: Option<WaypointBundle>,
 }
 
 impl DriverConfiguration {
@@ -71,12 +76,14 @@ impl DriverConfiguration {
         consensus_observer_config: ConsensusObserverConfig,
         role: RoleType,
         waypoint: Waypoint,
+        waypoint_bundle: Option<WaypointBundle>,
     ) -> Self {
         Self {
             config,
             consensus_observer_config,
             role,
             waypoint,
+            waypoint_bundle,
         }
     }
 }
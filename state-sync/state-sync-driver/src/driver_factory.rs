@@ -162,6 +162,7 @@ impl DriverFactory {
             node_config.consensus_observer,
             node_config.base.role,
             waypoint,
+            node_config.base.waypoint.waypoint_bundle(),
         );
 
         // Create the state sync driver
@@ -102,6 +102,7 @@ pub fn create_full_node_driver_configuration() -> DriverConfiguration {
         consensus_observer_config,
         role,
         waypoint,
+        waypoint_bundle: None,
     }
 }
 
@@ -116,6 +116,20 @@ impl StorageServiceResponse {
     pub fn is_compressed(&self) -> bool {
         matches!(self, Self::CompressedResponse(_, _))
     }
+
+    /// Returns the number of bytes of the response (as sent over the network)
+    pub fn get_num_bytes(&self) -> Result<u64, Error> {
+        match self {
+            StorageServiceResponse::CompressedResponse(_, compressed_data) => {
+                Ok(compressed_data.len() as u64)
+            },
+            StorageServiceResponse::RawResponse(data_response) => {
+                let num_bytes = bcs::serialized_size(data_response)
+                    .map_err(|error| Error::UnexpectedErrorEncountered(error.to_string()))?;
+                Ok(num_bytes as u64)
+            },
+        }
+    }
 }
 
 /// A useful type to hold optional transaction data
@@ -0,0 +1,276 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Export and import of a state snapshot as a directory of checksummed chunk files, so that a fresh
+//! DB can be bootstrapped from another one without going through the backup service and the
+//! backup CLI format.
+//!
+//! The directory holds a manifest, the proof of the state root hash, and one file per chunk with
+//! the state items and the range proof of the chunk. Both the export and the import can be resumed
+//! after an interruption: the export from the chunks already listed in the manifest, and the import
+//! from the restore progress saved in the DB.
+
+use crate::{
+    backup::{backup_handler::BackupHandler, restore_handler::RestoreHandler},
+    state_restore::StateSnapshotRestoreMode,
+};
+use aptos_crypto::{hash::CryptoHash, HashValue};
+use aptos_logger::info;
+use aptos_storage_interface::{db_ensure as ensure, AptosDbError, Result, StateSnapshotReceiver};
+use aptos_types::{
+    ledger_info::LedgerInfoWithSignatures,
+    proof::{SparseMerkleRangeProof, TransactionInfoWithProof},
+    state_store::{state_key::StateKey, state_value::StateValue},
+    transaction::Version,
+};
+use serde::{Deserialize, Serialize};
+use std::{fs, path::Path};
+
+pub const MANIFEST_FILE_NAME: &str = "manifest.bcs";
+pub const ROOT_PROOF_FILE_NAME: &str = "root_proof.bcs";
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct StateSnapshotChunk {
+    /// Index of the first item in the snapshot.
+    pub first_idx: usize,
+    /// Index of the last item in the snapshot.
+    pub last_idx: usize,
+    /// Hash of the key of the first item.
+    pub first_key: HashValue,
+    /// Hash of the key of the last item.
+    pub last_key: HashValue,
+    /// File name of the BCS encoded items and range proof, relative to the snapshot directory.
+    pub file_name: String,
+    /// SHA3-256 of the file content.
+    pub checksum: HashValue,
+}
+
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct StateSnapshotManifest {
+    pub version: Version,
+    pub root_hash: HashValue,
+    pub num_items: usize,
+    pub chunk_size: usize,
+    /// SHA3-256 of the root proof file content.
+    pub root_proof_checksum: HashValue,
+    pub chunks: Vec<StateSnapshotChunk>,
+    /// Whether all the chunks are exported.
+    pub complete: bool,
+}
+
+impl StateSnapshotManifest {
+    /// Loads the manifest in `dir`, if any.
+    pub fn load(dir: &Path) -> Result<Option<Self>> {
+        let path = dir.join(MANIFEST_FILE_NAME);
+        if !path.exists() {
+            return Ok(None);
+        }
+        Ok(Some(bcs::from_bytes(&fs::read(path)?)?))
+    }
+
+    fn save(&self, dir: &Path) -> Result<()> {
+        write_file_atomically(dir, MANIFEST_FILE_NAME, &bcs::to_bytes(self)?)
+    }
+
+    /// Loads the proof of the state root hash, and verifies it against the ledger info, which the
+    /// caller is responsible for verifying against a trusted waypoint or epoch state.
+    pub fn load_root_proof(&self, dir: &Path) -> Result<LedgerInfoWithSignatures> {
+        let (txn_info_with_proof, li): (TransactionInfoWithProof, LedgerInfoWithSignatures) =
+            bcs::from_bytes(&read_checksummed_file(
+                dir,
+                ROOT_PROOF_FILE_NAME,
+                self.root_proof_checksum,
+            )?)?;
+        txn_info_with_proof.verify(li.ledger_info(), self.version)?;
+        let root_hash = txn_info_with_proof
+            .transaction_info()
+            .ensure_state_checkpoint_hash()?;
+        ensure!(
+            root_hash == self.root_hash,
+            "Root hash mismatch with that in proof. root hash: {}, expected: {}",
+            self.root_hash,
+            root_hash,
+        );
+        Ok(li)
+    }
+}
+
+/// Writes to a temporary file first, so that an interrupted write never leaves a partial file.
+fn write_file_atomically(dir: &Path, file_name: &str, bytes: &[u8]) -> Result<()> {
+    let tmp_path = dir.join(format!("{}.tmp", file_name));
+    fs::write(&tmp_path, bytes)?;
+    fs::rename(tmp_path, dir.join(file_name))?;
+    Ok(())
+}
+
+fn read_checksummed_file(dir: &Path, file_name: &str, checksum: HashValue) -> Result<Vec<u8>> {
+    let bytes = fs::read(dir.join(file_name))?;
+    let actual = HashValue::sha3_256_of(&bytes);
+    ensure!(
+        actual == checksum,
+        "Checksum mismatch for {}. checksum: {}, expected: {}",
+        file_name,
+        actual,
+        checksum,
+    );
+    Ok(bytes)
+}
+
+impl BackupHandler {
+    /// Exports the state snapshot at `version` to `dir`, in chunks of `chunk_size` items. Resumes
+    /// a previous export of the same snapshot to `dir` if there's one.
+    pub fn export_state_snapshot(
+        &self,
+        version: Version,
+        dir: &Path,
+        chunk_size: usize,
+    ) -> Result<StateSnapshotManifest> {
+        ensure!(chunk_size > 0, "Chunk size must be positive.");
+        fs::create_dir_all(dir)?;
+
+        let mut manifest = match StateSnapshotManifest::load(dir)? {
+            Some(manifest) => {
+                ensure!(
+                    manifest.version == version && manifest.chunk_size == chunk_size,
+                    "{:?} holds the snapshot at version {} in chunks of {}, not at version {} in \
+                     chunks of {}.",
+                    dir,
+                    manifest.version,
+                    manifest.chunk_size,
+                    version,
+                    chunk_size,
+                );
+                manifest
+            },
+            None => {
+                let (txn_info_with_proof, li) = self.get_state_root_proof(version)?;
+                let root_hash = txn_info_with_proof
+                    .transaction_info()
+                    .ensure_state_checkpoint_hash()?;
+                let root_proof = bcs::to_bytes(&(txn_info_with_proof, li))?;
+                write_file_atomically(dir, ROOT_PROOF_FILE_NAME, &root_proof)?;
+                let manifest = StateSnapshotManifest {
+                    version,
+                    root_hash,
+                    num_items: self.get_state_item_count(version)?,
+                    chunk_size,
+                    root_proof_checksum: HashValue::sha3_256_of(&root_proof),
+                    chunks: Vec::new(),
+                    complete: false,
+                };
+                manifest.save(dir)?;
+                manifest
+            },
+        };
+        if manifest.complete {
+            return Ok(manifest);
+        }
+        if !manifest.chunks.is_empty() {
+            info!(
+                version = version,
+                exported_chunks = manifest.chunks.len(),
+                "Resuming state snapshot export."
+            );
+        }
+
+        let mut next_idx = manifest.chunks.last().map_or(0, |chunk| chunk.last_idx + 1);
+        while next_idx < manifest.num_items {
+            let items = self
+                .get_state_item_iter(version, next_idx, chunk_size)?
+                .collect::<Result<Vec<_>>>()?;
+            let (first_key, last_key) = match (items.first(), items.last()) {
+                (Some((first_key, _)), Some((last_key, _))) => (first_key.hash(), last_key.hash()),
+                _ => {
+                    return Err(AptosDbError::NotFound(format!(
+                        "State item {} at version {}",
+                        next_idx, version
+                    )))
+                },
+            };
+            let proof = self.get_account_state_range_proof(last_key, version)?;
+            let bytes = bcs::to_bytes(&(&items, &proof))?;
+            let file_name = format!("chunk_{:08}.bcs", manifest.chunks.len());
+            write_file_atomically(dir, &file_name, &bytes)?;
+
+            manifest.chunks.push(StateSnapshotChunk {
+                first_idx: next_idx,
+                last_idx: next_idx + items.len() - 1,
+                first_key,
+                last_key,
+                file_name,
+                checksum: HashValue::sha3_256_of(&bytes),
+            });
+            // The manifest is the record of the progress, so it's saved after every chunk.
+            manifest.save(dir)?;
+            next_idx += items.len();
+        }
+
+        manifest.complete = true;
+        manifest.save(dir)?;
+        info!(
+            version = version,
+            num_items = manifest.num_items,
+            num_chunks = manifest.chunks.len(),
+            "State snapshot exported."
+        );
+        Ok(manifest)
+    }
+}
+
+impl RestoreHandler {
+    /// Imports the state snapshot exported to `dir` by `BackupHandler::export_state_snapshot`,
+    /// verifying the checksums and the proofs of all the chunks against the root hash. Resumes a
+    /// previous import of the same snapshot if there's one.
+    ///
+    /// Returns the manifest and the ledger info the root hash is proven by, which the caller is
+    /// responsible for verifying.
+    pub fn import_state_snapshot(
+        &self,
+        dir: &Path,
+    ) -> Result<(StateSnapshotManifest, LedgerInfoWithSignatures)> {
+        let manifest = StateSnapshotManifest::load(dir)?.ok_or_else(|| {
+            AptosDbError::NotFound(format!("State snapshot manifest in {:?}", dir))
+        })?;
+        ensure!(
+            manifest.complete,
+            "The export of the state snapshot in {:?} is not complete.",
+            dir,
+        );
+        let li = manifest.load_root_proof(dir)?;
+
+        let mut receiver = self.get_state_restore_receiver(
+            manifest.version,
+            manifest.root_hash,
+            StateSnapshotRestoreMode::Default,
+        )?;
+        let resume_point = receiver.previous_key_hash()?;
+        let chunks = manifest
+            .chunks
+            .iter()
+            .skip_while(|chunk| resume_point.map_or(false, |key| chunk.last_key <= key));
+        for chunk in chunks {
+            let (items, proof): (Vec<(StateKey, StateValue)>, SparseMerkleRangeProof) =
+                bcs::from_bytes(&read_checksummed_file(
+                    dir,
+                    &chunk.file_name,
+                    chunk.checksum,
+                )?)?;
+            ensure!(
+                items.len() == chunk.last_idx + 1 - chunk.first_idx,
+                "Chunk {} has {} items, expected {}.",
+                chunk.file_name,
+                items.len(),
+                chunk.last_idx + 1 - chunk.first_idx,
+            );
+            receiver.add_chunk(items, proof)?;
+        }
+        receiver.finish()?;
+
+        info!(
+            version = manifest.version,
+            num_items = manifest.num_items,
+            "State snapshot imported."
+        );
+        Ok((manifest, li))
+    }
+}
@@ -2,14 +2,20 @@
 // Parts of the project are originally copyright © Meta Platforms, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::db::{
-    test_helper::{arb_blocks_to_commit, update_in_memory_state},
-    AptosDB,
+use crate::{
+    backup::state_snapshot_stream::{StateSnapshotManifest, MANIFEST_FILE_NAME},
+    db::{
+        test_helper::{arb_blocks_to_commit, update_in_memory_state},
+        AptosDB,
+    },
+    get_restore_handler::GetRestoreHandler,
 };
 use anyhow::Result;
+use aptos_storage_interface::DbReader;
 use aptos_temppath::TempPath;
 use aptos_types::transaction::Version;
 use proptest::prelude::*;
+use std::sync::Arc;
 
 proptest! {
     #![proptest_config(ProptestConfig::with_cases(10))]
@@ -72,4 +78,68 @@ proptest! {
             .unwrap();
         prop_assert_eq!(&non_existent, &[]);
     }
+
+    #[test]
+    fn test_export_import_state_snapshot(input in arb_blocks_to_commit()) {
+        let tmp_dir = TempPath::new();
+        let db = AptosDB::new_for_test(&tmp_dir);
+        let mut in_memory_state = db.state_store.current_state_cloned();
+        let mut cur_ver: Version = 0;
+        for (txns_to_commit, ledger_info_with_sigs) in input.iter() {
+            update_in_memory_state(&mut in_memory_state, txns_to_commit.as_slice());
+            db.save_transactions_for_test(
+                txns_to_commit,
+                cur_ver,
+                cur_ver.checked_sub(1),
+                Some(ledger_info_with_sigs),
+                true, // sync commit
+                &in_memory_state,
+            )
+            .unwrap();
+            cur_ver += txns_to_commit.len() as u64;
+        }
+        let version = db.get_latest_state_checkpoint_version().unwrap().unwrap();
+        let bh = db.get_backup_handler();
+        let expected = bh
+            .get_state_item_iter(version, 0, usize::MAX)
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+
+        let export_dir = TempPath::new();
+        let manifest = bh.export_state_snapshot(version, export_dir.path(), 3).unwrap();
+        prop_assert_eq!(manifest.num_items, expected.len());
+        prop_assert!(manifest.complete);
+
+        // Resume an interrupted export.
+        let mut interrupted = manifest.clone();
+        interrupted.chunks.truncate(1);
+        interrupted.complete = false;
+        std::fs::write(
+            export_dir.path().join(MANIFEST_FILE_NAME),
+            bcs::to_bytes(&interrupted).unwrap(),
+        )
+        .unwrap();
+        let resumed = bh.export_state_snapshot(version, export_dir.path(), 3).unwrap();
+        prop_assert_eq!(&resumed, &manifest);
+        prop_assert_eq!(
+            StateSnapshotManifest::load(export_dir.path()).unwrap(),
+            Some(manifest.clone())
+        );
+
+        let restore_dir = TempPath::new();
+        let restore_db = Arc::new(AptosDB::new_for_test(&restore_dir));
+        let (imported, _li) = restore_db
+            .get_restore_handler()
+            .import_state_snapshot(export_dir.path())
+            .unwrap();
+        prop_assert_eq!(imported, manifest);
+        let actual = restore_db
+            .get_backup_handler()
+            .get_state_item_iter(version, 0, usize::MAX)
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        prop_assert_eq!(actual, expected);
+    }
 }
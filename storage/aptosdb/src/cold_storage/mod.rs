@@ -0,0 +1,197 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Tiered storage of the historical ledger data.
+//!
+//! The transactions, events and write sets older than a configurable horizon are moved to an
+//! object store (see `ObjectStore`), in chunks of `chunk_size` versions aligned on multiples of
+//! `chunk_size`. The indices (transaction by hash, transaction infos, accumulators, event indices)
+//! stay local, and the reads of the offloaded versions are served from the object store, with the
+//! latest objects read cached in memory.
+//!
+//! The offloading progress is only advanced once a chunk is durably in the object store, and the
+//! local copy is deleted after that, so the data of any version is always either local or in the
+//! object store.
+
+use crate::metrics::OTHER_TIMERS_SECONDS;
+use aptos_infallible::Mutex;
+use aptos_metrics_core::TimerHelper;
+use aptos_storage_interface::{AptosDbError, Result};
+use aptos_types::{
+    contract_event::ContractEvent,
+    transaction::{Transaction, Version},
+    write_set::WriteSet,
+};
+use lru::LruCache;
+use serde::{Deserialize, Serialize};
+use std::{
+    fmt::{self, Debug, Formatter},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+};
+
+pub(crate) mod object_store;
+pub(crate) mod offloader;
+
+#[cfg(test)]
+mod test;
+
+use object_store::ObjectStore;
+
+/// The ledger data of a range of versions, as stored in an object.
+#[derive(Debug, Deserialize, Serialize)]
+pub(crate) struct ColdLedgerChunk {
+    pub first_version: Version,
+    pub transactions: Vec<Transaction>,
+    pub events: Vec<Vec<ContractEvent>>,
+    pub write_sets: Vec<WriteSet>,
+}
+
+impl ColdLedgerChunk {
+    fn index_of(&self, version: Version) -> Result<usize> {
+        version
+            .checked_sub(self.first_version)
+            .map(|index| index as usize)
+            .filter(|index| *index < self.transactions.len())
+            .ok_or_else(|| AptosDbError::NotFound(format!("Txn {version} in cold storage")))
+    }
+}
+
+pub(crate) struct ColdLedgerStore {
+    object_store: Arc<dyn ObjectStore>,
+    chunk_size: u64,
+    /// The versions below this one are offloaded.
+    progress: AtomicU64,
+    /// The latest chunks read, by chunk index.
+    cache: Mutex<LruCache<u64, Arc<ColdLedgerChunk>>>,
+}
+
+impl Debug for ColdLedgerStore {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.debug_struct("ColdLedgerStore")
+            .field("object_store", &self.object_store)
+            .field("chunk_size", &self.chunk_size)
+            .field("progress", &self.progress())
+            .finish()
+    }
+}
+
+impl ColdLedgerStore {
+    pub(crate) fn new(
+        object_store: Arc<dyn ObjectStore>,
+        chunk_size: u64,
+        progress: Version,
+        cache_size: usize,
+    ) -> Self {
+        assert!(chunk_size > 0, "Cold storage chunk size must be positive.");
+        Self {
+            object_store,
+            chunk_size,
+            progress: AtomicU64::new(progress),
+            cache: Mutex::new(LruCache::new(cache_size.max(1))),
+        }
+    }
+
+    pub(crate) fn chunk_size(&self) -> u64 {
+        self.chunk_size
+    }
+
+    pub(crate) fn progress(&self) -> Version {
+        self.progress.load(Ordering::Acquire)
+    }
+
+    pub(crate) fn is_offloaded(&self, version: Version) -> bool {
+        version < self.progress()
+    }
+
+    /// Returns the end (exclusive) of the chunk `version` belongs to.
+    pub(crate) fn chunk_end(&self, version: Version) -> Version {
+        (version / self.chunk_size + 1) * self.chunk_size
+    }
+
+    fn object_name(chunk_index: u64) -> String {
+        format!("ledger/{:020}.bcs", chunk_index)
+    }
+
+    /// Writes `chunk` to the object store.
+    pub(crate) fn put_chunk(&self, chunk: &ColdLedgerChunk) -> Result<()> {
+        let _timer = OTHER_TIMERS_SECONDS.timer_with(&["cold_storage_put_chunk"]);
+
+        let chunk_index = chunk.first_version / self.chunk_size;
+        let end_version = chunk.first_version + chunk.transactions.len() as u64;
+        assert!(
+            end_version <= self.chunk_end(chunk.first_version),
+            "Chunk must not span multiple chunk ranges."
+        );
+        self.object_store
+            .put(&Self::object_name(chunk_index), &bcs::to_bytes(chunk)?)
+    }
+
+    pub(crate) fn set_progress(&self, version: Version) {
+        self.progress.store(version, Ordering::Release);
+    }
+
+    fn get_chunk(&self, version: Version) -> Result<Arc<ColdLedgerChunk>> {
+        if !self.is_offloaded(version) {
+            return Err(AptosDbError::NotFound(format!(
+                "Txn {version} in cold storage"
+            )));
+        }
+
+        let chunk_index = version / self.chunk_size;
+        if let Some(chunk) = self.cache.lock().get(&chunk_index) {
+            return Ok(chunk.clone());
+        }
+
+        let _timer = OTHER_TIMERS_SECONDS.timer_with(&["cold_storage_get_chunk"]);
+        let bytes = self.object_store.get(&Self::object_name(chunk_index))?;
+        let chunk: Arc<ColdLedgerChunk> = Arc::new(bcs::from_bytes(&bytes)?);
+        self.cache.lock().put(chunk_index, chunk.clone());
+        Ok(chunk)
+    }
+
+    pub(crate) fn get_transaction(&self, version: Version) -> Result<Transaction> {
+        let chunk = self.get_chunk(version)?;
+        Ok(chunk.transactions[chunk.index_of(version)?].clone())
+    }
+
+    pub(crate) fn get_events(&self, version: Version) -> Result<Vec<ContractEvent>> {
+        let chunk = self.get_chunk(version)?;
+        Ok(chunk.events[chunk.index_of(version)?].clone())
+    }
+
+    pub(crate) fn get_write_set(&self, version: Version) -> Result<WriteSet> {
+        let chunk = self.get_chunk(version)?;
+        Ok(chunk.write_sets[chunk.index_of(version)?].clone())
+    }
+}
+
+/// Splits the `num_versions` versions starting at `start_version` into the offloaded ones and the
+/// local ones. Returns an iterator reading the former from the cold storage with `get`, along with
+/// the first local version and the number of local versions.
+pub(crate) fn split_offloaded<T>(
+    cold_storage: Option<&Arc<ColdLedgerStore>>,
+    start_version: Version,
+    num_versions: usize,
+    get: fn(&ColdLedgerStore, Version) -> Result<T>,
+) -> (impl Iterator<Item = Result<T>>, Version, usize) {
+    let end_version = cold_storage.map_or(start_version, |cold_storage| {
+        cold_storage.progress().clamp(
+            start_version,
+            start_version.saturating_add(num_versions as u64),
+        )
+    });
+    let num_offloaded = (end_version - start_version) as usize;
+    let cold_storage = cold_storage.cloned();
+    let iter = (start_version..end_version).map(move |version| {
+        get(
+            cold_storage
+                .as_ref()
+                .expect("Cold storage must exist for offloaded versions."),
+            version,
+        )
+    });
+    (iter, end_version, num_versions - num_offloaded)
+}
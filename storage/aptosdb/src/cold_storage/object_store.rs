@@ -0,0 +1,123 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use aptos_config::config::ColdStorageBackend;
+use aptos_storage_interface::{AptosDbError, Result};
+use std::{
+    fmt::Debug,
+    fs,
+    io::Write,
+    path::PathBuf,
+    process::{Command, Stdio},
+    sync::Arc,
+};
+
+/// A store of immutable, named objects, e.g., an S3 or GCS bucket.
+pub(crate) trait ObjectStore: Debug + Send + Sync {
+    /// Writes the object `name`, overwriting it if it exists.
+    fn put(&self, name: &str, bytes: &[u8]) -> Result<()>;
+
+    /// Reads the object `name`.
+    fn get(&self, name: &str) -> Result<Vec<u8>>;
+}
+
+pub(crate) fn create_object_store(backend: &ColdStorageBackend) -> Result<Arc<dyn ObjectStore>> {
+    Ok(match backend {
+        ColdStorageBackend::LocalFs { path } => Arc::new(LocalFsObjectStore::new(path.clone())?),
+        ColdStorageBackend::Command {
+            put_command,
+            get_command,
+        } => Arc::new(CommandObjectStore {
+            put_command: put_command.clone(),
+            get_command: get_command.clone(),
+        }),
+    })
+}
+
+/// Stores the objects as files under a directory.
+#[derive(Debug)]
+pub(crate) struct LocalFsObjectStore {
+    root: PathBuf,
+}
+
+impl LocalFsObjectStore {
+    pub(crate) fn new(root: PathBuf) -> Result<Self> {
+        fs::create_dir_all(&root)?;
+        Ok(Self { root })
+    }
+}
+
+impl ObjectStore for LocalFsObjectStore {
+    fn put(&self, name: &str, bytes: &[u8]) -> Result<()> {
+        let path = self.root.join(name);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        // Write to a temporary file first, so that a crash never leaves a partial object behind.
+        let tmp_path = path.with_extension("tmp");
+        let mut file = fs::File::create(&tmp_path)?;
+        file.write_all(bytes)?;
+        file.sync_all()?;
+        fs::rename(tmp_path, path)?;
+        Ok(())
+    }
+
+    fn get(&self, name: &str) -> Result<Vec<u8>> {
+        Ok(fs::read(self.root.join(name))?)
+    }
+}
+
+/// Writes and reads the objects with shell commands, see `ColdStorageBackend::Command`.
+#[derive(Debug)]
+pub(crate) struct CommandObjectStore {
+    put_command: String,
+    get_command: String,
+}
+
+impl CommandObjectStore {
+    fn command(command: &str, name: &str) -> Command {
+        let mut cmd = Command::new("bash");
+        cmd.args(["-c", command]).env("OBJECT_NAME", name);
+        cmd
+    }
+}
+
+impl ObjectStore for CommandObjectStore {
+    fn put(&self, name: &str, bytes: &[u8]) -> Result<()> {
+        let mut child = Self::command(&self.put_command, name)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::piped())
+            .spawn()?;
+        child
+            .stdin
+            .take()
+            .expect("Stdin must be piped.")
+            .write_all(bytes)?;
+        let output = child.wait_with_output()?;
+        if !output.status.success() {
+            return Err(AptosDbError::Other(format!(
+                "Failed to put object {}: {}. {}",
+                name,
+                output.status,
+                String::from_utf8_lossy(&output.stderr)
+            )));
+        }
+        Ok(())
+    }
+
+    fn get(&self, name: &str) -> Result<Vec<u8>> {
+        let output = Self::command(&self.get_command, name)
+            .stdin(Stdio::null())
+            .output()?;
+        if !output.status.success() {
+            return Err(AptosDbError::Other(format!(
+                "Failed to get object {}: {}. {}",
+                name,
+                output.status,
+                String::from_utf8_lossy(&output.stderr)
+            )));
+        }
+        Ok(output.stdout)
+    }
+}
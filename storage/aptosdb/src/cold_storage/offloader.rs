@@ -0,0 +1,177 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    cold_storage::{ColdLedgerChunk, ColdLedgerStore},
+    ledger_db::{write_set_db::WriteSetDb, LedgerDb},
+    schema::event::EventSchema,
+};
+use aptos_logger::{
+    error, info,
+    prelude::{sample, SampleRate},
+};
+use aptos_schemadb::SchemaBatch;
+use aptos_storage_interface::Result;
+use aptos_types::transaction::Version;
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc,
+    },
+    thread::{sleep, JoinHandle},
+    time::Duration,
+};
+
+/// Periodically moves the chunks of versions older than the horizon to the cold storage.
+pub(crate) struct ColdStorageOffloader {
+    worker_thread: Option<JoinHandle<()>>,
+    inner: Arc<ColdStorageOffloaderInner>,
+}
+
+struct ColdStorageOffloaderInner {
+    ledger_db: Arc<LedgerDb>,
+    cold_storage: Arc<ColdLedgerStore>,
+    /// The number of latest versions kept locally.
+    horizon: u64,
+    /// The worker will sleep for this period of time when there's nothing to offload.
+    idle_interval_ms: u64,
+    quit_worker: AtomicBool,
+}
+
+impl ColdStorageOffloaderInner {
+    fn work(&self) {
+        while !self.quit_worker.load(Ordering::SeqCst) {
+            match self.offload_next_chunk() {
+                Ok(true) => continue,
+                Ok(false) => {},
+                Err(err) => {
+                    sample!(
+                        SampleRate::Duration(Duration::from_secs(1)),
+                        error!(error = ?err, "Cold storage offloader has error.")
+                    );
+                },
+            }
+            sleep(Duration::from_millis(self.idle_interval_ms));
+        }
+    }
+
+    /// Offloads the next chunk if it's entirely past the horizon, and returns whether it did.
+    fn offload_next_chunk(&self) -> Result<bool> {
+        let Some(synced_version) = self.ledger_db.metadata_db().get_synced_version()? else {
+            return Ok(false);
+        };
+        let target_version = synced_version.saturating_sub(self.horizon);
+        // The pruned versions can't be offloaded, and won't be read anyway.
+        let pruner_progress = self
+            .ledger_db
+            .metadata_db()
+            .get_pruner_progress()
+            .unwrap_or(0);
+        let begin = self.cold_storage.progress().max(pruner_progress);
+        let end = self.cold_storage.chunk_end(begin);
+        if end > target_version {
+            return Ok(false);
+        }
+
+        offload_chunk(&self.ledger_db, &self.cold_storage, begin, end)?;
+        Ok(true)
+    }
+}
+
+/// Moves the ledger data of versions [begin, end) to the cold storage.
+pub(crate) fn offload_chunk(
+    ledger_db: &LedgerDb,
+    cold_storage: &ColdLedgerStore,
+    begin: Version,
+    end: Version,
+) -> Result<()> {
+    let num_versions = (end - begin) as usize;
+    let chunk = ColdLedgerChunk {
+        first_version: begin,
+        transactions: ledger_db
+            .transaction_db()
+            .get_transaction_iter(begin, num_versions)?
+            .collect::<Result<_>>()?,
+        events: ledger_db
+            .event_db()
+            .get_events_by_version_iter(begin, num_versions)?
+            .collect::<Result<_>>()?,
+        write_sets: ledger_db
+            .write_set_db()
+            .get_write_set_iter(begin, num_versions)?
+            .collect::<Result<_>>()?,
+    };
+    cold_storage.put_chunk(&chunk)?;
+
+    // Only advance the progress once the chunk is durably stored, and only delete the local copy
+    // after that, so that every version is readable at any time.
+    ledger_db
+        .metadata_db()
+        .write_cold_storage_progress(end, cold_storage.chunk_size())?;
+    cold_storage.set_progress(end);
+
+    let transaction_batch = SchemaBatch::new();
+    ledger_db
+        .transaction_db()
+        .prune_transactions(begin, end, &transaction_batch)?;
+    ledger_db
+        .transaction_db()
+        .write_schemas(transaction_batch)?;
+
+    let event_batch = SchemaBatch::new();
+    for (version, events) in (begin..end).zip(chunk.events.iter()) {
+        for index in 0..events.len() {
+            event_batch.delete::<EventSchema>(&(version, index as u64))?;
+        }
+    }
+    ledger_db.event_db().write_schemas(event_batch)?;
+
+    let write_set_batch = SchemaBatch::new();
+    WriteSetDb::prune(begin, end, &write_set_batch)?;
+    ledger_db.write_set_db().write_schemas(write_set_batch)?;
+
+    info!(
+        begin = begin,
+        end = end,
+        "Offloaded ledger data to cold storage."
+    );
+    Ok(())
+}
+
+impl ColdStorageOffloader {
+    pub(crate) fn new(
+        ledger_db: Arc<LedgerDb>,
+        cold_storage: Arc<ColdLedgerStore>,
+        horizon: u64,
+    ) -> Self {
+        let inner = Arc::new(ColdStorageOffloaderInner {
+            ledger_db,
+            cold_storage,
+            horizon,
+            idle_interval_ms: if cfg!(test) { 10 } else { 1000 },
+            quit_worker: AtomicBool::new(false),
+        });
+        let inner_cloned = Arc::clone(&inner);
+
+        let worker_thread = std::thread::Builder::new()
+            .name("cold_storage_offloader".into())
+            .spawn(move || inner_cloned.work())
+            .expect("Creating cold storage offloader thread should succeed.");
+
+        Self {
+            worker_thread: Some(worker_thread),
+            inner,
+        }
+    }
+}
+
+impl Drop for ColdStorageOffloader {
+    fn drop(&mut self) {
+        self.inner.quit_worker.store(true, Ordering::SeqCst);
+        self.worker_thread
+            .take()
+            .expect("Cold storage offloader thread must exist.")
+            .join()
+            .expect("Cold storage offloader thread should join peacefully.");
+    }
+}
@@ -0,0 +1,221 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    cold_storage::{object_store::create_object_store, offloader::offload_chunk, ColdLedgerStore},
+    db::AptosDB,
+    ledger_db::LedgerDb,
+    schema::{transaction::TransactionSchema, write_set::WriteSetSchema},
+};
+use aptos_config::config::ColdStorageBackend;
+use aptos_crypto::HashValue;
+use aptos_schemadb::SchemaBatch;
+use aptos_storage_interface::Result;
+use aptos_temppath::TempPath;
+use aptos_types::{
+    contract_event::ContractEvent,
+    state_store::state_key::StateKey,
+    transaction::{Transaction, Version},
+    write_set::{WriteOp, WriteSet, WriteSetMut},
+};
+use rayon::prelude::*;
+use std::sync::Arc;
+
+const CHUNK_SIZE: u64 = 10;
+const NUM_VERSIONS: u64 = 25;
+
+struct LedgerData {
+    transactions: Vec<Transaction>,
+    events: Vec<Vec<ContractEvent>>,
+    write_sets: Vec<WriteSet>,
+}
+
+fn put_ledger_data(ledger_db: &LedgerDb) -> LedgerData {
+    let transactions: Vec<_> = (0..NUM_VERSIONS)
+        .map(|_| Transaction::StateCheckpoint(HashValue::random()))
+        .collect();
+    let events: Vec<_> = (0..NUM_VERSIONS)
+        .map(|version| {
+            (0..version % 3)
+                .map(|index| {
+                    ContractEvent::new_v2_with_type_tag_str("0x1::test::Event", vec![
+                        version as u8,
+                        index as u8,
+                    ])
+                })
+                .collect()
+        })
+        .collect();
+    let write_sets: Vec<_> = (0..NUM_VERSIONS)
+        .map(|version| {
+            WriteSetMut::new(vec![(
+                StateKey::raw(&version.to_be_bytes()),
+                WriteOp::legacy_modification(vec![version as u8].into()),
+            )])
+            .freeze()
+            .unwrap()
+        })
+        .collect();
+
+    ledger_db
+        .transaction_db()
+        .commit_transactions(0, &transactions, /*skip_index=*/ false)
+        .unwrap();
+    let batch = SchemaBatch::new();
+    ledger_db
+        .event_db()
+        .put_events_multiple_versions(0, &events, &batch)
+        .unwrap();
+    ledger_db.event_db().write_schemas(batch).unwrap();
+    ledger_db
+        .write_set_db()
+        .commit_write_sets(0, write_sets.par_iter())
+        .unwrap();
+
+    LedgerData {
+        transactions,
+        events,
+        write_sets,
+    }
+}
+
+fn new_cold_storage(path: &TempPath, progress: Version) -> Arc<ColdLedgerStore> {
+    let object_store = create_object_store(&ColdStorageBackend::LocalFs {
+        path: path.path().to_path_buf(),
+    })
+    .unwrap();
+    Arc::new(ColdLedgerStore::new(object_store, CHUNK_SIZE, progress, 1))
+}
+
+fn verify_reads(ledger_db: &LedgerDb, data: &LedgerData) {
+    for version in 0..NUM_VERSIONS {
+        let index = version as usize;
+        assert_eq!(
+            ledger_db.transaction_db().get_transaction(version).unwrap(),
+            data.transactions[index]
+        );
+        assert_eq!(
+            ledger_db.event_db().get_events_by_version(version).unwrap(),
+            data.events[index]
+        );
+        assert_eq!(
+            ledger_db.write_set_db().get_write_set(version).unwrap(),
+            data.write_sets[index]
+        );
+    }
+
+    // Ranges spanning the offloaded and the local versions.
+    let (start, num) = (5, 15);
+    let range = start as usize..(start + num) as usize;
+    assert_eq!(
+        ledger_db
+            .transaction_db()
+            .get_transaction_iter(start, num as usize)
+            .unwrap()
+            .collect::<Result<Vec<_>>>()
+            .unwrap(),
+        data.transactions[range.clone()]
+    );
+    assert_eq!(
+        ledger_db
+            .event_db()
+            .get_events_by_version_iter(start, num as usize)
+            .unwrap()
+            .collect::<Result<Vec<_>>>()
+            .unwrap(),
+        data.events[range.clone()]
+    );
+    assert_eq!(
+        ledger_db
+            .write_set_db()
+            .get_write_set_iter(start, num as usize)
+            .unwrap()
+            .collect::<Result<Vec<_>>>()
+            .unwrap(),
+        data.write_sets[range.clone()]
+    );
+    assert_eq!(
+        ledger_db
+            .write_set_db()
+            .get_write_sets(start, start + num)
+            .unwrap(),
+        data.write_sets[range]
+    );
+}
+
+#[test]
+fn test_chunk_end() {
+    let tmp_dir = TempPath::new();
+    let cold_storage = new_cold_storage(&tmp_dir, 0);
+    assert_eq!(cold_storage.chunk_end(0), 10);
+    assert_eq!(cold_storage.chunk_end(9), 10);
+    assert_eq!(cold_storage.chunk_end(10), 20);
+    // An unaligned starting progress ends at the next aligned version.
+    assert_eq!(cold_storage.chunk_end(13), 20);
+}
+
+#[test]
+fn test_offload_and_read_through() {
+    let tmp_dir = TempPath::new();
+    let db = AptosDB::new_for_test(&tmp_dir);
+    let ledger_db = &db.ledger_db;
+    let data = put_ledger_data(ledger_db);
+
+    let cold_dir = TempPath::new();
+    let cold_storage = new_cold_storage(&cold_dir, 0);
+    ledger_db.enable_cold_storage(Arc::clone(&cold_storage));
+    db.event_store.set_cold_storage(Arc::clone(&cold_storage));
+    verify_reads(ledger_db, &data);
+
+    offload_chunk(ledger_db, &cold_storage, 0, 10).unwrap();
+    offload_chunk(ledger_db, &cold_storage, 10, 20).unwrap();
+    assert_eq!(cold_storage.progress(), 20);
+    assert!(cold_storage.is_offloaded(19));
+    assert!(!cold_storage.is_offloaded(20));
+    assert_eq!(
+        ledger_db.metadata_db().get_cold_storage_progress().unwrap(),
+        Some(20)
+    );
+    assert_eq!(
+        ledger_db
+            .metadata_db()
+            .get_cold_storage_chunk_size()
+            .unwrap(),
+        Some(CHUNK_SIZE)
+    );
+
+    // The local copies are deleted.
+    for version in 0..20 {
+        assert!(ledger_db
+            .transaction_db_raw()
+            .get::<TransactionSchema>(&version)
+            .unwrap()
+            .is_none());
+        assert!(ledger_db
+            .write_set_db_raw()
+            .get::<WriteSetSchema>(&version)
+            .unwrap()
+            .is_none());
+    }
+    assert!(ledger_db
+        .transaction_db_raw()
+        .get::<TransactionSchema>(&20)
+        .unwrap()
+        .is_some());
+
+    verify_reads(ledger_db, &data);
+    assert_eq!(
+        db.event_store.get_event_by_version_and_index(5, 1).unwrap(),
+        data.events[5][1]
+    );
+
+    // The objects can be read by a new store, e.g. after a restart.
+    let cold_storage = new_cold_storage(&cold_dir, 20);
+    for version in 0..20 {
+        assert_eq!(
+            cold_storage.get_transaction(version).unwrap(),
+            data.transactions[version as usize]
+        );
+    }
+    assert!(cold_storage.get_transaction(20).is_err());
+}
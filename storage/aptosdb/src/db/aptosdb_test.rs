@@ -14,8 +14,8 @@ use crate::{
     schema::stale_node_index::StaleNodeIndexSchema,
 };
 use aptos_config::config::{
-    EpochSnapshotPrunerConfig, LedgerPrunerConfig, PrunerConfig, RocksdbConfigs,
-    StateMerklePrunerConfig, StorageDirPaths, BUFFERED_STATE_TARGET_ITEMS_FOR_TEST,
+    EpochSnapshotPrunerConfig, LedgerPrunerConfig, PrunerConfig, PrunerPacingConfig,
+    RocksdbConfigs, StateMerklePrunerConfig, StorageDirPaths, BUFFERED_STATE_TARGET_ITEMS_FOR_TEST,
     DEFAULT_MAX_NUM_NODES_PER_LRU_CACHE_SHARD,
 };
 use aptos_crypto::{hash::CryptoHash, HashValue};
@@ -235,6 +235,7 @@ pub fn test_state_merkle_pruning_impl(
                 prune_window: 10,
                 batch_size: 1,
             },
+            pacing_config: PrunerPacingConfig::default(),
         },
         RocksdbConfigs::default(),
         false, /* enable_indexer */
@@ -17,6 +17,7 @@ impl AptosDB {
         skip_index_and_usage: bool,
         internal_indexer_db: Option<InternalIndexerDB>,
     ) -> Self {
+        PRUNING_CONTROLLER.set_config(pruner_config.pacing_config);
         let ledger_db = Arc::new(ledger_db);
         let state_merkle_db = Arc::new(state_merkle_db);
         let state_kv_db = Arc::new(state_kv_db);
@@ -67,6 +68,7 @@ impl AptosDB {
             indexer: None,
             skip_index_and_usage,
             update_subscriber: None,
+            cold_storage_offloader: None,
         }
     }
 
@@ -1,7 +1,9 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
+use aptos_storage_interface::read_view::ReadView;
 use aptos_types::block_info::BlockHeight;
+use std::time::Duration;
 
 impl DbReader for AptosDB {
     fn get_epoch_ending_ledger_infos(
@@ -249,6 +251,15 @@ impl DbReader for AptosDB {
         })
     }
 
+    /// Get the first version at which the state values can be read.
+    fn get_first_state_value_version(&self) -> Result<Option<Version>> {
+        gauged_api("get_first_state_value_version", || {
+            Ok(Some(
+                self.state_store.state_kv_pruner.get_min_readable_version(),
+            ))
+        })
+    }
+
     /// Returns a batch of transactions for the purpose of synchronizing state to another node.
     ///
     /// If any version beyond ledger_version is requested, it is ignored.
@@ -538,10 +549,8 @@ impl DbReader for AptosDB {
                 .get_frozen_subtree_hashes(num_txns)?;
             let transaction_accumulator =
                 Arc::new(InMemoryAccumulator::new(frozen_subtrees, num_txns)?);
-            let ledger_summary = LedgerSummary::new(
-                Arc::new(current_state),
-                transaction_accumulator,
-            );
+            let ledger_summary =
+                LedgerSummary::new(Arc::new(current_state), transaction_accumulator);
             Ok(ledger_summary)
         })
     }
@@ -638,11 +647,7 @@ impl DbReader for AptosDB {
 
     fn get_latest_state_checkpoint_version(&self) -> Result<Option<Version>> {
         gauged_api("get_latest_state_checkpoint_version", || {
-            Ok(self
-                .state_store
-                .current_state()
-                .base_version
-            )
+            Ok(self.state_store.current_state().base_version)
         })
     }
 
@@ -781,6 +786,32 @@ impl DbReader for AptosDB {
                 .get_event_by_version_and_index(version, index)
         })
     }
+
+    fn pin_read_view(&self, version: Version, lease: Duration) -> Result<ReadView> {
+        gauged_api("pin_read_view", || {
+            let synced_version = self
+                .get_synced_version()?
+                .ok_or_else(|| AptosDbError::NotFound("Synced version.".to_string()))?;
+            ensure!(
+                version <= synced_version,
+                "Can't pin a read view at version {} beyond the synced version {}.",
+                version,
+                synced_version,
+            );
+            let state_snapshot = self.state_store.get_state_snapshot_before(version + 1)?;
+
+            self.state_store
+                .read_view_pins
+                .pin(version, state_snapshot, lease, || {
+                    self.error_if_ledger_pruned("Transaction", version)?;
+                    self.error_if_state_kv_pruned("StateValue", version)?;
+                    if let Some((snapshot_version, _)) = state_snapshot {
+                        self.error_if_state_merkle_pruned("State merkle", snapshot_version)?;
+                    }
+                    Ok(())
+                })
+        })
+    }
 }
 
 impl AptosDB {
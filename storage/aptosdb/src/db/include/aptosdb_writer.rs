@@ -1,15 +1,11 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use itertools::Itertools;
 use aptos_storage_interface::chunk_to_commit::ChunkToCommit;
+use itertools::Itertools;
 
 impl DbWriter for AptosDB {
-    fn pre_commit_ledger(
-        &self,
-        chunk: ChunkToCommit,
-        sync_commit: bool,
-    ) -> Result<()> {
+    fn pre_commit_ledger(&self, chunk: ChunkToCommit, sync_commit: bool) -> Result<()> {
         gauged_api("pre_commit_ledger", || {
             // Pre-committing and committing in concurrency is allowed but not pre-committing at the
             // same time from multiple threads, the same for committing.
@@ -20,14 +16,20 @@ impl DbWriter for AptosDB {
                 .try_lock()
                 .expect("Concurrent committing detected.");
             let _timer = OTHER_TIMERS_SECONDS.timer_with(&["pre_commit_ledger"]);
+            let recording = COMMIT_RECORDER
+                .start_commit("pre_commit_ledger", chunk.next_version().saturating_sub(1));
 
-            chunk.latest_in_memory_state.current.log_generation("db_save");
+            chunk
+                .latest_in_memory_state
+                .current
+                .log_generation("db_save");
 
             self.pre_commit_validation(&chunk)?;
-            let _new_root_hash = self.calculate_and_commit_ledger_and_state_kv(
-                &chunk,
-                self.skip_index_and_usage,
-            )?;
+            let start = Instant::now();
+            let _new_root_hash =
+                self.calculate_and_commit_ledger_and_state_kv(&chunk, self.skip_index_and_usage)?;
+            // The pruners back off when the commits slow down.
+            PRUNING_CONTROLLER.observe_commit_latency(start.elapsed());
 
             // n.b make sure buffered_state.update() is called after all other commits are done, since
             // internally it updates state_store.current_state which indicates the "pre-committed version"
@@ -42,6 +44,7 @@ impl DbWriter for AptosDB {
                     sync_commit || chunk.is_reconfig,
                 )?;
             }
+            recording.finish();
 
             Ok(())
         })
@@ -63,6 +66,7 @@ impl DbWriter for AptosDB {
                 .try_lock()
                 .expect("Concurrent committing detected.");
             let _timer = OTHER_TIMERS_SECONDS.timer_with(&["commit_ledger"]);
+            let recording = COMMIT_RECORDER.start_commit("commit_ledger", version);
 
             let old_committed_ver = self.get_and_check_commit_range(version)?;
 
@@ -79,16 +83,13 @@ impl DbWriter for AptosDB {
             self.ledger_db.metadata_db().write_schemas(ledger_batch)?;
 
             // Notify the pruners, invoke the indexer, and update in-memory ledger info.
-            self.post_commit(
-                old_committed_ver,
-                version,
-                ledger_info_with_sigs,
-                chunk_opt,
-            )
+            self.post_commit(old_committed_ver, version, ledger_info_with_sigs, chunk_opt)?;
+            recording.finish();
+
+            Ok(())
         })
     }
 
-
     fn get_state_snapshot_receiver(
         &self,
         version: Version,
@@ -215,21 +216,58 @@ impl DbWriter for AptosDB {
             Ok(())
         })
     }
+
+    fn prune_to_version(&self, version: Version) -> Result<()> {
+        gauged_api("prune_to_version", || {
+            let latest_checkpoint_version = self
+                .get_latest_state_checkpoint_version()?
+                .ok_or_else(|| AptosDbError::NotFound("Latest state checkpoint.".to_string()))?;
+            // The state at the latest checkpoint must stay readable.
+            ensure!(
+                version <= latest_checkpoint_version,
+                "Can't prune to version {} beyond the latest state checkpoint version {}.",
+                version,
+                latest_checkpoint_version,
+            );
+
+            self.state_store
+                .read_view_pins
+                .with_min_pinned_version(|min_pinned_version| {
+                    if let Some(min_pinned_version) = min_pinned_version {
+                        ensure!(
+                            version <= min_pinned_version,
+                            "Can't prune to version {} beyond the version {} pinned by a read view.",
+                            version,
+                            min_pinned_version,
+                        );
+                    }
+
+                    self.ledger_pruner.prune_to_version(version)?;
+                    self.state_store.state_kv_pruner.prune_to_version(version)?;
+                    self.state_store
+                        .state_merkle_pruner
+                        .prune_to_version(version)
+                })?;
+            info!(version = version, "Manual pruning requested.");
+
+            Ok(())
+        })
+    }
+
+    fn set_pruner_pacing_enabled(&self, enable: bool) -> Result<bool> {
+        let was_enabled = PRUNING_CONTROLLER.set_pacing_enabled(enable);
+        info!(enable = enable, "Pruner pacing updated.");
+        Ok(was_enabled)
+    }
 }
 
 impl AptosDB {
-    fn pre_commit_validation(
-        &self,
-        chunk: &ChunkToCommit,
-    ) -> Result<()> {
+    fn pre_commit_validation(&self, chunk: &ChunkToCommit) -> Result<()> {
         let _timer = OTHER_TIMERS_SECONDS
             .with_label_values(&["save_transactions_validation"])
             .start_timer();
 
-        ensure!(
-            !chunk.is_empty(),
-            "chunk is empty, nothing to save.",
-        );
+        ensure!(!chunk.is_empty(), "chunk is empty, nothing to save.",);
         ensure!(
             Some(chunk.expect_last_version()) == chunk.latest_in_memory_state.current_version,
             "the last_version {:?} to commit doesn't match the current_version {:?} in latest_in_memory_state",
@@ -274,30 +312,38 @@ impl AptosDB {
             //
             // TODO(grao): Consider propagating the error instead of panic, if necessary.
             s.spawn(|_| {
-                self.commit_events(chunk.first_version, chunk.transaction_outputs, skip_index_and_usage)
-                    .unwrap()
+                self.commit_events(
+                    chunk.first_version,
+                    chunk.transaction_outputs,
+                    skip_index_and_usage,
+                )
+                .unwrap()
             });
             s.spawn(|_| {
                 self.ledger_db
                     .write_set_db()
                     .commit_write_sets(
                         chunk.first_version,
-                        chunk.transaction_outputs.par_iter().map(TransactionOutput::write_set)
+                        chunk
+                            .transaction_outputs
+                            .par_iter()
+                            .map(TransactionOutput::write_set),
                     )
                     .unwrap()
             });
             s.spawn(|_| {
                 self.ledger_db
                     .transaction_db()
-                    .commit_transactions(chunk.first_version, chunk.transactions, skip_index_and_usage)
+                    .commit_transactions(
+                        chunk.first_version,
+                        chunk.transactions,
+                        skip_index_and_usage,
+                    )
                     .unwrap()
             });
             s.spawn(|_| {
-                self.commit_state_kv_and_ledger_metadata(
-                    chunk,
-                    skip_index_and_usage,
-                )
-                .unwrap()
+                self.commit_state_kv_and_ledger_metadata(chunk, skip_index_and_usage)
+                    .unwrap()
             });
             s.spawn(|_| {
                 self.commit_transaction_infos(chunk.first_version, chunk.transaction_infos)
@@ -340,7 +386,8 @@ impl AptosDB {
             // Always put in state value index for now.
             // TODO(grao): remove after APIs migrated off the DB to the indexer.
             self.state_store.state_kv_db.enabled_sharding(),
-            chunk.transaction_infos
+            chunk
+                .transaction_infos
                 .iter()
                 .rposition(|t| t.state_checkpoint_hash().is_some()),
         )?;
@@ -439,11 +486,7 @@ impl AptosDB {
         let root_hash = self
             .ledger_db
             .transaction_accumulator_db()
-            .put_transaction_accumulator(
-                first_version,
-                transaction_infos,
-                &batch,
-            )?;
+            .put_transaction_accumulator(first_version, transaction_infos, &batch)?;
 
         let _timer = OTHER_TIMERS_SECONDS
             .with_label_values(&["commit_transaction_accumulator___commit"])
@@ -453,8 +496,7 @@ impl AptosDB {
             .write_schemas(batch)?;
 
         let batch = SchemaBatch::new();
-        let all_versions: Vec<_> =
-            (first_version..first_version + num_txns).collect();
+        let all_versions: Vec<_> = (first_version..first_version + num_txns).collect();
         THREAD_MANAGER
             .get_non_exe_cpu_pool()
             .install(|| -> Result<()> {
@@ -529,11 +571,7 @@ impl AptosDB {
             .enumerate()
             .try_for_each(|(i, txn_info)| -> Result<()> {
                 let version = first_version + i as u64;
-                TransactionInfoDb::put_transaction_info(
-                    version,
-                    txn_info,
-                    &batch,
-                )?;
+                TransactionInfoDb::put_transaction_info(version, txn_info, &batch)?;
 
                 Ok(())
             })?;
@@ -544,10 +582,7 @@ impl AptosDB {
         self.ledger_db.transaction_info_db().write_schemas(batch)
     }
 
-    fn get_and_check_commit_range(
-        &self,
-        version_to_commit: Version,
-    ) -> Result<Option<Version>> {
+    fn get_and_check_commit_range(&self, version_to_commit: Version) -> Result<Option<Version>> {
         let old_committed_ver = self.ledger_db.metadata_db().get_synced_version()?;
         let pre_committed_ver = self.state_store.current_state().current_version;
         ensure!(
@@ -569,7 +604,7 @@ impl AptosDB {
         &self,
         version: Version,
         ledger_info_with_sig: &LedgerInfoWithSignatures,
-        ledger_batch: &SchemaBatch
+        ledger_batch: &SchemaBatch,
     ) -> Result<(), AptosDbError> {
         let ledger_info = ledger_info_with_sig.ledger_info();
 
@@ -582,8 +617,13 @@ impl AptosDB {
         );
 
         // Verify the root hash.
-        let db_root_hash = self.ledger_db.transaction_accumulator_db().get_root_hash(version)?;
-        let li_root_hash = ledger_info_with_sig.ledger_info().transaction_accumulator_hash();
+        let db_root_hash = self
+            .ledger_db
+            .transaction_accumulator_db()
+            .get_root_hash(version)?;
+        let li_root_hash = ledger_info_with_sig
+            .ledger_info()
+            .transaction_accumulator_hash();
         ensure!(
             db_root_hash == li_root_hash,
             "Root hash pre-committed doesn't match LedgerInfo. pre-commited: {:?} vs in LedgerInfo: {:?}",
@@ -639,20 +679,19 @@ impl AptosDB {
             COMMITTED_TXNS.inc_by(num_txns);
             LATEST_TXN_VERSION.set(version as i64);
             if let Some(update_sender) = &self.update_subscriber {
-                update_sender.send(
-                    version
-                ).map_err(| err | {
-                        AptosDbError::Other(format!("Failed to send update to subscriber: {}", err))
-                    })?;
+                update_sender.send(version).map_err(|err| {
+                    AptosDbError::Other(format!("Failed to send update to subscriber: {}", err))
+                })?;
             }
             // Activate the ledger pruner and state kv pruner.
             // Note the state merkle pruner is activated when state snapshots are persisted
             // in their async thread.
+            let read_view_pins = &self.state_store.read_view_pins;
             self.ledger_pruner
-                .maybe_set_pruner_target_db_version(version);
+                .maybe_set_pruner_target_db_version_with_pins(version, read_view_pins);
             self.state_store
                 .state_kv_pruner
-                .maybe_set_pruner_target_db_version(version);
+                .maybe_set_pruner_target_db_version_with_pins(version, read_view_pins);
 
             // Note: this must happen after txns have been saved to db because types can be newly
             // created in this same chunk of transactions.
@@ -661,10 +700,20 @@ impl AptosDB {
                 // n.b. txns_to_commit can be partial, when the control was handed over from consensus to state sync
                 // where state sync won't send the pre-committed part to the DB again.
                 if chunk_opt.is_some() && chunk_opt.as_ref().unwrap().len() == num_txns as usize {
-                    let write_sets = chunk_opt.as_ref().unwrap().transaction_outputs.iter().map(|t| t.write_set()).collect_vec();
+                    let write_sets = chunk_opt
+                        .as_ref()
+                        .unwrap()
+                        .transaction_outputs
+                        .iter()
+                        .map(|t| t.write_set())
+                        .collect_vec();
                     indexer.index(self.state_store.clone(), first_version, &write_sets)?;
                 } else {
-                    let write_sets: Vec<_> = self.ledger_db.write_set_db().get_write_set_iter(first_version, num_txns as usize)?.try_collect()?;
+                    let write_sets: Vec<_> = self
+                        .ledger_db
+                        .write_set_db()
+                        .get_write_set_iter(first_version, num_txns as usize)?
+                        .try_collect()?;
                     let write_set_refs = write_sets.iter().collect_vec();
                     indexer.index(self.state_store.clone(), first_version, &write_set_refs)?;
                 };
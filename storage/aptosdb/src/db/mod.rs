@@ -4,6 +4,9 @@
 
 use crate::{
     backup::{backup_handler::BackupHandler, restore_utils},
+    cold_storage::{
+        object_store::create_object_store, offloader::ColdStorageOffloader, ColdLedgerStore,
+    },
     common::MAX_NUM_EPOCH_ENDING_LEDGER_INFO,
     event_store::EventStore,
     ledger_db::{
@@ -15,7 +18,10 @@ use crate::{
         API_LATENCY_SECONDS, COMMITTED_TXNS, LATEST_TXN_VERSION, LEDGER_VERSION, NEXT_BLOCK_EPOCH,
         OTHER_TIMERS_SECONDS,
     },
-    pruner::{LedgerPrunerManager, PrunerManager, StateKvPrunerManager, StateMerklePrunerManager},
+    pruner::{
+        LedgerPrunerManager, PrunerManager, StateKvPrunerManager, StateMerklePrunerManager,
+        PRUNING_CONTROLLER,
+    },
     rocksdb_property_reporter::RocksdbPropertyReporter,
     schema::{
         block_info::BlockInfoSchema,
@@ -24,12 +30,13 @@ use crate::{
     },
     state_kv_db::StateKvDb,
     state_merkle_db::StateMerkleDb,
-    state_store::StateStore,
+    state_store::{partial_state_filter::PartialStateFilter, StateStore},
     transaction_store::TransactionStore,
     utils::new_sharded_kv_schema_batch,
 };
 use aptos_config::config::{
-    PrunerConfig, RocksdbConfig, RocksdbConfigs, StorageDirPaths, NO_OP_STORAGE_PRUNER_CONFIG,
+    ColdStorageConfig, PartialStateSyncConfig, PrunerConfig, RocksdbConfig, RocksdbConfigs,
+    StorageDirPaths, NO_OP_STORAGE_PRUNER_CONFIG,
 };
 use aptos_crypto::HashValue;
 use aptos_db_indexer::{db_indexer::InternalIndexerDB, Indexer};
@@ -40,7 +47,7 @@ use aptos_resource_viewer::AptosValueAnnotator;
 use aptos_schemadb::SchemaBatch;
 use aptos_scratchpad::SparseMerkleTree;
 use aptos_storage_interface::{
-    db_ensure as ensure, db_other_bail as bail,
+    commit_recorder::COMMIT_RECORDER, db_ensure as ensure, db_other_bail as bail,
     state_store::sharded_state_updates::ShardedStateUpdates, AptosDbError, DbReader, DbWriter,
     LedgerSummary, Order, Result, StateSnapshotReceiver, MAX_REQUEST_LIMIT,
 };
@@ -104,6 +111,7 @@ pub struct AptosDB {
     indexer: Option<Indexer>,
     skip_index_and_usage: bool,
     update_subscriber: Option<Sender<Version>>,
+    cold_storage_offloader: Option<ColdStorageOffloader>,
 }
 
 // DbReader implementations and private functions used by them.
@@ -194,6 +202,67 @@ impl AptosDB {
         Ok(())
     }
 
+    /// Serves the reads of the ledger data offloaded to the cold storage, and starts offloading
+    /// the ledger data older than the horizon.
+    pub fn enable_cold_storage(&mut self, config: &ColdStorageConfig) -> Result<()> {
+        let metadata_db = self.ledger_db.metadata_db();
+        // The chunk size can't change once some data is offloaded, since it determines the objects
+        // the versions are in.
+        let chunk_size = match metadata_db.get_cold_storage_chunk_size()? {
+            Some(chunk_size) => {
+                if chunk_size != config.chunk_size {
+                    warn!(
+                        stored_chunk_size = chunk_size,
+                        configured_chunk_size = config.chunk_size,
+                        "Ignoring the configured cold storage chunk size."
+                    );
+                }
+                chunk_size
+            },
+            None => config.chunk_size,
+        };
+        ensure!(chunk_size > 0, "Cold storage chunk size must be positive.");
+        let progress = match metadata_db.get_cold_storage_progress()? {
+            Some(progress) => progress,
+            None => metadata_db.get_pruner_progress().unwrap_or(0),
+        };
+
+        let cold_storage = Arc::new(ColdLedgerStore::new(
+            create_object_store(&config.backend)?,
+            chunk_size,
+            progress,
+            config.cache_size,
+        ));
+        self.ledger_db
+            .enable_cold_storage(Arc::clone(&cold_storage));
+        self.event_store.set_cold_storage(Arc::clone(&cold_storage));
+        info!(
+            progress = progress,
+            chunk_size = chunk_size,
+            horizon = config.horizon,
+            "Cold storage enabled."
+        );
+
+        self.cold_storage_offloader = Some(ColdStorageOffloader::new(
+            Arc::clone(&self.ledger_db),
+            cold_storage,
+            config.horizon,
+        ));
+        Ok(())
+    }
+
+    /// Stores only the state values under the configured account address prefixes (and the
+    /// framework state), see `PartialStateFilter` for the limitations.
+    pub fn enable_partial_state_sync(&self, config: &PartialStateSyncConfig) -> Result<()> {
+        self.state_store
+            .set_partial_state_filter(PartialStateFilter::new(config)?)?;
+        info!(
+            account_address_prefixes = ?config.account_address_prefixes,
+            "Partial state sync enabled."
+        );
+        Ok(())
+    }
+
     /// Gets an instance of `BackupHandler` for data backup purpose.
     pub fn get_backup_handler(&self) -> BackupHandler {
         BackupHandler::new(Arc::clone(&self.state_store), Arc::clone(&self.ledger_db))
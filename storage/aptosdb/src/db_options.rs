@@ -2,10 +2,10 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::schema::*;
-use aptos_config::config::RocksdbConfig;
+use aptos_config::config::{RocksdbConfig, RocksdbTuningProfile};
 use aptos_schemadb::{
-    BlockBasedOptions, Cache, ColumnFamilyDescriptor, ColumnFamilyName, DBCompressionType, Options,
-    SliceTransform, DEFAULT_COLUMN_FAMILY_NAME,
+    BlockBasedOptions, Cache, ColumnFamilyDescriptor, ColumnFamilyName, DBCompactionStyle,
+    DBCompressionType, Options, SliceTransform, DEFAULT_COLUMN_FAMILY_NAME,
 };
 use aptos_types::transaction::Version;
 
@@ -145,15 +145,19 @@ fn gen_cfds<F>(
 where
     F: Fn(ColumnFamilyName, &mut Options),
 {
+    let profile = rocksdb_config.tuning_profile;
     let mut table_options = BlockBasedOptions::default();
-    table_options.set_cache_index_and_filter_blocks(rocksdb_config.cache_index_and_filter_blocks);
+    table_options.set_cache_index_and_filter_blocks(
+        rocksdb_config.cache_index_and_filter_blocks || profile == RocksdbTuningProfile::LowMemory,
+    );
     table_options.set_block_size(rocksdb_config.block_size as usize);
-    let cache = Cache::new_lru_cache(rocksdb_config.block_cache_size as usize);
+    let cache = Cache::new_lru_cache(block_cache_size(rocksdb_config) as usize);
     table_options.set_block_cache(&cache);
     let mut cfds = Vec::with_capacity(cfs.len());
     for cf_name in cfs {
         let mut cf_opts = Options::default();
-        cf_opts.set_compression_type(DBCompressionType::Lz4);
+        cf_opts.set_compression_type(compression_type(profile, cf_name));
+        cf_opts.set_compaction_style(compaction_style(profile, cf_name));
         cf_opts.set_block_based_table_factory(&table_options);
         cf_opts_post_processor(cf_name, &mut cf_opts);
         cfds.push(ColumnFamilyDescriptor::new((*cf_name).to_string(), cf_opts));
@@ -161,6 +165,58 @@ where
     cfds
 }
 
+fn block_cache_size(rocksdb_config: &RocksdbConfig) -> u64 {
+    match rocksdb_config.tuning_profile {
+        RocksdbTuningProfile::Default | RocksdbTuningProfile::Archive => {
+            rocksdb_config.block_cache_size
+        },
+        RocksdbTuningProfile::Throughput => rocksdb_config.block_cache_size * 4,
+        RocksdbTuningProfile::LowMemory => rocksdb_config.block_cache_size / 2,
+    }
+}
+
+/// The column families read on the critical path of execution.
+fn is_state_cf(cf_name: ColumnFamilyName) -> bool {
+    [
+        JELLYFISH_MERKLE_NODE_CF_NAME,
+        STATE_VALUE_CF_NAME,
+        STATE_VALUE_BY_KEY_HASH_CF_NAME,
+    ]
+    .contains(&cf_name)
+}
+
+/// The column families of the historical ledger data, which are written once and rarely read.
+fn is_ledger_history_cf(cf_name: ColumnFamilyName) -> bool {
+    [
+        EVENT_CF_NAME,
+        TRANSACTION_CF_NAME,
+        TRANSACTION_AUXILIARY_DATA_CF_NAME,
+        TRANSACTION_INFO_CF_NAME,
+        WRITE_SET_CF_NAME,
+    ]
+    .contains(&cf_name)
+}
+
+fn compression_type(profile: RocksdbTuningProfile, cf_name: ColumnFamilyName) -> DBCompressionType {
+    match profile {
+        RocksdbTuningProfile::Default => DBCompressionType::Lz4,
+        RocksdbTuningProfile::Throughput if is_state_cf(cf_name) => DBCompressionType::None,
+        RocksdbTuningProfile::Throughput => DBCompressionType::Lz4,
+        RocksdbTuningProfile::LowMemory => DBCompressionType::Zstd,
+        RocksdbTuningProfile::Archive if is_ledger_history_cf(cf_name) => DBCompressionType::Zstd,
+        RocksdbTuningProfile::Archive => DBCompressionType::Lz4,
+    }
+}
+
+fn compaction_style(profile: RocksdbTuningProfile, cf_name: ColumnFamilyName) -> DBCompactionStyle {
+    match profile {
+        RocksdbTuningProfile::Archive if is_ledger_history_cf(cf_name) => {
+            DBCompactionStyle::Universal
+        },
+        _ => DBCompactionStyle::Level,
+    }
+}
+
 fn with_state_key_extractor_processor(cf_name: ColumnFamilyName, cf_opts: &mut Options) {
     if cf_name == STATE_VALUE_CF_NAME || cf_name == STATE_VALUE_BY_KEY_HASH_CF_NAME {
         let prefix_extractor =
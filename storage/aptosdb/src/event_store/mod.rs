@@ -8,6 +8,7 @@
 
 use super::AptosDB;
 use crate::{
+    cold_storage::ColdLedgerStore,
     schema::{event::EventSchema, event_accumulator::EventAccumulatorSchema},
     utils::iterators::EventsByVersionIter,
 };
@@ -31,6 +32,7 @@ use aptos_types::{
     proof::position::Position,
     transaction::Version,
 };
+use once_cell::sync::OnceCell;
 use std::{
     collections::{hash_map::Entry, HashMap, HashSet},
     convert::{TryFrom, TryInto},
@@ -41,11 +43,19 @@ use std::{
 #[derive(Debug)]
 pub struct EventStore {
     event_db: Arc<DB>,
+    cold_storage: OnceCell<Arc<ColdLedgerStore>>,
 }
 
 impl EventStore {
     pub fn new(event_db: Arc<DB>) -> Self {
-        Self { event_db }
+        Self {
+            event_db,
+            cold_storage: OnceCell::new(),
+        }
+    }
+
+    pub(crate) fn set_cold_storage(&self, cold_storage: Arc<ColdLedgerStore>) {
+        let _ = self.cold_storage.set(cold_storage);
     }
 
     pub fn get_event_by_version_and_index(
@@ -53,6 +63,17 @@ impl EventStore {
         version: Version,
         index: u64,
     ) -> Result<ContractEvent> {
+        if let Some(cold_storage) = self.cold_storage.get() {
+            if cold_storage.is_offloaded(version) {
+                return cold_storage
+                    .get_events(version)?
+                    .into_iter()
+                    .nth(index as usize)
+                    .ok_or_else(|| {
+                        AptosDbError::NotFound(format!("Event {} of Txn {}", index, version))
+                    });
+            }
+        }
         self.event_db
             .get::<EventSchema>(&(version, index))?
             .ok_or_else(|| AptosDbError::NotFound(format!("Event {} of Txn {}", index, version)))
@@ -8,7 +8,8 @@ use aptos_crypto::HashValue;
 use aptos_db_indexer::db_indexer::InternalIndexerDB;
 use aptos_infallible::RwLock;
 use aptos_storage_interface::{
-    chunk_to_commit::ChunkToCommit, DbReader, DbWriter, Result, StateSnapshotReceiver,
+    chunk_to_commit::ChunkToCommit, db_ensure as ensure, read_view::ReadView, AptosDbError,
+    DbReader, DbWriter, Result, StateSnapshotReceiver,
 };
 use aptos_types::{
     ledger_info::LedgerInfoWithSignatures,
@@ -16,7 +17,7 @@ use aptos_types::{
     transaction::{TransactionOutputListWithProof, Version},
 };
 use either::Either;
-use std::sync::Arc;
+use std::{sync::Arc, time::Duration};
 use tokio::sync::watch::Sender;
 
 pub const SECONDARY_DB_DIR: &str = "fast_sync_secondary";
@@ -60,6 +61,12 @@ impl FastSyncStorageWrapper {
         if let Some(sender) = update_sender {
             db_main.add_version_update_subscriber(sender)?;
         }
+        if config.storage.cold_storage.enable {
+            db_main.enable_cold_storage(&config.storage.cold_storage)?;
+        }
+        if config.storage.partial_state_sync.enable {
+            db_main.enable_partial_state_sync(&config.storage.partial_state_sync)?;
+        }
 
         let mut db_dir = config.storage.dir();
         // when the db is empty and configured to do fast sync, we will create a second DB
@@ -182,10 +189,29 @@ impl DbWriter for FastSyncStorageWrapper {
         self.get_aptos_db_write_ref()
             .commit_ledger(version, ledger_info_with_sigs, chunk_opt)
     }
+
+    fn prune_to_version(&self, version: Version) -> Result<()> {
+        self.get_aptos_db_write_ref().prune_to_version(version)
+    }
+
+    fn set_pruner_pacing_enabled(&self, enable: bool) -> Result<bool> {
+        self.get_aptos_db_write_ref()
+            .set_pruner_pacing_enabled(enable)
+    }
 }
 
 impl DbReader for FastSyncStorageWrapper {
     fn get_read_delegatee(&self) -> &dyn DbReader {
         self.get_aptos_db_read_ref()
     }
+
+    fn pin_read_view(&self, version: Version, lease: Duration) -> Result<ReadView> {
+        // Until fast sync finishes, the reads are served by the temporary DB, which is discarded
+        // along with the views pinned on it.
+        ensure!(
+            self.is_fast_sync_bootstrap_finished(),
+            "Can't pin a read view before fast sync finishes."
+        );
+        self.get_aptos_db_read_ref().pin_read_view(version, lease)
+    }
 }
@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    cold_storage::{split_offloaded, ColdLedgerStore},
     event_store::{EmptyReader, EventStore},
     schema::{
         db_metadata::{DbMetadataKey, DbMetadataSchema, DbMetadataValue},
@@ -23,6 +24,7 @@ use aptos_storage_interface::{AptosDbError, Result};
 use aptos_types::{
     account_config::new_block_event_key, contract_event::ContractEvent, transaction::Version,
 };
+use once_cell::sync::OnceCell;
 use std::{path::Path, sync::Arc};
 
 #[derive(Debug)]
@@ -30,11 +32,21 @@ pub(crate) struct EventDb {
     db: Arc<DB>,
     // TODO(grao): Remove this after sharding migration.
     event_store: EventStore,
+    cold_storage: OnceCell<Arc<ColdLedgerStore>>,
 }
 
 impl EventDb {
     pub(super) fn new(db: Arc<DB>, event_store: EventStore) -> Self {
-        Self { db, event_store }
+        Self {
+            db,
+            event_store,
+            cold_storage: OnceCell::new(),
+        }
+    }
+
+    pub(super) fn set_cold_storage(&self, cold_storage: Arc<ColdLedgerStore>) {
+        self.event_store.set_cold_storage(Arc::clone(&cold_storage));
+        let _ = self.cold_storage.set(cold_storage);
     }
 
     pub(super) fn create_checkpoint(&self, path: impl AsRef<Path>) -> Result<()> {
@@ -62,6 +74,12 @@ impl EventDb {
 
     /// Returns all of the events for a given transaction version.
     pub(crate) fn get_events_by_version(&self, version: Version) -> Result<Vec<ContractEvent>> {
+        if let Some(cold_storage) = self.cold_storage.get() {
+            if cold_storage.is_offloaded(version) {
+                return cold_storage.get_events(version);
+            }
+        }
+
         let mut events = vec![];
 
         let mut iter = self.db.iter::<EventSchema>()?;
@@ -98,17 +116,24 @@ impl EventDb {
         &self,
         start_version: Version,
         num_versions: usize,
-    ) -> Result<EventsByVersionIter> {
+    ) -> Result<impl Iterator<Item = Result<Vec<ContractEvent>>> + '_> {
+        let end_version = start_version.checked_add(num_versions as u64).ok_or(
+            AptosDbError::TooManyRequested(num_versions as u64, Version::max_value()),
+        )?;
+        let (offloaded, local_start_version, _) = split_offloaded(
+            self.cold_storage.get(),
+            start_version,
+            num_versions,
+            ColdLedgerStore::get_events,
+        );
         let mut iter = self.db.iter::<EventSchema>()?;
-        iter.seek(&start_version)?;
+        iter.seek(&local_start_version)?;
 
-        Ok(EventsByVersionIter::new(
+        Ok(offloaded.chain(EventsByVersionIter::new(
             iter,
-            start_version,
-            start_version.checked_add(num_versions as u64).ok_or(
-                AptosDbError::TooManyRequested(num_versions as u64, Version::max_value()),
-            )?,
-        ))
+            local_start_version,
+            end_version,
+        )))
     }
 
     /// Returns the version of the latest event committed in the event db.
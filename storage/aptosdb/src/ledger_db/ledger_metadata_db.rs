@@ -86,6 +86,33 @@ impl LedgerMetadataDb {
         get_progress(&self.db, &DbMetadataKey::LedgerPrunerProgress)?
             .ok_or_else(|| AptosDbError::NotFound("No LedgerPrunerProgress in db.".to_string()))
     }
+
+    /// Returns the version below which the ledger data is offloaded to the cold storage.
+    pub(crate) fn get_cold_storage_progress(&self) -> Result<Option<Version>> {
+        get_progress(&self.db, &DbMetadataKey::ColdStorageProgress)
+    }
+
+    /// Returns the chunk size the ledger data was offloaded with.
+    pub(crate) fn get_cold_storage_chunk_size(&self) -> Result<Option<u64>> {
+        get_progress(&self.db, &DbMetadataKey::ColdStorageChunkSize)
+    }
+
+    pub(crate) fn write_cold_storage_progress(
+        &self,
+        version: Version,
+        chunk_size: u64,
+    ) -> Result<()> {
+        let batch = SchemaBatch::new();
+        batch.put::<DbMetadataSchema>(
+            &DbMetadataKey::ColdStorageProgress,
+            &DbMetadataValue::Version(version),
+        )?;
+        batch.put::<DbMetadataSchema>(
+            &DbMetadataKey::ColdStorageChunkSize,
+            &DbMetadataValue::Version(chunk_size),
+        )?;
+        self.db.write_schemas(batch)
+    }
 }
 
 /// LedgerInfo APIs.
@@ -5,6 +5,7 @@
 #![allow(dead_code)]
 
 use crate::{
+    cold_storage::ColdLedgerStore,
     db_options::{
         event_db_column_families, gen_event_cfds, gen_ledger_cfds, gen_ledger_metadata_cfds,
         gen_transaction_accumulator_cfds, gen_transaction_auxiliary_data_cfds,
@@ -330,6 +331,14 @@ impl LedgerDb {
         Ok(())
     }
 
+    /// Serves the reads of the versions offloaded to `cold_storage` from it.
+    pub(crate) fn enable_cold_storage(&self, cold_storage: Arc<ColdLedgerStore>) {
+        self.transaction_db
+            .set_cold_storage(Arc::clone(&cold_storage));
+        self.event_db.set_cold_storage(Arc::clone(&cold_storage));
+        self.write_set_db.set_cold_storage(cold_storage);
+    }
+
     pub(crate) fn metadata_db(&self) -> &LedgerMetadataDb {
         &self.ledger_metadata_db
     }
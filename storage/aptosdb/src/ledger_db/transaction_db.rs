@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    cold_storage::{split_offloaded, ColdLedgerStore},
     metrics::OTHER_TIMERS_SECONDS,
     schema::{
         db_metadata::{DbMetadataKey, DbMetadataSchema, DbMetadataValue},
@@ -15,17 +16,26 @@ use aptos_db_indexer_schemas::schema::transaction_by_account::TransactionByAccou
 use aptos_schemadb::{SchemaBatch, DB};
 use aptos_storage_interface::{AptosDbError, Result};
 use aptos_types::transaction::{Transaction, Version};
+use once_cell::sync::OnceCell;
 use rayon::prelude::*;
 use std::{path::Path, sync::Arc};
 
 #[derive(Debug)]
 pub(crate) struct TransactionDb {
     db: Arc<DB>,
+    cold_storage: OnceCell<Arc<ColdLedgerStore>>,
 }
 
 impl TransactionDb {
     pub(super) fn new(db: Arc<DB>) -> Self {
-        Self { db }
+        Self {
+            db,
+            cold_storage: OnceCell::new(),
+        }
+    }
+
+    pub(super) fn set_cold_storage(&self, cold_storage: Arc<ColdLedgerStore>) {
+        let _ = self.cold_storage.set(cold_storage);
     }
 
     pub(super) fn create_checkpoint(&self, path: impl AsRef<Path>) -> Result<()> {
@@ -49,6 +59,11 @@ impl TransactionDb {
 
     /// Returns signed transaction given its `version`.
     pub(crate) fn get_transaction(&self, version: Version) -> Result<Transaction> {
+        if let Some(cold_storage) = self.cold_storage.get() {
+            if cold_storage.is_offloaded(version) {
+                return cold_storage.get_transaction(version);
+            }
+        }
         self.db
             .get::<TransactionSchema>(&version)?
             .ok_or_else(|| AptosDbError::NotFound(format!("Txn {version}")))
@@ -60,9 +75,16 @@ impl TransactionDb {
         start_version: Version,
         num_transactions: usize,
     ) -> Result<impl Iterator<Item = Result<Transaction>> + '_> {
+        let (offloaded, local_start_version, num_local_transactions) = split_offloaded(
+            self.cold_storage.get(),
+            start_version,
+            num_transactions,
+            ColdLedgerStore::get_transaction,
+        );
         let mut iter = self.db.iter::<TransactionSchema>()?;
-        iter.seek(&start_version)?;
-        iter.expect_continuous_versions(start_version, num_transactions)
+        iter.seek(&local_start_version)?;
+        Ok(offloaded
+            .chain(iter.expect_continuous_versions(local_start_version, num_local_transactions)?))
     }
 
     /// Returns the version of a transaction given its hash.
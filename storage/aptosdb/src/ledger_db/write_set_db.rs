@@ -2,6 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
+    cold_storage::{split_offloaded, ColdLedgerStore},
     metrics::OTHER_TIMERS_SECONDS,
     schema::{
         db_metadata::{DbMetadataKey, DbMetadataSchema, DbMetadataValue},
@@ -13,17 +14,26 @@ use aptos_experimental_runtimes::thread_manager::optimal_min_len;
 use aptos_schemadb::{SchemaBatch, DB};
 use aptos_storage_interface::{db_ensure as ensure, AptosDbError, Result};
 use aptos_types::{transaction::Version, write_set::WriteSet};
+use once_cell::sync::OnceCell;
 use rayon::prelude::*;
 use std::{path::Path, sync::Arc};
 
 #[derive(Debug)]
 pub(crate) struct WriteSetDb {
     db: Arc<DB>,
+    cold_storage: OnceCell<Arc<ColdLedgerStore>>,
 }
 
 impl WriteSetDb {
     pub(super) fn new(db: Arc<DB>) -> Self {
-        Self { db }
+        Self {
+            db,
+            cold_storage: OnceCell::new(),
+        }
+    }
+
+    pub(super) fn set_cold_storage(&self, cold_storage: Arc<ColdLedgerStore>) {
+        let _ = self.cold_storage.set(cold_storage);
     }
 
     pub(super) fn create_checkpoint(&self, path: impl AsRef<Path>) -> Result<()> {
@@ -49,6 +59,11 @@ impl WriteSetDb {
 impl WriteSetDb {
     /// Returns executed transaction vm output given the `version`.
     pub(crate) fn get_write_set(&self, version: Version) -> Result<WriteSet> {
+        if let Some(cold_storage) = self.cold_storage.get() {
+            if cold_storage.is_offloaded(version) {
+                return cold_storage.get_write_set(version);
+            }
+        }
         self.db
             .get::<WriteSetSchema>(&version)?
             .ok_or_else(|| AptosDbError::NotFound(format!("WriteSet at version {}", version)))
@@ -60,9 +75,16 @@ impl WriteSetDb {
         start_version: Version,
         num_transactions: usize,
     ) -> Result<impl Iterator<Item = Result<WriteSet>> + '_> {
+        let (offloaded, local_start_version, num_local_transactions) = split_offloaded(
+            self.cold_storage.get(),
+            start_version,
+            num_transactions,
+            ColdLedgerStore::get_write_set,
+        );
         let mut iter = self.db.iter::<WriteSetSchema>()?;
-        iter.seek(&start_version)?;
-        iter.expect_continuous_versions(start_version, num_transactions)
+        iter.seek(&local_start_version)?;
+        Ok(offloaded
+            .chain(iter.expect_continuous_versions(local_start_version, num_local_transactions)?))
     }
 
     /// Returns write sets in `[begin_version, end_version)` half-open range.
@@ -83,11 +105,20 @@ impl WriteSetDb {
             end_version
         );
 
-        let mut iter = self.db.iter::<WriteSetSchema>()?;
-        iter.seek(&begin_version)?;
-
+        let (offloaded, local_begin_version, _) = split_offloaded(
+            self.cold_storage.get(),
+            begin_version,
+            (end_version - begin_version) as usize,
+            ColdLedgerStore::get_write_set,
+        );
         let mut ret = Vec::with_capacity((end_version - begin_version) as usize);
-        for current_version in begin_version..end_version {
+        for write_set in offloaded {
+            ret.push(write_set?);
+        }
+
+        let mut iter = self.db.iter::<WriteSetSchema>()?;
+        iter.seek(&local_begin_version)?;
+        for current_version in local_begin_version..end_version {
             let (version, write_set) = iter.next().transpose()?.ok_or_else(|| {
                 AptosDbError::NotFound(format!("Write set missing for version {}", current_version))
             })?;
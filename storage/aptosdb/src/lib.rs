@@ -28,6 +28,7 @@ pub mod utils;
 pub mod db_debugger;
 pub mod fast_sync_storage_wrapper;
 
+mod cold_storage;
 mod db_options;
 mod event_store;
 mod ledger_db;
@@ -89,6 +89,28 @@ pub static PRUNER_VERSIONS: Lazy<IntGaugeVec> = Lazy::new(|| {
     .unwrap()
 });
 
+/// Estimated time for each pruner to prune its backlog.
+pub static PRUNER_ETA_SECONDS: Lazy<IntGaugeVec> = Lazy::new(|| {
+    register_int_gauge_vec!(
+        // metric name
+        "aptos_pruner_eta_seconds",
+        // metric description
+        "Estimated time for the pruner to catch up with its target",
+        // metric labels (dimensions)
+        &["pruner_name"]
+    )
+    .unwrap()
+});
+
+/// The time the pruners wait after each batch because the commits are slow.
+pub static PRUNER_PACING_DELAY_MS: Lazy<IntGauge> = Lazy::new(|| {
+    register_int_gauge!(
+        "aptos_pruner_pacing_delay_ms",
+        "Time the pruners wait after each batch because the commits are slow"
+    )
+    .unwrap()
+});
+
 /// Pruner batch size. For ledger pruner, this means the number of versions to be pruned at a time.
 /// For state store pruner, this means the number of stale nodes to be pruned at a time.
 pub static PRUNER_BATCH_SIZE: Lazy<IntGaugeVec> = Lazy::new(|| {
@@ -23,6 +23,7 @@ use crate::{
             transaction_info_pruner::TransactionInfoPruner, transaction_pruner::TransactionPruner,
             write_set_pruner::WriteSetPruner,
         },
+        PRUNING_CONTROLLER,
     },
     transaction_store::TransactionStore,
 };
@@ -84,6 +85,7 @@ impl DBPruner for LedgerPruner {
             progress = current_batch_target_version;
             self.record_progress(progress);
             info!(progress = progress, "Pruning ledger data is done.");
+            PRUNING_CONTROLLER.on_batch_pruned(self.name(), progress, self.target_version());
         }
 
         Ok(target_version)
@@ -8,10 +8,12 @@ mod ledger_pruner;
 mod pruner_manager;
 mod pruner_utils;
 mod pruner_worker;
+mod pruning_controller;
 mod state_kv_pruner;
 mod state_merkle_pruner;
 
 pub(crate) use ledger_pruner::ledger_pruner_manager::LedgerPrunerManager;
 pub(crate) use pruner_manager::PrunerManager;
+pub(crate) use pruning_controller::PRUNING_CONTROLLER;
 pub(crate) use state_kv_pruner::state_kv_pruner_manager::StateKvPrunerManager;
 pub(crate) use state_merkle_pruner::state_merkle_pruner_manager::StateMerklePrunerManager;
@@ -2,7 +2,7 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::pruner::db_pruner::DBPruner;
-use aptos_storage_interface::Result;
+use aptos_storage_interface::{read_view::ReadViewPins, Result};
 use aptos_types::transaction::Version;
 
 /// This module provides `Pruner` which manages a thread pruning old data in the background and is
@@ -30,10 +30,32 @@ pub trait PrunerManager: Sync {
     /// Sets pruner target version when necessary.
     fn maybe_set_pruner_target_db_version(&self, latest_version: Version);
 
+    /// Like `maybe_set_pruner_target_db_version`, but holds the pruning back so that the versions
+    /// pinned by the read views stay readable.
+    fn maybe_set_pruner_target_db_version_with_pins(
+        &self,
+        latest_version: Version,
+        read_view_pins: &ReadViewPins,
+    ) {
+        read_view_pins.with_min_pinned_version(|min_pinned_version| {
+            let latest_version = min_pinned_version.map_or(latest_version, |pinned| {
+                std::cmp::min(
+                    latest_version,
+                    pinned.saturating_add(self.get_prune_window()),
+                )
+            });
+            self.maybe_set_pruner_target_db_version(latest_version);
+        })
+    }
+
     // Only used at the end of fast sync to store the min_readable_version to db and update the
     // in memory progress.
     fn save_min_readable_version(&self, min_readable_version: Version) -> Result<()>;
 
+    /// Prunes the data below `min_readable_version` without waiting for the prune window, e.g.,
+    /// to free up disk space. Does nothing if it's already pruned.
+    fn prune_to_version(&self, min_readable_version: Version) -> Result<()>;
+
     #[allow(unused)]
     fn is_pruning_pending(&self) -> bool;
 
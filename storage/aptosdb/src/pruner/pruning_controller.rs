@@ -0,0 +1,190 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Paces the pruners based on the commit latency, so that pruning backs off when the DB is under
+//! load, and reports the pruning progress.
+
+use crate::metrics::{PRUNER_ETA_SECONDS, PRUNER_PACING_DELAY_MS, PRUNER_VERSIONS};
+use aptos_config::config::PrunerPacingConfig;
+use aptos_infallible::Mutex;
+use aptos_storage_interface::pruner_progress::{PrunerProgress, PRUNER_PROGRESS};
+use aptos_types::transaction::Version;
+use once_cell::sync::Lazy;
+use std::{
+    collections::HashMap,
+    sync::atomic::{AtomicU64, Ordering},
+    thread::sleep,
+    time::{Duration, Instant},
+};
+
+pub(crate) static PRUNING_CONTROLLER: Lazy<PruningController> = Lazy::new(PruningController::new);
+
+/// The weight of the latest observation in the moving averages.
+const SMOOTHING_FACTOR: f64 = 0.2;
+
+pub(crate) struct PruningController {
+    config: Mutex<PrunerPacingConfig>,
+    /// Moving average of the commit latency, in microseconds.
+    commit_latency_us: AtomicU64,
+    /// The last batch pruned by each pruner, to compute the pruning rate.
+    last_batches: Mutex<HashMap<&'static str, (Instant, PrunerProgress)>>,
+}
+
+impl PruningController {
+    fn new() -> Self {
+        Self {
+            config: Mutex::new(PrunerPacingConfig::default()),
+            commit_latency_us: AtomicU64::new(0),
+            last_batches: Mutex::new(HashMap::new()),
+        }
+    }
+
+    pub(crate) fn set_config(&self, config: PrunerPacingConfig) {
+        *self.config.lock() = config;
+    }
+
+    /// Enables or disables the pacing, keeping the thresholds. Returns whether it was enabled.
+    pub(crate) fn set_pacing_enabled(&self, enable: bool) -> bool {
+        std::mem::replace(&mut self.config.lock().enable, enable)
+    }
+
+    pub(crate) fn observe_commit_latency(&self, latency: Duration) {
+        let latency_us = latency.as_micros() as f64;
+        let _ =
+            self.commit_latency_us
+                .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |average| {
+                    Some(
+                        if average == 0 {
+                            latency_us as u64
+                        } else {
+                            (average as f64 * (1.0 - SMOOTHING_FACTOR)
+                                + latency_us * SMOOTHING_FACTOR) as u64
+                        },
+                    )
+                });
+    }
+
+    /// Returns how long the pruners should wait after each batch. The delay grows linearly with
+    /// how much the commit latency exceeds the threshold, up to the max delay at twice the
+    /// threshold.
+    pub(crate) fn pacing_delay(&self) -> Duration {
+        let config = *self.config.lock();
+        let threshold_us = config.commit_latency_threshold_ms * 1000;
+        if !config.enable || threshold_us == 0 {
+            return Duration::ZERO;
+        }
+        let latency_us = self.commit_latency_us.load(Ordering::Relaxed);
+        if latency_us <= threshold_us {
+            return Duration::ZERO;
+        }
+        let excess = ((latency_us - threshold_us) as f64 / threshold_us as f64).min(1.0);
+        Duration::from_millis((config.max_delay_ms as f64 * excess) as u64)
+    }
+
+    /// Reports the progress of `pruner_name` after pruning a batch, and waits for the pacing delay.
+    pub(crate) fn on_batch_pruned(
+        &self,
+        pruner_name: &'static str,
+        progress: Version,
+        target: Version,
+    ) {
+        let delay = self.pacing_delay();
+        let now = Instant::now();
+        let report = {
+            let mut last_batches = self.last_batches.lock();
+            let report = match last_batches.get(pruner_name) {
+                Some((last_time, last)) => {
+                    let pruned = progress.saturating_sub(last.progress);
+                    let elapsed = now.duration_since(*last_time).as_secs_f64();
+                    let rate = if elapsed > 0.0 {
+                        pruned as f64 / elapsed
+                    } else {
+                        last.versions_per_second
+                    };
+                    PrunerProgress {
+                        progress,
+                        target,
+                        versions_pruned: last.versions_pruned + pruned,
+                        versions_per_second: if last.versions_per_second > 0.0 {
+                            last.versions_per_second * (1.0 - SMOOTHING_FACTOR)
+                                + rate * SMOOTHING_FACTOR
+                        } else {
+                            rate
+                        },
+                        pacing_delay: delay,
+                    }
+                },
+                // The first batch after start, of which the start time is unknown.
+                None => PrunerProgress {
+                    progress,
+                    target,
+                    versions_pruned: 0,
+                    versions_per_second: 0.0,
+                    pacing_delay: delay,
+                },
+            };
+            // The pacing delay doesn't count in the pruning time of the next batch.
+            last_batches.insert(pruner_name, (now + delay, report.clone()));
+            report
+        };
+
+        PRUNER_VERSIONS
+            .with_label_values(&[pruner_name, "backlog"])
+            .set(report.backlog() as i64);
+        if let Some(eta) = report.eta() {
+            PRUNER_ETA_SECONDS
+                .with_label_values(&[pruner_name])
+                .set(eta.as_secs() as i64);
+        }
+        PRUNER_PACING_DELAY_MS.set(delay.as_millis() as i64);
+        PRUNER_PROGRESS.update(pruner_name, report);
+
+        if !delay.is_zero() {
+            sleep(delay);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PruningController;
+    use aptos_config::config::PrunerPacingConfig;
+    use std::time::Duration;
+
+    #[test]
+    fn test_pacing_delay() {
+        let controller = PruningController::new();
+        controller.set_config(PrunerPacingConfig {
+            enable: true,
+            commit_latency_threshold_ms: 100,
+            max_delay_ms: 1000,
+        });
+        assert_eq!(controller.pacing_delay(), Duration::ZERO);
+
+        controller.observe_commit_latency(Duration::from_millis(50));
+        assert_eq!(controller.pacing_delay(), Duration::ZERO);
+
+        // The average goes from 50ms to 150ms after enough slow commits.
+        for _ in 0..100 {
+            controller.observe_commit_latency(Duration::from_millis(150));
+        }
+        let delay = controller.pacing_delay();
+        assert!(delay > Duration::from_millis(400) && delay <= Duration::from_millis(500));
+
+        for _ in 0..100 {
+            controller.observe_commit_latency(Duration::from_secs(1));
+        }
+        assert_eq!(controller.pacing_delay(), Duration::from_millis(1000));
+
+        assert!(controller.set_pacing_enabled(false));
+        assert_eq!(controller.pacing_delay(), Duration::ZERO);
+        assert!(!controller.set_pacing_enabled(true));
+        assert_eq!(controller.pacing_delay(), Duration::from_millis(1000));
+
+        controller.set_config(PrunerPacingConfig {
+            enable: false,
+            ..PrunerPacingConfig::default()
+        });
+        assert_eq!(controller.pacing_delay(), Duration::ZERO);
+    }
+}
@@ -13,6 +13,7 @@ use crate::{
             state_kv_metadata_pruner::StateKvMetadataPruner,
             state_kv_shard_pruner::StateKvShardPruner,
         },
+        PRUNING_CONTROLLER,
     },
     state_kv_db::StateKvDb,
 };
@@ -81,6 +82,7 @@ impl DBPruner for StateKvPruner {
             progress = current_batch_target_version;
             self.record_progress(progress);
             info!(progress = progress, "Pruning state kv data is done.");
+            PRUNING_CONTROLLER.on_batch_pruned(self.name(), progress, self.target_version());
         }
 
         Ok(target_version)
@@ -10,7 +10,7 @@ use crate::{
     state_kv_db::StateKvDb,
 };
 use aptos_config::config::LedgerPrunerConfig;
-use aptos_storage_interface::Result;
+use aptos_storage_interface::{db_ensure as ensure, Result};
 use aptos_types::transaction::{AtomicVersion, Version};
 use std::sync::{atomic::Ordering, Arc};
 
@@ -65,6 +65,12 @@ impl PrunerManager for StateKvPrunerManager {
         self.state_kv_db.write_pruner_progress(min_readable_version)
     }
 
+    fn prune_to_version(&self, min_readable_version: Version) -> Result<()> {
+        ensure!(self.is_pruner_enabled(), "The state kv pruner is disabled.");
+        self.set_min_readable_version(min_readable_version);
+        Ok(())
+    }
+
     fn is_pruning_pending(&self) -> bool {
         self.pruner_worker
             .as_ref()
@@ -127,9 +133,16 @@ impl StateKvPrunerManager {
 
     fn set_pruner_target_db_version(&self, latest_version: Version) {
         assert!(self.pruner_worker.is_some());
-        let min_readable_version = latest_version.saturating_sub(self.prune_window);
-        self.min_readable_version
-            .store(min_readable_version, Ordering::SeqCst);
+        self.set_min_readable_version(latest_version.saturating_sub(self.prune_window));
+    }
+
+    /// Moves the min readable version forward to `min_readable_version`, and the worker target
+    /// along with it. A manual target ahead of the prune window is never moved backward.
+    fn set_min_readable_version(&self, min_readable_version: Version) {
+        let min_readable_version = self
+            .min_readable_version
+            .fetch_max(min_readable_version, Ordering::SeqCst)
+            .max(min_readable_version);
 
         PRUNER_VERSIONS
             .with_label_values(&["state_kv_pruner", "min_readable"])
@@ -17,6 +17,7 @@ use crate::{
             state_merkle_metadata_pruner::StateMerkleMetadataPruner,
             state_merkle_shard_pruner::StateMerkleShardPruner,
         },
+        PRUNING_CONTROLLER,
     },
     state_merkle_db::StateMerkleDb,
 };
@@ -83,6 +84,7 @@ where
                 progress = target_version_for_this_round;
                 info!(name = S::name(), progress = progress);
                 self.record_progress(target_version_for_this_round);
+                PRUNING_CONTROLLER.on_batch_pruned(S::name(), progress, self.target_version());
             } else {
                 self.record_progress(target_version);
                 break;
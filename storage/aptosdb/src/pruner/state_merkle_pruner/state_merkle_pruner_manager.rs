@@ -17,7 +17,7 @@ use crate::{
 use aptos_config::config::StateMerklePrunerConfig;
 use aptos_jellyfish_merkle::StaleNodeIndex;
 use aptos_schemadb::schema::KeyCodec;
-use aptos_storage_interface::Result;
+use aptos_storage_interface::{db_ensure as ensure, Result};
 use aptos_types::transaction::{AtomicVersion, Version};
 use std::{
     marker::PhantomData,
@@ -83,6 +83,12 @@ where
             .write_pruner_progress(min_readable_version)
     }
 
+    fn prune_to_version(&self, min_readable_version: Version) -> Result<()> {
+        ensure!(self.is_pruner_enabled(), "The {} is disabled.", S::name());
+        self.set_min_readable_version(min_readable_version);
+        Ok(())
+    }
+
     fn is_pruning_pending(&self) -> bool {
         self.pruner_worker
             .as_ref()
@@ -158,10 +164,16 @@ where
 
     fn set_pruner_target_db_version(&self, latest_version: Version) {
         assert!(self.pruner_worker.is_some());
+        self.set_min_readable_version(latest_version.saturating_sub(self.prune_window));
+    }
 
-        let min_readable_version = latest_version.saturating_sub(self.prune_window);
-        self.min_readable_version
-            .store(min_readable_version, Ordering::SeqCst);
+    /// Moves the min readable version forward to `min_readable_version`, and the worker target
+    /// along with it. A manual target ahead of the prune window is never moved backward.
+    fn set_min_readable_version(&self, min_readable_version: Version) {
+        let min_readable_version = self
+            .min_readable_version
+            .fetch_max(min_readable_version, Ordering::SeqCst)
+            .max(min_readable_version);
 
         PRUNER_VERSIONS
             .with_label_values(&[S::name(), "min_readable"])
@@ -68,6 +68,8 @@ pub enum DbMetadataKey {
     StateKvShardPrunerProgress(ShardId),
     StateMerkleShardRestoreProgress(ShardId, Version),
     TransactionAuxiliaryDataPrunerProgress,
+    ColdStorageProgress,
+    ColdStorageChunkSize,
 }
 
 define_schema!(
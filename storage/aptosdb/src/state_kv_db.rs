@@ -18,8 +18,12 @@ use aptos_crypto::hash::CryptoHash;
 use aptos_experimental_runtimes::thread_manager::THREAD_MANAGER;
 use aptos_logger::prelude::info;
 use aptos_rocksdb_options::gen_rocksdb_options;
-use aptos_schemadb::{ReadOptions, SchemaBatch, DB};
-use aptos_storage_interface::{state_store::NUM_STATE_SHARDS, Result};
+use aptos_schemadb::{perf::with_read_perf_stats, ReadOptions, SchemaBatch, DB};
+use aptos_storage_interface::{
+    state_read_profiler::{StateReadRecord, STATE_READ_PROFILER},
+    state_store::NUM_STATE_SHARDS,
+    Result,
+};
 use aptos_types::{
     state_store::{state_key::StateKey, state_value::StateValue},
     transaction::Version,
@@ -29,6 +33,7 @@ use rayon::prelude::*;
 use std::{
     path::{Path, PathBuf},
     sync::Arc,
+    time::Instant,
 };
 
 pub const STATE_KV_DB_FOLDER_NAME: &str = "state_kv_db";
@@ -299,6 +304,35 @@ impl StateKvDb {
         &self,
         state_key: &StateKey,
         version: Version,
+    ) -> Result<Option<(Version, StateValue)>> {
+        if !STATE_READ_PROFILER.is_enabled() {
+            return self.read_state_value_with_version_by_version(state_key, version);
+        }
+
+        let start = Instant::now();
+        let (result, perf_stats) = with_read_perf_stats(|| {
+            self.read_state_value_with_version_by_version(state_key, version)
+        });
+        if let Ok(value) = &result {
+            STATE_READ_PROFILER.record(StateReadRecord {
+                state_key: state_key.clone(),
+                version,
+                value_version: value.as_ref().map(|(value_version, _)| *value_version),
+                cache_hit: perf_stats.is_cache_hit(),
+                block_cache_hits: perf_stats.block_cache_hits,
+                block_reads: perf_stats.block_reads,
+                sorted_runs_probed: perf_stats.sorted_runs_probed,
+                internal_keys_skipped: perf_stats.internal_keys_skipped,
+                latency: start.elapsed(),
+            });
+        }
+        result
+    }
+
+    fn read_state_value_with_version_by_version(
+        &self,
+        state_key: &StateKey,
+        version: Version,
     ) -> Result<Option<(Version, StateValue)>> {
         let mut read_opts = ReadOptions::default();
 
@@ -31,6 +31,10 @@ pub static IO_POOL: Lazy<ThreadPool> = Lazy::new(|| {
 /// Key-Value batch that will be written into db atomically with other batches.
 pub type StateValueBatch<K, V> = HashMap<(K, Version), V>;
 
+/// Decides which of the restored keys have their values written, the others are only added to the
+/// tree.
+pub type KvFilter<K> = Arc<dyn Fn(&K) -> bool + Send + Sync>;
+
 pub trait StateValueWriter<K, V>: Send + Sync {
     /// Writes a kv batch into storage.
     fn write_kv_batch(
@@ -77,11 +81,16 @@ impl FromStr for StateSnapshotRestoreMode {
 struct StateValueRestore<K, V> {
     version: Version,
     db: Arc<dyn StateValueWriter<K, V>>,
+    kv_filter: Option<KvFilter<K>>,
 }
 
 impl<K: Key + CryptoHash + Eq + Hash, V: Value> StateValueRestore<K, V> {
     pub fn new<D: 'static + StateValueWriter<K, V>>(db: Arc<D>, version: Version) -> Self {
-        Self { version, db }
+        Self {
+            version,
+            db,
+            kv_filter: None,
+        }
     }
 
     pub fn add_chunk(&mut self, mut chunk: Vec<(K, V)>) -> Result<()> {
@@ -112,9 +121,10 @@ impl<K: Key + CryptoHash + Eq + Hash, V: Value> StateValueRestore<K, V> {
             usage.add_item(k.key_size() + v.value_size());
         }
 
-        // prepare the sharded kv batch
+        // prepare the sharded kv batch (the usage above still counts the keys filtered out)
         let kv_batch: StateValueBatch<K, Option<V>> = chunk
             .into_iter()
+            .filter(|(k, _v)| self.kv_filter.as_ref().map_or(true, |filter| filter(k)))
             .map(|(k, v)| ((k, self.version), Some(v)))
             .collect();
 
@@ -192,6 +202,15 @@ impl<K: Key + CryptoHash + Hash + Eq, V: Value> StateSnapshotRestore<K, V> {
         })
     }
 
+    /// Writes only the values of the keys included by `kv_filter`, while the tree is restored in
+    /// full.
+    pub fn with_kv_filter(self, kv_filter: KvFilter<K>) -> Self {
+        if let Some(kv_restore) = self.kv_restore.lock().as_mut() {
+            kv_restore.kv_filter = Some(kv_filter);
+        }
+        self
+    }
+
     pub fn previous_key_hash(&self) -> Result<Option<HashValue>> {
         let hash_opt = match (
             self.kv_restore
@@ -22,7 +22,8 @@ use crate::{
     state_merkle_db::StateMerkleDb,
     state_restore::{StateSnapshotRestore, StateSnapshotRestoreMode, StateValueWriter},
     state_store::{
-        buffered_state::BufferedState, current_state::CurrentState, persisted_state::PersistedState,
+        buffered_state::BufferedState, current_state::CurrentState,
+        partial_state_filter::PartialStateFilter, persisted_state::PersistedState,
     },
     utils::{
         iterators::PrefixedStateValueIterator,
@@ -54,6 +55,7 @@ use aptos_schemadb::SchemaBatch;
 use aptos_scratchpad::SparseMerkleTree;
 use aptos_storage_interface::{
     db_ensure as ensure, db_other_bail as bail,
+    read_view::ReadViewPins,
     state_store::{
         sharded_state_update_refs::ShardedStateUpdateRefs,
         state_delta::StateDelta,
@@ -81,6 +83,7 @@ use aptos_types::{
 };
 use claims::{assert_ge, assert_le};
 use itertools::Itertools;
+use once_cell::sync::OnceCell;
 use rayon::prelude::*;
 use std::{
     collections::HashSet,
@@ -93,6 +96,7 @@ mod state_merkle_batch_committer;
 mod state_snapshot_committer;
 
 mod current_state;
+pub(crate) mod partial_state_filter;
 mod persisted_state;
 #[cfg(test)]
 mod state_store_test;
@@ -113,6 +117,8 @@ pub(crate) struct StateDb {
     pub state_merkle_pruner: StateMerklePrunerManager<StaleNodeIndexSchema>,
     pub epoch_snapshot_pruner: StateMerklePrunerManager<StaleNodeIndexCrossEpochSchema>,
     pub state_kv_pruner: StateKvPrunerManager,
+    /// The read views pinned by the readers, which the pruners don't prune.
+    pub read_view_pins: Arc<ReadViewPins>,
     pub skip_usage: bool,
 }
 
@@ -129,6 +135,8 @@ pub(crate) struct StateStore {
     persisted_state: Arc<Mutex<PersistedState>>,
     buffered_state_target_items: usize,
     internal_indexer_db: Option<InternalIndexerDB>,
+    /// If set, only the state values it includes are stored, see `PartialStateFilter`.
+    partial_state_filter: OnceCell<Arc<PartialStateFilter>>,
 }
 
 impl Deref for StateStore {
@@ -329,6 +337,7 @@ impl StateStore {
             state_merkle_pruner,
             epoch_snapshot_pruner,
             state_kv_pruner,
+            read_view_pins: Arc::new(ReadViewPins::default()),
             skip_usage,
         });
         let current_state = Arc::new(Mutex::new(CurrentState::new_dummy()));
@@ -360,9 +369,17 @@ impl StateStore {
             current_state,
             persisted_state,
             internal_indexer_db,
+            partial_state_filter: OnceCell::new(),
         }
     }
 
+    /// Stores only the state values included by the filter from now on.
+    pub fn set_partial_state_filter(&self, filter: PartialStateFilter) -> Result<()> {
+        self.partial_state_filter
+            .set(Arc::new(filter))
+            .map_err(|_| AptosDbError::Other("Partial state filter already set.".to_string()))
+    }
+
     // We commit the overall commit progress at the last, and use it as the source of truth of the
     // commit progress.
     pub fn sync_commit_progress(
@@ -484,6 +501,7 @@ impl StateStore {
             state_merkle_pruner,
             epoch_snapshot_pruner,
             state_kv_pruner,
+            read_view_pins: Arc::new(ReadViewPins::default()),
             skip_usage: false,
         });
         let current_state = Arc::new(Mutex::new(CurrentState::new_dummy()));
@@ -759,6 +777,11 @@ impl StateStore {
             .zip_eq(state_update_refs.shards.par_iter())
             .try_for_each(|(batch, updates)| {
                 updates.iter().try_for_each(|(idx, key, val)| {
+                    if let Some(filter) = self.partial_state_filter.get() {
+                        if !filter.includes(key) {
+                            return Ok(());
+                        }
+                    }
                     let ver = first_version + *idx as Version;
                     if enable_sharding {
                         batch.put::<StateValueByKeyHashSchema>(
@@ -881,7 +904,9 @@ impl StateStore {
             }
         }
 
-        if !expected_usage.is_untracked() {
+        // With a partial state, the old values of the keys filtered out are unknown, so the usage
+        // can't be calculated exactly.
+        if !expected_usage.is_untracked() && self.partial_state_filter.get().is_none() {
             ensure!(
                 expected_usage == usage,
                 "Calculated state db usage at version {} not expected. expected: {:?}, calculated: {:?}, base version: {:?}, base version usage: {:?}",
@@ -1139,14 +1164,19 @@ impl StateStore {
         version: Version,
         expected_root_hash: HashValue,
     ) -> Result<Box<dyn StateSnapshotReceiver<StateKey, StateValue>>> {
-        Ok(Box::new(StateSnapshotRestore::new(
+        let mut restore = StateSnapshotRestore::new(
             &self.state_merkle_db,
             self,
             version,
             expected_root_hash,
             false, /* async_commit */
             StateSnapshotRestoreMode::Default,
-        )?))
+        )?;
+        if let Some(filter) = self.partial_state_filter.get() {
+            let filter = Arc::clone(filter);
+            restore = restore.with_kv_filter(Arc::new(move |key| filter.includes(key)));
+        }
+        Ok(Box::new(restore))
     }
 
     #[cfg(test)]
@@ -0,0 +1,85 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! The filter of the state values stored by a node syncing only a subset of the state, e.g. an
+//! exchange tracking its own accounts. The state tree is still stored in full, so the synced state
+//! is verified against the root hash, and the proofs of the stored values can be served.
+//!
+//! Limitations:
+//! * Reads of the state values filtered out return `None`, so the node can't execute transactions
+//!   and must apply the transaction outputs.
+//! * The state storage usage is approximate, since the old values of the keys filtered out are
+//!   unknown.
+//! * The node must not serve state snapshots to its peers, as they'd be incomplete.
+
+use aptos_config::config::PartialStateSyncConfig;
+use aptos_storage_interface::Result;
+use aptos_types::state_store::state_key::{inner::StateKeyInner, StateKey};
+
+#[derive(Debug)]
+pub(crate) struct PartialStateFilter {
+    /// The lower case hex prefixes of the account addresses, without the `0x`.
+    prefixes: Vec<String>,
+}
+
+impl PartialStateFilter {
+    pub fn new(config: &PartialStateSyncConfig) -> Result<Self> {
+        Ok(Self {
+            prefixes: config.normalized_prefixes()?,
+        })
+    }
+
+    /// Returns true iff the value of the key is stored, i.e. the key is a resource or module of
+    /// the framework or of an account under the prefixes, or an item of a table of which the
+    /// handle is under the prefixes.
+    pub fn includes(&self, state_key: &StateKey) -> bool {
+        match state_key.inner() {
+            StateKeyInner::AccessPath(access_path) => {
+                access_path.address.is_special() || self.matches(&access_path.address.to_hex())
+            },
+            StateKeyInner::TableItem { handle, .. } => self.matches(&handle.0.to_hex()),
+            StateKeyInner::Raw(_) => false,
+        }
+    }
+
+    fn matches(&self, address_hex: &str) -> bool {
+        self.prefixes
+            .iter()
+            .any(|prefix| address_hex.starts_with(prefix.as_str()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PartialStateFilter;
+    use aptos_config::config::PartialStateSyncConfig;
+    use aptos_types::{
+        account_address::AccountAddress,
+        account_config::AccountResource,
+        state_store::{state_key::StateKey, table::TableHandle},
+    };
+
+    #[test]
+    fn test_partial_state_filter() {
+        let filter = PartialStateFilter::new(&PartialStateSyncConfig {
+            enable: true,
+            account_address_prefixes: vec!["0xAB".to_string()],
+        })
+        .unwrap();
+
+        let tracked = AccountAddress::from_hex_literal(&format!("0xab{}", "0".repeat(62))).unwrap();
+        let untracked =
+            AccountAddress::from_hex_literal(&format!("0xcd{}", "0".repeat(62))).unwrap();
+
+        // The resources of the tracked accounts and of the framework are included
+        assert!(filter.includes(&StateKey::resource_typed::<AccountResource>(&tracked).unwrap()));
+        assert!(filter
+            .includes(&StateKey::resource_typed::<AccountResource>(&AccountAddress::ONE).unwrap()));
+        assert!(!filter.includes(&StateKey::resource_typed::<AccountResource>(&untracked).unwrap()));
+
+        // The table items are included by their handles
+        assert!(filter.includes(&StateKey::table_item(&TableHandle(tracked), b"key")));
+        assert!(!filter.includes(&StateKey::table_item(&TableHandle(untracked), b"key")));
+        assert!(!filter.includes(&StateKey::raw(b"key")));
+    }
+}
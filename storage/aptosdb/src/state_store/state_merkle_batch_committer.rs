@@ -87,10 +87,16 @@ impl StateMerkleBatchCommitter {
                     LATEST_SNAPSHOT_VERSION.set(current_version as i64);
                     self.state_db
                         .state_merkle_pruner
-                        .maybe_set_pruner_target_db_version(current_version);
+                        .maybe_set_pruner_target_db_version_with_pins(
+                            current_version,
+                            &self.state_db.read_view_pins,
+                        );
                     self.state_db
                         .epoch_snapshot_pruner
-                        .maybe_set_pruner_target_db_version(current_version);
+                        .maybe_set_pruner_target_db_version_with_pins(
+                            current_version,
+                            &self.state_db.read_view_pins,
+                        );
 
                     self.check_usage_consistency(&state_delta).unwrap();
 
@@ -420,6 +420,7 @@ impl StateSnapshotBackupController {
             .storage
             .create_for_write(backup_handle, &Self::chunk_name(first_idx))
             .await?;
+        let blobs_hash = HashValue::sha3_256_of(&bytes);
         chunk_file.write_all(&bytes).await?;
         chunk_file.shutdown().await?;
         let (proof_handle, mut proof_file) = self
@@ -442,6 +443,7 @@ impl StateSnapshotBackupController {
             first_key,
             last_key,
             blobs: chunk_handle,
+            blobs_hash: Some(blobs_hash),
             proof: proof_handle,
         })
     }
@@ -22,6 +22,10 @@ pub struct StateSnapshotChunk {
     /// Repeated `len(record) + record` where `record` is BCS serialized tuple
     /// `(key, state_value)`
     pub blobs: FileHandle,
+    /// sha3-256 of the whole `blobs` file, verified while streaming it at restore time. Absent in
+    /// the backups taken before it was introduced.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub blobs_hash: Option<HashValue>,
     /// BCS serialized `SparseMerkleRangeProof` that proves this chunk adds up to the root hash
     /// indicated in the backup (`StateSnapshotBackup::root_hash`).
     pub proof: FileHandle,
@@ -23,6 +23,7 @@ use crate::{
     },
 };
 use anyhow::{anyhow, ensure, Result};
+use aptos_crypto::{hash::Sha3Hasher, HashValue};
 use aptos_db::state_restore::StateSnapshotRestoreMode;
 use aptos_infallible::Mutex;
 use aptos_logger::prelude::*;
@@ -188,7 +189,9 @@ impl StateSnapshotRestoreController {
             let storage = storage.clone();
             async move {
                 tokio::spawn(async move {
-                    let blobs = Self::read_state_value(&storage, chunk.blobs.clone()).await?;
+                    let blobs =
+                        Self::read_state_value(&storage, chunk.blobs.clone(), chunk.blobs_hash)
+                            .await?;
                     let proof = storage.load_bcs_file(&chunk.proof).await?;
                     Result::<_>::Ok((chunk_idx, chunk, blobs, proof))
                 })
@@ -252,18 +255,35 @@ impl StateSnapshotRestoreController {
         }
     }
 
+    /// Reads the records of a chunk, verifying the hash of the file along the way if the manifest
+    /// has one.
     async fn read_state_value(
         storage: &Arc<dyn BackupStorage>,
         file_handle: FileHandle,
+        expected_hash: Option<HashValue>,
     ) -> Result<Vec<(StateKey, StateValue)>> {
         let mut file = storage.open_for_read(&file_handle).await?;
 
         let mut chunk = vec![];
+        let mut hasher = Sha3Hasher::new();
 
         while let Some(record_bytes) = file.read_record_bytes().await? {
+            hasher.update(&(record_bytes.len() as u32).to_be_bytes());
+            hasher.update(&record_bytes);
             chunk.push(bcs::from_bytes(&record_bytes)?);
         }
 
+        if let Some(expected_hash) = expected_hash {
+            let hash = hasher.finish();
+            ensure!(
+                hash == expected_hash,
+                "State snapshot chunk {} is corrupted: hash {} does not match the manifest {}",
+                file_handle,
+                hash,
+                expected_hash,
+            );
+        }
+
         Ok(chunk)
     }
 }
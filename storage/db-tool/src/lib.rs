@@ -13,6 +13,8 @@ pub mod restore;
 #[cfg(test)]
 mod tests;
 mod utils;
+mod verify_range;
+mod waypoint_bundle;
 
 use anyhow::Result;
 use aptos_db::db_debugger;
@@ -41,6 +43,11 @@ pub enum DBTool {
     Restore(restore::Command),
 
     ReplayOnArchive(replay_on_archive::Opt),
+
+    VerifyRange(verify_range::Opt),
+
+    #[clap(subcommand)]
+    WaypointBundle(waypoint_bundle::Command),
 }
 
 impl DBTool {
@@ -58,6 +65,8 @@ impl DBTool {
             DBTool::GenReplayVerifyJobs(cmd) => cmd.run().await,
             DBTool::Restore(cmd) => cmd.run().await,
             DBTool::ReplayOnArchive(cmd) => cmd.run().await.map_err(anyhow::Error::from),
+            DBTool::VerifyRange(cmd) => cmd.run().await,
+            DBTool::WaypointBundle(cmd) => cmd.run().await,
         }
     }
 }
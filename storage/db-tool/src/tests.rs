@@ -64,6 +64,25 @@ fn test_various_cmd_parsing() {
         "--start-version",
         "Max",
     ]);
+    run_cmd(&[
+        "aptos-db-tool",
+        "verify-range",
+        "--from",
+        "0",
+        "--to",
+        "100",
+        "--db-dir",
+        ".",
+    ]);
+    run_cmd(&[
+        "aptos-db-tool",
+        "waypoint-bundle",
+        "export",
+        "--db-dir",
+        ".",
+        "--output-path",
+        "./bundle",
+    ]);
 }
 
 fn run_cmd(args: &[&str]) {
@@ -72,7 +91,7 @@ fn run_cmd(args: &[&str]) {
 
 #[cfg(test)]
 mod dbtool_tests {
-    use crate::DBTool;
+    use crate::{verify_range::RangeVerifier, waypoint_bundle::export_waypoint_bundle, DBTool};
     use aptos_backup_cli::{
         coordinators::backup::BackupCompactor,
         metadata,
@@ -89,6 +108,7 @@ mod dbtool_tests {
     use aptos_types::{
         state_store::state_key::{inner::StateKeyTag::AccessPath, prefix::StateKeyPrefix},
         transaction::Version,
+        waypoint::Waypoint,
     };
     use clap::Parser;
     use std::{
@@ -113,6 +133,39 @@ mod dbtool_tests {
         );
     }
 
+    #[test]
+    fn test_verify_range() {
+        let db = test_execution_with_storage_impl();
+        let latest_version = db.expect_synced_version();
+
+        let verifier = RangeVerifier::new(db, 3, 5).unwrap();
+        let report = verifier.verify(0, Version::MAX).unwrap();
+        assert_eq!(report.first_version, 0);
+        assert_eq!(report.last_version, latest_version);
+        assert!(report.mismatches.is_empty(), "{:?}", report.mismatches);
+        assert!(report.num_state_values_verified > 0);
+        assert_eq!(report.num_state_values_skipped, 0);
+
+        // A range beyond the DB has nothing to verify.
+        assert!(verifier
+            .verify(latest_version + 1, latest_version + 10)
+            .is_err());
+    }
+
+    #[test]
+    fn test_export_waypoint_bundle() {
+        let db = test_execution_with_storage_impl();
+        let bundle = export_waypoint_bundle(db.as_ref()).unwrap();
+        let epoch_ending_ledger_infos = bundle.epoch_ending_ledger_infos();
+        assert!(epoch_ending_ledger_infos.len() > 1);
+
+        // The bundle verifies from genesis, up to the latest epoch ending ledger info.
+        let genesis_waypoint =
+            Waypoint::new_epoch_boundary(epoch_ending_ledger_infos[0].ledger_info()).unwrap();
+        let epoch_state = bundle.verify(&genesis_waypoint).unwrap();
+        assert_eq!(epoch_state, db.get_latest_epoch_state().unwrap());
+    }
+
     #[test]
     fn test_backup_compaction() {
         let db = test_execution_with_storage_impl();
@@ -0,0 +1,354 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::{bail, ensure, Result};
+use aptos_backup_cli::utils::RocksdbOpt;
+use aptos_config::config::{
+    StorageDirPaths, BUFFERED_STATE_TARGET_ITEMS, DEFAULT_MAX_NUM_NODES_PER_LRU_CACHE_SHARD,
+    NO_OP_STORAGE_PRUNER_CONFIG,
+};
+use aptos_crypto::{hash::CryptoHash, HashValue};
+use aptos_db::AptosDB;
+use aptos_logger::{info, warn};
+use aptos_storage_interface::{AptosDbError, DbReader};
+use aptos_types::{
+    state_store::{state_key::StateKey, state_value::StateValue},
+    transaction::{TransactionInfo, Version},
+    write_set::TransactionWrite,
+};
+use clap::Parser;
+use rayon::prelude::*;
+use std::{collections::BTreeMap, fmt, path::PathBuf, sync::Arc};
+
+/// Verifies a range of versions in a DB, which can be a live one as it's opened read-only: the
+/// transaction infos are checked against the transaction accumulator, and a sample of the state
+/// values written in the range are checked against the state tree.
+#[derive(Parser)]
+pub struct Opt {
+    #[clap(long, help = "The first version to verify")]
+    from: Version,
+
+    #[clap(long, help = "The last version to verify, inclusive")]
+    to: Version,
+
+    #[clap(long, value_parser)]
+    db_dir: PathBuf,
+
+    #[clap(flatten)]
+    rocksdb_opt: RocksdbOpt,
+
+    #[clap(
+        long,
+        default_value_t = 10000,
+        help = "The number of versions verified together, the chunks are verified in parallel"
+    )]
+    chunk_size: usize,
+
+    #[clap(
+        long,
+        default_value_t = 10,
+        help = "The number of state values to verify against the state tree in each chunk"
+    )]
+    state_samples_per_chunk: usize,
+}
+
+impl Opt {
+    pub async fn run(self) -> Result<()> {
+        let db = AptosDB::open(
+            StorageDirPaths::from_path(self.db_dir.as_path()),
+            true,
+            NO_OP_STORAGE_PRUNER_CONFIG,
+            self.rocksdb_opt.into(),
+            false,
+            BUFFERED_STATE_TARGET_ITEMS,
+            DEFAULT_MAX_NUM_NODES_PER_LRU_CACHE_SHARD,
+            None,
+        )?;
+        let verifier =
+            RangeVerifier::new(Arc::new(db), self.chunk_size, self.state_samples_per_chunk)?;
+
+        let report = verifier.verify(self.from, self.to)?;
+        info!(
+            first_version = report.first_version,
+            last_version = report.last_version,
+            num_state_values_verified = report.num_state_values_verified,
+            num_state_values_skipped = report.num_state_values_skipped,
+            num_mismatches = report.mismatches.len(),
+            "Range verified."
+        );
+        if !report.mismatches.is_empty() {
+            for mismatch in &report.mismatches {
+                println!("{}", mismatch);
+            }
+            bail!("Found {} mismatches.", report.mismatches.len());
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub enum Mismatch {
+    /// The transaction info at `version` is not proven by the transaction accumulator.
+    TransactionInfo { version: Version, error: String },
+    /// The state value of `state_key` at the state checkpoint `version` is not the one last
+    /// written, or is not proven by the state tree.
+    StateValue {
+        version: Version,
+        state_key: StateKey,
+        error: String,
+    },
+}
+
+impl fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Mismatch::TransactionInfo { version, error } => {
+                write!(
+                    f,
+                    "Transaction info mismatch at version {}: {}",
+                    version, error
+                )
+            },
+            Mismatch::StateValue {
+                version,
+                state_key,
+                error,
+            } => write!(
+                f,
+                "State value mismatch at version {} for {:?}: {}",
+                version, state_key, error
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct VerifyRangeReport {
+    pub first_version: Version,
+    pub last_version: Version,
+    pub num_state_values_verified: usize,
+    /// The sampled state values which couldn't be read, e.g. because the state tree is pruned.
+    pub num_state_values_skipped: usize,
+    pub mismatches: Vec<Mismatch>,
+}
+
+impl VerifyRangeReport {
+    fn merge(mut self, other: Self) -> Self {
+        self.num_state_values_verified += other.num_state_values_verified;
+        self.num_state_values_skipped += other.num_state_values_skipped;
+        self.mismatches.extend(other.mismatches);
+        self
+    }
+}
+
+pub struct RangeVerifier {
+    db: Arc<dyn DbReader>,
+    chunk_size: usize,
+    state_samples_per_chunk: usize,
+    /// The version and transaction accumulator root hash of the latest ledger info, which all
+    /// the transaction infos are verified against.
+    ledger_version: Version,
+    accumulator_root_hash: HashValue,
+}
+
+impl RangeVerifier {
+    pub fn new(
+        db: Arc<dyn DbReader>,
+        chunk_size: usize,
+        state_samples_per_chunk: usize,
+    ) -> Result<Self> {
+        ensure!(chunk_size > 0, "Chunk size must be positive.");
+        let ledger_info = db.get_latest_ledger_info()?;
+        Ok(Self {
+            db,
+            chunk_size,
+            state_samples_per_chunk,
+            ledger_version: ledger_info.ledger_info().version(),
+            accumulator_root_hash: ledger_info.ledger_info().transaction_accumulator_hash(),
+        })
+    }
+
+    /// Verifies the versions from `from` to `to` inclusive, clamped to the versions in the DB.
+    pub fn verify(&self, from: Version, to: Version) -> Result<VerifyRangeReport> {
+        let first_version = std::cmp::max(
+            from,
+            self.db
+                .get_first_txn_version()?
+                .ok_or_else(|| AptosDbError::NotFound("First txn version".to_string()))?,
+        );
+        let last_version = std::cmp::min(to, self.ledger_version);
+        ensure!(
+            first_version <= last_version,
+            "Nothing to verify between versions {} and {}, the DB has the versions from {} to {}.",
+            from,
+            to,
+            first_version,
+            self.ledger_version,
+        );
+        info!(
+            first_version = first_version,
+            last_version = last_version,
+            ledger_version = self.ledger_version,
+            "Verifying range."
+        );
+
+        let chunks: Vec<_> = (first_version..=last_version)
+            .step_by(self.chunk_size)
+            .map(|start| {
+                let end = std::cmp::min(start + self.chunk_size as u64 - 1, last_version);
+                (start, end - start + 1)
+            })
+            .collect();
+        let report = chunks
+            .into_par_iter()
+            .map(|(start, limit)| self.verify_chunk(start, limit))
+            .try_reduce(VerifyRangeReport::default, |a, b| Ok(a.merge(b)))?;
+
+        Ok(VerifyRangeReport {
+            first_version,
+            last_version,
+            ..report
+        })
+    }
+
+    fn verify_chunk(&self, start: Version, limit: u64) -> Result<VerifyRangeReport> {
+        let txn_infos: Vec<TransactionInfo> = self
+            .db
+            .get_transaction_info_iterator(start, limit)?
+            .collect::<aptos_storage_interface::Result<_>>()?;
+        ensure!(
+            txn_infos.len() as u64 == limit,
+            "Expecting {} transaction infos from version {}, got {}.",
+            limit,
+            start,
+            txn_infos.len(),
+        );
+
+        let mut report = VerifyRangeReport::default();
+        self.verify_txn_infos(start, &txn_infos, &mut report)?;
+        self.verify_state_samples(start, &txn_infos, &mut report)?;
+
+        if start % 100_000 < self.chunk_size as u64 {
+            info!(version = start, "Verification in progress.");
+        }
+        Ok(report)
+    }
+
+    /// Recomputes the accumulator root hash from the hashes of the transaction infos of the chunk
+    /// and the range proof. If they don't match, verifies the versions one by one to locate the
+    /// mismatches.
+    fn verify_txn_infos(
+        &self,
+        start: Version,
+        txn_infos: &[TransactionInfo],
+        report: &mut VerifyRangeReport,
+    ) -> Result<()> {
+        let txn_info_hashes: Vec<_> = txn_infos.iter().map(CryptoHash::hash).collect();
+        let range_proof = self.db.get_transaction_accumulator_range_proof(
+            start,
+            txn_infos.len() as u64,
+            self.ledger_version,
+        )?;
+        if range_proof
+            .verify(self.accumulator_root_hash, Some(start), &txn_info_hashes)
+            .is_ok()
+        {
+            return Ok(());
+        }
+
+        for (version, txn_info_hash) in (start..).zip(txn_info_hashes) {
+            let proof =
+                self.db
+                    .get_transaction_accumulator_range_proof(version, 1, self.ledger_version)?;
+            if let Err(error) =
+                proof.verify(self.accumulator_root_hash, Some(version), &[txn_info_hash])
+            {
+                report.mismatches.push(Mismatch::TransactionInfo {
+                    version,
+                    error: error.to_string(),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Verifies a sample of the state values written in the chunk against the state tree at the
+    /// last state checkpoint of the chunk.
+    fn verify_state_samples(
+        &self,
+        start: Version,
+        txn_infos: &[TransactionInfo],
+        report: &mut VerifyRangeReport,
+    ) -> Result<()> {
+        let checkpoint = (start..)
+            .zip(txn_infos)
+            .filter_map(|(version, txn_info)| {
+                txn_info
+                    .state_checkpoint_hash()
+                    .map(|root_hash| (version, root_hash))
+            })
+            .last();
+        let (checkpoint_version, root_hash) = match checkpoint {
+            Some(checkpoint) => checkpoint,
+            None => return Ok(()),
+        };
+        if self.state_samples_per_chunk == 0 {
+            return Ok(());
+        }
+
+        // The value of each key at the checkpoint is the one it was last written.
+        let mut latest_writes: BTreeMap<StateKey, Option<StateValue>> = BTreeMap::new();
+        for write_set in self
+            .db
+            .get_write_set_iterator(start, checkpoint_version - start + 1)?
+        {
+            for (state_key, write_op) in &write_set? {
+                latest_writes.insert(state_key.clone(), write_op.as_state_value());
+            }
+        }
+        let step = std::cmp::max(latest_writes.len() / self.state_samples_per_chunk, 1);
+
+        for (state_key, expected_value) in latest_writes
+            .into_iter()
+            .step_by(step)
+            .take(self.state_samples_per_chunk)
+        {
+            let (value, proof) = match self.db.get_state_value_with_proof_by_version_ext(
+                &state_key,
+                checkpoint_version,
+                0,
+            ) {
+                Ok(value_and_proof) => value_and_proof,
+                Err(error) => {
+                    warn!(
+                        version = checkpoint_version,
+                        error = ?error,
+                        "Failed to read state value with proof, skipping."
+                    );
+                    report.num_state_values_skipped += 1;
+                    continue;
+                },
+            };
+
+            let result = if value != expected_value {
+                Err(format!(
+                    "value in DB {:?}, expected {:?}",
+                    value, expected_value
+                ))
+            } else {
+                proof
+                    .verify(root_hash, state_key.hash(), value.as_ref())
+                    .map_err(|error| error.to_string())
+            };
+            match result {
+                Ok(()) => report.num_state_values_verified += 1,
+                Err(error) => report.mismatches.push(Mismatch::StateValue {
+                    version: checkpoint_version,
+                    state_key,
+                    error,
+                }),
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,114 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use anyhow::{ensure, Result};
+use aptos_backup_cli::utils::RocksdbOpt;
+use aptos_config::config::{
+    StorageDirPaths, BUFFERED_STATE_TARGET_ITEMS, DEFAULT_MAX_NUM_NODES_PER_LRU_CACHE_SHARD,
+    NO_OP_STORAGE_PRUNER_CONFIG,
+};
+use aptos_db::AptosDB;
+use aptos_logger::info;
+use aptos_storage_interface::DbReader;
+use aptos_types::{waypoint::Waypoint, waypoint_bundle::WaypointBundle};
+use clap::{Parser, Subcommand};
+use std::{fs, path::PathBuf};
+
+/// Exports and verifies waypoint bundles, which let a fresh node bootstrap from a trusted
+/// waypoint without fetching the epoch ending ledger infos from the network.
+#[derive(Subcommand)]
+pub enum Command {
+    #[clap(about = "Export the waypoint bundle of the latest epoch ending ledger info in a DB")]
+    Export(ExportOpt),
+    #[clap(about = "Verify a waypoint bundle from a trusted root")]
+    Verify(VerifyOpt),
+}
+
+#[derive(Parser)]
+pub struct ExportOpt {
+    #[clap(long, value_parser)]
+    db_dir: PathBuf,
+
+    #[clap(flatten)]
+    rocksdb_opt: RocksdbOpt,
+
+    #[clap(long, value_parser, help = "The file to write the bundle to")]
+    output_path: PathBuf,
+}
+
+#[derive(Parser)]
+pub struct VerifyOpt {
+    #[clap(long, value_parser)]
+    bundle_path: PathBuf,
+
+    #[clap(
+        long,
+        help = "The waypoint the bundle is verified from, e.g. the genesis waypoint"
+    )]
+    trusted_root: Waypoint,
+}
+
+impl Command {
+    pub async fn run(self) -> Result<()> {
+        match self {
+            Command::Export(opt) => {
+                let db = AptosDB::open(
+                    StorageDirPaths::from_path(opt.db_dir.as_path()),
+                    true,
+                    NO_OP_STORAGE_PRUNER_CONFIG,
+                    opt.rocksdb_opt.into(),
+                    false,
+                    BUFFERED_STATE_TARGET_ITEMS,
+                    DEFAULT_MAX_NUM_NODES_PER_LRU_CACHE_SHARD,
+                    None,
+                )?;
+                let bundle = export_waypoint_bundle(&db)?;
+                fs::write(&opt.output_path, bcs::to_bytes(&bundle)?)?;
+                info!(
+                    waypoint = %bundle.waypoint(),
+                    num_epoch_ending_ledger_infos = bundle.epoch_ending_ledger_infos().len(),
+                    "Waypoint bundle exported."
+                );
+                println!("{}", bundle.waypoint());
+            },
+            Command::Verify(opt) => {
+                let bundle: WaypointBundle = bcs::from_bytes(&fs::read(&opt.bundle_path)?)?;
+                let epoch_state = bundle.verify(&opt.trusted_root)?;
+                info!(
+                    waypoint = %bundle.waypoint(),
+                    next_epoch = epoch_state.epoch,
+                    "Waypoint bundle verified."
+                );
+                println!("{}", bundle.waypoint());
+            },
+        }
+        Ok(())
+    }
+}
+
+/// Creates the waypoint bundle of the latest epoch ending ledger info in the DB, with all the
+/// epoch ending ledger infos since genesis.
+pub fn export_waypoint_bundle(db: &dyn DbReader) -> Result<WaypointBundle> {
+    let latest_ledger_info = db.get_latest_ledger_info()?;
+    let latest_ledger_info = latest_ledger_info.ledger_info();
+    let end_epoch = if latest_ledger_info.ends_epoch() {
+        latest_ledger_info.epoch() + 1
+    } else {
+        latest_ledger_info.epoch()
+    };
+
+    // The epoch ending ledger infos are returned in pages.
+    let mut epoch_ending_ledger_infos = vec![];
+    while (epoch_ending_ledger_infos.len() as u64) < end_epoch {
+        let start_epoch = epoch_ending_ledger_infos.len() as u64;
+        let proof = db.get_epoch_ending_ledger_infos(start_epoch, end_epoch)?;
+        ensure!(
+            !proof.ledger_info_with_sigs.is_empty(),
+            "No epoch ending ledger infos from epoch {}, the DB may be pruned.",
+            start_epoch,
+        );
+        epoch_ending_ledger_infos.extend(proof.ledger_info_with_sigs);
+    }
+
+    WaypointBundle::new(epoch_ending_ledger_infos)
+}
@@ -184,6 +184,29 @@ impl IndexerAsyncV2 {
             .map_or(0, |v| v.expect_version())
     }
 
+    /// Returns the version up to which a backfill is in progress, if any.
+    pub fn backfill_target_version(&self) -> Result<Option<Version>> {
+        Ok(self
+            .db
+            .get::<IndexerMetadataSchema>(&MetadataKey::TableInfoBackfillTargetVersion)?
+            .map(|v| v.expect_version()))
+    }
+
+    /// Records the version up to which a backfill is in progress, or clears it once the
+    /// backfill is done.
+    pub fn set_backfill_target_version(&self, target_version: Option<Version>) -> Result<()> {
+        match target_version {
+            Some(version) => self.db.put::<IndexerMetadataSchema>(
+                &MetadataKey::TableInfoBackfillTargetVersion,
+                &MetadataValue::Version(version),
+            )?,
+            None => self
+                .db
+                .delete::<IndexerMetadataSchema>(&MetadataKey::TableInfoBackfillTargetVersion)?,
+        }
+        Ok(())
+    }
+
     pub fn get_table_info(&self, handle: TableHandle) -> Result<Option<TableInfo>> {
         self.db.get::<TableInfoSchema>(&handle).map_err(Into::into)
     }
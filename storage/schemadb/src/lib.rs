@@ -18,33 +18,40 @@ mod metrics;
 #[macro_use]
 pub mod schema;
 pub mod iterator;
+pub mod perf;
 
 use crate::{
     metrics::{
         APTOS_SCHEMADB_BATCH_COMMIT_BYTES, APTOS_SCHEMADB_BATCH_COMMIT_LATENCY_SECONDS,
-        APTOS_SCHEMADB_DELETES_SAMPLED, APTOS_SCHEMADB_GET_BYTES,
-        APTOS_SCHEMADB_GET_LATENCY_SECONDS, APTOS_SCHEMADB_ITER_BYTES,
+        APTOS_SCHEMADB_BATCH_COMMIT_STAGE_SECONDS, APTOS_SCHEMADB_DELETES_SAMPLED,
+        APTOS_SCHEMADB_GET_BYTES, APTOS_SCHEMADB_GET_LATENCY_SECONDS, APTOS_SCHEMADB_ITER_BYTES,
         APTOS_SCHEMADB_ITER_LATENCY_SECONDS, APTOS_SCHEMADB_PUT_BYTES_SAMPLED,
         APTOS_SCHEMADB_SEEK_LATENCY_SECONDS,
     },
+    perf::with_write_perf_stats,
     schema::{KeyCodec, Schema, SeekKeyCodec, ValueCodec},
 };
 use anyhow::format_err;
 use aptos_infallible::Mutex;
 use aptos_logger::prelude::*;
-use aptos_storage_interface::{AptosDbError, Result as DbResult};
+use aptos_storage_interface::{
+    commit_recorder::{WriteBatchStats, COMMIT_RECORDER},
+    AptosDbError, Result as DbResult,
+};
 use iterator::{ScanDirection, SchemaIterator};
 use rand::Rng;
 use rocksdb::ErrorKind;
 /// Type alias to `rocksdb::ReadOptions`. See [`rocksdb doc`](https://github.com/pingcap/rust-rocksdb/blob/master/src/rocksdb_options.rs)
 pub use rocksdb::{
-    BlockBasedOptions, Cache, ColumnFamilyDescriptor, DBCompressionType, Options, ReadOptions,
-    SliceTransform, DEFAULT_COLUMN_FAMILY_NAME,
+    BlockBasedOptions, Cache, ColumnFamilyDescriptor, DBCompactionStyle, DBCompressionType,
+    Options, ReadOptions, SliceTransform, DEFAULT_COLUMN_FAMILY_NAME,
 };
 use std::{
     collections::{HashMap, HashSet},
     iter::Iterator,
     path::Path,
+    sync::atomic::{AtomicU64, Ordering},
+    time::{Duration, Instant},
 };
 
 pub type ColumnFamilyName = &'static str;
@@ -60,12 +67,15 @@ enum WriteOp {
 #[derive(Debug)]
 pub struct SchemaBatch {
     rows: Mutex<HashMap<ColumnFamilyName, Vec<WriteOp>>>,
+    /// Time spent encoding the keys and values, in nanoseconds.
+    serialization_nanos: AtomicU64,
 }
 
 impl Default for SchemaBatch {
     fn default() -> Self {
         Self {
             rows: Mutex::new(HashMap::new()),
+            serialization_nanos: AtomicU64::new(0),
         }
     }
 }
@@ -82,8 +92,10 @@ impl SchemaBatch {
         key: &S::Key,
         value: &S::Value,
     ) -> aptos_storage_interface::Result<()> {
+        let start = Instant::now();
         let key = <S::Key as KeyCodec<S>>::encode_key(key)?;
         let value = <S::Value as ValueCodec<S>>::encode_value(value)?;
+        self.add_serialization_time(start.elapsed());
         self.rows
             .lock()
             .entry(S::COLUMN_FAMILY_NAME)
@@ -95,7 +107,9 @@ impl SchemaBatch {
 
     /// Adds a delete operation to the batch.
     pub fn delete<S: Schema>(&self, key: &S::Key) -> DbResult<()> {
+        let start = Instant::now();
         let key = <S::Key as KeyCodec<S>>::encode_key(key)?;
+        self.add_serialization_time(start.elapsed());
         self.rows
             .lock()
             .entry(S::COLUMN_FAMILY_NAME)
@@ -104,6 +118,15 @@ impl SchemaBatch {
 
         Ok(())
     }
+
+    fn add_serialization_time(&self, time: Duration) {
+        self.serialization_nanos
+            .fetch_add(time.as_nanos() as u64, Ordering::Relaxed);
+    }
+
+    fn serialization_time(&self) -> Duration {
+        Duration::from_nanos(self.serialization_nanos.load(Ordering::Relaxed))
+    }
 }
 
 #[derive(Debug)]
@@ -331,6 +354,7 @@ impl DB {
         let sampling_rate_pct = 1;
         let sampled_kv_bytes = should_sample(sampling_rate_pct);
 
+        let build_start = Instant::now();
         let mut db_batch = rocksdb::WriteBatch::default();
         for (cf_name, rows) in rows_locked.iter() {
             let cf_handle = self.get_cf_handle(cf_name)?;
@@ -342,10 +366,14 @@ impl DB {
             }
         }
         let serialized_size = db_batch.size_in_bytes();
+        let num_rows = db_batch.len();
+        let build_time = build_start.elapsed();
 
-        self.inner
-            .write_opt(db_batch, &default_write_options())
-            .into_db_res()?;
+        let write_start = Instant::now();
+        let (res, write_perf_stats) =
+            with_write_perf_stats(|| self.inner.write_opt(db_batch, &default_write_options()));
+        res.into_db_res()?;
+        let write_time = write_start.elapsed();
 
         // Bump counters only after DB write succeeds.
         if sampled_kv_bytes {
@@ -370,6 +398,31 @@ impl DB {
         APTOS_SCHEMADB_BATCH_COMMIT_BYTES
             .with_label_values(&[&self.name])
             .observe(serialized_size as f64);
+        let stats = WriteBatchStats {
+            db_name: self.name.clone(),
+            num_rows,
+            bytes: serialized_size,
+            serialization: batch.serialization_time(),
+            build: build_time,
+            wal: write_perf_stats.wal,
+            memtable: write_perf_stats.memtable,
+            write_delay: write_perf_stats.write_delay,
+            write: write_time,
+            finished_at: Instant::now(),
+        };
+        for (stage, time) in [
+            ("serialization", stats.serialization),
+            ("build", stats.build),
+            ("wal", stats.wal),
+            ("memtable", stats.memtable),
+            ("write_delay", stats.write_delay),
+            ("write", stats.write),
+        ] {
+            APTOS_SCHEMADB_BATCH_COMMIT_STAGE_SECONDS
+                .with_label_values(&[&self.name, stage])
+                .observe(time.as_secs_f64());
+        }
+        COMMIT_RECORDER.record_batch(stats);
 
         Ok(())
     }
@@ -96,6 +96,19 @@ pub static APTOS_SCHEMADB_BATCH_COMMIT_BYTES: Lazy<HistogramVec> = Lazy::new(||
     .unwrap()
 });
 
+pub static APTOS_SCHEMADB_BATCH_COMMIT_STAGE_SECONDS: Lazy<HistogramVec> = Lazy::new(|| {
+    register_histogram_vec!(
+        // metric name
+        "aptos_schemadb_batch_commit_stage_seconds",
+        // metric description
+        "Aptos schemadb schema batch commit time in seconds, by stage",
+        // metric labels (dimensions)
+        &["db_name", "stage"],
+        exponential_buckets(/*start=*/ 1e-6, /*factor=*/ 2.0, /*count=*/ 24).unwrap(),
+    )
+    .unwrap()
+});
+
 pub static APTOS_SCHEMADB_PUT_BYTES_SAMPLED: Lazy<HistogramVec> = Lazy::new(|| {
     register_histogram_vec!(
         // metric name
@@ -0,0 +1,85 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Per-operation RocksDB perf counters, for profiling the read amplification of individual reads
+//! and the breakdown of individual writes.
+
+use rocksdb::perf::{set_perf_stats, PerfContext, PerfMetric, PerfStatsLevel};
+use std::time::Duration;
+
+/// The RocksDB work done by a read, see `with_read_perf_stats`.
+///
+/// RocksDB does not report which level a read was served from, the number of sorted runs
+/// (memtables, L0 files and levels) probed stands in for it.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct ReadPerfStats {
+    /// Number of blocks found in the block cache.
+    pub block_cache_hits: u64,
+    /// Number of blocks read from the SST files, i.e., block cache misses.
+    pub block_reads: u64,
+    /// Number of memtables and SST files seeked by iterators.
+    pub sorted_runs_probed: u64,
+    /// Number of SST files skipped thanks to their bloom filters.
+    pub bloom_filter_skips: u64,
+    /// Number of stale versions and tombstones skipped.
+    pub internal_keys_skipped: u64,
+}
+
+impl ReadPerfStats {
+    /// Whether all the blocks needed were in the block cache (or the memtables).
+    pub fn is_cache_hit(&self) -> bool {
+        self.block_reads == 0
+    }
+}
+
+/// Runs `f` on the current thread with the RocksDB perf counters enabled, and returns the work done
+/// by the reads in `f`.
+pub fn with_read_perf_stats<T>(f: impl FnOnce() -> T) -> (T, ReadPerfStats) {
+    set_perf_stats(PerfStatsLevel::EnableCount);
+    let mut context = PerfContext::default();
+    context.reset();
+
+    let result = f();
+
+    let stats = ReadPerfStats {
+        block_cache_hits: context.metric(PerfMetric::BlockCacheHitCount),
+        block_reads: context.metric(PerfMetric::BlockReadCount),
+        sorted_runs_probed: context.metric(PerfMetric::SeekChildSeekCount),
+        bloom_filter_skips: context.metric(PerfMetric::BloomSstMissCount),
+        internal_keys_skipped: context.metric(PerfMetric::InternalKeySkippedCount)
+            + context.metric(PerfMetric::InternalDeleteSkippedCount),
+    };
+    set_perf_stats(PerfStatsLevel::Disable);
+
+    (result, stats)
+}
+
+/// Where the time went in a write, see `with_write_perf_stats`.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+pub struct WritePerfStats {
+    /// Time spent writing the WAL, including the fsync of synchronous writes.
+    pub wal: Duration,
+    /// Time spent inserting into the memtables.
+    pub memtable: Duration,
+    /// Time spent stalled because of pending flushes and compactions.
+    pub write_delay: Duration,
+}
+
+/// Runs `f` on the current thread with the RocksDB perf timers enabled, and returns the time spent
+/// by the writes in `f`.
+pub fn with_write_perf_stats<T>(f: impl FnOnce() -> T) -> (T, WritePerfStats) {
+    set_perf_stats(PerfStatsLevel::EnableTimeExceptForMutex);
+    let mut context = PerfContext::default();
+    context.reset();
+
+    let result = f();
+
+    let stats = WritePerfStats {
+        wal: Duration::from_nanos(context.metric(PerfMetric::WriteWalTime)),
+        memtable: Duration::from_nanos(context.metric(PerfMetric::WriteMemtableTime)),
+        write_delay: Duration::from_nanos(context.metric(PerfMetric::WriteDelayTime)),
+    };
+    set_perf_stats(PerfStatsLevel::Disable);
+
+    (result, stats)
+}
@@ -0,0 +1,225 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! A flight recorder of the slow DB commits. The breakdowns of the latest batches written to the
+//! DB are kept in a ring buffer, and when a commit takes longer than the threshold, it's recorded
+//! along with the breakdowns of all the batches written while it was in progress. The latest slow
+//! commits are exposed by the inspection service.
+
+use aptos_types::transaction::Version;
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use std::{
+    collections::VecDeque,
+    sync::atomic::{AtomicBool, AtomicU64, Ordering},
+    time::{Duration, Instant, SystemTime},
+};
+
+pub static COMMIT_RECORDER: Lazy<CommitRecorder> = Lazy::new(CommitRecorder::new);
+
+/// The number of latest batches kept to be attributed to the slow commits, which is well above the
+/// number of batches written by a commit.
+const NUM_RECENT_BATCHES: usize = 4096;
+
+/// Where the time went when writing a batch to a DB.
+#[derive(Clone, Debug)]
+pub struct WriteBatchStats {
+    pub db_name: String,
+    pub num_rows: usize,
+    pub bytes: usize,
+    /// Time spent encoding the keys and values put in the batch.
+    pub serialization: Duration,
+    /// Time spent building the RocksDB write batch.
+    pub build: Duration,
+    /// Time spent writing the WAL, including the fsync.
+    pub wal: Duration,
+    /// Time spent inserting into the memtables.
+    pub memtable: Duration,
+    /// Time spent stalled by RocksDB because of pending flushes and compactions.
+    pub write_delay: Duration,
+    /// Time spent in the RocksDB write in total.
+    pub write: Duration,
+    pub finished_at: Instant,
+}
+
+/// A commit which took longer than the threshold.
+#[derive(Clone, Debug)]
+pub struct SlowCommit {
+    /// The name of the DB API, e.g. `pre_commit_ledger`.
+    pub operation: &'static str,
+    /// The last version committed.
+    pub version: Version,
+    pub timestamp: SystemTime,
+    pub started_at: Instant,
+    pub latency: Duration,
+    /// All the batches written to the DB while the commit was in progress, which can include the
+    /// ones written concurrently by e.g. the pruners.
+    pub batches: Vec<WriteBatchStats>,
+}
+
+pub struct CommitRecorder {
+    enabled: AtomicBool,
+    threshold_us: AtomicU64,
+    capacity: AtomicU64,
+    recent_batches: Mutex<VecDeque<WriteBatchStats>>,
+    slow_commits: Mutex<VecDeque<SlowCommit>>,
+}
+
+impl CommitRecorder {
+    fn new() -> Self {
+        Self {
+            enabled: AtomicBool::new(false),
+            threshold_us: AtomicU64::new(0),
+            capacity: AtomicU64::new(0),
+            recent_batches: Mutex::new(VecDeque::new()),
+            slow_commits: Mutex::new(VecDeque::new()),
+        }
+    }
+
+    /// Starts recording the commits taking longer than `threshold`, keeping the latest `capacity`
+    /// ones.
+    pub fn enable(&self, threshold: Duration, capacity: usize) {
+        self.threshold_us
+            .store(threshold.as_micros() as u64, Ordering::Relaxed);
+        self.capacity.store(capacity as u64, Ordering::Relaxed);
+        {
+            let mut slow_commits = self.slow_commits.lock();
+            let num_to_evict = slow_commits.len().saturating_sub(capacity);
+            slow_commits.drain(..num_to_evict);
+        }
+        self.enabled.store(capacity > 0, Ordering::Release);
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.load(Ordering::Acquire)
+    }
+
+    pub fn record_batch(&self, stats: WriteBatchStats) {
+        if !self.is_enabled() {
+            return;
+        }
+        let mut recent_batches = self.recent_batches.lock();
+        if recent_batches.len() >= NUM_RECENT_BATCHES {
+            recent_batches.pop_front();
+        }
+        recent_batches.push_back(stats);
+    }
+
+    /// Starts timing a commit of the versions up to `version`, see `CommitRecording::finish`.
+    pub fn start_commit(&self, operation: &'static str, version: Version) -> CommitRecording<'_> {
+        CommitRecording {
+            recorder: self,
+            operation,
+            version,
+            timestamp: SystemTime::now(),
+            started_at: Instant::now(),
+        }
+    }
+
+    /// Returns the recorded slow commits, oldest first.
+    pub fn slow_commits(&self) -> Vec<SlowCommit> {
+        self.slow_commits.lock().iter().cloned().collect()
+    }
+
+    fn finish_commit(&self, recording: CommitRecording) {
+        let latency = recording.started_at.elapsed();
+        let threshold = Duration::from_micros(self.threshold_us.load(Ordering::Relaxed));
+        if !self.is_enabled() || latency < threshold {
+            return;
+        }
+
+        let batches = self
+            .recent_batches
+            .lock()
+            .iter()
+            .filter(|batch| batch.finished_at >= recording.started_at)
+            .cloned()
+            .collect();
+        let mut slow_commits = self.slow_commits.lock();
+        if slow_commits.len() as u64 >= self.capacity.load(Ordering::Relaxed) {
+            slow_commits.pop_front();
+        }
+        slow_commits.push_back(SlowCommit {
+            operation: recording.operation,
+            version: recording.version,
+            timestamp: recording.timestamp,
+            started_at: recording.started_at,
+            latency,
+            batches,
+        });
+    }
+}
+
+/// A commit in progress, see `CommitRecorder::start_commit`.
+pub struct CommitRecording<'a> {
+    recorder: &'a CommitRecorder,
+    operation: &'static str,
+    version: Version,
+    timestamp: SystemTime,
+    started_at: Instant,
+}
+
+impl CommitRecording<'_> {
+    /// Records the commit if it took longer than the threshold. Failed commits are not finished,
+    /// so they're not recorded.
+    pub fn finish(self) {
+        self.recorder.finish_commit(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{CommitRecorder, WriteBatchStats};
+    use std::time::{Duration, Instant};
+
+    fn batch(db_name: &str) -> WriteBatchStats {
+        WriteBatchStats {
+            db_name: db_name.to_string(),
+            num_rows: 10,
+            bytes: 1000,
+            serialization: Duration::from_micros(10),
+            build: Duration::from_micros(5),
+            wal: Duration::from_millis(2),
+            memtable: Duration::from_micros(50),
+            write_delay: Duration::ZERO,
+            write: Duration::from_millis(3),
+            finished_at: Instant::now(),
+        }
+    }
+
+    #[test]
+    fn test_slow_commits() {
+        let recorder = CommitRecorder::new();
+        recorder.enable(Duration::from_millis(10), 2);
+
+        // A batch before the commit isn't attributed to it.
+        recorder.record_batch(batch("before"));
+        let recording = recorder.start_commit("pre_commit_ledger", 100);
+        recorder.record_batch(batch("during"));
+        std::thread::sleep(Duration::from_millis(10));
+        recording.finish();
+
+        // A fast commit isn't recorded.
+        recorder.start_commit("commit_ledger", 100).finish();
+
+        let slow_commits = recorder.slow_commits();
+        assert_eq!(slow_commits.len(), 1);
+        assert_eq!(slow_commits[0].operation, "pre_commit_ledger");
+        assert_eq!(slow_commits[0].version, 100);
+        assert!(slow_commits[0].latency >= Duration::from_millis(10));
+        assert_eq!(slow_commits[0].batches.len(), 1);
+        assert_eq!(slow_commits[0].batches[0].db_name, "during");
+
+        // Only the latest slow commits are kept.
+        recorder.enable(Duration::ZERO, 2);
+        for version in 101..104 {
+            recorder.start_commit("commit_ledger", version).finish();
+        }
+        let versions: Vec<_> = recorder
+            .slow_commits()
+            .iter()
+            .map(|commit| commit.version)
+            .collect();
+        assert_eq!(versions, vec![102, 103]);
+    }
+}
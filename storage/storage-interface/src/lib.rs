@@ -31,19 +31,23 @@ use aptos_types::{
     write_set::WriteSet,
 };
 use serde::{Deserialize, Serialize};
-use std::{collections::HashMap, sync::Arc};
+use std::{collections::HashMap, sync::Arc, time::Duration};
 use thiserror::Error;
 
 pub mod block_info;
 pub mod chunk_to_commit;
+pub mod commit_recorder;
 pub mod errors;
 mod ledger_summary;
 mod metrics;
 #[cfg(any(test, feature = "fuzzing"))]
 pub mod mock;
+pub mod pruner_progress;
+pub mod read_view;
+pub mod state_read_profiler;
 pub mod state_store;
 
-use crate::chunk_to_commit::ChunkToCommit;
+use crate::{chunk_to_commit::ChunkToCommit, read_view::ReadView};
 use aptos_scratchpad::SparseMerkleTree;
 pub use aptos_types::block_info::BlockHeight;
 use aptos_types::state_store::state_key::prefix::StateKeyPrefix;
@@ -178,6 +182,11 @@ pub trait DbReader: Send + Sync {
         /// [AptosDB::get_first_write_set_version]: ../aptosdb/struct.AptosDB.html#method.get_first_write_set_version
         fn get_first_write_set_version(&self) -> Result<Option<Version>>;
 
+        /// See [AptosDB::get_first_state_value_version].
+        ///
+        /// [AptosDB::get_first_state_value_version]: ../aptosdb/struct.AptosDB.html#method.get_first_state_value_version
+        fn get_first_state_value_version(&self) -> Result<Option<Version>>;
+
         /// See [AptosDB::get_transaction_outputs].
         ///
         /// [AptosDB::get_transaction_outputs]: ../aptosdb/struct.AptosDB.html#method.get_transaction_outputs
@@ -457,6 +466,10 @@ pub trait DbReader: Send + Sync {
             version: Version,
             index: u64,
         ) -> Result<ContractEvent>;
+
+        /// Pins a consistent view of the DB at `version`, which the pruners don't prune until the
+        /// view is dropped or `lease` expires without being renewed. See [read_view::ReadView].
+        fn pin_read_view(&self, version: Version, lease: Duration) -> Result<ReadView>;
     ); // end delegated
 
     /// Returns the latest ledger info.
@@ -583,6 +596,18 @@ pub trait DbWriter: Send + Sync {
     ) -> Result<()> {
         unimplemented!()
     }
+
+    /// Makes the ledger and state pruners prune everything below `version`, regardless of the
+    /// prune windows. The pruning happens in the background, at the pace of the pruners.
+    fn prune_to_version(&self, version: Version) -> Result<()> {
+        unimplemented!()
+    }
+
+    /// Enables or disables slowing down the pruners when the commits are slow, keeping the
+    /// configured thresholds. Returns whether the pacing was enabled before.
+    fn set_pruner_pacing_enabled(&self, enable: bool) -> Result<bool> {
+        unimplemented!()
+    }
 }
 
 #[derive(Clone)]
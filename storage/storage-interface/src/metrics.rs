@@ -3,7 +3,10 @@
 
 #![forbid(unsafe_code)]
 
-use aptos_metrics_core::{exponential_buckets, register_histogram_vec, HistogramVec};
+use aptos_metrics_core::{
+    exponential_buckets, register_histogram_vec, register_int_counter, register_int_gauge_vec,
+    HistogramVec, IntCounter, IntGaugeVec,
+};
 use once_cell::sync::Lazy;
 
 pub static TIMER: Lazy<HistogramVec> = Lazy::new(|| {
@@ -15,3 +18,20 @@ pub static TIMER: Lazy<HistogramVec> = Lazy::new(|| {
     )
     .unwrap()
 });
+
+pub static READ_VIEW_PINS: Lazy<IntGaugeVec> = Lazy::new(|| {
+    register_int_gauge_vec!(
+        "aptos_storage_read_view_pins",
+        "The number of pinned read views, and the range of versions they pin (-1 if none).",
+        &["tag"]
+    )
+    .unwrap()
+});
+
+pub static READ_VIEW_LEASES_EXPIRED: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "aptos_storage_read_view_leases_expired",
+        "The number of pinned read views dropped because their lease expired."
+    )
+    .unwrap()
+});
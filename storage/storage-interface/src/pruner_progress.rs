@@ -0,0 +1,92 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! The progress of the DB pruners, as reported by the pruners after every batch, and exposed by
+//! the inspection service.
+
+use aptos_types::transaction::Version;
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use std::{collections::BTreeMap, time::Duration};
+
+pub static PRUNER_PROGRESS: Lazy<PrunerProgressRegistry> =
+    Lazy::new(PrunerProgressRegistry::default);
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct PrunerProgress {
+    /// The versions below this one are pruned.
+    pub progress: Version,
+    /// The version the pruner is pruning to.
+    pub target: Version,
+    /// The number of versions pruned since the node started.
+    pub versions_pruned: u64,
+    /// The recent pruning rate.
+    pub versions_per_second: f64,
+    /// The time the pruner waited after the last batch, because of the commit latency.
+    pub pacing_delay: Duration,
+}
+
+impl PrunerProgress {
+    /// The number of versions left to prune.
+    pub fn backlog(&self) -> u64 {
+        self.target.saturating_sub(self.progress)
+    }
+
+    /// The estimated time to prune the backlog at the recent pruning rate, if there's one.
+    pub fn eta(&self) -> Option<Duration> {
+        if self.backlog() == 0 {
+            return Some(Duration::ZERO);
+        }
+        (self.versions_per_second > 0.0)
+            .then(|| Duration::from_secs_f64(self.backlog() as f64 / self.versions_per_second))
+    }
+}
+
+#[derive(Default)]
+pub struct PrunerProgressRegistry {
+    pruners: Mutex<BTreeMap<&'static str, PrunerProgress>>,
+}
+
+impl PrunerProgressRegistry {
+    pub fn update(&self, pruner_name: &'static str, progress: PrunerProgress) {
+        self.pruners.lock().insert(pruner_name, progress);
+    }
+
+    pub fn get(&self, pruner_name: &str) -> Option<PrunerProgress> {
+        self.pruners.lock().get(pruner_name).cloned()
+    }
+
+    /// Returns the progress of all the pruners, by name.
+    pub fn get_all(&self) -> Vec<(&'static str, PrunerProgress)> {
+        self.pruners
+            .lock()
+            .iter()
+            .map(|(name, progress)| (*name, progress.clone()))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PrunerProgress;
+    use std::time::Duration;
+
+    #[test]
+    fn test_eta() {
+        let mut progress = PrunerProgress {
+            progress: 100,
+            target: 1100,
+            versions_pruned: 100,
+            versions_per_second: 0.0,
+            pacing_delay: Duration::ZERO,
+        };
+        assert_eq!(progress.backlog(), 1000);
+        assert_eq!(progress.eta(), None);
+
+        progress.versions_per_second = 100.0;
+        assert_eq!(progress.eta(), Some(Duration::from_secs(10)));
+
+        progress.progress = 1100;
+        assert_eq!(progress.eta(), Some(Duration::ZERO));
+    }
+}
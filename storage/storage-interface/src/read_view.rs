@@ -0,0 +1,235 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Pinned read views, for long-running readers (e.g. the indexer, or an API backfill) which need
+//! the data at a version to stay readable until they're done. The pruners don't prune the pinned
+//! versions. A pin is held under a lease, so that a stuck reader can't block the pruning forever:
+//! the reader needs to renew the lease before it expires, and an expired pin is dropped.
+
+use crate::{
+    db_ensure as ensure,
+    metrics::{READ_VIEW_LEASES_EXPIRED, READ_VIEW_PINS},
+    AptosDbError, Result,
+};
+use aptos_crypto::HashValue;
+use aptos_types::transaction::Version;
+use parking_lot::Mutex;
+use std::{
+    collections::HashMap,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+
+struct Pin {
+    /// The versions from this one are kept readable.
+    min_version: Version,
+    expires_at: Instant,
+}
+
+#[derive(Default)]
+struct PinsInner {
+    next_id: u64,
+    pins: HashMap<u64, Pin>,
+}
+
+impl PinsInner {
+    fn remove_expired(&mut self) {
+        let now = Instant::now();
+        let num_pins = self.pins.len();
+        self.pins.retain(|_, pin| pin.expires_at > now);
+        READ_VIEW_LEASES_EXPIRED.inc_by((num_pins - self.pins.len()) as u64);
+    }
+
+    fn min_pinned_version(&self) -> Option<Version> {
+        self.pins.values().map(|pin| pin.min_version).min()
+    }
+
+    fn update_metrics(&self) {
+        READ_VIEW_PINS
+            .with_label_values(&["count"])
+            .set(self.pins.len() as i64);
+        let versions = self.pins.values().map(|pin| pin.min_version);
+        READ_VIEW_PINS
+            .with_label_values(&["min_version"])
+            .set(versions.clone().min().map_or(-1, |v| v as i64));
+        READ_VIEW_PINS
+            .with_label_values(&["max_version"])
+            .set(versions.max().map_or(-1, |v| v as i64));
+    }
+}
+
+/// The read views pinned on a DB.
+#[derive(Default)]
+pub struct ReadViewPins {
+    inner: Mutex<PinsInner>,
+}
+
+impl ReadViewPins {
+    /// Pins the versions from `min_version` under a lease of `lease`. `ensure_readable` is called
+    /// after the pin is registered, and the pin is dropped if it fails, so that the data checked
+    /// by `ensure_readable` can't be pruned in between.
+    pub fn pin(
+        self: &Arc<Self>,
+        version: Version,
+        state_snapshot: Option<(Version, HashValue)>,
+        lease: Duration,
+        ensure_readable: impl FnOnce() -> Result<()>,
+    ) -> Result<ReadView> {
+        let min_version = state_snapshot.map_or(version, |(snapshot_version, _)| {
+            std::cmp::min(version, snapshot_version)
+        });
+
+        let mut inner = self.inner.lock();
+        inner.remove_expired();
+        let id = inner.next_id;
+        inner.next_id += 1;
+        inner.pins.insert(id, Pin {
+            min_version,
+            expires_at: Instant::now() + lease,
+        });
+        if let Err(error) = ensure_readable() {
+            inner.pins.remove(&id);
+            return Err(error);
+        }
+        inner.update_metrics();
+
+        Ok(ReadView {
+            id,
+            version,
+            state_snapshot,
+            pins: Arc::clone(self),
+        })
+    }
+
+    /// Runs `f` with the minimal version pinned by the read views, if any. No read view can be
+    /// pinned while `f` runs, so `f` can safely prune up to the version it's given.
+    pub fn with_min_pinned_version<T>(&self, f: impl FnOnce(Option<Version>) -> T) -> T {
+        let mut inner = self.inner.lock();
+        inner.remove_expired();
+        inner.update_metrics();
+        f(inner.min_pinned_version())
+    }
+
+    pub fn min_pinned_version(&self) -> Option<Version> {
+        self.with_min_pinned_version(|min_pinned_version| min_pinned_version)
+    }
+
+    fn renew(&self, id: u64, lease: Duration) -> Result<()> {
+        let mut inner = self.inner.lock();
+        inner.remove_expired();
+        let pin = inner
+            .pins
+            .get_mut(&id)
+            .ok_or_else(|| AptosDbError::Other("The read view lease expired.".to_string()))?;
+        pin.expires_at = Instant::now() + lease;
+        Ok(())
+    }
+
+    fn is_pinned(&self, id: u64) -> bool {
+        self.inner
+            .lock()
+            .pins
+            .get(&id)
+            .map_or(false, |pin| pin.expires_at > Instant::now())
+    }
+
+    fn unpin(&self, id: u64) {
+        let mut inner = self.inner.lock();
+        inner.pins.remove(&id);
+        inner.update_metrics();
+    }
+}
+
+/// A consistent view of the DB at a version, which stays readable until the view is dropped or its
+/// lease expires. The ledger data from the version, and the state at the version and at the
+/// latest state snapshot up to it, are not pruned.
+pub struct ReadView {
+    id: u64,
+    version: Version,
+    state_snapshot: Option<(Version, HashValue)>,
+    pins: Arc<ReadViewPins>,
+}
+
+impl ReadView {
+    pub fn version(&self) -> Version {
+        self.version
+    }
+
+    /// The version and root hash of the latest state snapshot up to the version, if any.
+    pub fn state_snapshot(&self) -> Option<(Version, HashValue)> {
+        self.state_snapshot
+    }
+
+    /// Extends the lease to `lease` from now. Fails if the lease already expired, in which case
+    /// the data may have been pruned.
+    pub fn renew(&self, lease: Duration) -> Result<()> {
+        self.pins.renew(self.id, lease)
+    }
+
+    /// Returns an error if the lease expired, in which case the data may have been pruned.
+    pub fn ensure_pinned(&self) -> Result<()> {
+        ensure!(
+            self.pins.is_pinned(self.id),
+            "The lease of the read view at version {} expired.",
+            self.version,
+        );
+        Ok(())
+    }
+}
+
+impl Drop for ReadView {
+    fn drop(&mut self) {
+        self.pins.unpin(self.id);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ReadViewPins;
+    use crate::AptosDbError;
+    use aptos_crypto::HashValue;
+    use std::{sync::Arc, thread::sleep, time::Duration};
+
+    #[test]
+    fn test_pins() {
+        let pins = Arc::new(ReadViewPins::default());
+        assert_eq!(pins.min_pinned_version(), None);
+
+        let lease = Duration::from_secs(60);
+        let view1 = pins
+            .pin(100, Some((90, HashValue::zero())), lease, || Ok(()))
+            .unwrap();
+        let view2 = pins.pin(200, None, lease, || Ok(())).unwrap();
+        assert_eq!(view1.version(), 100);
+        // The state snapshot is pinned too.
+        assert_eq!(pins.min_pinned_version(), Some(90));
+
+        // A view which isn't readable isn't pinned.
+        assert!(pins
+            .pin(50, None, lease, || Err(AptosDbError::Other(
+                "pruned".to_string()
+            )))
+            .is_err());
+        assert_eq!(pins.min_pinned_version(), Some(90));
+
+        drop(view1);
+        assert_eq!(pins.min_pinned_version(), Some(200));
+        drop(view2);
+        assert_eq!(pins.min_pinned_version(), None);
+    }
+
+    #[test]
+    fn test_lease_expiry() {
+        let pins = Arc::new(ReadViewPins::default());
+        let view = pins
+            .pin(100, None, Duration::from_millis(50), || Ok(()))
+            .unwrap();
+        view.renew(Duration::from_millis(50)).unwrap();
+        view.ensure_pinned().unwrap();
+
+        sleep(Duration::from_millis(100));
+        assert!(view.ensure_pinned().is_err());
+        assert_eq!(pins.min_pinned_version(), None);
+        assert!(view.renew(Duration::from_secs(60)).is_err());
+    }
+}
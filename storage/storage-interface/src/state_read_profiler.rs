@@ -0,0 +1,192 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! An optional profiler of the state reads served by the DB, for diagnosing hot keys and read
+//! amplification on live nodes. When enabled, the latest reads are kept in a ring buffer, which is
+//! exposed by the inspection service.
+
+use aptos_types::{state_store::state_key::StateKey, transaction::Version};
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use std::{
+    collections::{HashMap, VecDeque},
+    sync::atomic::{AtomicBool, Ordering},
+    time::Duration,
+};
+
+pub static STATE_READ_PROFILER: Lazy<StateReadProfiler> = Lazy::new(StateReadProfiler::new);
+
+/// A state read, along with the RocksDB work it took.
+#[derive(Clone, Debug)]
+pub struct StateReadRecord {
+    pub state_key: StateKey,
+    /// The version the read was made at.
+    pub version: Version,
+    /// The version the value was written at, if any.
+    pub value_version: Option<Version>,
+    /// Whether all the blocks needed were in the block cache.
+    pub cache_hit: bool,
+    pub block_cache_hits: u64,
+    pub block_reads: u64,
+    /// Number of memtables and SST files probed, which approximates how deep in the LSM tree the
+    /// value was found.
+    pub sorted_runs_probed: u64,
+    pub internal_keys_skipped: u64,
+    pub latency: Duration,
+}
+
+/// The aggregated reads of a key, see `StateReadProfiler::hot_keys`.
+#[derive(Clone, Debug)]
+pub struct HotKey {
+    pub state_key: StateKey,
+    pub num_reads: usize,
+    pub num_cache_misses: usize,
+    pub block_reads: u64,
+}
+
+pub struct StateReadProfiler {
+    enabled: AtomicBool,
+    records: Mutex<RingBuffer>,
+}
+
+struct RingBuffer {
+    capacity: usize,
+    records: VecDeque<StateReadRecord>,
+}
+
+impl StateReadProfiler {
+    fn new() -> Self {
+        Self {
+            enabled: AtomicBool::new(false),
+            records: Mutex::new(RingBuffer {
+                capacity: 0,
+                records: VecDeque::new(),
+            }),
+        }
+    }
+
+    /// Starts recording the reads, keeping the latest `capacity` ones.
+    pub fn enable(&self, capacity: usize) {
+        {
+            let mut buffer = self.records.lock();
+            buffer.capacity = capacity;
+            let num_to_evict = buffer.records.len().saturating_sub(capacity);
+            buffer.records.drain(..num_to_evict);
+        }
+        self.enabled.store(capacity > 0, Ordering::Release);
+    }
+
+    /// Stops recording the reads, and drops the recorded ones.
+    pub fn disable(&self) {
+        self.enabled.store(false, Ordering::Release);
+        self.records.lock().records.clear();
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.load(Ordering::Acquire)
+    }
+
+    pub fn record(&self, record: StateReadRecord) {
+        if !self.is_enabled() {
+            return;
+        }
+        let mut buffer = self.records.lock();
+        if buffer.records.len() >= buffer.capacity {
+            buffer.records.pop_front();
+        }
+        buffer.records.push_back(record);
+    }
+
+    /// Returns the recorded reads, oldest first.
+    pub fn records(&self) -> Vec<StateReadRecord> {
+        self.records.lock().records.iter().cloned().collect()
+    }
+
+    /// Returns the `limit` keys read the most among the recorded reads, most read first.
+    pub fn hot_keys(&self, limit: usize) -> Vec<HotKey> {
+        let mut hot_keys: HashMap<StateKey, HotKey> = HashMap::new();
+        for record in self.records.lock().records.iter() {
+            let hot_key = hot_keys
+                .entry(record.state_key.clone())
+                .or_insert_with(|| HotKey {
+                    state_key: record.state_key.clone(),
+                    num_reads: 0,
+                    num_cache_misses: 0,
+                    block_reads: 0,
+                });
+            hot_key.num_reads += 1;
+            hot_key.num_cache_misses += usize::from(!record.cache_hit);
+            hot_key.block_reads += record.block_reads;
+        }
+
+        let mut hot_keys: Vec<_> = hot_keys.into_values().collect();
+        hot_keys.sort_by(|a, b| {
+            b.num_reads
+                .cmp(&a.num_reads)
+                .then(b.block_reads.cmp(&a.block_reads))
+        });
+        hot_keys.truncate(limit);
+        hot_keys
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{StateReadProfiler, StateReadRecord};
+    use aptos_types::state_store::state_key::StateKey;
+    use std::time::Duration;
+
+    fn record(key: &[u8], block_reads: u64) -> StateReadRecord {
+        StateReadRecord {
+            state_key: StateKey::raw(key),
+            version: 10,
+            value_version: Some(5),
+            cache_hit: block_reads == 0,
+            block_cache_hits: 1,
+            block_reads,
+            sorted_runs_probed: 3,
+            internal_keys_skipped: 0,
+            latency: Duration::from_micros(10),
+        }
+    }
+
+    #[test]
+    fn test_ring_buffer() {
+        let profiler = StateReadProfiler::new();
+        profiler.record(record(b"ignored", 0));
+        assert!(profiler.records().is_empty());
+
+        profiler.enable(2);
+        for key in [b"a", b"b", b"c"] {
+            profiler.record(record(key, 0));
+        }
+        let records = profiler.records();
+        assert_eq!(records.len(), 2);
+        assert_eq!(records[0].state_key, StateKey::raw(b"b"));
+        assert_eq!(records[1].state_key, StateKey::raw(b"c"));
+
+        profiler.enable(1);
+        assert_eq!(profiler.records()[0].state_key, StateKey::raw(b"c"));
+
+        profiler.disable();
+        profiler.record(record(b"d", 0));
+        assert!(profiler.records().is_empty());
+    }
+
+    #[test]
+    fn test_hot_keys() {
+        let profiler = StateReadProfiler::new();
+        profiler.enable(10);
+        for (key, block_reads) in [(b"a", 0), (b"b", 2), (b"a", 1), (b"c", 5), (b"b", 0)] {
+            profiler.record(record(key, block_reads));
+        }
+
+        let hot_keys = profiler.hot_keys(2);
+        assert_eq!(hot_keys.len(), 2);
+        assert_eq!(hot_keys[0].state_key, StateKey::raw(b"b"));
+        assert_eq!(hot_keys[0].num_reads, 2);
+        assert_eq!(hot_keys[0].num_cache_misses, 1);
+        assert_eq!(hot_keys[0].block_reads, 2);
+        assert_eq!(hot_keys[1].state_key, StateKey::raw(b"a"));
+    }
+}
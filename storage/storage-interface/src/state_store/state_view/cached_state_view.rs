@@ -3,7 +3,10 @@
 
 use crate::{
     metrics::TIMER,
-    state_store::state_view::{async_proof_fetcher::AsyncProofFetcher, db_state_view::DbStateView},
+    state_store::{
+        sharded_state_updates::ShardedStateUpdates,
+        state_view::{async_proof_fetcher::AsyncProofFetcher, db_state_view::DbStateView},
+    },
     DbReader,
 };
 use aptos_crypto::{hash::CryptoHash, HashValue};
@@ -96,6 +99,13 @@ pub struct CachedStateView {
     /// The in-memory state on top of the snapshot.
     speculative_state: FrozenSparseMerkleTree<StateValue>,
 
+    /// Updates on top of `speculative_state` which are not reflected in it yet, oldest first,
+    /// e.g., those of the parent blocks whose state checkpoints are still being calculated.
+    pending_updates: Vec<Arc<ShardedStateUpdates>>,
+
+    /// The usage after `pending_updates`, if there are any.
+    pending_usage: Option<StateStorageUsage>,
+
     /// The cache of verified account states from `reader` and `speculative_state_view`,
     /// represented by a hashmap with an account address as key and a pair of an ordered
     /// account state map and an an optional account state proof as value. When the VM queries an
@@ -188,11 +198,27 @@ impl CachedStateView {
             next_version,
             snapshot,
             speculative_state,
+            pending_updates: vec![],
+            pending_usage: None,
             sharded_state_cache: ShardedStateCache::default(),
             proof_fetcher,
         }
     }
 
+    /// Reads the state through `pending_updates` (oldest first) before the speculative state,
+    /// and reports `usage` as the usage after them.
+    pub fn with_pending_updates(
+        mut self,
+        pending_updates: Vec<Arc<ShardedStateUpdates>>,
+        usage: StateStorageUsage,
+    ) -> Self {
+        if !pending_updates.is_empty() {
+            self.pending_updates = pending_updates;
+            self.pending_usage = Some(usage);
+        }
+        self
+    }
+
     pub fn prime_cache_by_write_set<'a, T: IntoIterator<Item = &'a WriteSet> + Send>(
         &self,
         write_sets: T,
@@ -226,6 +252,13 @@ impl CachedStateView {
         state_key: &StateKey,
     ) -> Result<(Option<Version>, Option<StateValue>)> {
         // Do most of the work outside the write lock.
+        let shard_id = state_key.get_shard_id() as usize;
+        for updates in self.pending_updates.iter().rev() {
+            if let Some(value) = updates.shards[shard_id].get(state_key) {
+                return Ok((None, value.clone()));
+            }
+        }
+
         let key_hash = state_key.hash();
         match self.speculative_state.get(key_hash) {
             StateStoreStatus::ExistsInScratchPad(value) => Ok((None, Some(value))),
@@ -330,7 +363,9 @@ impl TStateView for CachedStateView {
     }
 
     fn get_usage(&self) -> Result<StateStorageUsage> {
-        Ok(self.speculative_state.usage())
+        Ok(self
+            .pending_usage
+            .unwrap_or_else(|| self.speculative_state.usage()))
     }
 }
 
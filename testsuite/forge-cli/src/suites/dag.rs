@@ -107,7 +107,8 @@ fn dag_realistic_env_max_load_test(
                 OnChainExecutionConfig::Missing
                 | OnChainExecutionConfig::V1(_)
                 | OnChainExecutionConfig::V2(_)
-                | OnChainExecutionConfig::V3(_) => {
+                | OnChainExecutionConfig::V3(_)
+                | OnChainExecutionConfig::V5(_) => {
                     unreachable!("Unexpected on-chain execution config type, if OnChainExecutionConfig::default_for_genesis() has been updated, this test must be updated too.")
                 }
                 OnChainExecutionConfig::V4(config_v4) => {
@@ -208,7 +209,8 @@ fn dag_reconfig_enable_test() -> ForgeConfig {
                     OnChainExecutionConfig::Missing
                     | OnChainExecutionConfig::V1(_)
                     | OnChainExecutionConfig::V2(_)
-                    | OnChainExecutionConfig::V3(_) => {
+                    | OnChainExecutionConfig::V3(_)
+                    | OnChainExecutionConfig::V5(_) => {
                         unreachable!("Unexpected on-chain execution config type, if OnChainExecutionConfig::default_for_genesis() has been updated, this test must be updated too.")
                     }
                     OnChainExecutionConfig::V4(config_v4) => {
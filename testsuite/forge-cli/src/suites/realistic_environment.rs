@@ -433,7 +433,8 @@ pub(crate) fn realistic_network_tuned_for_throughput_test() -> ForgeConfig {
                     OnChainExecutionConfig::Missing
                     | OnChainExecutionConfig::V1(_)
                     | OnChainExecutionConfig::V2(_)
-                    | OnChainExecutionConfig::V3(_) => {
+                    | OnChainExecutionConfig::V3(_)
+                    | OnChainExecutionConfig::V5(_) => {
                         unreachable!("Unexpected on-chain execution config type, if OnChainExecutionConfig::default_for_genesis() has been updated, this test must be updated too.")
                     }
                     OnChainExecutionConfig::V4(config_v4) => {
@@ -15,8 +15,11 @@ use crate::{
     },
     CompiledModule,
 };
-use move_core_types::vm_status::StatusCode;
-use std::collections::BTreeSet;
+use move_core_types::{
+    account_address::AccountAddress, identifier::Identifier, language_storage::ModuleId,
+    vm_status::StatusCode,
+};
+use std::{collections::BTreeSet, fmt};
 
 /// The result of a linking and layout compatibility check. Here is what the different combinations. NOTE that if `check_struct_layout` is false, type safety over a series of upgrades cannot be guaranteed.
 /// mean:
@@ -87,17 +90,46 @@ impl Compatibility {
         old_module: &CompiledModule,
         new_module: &CompiledModule,
     ) -> PartialVMResult<()> {
+        let issues = self.check_issues(old_module, new_module);
+        if !issues.is_empty() {
+            Err(
+                PartialVMError::new(StatusCode::BACKWARD_INCOMPATIBLE_MODULE_UPDATE).with_message(
+                    format!(
+                        "Module update failure: new module not compatible with \
+                        existing module in `{}`: {}",
+                        old_module.self_id(),
+                        issues
+                            .iter()
+                            .map(|issue| issue.to_string())
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    ),
+                ),
+            )
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Check compatibility for `new_module` relative to old module `old_module`, returning all
+    /// the incompatibilities found. The modules are compatible if none is returned.
+    pub fn check_issues(
+        &self,
+        old_module: &CompiledModule,
+        new_module: &CompiledModule,
+    ) -> Vec<CompatibilityIssue> {
         let mut errors = vec![];
 
         // module's name and address are unchanged
         if old_module.address() != new_module.address() {
-            errors.push(format!(
-                "module address changed to `{}`",
-                new_module.address()
+            errors.push(CompatibilityIssue::ModuleAddressChanged(
+                *new_module.address(),
             ));
         }
         if old_module.name() != new_module.name() {
-            errors.push(format!("module name changed to `{}`", new_module.name()));
+            errors.push(CompatibilityIssue::ModuleNameChanged(
+                new_module.name().to_owned(),
+            ));
         }
 
         let old_view = ModuleView::new(old_module);
@@ -111,30 +143,32 @@ impl Compatibility {
                     // Struct not present in new . Existing modules that depend on this struct will fail to link with the new version of the module.
                     // Also, struct layout cannot be guaranteed transitively, because after
                     // removing the struct, it could be re-added later with a different layout.
-                    errors.push(format!("removed struct `{}`", old_struct.name()));
+                    errors.push(CompatibilityIssue::StructRemoved(
+                        old_struct.name().to_owned(),
+                    ));
                     break;
                 },
             };
 
             if !struct_abilities_compatible(old_struct.abilities(), new_struct.abilities()) {
-                errors.push(format!(
-                    "removed abilities `{}` from struct `{}`",
-                    old_struct.abilities().setminus(new_struct.abilities()),
-                    old_struct.name()
-                ));
+                errors.push(CompatibilityIssue::StructAbilitiesRemoved {
+                    name: old_struct.name().to_owned(),
+                    abilities: old_struct.abilities().setminus(new_struct.abilities()),
+                });
             }
             if !struct_type_parameters_compatible(
                 old_struct.type_parameters(),
                 new_struct.type_parameters(),
             ) {
-                errors.push(format!(
-                    "changed type parameters of struct `{}`",
-                    old_struct.name()
+                errors.push(CompatibilityIssue::StructTypeParametersChanged(
+                    old_struct.name().to_owned(),
                 ));
             }
             // Layout of old and new struct need to be compatible
             if self.check_struct_layout && !struct_layout_compatible(&old_struct, new_struct) {
-                errors.push(format!("changed layout of struct `{}`", old_struct.name()));
+                errors.push(CompatibilityIssue::StructLayoutChanged(
+                    old_struct.name().to_owned(),
+                ));
             }
         }
 
@@ -168,7 +202,9 @@ impl Compatibility {
                     // Here we know that the old_func has to be Friend, and the check_friend_linking is set to false.
                     // We make sure that we don't allow any Entry functions to be deleted, when self.treat_entry_as_public is set (treats entry as public)
                     {
-                        errors.push(format!("removed function `{}`", old_func.name()));
+                        errors.push(CompatibilityIssue::FunctionRemoved(
+                            old_func.name().to_owned(),
+                        ));
                     }
                     continue;
                 },
@@ -203,34 +239,37 @@ impl Compatibility {
                     // If it was not an entry function, it is allowed to become one.
                     !old_func.is_entry() || new_func.is_entry()
                 };
-            let error_msg = if !is_vis_compatible {
-                Some("changed visibility")
+            let change = if !is_vis_compatible {
+                Some(FunctionChange::Visibility)
             } else if !is_entry_compatible {
-                Some("removed `entry` modifier")
+                Some(FunctionChange::EntryRemoved)
             } else if !signature_compatible(
                 old_module,
                 old_func.parameters(),
                 new_module,
                 new_func.parameters(),
             ) {
-                Some("changed parameter types")
+                Some(FunctionChange::ParameterTypes)
             } else if !signature_compatible(
                 old_module,
                 old_func.return_type(),
                 new_module,
                 new_func.return_type(),
             ) {
-                Some("changed return type")
+                Some(FunctionChange::ReturnType)
             } else if !fun_type_parameters_compatible(
                 old_func.type_parameters(),
                 new_func.type_parameters(),
             ) {
-                Some("changed type parameters")
+                Some(FunctionChange::TypeParameters)
             } else {
                 None
             };
-            if let Some(msg) = error_msg {
-                errors.push(format!("{} of function `{}`", msg, old_func.name()));
+            if let Some(change) = change {
+                errors.push(CompatibilityIssue::FunctionChanged {
+                    name: old_func.name().to_owned(),
+                    change,
+                });
             }
         }
 
@@ -245,30 +284,86 @@ impl Compatibility {
             let new_friend_module_ids: BTreeSet<_> =
                 new_module.immediate_friends().iter().cloned().collect();
             if !old_friend_module_ids.is_subset(&new_friend_module_ids) {
-                errors.push(format!(
-                    "removed friend declaration {}",
+                errors.push(CompatibilityIssue::FriendsRemoved(
                     old_friend_module_ids
                         .difference(&new_friend_module_ids)
-                        .map(|id| format!("`{}`", id))
-                        .collect::<Vec<_>>()
-                        .join(" and ")
+                        .cloned()
+                        .collect(),
                 ))
             }
         }
 
-        if !errors.is_empty() {
-            Err(
-                PartialVMError::new(StatusCode::BACKWARD_INCOMPATIBLE_MODULE_UPDATE).with_message(
-                    format!(
-                        "Module update failure: new module not compatible with \
-                        existing module in `{}`: {}",
-                        old_view.id(),
-                        errors.join(", ")
-                    ),
-                ),
-            )
-        } else {
-            Ok(())
+        errors
+    }
+}
+
+/// An incompatibility of a module upgrade, as found by [`Compatibility::check_issues`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CompatibilityIssue {
+    ModuleAddressChanged(AccountAddress),
+    ModuleNameChanged(Identifier),
+    StructRemoved(Identifier),
+    StructAbilitiesRemoved {
+        name: Identifier,
+        abilities: AbilitySet,
+    },
+    StructTypeParametersChanged(Identifier),
+    StructLayoutChanged(Identifier),
+    FunctionRemoved(Identifier),
+    FunctionChanged {
+        name: Identifier,
+        change: FunctionChange,
+    },
+    FriendsRemoved(Vec<ModuleId>),
+}
+
+/// The incompatible change of an upgraded function.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FunctionChange {
+    Visibility,
+    EntryRemoved,
+    ParameterTypes,
+    ReturnType,
+    TypeParameters,
+}
+
+impl fmt::Display for CompatibilityIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::ModuleAddressChanged(address) => {
+                write!(f, "module address changed to `{}`", address)
+            },
+            Self::ModuleNameChanged(name) => write!(f, "module name changed to `{}`", name),
+            Self::StructRemoved(name) => write!(f, "removed struct `{}`", name),
+            Self::StructAbilitiesRemoved { name, abilities } => write!(
+                f,
+                "removed abilities `{}` from struct `{}`",
+                abilities, name
+            ),
+            Self::StructTypeParametersChanged(name) => {
+                write!(f, "changed type parameters of struct `{}`", name)
+            },
+            Self::StructLayoutChanged(name) => write!(f, "changed layout of struct `{}`", name),
+            Self::FunctionRemoved(name) => write!(f, "removed function `{}`", name),
+            Self::FunctionChanged { name, change } => {
+                let change = match change {
+                    FunctionChange::Visibility => "changed visibility",
+                    FunctionChange::EntryRemoved => "removed `entry` modifier",
+                    FunctionChange::ParameterTypes => "changed parameter types",
+                    FunctionChange::ReturnType => "changed return type",
+                    FunctionChange::TypeParameters => "changed type parameters",
+                };
+                write!(f, "{} of function `{}`", change, name)
+            },
+            Self::FriendsRemoved(module_ids) => write!(
+                f,
+                "removed friend declaration {}",
+                module_ids
+                    .iter()
+                    .map(|id| format!("`{}`", id))
+                    .collect::<Vec<_>>()
+                    .join(" and ")
+            ),
         }
     }
 }
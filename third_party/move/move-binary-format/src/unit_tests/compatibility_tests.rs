@@ -1,7 +1,10 @@
 // Copyright (c) The Move Contributors
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::{compatibility::Compatibility, file_format::*};
+use crate::{
+    compatibility::{Compatibility, CompatibilityIssue, FunctionChange},
+    file_format::*,
+};
 use move_core_types::{account_address::AccountAddress, identifier::Identifier};
 use std::convert::TryFrom;
 
@@ -119,3 +122,31 @@ fn deprecated_add_script_visibility() {
         .check(&friend_module, &script_module)
         .is_err());
 }
+
+#[test]
+fn compatibility_issues() {
+    let script_module = mk_module(Visibility::DEPRECATED_SCRIPT);
+    let public_module = mk_module(Visibility::Public as u8);
+    let private_module = mk_module(Visibility::Private as u8);
+    let fn_name = Identifier::new("fn").unwrap();
+
+    assert!(Compatibility::full_check()
+        .check_issues(&public_module, &public_module)
+        .is_empty());
+    assert_eq!(
+        Compatibility::full_check().check_issues(&public_module, &private_module),
+        vec![CompatibilityIssue::FunctionChanged {
+            name: fn_name.clone(),
+            change: FunctionChange::Visibility,
+        }]
+    );
+    let issues = Compatibility::full_check().check_issues(&script_module, &public_module);
+    assert_eq!(issues, vec![CompatibilityIssue::FunctionChanged {
+        name: fn_name,
+        change: FunctionChange::EntryRemoved,
+    }]);
+    assert_eq!(
+        issues[0].to_string(),
+        "removed `entry` modifier of function `fn`"
+    );
+}
@@ -236,6 +236,7 @@ pub fn run_checker(options: Options) -> anyhow::Result<GlobalEnv> {
         options.language_version.unwrap_or_default(),
         options.warn_deprecated,
         options.warn_of_deprecation_use_in_aptos_libs,
+        options.deprecation_as_error,
         options.compile_test_code,
         options.compile_verify_code,
     )?;
@@ -13,7 +13,7 @@ use move_command_line_common::env::{bool_to_str, read_env_var};
 use move_compiler::{
     command_line as cli,
     shared::{
-        move_compiler_warn_of_deprecation_use_env_var,
+        move_compiler_deprecation_as_error_env_var, move_compiler_warn_of_deprecation_use_env_var,
         warn_of_deprecation_use_in_aptos_libs_env_var,
     },
 };
@@ -109,6 +109,12 @@ pub struct Options {
            default_value=bool_to_str(warn_of_deprecation_use_in_aptos_libs_env_var()))]
     pub warn_of_deprecation_use_in_aptos_libs: bool,
 
+    /// Report the use of deprecated functions, modules, etc. as errors rather than warnings.
+    /// Note that current value of this constant is "Wdeprecation-as-error"
+    #[clap(long = cli::MOVE_COMPILER_DEPRECATION_AS_ERROR_FLAG,
+           default_value=bool_to_str(move_compiler_deprecation_as_error_env_var()))]
+    pub deprecation_as_error: bool,
+
     /// Show warnings about unused functions, fields, constants, etc.
     /// Note that the current value of this constant is "Wunused"
     #[clap(long = cli::WARN_UNUSED_FLAG, default_value="false")]
@@ -239,6 +245,13 @@ impl Options {
         }
     }
 
+    pub fn set_deprecation_as_error(self, value: bool) -> Self {
+        Self {
+            deprecation_as_error: value,
+            ..self
+        }
+    }
+
     pub fn set_warn_unused(self, value: bool) -> Self {
         Self {
             warn_unused: value,
@@ -0,0 +1,16 @@
+module 0x42::m {
+    #[deprecated(reason = b"Use g instead")]
+    public fun f() {}
+
+    #[deprecated(since = 1)]
+    public fun g() {}
+
+    #[deprecated(note)]
+    public fun h() {}
+
+    #[deprecated(since = b"1.0", note = 0x1)]
+    public fun i() {}
+
+    #[deprecated = b"Use f instead"]
+    public fun j() {}
+}
@@ -0,0 +1,18 @@
+#[deprecated(since = b"1.10")]
+module 0x42::old_coin {
+    public fun transfer() {}
+}
+
+module 0x42::event {
+    #[deprecated(note = b"Use 'event::emit' instead")]
+    struct EventHandle has drop {}
+}
+
+module 0x41::wallet {
+    use 0x42::old_coin;
+    use 0x42::event::EventHandle;
+
+    fun send(_handle: EventHandle) {
+        old_coin::transfer();
+    }
+}
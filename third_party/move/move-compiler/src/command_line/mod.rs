@@ -64,6 +64,9 @@ pub const MOVE_COMPILER_WARN_OF_DEPRECATION_USE_FLAG: &str = "Wdeprecation";
 pub const WARN_OF_DEPRECATION_USE_IN_APTOS_LIBS: &str = "WARN_OF_DEPRECATION_USE_IN_APTOS_LIBS";
 pub const WARN_OF_DEPRECATION_USE_IN_APTOS_LIBS_FLAG: &str = "Wdeprecation-aptos";
 
+pub const MOVE_COMPILER_DEPRECATION_AS_ERROR: &str = "MOVE_COMPILER_DEPRECATION_AS_ERROR";
+pub const MOVE_COMPILER_DEPRECATION_AS_ERROR_FLAG: &str = "Wdeprecation-as-error";
+
 pub const WARN_UNUSED_FLAG: &str = "Wunused";
 
 pub const LANG_V2_FLAG: &str = "lang_v2";
@@ -292,6 +292,10 @@ impl DiagnosticInfo {
     pub fn severity(&self) -> Severity {
         self.severity
     }
+
+    pub fn set_severity(&mut self, severity: Severity) {
+        self.severity = severity
+    }
 }
 
 impl Severity {
@@ -323,6 +323,13 @@ impl Diagnostic {
         self
     }
 
+    /// Overrides the severity given by the code of the diagnostic, e.g., to report a warning as
+    /// an error.
+    pub fn set_severity(mut self, severity: Severity) -> Self {
+        self.info.set_severity(severity);
+        self
+    }
+
     #[allow(unused)]
     pub fn add_secondary_labels(
         &mut self,
@@ -6,7 +6,10 @@ use super::aliases::{AliasMapBuilder, OldAliasMap};
 use crate::{
     command_line::SKIP_ATTRIBUTE_CHECKS,
     diag,
-    diagnostics::{codes::DeprecatedItem, Diagnostic},
+    diagnostics::{
+        codes::{DeprecatedItem, Severity},
+        Diagnostic,
+    },
     expansion::{
         aliases::{AliasMap, AliasSet},
         ast::{
@@ -21,7 +24,9 @@ use crate::{
         NameAccessChain, NameAccessChain_, StructName, Var,
     },
     shared::{
-        known_attributes::{AttributeKind, AttributePosition, KnownAttribute},
+        known_attributes::{
+            AttributeKind, AttributePosition, DeprecationAttribute, KnownAttribute,
+        },
         parse_u128, parse_u64, parse_u8,
         unique_map::UniqueMap,
         CompilationEnv, Identifier, Name, NamedAddressMap, NamedAddressMaps, NumericalAddress,
@@ -46,7 +51,7 @@ use str;
 type ModuleMembers = BTreeMap<Name, ModuleMemberInfo>;
 struct Context<'env, 'map> {
     module_members: UniqueMap<ModuleIdent, ModuleMembers>,
-    module_deprecation_attribute_locs: BTreeMap<ModuleIdent, Loc>, // if any
+    module_deprecations: BTreeMap<ModuleIdent, Deprecation>, // if any
     named_address_mapping: Option<&'map NamedAddressMap>,
     address: Option<Address>,
     current_module: Option<ModuleIdent>,
@@ -62,11 +67,11 @@ impl<'env, 'map> Context<'env, 'map> {
     fn new(
         compilation_env: &'env mut CompilationEnv,
         module_members: UniqueMap<ModuleIdent, ModuleMembers>,
-        module_deprecation_attribute_locs: BTreeMap<ModuleIdent, Loc>,
+        module_deprecations: BTreeMap<ModuleIdent, Deprecation>,
     ) -> Self {
         Self {
             module_members,
-            module_deprecation_attribute_locs,
+            module_deprecations,
             env: compilation_env,
             named_address_mapping: None,
             address: None,
@@ -86,7 +91,7 @@ impl<'env, 'map> Context<'env, 'map> {
 
     fn set_current_module(&mut self, module: Option<ModuleIdent>) {
         self.in_deprecated_code = match &module {
-            Some(m) => self.module_deprecation_attribute_locs.contains_key(m),
+            Some(m) => self.module_deprecations.contains_key(m),
             None => false,
         };
         self.current_module = module;
@@ -151,18 +156,18 @@ pub fn program(
     pre_compiled_lib: Option<&FullyCompiledProgram>,
     prog: P::Program,
 ) -> E::Program {
-    let mut module_deprecation_attribute_locs = BTreeMap::new();
+    let mut module_deprecations = BTreeMap::new();
 
     // Process all members from program source, lib, and pre-compiled libs,
     // recording just module->SpannedSymbol->ModuleMemberInfo for each,
-    // plus per-module deprecation info in module_deprecation_attribute_locs.
+    // plus per-module deprecation info in module_deprecations.
     let module_members = {
         let mut members = UniqueMap::new();
         all_module_members(
             compilation_env,
             &prog.named_address_maps,
             &mut members,
-            &mut module_deprecation_attribute_locs,
+            &mut module_deprecations,
             true,
             &prog.source_definitions,
         );
@@ -170,7 +175,7 @@ pub fn program(
             compilation_env,
             &prog.named_address_maps,
             &mut members,
-            &mut module_deprecation_attribute_locs,
+            &mut module_deprecations,
             true,
             &prog.lib_definitions,
         );
@@ -180,7 +185,7 @@ pub fn program(
                 compilation_env,
                 &pre_compiled.parser.named_address_maps,
                 &mut members,
-                &mut module_deprecation_attribute_locs,
+                &mut module_deprecations,
                 false,
                 &pre_compiled.parser.source_definitions,
             );
@@ -188,11 +193,7 @@ pub fn program(
         members
     };
 
-    let mut context = Context::new(
-        compilation_env,
-        module_members,
-        module_deprecation_attribute_locs,
-    );
+    let mut context = Context::new(compilation_env, module_members, module_deprecations);
 
     let mut source_module_map = UniqueMap::new();
     let mut lib_module_map = UniqueMap::new();
@@ -528,10 +529,7 @@ fn module_(
 
     let name_loc = name.0.loc;
     let current_module = sp(name_loc, ModuleIdent_::new(*context.cur_address(), name));
-    if context
-        .module_deprecation_attribute_locs
-        .contains_key(&current_module)
-    {
+    if context.module_deprecations.contains_key(&current_module) {
         context.in_deprecated_code = true;
     }
     if context.env.flags().warn_of_deprecation_use_in_aptos_libs() {
@@ -689,25 +687,123 @@ fn script_(context: &mut Context, package_name: Option<Symbol>, pscript: P::Scri
     }
 }
 
-/// If attributes contains a `#[deprecated]` attribute, then returns the location of the attribute.
-fn deprecated_attribute_location(attributes: &[P::Attributes]) -> Option<Loc> {
+/// The deprecation of a module or module member, as declared by a
+/// `#[deprecated(note = b"...", since = b"...")]` attribute, where both parameters are optional.
+#[derive(Clone, Debug)]
+struct Deprecation {
+    /// The location of the attribute
+    loc: Loc,
+    /// Why the item is deprecated, typically with a hint of what to use instead
+    note: Option<String>,
+    /// The version the item is deprecated since
+    since: Option<String>,
+}
+
+impl Deprecation {
+    /// Adds the note and version of the deprecation to the diagnostic of a use of the deprecated
+    /// item, and reports it as an error if requested by the flags.
+    fn complete_diag(&self, context: &Context, mut diag: Diagnostic) -> Diagnostic {
+        if let Some(since) = &self.since {
+            diag.add_note(format!("Deprecated since {}", since));
+        }
+        if let Some(note) = &self.note {
+            diag.add_note(note);
+        }
+        if context.env.flags().deprecation_as_error() {
+            diag = diag.set_severity(Severity::NonblockingError);
+        }
+        diag
+    }
+}
+
+/// If attributes contains a `#[deprecated]` attribute, then returns the deprecation it declares.
+/// Malformed parameters are ignored here, and reported when the attributes are expanded.
+fn deprecation(attributes: &[P::Attributes]) -> Option<Deprecation> {
     attributes
         .iter()
         .flat_map(|attrs| &attrs.value)
         .filter_map(|attr| {
-            let sp!(nloc, sym) = match &attr.value {
-                P::Attribute_::Name(n)
-                | P::Attribute_::Assigned(n, _)
-                | P::Attribute_::Parameterized(n, _) => *n,
+            let (sp!(nloc, sym), params) = match &attr.value {
+                P::Attribute_::Name(n) | P::Attribute_::Assigned(n, _) => (*n, None),
+                P::Attribute_::Parameterized(n, params) => (*n, Some(params)),
             };
             match KnownAttribute::resolve(sym) {
-                Some(KnownAttribute::Deprecation(_dep)) => Some(nloc),
+                Some(KnownAttribute::Deprecation(_dep)) => {
+                    let mut deprecation = Deprecation {
+                        loc: nloc,
+                        note: None,
+                        since: None,
+                    };
+                    for param in params.iter().flat_map(|params| &params.value) {
+                        if let P::Attribute_::Assigned(name, value) = &param.value {
+                            let text = match &value.value {
+                                P::AttributeValue_::Value(sp!(vloc, P::Value_::ByteString(s))) => {
+                                    byte_string::decode(*vloc, s.as_str())
+                                        .ok()
+                                        .map(|bytes| String::from_utf8_lossy(&bytes).into_owned())
+                                },
+                                _ => None,
+                            };
+                            match name.value.as_str() {
+                                DeprecationAttribute::NOTE_NAME => deprecation.note = text,
+                                DeprecationAttribute::SINCE_NAME => deprecation.since = text,
+                                _ => (),
+                            }
+                        }
+                    }
+                    Some(deprecation)
+                },
                 _ => None,
             }
         })
         .next()
 }
 
+/// Checks that the parameters of a `#[deprecated]` attribute, if any, are `note` and `since`,
+/// each assigned a byte string.
+fn check_deprecation_attribute(context: &mut Context, attr_: &E::Attribute_) {
+    let params = match attr_ {
+        E::Attribute_::Name(_) => return,
+        E::Attribute_::Assigned(n, _) => {
+            let msg = format!(
+                "Expected '{}' or '{}(...)'",
+                n,
+                DeprecationAttribute::DEPRECATED_NAME
+            );
+            context
+                .env
+                .add_diag(diag!(Attributes::InvalidValue, (n.loc, msg)));
+            return;
+        },
+        E::Attribute_::Parameterized(_, params) => params,
+    };
+    for (_, _, sp!(loc, param_)) in params {
+        let is_valid = match param_ {
+            E::Attribute_::Assigned(n, value) => {
+                matches!(
+                    n.value.as_str(),
+                    DeprecationAttribute::NOTE_NAME | DeprecationAttribute::SINCE_NAME
+                ) && matches!(
+                    &value.value,
+                    E::AttributeValue_::Value(sp!(_, E::Value_::Bytearray(_)))
+                )
+            },
+            E::Attribute_::Name(_) | E::Attribute_::Parameterized(..) => false,
+        };
+        if !is_valid {
+            let msg = format!(
+                "Invalid parameter of '{}'. Expected '{} = b\"...\"' or '{} = b\"...\"'",
+                DeprecationAttribute::DEPRECATED_NAME,
+                DeprecationAttribute::NOTE_NAME,
+                DeprecationAttribute::SINCE_NAME
+            );
+            context
+                .env
+                .add_diag(diag!(Attributes::InvalidValue, (*loc, msg)));
+        }
+    }
+}
+
 fn flatten_attributes(
     context: &mut Context,
     attr_position: AttributePosition,
@@ -794,6 +890,9 @@ fn unique_attributes(
                     ));
                     continue;
                 }
+                if let KnownAttribute::Deprecation(_) = known {
+                    check_deprecation_attribute(context, &attr_);
+                }
                 E::AttributeName_::Known(known)
             },
         };
@@ -897,12 +996,12 @@ fn attribute_value(
 /// Process the PackageDefinition refs provided by the defs iterator,
 /// adding all symbol definitions to members, which records
 /// moduleId->SpannedSymbol->ModuleMemberInfo.  Also add a record
-/// for each deprecated module to module_deprecation_attribute_locs.
+/// for each deprecated module to module_deprecations.
 fn all_module_members<'a>(
     compilation_env: &mut CompilationEnv,
     named_addr_maps: &NamedAddressMaps,
     members: &mut UniqueMap<ModuleIdent, ModuleMembers>,
-    module_deprecation_attribute_locs: &mut BTreeMap<ModuleIdent, Loc>,
+    module_deprecations: &mut BTreeMap<ModuleIdent, Deprecation>,
     always_add: bool,
     defs: impl IntoIterator<Item = &'a P::PackageDefinition>,
 ) {
@@ -931,8 +1030,8 @@ fn all_module_members<'a>(
                 };
                 let mident = sp(m.name.loc(), ModuleIdent_::new(addr, m.name));
                 module_members(members, always_add, m, &mident);
-                if let Some(loc) = deprecated_attribute_location(&m.attributes) {
-                    module_deprecation_attribute_locs.insert(mident, loc);
+                if let Some(deprecation) = deprecation(&m.attributes) {
+                    module_deprecations.insert(mident, deprecation);
                 }
             },
             P::Definition::Address(addr_def) => {
@@ -945,10 +1044,10 @@ fn all_module_members<'a>(
                 for m in &addr_def.modules {
                     let mident = sp(m.name.loc(), ModuleIdent_::new(addr, m.name));
                     module_members(members, always_add, m, &mident);
-                    if let Some(loc) = deprecated_attribute_location(&addr_def.attributes) {
-                        module_deprecation_attribute_locs.insert(mident, loc);
-                    } else if let Some(loc) = deprecated_attribute_location(&m.attributes) {
-                        module_deprecation_attribute_locs.insert(mident, loc);
+                    if let Some(deprecation) =
+                        deprecation(&addr_def.attributes).or_else(|| deprecation(&m.attributes))
+                    {
+                        module_deprecations.insert(mident, deprecation);
                     }
                 }
             },
@@ -967,7 +1066,7 @@ fn record_module_member_info(
 ) {
     cur_members.insert(*name, ModuleMemberInfo {
         kind: member_kind,
-        deprecation: deprecated_attribute_location(attributes),
+        deprecation: deprecation(attributes),
     });
 }
 
@@ -1151,39 +1250,44 @@ fn warn_about_unbound_module_use(context: &mut Context, mident: &ModuleIdent) {
     ));
 }
 
-fn module_has_deprecated_annotation(context: &mut Context, mident: &ModuleIdent) -> Option<Loc> {
-    context
-        .module_deprecation_attribute_locs
-        .get(mident)
-        .copied()
+fn module_has_deprecated_annotation(
+    context: &mut Context,
+    mident: &ModuleIdent,
+) -> Option<Deprecation> {
+    context.module_deprecations.get(mident).cloned()
 }
 
 fn member_has_deprecated_annotation(
     context: &mut Context,
     mident: &ModuleIdent,
     member: &Spanned<Symbol>,
-) -> Option<Loc> {
+) -> Option<Deprecation> {
     context
         .module_members
         .get(mident)
         .and_then(|members| members.get(member))
-        .and_then(|member_info| member_info.deprecation)
+        .and_then(|member_info| member_info.deprecation.clone())
 }
 
 fn check_for_deprecated_module_use(context: &mut Context, mident: &ModuleIdent) -> bool {
-    let warn_deprecation = &context.env.flags().warn_of_deprecation_use();
-    if !warn_deprecation || context.in_deprecated_code || context.in_aptos_libs {
+    let report_deprecation = &context.env.flags().report_deprecation_use();
+    if !report_deprecation || context.in_deprecated_code || context.in_aptos_libs {
         return false;
     }
-    if let Some(loc) = module_has_deprecated_annotation(context, mident) {
-        context.env.add_diag(diag!(
+    if let Some(deprecation) = module_has_deprecated_annotation(context, mident) {
+        let diag = diag!(
             NameResolution::DeprecatedModule,
             (
                 mident.loc,
                 format!("Use of deprecated module '{}'", mident.value),
             ),
-            (loc, format!("Module '{}' deprecated here", mident.value),),
-        ));
+            (
+                deprecation.loc,
+                format!("Module '{}' deprecated here", mident.value),
+            ),
+        );
+        let diag = deprecation.complete_diag(context, diag);
+        context.env.add_diag(diag);
         true
     } else {
         false
@@ -1196,8 +1300,8 @@ fn check_for_deprecated_member_use(
     member: &Spanned<Symbol>,
     deprecated_item: DeprecatedItem,
 ) {
-    let warn_deprecation = &context.env.flags().warn_of_deprecation_use();
-    if !warn_deprecation || context.in_deprecated_code || context.in_aptos_libs {
+    let report_deprecation = &context.env.flags().report_deprecation_use();
+    if !report_deprecation || context.in_deprecated_code || context.in_aptos_libs {
         return;
     }
     let mident = match mident_in {
@@ -1214,8 +1318,8 @@ fn check_for_deprecated_member_use(
             *mident
         },
     };
-    if let Some(loc) = member_has_deprecated_annotation(context, &mident, member) {
-        context.env.add_diag(diag!(
+    if let Some(deprecation) = member_has_deprecated_annotation(context, &mident, member) {
+        let diag = diag!(
             deprecated_item.get_code(),
             (
                 member.loc,
@@ -1227,7 +1331,7 @@ fn check_for_deprecated_member_use(
                 )
             ),
             (
-                loc,
+                deprecation.loc,
                 format!(
                     "{} '{}' in module '{}' deprecated here",
                     deprecated_item.get_capitalized_string(),
@@ -1235,7 +1339,9 @@ fn check_for_deprecated_member_use(
                     mident
                 )
             ),
-        ));
+        );
+        let diag = deprecation.complete_diag(context, diag);
+        context.env.add_diag(diag);
     }
 }
 
@@ -3564,10 +3670,10 @@ fn check_valid_local_name(context: &mut Context, v: &Var) {
     let _ = check_restricted_name_all_cases(context, NameCase::Variable, &v.0);
 }
 
-#[derive(Copy, Clone, Debug)]
+#[derive(Clone, Debug)]
 struct ModuleMemberInfo {
     pub kind: ModuleMemberKind,
-    pub deprecation: Option<Loc>, // Some(loc) if member is deprecated at loc
+    pub deprecation: Option<Deprecation>, // Some(deprecation) if member is deprecated
 }
 
 #[derive(Copy, Clone, Debug)]
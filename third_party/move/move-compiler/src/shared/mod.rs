@@ -331,6 +331,12 @@ pub fn move_compiler_warn_of_deprecation_use_env_var() -> bool {
     *WARN_OF_DEPRECATION
 }
 
+pub fn move_compiler_deprecation_as_error_env_var() -> bool {
+    static DEPRECATION_AS_ERROR: Lazy<bool> =
+        Lazy::new(|| read_bool_env_var(cli::MOVE_COMPILER_DEPRECATION_AS_ERROR));
+    *DEPRECATION_AS_ERROR
+}
+
 pub fn warn_of_deprecation_use_in_aptos_libs_env_var() -> bool {
     static WARN_OF_DEPRECATION: Lazy<bool> =
         Lazy::new(|| read_bool_env_var(cli::WARN_OF_DEPRECATION_USE_IN_APTOS_LIBS));
@@ -401,6 +407,13 @@ pub struct Flags {
     #[clap(long = cli::WARN_OF_DEPRECATION_USE_IN_APTOS_LIBS_FLAG, default_value=bool_to_str(warn_of_deprecation_use_in_aptos_libs_env_var()))]
     warn_of_deprecation_use_in_aptos_libs: bool,
 
+    /// Report the use of deprecated functions, modules, constants, etc. as errors rather than
+    /// warnings, e.g., to make sure a package has migrated off a deprecated API.
+    /// Note that current value of this constant is "Wdeprecation-as-error"
+    #[clap(long = cli::MOVE_COMPILER_DEPRECATION_AS_ERROR_FLAG,
+           default_value=bool_to_str(move_compiler_deprecation_as_error_env_var()))]
+    deprecation_as_error: bool,
+
     /// Show warnings about unused functions, fields, constants, etc.
     /// Note that the current value of this constant is "Wunused"
     #[clap(long = cli::WARN_UNUSED_FLAG, default_value="false")]
@@ -436,6 +449,7 @@ impl Flags {
             debug: debug_compiler_env_var(),
             warn_of_deprecation_use: move_compiler_warn_of_deprecation_use_env_var(),
             warn_of_deprecation_use_in_aptos_libs: warn_of_deprecation_use_in_aptos_libs_env_var(),
+            deprecation_as_error: move_compiler_deprecation_as_error_env_var(),
             warn_unused: false,
             lang_v2: false,
             compiler_v2: false,
@@ -568,6 +582,22 @@ impl Flags {
         }
     }
 
+    /// Whether the use of deprecated items is reported, either as warnings or as errors.
+    pub fn report_deprecation_use(&self) -> bool {
+        self.warn_of_deprecation_use || self.deprecation_as_error
+    }
+
+    pub fn deprecation_as_error(&self) -> bool {
+        self.deprecation_as_error
+    }
+
+    pub fn set_deprecation_as_error(self, new_value: bool) -> Self {
+        Self {
+            deprecation_as_error: new_value,
+            ..self
+        }
+    }
+
     pub fn get_block_v1_compiler(&self) -> bool {
         self.block_v1_compiler
     }
@@ -968,6 +998,9 @@ pub mod known_attributes {
     impl DeprecationAttribute {
         const ALL_ATTRIBUTE_NAMES: [&'static str; 1] = [Self::DEPRECATED_NAME];
         pub const DEPRECATED_NAME: &'static str = "deprecated";
+        // Parameters of the attribute, as in `#[deprecated(note = b"...", since = b"...")]`
+        pub const NOTE_NAME: &'static str = "note";
+        pub const SINCE_NAME: &'static str = "since";
     }
 
     impl AttributeKind for DeprecationAttribute {
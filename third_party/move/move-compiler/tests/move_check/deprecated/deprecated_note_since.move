@@ -0,0 +1,15 @@
+module 0x42::coin {
+    #[deprecated(note = b"Use 'coin::transfer_v2' instead", since = b"1.12")]
+    public fun transfer() {}
+
+    public fun transfer_v2() {}
+}
+
+module 0x41::wallet {
+    use 0x42::coin;
+
+    fun send() {
+        coin::transfer();
+        coin::transfer_v2();
+    }
+}
@@ -48,6 +48,12 @@ pub fn get_info(target: &FunctionTarget<'_>) -> VerificationInfo {
         .unwrap_or_default()
 }
 
+/// The functions within the verification scope which have already been verified, with the
+/// same code, specification and dependencies, in a previous run. The prover driver sets this as
+/// an extension of the environment, and the functions are not verified again.
+#[derive(Clone, Default)]
+pub struct CachedVerifiedFunctions(pub BTreeSet<QualifiedId<FunId>>);
+
 /// A named tuple for holding the information on how an invariant is relevant to a function.
 pub struct InvariantRelevance {
     /// Global invariants covering memories that are accessed in a function
@@ -358,6 +364,15 @@ impl VerificationAnalysisProcessor {
             return false;
         }
         let env = fun_env.module_env.env;
+        if env
+            .get_extension::<CachedVerifiedFunctions>()
+            .map_or(false, |cached| {
+                cached.0.contains(&fun_env.get_qualified_id())
+            })
+        {
+            // already verified in a previous run
+            return false;
+        }
         let options = ProverOptions::get(env);
         match &options.verify_scope {
             VerificationScope::Public => fun_env.is_exposed(),
@@ -83,6 +83,9 @@ pub struct Options {
     pub compiler_v2: bool,
     /// The language version to use
     pub language_version: Option<LanguageVersion>,
+    /// The directory of the cache of verified functions. If set, functions which have been
+    /// verified before, and did not change since, are not verified again.
+    pub verification_cache_dir: Option<String>,
     /// BEGIN OF STRUCTURED OPTIONS. DO NOT ADD VALUE FIELDS AFTER THIS
     /// Options for the model builder.
     pub model_builder: ModelBuilderOptions,
@@ -125,6 +128,7 @@ impl Default for Options {
                 CompilerVersion::V2_0 | CompilerVersion::V2_1 => true,
             },
             language_version: Some(LanguageVersion::default()),
+            verification_cache_dir: None,
         }
     }
 }
@@ -204,6 +208,13 @@ impl Options {
                     .value_name("BOOGIE_FILE")
                     .help("path to the boogie output which represents the verification problem"),
             )
+            .arg(
+                Arg::new("verification-cache")
+                    .long("verification-cache")
+                    .value_name("DIR")
+                    .help("directory of a cache of verified functions. Functions which have been \
+                     verified before and did not change since are not verified again"),
+            )
             .arg(
                 Arg::new("verbosity")
                     .short('v')
@@ -607,6 +618,11 @@ impl Options {
         if matches.contains_id("output") {
             options.output_path = matches.get_one::<String>("output").unwrap().to_string();
         }
+        if matches.contains_id("verification-cache") {
+            options.verification_cache_dir = matches
+                .get_one::<String>("verification-cache")
+                .map(|s| s.to_string());
+        }
         if matches.contains_id("verbosity") {
             options.verbosity_level = match matches.get_one::<String>("verbosity").unwrap().as_str()
             {
@@ -30,8 +30,10 @@ use std::{
     path::{Path, PathBuf},
     time::Instant,
 };
+use verification_cache::VerificationCache;
 
 pub mod cli;
+pub mod verification_cache;
 
 // =================================================================================================
 // Prover API
@@ -94,6 +96,7 @@ pub fn create_move_prover_v2_model<W: WriteColor>(
         sources_deps: vec![],
         warn_deprecated: false,
         warn_of_deprecation_use_in_aptos_libs: false,
+        deprecation_as_error: false,
         warn_unused: false,
         whole_program: false,
         compile_test_code: false,
@@ -183,10 +186,21 @@ pub fn run_move_prover_with_model_v2<W: WriteColor>(
     // Check correct backend versions.
     options.backend.check_tool_versions()?;
 
+    // Exclude the functions which have been verified before from verification.
+    let mut cache = match &options.verification_cache_dir {
+        Some(dir) if !options.prover.generate_only => {
+            Some(VerificationCache::lookup(dir, env, &options)?)
+        },
+        _ => None,
+    };
+
     // Create and process bytecode
     let now = Instant::now();
     let targets = create_and_process_bytecode(&options, env);
     let trafo_duration = now.elapsed();
+    if let Some(cache) = &mut cache {
+        cache.count_misses(env, &targets);
+    }
     check_errors(
         env,
         &options,
@@ -210,6 +224,14 @@ pub fn run_move_prover_with_model_v2<W: WriteColor>(
     verify_boogie(env, &options, &targets, code_writer)?;
     let verify_duration = now.elapsed();
 
+    // Remember the functions which have been verified successfully.
+    if let Some(cache) = &mut cache {
+        if !env.has_errors() {
+            cache.record(env, &targets)?;
+        }
+        cache.report_stats();
+    }
+
     // Report durations.
     info!(
         "{:.3}s build, {:.3}s trafo, {:.3}s gen, {:.3}s verify, total {:.3}s",
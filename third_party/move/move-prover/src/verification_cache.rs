@@ -0,0 +1,338 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! A cache of the functions which have been verified successfully, so that verification of a
+//! package only needs to call the backend for the functions which changed.
+//!
+//! A function is identified by a hash of its bytecode and specification, of the bytecode and
+//! specifications of the functions it transitively calls, of the module level specifications
+//! (e.g. global invariants, spec functions and struct invariants) of all modules, and of the
+//! prover options. The cache is a directory with one empty file per verified hash, so it can be
+//! shared between packages, and between concurrent runs of the prover.
+
+use crate::cli::Options;
+use log::{debug, info};
+use move_command_line_common::files::FileHash;
+use move_model::{
+    ast::Spec,
+    model::{FunId, FunctionEnv, GlobalEnv, Loc, QualifiedId, VerificationScope},
+};
+use move_prover_bytecode_pipeline::verification_analysis::{self, CachedVerifiedFunctions};
+use move_stackless_bytecode::function_target_pipeline::{FunctionTargetsHolder, FunctionVariant};
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fmt::Write,
+    fs,
+    path::PathBuf,
+};
+
+/// Statistics on the use of the cache in a prover run.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct VerificationCacheStats {
+    /// The number of functions which were not verified again
+    pub hits: usize,
+    /// The number of functions which had to be verified
+    pub misses: usize,
+    /// The number of functions added to the cache after successful verification
+    pub recorded: usize,
+}
+
+pub struct VerificationCache {
+    dir: PathBuf,
+    /// The hashes of the functions of the target modules which are not in the cache
+    uncached: BTreeMap<QualifiedId<FunId>, FileHash>,
+    stats: VerificationCacheStats,
+}
+
+impl VerificationCache {
+    /// Looks up the functions of the target modules in the cache at `dir`, and registers the ones
+    /// found with the environment, so that the verification analysis excludes them.
+    pub fn lookup(dir: &str, env: &GlobalEnv, options: &Options) -> anyhow::Result<Self> {
+        let dir = PathBuf::from(dir);
+        fs::create_dir_all(&dir)?;
+
+        let mut hasher = FunctionHasher::new(env, options);
+        let mut cached = BTreeSet::new();
+        let mut uncached = BTreeMap::new();
+        for module_env in env.get_modules() {
+            if !module_env.is_target() {
+                continue;
+            }
+            for fun_env in module_env.get_functions() {
+                if fun_env.is_native() || fun_env.is_intrinsic() || fun_env.is_inline() {
+                    continue;
+                }
+                let hash = hasher.hash(&fun_env);
+                if dir.join(hash.to_string()).exists() {
+                    debug!("verification cache hit for {}", fun_env.get_full_name_str());
+                    cached.insert(fun_env.get_qualified_id());
+                } else {
+                    uncached.insert(fun_env.get_qualified_id(), hash);
+                }
+            }
+        }
+
+        let stats = VerificationCacheStats {
+            hits: cached.len(),
+            ..Default::default()
+        };
+        env.set_extension(CachedVerifiedFunctions(cached));
+        Ok(Self {
+            dir,
+            uncached,
+            stats,
+        })
+    }
+
+    /// Counts the functions which are verified in this run, as determined by the verification
+    /// analysis. Must be called after the bytecode has been processed.
+    pub fn count_misses(&mut self, env: &GlobalEnv, targets: &FunctionTargetsHolder) {
+        self.stats.misses = self.verified_functions(env, targets).len();
+    }
+
+    /// Adds the functions which have been verified in this run to the cache. Must only be
+    /// called if verification succeeded.
+    pub fn record(
+        &mut self,
+        env: &GlobalEnv,
+        targets: &FunctionTargetsHolder,
+    ) -> anyhow::Result<()> {
+        for fun_id in self.verified_functions(env, targets) {
+            fs::write(self.dir.join(self.uncached[&fun_id].to_string()), "")?;
+            self.stats.recorded += 1;
+        }
+        Ok(())
+    }
+
+    pub fn stats(&self) -> VerificationCacheStats {
+        self.stats
+    }
+
+    pub fn report_stats(&self) {
+        info!(
+            "verification cache: {} hits, {} misses, {} recorded",
+            self.stats.hits, self.stats.misses, self.stats.recorded
+        );
+    }
+
+    fn verified_functions(
+        &self,
+        env: &GlobalEnv,
+        targets: &FunctionTargetsHolder,
+    ) -> Vec<QualifiedId<FunId>> {
+        self.uncached
+            .keys()
+            .filter(|fun_id| {
+                let fun_env = env.get_function(**fun_id);
+                targets
+                    .get_target_variants(&fun_env)
+                    .contains(&FunctionVariant::Baseline)
+                    && verification_analysis::get_info(
+                        &targets.get_target(&fun_env, &FunctionVariant::Baseline),
+                    )
+                    .verified
+            })
+            .copied()
+            .collect()
+    }
+}
+
+/// Computes the hashes which identify the functions in the cache.
+struct FunctionHasher<'env> {
+    env: &'env GlobalEnv,
+    /// The hash of everything which is shared by all functions: the prover options and the
+    /// module level specifications.
+    global_hash: FileHash,
+    /// The hashes of the code and specification of a single function, without dependencies
+    local_hashes: BTreeMap<QualifiedId<FunId>, FileHash>,
+}
+
+impl<'env> FunctionHasher<'env> {
+    fn new(env: &'env GlobalEnv, options: &Options) -> Self {
+        // The verification scope only decides which functions are verified, but not how.
+        let mut prover_options = options.prover.clone();
+        prover_options.verify_scope = VerificationScope::All;
+        let mut text = format!("{:?}\n{:?}\n", prover_options, options.backend);
+
+        let mut modules = env.get_modules().collect::<Vec<_>>();
+        modules.sort_by_key(|module_env| module_env.get_full_name_str());
+        for module_env in modules {
+            writeln!(text, "module {}", module_env.get_full_name_str()).unwrap();
+            write_spec(env, &mut text, &module_env.get_spec());
+            for (_, decl) in module_env.get_spec_funs() {
+                write_source(env, &mut text, &decl.loc);
+            }
+            for (_, decl) in module_env.get_spec_vars() {
+                write_source(env, &mut text, &decl.loc);
+            }
+            for struct_env in module_env.get_structs() {
+                write_source(env, &mut text, &struct_env.get_loc());
+                write_spec(env, &mut text, &struct_env.get_spec());
+            }
+        }
+
+        Self {
+            env,
+            global_hash: FileHash::new(&text),
+            local_hashes: BTreeMap::new(),
+        }
+    }
+
+    /// Returns the hash of the function, its transitive callees and the global specifications.
+    fn hash(&mut self, fun_env: &FunctionEnv) -> FileHash {
+        let mut text = format!("{}\n", self.global_hash);
+        for fun_id in std::iter::once(fun_env.get_qualified_id())
+            .chain(called_functions(self.env, fun_env.get_qualified_id()))
+        {
+            writeln!(text, "{}", self.local_hash(fun_id)).unwrap();
+        }
+        FileHash::new(&text)
+    }
+
+    fn local_hash(&mut self, fun_id: QualifiedId<FunId>) -> FileHash {
+        if let Some(hash) = self.local_hashes.get(&fun_id) {
+            return *hash;
+        }
+        let fun_env = self.env.get_function(fun_id);
+        let mut text = format!("fun {}\n", fun_env.get_full_name_str());
+        if let Some(code) = fun_env.get_bytecode() {
+            writeln!(text, "{:?}", code).unwrap();
+        }
+        write_source(self.env, &mut text, &fun_env.get_loc());
+        write_spec(self.env, &mut text, &fun_env.get_spec());
+        let hash = FileHash::new(&text);
+        self.local_hashes.insert(fun_id, hash);
+        hash
+    }
+}
+
+/// Returns the functions transitively called by the function, in a stable order.
+fn called_functions(env: &GlobalEnv, fun_id: QualifiedId<FunId>) -> BTreeSet<QualifiedId<FunId>> {
+    let mut called = BTreeSet::new();
+    let mut todo = vec![fun_id];
+    while let Some(fun_id) = todo.pop() {
+        let fun_env = env.get_function(fun_id);
+        for callee in fun_env.get_called_functions().into_iter().flatten() {
+            if called.insert(*callee) {
+                todo.push(*callee);
+            }
+        }
+    }
+    called.remove(&fun_id);
+    called
+}
+
+fn write_spec(env: &GlobalEnv, text: &mut String, spec: &Spec) {
+    if let Some(loc) = &spec.loc {
+        write_source(env, text, loc);
+    }
+    // Conditions may come from other places than the spec block, e.g. schemas.
+    for condition in &spec.conditions {
+        write_source(env, text, &condition.loc);
+    }
+}
+
+fn write_source(env: &GlobalEnv, text: &mut String, loc: &Loc) {
+    writeln!(text, "{}", env.get_source(loc).unwrap_or_default()).unwrap();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        create_and_process_bytecode, create_init_num_operation_state, create_move_prover_v2_model,
+    };
+    use codespan_reporting::term::termcolor::Buffer;
+    use tempfile::TempDir;
+
+    const SOURCE: &str = r#"
+module 0x42::m {
+    public fun unrelated(x: u64): u64 { x }
+    spec unrelated { ensures result == x; }
+
+    public fun callee(x: u64): u64 { x + 1 }
+    spec callee { ensures result == x + 1; }
+
+    public fun caller(x: u64): u64 { callee(x) }
+    spec caller { ensures result == x + 1; }
+}
+"#;
+
+    /// Builds the model of `source`, and runs the cache like the prover driver does, assuming
+    /// verification succeeds.
+    fn run(work_dir: &TempDir, source: &str) -> VerificationCacheStats {
+        let source_path = work_dir.path().join("m.move");
+        fs::write(&source_path, source).unwrap();
+        let options = Options {
+            output_path: work_dir
+                .path()
+                .join("output.bpl")
+                .to_string_lossy()
+                .to_string(),
+            move_sources: vec![source_path.to_string_lossy().to_string()],
+            ..Default::default()
+        };
+        let mut error_writer = Buffer::no_color();
+        let env = create_move_prover_v2_model(&mut error_writer, options.clone()).unwrap();
+        assert!(
+            !env.has_errors(),
+            "{}",
+            String::from_utf8_lossy(&error_writer.into_inner())
+        );
+        env.set_extension(options.prover.clone());
+        create_init_num_operation_state(&env);
+
+        let cache_dir = work_dir.path().join("cache");
+        let mut cache =
+            VerificationCache::lookup(cache_dir.to_str().unwrap(), &env, &options).unwrap();
+        let targets = create_and_process_bytecode(&options, &env);
+        cache.count_misses(&env, &targets);
+        cache.record(&env, &targets).unwrap();
+        cache.stats()
+    }
+
+    #[test]
+    fn test_verification_cache() {
+        let work_dir = TempDir::new().unwrap();
+
+        // Nothing is cached in the first run.
+        assert_eq!(run(&work_dir, SOURCE), VerificationCacheStats {
+            hits: 0,
+            misses: 3,
+            recorded: 3,
+        });
+
+        // Unchanged functions are not verified again.
+        assert_eq!(run(&work_dir, SOURCE), VerificationCacheStats {
+            hits: 3,
+            misses: 0,
+            recorded: 0,
+        });
+
+        // Changing the specification of a function only invalidates that function.
+        let changed_spec = SOURCE.replace(
+            "spec caller { ensures result == x + 1; }",
+            "spec caller { ensures result > x; }",
+        );
+        assert_eq!(run(&work_dir, &changed_spec), VerificationCacheStats {
+            hits: 2,
+            misses: 1,
+            recorded: 1,
+        });
+
+        // Changing the code of a function invalidates the function and its callers.
+        let changed_callee = changed_spec.replace("{ x + 1 }", "{ 1 + x }");
+        assert_eq!(run(&work_dir, &changed_callee), VerificationCacheStats {
+            hits: 1,
+            misses: 2,
+            recorded: 2,
+        });
+
+        // Reverting the changes hits the entries of the first run.
+        assert_eq!(run(&work_dir, SOURCE), VerificationCacheStats {
+            hits: 3,
+            misses: 0,
+            recorded: 0,
+        });
+    }
+}
@@ -2,7 +2,9 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use once_cell::sync::Lazy;
-use prometheus::{register_histogram_vec, HistogramTimer, HistogramVec};
+use prometheus::{
+    register_histogram_vec, register_int_counter, HistogramTimer, HistogramVec, IntCounter,
+};
 
 /// Helper trait to encapsulate [HistogramVec] functionality. Users can use this trait to time
 /// different VM parts collecting metrics for different labels. Use wisely as timers do introduce
@@ -44,3 +46,13 @@ pub static VM_TIMER: Lazy<HistogramVec> = Lazy::new(|| {
     )
     .expect("Registering the histogram should always succeed")
 });
+
+/// Counts the failures of the runtime type checks of the paranoid mode. Any such failure is a bug
+/// in the VM or in the bytecode verifier.
+pub static PARANOID_CHECK_FAILURES: Lazy<IntCounter> = Lazy::new(|| {
+    register_int_counter!(
+        "move_vm_paranoid_check_failures",
+        "Number of failed paranoid mode checks"
+    )
+    .expect("Registering the counter should always succeed")
+});
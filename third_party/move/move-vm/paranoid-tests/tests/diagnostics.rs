@@ -0,0 +1,120 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use fail::FailScenario;
+use move_binary_format::file_format::{
+    Bytecode::*, CodeUnit, CompiledScript, Signature, SignatureIndex, SignatureToken,
+};
+use move_core_types::vm_status::{
+    sub_status::unknown_invariant_violation::EPARANOID_FAILURE, StatusCode,
+};
+use move_vm_runtime::{
+    config::VMConfig,
+    module_traversal::*,
+    move_vm::MoveVM,
+    paranoid_diagnostics::{
+        clear_paranoid_check_diagnostic_hook, set_paranoid_check_diagnostic_hook,
+        ParanoidCheckDiagnostic,
+    },
+    AsUnsyncCodeStorage, RuntimeEnvironment,
+};
+use move_vm_test_utils::InMemoryStorage;
+use move_vm_types::gas::UnmeteredGasMeter;
+use std::sync::{Arc, Mutex};
+
+#[test]
+fn paranoid_failure_diagnostic() {
+    let scenario = FailScenario::setup();
+    fail::cfg("skip-verification-for-paranoid-tests", "100%return").unwrap();
+
+    // Writes a u64 to a reference to a bool, which only the paranoid mode catches when the
+    // bytecode verifier is skipped.
+    let script = CompiledScript {
+        version: 6,
+        module_handles: vec![],
+        struct_handles: vec![],
+        function_handles: vec![],
+        function_instantiations: vec![],
+        signatures: vec![Signature(vec![]), Signature(vec![SignatureToken::Bool])],
+        identifiers: vec![],
+        address_identifiers: vec![],
+        constant_pool: vec![],
+        metadata: vec![],
+        code: CodeUnit {
+            locals: SignatureIndex(1),
+            code: vec![
+                LdU8(7),
+                LdTrue,
+                StLoc(0),
+                LdU64(10),
+                MutBorrowLoc(0),
+                WriteRef,
+                Pop,
+                Ret,
+            ],
+        },
+        type_parameters: vec![],
+        parameters: SignatureIndex(0),
+    };
+    let mut script_bytes = vec![];
+    script.serialize(&mut script_bytes).unwrap();
+
+    let diagnostics = Arc::new(Mutex::new(vec![]));
+    let captured = diagnostics.clone();
+    set_paranoid_check_diagnostic_hook(move |diagnostic: &ParanoidCheckDiagnostic| {
+        captured.lock().unwrap().push(diagnostic.clone());
+    });
+
+    let vm_config = VMConfig {
+        paranoid_type_checks: true,
+        ..Default::default()
+    };
+    let runtime_environment = RuntimeEnvironment::new_with_config(vec![], vm_config);
+    let vm = MoveVM::new_with_runtime_environment(&runtime_environment);
+    let storage = InMemoryStorage::new();
+    let mut session = vm.new_session(&storage);
+    let code_storage = storage.as_unsync_code_storage(runtime_environment);
+    let traversal_storage = TraversalStorage::new();
+    let err = session
+        .execute_script(
+            script_bytes.as_slice(),
+            vec![],
+            Vec::<Vec<u8>>::new(),
+            &mut UnmeteredGasMeter,
+            &mut TraversalContext::new(&traversal_storage),
+            &code_storage,
+        )
+        .unwrap_err();
+    clear_paranoid_check_diagnostic_hook();
+
+    assert_eq!(
+        err.major_status(),
+        StatusCode::UNKNOWN_INVARIANT_VIOLATION_ERROR
+    );
+    assert_eq!(err.sub_status(), Some(EPARANOID_FAILURE));
+
+    let diagnostics = diagnostics.lock().unwrap();
+    assert_eq!(diagnostics.len(), 1);
+    let diagnostic = &diagnostics[0];
+    assert_eq!(diagnostic.function, "script::main");
+    assert_eq!(diagnostic.code_offset, 5);
+    assert_eq!(diagnostic.instruction, "WriteRef");
+    // The operands of the failed instruction have been popped.
+    assert_eq!(diagnostic.type_stack, vec!["u8".to_string()]);
+    assert_eq!(
+        diagnostic.module_hash.as_ref().map(|hash| hash.len()),
+        Some(64)
+    );
+    assert_eq!(
+        diagnostic.message.as_deref(),
+        Some("Cannot write type u64 to type &mut bool")
+    );
+
+    // The diagnostic is also part of the error message.
+    assert!(err
+        .message()
+        .unwrap()
+        .contains(&format!("Paranoid check failure:\n{}", diagnostic)));
+
+    scenario.teardown();
+}
@@ -10,6 +10,7 @@ use crate::{
     module_traversal::TraversalContext,
     native_extensions::NativeContextExtensions,
     native_functions::NativeContext,
+    paranoid_diagnostics::{self, ParanoidCheckDiagnostic},
     runtime_type_checks::{FullRuntimeTypeCheck, NoRuntimeTypeCheck, RuntimeTypeCheck},
     trace, LoadedFunction, ModuleStorage,
 };
@@ -24,7 +25,9 @@ use move_core_types::{
     account_address::AccountAddress,
     gas_algebra::{NumArgs, NumBytes, NumTypeNodes},
     language_storage::{ModuleId, TypeTag},
-    vm_status::{StatusCode, StatusType},
+    vm_status::{
+        sub_status::unknown_invariant_violation::EPARANOID_FAILURE, StatusCode, StatusType,
+    },
 };
 use move_vm_types::{
     debug_write, debug_writeln,
@@ -1028,8 +1031,18 @@ impl InterpreterImpl {
         {
             let location = err.location().clone();
             let state = self.internal_state_str(current_frame);
-            err = err
-                .to_partial()
+            let mut partial_err = err.to_partial();
+            if partial_err.major_status() == StatusCode::UNKNOWN_INVARIANT_VIOLATION_ERROR
+                && partial_err.sub_status() == Some(EPARANOID_FAILURE)
+            {
+                let diagnostic = self.paranoid_check_diagnostic(&partial_err, current_frame);
+                paranoid_diagnostics::report(&diagnostic);
+                partial_err = partial_err.append_message_with_separator(
+                    '\n',
+                    format!("\nParanoid check failure:\n{}\n", diagnostic),
+                );
+            }
+            err = partial_err
                 .append_message_with_separator(
                     '\n',
                     format!("\nState: >>>>>>>>>>>>\n{}\n<<<<<<<<<<<<\n", state),
@@ -1039,6 +1052,32 @@ impl InterpreterImpl {
         err
     }
 
+    /// Collects the state of the current frame for a failed paranoid mode check.
+    fn paranoid_check_diagnostic(
+        &self,
+        err: &PartialVMError,
+        current_frame: &Frame,
+    ) -> ParanoidCheckDiagnostic {
+        let function = &current_frame.function;
+        ParanoidCheckDiagnostic {
+            function: function.name_as_pretty_string(),
+            code_offset: current_frame.pc,
+            instruction: function
+                .code()
+                .get(current_frame.pc as usize)
+                .map(|instruction| format!("{:?}", instruction))
+                .unwrap_or_default(),
+            type_stack: self
+                .operand_stack
+                .types
+                .iter()
+                .map(|ty| ty.to_string())
+                .collect(),
+            module_hash: function.owner_hash(),
+            message: err.message().map(|message| message.to_string()),
+        }
+    }
+
     #[allow(dead_code)]
     fn debug_print_frame<B: Write>(
         &self,
@@ -18,6 +18,7 @@ pub mod logging;
 pub mod move_vm;
 pub mod native_extensions;
 pub mod native_functions;
+pub mod paranoid_diagnostics;
 mod runtime;
 pub mod session;
 #[macro_use]
@@ -21,6 +21,7 @@ use move_vm_types::loaded_data::{
     runtime_access_specifier::AccessSpecifier,
     runtime_types::{StructIdentifier, Type},
 };
+use sha3::{Digest, Sha3_256};
 use std::{fmt::Debug, sync::Arc};
 
 /// A runtime function definition representation.
@@ -141,6 +142,18 @@ impl LoadedFunction {
         }
     }
 
+    /// Returns the hex encoded SHA3-256 hash of the serialized module or script which defines
+    /// this function, if it can be serialized.
+    pub(crate) fn owner_hash(&self) -> Option<String> {
+        let mut bytes = vec![];
+        match &self.owner {
+            LoadedFunctionOwner::Module(module) => module.module.serialize(&mut bytes),
+            LoadedFunctionOwner::Script(script) => script.script.serialize(&mut bytes),
+        }
+        .ok()?;
+        Some(hex::encode(Sha3_256::digest(&bytes)))
+    }
+
     pub(crate) fn get_resolver<'a>(
         &self,
         loader: &'a Loader,
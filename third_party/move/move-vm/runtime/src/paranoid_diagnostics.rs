@@ -0,0 +1,72 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! Structured diagnostics for the failures of the paranoid mode, i.e., of the runtime type
+//! checks. Such a failure means that the bytecode verifier or the VM has a bug, so besides
+//! failing the execution with an invariant violation, the VM records what it was executing. The
+//! embedder can install a hook to log the diagnostics, or to capture them for bug reports.
+
+use move_binary_format::file_format::CodeOffset;
+use move_vm_metrics::PARANOID_CHECK_FAILURES;
+use once_cell::sync::Lazy;
+use parking_lot::RwLock;
+use serde::Serialize;
+use std::fmt;
+
+/// The state of the VM when a paranoid mode check failed.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct ParanoidCheckDiagnostic {
+    /// The fully qualified name of the function being executed.
+    pub function: String,
+    /// The offset of the instruction being executed.
+    pub code_offset: CodeOffset,
+    /// The instruction being executed.
+    pub instruction: String,
+    /// The types on the operand stack, from the bottom to the top.
+    pub type_stack: Vec<String>,
+    /// The hex encoded SHA3-256 hash of the serialized module (or script) of the function, if
+    /// it can be serialized.
+    pub module_hash: Option<String>,
+    /// The message of the failed check.
+    pub message: Option<String>,
+}
+
+impl fmt::Display for ParanoidCheckDiagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "function: {}", self.function)?;
+        writeln!(f, "code offset: {}", self.code_offset)?;
+        writeln!(f, "instruction: {}", self.instruction)?;
+        writeln!(f, "type stack: [{}]", self.type_stack.join(", "))?;
+        writeln!(
+            f,
+            "module hash: {}",
+            self.module_hash.as_deref().unwrap_or("unknown")
+        )?;
+        write!(f, "message: {}", self.message.as_deref().unwrap_or(""))
+    }
+}
+
+type ParanoidCheckDiagnosticHook = Box<dyn Fn(&ParanoidCheckDiagnostic) + Send + Sync>;
+
+static HOOK: Lazy<RwLock<Option<ParanoidCheckDiagnosticHook>>> = Lazy::new(|| RwLock::new(None));
+
+/// Sets the hook which is called with the diagnostic of every paranoid mode failure, replacing
+/// the previous one. The hook is shared by all VM instances, and may be called concurrently.
+pub fn set_paranoid_check_diagnostic_hook(
+    hook: impl Fn(&ParanoidCheckDiagnostic) + Send + Sync + 'static,
+) {
+    *HOOK.write() = Some(Box::new(hook));
+}
+
+/// Removes the hook, if any.
+pub fn clear_paranoid_check_diagnostic_hook() {
+    *HOOK.write() = None;
+}
+
+/// Counts the failure, and passes the diagnostic to the hook.
+pub(crate) fn report(diagnostic: &ParanoidCheckDiagnostic) {
+    PARANOID_CHECK_FAILURES.inc();
+    if let Some(hook) = HOOK.read().as_ref() {
+        hook(diagnostic);
+    }
+}
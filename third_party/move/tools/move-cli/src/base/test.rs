@@ -22,7 +22,7 @@ use move_package::{
 };
 use move_unit_test::{
     test_reporter::{UnitTestFactory, UnitTestFactoryWithCostTable},
-    UnitTestingConfig,
+    TestShard, UnitTestingConfig,
 };
 use move_vm_runtime::tracing::{LOGGING_FILE_WRITER, TRACING_ENABLED};
 use move_vm_test_utils::gas_schedule::CostTable;
@@ -66,6 +66,11 @@ pub struct Test {
         long = "threads"
     )]
     pub num_threads: usize,
+    /// Only run the tests of the shard, given as `index/count`, e.g. `1/4`, to split the tests
+    /// across CI jobs. With `--coverage`, the coverage map of the package merges the coverage of
+    /// all the shards whose maps are present in the package.
+    #[clap(name = "shard", long = "shard")]
+    pub shard: Option<TestShard>,
     /// Report test statistics at the end of testing
     #[clap(name = "report_statistics", short = 's', long = "statistics")]
     pub report_statistics: bool,
@@ -110,6 +115,7 @@ impl Test {
             filter,
             list,
             num_threads,
+            shard,
             report_statistics,
             report_storage_on_error,
             ignore_compile_warnings,
@@ -123,6 +129,7 @@ impl Test {
             filter,
             list,
             num_threads,
+            shard,
             report_statistics,
             report_storage_on_error,
             check_stackless_vm,
@@ -348,13 +355,47 @@ pub fn run_move_unit_tests_with_factory<W: Write + Send, F: UnitTestFactory + Se
             let buf_writer = &mut *LOGGING_FILE_WRITER.lock().unwrap();
             buf_writer.flush().unwrap();
         }
-        let coverage_map = CoverageMap::from_trace_file(trace_path.clone());
-        output_map_to_file(coverage_map_path, &coverage_map).unwrap();
+        // A shard which runs none of the tests doesn't produce a trace
+        let coverage_map = if trace_path.exists() {
+            CoverageMap::from_trace_file(trace_path.clone())
+        } else {
+            CoverageMap::empty()
+        };
+        match unit_test_config.shard {
+            None => output_map_to_file(coverage_map_path, &coverage_map).unwrap(),
+            Some(shard) => {
+                // Each shard saves its own map, and the map of the package aggregates those of
+                // the shards run so far, so it is complete once the maps of all the shards
+                // (possibly copied from other CI jobs) are in the package.
+                output_map_to_file(shard_coverage_map_path(pkg_path, shard), &coverage_map)?;
+                let merged_coverage_map = merge_shard_coverage_maps(pkg_path, shard.count)?;
+                output_map_to_file(coverage_map_path, &merged_coverage_map)?;
+            },
+        }
     }
     cleanup_trace();
     Ok(UnitTestResult::Success)
 }
 
+fn shard_coverage_map_path(pkg_path: &Path, shard: TestShard) -> PathBuf {
+    pkg_path.join(format!(
+        ".coverage_map_shard_{}_of_{}.{}",
+        shard.index, shard.count, MOVE_COVERAGE_MAP_EXTENSION
+    ))
+}
+
+/// Merges the coverage maps of the shards of a test run which are present in the package.
+fn merge_shard_coverage_maps(pkg_path: &Path, count: usize) -> Result<CoverageMap> {
+    let mut merged_coverage_map = CoverageMap::empty();
+    for index in 1..=count {
+        let path = shard_coverage_map_path(pkg_path, TestShard { index, count });
+        if path.exists() {
+            merged_coverage_map.merge(CoverageMap::from_binary_file(path)?);
+        }
+    }
+    Ok(merged_coverage_map)
+}
+
 impl From<UnitTestResult> for ExitStatus {
     fn from(result: UnitTestResult) -> Self {
         match result {
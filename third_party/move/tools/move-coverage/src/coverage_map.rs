@@ -90,12 +90,15 @@ impl CoverageMap {
         self
     }
 
+    pub fn empty() -> Self {
+        CoverageMap {
+            exec_maps: BTreeMap::new(),
+        }
+    }
+
     /// Takes in a file containing a raw VM trace, and returns a coverage map.
     pub fn from_trace_file<P: AsRef<Path> + std::fmt::Debug>(filename: P) -> Self {
-        let empty_module_map = CoverageMap {
-            exec_maps: BTreeMap::new(),
-        };
-        empty_module_map.update_coverage_from_trace_file(filename)
+        Self::empty().update_coverage_from_trace_file(filename)
     }
 
     /// Takes in a file containing a serialized coverage map and returns a coverage map.
@@ -125,6 +128,29 @@ impl CoverageMap {
         exec_entry.insert(module_addr, module_name, func_name, pc);
     }
 
+    /// Adds the execution counts of another coverage map, e.g., of another shard of the tests.
+    pub fn merge(&mut self, another: CoverageMap) {
+        for (exec_id, exec_map) in another.exec_maps {
+            let exec_entry = self
+                .exec_maps
+                .entry(exec_id.clone())
+                .or_insert_with(|| ExecCoverageMap::new(exec_id));
+            for ((module_addr, module_name), module_map) in exec_map.module_maps {
+                for (func_name, func_map) in module_map.function_maps {
+                    for (pc, count) in func_map {
+                        exec_entry.insert_multi(
+                            module_addr,
+                            module_name.clone(),
+                            func_name.clone(),
+                            pc,
+                            count,
+                        );
+                    }
+                }
+            }
+        }
+    }
+
     pub fn to_unified_exec_map(&self) -> ExecCoverageMap {
         let mut unified_map = ExecCoverageMap::new(String::new());
         for (_, exec_map) in self.exec_maps.iter() {
@@ -3,7 +3,10 @@
 // SPDX-License-Identifier: Apache-2.0
 
 use crate::{
-    compilation::{build_plan::CompilerDriverResult, package_layout::CompiledPackageLayout},
+    compilation::{
+        build_plan::CompilerDriverResult, metrics_report::MetricsReport,
+        package_layout::CompiledPackageLayout,
+    },
     resolution::resolution_graph::{Renaming, ResolvedGraph, ResolvedPackage, ResolvedTable},
     source_package::{
         layout::{SourcePackageLayout, REFERENCE_TEMPLATE_FILENAME},
@@ -954,6 +957,18 @@ impl CompiledPackage {
             }
         }
 
+        if self
+            .compiled_package_info
+            .build_flags
+            .generate_metrics_report
+        {
+            let metrics_report = self.metrics_report(bytecode_version);
+            on_disk_package.save_under(
+                CompiledPackageLayout::MetricsReport.path(),
+                &serde_json::to_vec_pretty(&metrics_report)?,
+            )?;
+        }
+
         on_disk_package.save_under(
             CompiledPackageLayout::BuildInfo.path(),
             serde_yaml::to_string(&on_disk_package.package)?.as_bytes(),
@@ -962,6 +977,28 @@ impl CompiledPackage {
         Ok(on_disk_package)
     }
 
+    /// Returns the size and complexity metrics of the modules of the root package
+    pub fn metrics_report(&self, bytecode_version: u32) -> MetricsReport {
+        let root_modules = self
+            .root_compiled_units
+            .iter()
+            .filter_map(|unit| match &unit.unit {
+                CompiledUnit::Module(NamedCompiledModule { module, .. }) => {
+                    Some((module, unit.unit.serialize(Some(bytecode_version)).len()))
+                },
+                CompiledUnit::Script(_) => None,
+            });
+        let all_modules = self.all_modules().filter_map(|unit| match &unit.unit {
+            CompiledUnit::Module(NamedCompiledModule { module, .. }) => Some(module),
+            CompiledUnit::Script(_) => None,
+        });
+        MetricsReport::new(
+            self.compiled_package_info.package_name.to_string(),
+            root_modules,
+            all_modules,
+        )
+    }
+
     fn build_abis(
         bytecode_version: u32,
         model: &GlobalEnv,
@@ -0,0 +1,123 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+//! A report of the size and complexity of each module of a compiled package, so that the growth
+//! of the modules can be tracked over time, and budgets enforced in CI.
+
+use move_binary_format::{
+    access::ModuleAccess,
+    control_flow_graph::{ControlFlowGraph, VMControlFlowGraph},
+    file_format::CompiledModule,
+};
+use move_core_types::language_storage::ModuleId;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ModuleMetrics {
+    /// The fully qualified name of the module, e.g. `0x1::coin`
+    pub module: String,
+    /// The size of the serialized module, in bytes
+    pub bytecode_size: usize,
+    pub num_functions: usize,
+    /// The largest number of locals (including parameters) of a function of the module
+    pub max_function_locals: usize,
+    /// The largest number of basic blocks of a function of the module
+    pub max_function_blocks: usize,
+    /// The number of modules of the package and its dependencies that depend on this module
+    pub dependency_fan_in: usize,
+    /// The number of modules this module depends on
+    pub dependency_fan_out: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct MetricsReport {
+    pub package: String,
+    /// The metrics of the modules of the package (not its dependencies), sorted by module name
+    pub modules: Vec<ModuleMetrics>,
+}
+
+impl MetricsReport {
+    /// Builds the report of the `package_modules` along with their serialized size. The
+    /// `all_modules` are the modules of the package and its dependencies, which are only used
+    /// to compute the fan-in of the package modules.
+    pub fn new<'a>(
+        package: String,
+        package_modules: impl IntoIterator<Item = (&'a CompiledModule, usize)>,
+        all_modules: impl IntoIterator<Item = &'a CompiledModule>,
+    ) -> Self {
+        let mut fan_in: BTreeMap<ModuleId, usize> = BTreeMap::new();
+        for module in all_modules {
+            for dependency in module.immediate_dependencies() {
+                *fan_in.entry(dependency).or_default() += 1;
+            }
+        }
+
+        let mut modules: Vec<ModuleMetrics> = package_modules
+            .into_iter()
+            .map(|(module, bytecode_size)| {
+                let self_id = module.self_id();
+                ModuleMetrics {
+                    module: format!("{}", self_id.short_str_lossless()),
+                    bytecode_size,
+                    num_functions: module.function_defs().len(),
+                    max_function_locals: max_function_locals(module),
+                    max_function_blocks: max_function_blocks(module),
+                    dependency_fan_in: fan_in.get(&self_id).copied().unwrap_or_default(),
+                    dependency_fan_out: module.immediate_dependencies().len(),
+                }
+            })
+            .collect();
+        modules.sort_by(|a, b| a.module.cmp(&b.module));
+        Self { package, modules }
+    }
+}
+
+fn max_function_locals(module: &CompiledModule) -> usize {
+    module
+        .function_defs()
+        .iter()
+        .filter_map(|function_def| {
+            let code = function_def.code.as_ref()?;
+            let handle = module.function_handle_at(function_def.function);
+            Some(
+                module.signature_at(handle.parameters).len()
+                    + module.signature_at(code.locals).len(),
+            )
+        })
+        .max()
+        .unwrap_or_default()
+}
+
+fn max_function_blocks(module: &CompiledModule) -> usize {
+    module
+        .function_defs()
+        .iter()
+        .filter_map(|function_def| {
+            let code = function_def.code.as_ref()?;
+            Some(VMControlFlowGraph::new(&code.code).num_blocks() as usize)
+        })
+        .max()
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use move_binary_format::file_format::empty_module;
+
+    #[test]
+    fn test_empty_module_metrics() {
+        let module = empty_module();
+        let report = MetricsReport::new("Package".to_string(), [(&module, 42)], [&module]);
+        assert_eq!(report.modules, vec![ModuleMetrics {
+            module: format!("{}", module.self_id().short_str_lossless()),
+            bytecode_size: 42,
+            num_functions: 0,
+            max_function_locals: 0,
+            max_function_blocks: 0,
+            dependency_fan_in: 0,
+            dependency_fan_out: 0,
+        }]);
+    }
+}
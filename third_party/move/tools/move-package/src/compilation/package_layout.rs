@@ -15,6 +15,7 @@ pub enum CompiledPackageLayout {
     CompiledScripts,
     CompiledDocs,
     CompiledABIs,
+    MetricsReport,
 }
 
 impl CompiledPackageLayout {
@@ -29,6 +30,7 @@ impl CompiledPackageLayout {
             Self::CompiledScripts => "bytecode_scripts",
             Self::CompiledDocs => "docs",
             Self::CompiledABIs => "abis",
+            Self::MetricsReport => "metrics_report.json",
         };
         Path::new(path)
     }
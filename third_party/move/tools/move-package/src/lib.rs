@@ -112,6 +112,15 @@ pub struct BuildConfig {
     #[clap(name = "generate-abis", long = "abi", global = true)]
     pub generate_abis: bool,
 
+    /// Generate a JSON report of the size and complexity of each module of the package
+    #[clap(
+        name = "generate-metrics-report",
+        long = "metrics-report",
+        global = true
+    )]
+    #[serde(default)]
+    pub generate_metrics_report: bool,
+
     /// Whether to generate a move model. Used programmatically only.
     #[clap(skip)]
     pub generate_move_model: bool,
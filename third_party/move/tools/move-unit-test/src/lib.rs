@@ -7,6 +7,7 @@ pub mod test_reporter;
 pub mod test_runner;
 
 use crate::test_runner::TestRunner;
+use anyhow::{bail, ensure};
 use clap::*;
 use move_command_line_common::files::verify_and_create_named_address_mapping;
 use move_compiler::{
@@ -21,8 +22,10 @@ use move_package::compilation::compiled_package::build_and_report_v2_driver;
 use move_vm_runtime::native_functions::NativeFunctionTable;
 use std::{
     collections::BTreeMap,
+    fmt,
     io::{Result, Write},
     marker::Send,
+    str::FromStr,
     sync::Mutex,
 };
 use test_reporter::UnitTestFactory;
@@ -30,6 +33,46 @@ use test_reporter::UnitTestFactory;
 /// The default value bounding the amount of gas consumed in a test.
 const DEFAULT_EXECUTION_BOUND: u64 = 1_000_000;
 
+/// One of the disjoint subsets of the tests, given as `index/count` with `index` starting at 1, so
+/// that a test suite can be split across CI jobs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TestShard {
+    pub index: usize,
+    pub count: usize,
+}
+
+impl TestShard {
+    /// Whether the test at the position, in the order of the fully qualified test names, belongs
+    /// to this shard.
+    pub fn contains(&self, position: usize) -> bool {
+        position % self.count == self.index - 1
+    }
+}
+
+impl FromStr for TestShard {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> anyhow::Result<Self> {
+        let Some((index, count)) = s.split_once('/') else {
+            bail!("Invalid shard '{}', expected 'index/count', e.g. '1/4'", s);
+        };
+        let index: usize = index.trim().parse()?;
+        let count: usize = count.trim().parse()?;
+        ensure!(
+            index >= 1 && index <= count,
+            "Invalid shard '{}', the index must be between 1 and the number of shards",
+            s
+        );
+        Ok(Self { index, count })
+    }
+}
+
+impl fmt::Display for TestShard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.index, self.count)
+    }
+}
+
 #[derive(Debug, Parser, Clone)]
 #[clap(author, version, about)]
 pub struct UnitTestingConfig {
@@ -50,6 +93,11 @@ pub struct UnitTestingConfig {
     )]
     pub num_threads: usize,
 
+    /// Only run the tests of the shard, given as `index/count`, e.g. `1/4`. The tests are
+    /// assigned to the shards round-robin, after filtering.
+    #[clap(name = "shard", long = "shard")]
+    pub shard: Option<TestShard>,
+
     /// Dependency files
     #[clap(
         name = "dependencies",
@@ -123,6 +171,7 @@ impl Default for UnitTestingConfig {
         Self {
             filter: None,
             num_threads: 8,
+            shard: None,
             report_statistics: false,
             report_storage_on_error: false,
             report_stacktrace_on_abort: false,
@@ -238,6 +287,10 @@ impl UnitTestingConfig {
             test_runner.filter(filter_str)
         }
 
+        if let Some(shard) = self.shard {
+            test_runner.shard(shard)
+        }
+
         let test_results = test_runner.run(&shared_writer, &shared_options).unwrap();
         if self.report_statistics {
             test_results.report_statistics(&shared_writer)?;
@@ -259,3 +312,17 @@ fn verify_tool() {
     use clap::CommandFactory;
     UnitTestingConfig::command().debug_assert()
 }
+
+#[test]
+fn parse_test_shard() {
+    let shard: TestShard = "2/3".parse().unwrap();
+    assert_eq!(shard, TestShard { index: 2, count: 3 });
+    assert_eq!(shard.to_string(), "2/3");
+    assert!(!shard.contains(0));
+    assert!(shard.contains(1));
+    assert!(shard.contains(4));
+
+    assert!("0/3".parse::<TestShard>().is_err());
+    assert!("4/3".parse::<TestShard>().is_err());
+    assert!("3".parse::<TestShard>().is_err());
+}
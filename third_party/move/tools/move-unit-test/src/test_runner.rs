@@ -8,6 +8,7 @@ use crate::{
         FailureReason, MoveError, TestFailure, TestResults, TestRunInfo, TestStatistics,
         UnitTestFactory,
     },
+    TestShard,
 };
 use anyhow::Result;
 use colored::*;
@@ -187,6 +188,23 @@ impl TestRunner {
             })
     }
 
+    /// Only keeps the tests of the shard. The tests are assigned to the shards round-robin, in
+    /// the order of their fully qualified names, so that the shards are of similar sizes.
+    pub fn shard(&mut self, shard: TestShard) {
+        let mut position = 0;
+        for module_test in self.tests.module_tests.values_mut() {
+            let tests = std::mem::take(&mut module_test.tests);
+            module_test.tests = tests
+                .into_iter()
+                .filter(|_| {
+                    let in_shard = shard.contains(position);
+                    position += 1;
+                    in_shard
+                })
+                .collect();
+        }
+    }
+
     pub fn filter(&mut self, test_name_slice: &str) {
         for (module_id, module_test) in self.tests.module_tests.iter_mut() {
             if module_id.name().as_str().contains(test_name_slice) {
@@ -320,150 +338,158 @@ impl SharedTestingConfig {
         }
     }
 
-    fn exec_module_tests_move_vm_and_stackless_vm<F: UnitTestFactory>(
+    fn exec_module_tests_move_vm_and_stackless_vm<F: UnitTestFactory + Send>(
         &self,
         test_plan: &ModuleTestPlan,
-        output: &TestOutput<impl Write>,
+        output: &TestOutput<impl Write + Send>,
         factory: &Mutex<F>,
     ) -> TestStatistics {
-        let mut stats = TestStatistics::new();
-
-        for (function_name, test_info) in &test_plan.tests {
-            let (cs_result, ext_result, exec_result, test_run_info) =
-                self.execute_via_move_vm(test_plan, function_name, test_info, factory);
-
-            if self.record_writeset {
-                stats.test_output(
-                    function_name.to_string(),
-                    test_plan,
-                    format!("{:?}", cs_result),
-                );
-            }
-
-            let save_session_state = || {
-                if self.save_storage_state_on_failure {
-                    cs_result.ok().and_then(|changeset| {
-                        ext_result.ok().and_then(|mut extensions| {
-                            print_resources_and_extensions(
-                                &changeset,
-                                &mut extensions,
-                                &self.starting_storage_state,
-                                &self.native_function_table,
-                            )
-                            .ok()
-                        })
-                    })
-                } else {
-                    None
+        // Each test runs in its own session on top of the starting storage state, so tests don't
+        // observe each other's effects and can run in parallel.
+        test_plan
+            .tests
+            .par_iter()
+            .map(|(function_name, test_info)| {
+                let mut stats = TestStatistics::new();
+                let (cs_result, ext_result, exec_result, test_run_info) =
+                    self.execute_via_move_vm(test_plan, function_name, test_info, factory);
+
+                if self.record_writeset {
+                    stats.test_output(
+                        function_name.to_string(),
+                        test_plan,
+                        format!("{:?}", cs_result),
+                    );
                 }
-            };
 
-            match exec_result {
-                Err(err) => {
-                    let actual_err = MoveError(
-                        err.major_status(),
-                        err.sub_status(),
-                        err.location().clone(),
-                        err.message().cloned(),
-                    );
-                    assert!(err.major_status() != StatusCode::EXECUTED);
-                    match test_info.expected_failure.as_ref() {
-                        Some(ExpectedFailure::Expected) => {
-                            output.pass(function_name);
-                            stats.test_success(test_run_info, test_plan);
-                        },
-                        Some(ExpectedFailure::ExpectedWithError(expected_err))
-                            if expected_err == &actual_err =>
-                        {
-                            output.pass(function_name);
-                            stats.test_success(test_run_info, test_plan);
-                        },
-                        Some(ExpectedFailure::ExpectedWithCodeDEPRECATED(code))
-                            if actual_err.0 == StatusCode::ABORTED
-                                && actual_err.1.is_some()
-                                && actual_err.1.unwrap() == *code =>
-                        {
-                            output.pass(function_name);
-                            stats.test_success(test_run_info, test_plan);
-                        },
-                        // incorrect cases
-                        Some(ExpectedFailure::ExpectedWithError(expected_err)) => {
-                            output.fail(function_name);
-                            stats.test_failure(
-                                TestFailure::new(
-                                    FailureReason::wrong_error(expected_err.clone(), actual_err),
-                                    test_run_info,
-                                    Some(err),
-                                    save_session_state(),
-                                ),
-                                test_plan,
-                            )
-                        },
-                        Some(ExpectedFailure::ExpectedWithCodeDEPRECATED(expected_code)) => {
-                            output.fail(function_name);
-                            stats.test_failure(
-                                TestFailure::new(
-                                    FailureReason::wrong_abort_deprecated(
-                                        *expected_code,
-                                        actual_err,
+                let save_session_state = || {
+                    if self.save_storage_state_on_failure {
+                        cs_result.ok().and_then(|changeset| {
+                            ext_result.ok().and_then(|mut extensions| {
+                                print_resources_and_extensions(
+                                    &changeset,
+                                    &mut extensions,
+                                    &self.starting_storage_state,
+                                    &self.native_function_table,
+                                )
+                                .ok()
+                            })
+                        })
+                    } else {
+                        None
+                    }
+                };
+
+                match exec_result {
+                    Err(err) => {
+                        let actual_err = MoveError(
+                            err.major_status(),
+                            err.sub_status(),
+                            err.location().clone(),
+                            err.message().cloned(),
+                        );
+                        assert!(err.major_status() != StatusCode::EXECUTED);
+                        match test_info.expected_failure.as_ref() {
+                            Some(ExpectedFailure::Expected) => {
+                                output.pass(function_name);
+                                stats.test_success(test_run_info, test_plan);
+                            },
+                            Some(ExpectedFailure::ExpectedWithError(expected_err))
+                                if expected_err == &actual_err =>
+                            {
+                                output.pass(function_name);
+                                stats.test_success(test_run_info, test_plan);
+                            },
+                            Some(ExpectedFailure::ExpectedWithCodeDEPRECATED(code))
+                                if actual_err.0 == StatusCode::ABORTED
+                                    && actual_err.1.is_some()
+                                    && actual_err.1.unwrap() == *code =>
+                            {
+                                output.pass(function_name);
+                                stats.test_success(test_run_info, test_plan);
+                            },
+                            // incorrect cases
+                            Some(ExpectedFailure::ExpectedWithError(expected_err)) => {
+                                output.fail(function_name);
+                                stats.test_failure(
+                                    TestFailure::new(
+                                        FailureReason::wrong_error(
+                                            expected_err.clone(),
+                                            actual_err,
+                                        ),
+                                        test_run_info,
+                                        Some(err),
+                                        save_session_state(),
                                     ),
-                                    test_run_info,
-                                    Some(err),
-                                    save_session_state(),
-                                ),
-                                test_plan,
-                            )
-                        },
-                        None if err.major_status() == StatusCode::OUT_OF_GAS => {
-                            // Ran out of ticks, report a test timeout and log a test failure
-                            output.timeout(function_name);
-                            stats.test_failure(
-                                TestFailure::new(
-                                    FailureReason::timeout(),
-                                    test_run_info,
-                                    Some(err),
-                                    save_session_state(),
-                                ),
-                                test_plan,
-                            )
-                        },
-                        None => {
+                                    test_plan,
+                                )
+                            },
+                            Some(ExpectedFailure::ExpectedWithCodeDEPRECATED(expected_code)) => {
+                                output.fail(function_name);
+                                stats.test_failure(
+                                    TestFailure::new(
+                                        FailureReason::wrong_abort_deprecated(
+                                            *expected_code,
+                                            actual_err,
+                                        ),
+                                        test_run_info,
+                                        Some(err),
+                                        save_session_state(),
+                                    ),
+                                    test_plan,
+                                )
+                            },
+                            None if err.major_status() == StatusCode::OUT_OF_GAS => {
+                                // Ran out of ticks, report a test timeout and log a test failure
+                                output.timeout(function_name);
+                                stats.test_failure(
+                                    TestFailure::new(
+                                        FailureReason::timeout(),
+                                        test_run_info,
+                                        Some(err),
+                                        save_session_state(),
+                                    ),
+                                    test_plan,
+                                )
+                            },
+                            None => {
+                                output.fail(function_name);
+                                stats.test_failure(
+                                    TestFailure::new(
+                                        FailureReason::unexpected_error(actual_err),
+                                        test_run_info,
+                                        Some(err),
+                                        save_session_state(),
+                                    ),
+                                    test_plan,
+                                )
+                            },
+                        }
+                    },
+                    Ok(_) => {
+                        // Expected the test to fail, but it executed
+                        if test_info.expected_failure.is_some() {
                             output.fail(function_name);
                             stats.test_failure(
                                 TestFailure::new(
-                                    FailureReason::unexpected_error(actual_err),
+                                    FailureReason::no_error(),
                                     test_run_info,
-                                    Some(err),
+                                    None,
                                     save_session_state(),
                                 ),
                                 test_plan,
                             )
-                        },
-                    }
-                },
-                Ok(_) => {
-                    // Expected the test to fail, but it executed
-                    if test_info.expected_failure.is_some() {
-                        output.fail(function_name);
-                        stats.test_failure(
-                            TestFailure::new(
-                                FailureReason::no_error(),
-                                test_run_info,
-                                None,
-                                save_session_state(),
-                            ),
-                            test_plan,
-                        )
-                    } else {
-                        // Expected the test to execute fully and it did
-                        output.pass(function_name);
-                        stats.test_success(test_run_info, test_plan);
-                    }
-                },
-            }
-        }
+                        } else {
+                            // Expected the test to execute fully and it did
+                            output.pass(function_name);
+                            stats.test_success(test_run_info, test_plan);
+                        }
+                    },
+                }
 
-        stats
+                stats
+            })
+            .reduce(TestStatistics::new, |acc, stats| acc.combine(stats))
     }
 
     #[cfg(feature = "evm-backend")]
@@ -683,10 +709,10 @@ impl SharedTestingConfig {
 
     // TODO: comparison of results via different backends
 
-    fn exec_module_tests<F: UnitTestFactory>(
+    fn exec_module_tests<F: UnitTestFactory + Send>(
         &self,
         test_plan: &ModuleTestPlan,
-        writer: &Mutex<impl Write>,
+        writer: &Mutex<impl Write + Send>,
         factory: &Mutex<F>,
     ) -> TestStatistics {
         let output = TestOutput { test_plan, writer };
@@ -0,0 +1,77 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Share of the block gas limit given to one of multiple executors that execute parts of the same
+/// block, e.g. a shard of the sharded block executor.
+///
+/// The budget is carried across the rounds the executor runs one after another, so that the block
+/// gas limit applies to all the txns of the executor instead of to each round. Each budget is only
+/// consumed by a single executor, in the order in which its txns are committed, so the txns it
+/// stops at do not depend on how fast the other executors run.
+#[derive(Debug)]
+pub struct BlockGasBudget {
+    budget: u64,
+    consumed: AtomicU64,
+}
+
+impl BlockGasBudget {
+    pub fn new(budget: u64) -> Self {
+        Self {
+            budget,
+            consumed: AtomicU64::new(0),
+        }
+    }
+
+    pub fn budget(&self) -> u64 {
+        self.budget
+    }
+
+    /// Consumes the effective gas of committed txns, and returns the consumed effective gas.
+    pub fn consume(&self, effective_block_gas: u64) -> u64 {
+        self.consumed
+            .fetch_add(effective_block_gas, Ordering::Relaxed)
+            .saturating_add(effective_block_gas)
+    }
+
+    pub fn consumed(&self) -> u64 {
+        self.consumed.load(Ordering::Relaxed)
+    }
+
+    /// Sets the consumed effective gas back to a previous value, to undo the consumption of an
+    /// execution attempt whose outputs are discarded (e.g. when falling back to sequential).
+    pub fn reset_consumed(&self, consumed: u64) {
+        self.consumed.store(consumed, Ordering::Relaxed);
+    }
+
+    pub fn is_exhausted(&self) -> bool {
+        self.consumed() >= self.budget
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_block_gas_budget() {
+        let budget = BlockGasBudget::new(100);
+        // Consumed by consecutive rounds of the same executor.
+        for _ in 0..4 {
+            for _ in 0..5 {
+                budget.consume(4);
+            }
+        }
+        assert_eq!(budget.consumed(), 80);
+        assert!(!budget.is_exhausted());
+
+        assert_eq!(budget.consume(20), 100);
+        assert!(budget.is_exhausted());
+
+        // A discarded execution attempt does not consume the budget.
+        budget.reset_consumed(80);
+        assert_eq!(budget.consumed(), 80);
+        assert!(!budget.is_exhausted());
+    }
+}
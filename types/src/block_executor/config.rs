@@ -1,8 +1,12 @@
 // Copyright © Aptos Foundation
 // SPDX-License-Identifier: Apache-2.0
 
-use crate::on_chain_config::BlockGasLimitType;
+use crate::{
+    block_executor::{block_gas_budget::BlockGasBudget, execution_order_hint::ExecutionOrderHint},
+    on_chain_config::BlockGasLimitType,
+};
 use serde::{Deserialize, Serialize};
+use std::{path::PathBuf, sync::Arc, time::Duration};
 
 /// Local, per-node configurations for module cache. While caches can be persisted across multiple
 /// block executions, these configurations allow to specify cache sizes, etc.
@@ -30,6 +34,80 @@ impl Default for BlockExecutorModuleCacheLocalConfig {
     }
 }
 
+/// Local, per-node configuration for BlockSTM profiling.
+#[derive(Clone, Debug)]
+pub struct BlockSTMProfilingLocalConfig {
+    /// If true, after each successful parallel execution, a report describing re-executions,
+    /// dependencies and conflicts of each transaction in the block is written as JSON.
+    pub enable_block_stm_profiling: bool,
+    /// Directory where the profiling reports are written to.
+    pub output_dir: PathBuf,
+}
+
+impl Default for BlockSTMProfilingLocalConfig {
+    fn default() -> Self {
+        Self {
+            enable_block_stm_profiling: false,
+            output_dir: PathBuf::from("block_stm_profiles"),
+        }
+    }
+}
+
+/// Policy for the BlockSTM committer backup. When the next transaction to commit is stalled
+/// waiting for a validation, the thread coordinating commits may perform the validation (and, if
+/// it fails, the re-execution) of that transaction itself, instead of waiting for other workers.
+#[derive(Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BlockSTMCommitterBackup {
+    /// Committer never performs backup work.
+    #[default]
+    Disabled,
+    /// Committer always performs backup work when commit is stalled.
+    Always,
+    /// Committer performs backup work only if the observed validation failure rate or commit
+    /// stall duration in the block exceed the thresholds in [BlockSTMCommitterBackupThresholds].
+    Adaptive,
+}
+
+/// Thresholds used by [BlockSTMCommitterBackup::Adaptive] policy. Backup is enabled as soon as
+/// any of the thresholds is exceeded.
+#[derive(Clone, Debug)]
+pub struct BlockSTMCommitterBackupThresholds {
+    /// Percentage (0 - 100) of failed validations among all validations performed in the block.
+    pub validation_failure_rate_percent: u64,
+    /// Minimum number of validations that need to be observed before the failure rate is taken
+    /// into account, so that the decision is not made based on a handful of samples.
+    pub min_num_validations: u64,
+    /// Time elapsed since the last commit (or since the start of the block if nothing has been
+    /// committed yet).
+    pub commit_stall_duration: Duration,
+}
+
+impl Default for BlockSTMCommitterBackupThresholds {
+    fn default() -> Self {
+        Self {
+            validation_failure_rate_percent: 20,
+            min_num_validations: 32,
+            commit_stall_duration: Duration::from_micros(500),
+        }
+    }
+}
+
+/// Debug mode to reproduce rare BlockSTM issues. The schedule of a parallel execution (executions
+/// and validations of transactions, in the order they finished) can be recorded, and replayed
+/// deterministically on a single thread in a later run.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum BlockSTMScheduleReplayMode {
+    Disabled,
+    /// The schedule of each parallel block execution is written as JSON to a new file in the
+    /// specified directory.
+    Record(PathBuf),
+    /// Instead of running workers on multiple threads, parallel execution replays the schedule
+    /// stored in the specified file. The block must be the same as the one executed when the
+    /// schedule was recorded.
+    Replay(PathBuf),
+}
+
 /// Local, per-node configuration.
 #[derive(Clone, Debug)]
 pub struct BlockExecutorLocalConfig {
@@ -41,6 +119,31 @@ pub struct BlockExecutorLocalConfig {
     // (allow_fallback needs to be set)
     pub discard_failed_blocks: bool,
     pub module_cache_config: BlockExecutorModuleCacheLocalConfig,
+    // Policy for the committer to perform backup validation / execution of stalled transactions.
+    pub committer_backup: BlockSTMCommitterBackup,
+    // Thresholds used when committer backup policy is adaptive.
+    pub committer_backup_thresholds: BlockSTMCommitterBackupThresholds,
+    pub profiling_config: BlockSTMProfilingLocalConfig,
+    // Debug mode to record or replay schedules of parallel executions.
+    pub schedule_replay_mode: BlockSTMScheduleReplayMode,
+    // If set, used to compute the order in which transactions are picked for parallel execution.
+    // Transactions are still committed in the original order.
+    pub execution_order_hint: Option<Arc<dyn ExecutionOrderHint>>,
+    // If true, parallel execution workers are pinned to cores while they execute the block, such
+    // that workers are grouped by NUMA node (socket), reducing cross-socket traffic on the shared
+    // multi-version data structure. Transactions are still scheduled across all workers. Ignored
+    // if the NUMA topology cannot be detected.
+    pub pin_workers_to_numa_nodes: bool,
+    // If set, the block is a part of a larger block split between multiple executors (e.g. a
+    // sub-block of a shard), and no more transactions are committed once the effective gas of
+    // the transactions committed by this executor (across all its blocks) exhausts its share of
+    // the block gas limit (in addition to the block gas limit of this block).
+    pub block_gas_budget: Option<Arc<BlockGasBudget>>,
+    // If set, when parallel execution fails and the block falls back to sequential execution,
+    // the transactions of the block, the keys they read and the parallel execution error are
+    // written as JSON to a new file in this directory. Fallbacks can mask bugs, so the dumps
+    // help to diagnose them.
+    pub sequential_fallback_dump_dir: Option<PathBuf>,
 }
 
 impl BlockExecutorLocalConfig {
@@ -48,12 +151,27 @@ impl BlockExecutorLocalConfig {
     ///   - Allowed fallback to sequential execution from parallel.
     ///   - Not allowed discards of failed blocks.
     ///   - Default module cache configs.
+    ///   - Disabled committer backup.
+    ///   - Disabled profiling.
+    ///   - Disabled recording and replay of schedules.
+    ///   - No execution order hint.
+    ///   - No NUMA pinning of workers.
+    ///   - No share of a block gas limit split between executors.
+    ///   - No dumps of blocks that fall back to sequential execution.
     pub fn default_with_concurrency_level(concurrency_level: usize) -> Self {
         Self {
             concurrency_level,
             allow_fallback: true,
             discard_failed_blocks: false,
             module_cache_config: BlockExecutorModuleCacheLocalConfig::default(),
+            committer_backup: BlockSTMCommitterBackup::Disabled,
+            committer_backup_thresholds: BlockSTMCommitterBackupThresholds::default(),
+            profiling_config: BlockSTMProfilingLocalConfig::default(),
+            schedule_replay_mode: BlockSTMScheduleReplayMode::Disabled,
+            execution_order_hint: None,
+            pin_workers_to_numa_nodes: false,
+            block_gas_budget: None,
+            sequential_fallback_dump_dir: None,
         }
     }
 }
@@ -0,0 +1,82 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use move_core_types::account_address::AccountAddress;
+use std::{collections::HashMap, fmt::Debug};
+
+/// Information about a transaction in a block, based on which the execution order is computed.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TransactionOrderingInfo {
+    /// Sender of the transaction, if it is a user transaction.
+    pub sender: Option<AccountAddress>,
+    /// Size of the user transaction in bytes, 0 otherwise.
+    pub user_txn_bytes_len: usize,
+}
+
+/// Strategy that reorders (or partitions) transactions of a block before the parallel execution
+/// starts, e.g. groups transactions by sender, or separates writers of hot resources.
+///
+/// The reordering is only a hint for the order in which BlockSTM picks transactions for
+/// (speculative) execution. The original order is kept for validation and commit, so outputs
+/// and the block gas limit (which always cuts the block at a prefix of the original order) are
+/// not affected. Hence, strategies should keep early transactions early, as work spent on
+/// transactions past the block limit is wasted.
+pub trait ExecutionOrderHint: Debug + Send + Sync {
+    /// Returns the execution order: a permutation of indices of the provided transactions. If
+    /// the returned vector is not a permutation, the hint is ignored.
+    fn execution_order(&self, txns: &[TransactionOrderingInfo]) -> Vec<u32>;
+}
+
+/// Executes transactions of the same sender one after another. Senders are ordered by their
+/// first transaction in the block, and non-user transactions keep their position.
+#[derive(Debug, Default)]
+pub struct GroupBySenderExecutionOrderHint;
+
+impl ExecutionOrderHint for GroupBySenderExecutionOrderHint {
+    fn execution_order(&self, txns: &[TransactionOrderingInfo]) -> Vec<u32> {
+        let mut groups: Vec<Vec<u32>> = Vec::new();
+        let mut sender_to_group = HashMap::new();
+
+        for (idx, txn) in txns.iter().enumerate() {
+            let idx = idx as u32;
+            match txn.sender {
+                Some(sender) => {
+                    let group = *sender_to_group.entry(sender).or_insert_with(|| {
+                        groups.push(Vec::new());
+                        groups.len() - 1
+                    });
+                    groups[group].push(idx);
+                },
+                None => groups.push(vec![idx]),
+            }
+        }
+        groups.into_iter().flatten().collect()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn info(sender: Option<u8>) -> TransactionOrderingInfo {
+        TransactionOrderingInfo {
+            sender: sender.map(|s| AccountAddress::new([s; AccountAddress::LENGTH])),
+            user_txn_bytes_len: 100,
+        }
+    }
+
+    #[test]
+    fn test_group_by_sender() {
+        let txns = vec![
+            info(None),
+            info(Some(1)),
+            info(Some(2)),
+            info(Some(1)),
+            info(None),
+            info(Some(2)),
+            info(Some(3)),
+        ];
+        let order = GroupBySenderExecutionOrderHint.execution_order(&txns);
+        assert_eq!(order, vec![0, 1, 3, 2, 5, 4, 6]);
+    }
+}
@@ -2,6 +2,8 @@
 // Parts of the project are originally copyright © Meta Platforms, Inc.
 // SPDX-License-Identifier: Apache-2.0
 
+pub mod block_gas_budget;
 pub mod config;
+pub mod execution_order_hint;
 pub mod partitioner;
 pub mod transaction_slice_metadata;
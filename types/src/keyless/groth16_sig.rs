@@ -12,7 +12,7 @@ use crate::{
     transaction::authenticator::EphemeralSignature,
 };
 use anyhow::bail;
-use aptos_crypto::CryptoMaterialError;
+use aptos_crypto::{signed_envelope::SignedEnvelope, CryptoMaterialError};
 use aptos_crypto_derive::{BCSCryptoHash, CryptoHasher};
 use ark_bn254::{Bn254, Fr};
 use ark_ff::{BigInteger, PrimeField};
@@ -49,6 +49,10 @@ pub struct ZeroKnowledgeSig {
     pub training_wheels_signature: Option<EphemeralSignature>,
 }
 
+/// A Groth16 proof and its statement, along with the training wheels signature of the prover
+/// service on them.
+pub type TrainingWheelsSignedProof = SignedEnvelope<Groth16ProofAndStatement, EphemeralSignature>;
+
 /// This struct is used to wrap together the Groth16 ZKP and the statement it proves so that the
 /// prover service can sign them together. It is only used during signature verification & never
 /// sent over the network.
@@ -59,6 +63,9 @@ pub struct Groth16ProofAndStatement {
     pub public_inputs_hash: [u8; 32],
 }
 
+// The message types of this crate which are signed through a `SignedEnvelope`.
+aptos_crypto::signing_domains!(Groth16ProofAndStatement);
+
 impl Groth16ProofAndStatement {
     pub fn new(proof: Groth16Proof, public_inputs_hash: Fr) -> Self {
         let public_inputs_hash: [u8; 32] = public_inputs_hash
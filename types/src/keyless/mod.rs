@@ -38,7 +38,9 @@ pub use bn254_circom::{
     G2Bytes, G1_PROJECTIVE_COMPRESSED_NUM_BYTES, G2_PROJECTIVE_COMPRESSED_NUM_BYTES,
 };
 pub use configuration::Configuration;
-pub use groth16_sig::{Groth16Proof, Groth16ProofAndStatement, ZeroKnowledgeSig};
+pub use groth16_sig::{
+    Groth16Proof, Groth16ProofAndStatement, TrainingWheelsSignedProof, ZeroKnowledgeSig,
+};
 pub use groth16_vk::Groth16VerificationKey;
 use move_core_types::account_address::AccountAddress;
 pub use openid_sig::{Claims, OpenIdSig};
@@ -54,6 +54,7 @@ pub mod validator_verifier;
 pub mod vesting;
 pub mod vm_status;
 pub mod waypoint;
+pub mod waypoint_bundle;
 pub mod write_set;
 
 pub use account_address::AccountAddress as PeerId;
@@ -104,6 +104,9 @@ pub enum FeatureFlag {
     /// that results in a new package created but without any code. With this feature, it is no
     /// longer possible and an explicit error is returned if publishing is attempted.
     DISALLOW_INIT_MODULE_TO_PUBLISH_MODULES = 82,
+    /// With this feature, blocks that contain only governance proposals (and system transactions)
+    /// use a higher effective block gas limit, if the on-chain block gas limit specifies one.
+    GOVERNANCE_BLOCK_GAS_LIMIT_OVERRIDE = 83,
 }
 
 impl FeatureFlag {
@@ -338,6 +341,10 @@ impl Features {
         self.is_enabled(FeatureFlag::DISALLOW_INIT_MODULE_TO_PUBLISH_MODULES)
     }
 
+    pub fn is_governance_block_gas_limit_override_enabled(&self) -> bool {
+        self.is_enabled(FeatureFlag::GOVERNANCE_BLOCK_GAS_LIMIT_OVERRIDE)
+    }
+
     pub fn get_max_identifier_size(&self) -> u64 {
         if self.is_enabled(FeatureFlag::LIMIT_MAX_IDENTIFIER_LENGTH) {
             IDENTIFIER_SIZE_MAX
@@ -18,6 +18,7 @@ pub enum OnChainExecutionConfig {
     Missing,
     // Reminder: Add V4 and future versions here, after Missing (order matters for enums).
     V4(ExecutionConfigV4),
+    V5(ExecutionConfigV5),
 }
 
 /// The public interface that exposes all values with safe fallback.
@@ -30,6 +31,7 @@ impl OnChainExecutionConfig {
             OnChainExecutionConfig::V2(config) => config.transaction_shuffler_type.clone(),
             OnChainExecutionConfig::V3(config) => config.transaction_shuffler_type.clone(),
             OnChainExecutionConfig::V4(config) => config.transaction_shuffler_type.clone(),
+            OnChainExecutionConfig::V5(config) => config.transaction_shuffler_type.clone(),
         }
     }
 
@@ -45,6 +47,19 @@ impl OnChainExecutionConfig {
                 .block_gas_limit
                 .map_or(BlockGasLimitType::NoLimit, BlockGasLimitType::Limit),
             OnChainExecutionConfig::V4(config) => config.block_gas_limit_type.clone(),
+            OnChainExecutionConfig::V5(config) => config.block_gas_limit_type.clone(),
+        }
+    }
+
+    /// The per-transaction limits on the output being used.
+    pub fn transaction_output_limits(&self) -> TransactionOutputLimits {
+        match &self {
+            OnChainExecutionConfig::Missing
+            | OnChainExecutionConfig::V1(_)
+            | OnChainExecutionConfig::V2(_)
+            | OnChainExecutionConfig::V3(_)
+            | OnChainExecutionConfig::V4(_) => TransactionOutputLimits::default(),
+            OnChainExecutionConfig::V5(config) => config.transaction_output_limits.clone(),
         }
     }
 
@@ -63,6 +78,7 @@ impl OnChainExecutionConfig {
             OnChainExecutionConfig::V2(_config) => TransactionDeduperType::NoDedup,
             OnChainExecutionConfig::V3(config) => config.transaction_deduper_type.clone(),
             OnChainExecutionConfig::V4(config) => config.transaction_deduper_type.clone(),
+            OnChainExecutionConfig::V5(config) => config.transaction_deduper_type.clone(),
         }
     }
 
@@ -142,6 +158,31 @@ pub struct ExecutionConfigV4 {
     pub transaction_deduper_type: TransactionDeduperType,
 }
 
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq, Serialize)]
+pub struct ExecutionConfigV5 {
+    pub transaction_shuffler_type: TransactionShufflerType,
+    pub block_gas_limit_type: BlockGasLimitType,
+    pub transaction_deduper_type: TransactionDeduperType,
+    pub transaction_output_limits: TransactionOutputLimits,
+}
+
+/// Hard limits on the output of a single transaction, enforced by the VM in addition to the limits
+/// of the gas schedule. Transactions whose output exceeds them fail with
+/// TRANSACTION_OUTPUT_EXCEEDS_LIMITS status, and are charged for the gas they used.
+#[derive(Clone, Debug, Default, Deserialize, Eq, PartialEq, Serialize)]
+pub struct TransactionOutputLimits {
+    /// Limit on the approximate size of the output (writes and events) in bytes.
+    pub max_output_bytes: Option<u64>,
+    /// Limit on the number of write ops in the output.
+    pub max_write_ops: Option<u64>,
+}
+
+impl TransactionOutputLimits {
+    pub fn is_unlimited(&self) -> bool {
+        self.max_output_bytes.is_none() && self.max_write_ops.is_none()
+    }
+}
+
 #[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
 #[serde(rename_all = "snake_case")] // cannot use tag = "type" as nested enums cannot work, and bcs doesn't support it
 pub enum TransactionShufflerType {
@@ -227,6 +268,15 @@ pub enum BlockGasLimitType {
         /// NOTE: Currently not supported.
         add_block_limit_outcome_onchain: bool,
     },
+    /// Same as the base block limit, but blocks that contain only governance proposals and system
+    /// transactions use governance_effective_block_gas_limit instead of the effective block gas
+    /// limit of the base, so that large framework upgrades do not need manual limit changes.
+    /// The override is only applied if GOVERNANCE_BLOCK_GAS_LIMIT_OVERRIDE feature is enabled,
+    /// see [BlockGasLimitType::resolve_governance_override].
+    WithGovernanceOverride {
+        base: Box<BlockGasLimitType>,
+        governance_effective_block_gas_limit: u64,
+    },
 }
 
 impl BlockGasLimitType {
@@ -238,6 +288,7 @@ impl BlockGasLimitType {
                 effective_block_gas_limit,
                 ..
             } => Some(*effective_block_gas_limit),
+            BlockGasLimitType::WithGovernanceOverride { base, .. } => base.block_gas_limit(),
         }
     }
 
@@ -249,6 +300,9 @@ impl BlockGasLimitType {
                 execution_gas_effective_multiplier,
                 ..
             } => *execution_gas_effective_multiplier,
+            BlockGasLimitType::WithGovernanceOverride { base, .. } => {
+                base.execution_gas_effective_multiplier()
+            },
         }
     }
 
@@ -260,6 +314,9 @@ impl BlockGasLimitType {
                 io_gas_effective_multiplier,
                 ..
             } => *io_gas_effective_multiplier,
+            BlockGasLimitType::WithGovernanceOverride { base, .. } => {
+                base.io_gas_effective_multiplier()
+            },
         }
     }
 
@@ -270,6 +327,7 @@ impl BlockGasLimitType {
             BlockGasLimitType::ComplexLimitV1 {
                 block_output_limit, ..
             } => *block_output_limit,
+            BlockGasLimitType::WithGovernanceOverride { base, .. } => base.block_output_limit(),
         }
     }
 
@@ -287,6 +345,9 @@ impl BlockGasLimitType {
                     None
                 }
             },
+            BlockGasLimitType::WithGovernanceOverride { base, .. } => {
+                base.conflict_penalty_window()
+            },
         }
     }
 
@@ -298,6 +359,9 @@ impl BlockGasLimitType {
                 use_module_publishing_block_conflict,
                 ..
             } => *use_module_publishing_block_conflict,
+            BlockGasLimitType::WithGovernanceOverride { base, .. } => {
+                base.use_module_publishing_block_conflict()
+            },
         }
     }
 
@@ -309,6 +373,9 @@ impl BlockGasLimitType {
                 include_user_txn_size_in_block_output,
                 ..
             } => *include_user_txn_size_in_block_output,
+            BlockGasLimitType::WithGovernanceOverride { base, .. } => {
+                base.include_user_txn_size_in_block_output()
+            },
         }
     }
 
@@ -320,6 +387,9 @@ impl BlockGasLimitType {
                 add_block_limit_outcome_onchain,
                 ..
             } => *add_block_limit_outcome_onchain,
+            BlockGasLimitType::WithGovernanceOverride { base, .. } => {
+                base.add_block_limit_outcome_onchain()
+            },
         }
     }
 
@@ -331,6 +401,63 @@ impl BlockGasLimitType {
                 use_granular_resource_group_conflicts,
                 ..
             } => *use_granular_resource_group_conflicts,
+            BlockGasLimitType::WithGovernanceOverride { base, .. } => {
+                base.use_granular_resource_group_conflicts()
+            },
+        }
+    }
+
+    /// Returns the block limit to use for a block, resolving the governance override (if any):
+    /// if the block contains only governance proposals and system transactions, and the feature
+    /// is enabled, the effective block gas limit is replaced by the governance one.
+    pub fn resolve_governance_override(
+        &self,
+        is_governance_only_block: bool,
+        governance_override_enabled: bool,
+    ) -> BlockGasLimitType {
+        match self {
+            BlockGasLimitType::WithGovernanceOverride {
+                base,
+                governance_effective_block_gas_limit,
+            } => {
+                let base = base.resolve_governance_override(
+                    is_governance_only_block,
+                    governance_override_enabled,
+                );
+                if !(is_governance_only_block && governance_override_enabled) {
+                    return base;
+                }
+                match base {
+                    BlockGasLimitType::NoLimit => BlockGasLimitType::NoLimit,
+                    BlockGasLimitType::Limit(_) => {
+                        BlockGasLimitType::Limit(*governance_effective_block_gas_limit)
+                    },
+                    BlockGasLimitType::ComplexLimitV1 {
+                        execution_gas_effective_multiplier,
+                        io_gas_effective_multiplier,
+                        conflict_penalty_window,
+                        use_granular_resource_group_conflicts,
+                        use_module_publishing_block_conflict,
+                        block_output_limit,
+                        include_user_txn_size_in_block_output,
+                        add_block_limit_outcome_onchain,
+                        ..
+                    } => BlockGasLimitType::ComplexLimitV1 {
+                        effective_block_gas_limit: *governance_effective_block_gas_limit,
+                        execution_gas_effective_multiplier,
+                        io_gas_effective_multiplier,
+                        conflict_penalty_window,
+                        use_granular_resource_group_conflicts,
+                        use_module_publishing_block_conflict,
+                        block_output_limit,
+                        include_user_txn_size_in_block_output,
+                        add_block_limit_outcome_onchain,
+                    },
+                    // Resolved recursively above.
+                    BlockGasLimitType::WithGovernanceOverride { .. } => base,
+                }
+            },
+            _ => self.clone(),
         }
     }
 }
@@ -473,4 +600,68 @@ mod test {
         ));
         assert_eq!(result.block_gas_limit_type(), BlockGasLimitType::NoLimit);
     }
+
+    #[test]
+    fn test_config_transaction_output_limits() {
+        let transaction_output_limits = TransactionOutputLimits {
+            max_output_bytes: Some(1024 * 1024),
+            max_write_ops: Some(8192),
+        };
+        let config = OnChainExecutionConfig::V5(ExecutionConfigV5 {
+            transaction_shuffler_type: TransactionShufflerType::default_for_genesis(),
+            block_gas_limit_type: BlockGasLimitType::default_for_genesis(),
+            transaction_deduper_type: TransactionDeduperType::TxnHashAndAuthenticatorV1,
+            transaction_output_limits: transaction_output_limits.clone(),
+        });
+
+        let bytes = bcs::to_bytes(&config).unwrap();
+        let result = bcs::from_bytes::<OnChainExecutionConfig>(&bytes).unwrap();
+        assert_eq!(
+            result.transaction_output_limits(),
+            transaction_output_limits
+        );
+
+        // Older versions have no limits.
+        assert!(OnChainExecutionConfig::default_for_genesis()
+            .transaction_output_limits()
+            .is_unlimited());
+    }
+
+    #[test]
+    fn test_block_gas_limit_governance_override() {
+        let block_gas_limit_type = BlockGasLimitType::WithGovernanceOverride {
+            base: Box::new(BlockGasLimitType::default_for_genesis()),
+            governance_effective_block_gas_limit: 1_000_000,
+        };
+
+        let bytes = bcs::to_bytes(&block_gas_limit_type).unwrap();
+        assert_eq!(
+            bcs::from_bytes::<BlockGasLimitType>(&bytes).unwrap(),
+            block_gas_limit_type
+        );
+
+        // Unless resolved, the base limit is used.
+        assert_eq!(block_gas_limit_type.block_gas_limit(), Some(30000));
+        assert_eq!(
+            block_gas_limit_type.block_output_limit(),
+            Some(5 * 1024 * 1024)
+        );
+
+        for (is_governance_only_block, governance_override_enabled) in
+            [(false, false), (false, true), (true, false)]
+        {
+            assert_eq!(
+                block_gas_limit_type.resolve_governance_override(
+                    is_governance_only_block,
+                    governance_override_enabled
+                ),
+                BlockGasLimitType::default_for_genesis()
+            );
+        }
+
+        let resolved = block_gas_limit_type.resolve_governance_override(true, true);
+        assert_eq!(resolved.block_gas_limit(), Some(1_000_000));
+        assert_eq!(resolved.block_output_limit(), Some(5 * 1024 * 1024));
+        assert_eq!(resolved.conflict_penalty_window(), Some(9));
+    }
 }
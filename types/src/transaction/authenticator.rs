@@ -15,9 +15,11 @@ use crate::{
 use anyhow::{bail, ensure, Error, Result};
 use aptos_crypto::{
     ed25519::{Ed25519PublicKey, Ed25519Signature},
+    error::CryptoError,
     hash::CryptoHash,
     multi_ed25519::{MultiEd25519PublicKey, MultiEd25519Signature},
     secp256k1_ecdsa, secp256r1_ecdsa,
+    signed_envelope::EnvelopeSignature,
     traits::Signature,
     CryptoMaterialError, HashValue, ValidCryptoMaterial, ValidCryptoMaterialStringExt,
 };
@@ -26,7 +28,7 @@ use aptos_crypto_derive::{CryptoHasher, DeserializeKey, SerializeKey};
 use proptest_derive::Arbitrary;
 use rand::{rngs::OsRng, Rng};
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
-use std::{convert::TryFrom, fmt, str::FromStr};
+use std::{collections::BTreeMap, convert::TryFrom, fmt, str::FromStr};
 use thiserror::Error;
 
 /// Maximum number of signatures supported in `TransactionAuthenticator`,
@@ -147,7 +149,7 @@ impl TransactionAuthenticator {
             Self::Ed25519 {
                 public_key,
                 signature,
-            } => signature.verify(raw_txn, public_key),
+            } => signature.verify(raw_txn, public_key).map_err(Into::into),
             Self::FeePayer {
                 sender,
                 secondary_signer_addresses,
@@ -197,7 +199,7 @@ impl TransactionAuthenticator {
             Self::MultiEd25519 {
                 public_key,
                 signature,
-            } => signature.verify(raw_txn, public_key),
+            } => signature.verify(raw_txn, public_key).map_err(Into::into),
             Self::MultiAgent {
                 sender,
                 secondary_signer_addresses,
@@ -561,11 +563,11 @@ impl AccountAuthenticator {
             Self::Ed25519 {
                 public_key,
                 signature,
-            } => signature.verify(message, public_key),
+            } => signature.verify(message, public_key).map_err(Into::into),
             Self::MultiEd25519 {
                 public_key,
                 signature,
-            } => signature.verify(message, public_key),
+            } => signature.verify(message, public_key).map_err(Into::into),
             Self::SingleKey { authenticator } => authenticator.verify(message),
             Self::MultiKey { authenticator } => authenticator.verify(message),
             Self::NoAccountAuthenticator => bail!("No signature to verify."),
@@ -946,11 +948,157 @@ impl MultiKey {
         self.signatures_required
     }
 
+    /// Returns the index of the first occurrence of `public_key`, if any.
+    pub fn index_of(&self, public_key: &AnyPublicKey) -> Option<u8> {
+        self.public_keys
+            .iter()
+            .position(|key| key == public_key)
+            .map(|idx| idx as u8)
+    }
+
     pub fn to_bytes(&self) -> Vec<u8> {
         bcs::to_bytes(&self).expect("Only unhandleable errors happen here.")
     }
 }
 
+/// Builds a K-of-N `MultiKey`, whose public keys may mix signature schemes.
+#[derive(Clone, Debug)]
+pub struct MultiKeyBuilder {
+    public_keys: Vec<AnyPublicKey>,
+    signatures_required: u8,
+}
+
+impl MultiKeyBuilder {
+    pub fn new(signatures_required: u8) -> Self {
+        Self {
+            public_keys: vec![],
+            signatures_required,
+        }
+    }
+
+    pub fn public_key(mut self, public_key: AnyPublicKey) -> Self {
+        self.public_keys.push(public_key);
+        self
+    }
+
+    pub fn ed25519(self, public_key: Ed25519PublicKey) -> Self {
+        self.public_key(AnyPublicKey::ed25519(public_key))
+    }
+
+    pub fn secp256k1_ecdsa(self, public_key: secp256k1_ecdsa::PublicKey) -> Self {
+        self.public_key(AnyPublicKey::secp256k1_ecdsa(public_key))
+    }
+
+    pub fn secp256r1_ecdsa(self, public_key: secp256r1_ecdsa::PublicKey) -> Self {
+        self.public_key(AnyPublicKey::secp256r1_ecdsa(public_key))
+    }
+
+    pub fn keyless(self, public_key: KeylessPublicKey) -> Self {
+        self.public_key(AnyPublicKey::keyless(public_key))
+    }
+
+    pub fn federated_keyless(self, public_key: FederatedKeylessPublicKey) -> Self {
+        self.public_key(AnyPublicKey::federated_keyless(public_key))
+    }
+
+    pub fn build(self) -> Result<MultiKey> {
+        // The signatures of a `MultiKeyAuthenticator` are indexed by a u8
+        ensure!(
+            self.public_keys.len() < (u8::MAX as usize),
+            "Too many public keys, {}, in MultiKey.",
+            self.public_keys.len(),
+        );
+        MultiKey::new(self.public_keys, self.signatures_required)
+    }
+}
+
+/// Assembles a `MultiKeyAuthenticator` from the signatures of its signers, collected in any
+/// order (e.g., as they come back from each signer).
+#[derive(Clone, Debug)]
+pub struct MultiKeyAuthenticatorBuilder {
+    public_keys: MultiKey,
+    signatures: BTreeMap<u8, AnySignature>,
+}
+
+impl MultiKeyAuthenticatorBuilder {
+    pub fn new(public_keys: MultiKey) -> Self {
+        Self {
+            public_keys,
+            signatures: BTreeMap::new(),
+        }
+    }
+
+    /// Adds the signature of the `idx`-th public key.
+    pub fn add_signature_at(&mut self, idx: u8, signature: AnySignature) -> Result<&mut Self> {
+        ensure!(
+            (idx as usize) < self.public_keys.len(),
+            "Signature index is out of public key range, {} < {}.",
+            idx,
+            self.public_keys.len(),
+        );
+        ensure!(
+            !self.signatures.contains_key(&idx),
+            "Duplicate signature index, {}.",
+            idx
+        );
+        self.signatures.insert(idx, signature);
+        Ok(self)
+    }
+
+    /// Adds the signature of `public_key`. If the public key appears several times in the
+    /// `MultiKey`, the signature is assigned to its first occurrence without a signature yet.
+    pub fn add_signature(
+        &mut self,
+        public_key: &AnyPublicKey,
+        signature: AnySignature,
+    ) -> Result<&mut Self> {
+        let idx = self
+            .public_keys
+            .public_keys()
+            .iter()
+            .enumerate()
+            .position(|(idx, key)| {
+                key == public_key && !self.signatures.contains_key(&(idx as u8))
+            });
+        match idx {
+            Some(idx) => self.add_signature_at(idx as u8, signature),
+            None => bail!("The public key is not part of the MultiKey, or has already signed."),
+        }
+    }
+
+    /// Same as `add_signature`, but first verifies the signature on `message`, so that an invalid
+    /// partial signature is rejected before it invalidates the whole authenticator.
+    pub fn add_verified_signature<T: Serialize + CryptoHash>(
+        &mut self,
+        message: &T,
+        public_key: &AnyPublicKey,
+        signature: AnySignature,
+    ) -> Result<&mut Self> {
+        signature.verify(public_key, message)?;
+        self.add_signature(public_key, signature)
+    }
+
+    pub fn num_signatures(&self) -> usize {
+        self.signatures.len()
+    }
+
+    /// Returns true if there are enough signatures to build the authenticator.
+    pub fn is_complete(&self) -> bool {
+        self.signatures.len() >= self.public_keys.signatures_required() as usize
+    }
+
+    pub fn build(self) -> Result<MultiKeyAuthenticator> {
+        ensure!(
+            self.is_complete(),
+            "Not enough signatures, {} < {}.",
+            self.signatures.len(),
+            self.public_keys.signatures_required(),
+        );
+        // The signatures are sorted by index, as expected by the bitmap of the authenticator.
+        MultiKeyAuthenticator::new(self.public_keys, self.signatures.into_iter().collect())
+    }
+}
+
 #[derive(Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
 pub struct SingleKeyAuthenticator {
     public_key: AnyPublicKey,
@@ -1035,10 +1183,10 @@ impl AnySignature {
     ) -> Result<()> {
         match (self, public_key) {
             (Self::Ed25519 { signature }, AnyPublicKey::Ed25519 { public_key }) => {
-                signature.verify(message, public_key)
+                signature.verify(message, public_key).map_err(Into::into)
             },
             (Self::Secp256k1Ecdsa { signature }, AnyPublicKey::Secp256k1Ecdsa { public_key }) => {
-                signature.verify(message, public_key)
+                signature.verify(message, public_key).map_err(Into::into)
             },
             (Self::WebAuthn { signature }, _) => signature.verify(message, public_key),
             (Self::Keyless { signature }, AnyPublicKey::Keyless { public_key: _ }) => {
@@ -1176,7 +1324,7 @@ impl EphemeralSignature {
     ) -> Result<()> {
         match (self, public_key) {
             (Self::Ed25519 { signature }, EphemeralPublicKey::Ed25519 { public_key }) => {
-                signature.verify(message, public_key)
+                signature.verify(message, public_key).map_err(Into::into)
             },
             (Self::WebAuthn { signature }, EphemeralPublicKey::Secp256r1Ecdsa { public_key }) => {
                 signature.verify(message, &AnyPublicKey::secp256r1_ecdsa(public_key.clone()))
@@ -1193,9 +1341,9 @@ impl EphemeralSignature {
         public_key: &EphemeralPublicKey,
     ) -> Result<()> {
         match (self, public_key) {
-            (Self::Ed25519 { signature }, EphemeralPublicKey::Ed25519 { public_key }) => {
-                signature.verify_arbitrary_msg(message, public_key)
-            },
+            (Self::Ed25519 { signature }, EphemeralPublicKey::Ed25519 { public_key }) => signature
+                .verify_arbitrary_msg(message, public_key)
+                .map_err(Into::into),
             (Self::WebAuthn { signature }, EphemeralPublicKey::Secp256r1Ecdsa { public_key }) => {
                 signature.verify_arbitrary_msg(
                     message,
@@ -1209,6 +1357,22 @@ impl EphemeralSignature {
     }
 }
 
+impl EnvelopeSignature for EphemeralSignature {
+    type PublicKey = EphemeralPublicKey;
+
+    fn verify_message<T: Serialize + CryptoHash>(
+        &self,
+        message: &T,
+        public_key: &EphemeralPublicKey,
+    ) -> Result<(), CryptoError> {
+        self.verify(message, public_key)
+            .map_err(|error| match error.downcast::<CryptoError>() {
+                Ok(error) => error,
+                Err(error) => CryptoError::InvalidSignature(error.to_string()),
+            })
+    }
+}
+
 impl TryFrom<&[u8]> for EphemeralSignature {
     type Error = CryptoMaterialError;
 
@@ -1520,6 +1684,85 @@ mod tests {
         .unwrap_err();
     }
 
+    #[test]
+    fn build_mixed_multi_key_auth() {
+        let sender0 = Ed25519PrivateKey::generate_for_testing();
+        let any_sender0_pub = AnyPublicKey::ed25519(sender0.public_key());
+        let sender1 = secp256k1_ecdsa::PrivateKey::generate_for_testing();
+        let any_sender1_pub = AnyPublicKey::secp256k1_ecdsa(sender1.public_key());
+        let (_, keyless_pub) = get_sample_openid_sig_and_pk();
+        let any_keyless_pub = AnyPublicKey::keyless(keyless_pub.clone());
+
+        let multi_key = MultiKeyBuilder::new(2)
+            .keyless(keyless_pub)
+            .secp256k1_ecdsa(sender1.public_key())
+            .ed25519(sender0.public_key())
+            .build()
+            .unwrap();
+        assert_eq!(multi_key.len(), 3);
+        assert_eq!(multi_key.index_of(&any_sender0_pub), Some(2));
+        assert_eq!(multi_key.index_of(&any_keyless_pub), Some(0));
+        MultiKeyBuilder::new(2)
+            .ed25519(sender0.public_key())
+            .build()
+            .unwrap_err();
+        MultiKeyBuilder::new(0)
+            .ed25519(sender0.public_key())
+            .build()
+            .unwrap_err();
+
+        let sender_addr = AuthenticationKey::multi_key(multi_key.clone()).account_address();
+        let raw_txn = crate::test_helpers::transaction_test_helpers::get_test_signed_transaction(
+            sender_addr,
+            0,
+            &sender0,
+            sender0.public_key(),
+            None,
+            0,
+            0,
+            None,
+        )
+        .into_raw_transaction();
+        let signature0 = AnySignature::ed25519(sender0.sign(&raw_txn).unwrap());
+        let signature1 = AnySignature::secp256k1_ecdsa(sender1.sign(&raw_txn).unwrap());
+
+        // The signatures are collected out of order
+        let mut builder = MultiKeyAuthenticatorBuilder::new(multi_key.clone());
+        builder
+            .add_verified_signature(&raw_txn, &any_sender0_pub, signature0.clone())
+            .unwrap();
+        assert!(!builder.is_complete());
+        builder.build().unwrap_err();
+
+        let mut builder = MultiKeyAuthenticatorBuilder::new(multi_key.clone());
+        builder
+            .add_verified_signature(&raw_txn, &any_sender0_pub, signature0.clone())
+            .unwrap();
+        // An invalid partial signature is rejected
+        builder
+            .add_verified_signature(&raw_txn, &any_sender1_pub, signature0.clone())
+            .unwrap_err();
+        // A public key only signs once
+        builder
+            .add_signature(&any_sender0_pub, signature0.clone())
+            .unwrap_err();
+        builder
+            .add_signature(&any_sender1_pub, signature1.clone())
+            .unwrap();
+        assert!(builder.is_complete());
+        assert_eq!(builder.num_signatures(), 2);
+
+        let mk_auth = builder.build().unwrap();
+        assert_eq!(mk_auth.signatures(), vec![
+            (1, &signature1),
+            (2, &signature0)
+        ]);
+        mk_auth.verify(&raw_txn).unwrap();
+        let account_auth = AccountAuthenticator::multi_key(mk_auth);
+        let signed_txn = SignedTransaction::new_single_sender(raw_txn, account_auth);
+        signed_txn.verify_signature().unwrap();
+    }
+
     #[test]
     fn verify_fee_payer_with_optional_fee_payer_address() {
         // This massive test basically verifies that various combinations of signatures work
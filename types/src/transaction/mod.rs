@@ -2044,7 +2044,7 @@ impl TryFrom<Transaction> for SignedTransaction {
 
 /// Trait that defines a transaction type that can be executed by the block executor. A transaction
 /// transaction will write to a key value storage as their side effect.
-pub trait BlockExecutableTransaction: Sync + Send + Clone + 'static {
+pub trait BlockExecutableTransaction: Sync + Send + Clone + Debug + 'static {
     type Key: PartialOrd + Ord + Send + Sync + Clone + Hash + Eq + ModulePath + Debug;
     /// Some keys contain multiple "resources" distinguished by a tag. Reading these keys requires
     /// specifying a tag, and output requires merging all resources together (Note: this may change
@@ -2065,6 +2065,11 @@ pub trait BlockExecutableTransaction: Sync + Send + Clone + 'static {
 
     /// Size of the user transaction in bytes, 0 otherwise
     fn user_txn_bytes_len(&self) -> usize;
+
+    /// Sender of the user transaction, None otherwise
+    fn sender(&self) -> Option<AccountAddress> {
+        None
+    }
 }
 
 pub struct ViewFunctionOutput {
@@ -72,6 +72,10 @@ impl BlockExecutableTransaction for SignatureVerifiedTransaction {
             _ => 0,
         }
     }
+
+    fn sender(&self) -> Option<AccountAddress> {
+        SignatureVerifiedTransaction::sender(self)
+    }
 }
 
 impl From<Transaction> for SignatureVerifiedTransaction {
@@ -157,7 +157,9 @@ impl PartialAuthenticatorAssertionResponse {
             (
                 AnyPublicKey::Secp256r1Ecdsa { public_key },
                 AssertionSignature::Secp256r1Ecdsa { signature },
-            ) => signature.verify_arbitrary_msg(&verification_data, public_key),
+            ) => signature
+                .verify_arbitrary_msg(&verification_data, public_key)
+                .map_err(Into::into),
             _ => Err(anyhow!(
                 "WebAuthn verification failure, invalid key, signature pairing"
             )),
@@ -200,7 +202,9 @@ impl PartialAuthenticatorAssertionResponse {
             (
                 AnyPublicKey::Secp256r1Ecdsa { public_key },
                 AssertionSignature::Secp256r1Ecdsa { signature },
-            ) => signature.verify_arbitrary_msg(&verification_data, public_key),
+            ) => signature
+                .verify_arbitrary_msg(&verification_data, public_key)
+                .map_err(Into::into),
             _ => Err(anyhow!(
                 "WebAuthn verification failure, invalid key, signature pairing"
             )),
@@ -0,0 +1,164 @@
+// Copyright © Aptos Foundation
+// SPDX-License-Identifier: Apache-2.0
+
+use crate::{
+    epoch_change::EpochChangeProof, epoch_state::EpochState, ledger_info::LedgerInfoWithSignatures,
+    waypoint::Waypoint,
+};
+use anyhow::{ensure, format_err, Result};
+use serde::{Deserialize, Serialize};
+
+/// A waypoint along with the epoch ending ledger infos proving it, which allows a fresh node to
+/// verify the waypoint without fetching the epoch ending ledger infos from the network.
+///
+/// The ledger infos start at a trusted root (e.g., genesis) and are chained: each ledger info is
+/// signed by the validator set carried by the previous one, so the whole bundle can be verified
+/// from the root. The waypoint is the one of the last ledger info.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct WaypointBundle {
+    waypoint: Waypoint,
+    epoch_ending_ledger_infos: Vec<LedgerInfoWithSignatures>,
+}
+
+impl WaypointBundle {
+    /// Creates a bundle for the waypoint of the last of the given epoch ending ledger infos.
+    pub fn new(epoch_ending_ledger_infos: Vec<LedgerInfoWithSignatures>) -> Result<Self> {
+        let last_ledger_info = epoch_ending_ledger_infos
+            .last()
+            .ok_or_else(|| format_err!("The waypoint bundle has no epoch ending ledger infos"))?;
+        let waypoint = Waypoint::new_epoch_boundary(last_ledger_info.ledger_info())?;
+
+        Ok(Self {
+            waypoint,
+            epoch_ending_ledger_infos,
+        })
+    }
+
+    pub fn waypoint(&self) -> Waypoint {
+        self.waypoint
+    }
+
+    pub fn epoch_ending_ledger_infos(&self) -> &[LedgerInfoWithSignatures] {
+        &self.epoch_ending_ledger_infos
+    }
+
+    /// Verifies the signature chain of the epoch ending ledger infos, starting at the ledger info
+    /// matching `trusted_root`, and that the waypoint matches the last ledger info. Returns the
+    /// epoch state following the waypoint.
+    pub fn verify(&self, trusted_root: &Waypoint) -> Result<EpochState> {
+        ensure!(
+            self.epoch_ending_ledger_infos
+                .iter()
+                .any(|ledger_info| ledger_info.ledger_info().version() == trusted_root.version()),
+            "The waypoint bundle doesn't contain the trusted root at version {}",
+            trusted_root.version()
+        );
+
+        // The ledger infos before the trusted root are skipped as stale, the one at the
+        // trusted root is verified by the root, and the next ones by the previous validator sets.
+        let proof = EpochChangeProof::new(self.epoch_ending_ledger_infos.clone(), false);
+        let last_ledger_info = proof.verify(trusted_root)?;
+        self.waypoint.verify(last_ledger_info.ledger_info())?;
+
+        last_ledger_info
+            .ledger_info()
+            .next_epoch_state()
+            .cloned()
+            .ok_or_else(|| format_err!("The last ledger info doesn't carry a validator set"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::WaypointBundle;
+    use crate::{
+        aggregate_signature::{AggregateSignature, PartialSignatures},
+        block_info::BlockInfo,
+        epoch_state::EpochState,
+        ledger_info::{LedgerInfo, LedgerInfoWithSignatures},
+        validator_signer::ValidatorSigner,
+        validator_verifier::{random_validator_verifier, ValidatorVerifier},
+        waypoint::Waypoint,
+    };
+    use aptos_crypto::HashValue;
+    use std::sync::Arc;
+
+    /// Creates the epoch ending ledger infos of epochs 0 (i.e., genesis, which isn't signed) to
+    /// `num_epochs - 1`, each signed by the validator set of its epoch.
+    fn create_epoch_ending_ledger_infos(num_epochs: u64) -> Vec<LedgerInfoWithSignatures> {
+        let mut ledger_infos = vec![];
+        let mut current_signers: Vec<ValidatorSigner> = vec![];
+        let mut current_verifier: Option<Arc<ValidatorVerifier>> = None;
+        for epoch in 0..num_epochs {
+            let (next_signers, next_verifier) = random_validator_verifier(3, None, true);
+            let next_verifier = Arc::new(next_verifier);
+            let ledger_info = LedgerInfo::new(
+                BlockInfo::new(
+                    epoch,
+                    0,
+                    HashValue::zero(),
+                    HashValue::zero(),
+                    epoch * 100,
+                    0,
+                    Some(EpochState {
+                        epoch: epoch + 1,
+                        verifier: next_verifier.clone(),
+                    }),
+                ),
+                HashValue::zero(),
+            );
+            let signatures = match &current_verifier {
+                Some(verifier) => {
+                    let partial_signatures = PartialSignatures::new(
+                        current_signers
+                            .iter()
+                            .map(|s| (s.author(), s.sign(&ledger_info).unwrap()))
+                            .collect(),
+                    );
+                    verifier
+                        .aggregate_signatures(partial_signatures.signatures_iter())
+                        .unwrap()
+                },
+                None => AggregateSignature::empty(),
+            };
+            ledger_infos.push(LedgerInfoWithSignatures::new(ledger_info, signatures));
+            current_signers = next_signers;
+            current_verifier = Some(next_verifier);
+        }
+        ledger_infos
+    }
+
+    #[test]
+    fn test_verify_waypoint_bundle() {
+        let ledger_infos = create_epoch_ending_ledger_infos(5);
+        let genesis_waypoint = Waypoint::new_epoch_boundary(ledger_infos[0].ledger_info()).unwrap();
+        let bundle = WaypointBundle::new(ledger_infos.clone()).unwrap();
+        assert_eq!(bundle.waypoint().version(), 400);
+
+        // The bundle verifies from genesis, and from any of its ledger infos
+        let epoch_state = bundle.verify(&genesis_waypoint).unwrap();
+        assert_eq!(epoch_state.epoch, 5);
+        let trusted_root = Waypoint::new_epoch_boundary(ledger_infos[2].ledger_info()).unwrap();
+        assert!(bundle.verify(&trusted_root).is_ok());
+
+        // The bundle doesn't verify from another chain
+        let other_ledger_infos = create_epoch_ending_ledger_infos(5);
+        let other_genesis_waypoint =
+            Waypoint::new_epoch_boundary(other_ledger_infos[0].ledger_info()).unwrap();
+        assert!(bundle.verify(&other_genesis_waypoint).is_err());
+
+        // The bundle doesn't verify if the chain is broken
+        let mut broken_ledger_infos = ledger_infos.clone();
+        broken_ledger_infos[3] = other_ledger_infos[3].clone();
+        let broken_bundle = WaypointBundle::new(broken_ledger_infos).unwrap();
+        assert!(broken_bundle.verify(&genesis_waypoint).is_err());
+
+        // The bundle doesn't verify if the waypoint doesn't match the last ledger info
+        let mut bundle_with_wrong_waypoint = bundle;
+        bundle_with_wrong_waypoint.waypoint =
+            Waypoint::new_epoch_boundary(ledger_infos[3].ledger_info()).unwrap();
+        assert!(bundle_with_wrong_waypoint
+            .verify(&genesis_waypoint)
+            .is_err());
+    }
+}